@@ -0,0 +1,54 @@
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use serde_json::json;
+use vector::codecs::{JsonEngine, JsonParser, Parser};
+
+/// A JSON array of log records, the shape of a typical `http` or
+/// `datadog_agent` request body.
+fn logs_payload(count: usize) -> Bytes {
+    let logs = (0..count)
+        .map(|i| {
+            json!({
+                "message": format!("GET /api/v1/users/{} HTTP/1.1 200 {}", i, i * 31 % 4096),
+                "status": "info",
+                "timestamp": 1_633_089_600_000_i64 + i as i64,
+                "hostname": "web-01.example.com",
+                "service": "frontend",
+                "ddsource": "nginx",
+                "ddtags": "env:prod,version:1.2.3,team:web",
+                "http": {
+                    "method": "GET",
+                    "status_code": 200,
+                    "duration_ms": 12.5,
+                    "headers": ["accept", "user-agent", "x-request-id"],
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_vec(&logs).unwrap().into()
+}
+
+fn benchmark_json(c: &mut Criterion) {
+    let mut group = c.benchmark_group("codecs/json");
+
+    for count in [1, 100, 1000] {
+        let input = logs_payload(count);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+
+        for (name, engine) in [("serde", JsonEngine::Serde), ("simd", JsonEngine::Simd)] {
+            let parser = JsonParser::with_engine(engine);
+            group.bench_function(format!("{}/{}", name, count), |b| {
+                b.iter_batched(
+                    || input.clone(),
+                    |input| parser.parse(input).unwrap(),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_json);
+criterion_main!(benches);
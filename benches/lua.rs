@@ -2,7 +2,7 @@ use criterion::{criterion_group, BatchSize, Criterion, Throughput};
 use futures::{stream, SinkExt, Stream, StreamExt};
 use indexmap::IndexMap;
 use indoc::indoc;
-use std::pin::Pin;
+use std::{future::ready, pin::Pin};
 use transforms::lua::v2::LuaConfig;
 use vector::{
     config::{TransformConfig, TransformContext},
@@ -74,6 +74,10 @@ fn bench_add_fields(c: &mut Criterion) {
                 }))
             }
             Transform::Task(t) => t.transform(Box::pin(rx)),
+            Transform::MultiOutputTask(t) => Box::pin(
+                t.transform(Box::pin(rx))
+                    .filter_map(|routed| ready(routed.output.is_none().then(|| routed.event))),
+            ),
         };
 
         group.bench_function(name.to_owned(), |b| {
@@ -169,6 +173,10 @@ fn bench_field_filter(c: &mut Criterion) {
                 }))
             }
             Transform::Task(t) => t.transform(Box::pin(rx)),
+            Transform::MultiOutputTask(t) => Box::pin(
+                t.transform(Box::pin(rx))
+                    .filter_map(|routed| ready(routed.output.is_none().then(|| routed.event))),
+            ),
         };
 
         group.bench_function(name.to_owned(), |b| {
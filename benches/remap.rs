@@ -56,6 +56,7 @@ fn benchmark_remap(c: &mut Criterion) {
                     timezone: TimeZone::default(),
                     drop_on_error: true,
                     drop_on_abort: true,
+                    ..Default::default()
                 },
                 &Default::default(),
             )
@@ -122,6 +123,7 @@ fn benchmark_remap(c: &mut Criterion) {
                     timezone: TimeZone::default(),
                     drop_on_error: true,
                     drop_on_abort: true,
+                    ..Default::default()
                 },
                 &Default::default(),
             )
@@ -196,6 +198,7 @@ fn benchmark_remap(c: &mut Criterion) {
                 timezone: TimeZone::default(),
                 drop_on_error: true,
                 drop_on_abort: true,
+                ..Default::default()
             }, &Default::default())
             .unwrap(),
         );
@@ -122,6 +122,46 @@ fn main() {
             .unwrap();
     }
 
+    #[cfg(feature = "sinks-gcp")]
+    {
+        println!("cargo:rerun-if-changed=proto/google/cloud/bigquery/storage/v1/storage.proto");
+        println!("cargo:rerun-if-changed=proto/google/rpc/status.proto");
+
+        tonic_build::configure()
+            .build_server(false)
+            .compile(
+                &["proto/google/cloud/bigquery/storage/v1/storage.proto"],
+                &["proto/"],
+            )
+            .unwrap();
+    }
+
+    #[cfg(feature = "sinks-opentelemetry")]
+    {
+        println!("cargo:rerun-if-changed=proto/opentelemetry");
+
+        tonic_build::configure()
+            .build_server(false)
+            .compile(
+                &[
+                    "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                    "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                ],
+                &["proto/"],
+            )
+            .unwrap();
+    }
+
+    #[cfg(feature = "tls-spiffe")]
+    {
+        println!("cargo:rerun-if-changed=proto/spiffe/workload.proto");
+
+        tonic_build::configure()
+            .build_server(false)
+            .compile(&["proto/spiffe/workload.proto"], &["proto/"])
+            .unwrap();
+    }
+
     // We keep track of which environment variables we slurp in, and then emit stanzas at the end to
     // inform Cargo when it needs to rerun this build script.  This allows us to avoid rerunning it
     // every single time unless something _actually_ changes.
@@ -7,21 +7,21 @@ use dashmap::DashMap;
 use glob::glob;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fs, io,
     path::{Path, PathBuf},
     sync::Arc,
     sync::Mutex,
 };
 use tracing::{error, info, warn};
+use vector_core::checkpoint::{self, CheckpointStore, Checkpoints};
 
 const TMP_FILE_NAME: &str = "checkpoints.new.json";
 const STABLE_FILE_NAME: &str = "checkpoints.json";
 
-/// This enum represents the file format of checkpoints persisted to disk. Right
-/// now there is only one variant, but any incompatible changes will require and
-/// additional variant to be added here and handled anywhere that we transit
-/// this format.
+/// This enum represents the file format checkpoints were persisted to disk in
+/// before the shared checkpoint store. They're only read to be migrated to the
+/// store.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "version", rename_all = "snake_case")]
 enum State {
@@ -44,8 +44,10 @@ pub struct Checkpointer {
     tmp_file_path: PathBuf,
     stable_file_path: PathBuf,
     glob_string: String,
+    store: CheckpointStore,
+    component: String,
     checkpoints: Arc<CheckpointsView>,
-    last: Mutex<Option<State>>,
+    persisted: Mutex<Option<Checkpoints>>,
 }
 
 /// A thread-safe handle for reading and writing checkpoints in-memory across
@@ -138,28 +140,47 @@ impl CheckpointsView {
         }
     }
 
-    fn get_state(&self) -> State {
-        State::V1 {
-            checkpoints: self
-                .checkpoints
-                .iter()
-                .map(|entry| {
-                    let fingerprint = entry.key();
-                    let position = entry.value();
-                    Checkpoint {
-                        fingerprint: *fingerprint,
-                        position: *position,
-                        modified: self
-                            .modified_times
-                            .get(fingerprint)
-                            .map(|r| *r.value())
-                            .unwrap_or_else(Utc::now),
-                    }
-                })
-                .collect(),
+    fn set_stored(&self, stored: &Checkpoints, ignore_before: Option<DateTime<Utc>>) {
+        for (key, stored) in stored.iter() {
+            let checkpoint = match (decode_key(key), stored.position.parse()) {
+                (Some(fingerprint), Ok(position)) => Checkpoint {
+                    fingerprint,
+                    position,
+                    modified: stored.updated_at,
+                },
+                _ => {
+                    warn!(message = "Ignoring invalid checkpoint.", %key, position = %stored.position);
+                    continue;
+                }
+            };
+            if let Some(ignore_before) = ignore_before {
+                if checkpoint.modified < ignore_before {
+                    continue;
+                }
+            }
+            self.load(checkpoint);
         }
     }
 
+    /// The checkpoints in the form they're stored in, keyed by fingerprint.
+    fn get_stored(&self) -> BTreeMap<String, checkpoint::Checkpoint> {
+        self.checkpoints
+            .iter()
+            .map(|entry| {
+                let fingerprint = entry.key();
+                let checkpoint = checkpoint::Checkpoint {
+                    position: entry.value().to_string(),
+                    updated_at: self
+                        .modified_times
+                        .get(fingerprint)
+                        .map(|r| *r.value())
+                        .unwrap_or_else(Utc::now),
+                };
+                (encode_key(*fingerprint), checkpoint)
+            })
+            .collect()
+    }
+
     fn maybe_upgrade(
         &self,
         path: &Path,
@@ -191,7 +212,10 @@ impl CheckpointsView {
 }
 
 impl Checkpointer {
-    pub fn new(data_dir: &Path) -> Checkpointer {
+    /// Creates a checkpointer keeping the checkpoints of `component` in the
+    /// shared checkpoint store. The checkpoints kept in `data_dir` before the
+    /// store existed are migrated to it.
+    pub fn new(store: &CheckpointStore, component: &str, data_dir: &Path) -> Checkpointer {
         let directory = data_dir.join("checkpoints");
         let glob_string = directory.join("*").to_string_lossy().into_owned();
         let tmp_file_path = data_dir.join(TMP_FILE_NAME);
@@ -202,8 +226,10 @@ impl Checkpointer {
             glob_string,
             tmp_file_path,
             stable_file_path,
+            store: store.clone(),
+            component: component.to_owned(),
             checkpoints: Arc::new(CheckpointsView::default()),
-            last: Mutex::new(None),
+            persisted: Mutex::new(None),
         }
     }
 
@@ -224,6 +250,7 @@ impl Checkpointer {
             BytesChecksum(c) => format!("g{:x}.{}", c, pos),
             FirstLinesChecksum(c) => format!("h{:x}.{}", c, pos),
             DevInode(dev, ino) => format!("i{:x}.{:x}.{}", dev, ino, pos),
+            PathChecksum(c) => format!("j{:x}.{}", c, pos),
             Unknown(x) => format!("{:x}.{}", x, pos),
         };
         self.directory.join(path)
@@ -255,6 +282,10 @@ impl Checkpointer {
                         .unwrap();
                 (DevInode(dev, ino), pos)
             }
+            'j' => {
+                let (c, pos) = scan_fmt!(file_name, "j{x}.{}", [hex u64], FilePosition).unwrap();
+                (PathChecksum(c), pos)
+            }
             _ => {
                 let (c, pos) = scan_fmt!(file_name, "{x}.{}", [hex u64], FilePosition).unwrap();
                 (Unknown(c), pos)
@@ -285,9 +316,8 @@ impl Checkpointer {
             .maybe_upgrade(path, fresh, fingerprinter, fingerprint_buffer)
     }
 
-    /// Persist the current checkpoints state to disk, making our best effort to
-    /// do so in an atomic way that allow for recovering the previous state in
-    /// the event of a crash.
+    /// Persist the current checkpoints state to the checkpoint store, which
+    /// replaces them atomically.
     pub fn write_checkpoints(&self) -> Result<usize, io::Error> {
         // First drop any checkpoints for files that were removed more than 60
         // seconds ago. This keeps our working set as small as possible and
@@ -295,27 +325,35 @@ impl Checkpointer {
         // matter anymore.
         self.checkpoints.remove_expired();
 
-        let current = self.checkpoints.get_state();
-
-        // Fetch last written state.
-        let mut last = self.last.lock().expect("Data poisoned.");
-        if last.as_ref() != Some(&current) {
-            // Write the new checkpoints to a tmp file and flush it fully to
-            // disk. If vector dies anywhere during this section, the existing
-            // stable file will still be in its current valid state and we'll be
-            // able to recover.
-            let mut f = io::BufWriter::new(fs::File::create(&self.tmp_file_path)?);
-            serde_json::to_writer(&mut f, &current)?;
-            f.into_inner()?.sync_all()?;
-
-            // Once the temp file is fully flushed, rename the tmp file to replace
-            // the previous stable file. This is an atomic operation on POSIX
-            // systems (and the stdlib claims to provide equivalent behavior on
-            // Windows), which should prevent scenarios where we don't have at least
-            // one full valid file to recover from.
-            fs::rename(&self.tmp_file_path, &self.stable_file_path)?;
-
-            *last = Some(current);
+        let current = self.checkpoints.get_stored();
+
+        let mut persisted = self.persisted.lock().expect("Data poisoned.");
+        if persisted.is_none() {
+            *persisted = Some(self.store.namespace(&self.component)?);
+        }
+        let persisted = persisted.as_mut().expect("Checkpoints were just loaded.");
+
+        // Only persist what changed, and nothing at all when nothing did.
+        let previous = persisted.iter().collect::<BTreeMap<_, _>>();
+        let removed = previous
+            .keys()
+            .filter(|key| !current.contains_key(**key))
+            .map(|key| (*key).to_owned())
+            .collect::<Vec<_>>();
+        let updated = current
+            .into_iter()
+            .filter(|(key, checkpoint)| previous.get(key.as_str()) != Some(&checkpoint))
+            .collect::<Vec<_>>();
+
+        if !removed.is_empty() || !updated.is_empty() {
+            let mut transaction = persisted.transaction();
+            for key in removed {
+                transaction.remove(key);
+            }
+            for (key, checkpoint) in updated {
+                transaction.set_at(key, checkpoint.position, checkpoint.updated_at);
+            }
+            transaction.commit()?;
         }
 
         Ok(self.checkpoints.checkpoints.len())
@@ -333,10 +371,66 @@ impl Checkpointer {
         Ok(self.checkpoints.checkpoints.len())
     }
 
-    /// Read persisted checkpoints from disk, preferring the new JSON file
-    /// format but falling back to the legacy system when those files are found
-    /// instead.
+    /// Read persisted checkpoints from the checkpoint store, migrating the
+    /// ones written before it when the store doesn't have any yet.
     pub fn read_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) {
+        let stored = match self.store.namespace(&self.component) {
+            Ok(stored) => stored,
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                // Start over rather than failing every write, like an
+                // unreadable checkpoints file used to be overwritten.
+                warn!(message = "Unable to load checkpoint data, resetting it.", %error);
+                if let Err(error) = self.store.reset(&self.component) {
+                    error!(message = "Unable to reset checkpoint data.", %error);
+                }
+                return;
+            }
+            Err(error) => {
+                warn!(message = "Unable to load checkpoint data.", %error);
+                return;
+            }
+        };
+
+        let is_empty = stored.is_empty();
+        if !is_empty {
+            info!(message = "Loaded checkpoint data.");
+            self.checkpoints.set_stored(&stored, ignore_before);
+        }
+        *self.persisted.get_mut().expect("Data poisoned.") = Some(stored);
+
+        if is_empty {
+            self.migrate_legacy_checkpoints(ignore_before);
+        }
+    }
+
+    /// Moves the checkpoints written before the checkpoint store, preferring
+    /// the JSON file format but falling back to the legacy system when those
+    /// files are found instead.
+    fn migrate_legacy_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) {
+        if !self.read_legacy_checkpoints_files(ignore_before) {
+            if !self.directory.is_dir() {
+                return;
+            }
+            info!("Attempting to read legacy checkpoint files.");
+            self.read_legacy_checkpoints(ignore_before);
+        }
+
+        match self.write_checkpoints() {
+            Ok(_) => {
+                info!(message = "Migrated legacy checkpoint data to the checkpoint store.");
+                fs::remove_file(&self.tmp_file_path).ok();
+                fs::remove_file(&self.stable_file_path).ok();
+                fs::remove_dir_all(&self.directory).ok();
+            }
+            Err(error) => {
+                error!(message = "Unable to migrate legacy checkpoint data.", %error);
+            }
+        }
+    }
+
+    /// Reads the checkpoints of the JSON file format, returning whether any
+    /// file was found.
+    fn read_legacy_checkpoints_files(&mut self, ignore_before: Option<DateTime<Utc>>) -> bool {
         // First try reading from the tmp file location. If this works, it means
         // that the previous process was interrupted in the process of
         // checkpointing and the tmp file should contain more recent data that
@@ -345,13 +439,7 @@ impl Checkpointer {
             Ok(state) => {
                 warn!(message = "Recovered checkpoint data from interrupted process.");
                 self.checkpoints.set_state(state, ignore_before);
-
-                // Try to move this tmp file to the stable location so we don't
-                // immediately overwrite it when we next persist checkpoints.
-                if let Err(error) = fs::rename(&self.tmp_file_path, &self.stable_file_path) {
-                    warn!(message = "Error persisting recovered checkpoint file.", %error);
-                }
-                return;
+                return true;
             }
             Err(error) if error.kind() == io::ErrorKind::NotFound => {
                 // This is expected, so no warning needed
@@ -361,32 +449,19 @@ impl Checkpointer {
             }
         }
 
-        // Next, attempt to read checkpoints from the stable file location. This
-        // is the expected location, so warn more aggressively if something goes
-        // wrong.
+        // Next, attempt to read checkpoints from the stable file location.
         match self.read_checkpoints_file(&self.stable_file_path) {
             Ok(state) => {
-                info!(message = "Loaded checkpoint data.");
+                info!(message = "Loaded legacy checkpoint data.");
                 self.checkpoints.set_state(state, ignore_before);
-                return;
-            }
-            Err(error) if error.kind() == io::ErrorKind::NotFound => {
-                // This is expected, so no warning needed
+                true
             }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => false,
             Err(error) => {
-                warn!(message = "Unable to load checkpoint data.", %error);
-                return;
+                warn!(message = "Unable to load legacy checkpoint data.", %error);
+                true
             }
         }
-
-        // If we haven't returned yet, go ahead and look for the legacy files
-        // and try to read them.
-        info!("Attempting to read legacy checkpoint files.");
-        self.read_legacy_checkpoints(ignore_before);
-
-        if self.write_checkpoints().is_ok() {
-            fs::remove_dir_all(&self.directory).ok();
-        }
     }
 
     fn read_checkpoints_file(&self, path: &Path) -> Result<State, io::Error> {
@@ -397,15 +472,12 @@ impl Checkpointer {
     fn read_legacy_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) {
         for path in glob(&self.glob_string).unwrap().flatten() {
             let mut mtime = None;
-            if let Some(ignore_before) = ignore_before {
-                if let Ok(Ok(modified)) = fs::metadata(&path).map(|metadata| metadata.modified()) {
-                    let modified = DateTime::<Utc>::from(modified);
-                    if modified < ignore_before {
-                        fs::remove_file(path).ok();
-                        continue;
-                    }
-                    mtime = Some(modified);
+            if let Ok(Ok(modified)) = fs::metadata(&path).map(|metadata| metadata.modified()) {
+                let modified = DateTime::<Utc>::from(modified);
+                if ignore_before.map_or(false, |ignore_before| modified < ignore_before) {
+                    continue;
                 }
+                mtime = Some(modified);
             }
             let (fng, pos) = self.decode(&path);
             self.checkpoints.checkpoints.insert(fng, pos);
@@ -416,6 +488,37 @@ impl Checkpointer {
     }
 }
 
+/// Encodes a fingerprint into the key of its checkpoint in the store, named
+/// after its variant in the JSON file format.
+fn encode_key(fng: FileFingerprint) -> String {
+    use FileFingerprint::*;
+
+    match fng {
+        BytesChecksum(c) => format!("checksum:{}", c),
+        FirstLinesChecksum(c) => format!("first_lines_checksum:{}", c),
+        DevInode(dev, ino) => format!("dev_inode:{}:{}", dev, ino),
+        PathChecksum(c) => format!("path_checksum:{}", c),
+        Unknown(x) => format!("unknown:{}", x),
+    }
+}
+
+fn decode_key(key: &str) -> Option<FileFingerprint> {
+    use FileFingerprint::*;
+
+    let (kind, value) = key.split_once(':')?;
+    match kind {
+        "checksum" => value.parse().ok().map(BytesChecksum),
+        "first_lines_checksum" => value.parse().ok().map(FirstLinesChecksum),
+        "dev_inode" => {
+            let (dev, ino) = value.split_once(':')?;
+            Some(DevInode(dev.parse().ok()?, ino.parse().ok()?))
+        }
+        "path_checksum" => value.parse().ok().map(PathChecksum),
+        "unknown" => value.parse().ok().map(Unknown),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{
@@ -424,7 +527,19 @@ mod test {
     };
     use chrono::{Duration, Utc};
     use pretty_assertions::assert_eq;
+    use std::path::Path;
     use tempfile::tempdir;
+    use vector_core::checkpoint::CheckpointStore;
+
+    /// A checkpointer for a component laid out like the file source's, with
+    /// its legacy checkpoints in its own subdirectory of the data directory.
+    fn checkpointer(data_dir: &Path) -> Checkpointer {
+        Checkpointer::new(
+            &CheckpointStore::new(data_dir),
+            "file",
+            &data_dir.join("file"),
+        )
+    }
 
     #[test]
     fn test_checkpointer_basics() {
@@ -432,12 +547,13 @@ mod test {
             FileFingerprint::DevInode(1, 2),
             FileFingerprint::BytesChecksum(3456),
             FileFingerprint::FirstLinesChecksum(78910),
+            FileFingerprint::PathChecksum(1112),
             FileFingerprint::Unknown(1337),
         ];
         for fingerprint in fingerprints {
             let position: FilePosition = 1234;
             let data_dir = tempdir().unwrap();
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             assert_eq!(
                 chkptr.decode(&chkptr.encode(fingerprint, position)),
                 (fingerprint, position)
@@ -472,7 +588,7 @@ mod test {
 
         // load and persist the checkpoints
         {
-            let chkptr = Checkpointer::new(data_dir.path());
+            let chkptr = checkpointer(data_dir.path());
 
             for (fingerprint, modified) in &[&newer, &newish, &oldish, &older] {
                 chkptr.checkpoints.load(Checkpoint {
@@ -487,7 +603,7 @@ mod test {
 
         // read them back and assert old are removed
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.read_checkpoints(ignore_before);
 
             assert_eq!(chkptr.get_checkpoint(newish.0), Some(position));
@@ -509,13 +625,13 @@ mod test {
             let position: FilePosition = 1234;
             let data_dir = tempdir().unwrap();
             {
-                let mut chkptr = Checkpointer::new(data_dir.path());
+                let mut chkptr = checkpointer(data_dir.path());
                 chkptr.update_checkpoint(fingerprint, position);
                 assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
                 chkptr.write_checkpoints().ok();
             }
             {
-                let mut chkptr = Checkpointer::new(data_dir.path());
+                let mut chkptr = checkpointer(data_dir.path());
                 assert_eq!(chkptr.get_checkpoint(fingerprint), None);
                 chkptr.read_checkpoints(None);
                 assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
@@ -543,13 +659,13 @@ mod test {
 
         let data_dir = tempdir().unwrap();
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.update_checkpoint(old_fingerprint, position);
             assert_eq!(chkptr.get_checkpoint(old_fingerprint), Some(position));
             chkptr.write_checkpoints().ok();
         }
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.read_checkpoints(None);
             assert_eq!(chkptr.get_checkpoint(new_fingerprint), None);
 
@@ -584,13 +700,13 @@ mod test {
 
         let data_dir = tempdir().unwrap();
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.update_checkpoint(old_fingerprint, position);
             assert_eq!(chkptr.get_checkpoint(old_fingerprint), Some(position));
             chkptr.write_checkpoints().ok();
         }
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.read_checkpoints(None);
             assert_eq!(chkptr.get_checkpoint(new_fingerprint), None);
 
@@ -610,35 +726,32 @@ mod test {
 
         // Write out checkpoints in the legacy file format
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.update_checkpoint(fingerprint, position);
             assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
             chkptr.write_legacy_checkpoints().unwrap();
         }
 
-        // Ensure that the new files were not written but the old style of files were
-        assert!(!data_dir.path().join(TMP_FILE_NAME).exists());
-        assert!(!data_dir.path().join(STABLE_FILE_NAME).exists());
-        assert!(data_dir.path().join("checkpoints").is_dir());
+        // Ensure that the store was not written but the old style of files were
+        let store_path = data_dir.path().join("checkpoints").join("file.json");
+        assert!(!store_path.exists());
+        assert!(data_dir.path().join("file").join("checkpoints").is_dir());
 
-        // Read from those old files, ensure the checkpoints were loaded properly, and then write
-        // them normally (i.e. in the new format)
+        // Read from those old files, ensure the checkpoints were loaded properly and migrated
+        // to the store
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.read_checkpoints(None);
             assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
-            chkptr.write_checkpoints().unwrap();
         }
 
-        // Ensure that the stable file is present, the tmp file is not, and the legacy files have
-        // been cleaned up
-        assert!(!data_dir.path().join(TMP_FILE_NAME).exists());
-        assert!(data_dir.path().join(STABLE_FILE_NAME).exists());
-        assert!(!data_dir.path().join("checkpoints").is_dir());
+        // Ensure that the store is present and the legacy files have been cleaned up
+        assert!(store_path.exists());
+        assert!(!data_dir.path().join("file").join("checkpoints").is_dir());
 
-        // Ensure one last time that we can reread from the new files and get the same result
+        // Ensure one last time that we can reread from the store and get the same result
         {
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
             chkptr.read_checkpoints(None);
             assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
         }
@@ -655,7 +768,7 @@ mod test {
         ];
 
         let data_dir = tempdir().unwrap();
-        let mut chkptr = Checkpointer::new(data_dir.path());
+        let mut chkptr = checkpointer(data_dir.path());
 
         for (fingerprint, position, removed) in cases.clone() {
             chkptr.update_checkpoint(fingerprint, position);
@@ -717,7 +830,7 @@ mod test {
             _ => panic!("unexpected checksum types"),
         }
 
-        let mut chkptr = Checkpointer::new(data_dir.path());
+        let mut chkptr = checkpointer(data_dir.path());
 
         // pretend that we had loaded this old style checksum from disk after an upgrade
         chkptr.update_checkpoint(old, 1234);
@@ -737,19 +850,23 @@ mod test {
         let fingerprints = vec![
             (
                 FileFingerprint::DevInode(1, 2),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"dev_inode":[1,2]},"position":1234}]}"#,
+                r#"{"version":1,"checkpoints":{"dev_inode:1:2":{"position":"1234"}}}"#,
             ),
             (
                 FileFingerprint::BytesChecksum(3456),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"checksum":3456},"position":1234}]}"#,
+                r#"{"version":1,"checkpoints":{"checksum:3456":{"position":"1234"}}}"#,
             ),
             (
                 FileFingerprint::FirstLinesChecksum(78910),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"first_lines_checksum":78910},"position":1234}]}"#,
+                r#"{"version":1,"checkpoints":{"first_lines_checksum:78910":{"position":"1234"}}}"#,
+            ),
+            (
+                FileFingerprint::PathChecksum(1112),
+                r#"{"version":1,"checkpoints":{"path_checksum:1112":{"position":"1234"}}}"#,
             ),
             (
                 FileFingerprint::Unknown(1337),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"unknown":1337},"position":1234}]}"#,
+                r#"{"version":1,"checkpoints":{"unknown:1337":{"position":"1234"}}}"#,
             ),
         ];
         for (fingerprint, expected) in fingerprints {
@@ -757,16 +874,21 @@ mod test {
 
             let position: FilePosition = 1234;
             let data_dir = tempdir().unwrap();
-            let mut chkptr = Checkpointer::new(data_dir.path());
+            let mut chkptr = checkpointer(data_dir.path());
 
             chkptr.update_checkpoint(fingerprint, position);
             chkptr.write_checkpoints().unwrap();
 
             let got: serde_json::Value = {
-                let s = std::fs::read_to_string(data_dir.path().join("checkpoints.json")).unwrap();
+                let path = data_dir.path().join("checkpoints").join("file.json");
+                let s = std::fs::read_to_string(path).unwrap();
                 let mut checkpoints: serde_json::Value = serde_json::from_str(&s).unwrap();
-                for checkpoint in checkpoints["checkpoints"].as_array_mut().unwrap() {
-                    checkpoint.as_object_mut().unwrap().remove("modified");
+                for checkpoint in checkpoints["checkpoints"]
+                    .as_object_mut()
+                    .unwrap()
+                    .values_mut()
+                {
+                    checkpoint.as_object_mut().unwrap().remove("updated_at");
                 }
                 checkpoints
             };
@@ -775,8 +897,8 @@ mod test {
         }
     }
 
-    // guards against accidental changes to the checkpoint deserialization and tests deserializing
-    // old checkpoint versions
+    // guards against accidental changes to the legacy checkpoint deserialization and tests
+    // migrating old checkpoint versions to the store
     #[test]
     fn test_checkpointer_deserialization() {
         let serialized_checkpoints = r#"
@@ -821,18 +943,55 @@ mod test {
 
         let data_dir = tempdir().unwrap();
 
-        let mut chkptr = Checkpointer::new(data_dir.path());
+        let legacy_path = data_dir.path().join("file").join(STABLE_FILE_NAME);
+
+        std::fs::create_dir(data_dir.path().join("file")).unwrap();
+        std::fs::write(&legacy_path, serialized_checkpoints).unwrap();
 
+        {
+            let mut chkptr = checkpointer(data_dir.path());
+            chkptr.read_checkpoints(None);
+
+            for fingerprint in &fingerprints {
+                assert_eq!(chkptr.get_checkpoint(*fingerprint), Some(1234))
+            }
+        }
+
+        assert!(!legacy_path.exists());
+
+        // the migrated checkpoints are read back from the store, keeping their modified times
+        let mut chkptr = checkpointer(data_dir.path());
+        let modified = "2021-07-12T18:19:11.769003Z".parse().unwrap();
+        chkptr.read_checkpoints(Some(modified));
+
+        for fingerprint in fingerprints {
+            assert_eq!(chkptr.get_checkpoint(fingerprint), Some(1234))
+        }
+    }
+
+    #[test]
+    fn test_checkpointer_recovers_legacy_tmp_file() {
+        let fingerprint = FileFingerprint::DevInode(1, 2);
+        let data_dir = tempdir().unwrap();
+        let legacy_dir = data_dir.path().join("file");
+
+        std::fs::create_dir(&legacy_dir).unwrap();
         std::fs::write(
-            data_dir.path().join("checkpoints.json"),
-            serialized_checkpoints,
+            legacy_dir.join(STABLE_FILE_NAME),
+            r#"{"version":"1","checkpoints":[{"fingerprint":{"dev_inode":[1,2]},"position":12}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            legacy_dir.join(TMP_FILE_NAME),
+            r#"{"version":"1","checkpoints":[{"fingerprint":{"dev_inode":[1,2]},"position":34}]}"#,
         )
         .unwrap();
 
+        let mut chkptr = checkpointer(data_dir.path());
         chkptr.read_checkpoints(None);
 
-        for fingerprint in fingerprints {
-            assert_eq!(chkptr.get_checkpoint(fingerprint), Some(1234))
-        }
+        assert_eq!(chkptr.get_checkpoint(fingerprint), Some(34));
+        assert!(!legacy_dir.join(STABLE_FILE_NAME).exists());
+        assert!(!legacy_dir.join(TMP_FILE_NAME).exists());
     }
 }
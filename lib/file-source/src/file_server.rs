@@ -48,6 +48,9 @@ where
     pub fingerprinter: Fingerprinter,
     pub oldest_first: bool,
     pub remove_after: Option<Duration>,
+    /// Reopens files once read to their end, for network filesystems, which
+    /// only guarantee to show the writes of other hosts when opening a file.
+    pub network_filesystem: bool,
     pub emitter: E,
     pub handle: tokio::runtime::Handle,
 }
@@ -282,6 +285,25 @@ where
                 }
                 stats.record("reading", start.elapsed());
 
+                if self.network_filesystem
+                    && bytes_read <= self.max_read_bytes
+                    && watcher.reopened_at().elapsed() >= self.glob_minimum_cooldown
+                {
+                    // The file may have been replaced since it was opened, so
+                    // it is only reopened if its fingerprint is still the same.
+                    let current_id = self.fingerprinter.get_fingerprint_or_log_error(
+                        &watcher.path,
+                        &mut fingerprint_buffer,
+                        &mut known_small_files,
+                        &self.emitter,
+                    );
+                    if current_id == Some(file_id) {
+                        if let Err(error) = watcher.reopen() {
+                            debug!(message = "Failed reopening file.", path = ?watcher.path, %error);
+                        }
+                    }
+                }
+
                 if bytes_read > 0 {
                     global_bytes_read = global_bytes_read.saturating_add(bytes_read);
                 } else {
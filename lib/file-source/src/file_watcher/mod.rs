@@ -28,9 +28,11 @@ pub struct FileWatcher {
     file_position: FilePosition,
     devno: u64,
     inode: u64,
+    compressed: bool,
     is_dead: bool,
     last_read_attempt: Instant,
     last_read_success: Instant,
+    reopened_at: Instant,
     max_line_bytes: usize,
     line_delimiter: Bytes,
     buf: BytesMut,
@@ -129,9 +131,11 @@ impl FileWatcher {
             file_position,
             devno,
             inode: ino,
+            compressed: gzipped,
             is_dead: false,
             last_read_attempt: ts,
             last_read_success: ts,
+            reopened_at: Instant::now(),
             max_line_bytes,
             line_delimiter,
             buf: BytesMut::new(),
@@ -156,11 +160,45 @@ impl FileWatcher {
             self.reader = new_reader;
             self.devno = file_handle.portable_dev()?;
             self.inode = file_handle.portable_ino()?;
+            self.compressed = gzipped;
         }
         self.path = path;
         Ok(())
     }
 
+    /// Reopens the file at its path, resuming from the current position, or
+    /// from the start if the file has been truncated below it.
+    ///
+    /// Clients of network filesystems such as NFS only guarantee to see the
+    /// writes of other hosts when opening a file, so files on those are
+    /// reopened once read to their end, rather than kept open.
+    pub fn reopen(&mut self) -> io::Result<()> {
+        self.reopened_at = Instant::now();
+        if self.compressed {
+            return Ok(());
+        }
+
+        let mut file_handle = File::open(&self.path)?;
+        if file_handle.metadata()?.len() < self.file_position {
+            debug!(
+                message = "File has been truncated, reading from the start.",
+                path = ?self.path,
+                file_position = %self.file_position,
+            );
+            self.file_position = 0;
+            self.buf.clear();
+        }
+        file_handle.seek(io::SeekFrom::Start(self.file_position))?;
+        self.devno = file_handle.portable_dev()?;
+        self.inode = file_handle.portable_ino()?;
+        self.reader = Box::new(io::BufReader::new(file_handle));
+        Ok(())
+    }
+
+    pub fn reopened_at(&self) -> Instant {
+        self.reopened_at
+    }
+
     pub fn set_file_findable(&mut self, f: bool) {
         self.findable = f;
     }
@@ -220,7 +258,7 @@ impl FileWatcher {
                 }
             }
             Err(e) => {
-                if let io::ErrorKind::NotFound = e.kind() {
+                if e.kind() == io::ErrorKind::NotFound || is_stale(&e) {
                     self.set_dead();
                 }
                 Err(e)
@@ -257,6 +295,18 @@ fn is_gzipped(r: &mut io::BufReader<fs::File>) -> io::Result<bool> {
     Ok(header_bytes.starts_with(&[0x1f, 0x8b]))
 }
 
+/// Whether the error is a stale file handle, returned by network filesystems
+/// once the file has been removed by another host.
+#[cfg(unix)]
+fn is_stale(error: &io::Error) -> bool {
+    error.raw_os_error() == Some(libc::ESTALE)
+}
+
+#[cfg(not(unix))]
+const fn is_stale(_error: &io::Error) -> bool {
+    false
+}
+
 fn null_reader() -> impl BufRead {
     io::Cursor::new(Vec::new())
 }
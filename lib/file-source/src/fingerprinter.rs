@@ -30,6 +30,10 @@ pub enum FingerprintStrategy {
         lines: usize,
     },
     DevInode,
+    /// Identifies files by their path, for filesystems which can't be trusted
+    /// to keep device and inode numbers stable, such as network filesystems,
+    /// and whose files can't be told apart by their content.
+    Path,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, Ord, PartialOrd)]
@@ -40,6 +44,7 @@ pub enum FileFingerprint {
     #[serde(alias = "first_line_checksum")]
     FirstLinesChecksum(u64),
     DevInode(u64, u64),
+    PathChecksum(u64),
     Unknown(u64),
 }
 
@@ -56,6 +61,7 @@ impl FileFingerprint {
                 buf.write_all(&ino.to_be_bytes()).expect("writing to array");
                 FINGERPRINT_CRC.checksum(&buf[..])
             }
+            PathChecksum(c) => *c,
             Unknown(c) => *c,
         }
     }
@@ -82,6 +88,10 @@ impl Fingerprinter {
                 let ino = file_handle.portable_ino()?;
                 Ok(DevInode(dev, ino))
             }
+            FingerprintStrategy::Path => {
+                let path = path.to_string_lossy();
+                Ok(PathChecksum(FINGERPRINT_CRC.checksum(path.as_bytes())))
+            }
             FingerprintStrategy::Checksum {
                 ignored_header_bytes,
                 bytes: _,
@@ -270,6 +280,42 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_path_fingerprint() {
+        let fingerprinter = Fingerprinter {
+            strategy: FingerprintStrategy::Path,
+            max_line_length: 1024,
+            ignore_not_found: false,
+        };
+
+        let target_dir = tempdir().unwrap();
+        let path = target_dir.path().join("app.log");
+        let other_path = target_dir.path().join("other.log");
+        fs::write(&path, b"first\n").unwrap();
+        fs::write(&other_path, b"first\n").unwrap();
+
+        let mut buf = Vec::new();
+        let fingerprint = fingerprinter
+            .get_fingerprint_of_file(&path, &mut buf)
+            .unwrap();
+        assert_ne!(
+            fingerprint,
+            fingerprinter
+                .get_fingerprint_of_file(&other_path, &mut buf)
+                .unwrap()
+        );
+
+        // Replacing the file, even with a different content, keeps its identity.
+        fs::remove_file(&path).unwrap();
+        fs::write(&path, b"").unwrap();
+        assert_eq!(
+            fingerprint,
+            fingerprinter
+                .get_fingerprint_of_file(&path, &mut buf)
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_first_line_checksum_fingerprint() {
         let max_line_length = 64;
@@ -15,6 +15,9 @@ pub use datetime::TimeZone;
 pub mod event_data_eq;
 pub use event_data_eq::EventDataEq;
 
+#[cfg(feature = "severity")]
+pub mod severity;
+
 #[cfg(feature = "tokenize")]
 pub mod tokenize;
 
@@ -0,0 +1,293 @@
+//! Normalization of the severities reported by the many logging conventions
+//! out there to the eight severities of syslog (RFC 5424).
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// A canonical severity, ordered from the most to the least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Emergency,
+    Alert,
+    Critical,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 8] = [
+        Severity::Emergency,
+        Severity::Alert,
+        Severity::Critical,
+        Severity::Error,
+        Severity::Warning,
+        Severity::Notice,
+        Severity::Info,
+        Severity::Debug,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Severity::Emergency => "emergency",
+            Severity::Alert => "alert",
+            Severity::Critical => "critical",
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Notice => "notice",
+            Severity::Info => "info",
+            Severity::Debug => "debug",
+        }
+    }
+
+    /// The syslog severity number, from `0` (emergency) to `7` (debug).
+    pub const fn number(self) -> u8 {
+        self as u8
+    }
+
+    /// Recognizes the names used by syslog, RFC 5424, Windows event logs,
+    /// Python, Java (log4j and java.util.logging), Go and OpenTelemetry,
+    /// regardless of their case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let severity = match name.trim().to_ascii_lowercase().as_str() {
+            "emerg" | "emergency" | "panic" => Severity::Emergency,
+            "alert" => Severity::Alert,
+            "crit" | "critical" | "fatal" => Severity::Critical,
+            "err" | "error" | "severe" => Severity::Error,
+            "warn" | "warning" => Severity::Warning,
+            "notice" => Severity::Notice,
+            "info" | "informational" | "information" => Severity::Info,
+            "debug" | "trace" | "verbose" | "fine" | "finer" | "finest" => Severity::Debug,
+            _ => return None,
+        };
+        Some(severity)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    /// Parses a canonical severity name, unlike [`Severity::from_name`] which
+    /// also recognizes their aliases.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|severity| severity.as_str() == s)
+            .ok_or_else(|| format!("unknown severity {:?}", s))
+    }
+}
+
+/// How numeric severities are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumericScale {
+    /// Syslog severities, from `0` (emergency) to `7` (debug).
+    Syslog,
+    /// Windows event log levels, from `1` (critical) to `5` (verbose).
+    Windows,
+    /// Python logging levels, from `10` (debug) to `50` (critical).
+    Python,
+    /// OpenTelemetry severity numbers, from `1` (trace) to `24` (fatal).
+    Opentelemetry,
+}
+
+impl Default for NumericScale {
+    fn default() -> Self {
+        NumericScale::Syslog
+    }
+}
+
+impl NumericScale {
+    pub const ALL: [NumericScale; 4] = [
+        NumericScale::Syslog,
+        NumericScale::Windows,
+        NumericScale::Python,
+        NumericScale::Opentelemetry,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            NumericScale::Syslog => "syslog",
+            NumericScale::Windows => "windows",
+            NumericScale::Python => "python",
+            NumericScale::Opentelemetry => "opentelemetry",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|scale| scale.as_str() == name)
+    }
+
+    pub const fn severity(self, number: i64) -> Option<Severity> {
+        let severity = match self {
+            NumericScale::Syslog => match number {
+                0 => Severity::Emergency,
+                1 => Severity::Alert,
+                2 => Severity::Critical,
+                3 => Severity::Error,
+                4 => Severity::Warning,
+                5 => Severity::Notice,
+                6 => Severity::Info,
+                7 => Severity::Debug,
+                _ => return None,
+            },
+            NumericScale::Windows => match number {
+                1 => Severity::Critical,
+                2 => Severity::Error,
+                3 => Severity::Warning,
+                // `0` is "log always", which events of the information level
+                // are often reported with.
+                0 | 4 => Severity::Info,
+                5 => Severity::Debug,
+                _ => return None,
+            },
+            // Custom Python levels fall between the standard ones, and are
+            // rounded down to them.
+            NumericScale::Python => match number {
+                50..=i64::MAX => Severity::Critical,
+                40..=49 => Severity::Error,
+                30..=39 => Severity::Warning,
+                20..=29 => Severity::Info,
+                0..=19 => Severity::Debug,
+                _ => return None,
+            },
+            NumericScale::Opentelemetry => match number {
+                1..=8 => Severity::Debug,
+                9..=12 => Severity::Info,
+                13..=16 => Severity::Warning,
+                17..=20 => Severity::Error,
+                21..=24 => Severity::Critical,
+                _ => return None,
+            },
+        };
+        Some(severity)
+    }
+}
+
+/// Derives canonical severities, from a custom mapping first, then from the
+/// known names or the numeric scale.
+#[derive(Debug, Clone, Default)]
+pub struct SeverityMapper {
+    mapping: HashMap<String, Severity>,
+    scale: NumericScale,
+}
+
+impl SeverityMapper {
+    /// Builds a mapper whose custom `mapping` matches values regardless of
+    /// their case.
+    pub fn new(mapping: impl IntoIterator<Item = (String, Severity)>, scale: NumericScale) -> Self {
+        Self {
+            mapping: mapping
+                .into_iter()
+                .map(|(value, severity)| (value.trim().to_ascii_lowercase(), severity))
+                .collect(),
+            scale,
+        }
+    }
+
+    pub fn map_str(&self, value: &str) -> Option<Severity> {
+        let value = value.trim();
+        self.custom(value)
+            .or_else(|| Severity::from_name(value))
+            .or_else(|| {
+                value
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|number| self.scale.severity(number))
+            })
+    }
+
+    pub fn map_integer(&self, value: i64) -> Option<Severity> {
+        self.custom(&value.to_string())
+            .or_else(|| self.scale.severity(value))
+    }
+
+    fn custom(&self, value: &str) -> Option<Severity> {
+        if self.mapping.is_empty() {
+            return None;
+        }
+        self.mapping.get(&value.to_ascii_lowercase()).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_names() {
+        let mapper = SeverityMapper::default();
+        for (name, severity) in [
+            ("EMERG", Severity::Emergency),
+            ("alert", Severity::Alert),
+            ("Fatal", Severity::Critical),
+            ("SEVERE", Severity::Error),
+            ("err", Severity::Error),
+            ("WARN", Severity::Warning),
+            ("notice", Severity::Notice),
+            ("Informational", Severity::Info),
+            ("finest", Severity::Debug),
+            ("TRACE", Severity::Debug),
+        ] {
+            assert_eq!(mapper.map_str(name), Some(severity), "{}", name);
+        }
+        assert_eq!(mapper.map_str("bogus"), None);
+    }
+
+    #[test]
+    fn interprets_numbers_by_scale() {
+        let syslog = SeverityMapper::default();
+        assert_eq!(syslog.map_integer(3), Some(Severity::Error));
+        assert_eq!(syslog.map_str("6"), Some(Severity::Info));
+        assert_eq!(syslog.map_integer(8), None);
+
+        let windows = SeverityMapper::new(Vec::new(), NumericScale::Windows);
+        assert_eq!(windows.map_integer(1), Some(Severity::Critical));
+        assert_eq!(windows.map_integer(0), Some(Severity::Info));
+
+        let python = SeverityMapper::new(Vec::new(), NumericScale::Python);
+        assert_eq!(python.map_integer(30), Some(Severity::Warning));
+        assert_eq!(python.map_integer(25), Some(Severity::Info));
+        assert_eq!(python.map_integer(-1), None);
+
+        let otel = SeverityMapper::new(Vec::new(), NumericScale::Opentelemetry);
+        assert_eq!(otel.map_integer(9), Some(Severity::Info));
+        assert_eq!(otel.map_integer(21), Some(Severity::Critical));
+        assert_eq!(otel.map_integer(0), None);
+    }
+
+    #[test]
+    fn prefers_custom_mapping() {
+        let mapper = SeverityMapper::new(
+            vec![
+                ("FATAL".to_owned(), Severity::Emergency),
+                ("7".to_owned(), Severity::Info),
+                ("chatty".to_owned(), Severity::Debug),
+            ],
+            NumericScale::Syslog,
+        );
+        assert_eq!(mapper.map_str("fatal"), Some(Severity::Emergency));
+        assert_eq!(mapper.map_integer(7), Some(Severity::Info));
+        assert_eq!(mapper.map_str("Chatty"), Some(Severity::Debug));
+        assert_eq!(mapper.map_str("error"), Some(Severity::Error));
+    }
+
+    #[test]
+    fn parses_canonical_names_only() {
+        assert_eq!("warning".parse(), Ok(Severity::Warning));
+        assert!("warn".parse::<Severity>().is_err());
+    }
+}
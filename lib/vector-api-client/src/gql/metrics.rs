@@ -123,6 +123,26 @@ pub struct ComponentProcessedBytesThroughputsSubscription;
 )]
 pub struct ComponentProcessedBytesTotalsSubscription;
 
+/// ComponentAllocatedBytesSubscription contains the approximate number of bytes held by
+/// each component, including its buffer and its state.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_allocated_bytes.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentAllocatedBytesSubscription;
+
+/// ComponentErrorsTotalsSubscription contains metrics on the number of errors encountered
+/// by specific components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsTotalsSubscription;
+
 /// ComponentReceivedEventsThroughputsSubscription contains metrics on the number of events
 /// that have been accepted for processing between `interval` samples, against specific components.
 #[derive(GraphQLQuery, Debug, Copy, Clone)]
@@ -234,6 +254,18 @@ pub trait MetricsSubscriptionExt {
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentProcessedBytesThroughputsSubscription>;
 
+    /// Executes a component allocated bytes subscription.
+    fn component_allocated_bytes_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentAllocatedBytesSubscription>;
+
+    /// Executes a component errors totals subscription.
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
+
     /// Executes a component received events totals subscription.
     fn component_received_events_totals_subscription(
         &self,
@@ -400,6 +432,30 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
         self.start::<ComponentProcessedBytesThroughputsSubscription>(&request_body)
     }
 
+    /// Executes an all component allocated bytes subscription.
+    fn component_allocated_bytes_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentAllocatedBytesSubscription> {
+        let request_body = ComponentAllocatedBytesSubscription::build_query(
+            component_allocated_bytes_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentAllocatedBytesSubscription>(&request_body)
+    }
+
+    /// Executes an all component errors totals subscription.
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentErrorsTotalsSubscription> {
+        let request_body = ComponentErrorsTotalsSubscription::build_query(
+            component_errors_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsTotalsSubscription>(&request_body)
+    }
+
     /// Executes an all component received events totals subscription.
     fn component_received_events_totals_subscription(
         &self,
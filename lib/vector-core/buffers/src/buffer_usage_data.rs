@@ -1,11 +1,11 @@
 use core_common::internal_event::emit;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
 use std::{sync::Arc, time::Duration};
 use tokio::time::interval;
 use tracing::{Instrument, Span};
 
 use crate::internal_events::{
-    BufferCreated, BufferEventsReceived, BufferEventsSent, EventsDropped,
+    BufferCreated, BufferEventsEvicted, BufferEventsReceived, BufferEventsSent, EventsDropped,
 };
 use crate::WhenFull;
 
@@ -15,6 +15,12 @@ pub struct BufferUsageData {
     sent_event_count: AtomicU64,
     sent_byte_size: AtomicUsize,
     dropped_event_count: Option<AtomicU64>,
+    evicted_event_count: AtomicU64,
+    evicted_byte_size: AtomicUsize,
+    // Unlike the counts above these are never reset, and go below zero when
+    // events are counted as sent before being counted as received.
+    buffered_event_count: AtomicI64,
+    buffered_byte_size: AtomicI64,
     max_size_bytes: Option<usize>,
     max_size_events: Option<usize>,
 }
@@ -37,16 +43,26 @@ impl BufferUsageData {
             sent_event_count: AtomicU64::new(0),
             sent_byte_size: AtomicUsize::new(0),
             dropped_event_count,
+            evicted_event_count: AtomicU64::new(0),
+            evicted_byte_size: AtomicUsize::new(0),
+            buffered_event_count: AtomicI64::new(0),
+            buffered_byte_size: AtomicI64::new(0),
             max_size_bytes,
             max_size_events,
         });
 
-        let usage_data = buffer_usage_data.clone();
+        // The reporting task holds a weak reference so the data is dropped,
+        // and the task stops, along with the buffer.
+        let usage_data = Arc::downgrade(&buffer_usage_data);
         tokio::spawn(
             async move {
                 let mut interval = interval(Duration::from_secs(2));
                 loop {
                     interval.tick().await;
+                    let usage_data = match usage_data.upgrade() {
+                        Some(usage_data) => usage_data,
+                        None => break,
+                    };
 
                     emit(&BufferCreated {
                         max_size_bytes: usage_data.max_size_bytes,
@@ -67,6 +83,11 @@ impl BufferUsageData {
                         emit(&EventsDropped {
                             count: dropped_event_count.swap(0, Ordering::Relaxed),
                         });
+
+                        emit(&BufferEventsEvicted {
+                            count: usage_data.evicted_event_count.swap(0, Ordering::Relaxed),
+                            byte_size: usage_data.evicted_byte_size.swap(0, Ordering::Relaxed),
+                        });
                     }
                 }
             }
@@ -81,11 +102,54 @@ impl BufferUsageData {
             .fetch_add(count, Ordering::Relaxed);
         self.received_byte_size
             .fetch_add(byte_size, Ordering::Relaxed);
+        self.add_buffered(count, byte_size);
     }
 
     pub fn increment_sent_event_count_and_byte_size(&self, count: u64, byte_size: usize) {
         self.sent_event_count.fetch_add(count, Ordering::Relaxed);
         self.sent_byte_size.fetch_add(byte_size, Ordering::Relaxed);
+        self.remove_buffered(count, byte_size);
+    }
+
+    /// Counts events dropped from a full buffer to make room for events of a
+    /// higher priority.
+    pub fn increment_evicted_event_count_and_byte_size(&self, count: u64, byte_size: usize) {
+        self.evicted_event_count.fetch_add(count, Ordering::Relaxed);
+        self.evicted_byte_size
+            .fetch_add(byte_size, Ordering::Relaxed);
+        self.remove_buffered(count, byte_size);
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn add_buffered(&self, count: u64, byte_size: usize) {
+        self.buffered_event_count
+            .fetch_add(count as i64, Ordering::Relaxed);
+        self.buffered_byte_size
+            .fetch_add(byte_size as i64, Ordering::Relaxed);
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn remove_buffered(&self, count: u64, byte_size: usize) {
+        self.buffered_event_count
+            .fetch_sub(count as i64, Ordering::Relaxed);
+        self.buffered_byte_size
+            .fetch_sub(byte_size as i64, Ordering::Relaxed);
+    }
+
+    /// The fraction of the buffer currently in use, by events for buffers
+    /// limited in events and by bytes for those limited in size.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn utilization(&self) -> f64 {
+        let (used, max) = match (self.max_size_events, self.max_size_bytes) {
+            (Some(max), _) => (self.buffered_event_count.load(Ordering::Relaxed), max),
+            (None, Some(max)) => (self.buffered_byte_size.load(Ordering::Relaxed), max),
+            (None, None) => return 0.0,
+        };
+        if max == 0 {
+            return 0.0;
+        }
+        used.max(0) as f64 / max as f64
     }
 
     pub fn try_increment_dropped_event_count(&self, count: u64) {
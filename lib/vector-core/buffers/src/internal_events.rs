@@ -41,6 +41,21 @@ impl InternalEvent for EventsDropped {
     }
 }
 
+pub struct BufferEventsEvicted {
+    pub count: u64,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for BufferEventsEvicted {
+    #[allow(clippy::cast_precision_loss)]
+    fn emit_metrics(&self) {
+        counter!("buffer_discarded_events_total", self.count);
+        counter!("buffer_evicted_events_total", self.count);
+        decrement_gauge!("buffer_events", self.count as f64);
+        decrement_gauge!("buffer_byte_size", self.byte_size as f64);
+    }
+}
+
 pub struct BufferCreated {
     pub max_size_events: Option<usize>,
     pub max_size_bytes: Option<usize>,
@@ -0,0 +1,354 @@
+//! An in-memory buffer split into priority lanes.
+//!
+//! Each item goes into the lane of its priority, the last lane holding the
+//! items of the highest priority. The lanes are drained from the highest to
+//! the lowest, taking up to the weight of each lane from it in turn, so that
+//! lower priorities are slowed down rather than starved.
+//!
+//! When the buffer is full and set to drop the newest items, the newest item
+//! of the lowest priority lane below the priority of an incoming item is
+//! dropped to make room for it, and the incoming item is dropped otherwise.
+
+use crate::buffer_usage_data::BufferUsageData;
+use crate::WhenFull;
+use core_common::byte_size_of::ByteSizeOf;
+use futures::{Sink, Stream};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+type Priority<T> = Arc<dyn Fn(&T) -> usize + Send + Sync>;
+
+struct Lanes<T> {
+    lanes: Vec<VecDeque<T>>,
+    len: usize,
+    senders: usize,
+    receiver_alive: bool,
+    receiver_waker: Option<Waker>,
+    sender_wakers: Vec<Waker>,
+    /// The lane being drained, and how many more items it may give before
+    /// the next lane's turn.
+    draining: usize,
+    credit: usize,
+}
+
+impl<T> Lanes<T> {
+    fn pop(&mut self, weights: &[usize]) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // Every lane has a weight of at least one, so a non-empty lane comes
+        // up within a round.
+        loop {
+            if self.credit > 0 {
+                if let Some(item) = self.lanes[self.draining].pop_front() {
+                    self.credit -= 1;
+                    self.len -= 1;
+                    return Some(item);
+                }
+            }
+
+            self.draining = self.draining.checked_sub(1).unwrap_or(self.lanes.len() - 1);
+            self.credit = weights[self.draining];
+        }
+    }
+
+    fn wake_receiver(&mut self) {
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_senders(&mut self) {
+        for waker in self.sender_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+struct Shared<T> {
+    lanes: Mutex<Lanes<T>>,
+    max_events: usize,
+    weights: Vec<usize>,
+}
+
+/// Creates an in-memory buffer of up to `max_events` items, with a lane per
+/// weight. `priority` gives the lane of an item, the last lane being used for
+/// priorities past it.
+///
+/// # Panics
+///
+/// Panics if there are no weights, or if a weight is zero.
+#[must_use]
+pub fn channel<T>(
+    max_events: usize,
+    weights: Vec<usize>,
+    when_full: WhenFull,
+    priority: impl Fn(&T) -> usize + Send + Sync + 'static,
+    buffer_usage_data: Option<Arc<BufferUsageData>>,
+) -> (Sender<T>, Receiver<T>) {
+    assert!(!weights.is_empty(), "A buffer needs at least one lane.");
+    assert!(
+        weights.iter().all(|weight| *weight > 0),
+        "Lane weights must be positive."
+    );
+
+    let shared = Arc::new(Shared {
+        lanes: Mutex::new(Lanes {
+            lanes: weights.iter().map(|_| VecDeque::new()).collect(),
+            len: 0,
+            senders: 1,
+            receiver_alive: true,
+            receiver_waker: None,
+            sender_wakers: Vec::new(),
+            draining: 0,
+            credit: 0,
+        }),
+        max_events,
+        weights,
+    });
+
+    let sender = Sender {
+        shared: Arc::clone(&shared),
+        priority: Arc::new(priority),
+        when_full,
+        buffer_usage_data,
+    };
+    (sender, Receiver { shared })
+}
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    priority: Priority<T>,
+    when_full: WhenFull,
+    buffer_usage_data: Option<Arc<BufferUsageData>>,
+}
+
+impl<T> Sender<T> {
+    pub(crate) fn buffer_usage_data(&self) -> Option<&Arc<BufferUsageData>> {
+        self.buffer_usage_data.as_ref()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lanes.lock().expect("poisoned lock").senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+            priority: Arc::clone(&self.priority),
+            when_full: self.when_full,
+            buffer_usage_data: self.buffer_usage_data.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut lanes = self.shared.lanes.lock().expect("poisoned lock");
+        lanes.senders -= 1;
+        if lanes.senders == 0 {
+            lanes.wake_receiver();
+        }
+    }
+}
+
+impl<T: ByteSizeOf> Sink<T> for Sender<T> {
+    type Error = ();
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut lanes = self.shared.lanes.lock().expect("poisoned lock");
+        if !lanes.receiver_alive {
+            return Poll::Ready(Err(()));
+        }
+
+        // Full buffers dropping the newest items make room in `start_send`.
+        if self.when_full == WhenFull::DropNewest || lanes.len < self.shared.max_events {
+            Poll::Ready(Ok(()))
+        } else {
+            if !lanes
+                .sender_wakers
+                .iter()
+                .any(|waker| waker.will_wake(cx.waker()))
+            {
+                lanes.sender_wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let lane = (self.priority)(&item).min(self.shared.weights.len() - 1);
+        let mut lanes = self.shared.lanes.lock().expect("poisoned lock");
+        if !lanes.receiver_alive {
+            return Err(());
+        }
+
+        if self.when_full == WhenFull::DropNewest && lanes.len >= self.shared.max_events {
+            let evicted = lanes.lanes[..lane]
+                .iter_mut()
+                .find(|lower| !lower.is_empty())
+                .and_then(VecDeque::pop_back);
+            match evicted {
+                Some(evicted) => {
+                    debug!(
+                        message = "Shedding load; dropping lower priority event.",
+                        internal_log_rate_secs = 10
+                    );
+                    lanes.len -= 1;
+                    if let Some(buffer_usage_data) = &self.buffer_usage_data {
+                        buffer_usage_data
+                            .increment_evicted_event_count_and_byte_size(1, evicted.size_of());
+                    }
+                }
+                None => {
+                    debug!(
+                        message = "Shedding load; dropping event.",
+                        internal_log_rate_secs = 10
+                    );
+                    if let Some(buffer_usage_data) = &self.buffer_usage_data {
+                        buffer_usage_data.try_increment_dropped_event_count(1);
+                    }
+                    return Ok(());
+                }
+            }
+        }
+
+        if let Some(buffer_usage_data) = &self.buffer_usage_data {
+            buffer_usage_data.increment_received_event_count_and_byte_size(1, item.size_of());
+        }
+        lanes.lanes[lane].push_back(item);
+        lanes.len += 1;
+        lanes.wake_receiver();
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut lanes = self.shared.lanes.lock().expect("poisoned lock");
+        lanes.receiver_alive = false;
+        lanes.wake_senders();
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut lanes = self.shared.lanes.lock().expect("poisoned lock");
+        match lanes.pop(&self.shared.weights) {
+            Some(item) => {
+                lanes.wake_senders();
+                Poll::Ready(Some(item))
+            }
+            None if lanes.senders == 0 => Poll::Ready(None),
+            None => {
+                lanes.receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Item {
+        priority: usize,
+        id: u64,
+    }
+
+    impl ByteSizeOf for Item {
+        fn allocated_bytes(&self) -> usize {
+            0
+        }
+    }
+
+    fn item(priority: usize, id: u64) -> Item {
+        Item { priority, id }
+    }
+
+    fn lanes(max_events: usize, when_full: WhenFull) -> (Sender<Item>, Receiver<Item>) {
+        channel(
+            max_events,
+            vec![1, 2],
+            when_full,
+            |item: &Item| item.priority,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn drains_lanes_by_weight() {
+        let (mut tx, rx) = lanes(10, WhenFull::Block);
+        for id in 0..4 {
+            tx.send(item(0, id)).await.unwrap();
+            tx.send(item(1, id)).await.unwrap();
+        }
+        drop(tx);
+
+        let drained: Vec<_> = rx.map(|item| (item.priority, item.id)).collect().await;
+        assert_eq!(
+            drained,
+            vec![
+                (1, 0),
+                (1, 1),
+                (0, 0),
+                (1, 2),
+                (1, 3),
+                (0, 1),
+                (0, 2),
+                (0, 3)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_lowest_priority_first_when_full() {
+        let (mut tx, rx) = lanes(2, WhenFull::DropNewest);
+        tx.send(item(0, 0)).await.unwrap();
+        tx.send(item(0, 1)).await.unwrap();
+        // Evicts the newest low priority item.
+        tx.send(item(1, 2)).await.unwrap();
+        // Nothing of a lower priority is left to evict.
+        tx.send(item(0, 3)).await.unwrap();
+        tx.send(item(1, 4)).await.unwrap();
+        // Items never evict items of the same lane.
+        tx.send(item(5, 5)).await.unwrap();
+        drop(tx);
+
+        let drained: Vec<_> = rx.map(|item| item.id).collect().await;
+        assert_eq!(drained, vec![2, 4]);
+    }
+
+    #[tokio::test]
+    async fn blocks_when_full() {
+        let (mut tx, mut rx) = lanes(1, WhenFull::Block);
+        tx.send(item(1, 0)).await.unwrap();
+
+        let blocked =
+            tokio::time::timeout(std::time::Duration::from_millis(10), tx.send(item(1, 1))).await;
+        assert!(blocked.is_err());
+
+        assert_eq!(rx.next().await, Some(item(1, 0)));
+        tx.send(item(1, 1)).await.unwrap();
+        assert_eq!(rx.next().await, Some(item(1, 1)));
+    }
+}
@@ -17,6 +17,7 @@ pub mod bytes;
 #[cfg(feature = "disk-buffer")]
 pub mod disk;
 mod internal_events;
+pub mod lanes;
 #[cfg(test)]
 mod test;
 mod variant;
@@ -33,7 +34,7 @@ use quickcheck::{Arbitrary, Gen};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::task::{Context, Poll};
 use tracing::Span;
 pub use variant::*;
@@ -103,6 +104,47 @@ where
     }
 }
 
+/// Build a new in-memory buffer split into priority lanes, one per weight in
+/// `weights`, with `priority` giving the lane of each event.
+///
+/// See [`lanes`] for how the lanes are drained, and how events are dropped
+/// when the buffer is full.
+///
+/// # Panics
+///
+/// Panics if there are no weights, or if a weight is zero.
+#[must_use]
+pub fn build_with_lanes<'a, T>(
+    max_events: usize,
+    when_full: WhenFull,
+    weights: Vec<usize>,
+    priority: impl Fn(&T) -> usize + Send + Sync + 'static,
+    span: Span,
+) -> (
+    BufferInputCloner<T>,
+    Box<dyn Stream<Item = T> + 'a + Unpin + Send>,
+    Acker,
+)
+where
+    T: 'a + ByteSizeOf + Send + Sync + Unpin + Clone + EncodeBytes<T> + DecodeBytes<T>,
+    <T as EncodeBytes<T>>::Error: Debug,
+    <T as DecodeBytes<T>>::Error: Debug + Display,
+{
+    let buffer_usage_data = BufferUsageData::new(when_full, span, None, Some(max_events));
+    let (tx, rx) = lanes::channel(
+        max_events,
+        weights,
+        when_full,
+        priority,
+        Some(buffer_usage_data.clone()),
+    );
+    let rx = rx.inspect(move |item: &T| {
+        buffer_usage_data.increment_sent_event_count_and_byte_size(1, item.size_of());
+    });
+
+    (BufferInputCloner::Lanes(tx), Box::new(rx), Acker::Null)
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum WhenFull {
@@ -139,6 +181,7 @@ where
     <T as DecodeBytes<T>>::Error: Debug,
 {
     Memory(mpsc::Sender<T>, WhenFull, Option<Arc<BufferUsageData>>),
+    Lanes(lanes::Sender<T>),
     #[cfg(feature = "disk-buffer")]
     Disk(disk::Writer<T>, WhenFull, Arc<BufferUsageData>),
 }
@@ -164,6 +207,8 @@ where
                 ))
             }
 
+            BufferInputCloner::Lanes(tx) => Box::new(tx.clone()),
+
             #[cfg(feature = "disk-buffer")]
             BufferInputCloner::Disk(writer, when_full, buffer_usage_data) => {
                 let inner: disk::Writer<T> = (*writer).clone();
@@ -175,6 +220,39 @@ where
             }
         }
     }
+
+    /// A handle on how full the buffer is, if it is instrumented.
+    #[must_use]
+    pub fn usage(&self) -> Option<BufferUsage> {
+        let buffer_usage_data = match self {
+            BufferInputCloner::Memory(_, _, buffer_usage_data) => buffer_usage_data.as_ref(),
+            BufferInputCloner::Lanes(tx) => tx.buffer_usage_data(),
+            #[cfg(feature = "disk-buffer")]
+            BufferInputCloner::Disk(_, _, buffer_usage_data) => Some(buffer_usage_data),
+        };
+        buffer_usage_data.map(|data| BufferUsage(Arc::downgrade(data)))
+    }
+}
+
+/// A handle on how full a buffer is, which doesn't keep the buffer alive.
+#[derive(Clone)]
+pub struct BufferUsage(Weak<BufferUsageData>);
+
+impl BufferUsage {
+    /// The fraction of the buffer in use, from zero for an empty buffer to one
+    /// for a full one, or `None` once the buffer is gone.
+    #[must_use]
+    pub fn utilization(&self) -> Option<f64> {
+        self.0.upgrade().map(|data| data.utilization())
+    }
+}
+
+impl Debug for BufferUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BufferUsage")
+            .field(&self.utilization())
+            .finish()
+    }
 }
 
 #[pin_project]
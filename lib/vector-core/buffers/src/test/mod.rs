@@ -2,10 +2,11 @@ mod common;
 mod model;
 
 use crate::buffer_usage_data::BufferUsageData;
-use crate::{Acker, DropWhenFull, WhenFull};
+use crate::{Acker, DropWhenFull, Variant, WhenFull};
+use common::Message;
 use futures::task::Poll;
 use futures::{channel::mpsc, future, task::AtomicWaker};
-use futures::{Sink, Stream};
+use futures::{Sink, SinkExt, Stream, StreamExt};
 use std::sync::{atomic::AtomicUsize, Arc};
 use tokio_test::task::spawn;
 use tracing::Span;
@@ -58,3 +59,29 @@ fn ack_with_none() {
     acker.ack(1);
     assert!(mock.is_woken());
 }
+
+#[tokio::test]
+async fn usage_tracks_buffered_events() {
+    let (tx, mut rx, _acker) = crate::build::<Message>(
+        Variant::Memory {
+            max_events: 4,
+            when_full: WhenFull::Block,
+            instrument: true,
+        },
+        Span::none(),
+    )
+    .unwrap();
+    let usage = tx.usage().unwrap();
+    assert_eq!(usage.utilization(), Some(0.0));
+
+    let mut sink = tx.get();
+    sink.send(Message::new(1)).await.unwrap();
+    sink.send(Message::new(2)).await.unwrap();
+    assert_eq!(usage.utilization(), Some(0.5));
+
+    rx.next().await.unwrap();
+    assert_eq!(usage.utilization(), Some(0.25));
+
+    drop((tx, sink, rx));
+    assert_eq!(usage.utilization(), None);
+}
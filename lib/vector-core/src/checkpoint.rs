@@ -0,0 +1,425 @@
+//! A shared store for source checkpoints.
+//!
+//! Checkpoints are positions, such as a journald cursor, that a component
+//! resumes from after a restart. The store keeps them under the `checkpoints`
+//! directory of the data directory, in one file per component. Each file maps
+//! keys to positions, and is only ever replaced atomically, so that a set of
+//! changes made through a [`Transaction`] is either fully persisted or not at
+//! all, even if Vector crashes while writing it.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const CHECKPOINTS_DIR: &str = "checkpoints";
+const EXTENSION: &str = "json";
+const TMP_EXTENSION: &str = "json.tmp";
+
+/// The version of the checkpoint file format.
+const VERSION: u32 = 1;
+
+/// The store holding the checkpoints of every component.
+#[derive(Clone, Debug)]
+pub struct CheckpointStore {
+    root: PathBuf,
+}
+
+impl CheckpointStore {
+    /// Creates a store keeping checkpoints under the given data directory.
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        Self {
+            root: data_dir.as_ref().join(CHECKPOINTS_DIR),
+        }
+    }
+
+    /// The directory the checkpoint files are stored in.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Loads the checkpoints of a component, creating the store directory
+    /// if needed.
+    ///
+    /// # Errors
+    ///
+    /// Function will error if the store directory can't be created, or the
+    /// component's checkpoint file can't be read or parsed.
+    pub fn namespace(&self, component: &str) -> io::Result<Checkpoints> {
+        fs::create_dir_all(&self.root)?;
+        Checkpoints::load(self.path(component))
+    }
+
+    /// Lists the components that have checkpoints in the store.
+    ///
+    /// # Errors
+    ///
+    /// Function will error if the store directory can't be read.
+    pub fn components(&self) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        let mut components = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(EXTENSION) {
+                continue;
+            }
+            if let Some(component) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(decode_name)
+            {
+                components.push(component);
+            }
+        }
+        components.sort();
+        Ok(components)
+    }
+
+    /// Removes all the checkpoints of a component, returning whether it had
+    /// any.
+    ///
+    /// # Errors
+    ///
+    /// Function will error if the checkpoint file exists but can't be removed.
+    pub fn reset(&self, component: &str) -> io::Result<bool> {
+        match fs::remove_file(self.path(component)) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn path(&self, component: &str) -> PathBuf {
+        self.root
+            .join(encode_name(component))
+            .with_extension(EXTENSION)
+    }
+}
+
+/// A checkpointed position.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Checkpoint {
+    pub position: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckpointFile {
+    version: u32,
+    checkpoints: BTreeMap<String, Checkpoint>,
+}
+
+/// The checkpoints of a single component.
+#[derive(Debug)]
+pub struct Checkpoints {
+    path: PathBuf,
+    checkpoints: BTreeMap<String, Checkpoint>,
+}
+
+impl Checkpoints {
+    fn load(path: PathBuf) -> io::Result<Self> {
+        let checkpoints = match fs::read(&path) {
+            Ok(contents) => {
+                let file: CheckpointFile = serde_json::from_slice(&contents)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                if file.version != VERSION {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unsupported checkpoint file version {}", file.version),
+                    ));
+                }
+                file.checkpoints
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Self { path, checkpoints })
+    }
+
+    /// The file the checkpoints are persisted to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The position checkpointed under `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.checkpoints
+            .get(key)
+            .map(|checkpoint| checkpoint.position.as_str())
+    }
+
+    /// Iterates over all the checkpoints, ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Checkpoint)> {
+        self.checkpoints
+            .iter()
+            .map(|(key, checkpoint)| (key.as_str(), checkpoint))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// Checkpoints a single position.
+    ///
+    /// # Errors
+    ///
+    /// Function will error if the checkpoints can't be persisted, in which
+    /// case they're left unchanged.
+    pub fn set(&mut self, key: impl Into<String>, position: impl Into<String>) -> io::Result<()> {
+        let mut transaction = self.transaction();
+        transaction.set(key, position);
+        transaction.commit()
+    }
+
+    /// Starts a set of changes to be persisted together.
+    pub fn transaction(&mut self) -> Transaction<'_> {
+        Transaction {
+            checkpoints: self,
+            changes: Vec::new(),
+        }
+    }
+
+    fn persist(&self, checkpoints: &BTreeMap<String, Checkpoint>) -> io::Result<()> {
+        #[derive(Serialize)]
+        struct CheckpointFileRef<'a> {
+            version: u32,
+            checkpoints: &'a BTreeMap<String, Checkpoint>,
+        }
+
+        let contents = serde_json::to_vec_pretty(&CheckpointFileRef {
+            version: VERSION,
+            checkpoints,
+        })
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        // Write the new contents aside and swap them in, so that the file is
+        // never observed partially written.
+        let tmp_path = self.path.with_extension(TMP_EXTENSION);
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&contents)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp_path, &self.path)?;
+        sync_parent(&self.path)
+    }
+}
+
+/// A set of changes to a component's checkpoints, persisted atomically on
+/// commit. Dropping the transaction discards the changes.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    checkpoints: &'a mut Checkpoints,
+    changes: Vec<(String, Option<Checkpoint>)>,
+}
+
+impl<'a> Transaction<'a> {
+    pub fn set(&mut self, key: impl Into<String>, position: impl Into<String>) -> &mut Self {
+        self.set_at(key, position, Utc::now())
+    }
+
+    /// Sets a position that was reached at `updated_at` rather than now, such
+    /// as one migrated from a legacy checkpoint.
+    pub fn set_at(
+        &mut self,
+        key: impl Into<String>,
+        position: impl Into<String>,
+        updated_at: DateTime<Utc>,
+    ) -> &mut Self {
+        let checkpoint = Checkpoint {
+            position: position.into(),
+            updated_at,
+        };
+        self.changes.push((key.into(), Some(checkpoint)));
+        self
+    }
+
+    pub fn remove(&mut self, key: impl Into<String>) -> &mut Self {
+        self.changes.push((key.into(), None));
+        self
+    }
+
+    /// Persists the changes.
+    ///
+    /// # Errors
+    ///
+    /// Function will error if the checkpoints can't be persisted, in which
+    /// case none of the changes are applied.
+    pub fn commit(self) -> io::Result<()> {
+        let mut checkpoints = self.checkpoints.checkpoints.clone();
+        for (key, checkpoint) in self.changes {
+            match checkpoint {
+                Some(checkpoint) => {
+                    checkpoints.insert(key, checkpoint);
+                }
+                None => {
+                    checkpoints.remove(&key);
+                }
+            }
+        }
+
+        self.checkpoints.persist(&checkpoints)?;
+        self.checkpoints.checkpoints = checkpoints;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    // The rename itself is only durable once the directory is synced.
+    match path.parent() {
+        Some(parent) => File::open(parent)?.sync_all(),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Escapes component IDs into file names, keeping alphanumerics, `-` and
+/// `_`, and percent-encoding other bytes.
+fn encode_name(component: &str) -> String {
+    let mut name = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            name.push(char::from(byte));
+        } else {
+            name.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    name
+}
+
+fn decode_name(name: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut input = name.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let high = char::from(input.next()?).to_digit(16)?;
+            let low = char::from(input.next()?).to_digit(16)?;
+            bytes.push(u8::try_from(high * 16 + low).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persists_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(dir.path());
+
+        let mut checkpoints = store.namespace("journald").unwrap();
+        assert!(checkpoints.is_empty());
+        checkpoints.set("cursor", "s=1;i=2").unwrap();
+        assert_eq!(checkpoints.get("cursor"), Some("s=1;i=2"));
+
+        let checkpoints = store.namespace("journald").unwrap();
+        assert_eq!(checkpoints.get("cursor"), Some("s=1;i=2"));
+        assert!(store.namespace("other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn commits_transactions_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(dir.path());
+        let mut checkpoints = store.namespace("file").unwrap();
+
+        let mut transaction = checkpoints.transaction();
+        transaction.set("a", "1").set("b", "2");
+        transaction.commit().unwrap();
+
+        // Uncommitted changes are discarded.
+        let mut transaction = checkpoints.transaction();
+        transaction.remove("a").set("c", "3");
+        drop(transaction);
+        assert_eq!(checkpoints.get("a"), Some("1"));
+        assert_eq!(checkpoints.get("c"), None);
+
+        let mut transaction = checkpoints.transaction();
+        transaction.remove("a").set("b", "4");
+        transaction.commit().unwrap();
+
+        let reloaded = store.namespace("file").unwrap();
+        let positions = reloaded
+            .iter()
+            .map(|(key, checkpoint)| (key, checkpoint.position.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![("b", "4")]);
+        assert!(!reloaded.path().with_extension(TMP_EXTENSION).exists());
+    }
+
+    #[test]
+    fn keeps_update_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(dir.path());
+        let mut checkpoints = store.namespace("file").unwrap();
+        let updated_at = Utc::now() - chrono::Duration::hours(1);
+
+        let mut transaction = checkpoints.transaction();
+        transaction.set_at("a", "1", updated_at).set("b", "2");
+        transaction.commit().unwrap();
+
+        let reloaded = store.namespace("file").unwrap();
+        let updates = reloaded
+            .iter()
+            .map(|(key, checkpoint)| (key, checkpoint.updated_at > updated_at))
+            .collect::<Vec<_>>();
+        assert_eq!(updates, vec![("a", false), ("b", true)]);
+        assert_eq!(reloaded.iter().next().unwrap().1.updated_at, updated_at);
+    }
+
+    #[test]
+    fn lists_and_resets_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(dir.path());
+        assert!(store.components().unwrap().is_empty());
+
+        for component in &["journald", "pipeline/in.logs"] {
+            store
+                .namespace(component)
+                .unwrap()
+                .set("cursor", "1")
+                .unwrap();
+        }
+        assert_eq!(
+            store.components().unwrap(),
+            vec!["journald".to_owned(), "pipeline/in.logs".to_owned()]
+        );
+
+        assert!(store.reset("pipeline/in.logs").unwrap());
+        assert!(!store.reset("pipeline/in.logs").unwrap());
+        assert_eq!(store.components().unwrap(), vec!["journald".to_owned()]);
+    }
+
+    #[test]
+    fn rejects_corrupted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(dir.path());
+        fs::create_dir_all(store.root()).unwrap();
+        fs::write(store.path("journald"), "{ not json").unwrap();
+
+        assert_eq!(
+            store.namespace("journald").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -30,6 +30,12 @@ impl Discriminant {
             .collect();
         Self { values }
     }
+
+    /// The values of the fields the discriminant was created from, in order,
+    /// `None` for the fields the event didn't contain.
+    pub fn values(&self) -> &[Option<Value>] {
+        &self.values
+    }
 }
 
 impl PartialEq for Discriminant {
@@ -8,7 +8,10 @@ use std::future::Future;
 use std::iter::{self, ExactSizeIterator};
 use std::pin::Pin;
 use std::task::Poll;
-use std::{cmp, mem, sync::Arc};
+use std::{
+    cmp, mem,
+    sync::{Arc, Mutex, Weak},
+};
 use tokio::sync::oneshot;
 
 type ImmutVec<T> = Box<[T]>;
@@ -104,6 +107,17 @@ impl EventFinalizers {
         }
     }
 
+    /// Weak references to the batches of these finalizers, through which
+    /// they can be expired without keeping them alive.
+    pub fn batch_expiry(&self) -> BatchExpiry {
+        BatchExpiry(
+            self.0
+                .iter()
+                .map(|finalizer| Arc::downgrade(&finalizer.batch))
+                .collect(),
+        )
+    }
+
     #[cfg(test)]
     fn count_finalizers(&self) -> usize {
         self.0.len()
@@ -162,6 +176,35 @@ impl Drop for EventFinalizer {
     }
 }
 
+/// Weak references to batches, which can be resolved as errored before all of
+/// their events are finalized, such as when a sink holds them for too long.
+#[derive(Clone, Debug, Default)]
+pub struct BatchExpiry(Vec<Weak<BatchNotifier>>);
+
+impl BatchExpiry {
+    /// Whether these refer to no batch at all.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether both refer to the same batches.
+    pub fn same_batches(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && (self.0.iter())
+                .zip(other.0.iter())
+                .all(|(a, b)| Weak::ptr_eq(a, b))
+    }
+
+    /// Resolve the batches still pending as errored, returning how many were.
+    pub fn expire(&self) -> usize {
+        self.0
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|batch| batch.expire())
+            .count()
+    }
+}
+
 /// A convenience newtype wrapper for the one-shot receiver for an
 /// individual batch status.
 #[pin_project::pin_project]
@@ -199,7 +242,7 @@ impl BatchStatusReceiver {
 #[derive(Debug)]
 pub struct BatchNotifier {
     status: Atomic<BatchStatus>,
-    notifier: Option<oneshot::Sender<BatchStatus>>,
+    notifier: Mutex<Option<oneshot::Sender<BatchStatus>>>,
 }
 
 impl BatchNotifier {
@@ -209,7 +252,7 @@ impl BatchNotifier {
         let (sender, receiver) = oneshot::channel();
         let notifier = Self {
             status: Atomic::new(BatchStatus::Delivered),
-            notifier: Some(sender),
+            notifier: Mutex::new(Some(sender)),
         };
         (Arc::new(notifier), BatchStatusReceiver(receiver))
     }
@@ -230,13 +273,35 @@ impl BatchNotifier {
 
     /// Send this notifier's status up to the source.
     fn send_status(&mut self) {
-        if let Some(notifier) = self.notifier.take() {
+        let notifier = match self.notifier.get_mut() {
+            Ok(notifier) => notifier.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        if let Some(notifier) = notifier {
             let status = self.status.load(Ordering::Relaxed);
             // Ignore the error case, as it will happen during normal
             // source shutdown and we can't detect that here.
             let _ = notifier.send(status);
         }
     }
+
+    /// Resolve this batch as errored without waiting for the rest of its
+    /// events, returning whether it was still pending. Its events finalized
+    /// later on no longer change its status.
+    fn expire(&self) -> bool {
+        let notifier = match self.notifier.lock() {
+            Ok(mut notifier) => notifier.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        match notifier {
+            Some(notifier) => {
+                // As when sending the status on drop, the source may be gone.
+                let _ = notifier.send(BatchStatus::Errored);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl Drop for BatchNotifier {
@@ -444,6 +509,25 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[test]
+    fn expire_pending_batches() {
+        let (fin1, mut receiver1) = make_finalizer();
+        let (fin2, mut receiver2) = make_finalizer();
+        let expiry1 = fin1.batch_expiry();
+        let expiry2 = fin2.batch_expiry();
+        assert!(expiry1.same_batches(&fin1.clone().batch_expiry()));
+        assert!(!expiry1.same_batches(&expiry2));
+
+        drop(fin2);
+        assert_eq!(receiver2.try_recv(), Ok(BatchStatus::Delivered));
+        assert_eq!(expiry2.expire(), 0);
+
+        assert_eq!(expiry1.expire(), 1);
+        assert_eq!(receiver1.try_recv(), Ok(BatchStatus::Errored));
+        assert_eq!(expiry1.expire(), 0);
+        drop(fin1);
+    }
+
     fn make_finalizer() -> (EventFinalizers, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
@@ -1,11 +1,11 @@
 #![deny(missing_docs)]
 
-use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
+use super::{BatchExpiry, BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
 use crate::ByteSizeOf;
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
 use shared::EventDataEq;
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 /// The top-level metadata structure contained by both `struct Metric`
 /// and `struct LogEvent` types.
@@ -19,6 +19,33 @@ pub struct EventMetadata {
     datadog_api_key: Option<Arc<str>>,
     #[serde(default, skip)]
     finalizers: EventFinalizers,
+    /// Set on events sampled for topology-wide event tracing
+    #[getset(get = "pub", set = "pub")]
+    #[serde(default, skip)]
+    trace: Option<EventTrace>,
+}
+
+/// Timing state carried by an event sampled for event tracing.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct EventTrace {
+    /// The identifier reported with every hop of this trace.
+    pub id: u64,
+    /// When the event left its source.
+    pub started_at: Instant,
+    /// When the event last entered or left a component.
+    pub hop_at: Instant,
+}
+
+impl EventTrace {
+    /// Start a new trace at the current instant.
+    pub fn new(id: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            id,
+            started_at: now,
+            hop_at: now,
+        }
+    }
 }
 
 impl ByteSizeOf for EventMetadata {
@@ -44,11 +71,15 @@ impl EventMetadata {
 
     /// Merge the other `EventMetadata` into this.
     /// If a Datadog API key is not set in `self`, the one from `other` will be used.
+    /// The same applies to the event trace.
     pub fn merge(&mut self, other: Self) {
         self.finalizers.merge(other.finalizers);
         if self.datadog_api_key.is_none() {
             self.datadog_api_key = other.datadog_api_key;
         }
+        if self.trace.is_none() {
+            self.trace = other.trace;
+        }
     }
 
     /// Update the finalizer(s) status.
@@ -66,6 +97,12 @@ impl EventMetadata {
         self.finalizers.add(finalizer);
     }
 
+    /// Weak references to the batches of the finalizers, see
+    /// [`EventFinalizers::batch_expiry`].
+    pub fn batch_expiry(&self) -> BatchExpiry {
+        self.finalizers.batch_expiry()
+    }
+
     /// Swap the finalizers list with an empty list and return the original.
     pub fn take_finalizers(&mut self) -> EventFinalizers {
         std::mem::take(&mut self.finalizers)
@@ -55,13 +55,21 @@ pub struct MetricSeries {
     pub name: MetricName,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<MetricTags>,
+    /// All the values, in order, of the tags set more than once. `tags`
+    /// holds the last value of these tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multi_value_tags: Option<MetricTagValues>,
 }
 
 pub type MetricTags = BTreeMap<String, String>;
 
+pub type MetricTagValues = BTreeMap<String, Vec<String>>;
+
 impl ByteSizeOf for MetricSeries {
     fn allocated_bytes(&self) -> usize {
-        self.name.allocated_bytes() + self.tags.allocated_bytes()
+        self.name.allocated_bytes()
+            + self.tags.allocated_bytes()
+            + self.multi_value_tags.allocated_bytes()
     }
 }
 
@@ -326,6 +334,7 @@ impl Metric {
                     namespace: None,
                 },
                 tags: None,
+                multi_value_tags: None,
             },
             data: MetricData {
                 timestamp: None,
@@ -366,6 +375,7 @@ impl Metric {
     #[inline]
     pub fn with_tags(mut self, tags: Option<MetricTags>) -> Self {
         self.series.tags = tags;
+        self.series.multi_value_tags = None;
         self
     }
 
@@ -509,13 +519,35 @@ impl Metric {
         self.series.insert_tag(name, value)
     }
 
-    /// Get the tag entry for the named key. *Note:* This will create
-    /// the tags map if it is not present, even if nothing is later
-    /// inserted.
+    /// Remove all the tags, returning the last value of each of them.
+    pub fn remove_tags(&mut self) -> Option<MetricTags> {
+        self.series.remove_tags()
+    }
+
+    /// Get the tag entry for the named key, dropping all but the last
+    /// value of the tag. *Note:* This will create the tags map if it is
+    /// not present, even if nothing is later inserted.
     pub fn tag_entry(&mut self, key: String) -> btree_map::Entry<String, String> {
         self.series.tag_entry(key)
     }
 
+    /// Add a value to a tag, keeping its previous values. *Note:* This
+    /// will create the tags map if it is not present.
+    pub fn add_tag_value(&mut self, name: String, value: String) {
+        self.series.add_tag_value(name, value);
+    }
+
+    /// Returns all the values of a tag, in the order they were added.
+    pub fn tag_values(&self, name: &str) -> Vec<&str> {
+        self.series.tag_values(name)
+    }
+
+    /// Iterate over all the tag values, repeating the keys of the tags
+    /// with more than one value.
+    pub fn iter_tag_values(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.series.iter_tag_values()
+    }
+
     /// Zero out the data in this metric
     pub fn zero(&mut self) {
         self.data.zero();
@@ -554,13 +586,70 @@ impl MetricSeries {
     /// Set or updates the string value of a tag. *Note:* This will
     /// create the tags map if it is not present.
     pub fn insert_tag(&mut self, key: String, value: String) -> Option<String> {
+        self.remove_tag_values(&key);
         (self.tags.get_or_insert_with(Default::default)).insert(key, value)
     }
 
+    /// Add a value to a tag, keeping its previous values. *Note:* This
+    /// will create the tags map if it is not present.
+    pub fn add_tag_value(&mut self, key: String, value: String) {
+        let tags = self.tags.get_or_insert_with(Default::default);
+        match tags.insert(key.clone(), value.clone()) {
+            None => (),
+            Some(previous) => self
+                .multi_value_tags
+                .get_or_insert_with(Default::default)
+                .entry(key)
+                .or_insert_with(|| vec![previous])
+                .push(value),
+        }
+    }
+
+    /// Returns all the values of a tag, in the order they were added.
+    pub fn tag_values(&self, key: &str) -> Vec<&str> {
+        match self
+            .multi_value_tags
+            .as_ref()
+            .and_then(|values| values.get(key))
+        {
+            Some(values) => values.iter().map(String::as_str).collect(),
+            None => self
+                .tags
+                .as_ref()
+                .and_then(|tags| tags.get(key))
+                .map(String::as_str)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Iterate over all the tag values, repeating the keys of the tags
+    /// with more than one value.
+    pub fn iter_tag_values(&self) -> impl Iterator<Item = (&str, &str)> {
+        let multi_value_tags = self.multi_value_tags.as_ref();
+        self.tags.iter().flatten().flat_map(move |(key, value)| {
+            let values = match multi_value_tags.and_then(|values| values.get(key)) {
+                Some(values) => values.iter().map(String::as_str).collect(),
+                None => vec![value.as_str()],
+            };
+            values.into_iter().map(move |value| (key.as_str(), value))
+        })
+    }
+
+    fn remove_tag_values(&mut self, key: &str) {
+        if let Some(values) = &mut self.multi_value_tags {
+            values.remove(key);
+            if values.is_empty() {
+                self.multi_value_tags = None;
+            }
+        }
+    }
+
     /// Remove the tag entry for the named key, if it exists, and return
     /// the old value. *Note:* This will drop the tags map if the tag
     /// was the last entry in it.
     pub fn remove_tag(&mut self, key: &str) -> Option<String> {
+        self.remove_tag_values(key);
         match &mut self.tags {
             None => None,
             Some(tags) => {
@@ -573,10 +662,17 @@ impl MetricSeries {
         }
     }
 
-    /// Get the tag entry for the named key. *Note:* This will create
-    /// the tags map if it is not present, even if nothing is later
-    /// inserted.
+    /// Remove all the tags, returning the last value of each of them.
+    pub fn remove_tags(&mut self) -> Option<MetricTags> {
+        self.multi_value_tags = None;
+        self.tags.take()
+    }
+
+    /// Get the tag entry for the named key, dropping all but the last
+    /// value of the tag. *Note:* This will create the tags map if it is
+    /// not present, even if nothing is later inserted.
     pub fn tag_entry(&mut self, key: String) -> btree_map::Entry<String, String> {
+        self.remove_tag_values(&key);
         self.tags.get_or_insert_with(Default::default).entry(key)
     }
 }
@@ -882,11 +978,9 @@ impl Display for MetricSeries {
         }
         write_word(fmt, &self.name.name)?;
         write!(fmt, "{{")?;
-        if let Some(tags) = &self.tags {
-            write_list(fmt, ",", tags.iter(), |fmt, (tag, value)| {
-                write_word(fmt, tag).and_then(|()| write!(fmt, "={:?}", value))
-            })?;
-        }
+        write_list(fmt, ",", self.iter_tag_values(), |fmt, (tag, value)| {
+            write_word(fmt, tag).and_then(|()| write!(fmt, "={:?}", value))
+        })?;
         write!(fmt, "}}")
     }
 }
@@ -1174,4 +1268,89 @@ mod test {
             r#"six{} = count=2 sum=127 1@63 2@64"#
         );
     }
+
+    #[test]
+    fn multi_value_tags() {
+        let mut metric = Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(tags()));
+        metric.add_tag_value("host_group".into(), "web".into());
+        metric.add_tag_value("host_group".into(), "frontend".into());
+        metric.add_tag_value("host_group".into(), "web".into());
+
+        assert_eq!(
+            metric.tag_values("host_group"),
+            vec!["web", "frontend", "web"]
+        );
+        assert_eq!(metric.tag_values("normal_tag"), vec!["value"]);
+        assert!(metric.tag_values("missing").is_empty());
+        assert_eq!(metric.tag_value("host_group"), Some("web".into()));
+        assert_eq!(
+            metric
+                .iter_tag_values()
+                .filter(|(key, _)| *key == "host_group")
+                .count(),
+            3
+        );
+
+        metric.insert_tag("host_group".into(), "db".into());
+        assert_eq!(metric.tag_values("host_group"), vec!["db"]);
+        assert_eq!(metric.series().multi_value_tags, None);
+    }
+
+    #[test]
+    fn tag_updates_drop_multiple_values() {
+        let multi_valued = || {
+            let mut metric = Metric::new(
+                "counter",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(tags()));
+            metric.add_tag_value("host_group".into(), "web".into());
+            metric.add_tag_value("host_group".into(), "frontend".into());
+            metric
+        };
+
+        let mut metric = multi_valued();
+        *metric.tag_entry("host_group".into()).or_default() = "db".into();
+        assert_eq!(metric.tag_values("host_group"), vec!["db"]);
+        assert_eq!(metric.series().multi_value_tags, None);
+
+        let mut metric = multi_valued();
+        assert_eq!(metric.remove_tag("host_group"), Some("frontend".into()));
+        assert!(metric.tag_values("host_group").is_empty());
+        assert_eq!(metric.series().multi_value_tags, None);
+
+        let mut metric = multi_valued();
+        metric.remove_tags();
+        assert_eq!(metric.iter_tag_values().count(), 0);
+        assert_eq!(metric.series().multi_value_tags, None);
+
+        let mut metric = multi_valued();
+        metric.add_tag_value("host_group".into(), "frontend".into());
+        metric.insert_tag("host_group".into(), "db".into());
+        assert_eq!(
+            metric
+                .iter_tag_values()
+                .filter(|(key, _)| *key == "host_group")
+                .collect::<Vec<_>>(),
+            vec![("host_group", "db")]
+        );
+    }
+
+    #[test]
+    fn display_multi_value_tags() {
+        let mut metric = Metric::new(
+            "one",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        );
+        metric.add_tag_value("group".into(), "a".into());
+        metric.add_tag_value("group".into(), "b".into());
+        assert_eq!(format!("{}", metric), r#"one{group="a",group="b"} = 1"#);
+    }
 }
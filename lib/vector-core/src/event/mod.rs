@@ -3,12 +3,12 @@ use buffers::bytes::{DecodeBytes, EncodeBytes};
 use bytes::{Buf, BufMut, Bytes};
 use chrono::{DateTime, SecondsFormat, Utc};
 pub use finalization::{
-    BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers, EventStatus,
-    Finalizable,
+    BatchExpiry, BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers,
+    EventStatus, Finalizable,
 };
 pub use legacy_lookup::Lookup;
 pub use log_event::LogEvent;
-pub use metadata::{EventMetadata, WithMetadata};
+pub use metadata::{EventMetadata, EventTrace, WithMetadata};
 pub use metric::{Metric, MetricKind, MetricValue, StatisticKind};
 use prost::{DecodeError, EncodeError, Message};
 use shared::EventDataEq;
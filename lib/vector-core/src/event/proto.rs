@@ -60,6 +60,8 @@ impl From<Metric> for event::Metric {
             Some(metric.tags)
         };
 
+        let multi_value_tags = metric.multi_value_tags;
+
         let value = match metric.value.unwrap() {
             MetricValue::Counter(counter) => event::MetricValue::Counter {
                 value: counter.value,
@@ -98,10 +100,17 @@ impl From<Metric> for event::Metric {
             },
         };
 
-        Self::new(name, kind, value)
+        let mut metric = Self::new(name, kind, value)
             .with_namespace(namespace)
             .with_tags(tags)
-            .with_timestamp(timestamp)
+            .with_timestamp(timestamp);
+        for (key, values) in multi_value_tags {
+            metric.remove_tag(&key);
+            for value in values.values {
+                metric.add_tag_value(key.clone(), value);
+            }
+        }
+        metric
     }
 }
 
@@ -153,6 +162,12 @@ impl From<event::Metric> for WithMetadata<Metric> {
         });
 
         let tags = series.tags.unwrap_or_default();
+        let multi_value_tags = series
+            .multi_value_tags
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, values)| (key, TagValues { values }))
+            .collect();
 
         let kind = match data.kind {
             event::MetricKind::Incremental => metric::Kind::Incremental,
@@ -203,6 +218,7 @@ impl From<event::Metric> for WithMetadata<Metric> {
             tags,
             kind,
             value: Some(metric),
+            multi_value_tags,
         };
         Self { data, metadata }
     }
@@ -434,6 +434,7 @@ impl Arbitrary for MetricSeries {
         MetricSeries {
             name: MetricName::arbitrary(g),
             tags,
+            multi_value_tags: None,
         }
     }
 
@@ -227,7 +227,7 @@ impl vrl_core::Target for VrlTarget {
                         }
                         ["timestamp"] => return Ok(metric.data.timestamp.take().map(Into::into)),
                         ["tags"] => {
-                            return Ok(metric.series.tags.take().map(|map| {
+                            return Ok(metric.remove_tags().map(|map| {
                                 map.into_iter()
                                     .map(|(k, v)| (k, v.into()))
                                     .collect::<vrl_core::Value>()
@@ -694,6 +694,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn metric_remove_multi_value_tags() {
+        let mut metric = Metric::new(
+            "name",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.23 },
+        );
+        metric.add_tag_value("tig".into(), "tog".into());
+        metric.add_tag_value("tig".into(), "tug".into());
+
+        let mut target = VrlTarget::new(Event::Metric(metric));
+        assert_eq!(
+            Ok(Some(btreemap! { "tig" => "tug" }.into())),
+            target.remove(&LookupBuf::from_str("tags").unwrap(), true)
+        );
+
+        let metric = target.into_events().next().unwrap().into_metric();
+        assert_eq!(metric.iter_tag_values().count(), 0);
+        assert_eq!(metric.series().multi_value_tags, None);
+    }
+
     #[test]
     fn metric_invalid_paths() {
         let metric = Metric::new(
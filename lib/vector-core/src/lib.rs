@@ -23,6 +23,7 @@
 
 #[cfg(feature = "api")]
 pub mod api;
+pub mod checkpoint;
 pub mod config;
 pub mod event;
 pub mod mapping;
@@ -18,6 +18,9 @@ pub enum ExpandType {
 #[cfg(feature = "vrl")]
 #[derive(Debug, Default)]
 pub struct TransformContext {
+    /// The ID of the transform being built, `None` when it isn't part of a
+    /// running topology, e.g. in unit tests.
+    pub key: Option<String>,
     pub globals: GlobalOptions,
     pub enrichment_tables: enrichment::TableRegistry,
 }
@@ -25,6 +28,9 @@ pub struct TransformContext {
 #[cfg(not(feature = "vrl"))]
 #[derive(Debug, Default)]
 pub struct TransformContext {
+    /// The ID of the transform being built, `None` when it isn't part of a
+    /// running topology, e.g. in unit tests.
+    pub key: Option<String>,
     pub globals: GlobalOptions,
 }
 
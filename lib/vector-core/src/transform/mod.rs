@@ -15,6 +15,7 @@ pub enum Transform {
     Function(Box<dyn FunctionTransform>),
     FallibleFunction(Box<dyn FallibleFunctionTransform>),
     Task(Box<dyn TaskTransform>),
+    MultiOutputTask(Box<dyn MultiOutputTaskTransform>),
 }
 
 impl Transform {
@@ -104,6 +105,14 @@ impl Transform {
         Transform::Task(Box::new(v))
     }
 
+    /// Create a new task transform with named outputs.
+    ///
+    /// These are task transforms which route each event they emit either to
+    /// their default output or to one of their named outputs.
+    pub fn multi_output_task(v: impl MultiOutputTaskTransform + 'static) -> Self {
+        Transform::MultiOutputTask(Box::new(v))
+    }
+
     /// Mutably borrow the inner transform as a task transform.
     ///
     /// # Panics
@@ -170,3 +179,36 @@ pub trait TaskTransform: Send {
     where
         Self: 'static;
 }
+
+/// An event emitted by a [`MultiOutputTaskTransform`], along with the named
+/// output it is sent to, if not the default one.
+#[derive(Debug)]
+pub struct RoutedEvent {
+    pub output: Option<String>,
+    pub event: Event,
+}
+
+impl From<Event> for RoutedEvent {
+    fn from(event: Event) -> Self {
+        Self {
+            output: None,
+            event,
+        }
+    }
+}
+
+/// Task transforms which, beyond their default output, emit events to the
+/// named outputs declared by their `TransformConfig::named_outputs`.
+///
+/// # Invariants
+///
+/// * Events are only routed to outputs declared by the configuration of the
+/// transform, others being dropped.
+pub trait MultiOutputTaskTransform: Send {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = RoutedEvent> + Send>>
+    where
+        Self: 'static;
+}
@@ -1,6 +1,9 @@
 mod vec_stream;
 
-use crate::{event::Event, transform::TaskTransform};
+use crate::{
+    event::Event,
+    transform::{MultiOutputTaskTransform, RoutedEvent, TaskTransform},
+};
 use futures::{
     stream::{self, BoxStream},
     FutureExt, Stream, StreamExt,
@@ -57,6 +60,41 @@ pub trait RuntimeTransform {
     }
 }
 
+/// A trait representing a runtime running user-defined code, which can emit
+/// events to named outputs.
+pub trait MultiOutputRuntimeTransform {
+    /// Call user-defined "init" hook.
+    fn hook_init<F>(&mut self, _emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+    }
+
+    /// Call user-defined "process" hook.
+    fn hook_process<F>(&mut self, event: Event, emit_fn: F)
+    where
+        F: FnMut(RoutedEvent);
+
+    /// Call user-defined "shutdown" hook.
+    fn hook_shutdown<F>(&mut self, _emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+    }
+
+    /// Call user-defined timer handler.
+    fn timer_handler<F>(&mut self, _timer: Timer, _emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+    }
+
+    /// Return (static) list of user-defined timers.
+    fn timers(&self) -> Vec<Timer> {
+        Vec::new()
+    }
+}
+
 #[derive(Debug)]
 enum Message {
     Init,
@@ -77,68 +115,97 @@ where
         Self: 'static,
     {
         let timers = self.timers();
-        let mut is_shutdown: bool = false; // TODO: consider using an enum describing the state instead of a
-                                           // a single boolean variable.
-                                           // It is used to prevent timers to emit messages after the source
-                                           // stream stopped.
-
-        Box::pin(
-            input_rx
-                .map(Message::Process)
-                .fuse()
-                .into_future()
-                .map(move |(first, rest)| {
-                    // The first message is always `Message::Init`.
-                    let init_msg = stream::once(ready(Message::Init));
-                    // After it comes the first event, if any.
-                    let first_event = first.map_or_else(
-                        || stream::empty().boxed(),
-                        |msg| stream::once(ready(msg)).boxed(),
-                    );
-                    // Then all other events followed by `Message::Shutdown` message
-                    let rest_events_and_shutdown_msg =
-                        rest.chain(stream::once(ready(Message::Shutdown)));
-                    // A stream of `Message::Timer(..)` events generated by timers.
-                    let timer_msgs = make_timer_msgs_stream(timers);
-
-                    init_msg
-                        .chain(first_event)
-                        .chain(
-                            // We need to finish when `rest_events_and_shutdown_msg` finishes so
-                            // not to hang on timers, but not finish when `timer_msgs` finishes
-                            // as there may not be any timer.
-                            rest_events_and_shutdown_msg
-                                .select_weak(timer_msgs.chain(stream::pending())),
-                        )
-                        .boxed()
-                })
-                .into_stream()
-                .flatten()
-                .map(move |msg| {
-                    let mut acc = Vec::new(); // TODO: create a stream adaptor to avoid buffering all events
-                    if !is_shutdown {
-                        match msg {
-                            Message::Init => self.hook_init(|event| acc.push(event)),
-                            Message::Process(event) => {
-                                self.hook_process(event, |event| acc.push(event));
-                            }
-                            Message::Shutdown => {
-                                self.hook_shutdown(|event| acc.push(event));
-                                is_shutdown = true;
-                            }
-                            Message::Timer(timer) => {
-                                self.timer_handler(timer, |event| acc.push(event));
-                            }
-                        }
-                    }
-                    stream::iter(acc).boxed()
-                })
-                .flatten()
-                .boxed(),
-        )
+        run(input_rx, timers, move |msg, acc| match msg {
+            Message::Init => self.hook_init(|event| acc.push(event)),
+            Message::Process(event) => self.hook_process(event, |event| acc.push(event)),
+            Message::Shutdown => self.hook_shutdown(|event| acc.push(event)),
+            Message::Timer(timer) => self.timer_handler(timer, |event| acc.push(event)),
+        })
     }
 }
 
+impl<T> MultiOutputTaskTransform for T
+where
+    T: MultiOutputRuntimeTransform + Send,
+{
+    fn transform(
+        mut self: Box<Self>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = RoutedEvent> + Send>>
+    where
+        Self: 'static,
+    {
+        let timers = self.timers();
+        run(input_rx, timers, move |msg, acc| match msg {
+            Message::Init => self.hook_init(|event| acc.push(event)),
+            Message::Process(event) => self.hook_process(event, |event| acc.push(event)),
+            Message::Shutdown => self.hook_shutdown(|event| acc.push(event)),
+            Message::Timer(timer) => self.timer_handler(timer, |event| acc.push(event)),
+        })
+    }
+}
+
+/// Drives a runtime, handing it the init message, followed by the input events
+/// interleaved with timer messages, and ending with the shutdown message.
+fn run<O, H>(
+    input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    timers: Vec<Timer>,
+    mut handle: H,
+) -> Pin<Box<dyn Stream<Item = O> + Send>>
+where
+    O: Send + 'static,
+    H: FnMut(Message, &mut Vec<O>) + Send + 'static,
+{
+    let mut is_shutdown: bool = false; // TODO: consider using an enum describing the state instead of a
+                                       // a single boolean variable.
+                                       // It is used to prevent timers to emit messages after the source
+                                       // stream stopped.
+
+    Box::pin(
+        input_rx
+            .map(Message::Process)
+            .fuse()
+            .into_future()
+            .map(move |(first, rest)| {
+                // The first message is always `Message::Init`.
+                let init_msg = stream::once(ready(Message::Init));
+                // After it comes the first event, if any.
+                let first_event = first.map_or_else(
+                    || stream::empty().boxed(),
+                    |msg| stream::once(ready(msg)).boxed(),
+                );
+                // Then all other events followed by `Message::Shutdown` message
+                let rest_events_and_shutdown_msg =
+                    rest.chain(stream::once(ready(Message::Shutdown)));
+                // A stream of `Message::Timer(..)` events generated by timers.
+                let timer_msgs = make_timer_msgs_stream(timers);
+
+                init_msg
+                    .chain(first_event)
+                    .chain(
+                        // We need to finish when `rest_events_and_shutdown_msg` finishes so
+                        // not to hang on timers, but not finish when `timer_msgs` finishes
+                        // as there may not be any timer.
+                        rest_events_and_shutdown_msg
+                            .select_weak(timer_msgs.chain(stream::pending())),
+                    )
+                    .boxed()
+            })
+            .into_stream()
+            .flatten()
+            .map(move |msg| {
+                let mut acc = Vec::new(); // TODO: create a stream adaptor to avoid buffering all events
+                if !is_shutdown {
+                    is_shutdown = matches!(msg, Message::Shutdown);
+                    handle(msg, &mut acc);
+                }
+                stream::iter(acc).boxed()
+            })
+            .flatten()
+            .boxed(),
+    )
+}
+
 fn make_timer_msgs_stream(timers: Vec<Timer>) -> BoxStream<'static, Message> {
     let streams = timers.into_iter().map(|timer| {
         let period = time::Duration::from_secs(timer.interval_seconds);
@@ -34,6 +34,11 @@ pub struct Opts {
     /// The timezone used to parse dates.
     #[structopt(short = "tz", long)]
     timezone: Option<String>,
+
+    /// Print the type inferred for each event path queried by the program, and where that type
+    /// comes from, instead of running the program.
+    #[structopt(long)]
+    explain_types: bool,
 }
 
 impl Opts {
@@ -100,11 +105,20 @@ fn run(opts: &Opts) -> Result<(), Error> {
 
         repl(repl_objects, &tz)
     } else {
-        let objects = opts.read_into_objects()?;
         let source = opts.read_program()?;
-        let program = vrl::compile(&source, &stdlib::all(), None).map_err(|diagnostics| {
-            Error::Parse(Formatter::new(&source, diagnostics).colored().to_string())
-        })?;
+        let mut state = state::Compiler::new();
+        let program = vrl::compile_with_state(&source, &stdlib::all(), &mut state).map_err(
+            |diagnostics| Error::Parse(Formatter::new(&source, diagnostics).colored().to_string()),
+        )?;
+
+        if opts.explain_types {
+            for queried in state.queried_paths() {
+                println!("{}", explain_type(queried));
+            }
+            return Ok(());
+        }
+
+        let objects = opts.read_into_objects()?;
 
         for mut object in objects {
             let result = execute(&mut object, &program, &tz).map(|v| {
@@ -134,6 +148,20 @@ fn repl(objects: Vec<Value>, timezone: &TimeZone) -> Result<(), Error> {
     }
 }
 
+fn explain_type(queried: &state::QueriedPath) -> String {
+    let mut explanation = format!(
+        ".{} at ({}:{}): {}",
+        queried.path,
+        queried.span.start(),
+        queried.span.end(),
+        queried.kind
+    );
+    if let Some(origin) = &queried.origin {
+        explanation.push_str(&format!(" (from {})", origin));
+    }
+    explanation
+}
+
 fn execute(
     object: &mut impl Target,
     program: &Program,
@@ -295,10 +295,18 @@ impl<'a> Compiler<'a> {
     }
 
     fn compile_query(&mut self, node: Node<ast::Query>) -> Query {
+        let span = node.span();
         let ast::Query { target, path } = node.into_inner();
         let target = self.compile_query_target(target);
+        let query = Query::new(target, path.into_inner());
+
+        if query.is_external() {
+            let kind = query.type_def(self.state).kind();
+            self.state
+                .insert_queried_path(query.path().clone(), span, kind);
+        }
 
-        Query::new(target, path.into_inner())
+        query
     }
 
     fn compile_query_target(&mut self, node: Node<ast::QueryTarget>) -> query::Target {
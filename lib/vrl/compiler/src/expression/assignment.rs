@@ -3,6 +3,7 @@ use crate::parser::{
     ast::{self, Ident},
     Node,
 };
+use crate::state::TypeOrigin;
 use crate::{Context, Expression, Span, State, TypeDef, Value};
 use diagnostic::{DiagnosticError, Label, Note};
 use lookup::LookupBuf;
@@ -58,7 +59,7 @@ impl Assignment {
                     _ => None,
                 };
 
-                target.insert_type_def(state, type_def, value);
+                target.insert_type_def(state, type_def, value, target_span);
 
                 Variant::Single {
                     target,
@@ -114,14 +115,14 @@ impl Assignment {
                     _ => None,
                 };
 
-                ok.insert_type_def(state, type_def, value);
+                ok.insert_type_def(state, type_def, value, ok_span);
 
                 // "err" target is assigned `null` or a string containing the
                 // error message.
                 let err = Target::try_from(err.into_inner())?;
                 let type_def = TypeDef::new().bytes().add_null().infallible();
 
-                err.insert_type_def(state, type_def, None);
+                err.insert_type_def(state, type_def, None, err_span);
 
                 Variant::Infallible {
                     ok,
@@ -188,7 +189,13 @@ pub enum Target {
 }
 
 impl Target {
-    fn insert_type_def(&self, state: &mut State, type_def: TypeDef, value: Option<Value>) {
+    fn insert_type_def(
+        &self,
+        state: &mut State,
+        type_def: TypeDef,
+        value: Option<Value>,
+        span: Span,
+    ) {
         use Target::*;
 
         fn set_type_def(
@@ -237,6 +244,10 @@ impl Target {
                 let details = Details { type_def, value };
 
                 state.update_target(details);
+                state.insert_target_origin(
+                    path.clone().unwrap_or_else(LookupBuf::root),
+                    TypeOrigin::Assignment(span),
+                );
             }
         }
     }
@@ -1,6 +1,7 @@
-use crate::expression::{levenstein, ExpressionError, FunctionArgument, Noop};
+use crate::expression::{levenstein, Expr, ExpressionError, FunctionArgument, Noop};
 use crate::function::{ArgumentList, FunctionCompileContext, Parameter};
 use crate::parser::{Ident, Node};
+use crate::state::TypeOrigin;
 use crate::{value::Kind, Context, Expression, Function, Resolved, Span, State, TypeDef};
 
 use diagnostic::{DiagnosticError, Label, Note, Urls};
@@ -124,6 +125,14 @@ impl FunctionCall {
             let param_kind = parameter.kind();
 
             if !param_kind.intersects(expr_kind) {
+                // Explain where the type of event paths comes from.
+                let origin = match argument.inner() {
+                    Expr::Query(query) if query.is_external() => {
+                        state.target_origin(query.path()).cloned()
+                    }
+                    _ => None,
+                };
+
                 return Err(Error::InvalidArgumentKind {
                     function_ident: function.identifier(),
                     abort_on_error,
@@ -132,6 +141,7 @@ impl FunctionCall {
                     got: expr_kind,
                     argument,
                     argument_span,
+                    origin,
                 });
             } else if !param_kind.contains(expr_kind) {
                 maybe_fallible_arguments = true;
@@ -400,6 +410,7 @@ pub enum Error {
         got: Kind,
         argument: FunctionArgument,
         argument_span: Span,
+        origin: Option<TypeOrigin>,
     },
 
     #[error("fallible argument")]
@@ -531,6 +542,7 @@ impl DiagnosticError for Error {
                 got,
                 argument,
                 argument_span,
+                origin,
                 ..
             } => {
                 let keyword = parameter.keyword;
@@ -548,7 +560,7 @@ impl DiagnosticError for Error {
                     }
                 };
 
-                vec![
+                let mut labels = vec![
                     Label::primary(
                         format!("this expression resolves to {}", kind_str(got)),
                         expr_span,
@@ -561,7 +573,16 @@ impl DiagnosticError for Error {
                         ),
                         argument_span,
                     ),
-                ]
+                ];
+
+                if let Some(TypeOrigin::Assignment(span)) = origin {
+                    labels.push(Label::context(
+                        format!("the type of {} is inferred from this assignment", argument),
+                        span,
+                    ));
+                }
+
+                labels
             }
 
             FallibleArgument { expr_span } => vec![
@@ -594,6 +615,7 @@ impl DiagnosticError for Error {
                 arguments_fmt,
                 parameter,
                 argument,
+                origin,
                 ..
             } => {
                 // TODO: move this into a generic helper function
@@ -634,6 +656,13 @@ impl DiagnosticError for Error {
 
                 let mut notes = vec![];
 
+                if let Some(TypeOrigin::Definition(definition)) = origin {
+                    notes.push(Note::Hint(format!(
+                        "the type of {} is defined by {}",
+                        argument, definition
+                    )));
+                }
+
                 let call = format!("{}{}({})", function_ident, abort, args);
 
                 notes.append(&mut Note::solution(
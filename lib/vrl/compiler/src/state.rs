@@ -1,6 +1,12 @@
 use crate::expression::assignment;
-use crate::{parser::ast::Ident, TypeDef, Value};
-use std::{any::Any, collections::HashMap};
+use crate::{parser::ast::Ident, value::Kind, Span, TypeDef, Value};
+use lookup::LookupBuf;
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    time::{Duration, Instant},
+};
 
 /// The state held by the compiler.
 ///
@@ -14,6 +20,13 @@ pub struct Compiler {
     /// stored internal variable type definitions
     variables: HashMap<Ident, assignment::Details>,
 
+    /// Where the type definitions of external paths come from, most recent
+    /// last.
+    target_origins: Vec<(LookupBuf, TypeOrigin)>,
+
+    /// The external paths queried by the program, in order.
+    queried_paths: Vec<QueriedPath>,
+
     /// context passed between the client program and a VRL function.
     external_context: Option<Box<dyn Any>>,
 
@@ -66,16 +79,62 @@ impl Compiler {
         self.target = Some(details);
     }
 
+    /// Records where the type definition of an external path, and of the
+    /// paths below it, comes from.
+    pub(crate) fn insert_target_origin(&mut self, path: LookupBuf, origin: TypeOrigin) {
+        self.target_origins.push((path, origin));
+    }
+
+    /// Records that the type definition given to the compiler for an external
+    /// path comes from the given definition, such as a field of the schema of
+    /// the component sending events to the program.
+    ///
+    /// This is shown in diagnostics about the types of these paths.
+    pub fn set_target_origin(&mut self, path: LookupBuf, definition: impl Into<String>) {
+        self.insert_target_origin(path, TypeOrigin::Definition(definition.into()));
+    }
+
+    /// Returns where the type definition of an external path comes from, if
+    /// known.
+    pub fn target_origin(&self, path: &LookupBuf) -> Option<&TypeOrigin> {
+        self.target_origins
+            .iter()
+            .rev()
+            .find(|(prefix, _)| prefix.len() <= path.len() && path.starts_with(prefix))
+            .map(|(_, origin)| origin)
+    }
+
+    pub(crate) fn insert_queried_path(&mut self, path: LookupBuf, span: Span, kind: Kind) {
+        let origin = self.target_origin(&path).cloned();
+
+        self.queried_paths.push(QueriedPath {
+            path,
+            span,
+            kind,
+            origin,
+        });
+    }
+
+    /// Returns the external paths queried by the program so far, with the
+    /// type inferred for them at that point of the program.
+    pub fn queried_paths(&self) -> &[QueriedPath] {
+        &self.queried_paths
+    }
+
     /// Take a snapshot of the current state of the compiler.
     ///
     /// This overwrites any existing snapshot currently stored.
     pub(crate) fn snapshot(&mut self) {
         let target = self.target.clone();
         let variables = self.variables.clone();
+        let target_origins = self.target_origins.clone();
+        let queried_paths = self.queried_paths.clone();
 
         let snapshot = Self {
             target,
             variables,
+            target_origins,
+            queried_paths,
             external_context: None,
             snapshot: None,
         };
@@ -118,20 +177,73 @@ impl Compiler {
     }
 }
 
+/// Where the type definition of an external path comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeOrigin {
+    /// The path was assigned by the program.
+    Assignment(Span),
+
+    /// The type definition was given to the compiler, from the given
+    /// definition.
+    Definition(String),
+}
+
+impl fmt::Display for TypeOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeOrigin::Assignment(span) => {
+                write!(f, "assignment at ({}:{})", span.start(), span.end())
+            }
+            TypeOrigin::Definition(definition) => definition.fmt(f),
+        }
+    }
+}
+
+/// An external path queried by the program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueriedPath {
+    pub path: LookupBuf,
+    pub span: Span,
+
+    /// The kind inferred for the path where it's queried.
+    pub kind: Kind,
+
+    /// Where that kind comes from, if the path was assigned or defined.
+    pub origin: Option<TypeOrigin>,
+}
+
 /// The state used at runtime to track changes as they happen.
 #[derive(Debug, Default)]
 pub struct Runtime {
     /// The [`Value`] stored in each variable.
     variables: HashMap<Ident, Value>,
+
+    /// Values cached by the program, kept across runs.
+    cache: Cache,
+
+    /// Metrics emitted by the program during the current run.
+    emitted_metrics: Vec<EmittedMetric>,
 }
 
 impl Runtime {
+    /// Creates a runtime state whose cache holds at most `capacity` values.
+    pub fn with_cache_capacity(capacity: usize) -> Self {
+        Self {
+            variables: HashMap::default(),
+            cache: Cache::new(capacity),
+            emitted_metrics: Vec::default(),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.variables.is_empty()
+        self.variables.is_empty() && self.emitted_metrics.is_empty()
     }
 
+    /// Clears the variables and emitted metrics, but not the cache, which
+    /// outlives a single run.
     pub fn clear(&mut self) {
         self.variables.clear();
+        self.emitted_metrics.clear();
     }
 
     pub fn variable(&self, ident: &Ident) -> Option<&Value> {
@@ -145,4 +257,221 @@ impl Runtime {
     pub(crate) fn insert_variable(&mut self, ident: Ident, value: Value) {
         self.variables.insert(ident, value);
     }
+
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    pub fn cache_mut(&mut self) -> &mut Cache {
+        &mut self.cache
+    }
+
+    pub fn emit_metric(&mut self, metric: EmittedMetric) {
+        self.emitted_metrics.push(metric);
+    }
+
+    pub fn emitted_metrics(&self) -> &[EmittedMetric] {
+        &self.emitted_metrics
+    }
+
+    /// Takes the metrics emitted during the current run, for the host to send
+    /// along with the target.
+    pub fn take_emitted_metrics(&mut self) -> Vec<EmittedMetric> {
+        std::mem::take(&mut self.emitted_metrics)
+    }
+}
+
+/// A metric emitted by the program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmittedMetric {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub kind: EmittedMetricKind,
+    pub value: f64,
+    pub tags: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmittedMetricKind {
+    /// Incremented by the value.
+    Counter,
+    /// Set to the value.
+    Gauge,
+    /// The value is a sample of a distribution.
+    Histogram,
+}
+
+/// A bounded cache of values, evicting the least recently used value once
+/// full. Values can optionally expire after a time-to-live.
+#[derive(Debug)]
+pub struct Cache {
+    capacity: usize,
+    entries: HashMap<String, CacheEntry>,
+    /// The keys of the entries, ordered from least to most recently used.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    value: Value,
+    expires: Option<Instant>,
+    tick: u64,
+}
+
+impl Cache {
+    pub const DEFAULT_CAPACITY: usize = 1000;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::default(),
+            recency: BTreeMap::default(),
+            tick: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value cached under `key`, unless it expired.
+    pub fn get(&mut self, key: &str) -> Option<&Value> {
+        let expired = match self.entries.get(key) {
+            None => return None,
+            Some(entry) => entry.expires.map_or(false, |at| at <= Instant::now()),
+        };
+        if expired {
+            self.remove(key);
+            return None;
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.entries.get_mut(key)?;
+        let key = self.recency.remove(&entry.tick)?;
+        self.recency.insert(tick, key);
+        entry.tick = tick;
+        Some(&entry.value)
+    }
+
+    /// Caches `value` under `key`, evicting the least recently used value if
+    /// the cache is full.
+    pub fn insert(&mut self, key: String, value: Value, ttl: Option<Duration>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.remove(&key);
+        while self.entries.len() >= self.capacity {
+            let oldest = match self.recency.keys().next() {
+                Some(tick) => *tick,
+                None => break,
+            };
+            if let Some(key) = self.recency.remove(&oldest) {
+                self.entries.remove(&key);
+            }
+        }
+
+        self.tick += 1;
+        let entry = CacheEntry {
+            value,
+            expires: ttl.map(|ttl| Instant::now() + ttl),
+            tick: self.tick,
+        };
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, entry);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.tick);
+        Some(entry.value)
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn tracks_origins_of_queried_paths() {
+        let mut state = Compiler::new();
+        state.set_target_origin(LookupBuf::from_str("foo").unwrap(), "the schema of `in`");
+
+        let ast = parser::parse(".bar = 1\n.foo.baz\n.bar\n.qux").unwrap();
+        crate::compile_with_state(ast, &[], &mut state).unwrap();
+
+        let queried = state.queried_paths();
+        assert_eq!(queried.len(), 3);
+
+        assert_eq!(queried[0].path.to_string(), "foo.baz");
+        assert!(queried[0].kind.is_all());
+        assert_eq!(
+            queried[0].origin,
+            Some(TypeOrigin::Definition("the schema of `in`".to_owned()))
+        );
+
+        assert_eq!(queried[1].path.to_string(), "bar");
+        assert_eq!(queried[1].kind, Kind::Integer);
+        assert!(matches!(queried[1].origin, Some(TypeOrigin::Assignment(_))));
+
+        assert_eq!(queried[2].path.to_string(), "qux");
+        assert_eq!(queried[2].origin, None);
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let mut cache = Cache::new(2);
+        cache.insert("a".to_owned(), Value::Integer(1), None);
+        cache.insert("b".to_owned(), Value::Integer(2), None);
+        assert_eq!(cache.get("a"), Some(&Value::Integer(1)));
+
+        cache.insert("c".to_owned(), Value::Integer(3), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(&Value::Integer(1)));
+        assert_eq!(cache.get("c"), Some(&Value::Integer(3)));
+    }
+
+    #[test]
+    fn cache_expires_values() {
+        let mut cache = Cache::new(2);
+        cache.insert(
+            "a".to_owned(),
+            Value::Integer(1),
+            Some(Duration::from_secs(0)),
+        );
+        cache.insert(
+            "b".to_owned(),
+            Value::Integer(2),
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(&Value::Integer(2)));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn runtime_clear_keeps_cache() {
+        let mut state = Runtime::with_cache_capacity(1);
+        state.insert_variable(Ident::new("foo"), true.into());
+        state
+            .cache_mut()
+            .insert("a".to_owned(), Value::Integer(1), None);
+        state.clear();
+        assert!(state.is_empty());
+        assert_eq!(state.cache_mut().get("a"), Some(&Value::Integer(1)));
+    }
 }
@@ -57,6 +57,11 @@ impl Runtime {
         self.state.clear();
     }
 
+    /// Takes the metrics emitted by the program since the last run.
+    pub fn take_emitted_metrics(&mut self) -> Vec<state::EmittedMetric> {
+        self.state.take_emitted_metrics()
+    }
+
     /// Given the provided [`Target`], resolve the provided [`Program`] to
     /// completion.
     pub fn resolve(
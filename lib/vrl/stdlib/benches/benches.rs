@@ -20,6 +20,7 @@ criterion_group!(
               decode_percent,
               // TODO: Cannot pass a Path to bench_function
               //del,
+              derive_severity,
               downcase,
               encode_base64,
               encode_key_value,
@@ -235,6 +236,25 @@ bench_function! {
     }
 }
 
+bench_function! {
+    derive_severity => vrl_stdlib::DeriveSeverity;
+
+    name {
+        args: func_args![value: "WARN"],
+        want: Ok("warning"),
+    }
+
+    number {
+        args: func_args![value: 30, scale: "python"],
+        want: Ok("warning"),
+    }
+
+    mapping {
+        args: func_args![value: "W", mapping: value!({"W": "warning"})],
+        want: Ok("warning"),
+    }
+}
+
 bench_function! {
     downcase => vrl_stdlib::Downcase;
 
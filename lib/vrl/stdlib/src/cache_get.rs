@@ -0,0 +1,93 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CacheGet;
+
+impl Function for CacheGet {
+    fn identifier(&self) -> &'static str {
+        "cache_get"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "cached value",
+            source: r#"cache_set("foo", "bar"); cache_get!("foo")"#,
+            result: Ok("bar"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+
+        Ok(Box::new(CacheGetFn { key }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CacheGetFn {
+    key: Box<dyn Expression>,
+}
+
+impl Expression for CacheGetFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        let key = key.try_bytes_utf8_lossy()?;
+
+        ctx.state_mut()
+            .cache_mut()
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| format!("no cached value for key {:?}", key).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().unknown()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::TimeZone;
+
+    test_function![
+        cache_get => CacheGet;
+
+        missing {
+            args: func_args![key: "foo"],
+            want: Err(r#"no cached value for key "foo""#),
+            tdef: TypeDef::new().fallible().unknown(),
+        }
+    ];
+
+    #[test]
+    fn cached_across_runs() {
+        let tz = TimeZone::default();
+        let mut runtime_state = vrl::state::Runtime::default();
+        runtime_state
+            .cache_mut()
+            .insert("foo".to_owned(), value!([1, 2]), None);
+        runtime_state.clear();
+
+        let func = CacheGetFn { key: expr!("foo") };
+        let mut object = value!({});
+        let mut ctx = Context::new(&mut object, &mut runtime_state, &tz);
+        let got = func
+            .resolve(&mut ctx)
+            .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+        assert_eq!(got, Ok(value!([1, 2])));
+    }
+}
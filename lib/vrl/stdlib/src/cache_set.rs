@@ -0,0 +1,106 @@
+use std::time::Duration;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CacheSet;
+
+impl Function for CacheSet {
+    fn identifier(&self) -> &'static str {
+        "cache_set"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "ttl_secs",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "cache a value",
+            source: r#"cache_set("foo", "bar", ttl_secs: 60)"#,
+            result: Ok("bar"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let value = arguments.required("value");
+        let ttl_secs = arguments.optional("ttl_secs");
+
+        Ok(Box::new(CacheSetFn {
+            key,
+            value,
+            ttl_secs,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CacheSetFn {
+    key: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+    ttl_secs: Option<Box<dyn Expression>>,
+}
+
+impl Expression for CacheSetFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned();
+        let value = self.value.resolve(ctx)?;
+        let ttl = match &self.ttl_secs {
+            Some(ttl_secs) => {
+                let ttl_secs = ttl_secs.resolve(ctx)?.try_integer()?;
+                Some(Duration::from_secs(ttl_secs.max(0) as u64))
+            }
+            None => None,
+        };
+
+        ctx.state_mut().cache_mut().insert(key, value.clone(), ttl);
+
+        Ok(value)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value.type_def(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        cache_set => CacheSet;
+
+        returns_value {
+            args: func_args![key: "foo", value: "bar"],
+            want: Ok("bar"),
+            tdef: TypeDef::new().bytes(),
+        }
+
+        with_ttl {
+            args: func_args![key: "foo", value: 12, ttl_secs: 60],
+            want: Ok(12),
+            tdef: TypeDef::new().integer(),
+        }
+    ];
+}
@@ -0,0 +1,359 @@
+use ::sha1::Digest;
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+/// The version of the Community ID specification implemented, prefixed to
+/// the hashes.
+const VERSION: &str = "1";
+
+const ICMP: u8 = 1;
+const TCP: u8 = 6;
+const UDP: u8 = 17;
+const ICMP6: u8 = 58;
+const SCTP: u8 = 132;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CommunityId;
+
+impl Function for CommunityId {
+    fn identifier(&self) -> &'static str {
+        "community_id"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "source_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "destination_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::INTEGER | kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "source_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "destination_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "seed",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "TCP",
+                source: r#"community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 3344)"#,
+                result: Ok(r#""1:wCb3OG7yAFWelaUydu0D+125CLM=""#),
+            },
+            Example {
+                title: "with seed",
+                source: r#"community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: "tcp", source_port: 1122, destination_port: 3344, seed: 123)"#,
+                result: Ok(r#""1:C6Pb16GyzQL53fHtCtR5PliXXkY=""#),
+            },
+            Example {
+                title: "ICMP",
+                source: r#"community_id!(source_ip: "192.168.0.89", destination_ip: "192.168.0.1", protocol: "icmp", source_port: 8, destination_port: 0)"#,
+                result: Ok(r#""1:X0snYXpgwiv9TZtqg64sgzUn6Dk=""#),
+            },
+            Example {
+                title: "mixed address families",
+                source: r#"community_id!(source_ip: "1.2.3.4", destination_ip: "::1", protocol: 6)"#,
+                result: Err(
+                    r#"function call error for "community_id" at (0:71): source_ip and destination_ip must both be IPv4 or IPv6 addresses"#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let source_ip = arguments.required("source_ip");
+        let destination_ip = arguments.required("destination_ip");
+        let protocol = arguments.required("protocol");
+        let source_port = arguments.optional("source_port");
+        let destination_port = arguments.optional("destination_port");
+        let seed = arguments.optional("seed");
+
+        Ok(Box::new(CommunityIdFn {
+            source_ip,
+            destination_ip,
+            protocol,
+            source_port,
+            destination_port,
+            seed,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CommunityIdFn {
+    source_ip: Box<dyn Expression>,
+    destination_ip: Box<dyn Expression>,
+    protocol: Box<dyn Expression>,
+    source_port: Option<Box<dyn Expression>>,
+    destination_port: Option<Box<dyn Expression>>,
+    seed: Option<Box<dyn Expression>>,
+}
+
+impl Expression for CommunityIdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let source_ip = parse_ip(self.source_ip.resolve(ctx)?)?;
+        let destination_ip = parse_ip(self.destination_ip.resolve(ctx)?)?;
+
+        let protocol = match self.protocol.resolve(ctx)? {
+            Value::Bytes(name) => {
+                let name = String::from_utf8_lossy(&name);
+                protocol_number(&name, source_ip.is_ipv6())
+                    .ok_or_else(|| format!("unknown protocol {:?}", name))?
+            }
+            value => {
+                let protocol = value.try_integer()?;
+                u8::try_from(protocol)
+                    .map_err(|_| format!("protocol must be between 0 and 255, got {}", protocol))?
+            }
+        };
+
+        let ports = match (&self.source_port, &self.destination_port) {
+            (Some(source_port), Some(destination_port)) => Some((
+                port(source_port.resolve(ctx)?, "source_port")?,
+                port(destination_port.resolve(ctx)?, "destination_port")?,
+            )),
+            (None, None) => None,
+            _ => return Err("source_port and destination_port must be set together".into()),
+        };
+
+        let seed = match &self.seed {
+            Some(seed) => {
+                let seed = seed.resolve(ctx)?.try_integer()?;
+                u16::try_from(seed)
+                    .map_err(|_| format!("seed must be between 0 and 65535, got {}", seed))?
+            }
+            None => 0,
+        };
+
+        Ok(community_id(seed, protocol, source_ip, destination_ip, ports)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+fn parse_ip(value: Value) -> Result<IpAddr> {
+    value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {}", err).into())
+}
+
+fn port(value: Value, name: &str) -> Result<u16> {
+    let port = value.try_integer()?;
+    u16::try_from(port)
+        .map_err(|_| format!("{} must be between 0 and 65535, got {}", name, port).into())
+}
+
+/// The number of the protocol named as in the `proto` field of Zeek and
+/// Suricata logs. Both name ICMPv6 `icmp`, which is told apart by the family
+/// of the addresses of the flow.
+fn protocol_number(name: &str, ipv6: bool) -> Option<u8> {
+    match name.to_ascii_lowercase().as_str() {
+        "icmp" if ipv6 => Some(ICMP6),
+        "icmp" => Some(ICMP),
+        "tcp" => Some(TCP),
+        "udp" => Some(UDP),
+        "icmp6" | "icmpv6" | "ipv6-icmp" => Some(ICMP6),
+        "sctp" => Some(SCTP),
+        _ => None,
+    }
+}
+
+/// The type of the ICMP message answering messages of `message_type`, or
+/// answered by them, if any.
+const fn icmp_counterpart(protocol: u8, message_type: u16) -> Option<u16> {
+    match (protocol, message_type) {
+        // Echo, router discovery, timestamp, information and address mask.
+        (ICMP, 8) => Some(0),
+        (ICMP, 0) => Some(8),
+        (ICMP, 10) => Some(9),
+        (ICMP, 9) => Some(10),
+        (ICMP, 13) => Some(14),
+        (ICMP, 14) => Some(13),
+        (ICMP, 15) => Some(16),
+        (ICMP, 16) => Some(15),
+        (ICMP, 17) => Some(18),
+        (ICMP, 18) => Some(17),
+        // Echo, multicast listener, router and neighbor discovery, who are
+        // you and home agent address discovery.
+        (ICMP6, 128) => Some(129),
+        (ICMP6, 129) => Some(128),
+        (ICMP6, 130) => Some(131),
+        (ICMP6, 131) => Some(130),
+        (ICMP6, 133) => Some(134),
+        (ICMP6, 134) => Some(133),
+        (ICMP6, 135) => Some(136),
+        (ICMP6, 136) => Some(135),
+        (ICMP6, 139) => Some(140),
+        (ICMP6, 140) => Some(139),
+        (ICMP6, 144) => Some(145),
+        (ICMP6, 145) => Some(144),
+        _ => None,
+    }
+}
+
+/// The Community ID of a flow, which is the same whatever the direction the
+/// flow is seen in.
+///
+/// The endpoints of the flow are ordered, and hashed with SHA-1 along with
+/// the seed, the protocol and, for protocols that have them, the ports. ICMP
+/// messages have their type and code in place of ports, with the code
+/// replaced by the type of the counterpart of the message, so that requests
+/// and their replies share an ID. Messages without a counterpart are one-way,
+/// and their endpoints aren't ordered.
+fn community_id(
+    seed: u16,
+    protocol: u8,
+    source_ip: IpAddr,
+    destination_ip: IpAddr,
+    ports: Option<(u16, u16)>,
+) -> Result<String> {
+    let (mut source, mut destination) = match (source_ip, destination_ip) {
+        (IpAddr::V4(source), IpAddr::V4(destination)) => {
+            (source.octets().to_vec(), destination.octets().to_vec())
+        }
+        (IpAddr::V6(source), IpAddr::V6(destination)) => {
+            (source.octets().to_vec(), destination.octets().to_vec())
+        }
+        _ => return Err("source_ip and destination_ip must both be IPv4 or IPv6 addresses".into()),
+    };
+
+    // Ports are only part of the flow of protocols that have them.
+    let mut ports = ports.filter(|_| matches!(protocol, ICMP | TCP | UDP | ICMP6 | SCTP));
+    let mut one_way = false;
+    if let (ICMP | ICMP6, Some((message_type, code))) = (protocol, &mut ports) {
+        match icmp_counterpart(protocol, *message_type) {
+            Some(counterpart) => *code = counterpart,
+            None => one_way = true,
+        }
+    }
+
+    let ordered = source < destination
+        || (source == destination && ports.map_or(false, |(from, to)| from < to));
+    if !one_way && !ordered {
+        std::mem::swap(&mut source, &mut destination);
+        ports = ports.map(|(from, to)| (to, from));
+    }
+
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(seed.to_be_bytes());
+    hasher.update(&source);
+    hasher.update(&destination);
+    hasher.update([protocol, 0]);
+    if let Some((source_port, destination_port)) = ports {
+        hasher.update(source_port.to_be_bytes());
+        hasher.update(destination_port.to_be_bytes());
+    }
+
+    Ok(format!("{}:{}", VERSION, base64::encode(hasher.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        community_id => CommunityId;
+
+        tcp {
+            args: func_args![source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80],
+            want: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        tcp_reversed {
+            args: func_args![source_ip: "66.35.250.204", destination_ip: "128.232.110.120", protocol: "TCP", source_port: 80, destination_port: 34855],
+            want: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        udp {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: "udp", source_port: 1122, destination_port: 3344],
+            want: Ok("1:0Mu9InQx6z4ZiCZM/7HXi2WMhOg="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        seeded {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 3344, seed: 123],
+            want: Ok("1:C6Pb16GyzQL53fHtCtR5PliXXkY="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        ipv6 {
+            args: func_args![source_ip: "2001:db8::1", destination_ip: "2001:db8::2", protocol: 6, source_port: 1122, destination_port: 3344],
+            want: Ok("1:dtKkTDNeFYoQ6mQ5GbF61t2D04c="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_echo_reply {
+            args: func_args![source_ip: "192.168.0.1", destination_ip: "192.168.0.89", protocol: 1, source_port: 0, destination_port: 0],
+            want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_one_way {
+            args: func_args![source_ip: "192.168.0.89", destination_ip: "192.168.0.1", protocol: 1, source_port: 3, destination_port: 1],
+            want: Ok("1:LXCfhMUFbYXe+CEMrQuZCbiLY3o="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp6 {
+            args: func_args![source_ip: "fe80::200:86ff:fe05:80da", destination_ip: "fe80::260:97ff:fe07:69ea", protocol: "icmp", source_port: 135, destination_port: 0],
+            want: Ok("1:dGHyGvjMfljg6Bppwm3bg0LO8TY="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        without_ports {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 47],
+            want: Ok("1:+mluqz76jObh6dJLwnhX08n5St4="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        single_port {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122],
+            want: Err("source_port and destination_port must be set together"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_port {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 70000],
+            want: Err("destination_port must be between 0 and 65535, got 70000"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown_protocol {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: "gre"],
+            want: Err(r#"unknown protocol "gre""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
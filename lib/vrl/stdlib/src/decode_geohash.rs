@@ -0,0 +1,148 @@
+use crate::util::GEOHASH_BASE32;
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeGeohash;
+
+impl Function for DecodeGeohash {
+    fn identifier(&self) -> &'static str {
+        "decode_geohash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "geohash",
+                source: r#"decode_geohash("u09tvw")"#,
+                result: Ok(r#"{ "latitude": 48.85894775390625, "longitude": 2.3565673828125 }"#),
+            },
+            Example {
+                title: "invalid geohash",
+                source: r#"decode_geohash("u09ta")"#,
+                result: Err(
+                    r#"function call error for "decode_geohash" at (0:23): invalid geohash character "a""#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(DecodeGeohashFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecodeGeohashFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for DecodeGeohashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let geohash = value.try_bytes_utf8_lossy()?;
+        let (latitude, longitude) = decode(&geohash)?;
+
+        let mut map = BTreeMap::new();
+        map.insert("latitude".to_owned(), latitude.into());
+        map.insert("longitude".to_owned(), longitude.into());
+        Ok(Value::Object(map))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().object(type_def())
+    }
+}
+
+/// Decodes a geohash into the center of the cell it stands for, as a latitude
+/// and longitude.
+fn decode(geohash: &str) -> std::result::Result<(f64, f64), String> {
+    if geohash.is_empty() {
+        return Err("geohash must not be empty".to_owned());
+    }
+
+    let mut latitude_range = (-90.0, 90.0);
+    let mut longitude_range = (-180.0, 180.0);
+    let mut even = true;
+
+    for character in geohash.chars() {
+        let index = GEOHASH_BASE32
+            .iter()
+            .position(|&c| c as char == character.to_ascii_lowercase())
+            .ok_or_else(|| format!(r#"invalid geohash character "{}""#, character))?;
+        for bit in (0..5).rev() {
+            let range = if even {
+                &mut longitude_range
+            } else {
+                &mut latitude_range
+            };
+            let middle = (range.0 + range.1) / 2.0;
+            if index >> bit & 1 == 1 {
+                range.0 = middle;
+            } else {
+                range.1 = middle;
+            }
+            even = !even;
+        }
+    }
+
+    Ok((
+        (latitude_range.0 + latitude_range.1) / 2.0,
+        (longitude_range.0 + longitude_range.1) / 2.0,
+    ))
+}
+
+fn type_def() -> BTreeMap<&'static str, TypeDef> {
+    map! {
+        "latitude": Kind::Float,
+        "longitude": Kind::Float,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        decode_geohash => DecodeGeohash;
+
+        geohash {
+            args: func_args![value: "u4pruydqqvj"],
+            want: Ok(value!({ "latitude": 57.64911063015461, "longitude": 10.407439693808556 })),
+            tdef: TypeDef::new().fallible().object(type_def()),
+        }
+
+        uppercase {
+            args: func_args![value: "EZS42"],
+            want: Ok(value!({ "latitude": 42.60498046875, "longitude": -5.60302734375 })),
+            tdef: TypeDef::new().fallible().object(type_def()),
+        }
+
+        invalid_character {
+            args: func_args![value: "u4pi"],
+            want: Err(r#"invalid geohash character "i""#),
+            tdef: TypeDef::new().fallible().object(type_def()),
+        }
+
+        empty {
+            args: func_args![value: ""],
+            want: Err("geohash must not be empty"),
+            tdef: TypeDef::new().fallible().object(type_def()),
+        }
+    ];
+}
@@ -0,0 +1,137 @@
+use crate::protobuf_util::{compile_message, MessageRef};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeProtobuf;
+
+impl Function for DecodeProtobuf {
+    fn identifier(&self) -> &'static str {
+        "decode_protobuf"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "desc_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "message_type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "decode protobuf message",
+            source: r#"decode_protobuf!(decode_base64!("Cgdzb21lb25lEAE="), "../stdlib/data/protobuf/test_protobuf.desc", "test_protobuf.Person")"#,
+            result: Ok(r#"{ "id": 1, "name": "someone" }"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let message = compile_message(&mut arguments)?;
+
+        Ok(Box::new(DecodeProtobufFn { value, message }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeProtobufFn {
+    value: Box<dyn Expression>,
+
+    // Shares the descriptor set loaded at compile time.
+    message: MessageRef,
+}
+
+impl Expression for DecodeProtobufFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let bytes = self.value.resolve(ctx)?.try_bytes()?;
+
+        self.message.decode(&bytes)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().object::<(), Kind>(map! {
+            (): Kind::all(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf_util::test_util::{person, DESC_FILE, PERSON};
+    use shared::btreemap;
+
+    test_function![
+        decode_protobuf => DecodeProtobuf;
+
+        scalars {
+            args: func_args![value: Bytes::from_static(b"\n\x07someone\x10\x01"),
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Ok(Value::from(btreemap! {
+                "name" => "someone",
+                "id" => 1,
+            })),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! {
+                (): Kind::all(),
+            }),
+        }
+
+        nested_repeated_and_maps {
+            args: func_args![value: Bytes::from_static(PERSON),
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Ok(person()),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! {
+                (): Kind::all(),
+            }),
+        }
+
+        truncated {
+            args: func_args![value: Bytes::from_static(b"\n\x07some"),
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Err("unable to decode protobuf message: buffer underflow"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! {
+                (): Kind::all(),
+            }),
+        }
+
+        unknown_message_type {
+            args: func_args![value: Bytes::from_static(b""),
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Nobody"],
+            want: Err(r#"message type "test_protobuf.Nobody" not found in descriptor set "data/protobuf/test_protobuf.desc""#),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! {
+                (): Kind::all(),
+            }),
+        }
+
+        missing_desc_file {
+            args: func_args![value: Bytes::from_static(b""),
+                             desc_file: "data/protobuf/missing.desc",
+                             message_type: "test_protobuf.Person"],
+            want: Err(r#"unable to load descriptor set "data/protobuf/missing.desc": No such file or directory (os error 2)"#),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! {
+                (): Kind::all(),
+            }),
+        }
+    ];
+}
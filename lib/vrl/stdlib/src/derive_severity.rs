@@ -0,0 +1,179 @@
+use shared::severity::{NumericScale, Severity, SeverityMapper};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DeriveSeverity;
+
+impl Function for DeriveSeverity {
+    fn identifier(&self) -> &'static str {
+        "derive_severity"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES | kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "mapping",
+                kind: kind::OBJECT,
+                required: false,
+            },
+            Parameter {
+                keyword: "scale",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "name",
+                source: r#"derive_severity!("WARN")"#,
+                result: Ok("warning"),
+            },
+            Example {
+                title: "numeric scale",
+                source: r#"derive_severity!(40, scale: "python")"#,
+                result: Ok("error"),
+            },
+            Example {
+                title: "custom mapping",
+                source: r#"derive_severity!("E", mapping: { "E": "error", "W": "warning" })"#,
+                result: Ok("error"),
+            },
+            Example {
+                title: "unknown",
+                source: r#"derive_severity!("loud")"#,
+                result: Err(
+                    r#"function call error for "derive_severity" at (0:24): unknown severity "loud""#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let mapping = arguments.optional("mapping");
+        let scale = arguments
+            .optional_enum("scale", &scales())?
+            .map(|s| {
+                NumericScale::from_name(&s.try_bytes_utf8_lossy().expect("scale not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        Ok(Box::new(DeriveSeverityFn {
+            value,
+            mapping,
+            scale,
+        }))
+    }
+}
+
+fn scales() -> Vec<Value> {
+    NumericScale::ALL
+        .iter()
+        .map(|scale| scale.as_str().into())
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+struct DeriveSeverityFn {
+    value: Box<dyn Expression>,
+    mapping: Option<Box<dyn Expression>>,
+    scale: NumericScale,
+}
+
+impl Expression for DeriveSeverityFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        let mapping = match &self.mapping {
+            Some(mapping) => mapping
+                .resolve(ctx)?
+                .try_object()?
+                .into_iter()
+                .map(|(key, severity)| {
+                    let severity = severity.try_bytes_utf8_lossy()?.parse::<Severity>()?;
+                    Ok((key, severity))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        let mapper = SeverityMapper::new(mapping, self.scale);
+
+        let severity = match &value {
+            Value::Integer(number) => mapper.map_integer(*number),
+            value => mapper.map_str(&value.try_bytes_utf8_lossy()?),
+        };
+
+        severity
+            .map(|severity| severity.as_str().into())
+            .ok_or_else(|| format!("unknown severity {}", value).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        derive_severity => DeriveSeverity;
+
+        name {
+            args: func_args![value: "Fatal"],
+            want: Ok("critical"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        syslog_number {
+            args: func_args![value: 4],
+            want: Ok("warning"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        windows_level {
+            args: func_args![value: "2", scale: "windows"],
+            want: Ok("error"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        opentelemetry_number {
+            args: func_args![value: 9, scale: "opentelemetry"],
+            want: Ok("info"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        custom_mapping {
+            args: func_args![value: "FATAL", mapping: value!({"fatal": "emergency"})],
+            want: Ok("emergency"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_mapping {
+            args: func_args![value: "x", mapping: value!({"x": "loud"})],
+            want: Err(r#"unknown severity "loud""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown {
+            args: func_args![value: 42],
+            want: Err("unknown severity 42"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
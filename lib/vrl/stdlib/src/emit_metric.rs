@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+use vrl::state::{EmittedMetric, EmittedMetricKind};
+
+#[derive(Clone, Copy, Debug)]
+pub struct EmitMetric;
+
+impl Function for EmitMetric {
+    fn identifier(&self) -> &'static str {
+        "emit_metric"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "name",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "type",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "tags",
+                kind: kind::OBJECT,
+                required: false,
+            },
+            Parameter {
+                keyword: "namespace",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "counter",
+                source: r#"emit_metric("requests_total", 1, tags: {"status": "200"})"#,
+                result: Ok("null"),
+            },
+            Example {
+                title: "histogram",
+                source: r#"emit_metric("response_time_seconds", 0.25, type: "histogram", namespace: "app")"#,
+                result: Ok("null"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let name = arguments.required("name");
+        let value = arguments.required("value");
+        let kind = arguments
+            .optional_enum("type", &kinds())?
+            .map(
+                |kind| match kind.try_bytes().expect("type not bytes").as_ref() {
+                    b"gauge" => EmittedMetricKind::Gauge,
+                    b"histogram" => EmittedMetricKind::Histogram,
+                    _ => EmittedMetricKind::Counter,
+                },
+            )
+            .unwrap_or(EmittedMetricKind::Counter);
+        let tags = arguments.optional("tags");
+        let namespace = arguments.optional("namespace");
+
+        Ok(Box::new(EmitMetricFn {
+            name,
+            value,
+            kind,
+            tags,
+            namespace,
+        }))
+    }
+}
+
+fn kinds() -> Vec<Value> {
+    vec![value!("counter"), value!("gauge"), value!("histogram")]
+}
+
+#[derive(Debug, Clone)]
+struct EmitMetricFn {
+    name: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+    kind: EmittedMetricKind,
+    tags: Option<Box<dyn Expression>>,
+    namespace: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EmitMetricFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let name = self.name.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned();
+        let value = f64::try_from(&self.value.resolve(ctx)?)?;
+        let tags = match &self.tags {
+            Some(tags) => tags
+                .resolve(ctx)?
+                .try_object()?
+                .into_iter()
+                .filter_map(|(key, value)| match value {
+                    Value::Null => None,
+                    Value::Bytes(bytes) => {
+                        Some((key, String::from_utf8_lossy(&bytes).into_owned()))
+                    }
+                    value => Some((key, value.to_string())),
+                })
+                .collect(),
+            None => BTreeMap::new(),
+        };
+        let namespace = match &self.namespace {
+            Some(namespace) => Some(namespace.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned()),
+            None => None,
+        };
+
+        ctx.state_mut().emit_metric(EmittedMetric {
+            name,
+            namespace,
+            kind: self.kind,
+            value,
+            tags,
+        });
+
+        Ok(Value::Null)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().null()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::TimeZone;
+
+    test_function![
+        emit_metric => EmitMetric;
+
+        counter {
+            args: func_args![name: "requests_total", value: 1],
+            want: Ok(Value::Null),
+            tdef: TypeDef::new().infallible().null(),
+        }
+
+        histogram {
+            args: func_args![name: "response_time_seconds", value: 0.25, type: "histogram"],
+            want: Ok(Value::Null),
+            tdef: TypeDef::new().infallible().null(),
+        }
+
+        invalid_type {
+            args: func_args![name: "requests_total", value: 1, type: "summary"],
+            want: Err(r#"invalid enum variant""#),
+            tdef: TypeDef::new().infallible().null(),
+        }
+    ];
+
+    #[test]
+    fn emits_metrics() {
+        let tz = TimeZone::default();
+        let mut runtime_state = vrl::state::Runtime::default();
+        let mut object = value!({});
+        let mut ctx = Context::new(&mut object, &mut runtime_state, &tz);
+
+        let func = EmitMetricFn {
+            name: expr!("queue_depth"),
+            value: expr!(12),
+            kind: EmittedMetricKind::Gauge,
+            tags: Some(expr!({"queue": "jobs", "shard": 3, "missing": null})),
+            namespace: Some(expr!("app")),
+        };
+        assert_eq!(func.resolve(&mut ctx), Ok(Value::Null));
+        assert_eq!(func.resolve(&mut ctx), Ok(Value::Null));
+
+        let tags = vec![
+            ("queue".to_owned(), "jobs".to_owned()),
+            ("shard".to_owned(), "3".to_owned()),
+        ]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+        let metric = EmittedMetric {
+            name: "queue_depth".to_owned(),
+            namespace: Some("app".to_owned()),
+            kind: EmittedMetricKind::Gauge,
+            value: 12.0,
+            tags,
+        };
+        assert_eq!(
+            runtime_state.take_emitted_metrics(),
+            vec![metric.clone(), metric]
+        );
+        assert!(runtime_state.emitted_metrics().is_empty());
+    }
+}
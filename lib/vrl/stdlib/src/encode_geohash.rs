@@ -0,0 +1,190 @@
+use crate::util::GEOHASH_BASE32;
+use vrl::prelude::*;
+
+/// The longest geohash, precise to a few centimeters.
+const MAX_PRECISION: i64 = 12;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeGeohash;
+
+impl Function for EncodeGeohash {
+    fn identifier(&self) -> &'static str {
+        "encode_geohash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "latitude",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "longitude",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "precision",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default precision",
+                source: r#"encode_geohash(57.64911, 10.40744)"#,
+                result: Ok(r#""u4pruydqqvj8""#),
+            },
+            Example {
+                title: "with precision",
+                source: r#"encode_geohash(48.8566, 2.3522, precision: 6)"#,
+                result: Ok(r#""u09tvw""#),
+            },
+            Example {
+                title: "invalid latitude",
+                source: r#"encode_geohash(91, 0)"#,
+                result: Err(
+                    r#"function call error for "encode_geohash" at (0:21): latitude must be between -90 and 90, got 91"#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let latitude = arguments.required("latitude");
+        let longitude = arguments.required("longitude");
+        let precision = arguments.optional("precision");
+
+        Ok(Box::new(EncodeGeohashFn {
+            latitude,
+            longitude,
+            precision,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeGeohashFn {
+    latitude: Box<dyn Expression>,
+    longitude: Box<dyn Expression>,
+    precision: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeGeohashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let latitude = f64::try_from(&self.latitude.resolve(ctx)?)?;
+        let longitude = f64::try_from(&self.longitude.resolve(ctx)?)?;
+        let precision = match &self.precision {
+            Some(precision) => precision.resolve(ctx)?.try_integer()?,
+            None => MAX_PRECISION,
+        };
+
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(format!("latitude must be between -90 and 90, got {}", latitude).into());
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(
+                format!("longitude must be between -180 and 180, got {}", longitude).into(),
+            );
+        }
+        if !(1..=MAX_PRECISION).contains(&precision) {
+            return Err(format!(
+                "precision must be between 1 and {}, got {}",
+                MAX_PRECISION, precision
+            )
+            .into());
+        }
+
+        Ok(encode(latitude, longitude, precision as usize).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+/// Encodes a point as a geohash of `precision` characters, by halving the
+/// longitude and latitude ranges in turn, five bits to a character.
+fn encode(latitude: f64, longitude: f64, precision: usize) -> String {
+    let mut latitude_range = (-90.0, 90.0);
+    let mut longitude_range = (-180.0, 180.0);
+    let mut geohash = String::with_capacity(precision);
+    let mut even = true;
+
+    while geohash.len() < precision {
+        let mut index = 0;
+        for _ in 0..5 {
+            let (range, value) = if even {
+                (&mut longitude_range, longitude)
+            } else {
+                (&mut latitude_range, latitude)
+            };
+            let middle = (range.0 + range.1) / 2.0;
+            index <<= 1;
+            if value >= middle {
+                index |= 1;
+                range.0 = middle;
+            } else {
+                range.1 = middle;
+            }
+            even = !even;
+        }
+        geohash.push(GEOHASH_BASE32[index] as char);
+    }
+
+    geohash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        encode_geohash => EncodeGeohash;
+
+        default_precision {
+            args: func_args![latitude: 57.64911, longitude: 10.40744],
+            want: Ok("u4pruydqqvj8"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        with_precision {
+            args: func_args![latitude: 48.8566, longitude: 2.3522, precision: 6],
+            want: Ok("u09tvw"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        integers {
+            args: func_args![latitude: 0, longitude: 0, precision: 1],
+            want: Ok("s"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        bounds {
+            args: func_args![latitude: 90, longitude: 180, precision: 4],
+            want: Ok("zzzz"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_longitude {
+            args: func_args![latitude: 0, longitude: -180.5],
+            want: Err("longitude must be between -180 and 180, got -180.5"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_precision {
+            args: func_args![latitude: 0, longitude: 0, precision: 13],
+            want: Err("precision must be between 1 and 12, got 13"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
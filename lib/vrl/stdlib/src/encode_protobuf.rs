@@ -0,0 +1,133 @@
+use crate::protobuf_util::{compile_message, MessageRef};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeProtobuf;
+
+impl Function for EncodeProtobuf {
+    fn identifier(&self) -> &'static str {
+        "encode_protobuf"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "desc_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "message_type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "encode protobuf message",
+            source: r#"encode_base64(encode_protobuf!({ "name": "someone", "id": 1 }, "../stdlib/data/protobuf/test_protobuf.desc", "test_protobuf.Person"))"#,
+            result: Ok("Cgdzb21lb25lEAE="),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let message = compile_message(&mut arguments)?;
+
+        Ok(Box::new(EncodeProtobufFn { value, message }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeProtobufFn {
+    value: Box<dyn Expression>,
+
+    // Shares the descriptor set loaded at compile time.
+    message: MessageRef,
+}
+
+impl Expression for EncodeProtobufFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        self.message.encode(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf_util::test_util::{person, DESC_FILE, PERSON};
+    use shared::btreemap;
+
+    test_function![
+        encode_protobuf => EncodeProtobuf;
+
+        scalars {
+            args: func_args![value: btreemap! {
+                                 "name" => "someone",
+                                 "id" => 1,
+                             },
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Ok(Bytes::from_static(b"\n\x07someone\x10\x01")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested_repeated_and_maps {
+            args: func_args![value: person(),
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Ok(Bytes::from_static(PERSON)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        enum_by_number {
+            args: func_args![value: btreemap! { "favorite_type" => 2 },
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Ok(Bytes::from_static(b"\x30\x02")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown_enum_variant {
+            args: func_args![value: btreemap! { "favorite_type" => "PAGER" },
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Err(r#"unknown variant "PAGER" of enum "test_protobuf.Person.PhoneType""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown_field {
+            args: func_args![value: btreemap! { "nickname" => "someone" },
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Err(r#"unknown field "nickname""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        wrong_type {
+            args: func_args![value: btreemap! { "id" => "one" },
+                             desc_file: DESC_FILE,
+                             message_type: "test_protobuf.Person"],
+            want: Err("expected \"integer\", got \"string\""),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
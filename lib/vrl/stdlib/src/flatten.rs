@@ -10,11 +10,18 @@ impl Function for Flatten {
     }
 
     fn parameters(&self) -> &'static [Parameter] {
-        &[Parameter {
-            keyword: "value",
-            kind: kind::OBJECT | kind::ARRAY,
-            required: true,
-        }]
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT | kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "separator",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
     }
 
     fn examples(&self) -> &'static [Example] {
@@ -24,6 +31,11 @@ impl Function for Flatten {
                 source: r#"flatten({ "foo": { "bar": true }})"#,
                 result: Ok(r#"{ "foo.bar": true }"#),
             },
+            Example {
+                title: "object with separator",
+                source: r#"flatten({ "foo": { "bar": true }}, "_")"#,
+                result: Ok(r#"{ "foo_bar": true }"#),
+            },
             Example {
                 title: "array",
                 source: r#"flatten([[true]])"#,
@@ -39,23 +51,30 @@ impl Function for Flatten {
         mut arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
-        Ok(Box::new(FlattenFn { value }))
+        let separator = arguments.optional("separator");
+        Ok(Box::new(FlattenFn { value, separator }))
     }
 }
 
 #[derive(Debug, Clone)]
 struct FlattenFn {
     value: Box<dyn Expression>,
+    separator: Option<Box<dyn Expression>>,
 }
 
 impl Expression for FlattenFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let separator = match &self.separator {
+            Some(separator) => separator.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned(),
+            None => ".".to_owned(),
+        };
+
         match self.value.resolve(ctx)? {
             Value::Array(arr) => Ok(Value::Array(
                 ArrayFlatten::new(arr.iter()).cloned().collect(),
             )),
             Value::Object(map) => Ok(Value::Object(
-                MapFlatten::new(map.iter())
+                MapFlatten::new(map.iter(), &separator)
                     .map(|(k, v)| (k, v.clone()))
                     .collect(),
             )),
@@ -83,30 +102,37 @@ struct MapFlatten<'a> {
     values: btree_map::Iter<'a, String, Value>,
     inner: Option<Box<MapFlatten<'a>>>,
     parent: Option<String>,
+    separator: &'a str,
 }
 
 impl<'a> MapFlatten<'a> {
-    fn new(values: btree_map::Iter<'a, String, Value>) -> Self {
+    fn new(values: btree_map::Iter<'a, String, Value>, separator: &'a str) -> Self {
         Self {
             values,
             inner: None,
             parent: None,
+            separator,
         }
     }
 
-    fn new_from_parent(parent: String, values: btree_map::Iter<'a, String, Value>) -> Self {
+    fn new_from_parent(
+        parent: String,
+        values: btree_map::Iter<'a, String, Value>,
+        separator: &'a str,
+    ) -> Self {
         Self {
             values,
             inner: None,
             parent: Some(parent),
+            separator,
         }
     }
 
-    /// Returns the key with the parent prepended.
+    /// Returns the key with the parent and separator prepended.
     fn new_key(&self, key: &str) -> String {
         match self.parent {
             None => key.to_string(),
-            Some(ref parent) => format!("{}.{}", parent, key),
+            Some(ref parent) => format!("{}{}{}", parent, self.separator, key),
         }
     }
 }
@@ -129,6 +155,7 @@ impl<'a> std::iter::Iterator for MapFlatten<'a> {
                 self.inner = Some(Box::new(MapFlatten::new_from_parent(
                     self.new_key(key),
                     value.iter(),
+                    self.separator,
                 )));
                 self.next()
             }
@@ -283,6 +310,12 @@ mod test {
             tdef: TypeDef::new().array_mapped::<(), Kind>(map! { (): Kind::all() }),
         }
 
+        nested_map_with_separator {
+            args: func_args![value: value!({parent: {child1: 1, child2: {grandchild: 2}}, key: "val"}), separator: "_"],
+            want: Ok(value!({"parent_child1": 1, "parent_child2_grandchild": 2, key: "val"})),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+
         triple_nested_map {
             args: func_args![value: value!({
                 parent1: {
@@ -0,0 +1,241 @@
+use crate::util::round_to_precision;
+use std::{collections::BTreeMap, str::FromStr};
+use vrl::prelude::*;
+
+/// The mean radius of the Earth, in kilometers.
+const EARTH_RADIUS_KILOMETERS: f64 = 6_371.008_8;
+/// The mean radius of the Earth, in miles.
+const EARTH_RADIUS_MILES: f64 = 3_958.761_3;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Haversine;
+
+impl Function for Haversine {
+    fn identifier(&self) -> &'static str {
+        "haversine"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "latitude1",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "longitude1",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "latitude2",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "longitude2",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "measurement_unit",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "kilometers",
+                source: r#"haversine(0.0, 0.0, 10.0, 10.0)"#,
+                result: Ok(r#"{ "bearing": 44.561, "distance": 1568.5227233 }"#),
+            },
+            Example {
+                title: "miles",
+                source: r#"haversine(0.0, 0.0, 10.0, 10.0, measurement_unit: "miles")"#,
+                result: Ok(r#"{ "bearing": 44.561, "distance": 974.6348263 }"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let latitude1 = arguments.required("latitude1");
+        let longitude1 = arguments.required("longitude1");
+        let latitude2 = arguments.required("latitude2");
+        let longitude2 = arguments.required("longitude2");
+        let unit = arguments
+            .optional_enum("measurement_unit", Unit::all_value().as_slice())?
+            .map(|s| {
+                Unit::from_str(&s.try_bytes_utf8_lossy().expect("unit not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        Ok(Box::new(HaversineFn {
+            latitude1,
+            longitude1,
+            latitude2,
+            longitude2,
+            unit,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Kilometers,
+    Miles,
+}
+
+impl Unit {
+    fn all_value() -> Vec<Value> {
+        use Unit::*;
+
+        vec![Kilometers, Miles]
+            .into_iter()
+            .map(|u| u.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use Unit::*;
+
+        match self {
+            Kilometers => "kilometers",
+            Miles => "miles",
+        }
+    }
+
+    const fn earth_radius(self) -> f64 {
+        match self {
+            Unit::Kilometers => EARTH_RADIUS_KILOMETERS,
+            Unit::Miles => EARTH_RADIUS_MILES,
+        }
+    }
+}
+
+impl Default for Unit {
+    fn default() -> Self {
+        Unit::Kilometers
+    }
+}
+
+impl FromStr for Unit {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Unit::*;
+
+        match s {
+            "kilometers" => Ok(Kilometers),
+            "miles" => Ok(Miles),
+            _ => Err("unit not recognized"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HaversineFn {
+    latitude1: Box<dyn Expression>,
+    longitude1: Box<dyn Expression>,
+    latitude2: Box<dyn Expression>,
+    longitude2: Box<dyn Expression>,
+    unit: Unit,
+}
+
+impl Expression for HaversineFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let latitude1 = f64::try_from(&self.latitude1.resolve(ctx)?)?;
+        let longitude1 = f64::try_from(&self.longitude1.resolve(ctx)?)?;
+        let latitude2 = f64::try_from(&self.latitude2.resolve(ctx)?)?;
+        let longitude2 = f64::try_from(&self.longitude2.resolve(ctx)?)?;
+
+        let (distance, bearing) = haversine(
+            (latitude1, longitude1),
+            (latitude2, longitude2),
+            self.unit.earth_radius(),
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert(
+            "distance".to_owned(),
+            round_to_precision(distance, 7, f64::round).into(),
+        );
+        map.insert(
+            "bearing".to_owned(),
+            round_to_precision(bearing, 3, f64::round).into(),
+        );
+        Ok(Value::Object(map))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().object(type_def())
+    }
+}
+
+/// The great-circle distance between two points, given as latitude and
+/// longitude in degrees, on a sphere of the given radius, along with the
+/// initial bearing from the first point to the second in degrees from north.
+fn haversine(from: (f64, f64), to: (f64, f64), radius: f64) -> (f64, f64) {
+    let (latitude1, latitude2) = (from.0.to_radians(), to.0.to_radians());
+    let delta_latitude = latitude2 - latitude1;
+    let delta_longitude = (to.1 - from.1).to_radians();
+
+    let a = (delta_latitude / 2.0).sin().powi(2)
+        + latitude1.cos() * latitude2.cos() * (delta_longitude / 2.0).sin().powi(2);
+    let distance = 2.0 * radius * a.sqrt().asin();
+
+    let y = delta_longitude.sin() * latitude2.cos();
+    let x = latitude1.cos() * latitude2.sin()
+        - latitude1.sin() * latitude2.cos() * delta_longitude.cos();
+    let bearing = (y.atan2(x).to_degrees() + 360.0) % 360.0;
+
+    (distance, bearing)
+}
+
+fn type_def() -> BTreeMap<&'static str, TypeDef> {
+    map! {
+        "distance": Kind::Float,
+        "bearing": Kind::Float,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        haversine => Haversine;
+
+        kilometers {
+            args: func_args![latitude1: 0.0, longitude1: 0.0, latitude2: 10.0, longitude2: 10.0],
+            want: Ok(value!({ "distance": 1568.5227233, "bearing": 44.561 })),
+            tdef: TypeDef::new().infallible().object(type_def()),
+        }
+
+        miles {
+            args: func_args![latitude1: 0.0, longitude1: 0.0, latitude2: 10.0, longitude2: 10.0, measurement_unit: "miles"],
+            want: Ok(value!({ "distance": 974.6348263, "bearing": 44.561 })),
+            tdef: TypeDef::new().infallible().object(type_def()),
+        }
+
+        between_cities {
+            args: func_args![latitude1: 52.52, longitude1: 13.405, latitude2: 48.8566, longitude2: 2.3522],
+            want: Ok(value!({ "distance": 877.4645379, "bearing": 246.743 })),
+            tdef: TypeDef::new().infallible().object(type_def()),
+        }
+
+        same_point {
+            args: func_args![latitude1: 45, longitude1: 90, latitude2: 45, longitude2: 90],
+            want: Ok(value!({ "distance": 0.0, "bearing": 0.0 })),
+            tdef: TypeDef::new().infallible().object(type_def()),
+        }
+    ];
+}
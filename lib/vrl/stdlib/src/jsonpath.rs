@@ -0,0 +1,798 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Jsonpath;
+
+impl Function for Jsonpath {
+    fn identifier(&self) -> &'static str {
+        "jsonpath"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "path",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "filter",
+                source: r#"jsonpath({ "events": [{ "type": "error", "id": 1 }, { "type": "info", "id": 2 }] }, "$.events[?(@.type == 'error')].id")"#,
+                result: Ok("[1]"),
+            },
+            Example {
+                title: "wildcard",
+                source: r#"jsonpath({ "a": { "id": 1 }, "b": { "id": 2 } }, "$.*.id")"#,
+                result: Ok("[1, 2]"),
+            },
+            Example {
+                title: "recursive descent",
+                source: r#"jsonpath({ "id": 1, "children": [{ "id": 2 }, { "id": 3 }] }, "$..id")"#,
+                result: Ok("[1, 2, 3]"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let path = arguments
+            .required_literal("path")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("jsonpath not bytes")
+            .into_owned();
+
+        let path = Parser::new(&path)
+            .parse()
+            .map_err(|e| Box::new(ExpressionError::from(e)) as Box<dyn DiagnosticError>)?;
+
+        Ok(Box::new(JsonpathFn { value, path }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct JsonpathFn {
+    value: Box<dyn Expression>,
+    path: Vec<Segment>,
+}
+
+impl Expression for JsonpathFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        Ok(select(&self.path, &value, vec![&value])
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new()
+            .infallible()
+            .array_mapped::<(), Kind>(map! { (): Kind::all() })
+    }
+}
+
+/// A step of a path, which selects from the children of each node, or from
+/// the node and all of its descendants.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Exists(Query),
+    Compare(Operand, Comparison, Operand),
+}
+
+/// A path within a filter, from either the node being filtered (`@`) or the
+/// root (`$`).
+#[derive(Debug, Clone, PartialEq)]
+struct Query {
+    absolute: bool,
+    path: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Value),
+    Query(Query),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+fn select<'a>(path: &[Segment], root: &'a Value, nodes: Vec<&'a Value>) -> Vec<&'a Value> {
+    path.iter().fold(nodes, |nodes, segment| {
+        let mut selected = Vec::new();
+        for node in nodes {
+            match segment {
+                Segment::Child(selectors) => {
+                    apply_selectors(selectors, root, node, &mut selected);
+                }
+                Segment::Descendant(selectors) => {
+                    let mut descendants = Vec::new();
+                    collect_descendants(node, &mut descendants);
+                    for descendant in descendants {
+                        apply_selectors(selectors, root, descendant, &mut selected);
+                    }
+                }
+            }
+        }
+        selected
+    })
+}
+
+/// Collects the node and all of its descendants, in document order.
+fn collect_descendants<'a>(node: &'a Value, descendants: &mut Vec<&'a Value>) {
+    descendants.push(node);
+    for child in children(node) {
+        collect_descendants(child, descendants);
+    }
+}
+
+fn children(node: &Value) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match node {
+        Value::Object(map) => Box::new(map.values()),
+        Value::Array(array) => Box::new(array.iter()),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+fn apply_selectors<'a>(
+    selectors: &[Selector],
+    root: &'a Value,
+    node: &'a Value,
+    selected: &mut Vec<&'a Value>,
+) {
+    for selector in selectors {
+        match (selector, node) {
+            (Selector::Name(name), Value::Object(map)) => selected.extend(map.get(name)),
+            (Selector::Wildcard, _) => selected.extend(children(node)),
+            (Selector::Index(index), Value::Array(array)) => {
+                let index = if *index < 0 {
+                    array.len() as i64 + index
+                } else {
+                    *index
+                };
+                if index >= 0 {
+                    selected.extend(array.get(index as usize));
+                }
+            }
+            (Selector::Slice { start, end, step }, Value::Array(array)) => {
+                selected.extend(slice(array.len() as i64, *start, *end, *step).map(|i| &array[i]));
+            }
+            (Selector::Filter(filter), _) => {
+                selected.extend(children(node).filter(|child| filter.matches(root, child)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The indices of the elements in a slice of an array of the given length,
+/// with negative bounds counting from its end.
+fn slice(
+    len: i64,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+) -> impl Iterator<Item = usize> {
+    let normalize = move |i: i64| if i < 0 { len + i } else { i };
+    let step = step.unwrap_or(1);
+
+    let (mut i, lower, upper) = if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        (lower, lower, upper)
+    } else {
+        let upper = start.map_or(len - 1, normalize).clamp(-1, len - 1);
+        let lower = end.map_or(-1, normalize).clamp(-1, len - 1);
+        (upper, lower, upper)
+    };
+
+    std::iter::from_fn(move || {
+        if step == 0 || (step > 0 && i >= upper) || (step < 0 && i <= lower) {
+            return None;
+        }
+        let index = i as usize;
+        i += step;
+        Some(index)
+    })
+}
+
+impl Filter {
+    fn matches(&self, root: &Value, node: &Value) -> bool {
+        match self {
+            Filter::Or(left, right) => left.matches(root, node) || right.matches(root, node),
+            Filter::And(left, right) => left.matches(root, node) && right.matches(root, node),
+            Filter::Not(filter) => !filter.matches(root, node),
+            Filter::Exists(query) => !query.select(root, node).is_empty(),
+            Filter::Compare(left, comparison, right) => {
+                let left = left.resolve(root, node);
+                let right = right.resolve(root, node);
+                match comparison {
+                    Comparison::Eq => equal(left, right),
+                    Comparison::Ne => !equal(left, right),
+                    Comparison::Lt => less(left, right),
+                    Comparison::Le => less(left, right) || equal(left, right),
+                    Comparison::Gt => less(right, left),
+                    Comparison::Ge => less(right, left) || equal(left, right),
+                }
+            }
+        }
+    }
+}
+
+impl Query {
+    fn select<'a>(&self, root: &'a Value, node: &'a Value) -> Vec<&'a Value> {
+        let start = if self.absolute { root } else { node };
+        select(&self.path, root, vec![start])
+    }
+}
+
+impl Operand {
+    /// The value of the operand, or nothing if it is a query that doesn't
+    /// select exactly one node.
+    fn resolve<'a>(&'a self, root: &'a Value, node: &'a Value) -> Option<&'a Value> {
+        match self {
+            Operand::Literal(value) => Some(value),
+            Operand::Query(query) => match query.select(root, node).as_slice() {
+                [value] => Some(*value),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn equal(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (None, None) => true,
+        (Some(left), Some(right)) => match (number(left), number(right)) {
+            (Some(left), Some(right)) => left == right,
+            _ => left == right,
+        },
+        _ => false,
+    }
+}
+
+fn less(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (Some(Value::Bytes(left)), Some(Value::Bytes(right))) => left < right,
+        (Some(Value::Timestamp(left)), Some(Value::Timestamp(right))) => left < right,
+        (Some(left), Some(right)) => match (number(left), number(right)) {
+            (Some(left), Some(right)) => left < right,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(value) => Some(*value as f64),
+        Value::Float(value) => Some(value.into_inner()),
+        _ => None,
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+impl Parser {
+    fn new(path: &str) -> Self {
+        Self {
+            chars: path.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn parse(mut self) -> ParseResult<Vec<Segment>> {
+        self.skip_whitespace();
+        self.expect('$')?;
+        let path = self.segments()?;
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(path),
+            Some(c) => Err(self.unexpected(c)),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_str(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let matched = self.peek_str(s);
+        if matched {
+            self.pos += s.chars().count();
+        }
+        matched
+    }
+
+    fn expect(&mut self, c: char) -> ParseResult<()> {
+        match self.peek() {
+            Some(next) if next == c => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(next) => Err(self.unexpected(next)),
+            None => Err(format!("expected {:?} at end of path", c)),
+        }
+    }
+
+    fn unexpected(&self, c: char) -> String {
+        format!("unexpected {:?} at position {} of path", c, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn segments(&mut self) -> ParseResult<Vec<Segment>> {
+        let mut segments = Vec::new();
+        loop {
+            if self.eat("..") {
+                segments.push(Segment::Descendant(self.dotted_selectors(true)?));
+            } else if self.eat(".") {
+                segments.push(Segment::Child(self.dotted_selectors(false)?));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracketed_selectors()?));
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    /// The selectors following a `.` or `..`, either a wildcard, a member name
+    /// or, after `..` only, bracketed selectors.
+    fn dotted_selectors(&mut self, descendant: bool) -> ParseResult<Vec<Selector>> {
+        if self.eat("*") {
+            return Ok(vec![Selector::Wildcard]);
+        }
+        if descendant && self.peek() == Some('[') {
+            return self.bracketed_selectors();
+        }
+
+        let start = self.pos;
+        while self
+            .peek()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return match self.peek() {
+                Some(c) => Err(self.unexpected(c)),
+                None => Err("expected a member name at end of path".to_owned()),
+            };
+        }
+
+        Ok(vec![Selector::Name(
+            self.chars[start..self.pos].iter().collect(),
+        )])
+    }
+
+    fn bracketed_selectors(&mut self) -> ParseResult<Vec<Selector>> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(']')?;
+        Ok(selectors)
+    }
+
+    fn selector(&mut self) -> ParseResult<Selector> {
+        match self.peek() {
+            Some('\'') | Some('"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                Ok(Selector::Filter(self.or()?))
+            }
+            Some(c) if c == '-' || c == ':' || c.is_ascii_digit() => {
+                let start = self.optional_integer()?;
+                self.skip_whitespace();
+                if !self.eat(":") {
+                    return start.map(Selector::Index).ok_or_else(|| self.unexpected(c));
+                }
+                self.skip_whitespace();
+                let end = self.optional_integer()?;
+                self.skip_whitespace();
+                let step = if self.eat(":") {
+                    self.skip_whitespace();
+                    self.optional_integer()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice { start, end, step })
+            }
+            Some(c) => Err(self.unexpected(c)),
+            None => Err("expected a selector at end of path".to_owned()),
+        }
+    }
+
+    fn optional_integer(&mut self) -> ParseResult<Option<i64>> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self.peek().map_or(false, |c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+
+        let integer = self.chars[start..self.pos].iter().collect::<String>();
+        integer.parse().map(Some).map_err(|_| {
+            format!(
+                "invalid integer {:?} at position {} of path",
+                integer, start
+            )
+        })
+    }
+
+    fn or(&mut self) -> ParseResult<Filter> {
+        let mut filter = self.and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat("||") {
+                return Ok(filter);
+            }
+            self.skip_whitespace();
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> ParseResult<Filter> {
+        let mut filter = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat("&&") {
+                return Ok(filter);
+            }
+            self.skip_whitespace();
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> ParseResult<Filter> {
+        if self.peek() == Some('!') && !self.peek_str("!=") {
+            self.pos += 1;
+            self.skip_whitespace();
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            self.skip_whitespace();
+            let filter = self.or()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            return Ok(filter);
+        }
+
+        let start = self.pos;
+        let left = self.operand()?;
+        self.skip_whitespace();
+        match self.comparison() {
+            Some(comparison) => {
+                self.skip_whitespace();
+                let right = self.operand()?;
+                Ok(Filter::Compare(left, comparison, right))
+            }
+            None => match left {
+                Operand::Query(query) => Ok(Filter::Exists(query)),
+                Operand::Literal(_) => Err(format!(
+                    "expected a comparison after the literal at position {} of path",
+                    start
+                )),
+            },
+        }
+    }
+
+    fn comparison(&mut self) -> Option<Comparison> {
+        [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+        .iter()
+        .find(|(operator, _)| self.eat(operator))
+        .map(|(_, comparison)| *comparison)
+    }
+
+    fn operand(&mut self) -> ParseResult<Operand> {
+        match self.peek() {
+            Some('@') | Some('$') => {
+                let absolute = self.peek() == Some('$');
+                self.pos += 1;
+                Ok(Operand::Query(Query {
+                    absolute,
+                    path: self.segments()?,
+                }))
+            }
+            Some('\'') | Some('"') => Ok(Operand::Literal(self.string()?.into())),
+            Some(c) if c == '-' || c.is_ascii_digit() => Ok(Operand::Literal(self.number()?)),
+            _ if self.eat("true") => Ok(Operand::Literal(true.into())),
+            _ if self.eat("false") => Ok(Operand::Literal(false.into())),
+            _ if self.eat("null") => Ok(Operand::Literal(Value::Null)),
+            Some(c) => Err(self.unexpected(c)),
+            None => Err("expected a filter operand at end of path".to_owned()),
+        }
+    }
+
+    fn number(&mut self) -> ParseResult<Value> {
+        let start = self.pos;
+        while self
+            .peek()
+            .map_or(false, |c| c.is_ascii_digit() || "-+.eE".contains(c))
+        {
+            self.pos += 1;
+        }
+
+        let number = self.chars[start..self.pos].iter().collect::<String>();
+        if let Ok(integer) = number.parse::<i64>() {
+            return Ok(integer.into());
+        }
+        number
+            .parse::<f64>()
+            .ok()
+            .filter(|float| float.is_finite())
+            .map(Value::from)
+            .ok_or_else(|| format!("invalid number {:?} at position {} of path", number, start))
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let quote = self.peek().expect("string starts with a quote");
+        self.pos += 1;
+
+        let mut string = String::new();
+        loop {
+            match self.peek() {
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    string.push(self.escaped()?);
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    string.push(c);
+                }
+                None => return Err("unterminated string in path".to_owned()),
+            }
+        }
+    }
+
+    fn escaped(&mut self) -> ParseResult<char> {
+        let c = self
+            .peek()
+            .ok_or_else(|| "unterminated string in path".to_owned())?;
+        self.pos += 1;
+
+        Ok(match c {
+            'b' => '\u{08}',
+            'f' => '\u{0c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let start = self.pos;
+                let hex = self
+                    .chars
+                    .get(start..start + 4)
+                    .map(|hex| hex.iter().collect::<String>())
+                    .ok_or_else(|| "unterminated string in path".to_owned())?;
+                self.pos += 4;
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        format!("invalid escape \\u{} at position {} of path", hex, start)
+                    })?
+            }
+            '\\' | '/' | '\'' | '"' => c,
+            c => return Err(self.unexpected(c)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> Value {
+        value!({
+            "store": {
+                "name": "vendor",
+                "events": [
+                    { "type": "error", "code": 500, "tags": ["a"] },
+                    { "type": "info", "code": 200 },
+                    { "type": "error", "code": 404, "tags": ["b", "c"] },
+                    { "type": "warning", "code": 302.5 },
+                ],
+            },
+            "threshold": 400,
+        })
+    }
+
+    fn tdef() -> TypeDef {
+        TypeDef::new()
+            .infallible()
+            .array_mapped::<(), Kind>(map! { (): Kind::all() })
+    }
+
+    test_function![
+        jsonpath => Jsonpath;
+
+        root {
+            args: func_args![value: value!({ "a": 1 }), path: "$"],
+            want: Ok(value!([{ "a": 1 }])),
+            tdef: tdef(),
+        }
+
+        member {
+            args: func_args![value: payload(), path: "$.store.name"],
+            want: Ok(value!(["vendor"])),
+            tdef: tdef(),
+        }
+
+        quoted_member {
+            args: func_args![value: value!({ "event-type": { "a b": 1 } }), path: r#"$['event-type']["a b"]"#],
+            want: Ok(value!([1])),
+            tdef: tdef(),
+        }
+
+        missing_member {
+            args: func_args![value: payload(), path: "$.store.missing"],
+            want: Ok(value!([])),
+            tdef: tdef(),
+        }
+
+        wildcard {
+            args: func_args![value: payload(), path: "$.store.events[*].code"],
+            want: Ok(value!([500, 200, 404, 302.5])),
+            tdef: tdef(),
+        }
+
+        indices {
+            args: func_args![value: payload(), path: "$.store.events[0, -1].type"],
+            want: Ok(value!(["error", "warning"])),
+            tdef: tdef(),
+        }
+
+        slice {
+            args: func_args![value: value!([0, 1, 2, 3, 4, 5]), path: "$[1:5:2]"],
+            want: Ok(value!([1, 3])),
+            tdef: tdef(),
+        }
+
+        reversed_slice {
+            args: func_args![value: value!([0, 1, 2, 3]), path: "$[::-1]"],
+            want: Ok(value!([3, 2, 1, 0])),
+            tdef: tdef(),
+        }
+
+        recursive_descent {
+            args: func_args![value: payload(), path: "$..tags[*]"],
+            want: Ok(value!(["a", "b", "c"])),
+            tdef: tdef(),
+        }
+
+        filter_equal {
+            args: func_args![value: payload(), path: "$.store.events[?(@.type == 'error')].code"],
+            want: Ok(value!([500, 404])),
+            tdef: tdef(),
+        }
+
+        filter_logical {
+            args: func_args![value: payload(), path: "$.store.events[?(@.code >= 300 && !(@.type == 'error') || @.code == 200)].type"],
+            want: Ok(value!(["info", "warning"])),
+            tdef: tdef(),
+        }
+
+        filter_root {
+            args: func_args![value: payload(), path: "$.store.events[?@.code > $.threshold].code"],
+            want: Ok(value!([500, 404])),
+            tdef: tdef(),
+        }
+
+        filter_exists {
+            args: func_args![value: payload(), path: "$.store.events[?(@.tags)].tags[0]"],
+            want: Ok(value!(["a", "b"])),
+            tdef: tdef(),
+        }
+
+        descendant_filter {
+            args: func_args![value: payload(), path: "$..[?(@.code < 300)].type"],
+            want: Ok(value!(["info"])),
+            tdef: tdef(),
+        }
+
+        missing_root {
+            args: func_args![value: value!({ "a": 1 }), path: ".a"],
+            want: Err("unexpected '.' at position 0 of path"),
+            tdef: tdef(),
+        }
+
+        unclosed_bracket {
+            args: func_args![value: value!({ "a": 1 }), path: "$['a'"],
+            want: Err("expected ']' at end of path"),
+            tdef: tdef(),
+        }
+
+        trailing_literal {
+            args: func_args![value: value!([1]), path: "$[?(1)]"],
+            want: Err("expected a comparison after the literal at position 4 of path"),
+            tdef: tdef(),
+        }
+    ];
+}
@@ -10,22 +10,38 @@ mod assert;
 mod assert_eq;
 #[cfg(feature = "boolean")]
 mod boolean;
+#[cfg(feature = "cache_get")]
+mod cache_get;
+#[cfg(feature = "cache_set")]
+mod cache_set;
 #[cfg(feature = "ceil")]
 mod ceil;
+#[cfg(feature = "community_id")]
+mod community_id;
 #[cfg(feature = "compact")]
 mod compact;
 #[cfg(feature = "contains")]
 mod contains;
 #[cfg(feature = "decode_base64")]
 mod decode_base64;
+#[cfg(feature = "decode_geohash")]
+mod decode_geohash;
 #[cfg(feature = "decode_percent")]
 mod decode_percent;
+#[cfg(feature = "decode_protobuf")]
+mod decode_protobuf;
 #[cfg(feature = "del")]
 mod del;
+#[cfg(feature = "derive_severity")]
+mod derive_severity;
 #[cfg(feature = "downcase")]
 mod downcase;
+#[cfg(feature = "emit_metric")]
+mod emit_metric;
 #[cfg(feature = "encode_base64")]
 mod encode_base64;
+#[cfg(feature = "encode_geohash")]
+mod encode_geohash;
 #[cfg(feature = "encode_json")]
 mod encode_json;
 #[cfg(feature = "encode_key_value")]
@@ -34,6 +50,8 @@ mod encode_key_value;
 mod encode_logfmt;
 #[cfg(feature = "encode_percent")]
 mod encode_percent;
+#[cfg(feature = "encode_protobuf")]
+mod encode_protobuf;
 #[cfg(feature = "ends_with")]
 mod ends_with;
 #[cfg(feature = "exists")]
@@ -58,6 +76,8 @@ mod get;
 mod get_env_var;
 #[cfg(feature = "get_hostname")]
 mod get_hostname;
+#[cfg(feature = "haversine")]
+mod haversine;
 #[cfg(feature = "includes")]
 mod includes;
 #[cfg(feature = "integer")]
@@ -96,6 +116,8 @@ mod is_string;
 mod is_timestamp;
 #[cfg(feature = "join")]
 mod join;
+#[cfg(feature = "jsonpath")]
+mod jsonpath;
 #[cfg(feature = "length")]
 mod length;
 #[cfg(feature = "log")]
@@ -176,6 +198,10 @@ mod parse_url;
 mod parse_user_agent;
 #[cfg(feature = "parse_xml")]
 mod parse_xml;
+#[cfg(feature = "point_in_polygon")]
+mod point_in_polygon;
+#[cfg(any(feature = "decode_protobuf", feature = "encode_protobuf"))]
+mod protobuf_util;
 #[cfg(feature = "push")]
 mod push;
 #[cfg(feature = "redact")]
@@ -236,6 +262,8 @@ mod to_timestamp;
 mod to_unix_timestamp;
 #[cfg(feature = "truncate")]
 mod truncate;
+#[cfg(feature = "unflatten")]
+mod unflatten;
 #[cfg(feature = "unique")]
 mod unique;
 #[cfg(feature = "unnest")]
@@ -261,22 +289,38 @@ pub use assert::Assert;
 pub use assert_eq::AssertEq;
 #[cfg(feature = "boolean")]
 pub use boolean::Boolean;
+#[cfg(feature = "cache_get")]
+pub use cache_get::CacheGet;
+#[cfg(feature = "cache_set")]
+pub use cache_set::CacheSet;
 #[cfg(feature = "ceil")]
 pub use ceil::Ceil;
+#[cfg(feature = "community_id")]
+pub use community_id::CommunityId;
 #[cfg(feature = "compact")]
 pub use compact::Compact;
 #[cfg(feature = "contains")]
 pub use contains::Contains;
 #[cfg(feature = "decode_base64")]
 pub use decode_base64::DecodeBase64;
+#[cfg(feature = "decode_geohash")]
+pub use decode_geohash::DecodeGeohash;
 #[cfg(feature = "decode_percent")]
 pub use decode_percent::DecodePercent;
+#[cfg(feature = "decode_protobuf")]
+pub use decode_protobuf::DecodeProtobuf;
 #[cfg(feature = "del")]
 pub use del::Del;
+#[cfg(feature = "derive_severity")]
+pub use derive_severity::DeriveSeverity;
 #[cfg(feature = "downcase")]
 pub use downcase::Downcase;
+#[cfg(feature = "emit_metric")]
+pub use emit_metric::EmitMetric;
 #[cfg(feature = "encode_base64")]
 pub use encode_base64::EncodeBase64;
+#[cfg(feature = "encode_geohash")]
+pub use encode_geohash::EncodeGeohash;
 #[cfg(feature = "encode_json")]
 pub use encode_json::EncodeJson;
 #[cfg(feature = "encode_key_value")]
@@ -285,6 +329,8 @@ pub use encode_key_value::EncodeKeyValue;
 pub use encode_logfmt::EncodeLogfmt;
 #[cfg(feature = "encode_percent")]
 pub use encode_percent::EncodePercent;
+#[cfg(feature = "encode_protobuf")]
+pub use encode_protobuf::EncodeProtobuf;
 #[cfg(feature = "ends_with")]
 pub use ends_with::EndsWith;
 #[cfg(feature = "exists")]
@@ -309,6 +355,8 @@ pub use get::Get;
 pub use get_env_var::GetEnvVar;
 #[cfg(feature = "get_hostname")]
 pub use get_hostname::GetHostname;
+#[cfg(feature = "haversine")]
+pub use haversine::Haversine;
 #[cfg(feature = "includes")]
 pub use includes::Includes;
 #[cfg(feature = "integer")]
@@ -347,6 +395,8 @@ pub use is_string::IsString;
 pub use is_timestamp::IsTimestamp;
 #[cfg(feature = "join")]
 pub use join::Join;
+#[cfg(feature = "jsonpath")]
+pub use jsonpath::Jsonpath;
 #[cfg(feature = "length")]
 pub use length::Length;
 #[cfg(feature = "log")]
@@ -417,6 +467,10 @@ pub use parse_url::ParseUrl;
 pub use parse_user_agent::ParseUserAgent;
 #[cfg(feature = "parse_xml")]
 pub use parse_xml::ParseXml;
+#[cfg(feature = "point_in_polygon")]
+pub use point_in_polygon::PointInPolygon;
+#[cfg(any(feature = "decode_protobuf", feature = "encode_protobuf"))]
+pub use protobuf_util::MessageRef as ProtobufMessage;
 #[cfg(feature = "push")]
 pub use push::Push;
 #[cfg(feature = "match")]
@@ -477,6 +531,8 @@ pub use to_timestamp::ToTimestamp;
 pub use to_unix_timestamp::ToUnixTimestamp;
 #[cfg(feature = "truncate")]
 pub use truncate::Truncate;
+#[cfg(feature = "unflatten")]
+pub use unflatten::Unflatten;
 #[cfg(feature = "unique")]
 pub use unique::Unique;
 #[cfg(feature = "unnest")]
@@ -498,22 +554,38 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(AssertEq),
         #[cfg(feature = "boolean")]
         Box::new(Boolean),
+        #[cfg(feature = "cache_get")]
+        Box::new(CacheGet),
+        #[cfg(feature = "cache_set")]
+        Box::new(CacheSet),
         #[cfg(feature = "ceil")]
         Box::new(Ceil),
+        #[cfg(feature = "community_id")]
+        Box::new(CommunityId),
         #[cfg(feature = "compact")]
         Box::new(Compact),
         #[cfg(feature = "contains")]
         Box::new(Contains),
         #[cfg(feature = "decode_base64")]
         Box::new(DecodeBase64),
+        #[cfg(feature = "decode_geohash")]
+        Box::new(DecodeGeohash),
         #[cfg(feature = "decode_percent")]
         Box::new(DecodePercent),
+        #[cfg(feature = "decode_protobuf")]
+        Box::new(DecodeProtobuf),
         #[cfg(feature = "del")]
         Box::new(Del),
+        #[cfg(feature = "derive_severity")]
+        Box::new(DeriveSeverity),
         #[cfg(feature = "downcase")]
         Box::new(Downcase),
+        #[cfg(feature = "emit_metric")]
+        Box::new(EmitMetric),
         #[cfg(feature = "encode_base64")]
         Box::new(EncodeBase64),
+        #[cfg(feature = "encode_geohash")]
+        Box::new(EncodeGeohash),
         #[cfg(feature = "encode_json")]
         Box::new(EncodeJson),
         #[cfg(feature = "encode_key_value")]
@@ -522,6 +594,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(EncodeLogfmt),
         #[cfg(feature = "encode_percent")]
         Box::new(EncodePercent),
+        #[cfg(feature = "encode_protobuf")]
+        Box::new(EncodeProtobuf),
         #[cfg(feature = "ends_with")]
         Box::new(EndsWith),
         #[cfg(feature = "exists")]
@@ -546,6 +620,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(GetEnvVar),
         #[cfg(feature = "get_hostname")]
         Box::new(GetHostname),
+        #[cfg(feature = "haversine")]
+        Box::new(Haversine),
         #[cfg(feature = "includes")]
         Box::new(Includes),
         #[cfg(feature = "integer")]
@@ -584,6 +660,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IsTimestamp),
         #[cfg(feature = "join")]
         Box::new(Join),
+        #[cfg(feature = "jsonpath")]
+        Box::new(Jsonpath),
         #[cfg(feature = "length")]
         Box::new(Length),
         #[cfg(feature = "log")]
@@ -660,6 +738,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseUserAgent),
         #[cfg(feature = "parse_xml")]
         Box::new(ParseXml),
+        #[cfg(feature = "point_in_polygon")]
+        Box::new(PointInPolygon),
         #[cfg(feature = "push")]
         Box::new(Push),
         #[cfg(feature = "redact")]
@@ -720,6 +800,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ToUnixTimestamp),
         #[cfg(feature = "truncate")]
         Box::new(Truncate),
+        #[cfg(feature = "unflatten")]
+        Box::new(Unflatten),
         #[cfg(feature = "unique")]
         Box::new(Unique),
         #[cfg(feature = "unnest")]
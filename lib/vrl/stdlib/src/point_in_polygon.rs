@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PointInPolygon;
+
+impl Function for PointInPolygon {
+    fn identifier(&self) -> &'static str {
+        "point_in_polygon"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "latitude",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "longitude",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+            Parameter {
+                keyword: "polygon",
+                kind: kind::OBJECT | kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "inside",
+                source: r#"point_in_polygon(5, 5, { "type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]] })"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "outside, as a GeoJSON string",
+                source: r#"point_in_polygon(15, 5, s'{ "type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]] }')"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let latitude = arguments.required("latitude");
+        let longitude = arguments.required("longitude");
+        let polygon = arguments.required("polygon");
+
+        Ok(Box::new(PointInPolygonFn {
+            latitude,
+            longitude,
+            polygon,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PointInPolygonFn {
+    latitude: Box<dyn Expression>,
+    longitude: Box<dyn Expression>,
+    polygon: Box<dyn Expression>,
+}
+
+impl Expression for PointInPolygonFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let latitude = f64::try_from(&self.latitude.resolve(ctx)?)?;
+        let longitude = f64::try_from(&self.longitude.resolve(ctx)?)?;
+        let geometry = match self.polygon.resolve(ctx)? {
+            Value::Object(geometry) => geometry,
+            value => {
+                let bytes = value.try_bytes()?;
+                serde_json::from_slice::<'_, Value>(&bytes)
+                    .map_err(|e| format!("unable to parse GeoJSON: {}", e))?
+                    .try_object()?
+            }
+        };
+
+        Ok(contains(&geometry, (longitude, latitude))?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().boolean()
+    }
+}
+
+/// A position, as a longitude and latitude, the order of GeoJSON coordinates.
+type Position = (f64, f64);
+
+/// Whether the point is in any of the polygons of a GeoJSON `Polygon`,
+/// `MultiPolygon`, `Feature` or `FeatureCollection`.
+fn contains(
+    geometry: &BTreeMap<String, Value>,
+    point: Position,
+) -> std::result::Result<bool, String> {
+    let kind = match geometry.get("type") {
+        Some(Value::Bytes(kind)) => String::from_utf8_lossy(kind).into_owned(),
+        _ => return Err(r#"GeoJSON "type" must be a string"#.to_owned()),
+    };
+
+    match kind.as_str() {
+        "Polygon" => Ok(polygon_contains(&rings(coordinates(geometry)?)?, point)),
+        "MultiPolygon" => {
+            for polygon in as_array(coordinates(geometry)?, "coordinates")? {
+                if polygon_contains(&rings(polygon)?, point) {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        "Feature" => match geometry.get("geometry") {
+            Some(Value::Object(geometry)) => contains(geometry, point),
+            _ => Ok(false),
+        },
+        "FeatureCollection" => {
+            let features = geometry
+                .get("features")
+                .ok_or_else(|| r#"GeoJSON "features" are missing"#.to_owned())?;
+            for feature in as_array(features, "features")? {
+                let feature = match feature {
+                    Value::Object(feature) => feature,
+                    _ => return Err(r#"GeoJSON "features" must be objects"#.to_owned()),
+                };
+                if contains(feature, point)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        kind => Err(format!(
+            r#"GeoJSON "type" must be "Polygon", "MultiPolygon", "Feature" or "FeatureCollection", got "{}""#,
+            kind
+        )),
+    }
+}
+
+fn coordinates(geometry: &BTreeMap<String, Value>) -> std::result::Result<&Value, String> {
+    geometry
+        .get("coordinates")
+        .ok_or_else(|| r#"GeoJSON "coordinates" are missing"#.to_owned())
+}
+
+fn as_array<'a>(value: &'a Value, name: &str) -> std::result::Result<&'a [Value], String> {
+    match value {
+        Value::Array(array) => Ok(array),
+        _ => Err(format!(r#"GeoJSON "{}" must be arrays"#, name)),
+    }
+}
+
+/// The rings of a polygon, the first being its exterior and the others its
+/// holes.
+fn rings(polygon: &Value) -> std::result::Result<Vec<Vec<Position>>, String> {
+    as_array(polygon, "coordinates")?
+        .iter()
+        .map(|ring| {
+            as_array(ring, "coordinates")?
+                .iter()
+                .map(|position| match as_array(position, "coordinates")? {
+                    [longitude, latitude, ..] => Ok((
+                        f64::try_from(longitude).map_err(|e| e.to_string())?,
+                        f64::try_from(latitude).map_err(|e| e.to_string())?,
+                    )),
+                    _ => Err("GeoJSON positions must have a longitude and a latitude".to_owned()),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Casts a ray from the point, which crosses the rings of the polygon an odd
+/// number of times if the point is in the polygon, and so outside its holes.
+fn polygon_contains(rings: &[Vec<Position>], (x, y): Position) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        for (&(x1, y1), &(x2, y2)) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+            if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square_with_hole() -> Value {
+        value!({
+            "type": "Polygon",
+            "coordinates": [
+                [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                [[4, 4], [6, 4], [6, 6], [4, 6], [4, 4]],
+            ],
+        })
+    }
+
+    test_function![
+        point_in_polygon => PointInPolygon;
+
+        inside {
+            args: func_args![latitude: 2.5, longitude: 2.5, polygon: square_with_hole()],
+            want: Ok(true),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        outside {
+            args: func_args![latitude: 11, longitude: 5, polygon: square_with_hole()],
+            want: Ok(false),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        in_hole {
+            args: func_args![latitude: 5, longitude: 5, polygon: square_with_hole()],
+            want: Ok(false),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        multi_polygon {
+            args: func_args![
+                latitude: 21,
+                longitude: 21,
+                polygon: value!({
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [[[0, 0], [1, 0], [1, 1], [0, 0]]],
+                        [[[20, 20], [22, 20], [22, 22], [20, 22], [20, 20]]],
+                    ],
+                }),
+            ],
+            want: Ok(true),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        feature_collection_string {
+            args: func_args![
+                latitude: 1.5,
+                longitude: -1.5,
+                polygon: r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {}, "geometry": {"type": "Polygon", "coordinates": [[[-2, 1], [-1, 1], [-1, 2], [-2, 2], [-2, 1]]]}}]}"#,
+            ],
+            want: Ok(true),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid_type {
+            args: func_args![latitude: 0, longitude: 0, polygon: value!({"type": "Point", "coordinates": [0, 0]})],
+            want: Err(r#"GeoJSON "type" must be "Polygon", "MultiPolygon", "Feature" or "FeatureCollection", got "Point""#),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid_json {
+            args: func_args![latitude: 0, longitude: 0, polygon: "{"],
+            want: Err("unable to parse GeoJSON: EOF while parsing an object at line 1 column 1"),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+    ];
+}
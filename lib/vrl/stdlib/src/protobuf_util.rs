@@ -0,0 +1,620 @@
+//! Shared support for the `decode_protobuf` and `encode_protobuf` functions.
+//!
+//! Messages are described by a `FileDescriptorSet`, as produced by
+//! `protoc --include_imports --descriptor_set_out=...`. Descriptor sets are
+//! loaded once per path and shared between every program that references them.
+
+use bytes::{Buf, BufMut};
+use lazy_static::lazy_static;
+use prost::{
+    encoding::{decode_key, decode_varint, encode_key, encode_varint, WireType},
+    Message,
+};
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, FileDescriptorSet,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use vrl::{
+    diagnostic::{Label as DiagnosticLabel, Span},
+    prelude::*,
+};
+
+lazy_static! {
+    static ref POOLS: Mutex<HashMap<PathBuf, Arc<DescriptorPool>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug)]
+pub enum Error {
+    ReadDescriptorFile { path: PathBuf, error: String },
+    UnknownMessageType { path: PathBuf, name: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ReadDescriptorFile { path, error } => write!(
+                f,
+                "unable to load descriptor set {:?}: {}",
+                path.display(),
+                error
+            ),
+            Error::UnknownMessageType { path, name } => write!(
+                f,
+                "message type {:?} not found in descriptor set {:?}",
+                name,
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        403
+    }
+
+    fn labels(&self) -> Vec<DiagnosticLabel> {
+        vec![DiagnosticLabel::primary(self.to_string(), Span::default())]
+    }
+}
+
+/// Resolves the `desc_file` and `message_type` literal arguments of a function
+/// into the descriptor of the message to decode or encode.
+pub fn compile_message(
+    arguments: &mut ArgumentList,
+) -> std::result::Result<MessageRef, Box<dyn DiagnosticError>> {
+    let path = arguments
+        .required_literal("desc_file")?
+        .to_value()
+        .try_bytes_utf8_lossy()
+        .expect("desc_file not bytes")
+        .into_owned();
+    let name = arguments
+        .required_literal("message_type")?
+        .to_value()
+        .try_bytes_utf8_lossy()
+        .expect("message_type not bytes")
+        .into_owned();
+
+    let path = PathBuf::from(path);
+    let pool = load_pool(&path).map_err(|error| Box::new(error) as Box<dyn DiagnosticError>)?;
+    let name = name.trim_start_matches('.').to_owned();
+    if !pool.messages.contains_key(&name) {
+        return Err(Box::new(Error::UnknownMessageType { path, name }));
+    }
+
+    Ok(MessageRef { pool, name })
+}
+
+fn load_pool(path: &Path) -> std::result::Result<Arc<DescriptorPool>, Error> {
+    let mut pools = POOLS.lock().expect("descriptor pool cache poisoned");
+    if let Some(pool) = pools.get(path) {
+        return Ok(Arc::clone(pool));
+    }
+
+    let read_error = |error: String| Error::ReadDescriptorFile {
+        path: path.to_owned(),
+        error,
+    };
+    let bytes = std::fs::read(path).map_err(|error| read_error(error.to_string()))?;
+    let set = FileDescriptorSet::decode(bytes.as_slice())
+        .map_err(|error| read_error(error.to_string()))?;
+    let pool = Arc::new(DescriptorPool::new(set));
+    if pool.messages.is_empty() {
+        return Err(read_error("no message types defined".to_owned()));
+    }
+
+    pools.insert(path.to_owned(), Arc::clone(&pool));
+    Ok(pool)
+}
+
+/// A message type of a loaded descriptor set.
+#[derive(Debug, Clone)]
+pub struct MessageRef {
+    pool: Arc<DescriptorPool>,
+    name: String,
+}
+
+impl MessageRef {
+    /// Returns all the message types of a descriptor set, by their fully
+    /// qualified names.
+    pub fn all(set: FileDescriptorSet) -> HashMap<String, MessageRef> {
+        let pool = Arc::new(DescriptorPool::new(set));
+        pool.messages
+            .keys()
+            .map(|name| {
+                let message = MessageRef {
+                    pool: Arc::clone(&pool),
+                    name: name.clone(),
+                };
+                (name.clone(), message)
+            })
+            .collect()
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Resolved {
+        self.pool.decode_message(&self.name, bytes)
+    }
+
+    pub fn encode(&self, value: Value) -> Resolved {
+        let mut buf = Vec::new();
+        self.pool.encode_message(&self.name, value, &mut buf)?;
+        Ok(Value::from(Bytes::from(buf)))
+    }
+}
+
+#[derive(Debug, Default)]
+struct DescriptorPool {
+    messages: HashMap<String, MessageDescriptor>,
+    enums: HashMap<String, EnumDescriptor>,
+}
+
+#[derive(Debug)]
+struct MessageDescriptor {
+    fields: Vec<FieldDescriptor>,
+    map_entry: bool,
+}
+
+#[derive(Debug)]
+struct FieldDescriptor {
+    name: String,
+    number: u32,
+    kind: Type,
+    repeated: bool,
+    packed: bool,
+    type_name: String,
+}
+
+#[derive(Debug, Default)]
+struct EnumDescriptor {
+    names: HashMap<i32, String>,
+    numbers: HashMap<String, i32>,
+}
+
+impl DescriptorPool {
+    fn new(set: FileDescriptorSet) -> Self {
+        let mut pool = Self::default();
+        for file in set.file {
+            let proto3 = file.syntax() == "proto3";
+            let scope = file.package().to_owned();
+            for message in &file.message_type {
+                pool.add_message(&scope, message, proto3);
+            }
+            for enumeration in &file.enum_type {
+                pool.add_enum(&scope, enumeration);
+            }
+        }
+        pool
+    }
+
+    fn add_message(&mut self, scope: &str, message: &DescriptorProto, proto3: bool) {
+        let name = qualify(scope, message.name());
+        for nested in &message.nested_type {
+            self.add_message(&name, nested, proto3);
+        }
+        for enumeration in &message.enum_type {
+            self.add_enum(&name, enumeration);
+        }
+
+        let fields = message
+            .field
+            .iter()
+            .map(|field| {
+                let kind = field.r#type();
+                let repeated = field.label() == Label::Repeated;
+                // Scalar repeated fields are packed by default in proto3.
+                let packed = repeated
+                    && is_packable(kind)
+                    && field
+                        .options
+                        .as_ref()
+                        .and_then(|options| options.packed)
+                        .unwrap_or(proto3);
+
+                FieldDescriptor {
+                    name: field.name().to_owned(),
+                    number: field.number() as u32,
+                    kind,
+                    repeated,
+                    packed,
+                    type_name: field.type_name().trim_start_matches('.').to_owned(),
+                }
+            })
+            .collect();
+        let map_entry = message
+            .options
+            .as_ref()
+            .map_or(false, |options| options.map_entry());
+
+        self.messages
+            .insert(name, MessageDescriptor { fields, map_entry });
+    }
+
+    fn add_enum(&mut self, scope: &str, enumeration: &EnumDescriptorProto) {
+        let mut descriptor = EnumDescriptor::default();
+        for value in &enumeration.value {
+            descriptor
+                .names
+                .insert(value.number(), value.name().to_owned());
+            descriptor
+                .numbers
+                .insert(value.name().to_owned(), value.number());
+        }
+        self.enums
+            .insert(qualify(scope, enumeration.name()), descriptor);
+    }
+
+    fn message(&self, name: &str) -> Result<&MessageDescriptor> {
+        self.messages
+            .get(name)
+            .ok_or_else(|| format!("message type {:?} not found", name).into())
+    }
+
+    fn decode_message(&self, name: &str, mut buf: &[u8]) -> Resolved {
+        let message = self.message(name)?;
+        let mut object = BTreeMap::new();
+
+        while buf.has_remaining() {
+            let (number, wire_type) = decode_key(&mut buf).map_err(decode_error)?;
+            let field = match message.fields.iter().find(|field| field.number == number) {
+                Some(field) => field,
+                None => {
+                    skip_field(wire_type, &mut buf)?;
+                    continue;
+                }
+            };
+
+            if field.repeated {
+                let values = match object
+                    .entry(field.name.clone())
+                    .or_insert_with(|| Value::Array(vec![]))
+                {
+                    Value::Array(values) => values,
+                    _ => unreachable!("repeated fields are arrays"),
+                };
+
+                if wire_type == WireType::LengthDelimited && is_packable(field.kind) {
+                    let mut packed = take_length_delimited(&mut buf)?;
+                    while packed.has_remaining() {
+                        values.push(self.decode_value(
+                            field,
+                            wire_type_of(field.kind),
+                            &mut packed,
+                        )?);
+                    }
+                } else {
+                    values.push(self.decode_value(field, wire_type, &mut buf)?);
+                }
+            } else {
+                let value = self.decode_value(field, wire_type, &mut buf)?;
+                object.insert(field.name.clone(), value);
+            }
+        }
+
+        // Map fields are encoded as repeated key/value entry messages.
+        for field in &message.fields {
+            if !field.repeated || field.kind != Type::Message {
+                continue;
+            }
+            if !self.message(&field.type_name)?.map_entry {
+                continue;
+            }
+            if let Some(Value::Array(entries)) = object.remove(&field.name) {
+                let mut map = BTreeMap::new();
+                for entry in entries {
+                    let mut entry = entry.try_object()?;
+                    let key = match entry.remove("key") {
+                        Some(Value::Bytes(key)) => String::from_utf8_lossy(&key).into_owned(),
+                        Some(key) => key.to_string(),
+                        None => String::new(),
+                    };
+                    map.insert(key, entry.remove("value").unwrap_or(Value::Null));
+                }
+                object.insert(field.name.clone(), Value::Object(map));
+            }
+        }
+
+        Ok(Value::Object(object))
+    }
+
+    fn decode_value(
+        &self,
+        field: &FieldDescriptor,
+        wire_type: WireType,
+        buf: &mut &[u8],
+    ) -> Resolved {
+        let expected = wire_type_of(field.kind);
+        if wire_type != expected {
+            return Err(format!(
+                "field {:?} has wire type {:?}, expected {:?}",
+                field.name, wire_type, expected
+            )
+            .into());
+        }
+
+        let value = match field.kind {
+            Type::Double => Value::from(f64::from_bits(take_fixed64(buf)?)),
+            Type::Float => Value::from(f32::from_bits(take_fixed32(buf)?) as f64),
+            Type::Int64 => Value::from(take_varint(buf)? as i64),
+            Type::Int32 => Value::from(take_varint(buf)? as i32 as i64),
+            Type::Uint32 => Value::from(take_varint(buf)? as u32 as i64),
+            Type::Uint64 => Value::from(unsigned_to_i64(take_varint(buf)?, field)?),
+            Type::Sint32 | Type::Sint64 => Value::from(zigzag_decode(take_varint(buf)?)),
+            Type::Fixed32 => Value::from(take_fixed32(buf)? as i64),
+            Type::Fixed64 => Value::from(unsigned_to_i64(take_fixed64(buf)?, field)?),
+            Type::Sfixed32 => Value::from(take_fixed32(buf)? as i32 as i64),
+            Type::Sfixed64 => Value::from(take_fixed64(buf)? as i64),
+            Type::Bool => Value::from(take_varint(buf)? != 0),
+            Type::String | Type::Bytes => {
+                Value::from(Bytes::copy_from_slice(take_length_delimited(buf)?))
+            }
+            Type::Enum => {
+                let number = take_varint(buf)? as i32;
+                match self
+                    .enums
+                    .get(&field.type_name)
+                    .and_then(|enumeration| enumeration.names.get(&number))
+                {
+                    Some(name) => Value::from(name.as_str()),
+                    None => Value::from(number as i64),
+                }
+            }
+            Type::Message => self.decode_message(&field.type_name, take_length_delimited(buf)?)?,
+            Type::Group => return Err("groups are not supported".into()),
+        };
+
+        Ok(value)
+    }
+
+    fn encode_message(&self, name: &str, value: Value, buf: &mut Vec<u8>) -> Result<()> {
+        let message = self.message(name)?;
+        let mut object = value.try_object()?;
+
+        for field in &message.fields {
+            let value = match object.remove(&field.name) {
+                None | Some(Value::Null) => continue,
+                Some(value) => value,
+            };
+
+            if !field.repeated {
+                self.encode_field(field, value, buf)?;
+                continue;
+            }
+
+            let values = match value {
+                // Map fields are given as objects.
+                Value::Object(map)
+                    if field.kind == Type::Message && self.message(&field.type_name)?.map_entry =>
+                {
+                    map.into_iter()
+                        .map(|(key, value)| {
+                            let mut entry = BTreeMap::new();
+                            entry.insert("key".to_owned(), Value::from(key));
+                            entry.insert("value".to_owned(), value);
+                            Value::Object(entry)
+                        })
+                        .collect()
+                }
+                value => value.try_array()?,
+            };
+
+            if field.packed {
+                let mut packed = Vec::new();
+                for value in values {
+                    self.encode_value(field, value, &mut packed)?;
+                }
+                encode_key(field.number, WireType::LengthDelimited, buf);
+                encode_varint(packed.len() as u64, buf);
+                buf.put_slice(&packed);
+            } else {
+                for value in values {
+                    self.encode_field(field, value, buf)?;
+                }
+            }
+        }
+
+        if let Some(name) = object.keys().next() {
+            return Err(format!("unknown field {:?}", name).into());
+        }
+
+        Ok(())
+    }
+
+    fn encode_field(&self, field: &FieldDescriptor, value: Value, buf: &mut Vec<u8>) -> Result<()> {
+        encode_key(field.number, wire_type_of(field.kind), buf);
+        self.encode_value(field, value, buf)
+    }
+
+    fn encode_value(&self, field: &FieldDescriptor, value: Value, buf: &mut Vec<u8>) -> Result<()> {
+        match field.kind {
+            Type::Double => buf.put_u64_le(as_float(value)?.to_bits()),
+            Type::Float => buf.put_u32_le((as_float(value)? as f32).to_bits()),
+            Type::Int64 | Type::Int32 => encode_varint(value.try_integer()? as u64, buf),
+            Type::Uint32 | Type::Uint64 => encode_varint(as_unsigned(value, field)?, buf),
+            Type::Sint32 | Type::Sint64 => encode_varint(zigzag_encode(value.try_integer()?), buf),
+            Type::Fixed32 => buf.put_u32_le(as_unsigned(value, field)? as u32),
+            Type::Fixed64 => buf.put_u64_le(as_unsigned(value, field)?),
+            Type::Sfixed32 => buf.put_i32_le(value.try_integer()? as i32),
+            Type::Sfixed64 => buf.put_i64_le(value.try_integer()?),
+            Type::Bool => encode_varint(value.try_boolean()? as u64, buf),
+            Type::String | Type::Bytes => {
+                let bytes = value.try_bytes()?;
+                encode_varint(bytes.len() as u64, buf);
+                buf.put_slice(&bytes);
+            }
+            Type::Enum => {
+                let number = match value {
+                    Value::Bytes(name) => {
+                        let name = String::from_utf8_lossy(&name);
+                        *self
+                            .enums
+                            .get(&field.type_name)
+                            .and_then(|enumeration| enumeration.numbers.get(name.as_ref()))
+                            .ok_or_else(|| {
+                                format!("unknown variant {:?} of enum {:?}", name, field.type_name)
+                            })?
+                    }
+                    value => value.try_integer()? as i32,
+                };
+                encode_varint(number as i64 as u64, buf);
+            }
+            Type::Message => {
+                let mut message = Vec::new();
+                self.encode_message(&field.type_name, value, &mut message)?;
+                encode_varint(message.len() as u64, buf);
+                buf.put_slice(&message);
+            }
+            Type::Group => return Err("groups are not supported".into()),
+        }
+
+        Ok(())
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+const fn is_packable(kind: Type) -> bool {
+    !matches!(
+        kind,
+        Type::String | Type::Bytes | Type::Message | Type::Group
+    )
+}
+
+const fn wire_type_of(kind: Type) -> WireType {
+    match kind {
+        Type::Double | Type::Fixed64 | Type::Sfixed64 => WireType::SixtyFourBit,
+        Type::Float | Type::Fixed32 | Type::Sfixed32 => WireType::ThirtyTwoBit,
+        Type::String | Type::Bytes | Type::Message => WireType::LengthDelimited,
+        Type::Group => WireType::StartGroup,
+        _ => WireType::Varint,
+    }
+}
+
+fn decode_error(error: prost::DecodeError) -> ExpressionError {
+    format!("unable to decode protobuf message: {}", error).into()
+}
+
+fn truncated() -> ExpressionError {
+    "unable to decode protobuf message: buffer underflow".into()
+}
+
+fn take_varint(buf: &mut &[u8]) -> Result<u64> {
+    decode_varint(buf).map_err(decode_error)
+}
+
+fn take_fixed32(buf: &mut &[u8]) -> Result<u32> {
+    if buf.remaining() < 4 {
+        return Err(truncated());
+    }
+    Ok(buf.get_u32_le())
+}
+
+fn take_fixed64(buf: &mut &[u8]) -> Result<u64> {
+    if buf.remaining() < 8 {
+        return Err(truncated());
+    }
+    Ok(buf.get_u64_le())
+}
+
+fn take_length_delimited<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = take_varint(buf)? as usize;
+    if buf.len() < len {
+        return Err(truncated());
+    }
+    let (value, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(value)
+}
+
+fn skip_field(wire_type: WireType, buf: &mut &[u8]) -> Result<()> {
+    match wire_type {
+        WireType::Varint => take_varint(buf).map(drop),
+        WireType::SixtyFourBit => take_fixed64(buf).map(drop),
+        WireType::ThirtyTwoBit => take_fixed32(buf).map(drop),
+        WireType::LengthDelimited => take_length_delimited(buf).map(drop),
+        WireType::StartGroup | WireType::EndGroup => Err("groups are not supported".into()),
+    }
+}
+
+const fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+const fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unsigned_to_i64(value: u64, field: &FieldDescriptor) -> Result<i64> {
+    i64::try_from(value).map_err(|_| {
+        format!(
+            "value {} of field {:?} doesn't fit in an integer",
+            value, field.name
+        )
+        .into()
+    })
+}
+
+fn as_unsigned(value: Value, field: &FieldDescriptor) -> Result<u64> {
+    let value = value.try_integer()?;
+    u64::try_from(value).map_err(|_| {
+        format!(
+            "field {:?} is unsigned, got negative value {}",
+            field.name, value
+        )
+        .into()
+    })
+}
+
+fn as_float(value: Value) -> Result<f64> {
+    match value {
+        Value::Integer(value) => Ok(value as f64),
+        value => Ok(value.try_float()?),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use super::*;
+    use shared::btreemap;
+
+    pub(crate) const DESC_FILE: &str = "data/protobuf/test_protobuf.desc";
+
+    /// A `test_protobuf.Person` with every field set, see
+    /// `data/protobuf/test_protobuf.proto`.
+    pub(crate) const PERSON: &[u8] = b"\x0a\x07someone\x10\x01\x1a\x13someone@example.com\x22\x0a\x0a\x06123456\x10\x02\x2a\x0c\x0a\x03key\x12\x05value\x30\x01\x39\x00\x00\x00\x00\x00\x00\xf8\x3f\x40\x01\x4a\x02\x03\x07\x52\x02\x01\x02\x58\x03";
+
+    pub(crate) fn person() -> Value {
+        Value::from(btreemap! {
+            "name" => "someone",
+            "id" => 1,
+            "email" => "someone@example.com",
+            "phones" => vec![Value::from(btreemap! {
+                "number" => "123456",
+                "type" => "WORK",
+            })],
+            "data" => btreemap! {
+                "key" => "value",
+            },
+            "favorite_type" => "HOME",
+            "score" => 1.5,
+            "active" => true,
+            "lucky_numbers" => vec![Value::from(3), Value::from(7)],
+            "avatar" => Bytes::from_static(b"\x01\x02"),
+            "offset" => -2,
+        })
+    }
+}
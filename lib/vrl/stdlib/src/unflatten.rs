@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Unflatten;
+
+impl Function for Unflatten {
+    fn identifier(&self) -> &'static str {
+        "unflatten"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "separator",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "object",
+                source: r#"unflatten({ "foo.bar": true })"#,
+                result: Ok(r#"{ "foo": { "bar": true } }"#),
+            },
+            Example {
+                title: "object with separator",
+                source: r#"unflatten({ "foo_bar": true }, "_")"#,
+                result: Ok(r#"{ "foo": { "bar": true } }"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let separator = arguments.optional("separator");
+        Ok(Box::new(UnflattenFn { value, separator }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UnflattenFn {
+    value: Box<dyn Expression>,
+    separator: Option<Box<dyn Expression>>,
+}
+
+impl Expression for UnflattenFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let separator = match &self.separator {
+            Some(separator) => separator.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned(),
+            None => ".".to_owned(),
+        };
+        if separator.is_empty() {
+            return Err("separator must not be empty".into());
+        }
+
+        let map = self.value.resolve(ctx)?.try_object()?;
+        Ok(Value::Object(unflatten(map, &separator)))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new()
+            .with_fallibility(self.separator.is_some())
+            .object::<(), Kind>(map! { (): Kind::all() })
+    }
+}
+
+/// Splits the keys of the map, and of any maps nested in it, into nested maps.
+fn unflatten(map: BTreeMap<String, Value>, separator: &str) -> BTreeMap<String, Value> {
+    let mut output = BTreeMap::new();
+    for (key, value) in map {
+        let value = match value {
+            Value::Object(map) => Value::Object(unflatten(map, separator)),
+            value => value,
+        };
+        let segments = key.split(separator).collect::<Vec<_>>();
+        if let Err(value) = insert_nested(&mut output, &segments, value) {
+            // The key conflicts with a value already at one of its parents,
+            // so it's kept as it is.
+            output.insert(key, value);
+        }
+    }
+    output
+}
+
+fn insert_nested(
+    map: &mut BTreeMap<String, Value>,
+    segments: &[&str],
+    value: Value,
+) -> std::result::Result<(), Value> {
+    match segments {
+        [] => Err(value),
+        [last] => match map.get_mut(*last) {
+            Some(Value::Object(existing)) => match value {
+                Value::Object(value) => {
+                    for (key, value) in value {
+                        if let Err(value) = insert_nested(existing, &[key.as_str()], value) {
+                            existing.insert(key, value);
+                        }
+                    }
+                    Ok(())
+                }
+                value => Err(value),
+            },
+            Some(_) => Err(value),
+            None => {
+                map.insert((*last).to_owned(), value);
+                Ok(())
+            }
+        },
+        [first, rest @ ..] => match map
+            .entry((*first).to_owned())
+            .or_insert_with(|| Value::Object(BTreeMap::new()))
+        {
+            Value::Object(child) => insert_nested(child, rest, value),
+            _ => Err(value),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        unflatten => Unflatten;
+
+        map {
+            args: func_args![value: value!({parent: "child"})],
+            want: Ok(value!({parent: "child"})),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+
+        flattened_map {
+            args: func_args![value: value!({
+                "parent.child1": 1,
+                "parent.child2.grandchild1": 1,
+                "parent.child2.grandchild2": 2,
+                key: "val",
+            })],
+            want: Ok(value!({
+                parent: {
+                    child1: 1,
+                    child2: { grandchild1: 1, grandchild2: 2 },
+                },
+                key: "val",
+            })),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+
+        merges_into_nested_map {
+            args: func_args![value: value!({"parent.child1": 1, parent: {child2: 2, "child3.grandchild": 3}})],
+            want: Ok(value!({parent: {child1: 1, child2: 2, child3: {grandchild: 3}}})),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+
+        keeps_conflicting_keys {
+            args: func_args![value: value!({parent: 1, "parent.child": 2})],
+            want: Ok(value!({parent: 1, "parent.child": 2})),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+
+        with_separator {
+            args: func_args![value: value!({"parent_child": 1, "other.key": 2}), separator: "_"],
+            want: Ok(value!({parent: {child: 1}, "other.key": 2})),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+
+        empty_separator {
+            args: func_args![value: value!({"parent.child": 1}), separator: ""],
+            want: Err("separator must not be empty"),
+            tdef: TypeDef::new().fallible().object::<(), Kind>(map! { (): Kind::all() }),
+        }
+    ];
+}
@@ -1,7 +1,12 @@
 /// Rounds the given number to the given precision.
 /// Takes a function parameter so the exact rounding function (ceil, floor or round)
 /// can be specified.
-#[cfg(any(feature = "ceil", feature = "floor", feature = "round"))]
+#[cfg(any(
+    feature = "ceil",
+    feature = "floor",
+    feature = "haversine",
+    feature = "round"
+))]
 #[inline]
 pub(crate) fn round_to_precision<F>(num: f64, precision: i64, fun: F) -> f64
 where
@@ -122,3 +127,7 @@ impl std::str::FromStr for Base64Charset {
         }
     }
 }
+
+/// The characters of the base 32 alphabet used by geohashes, in order.
+#[cfg(any(feature = "decode_geohash", feature = "encode_geohash"))]
+pub(crate) const GEOHASH_BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
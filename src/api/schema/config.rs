@@ -0,0 +1,458 @@
+use crate::{
+    config::{self, ComponentAction, ConfigSnapshot, ReloadReport},
+    internal_events::ApiConfigApplied,
+    signal::{SignalTo, SignalTx},
+};
+use async_graphql::{Context, Enum, Object, SimpleObject};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+use tokio::sync::{oneshot, Mutex};
+
+/// How many revisions the audit log keeps.
+const MAX_REVISIONS: usize = 100;
+
+lazy_static! {
+    static ref RUNNING_CONFIG: RwLock<ConfigSnapshot> = RwLock::new(ConfigSnapshot::default());
+    static ref RUNNING_CONFIG_JSON: RwLock<serde_json::Value> = RwLock::new(serde_json::Value::Null);
+    static ref REVISIONS: RwLock<VecDeque<ConfigRevision>> = RwLock::new(VecDeque::new());
+    /// Candidates are applied one at a time, each compared to the config the
+    /// previous one left running.
+    static ref APPLYING: Mutex<()> = Mutex::new(());
+}
+
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// Keeps a snapshot of the running config to compare candidate configs to.
+pub fn update_config(config: &config::Config) {
+    *RUNNING_CONFIG.write().unwrap() = ConfigSnapshot::new(config);
+    *RUNNING_CONFIG_JSON.write().unwrap() = render(config);
+}
+
+/// Renders the running config as JSON, as compiled, so that components
+/// expanded from others appear expanded.
+fn render(config: &config::Config) -> serde_json::Value {
+    #[derive(Serialize)]
+    struct Rendered<'a> {
+        #[serde(flatten)]
+        global: &'a vector_core::config::GlobalOptions,
+        healthchecks: &'a config::HealthcheckOptions,
+        enrichment_tables:
+            &'a indexmap::IndexMap<config::ComponentKey, config::EnrichmentTableOuter>,
+        sources: &'a indexmap::IndexMap<config::ComponentKey, config::SourceOuter>,
+        transforms: serde_json::Map<String, serde_json::Value>,
+        sinks: serde_json::Map<String, serde_json::Value>,
+    }
+
+    // Inputs are rendered the way they are written in config files.
+    fn with_inputs<T: ToString>(outer: &impl Serialize, inputs: &[T]) -> serde_json::Value {
+        let mut rendered = serde_json::to_value(outer).unwrap_or_default();
+        if let Some(rendered) = rendered.as_object_mut() {
+            let inputs = inputs.iter().map(ToString::to_string).collect::<Vec<_>>();
+            rendered.insert("inputs".into(), inputs.into());
+        }
+        rendered
+    }
+
+    let rendered = Rendered {
+        global: &config.global,
+        healthchecks: &config.healthchecks,
+        enrichment_tables: &config.enrichment_tables,
+        sources: &config.sources,
+        transforms: config
+            .transforms
+            .iter()
+            .map(|(key, transform)| (key.to_string(), with_inputs(transform, &transform.inputs)))
+            .collect(),
+        sinks: config
+            .sinks
+            .iter()
+            .map(|(key, sink)| (key.to_string(), with_inputs(sink, &sink.inputs)))
+            .collect(),
+    };
+    serde_json::to_value(rendered).unwrap_or_default()
+}
+
+/// Whether a request carries the `api.auth_token`, which mutating or reading
+/// the config requires.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Authorization {
+    /// No `api.auth_token` is set.
+    Disabled,
+    Unauthorized,
+    Authorized,
+}
+
+impl Authorization {
+    pub fn new(auth_token: Option<&str>, header: Option<&str>) -> Self {
+        let auth_token = match auth_token {
+            Some(auth_token) => auth_token.as_bytes(),
+            None => return Self::Disabled,
+        };
+        match header.and_then(|header| header.strip_prefix("Bearer ")) {
+            Some(token)
+                if token.len() == auth_token.len()
+                    && openssl::memcmp::eq(token.as_bytes(), auth_token) =>
+            {
+                Self::Authorized
+            }
+            _ => Self::Unauthorized,
+        }
+    }
+
+    fn check(ctx: &Context<'_>) -> async_graphql::Result<()> {
+        match ctx.data_opt::<Self>() {
+            Some(Self::Authorized) => Ok(()),
+            Some(Self::Unauthorized) => {
+                Err("Unauthorized, `Authorization: Bearer <api.auth_token>` is required.".into())
+            }
+            Some(Self::Disabled) | None => Err(
+                "Disabled, `api.auth_token` must be set to manage the config through the API."
+                    .into(),
+            ),
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl From<ConfigFormat> for config::Format {
+    fn from(format: ConfigFormat) -> Self {
+        match format {
+            ConfigFormat::Toml => Self::Toml,
+            ConfigFormat::Json => Self::Json,
+            ConfigFormat::Yaml => Self::Yaml,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ReloadComponentKind {
+    Source,
+    Transform,
+    Sink,
+    EnrichmentTable,
+}
+
+impl From<config::ComponentKind> for ReloadComponentKind {
+    fn from(kind: config::ComponentKind) -> Self {
+        match kind {
+            config::ComponentKind::Source => Self::Source,
+            config::ComponentKind::Transform => Self::Transform,
+            config::ComponentKind::Sink => Self::Sink,
+            config::ComponentKind::EnrichmentTable => Self::EnrichmentTable,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ReloadAction {
+    /// Built and started
+    Add,
+    /// Shut down
+    Remove,
+    /// Rebuilt with new options
+    Reconfigure,
+    /// Rebuilt with the same options, as only its inputs changed
+    Restart,
+}
+
+impl From<ComponentAction> for ReloadAction {
+    fn from(action: ComponentAction) -> Self {
+        match action {
+            ComponentAction::Add => Self::Add,
+            ComponentAction::Remove => Self::Remove,
+            ComponentAction::Reconfigure => Self::Reconfigure,
+            ComponentAction::Restart => Self::Restart,
+        }
+    }
+}
+
+pub struct ComponentReload(config::ComponentReport);
+
+#[Object]
+impl ComponentReload {
+    /// Component ID
+    async fn component_id(&self) -> &str {
+        &self.0.id
+    }
+
+    /// Component kind
+    async fn component_kind(&self) -> ReloadComponentKind {
+        self.0.kind.into()
+    }
+
+    /// What the reload does to the component
+    async fn action(&self) -> ReloadAction {
+        self.0.action.into()
+    }
+
+    /// Whether a rebuilt sink keeps the events of its buffer
+    async fn keeps_buffer(&self) -> Option<bool> {
+        self.0.keeps_buffer
+    }
+}
+
+pub struct ConfigDiff(ReloadReport);
+
+#[Object]
+impl ConfigDiff {
+    /// Whether global options changed, which requires restarting Vector
+    async fn global_changed(&self) -> bool {
+        self.0.global_changed
+    }
+
+    /// Components the reload would add, remove, or rebuild
+    async fn components(&self) -> Vec<ComponentReload> {
+        self.0
+            .components
+            .iter()
+            .cloned()
+            .map(ComponentReload)
+            .collect()
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum RevisionOutcome {
+    /// The running topology was reloaded into the candidate
+    Applied,
+    /// The candidate doesn't change the running config
+    Unchanged,
+    /// The candidate failed to compile, or changes global options
+    Rejected,
+    /// Components of the candidate failed to build or their healthchecks
+    /// failed, so the previous config was restored
+    RolledBack,
+    /// The previous config couldn't be restored either, so Vector shuts down
+    Failed,
+}
+
+impl RevisionOutcome {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Unchanged => "unchanged",
+            Self::Rejected => "rejected",
+            Self::RolledBack => "rolled_back",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// A candidate config submitted through the API, as recorded in the audit log
+#[derive(SimpleObject, Clone)]
+#[graphql(complex)]
+pub struct ConfigRevision {
+    /// Sequence number of the revision, since Vector started
+    revision: u64,
+    /// When the revision was submitted
+    submitted_at: DateTime<Utc>,
+    /// SHA-256 digest of the candidate, as submitted
+    digest: String,
+    /// What became of the candidate
+    outcome: RevisionOutcome,
+    /// Why the candidate was rejected
+    errors: Vec<String>,
+    #[graphql(skip)]
+    report: Option<ReloadReport>,
+}
+
+#[async_graphql::ComplexObject]
+impl ConfigRevision {
+    /// Components the candidate added, removed, or rebuilt
+    async fn components(&self) -> Vec<ComponentReload> {
+        self.report
+            .iter()
+            .flat_map(|report| report.components.iter().cloned().map(ComponentReload))
+            .collect()
+    }
+}
+
+impl ConfigRevision {
+    fn record(
+        candidate: &str,
+        outcome: RevisionOutcome,
+        errors: Vec<String>,
+        report: Option<ReloadReport>,
+    ) -> Self {
+        let digest = openssl::sha::sha256(candidate.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        let revision = Self {
+            revision: NEXT_REVISION.fetch_add(1, Ordering::Relaxed),
+            submitted_at: Utc::now(),
+            digest,
+            outcome,
+            errors,
+            report,
+        };
+        emit!(&ApiConfigApplied {
+            revision: revision.revision,
+            outcome: outcome.as_str(),
+            digest: &revision.digest,
+        });
+
+        let mut revisions = REVISIONS.write().unwrap();
+        if revisions.len() == MAX_REVISIONS {
+            revisions.pop_front();
+        }
+        revisions.push_back(revision.clone());
+        revision
+    }
+}
+
+#[derive(Default)]
+pub struct ConfigQuery;
+
+#[Object]
+impl ConfigQuery {
+    /// Compiles a candidate config and reports which components reloading
+    /// the running config into it would affect, without applying it
+    async fn config_diff(
+        &self,
+        config: String,
+        format: Option<ConfigFormat>,
+    ) -> async_graphql::Result<ConfigDiff> {
+        let candidate = config::load_from_str(&config, format.map(Into::into))
+            .map_err(|errors| async_graphql::Error::new(errors.join("\n")))?;
+        let report = ReloadReport::new(
+            &RUNNING_CONFIG.read().unwrap(),
+            &ConfigSnapshot::new(&candidate),
+        );
+        Ok(ConfigDiff(report))
+    }
+
+    /// The running config as JSON, as compiled. Requires authorization
+    async fn running_config(&self, ctx: &Context<'_>) -> async_graphql::Result<String> {
+        Authorization::check(ctx)?;
+        Ok(serde_json::to_string_pretty(
+            &*RUNNING_CONFIG_JSON.read().unwrap(),
+        )?)
+    }
+
+    /// The audit log of the configs submitted through the API, from the
+    /// oldest to the latest. Requires authorization
+    async fn config_revisions(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<ConfigRevision>> {
+        Authorization::check(ctx)?;
+        Ok(REVISIONS.read().unwrap().iter().cloned().collect())
+    }
+}
+
+#[derive(Default)]
+pub struct ConfigMutation;
+
+#[Object]
+impl ConfigMutation {
+    /// Compiles a candidate config and reloads the running topology into it.
+    /// The previous config is restored if components of the candidate fail to
+    /// build or, unless `requireHealthy` is false, if their healthchecks fail
+    /// within their timeout. Requires authorization
+    async fn apply_config(
+        &self,
+        ctx: &Context<'_>,
+        config: String,
+        format: Option<ConfigFormat>,
+        #[graphql(default = true)] require_healthy: bool,
+    ) -> async_graphql::Result<ConfigRevision> {
+        Authorization::check(ctx)?;
+        let _applying = APPLYING.lock().await;
+
+        let mut candidate = match config::load_from_str(&config, format.map(Into::into)) {
+            Ok(candidate) => candidate,
+            Err(errors) => {
+                return Ok(ConfigRevision::record(
+                    &config,
+                    RevisionOutcome::Rejected,
+                    errors,
+                    None,
+                ))
+            }
+        };
+        let report = ReloadReport::new(
+            &RUNNING_CONFIG.read().unwrap(),
+            &ConfigSnapshot::new(&candidate),
+        );
+        if report.global_changed {
+            let error = "Global options can't be changed while Vector is running.".to_owned();
+            return Ok(ConfigRevision::record(
+                &config,
+                RevisionOutcome::Rejected,
+                vec![error],
+                Some(report),
+            ));
+        }
+        if report.is_empty() {
+            return Ok(ConfigRevision::record(
+                &config,
+                RevisionOutcome::Unchanged,
+                Vec::new(),
+                Some(report),
+            ));
+        }
+
+        candidate.healthchecks.set_require_healthy(require_healthy);
+        let (tx, rx) = oneshot::channel();
+        ctx.data::<SignalTx>()?
+            .send(SignalTo::ReloadFromApi(candidate, tx))
+            .await
+            .map_err(|_| "Vector is shutting down.")?;
+        let outcome = match rx.await.map_err(|_| "Vector is shutting down.")? {
+            Ok(true) => RevisionOutcome::Applied,
+            Ok(false) => RevisionOutcome::RolledBack,
+            Err(()) => RevisionOutcome::Failed,
+        };
+
+        Ok(ConfigRevision::record(
+            &config,
+            outcome,
+            Vec::new(),
+            Some(report),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorizes_bearer_token() {
+        assert_eq!(
+            Authorization::new(None, Some("Bearer secret")),
+            Authorization::Disabled
+        );
+        assert_eq!(
+            Authorization::new(Some("secret"), Some("Bearer secret")),
+            Authorization::Authorized
+        );
+        assert_eq!(
+            Authorization::new(Some("secret"), Some("Bearer secre")),
+            Authorization::Unauthorized
+        );
+        assert_eq!(
+            Authorization::new(Some("secret"), Some("secret")),
+            Authorization::Unauthorized
+        );
+        assert_eq!(
+            Authorization::new(Some("secret"), None),
+            Authorization::Unauthorized
+        );
+    }
+}
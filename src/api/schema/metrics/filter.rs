@@ -230,6 +230,28 @@ pub fn component_counter_metrics(
     })
 }
 
+/// Returns a stream of `Vec<Metric>`, where each metric is the sum of the 'gauge' metrics matching
+/// `filter_fn` for a component. Unlike counters, gauges can decrease, so every component is
+/// returned on each iteration.
+pub fn component_gauge_metrics(
+    interval: i32,
+    filter_fn: &'static MetricFilterFn,
+) -> impl Stream<Item = Vec<Metric>> {
+    get_all_metrics(interval).map(move |m| {
+        m.into_iter()
+            .filter(filter_fn)
+            .filter(|m| matches!(m.value(), MetricValue::Gauge { .. }))
+            .filter_map(|m| m.tag_value("component_id").map(|id| (id, m)))
+            .fold(BTreeMap::new(), |mut map, (id, m)| {
+                map.entry(id).or_insert_with(Vec::new).push(m);
+                map
+            })
+            .into_values()
+            .filter_map(sum_metrics_owned)
+            .collect()
+    })
+}
+
 /// Returns the throughput of a 'counter' metric, sampled over `interval` millseconds
 /// and filtered by the provided `filter_fn`.
 pub fn counter_throughput(
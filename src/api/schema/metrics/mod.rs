@@ -1,3 +1,4 @@
+mod allocated_bytes;
 mod errors;
 mod events_in;
 mod events_out;
@@ -20,6 +21,7 @@ use async_graphql::{validators::IntRange, Interface, Object, Subscription};
 use chrono::{DateTime, Utc};
 use tokio_stream::{Stream, StreamExt};
 
+pub use allocated_bytes::{AllocatedBytes, ComponentAllocatedBytes};
 pub use errors::{ComponentErrorsTotal, ErrorsTotal};
 pub use events_in::{ComponentEventsInThroughput, ComponentEventsInTotal, EventsInTotal};
 pub use events_out::{ComponentEventsOutThroughput, ComponentEventsOutTotal, EventsOutTotal};
@@ -394,6 +396,18 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Approximate number of bytes held by each component over `interval`, counting the events
+    /// in its buffer and its state, such as caches or events being aggregated.
+    async fn component_allocated_bytes(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "10", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentAllocatedBytes>> {
+        component_gauge_metrics(interval, &|m| {
+            matches!(m.name(), "component_allocated_bytes" | "buffer_byte_size")
+        })
+        .map(|m| m.into_iter().map(ComponentAllocatedBytes::new).collect())
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
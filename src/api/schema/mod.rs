@@ -1,4 +1,5 @@
 pub mod components;
+pub mod config;
 mod events;
 pub mod filter;
 mod health;
@@ -7,7 +8,7 @@ mod metrics;
 mod relay;
 pub mod sort;
 
-use async_graphql::{EmptyMutation, MergedObject, MergedSubscription, Schema, SchemaBuilder};
+use async_graphql::{MergedObject, MergedSubscription, Schema, SchemaBuilder};
 pub use vector_core::api::schema::scalar;
 
 #[derive(MergedObject, Default)]
@@ -16,8 +17,12 @@ pub struct Query(
     components::ComponentsQuery,
     metrics::MetricsQuery,
     meta::MetaQuery,
+    config::ConfigQuery,
 );
 
+#[derive(MergedObject, Default)]
+pub struct Mutation(config::ConfigMutation);
+
 #[derive(MergedSubscription, Default)]
 pub struct Subscription(
     health::HealthSubscription,
@@ -27,6 +32,10 @@ pub struct Subscription(
 );
 
 /// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
-pub fn build_schema() -> SchemaBuilder<Query, EmptyMutation, Subscription> {
-    Schema::build(Query::default(), EmptyMutation, Subscription::default())
+pub fn build_schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(
+        Query::default(),
+        Mutation::default(),
+        Subscription::default(),
+    )
 }
@@ -1,5 +1,5 @@
 use super::{handler, schema, ShutdownTx};
-use crate::{config, topology};
+use crate::{config, signal::SignalTx, topology};
 use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
     Data, Request, Schema,
@@ -17,8 +17,17 @@ pub struct Server {
 impl Server {
     /// Start the API server. This creates the routes and spawns a Warp server. The server is
     /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing.
-    pub fn start(config: &config::Config, watch_rx: topology::WatchRx) -> Self {
-        let routes = make_routes(config.api.playground, watch_rx);
+    pub fn start(
+        config: &config::Config,
+        watch_rx: topology::WatchRx,
+        signal_tx: SignalTx,
+    ) -> Self {
+        let routes = make_routes(
+            config.api.playground,
+            config.api.auth_token.clone(),
+            watch_rx,
+            signal_tx,
+        );
 
         let (_shutdown, rx) = oneshot::channel();
         let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
@@ -30,6 +39,7 @@ impl Server {
 
         // Update component schema with the config before starting the server.
         schema::components::update_config(config);
+        schema::config::update_config(config);
 
         // Spawn the server in the background.
         tokio::spawn(server);
@@ -46,13 +56,19 @@ impl Server {
     /// directly involve `self`, it provides a neater API to expose an internal implementation
     /// detail than exposing the function of the sub-mod directly.
     pub fn update_config(&self, config: &config::Config) {
-        schema::components::update_config(config)
+        schema::components::update_config(config);
+        schema::config::update_config(config);
     }
 }
 
-fn make_routes(playground: bool, watch_tx: topology::WatchRx) -> BoxedFilter<(impl Reply,)> {
+fn make_routes(
+    playground: bool,
+    auth_token: Option<String>,
+    watch_tx: topology::WatchRx,
+    signal_tx: SignalTx,
+) -> BoxedFilter<(impl Reply,)> {
     // Build the GraphQL schema.
-    let schema = schema::build_schema().finish();
+    let schema = schema::build_schema().data(signal_tx).finish();
 
     // Routes...
 
@@ -69,11 +85,21 @@ fn make_routes(playground: bool, watch_tx: topology::WatchRx) -> BoxedFilter<(im
             data.insert(watch_tx);
             Ok(data)
         })
-        .or(async_graphql_warp::graphql(schema).and_then(
-            |(schema, request): (Schema<_, _, _>, Request)| async move {
-                Ok::<_, Infallible>(GQLResponse::from(schema.execute(request).await))
-            },
-        )),
+        .or(warp::header::optional::<String>("authorization")
+            .and(async_graphql_warp::graphql(schema))
+            .and_then(
+                move |authorization: Option<String>,
+                      (schema, request): (Schema<_, _, _>, Request)| {
+                    let authorization = schema::config::Authorization::new(
+                        auth_token.as_deref(),
+                        authorization.as_deref(),
+                    );
+                    async move {
+                        let request = request.data(authorization);
+                        Ok::<_, Infallible>(GQLResponse::from(schema.execute(request).await))
+                    }
+                },
+            )),
     );
 
     // GraphQL playground
@@ -1,6 +1,7 @@
 use crate::{
-    cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubCommand},
-    config, generate, graph, heartbeat, list, metrics,
+    checkpoints,
+    cli::{handle_config_errors, Color, ConfigSubCommand, LogFormat, Opts, RootOpts, SubCommand},
+    config, config_diff, generate, graph, heartbeat, list, metrics,
     signal::{self, SignalTo},
     topology::{self, RunningTopology},
     trace, unit_test, validate,
@@ -13,6 +14,8 @@ use tokio::{
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
+#[cfg(feature = "sinks-datadog")]
+use crate::replay;
 #[cfg(feature = "api")]
 use crate::{api, internal_events::ApiStarted};
 #[cfg(feature = "api-client")]
@@ -129,9 +132,15 @@ impl Application {
 
                 if let Some(s) = sub_command {
                     let code = match s {
+                        SubCommand::Checkpoints(c) => checkpoints::cmd(&c),
+                        SubCommand::Config(ConfigSubCommand::Diff(d)) => {
+                            config_diff::cmd(&d, color)
+                        }
                         SubCommand::Generate(g) => generate::cmd(&g),
                         SubCommand::Graph(g) => graph::cmd(&g),
                         SubCommand::List(l) => list::cmd(&l),
+                        #[cfg(feature = "sinks-datadog")]
+                        SubCommand::Replay(r) => replay::cmd(&r).await,
                         SubCommand::Test(t) => unit_test::cmd(&t).await,
                         #[cfg(windows)]
                         SubCommand::Service(s) => service::cmd(&s),
@@ -188,7 +197,7 @@ impl Application {
                     .ok_or(exitcode::CONFIG)?;
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                let api = config.api.clone();
 
                 let result = topology::start_validated(config, diff, pieces).await;
                 let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
@@ -245,7 +254,11 @@ impl Application {
                     playground: api_config.playground
                 });
 
-                Some(api::Server::start(topology.config(), topology.watch()))
+                Some(api::Server::start(
+                    topology.config(),
+                    topology.watch(),
+                    signal_handler.clone_tx(),
+                ))
             } else {
                 info!(message="API is disabled, enable by setting `api.enabled` to `true` and use commands like `vector top`.");
                 None
@@ -334,6 +347,31 @@ impl Application {
                                     emit!(&VectorConfigLoadFailed);
                                 }
                             }
+                            #[cfg(feature = "api")]
+                            SignalTo::ReloadFromApi(new_config, reply) => {
+                                #[cfg(feature = "datadog-pipelines")]
+                                let mut new_config = new_config;
+                                #[cfg(feature = "datadog-pipelines")]
+                                config::datadog::try_attach(&mut new_config);
+
+                                let result = topology.reload_config_and_respawn(new_config).await;
+                                match result {
+                                    Ok(true) => {
+                                        if let Some(ref api_server) = api_server {
+                                            api_server.update_config(topology.config());
+                                        }
+
+                                        emit!(&VectorReloaded { config_paths: &config_paths })
+                                    },
+                                    Ok(false) | Err(()) => emit!(&VectorReloadFailed),
+                                }
+                                let _ = reply.send(result);
+                                if result.is_err() {
+                                    emit!(&VectorRecoveryFailed);
+                                    break SignalTo::Shutdown;
+                                }
+                                sources_finished = topology.sources_finished();
+                            }
                             _ => break signal,
                         }
                     }
@@ -1,11 +1,12 @@
 use crate::config::{ComponentKey, Resource};
-use crate::event::Event;
+use crate::event::{Event, Value};
 use futures::Stream;
+use lazy_static::lazy_static;
 use serde::{
     de::{Deserializer, Error, Visitor},
     Deserialize, Serialize,
 };
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf, sync::RwLock};
 use tracing::Span;
 pub use vector_core::buffers::*;
 
@@ -18,9 +19,9 @@ enum BufferConfigKind {
 }
 
 #[cfg(feature = "disk-buffer")]
-const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
+const ALL_FIELDS: [&str; 5] = ["type", "max_events", "max_size", "when_full", "priority"];
 #[cfg(not(feature = "disk-buffer"))]
-const ALL_FIELDS: [&str; 3] = ["type", "max_events", "when_full"];
+const ALL_FIELDS: [&str; 4] = ["type", "max_events", "when_full", "priority"];
 
 struct BufferConfigVisitor;
 
@@ -40,6 +41,7 @@ impl<'de> Visitor<'de> for BufferConfigVisitor {
         #[cfg(feature = "disk-buffer")]
         let mut max_size: Option<usize> = None;
         let mut when_full: Option<WhenFull> = None;
+        let mut priority: Option<BufferPriorityConfig> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
                 "type" => {
@@ -67,6 +69,12 @@ impl<'de> Visitor<'de> for BufferConfigVisitor {
                     }
                     when_full = Some(map.next_value()?);
                 }
+                "priority" => {
+                    if priority.is_some() {
+                        return Err(Error::duplicate_field("priority"));
+                    }
+                    priority = Some(map.next_value()?);
+                }
                 other => {
                     return Err(Error::unknown_field(other, &ALL_FIELDS));
                 }
@@ -80,12 +88,20 @@ impl<'de> Visitor<'de> for BufferConfigVisitor {
                 if max_size.is_some() {
                     return Err(Error::unknown_field(
                         "max_size",
-                        &["type", "max_events", "when_full"],
+                        &["type", "max_events", "when_full", "priority"],
                     ));
                 }
+                if let Some(priority) = &priority {
+                    if priority.weights.is_empty() || priority.weights.contains(&0) {
+                        return Err(Error::custom(
+                            "priority weights must be a non-empty list of positive integers",
+                        ));
+                    }
+                }
                 Ok(BufferConfig::Memory {
                     max_events: max_events.unwrap_or_else(BufferConfig::memory_max_events),
                     when_full,
+                    priority,
                 })
             }
             #[cfg(feature = "disk-buffer")]
@@ -96,6 +112,12 @@ impl<'de> Visitor<'de> for BufferConfigVisitor {
                         &["type", "max_size", "when_full"],
                     ));
                 }
+                if priority.is_some() {
+                    return Err(Error::unknown_field(
+                        "priority",
+                        &["type", "max_size", "when_full"],
+                    ));
+                }
                 Ok(BufferConfig::Disk {
                     max_size: max_size.ok_or_else(|| Error::missing_field("max_size"))?,
                     when_full,
@@ -123,6 +145,8 @@ pub enum BufferConfig {
         max_events: usize,
         #[serde(default)]
         when_full: WhenFull,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        priority: Option<BufferPriorityConfig>,
     },
     #[cfg(feature = "disk-buffer")]
     Disk {
@@ -137,10 +161,79 @@ impl Default for BufferConfig {
         BufferConfig::Memory {
             max_events: BufferConfig::memory_max_events(),
             when_full: Default::default(),
+            priority: None,
         }
     }
 }
 
+/// Splits a memory buffer into lanes by the priority of events, so that full
+/// buffers dropping the newest events drop those of the lowest priority first.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BufferPriorityConfig {
+    /// The log field or metric tag holding the priority of events, as an
+    /// integer from zero. Events without one have the lowest priority.
+    #[serde(default = "default_priority_field")]
+    pub field: String,
+    /// The draining weight of each lane, from the lowest priority to the
+    /// highest. Events of priorities past the last lane go in the last lane.
+    #[serde(default = "default_priority_weights")]
+    pub weights: Vec<usize>,
+}
+
+pub(crate) fn default_priority_field() -> String {
+    "priority".to_owned()
+}
+
+fn default_priority_weights() -> Vec<usize> {
+    vec![1, 2, 4]
+}
+
+impl BufferPriorityConfig {
+    fn priority(&self, event: &Event) -> usize {
+        event_priority(event, &self.field)
+    }
+}
+
+/// The priority of an event, held by the log field or metric tag `field` as
+/// an integer from zero. Events without one have the lowest priority.
+pub(crate) fn event_priority(event: &Event, field: &str) -> usize {
+    match event {
+        Event::Log(log) => match log.get(field) {
+            Some(Value::Integer(priority)) => (*priority).max(0) as usize,
+            _ => 0,
+        },
+        Event::Metric(metric) => metric
+            .tag_value(field)
+            .and_then(|priority| priority.parse().ok())
+            .unwrap_or(0),
+    }
+}
+
+lazy_static! {
+    static ref USAGE: RwLock<HashMap<ComponentKey, BufferUsage>> = RwLock::new(HashMap::new());
+}
+
+/// Makes the usage of the buffer of the sink `sink_id` available to
+/// [`utilization`], replacing that of any previous buffer of the sink.
+pub(crate) fn register_usage(sink_id: &ComponentKey, usage: BufferUsage) {
+    USAGE
+        .write()
+        .expect("poisoned lock")
+        .insert(sink_id.clone(), usage);
+}
+
+/// The fraction of the buffer of the sink `sink_id` in use, or `None` if the
+/// sink isn't running.
+#[cfg_attr(not(feature = "transforms-pressure_sample"), allow(dead_code))]
+pub(crate) fn utilization(sink_id: &ComponentKey) -> Option<f64> {
+    USAGE
+        .read()
+        .expect("poisoned lock")
+        .get(sink_id)
+        .and_then(BufferUsage::utilization)
+}
+
 pub(crate) type EventStream = Box<dyn Stream<Item = Event> + Unpin + Send>;
 
 impl BufferConfig {
@@ -160,6 +253,21 @@ impl BufferConfig {
             BufferConfig::Memory {
                 max_events,
                 when_full,
+                priority: Some(priority),
+            } => {
+                let priority = priority.clone();
+                return Ok(build_with_lanes(
+                    *max_events,
+                    *when_full,
+                    priority.weights.clone(),
+                    move |event: &Event| priority.priority(event),
+                    span,
+                ));
+            }
+            BufferConfig::Memory {
+                max_events,
+                when_full,
+                priority: None,
             } => Variant::Memory {
                 max_events: *max_events,
                 when_full: *when_full,
@@ -196,7 +304,8 @@ impl BufferConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::buffers::{BufferConfig, WhenFull};
+    use crate::buffers::{default_priority_weights, BufferConfig, BufferPriorityConfig, WhenFull};
+    use crate::event::{LogEvent, Metric, MetricKind, MetricValue};
 
     fn check(source: &str, config: BufferConfig) {
         let conf: BufferConfig = toml::from_str(source).unwrap();
@@ -212,6 +321,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 500,
                 when_full: WhenFull::Block,
+                priority: None,
             },
         );
 
@@ -223,6 +333,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 100,
                 when_full: WhenFull::Block,
+                priority: None,
             },
         );
 
@@ -234,6 +345,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 500,
                 when_full: WhenFull::DropNewest,
+                priority: None,
             },
         );
 
@@ -259,6 +371,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 100,
                 when_full: WhenFull::Block,
+                priority: None,
             },
         );
     }
@@ -272,7 +385,59 @@ mod test {
         let error = toml::from_str::<BufferConfig>(source).unwrap_err();
         assert_eq!(
             error.to_string(),
-            "unknown field `max_size`, expected one of `type`, `max_events`, `when_full` at line 1 column 1"
+            "unknown field `max_size`, expected one of `type`, `max_events`, `when_full`, `priority` at line 1 column 1"
+        );
+    }
+
+    #[test]
+    fn parse_priority() {
+        check(
+            r#"
+          when_full = "drop_newest"
+          priority.weights = [1, 10]
+          "#,
+            BufferConfig::Memory {
+                max_events: 500,
+                when_full: WhenFull::DropNewest,
+                priority: Some(BufferPriorityConfig {
+                    field: "priority".into(),
+                    weights: vec![1, 10],
+                }),
+            },
         );
+
+        let source = r#"
+    priority.weights = [1, 0]
+    "#;
+        assert!(toml::from_str::<BufferConfig>(source).is_err());
+    }
+
+    #[test]
+    fn event_priorities() {
+        let config = BufferPriorityConfig {
+            field: "level".into(),
+            weights: default_priority_weights(),
+        };
+
+        let mut log = LogEvent::from("error");
+        log.insert("level", 2);
+        assert_eq!(config.priority(&log.into()), 2);
+
+        let mut log = LogEvent::from("debug");
+        log.insert("level", -1);
+        assert_eq!(config.priority(&log.into()), 0);
+        assert_eq!(config.priority(&LogEvent::from("info").into()), 0);
+
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(
+            vec![("level".to_owned(), "1".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
+        assert_eq!(config.priority(&metric.into()), 1);
     }
 }
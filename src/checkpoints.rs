@@ -0,0 +1,194 @@
+use crate::config;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use vector_core::checkpoint::CheckpointStore;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The data directory holding the checkpoints. Defaults to the global
+    /// `data_dir` of the configuration.
+    #[structopt(long)]
+    data_dir: Option<PathBuf>,
+
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.toml` will be targeted.
+    #[structopt(
+        name = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        use_delimiter(true)
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[structopt(
+        name = "config-dir",
+        short = "C",
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        use_delimiter(true)
+    )]
+    config_dirs: Vec<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+enum Command {
+    /// List the components with checkpoints.
+    List,
+
+    /// Show the checkpointed positions of a component.
+    Show {
+        /// The ID of the component.
+        component: String,
+
+        /// Output the checkpoints as JSON.
+        #[structopt(long)]
+        json: bool,
+    },
+
+    /// Reset the checkpoints of a component, so that it starts over from its
+    /// configured initial position. Vector must not be running.
+    Reset {
+        /// The ID of the component.
+        component: String,
+
+        /// Only reset the position checkpointed under this key.
+        #[structopt(long)]
+        key: Option<String>,
+    },
+}
+
+impl Opts {
+    fn data_dir(&self) -> Result<PathBuf, Vec<String>> {
+        if let Some(data_dir) = &self.data_dir {
+            return Ok(data_dir.clone());
+        }
+
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| config::ConfigPath::File(path.clone(), None))
+            .chain(
+                self.config_dirs
+                    .iter()
+                    .map(|dir| config::ConfigPath::Dir(dir.clone())),
+            )
+            .collect::<Vec<_>>();
+        let paths = config::process_paths(&paths)
+            .ok_or_else(|| vec!["No configuration found to read `data_dir` from.".to_owned()])?;
+        let (builder, _) = config::load_builder_from_paths(&paths)?;
+
+        builder
+            .global
+            .data_dir
+            .ok_or_else(|| vec!["The configuration doesn't set `data_dir`.".to_owned()])
+    }
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let data_dir = match opts.data_dir() {
+        Ok(data_dir) => data_dir,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            return exitcode::CONFIG;
+        }
+    };
+    let store = CheckpointStore::new(data_dir);
+
+    let result = match &opts.command {
+        Command::List => list(&store),
+        Command::Show { component, json } => show(&store, component, *json),
+        Command::Reset { component, key } => reset(&store, component, key.as_deref()),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!(
+                "Failed to access checkpoints in {:?}: {}",
+                store.root(),
+                error
+            );
+            exitcode::IOERR
+        }
+    }
+}
+
+fn list(store: &CheckpointStore) -> std::io::Result<exitcode::ExitCode> {
+    for component in store.components()? {
+        println!("{}", component);
+    }
+    Ok(exitcode::OK)
+}
+
+fn show(
+    store: &CheckpointStore,
+    component: &str,
+    json: bool,
+) -> std::io::Result<exitcode::ExitCode> {
+    let checkpoints = store.namespace(component)?;
+
+    if json {
+        let checkpoints = checkpoints
+            .iter()
+            .collect::<std::collections::BTreeMap<_, _>>();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&checkpoints).expect("checkpoints are serializable")
+        );
+    } else if checkpoints.is_empty() {
+        eprintln!("No checkpoints for component {:?}.", component);
+    } else {
+        for (key, checkpoint) in checkpoints.iter() {
+            println!(
+                "{}\t{}\t{}",
+                key,
+                checkpoint.position,
+                checkpoint.updated_at.to_rfc3339()
+            );
+        }
+    }
+
+    Ok(exitcode::OK)
+}
+
+fn reset(
+    store: &CheckpointStore,
+    component: &str,
+    key: Option<&str>,
+) -> std::io::Result<exitcode::ExitCode> {
+    let reset = match key {
+        None => store.reset(component)?,
+        Some(key) => {
+            let mut checkpoints = store.namespace(component)?;
+            let found = checkpoints.get(key).is_some();
+            if found {
+                let mut transaction = checkpoints.transaction();
+                transaction.remove(key);
+                transaction.commit()?;
+            }
+            found
+        }
+    };
+
+    if reset {
+        println!("Reset checkpoints of component {:?}.", component);
+        Ok(exitcode::OK)
+    } else {
+        eprintln!("No checkpoints to reset for component {:?}.", component);
+        Ok(exitcode::DATAERR)
+    }
+}
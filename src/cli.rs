@@ -1,7 +1,11 @@
-use crate::{config, generate, get_version, graph, list, unit_test, validate};
+use crate::{
+    checkpoints, config, config_diff, generate, get_version, graph, list, unit_test, validate,
+};
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
+#[cfg(feature = "sinks-datadog")]
+use crate::replay;
 #[cfg(feature = "api-client")]
 use crate::tap;
 #[cfg(feature = "api-client")]
@@ -36,7 +40,10 @@ impl Opts {
             Some(SubCommand::Validate(_))
             | Some(SubCommand::Graph(_))
             | Some(SubCommand::Generate(_))
-            | Some(SubCommand::List(_)) => {
+            | Some(SubCommand::List(_))
+            | Some(SubCommand::Checkpoints(_))
+            | Some(SubCommand::Replay(_))
+            | Some(SubCommand::Config(_)) => {
                 if self.root.verbose == 0 {
                     (self.root.quiet + 1, self.root.verbose)
                 } else {
@@ -190,6 +197,17 @@ pub enum SubCommand {
     /// Output the topology as visual representation using the DOT language which can be rendered by GraphViz
     Graph(graph::Opts),
 
+    /// Inspect and reset the positions sources resume from.
+    Checkpoints(checkpoints::Opts),
+
+    /// Inspect configurations before applying them.
+    Config(ConfigSubCommand),
+
+    /// Submit the payloads a `datadog_logs` sink spooled again, once the
+    /// configuration that got them rejected is fixed.
+    #[cfg(feature = "sinks-datadog")]
+    Replay(replay::Opts),
+
     /// Display topology and metrics in the console, for a local or remote Vector instance
     #[cfg(feature = "api-client")]
     Top(top::Opts),
@@ -207,6 +225,14 @@ pub enum SubCommand {
     Vrl(vrl_cli::Opts),
 }
 
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub enum ConfigSubCommand {
+    /// Report which components reloading the running config into a candidate
+    /// config would add, remove, reconfigure, or restart, without applying it.
+    Diff(config_diff::Opts),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Color {
     Auto,
@@ -0,0 +1,426 @@
+use crate::{
+    codecs::{BoxedFramer, BoxedFramingError, FramingConfig},
+    internal_events::{DecoderGelfMessageDropped, GelfDropReason},
+};
+use bytes::{Buf, Bytes, BytesMut};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio_util::codec::Decoder;
+
+/// The magic bytes every GELF chunk starts with.
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+/// The length of a chunk header: the magic bytes, the message ID, the sequence
+/// number and the sequence count.
+const CHUNK_HEADER_LENGTH: usize = 12;
+/// The maximum number of chunks a message can be split into.
+const MAX_CHUNKS: u8 = 128;
+
+/// Config used to build a `ChunkedGelfCodec`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ChunkedGelfDecoderConfig {
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    chunked_gelf: ChunkedGelfDecoderOptions,
+}
+
+/// Options for building a `ChunkedGelfCodec`.
+#[derive(Debug, Clone, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+pub struct ChunkedGelfDecoderOptions {
+    /// How long to wait for the missing chunks of a message before dropping
+    /// it.
+    #[serde(default = "default_timeout_ms")]
+    #[derivative(Default(value = "default_timeout_ms()"))]
+    timeout_ms: u64,
+    /// The maximum number of messages waiting for chunks at a time. Chunks of
+    /// further messages are dropped.
+    #[serde(default = "default_pending_messages_limit")]
+    #[derivative(Default(value = "default_pending_messages_limit()"))]
+    pending_messages_limit: usize,
+    /// The maximum length of a message, once reassembled and decompressed.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    max_length: Option<usize>,
+    /// How messages are decompressed.
+    #[serde(default)]
+    decompression: GelfDecompression,
+}
+
+/// The GELF specification has senders drop incomplete messages after 5
+/// seconds.
+const fn default_timeout_ms() -> u64 {
+    5_000
+}
+
+const fn default_pending_messages_limit() -> usize {
+    1_000
+}
+
+/// How GELF messages are decompressed.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GelfDecompression {
+    /// Detect gzip and zlib compression from the leading bytes of the message.
+    Auto,
+    /// Decompress messages with gzip.
+    Gzip,
+    /// Decompress messages with zlib.
+    Zlib,
+    /// Pass messages through as-is.
+    None,
+}
+
+impl Default for GelfDecompression {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+#[typetag::serde(name = "chunked_gelf")]
+impl FramingConfig for ChunkedGelfDecoderConfig {
+    fn build(&self) -> crate::Result<BoxedFramer> {
+        Ok(Box::new(ChunkedGelfCodec::from(&self.chunked_gelf)))
+    }
+}
+
+/// A message whose chunks haven't all arrived yet.
+#[derive(Debug)]
+struct PendingMessage {
+    chunks: Vec<Option<Bytes>>,
+    received: usize,
+    first_seen: Instant,
+}
+
+/// A codec for reassembling GELF messages that were split into chunks, as
+/// specified in https://docs.graylog.org/docs/gelf#chunking, and
+/// decompressing them.
+///
+/// Each byte message must hold a single chunk, or a message that wasn't
+/// split. The chunks of incomplete messages are shared between clones of the
+/// codec, so chunks decoded by any clone are put together.
+#[derive(Debug, Clone)]
+pub struct ChunkedGelfCodec {
+    timeout: Duration,
+    pending_messages_limit: usize,
+    max_length: usize,
+    decompression: GelfDecompression,
+    pending: Arc<Mutex<HashMap<u64, PendingMessage>>>,
+    /// Whether the byte message has been flushed.
+    flushed: bool,
+}
+
+impl From<&ChunkedGelfDecoderOptions> for ChunkedGelfCodec {
+    fn from(options: &ChunkedGelfDecoderOptions) -> Self {
+        Self {
+            timeout: Duration::from_millis(options.timeout_ms),
+            pending_messages_limit: options.pending_messages_limit,
+            max_length: options.max_length.unwrap_or(usize::MAX),
+            decompression: options.decompression,
+            pending: Arc::default(),
+            flushed: false,
+        }
+    }
+}
+
+impl ChunkedGelfCodec {
+    /// Creates a new `ChunkedGelfCodec` with the default options.
+    pub fn new() -> Self {
+        Self::from(&ChunkedGelfDecoderOptions::default())
+    }
+
+    /// Stores a chunk, returning the message if it was the last one missing.
+    fn reassemble(&self, mut chunk: Bytes) -> Result<Option<Bytes>, BoxedFramingError> {
+        if chunk.len() < CHUNK_HEADER_LENGTH {
+            return Err(invalid_data("GELF chunk is shorter than its header.").into());
+        }
+
+        chunk.advance(CHUNK_MAGIC.len());
+        let message_id = chunk.get_u64();
+        let sequence_number = chunk.get_u8();
+        let sequence_count = chunk.get_u8();
+        if sequence_count == 0 || sequence_count > MAX_CHUNKS {
+            return Err(invalid_data(format!(
+                "GELF message is split into {} chunks, but must be split into 1 to {}.",
+                sequence_count, MAX_CHUNKS
+            ))
+            .into());
+        }
+        if sequence_number >= sequence_count {
+            return Err(invalid_data(format!(
+                "GELF chunk has sequence number {}, but its message has {} chunks.",
+                sequence_number, sequence_count
+            ))
+            .into());
+        }
+
+        let mut pending = self.pending.lock().unwrap();
+
+        let now = Instant::now();
+        let timeout = self.timeout;
+        pending.retain(|_, message| {
+            let expired = now.duration_since(message.first_seen) >= timeout;
+            if expired {
+                emit!(&DecoderGelfMessageDropped {
+                    reason: GelfDropReason::TimedOut,
+                    received_chunks: message.received,
+                    total_chunks: message.chunks.len(),
+                });
+            }
+            !expired
+        });
+
+        if !pending.contains_key(&message_id) && pending.len() >= self.pending_messages_limit {
+            emit!(&DecoderGelfMessageDropped {
+                reason: GelfDropReason::PendingMessagesLimit,
+                received_chunks: 1,
+                total_chunks: sequence_count as usize,
+            });
+            return Ok(None);
+        }
+
+        let message = pending.entry(message_id).or_insert_with(|| PendingMessage {
+            chunks: vec![None; sequence_count as usize],
+            received: 0,
+            first_seen: now,
+        });
+        if message.chunks.len() != sequence_count as usize {
+            return Err(invalid_data(format!(
+                "GELF chunk announces {} chunks, but earlier chunks of its message announced {}.",
+                sequence_count,
+                message.chunks.len()
+            ))
+            .into());
+        }
+
+        if message.chunks[sequence_number as usize]
+            .replace(chunk)
+            .is_none()
+        {
+            message.received += 1;
+        }
+
+        if message.received < message.chunks.len() {
+            return Ok(None);
+        }
+
+        let message = pending
+            .remove(&message_id)
+            .expect("message must be pending");
+        let length = message.chunks.iter().flatten().map(Bytes::len).sum();
+        let mut buf = BytesMut::with_capacity(length);
+        for chunk in message.chunks.into_iter().flatten() {
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(Some(buf.freeze()))
+    }
+
+    fn decompress(&self, message: Bytes) -> Result<Bytes, BoxedFramingError> {
+        let decompression = match self.decompression {
+            GelfDecompression::Auto if message.starts_with(&[0x1f, 0x8b]) => {
+                GelfDecompression::Gzip
+            }
+            // A zlib header starts with the deflate compression method, and
+            // is a multiple of 31 when read as a 16-bit integer.
+            GelfDecompression::Auto
+                if message.len() >= 2
+                    && message[0] & 0x0f == 0x08
+                    && u16::from_be_bytes([message[0], message[1]]) % 31 == 0 =>
+            {
+                GelfDecompression::Zlib
+            }
+            GelfDecompression::Auto => GelfDecompression::None,
+            decompression => decompression,
+        };
+
+        let reader: Box<dyn Read> = match decompression {
+            GelfDecompression::Gzip => Box::new(GzDecoder::new(message.as_ref())),
+            GelfDecompression::Zlib => Box::new(ZlibDecoder::new(message.as_ref())),
+            _ => {
+                if message.len() > self.max_length {
+                    return Err(self.too_long().into());
+                }
+                return Ok(message);
+            }
+        };
+
+        // Read one byte past the limit to tell whether it was exceeded.
+        let limit = self.max_length.saturating_add(1) as u64;
+        let mut buf = Vec::new();
+        reader.take(limit).read_to_end(&mut buf)?;
+        if buf.len() > self.max_length {
+            return Err(self.too_long().into());
+        }
+        Ok(buf.into())
+    }
+
+    fn too_long(&self) -> io::Error {
+        invalid_data(format!(
+            "GELF message is longer than the maximum length of {} bytes.",
+            self.max_length
+        ))
+    }
+}
+
+impl Default for ChunkedGelfCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+impl Decoder for ChunkedGelfCodec {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, _src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.flushed = false;
+        Ok(None)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.flushed {
+            return Ok(None);
+        }
+        self.flushed = true;
+
+        let frame = src.split().freeze();
+        let message = if frame.starts_with(&CHUNK_MAGIC) {
+            match self.reassemble(frame)? {
+                Some(message) => message,
+                None => return Ok(None),
+            }
+        } else {
+            frame
+        };
+
+        self.decompress(message).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use futures::StreamExt;
+    use std::io::Write;
+    use tokio_util::codec::FramedRead;
+
+    fn chunk(message_id: u64, sequence_number: u8, sequence_count: u8, data: &[u8]) -> BytesMut {
+        let mut chunk = BytesMut::from(&CHUNK_MAGIC[..]);
+        chunk.extend_from_slice(&message_id.to_be_bytes());
+        chunk.extend_from_slice(&[sequence_number, sequence_count]);
+        chunk.extend_from_slice(data);
+        chunk
+    }
+
+    fn decode(codec: &mut ChunkedGelfCodec, mut input: BytesMut) -> Option<Bytes> {
+        assert_eq!(codec.decode(&mut input).unwrap(), None);
+        codec.decode_eof(&mut input).unwrap()
+    }
+
+    #[test]
+    fn passes_through_unchunked_messages() {
+        let mut codec = ChunkedGelfCodec::new();
+        assert_eq!(
+            decode(&mut codec, BytesMut::from(r#"{"short_message":"foo"}"#)).unwrap(),
+            r#"{"short_message":"foo"}"#
+        );
+    }
+
+    #[test]
+    fn reassembles_chunks_out_of_order() {
+        let mut codec = ChunkedGelfCodec::new();
+        assert_eq!(decode(&mut codec, chunk(1, 2, 3, b"baz")), None);
+        assert_eq!(decode(&mut codec.clone(), chunk(2, 0, 2, b"other")), None);
+        assert_eq!(decode(&mut codec.clone(), chunk(1, 0, 3, b"foo")), None);
+        assert_eq!(
+            decode(&mut codec.clone(), chunk(1, 1, 3, b"bar")).unwrap(),
+            "foobarbaz"
+        );
+        assert_eq!(codec.pending.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn drops_timed_out_messages() {
+        let mut codec = ChunkedGelfCodec::from(&ChunkedGelfDecoderOptions {
+            timeout_ms: 0,
+            ..Default::default()
+        });
+        assert_eq!(decode(&mut codec, chunk(1, 0, 2, b"foo")), None);
+        assert_eq!(decode(&mut codec.clone(), chunk(1, 1, 2, b"bar")), None);
+        let pending = codec.pending.lock().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[&1].received, 1);
+    }
+
+    #[test]
+    fn limits_pending_messages() {
+        let mut codec = ChunkedGelfCodec::from(&ChunkedGelfDecoderOptions {
+            pending_messages_limit: 1,
+            ..Default::default()
+        });
+        assert_eq!(decode(&mut codec, chunk(1, 0, 2, b"foo")), None);
+        assert_eq!(decode(&mut codec.clone(), chunk(2, 0, 2, b"bar")), None);
+        assert_eq!(decode(&mut codec.clone(), chunk(2, 1, 2, b"baz")), None);
+        assert_eq!(
+            decode(&mut codec.clone(), chunk(1, 1, 2, b"bar")).unwrap(),
+            "foobar"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_chunks() {
+        let mut codec = ChunkedGelfCodec::new();
+        let mut input = BytesMut::from(&[0x1e, 0x0f, 0x00][..]);
+        assert!(codec.decode_eof(&mut input).is_err());
+
+        for mut input in [
+            chunk(1, 0, 0, b"foo"),
+            chunk(1, 0, 129, b"foo"),
+            chunk(1, 2, 2, b"foo"),
+        ] {
+            assert!(codec.clone().decode_eof(&mut input).is_err());
+        }
+    }
+
+    #[test]
+    fn decompresses_messages() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello world").unwrap();
+        let compressed = encoder.finish().unwrap();
+        let (first, second) = compressed.split_at(compressed.len() / 2);
+
+        let mut codec = ChunkedGelfCodec::new();
+        assert_eq!(decode(&mut codec, chunk(1, 0, 2, first)), None);
+        assert_eq!(
+            decode(&mut codec.clone(), chunk(1, 1, 2, second)).unwrap(),
+            "hello world"
+        );
+
+        let mut codec = ChunkedGelfCodec::from(&ChunkedGelfDecoderOptions {
+            max_length: Some(5),
+            ..Default::default()
+        });
+        let mut input = BytesMut::from(&compressed[..]);
+        assert!(codec.decode_eof(&mut input).is_err());
+    }
+
+    #[tokio::test]
+    async fn decode_frame_reader() {
+        let input = chunk(1, 0, 1, b"foo");
+        let mut reader = FramedRead::new(input.as_ref(), ChunkedGelfCodec::new());
+
+        assert_eq!(reader.next().await.unwrap().unwrap(), "foo");
+        assert!(reader.next().await.is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, io};
+use tokio_util::codec::Encoder;
+
+/// How encoded events are delimited when written to a byte stream, mirroring
+/// the framers that split them up on the way in.
+#[derive(Deserialize, Serialize, Debug, Derivative, Clone, PartialEq)]
+#[derivative(Default)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum FramingEncoder {
+    /// Each event is followed by a newline.
+    #[derivative(Default)]
+    NewlineDelimited,
+    /// Each event is followed by the given character.
+    CharacterDelimited {
+        /// The character following each event.
+        delimiter: char,
+    },
+    /// Each event is preceded by its length, as a 4 bytes big-endian integer.
+    LengthDelimited,
+    /// Each event is preceded by its length, as a varint, as when streaming
+    /// protobuf messages.
+    VarintLengthDelimited,
+}
+
+impl FramingEncoder {
+    /// Appends a frame holding `frame` to `buffer`.
+    pub fn frame(&self, frame: Bytes, buffer: &mut BytesMut) -> io::Result<()> {
+        match self {
+            Self::NewlineDelimited => {
+                buffer.reserve(frame.len() + 1);
+                buffer.put(frame);
+                buffer.put_u8(b'\n');
+            }
+            Self::CharacterDelimited { delimiter } => {
+                let mut encoded = [0; 4];
+                let delimiter = delimiter.encode_utf8(&mut encoded).as_bytes();
+                buffer.reserve(frame.len() + delimiter.len());
+                buffer.put(frame);
+                buffer.put_slice(delimiter);
+            }
+            Self::LengthDelimited => {
+                let length = u32::try_from(frame.len()).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Event exceeds the maximum frame length.",
+                    )
+                })?;
+                buffer.reserve(frame.len() + 4);
+                buffer.put_u32(length);
+                buffer.put(frame);
+            }
+            Self::VarintLengthDelimited => {
+                let mut length = frame.len() as u64;
+                buffer.reserve(frame.len() + 10);
+                while length >= 0x80 {
+                    buffer.put_u8(length as u8 | 0x80);
+                    length >>= 7;
+                }
+                buffer.put_u8(length as u8);
+                buffer.put(frame);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Encoder<Bytes> for FramingEncoder {
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: Bytes, buffer: &mut BytesMut) -> io::Result<()> {
+        self.frame(frame, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codecs::VarintLengthDelimitedCodec;
+    use tokio_util::codec::Decoder;
+
+    fn frame(framing: &FramingEncoder, frame: &str) -> BytesMut {
+        let mut buffer = BytesMut::new();
+        framing
+            .frame(Bytes::copy_from_slice(frame.as_bytes()), &mut buffer)
+            .unwrap();
+        buffer
+    }
+
+    #[test]
+    fn encodes_frames() {
+        assert_eq!(frame(&FramingEncoder::NewlineDelimited, "foo"), "foo\n");
+        assert_eq!(
+            frame(
+                &FramingEncoder::CharacterDelimited { delimiter: '€' },
+                "foo"
+            ),
+            "foo€"
+        );
+        assert_eq!(
+            frame(&FramingEncoder::LengthDelimited, "foo"),
+            &b"\x00\x00\x00\x03foo"[..]
+        );
+        assert_eq!(
+            frame(&FramingEncoder::VarintLengthDelimited, "foo"),
+            &b"\x03foo"[..]
+        );
+    }
+
+    #[test]
+    fn encodes_varints_decoded_back() {
+        let message = "x".repeat(300);
+        let mut buffer = frame(&FramingEncoder::VarintLengthDelimited, &message);
+        assert_eq!(&buffer[..2], b"\xac\x02");
+
+        let mut decoder = VarintLengthDelimitedCodec::new();
+        assert_eq!(decoder.decode(&mut buffer).unwrap().unwrap(), message);
+    }
+}
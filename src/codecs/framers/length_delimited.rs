@@ -5,26 +5,88 @@ use tokio_util::codec::Decoder;
 
 /// Config used to build a `LengthDelimitedCodec`.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct LengthDelimitedDecoderConfig;
+pub struct LengthDelimitedDecoderConfig {
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    length_delimited: LengthDelimitedDecoderOptions,
+}
+
+/// Options for building a `LengthDelimitedCodec`.
+#[derive(Debug, Clone, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+pub struct LengthDelimitedDecoderOptions {
+    /// The number of bytes of the length header.
+    #[serde(default = "default_length_field_length")]
+    #[derivative(Default(value = "default_length_field_length()"))]
+    pub length_field_length: usize,
+    /// Whether the length header is encoded in little-endian byte order
+    /// rather than network byte order.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub little_endian: bool,
+    /// The maximum frame length limit.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    pub max_length: Option<usize>,
+}
+
+const fn default_length_field_length() -> usize {
+    4
+}
 
 #[typetag::serde(name = "length_delimited")]
 impl FramingConfig for LengthDelimitedDecoderConfig {
     fn build(&self) -> crate::Result<BoxedFramer> {
-        Ok(Box::new(LengthDelimitedCodec::new()))
+        let length_field_length = self.length_delimited.length_field_length;
+        if !(1..=8).contains(&length_field_length) {
+            return Err(format!(
+                "`length_field_length` must be between 1 and 8 bytes, got {}",
+                length_field_length
+            )
+            .into());
+        }
+
+        Ok(Box::new(LengthDelimitedCodec::new_with_options(
+            self.length_delimited.clone(),
+        )))
     }
 }
 
 /// A codec for handling bytes sequences whose length is encoded in a frame head.
 ///
-/// Currently, this expects a length header in 32-bit MSB by default; options to
-/// control the format of the header can be added in the future.
+/// By default, this expects a length header in 32-bit MSB; the size and byte
+/// order of the header can be changed with `LengthDelimitedDecoderOptions`.
 #[derive(Debug)]
-pub struct LengthDelimitedCodec(tokio_util::codec::LengthDelimitedCodec);
+pub struct LengthDelimitedCodec {
+    codec: tokio_util::codec::LengthDelimitedCodec,
+    options: LengthDelimitedDecoderOptions,
+}
 
 impl LengthDelimitedCodec {
     /// Creates a new `LengthDelimitedCodec`.
     pub fn new() -> Self {
-        Self(tokio_util::codec::LengthDelimitedCodec::new())
+        Self::new_with_options(LengthDelimitedDecoderOptions::default())
+    }
+
+    /// Creates a `LengthDelimitedCodec` with the given header format and
+    /// frame length limit.
+    pub fn new_with_options(options: LengthDelimitedDecoderOptions) -> Self {
+        let mut builder = tokio_util::codec::LengthDelimitedCodec::builder();
+        builder.length_field_length(options.length_field_length);
+        if options.little_endian {
+            builder.little_endian();
+        }
+        if let Some(max_length) = options.max_length {
+            builder.max_frame_length(max_length);
+        }
+
+        Self {
+            codec: builder.new_codec(),
+            options,
+        }
     }
 }
 
@@ -45,7 +107,7 @@ impl Clone for LengthDelimitedCodec {
         // leave out the implementation. All of its internal fields implement
         // `Clone`, so adding an implementation for `Clone` could be contributed
         // to the upstream repo easily by adding it to the `derive` macro.
-        Self::new()
+        Self::new_with_options(self.options.clone())
     }
 }
 
@@ -54,14 +116,14 @@ impl Decoder for LengthDelimitedCodec {
     type Error = BoxedFramingError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        self.0
+        self.codec
             .decode(src)
             .map(|bytes| bytes.map(BytesMut::freeze))
             .map_err(Into::into)
     }
 
     fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        self.0
+        self.codec
             .decode_eof(src)
             .map(|bytes| bytes.map(BytesMut::freeze))
             .map_err(Into::into)
@@ -159,4 +221,29 @@ mod tests {
         assert_eq!(decoder.decode_eof(&mut input).unwrap().unwrap(), "bar");
         assert_eq!(decoder.decode_eof(&mut input).unwrap(), None);
     }
+
+    #[test]
+    fn decode_frames_with_configured_header() {
+        let mut input = BytesMut::from("\x03\x00foo\x03\x00bar");
+        let mut decoder = LengthDelimitedCodec::new_with_options(LengthDelimitedDecoderOptions {
+            length_field_length: 2,
+            little_endian: true,
+            max_length: None,
+        });
+
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "foo");
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "bar");
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_frame_exceeding_max_length() {
+        let mut input = BytesMut::from("\x00\x00\x00\x05hello");
+        let mut decoder = LengthDelimitedCodec::new_with_options(LengthDelimitedDecoderOptions {
+            max_length: Some(4),
+            ..Default::default()
+        });
+
+        assert!(decoder.decode(&mut input).is_err());
+    }
 }
@@ -2,15 +2,28 @@
 
 mod bytes;
 mod character_delimited;
+mod chunked_gelf;
+mod encoder;
 mod length_delimited;
 mod newline_delimited;
 mod octet_counting;
+mod varint_length_delimited;
 
 pub use self::bytes::{BytesCodec, BytesDecoderConfig};
 pub use character_delimited::{CharacterDelimitedCodec, CharacterDelimitedDecoderConfig};
-pub use length_delimited::{LengthDelimitedCodec, LengthDelimitedDecoderConfig};
+pub use chunked_gelf::{
+    ChunkedGelfCodec, ChunkedGelfDecoderConfig, ChunkedGelfDecoderOptions, GelfDecompression,
+};
+pub use encoder::FramingEncoder;
+pub use length_delimited::{
+    LengthDelimitedCodec, LengthDelimitedDecoderConfig, LengthDelimitedDecoderOptions,
+};
 pub use newline_delimited::{NewlineDelimitedCodec, NewlineDelimitedDecoderConfig};
 pub use octet_counting::{OctetCountingCodec, OctetCountingDecoderConfig};
+pub use varint_length_delimited::{
+    VarintLengthDelimitedCodec, VarintLengthDelimitedDecoderConfig,
+    VarintLengthDelimitedDecoderOptions,
+};
 
 use crate::sources::util::TcpError;
 use ::bytes::Bytes;
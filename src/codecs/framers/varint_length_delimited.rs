@@ -0,0 +1,187 @@
+use crate::codecs::{BoxedFramer, BoxedFramingError, FramingConfig};
+use bytes::{Buf, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, io};
+use tokio_util::codec::Decoder;
+
+/// The longest encoding of a 64-bit varint.
+const MAX_VARINT_LENGTH: usize = 10;
+
+/// Config used to build a `VarintLengthDelimitedCodec`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct VarintLengthDelimitedDecoderConfig {
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    varint_length_delimited: VarintLengthDelimitedDecoderOptions,
+}
+
+/// Options for building a `VarintLengthDelimitedCodec`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct VarintLengthDelimitedDecoderOptions {
+    /// The maximum frame length limit.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    pub max_length: Option<usize>,
+}
+
+#[typetag::serde(name = "varint_length_delimited")]
+impl FramingConfig for VarintLengthDelimitedDecoderConfig {
+    fn build(&self) -> crate::Result<BoxedFramer> {
+        Ok(Box::new(VarintLengthDelimitedCodec::new_with_max_length(
+            self.varint_length_delimited
+                .max_length
+                .unwrap_or(DEFAULT_MAX_LENGTH),
+        )))
+    }
+}
+
+/// The default frame length limit, matching the one of `LengthDelimitedCodec`.
+const DEFAULT_MAX_LENGTH: usize = 8 * 1024 * 1024;
+
+/// A codec for handling bytes sequences whose length is encoded as a varint in
+/// a frame head, as when streaming protobuf messages with
+/// `writeDelimitedTo`.
+#[derive(Debug, Clone)]
+pub struct VarintLengthDelimitedCodec {
+    max_length: usize,
+}
+
+impl VarintLengthDelimitedCodec {
+    /// Creates a new `VarintLengthDelimitedCodec`.
+    pub const fn new() -> Self {
+        Self::new_with_max_length(DEFAULT_MAX_LENGTH)
+    }
+
+    /// Creates a `VarintLengthDelimitedCodec` with a frame length limit.
+    pub const fn new_with_max_length(max_length: usize) -> Self {
+        Self { max_length }
+    }
+
+    /// Reads the length header at the start of `src`, returning its length
+    /// along with the frame length it holds, if it's complete.
+    fn decode_header(&self, src: &[u8]) -> io::Result<Option<(usize, usize)>> {
+        let mut length = 0u64;
+        for (index, byte) in src.iter().take(MAX_VARINT_LENGTH).enumerate() {
+            length |= u64::from(byte & 0x7f) << (7 * index);
+            if byte & 0x80 == 0 {
+                return match usize::try_from(length) {
+                    Ok(length) if length <= self.max_length => Ok(Some((index + 1, length))),
+                    _ => Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Frame of {} bytes exceeds the maximum length of {} bytes.",
+                            length, self.max_length
+                        ),
+                    )),
+                };
+            }
+        }
+
+        if src.len() >= MAX_VARINT_LENGTH {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Frame length header is not a valid varint.",
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl Default for VarintLengthDelimitedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for VarintLengthDelimitedCodec {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode_header(src)? {
+            Some((header_length, length)) if src.len() >= header_length + length => {
+                src.advance(header_length);
+                Ok(Some(src.split_to(length).freeze()))
+            }
+            Some((header_length, length)) => {
+                src.reserve(header_length + length - src.len());
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(
+                io::Error::new(io::ErrorKind::UnexpectedEof, "bytes remaining on stream").into(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_frames() {
+        let mut input = BytesMut::from("\x03foo\x00\x03bar");
+        let mut decoder = VarintLengthDelimitedCodec::new();
+
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "foo");
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "");
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), "bar");
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_multi_byte_header() {
+        let message = "x".repeat(300);
+        let mut input = BytesMut::from(&b"\xac\x02"[..]);
+        input.extend_from_slice(message.as_bytes());
+        let mut decoder = VarintLengthDelimitedCodec::new();
+
+        assert_eq!(decoder.decode(&mut input).unwrap().unwrap(), message);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn decode_frame_ignore_unexpected_eof() {
+        let mut decoder = VarintLengthDelimitedCodec::new();
+
+        let mut input = BytesMut::from("\x03fo");
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+        let mut input = BytesMut::from(&b"\xac"[..]);
+        assert_eq!(decoder.decode(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_eof_frame_unexpected_eof() {
+        let mut input = BytesMut::from("\x03foo\x03fo");
+        let mut decoder = VarintLengthDelimitedCodec::new();
+
+        assert_eq!(decoder.decode_eof(&mut input).unwrap().unwrap(), "foo");
+        assert!(decoder.decode_eof(&mut input).is_err());
+    }
+
+    #[test]
+    fn decode_frame_exceeding_max_length() {
+        let mut input = BytesMut::from("\x05hello");
+        let mut decoder = VarintLengthDelimitedCodec::new_with_max_length(4);
+
+        assert!(decoder.decode(&mut input).is_err());
+    }
+
+    #[test]
+    fn decode_invalid_header() {
+        let mut input = BytesMut::from(&[0xff; 11][..]);
+        let mut decoder = VarintLengthDelimitedCodec::new();
+
+        assert!(decoder.decode(&mut input).is_err());
+    }
+}
@@ -131,13 +131,13 @@ pub struct DecodingConfig {
     /// The framing config.
     framing: Box<dyn FramingConfig>,
     /// The decoding config.
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 impl DecodingConfig {
     /// Creates a new `DecodingConfig` with the provided `FramingConfig` and
-    /// `ParserConfig`.
-    pub fn new(framing: Box<dyn FramingConfig>, decoding: Box<dyn ParserConfig>) -> Self {
+    /// `ParserSettings`.
+    pub fn new(framing: Box<dyn FramingConfig>, decoding: ParserSettings) -> Self {
         Self { framing, decoding }
     }
 
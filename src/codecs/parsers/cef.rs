@@ -0,0 +1,422 @@
+use crate::{
+    codecs::{BoxedParser, Parser, ParserConfig},
+    config::log_schema,
+    event::{Event, LogEvent},
+};
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use std::collections::HashMap;
+
+/// The fields the CEF header is parsed into, in order.
+const HEADER_FIELDS: [&str; 7] = [
+    "cefVersion",
+    "deviceVendor",
+    "deviceProduct",
+    "deviceVersion",
+    "deviceEventClassId",
+    "name",
+    "severity",
+];
+
+/// The full names of the extension keys defined by the CEF specification.
+const STANDARD_NAMES: &[(&str, &str)] = &[
+    ("act", "deviceAction"),
+    ("app", "applicationProtocol"),
+    ("c6a1", "deviceCustomIPv6Address1"),
+    ("c6a1Label", "deviceCustomIPv6Address1Label"),
+    ("c6a2", "deviceCustomIPv6Address2"),
+    ("c6a2Label", "deviceCustomIPv6Address2Label"),
+    ("c6a3", "deviceCustomIPv6Address3"),
+    ("c6a3Label", "deviceCustomIPv6Address3Label"),
+    ("c6a4", "deviceCustomIPv6Address4"),
+    ("c6a4Label", "deviceCustomIPv6Address4Label"),
+    ("cat", "deviceEventCategory"),
+    ("cfp1", "deviceCustomFloatingPoint1"),
+    ("cfp1Label", "deviceCustomFloatingPoint1Label"),
+    ("cfp2", "deviceCustomFloatingPoint2"),
+    ("cfp2Label", "deviceCustomFloatingPoint2Label"),
+    ("cfp3", "deviceCustomFloatingPoint3"),
+    ("cfp3Label", "deviceCustomFloatingPoint3Label"),
+    ("cfp4", "deviceCustomFloatingPoint4"),
+    ("cfp4Label", "deviceCustomFloatingPoint4Label"),
+    ("cn1", "deviceCustomNumber1"),
+    ("cn1Label", "deviceCustomNumber1Label"),
+    ("cn2", "deviceCustomNumber2"),
+    ("cn2Label", "deviceCustomNumber2Label"),
+    ("cn3", "deviceCustomNumber3"),
+    ("cn3Label", "deviceCustomNumber3Label"),
+    ("cnt", "baseEventCount"),
+    ("cs1", "deviceCustomString1"),
+    ("cs1Label", "deviceCustomString1Label"),
+    ("cs2", "deviceCustomString2"),
+    ("cs2Label", "deviceCustomString2Label"),
+    ("cs3", "deviceCustomString3"),
+    ("cs3Label", "deviceCustomString3Label"),
+    ("cs4", "deviceCustomString4"),
+    ("cs4Label", "deviceCustomString4Label"),
+    ("cs5", "deviceCustomString5"),
+    ("cs5Label", "deviceCustomString5Label"),
+    ("cs6", "deviceCustomString6"),
+    ("cs6Label", "deviceCustomString6Label"),
+    ("dhost", "destinationHostName"),
+    ("dmac", "destinationMacAddress"),
+    ("dntdom", "destinationNtDomain"),
+    ("dpid", "destinationProcessId"),
+    ("dpriv", "destinationUserPrivileges"),
+    ("dproc", "destinationProcessName"),
+    ("dpt", "destinationPort"),
+    ("dst", "destinationAddress"),
+    ("dtz", "deviceTimeZone"),
+    ("duid", "destinationUserId"),
+    ("duser", "destinationUserName"),
+    ("dvc", "deviceAddress"),
+    ("dvchost", "deviceHostName"),
+    ("dvcmac", "deviceMacAddress"),
+    ("dvcpid", "deviceProcessId"),
+    ("end", "endTime"),
+    ("fname", "fileName"),
+    ("fsize", "fileSize"),
+    ("in", "bytesIn"),
+    ("msg", "message"),
+    ("out", "bytesOut"),
+    ("outcome", "eventOutcome"),
+    ("proto", "transportProtocol"),
+    ("request", "requestUrl"),
+    ("rt", "deviceReceiptTime"),
+    ("shost", "sourceHostName"),
+    ("smac", "sourceMacAddress"),
+    ("sntdom", "sourceNtDomain"),
+    ("spid", "sourceProcessId"),
+    ("spriv", "sourceUserPrivileges"),
+    ("sproc", "sourceProcessName"),
+    ("spt", "sourcePort"),
+    ("src", "sourceAddress"),
+    ("start", "startTime"),
+    ("suid", "sourceUserId"),
+    ("suser", "sourceUserName"),
+];
+
+/// Config used to build a `CefParser`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CefParserConfig {
+    /// Whether the extension keys defined by the CEF specification are
+    /// renamed to their full names, e.g. `src` to `sourceAddress`.
+    #[serde(default)]
+    pub standard_names: bool,
+    /// Whether custom extensions are renamed after their label, e.g. the value
+    /// of `cs1` is inserted as `Rule` when the event contains `cs1Label=Rule`.
+    #[serde(default)]
+    pub custom_labels: bool,
+    /// Extension keys renamed to the given field paths, taking precedence over
+    /// the standard names.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dictionary: HashMap<String, String>,
+}
+
+#[typetag::serde(name = "cef")]
+impl ParserConfig for CefParserConfig {
+    fn build(&self) -> crate::Result<BoxedParser> {
+        Ok(Box::new(CefParser::from(self)))
+    }
+}
+
+/// Parser that builds an `Event` from a byte frame containing a message in
+/// the Common Event Format.
+#[derive(Debug, Clone, Default)]
+pub struct CefParser {
+    names: HashMap<String, String>,
+    custom_labels: bool,
+}
+
+impl From<&CefParserConfig> for CefParser {
+    fn from(config: &CefParserConfig) -> Self {
+        let mut names = HashMap::new();
+        if config.standard_names {
+            names.extend(
+                STANDARD_NAMES
+                    .iter()
+                    .map(|(key, name)| (key.to_string(), name.to_string())),
+            );
+        }
+        names.extend(config.dictionary.clone());
+
+        Self {
+            names,
+            custom_labels: config.custom_labels,
+        }
+    }
+}
+
+impl Parser for CefParser {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let line = std::str::from_utf8(&bytes)?.trim();
+        if line.is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        // Messages are commonly wrapped in a syslog header, which is skipped.
+        let start = line
+            .find("CEF:")
+            .ok_or("Message doesn't contain a CEF header.")?;
+        let (header, extension) = split_header(&line[start + 4..], HEADER_FIELDS.len())
+            .ok_or("Incomplete CEF header.")?;
+        let pairs = parse_extension(extension)?;
+
+        let mut log = LogEvent::default();
+        for (field, value) in HEADER_FIELDS.iter().zip(header) {
+            log.insert_flat(*field, value);
+        }
+
+        let labels = if self.custom_labels {
+            custom_labels(&pairs)
+        } else {
+            HashMap::new()
+        };
+        for (key, value) in &pairs {
+            if key
+                .strip_suffix("Label")
+                .map_or(false, |base| labels.contains_key(base))
+            {
+                continue;
+            }
+            match (labels.get(key), self.names.get(*key)) {
+                (Some(label), _) => log.insert_flat(*label, value.clone()),
+                (None, Some(name)) => log.insert(name.as_str(), value.clone()),
+                (None, None) => log.insert_flat(*key, value.clone()),
+            };
+        }
+
+        let timestamp_key = log_schema().timestamp_key();
+        if !log.contains(timestamp_key) {
+            log.insert(timestamp_key, Utc::now());
+        }
+
+        Ok(smallvec![Event::Log(log)])
+    }
+}
+
+/// Splits the given number of `|` terminated fields off a CEF or LEEF header,
+/// unescaping `\|` and `\\` within them, and returns them with the remainder of
+/// the message.
+pub(super) fn split_header(input: &str, count: usize) -> Option<(Vec<String>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut field = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, next)) if next == '|' || next == '\\' => field.push(next),
+                Some((_, next)) => {
+                    field.push('\\');
+                    field.push(next);
+                }
+                None => field.push('\\'),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() == count {
+                    return Some((fields, &input[index + 1..]));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+
+    None
+}
+
+/// Parses the `key=value` pairs of a CEF extension.
+///
+/// Values may contain spaces, so each one runs until the space preceding the
+/// next unescaped `=` and its key. An `=` that isn't preceded by a valid key is
+/// considered part of the value.
+fn parse_extension(extension: &str) -> crate::Result<Vec<(&str, String)>> {
+    let extension = extension.trim();
+    let mut pairs = Vec::new();
+    // The key being read, and the index its value starts at.
+    let mut current: Option<(&str, usize)> = None;
+    let mut escaped = false;
+
+    for (index, byte) in extension.bytes().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match byte {
+            b'\\' => escaped = true,
+            b'=' => {
+                let value_start = current.map_or(0, |(_, start)| start);
+                let key_start = match extension[value_start..index].rfind(' ') {
+                    Some(space) => value_start + space + 1,
+                    None if current.is_none() => 0,
+                    None => continue,
+                };
+                let key = &extension[key_start..index];
+                if !is_key(key) {
+                    if current.is_none() {
+                        return Err(format!("Invalid CEF extension key {:?}.", key).into());
+                    }
+                    continue;
+                }
+
+                match current {
+                    Some((previous, start)) => {
+                        pairs.push((
+                            previous,
+                            unescape_value(extension[start..key_start].trim_end()),
+                        ));
+                    }
+                    None if key_start > 0 => {
+                        return Err("CEF extension doesn't start with a key.".into());
+                    }
+                    None => {}
+                }
+                current = Some((key, index + 1));
+            }
+            _ => {}
+        }
+    }
+
+    match current {
+        Some((key, start)) => pairs.push((key, unescape_value(&extension[start..]))),
+        None if !extension.is_empty() => {
+            return Err("CEF extension doesn't contain any key.".into());
+        }
+        None => {}
+    }
+
+    Ok(pairs)
+}
+
+fn is_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '[' | ']'))
+}
+
+/// Unescapes `\\`, `\=`, `\n` and `\r` within an extension value. Other
+/// backslashes are kept as-is.
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(next) if next == '\\' || next == '=' => unescaped.push(next),
+            Some(next) => {
+                unescaped.push('\\');
+                unescaped.push(next);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// The labels of the custom extensions, e.g. `cs1` for `cs1Label`, when the
+/// labelled extension is set as well.
+fn custom_labels<'a>(pairs: &'a [(&'a str, String)]) -> HashMap<&'a str, &'a str> {
+    pairs
+        .iter()
+        .filter_map(|(key, value)| {
+            key.strip_suffix("Label")
+                .filter(|base| pairs.iter().any(|(key, _)| key == base))
+                .map(|base| (base, value.as_str()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    fn parse(config: &CefParserConfig, message: &'static str) -> LogEvent {
+        let mut events = CefParser::from(config).parse(Bytes::from(message)).unwrap();
+        assert_eq!(events.len(), 1);
+        events.pop().unwrap().into_log()
+    }
+
+    #[test]
+    fn parses_header_and_extension() {
+        let log = parse(
+            &CefParserConfig::default(),
+            r#"<134>Sep 19 08:26:10 host CEF:0|Security|threat\|manager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 msg=Detected a \= sign\nand a \\ spt=1232"#,
+        );
+
+        assert_eq!(log["cefVersion"], "0".into());
+        assert_eq!(log["deviceVendor"], "Security".into());
+        assert_eq!(log["deviceProduct"], "threat|manager".into());
+        assert_eq!(log["deviceVersion"], "1.0".into());
+        assert_eq!(log["deviceEventClassId"], "100".into());
+        assert_eq!(log["name"], "worm successfully stopped".into());
+        assert_eq!(log["severity"], "10".into());
+        assert_eq!(log["src"], "10.0.0.1".into());
+        assert_eq!(log["dst"], "2.1.2.2".into());
+        assert_eq!(log["msg"], "Detected a = sign\nand a \\".into());
+        assert_eq!(log["spt"], "1232".into());
+        assert!(log.contains(log_schema().timestamp_key()));
+    }
+
+    #[test]
+    fn keeps_unkeyed_equal_signs_in_values() {
+        let log = parse(
+            &CefParserConfig::default(),
+            "CEF:0|V|P|1|2|N|3|request=http://host/?a=b&c=d suser=admin",
+        );
+
+        assert_eq!(log["request"], "http://host/?a=b&c=d".into());
+        assert_eq!(log["suser"], "admin".into());
+    }
+
+    #[test]
+    fn renames_keys() {
+        let config = CefParserConfig {
+            standard_names: true,
+            custom_labels: true,
+            dictionary: vec![
+                ("src".to_owned(), "source.ip".to_owned()),
+                ("ad.user".to_owned(), "user".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let log = parse(
+            &config,
+            "CEF:0|V|P|1|2|N|3|src=10.0.0.1 dpt=22 cs1Label=Rule Name cs1=block ssh cs2Label=Unused ad.user=alice",
+        );
+
+        assert_eq!(log["source.ip"], "10.0.0.1".into());
+        assert_eq!(log["destinationPort"], "22".into());
+        assert_eq!(log.get_flat("Rule Name"), Some(&Value::from("block ssh")));
+        assert_eq!(log["deviceCustomString2Label"], "Unused".into());
+        assert_eq!(log["user"], "alice".into());
+        assert!(!log.contains("cs1"));
+        assert!(!log.contains("deviceCustomString1Label"));
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        let parser = CefParser::default();
+
+        for message in &[
+            "not a CEF message",
+            "CEF:0|V|P|1|2|N",
+            "CEF:0|V|P|1|2|N|3|no key here",
+            "CEF:0|V|P|1|2|N|3|leading text key=value",
+        ] {
+            assert!(parser.parse(Bytes::from(*message)).is_err(), "{}", message);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    codecs::{BoxedParser, Parser},
+    event::Event,
+    internal_events::DecoderMalformedReplacement,
+};
+use bytes::Bytes;
+use encoding_rs::Encoding;
+use smallvec::SmallVec;
+use std::borrow::Cow;
+
+/// Parser that transcodes byte frames from a charset to UTF-8 before passing
+/// them to another parser.
+///
+/// Frames are transcoded on their own, so the charset must not need the
+/// previous frames to be decoded, and the framing must delimit frames on
+/// characters rather than on bytes, e.g. newlines aren't a single byte in
+/// UTF-16.
+#[derive(Debug, Clone)]
+pub struct CharsetParser {
+    charset: &'static Encoding,
+    inner: BoxedParser,
+}
+
+impl CharsetParser {
+    /// Creates a new `CharsetParser` transcoding frames from `charset` before
+    /// parsing them with `inner`.
+    pub fn new(charset: &'static Encoding, inner: BoxedParser) -> Self {
+        Self { charset, inner }
+    }
+}
+
+impl Parser for CharsetParser {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let (text, had_errors) = self.charset.decode_with_bom_removal(&bytes);
+        if had_errors {
+            emit!(&DecoderMalformedReplacement {
+                from_encoding: self.charset.name()
+            });
+        }
+
+        let bytes = match text {
+            // The frame is the same in UTF-8, e.g. when it's only made of ASCII
+            // characters, so it's passed on without its BOM, if any.
+            Cow::Borrowed(text) => bytes.slice(bytes.len() - text.len()..),
+            Cow::Owned(text) => Bytes::from(text),
+        };
+        self.inner.parse(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        codecs::{BytesParser, ParserSettings},
+        config::log_schema,
+    };
+    use encoding_rs::{SHIFT_JIS, WINDOWS_1252};
+
+    fn parse(charset: &'static Encoding, input: &[u8]) -> Event {
+        let parser = CharsetParser::new(charset, Box::new(BytesParser::new()));
+        let mut events = parser.parse(Bytes::copy_from_slice(input)).unwrap();
+        assert_eq!(events.len(), 1);
+        events.remove(0)
+    }
+
+    #[test]
+    fn transcodes_shift_jis() {
+        // "ログ" in Shift-JIS.
+        let event = parse(SHIFT_JIS, b"\x83\x8d\x83\x4f error");
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "ログ error".into()
+        );
+    }
+
+    #[test]
+    fn transcodes_windows_1252() {
+        let event = parse(WINDOWS_1252, b"caf\xe9 \x80 5");
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "café € 5".into()
+        );
+    }
+
+    #[test]
+    fn passes_ascii_through() {
+        let event = parse(WINDOWS_1252, b"plain ascii");
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "plain ascii".into()
+        );
+    }
+
+    #[test]
+    fn builds_from_decoding_options() {
+        let settings: ParserSettings = toml::from_str(
+            r#"
+            codec = "json"
+            charset = "windows-1252"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.charset(), Some(WINDOWS_1252));
+
+        let events = settings
+            .build()
+            .unwrap()
+            .parse(Bytes::from_static(b"{\"city\": \"Montr\xe9al\"}"))
+            .unwrap();
+        assert_eq!(events[0].as_log()["city"], "Montréal".into());
+
+        let settings: ParserSettings = toml::from_str(r#"codec = "bytes""#).unwrap();
+        assert_eq!(settings.charset(), None);
+        assert_eq!(
+            serde_json::to_value(&settings).unwrap(),
+            serde_json::json!({ "codec": "bytes" })
+        );
+    }
+
+    #[test]
+    fn replaces_malformed_sequences() {
+        // A lead byte without its trail byte.
+        let event = parse(SHIFT_JIS, b"log \x83");
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "log \u{fffd}".into()
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{
+    codecs::{BoxedParser, Parser, ParserConfig},
+    config::log_schema,
+    event::{Event, LogEvent},
+};
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use std::sync::Mutex;
+
+/// The directive of W3C extended logs listing the fields of the lines after it.
+const FIELDS_DIRECTIVE: &str = "#Fields:";
+
+/// Config used to build a `CsvParser`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CsvParserConfig {
+    /// The character separating the values of a line.
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// The names of the columns. If empty, the first line is a header row
+    /// naming them.
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Whether lines starting with `#` are W3C extended log directives, such
+    /// as the `#Fields:` directive naming the columns of the following lines.
+    #[serde(default)]
+    pub w3c_directives: bool,
+}
+
+const fn default_delimiter() -> char {
+    ','
+}
+
+impl Default for CsvParserConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+            columns: Vec::new(),
+            w3c_directives: false,
+        }
+    }
+}
+
+impl CsvParserConfig {
+    /// Creates a new `CsvParserConfig`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[typetag::serde(name = "csv")]
+impl ParserConfig for CsvParserConfig {
+    fn build(&self) -> crate::Result<BoxedParser> {
+        if !self.delimiter.is_ascii() {
+            return Err(format!(
+                "The CSV delimiter must be an ASCII character, got {:?}.",
+                self.delimiter
+            )
+            .into());
+        }
+
+        Ok(Box::new(CsvParser::new(
+            self.delimiter as u8,
+            self.columns.clone(),
+            self.w3c_directives,
+        )))
+    }
+}
+
+/// Parser that builds an `Event` from a byte frame containing a line of
+/// delimiter-separated values, with fields named after their column.
+///
+/// The columns are either configured, or named by a header row or by the
+/// `#Fields:` directives of W3C extended logs, such as those of IIS. The
+/// columns named by a frame apply to the frames parsed after it, so a parser
+/// should only be used for a single stream of frames, such as a single file.
+/// Clones of a parser start over with its configured columns.
+#[derive(Debug)]
+pub struct CsvParser {
+    delimiter: u8,
+    configured_columns: Option<Vec<String>>,
+    w3c_directives: bool,
+    columns: Mutex<Option<Vec<String>>>,
+}
+
+impl Clone for CsvParser {
+    fn clone(&self) -> Self {
+        Self::with_columns(
+            self.delimiter,
+            self.configured_columns.clone(),
+            self.w3c_directives,
+        )
+    }
+}
+
+impl CsvParser {
+    /// Creates a new `CsvParser`, which reads its columns from the first line
+    /// or `#Fields:` directive if `columns` is empty.
+    pub fn new(delimiter: u8, columns: Vec<String>, w3c_directives: bool) -> Self {
+        let columns = (!columns.is_empty()).then(|| columns);
+        Self::with_columns(delimiter, columns, w3c_directives)
+    }
+
+    fn with_columns(delimiter: u8, columns: Option<Vec<String>>, w3c_directives: bool) -> Self {
+        Self {
+            delimiter,
+            columns: Mutex::new(columns.clone()),
+            configured_columns: columns,
+            w3c_directives,
+        }
+    }
+
+    fn parse_values(&self, bytes: &[u8]) -> crate::Result<Vec<String>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .from_reader(bytes);
+
+        match reader.records().next() {
+            Some(record) => Ok(record
+                .map_err(|error| format!("Error parsing CSV: {}", error))?
+                .iter()
+                .map(Into::into)
+                .collect()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl Parser for CsvParser {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let line = bytes.strip_suffix(b"\r").unwrap_or(&bytes[..]);
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(smallvec![]);
+        }
+
+        let mut columns = self.columns.lock().expect("poisoned lock");
+
+        if self.w3c_directives && line.starts_with(b"#") {
+            // Directives other than `#Fields:`, such as `#Software:` and
+            // `#Date:`, only describe the file.
+            if let Some(fields) = line.strip_prefix(FIELDS_DIRECTIVE.as_bytes()) {
+                let fields = String::from_utf8_lossy(fields);
+                *columns = Some(fields.split_whitespace().map(Into::into).collect());
+            }
+            return Ok(smallvec![]);
+        }
+
+        let values = self.parse_values(line)?;
+        let columns = match columns.as_ref() {
+            Some(columns) => columns,
+            None => {
+                *columns = Some(values);
+                return Ok(smallvec![]);
+            }
+        };
+
+        if values.len() > columns.len() {
+            return Err(format!(
+                "CSV line has {} values, but there are only {} columns.",
+                values.len(),
+                columns.len()
+            )
+            .into());
+        }
+
+        let mut log = LogEvent::default();
+        for (column, value) in columns.iter().zip(values) {
+            // W3C extended logs use `-` for missing values.
+            if self.w3c_directives && value == "-" {
+                continue;
+            }
+            log.insert_flat(column.as_str(), value);
+        }
+        if !log.contains(log_schema().timestamp_key()) {
+            log.insert(log_schema().timestamp_key(), Utc::now());
+        }
+
+        Ok(smallvec![Event::Log(log)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(parser: &CsvParser, lines: &[&str]) -> Vec<LogEvent> {
+        lines
+            .iter()
+            .flat_map(|line| parser.parse(Bytes::from(line.to_string())).unwrap())
+            .map(Event::into_log)
+            .collect()
+    }
+
+    #[test]
+    fn parse_csv_with_header_row() {
+        let parser = CsvParser::new(b',', Vec::new(), false);
+        let logs = parse(
+            &parser,
+            &[
+                "host,message",
+                r#"a.example.org,"hello, world""#,
+                "b.example.org,\"\"\"quoted\"\"\"\r",
+            ],
+        );
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0]["host"], "a.example.org".into());
+        assert_eq!(logs[0]["message"], "hello, world".into());
+        assert_eq!(logs[1]["host"], "b.example.org".into());
+        assert_eq!(logs[1]["message"], r#""quoted""#.into());
+        assert!(logs[0].contains(log_schema().timestamp_key()));
+    }
+
+    #[test]
+    fn parse_csv_with_configured_columns() {
+        let parser = CsvParser::new(b'\t', vec!["a".into(), "b.c".into()], false);
+        let logs = parse(&parser, &["1\t2", "3"]);
+
+        assert_eq!(logs[0]["a"], "1".into());
+        assert_eq!(logs[0].get_flat("b.c"), Some(&"2".into()));
+        assert_eq!(logs[1]["a"], "3".into());
+        assert!(logs[1].get_flat("b.c").is_none());
+
+        assert!(parser.parse(Bytes::from("1\t2\t3")).is_err());
+    }
+
+    #[test]
+    fn parse_w3c_fields_directives() {
+        let parser = CsvParser::new(b' ', Vec::new(), true);
+        let logs = parse(
+            &parser,
+            &[
+                "#Software: Microsoft Internet Information Services 10.0",
+                "#Version: 1.0",
+                "#Fields: date time c-ip cs-method cs(User-Agent)",
+                "2021-11-02 10:00:00 192.0.2.1 GET Mozilla/5.0",
+                "#Fields: date time c-ip",
+                "2021-11-02 10:00:01 -",
+            ],
+        );
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0]["c-ip"], "192.0.2.1".into());
+        assert_eq!(
+            logs[0].get_flat("cs(User-Agent)"),
+            Some(&"Mozilla/5.0".into())
+        );
+        assert_eq!(logs[1]["time"], "10:00:01".into());
+        assert!(!logs[1].contains("c-ip"));
+        assert!(!logs[1].contains("cs-method"));
+    }
+
+    #[test]
+    fn clones_start_over_with_configured_columns() {
+        let parser = CsvParser::new(b',', Vec::new(), false);
+        parse(&parser, &["a,b"]);
+
+        let clone = parser.clone();
+        assert!(parse(&clone, &["c,d"]).is_empty());
+        assert_eq!(parse(&clone, &["1,2"])[0]["c"], "1".into());
+        assert_eq!(parse(&parser, &["1,2"])[0]["a"], "1".into());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    codecs::{BoxedParser, Parser, ParserConfig},
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use std::collections::BTreeMap;
+
+/// The GELF version Vector understands.
+const GELF_VERSION: &str = "1.1";
+
+/// Config used to build a `GelfParser`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GelfParserConfig;
+
+impl GelfParserConfig {
+    /// Creates a new `GelfParserConfig`.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+#[typetag::serde(name = "gelf")]
+impl ParserConfig for GelfParserConfig {
+    fn build(&self) -> crate::Result<BoxedParser> {
+        Ok(Box::new(GelfParser))
+    }
+}
+
+/// Parser that builds an `Event` from a byte frame containing a message in
+/// the Graylog Extended Log Format, as specified in
+/// https://docs.graylog.org/docs/gelf#gelf-payload-specification.
+///
+/// `short_message`, `host` and `timestamp` are inserted at the message, host
+/// and timestamp keys of the log schema. Other fields, including additional
+/// fields with their leading underscore, are inserted as they are.
+#[derive(Debug, Clone, Default)]
+pub struct GelfParser;
+
+impl GelfParser {
+    /// Creates a new `GelfParser`.
+    pub const fn new() -> Self {
+        Self
+    }
+
+    fn parse_message(fields: BTreeMap<String, serde_json::Value>) -> crate::Result<LogEvent> {
+        let mut log = LogEvent::default();
+        let mut has_short_message = false;
+
+        for (key, value) in fields {
+            match (key.as_str(), value) {
+                ("version", serde_json::Value::String(version)) => {
+                    if version != GELF_VERSION {
+                        return Err(format!(
+                            "GELF version {:?} isn't supported, expected {:?}.",
+                            version, GELF_VERSION
+                        )
+                        .into());
+                    }
+                    log.insert_flat("version", version);
+                }
+                ("short_message", serde_json::Value::String(message)) => {
+                    has_short_message = true;
+                    log.insert(log_schema().message_key(), message);
+                }
+                ("host", serde_json::Value::String(host)) => {
+                    log.insert(log_schema().host_key(), host);
+                }
+                ("timestamp", serde_json::Value::Number(timestamp)) => {
+                    let timestamp = timestamp
+                        .as_f64()
+                        .ok_or("GELF field `timestamp` must be a number of seconds.")?;
+                    let seconds = timestamp.trunc() as i64;
+                    // Senders rarely send more than microseconds, and the rest
+                    // is floating point noise.
+                    let microseconds =
+                        ((timestamp.fract() * 1_000_000.0).round() as u32).min(999_999);
+                    log.insert(
+                        log_schema().timestamp_key(),
+                        Utc.timestamp(seconds, microseconds * 1_000),
+                    );
+                }
+                ("version" | "short_message" | "host" | "timestamp", _) => {
+                    return Err(format!("GELF field `{}` has an invalid type.", key).into())
+                }
+                // The specification reserves `_id` for Graylog's own use.
+                ("_id", _) => {}
+                (name, value) => {
+                    if name.starts_with('_') && !is_valid_field_name(&name[1..]) {
+                        return Err(
+                            format!("GELF additional field name {:?} is invalid.", name).into()
+                        );
+                    }
+                    log.insert_flat(name, Value::from(value));
+                }
+            }
+        }
+
+        if !has_short_message {
+            return Err("GELF message is missing the `short_message` field.".into());
+        }
+        if !log.contains(log_schema().timestamp_key()) {
+            log.insert(log_schema().timestamp_key(), Utc::now());
+        }
+
+        Ok(log)
+    }
+}
+
+/// Additional field names must match `^[\w\.\-]*$`.
+fn is_valid_field_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+impl Parser for GelfParser {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        // GELF over TCP frames messages with a trailing null byte, which may
+        // be left over depending on the framing.
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(&bytes[..]);
+        if bytes.iter().all(u8::is_ascii_whitespace) {
+            return Ok(smallvec![]);
+        }
+
+        let fields: BTreeMap<String, serde_json::Value> = serde_json::from_slice(bytes)
+            .map_err(|error| format!("Error parsing GELF message: {}", error))?;
+
+        Ok(smallvec![Event::Log(Self::parse_message(fields)?)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> crate::Result<LogEvent> {
+        let mut events = GelfParser::new().parse(Bytes::from(input.to_owned()))?;
+        assert_eq!(events.len(), 1);
+        Ok(events.remove(0).into_log())
+    }
+
+    #[test]
+    fn parse_gelf() {
+        let log = parse(
+            r#"{
+                "version": "1.1",
+                "host": "example.org",
+                "short_message": "A short message",
+                "full_message": "Backtrace here\n\nmore stuff",
+                "timestamp": 1385053862.3072,
+                "level": 1,
+                "_user_id": 9001,
+                "_some.info": "foo",
+                "_id": "ignored"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(log[log_schema().message_key()], "A short message".into());
+        assert_eq!(log[log_schema().host_key()], "example.org".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1385053862, 307_200_000).into()
+        );
+        assert_eq!(log["version"], "1.1".into());
+        assert_eq!(log["full_message"], "Backtrace here\n\nmore stuff".into());
+        assert_eq!(log["level"], 1.into());
+        assert_eq!(log["_user_id"], 9001.into());
+        assert_eq!(log.get_flat("_some.info"), Some(&"foo".into()));
+        assert!(!log.contains("_id"));
+    }
+
+    #[test]
+    fn parse_gelf_without_timestamp() {
+        let log = parse(r#"{"short_message":"foo"}"#).unwrap();
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn parse_gelf_trailing_null_byte() {
+        let log = parse("{\"short_message\":\"foo\"}\0").unwrap();
+        assert_eq!(log[log_schema().message_key()], "foo".into());
+    }
+
+    #[test]
+    fn skip_empty() {
+        let events = GelfParser::new().parse(Bytes::from("\n")).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn error_invalid_gelf() {
+        for input in [
+            r#"{ foo"#,
+            r#"["short_message"]"#,
+            r#"{"host":"example.org"}"#,
+            r#"{"short_message":1}"#,
+            r#"{"short_message":"foo","version":"1.0"}"#,
+            r#"{"short_message":"foo","timestamp":"now"}"#,
+            r#"{"short_message":"foo","_bad key":1}"#,
+        ] {
+            assert!(parse(input).is_err(), "{} should be rejected", input);
+        }
+    }
+}
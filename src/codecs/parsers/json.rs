@@ -11,11 +11,36 @@ use std::convert::TryInto;
 
 /// Config used to build a `JsonParser`.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct JsonParserConfig;
+pub struct JsonParserConfig {
+    /// The implementation used to parse JSON.
+    #[serde(default)]
+    pub engine: JsonEngine,
+}
+
+/// The implementation used to parse JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonEngine {
+    /// Parse JSON with `serde_json`.
+    Serde,
+    /// Parse JSON with `simd-json`, falling back to `serde_json` when Vector
+    /// isn't built with the SIMD instructions it relies on.
+    Simd,
+}
+
+impl Default for JsonEngine {
+    fn default() -> Self {
+        Self::Serde
+    }
+}
 
 #[typetag::serde(name = "json")]
 impl ParserConfig for JsonParserConfig {
     fn build(&self) -> crate::Result<BoxedParser> {
+        if self.engine == JsonEngine::Simd && !cfg!(feature = "codecs-json-simd") {
+            return Err("The `simd` JSON engine requires Vector to be built with the `codecs-json-simd` feature.".into());
+        }
+
         Ok(Box::new(Into::<JsonParser>::into(self)))
     }
 }
@@ -29,13 +54,111 @@ impl JsonParserConfig {
 
 /// Parser that builds `Event`s from a byte frame containing JSON.
 #[derive(Debug, Clone, Default)]
-pub struct JsonParser;
+pub struct JsonParser {
+    engine: JsonEngine,
+}
 
 impl JsonParser {
     /// Creates a new `JsonParser`.
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Creates a new `JsonParser` using the given engine, if it's supported
+    /// by this build, or `serde_json` otherwise.
+    pub fn with_engine(engine: JsonEngine) -> Self {
+        let engine = match engine {
+            JsonEngine::Simd if !simd_supported() => {
+                warn!(
+                    message = "SIMD JSON parsing isn't supported by this build, falling back to the `serde` engine. Build Vector with the `avx2` or `sse4.2` target features to use it.",
+                    internal_log_rate_secs = 0
+                );
+                JsonEngine::Serde
+            }
+            engine => engine,
+        };
+
+        Self { engine }
+    }
+
+    fn parse_serde(bytes: &[u8]) -> crate::Result<SmallVec<[Event; 1]>> {
+        let json: serde_json::Value = serde_json::from_slice(bytes)
+            .map_err(|error| format!("Error parsing JSON: {:?}", error))?;
+
+        Ok(match json {
+            serde_json::Value::Array(values) => values
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<SmallVec<[Event; 1]>, _>>()?,
+            _ => smallvec![json.try_into()?],
+        })
+    }
+}
+
+/// Whether `simd-json` is built with the SIMD instructions it needs to
+/// outperform `serde_json`.
+///
+/// `simd-json` picks its implementation when it's compiled, from the target
+/// features Vector is built with (e.g. `-C target-feature=+avx2`), and falls
+/// back to a slower scalar one without them, whatever the CPU running Vector
+/// supports.
+const fn simd_supported() -> bool {
+    cfg!(all(
+        feature = "codecs-json-simd",
+        any(target_feature = "avx2", target_feature = "sse4.2")
+    ))
+}
+
+#[cfg(feature = "codecs-json-simd")]
+mod simd {
+    use crate::event::{Event, Value};
+    use simd_json::{OwnedValue, StaticNode};
+    use smallvec::{smallvec, SmallVec};
+    use std::convert::TryFrom;
+
+    pub(super) fn parse(bytes: &[u8]) -> crate::Result<SmallVec<[Event; 1]>> {
+        // `simd-json` parses in place, so it needs its own copy of the frame.
+        let mut buf = bytes.to_vec();
+        let json = simd_json::to_owned_value(&mut buf)
+            .map_err(|error| format!("Error parsing JSON: {:?}", error))?;
+
+        Ok(match json {
+            OwnedValue::Array(values) => values
+                .into_iter()
+                .map(into_event)
+                .collect::<Result<SmallVec<[Event; 1]>, _>>()?,
+            _ => smallvec![into_event(json)?],
+        })
+    }
+
+    fn into_event(json: OwnedValue) -> crate::Result<Event> {
+        match into_value(json) {
+            Value::Map(fields) => Ok(Event::from(fields)),
+            _ => Err("Attempted to convert non-Object JSON into an Event.".into()),
+        }
+    }
+
+    /// Mirrors the conversion from `serde_json::Value`, so that both engines
+    /// produce the same events.
+    fn into_value(json: OwnedValue) -> Value {
+        match json {
+            OwnedValue::Static(StaticNode::Null) => Value::Null,
+            OwnedValue::Static(StaticNode::Bool(value)) => Value::Boolean(value),
+            OwnedValue::Static(StaticNode::I64(value)) => Value::Integer(value),
+            OwnedValue::Static(StaticNode::U64(value)) => {
+                i64::try_from(value).map_or(Value::Float(value as f64), Value::Integer)
+            }
+            OwnedValue::Static(StaticNode::F64(value)) => Value::Float(value),
+            OwnedValue::String(value) => Value::Bytes(value.into()),
+            OwnedValue::Array(values) => Value::Array(values.into_iter().map(into_value).collect()),
+            OwnedValue::Object(fields) => Value::Map(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, into_value(value)))
+                    .collect(),
+            ),
+        }
+    }
 }
 
 impl Parser for JsonParser {
@@ -46,15 +169,10 @@ impl Parser for JsonParser {
             return Ok(smallvec![]);
         }
 
-        let json: serde_json::Value = serde_json::from_slice(&bytes)
-            .map_err(|error| format!("Error parsing JSON: {:?}", error))?;
-
-        let mut events = match json {
-            serde_json::Value::Array(values) => values
-                .into_iter()
-                .map(TryInto::try_into)
-                .collect::<Result<SmallVec<[Event; 1]>, _>>()?,
-            _ => smallvec![json.try_into()?],
+        let mut events = match self.engine {
+            #[cfg(feature = "codecs-json-simd")]
+            JsonEngine::Simd => simd::parse(&bytes)?,
+            _ => Self::parse_serde(&bytes)?,
         };
 
         let timestamp = Utc::now();
@@ -73,8 +191,8 @@ impl Parser for JsonParser {
 }
 
 impl From<&JsonParserConfig> for JsonParser {
-    fn from(_: &JsonParserConfig) -> Self {
-        Self
+    fn from(config: &JsonParserConfig) -> Self {
+        Self::with_engine(config.engine)
     }
 }
 
@@ -144,4 +262,69 @@ mod tests {
 
         assert!(parser.parse(input).is_err());
     }
+
+    #[test]
+    fn parse_engine_config() {
+        let config: JsonParserConfig = toml::from_str(r#"engine = "simd""#).unwrap();
+        assert_eq!(config.engine, JsonEngine::Simd);
+
+        let config: JsonParserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.engine, JsonEngine::Serde);
+    }
+
+    #[test]
+    fn simd_engine_requires_target_features() {
+        let parser = JsonParser::with_engine(JsonEngine::Simd);
+        let expected = if simd_supported() {
+            JsonEngine::Simd
+        } else {
+            JsonEngine::Serde
+        };
+        assert_eq!(parser.engine, expected);
+    }
+
+    #[cfg(feature = "codecs-json-simd")]
+    #[test]
+    fn simd_engine_matches_serde() {
+        let input = Bytes::from(
+            r#"[
+                { "message": "hello", "count": 123, "big": 18446744073709551615, "ratio": 0.5 },
+                { "nested": { "list": [true, null, "a"] } }
+            ]"#,
+        );
+        let serde = JsonParserConfig::new()
+            .build()
+            .unwrap()
+            .parse(input.clone())
+            .unwrap();
+        let simd = JsonParserConfig {
+            engine: JsonEngine::Simd,
+        }
+        .build()
+        .unwrap()
+        .parse(input)
+        .unwrap();
+
+        assert_eq!(serde.len(), 2);
+        for (serde, simd) in serde.iter().zip(simd.iter()) {
+            let (mut serde, mut simd) = (serde.as_log().clone(), simd.as_log().clone());
+            // The timestamps are taken from the clock when parsing.
+            serde.remove(log_schema().timestamp_key());
+            simd.remove(log_schema().timestamp_key());
+            assert_eq!(serde, simd);
+        }
+    }
+
+    #[cfg(feature = "codecs-json-simd")]
+    #[test]
+    fn simd_engine_errors() {
+        let parser = JsonParserConfig {
+            engine: JsonEngine::Simd,
+        }
+        .build()
+        .unwrap();
+
+        assert!(parser.parse(Bytes::from("{ foo")).is_err());
+        assert!(parser.parse(Bytes::from("[1, 2]")).is_err());
+    }
 }
@@ -0,0 +1,232 @@
+use super::cef::split_header;
+use crate::{
+    codecs::{BoxedParser, Parser, ParserConfig},
+    config::log_schema,
+    event::{Event, LogEvent},
+};
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use std::collections::HashMap;
+
+/// The fields the LEEF header is parsed into, in order.
+const HEADER_FIELDS: [&str; 5] = [
+    "leefVersion",
+    "vendor",
+    "productName",
+    "productVersion",
+    "eventId",
+];
+
+/// Config used to build a `LeefParser`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LeefParserConfig {
+    /// The character separating attributes, for LEEF 1.0 messages and LEEF 2.0
+    /// messages that don't define their own.
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Attribute keys renamed to the given field paths.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dictionary: HashMap<String, String>,
+}
+
+const fn default_delimiter() -> char {
+    '\t'
+}
+
+impl Default for LeefParserConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+            dictionary: HashMap::new(),
+        }
+    }
+}
+
+#[typetag::serde(name = "leef")]
+impl ParserConfig for LeefParserConfig {
+    fn build(&self) -> crate::Result<BoxedParser> {
+        Ok(Box::new(LeefParser::from(self)))
+    }
+}
+
+/// Parser that builds an `Event` from a byte frame containing a message in
+/// the Log Event Extended Format.
+#[derive(Debug, Clone)]
+pub struct LeefParser {
+    delimiter: char,
+    dictionary: HashMap<String, String>,
+}
+
+impl From<&LeefParserConfig> for LeefParser {
+    fn from(config: &LeefParserConfig) -> Self {
+        Self {
+            delimiter: config.delimiter,
+            dictionary: config.dictionary.clone(),
+        }
+    }
+}
+
+impl Parser for LeefParser {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        // Only trim line breaks, since tabs delimit attributes.
+        let line = std::str::from_utf8(&bytes)?.trim_end_matches(&['\r', '\n'][..]);
+        if line.trim().is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        // Messages are commonly wrapped in a syslog header, which is skipped.
+        let start = line
+            .find("LEEF:")
+            .ok_or("Message doesn't contain a LEEF header.")?;
+        let (header, mut attributes) = split_header(&line[start + 5..], HEADER_FIELDS.len())
+            .ok_or("Incomplete LEEF header.")?;
+
+        let mut delimiter = self.delimiter;
+        if header[0].starts_with('2') {
+            if let Some(end) = attributes.find('|') {
+                if let Some(custom) = parse_delimiter(&attributes[..end]) {
+                    delimiter = custom.unwrap_or(delimiter);
+                    attributes = &attributes[end + 1..];
+                }
+            }
+        }
+
+        let mut log = LogEvent::default();
+        for (field, value) in HEADER_FIELDS.iter().zip(header) {
+            log.insert_flat(*field, value);
+        }
+
+        for (key, value) in parse_attributes(attributes, delimiter) {
+            match self.dictionary.get(key) {
+                Some(name) => log.insert(name.as_str(), value),
+                None => log.insert_flat(key, value),
+            };
+        }
+
+        let timestamp_key = log_schema().timestamp_key();
+        if !log.contains(timestamp_key) {
+            log.insert(timestamp_key, Utc::now());
+        }
+
+        Ok(smallvec![Event::Log(log)])
+    }
+}
+
+/// Parses the delimiter field of a LEEF 2.0 header, which is either a single
+/// character or its hexadecimal code, e.g. `^` or `x5E`.
+///
+/// Returns `None` if the field isn't a delimiter, i.e. when the header omits
+/// it, and `Some(None)` if it's empty.
+fn parse_delimiter(field: &str) -> Option<Option<char>> {
+    let mut chars = field.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Some(None),
+        (Some(c), None) => Some(Some(c)),
+        _ => {
+            let hex = field
+                .strip_prefix("0x")
+                .or_else(|| field.strip_prefix('x'))?;
+            u32::from_str_radix(hex, 16)
+                .ok()
+                .and_then(std::char::from_u32)
+                .map(Some)
+        }
+    }
+}
+
+/// Parses the `key=value` attributes of a LEEF message.
+///
+/// Parts that don't contain an `=` are considered to be part of the preceding
+/// value, which contained the delimiter.
+fn parse_attributes(attributes: &str, delimiter: char) -> Vec<(&str, String)> {
+    let mut pairs: Vec<(&str, String)> = Vec::new();
+
+    for part in attributes.split(delimiter) {
+        match part.split_once('=') {
+            Some((key, value)) if !key.is_empty() => pairs.push((key, value.to_owned())),
+            _ => {
+                if let Some((_, value)) = pairs.last_mut() {
+                    value.push(delimiter);
+                    value.push_str(part);
+                }
+            }
+        }
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &LeefParserConfig, message: &'static str) -> LogEvent {
+        let mut events = LeefParser::from(config)
+            .parse(Bytes::from(message))
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        events.pop().unwrap().into_log()
+    }
+
+    #[test]
+    fn parses_leef_1() {
+        let log = parse(
+            &LeefParserConfig::default(),
+            "<13>Jan 18 11:07:53 host LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tmsg=a=b\n",
+        );
+
+        assert_eq!(log["leefVersion"], "1.0".into());
+        assert_eq!(log["vendor"], "Microsoft".into());
+        assert_eq!(log["productName"], "MSExchange".into());
+        assert_eq!(log["productVersion"], "4.0 SP1".into());
+        assert_eq!(log["eventId"], "15345".into());
+        assert_eq!(log["src"], "192.0.2.0".into());
+        assert_eq!(log["dst"], "172.50.123.1".into());
+        assert_eq!(log["sev"], "5".into());
+        assert_eq!(log["msg"], "a=b".into());
+        assert!(log.contains(log_schema().timestamp_key()));
+    }
+
+    #[test]
+    fn parses_leef_2_delimiters() {
+        let config = LeefParserConfig {
+            dictionary: vec![("usrName".to_owned(), "user.name".to_owned())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let log = parse(
+            &config,
+            "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^msg=up^down^usrName=bob",
+        );
+        assert_eq!(log["src"], "10.0.1.8".into());
+        assert_eq!(log["msg"], "up^down".into());
+        assert_eq!(log["user.name"], "bob".into());
+
+        let log = parse(
+            &config,
+            "LEEF:2.0|Lancope|StealthWatch|1.0|41|x7C|src=10.0.1.8|dst=10.0.0.5",
+        );
+        assert_eq!(log["src"], "10.0.1.8".into());
+        assert_eq!(log["dst"], "10.0.0.5".into());
+
+        let log = parse(
+            &config,
+            "LEEF:2.0|Lancope|StealthWatch|1.0|41|src=10.0.1.8\tdst=10.0.0.5",
+        );
+        assert_eq!(log["src"], "10.0.1.8".into());
+        assert_eq!(log["dst"], "10.0.0.5".into());
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        let parser = LeefParser::from(&LeefParserConfig::default());
+
+        for message in &["not a LEEF message", "LEEF:1.0|V|P|1.0"] {
+            assert!(parser.parse(Bytes::from(*message)).is_err(), "{}", message);
+        }
+    }
+}
@@ -4,18 +4,30 @@
 #![deny(missing_docs)]
 
 mod bytes;
+mod cef;
+mod charset;
+mod csv;
+mod gelf;
 mod json;
+mod leef;
 #[cfg(feature = "sources-syslog")]
 mod syslog;
 
 pub use self::bytes::{BytesParser, BytesParserConfig};
+pub use self::cef::{CefParser, CefParserConfig};
+pub use self::charset::CharsetParser;
+pub use self::csv::{CsvParser, CsvParserConfig};
+pub use self::gelf::{GelfParser, GelfParserConfig};
+pub use self::leef::{LeefParser, LeefParserConfig};
 #[cfg(feature = "sources-syslog")]
 pub use self::syslog::{SyslogParser, SyslogParserConfig};
-pub use json::{JsonParser, JsonParserConfig};
+pub use json::{JsonEngine, JsonParser, JsonParserConfig};
 
 use crate::event::Event;
 use ::bytes::Bytes;
 use dyn_clone::DynClone;
+use encoding_rs::{Encoding, UTF_8};
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::fmt::Debug;
 
@@ -49,3 +61,56 @@ pub trait ParserConfig: Debug + DynClone + Send + Sync {
 }
 
 dyn_clone::clone_trait_object!(ParserConfig);
+
+/// The `decoding` options of a source: the config of its parser, along with the
+/// charset of the byte frames it parses.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ParserSettings {
+    #[serde(flatten)]
+    parser: Box<dyn ParserConfig>,
+    /// The charset byte frames are transcoded to UTF-8 from before being
+    /// parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    charset: Option<&'static Encoding>,
+}
+
+impl ParserSettings {
+    /// Creates new `ParserSettings` for the provided `ParserConfig`, parsing
+    /// byte frames as they are.
+    pub fn new(parser: Box<dyn ParserConfig>) -> Self {
+        Self {
+            parser,
+            charset: None,
+        }
+    }
+
+    /// Transcodes byte frames from the provided charset before parsing them.
+    pub const fn with_charset(mut self, charset: &'static Encoding) -> Self {
+        self.charset = Some(charset);
+        self
+    }
+
+    /// The charset byte frames are transcoded from, if any.
+    pub const fn charset(&self) -> Option<&'static Encoding> {
+        self.charset
+    }
+
+    /// Builds a parser from these settings.
+    ///
+    /// Fails if the configuration of the parser is invalid.
+    pub fn build(&self) -> crate::Result<BoxedParser> {
+        let parser = self.parser.build()?;
+        Ok(match self.charset {
+            // Parsers already handle UTF-8, replacing malformed sequences as
+            // transcoding would.
+            Some(charset) if charset != UTF_8 => Box::new(CharsetParser::new(charset, parser)),
+            _ => parser,
+        })
+    }
+}
+
+impl<T: ParserConfig + 'static> From<T> for ParserSettings {
+    fn from(parser: T) -> Self {
+        Self::new(Box::new(parser))
+    }
+}
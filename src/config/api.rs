@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::net::{Ipv4Addr, SocketAddr};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -12,6 +12,11 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    /// The token mutations must be authorized with, as a bearer token of the
+    /// `Authorization` header. Mutations are disabled if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
 }
 
 impl Default for Options {
@@ -20,6 +25,7 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            auth_token: None,
         }
     }
 }
@@ -60,10 +66,18 @@ impl Options {
             }
         };
 
+        let auth_token = match (self.auth_token.take(), other.auth_token) {
+            (Some(a), Some(b)) if a != b => {
+                return Err("Conflicting `api` auth tokens.".to_owned());
+            }
+            (a, b) => a.or(b),
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            auth_token,
         };
 
         *self = options;
@@ -77,6 +91,7 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        auth_token: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -87,6 +102,7 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            auth_token: None,
         }
     );
 }
@@ -98,6 +114,7 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        auth_token: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -108,6 +125,7 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            auth_token: None,
         }
     );
 }
@@ -126,3 +144,20 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn auth_token_merge() {
+    let mut a = Options {
+        auth_token: Some("secret".to_owned()),
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+    assert_eq!(a.auth_token.as_deref(), Some("secret"));
+
+    let b = Options {
+        auth_token: Some("other".to_owned()),
+        ..Options::default()
+    };
+    assert!(a.merge(b).is_err());
+}
@@ -4,7 +4,8 @@ use super::api;
 use super::datadog;
 use super::{
     compiler, provider, ComponentKey, Config, EnrichmentTableConfig, EnrichmentTableOuter,
-    HealthcheckOptions, SinkConfig, SinkOuter, SourceConfig, SourceOuter, TestDefinition,
+    EventLimitsOptions, EventTracingOptions, HealthcheckOptions, PipelineConfig, RateLimiterConfig,
+    SinkConfig, SinkOuter, SourceConfig, SourceOuter, TemplateConfig, TestDefinition,
     TransformOuter,
 };
 use indexmap::IndexMap;
@@ -25,6 +26,12 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub healthchecks: HealthcheckOptions,
     #[serde(default)]
+    pub event_tracing: EventTracingOptions,
+    #[serde(default)]
+    pub limits: EventLimitsOptions,
+    #[serde(default)]
+    pub rate_limiters: IndexMap<String, RateLimiterConfig>,
+    #[serde(default)]
     pub enrichment_tables: IndexMap<ComponentKey, EnrichmentTableOuter>,
     #[serde(default)]
     pub sources: IndexMap<ComponentKey, SourceOuter>,
@@ -33,6 +40,10 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub transforms: IndexMap<ComponentKey, TransformOuter<String>>,
     #[serde(default)]
+    pub templates: IndexMap<String, TemplateConfig>,
+    #[serde(default)]
+    pub pipelines: IndexMap<ComponentKey, PipelineConfig>,
+    #[serde(default)]
     pub tests: Vec<TestDefinition>,
     pub provider: Option<Box<dyn provider::ProviderConfig>>,
 }
@@ -58,6 +69,9 @@ impl From<Config> for ConfigBuilder {
             #[cfg(feature = "datadog-pipelines")]
             datadog,
             healthchecks,
+            event_tracing,
+            limits,
+            rate_limiters,
             enrichment_tables,
             sources,
             sinks,
@@ -83,10 +97,15 @@ impl From<Config> for ConfigBuilder {
             #[cfg(feature = "datadog-pipelines")]
             datadog,
             healthchecks,
+            event_tracing,
+            limits,
+            rate_limiters,
             enrichment_tables,
             sources,
             sinks,
             transforms,
+            templates: Default::default(),
+            pipelines: Default::default(),
             provider: None,
             tests,
         }
@@ -152,6 +171,7 @@ impl ConfigBuilder {
         let transform = TransformOuter {
             inner: Box::new(transform),
             inputs,
+            distribution: Default::default(),
         };
 
         self.transforms
@@ -208,7 +228,14 @@ impl ConfigBuilder {
         }
 
         self.healthchecks.merge(with.healthchecks);
+        self.event_tracing.merge(with.event_tracing);
+        self.limits.merge(with.limits);
 
+        with.rate_limiters.keys().for_each(|k| {
+            if self.rate_limiters.contains_key(k) {
+                errors.push(format!("duplicate rate_limiter name found: {}", k));
+            }
+        });
         with.enrichment_tables.keys().for_each(|k| {
             if self.enrichment_tables.contains_key(k) {
                 errors.push(format!("duplicate enrichment_table name found: {}", k));
@@ -229,6 +256,16 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate transform id found: {}", k));
             }
         });
+        with.templates.keys().for_each(|k| {
+            if self.templates.contains_key(k) {
+                errors.push(format!("duplicate template name found: {}", k));
+            }
+        });
+        with.pipelines.keys().for_each(|k| {
+            if self.pipelines.contains_key(k) {
+                errors.push(format!("duplicate pipeline name found: {}", k));
+            }
+        });
         with.tests.iter().for_each(|wt| {
             if self.tests.iter().any(|t| t.name == wt.name) {
                 errors.push(format!("duplicate test name found: {}", wt.name));
@@ -238,10 +275,13 @@ impl ConfigBuilder {
             return Err(errors);
         }
 
+        self.rate_limiters.extend(with.rate_limiters);
         self.enrichment_tables.extend(with.enrichment_tables);
         self.sources.extend(with.sources);
         self.sinks.extend(with.sinks);
         self.transforms.extend(with.transforms);
+        self.templates.extend(with.templates);
+        self.pipelines.extend(with.pipelines);
         self.tests.extend(with.tests);
 
         Ok(())
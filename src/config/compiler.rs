@@ -1,6 +1,6 @@
 use super::{
-    builder::ConfigBuilder, graph::Graph, validation, ComponentKey, Config, ExpandType, OutputId,
-    TransformOuter,
+    builder::ConfigBuilder, graph::Graph, template, validation, ComponentKey, Config, ExpandType,
+    OutputId, TransformOuter,
 };
 use indexmap::{IndexMap, IndexSet};
 
@@ -15,11 +15,25 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
             .transforms
             .keys()
             .chain(builder.sources.keys())
-            .chain(builder.sinks.keys()),
+            .chain(builder.sinks.keys())
+            .chain(builder.pipelines.keys())
+            .chain(builder.templates.values().flat_map(|template| {
+                template
+                    .sources
+                    .keys()
+                    .chain(template.transforms.keys())
+                    .chain(template.sinks.keys())
+            })),
     ) {
         errors.extend(name_errors);
     }
 
+    // pipelines expand to components named after them, which may themselves
+    // be macros, so they are expanded first
+    if let Err(pipeline_errors) = template::expand_pipelines(&mut builder) {
+        errors.extend(pipeline_errors);
+    }
+
     let expansions = expand_macros(&mut builder)?;
 
     expand_globs(&mut builder);
@@ -39,11 +53,16 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         #[cfg(feature = "datadog-pipelines")]
         datadog,
         healthchecks,
+        event_tracing,
+        limits,
+        rate_limiters,
         enrichment_tables,
         sources,
         sinks,
         transforms,
         tests,
+        templates: _,
+        pipelines: _,
         provider: _,
     } = builder;
 
@@ -84,6 +103,9 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
             #[cfg(feature = "datadog-pipelines")]
             datadog,
             healthchecks,
+            event_tracing,
+            limits,
+            rate_limiters,
             enrichment_tables,
             sources,
             sinks,
@@ -127,6 +149,7 @@ pub(super) fn expand_macros(
                     full_name.clone(),
                     TransformOuter {
                         inputs,
+                        distribution: t.distribution,
                         inner: child,
                     },
                 );
@@ -157,6 +180,15 @@ fn expand_globs(config: &mut ConfigBuilder) {
         .keys()
         .chain(config.transforms.keys())
         .map(ToString::to_string)
+        .chain(config.sources.iter().flat_map(|(key, s)| {
+            s.inner.named_outputs().into_iter().map(move |port| {
+                OutputId {
+                    component: key.clone(),
+                    port: Some(port),
+                }
+                .to_string()
+            })
+        }))
         .chain(config.transforms.iter().flat_map(|(key, t)| {
             t.inner.named_outputs().into_iter().map(move |port| {
                 OutputId {
@@ -1,6 +1,7 @@
 use super::{ComponentKey, Config};
 use indexmap::IndexMap;
-use std::collections::HashSet;
+use serde::Serialize;
+use std::{collections::HashSet, fmt};
 
 pub struct ConfigDiff {
     pub sources: Difference,
@@ -15,12 +16,23 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
-        ConfigDiff {
+        let mut diff = ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
             transforms: Difference::new(&old.transforms, &new.transforms),
             sinks: Difference::new(&old.sinks, &new.sinks),
             enrichment_tables: Difference::new(&old.enrichment_tables, &new.enrichment_tables),
+        };
+
+        // Sources apply the global limits as they emit events.
+        if old.limits != new.limits {
+            for key in old.sources.keys() {
+                if new.sources.contains_key(key) {
+                    diff.sources.to_change.insert(key.clone());
+                }
+            }
         }
+
+        diff
     }
 
     /// Swaps removed with added in Differences.
@@ -86,3 +98,267 @@ impl Difference {
         self.to_change.iter().chain(self.to_remove.iter())
     }
 }
+
+/// The parts of a config that decide how reloading into another config
+/// affects each component. They are kept serialized, like the comparison of
+/// `Difference`, so that a snapshot of the running config outlives it.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSnapshot {
+    global: serde_json::Value,
+    components: IndexMap<ComponentKey, ComponentSnapshot>,
+}
+
+#[derive(Clone, Debug)]
+struct ComponentSnapshot {
+    kind: ComponentKind,
+    options: serde_json::Value,
+    inputs: serde_json::Value,
+    buffer: serde_json::Value,
+}
+
+impl ConfigSnapshot {
+    pub fn new(config: &Config) -> Self {
+        let components = config
+            .sources
+            .iter()
+            .map(|(key, source)| (key, ComponentSnapshot::new(ComponentKind::Source, source)))
+            .chain(config.transforms.iter().map(|(key, transform)| {
+                (
+                    key,
+                    ComponentSnapshot::new(ComponentKind::Transform, transform),
+                )
+            }))
+            .chain(
+                config
+                    .sinks
+                    .iter()
+                    .map(|(key, sink)| (key, ComponentSnapshot::new(ComponentKind::Sink, sink))),
+            )
+            .chain(config.enrichment_tables.iter().map(|(key, table)| {
+                (
+                    key,
+                    ComponentSnapshot::new(ComponentKind::EnrichmentTable, table),
+                )
+            }))
+            .map(|(key, snapshot)| (key.clone(), snapshot))
+            .collect();
+
+        Self {
+            global: serde_json::to_value(&config.global).unwrap(),
+            components,
+        }
+    }
+}
+
+impl ComponentSnapshot {
+    fn new(kind: ComponentKind, outer: &impl Serialize) -> Self {
+        let mut options = serde_json::to_value(outer).unwrap();
+        let (inputs, buffer) = match options.as_object_mut() {
+            Some(options) => (
+                options.remove("inputs").unwrap_or_default(),
+                options.get("buffer").cloned().unwrap_or_default(),
+            ),
+            None => Default::default(),
+        };
+
+        Self {
+            kind,
+            options,
+            inputs,
+            buffer,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Source,
+    Transform,
+    Sink,
+    EnrichmentTable,
+}
+
+impl fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source => write!(f, "source"),
+            Self::Transform => write!(f, "transform"),
+            Self::Sink => write!(f, "sink"),
+            Self::EnrichmentTable => write!(f, "enrichment_table"),
+        }
+    }
+}
+
+/// What reloading the running topology does to a component.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentAction {
+    /// The component is built and started.
+    Add,
+    /// The component is shut down.
+    Remove,
+    /// The component is rebuilt with new options.
+    Reconfigure,
+    /// The component is rebuilt with the same options, as only its inputs
+    /// changed.
+    Restart,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ComponentReport {
+    pub id: String,
+    pub kind: ComponentKind,
+    pub action: ComponentAction,
+    /// Whether a rebuilt sink keeps the events of its buffer, which it does
+    /// when the buffer options didn't change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keeps_buffer: Option<bool>,
+}
+
+/// The components a reload into a candidate config would affect, as
+/// `RunningTopology::reload_config_and_respawn` would apply it.
+#[derive(Clone, Debug, Serialize)]
+pub struct ReloadReport {
+    /// Global options can't be reloaded, changing them aborts the reload
+    /// and requires restarting Vector.
+    pub global_changed: bool,
+    pub components: Vec<ComponentReport>,
+}
+
+impl ReloadReport {
+    pub fn new(old: &ConfigSnapshot, new: &ConfigSnapshot) -> Self {
+        let removed = old
+            .components
+            .iter()
+            .filter(|(key, _)| !new.components.contains_key(*key))
+            .map(|(key, old)| ComponentReport {
+                id: key.to_string(),
+                kind: old.kind,
+                action: ComponentAction::Remove,
+                keeps_buffer: None,
+            });
+
+        let added_or_changed = new.components.iter().filter_map(|(key, new)| {
+            let action = match old.components.get(key) {
+                None => ComponentAction::Add,
+                Some(old) if old.options != new.options => ComponentAction::Reconfigure,
+                Some(old) if old.inputs != new.inputs => ComponentAction::Restart,
+                Some(_) => return None,
+            };
+            let keeps_buffer = match (action, new.kind) {
+                (ComponentAction::Reconfigure, ComponentKind::Sink)
+                | (ComponentAction::Restart, ComponentKind::Sink) => {
+                    Some(old.components[key].buffer == new.buffer)
+                }
+                _ => None,
+            };
+
+            Some(ComponentReport {
+                id: key.to_string(),
+                kind: new.kind,
+                action,
+                keeps_buffer,
+            })
+        });
+
+        let mut components = removed.chain(added_or_changed).collect::<Vec<_>>();
+        components.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Self {
+            global_changed: old.global != new.global,
+            components,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.global_changed && self.components.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "sources-stdin", feature = "sinks-blackhole"))]
+mod tests {
+    use super::*;
+    use crate::config::{self, Format};
+
+    fn snapshot(config: &str) -> ConfigSnapshot {
+        let config = config::load_from_str(config, Some(Format::Toml)).unwrap();
+        ConfigSnapshot::new(&config)
+    }
+
+    fn actions(report: &ReloadReport) -> Vec<(&str, ComponentAction, Option<bool>)> {
+        report
+            .components
+            .iter()
+            .map(|component| {
+                (
+                    component.id.as_str(),
+                    component.action,
+                    component.keeps_buffer,
+                )
+            })
+            .collect()
+    }
+
+    const OLD: &str = r#"
+        [sources.in]
+        type = "stdin"
+
+        [sources.other]
+        type = "stdin"
+
+        [sinks.out]
+        type = "blackhole"
+        inputs = ["in"]
+
+        [sinks.gone]
+        type = "blackhole"
+        inputs = ["in"]
+    "#;
+
+    #[test]
+    fn reports_unchanged_config() {
+        let report = ReloadReport::new(&snapshot(OLD), &snapshot(OLD));
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn reports_component_actions() {
+        let new = r#"
+            [sources.in]
+            type = "stdin"
+            max_length = 1024
+
+            [sources.other]
+            type = "stdin"
+
+            [sinks.out]
+            type = "blackhole"
+            inputs = ["other"]
+
+            [sinks.new]
+            type = "blackhole"
+            inputs = ["in"]
+        "#;
+        let report = ReloadReport::new(&snapshot(OLD), &snapshot(new));
+
+        assert!(!report.global_changed);
+        assert_eq!(
+            actions(&report),
+            vec![
+                ("gone", ComponentAction::Remove, None),
+                ("in", ComponentAction::Reconfigure, None),
+                ("new", ComponentAction::Add, None),
+                ("out", ComponentAction::Restart, Some(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_global_changes() {
+        let new = format!("data_dir = \"/tmp\"\n{}", OLD);
+        let report = ReloadReport::new(&snapshot(OLD), &snapshot(&new));
+        assert!(report.global_changed);
+        assert!(report.components.is_empty());
+    }
+}
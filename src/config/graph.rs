@@ -6,6 +6,7 @@ use std::collections::{HashMap, HashSet};
 pub enum Node {
     Source {
         ty: DataType,
+        named_outputs: Vec<String>,
     },
     Transform {
         in_ty: DataType,
@@ -61,6 +62,7 @@ impl Graph {
                 id.clone(),
                 Node::Source {
                     ty: config.inner.output_type(),
+                    named_outputs: config.inner.named_outputs(),
                 },
             );
         }
@@ -174,8 +176,8 @@ impl Graph {
                     continue;
                 }
                 match (self.nodes[x].clone(), self.nodes[y].clone()) {
-                    (Node::Source { ty: ty1 }, Node::Sink { ty: ty2, .. })
-                    | (Node::Source { ty: ty1 }, Node::Transform { in_ty: ty2, .. })
+                    (Node::Source { ty: ty1, .. }, Node::Sink { ty: ty2, .. })
+                    | (Node::Source { ty: ty1, .. }, Node::Transform { in_ty: ty2, .. })
                     | (Node::Transform { out_ty: ty1, .. }, Node::Transform { in_ty: ty2, .. })
                     | (Node::Transform { out_ty: ty1, .. }, Node::Sink { ty: ty2, .. }) => {
                         if ty1 != ty2 && ty1 != DataType::Any && ty2 != DataType::Any {
@@ -204,8 +206,7 @@ impl Graph {
             .iter()
             .flat_map(|(key, node)| match node {
                 Node::Sink { .. } => vec![],
-                Node::Source { .. } => vec![key.clone().into()],
-                Node::Transform { named_outputs, .. } => {
+                Node::Source { named_outputs, .. } | Node::Transform { named_outputs, .. } => {
                     let mut outputs = vec![key.clone().into()];
                     outputs.extend(
                         named_outputs
@@ -227,7 +228,7 @@ impl Graph {
     /// which of a few things that represents:
     ///
     ///   1. A component that's part of an expanded macro (e.g. `route.branch`)
-    ///   2. A named output of a branching transform (e.g. `name.errors`)
+    ///   2. A named output of a branching transform or a source (e.g. `name.errors`)
     ///
     /// A naive way to do that is to compare the string representation of all valid inputs to the
     /// provided string and pick the one that matches. This works better if you can assume that there
@@ -312,7 +313,21 @@ mod test {
 
     impl Graph {
         fn add_source(&mut self, id: &str, ty: DataType) {
-            self.nodes.insert(id.into(), Node::Source { ty });
+            self.nodes.insert(
+                id.into(),
+                Node::Source {
+                    ty,
+                    named_outputs: Default::default(),
+                },
+            );
+        }
+
+        fn add_source_output(&mut self, id: &str, name: &str) {
+            let id = id.into();
+            match self.nodes.get_mut(&id) {
+                Some(Node::Source { named_outputs, .. }) => named_outputs.push(name.into()),
+                _ => panic!("invalid source"),
+            }
         }
 
         fn add_transform(
@@ -562,17 +577,46 @@ mod test {
         );
     }
 
+    #[test]
+    fn allows_multiple_source_outputs() {
+        let mut graph = Graph::default();
+        graph.add_source("log_source", DataType::Log);
+        graph.add_source_output("log_source", "events");
+        graph.add_sink("log_sink", DataType::Log, vec!["log_source"]);
+        graph.add_sink("events_sink", DataType::Log, vec![]);
+        graph.add_sink("bad_sink", DataType::Log, vec![]);
+
+        assert_eq!(
+            Ok(()),
+            graph.test_add_input("events_sink", "log_source.events")
+        );
+
+        let expected =
+            "Input \"log_source.not_events\" for sink \"bad_sink\" doesn't match any components."
+                .to_string();
+        assert_eq!(
+            Err(expected),
+            graph.test_add_input("bad_sink", "log_source.not_events")
+        );
+    }
+
     #[test]
     fn disallows_ambiguous_inputs() {
         let mut graph = Graph::default();
         // these all look like "foo.bar", but should only yield one error
         graph.nodes.insert(
             ComponentKey::global("foo.bar"),
-            Node::Source { ty: DataType::Any },
+            Node::Source {
+                ty: DataType::Any,
+                named_outputs: Default::default(),
+            },
         );
         graph.nodes.insert(
             ComponentKey::from("foo.bar"),
-            Node::Source { ty: DataType::Any },
+            Node::Source {
+                ty: DataType::Any,
+                named_outputs: Default::default(),
+            },
         );
         graph.nodes.insert(
             ComponentKey::from("foo"),
@@ -586,7 +630,10 @@ mod test {
         // make sure we return more than one
         graph.nodes.insert(
             ComponentKey::from("baz.errors"),
-            Node::Source { ty: DataType::Any },
+            Node::Source {
+                ty: DataType::Any,
+                named_outputs: Default::default(),
+            },
         );
         graph.nodes.insert(
             ComponentKey::global("baz"),
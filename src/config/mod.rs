@@ -3,7 +3,13 @@ use crate::{
     conditions,
     event::Metric,
     shutdown::ShutdownSignal,
-    sinks::{self, util::UriSerde},
+    sinks::{
+        self,
+        util::{
+            service::{RateLimiterConfig, SharedRateLimiter, SharedRateLimiters},
+            UriSerde,
+        },
+    },
     sources, Pipeline,
 };
 use async_trait::async_trait;
@@ -30,19 +36,23 @@ mod graph;
 mod id;
 mod loading;
 pub mod provider;
+mod template;
 mod unit_test;
 mod validation;
 mod vars;
 pub mod watcher;
 
 pub use builder::ConfigBuilder;
-pub use diff::ConfigDiff;
+pub use diff::{
+    ComponentAction, ComponentKind, ComponentReport, ConfigDiff, ConfigSnapshot, ReloadReport,
+};
 pub use format::{Format, FormatHint};
 pub use id::{ComponentKey, ComponentScope, OutputId};
 pub use loading::{
     load, load_builder_from_paths, load_from_paths, load_from_paths_with_provider, load_from_str,
     merge_path_lists, process_paths, CONFIG_PATHS,
 };
+pub use template::{ParamType, PipelineConfig, TemplateConfig, TemplateParam};
 pub use unit_test::build_unit_tests_main as build_unit_tests;
 pub use validation::warnings;
 pub use vector_core::config::proxy::ProxyConfig;
@@ -94,6 +104,9 @@ pub struct Config {
     #[cfg(feature = "datadog-pipelines")]
     pub datadog: datadog::Options,
     pub healthchecks: HealthcheckOptions,
+    pub event_tracing: EventTracingOptions,
+    pub limits: EventLimitsOptions,
+    pub rate_limiters: IndexMap<String, RateLimiterConfig>,
     pub sources: IndexMap<ComponentKey, SourceOuter>,
     pub sinks: IndexMap<ComponentKey, SinkOuter<OutputId>>,
     pub transforms: IndexMap<ComponentKey, TransformOuter<OutputId>>,
@@ -131,6 +144,75 @@ impl Default for HealthcheckOptions {
     }
 }
 
+/// Samples a fraction of source events and follows them through the topology,
+/// recording how long they spend queued in and processed by each component.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventTracingOptions {
+    pub enabled: bool,
+    pub sample_rate: f64,
+}
+
+impl EventTracingOptions {
+    fn merge(&mut self, other: Self) {
+        self.enabled |= other.enabled;
+        if other.sample_rate != Self::default().sample_rate {
+            self.sample_rate = other.sample_rate;
+        }
+    }
+}
+
+impl Default for EventTracingOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: 0.01,
+        }
+    }
+}
+
+/// Caps on the size of the fields and events emitted by sources, set globally
+/// and overridden per source, protecting the rest of the topology from the
+/// occasional outsized event.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventLimitsOptions {
+    /// The most bytes a string value of a log event can hold.
+    pub max_field_bytes: Option<usize>,
+    /// The most bytes an event can hold, as estimated from its fields.
+    pub max_event_bytes: Option<usize>,
+    /// What to do with events holding values over `max_field_bytes`.
+    pub action: Option<LimitAction>,
+    /// The field of truncated events listing their truncated fields.
+    pub annotation_field: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitAction {
+    Truncate,
+    Drop,
+}
+
+impl EventLimitsOptions {
+    fn merge(&mut self, other: Self) {
+        *self = self.overridden_by(&other);
+    }
+
+    /// The limits of a component, taking its own over these global ones.
+    pub fn overridden_by(&self, component: &Self) -> Self {
+        Self {
+            max_field_bytes: component.max_field_bytes.or(self.max_field_bytes),
+            max_event_bytes: component.max_event_bytes.or(self.max_event_bytes),
+            action: component.action.or(self.action),
+            annotation_field: component
+                .annotation_field
+                .clone()
+                .or_else(|| self.annotation_field.clone()),
+        }
+    }
+}
+
 pub trait GenerateConfig {
     fn generate_config() -> toml::Value;
 }
@@ -146,6 +228,27 @@ macro_rules! impl_generate_config_from_default {
     };
 }
 
+/// How the events of a component output are distributed among the
+/// components consuming it.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Distribution {
+    /// Every consumer receives every event.
+    Broadcast,
+    /// Each event goes to the next consumer in turn, waiting for it if it's
+    /// not ready.
+    RoundRobin,
+    /// Each event goes to the next consumer in turn that is ready to accept
+    /// it, skipping the ones applying backpressure.
+    LeastLoaded,
+}
+
+impl Default for Distribution {
+    fn default() -> Self {
+        Self::Broadcast
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SourceOuter {
     #[serde(default = "default_acknowledgements")]
@@ -155,6 +258,16 @@ pub struct SourceOuter {
         skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
     )]
     pub proxy: ProxyConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub distribution: Distribution,
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub limits: EventLimitsOptions,
     #[serde(flatten)]
     pub(super) inner: Box<dyn SourceConfig>,
 }
@@ -169,6 +282,8 @@ impl SourceOuter {
             acknowledgements: default_acknowledgements(),
             inner: Box::new(source),
             proxy: Default::default(),
+            distribution: Default::default(),
+            limits: Default::default(),
         }
     }
 }
@@ -180,6 +295,11 @@ pub trait SourceConfig: core::fmt::Debug + Send + Sync {
 
     fn output_type(&self) -> DataType;
 
+    /// Additional outputs, beyond the default one, that the source may emit events to.
+    fn named_outputs(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     fn source_type(&self) -> &'static str;
 
     /// Resources that the source is using.
@@ -193,6 +313,7 @@ pub struct SourceContext {
     pub globals: GlobalOptions,
     pub shutdown: ShutdownSignal,
     pub out: Pipeline,
+    pub named_outputs: HashMap<String, Pipeline>,
     pub acknowledgements: bool,
     pub proxy: ProxyConfig,
 }
@@ -211,6 +332,7 @@ impl SourceContext {
                 globals: GlobalOptions::default(),
                 shutdown: shutdown_signal,
                 out,
+                named_outputs: HashMap::new(),
                 acknowledgements: default_acknowledgements(),
                 proxy: Default::default(),
             },
@@ -225,10 +347,17 @@ impl SourceContext {
             globals: GlobalOptions::default(),
             shutdown: ShutdownSignal::noop(),
             out,
+            named_outputs: HashMap::new(),
             acknowledgements: default_acknowledgements(),
             proxy: Default::default(),
         }
     }
+
+    /// Takes the pipeline for the named output `name`, as declared by
+    /// [`SourceConfig::named_outputs`].
+    pub fn take_named_output(&mut self, name: &str) -> Option<Pipeline> {
+        self.named_outputs.remove(name)
+    }
 }
 
 pub type SourceDescription = ComponentDescription<Box<dyn SourceConfig>>;
@@ -256,6 +385,11 @@ pub struct SinkOuter<T> {
     )]
     proxy: ProxyConfig,
 
+    /// Resolve the batches of events the sink still holds after this long as
+    /// errored, rather than leaving their sources waiting on them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgement_timeout_secs: Option<u64>,
+
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
@@ -269,6 +403,7 @@ impl<T> SinkOuter<T> {
             healthcheck_uri: None,
             inner,
             proxy: Default::default(),
+            acknowledgement_timeout_secs: None,
         }
     }
 
@@ -313,6 +448,7 @@ impl<T> SinkOuter<T> {
             healthcheck: self.healthcheck,
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
+            acknowledgement_timeout_secs: self.acknowledgement_timeout_secs,
         }
     }
 }
@@ -322,6 +458,12 @@ impl<T> SinkOuter<T> {
 pub struct SinkHealthcheckOptions {
     pub enabled: bool,
     pub uri: Option<UriSerde>,
+    /// Re-run the healthcheck at this interval once the sink is started,
+    /// pausing the sink while it's unhealthy.
+    pub interval_secs: Option<u64>,
+    /// The number of consecutive periodic healthcheck failures after which
+    /// the sink is paused.
+    pub failure_threshold: usize,
 }
 
 impl Default for SinkHealthcheckOptions {
@@ -329,21 +471,26 @@ impl Default for SinkHealthcheckOptions {
         Self {
             enabled: true,
             uri: None,
+            interval_secs: None,
+            failure_threshold: 3,
         }
     }
 }
 
 impl From<bool> for SinkHealthcheckOptions {
     fn from(enabled: bool) -> Self {
-        Self { enabled, uri: None }
+        Self {
+            enabled,
+            ..Self::default()
+        }
     }
 }
 
 impl From<UriSerde> for SinkHealthcheckOptions {
     fn from(uri: UriSerde) -> Self {
         Self {
-            enabled: true,
             uri: Some(uri),
+            ..Self::default()
         }
     }
 }
@@ -372,6 +519,7 @@ pub struct SinkContext {
     pub(super) healthcheck: SinkHealthcheckOptions,
     pub(super) globals: GlobalOptions,
     pub(super) proxy: ProxyConfig,
+    pub(super) rate_limiters: SharedRateLimiters,
 }
 
 impl SinkContext {
@@ -382,6 +530,7 @@ impl SinkContext {
             healthcheck: SinkHealthcheckOptions::default(),
             globals: GlobalOptions::default(),
             proxy: ProxyConfig::default(),
+            rate_limiters: SharedRateLimiters::default(),
         }
     }
 
@@ -396,6 +545,15 @@ impl SinkContext {
     pub const fn proxy(&self) -> &ProxyConfig {
         &self.proxy
     }
+
+    /// Gets the shared rate limiter of the given name, failing if there's
+    /// no such rate limiter in the config.
+    pub fn shared_rate_limiter(
+        &self,
+        name: Option<&str>,
+    ) -> crate::Result<Option<SharedRateLimiter>> {
+        name.map(|name| self.rate_limiters.get(name)).transpose()
+    }
 }
 
 pub type SinkDescription = ComponentDescription<Box<dyn SinkConfig>>;
@@ -406,6 +564,11 @@ inventory::collect!(SinkDescription);
 pub struct TransformOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
     pub inputs: Vec<T>,
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub distribution: Distribution,
     #[serde(flatten)]
     pub inner: Box<dyn TransformConfig>,
 }
@@ -419,6 +582,7 @@ impl<T> TransformOuter<T> {
     fn with_inputs<U>(self, inputs: Vec<U>) -> TransformOuter<U> {
         TransformOuter {
             inputs,
+            distribution: self.distribution,
             inner: self.inner,
         }
     }
@@ -570,6 +734,15 @@ pub struct TestInput {
     pub value: Option<String>,
     pub log_fields: Option<IndexMap<String, TestInputValue>>,
     pub metric: Option<Metric>,
+    /// A file to read the input events from, for the `file` input type.
+    pub file: Option<PathBuf>,
+    /// How the `file` input is split into frames, newline delimited by default.
+    #[cfg(feature = "codecs")]
+    pub framing: Option<Box<dyn crate::codecs::FramingConfig>>,
+    /// How the frames of the `file` input are decoded into events, as raw
+    /// messages by default.
+    #[cfg(feature = "codecs")]
+    pub decoding: Option<crate::codecs::ParserSettings>,
 }
 
 fn default_test_input_type() -> String {
@@ -581,6 +754,11 @@ fn default_test_input_type() -> String {
 pub struct TestOutput {
     pub extract_from: ComponentKey,
     pub conditions: Option<Vec<conditions::AnyCondition>>,
+    /// A file holding the expected output events, encoded as NDJSON.
+    pub golden_file: Option<PathBuf>,
+    /// The fields left out when comparing log events against the golden file,
+    /// the timestamp field by default.
+    pub golden_ignore_fields: Option<Vec<String>>,
 }
 
 impl Config {
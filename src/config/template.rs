@@ -0,0 +1,461 @@
+use super::{ComponentKey, ConfigBuilder, SinkOuter, SourceOuter, TransformOuter};
+use indexmap::{IndexMap, IndexSet};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// Matches the references to template parameters, which are namespaced under
+/// `params.` so as not to be confused with the event field templates of the
+/// components.
+static PARAM_REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*params\.(\w+)\s*\}\}").unwrap());
+
+/// A parameterized group of components, instantiated by pipelines.
+///
+/// The components are kept as raw values until instantiated, as their
+/// parameters have to be substituted before they can be deserialized.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateConfig {
+    #[serde(default)]
+    pub params: IndexMap<String, TemplateParam>,
+    #[serde(default)]
+    pub sources: IndexMap<ComponentKey, Value>,
+    #[serde(default)]
+    pub transforms: IndexMap<ComponentKey, Value>,
+    #[serde(default)]
+    pub sinks: IndexMap<ComponentKey, Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateParam {
+    #[serde(default, rename = "type")]
+    pub kind: ParamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamType {
+    #[derivative(Default)]
+    String,
+    Integer,
+    Float,
+    Bool,
+    Array,
+}
+
+impl ParamType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Float => value.is_number(),
+            Self::Bool => value.is_boolean(),
+            Self::Array => value.is_array(),
+        }
+    }
+}
+
+/// An instance of a template, whose components are added to the config with
+/// their keys prefixed by the name of the pipeline.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineConfig {
+    pub template: String,
+    #[serde(default)]
+    pub params: IndexMap<String, Value>,
+}
+
+/// Replaces the pipelines of the config with the components of their
+/// templates.
+pub(super) fn expand_pipelines(config: &mut ConfigBuilder) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    for (name, pipeline) in std::mem::take(&mut config.pipelines) {
+        let template = match config.templates.get(&pipeline.template) {
+            Some(template) => template,
+            None => {
+                errors.push(format!(
+                    "Pipeline \"{}\" uses undefined template \"{}\".",
+                    name, pipeline.template
+                ));
+                continue;
+            }
+        };
+
+        match template.instantiate(&name, &pipeline.params) {
+            Ok(instance) => {
+                add_components(&mut config.sources, instance.sources, "source", &mut errors);
+                add_components(
+                    &mut config.transforms,
+                    instance.transforms,
+                    "transform",
+                    &mut errors,
+                );
+                add_components(&mut config.sinks, instance.sinks, "sink", &mut errors);
+            }
+            Err(instance_errors) => errors.extend(instance_errors),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn add_components<T>(
+    components: &mut IndexMap<ComponentKey, T>,
+    instances: IndexMap<ComponentKey, T>,
+    kind: &str,
+    errors: &mut Vec<String>,
+) {
+    for (key, component) in instances {
+        if components.contains_key(&key) {
+            errors.push(format!("duplicate {} id found: {}", kind, key));
+        } else {
+            components.insert(key, component);
+        }
+    }
+}
+
+struct Instance {
+    sources: IndexMap<ComponentKey, SourceOuter>,
+    transforms: IndexMap<ComponentKey, TransformOuter<String>>,
+    sinks: IndexMap<ComponentKey, SinkOuter<String>>,
+}
+
+impl TemplateConfig {
+    fn instantiate(
+        &self,
+        pipeline: &ComponentKey,
+        params: &IndexMap<String, Value>,
+    ) -> Result<Instance, Vec<String>> {
+        let params = self.resolve_params(pipeline, params)?;
+        let locals = self
+            .sources
+            .keys()
+            .chain(self.transforms.keys())
+            .chain(self.sinks.keys())
+            .map(ComponentKey::id)
+            .collect::<IndexSet<_>>();
+
+        let mut errors = Vec::new();
+        let context = (pipeline, &params, &locals);
+        let sources = instantiate_components("source", &self.sources, context, &mut errors);
+        let transforms =
+            instantiate_components("transform", &self.transforms, context, &mut errors);
+        let sinks = instantiate_components("sink", &self.sinks, context, &mut errors);
+
+        if errors.is_empty() {
+            Ok(Instance {
+                sources,
+                transforms,
+                sinks,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks the parameters given by a pipeline against the ones of the
+    /// template, filling in the defaults of the missing ones.
+    fn resolve_params(
+        &self,
+        pipeline: &ComponentKey,
+        params: &IndexMap<String, Value>,
+    ) -> Result<IndexMap<String, Value>, Vec<String>> {
+        let mut errors = params
+            .keys()
+            .filter(|name| !self.params.contains_key(*name))
+            .map(|name| {
+                format!(
+                    "Pipeline \"{}\" sets unknown parameter \"{}\".",
+                    pipeline, name
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut resolved = IndexMap::new();
+        for (name, param) in &self.params {
+            match params.get(name).or_else(|| param.default.as_ref()) {
+                Some(value) if param.kind.matches(value) => {
+                    resolved.insert(name.clone(), value.clone());
+                }
+                Some(value) => errors.push(format!(
+                    "Parameter \"{}\" of pipeline \"{}\" should be of type {:?}, found {}.",
+                    name, pipeline, param.kind, value
+                )),
+                None => errors.push(format!(
+                    "Pipeline \"{}\" is missing required parameter \"{}\".",
+                    pipeline, name
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(resolved)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn instantiate_components<T: DeserializeOwned>(
+    kind: &str,
+    components: &IndexMap<ComponentKey, Value>,
+    (pipeline, params, locals): (&ComponentKey, &IndexMap<String, Value>, &IndexSet<&str>),
+    errors: &mut Vec<String>,
+) -> IndexMap<ComponentKey, T> {
+    components
+        .iter()
+        .filter_map(|(key, value)| {
+            substitute(value, params)
+                .and_then(|mut value| {
+                    prefix_inputs(&mut value, pipeline, locals);
+                    serde_json::from_value(value).map_err(|error| error.to_string())
+                })
+                .map(|component| {
+                    let key = ComponentKey::global(format!("{}.{}", pipeline, key));
+                    (key, component)
+                })
+                .map_err(|error| {
+                    errors.push(format!(
+                        "Failed to instantiate {} \"{}\" of pipeline \"{}\": {}",
+                        kind, key, pipeline, error
+                    ))
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/// Substitutes the parameter references in the string values of a component.
+/// A string holding nothing but a reference is replaced by the parameter
+/// itself, so that non-string parameters keep their types.
+fn substitute(value: &Value, params: &IndexMap<String, Value>) -> Result<Value, String> {
+    match value {
+        Value::String(text) => {
+            if let Some(captures) = PARAM_REFERENCE.captures(text) {
+                if captures[0].len() == text.len() {
+                    return lookup(&captures[1], params).map(Clone::clone);
+                }
+            }
+
+            let mut error = None;
+            let text = PARAM_REFERENCE.replace_all(text, |captures: &Captures<'_>| {
+                match lookup(&captures[1], params) {
+                    Ok(Value::String(param)) => param.clone(),
+                    Ok(param @ Value::Number(_)) | Ok(param @ Value::Bool(_)) => param.to_string(),
+                    Ok(_) => {
+                        error = Some(format!(
+                            "Parameter \"{}\" can't be interpolated into a string.",
+                            &captures[1]
+                        ));
+                        String::new()
+                    }
+                    Err(lookup_error) => {
+                        error = Some(lookup_error);
+                        String::new()
+                    }
+                }
+            });
+            match error {
+                Some(error) => Err(error),
+                None => Ok(Value::String(text.into_owned())),
+            }
+        }
+        Value::Array(values) => values
+            .iter()
+            .map(|value| substitute(value, params))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), substitute(value, params)?)))
+            .collect::<Result<_, String>>()
+            .map(Value::Object),
+        value => Ok(value.clone()),
+    }
+}
+
+fn lookup<'a>(name: &str, params: &'a IndexMap<String, Value>) -> Result<&'a Value, String> {
+    params
+        .get(name)
+        .ok_or_else(|| format!("Undefined parameter \"{}\".", name))
+}
+
+/// Points the inputs referencing other components of the template, or their
+/// named outputs, at the components of the pipeline.
+fn prefix_inputs(value: &mut Value, pipeline: &ComponentKey, locals: &IndexSet<&str>) {
+    let inputs = match value.get_mut("inputs") {
+        Some(Value::Array(inputs)) => inputs,
+        _ => return,
+    };
+    for input in inputs {
+        if let Value::String(input) = input {
+            let component = input.split('.').next().unwrap_or_default();
+            if locals.contains(component) {
+                *input = format!("{}.{}", pipeline, input);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Format;
+    use serde_json::json;
+
+    fn params(value: Value) -> IndexMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn substitutes_params() {
+        let params = params(json!({"service": "checkout", "replicas": 3, "tags": ["a", "b"]}));
+
+        assert_eq!(
+            substitute(
+                &json!({
+                    "index": "logs-{{ params.service }}-{{ timestamp }}",
+                    "replicas": "{{params.replicas}}",
+                    "tags": ["{{ params.tags }}", "x{{ params.replicas }}"],
+                }),
+                &params
+            ),
+            Ok(json!({
+                "index": "logs-checkout-{{ timestamp }}",
+                "replicas": 3,
+                "tags": [["a", "b"], "x3"],
+            }))
+        );
+        assert!(substitute(&json!("{{ params.missing }}"), &params).is_err());
+        assert!(substitute(&json!("x{{ params.tags }}"), &params).is_err());
+    }
+
+    #[test]
+    fn prefixes_local_inputs() {
+        let locals = vec!["parse", "route"].into_iter().collect();
+        let mut value = json!({"inputs": ["parse", "route.errors", "app", "parser"]});
+        prefix_inputs(&mut value, &ComponentKey::from("checkout"), &locals);
+
+        assert_eq!(
+            value,
+            json!({"inputs": ["checkout.parse", "checkout.route.errors", "app", "parser"]})
+        );
+    }
+
+    #[cfg(all(
+        feature = "sources-stdin",
+        feature = "transforms-remap",
+        feature = "sinks-console"
+    ))]
+    #[test]
+    fn expands_pipelines() {
+        let mut builder: ConfigBuilder = crate::config::format::deserialize(
+            r#"
+            [sources.in]
+            type = "stdin"
+
+            [templates.app_logs.params.service]
+            [templates.app_logs.params.inputs]
+            type = "array"
+            [templates.app_logs.params.target]
+            default = "stdout"
+
+            [templates.app_logs.transforms.tag]
+            type = "remap"
+            inputs = "{{ params.inputs }}"
+            source = '.service = "{{ params.service }}"'
+
+            [templates.app_logs.sinks.out]
+            type = "console"
+            inputs = ["tag"]
+            target = "{{ params.target }}"
+            encoding.codec = "json"
+
+            [pipelines.checkout]
+            template = "app_logs"
+            params.service = "checkout"
+            params.inputs = ["in"]
+
+            [pipelines.billing]
+            template = "app_logs"
+            params.service = "billing"
+            params.inputs = ["checkout.tag"]
+            params.target = "stderr"
+            "#,
+            Some(Format::Toml),
+        )
+        .unwrap();
+        expand_pipelines(&mut builder).unwrap();
+
+        let keys = |components: Vec<&ComponentKey>| {
+            components
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            keys(builder.transforms.keys().collect()),
+            vec!["checkout.tag", "billing.tag"]
+        );
+        assert_eq!(
+            keys(builder.sinks.keys().collect()),
+            vec!["checkout.out", "billing.out"]
+        );
+        assert_eq!(
+            builder.transforms[&ComponentKey::from("billing.tag")].inputs,
+            vec!["checkout.tag"]
+        );
+        assert_eq!(
+            builder.sinks[&ComponentKey::from("billing.out")].inputs,
+            vec!["billing.tag"]
+        );
+        assert!(builder.pipelines.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_pipelines() {
+        let mut builder: ConfigBuilder = crate::config::format::deserialize(
+            r#"
+            [templates.app_logs.params.service]
+            [templates.app_logs.params.replicas]
+            type = "integer"
+            default = 1
+
+            [pipelines.missing]
+            template = "app_logs"
+
+            [pipelines.mistyped]
+            template = "app_logs"
+            params.service = "checkout"
+            params.replicas = "two"
+            params.extra = true
+
+            [pipelines.undefined]
+            template = "other"
+            "#,
+            Some(Format::Toml),
+        )
+        .unwrap();
+
+        assert_eq!(
+            expand_pipelines(&mut builder),
+            Err(vec![
+                "Pipeline \"missing\" is missing required parameter \"service\".".to_owned(),
+                "Pipeline \"mistyped\" sets unknown parameter \"extra\".".to_owned(),
+                "Parameter \"replicas\" of pipeline \"mistyped\" should be of type Integer, found \"two\".".to_owned(),
+                "Pipeline \"undefined\" uses undefined template \"other\".".to_owned(),
+            ])
+        );
+    }
+}
@@ -1,16 +1,16 @@
 use super::{
     graph::Graph, ComponentKey, Config, ConfigBuilder, ConfigDiff, ConfigPath, GlobalOptions,
-    TestDefinition, TestInput, TestInputValue, TransformConfig, TransformContext,
+    TestDefinition, TestInput, TestInputValue, TestOutput, TransformConfig, TransformContext,
 };
 use crate::{
     conditions::Condition,
-    config,
+    config::{self, log_schema},
     event::{Event, Value},
     topology::builder::load_enrichment_tables,
     transforms::Transform,
 };
 use indexmap::IndexMap;
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 pub async fn build_unit_tests_main(paths: &[ConfigPath]) -> Result<Vec<UnitTest>, Vec<String>> {
     config::init_log_schema(paths, false)?;
@@ -24,6 +24,7 @@ async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, V
     let mut tests = vec![];
     let mut errors = vec![];
 
+    super::template::expand_pipelines(&mut builder)?;
     let expansions = super::compiler::expand_macros(&mut builder)?;
 
     // Resolve inputs via the graph, even though we haven't fully validated everything here
@@ -51,6 +52,9 @@ async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, V
         #[cfg(feature = "datadog-pipelines")]
         datadog: builder.datadog,
         healthchecks: builder.healthchecks,
+        event_tracing: builder.event_tracing,
+        limits: builder.limits,
+        rate_limiters: builder.rate_limiters,
         enrichment_tables: builder.enrichment_tables,
         sources: builder.sources,
         sinks,
@@ -81,7 +85,10 @@ async fn build_unit_tests(mut builder: ConfigBuilder) -> Result<Vec<UnitTest>, V
 
 pub struct UnitTest {
     pub name: String,
-    inputs: Vec<(Vec<ComponentKey>, Event)>,
+    /// Overwrite the golden files with the actual outputs instead of comparing
+    /// against them.
+    pub update_golden_files: bool,
+    inputs: Vec<(Vec<ComponentKey>, Vec<Event>)>,
     transforms: IndexMap<ComponentKey, UnitTestTransform>,
     checks: Vec<UnitTestCheck>,
     no_outputs_from: Vec<ComponentKey>,
@@ -97,6 +104,168 @@ struct UnitTestTransform {
 struct UnitTestCheck {
     extract_from: ComponentKey,
     conditions: Vec<Box<dyn Condition>>,
+    golden_file: Option<GoldenFile>,
+}
+
+/// The maximum number of differing events detailed when comparing outputs
+/// against a golden file.
+const MAX_REPORTED_GOLDEN_DIFFERENCES: usize = 10;
+
+struct GoldenFile {
+    path: PathBuf,
+    ignore_fields: Vec<String>,
+}
+
+impl GoldenFile {
+    fn new(output: &TestOutput) -> Option<Self> {
+        output.golden_file.as_ref().map(|path| Self {
+            path: path.clone(),
+            ignore_fields: output
+                .golden_ignore_fields
+                .clone()
+                .unwrap_or_else(|| vec![log_schema().timestamp_key().to_owned()]),
+        })
+    }
+
+    fn to_value(&self, event: &Event) -> serde_json::Value {
+        let value = match event {
+            Event::Log(log) => {
+                let mut log = log.clone();
+                for field in &self.ignore_fields {
+                    log.remove(field);
+                }
+                serde_json::to_value(&log)
+            }
+            Event::Metric(metric) => serde_json::to_value(&metric),
+        };
+        value.unwrap_or(serde_json::Value::Null)
+    }
+
+    /// Compares the events against the golden file, or overwrites the golden
+    /// file with them if `update` is set.
+    fn check(&self, events: &[Event], update: bool) -> Result<(), String> {
+        let actual = events
+            .iter()
+            .map(|event| self.to_value(event))
+            .collect::<Vec<_>>();
+
+        if update {
+            let contents = actual
+                .iter()
+                .map(|value| format!("{}\n", value))
+                .collect::<String>();
+            return std::fs::write(&self.path, contents).map_err(|error| {
+                format!("unable to write golden file {:?}: {}", self.path, error)
+            });
+        }
+
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|error| format!("unable to read golden file {:?}: {}", self.path, error))?;
+        let expected = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|error| {
+                    format!(
+                        "invalid JSON on line {} of golden file {:?}: {}",
+                        i + 1,
+                        self.path,
+                        error
+                    )
+                })
+            })
+            .collect::<Result<Vec<serde_json::Value>, _>>()?;
+
+        let report = diff_events(&expected, &actual);
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "outputs differ from golden file {:?} (- expected, + actual):\n{}",
+                self.path,
+                report.join("\n")
+            ))
+        }
+    }
+}
+
+/// Reports the differences between the expected and actual events, in order,
+/// down to the differing fields.
+fn diff_events(expected: &[serde_json::Value], actual: &[serde_json::Value]) -> Vec<String> {
+    let mut report = Vec::new();
+    let mut differences = 0;
+
+    if expected.len() != actual.len() {
+        report.push(format!(
+            "expected {} events, got {}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+
+    for i in 0..expected.len().max(actual.len()) {
+        let (expected, actual) = (expected.get(i), actual.get(i));
+        if expected == actual {
+            continue;
+        }
+        differences += 1;
+        if differences > MAX_REPORTED_GOLDEN_DIFFERENCES {
+            continue;
+        }
+
+        report.push(format!("event[{}]:", i));
+        match (expected, actual) {
+            (Some(expected), Some(actual)) => diff_values(".", expected, actual, &mut report),
+            (Some(expected), None) => report.push(format!("  - {}", expected)),
+            (None, Some(actual)) => report.push(format!("  + {}", actual)),
+            (None, None) => unreachable!("at least one of the events exists"),
+        }
+    }
+
+    if differences > MAX_REPORTED_GOLDEN_DIFFERENCES {
+        report.push(format!(
+            "... and {} more differing events",
+            differences - MAX_REPORTED_GOLDEN_DIFFERENCES
+        ));
+    }
+
+    report
+}
+
+fn diff_values(
+    path: &str,
+    expected: &serde_json::Value,
+    actual: &serde_json::Value,
+    report: &mut Vec<String>,
+) {
+    use serde_json::Value as JsonValue;
+
+    match (expected, actual) {
+        (JsonValue::Object(expected), JsonValue::Object(actual)) => {
+            let mut keys = expected.keys().chain(actual.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if path == "." {
+                    format!(".{}", key)
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => diff_values(&path, expected, actual, report),
+                    (Some(expected), None) => report.push(format!("  {}: - {}", path, expected)),
+                    (None, Some(actual)) => report.push(format!("  {}: + {}", path, actual)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (expected, actual) if expected != actual => {
+            report.push(format!("  {}: - {}", path, expected));
+            report.push(format!("  {}: + {}", path, actual));
+        }
+        _ => {}
+    }
 }
 
 fn event_to_string(event: &Event) -> String {
@@ -173,6 +342,24 @@ fn walk(
                     next: target.next
                 });
             }
+            Transform::MultiOutputTask(t) => {
+                let in_stream = futures::stream::iter(inputs.clone());
+                let out_stream = t.transform(Box::pin(in_stream));
+                // unit tests don't currently support multiple outputs, so only keep the default one
+                let out_iter = futures::executor::block_on_stream(out_stream)
+                    .filter(|routed| routed.output.is_none())
+                    .map(|routed| routed.event);
+                results.extend(out_iter);
+                targets = target.next.clone();
+                // TODO: This is a hack.
+                // Our tasktransforms must consume the transform to attach it to an input stream, so we rebuild it between input streams.
+                transforms.insert(key, UnitTestTransform {
+                    transform:  futures::executor::block_on(target.config.clone().build(&TransformContext::new_with_globals(globals.clone())))
+                        .expect("Failed to build a known valid transform config. Things may have changed during runtime."),
+                    config: target.config,
+                    next: target.next
+                });
+            }
         }
     }
 
@@ -201,12 +388,12 @@ impl UnitTest {
         let mut results = HashMap::new();
 
         let mut inputs_by_target = HashMap::new();
-        for (targets, event) in &self.inputs {
+        for (targets, events) in &self.inputs {
             for target in targets {
                 let entry = inputs_by_target
                     .entry(target.clone())
                     .or_insert_with(Vec::new);
-                entry.push(event.clone());
+                entry.extend(events.iter().cloned());
             }
         }
 
@@ -221,6 +408,22 @@ impl UnitTest {
         }
 
         for check in &self.checks {
+            if let Some(golden_file) = &check.golden_file {
+                let outputs = results
+                    .get(&check.extract_from)
+                    .map_or(&[][..], |(_, outputs)| outputs.as_slice());
+                if let Err(error) = golden_file.check(outputs, self.update_golden_files) {
+                    errors.push(format!(
+                        "check transform '{}' failed golden file comparison:\n  {}",
+                        check.extract_from,
+                        error.replace("\n", "\n  "),
+                    ));
+                }
+                if check.conditions.is_empty() {
+                    continue;
+                }
+            }
+
             if let Some((inputs, outputs)) = results.get(&check.extract_from) {
                 if check.conditions.is_empty() {
                     inspections.push(format!(
@@ -353,12 +556,15 @@ fn reduce_transforms(
     });
 }
 
-fn build_input(config: &Config, input: &TestInput) -> Result<(Vec<ComponentKey>, Event), String> {
+fn build_input(
+    config: &Config,
+    input: &TestInput,
+) -> Result<(Vec<ComponentKey>, Vec<Event>), String> {
     let target = config.get_inputs(&input.insert_at);
 
     match input.type_str.as_ref() {
         "raw" => match input.value.as_ref() {
-            Some(v) => Ok((target, Event::from(v.clone()))),
+            Some(v) => Ok((target, vec![Event::from(v.clone())])),
             None => Err("input type 'raw' requires the field 'value'".to_string()),
         },
         "log" => {
@@ -373,29 +579,77 @@ fn build_input(config: &Config, input: &TestInput) -> Result<(Vec<ComponentKey>,
                     };
                     event.as_mut_log().insert(path.to_owned(), value);
                 }
-                Ok((target, event))
+                Ok((target, vec![event]))
             } else {
                 Err("input type 'log' requires the field 'log_fields'".to_string())
             }
         }
         "metric" => {
             if let Some(metric) = &input.metric {
-                Ok((target, Event::Metric(metric.clone())))
+                Ok((target, vec![Event::Metric(metric.clone())]))
             } else {
                 Err("input type 'metric' requires the field 'metric'".to_string())
             }
         }
+        "file" => match input.file.as_ref() {
+            Some(path) => Ok((target, read_input_file(input, path)?)),
+            None => Err("input type 'file' requires the field 'file'".to_string()),
+        },
         _ => Err(format!(
-            "unrecognized input type '{}', expected one of: 'raw', 'log' or 'metric'",
+            "unrecognized input type '{}', expected one of: 'raw', 'log', 'metric' or 'file'",
             input.type_str
         )),
     }
 }
 
+/// Decodes the events of a `file` input, splitting the file into frames and
+/// decoding each of them the same way a source would.
+#[cfg(feature = "codecs")]
+fn read_input_file(input: &TestInput, path: &std::path::Path) -> Result<Vec<Event>, String> {
+    use crate::{
+        codecs::DecodingConfig,
+        serde::{default_decoding, default_framing_stream_based},
+    };
+    use tokio_util::codec::Decoder;
+
+    let contents = std::fs::read(path)
+        .map_err(|error| format!("unable to read input file {:?}: {}", path, error))?;
+    let mut decoder = DecodingConfig::new(
+        input
+            .framing
+            .clone()
+            .unwrap_or_else(default_framing_stream_based),
+        input.decoding.clone().unwrap_or_else(default_decoding),
+    )
+    .build()
+    .map_err(|error| format!("invalid decoding of input file {:?}: {}", path, error))?;
+
+    let mut buf = bytes::BytesMut::from(&contents[..]);
+    let mut events = Vec::new();
+    loop {
+        match decoder.decode_eof(&mut buf) {
+            Ok(Some((decoded, _))) => events.extend(decoded),
+            Ok(None) => break,
+            Err(error) => return Err(format!("unable to decode input file {:?}: {}", path, error)),
+        }
+    }
+
+    if events.is_empty() {
+        Err(format!("input file {:?} contains no events", path))
+    } else {
+        Ok(events)
+    }
+}
+
+#[cfg(not(feature = "codecs"))]
+fn read_input_file(_input: &TestInput, _path: &std::path::Path) -> Result<Vec<Event>, String> {
+    Err("input type 'file' requires Vector to be built with the `codecs` feature".to_string())
+}
+
 fn build_inputs(
     config: &Config,
     definition: &TestDefinition,
-) -> Result<Vec<(Vec<ComponentKey>, Event)>, Vec<String>> {
+) -> Result<Vec<(Vec<ComponentKey>, Vec<Event>)>, Vec<String>> {
     let mut inputs = Vec::new();
     let mut errors = vec![];
 
@@ -493,6 +747,7 @@ async fn build_unit_test(
     errors.extend(tables_errors);
 
     let context = TransformContext {
+        key: None,
         globals: config.global.clone(),
         enrichment_tables: enrichment_tables.clone(),
     };
@@ -565,6 +820,7 @@ async fn build_unit_test(
             UnitTestCheck {
                 extract_from: o.extract_from.clone(),
                 conditions,
+                golden_file: GoldenFile::new(o),
             }
         })
         .collect();
@@ -582,6 +838,7 @@ async fn build_unit_test(
     } else {
         Ok(UnitTest {
             name: definition.name.clone(),
+            update_golden_files: false,
             inputs,
             transforms,
             checks,
@@ -853,7 +1110,7 @@ mod tests {
             errs,
             vec![indoc! {r#"
                 Failed to build test 'broken test':
-                  unrecognized input type 'nah', expected one of: 'raw', 'log' or 'metric'"#}
+                  unrecognized input type 'nah', expected one of: 'raw', 'log', 'metric' or 'file'"#}
             .to_owned(),]
         );
     }
@@ -1266,6 +1523,92 @@ mod tests {
         assert_eq!(tests[0].run().1, Vec::<String>::new());
     }
 
+    #[cfg(feature = "codecs")]
+    #[tokio::test]
+    async fn test_file_input_golden_file() {
+        let dir = crate::test_util::temp_dir();
+        let input = dir.join("input.ndjson");
+        let golden = dir.join("golden.ndjson");
+        std::fs::write(
+            &input,
+            "{\"message\":\"first\"}\n\n{\"message\":\"second\"}\n",
+        )
+        .unwrap();
+
+        let config = || -> ConfigBuilder {
+            toml::from_str(&format!(
+                indoc! { r#"
+                    [transforms.foo]
+                      inputs = ["ignored"]
+                      type = "add_fields"
+                      [transforms.foo.fields]
+                        new_field = "string value"
+
+                    [[tests]]
+                      name = "successful test with file input"
+
+                      [[tests.inputs]]
+                        insert_at = "foo"
+                        type = "file"
+                        file = "{}"
+                        decoding.codec = "json"
+
+                      [[tests.outputs]]
+                        extract_from = "foo"
+                        golden_file = "{}"
+                "#},
+                input.display(),
+                golden.display()
+            ))
+            .unwrap()
+        };
+
+        // A missing golden file is an error, until it's generated.
+        let mut tests = build_unit_tests(config()).await.unwrap();
+        assert_ne!(tests[0].run().1, Vec::<String>::new());
+
+        tests[0].update_golden_files = true;
+        assert_eq!(tests[0].run().1, Vec::<String>::new());
+        assert_eq!(std::fs::read_to_string(&golden).unwrap().lines().count(), 2);
+
+        let mut tests = build_unit_tests(config()).await.unwrap();
+        assert_eq!(tests[0].run().1, Vec::<String>::new());
+
+        std::fs::write(
+            &golden,
+            "{\"message\":\"first\",\"new_field\":\"string value\"}\n\
+             {\"message\":\"other\",\"new_field\":\"string value\"}\n",
+        )
+        .unwrap();
+        let errors = tests[0].run().1;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("event[1]:"));
+        assert!(errors[0].contains(r#".message: - "other""#));
+        assert!(errors[0].contains(r#".message: + "second""#));
+        assert!(!errors[0].contains("event[0]:"));
+    }
+
+    #[test]
+    fn diff_events_reports_count_and_fields() {
+        let expected = vec![
+            serde_json::json!({"message": "a", "nested": {"x": 1}}),
+            serde_json::json!({"message": "b"}),
+        ];
+        let actual = vec![serde_json::json!({"message": "a", "nested": {"x": 2}})];
+
+        assert_eq!(
+            diff_events(&expected, &actual),
+            vec![
+                "expected 2 events, got 1",
+                "event[0]:",
+                "  .nested.x: - 1",
+                "  .nested.x: + 2",
+                "event[1]:",
+                r#"  - {"message":"b"}"#,
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_metric_input() {
         let config: ConfigBuilder = toml::from_str(indoc! { r#"
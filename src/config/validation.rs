@@ -43,6 +43,34 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         errors.push("No sinks defined in the config.".to_owned());
     }
 
+    let sample_rate = config.event_tracing.sample_rate;
+    if !(sample_rate > 0.0 && sample_rate <= 1.0) {
+        errors.push(format!(
+            "Event tracing sample rate must be in (0, 1], got {}.",
+            sample_rate
+        ));
+    }
+
+    for (scope, limits) in std::iter::once(("Global".to_owned(), &config.limits)).chain(
+        config
+            .sources
+            .iter()
+            .map(|(key, source)| (format!("Source \"{}\"", key), &source.limits)),
+    ) {
+        if limits.max_field_bytes == Some(0) || limits.max_event_bytes == Some(0) {
+            errors.push(format!("{} limits must be greater than zero.", scope));
+        }
+    }
+
+    for (name, rate_limiter) in &config.rate_limiters {
+        if rate_limiter.num == 0 || rate_limiter.duration_secs == 0 {
+            errors.push(format!(
+                "Rate limiter \"{}\" must allow at least one request over a duration of at least one second.",
+                name
+            ));
+        }
+    }
+
     // Helper for below
     fn tagged<'a>(
         tag: &'static str,
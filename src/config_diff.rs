@@ -0,0 +1,138 @@
+use crate::config::{self, ComponentAction, ConfigSnapshot, ReloadReport};
+use colored::*;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Output the report as JSON.
+    #[structopt(long)]
+    json: bool,
+
+    /// Exit with a non-zero code if the reload would change anything.
+    #[structopt(long)]
+    exit_code: bool,
+
+    /// The config files of the running Vector instance. Wildcard paths are
+    /// supported. File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.toml` will be targeted.
+    #[structopt(long, env = "VECTOR_CONFIG", use_delimiter(true))]
+    current: Vec<PathBuf>,
+
+    /// Directories holding the config files of the running Vector instance.
+    #[structopt(long, env = "VECTOR_CONFIG_DIR", use_delimiter(true))]
+    current_dir: Vec<PathBuf>,
+
+    /// The candidate config files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    candidate: Vec<PathBuf>,
+
+    /// Directories holding the candidate config files.
+    #[structopt(long, use_delimiter(true))]
+    candidate_dir: Vec<PathBuf>,
+}
+
+fn config_paths(paths: &[PathBuf], dirs: &[PathBuf]) -> Vec<config::ConfigPath> {
+    paths
+        .iter()
+        .map(|path| config::ConfigPath::File(path.clone(), None))
+        .chain(dirs.iter().map(|dir| config::ConfigPath::Dir(dir.clone())))
+        .collect()
+}
+
+fn load(name: &str, paths: &[config::ConfigPath]) -> Result<ConfigSnapshot, exitcode::ExitCode> {
+    let paths = config::process_paths(paths).ok_or(exitcode::CONFIG)?;
+    config::load_from_paths(&paths)
+        .map(|config| ConfigSnapshot::new(&config))
+        .map_err(|errors| {
+            eprintln!("Failed to load the {} config:", name);
+            for error in errors {
+                eprintln!("  {}", error);
+            }
+            exitcode::CONFIG
+        })
+}
+
+/// Reports which components reloading the running config into the candidate
+/// config would add, remove, or rebuild, without applying it.
+pub fn cmd(opts: &Opts, color: bool) -> exitcode::ExitCode {
+    let candidate = config_paths(&opts.candidate, &opts.candidate_dir);
+    if candidate.is_empty() {
+        eprintln!("No candidate config files or directories to compare.");
+        return exitcode::USAGE;
+    }
+
+    let current = match load("current", &config_paths(&opts.current, &opts.current_dir)) {
+        Ok(current) => current,
+        Err(code) => return code,
+    };
+    let candidate = match load("candidate", &candidate) {
+        Ok(candidate) => candidate,
+        Err(code) => return code,
+    };
+
+    let report = ReloadReport::new(&current, &candidate);
+    if opts.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report is serializable")
+        );
+    } else {
+        print_report(&report, color);
+    }
+
+    if opts.exit_code && !report.is_empty() {
+        exitcode::DATAERR
+    } else {
+        exitcode::OK
+    }
+}
+
+fn print_report(report: &ReloadReport, color: bool) {
+    if report.global_changed {
+        let warning =
+            "Global options changed, Vector must be restarted to apply the candidate config.";
+        if color {
+            println!("{}", warning.yellow());
+        } else {
+            println!("{}", warning);
+        }
+    }
+
+    if report.components.is_empty() {
+        println!("No components would change.");
+        return;
+    }
+
+    for component in &report.components {
+        let (symbol, action) = match component.action {
+            ComponentAction::Add => ("+", "added"),
+            ComponentAction::Remove => ("-", "removed"),
+            ComponentAction::Reconfigure => ("~", "reconfigured"),
+            ComponentAction::Restart => ("~", "restarted"),
+        };
+        let buffer = match component.keeps_buffer {
+            Some(true) => ", buffer kept",
+            Some(false) => ", buffer dropped",
+            None => "",
+        };
+        let line = format!(
+            "{} {} {} ({}{})",
+            symbol, component.kind, component.id, action, buffer
+        );
+
+        if !color {
+            println!("{}", line);
+        } else {
+            match component.action {
+                ComponentAction::Add => println!("{}", line.green()),
+                ComponentAction::Remove => println!("{}", line.red()),
+                ComponentAction::Reconfigure | ComponentAction::Restart => {
+                    println!("{}", line.yellow())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Running Vector embedded in another application.
+//!
+//! A `TopologyBuilder` puts together sources, transforms and sinks the way a
+//! config file does, along with inputs and outputs connecting the topology to
+//! the application: events sent to an `EventSender` come out of the input of
+//! the same name, and events going into an output come out of its
+//! `EventReceiver`.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), Vec<String>> {
+//! use futures::StreamExt;
+//! use vector::{config::DataType, embedded::TopologyBuilder, event::Event};
+//!
+//! let mut builder = TopologyBuilder::new();
+//! let mut sender = builder.input("in", DataType::Log);
+//! let mut receiver = builder.output("out", &["in"]);
+//!
+//! let topology = builder.start().await?;
+//! sender.send(Event::from("hello")).await.unwrap();
+//! let event = receiver.next().await;
+//!
+//! drop(sender);
+//! topology.sources_finished().await;
+//! topology.stop().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    buffers::Acker,
+    config::{
+        Config, ConfigBuilder, ConfigDiff, DataType, HealthcheckOptions, SinkConfig, SinkContext,
+        SourceConfig, SourceContext, TransformConfig,
+    },
+    event::Event,
+    sinks::{util::StreamSink, Healthcheck, VectorSink},
+    sources::Source,
+    topology::{self, RunningTopology},
+};
+use async_trait::async_trait;
+use futures::{
+    channel::mpsc,
+    future::{self, BoxFuture},
+    stream::BoxStream,
+    FutureExt, Sink, SinkExt, Stream, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use vector_core::config::GlobalOptions;
+
+pub use crate::pipeline::ClosedError;
+
+/// The number of events an input or output holds before applying back
+/// pressure.
+const CHANNEL_CAPACITY: usize = 100;
+
+/// Builds a topology to run embedded in an application.
+#[derive(Debug, Default)]
+pub struct TopologyBuilder {
+    config: ConfigBuilder,
+}
+
+impl From<ConfigBuilder> for TopologyBuilder {
+    /// Starts from a loaded config, such as the config files of the
+    /// application.
+    fn from(config: ConfigBuilder) -> Self {
+        Self { config }
+    }
+}
+
+impl TopologyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn global_mut(&mut self) -> &mut GlobalOptions {
+        &mut self.config.global
+    }
+
+    pub fn healthchecks_mut(&mut self) -> &mut HealthcheckOptions {
+        &mut self.config.healthchecks
+    }
+
+    pub fn source<S: SourceConfig + 'static>(&mut self, id: &str, source: S) -> &mut Self {
+        self.config.add_source(id, source);
+        self
+    }
+
+    pub fn transform<T: TransformConfig + 'static>(
+        &mut self,
+        id: &str,
+        inputs: &[&str],
+        transform: T,
+    ) -> &mut Self {
+        self.config.add_transform(id, inputs, transform);
+        self
+    }
+
+    pub fn sink<S: SinkConfig + 'static>(
+        &mut self,
+        id: &str,
+        inputs: &[&str],
+        sink: S,
+    ) -> &mut Self {
+        self.config.add_sink(id, inputs, sink);
+        self
+    }
+
+    /// Adds a source named `id` emitting the events sent to the returned
+    /// sender, which must be of `output_type`. The source finishes once every
+    /// clone of the sender is dropped.
+    pub fn input(&mut self, id: &str, output_type: DataType) -> EventSender {
+        let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        self.config.add_source(
+            id,
+            EmbeddedSourceConfig {
+                receiver: Arc::new(Mutex::new(Some(receiver))),
+                output_type: Some(output_type),
+            },
+        );
+        EventSender { inner: sender }
+    }
+
+    /// Adds a sink named `id` passing the events of `inputs` to the returned
+    /// receiver. The topology is held back while the receiver isn't polled,
+    /// and events are dropped once it is.
+    pub fn output(&mut self, id: &str, inputs: &[&str]) -> EventReceiver {
+        let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        self.config.add_sink(
+            id,
+            inputs,
+            EmbeddedSinkConfig {
+                sender: Some(sender),
+            },
+        );
+        EventReceiver { inner: receiver }
+    }
+
+    /// Validates the topology into a config.
+    pub fn build(self) -> Result<Config, Vec<String>> {
+        self.config.build()
+    }
+
+    /// Builds and starts the topology, failing on invalid configs, components
+    /// failing to build, and failed healthchecks if they're required.
+    pub async fn start(self) -> Result<Topology, Vec<String>> {
+        let config = self.build()?;
+        let diff = ConfigDiff::initial(&config);
+        let pieces = topology::builder::build_pieces(&config, &diff, HashMap::new()).await?;
+        let (running, crashed) = topology::start_validated(config, diff, pieces)
+            .await
+            .ok_or_else(|| vec!["Healthchecks failed.".to_owned()])?;
+        Ok(Topology { running, crashed })
+    }
+}
+
+/// A topology running embedded in an application.
+pub struct Topology {
+    running: RunningTopology,
+    crashed: tokio::sync::mpsc::UnboundedReceiver<()>,
+}
+
+impl Topology {
+    /// Resolves once every source has finished, such as after every sender
+    /// of every input is dropped.
+    pub fn sources_finished(&self) -> BoxFuture<'static, ()> {
+        self.running.sources_finished()
+    }
+
+    /// Resolves if a component crashes, after which the topology should be
+    /// stopped.
+    pub async fn crashed(&mut self) {
+        self.crashed.recv().await;
+    }
+
+    /// Stops the topology, giving its components a chance to finish
+    /// processing the events they hold.
+    pub async fn stop(self) {
+        self.running.stop().await
+    }
+}
+
+/// Sends events to an input of a topology.
+#[derive(Clone, Debug)]
+pub struct EventSender {
+    inner: mpsc::Sender<Event>,
+}
+
+impl EventSender {
+    pub async fn send(&mut self, event: impl Into<Event>) -> Result<(), ClosedError> {
+        SinkExt::send(self, event.into()).await
+    }
+
+    pub async fn send_all(
+        &mut self,
+        events: impl IntoIterator<Item = Event>,
+    ) -> Result<(), ClosedError> {
+        for event in events {
+            self.send(event).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Sink<Event> for EventSender {
+    type Error = ClosedError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready_unpin(cx).map_err(|_| ClosedError)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, event: Event) -> Result<(), Self::Error> {
+        self.inner.start_send_unpin(event).map_err(|_| ClosedError)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_flush_unpin(cx).map_err(|_| ClosedError)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_close_unpin(cx).map_err(|_| ClosedError)
+    }
+}
+
+/// Receives the events of an output of a topology, until the topology stops.
+#[derive(Debug)]
+pub struct EventReceiver {
+    inner: mpsc::Receiver<Event>,
+}
+
+impl Stream for EventReceiver {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct EmbeddedSourceConfig {
+    #[serde(skip)]
+    receiver: Arc<Mutex<Option<mpsc::Receiver<Event>>>>,
+    #[serde(skip)]
+    output_type: Option<DataType>,
+}
+
+#[async_trait]
+#[typetag::serde(name = "embedded")]
+impl SourceConfig for EmbeddedSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        let receiver = self
+            .receiver
+            .lock()
+            .expect("poisoned lock")
+            .take()
+            .ok_or("Embedded inputs can only be added with `TopologyBuilder::input`.")?;
+
+        let out = cx.out;
+        Ok(Box::pin(receiver.take_until(cx.shutdown).map(Ok).forward(
+            out.sink_map_err(|error| error!(message = "Error sending event.", %error)),
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        self.output_type.unwrap_or(DataType::Any)
+    }
+
+    fn source_type(&self) -> &'static str {
+        "embedded"
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddedSinkConfig {
+    #[serde(skip)]
+    sender: Option<mpsc::Sender<Event>>,
+}
+
+#[async_trait]
+#[typetag::serialize(name = "embedded")]
+impl SinkConfig for EmbeddedSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let sink = EmbeddedSink {
+            acker: cx.acker(),
+            sender: self
+                .sender
+                .clone()
+                .expect("built by `TopologyBuilder::output`"),
+        };
+        Ok((VectorSink::Stream(Box::new(sink)), future::ok(()).boxed()))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "embedded"
+    }
+
+    fn typetag_deserialize(&self) {
+        unimplemented!("embedded outputs can only be added with `TopologyBuilder::output`")
+    }
+}
+
+struct EmbeddedSink {
+    acker: Acker,
+    sender: mpsc::Sender<Event>,
+}
+
+#[async_trait]
+impl StreamSink for EmbeddedSink {
+    async fn run(mut self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        while let Some(event) = input.next().await {
+            if self.sender.send(event).await.is_err() {
+                debug!(
+                    message = "Embedded output receiver dropped; dropping event.",
+                    internal_log_rate_secs = 10
+                );
+            }
+            self.acker.ack(1);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    #[tokio::test]
+    async fn passes_events_from_inputs_to_outputs() {
+        let mut builder = TopologyBuilder::new();
+        let mut first = builder.input("first", DataType::Log);
+        let mut second = builder.input("second", DataType::Log);
+        let both = builder.output("both", &["first", "second"]);
+        let only_second = builder.output("only_second", &["second"]);
+        let topology = builder.start().await.unwrap();
+
+        first.send("one").await.unwrap();
+        second.send("two").await.unwrap();
+        drop((first, second));
+        topology.sources_finished().await;
+        topology.stop().await;
+
+        let messages = |receiver: EventReceiver| {
+            receiver
+                .map(|event| event.into_log()["message"].clone())
+                .collect::<Vec<_>>()
+        };
+        let mut both = messages(both).await;
+        both.sort();
+        assert_eq!(both, vec![Value::from("one"), Value::from("two")]);
+        assert_eq!(messages(only_second).await, vec![Value::from("two")]);
+    }
+
+    #[tokio::test]
+    async fn fails_on_invalid_topologies() {
+        let mut builder = TopologyBuilder::new();
+        let _sender = builder.input("in", DataType::Log);
+        let _receiver = builder.output("out", &["missing"]);
+
+        let errors = builder.start().await.err().unwrap();
+        assert_eq!(
+            errors,
+            vec![r#"Input "missing" for sink "out" doesn't match any components."#]
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use metrics::counter;
+use std::time::Duration;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SinkAcknowledgementsExpired {
+    pub count: usize,
+    pub timeout: Duration,
+}
+
+impl InternalEvent for SinkAcknowledgementsExpired {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Acknowledgements timed out, resolving their batches as errored.",
+            count = %self.count,
+            timeout_secs = %self.timeout.as_secs(),
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("acknowledgement_timeouts_total", self.count as u64);
+    }
+}
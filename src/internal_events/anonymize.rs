@@ -0,0 +1,36 @@
+use crate::transforms::anonymize::Detector;
+use metrics::counter;
+use openssl::error::ErrorStack;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct AnonymizeValuesAnonymized {
+    pub count: usize,
+}
+
+impl InternalEvent for AnonymizeValuesAnonymized {
+    fn emit_metrics(&self) {
+        counter!("values_anonymized_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AnonymizeFailed {
+    pub error: ErrorStack,
+    pub detector: Detector,
+}
+
+impl InternalEvent for AnonymizeFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Could not anonymize value, redacting it instead.",
+            error = %self.error,
+            detector = ?self.detector,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "encryption_failed");
+    }
+}
@@ -22,3 +22,25 @@ impl InternalEvent for ApiStarted {
         counter!("api_started_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ApiConfigApplied<'a> {
+    pub revision: u64,
+    pub outcome: &'static str,
+    pub digest: &'a str,
+}
+
+impl InternalEvent for ApiConfigApplied<'_> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Config submitted through the API.",
+            revision = %self.revision,
+            outcome = %self.outcome,
+            digest = %self.digest,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("api_config_revisions_total", 1, "outcome" => self.outcome);
+    }
+}
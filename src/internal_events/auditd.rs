@@ -0,0 +1,62 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use std::io;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AuditdEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AuditdEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received audit event.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditdReadFailed {
+    pub error: io::Error,
+}
+
+impl InternalEvent for AuditdReadFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reading audit records.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditdParseFailed<'a> {
+    pub error: &'static str,
+    pub line: &'a str,
+}
+
+impl<'a> InternalEvent for AuditdParseFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed parsing audit record.",
+            error = %self.error,
+            line = %self.line,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "parse_failed");
+    }
+}
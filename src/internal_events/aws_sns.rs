@@ -0,0 +1,57 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AwsSnsEventSent<'a> {
+    pub byte_size: usize,
+    pub message_id: Option<&'a String>,
+}
+
+impl InternalEvent for AwsSnsEventSent<'_> {
+    fn emit_logs(&self) {
+        trace!(message = "Event sent.", message_id = ?self.message_id);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSnsMessageTooLarge {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl InternalEvent for AwsSnsMessageTooLarge {
+    fn emit_logs(&self) {
+        error!(
+            message = "Message is larger than SNS accepts; dropping event.",
+            size = %self.size,
+            max_size = %self.max_size,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "message_too_large");
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsSnsMessageTruncated {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl InternalEvent for AwsSnsMessageTruncated {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Message is larger than SNS accepts; truncating it.",
+            size = %self.size,
+            max_size = %self.max_size,
+            internal_log_rate_secs = 10,
+        );
+    }
+}
@@ -0,0 +1,32 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AxiomIngestFailures<'a> {
+    pub dataset: &'a str,
+    pub count: usize,
+    pub error: Option<&'a str>,
+}
+
+impl<'a> InternalEvent for AxiomIngestFailures<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Axiom failed to ingest some events.",
+            dataset = %self.dataset,
+            count = %self.count,
+            error = ?self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "stage" => "sending",
+            "error_type" => "ingest_failed",
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+    }
+}
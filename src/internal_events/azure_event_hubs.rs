@@ -0,0 +1,121 @@
+// ## skip check-events ##
+
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AzureEventHubsEventReceived<'a> {
+    pub partition_id: &'a str,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AzureEventHubsEventReceived<'_> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received one event.",
+            partition_id = %self.partition_id,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AzureEventHubsReceiveFailed<'a> {
+    pub partition_id: &'a str,
+    pub error: &'a crate::Error,
+}
+
+impl InternalEvent for AzureEventHubsReceiveFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to receive events from partition.",
+            partition_id = %self.partition_id,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "read_failed",
+            "stage" => "receiving"
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AzureEventHubsCheckpointFailed<'a> {
+    pub partition_id: &'a str,
+    pub error: &'a crate::Error,
+}
+
+impl InternalEvent for AzureEventHubsCheckpointFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to write checkpoint.",
+            partition_id = %self.partition_id,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "write_failed",
+            "stage" => "receiving"
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AzureEventHubsLoadBalancingFailed<'a> {
+    pub error: &'a crate::Error,
+}
+
+impl InternalEvent for AzureEventHubsLoadBalancingFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to balance partition ownership.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "ownership_failed",
+            "stage" => "receiving"
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AzureEventHubsOwnershipChanged<'a> {
+    pub claimed: &'a [String],
+    pub released: &'a [String],
+    pub owned: usize,
+}
+
+impl InternalEvent for AzureEventHubsOwnershipChanged<'_> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Partition ownership changed.",
+            claimed = ?self.claimed,
+            released = ?self.released,
+            owned = %self.owned,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("owned_partitions", self.owned as f64);
+    }
+}
@@ -0,0 +1,15 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ChatWebhookEventProcessed {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ChatWebhookEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
@@ -1,4 +1,4 @@
-use metrics::counter;
+use metrics::{counter, gauge};
 use vector_core::internal_event::InternalEvent;
 
 #[derive(Debug)]
@@ -111,6 +111,19 @@ impl<'a> InternalEvent for EndpointBytesSent<'a> {
     }
 }
 
+/// The approximate number of bytes held by the state of a component, such as
+/// the events it caches.
+#[derive(Debug)]
+pub struct ComponentAllocatedBytes {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ComponentAllocatedBytes {
+    fn emit_metrics(&self) {
+        gauge!("component_allocated_bytes", self.byte_size as f64);
+    }
+}
+
 #[cfg(feature = "rusoto")]
 pub struct AwsBytesSent {
     pub byte_size: usize,
@@ -0,0 +1,64 @@
+use metrics::counter;
+use std::path::Path;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct DatadogAgentApiKeyRejected {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for DatadogAgentApiKeyRejected {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rejected request with an API key not on the allowlist.",
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("requests_rejected_total", 1,
+                 "reason" => self.reason);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogAgentApiKeysReloaded<'a> {
+    pub path: &'a Path,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for DatadogAgentApiKeysReloaded<'a> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Reloaded the API key allowlist.",
+            path = ?self.path,
+            count = %self.count,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("api_key_allowlist_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogAgentApiKeysReloadFailed<'a> {
+    pub path: &'a Path,
+    pub error: std::io::Error,
+}
+
+impl<'a> InternalEvent for DatadogAgentApiKeysReloadFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload the API key allowlist; keeping the previous keys.",
+            path = ?self.path,
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("api_key_allowlist_reload_errors_total", 1);
+    }
+}
@@ -1,4 +1,5 @@
-use metrics::counter;
+use metrics::{counter, histogram};
+use std::time::Duration;
 use vector_core::internal_event::InternalEvent;
 
 #[derive(Debug)]
@@ -12,3 +13,206 @@ impl InternalEvent for DatadogLogEventProcessed {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct DatadogLogsApiKeyCircuitOpened {
+    pub consecutive_failures: u32,
+    pub cooldown: Duration,
+}
+
+impl InternalEvent for DatadogLogsApiKeyCircuitOpened {
+    fn emit_logs(&self) {
+        warn!(
+            message = "API key failed too many requests in a row, failing its requests until it cools down.",
+            consecutive_failures = %self.consecutive_failures,
+            cooldown_secs = %self.cooldown.as_secs(),
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("api_key_circuit_breaks_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsApiKeyRejected {
+    pub count: usize,
+}
+
+impl InternalEvent for DatadogLogsApiKeyRejected {
+    fn emit_logs(&self) {
+        error!(
+            message = "Request failed without being sent, as its API key is cooling down.",
+            count = %self.count,
+            error_type = "circuit_open",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "circuit_open",
+            "stage" => "sending",
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsRequestEncoded {
+    pub events: usize,
+    pub uncompressed_bytes: usize,
+    pub compressed_bytes: usize,
+    pub duration: Duration,
+}
+
+impl InternalEvent for DatadogLogsRequestEncoded {
+    fn emit_metrics(&self) {
+        histogram!("encoded_request_events", self.events as f64);
+        histogram!("encoded_request_bytes", self.uncompressed_bytes as f64);
+        histogram!(
+            "encoded_request_compressed_bytes",
+            self.compressed_bytes as f64
+        );
+        histogram!("encoded_request_duration_seconds", self.duration);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsEventTooLarge<'a> {
+    pub service: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub byte_size: usize,
+    pub max_bytes: usize,
+}
+
+impl<'a> InternalEvent for DatadogLogsEventTooLarge<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Event is larger than the maximum payload size; dropping event.",
+            service = %self.service.unwrap_or("unknown"),
+            source = %self.source.unwrap_or("unknown"),
+            byte_size = %self.byte_size,
+            max_bytes = %self.max_bytes,
+            error_type = "event_too_large",
+            stage = "processing",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("oversized_events_total", 1, "reason" => "event_too_large");
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsPayloadTooLarge<'a> {
+    pub service: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub largest_event_byte_size: usize,
+    pub payload_byte_size: usize,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for DatadogLogsPayloadTooLarge<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Payload rejected by the Logs API for being too large.",
+            service = %self.service.unwrap_or("unknown"),
+            source = %self.source.unwrap_or("unknown"),
+            largest_event_byte_size = %self.largest_event_byte_size,
+            payload_byte_size = %self.payload_byte_size,
+            count = %self.count,
+            error_type = "payload_too_large",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("oversized_events_total", 1, "reason" => "payload_too_large");
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsPayloadSpooled {
+    pub status: u16,
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for DatadogLogsPayloadSpooled {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Payload rejected by the Logs API was written to the spool.",
+            status = %self.status,
+            count = %self.count,
+            byte_size = %self.byte_size,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("spooled_payloads_total", 1, "status" => self.status.to_string());
+        counter!("spooled_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsSpoolFull {
+    pub count: usize,
+    pub max_bytes: u64,
+}
+
+impl InternalEvent for DatadogLogsSpoolFull {
+    fn emit_logs(&self) {
+        error!(
+            message = "Spool is full; dropping rejected payload.",
+            count = %self.count,
+            max_bytes = %self.max_bytes,
+            error_type = "spool_full",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "spool_full",
+            "stage" => "sending",
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsSpoolWriteFailed {
+    pub error: std::io::Error,
+    pub count: usize,
+}
+
+impl InternalEvent for DatadogLogsSpoolWriteFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to write rejected payload to the spool; dropping it.",
+            error = %self.error,
+            count = %self.count,
+            error_type = "spool_write_failed",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "spool_write_failed",
+            "stage" => "sending",
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+    }
+}
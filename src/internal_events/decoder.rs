@@ -30,3 +30,44 @@ impl<'a> InternalEvent for DecoderParseFailed<'a> {
         counter!("decoder_parse_errors_total", 1);
     }
 }
+
+#[derive(Debug, Clone, Copy)]
+pub enum GelfDropReason {
+    TimedOut,
+    PendingMessagesLimit,
+}
+
+impl GelfDropReason {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::TimedOut => "timed_out",
+            Self::PendingMessagesLimit => "pending_messages_limit",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DecoderGelfMessageDropped {
+    pub reason: GelfDropReason,
+    pub received_chunks: usize,
+    pub total_chunks: usize,
+}
+
+impl InternalEvent for DecoderGelfMessageDropped {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Dropped incomplete GELF message.",
+            reason = self.reason.as_str(),
+            received_chunks = self.received_chunks,
+            total_chunks = self.total_chunks,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "decoder_gelf_messages_dropped_total", 1,
+            "reason" => self.reason.as_str(),
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use crate::event::Value;
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct DeriveSeverityUnknown<'a> {
+    pub field: &'a str,
+    pub value: &'a Value,
+}
+
+impl<'a> InternalEvent for DeriveSeverityUnknown<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Unknown severity.",
+            field = %self.field,
+            value = ?self.value,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "unknown_severity",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct DeriveSeverityFieldMissing<'a> {
+    pub fields: &'a [String],
+}
+
+impl<'a> InternalEvent for DeriveSeverityFieldMissing<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "None of the severity fields exist.",
+            fields = ?self.fields,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "field_missing",
+        );
+    }
+}
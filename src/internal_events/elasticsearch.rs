@@ -16,3 +16,74 @@ impl InternalEvent for ElasticSearchEventEncoded {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticSearchBulkItemsRetried {
+    pub count: usize,
+    pub attempt: usize,
+}
+
+impl InternalEvent for ElasticSearchBulkItemsRetried {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Some bulk items were rejected, retrying them.",
+            count = %self.count,
+            attempt = %self.attempt,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("bulk_items_retried_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchBulkItemsRejected<'a> {
+    pub error_type: &'a str,
+    pub reason: &'a str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for ElasticSearchBulkItemsRejected<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Bulk items were rejected; dropping them.",
+            error_type = %self.error_type,
+            reason = %self.reason,
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count as u64,
+                 "reason" => self.error_type.to_owned());
+        counter!("processing_errors_total", 1,
+                 "error_type" => self.error_type.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchBulkItemsDeadLettered<'a> {
+    pub error_type: &'a str,
+    pub reason: &'a str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for ElasticSearchBulkItemsDeadLettered<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Bulk items were rejected; written to the dead letter index.",
+            error_type = %self.error_type,
+            reason = %self.reason,
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_dead_lettered_total", self.count as u64,
+                 "error_type" => self.error_type.to_owned());
+    }
+}
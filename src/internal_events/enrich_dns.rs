@@ -0,0 +1,84 @@
+use metrics::counter;
+use std::time::Duration;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct EnrichDnsCacheHit;
+
+impl InternalEvent for EnrichDnsCacheHit {
+    fn emit_metrics(&self) {
+        counter!("dns_cache_hits_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichDnsCacheMiss;
+
+impl InternalEvent for EnrichDnsCacheMiss {
+    fn emit_metrics(&self) {
+        counter!("dns_cache_misses_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichDnsLookupFailed<'a> {
+    pub name: &'a str,
+    pub error: std::io::Error,
+}
+
+impl<'a> InternalEvent for EnrichDnsLookupFailed<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "DNS lookup failed.",
+            name = %self.name,
+            error = %self.error,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("dns_lookup_errors_total", 1, "error_type" => "lookup_failed");
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichDnsLookupTimedOut<'a> {
+    pub name: &'a str,
+    pub timeout: Duration,
+}
+
+impl<'a> InternalEvent for EnrichDnsLookupTimedOut<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "DNS lookup timed out.",
+            name = %self.name,
+            timeout = ?self.timeout,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("dns_lookup_errors_total", 1, "error_type" => "timed_out");
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichDnsInvalidIpAddress<'a> {
+    pub field: &'a str,
+    pub address: &'a str,
+}
+
+impl<'a> InternalEvent for EnrichDnsInvalidIpAddress<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Field isn't a valid IP address.",
+            field = %self.field,
+            address = %self.address,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "type_ip_address_parse_error");
+    }
+}
@@ -0,0 +1,71 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct EventFieldTruncated<'a> {
+    pub field: &'a str,
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl<'a> InternalEvent for EventFieldTruncated<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Truncated field over the size limit.",
+            field = %self.field,
+            size = %self.size,
+            max_size = %self.max_size,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("event_fields_truncated_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct EventFieldTooLarge<'a> {
+    pub field: &'a str,
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl<'a> InternalEvent for EventFieldTooLarge<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field is over the size limit; dropping event.",
+            field = %self.field,
+            size = %self.size,
+            max_size = %self.max_size,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_too_large");
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct EventTooLarge {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl InternalEvent for EventTooLarge {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event is over the size limit; dropping event.",
+            size = %self.size,
+            max_size = %self.max_size,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "event_too_large");
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -0,0 +1,71 @@
+use metrics::{counter, histogram};
+use std::time::Duration;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct EventTraceStarted {
+    pub trace_id: u64,
+}
+
+impl InternalEvent for EventTraceStarted {
+    fn emit_logs(&self) {
+        trace!(message = "Event trace started.", trace_id = %self.trace_id);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("event_traces_started_total", 1);
+    }
+}
+
+/// Which part of a component's handling of a traced event a latency covers.
+#[derive(Clone, Copy, Debug)]
+pub enum EventTraceStage {
+    /// Time spent waiting in the component's input buffer.
+    Queued,
+    /// Time spent inside the transform itself.
+    Processing,
+    /// Time from leaving the source to reaching the sink.
+    EndToEnd,
+}
+
+impl EventTraceStage {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Processing => "processing",
+            Self::EndToEnd => "end_to_end",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EventTraceHop {
+    pub trace_id: u64,
+    pub stage: EventTraceStage,
+    pub latency: Duration,
+}
+
+impl InternalEvent for EventTraceHop {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Event trace hop.",
+            trace_id = %self.trace_id,
+            stage = self.stage.as_str(),
+            latency_secs = %self.latency.as_secs_f64(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        match self.stage {
+            EventTraceStage::Queued => {
+                histogram!("event_trace_queue_latency_seconds", self.latency)
+            }
+            EventTraceStage::Processing => {
+                histogram!("event_trace_processing_latency_seconds", self.latency)
+            }
+            EventTraceStage::EndToEnd => {
+                histogram!("event_trace_end_to_end_latency_seconds", self.latency)
+            }
+        }
+    }
+}
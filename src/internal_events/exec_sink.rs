@@ -0,0 +1,116 @@
+use metrics::counter;
+use std::{io, process::ExitStatus};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ExecSinkSpawnFailed<'a> {
+    pub command: &'a str,
+    pub error: io::Error,
+}
+
+impl InternalEvent for ExecSinkSpawnFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to spawn subprocess.",
+            command = %self.command,
+            error = ?self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "spawn_failed",
+            "stage" => "sending",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecSinkWriteFailed<'a> {
+    pub command: &'a str,
+    pub error: io::Error,
+}
+
+impl InternalEvent for ExecSinkWriteFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to write to the stdin of the subprocess.",
+            command = %self.command,
+            error = ?self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "write_failed",
+            "stage" => "sending",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecSinkExited<'a> {
+    pub command: &'a str,
+    pub status: io::Result<ExitStatus>,
+}
+
+impl InternalEvent for ExecSinkExited<'_> {
+    fn emit_logs(&self) {
+        match &self.status {
+            Ok(status) => warn!(
+                message = "Subprocess exited.",
+                command = %self.command,
+                status = %status,
+                internal_log_rate_secs = 10,
+            ),
+            Err(error) => error!(
+                message = "Unable to wait for the subprocess to exit.",
+                command = %self.command,
+                error = ?error,
+                internal_log_rate_secs = 10,
+            ),
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("subprocess_exits_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecSinkUnexpectedMarker<'a> {
+    pub command: &'a str,
+}
+
+impl InternalEvent for ExecSinkUnexpectedMarker<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Subprocess acknowledged more events than it was sent; ignoring marker.",
+            command = %self.command,
+            internal_log_rate_secs = 30,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ExecSinkFieldNotFound<'a> {
+    pub missing_field: &'a str,
+}
+
+impl<'a> InternalEvent for ExecSinkFieldNotFound<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field not found; dropping event.",
+            missing_field = ?self.missing_field,
+            internal_log_rate_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_not_found");
+    }
+}
@@ -36,6 +36,47 @@ impl InternalEvent for FileBytesSent<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct FileRotated<'a> {
+    pub file: Cow<'a, str>,
+    pub rotated: &'a std::path::Path,
+}
+
+impl InternalEvent for FileRotated<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Rotated file.",
+            file = %self.file,
+            rotated = %self.rotated.display(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("files_rotated_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct FileRotationFailed<'a> {
+    pub file: Cow<'a, str>,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for FileRotationFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to rotate file.",
+            file = %self.file,
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("file_rotation_errors_total", 1);
+    }
+}
+
 #[cfg(any(feature = "sources-file", feature = "sources-kubernetes_logs"))]
 mod source {
     use super::{FileOpen, InternalEvent};
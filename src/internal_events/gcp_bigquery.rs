@@ -0,0 +1,48 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct GcpBigqueryRowsRejected<'a> {
+    pub count: usize,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for GcpBigqueryRowsRejected<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Rows were rejected by BigQuery; dropping them.",
+            reason = %self.reason,
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count as u64,
+                 "reason" => "row_error");
+        counter!("processing_errors_total", 1,
+                 "error_type" => "row_error");
+    }
+}
+
+#[derive(Debug)]
+pub struct GcpBigqueryColumnConversionFailed<'a> {
+    pub column: &'a str,
+    pub column_type: &'a str,
+}
+
+impl<'a> InternalEvent for GcpBigqueryColumnConversionFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field value can't be converted to the column type; leaving the column empty.",
+            column = %self.column,
+            column_type = %self.column_type,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+                 "error_type" => "type_conversion_failed");
+    }
+}
@@ -0,0 +1,54 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct HealthcheckFailed<'a> {
+    pub error: &'a crate::Error,
+    pub consecutive_failures: usize,
+}
+
+impl InternalEvent for HealthcheckFailed<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Periodic healthcheck failed.",
+            error = %self.error,
+            consecutive_failures = %self.consecutive_failures,
+            error_type = "healthcheck_failed",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "healthcheck_failed",
+            "stage" => "sending",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct HealthcheckStateChanged {
+    pub healthy: bool,
+}
+
+impl InternalEvent for HealthcheckStateChanged {
+    fn emit_logs(&self) {
+        if self.healthy {
+            info!(message = "Healthcheck recovered, resuming sink.");
+        } else {
+            error!(
+                message = "Sink became unhealthy, pausing it until its healthcheck passes again."
+            );
+        }
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("component_healthy", if self.healthy { 1.0 } else { 0.0 });
+        counter!(
+            "healthcheck_state_changes_total", 1,
+            "state" => if self.healthy { "healthy" } else { "unhealthy" },
+        );
+    }
+}
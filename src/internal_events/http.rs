@@ -102,6 +102,30 @@ impl InternalEvent for HttpEventMissingMessage {
     }
 }
 
+#[derive(Debug)]
+pub struct HttpEventInvalidUri<'a> {
+    pub uri: &'a str,
+    pub error: &'a str,
+}
+
+impl<'a> InternalEvent for HttpEventInvalidUri<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rendered URI is invalid; dropping event.",
+            uri = %self.uri,
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+            "error_type" => "invalid_uri");
+        counter!("events_discarded_total", 1);
+        counter!("component_discarded_events_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct HttpEventEncoded {
     pub byte_size: usize,
@@ -0,0 +1,54 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct IdempotentRequestDuplicate {
+    pub in_flight: bool,
+}
+
+impl InternalEvent for IdempotentRequestDuplicate {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Duplicate request dropped.",
+            in_flight = %self.in_flight,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "idempotency_duplicates_total", 1,
+            "state" => if self.in_flight { "in_flight" } else { "accepted" },
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct IdempotencyKeysTracked {
+    pub count: usize,
+}
+
+impl InternalEvent for IdempotencyKeysTracked {
+    fn emit_metrics(&self) {
+        gauge!("idempotency_keys_tracked", self.count as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct IdempotencyKeysExhausted {
+    pub max_keys: usize,
+}
+
+impl InternalEvent for IdempotencyKeysExhausted {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Maximum number of idempotency keys reached, request won't be deduplicated.",
+            max_keys = %self.max_keys,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("idempotency_untracked_requests_total", 1);
+    }
+}
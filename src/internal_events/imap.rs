@@ -0,0 +1,117 @@
+// ## skip check-events ##
+
+use mailparse::MailParseError;
+use metrics::counter;
+use std::io;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ImapMessageReceived {
+    pub uid: u32,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ImapMessageReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received one message.",
+            uid = %self.uid,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ImapMessageParseFailed<'a> {
+    pub uid: u32,
+    pub error: &'a MailParseError,
+}
+
+impl InternalEvent for ImapMessageParseFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to parse message, leaving it in the mailbox.",
+            uid = %self.uid,
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "parse_failed",
+            "stage" => "processing"
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ImapRequestFailed<'a> {
+    pub error: &'a crate::Error,
+}
+
+impl InternalEvent for ImapRequestFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to poll mailbox.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "request_failed",
+            "stage" => "receiving"
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ImapCheckpointFailed {
+    pub error: io::Error,
+}
+
+impl InternalEvent for ImapCheckpointFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to write checkpoint.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "write_failed",
+            "stage" => "receiving"
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ImapUidValidityChanged<'a> {
+    pub mailbox: &'a str,
+    pub previous: u32,
+    pub current: u32,
+}
+
+impl InternalEvent for ImapUidValidityChanged<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Mailbox UIDVALIDITY changed, fetching all of its messages again.",
+            mailbox = %self.mailbox,
+            previous = %self.previous,
+            current = %self.current,
+        );
+    }
+}
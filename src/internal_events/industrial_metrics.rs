@@ -0,0 +1,125 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use std::time::Duration;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct IndustrialMetricsEventsReceived<'a> {
+    pub count: usize,
+    pub byte_size: usize,
+    pub endpoint: &'a str,
+}
+
+impl InternalEvent for IndustrialMetricsEventsReceived<'_> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Events received.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            endpoint = %self.endpoint,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!(
+            "events_in_total", self.count as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct IndustrialMetricsConnected<'a> {
+    pub endpoint: &'a str,
+}
+
+impl InternalEvent for IndustrialMetricsConnected<'_> {
+    fn emit_logs(&self) {
+        info!(message = "Connected.", endpoint = %self.endpoint);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "connection_established_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct IndustrialMetricsConnectionFailed<'a> {
+    pub error: &'a crate::Error,
+    pub endpoint: &'a str,
+    pub retry_in: Duration,
+}
+
+impl InternalEvent for IndustrialMetricsConnectionFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to connect.",
+            endpoint = %self.endpoint,
+            error = %self.error,
+            retry_in_secs = %self.retry_in.as_secs_f64(),
+            error_type = "connection_failed",
+            stage = "receiving",
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "connection_errors_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "connection_failed",
+            "stage" => "receiving",
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct IndustrialMetricsReadFailed<'a> {
+    pub error: &'a crate::Error,
+    pub endpoint: &'a str,
+    /// The metric that couldn't be read, when the others could.
+    pub metric: Option<&'a str>,
+}
+
+impl InternalEvent for IndustrialMetricsReadFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to read values.",
+            endpoint = %self.endpoint,
+            metric = ?self.metric,
+            error = %self.error,
+            error_type = "request_failed",
+            stage = "receiving",
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "request_errors_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "request_failed",
+            "stage" => "receiving",
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
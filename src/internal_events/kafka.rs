@@ -1,6 +1,7 @@
 // ## skip check-events ##
 
-use metrics::{counter, gauge};
+use metrics::{counter, gauge, histogram};
+use std::time::Duration;
 use vector_core::internal_event::InternalEvent;
 use vector_core::update_counter;
 
@@ -74,6 +75,20 @@ impl InternalEvent for KafkaStatisticsReceived<'_> {
             "kafka_queue_messages_bytes",
             self.statistics.msg_size as f64
         );
+        gauge!("kafka_queue_messages_max", self.statistics.msg_max as f64);
+        gauge!(
+            "kafka_queue_messages_bytes_max",
+            self.statistics.msg_size_max as f64
+        );
+        // How full the producer queue is, with the fuller of its two limits,
+        // `queue.buffering.max.messages` and `queue.buffering.max.kbytes`.
+        gauge!(
+            "kafka_queue_utilization_ratio",
+            ratio(self.statistics.msg_cnt, self.statistics.msg_max).max(ratio(
+                self.statistics.msg_size,
+                self.statistics.msg_size_max
+            ))
+        );
         update_counter!("kafka_requests_total", self.statistics.tx as u64);
         update_counter!(
             "kafka_requests_bytes_total",
@@ -103,6 +118,76 @@ impl InternalEvent for KafkaStatisticsReceived<'_> {
     }
 }
 
+fn ratio(value: u64, max: u64) -> f64 {
+    if max == 0 {
+        0.0
+    } else {
+        value as f64 / max as f64
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaDeliveryReported<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub latency: Duration,
+}
+
+impl InternalEvent for KafkaDeliveryReported<'_> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Message delivered.",
+            topic = %self.topic,
+            partition = %self.partition,
+            latency = ?self.latency,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        histogram!(
+            "kafka_delivery_latency_seconds", self.latency,
+            "topic" => self.topic.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaDeliveryFailed<'a> {
+    pub topic: &'a str,
+    /// The partition of the message, or `-1` if it wasn't assigned one.
+    pub partition: i32,
+    pub error: &'a rdkafka::error::KafkaError,
+}
+
+impl KafkaDeliveryFailed<'_> {
+    fn error_code(&self) -> String {
+        self.error
+            .rdkafka_error_code()
+            .map_or_else(|| "unknown".to_owned(), |code| format!("{:?}", code))
+    }
+}
+
+impl InternalEvent for KafkaDeliveryFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to deliver message.",
+            topic = %self.topic,
+            partition = %self.partition,
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "kafka_delivery_errors_total", 1,
+            "topic" => self.topic.to_owned(),
+            "partition" => self.partition.to_string(),
+            "error_code" => self.error_code(),
+        );
+    }
+}
+
 pub struct KafkaHeaderExtractionFailed<'a> {
     pub header_field: &'a str,
 }
@@ -119,3 +204,84 @@ impl InternalEvent for KafkaHeaderExtractionFailed<'_> {
         counter!("kafka_header_extraction_failures_total", 1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdkafka::{error::KafkaError, types::RDKafkaErrorCode};
+
+    fn captured_tags(name: &str, topic: &str) -> Vec<(String, String)> {
+        crate::metrics::Controller::get()
+            .expect("failed to init metric container")
+            .capture_metrics()
+            .find(|metric| {
+                metric.name() == name
+                    && metric
+                        .tags()
+                        .and_then(|tags| tags.get("topic"))
+                        .map(String::as_str)
+                        == Some(topic)
+            })
+            .and_then(|metric| metric.tags().cloned())
+            .expect("metric wasn't emitted")
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn ratio_of_unbounded_queue_is_zero() {
+        assert_eq!(ratio(0, 0), 0.0);
+        assert_eq!(ratio(10, 0), 0.0);
+        assert_eq!(ratio(25, 100), 0.25);
+        assert_eq!(ratio(100, 100), 1.0);
+    }
+
+    #[test]
+    fn delivery_reported_is_tagged_with_topic() {
+        let _ = crate::metrics::init_test();
+
+        emit!(&KafkaDeliveryReported {
+            topic: "reported",
+            partition: 0,
+            latency: Duration::from_millis(3),
+        });
+
+        assert_eq!(
+            captured_tags("kafka_delivery_latency_seconds", "reported"),
+            vec![("topic".to_owned(), "reported".to_owned())]
+        );
+    }
+
+    #[test]
+    fn delivery_failed_is_tagged_with_error_code() {
+        let _ = crate::metrics::init_test();
+
+        emit!(&KafkaDeliveryFailed {
+            topic: "failed",
+            partition: 2,
+            error: &KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull),
+        });
+        emit!(&KafkaDeliveryFailed {
+            topic: "canceled",
+            partition: -1,
+            error: &KafkaError::Canceled,
+        });
+
+        assert_eq!(
+            captured_tags("kafka_delivery_errors_total", "failed"),
+            vec![
+                ("error_code".to_owned(), "QueueFull".to_owned()),
+                ("partition".to_owned(), "2".to_owned()),
+                ("topic".to_owned(), "failed".to_owned()),
+            ]
+        );
+        assert_eq!(
+            captured_tags("kafka_delivery_errors_total", "canceled"),
+            vec![
+                ("error_code".to_owned(), "unknown".to_owned()),
+                ("partition".to_owned(), "-1".to_owned()),
+                ("topic".to_owned(), "canceled".to_owned()),
+            ]
+        );
+    }
+}
@@ -107,3 +107,39 @@ impl InternalEvent for KubernetesLogsDockerFormatParseFailed<'_> {
         counter!("k8s_docker_format_parse_failures_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct KubernetesLogsKubeEventReceived<'a> {
+    pub reason: Option<&'a str>,
+}
+
+impl InternalEvent for KubernetesLogsKubeEventReceived<'_> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received one Kubernetes event.",
+            reason = ?self.reason
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1, "output" => "kube_events");
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesLogsKubeEventSendFailed {
+    pub error: crate::pipeline::ClosedError,
+}
+
+impl InternalEvent for KubernetesLogsKubeEventSendFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to forward Kubernetes event.",
+            error = %self.error,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("k8s_kube_event_send_failures_total", 1);
+    }
+}
@@ -0,0 +1,62 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use std::path::Path;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct LocalDatabaseWriteFailed<'a> {
+    pub error: &'a crate::Error,
+    pub count: usize,
+}
+
+impl InternalEvent for LocalDatabaseWriteFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to write events to database.",
+            error = %self.error,
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("write_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalDatabaseFileRotated<'a> {
+    pub path: &'a Path,
+}
+
+impl InternalEvent for LocalDatabaseFileRotated<'_> {
+    fn emit_logs(&self) {
+        debug!(message = "Rotated database file.", rotated = %self.path.display());
+    }
+
+    fn emit_metrics(&self) {
+        counter!("files_rotated_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalDatabaseRetentionApplied {
+    pub rows: usize,
+    pub files: usize,
+}
+
+impl InternalEvent for LocalDatabaseRetentionApplied {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Removed expired data.",
+            rows = %self.rows,
+            files = %self.files,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("rows_pruned_total", self.rows as u64);
+        counter!("files_pruned_total", self.files as u64);
+    }
+}
@@ -66,3 +66,22 @@ impl InternalEvent for LokiOutOfOrderEventRewritten {
                 "error_type" => "out_of_order");
     }
 }
+
+#[derive(Debug)]
+pub struct LokiLabelCardinalityExceeded {
+    pub limit: usize,
+}
+
+impl InternalEvent for LokiLabelCardinalityExceeded {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Label set limit reached; sending event with the fallback labels.",
+            limit = %self.limit,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("label_set_limit_exceeded_total", 1);
+    }
+}
@@ -30,11 +30,11 @@ impl InternalEvent for LuaScriptError {
 }
 
 #[derive(Debug)]
-pub struct LuaBuildError {
-    pub error: crate::transforms::lua::v2::BuildError,
+pub struct LuaBuildError<E> {
+    pub error: E,
 }
 
-impl InternalEvent for LuaBuildError {
+impl<E: std::fmt::Debug> InternalEvent for LuaBuildError<E> {
     fn emit_logs(&self) {
         error!(message = "Error in lua script; discarding event.", error = ?self.error, internal_log_rate_secs = 30);
     }
@@ -43,3 +43,35 @@ impl InternalEvent for LuaBuildError {
         counter!("processing_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct LuaStateUpdated {
+    pub bytes: usize,
+}
+
+impl InternalEvent for LuaStateUpdated {
+    fn emit_metrics(&self) {
+        gauge!("lua_state_bytes", self.bytes as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct LuaStateLimitReached<'a> {
+    pub key: &'a str,
+    pub max_bytes: usize,
+}
+
+impl<'a> InternalEvent for LuaStateLimitReached<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Lua state is full; value not stored.",
+            key = %self.key,
+            max_bytes = %self.max_bytes,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("lua_state_limit_reached_total", 1);
+    }
+}
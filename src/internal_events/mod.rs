@@ -1,14 +1,19 @@
 use std::borrow::Cow;
 
+mod acknowledgements;
 mod adaptive_concurrency;
 mod add_fields;
 mod add_tags;
 mod aggregate;
+#[cfg(feature = "transforms-anonymize")]
+mod anonymize;
 mod ansi_stripper;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
+#[cfg(all(target_os = "linux", feature = "sources-auditd"))]
+mod auditd;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 mod aws_cloudwatch_logs_subscription_parser;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -23,12 +28,20 @@ mod aws_kinesis_streams;
 pub(crate) mod aws_s3;
 #[cfg(feature = "sinks-aws_s3")]
 pub(crate) mod aws_s3_sink;
+#[cfg(feature = "sinks-aws_sns")]
+mod aws_sns;
 #[cfg(feature = "sinks-aws_sqs")]
 mod aws_sqs;
+#[cfg(feature = "sinks-axiom")]
+mod axiom;
 #[cfg(feature = "sinks-azure_blob")]
 pub(crate) mod azure_blob;
+#[cfg(feature = "sources-azure_event_hubs")]
+mod azure_event_hubs;
 mod batch;
 mod blackhole;
+#[cfg(feature = "sinks-chat_webhook")]
+mod chat_webhook;
 #[cfg(feature = "transforms-coercer")]
 mod coercer;
 mod common;
@@ -37,6 +50,8 @@ mod concat;
 mod conditions;
 #[cfg(feature = "sinks-console")]
 mod console;
+#[cfg(feature = "sources-datadog")]
+mod datadog_agent;
 #[cfg(feature = "sinks-datadog")]
 mod datadog_events;
 #[cfg(feature = "sinks-datadog")]
@@ -45,31 +60,48 @@ mod datadog_logs;
 mod decoder;
 #[cfg(feature = "transforms-dedupe")]
 mod dedupe;
+#[cfg(feature = "transforms-derive_severity")]
+mod derive_severity;
 #[cfg(feature = "sources-dnstap")]
 mod dnstap;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
 mod elasticsearch;
 mod encoding_transcode;
+#[cfg(feature = "transforms-enrich_dns")]
+mod enrich_dns;
+mod event_limits;
+mod event_tracing;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
 mod exec;
+#[cfg(feature = "sinks-exec")]
+mod exec_sink;
 #[cfg(feature = "transforms-filter")]
 mod filter;
 #[cfg(feature = "sources-fluent")]
 mod fluent;
+#[cfg(feature = "sinks-gcp")]
+mod gcp_bigquery;
 #[cfg(feature = "sources-generator")]
 mod generator;
 #[cfg(feature = "transforms-geoip")]
 mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
 mod grok_parser;
+mod healthcheck;
 mod heartbeat;
 #[cfg(feature = "sources-host_metrics")]
 mod host_metrics;
 mod http;
 pub mod http_client;
+#[cfg(feature = "sources-utils-http-idempotency")]
+mod idempotency;
+#[cfg(feature = "sources-imap")]
+mod imap;
+#[cfg(feature = "sources-industrial_metrics")]
+mod industrial_metrics;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
@@ -80,6 +112,8 @@ mod kafka;
 mod key_value_parser;
 #[cfg(feature = "sources-kubernetes_logs")]
 mod kubernetes_logs;
+#[cfg(feature = "sinks-local_database")]
+mod local_database;
 #[cfg(feature = "transforms-log_to_metric")]
 mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
@@ -95,15 +129,31 @@ mod metric_to_log;
 mod mongodb_metrics;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 mod nats;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
+#[cfg(feature = "sinks-opentelemetry")]
+mod opentelemetry;
+#[cfg(any(
+    feature = "sinks-aws_s3",
+    feature = "sinks-azure_blob",
+    feature = "sinks-gcp"
+))]
+mod parquet;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
+#[cfg(feature = "transforms-pressure_sample")]
+mod pressure_sample;
+#[cfg(all(target_os = "linux", feature = "sources-proc_connector"))]
+mod proc_connector;
 mod process;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 mod prometheus;
 mod pulsar;
+#[cfg(feature = "sources-utils-http-quota")]
+mod quota;
 #[cfg(feature = "sinks-redis")]
 mod redis;
 #[cfg(feature = "transforms-reduce")]
@@ -118,9 +168,18 @@ mod rename_fields;
 #[cfg(feature = "transforms-route")]
 mod route;
 mod sample;
+#[cfg(feature = "schema-registry")]
+mod schema_registry;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sinks-sentry")]
+mod sentry;
+#[cfg(feature = "transforms-sessionize")]
+mod sessionize;
+mod shared_rate_limit;
 mod socket;
+#[cfg(all(unix, feature = "tls-spiffe"))]
+mod spiffe;
 mod split;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
@@ -132,25 +191,36 @@ mod stdin;
 mod syslog;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+mod tail_sampling;
 mod tcp;
 mod template;
 #[cfg(feature = "transforms-tokenizer")]
 mod tokenizer;
 mod udp;
 mod unix;
+#[cfg(all(unix, feature = "sources-varnishlog"))]
+mod varnishlog;
 mod vector;
+#[cfg(feature = "sinks-webhook_fanout")]
+mod webhook_fanout;
 
 pub mod kubernetes;
 
+pub use self::acknowledgements::*;
 pub use self::adaptive_concurrency::*;
 pub use self::add_fields::*;
 pub use self::add_tags::*;
 pub use self::aggregate::*;
+#[cfg(feature = "transforms-anonymize")]
+pub(crate) use self::anonymize::*;
 pub use self::ansi_stripper::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub use self::api::*;
+#[cfg(all(target_os = "linux", feature = "sources-auditd"))]
+pub(crate) use self::auditd::*;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 pub(crate) use self::aws_cloudwatch_logs_subscription_parser::*;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -163,10 +233,18 @@ pub use self::aws_kinesis_firehose::*;
 pub use self::aws_kinesis_streams::*;
 #[cfg(feature = "sinks-aws_s3")]
 pub use self::aws_s3_sink::*;
+#[cfg(feature = "sinks-aws_sns")]
+pub use self::aws_sns::*;
 #[cfg(feature = "sinks-aws_sqs")]
 pub use self::aws_sqs::*;
+#[cfg(feature = "sinks-axiom")]
+pub(crate) use self::axiom::*;
+#[cfg(feature = "sources-azure_event_hubs")]
+pub(crate) use self::azure_event_hubs::*;
 pub use self::batch::*;
 pub use self::blackhole::*;
+#[cfg(feature = "sinks-chat_webhook")]
+pub use self::chat_webhook::*;
 #[cfg(feature = "transforms-coercer")]
 pub(crate) use self::coercer::*;
 pub use self::common::*;
@@ -175,6 +253,8 @@ pub use self::concat::*;
 pub use self::conditions::*;
 #[cfg(feature = "sinks-console")]
 pub use self::console::*;
+#[cfg(feature = "sources-datadog")]
+pub use self::datadog_agent::*;
 #[cfg(feature = "sinks-datadog")]
 pub use self::datadog_events::*;
 #[cfg(feature = "sinks-datadog")]
@@ -183,16 +263,24 @@ pub use self::datadog_logs::*;
 pub use self::decoder::*;
 #[cfg(feature = "transforms-dedupe")]
 pub(crate) use self::dedupe::*;
+#[cfg(feature = "transforms-derive_severity")]
+pub(crate) use self::derive_severity::*;
 #[cfg(feature = "sources-dnstap")]
 pub(crate) use self::dnstap::*;
 #[cfg(feature = "sources-docker_logs")]
 pub use self::docker_logs::*;
 pub use self::elasticsearch::*;
 pub use self::encoding_transcode::*;
+#[cfg(feature = "transforms-enrich_dns")]
+pub(crate) use self::enrich_dns::*;
+pub(crate) use self::event_limits::*;
+pub(crate) use self::event_tracing::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
 pub use self::exec::*;
+#[cfg(feature = "sinks-exec")]
+pub use self::exec_sink::*;
 #[cfg(any(
     feature = "sources-file",
     feature = "sources-kubernetes_logs",
@@ -203,12 +291,15 @@ pub use self::file::*;
 pub use self::filter::*;
 #[cfg(feature = "sources-fluent")]
 pub use self::fluent::*;
+#[cfg(feature = "sinks-gcp")]
+pub(crate) use self::gcp_bigquery::*;
 #[cfg(feature = "sources-generator")]
 pub use self::generator::*;
 #[cfg(feature = "transforms-geoip")]
 pub(crate) use self::geoip::*;
 #[cfg(feature = "transforms-grok_parser")]
 pub(crate) use self::grok_parser::*;
+pub use self::healthcheck::*;
 pub use self::heartbeat::*;
 #[cfg(feature = "sources-host_metrics")]
 pub(crate) use self::host_metrics::*;
@@ -220,6 +311,12 @@ pub(crate) use self::host_metrics::*;
     feature = "sources-splunk_hec",
 ))]
 pub(crate) use self::http::*;
+#[cfg(feature = "sources-utils-http-idempotency")]
+pub(crate) use self::idempotency::*;
+#[cfg(feature = "sources-imap")]
+pub(crate) use self::imap::*;
+#[cfg(feature = "sources-industrial_metrics")]
+pub(crate) use self::industrial_metrics::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
@@ -230,6 +327,8 @@ pub use self::kafka::*;
 pub(crate) use self::key_value_parser::*;
 #[cfg(feature = "sources-kubernetes_logs")]
 pub use self::kubernetes_logs::*;
+#[cfg(feature = "sinks-local_database")]
+pub(crate) use self::local_database::*;
 #[cfg(feature = "transforms-log_to_metric")]
 pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "transforms-logfmt_parser")]
@@ -243,15 +342,31 @@ pub use self::lua::*;
 pub(crate) use self::metric_to_log::*;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 pub use self::nats::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 pub use self::open::*;
+#[cfg(feature = "sinks-opentelemetry")]
+pub(crate) use self::opentelemetry::*;
+#[cfg(any(
+    feature = "sinks-aws_s3",
+    feature = "sinks-azure_blob",
+    feature = "sinks-gcp"
+))]
+pub(crate) use self::parquet::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
+#[cfg(feature = "transforms-pressure_sample")]
+pub(crate) use self::pressure_sample::*;
+#[cfg(all(target_os = "linux", feature = "sources-proc_connector"))]
+pub(crate) use self::proc_connector::*;
 pub use self::process::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
 pub use self::pulsar::*;
+#[cfg(feature = "sources-utils-http-quota")]
+pub(crate) use self::quota::*;
 #[cfg(feature = "sinks-redis")]
 pub use self::redis::*;
 #[cfg(feature = "transforms-reduce")]
@@ -266,9 +381,18 @@ pub use self::rename_fields::*;
 #[cfg(feature = "transforms-route")]
 pub use self::route::*;
 pub use self::sample::*;
+#[cfg(feature = "schema-registry")]
+pub(crate) use self::schema_registry::*;
 #[cfg(feature = "sinks-sematext")]
 pub use self::sematext_metrics::*;
+#[cfg(feature = "sinks-sentry")]
+pub use self::sentry::*;
+#[cfg(feature = "transforms-sessionize")]
+pub(crate) use self::sessionize::*;
+pub(crate) use self::shared_rate_limit::*;
 pub(crate) use self::socket::*;
+#[cfg(all(unix, feature = "tls-spiffe"))]
+pub(crate) use self::spiffe::*;
 pub use self::split::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
@@ -280,13 +404,19 @@ pub use self::stdin::*;
 pub use self::syslog::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
+#[cfg(feature = "transforms-tail_sampling")]
+pub(crate) use self::tail_sampling::*;
 pub use self::tcp::*;
 pub use self::template::*;
 #[cfg(feature = "transforms-tokenizer")]
 pub(crate) use self::tokenizer::*;
 pub use self::udp::*;
 pub use self::unix::*;
+#[cfg(all(unix, feature = "sources-varnishlog"))]
+pub(crate) use self::varnishlog::*;
 pub use self::vector::*;
+#[cfg(feature = "sinks-webhook_fanout")]
+pub(crate) use self::webhook_fanout::*;
 #[cfg(windows)]
 pub use self::windows::*;
 #[cfg(feature = "sources-mongodb_metrics")]
@@ -0,0 +1,90 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use std::net::SocketAddr;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct NetflowEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+    pub exporter: SocketAddr,
+}
+
+impl InternalEvent for NetflowEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received flows.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            exporter = %self.exporter,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!("events_in_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowSocketError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for NetflowSocketError {
+    fn emit_logs(&self) {
+        error!(
+            message = "UDP socket error.",
+            error = %self.error,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowParseError<'a> {
+    pub error: &'a crate::sources::netflow::parser::ParseError,
+    pub exporter: SocketAddr,
+}
+
+impl<'a> InternalEvent for NetflowParseError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Error parsing flow datagram.",
+            error = %self.error,
+            exporter = %self.exporter,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowTemplateMissing {
+    pub exporter: SocketAddr,
+    pub template_id: u16,
+}
+
+impl InternalEvent for NetflowTemplateMissing {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Dropping flows of a template not received yet.",
+            exporter = %self.exporter,
+            template_id = %self.template_id,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("netflow_template_missing_total", 1);
+    }
+}
@@ -0,0 +1,26 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct OpenTelemetryRecordsRejected<'a> {
+    pub count: i64,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for OpenTelemetryRecordsRejected<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Records were rejected by the OpenTelemetry endpoint; dropping them.",
+            reason = %self.reason,
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count as u64,
+                 "reason" => "rejected");
+        counter!("component_errors_total", 1,
+                 "error_type" => "partial_success", "stage" => "sending");
+    }
+}
@@ -0,0 +1,26 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ParquetValuesMismatched<'a> {
+    pub field: &'a str,
+    pub column_type: &'static str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for ParquetValuesMismatched<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Values not matching their Parquet column type were written as nulls.",
+            field = %self.field,
+            column_type = %self.column_type,
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parquet_values_mismatched_total", self.count as u64,
+                 "column_type" => self.column_type);
+    }
+}
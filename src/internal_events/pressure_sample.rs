@@ -0,0 +1,55 @@
+use crate::config::ComponentKey;
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct PressureSampleEventDiscarded;
+
+impl InternalEvent for PressureSampleEventDiscarded {
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct PressureSamplingActivated<'a> {
+    pub sink: &'a ComponentKey,
+    pub utilization: f64,
+    pub rate: u64,
+}
+
+impl<'a> InternalEvent for PressureSamplingActivated<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Sink buffer passed its high watermark; sampling low priority events.",
+            sink = %self.sink,
+            utilization = %self.utilization,
+            rate = %self.rate,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("pressure_sampling_activations_total", 1);
+        gauge!("pressure_sampling_active", 1.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct PressureSamplingDeactivated<'a> {
+    pub sink: &'a ComponentKey,
+    pub utilization: f64,
+}
+
+impl<'a> InternalEvent for PressureSamplingDeactivated<'a> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Sink buffer drained to its low watermark; no longer sampling.",
+            sink = %self.sink,
+            utilization = %self.utilization,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("pressure_sampling_active", 0.0);
+    }
+}
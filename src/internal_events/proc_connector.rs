@@ -0,0 +1,41 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use std::io;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ProcConnectorEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ProcConnectorEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received process event.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!("events_in_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ProcConnectorReadFailed {
+    pub error: io::Error,
+}
+
+impl InternalEvent for ProcConnectorReadFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reading process events.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1);
+    }
+}
@@ -0,0 +1,43 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct QuotaConsumed {
+    pub events: usize,
+    pub bytes: usize,
+}
+
+impl InternalEvent for QuotaConsumed {
+    fn emit_metrics(&self) {
+        if self.events > 0 {
+            counter!("quota_consumed_events_total", self.events as u64);
+        }
+        if self.bytes > 0 {
+            counter!("quota_consumed_bytes_total", self.bytes as u64);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct QuotaLimitExceeded {
+    pub identity: &'static str,
+    pub reset_secs: f64,
+}
+
+impl InternalEvent for QuotaLimitExceeded {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Request rejected, quota exceeded.",
+            identity = %self.identity,
+            reset_secs = %self.reset_secs,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "quota_exceeded_total", 1,
+            "identity" => self.identity,
+        );
+    }
+}
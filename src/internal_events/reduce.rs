@@ -9,3 +9,41 @@ impl InternalEvent for ReduceStaleEventFlushed {
         counter!("stale_events_flushed_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ReduceStatesRestored {
+    pub count: usize,
+}
+
+impl InternalEvent for ReduceStatesRestored {
+    fn emit_logs(&self) {
+        info!(message = "Restored in-progress reduce states.", count = %self.count);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceSnapshotFailed {
+    pub error: crate::Error,
+    pub operation: &'static str,
+}
+
+impl InternalEvent for ReduceSnapshotFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to access the snapshot of in-progress reduce states.",
+            error = %self.error,
+            operation = %self.operation,
+            error_type = "snapshot_failed",
+            stage = "processing",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "snapshot_failed",
+            "stage" => "processing",
+        );
+    }
+}
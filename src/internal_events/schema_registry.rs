@@ -0,0 +1,45 @@
+use crate::schema_registry::SchemaRegistryError;
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SchemaRegistryDecodeFailed<'a> {
+    pub error: &'a SchemaRegistryError,
+}
+
+impl InternalEvent for SchemaRegistryDecodeFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to decode message with schema from registry.",
+            error = %self.error,
+            error_type = "parse_failed",
+            stage = "processing",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SchemaRegistryEncodeFailed<'a> {
+    pub error: &'a SchemaRegistryError,
+}
+
+impl InternalEvent for SchemaRegistryEncodeFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to encode event with schema from registry.",
+            error = %self.error,
+            error_type = "encode_failed",
+            stage = "processing",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("encode_errors_total", 1);
+    }
+}
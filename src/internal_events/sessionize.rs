@@ -0,0 +1,69 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SessionizeSessionStarted;
+
+impl InternalEvent for SessionizeSessionStarted {
+    fn emit_metrics(&self) {
+        counter!("sessions_started_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SessionizeSessionEnded {
+    pub reason: &'static str,
+    pub events: u64,
+}
+
+impl InternalEvent for SessionizeSessionEnded {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Session ended.",
+            reason = %self.reason,
+            events = %self.events,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sessions_ended_total", 1, "reason" => self.reason);
+    }
+}
+
+#[derive(Debug)]
+pub struct SessionizeSessionsRestored {
+    pub count: usize,
+}
+
+impl InternalEvent for SessionizeSessionsRestored {
+    fn emit_logs(&self) {
+        info!(message = "Restored active sessions.", count = %self.count);
+    }
+}
+
+#[derive(Debug)]
+pub struct SessionizeSnapshotFailed {
+    pub error: crate::Error,
+    pub operation: &'static str,
+}
+
+impl InternalEvent for SessionizeSnapshotFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to access the snapshot of active sessions.",
+            error = %self.error,
+            operation = %self.operation,
+            error_type = "snapshot_failed",
+            stage = "processing",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "snapshot_failed",
+            "stage" => "processing",
+        );
+    }
+}
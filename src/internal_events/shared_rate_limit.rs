@@ -0,0 +1,24 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SharedRateLimitThrottled<'a> {
+    pub rate_limiter: &'a str,
+}
+
+impl<'a> InternalEvent for SharedRateLimitThrottled<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Request delayed by shared rate limiter.",
+            rate_limiter = %self.rate_limiter,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "rate_limiter_throttled_requests_total", 1,
+            "rate_limiter" => self.rate_limiter.to_owned(),
+        );
+    }
+}
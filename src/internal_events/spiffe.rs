@@ -0,0 +1,44 @@
+use metrics::counter;
+use std::path::Path;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SpiffeSvidUpdated<'a> {
+    pub spiffe_id: &'a str,
+}
+
+impl InternalEvent for SpiffeSvidUpdated<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Received X.509-SVID from the SPIFFE Workload API.",
+            spiffe_id = %self.spiffe_id,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("spiffe_svid_updates_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SpiffeWorkloadApiError<'a> {
+    pub error: &'a crate::Error,
+    pub socket_path: &'a Path,
+}
+
+impl InternalEvent for SpiffeWorkloadApiError<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to fetch X.509-SVID from the SPIFFE Workload API; retrying.",
+            error = %self.error,
+            socket_path = %self.socket_path.display(),
+            error_type = "connection_failed",
+            stage = "receiving",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
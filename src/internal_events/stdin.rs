@@ -1,6 +1,7 @@
 // ## skip check-events ##
 
 use metrics::counter;
+use vector_core::event::BatchStatus;
 use vector_core::internal_event::InternalEvent;
 
 #[derive(Debug)]
@@ -20,3 +21,28 @@ impl InternalEvent for StdinEventsReceived {
         counter!("processed_bytes_total", self.byte_size as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct StdinEventsAcknowledgementFailed {
+    pub status: BatchStatus,
+    pub offset: u64,
+}
+
+impl InternalEvent for StdinEventsAcknowledgementFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Events read from STDIN were not delivered.",
+            status = ?self.status,
+            offset = self.offset,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "delivery_failed",
+            "stage" => "sending"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct TailSamplingTraceDecided<'a> {
+    pub policy: &'a str,
+    pub kept: bool,
+    pub spans: usize,
+}
+
+impl<'a> InternalEvent for TailSamplingTraceDecided<'a> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Sampling decision made.",
+            policy = %self.policy,
+            kept = %self.kept,
+            spans = %self.spans,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        let decision = if self.kept { "kept" } else { "dropped" };
+        counter!(
+            "sampled_traces_total", 1,
+            "policy" => self.policy.to_owned(),
+            "decision" => decision,
+        );
+        counter!(
+            "sampled_spans_total", self.spans as u64,
+            "policy" => self.policy.to_owned(),
+            "decision" => decision,
+        );
+        if !self.kept {
+            counter!("events_discarded_total", self.spans as u64);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplingLateSpan {
+    pub kept: bool,
+}
+
+impl InternalEvent for TailSamplingLateSpan {
+    fn emit_metrics(&self) {
+        counter!(
+            "sampled_late_spans_total", 1,
+            "decision" => if self.kept { "kept" } else { "dropped" },
+        );
+        if !self.kept {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
@@ -132,3 +132,50 @@ impl InternalEvent for TcpBytesReceived {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct TcpConnectionRejected {
+    pub peer_addr: IpAddr,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for TcpConnectionRejected {
+    fn emit_logs(&self) {
+        let message = match self.reason {
+            "permit_origin" => "Connection rejected, peer address not permitted.",
+            _ => "Connection rejected, too many connections open.",
+        };
+        warn!(
+            message,
+            peer_addr = %self.peer_addr,
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_rejected_total", 1,
+                 "mode" => "tcp", "reason" => self.reason);
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpConnectionThrottled {
+    pub peer_addr: IpAddr,
+    pub delay: std::time::Duration,
+}
+
+impl InternalEvent for TcpConnectionThrottled {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Connection throttled, events rate limit exceeded.",
+            peer_addr = %self.peer_addr,
+            delay = ?self.delay,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_throttled_total", 1, "mode" => "tcp");
+    }
+}
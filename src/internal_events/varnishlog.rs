@@ -0,0 +1,22 @@
+// ## skip check-events ##
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct VarnishlogEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for VarnishlogEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received transactions.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!("events_in_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
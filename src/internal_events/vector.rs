@@ -35,3 +35,34 @@ impl<'a> InternalEvent for VectorProtoDecodeError<'a> {
         counter!("protobuf_decode_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct VectorEndpointEjected<'a> {
+    pub endpoint: &'a str,
+    pub failures: u32,
+}
+
+impl<'a> InternalEvent for VectorEndpointEjected<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Ejecting endpoint after consecutive failed requests.",
+            endpoint = %self.endpoint,
+            failures = %self.failures,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("endpoint_ejections_total", 1, "endpoint" => self.endpoint.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorEndpointReinstated<'a> {
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for VectorEndpointReinstated<'a> {
+    fn emit_logs(&self) {
+        info!(message = "Reinstating healthy endpoint.", endpoint = %self.endpoint);
+    }
+}
@@ -0,0 +1,113 @@
+use metrics::counter;
+use std::time::Duration;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct WebhookFanoutEventsDelivered<'a> {
+    pub endpoint: &'a str,
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl<'a> InternalEvent for WebhookFanoutEventsDelivered<'a> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Events delivered.",
+            endpoint = %self.endpoint,
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "endpoint_delivered_events_total", self.count as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!(
+            "endpoint_delivered_bytes_total", self.byte_size as u64,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WebhookFanoutDeliveryFailed<'a> {
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for WebhookFanoutDeliveryFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to deliver events to endpoint.",
+            endpoint = %self.endpoint,
+            error_type = "delivery_failed",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "delivery_failed",
+            "stage" => "sending",
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WebhookFanoutCircuitOpened<'a> {
+    pub endpoint: &'a str,
+    pub consecutive_failures: u32,
+    pub cooldown: Duration,
+}
+
+impl<'a> InternalEvent for WebhookFanoutCircuitOpened<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Endpoint failed too many requests in a row, failing its requests until it cools down.",
+            endpoint = %self.endpoint,
+            consecutive_failures = %self.consecutive_failures,
+            cooldown_secs = %self.cooldown.as_secs(),
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "endpoint_circuit_breaks_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WebhookFanoutRequestRejected<'a> {
+    pub endpoint: &'a str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for WebhookFanoutRequestRejected<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Request failed without being sent, as its endpoint is cooling down.",
+            endpoint = %self.endpoint,
+            count = %self.count,
+            error_type = "circuit_open",
+            stage = "sending",
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => "circuit_open",
+            "stage" => "sending",
+            "endpoint" => self.endpoint.to_owned(),
+        );
+        counter!("component_discarded_events_total", self.count as u64);
+    }
+}
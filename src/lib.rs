@@ -25,9 +25,11 @@ extern crate vrl_cli;
 pub mod config;
 pub mod cli;
 pub mod conditions;
+pub mod config_diff;
 pub mod dns;
 #[cfg(feature = "docker")]
 pub mod docker;
+pub mod embedded;
 pub mod expiring_hash_map;
 pub mod generate;
 #[macro_use]
@@ -37,6 +39,7 @@ pub mod api;
 pub mod app;
 pub mod async_read;
 pub mod buffers;
+pub mod checkpoints;
 #[cfg(feature = "codecs")]
 pub mod codecs;
 pub mod encoding_transcode;
@@ -52,8 +55,12 @@ pub mod list;
 pub(crate) mod pipeline;
 pub(crate) mod proto;
 pub mod providers;
+#[cfg(feature = "sinks-datadog")]
+pub mod replay;
 #[cfg(feature = "rusoto_core")]
 pub mod rusoto;
+#[cfg(feature = "schema-registry")]
+pub mod schema_registry;
 pub mod serde;
 #[cfg(windows)]
 pub mod service;
@@ -3,3 +3,12 @@ use crate::event::proto as event;
 
 #[cfg(any(feature = "sources-vector", feature = "sinks-vector"))]
 pub mod vector;
+
+#[cfg(feature = "sinks-gcp")]
+pub mod bigquery;
+
+#[cfg(feature = "sinks-opentelemetry")]
+pub mod opentelemetry;
+
+#[cfg(feature = "tls-spiffe")]
+pub mod spiffe;
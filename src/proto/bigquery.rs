@@ -0,0 +1,27 @@
+#![allow(clippy::clone_on_ref_ptr)]
+
+// The generated code refers to other packages by relative paths, so the
+// modules have to mirror the package hierarchy.
+pub mod google {
+    pub mod rpc {
+        tonic::include_proto!("google.rpc");
+    }
+
+    pub mod cloud {
+        pub mod bigquery {
+            pub mod storage {
+                pub mod v1 {
+                    tonic::include_proto!("google.cloud.bigquery.storage.v1");
+                }
+            }
+        }
+    }
+}
+
+pub use google::cloud::bigquery::storage::v1::{
+    append_rows_request, append_rows_response,
+    big_query_write_client::BigQueryWriteClient as Client, write_stream, AppendRowsRequest,
+    AppendRowsResponse, CreateWriteStreamRequest, GetWriteStreamRequest, ProtoRows, ProtoSchema,
+    RowError, WriteStream,
+};
+pub use google::rpc::Status;
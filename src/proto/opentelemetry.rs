@@ -0,0 +1,66 @@
+#![allow(clippy::clone_on_ref_ptr)]
+
+// The generated code refers to other packages by relative paths, so the
+// modules have to mirror the package hierarchy.
+pub mod opentelemetry {
+    pub mod proto {
+        pub mod common {
+            pub mod v1 {
+                tonic::include_proto!("opentelemetry.proto.common.v1");
+            }
+        }
+
+        pub mod resource {
+            pub mod v1 {
+                tonic::include_proto!("opentelemetry.proto.resource.v1");
+            }
+        }
+
+        pub mod logs {
+            pub mod v1 {
+                tonic::include_proto!("opentelemetry.proto.logs.v1");
+            }
+        }
+
+        pub mod metrics {
+            pub mod v1 {
+                tonic::include_proto!("opentelemetry.proto.metrics.v1");
+            }
+        }
+
+        pub mod collector {
+            pub mod logs {
+                pub mod v1 {
+                    tonic::include_proto!("opentelemetry.proto.collector.logs.v1");
+                }
+            }
+
+            pub mod metrics {
+                pub mod v1 {
+                    tonic::include_proto!("opentelemetry.proto.collector.metrics.v1");
+                }
+            }
+        }
+    }
+}
+
+pub use opentelemetry::proto::{
+    collector::{
+        logs::v1::{
+            logs_service_client::LogsServiceClient, ExportLogsServiceRequest,
+            ExportLogsServiceResponse,
+        },
+        metrics::v1::{
+            metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
+            ExportMetricsServiceResponse,
+        },
+    },
+    common::v1::{any_value, AnyValue, ArrayValue, InstrumentationScope, KeyValue, KeyValueList},
+    logs::v1::{LogRecord, ResourceLogs, ScopeLogs, SeverityNumber},
+    metrics::v1::{
+        metric, number_data_point, summary_data_point, AggregationTemporality, Gauge, Histogram,
+        HistogramDataPoint, Metric, NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary,
+        SummaryDataPoint,
+    },
+    resource::v1::Resource,
+};
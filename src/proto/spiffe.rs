@@ -0,0 +1,4 @@
+#![allow(clippy::clone_on_ref_ptr)]
+
+// The Workload API protobuf has no package, so prost names its module `_`.
+include!(concat!(env!("OUT_DIR"), "/_.rs"));
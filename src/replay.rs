@@ -0,0 +1,183 @@
+use crate::config::{self, ComponentKey};
+use crate::sinks::datadog::logs::{spool_entries, DatadogLogsConfig, SpoolEntry};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use vector_core::config::proxy::ProxyConfig;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The ID of the `datadog_logs` sink whose spooled payloads are replayed.
+    sink: String,
+
+    /// Replay the payloads with this API key, instead of the default API key of
+    /// the sink. Payloads that were sent with an API key set on their events
+    /// are only replayed with this option.
+    #[structopt(long)]
+    api_key: Option<String>,
+
+    /// List the spooled payloads without replaying them.
+    #[structopt(long)]
+    dry_run: bool,
+
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.toml` will be targeted.
+    #[structopt(
+        name = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        use_delimiter(true)
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[structopt(
+        name = "config-dir",
+        short = "C",
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        use_delimiter(true)
+    )]
+    config_dirs: Vec<PathBuf>,
+}
+
+impl Opts {
+    /// The configuration of the sink, along with the proxy settings it is built with.
+    fn sink(&self) -> Result<(DatadogLogsConfig, ProxyConfig), Vec<String>> {
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| config::ConfigPath::File(path.clone(), None))
+            .chain(
+                self.config_dirs
+                    .iter()
+                    .map(|dir| config::ConfigPath::Dir(dir.clone())),
+            )
+            .collect::<Vec<_>>();
+        let paths = config::process_paths(&paths)
+            .ok_or_else(|| vec!["No configuration found to read the sink from.".to_owned()])?;
+        let (builder, _) = config::load_builder_from_paths(&paths)?;
+
+        let sink = builder
+            .sinks
+            .get(&ComponentKey::from(self.sink.as_str()))
+            .ok_or_else(|| vec![format!("No sink {:?} in the configuration.", self.sink)])?;
+        if sink.inner.sink_type() != "datadog_logs" {
+            return Err(vec![format!(
+                "Sink {:?} isn't a `datadog_logs` sink.",
+                self.sink
+            )]);
+        }
+
+        // Sink configurations are only reachable as trait objects, so the sink
+        // is read back from its serialized configuration.
+        let mut value =
+            serde_json::to_value(&sink.inner).map_err(|error| vec![error.to_string()])?;
+        if let Some(object) = value.as_object_mut() {
+            object.remove("type");
+        }
+        let config = serde_json::from_value(value).map_err(|error| vec![error.to_string()])?;
+        let proxy = ProxyConfig::merge_with_env(&builder.global.proxy, sink.proxy());
+
+        Ok((config, proxy))
+    }
+}
+
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let (config, proxy) = match opts.sink() {
+        Ok(sink) => sink,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{}", error);
+            }
+            return exitcode::CONFIG;
+        }
+    };
+    let spool = match config.spool() {
+        Some(spool) => spool,
+        None => {
+            eprintln!("Sink {:?} doesn't have a `spool` configured.", opts.sink);
+            return exitcode::CONFIG;
+        }
+    };
+    let mut replayer = match config.replayer(&proxy) {
+        Ok(replayer) => replayer,
+        Err(error) => {
+            eprintln!("Failed to build the sink: {}", error);
+            return exitcode::CONFIG;
+        }
+    };
+    let paths = match spool_entries(spool.path()) {
+        Ok(paths) => paths,
+        Err(error) => {
+            eprintln!("Failed to read the spool {:?}: {}", spool.path(), error);
+            return exitcode::IOERR;
+        }
+    };
+
+    let (mut replayed, mut events, mut failed) = (0, 0, 0);
+    for path in paths {
+        let entry = match SpoolEntry::load(&path) {
+            Ok(entry) => entry,
+            Err(error) => {
+                eprintln!("Failed to read spooled payload {:?}: {}", path, error);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let api_key = match &opts.api_key {
+            Some(api_key) => api_key.as_str(),
+            None if entry.metadata.default_api_key => config.default_api_key(),
+            None => {
+                eprintln!(
+                    "Skipping {}, which was sent with the API key ending in {:?} set on its events. Replay it with `--api-key`.",
+                    entry.id(),
+                    entry.metadata.api_key_suffix
+                );
+                continue;
+            }
+        };
+
+        let metadata = &entry.metadata;
+        if opts.dry_run {
+            println!(
+                "{}\t{}\t{}\t{} events",
+                entry.id(),
+                metadata.status,
+                metadata.rejected_at.to_rfc3339(),
+                metadata.events
+            );
+            continue;
+        }
+
+        let id = entry.id().into_owned();
+        match replayer.replay(entry, api_key).await {
+            Ok(count) => {
+                println!("Replayed {} ({} events).", id, count);
+                replayed += 1;
+                events += count;
+            }
+            Err(error) => {
+                eprintln!("Failed to replay {}: {}", id, error);
+                failed += 1;
+            }
+        }
+    }
+
+    if !opts.dry_run {
+        println!("Replayed {} payloads ({} events).", replayed, events);
+    }
+    if failed > 0 {
+        eprintln!("{} payloads failed and were left in the spool.", failed);
+        exitcode::UNAVAILABLE
+    } else {
+        exitcode::OK
+    }
+}
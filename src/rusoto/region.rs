@@ -1,4 +1,5 @@
 use http::{uri::InvalidUri, Uri};
+use indexmap::IndexMap;
 use rusoto_core::{region::ParseRegionError, Region};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -10,22 +11,113 @@ use std::str::FromStr;
 pub struct RegionOrEndpoint {
     region: Option<String>,
     endpoint: Option<String>,
+    endpoint_discovery: EndpointDiscovery,
 }
 
 impl RegionOrEndpoint {
-    pub const fn with_region(region: String) -> Self {
+    pub fn with_region(region: String) -> Self {
         Self {
             region: Some(region),
-            endpoint: None,
+            ..Default::default()
         }
     }
 
-    pub const fn with_endpoint(endpoint: String) -> Self {
+    pub fn with_endpoint(endpoint: String) -> Self {
         Self {
-            region: None,
             endpoint: Some(endpoint),
+            ..Default::default()
         }
     }
+
+    /// Resolves the region of a service, identified by the prefix of its
+    /// endpoints such as `kinesis` or `logs`, using the endpoint discovery
+    /// options.
+    pub fn region_for_service(&self, service: &str) -> Result<Region, ParseError> {
+        let region = Region::try_from(self)?;
+        if self.endpoint.is_some() {
+            return if self.endpoint_discovery.is_default() {
+                Ok(region)
+            } else {
+                Err(ParseError::EndpointAndEndpointDiscovery)
+            };
+        }
+
+        Ok(
+            match self.endpoint_discovery.endpoint(service, region.name())? {
+                Some(endpoint) => Region::Custom {
+                    name: region.name().to_owned(),
+                    endpoint,
+                },
+                None => region,
+            },
+        )
+    }
+}
+
+/// Options to pick the endpoints of services in a region other than their
+/// default ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointDiscovery {
+    /// Use the FIPS 140-2 endpoints of services.
+    fips: bool,
+    /// Use the dual-stack (IPv4 and IPv6) endpoints of services.
+    dual_stack: bool,
+    /// Endpoint URL templates by service, or `*` for every other service,
+    /// with `{service}`, `{region}` and `{dns_suffix}` placeholders.
+    templates: IndexMap<String, String>,
+}
+
+impl EndpointDiscovery {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn endpoint(&self, service: &str, region: &str) -> Result<Option<String>, ParseError> {
+        let dns_suffix = if region.starts_with("cn-") {
+            "amazonaws.com.cn"
+        } else {
+            "amazonaws.com"
+        };
+
+        if let Some(template) = self
+            .templates
+            .get(service)
+            .or_else(|| self.templates.get("*"))
+        {
+            let endpoint = template
+                .replace("{service}", service)
+                .replace("{region}", region)
+                .replace("{dns_suffix}", dns_suffix);
+            if endpoint.contains('{') {
+                return Err(ParseError::InvalidEndpointTemplate {
+                    template: template.clone(),
+                });
+            }
+            endpoint.parse::<Uri>().context(EndpointParseError)?;
+            return Ok(Some(endpoint));
+        }
+
+        let service = if self.fips {
+            format!("{}-fips", service)
+        } else {
+            service.to_owned()
+        };
+        let host = match (self.fips, self.dual_stack) {
+            (false, false) => return Ok(None),
+            (_, false) => format!("{}.{}.{}", service, region, dns_suffix),
+            // S3 predates the `api.aws` domain of the dual-stack endpoints of
+            // the other services.
+            (_, true) if service.starts_with("s3") => {
+                format!("{}.dualstack.{}.{}", service, region, dns_suffix)
+            }
+            (_, true) if region.starts_with("cn-") => {
+                format!("{}.{}.api.amazonwebservices.com.cn", service, region)
+            }
+            (_, true) => format!("{}.{}.api.aws", service, region),
+        };
+        Ok(Some(format!("https://{}", host)))
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -38,6 +130,10 @@ pub enum ParseError {
     BothRegionAndEndpoint,
     #[snafu(display("Must set either 'region' or 'endpoint'"))]
     MissingRegionAndEndpoint,
+    #[snafu(display("'endpoint_discovery' can't be used with 'endpoint'"))]
+    EndpointAndEndpointDiscovery,
+    #[snafu(display("Unknown placeholder in endpoint template {:?}", template))]
+    InvalidEndpointTemplate { template: String },
 }
 
 impl TryFrom<&RegionOrEndpoint> for Region {
@@ -215,6 +311,110 @@ mod tests {
         );
     }
 
+    fn region_for_service(config: &str, service: &str) -> Result<Region, ParseError> {
+        let config: Config = toml::from_str(config).unwrap();
+        config.inner.region.region_for_service(service)
+    }
+
+    #[test]
+    fn region_for_service_without_discovery() {
+        let region = region_for_service(
+            indoc! {r#"
+                [inner]
+                region = "us-east-1"
+            "#},
+            "kinesis",
+        );
+        assert_eq!(region.unwrap(), Region::UsEast1);
+    }
+
+    #[test]
+    fn region_for_service_fips_dual_stack() {
+        let config = indoc! {r#"
+            [inner]
+            region = "us-gov-west-1"
+            endpoint_discovery.fips = true
+        "#};
+        assert_eq!(
+            region_for_service(config, "kinesis").unwrap(),
+            Region::Custom {
+                name: "us-gov-west-1".into(),
+                endpoint: "https://kinesis-fips.us-gov-west-1.amazonaws.com".into(),
+            }
+        );
+
+        let config = indoc! {r#"
+            [inner]
+            region = "us-east-1"
+            endpoint_discovery.fips = true
+            endpoint_discovery.dual_stack = true
+        "#};
+        assert_eq!(
+            region_for_service(config, "s3").unwrap(),
+            Region::Custom {
+                name: "us-east-1".into(),
+                endpoint: "https://s3-fips.dualstack.us-east-1.amazonaws.com".into(),
+            }
+        );
+        assert_eq!(
+            region_for_service(config, "logs").unwrap(),
+            Region::Custom {
+                name: "us-east-1".into(),
+                endpoint: "https://logs-fips.us-east-1.api.aws".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn region_for_service_templates() {
+        let config = indoc! {r#"
+            [inner]
+            region = "cn-north-1"
+            endpoint_discovery.fips = true
+
+            [inner.endpoint_discovery.templates]
+            sqs = "https://vpce-0123.{service}.{region}.vpce.{dns_suffix}"
+            "*" = "https://{service}.internal.example.com"
+        "#};
+        assert_eq!(
+            region_for_service(config, "sqs").unwrap(),
+            Region::Custom {
+                name: "cn-north-1".into(),
+                endpoint: "https://vpce-0123.sqs.cn-north-1.vpce.amazonaws.com.cn".into(),
+            }
+        );
+        assert_eq!(
+            region_for_service(config, "s3").unwrap(),
+            Region::Custom {
+                name: "cn-north-1".into(),
+                endpoint: "https://s3.internal.example.com".into(),
+            }
+        );
+
+        let config = indoc! {r#"
+            [inner]
+            region = "us-east-1"
+            endpoint_discovery.templates.sqs = "https://{service}.{zone}.example.com"
+        "#};
+        assert!(matches!(
+            region_for_service(config, "sqs"),
+            Err(ParseError::InvalidEndpointTemplate { .. })
+        ));
+    }
+
+    #[test]
+    fn region_for_service_rejects_endpoint_with_discovery() {
+        let config = indoc! {r#"
+            [inner]
+            endpoint = "http://localhost:4566"
+            endpoint_discovery.fips = true
+        "#};
+        assert!(matches!(
+            region_for_service(config, "sqs"),
+            Err(ParseError::EndpointAndEndpointDiscovery)
+        ));
+    }
+
     #[test]
     fn region_from_endpoint_strips_path_query() {
         assert_eq!(
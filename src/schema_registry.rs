@@ -0,0 +1,728 @@
+//! Client for schema registries speaking the Confluent Schema Registry REST
+//! API, which is also served by Apicurio Registry's `ccompat` endpoint.
+//!
+//! Schemas are fetched by ID or subject, cached, and used to decode and encode
+//! payloads in the registry wire format: a zero magic byte followed by the
+//! big-endian schema ID and the serialized record. Protobuf records are also
+//! preceded by the indexes of their message type within the schema.
+
+use crate::{
+    config::ProxyConfig,
+    event::{LogEvent, Value},
+    http::{Auth, HttpClient, HttpError},
+    tls::{TlsOptions, TlsSettings},
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use http::{header, Request, StatusCode, Uri};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use prost::Message;
+use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::{TryFrom, TryInto},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use vrl_stdlib::ProtobufMessage;
+
+const MAGIC_BYTE: u8 = 0;
+const CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+#[derive(Debug, Snafu)]
+pub enum SchemaRegistryError {
+    #[snafu(display("Invalid schema registry URL {:?}: {}", url, source))]
+    InvalidUrl {
+        url: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Failed to build schema registry request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Schema registry request failed: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("Failed to read schema registry response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Schema registry responded with {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[snafu(display("Invalid schema registry response: {}", source))]
+    InvalidResponse { source: serde_json::Error },
+    #[snafu(display("Invalid Avro schema with ID {}: {}", id, source))]
+    InvalidAvroSchema { id: u32, source: avro_rs::Error },
+    #[snafu(display("Invalid Protobuf schema with ID {}: {}", id, reason))]
+    InvalidProtobufSchema { id: u32, reason: String },
+    #[snafu(display("Payload is not in the schema registry wire format"))]
+    MissingSchemaId,
+    #[snafu(display("Protobuf message indexes {:?} not found in the schema", indexes))]
+    UnknownMessageIndexes { indexes: Vec<usize> },
+    #[snafu(display("Protobuf (de)serialization failed: {}", reason))]
+    Protobuf { reason: String },
+    #[snafu(display("Avro (de)serialization failed: {}", source))]
+    Avro { source: avro_rs::Error },
+    #[snafu(display("JSON (de)serialization failed: {}", source))]
+    Json { source: serde_json::Error },
+    #[snafu(display("Decoding and encoding {} schemas is not supported", schema_type))]
+    UnsupportedSchemaType { schema_type: &'static str },
+    #[snafu(display("Only log events can be encoded with a schema"))]
+    UnsupportedEvent,
+}
+
+/// Connection settings for a schema registry.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SchemaRegistryConfig {
+    pub url: String,
+    pub auth: Option<Auth>,
+    pub tls: Option<TlsOptions>,
+    /// How long the latest version of a subject is cached. Schemas looked up
+    /// by ID are immutable and cached for the lifetime of the client.
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+/// Settings for producers encoding events with a schema from the registry.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SchemaRegistryEncodingConfig {
+    #[serde(flatten)]
+    pub registry: SchemaRegistryConfig,
+    pub subject: String,
+    /// Schema registered under `subject` on startup. If unset, the latest
+    /// version of the subject is used.
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_type: SchemaType,
+}
+
+impl SchemaRegistryEncodingConfig {
+    /// Registers or looks up the schema events are encoded with.
+    pub async fn resolve(&self, proxy: &ProxyConfig) -> crate::Result<Arc<Schema>> {
+        let client = SchemaRegistryClient::new(&self.registry, proxy)?;
+        let schema = match &self.schema {
+            Some(schema) => {
+                client
+                    .register(&self.subject, self.schema_type, schema)
+                    .await?
+            }
+            None => client.latest(&self.subject).await?,
+        };
+        Ok(schema)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaType {
+    #[serde(alias = "AVRO")]
+    Avro,
+    #[serde(alias = "JSON")]
+    Json,
+    #[serde(alias = "PROTOBUF")]
+    Protobuf,
+}
+
+impl Default for SchemaType {
+    // The registry omits the type for Avro schemas.
+    fn default() -> Self {
+        Self::Avro
+    }
+}
+
+impl SchemaType {
+    const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Avro => "AVRO",
+            Self::Json => "JSON",
+            Self::Protobuf => "PROTOBUF",
+        }
+    }
+}
+
+/// A schema fetched from the registry.
+#[derive(Debug)]
+pub struct Schema {
+    id: u32,
+    schema_type: SchemaType,
+    avro: Option<avro_rs::Schema>,
+    protobuf: Option<ProtobufSchema>,
+}
+
+impl Schema {
+    /// Parses a schema as served by the registry. Protobuf schemas must be
+    /// in the `serialized` format, a base64-encoded `FileDescriptorProto`.
+    pub fn new(id: u32, schema_type: SchemaType, raw: &str) -> Result<Self, SchemaRegistryError> {
+        let (avro, protobuf) = match schema_type {
+            SchemaType::Avro => (
+                Some(avro_rs::Schema::parse_str(raw).context(InvalidAvroSchema { id })?),
+                None,
+            ),
+            SchemaType::Json => (None, None),
+            SchemaType::Protobuf => (None, Some(ProtobufSchema::parse(id, raw)?)),
+        };
+        Ok(Self {
+            id,
+            schema_type,
+            avro,
+            protobuf,
+        })
+    }
+
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Decodes a record written with this schema, without the wire format
+    /// header.
+    pub fn decode(&self, mut record: &[u8]) -> Result<Value, SchemaRegistryError> {
+        match (self.schema_type, &self.avro, &self.protobuf) {
+            (SchemaType::Avro, Some(schema), _) => {
+                avro_rs::from_avro_datum(schema, &mut record, None)
+                    .map(avro_to_value)
+                    .context(Avro)
+            }
+            (SchemaType::Json, _, _) => serde_json::from_slice(record).context(Json),
+            (SchemaType::Protobuf, _, Some(schema)) => schema.decode(record),
+            (schema_type, _, _) => Err(SchemaRegistryError::UnsupportedSchemaType {
+                schema_type: schema_type.as_str(),
+            }),
+        }
+    }
+
+    /// Encodes a log event with this schema, including the wire format header.
+    pub fn encode(&self, log: &LogEvent) -> Result<Vec<u8>, SchemaRegistryError> {
+        let mut buffer = Vec::with_capacity(5);
+        buffer.push(MAGIC_BYTE);
+        buffer.extend_from_slice(&self.id.to_be_bytes());
+        match (self.schema_type, &self.avro, &self.protobuf) {
+            (SchemaType::Avro, Some(schema), _) => {
+                let value = avro_rs::to_value(log).context(Avro)?;
+                let value = value.resolve(schema).context(Avro)?;
+                buffer.extend(avro_rs::to_avro_datum(schema, value).context(Avro)?);
+            }
+            (SchemaType::Json, _, _) => serde_json::to_writer(&mut buffer, log).context(Json)?,
+            (SchemaType::Protobuf, _, Some(schema)) => schema.encode(log, &mut buffer)?,
+            (schema_type, _, _) => {
+                return Err(SchemaRegistryError::UnsupportedSchemaType {
+                    schema_type: schema_type.as_str(),
+                })
+            }
+        }
+        Ok(buffer)
+    }
+}
+
+/// A Protobuf schema, which may define several message types.
+#[derive(Debug)]
+struct ProtobufSchema {
+    file: FileDescriptorProto,
+    messages: HashMap<String, ProtobufMessage>,
+}
+
+impl ProtobufSchema {
+    fn parse(id: u32, raw: &str) -> Result<Self, SchemaRegistryError> {
+        let invalid = |reason: String| SchemaRegistryError::InvalidProtobufSchema { id, reason };
+        let bytes = base64::decode(raw).map_err(|error| invalid(error.to_string()))?;
+        let file = FileDescriptorProto::decode(bytes.as_slice())
+            .map_err(|error| invalid(error.to_string()))?;
+        if file.message_type.is_empty() {
+            return Err(invalid("no message types defined".to_owned()));
+        }
+
+        let messages = ProtobufMessage::all(FileDescriptorSet {
+            file: vec![file.clone()],
+        });
+        Ok(Self { file, messages })
+    }
+
+    /// Returns the fully qualified name of the message type at the given
+    /// indexes, the first one into the top-level messages of the file and the
+    /// following ones into the messages nested in the previous one.
+    fn message_name(&self, indexes: &[usize]) -> Option<String> {
+        let (first, nested) = indexes.split_first()?;
+        let mut message: &DescriptorProto = self.file.message_type.get(*first)?;
+        let mut name = match self.file.package() {
+            "" => message.name().to_owned(),
+            package => format!("{}.{}", package, message.name()),
+        };
+        for index in nested {
+            message = message.nested_type.get(*index)?;
+            name = format!("{}.{}", name, message.name());
+        }
+        Some(name)
+    }
+
+    fn decode(&self, record: &[u8]) -> Result<Value, SchemaRegistryError> {
+        let (indexes, record) = split_message_indexes(record)?;
+        let message = self
+            .message_name(&indexes)
+            .and_then(|name| self.messages.get(&name))
+            .ok_or(SchemaRegistryError::UnknownMessageIndexes { indexes })?;
+        message
+            .decode(record)
+            .map(Into::into)
+            .map_err(|error| SchemaRegistryError::Protobuf {
+                reason: error.to_string(),
+            })
+    }
+
+    /// Encodes a log event as the first message type of the schema.
+    fn encode(&self, log: &LogEvent, buffer: &mut Vec<u8>) -> Result<(), SchemaRegistryError> {
+        let message = self
+            .message_name(&[0])
+            .and_then(|name| self.messages.get(&name))
+            .ok_or(SchemaRegistryError::UnknownMessageIndexes { indexes: vec![0] })?;
+        let record = message
+            .encode(Value::Map(log.as_map().clone()).into())
+            .map_err(|error| SchemaRegistryError::Protobuf {
+                reason: error.to_string(),
+            })?;
+
+        // The indexes of the first message type are written as a single zero.
+        buffer.push(0);
+        match record {
+            vrl::Value::Bytes(record) => buffer.extend_from_slice(&record),
+            _ => unreachable!("messages are encoded to bytes"),
+        }
+        Ok(())
+    }
+}
+
+/// Splits a Protobuf record into the indexes of its message type, written as
+/// zig-zag encoded varints prefixed by their count, and the message. A zero
+/// count stands for the first message type of the schema.
+fn split_message_indexes(mut record: &[u8]) -> Result<(Vec<usize>, &[u8]), SchemaRegistryError> {
+    let mut next = || {
+        prost::encoding::decode_varint(&mut record)
+            .ok()
+            .and_then(|value| usize::try_from((value >> 1) as i64 ^ -((value & 1) as i64)).ok())
+            .ok_or_else(|| SchemaRegistryError::Protobuf {
+                reason: "invalid message indexes".to_owned(),
+            })
+    };
+
+    let indexes = match next()? {
+        0 => vec![0],
+        count => (0..count).map(|_| next()).collect::<Result<Vec<_>, _>>()?,
+    };
+    Ok((indexes, record))
+}
+
+/// Splits a payload in the registry wire format into schema ID and record.
+pub fn split_wire_format(payload: &[u8]) -> Option<(u32, &[u8])> {
+    match payload {
+        [MAGIC_BYTE, id @ ..] if id.len() >= 4 => {
+            let (id, record) = id.split_at(4);
+            Some((u32::from_be_bytes(id.try_into().ok()?), record))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaResponse {
+    schema: String,
+    #[serde(default)]
+    schema_type: SchemaType,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubjectVersionResponse {
+    id: u32,
+    schema: String,
+    #[serde(default)]
+    schema_type: SchemaType,
+}
+
+#[derive(Deserialize)]
+struct RegisterResponse {
+    id: u32,
+}
+
+/// A caching schema registry client.
+#[derive(Clone, Debug)]
+pub struct SchemaRegistryClient {
+    client: HttpClient,
+    url: String,
+    auth: Option<Auth>,
+    cache_ttl: Duration,
+    by_id: Arc<RwLock<HashMap<u32, Arc<Schema>>>>,
+    by_subject: Arc<RwLock<HashMap<String, (Instant, Arc<Schema>)>>>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(config: &SchemaRegistryConfig, proxy: &ProxyConfig) -> crate::Result<Self> {
+        config
+            .url
+            .parse::<Uri>()
+            .context(InvalidUrl { url: &config.url })?;
+        let tls = TlsSettings::from_options(&config.tls)?;
+        let client = HttpClient::new(tls, proxy)?;
+
+        Ok(Self {
+            client,
+            url: config.url.trim_end_matches('/').to_owned(),
+            auth: config.auth.clone(),
+            cache_ttl: Duration::from_secs(config.cache_ttl_secs),
+            by_id: Default::default(),
+            by_subject: Default::default(),
+        })
+    }
+
+    /// Returns the schema with the given ID.
+    pub async fn schema_by_id(&self, id: u32) -> Result<Arc<Schema>, SchemaRegistryError> {
+        let cached = self.by_id.read().unwrap().get(&id).cloned();
+        if let Some(schema) = cached {
+            return Ok(schema);
+        }
+
+        let response: SchemaResponse = self.request(format!("/schemas/ids/{}", id), None).await?;
+        self.cache_schema(id, response.schema_type, &response.schema)
+            .await
+    }
+
+    /// Returns the latest version of the schema registered under `subject`.
+    pub async fn latest(&self, subject: &str) -> Result<Arc<Schema>, SchemaRegistryError> {
+        let cached = self.by_subject.read().unwrap().get(subject).cloned();
+        if let Some((fetched, schema)) = cached {
+            if fetched.elapsed() < self.cache_ttl {
+                return Ok(schema);
+            }
+        }
+
+        let path = format!(
+            "/subjects/{}/versions/latest",
+            utf8_percent_encode(subject, NON_ALPHANUMERIC)
+        );
+        let response: SubjectVersionResponse = self.request(path, None).await?;
+        let schema = self
+            .cache_schema(response.id, response.schema_type, &response.schema)
+            .await?;
+        self.by_subject
+            .write()
+            .unwrap()
+            .insert(subject.to_owned(), (Instant::now(), Arc::clone(&schema)));
+        Ok(schema)
+    }
+
+    /// Registers `schema` under `subject`. Registering a schema which already
+    /// exists returns its current ID.
+    pub async fn register(
+        &self,
+        subject: &str,
+        schema_type: SchemaType,
+        schema: &str,
+    ) -> Result<Arc<Schema>, SchemaRegistryError> {
+        let mut body = serde_json::json!({ "schema": schema });
+        // Older registries only know Avro and reject the field.
+        if schema_type != SchemaType::Avro {
+            body["schemaType"] = schema_type.as_str().into();
+        }
+        let path = format!(
+            "/subjects/{}/versions",
+            utf8_percent_encode(subject, NON_ALPHANUMERIC)
+        );
+        let response: RegisterResponse = self.request(path, Some(body.to_string())).await?;
+        self.cache_schema(response.id, schema_type, schema).await
+    }
+
+    /// Decodes a payload in the registry wire format, fetching its schema if
+    /// it isn't cached yet.
+    pub async fn decode(&self, payload: &[u8]) -> Result<Value, SchemaRegistryError> {
+        let (id, record) =
+            split_wire_format(payload).ok_or(SchemaRegistryError::MissingSchemaId)?;
+        self.schema_by_id(id).await?.decode(record)
+    }
+
+    async fn cache_schema(
+        &self,
+        id: u32,
+        schema_type: SchemaType,
+        raw: &str,
+    ) -> Result<Arc<Schema>, SchemaRegistryError> {
+        let schema = match schema_type {
+            // Protobuf schemas are served as `.proto` files, which only the
+            // registry parses, so their compiled descriptor is fetched instead.
+            SchemaType::Protobuf => {
+                let path = format!("/schemas/ids/{}?format=serialized", id);
+                let response: SchemaResponse = self.request(path, None).await?;
+                Schema::new(id, schema_type, &response.schema)?
+            }
+            _ => Schema::new(id, schema_type, raw)?,
+        };
+        let schema = Arc::new(schema);
+        self.by_id.write().unwrap().insert(id, Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    async fn request<T: serde::de::DeserializeOwned>(
+        &self,
+        path: String,
+        body: Option<String>,
+    ) -> Result<T, SchemaRegistryError> {
+        let builder = Request::builder()
+            .uri(format!("{}{}", self.url, path))
+            .header(header::ACCEPT, CONTENT_TYPE);
+        let mut request = match body {
+            Some(body) => builder
+                .method(http::Method::POST)
+                .header(header::CONTENT_TYPE, CONTENT_TYPE)
+                .body(Body::from(body)),
+            None => builder.method(http::Method::GET).body(Body::empty()),
+        }
+        .context(BuildRequest)?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await.context(Request)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadResponse)?;
+        if !status.is_success() {
+            return Err(SchemaRegistryError::UnexpectedStatus {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+        serde_json::from_slice(&body).context(InvalidResponse)
+    }
+}
+
+fn avro_to_value(value: avro_rs::types::Value) -> Value {
+    use avro_rs::types::Value as Avro;
+
+    match value {
+        Avro::Null => Value::Null,
+        Avro::Boolean(value) => Value::Boolean(value),
+        Avro::Int(value) | Avro::Date(value) | Avro::TimeMillis(value) => {
+            Value::Integer(value as i64)
+        }
+        Avro::Long(value) | Avro::TimeMicros(value) => Value::Integer(value),
+        Avro::Float(value) => Value::Float(value as f64),
+        Avro::Double(value) => Value::Float(value),
+        Avro::Bytes(value) | Avro::Fixed(_, value) => Value::Bytes(Bytes::from(value)),
+        Avro::String(value) | Avro::Enum(_, value) => Value::Bytes(Bytes::from(value)),
+        Avro::Union(value) => avro_to_value(*value),
+        Avro::Array(values) => Value::Array(values.into_iter().map(avro_to_value).collect()),
+        Avro::Map(values) => Value::Map(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, avro_to_value(value)))
+                .collect::<BTreeMap<_, _>>(),
+        ),
+        Avro::Record(fields) => Value::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, avro_to_value(value)))
+                .collect::<BTreeMap<_, _>>(),
+        ),
+        Avro::TimestampMillis(millis) => Value::Timestamp(Utc.timestamp_millis(millis)),
+        Avro::TimestampMicros(micros) => Value::Timestamp(Utc.timestamp_nanos(micros * 1000)),
+        Avro::Uuid(uuid) => Value::Bytes(Bytes::from(uuid.to_string())),
+        // Decimals and durations have no native counterpart.
+        value => Value::Bytes(Bytes::from(format!("{:?}", value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AVRO_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "login",
+        "fields": [
+            {"name": "user", "type": "string"},
+            {"name": "attempts", "type": "long"},
+            {"name": "admin", "type": ["null", "boolean"], "default": null}
+        ]
+    }"#;
+
+    #[test]
+    fn splits_wire_format() {
+        assert_eq!(
+            split_wire_format(&[0, 0, 0, 1, 2, 42]),
+            Some((258, &[42][..]))
+        );
+        assert_eq!(split_wire_format(&[0, 0, 0, 1, 2]), Some((258, &[][..])));
+        assert_eq!(split_wire_format(&[0, 0, 1]), None);
+        assert_eq!(split_wire_format(&[1, 0, 0, 0, 1, 42]), None);
+    }
+
+    #[test]
+    fn avro_round_trip() {
+        let schema = Schema::new(7, SchemaType::Avro, AVRO_SCHEMA).unwrap();
+
+        let mut log = LogEvent::default();
+        log.insert("user", "alice");
+        log.insert("attempts", 3);
+        log.insert("admin", true);
+        let encoded = schema.encode(&log).unwrap();
+        assert_eq!(&encoded[..5], &[0, 0, 0, 0, 7]);
+
+        let (id, record) = split_wire_format(&encoded).unwrap();
+        assert_eq!(id, 7);
+        let decoded = schema.decode(record).unwrap();
+        assert_eq!(
+            decoded,
+            Value::Map(
+                vec![
+                    ("user".to_owned(), Value::from("alice")),
+                    ("attempts".to_owned(), Value::Integer(3)),
+                    ("admin".to_owned(), Value::Boolean(true)),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn avro_encode_rejects_mismatched_events() {
+        let schema = Schema::new(7, SchemaType::Avro, AVRO_SCHEMA).unwrap();
+
+        let mut log = LogEvent::default();
+        log.insert("user", "alice");
+        assert!(schema.encode(&log).is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let schema = Schema::new(3, SchemaType::Json, r#"{"type": "object"}"#).unwrap();
+
+        let mut log = LogEvent::default();
+        log.insert("user", "alice");
+        let encoded = schema.encode(&log).unwrap();
+        assert_eq!(&encoded[..], &b"\0\0\0\0\x03{\"user\":\"alice\"}"[..]);
+
+        let (_, record) = split_wire_format(&encoded).unwrap();
+        assert_eq!(
+            schema.decode(record).unwrap(),
+            Value::Map(
+                vec![("user".to_owned(), Value::from("alice"))]
+                    .into_iter()
+                    .collect()
+            )
+        );
+    }
+
+    /// The `serialized` format of:
+    ///
+    /// ```protobuf
+    /// syntax = "proto3";
+    /// package test;
+    /// message Login {
+    ///   string user = 1;
+    ///   int64 attempts = 2;
+    ///   message Device { string name = 1; }
+    /// }
+    /// message Logout { string user = 1; }
+    /// ```
+    fn protobuf_schema() -> String {
+        use prost_types::{
+            field_descriptor_proto::{Label, Type},
+            FieldDescriptorProto,
+        };
+
+        let field = |name: &str, number, kind: Type| FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(kind as i32),
+            ..Default::default()
+        };
+        let message = |name: &str, field, nested_type| DescriptorProto {
+            name: Some(name.to_owned()),
+            field,
+            nested_type,
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("test.proto".to_owned()),
+            package: Some("test".to_owned()),
+            syntax: Some("proto3".to_owned()),
+            message_type: vec![
+                message(
+                    "Login",
+                    vec![
+                        field("user", 1, Type::String),
+                        field("attempts", 2, Type::Int64),
+                    ],
+                    vec![message(
+                        "Device",
+                        vec![field("name", 1, Type::String)],
+                        vec![],
+                    )],
+                ),
+                message("Logout", vec![field("user", 1, Type::String)], vec![]),
+            ],
+            ..Default::default()
+        };
+
+        let mut bytes = Vec::new();
+        file.encode(&mut bytes).unwrap();
+        base64::encode(bytes)
+    }
+
+    #[test]
+    fn protobuf_round_trip() {
+        let schema = Schema::new(5, SchemaType::Protobuf, &protobuf_schema()).unwrap();
+
+        let mut log = LogEvent::default();
+        log.insert("user", "alice");
+        log.insert("attempts", 3);
+        let encoded = schema.encode(&log).unwrap();
+        assert_eq!(&encoded[..], &b"\0\0\0\0\x05\0\x0a\x05alice\x10\x03"[..]);
+
+        let (id, record) = split_wire_format(&encoded).unwrap();
+        assert_eq!(id, 5);
+        assert_eq!(
+            schema.decode(record).unwrap(),
+            Value::Map(
+                vec![
+                    ("user".to_owned(), Value::from("alice")),
+                    ("attempts".to_owned(), Value::Integer(3)),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn protobuf_message_indexes() {
+        let schema = Schema::new(5, SchemaType::Protobuf, &protobuf_schema()).unwrap();
+        let user = |name: &str| {
+            Value::Map(
+                vec![(name.to_owned(), Value::from("bob"))]
+                    .into_iter()
+                    .collect(),
+            )
+        };
+
+        // `Logout`, the second top-level message.
+        assert_eq!(schema.decode(b"\x02\x02\x0a\x03bob").unwrap(), user("user"));
+        // `Login.Device`, the first message nested in the first one.
+        assert_eq!(schema.decode(b"\x04\0\0\x0a\x03bob").unwrap(), user("name"));
+        assert!(matches!(
+            schema.decode(b"\x02\x04\x0a\x03bob"),
+            Err(SchemaRegistryError::UnknownMessageIndexes { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_schema_type() {
+        let response: SchemaResponse =
+            serde_json::from_str(r#"{"schema": "{}", "schemaType": "JSON"}"#).unwrap();
+        assert_eq!(response.schema_type, SchemaType::Json);
+        let response: SchemaResponse = serde_json::from_str(r#"{"schema": "{}"}"#).unwrap();
+        assert_eq!(response.schema_type, SchemaType::Avro);
+    }
+}
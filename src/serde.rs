@@ -1,7 +1,7 @@
 #[cfg(feature = "codecs")]
 use crate::codecs::{
     BytesDecoderConfig, BytesParserConfig, FramingConfig, NewlineDelimitedDecoderConfig,
-    ParserConfig,
+    ParserSettings,
 };
 use indexmap::map::IndexMap;
 use serde::{de, Deserialize, Serialize};
@@ -35,8 +35,8 @@ pub fn default_framing_stream_based() -> Box<dyn FramingConfig> {
 }
 
 #[cfg(feature = "codecs")]
-pub fn default_decoding() -> Box<dyn ParserConfig> {
-    Box::new(BytesParserConfig::new())
+pub fn default_decoding() -> ParserSettings {
+    BytesParserConfig::new().into()
 }
 
 pub fn to_string(value: impl serde::Serialize) -> String {
@@ -1,4 +1,8 @@
+#[cfg(feature = "api")]
+use super::config::Config;
 use super::config::ConfigBuilder;
+#[cfg(feature = "api")]
+use tokio::sync::oneshot;
 use tokio::sync::{broadcast, mpsc};
 use tokio_stream::{Stream, StreamExt};
 
@@ -13,6 +17,10 @@ pub enum SignalTo {
     ReloadFromConfigBuilder(ConfigBuilder),
     /// Signal to reload config from the filesystem.
     ReloadFromDisk,
+    /// Signal to reload a config applied through the API, replying with the
+    /// outcome of `RunningTopology::reload_config_and_respawn`.
+    #[cfg(feature = "api")]
+    ReloadFromApi(Config, oneshot::Sender<Result<bool, ()>>),
     /// Signal to shutdown process.
     Shutdown,
     /// Shutdown process immediately.
@@ -158,12 +158,16 @@ pub enum CloudwatchError {
 
 impl CloudwatchLogsSinkConfig {
     fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<CloudWatchLogsClient> {
-        let region = (&self.region).try_into()?;
+        let region = self.region.region_for_service("logs")?;
 
         let client = rusoto::client(proxy)?;
-        let creds = self.auth.build(&region, self.assume_role.clone())?;
+        let creds = self.auth.build(
+            &self.region.region_for_service("sts")?,
+            self.assume_role.clone(),
+        )?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(CloudWatchLogsClient::new_with_client(client, region))
     }
 }
@@ -15,8 +15,11 @@ use crate::{
 };
 use chrono::{DateTime, SecondsFormat, Utc};
 use futures::{future, future::BoxFuture, stream, FutureExt, SinkExt};
+use glob::Pattern;
+use indexmap::IndexMap;
 use rusoto_cloudwatch::{
     CloudWatch, CloudWatchClient, Dimension, MetricDatum, PutMetricDataError, PutMetricDataInput,
+    StatisticSet,
 };
 use rusoto_core::{Region, RusotoError};
 use serde::{Deserialize, Serialize};
@@ -28,10 +31,14 @@ use std::{
 use tower::Service;
 use vector_core::ByteSizeOf;
 
+/// The most dimensions CloudWatch accepts per metric.
+const MAX_DIMENSIONS: usize = 30;
+
 #[derive(Clone)]
 pub struct CloudWatchMetricsSvc {
     client: CloudWatchClient,
     config: CloudWatchMetricsSinkConfig,
+    high_resolution_metrics: Vec<Pattern>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -51,6 +58,17 @@ pub struct CloudWatchMetricsSinkConfig {
     assume_role: Option<String>,
     #[serde(default)]
     pub auth: AwsAuthentication,
+    /// Patterns of the names of metrics stored at a one second resolution,
+    /// rather than the standard one minute.
+    #[serde(default)]
+    pub high_resolution_metrics: Vec<String>,
+    /// The tags sent as dimensions. All tags are sent when unset.
+    #[serde(default)]
+    pub dimensions: Option<Vec<String>>,
+    /// Whether to aggregate the values of each metric in a batch into a
+    /// single statistic set.
+    #[serde(default)]
+    pub statistic_sets: bool,
 }
 
 inventory::submit! {
@@ -97,7 +115,7 @@ impl CloudWatchMetricsSinkConfig {
     }
 
     fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<CloudWatchClient> {
-        let region = (&self.region).try_into()?;
+        let region = self.region.region_for_service("monitoring")?;
         let region = if cfg!(test) {
             // Moto (used for mocking AWS) doesn't recognize 'custom' as valid region name
             match region {
@@ -112,9 +130,13 @@ impl CloudWatchMetricsSinkConfig {
         };
 
         let client = rusoto::client(proxy)?;
-        let creds = self.auth.build(&region, self.assume_role.clone())?;
+        let creds = self.auth.build(
+            &self.region.region_for_service("sts")?,
+            self.assume_role.clone(),
+        )?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(CloudWatchClient::new_with_client(client, region))
     }
 }
@@ -136,7 +158,7 @@ impl CloudWatchMetricsSvc {
             ..Default::default()
         });
 
-        let cloudwatch_metrics = CloudWatchMetricsSvc { client, config };
+        let cloudwatch_metrics = CloudWatchMetricsSvc::from_config(config, client)?;
 
         let svc = request.service(CloudWatchMetricsRetryLogic, cloudwatch_metrics);
 
@@ -164,51 +186,178 @@ impl CloudWatchMetricsSvc {
         Ok(super::VectorSink::Sink(Box::new(sink)))
     }
 
+    fn from_config(
+        config: CloudWatchMetricsSinkConfig,
+        client: CloudWatchClient,
+    ) -> crate::Result<Self> {
+        let high_resolution_metrics = config
+            .high_resolution_metrics
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern).map_err(|error| {
+                    format!(
+                        "Invalid pattern {:?} in `high_resolution_metrics`: {}",
+                        pattern, error
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            client,
+            config,
+            high_resolution_metrics,
+        })
+    }
+
     fn encode_events(&mut self, events: Vec<Metric>) -> Vec<MetricDatum> {
-        events
-            .into_iter()
-            .filter_map(|event| {
-                let metric_name = event.name().to_string();
-                let timestamp = event.timestamp().map(timestamp_to_string);
-                let dimensions = event.tags().map(tags_to_dimensions);
-                // AwsCloudwatchMetricNormalize converts these to the right MetricKind
-                match event.value() {
-                    MetricValue::Counter { value } => Some(MetricDatum {
-                        metric_name,
-                        value: Some(*value),
-                        timestamp,
-                        dimensions,
-                        ..Default::default()
-                    }),
-                    MetricValue::Distribution {
-                        samples,
-                        statistic: _,
-                    } => Some(MetricDatum {
-                        metric_name,
-                        values: Some(samples.iter().map(|s| s.value).collect()),
-                        counts: Some(samples.iter().map(|s| f64::from(s.rate)).collect()),
-                        timestamp,
-                        dimensions,
-                        ..Default::default()
-                    }),
-                    MetricValue::Set { values } => Some(MetricDatum {
-                        metric_name,
-                        value: Some(values.len() as f64),
-                        timestamp,
-                        dimensions,
-                        ..Default::default()
-                    }),
-                    MetricValue::Gauge { value } => Some(MetricDatum {
-                        metric_name,
-                        value: Some(*value),
-                        timestamp,
-                        dimensions,
-                        ..Default::default()
-                    }),
-                    _ => None,
+        let data = events.into_iter().filter_map(|event| {
+            let mut datum = self.encode_event(event)?;
+            if self
+                .high_resolution_metrics
+                .iter()
+                .any(|pattern| pattern.matches(&datum.metric_name))
+            {
+                datum.storage_resolution = Some(1);
+            }
+            Some(datum)
+        });
+
+        if self.config.statistic_sets {
+            aggregate_statistic_sets(data)
+        } else {
+            data.collect()
+        }
+    }
+
+    fn encode_event(&self, event: Metric) -> Option<MetricDatum> {
+        let metric_name = event.name().to_string();
+        let timestamp = event.timestamp().map(timestamp_to_string);
+        let dimensions = event
+            .tags()
+            .map(|tags| tags_to_dimensions(tags, self.config.dimensions.as_deref()))
+            .filter(|dimensions| !dimensions.is_empty());
+        // AwsCloudwatchMetricNormalize converts these to the right MetricKind
+        match event.value() {
+            MetricValue::Counter { value } => Some(MetricDatum {
+                metric_name,
+                value: Some(*value),
+                timestamp,
+                dimensions,
+                ..Default::default()
+            }),
+            MetricValue::Distribution {
+                samples,
+                statistic: _,
+            } => Some(MetricDatum {
+                metric_name,
+                values: Some(samples.iter().map(|s| s.value).collect()),
+                counts: Some(samples.iter().map(|s| f64::from(s.rate)).collect()),
+                timestamp,
+                dimensions,
+                ..Default::default()
+            }),
+            MetricValue::Set { values } => Some(MetricDatum {
+                metric_name,
+                value: Some(values.len() as f64),
+                timestamp,
+                dimensions,
+                ..Default::default()
+            }),
+            MetricValue::Gauge { value } => Some(MetricDatum {
+                metric_name,
+                value: Some(*value),
+                timestamp,
+                dimensions,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Aggregates the values of the data of each metric, as identified by its
+/// name, dimensions and resolution, into a single statistic set, the latest
+/// timestamp of which it keeps.
+fn aggregate_statistic_sets(data: impl Iterator<Item = MetricDatum>) -> Vec<MetricDatum> {
+    let mut aggregated = IndexMap::<_, MetricDatum>::new();
+    for mut datum in data {
+        let statistics = match statistic_set(&datum) {
+            Some(statistics) => statistics,
+            None => continue,
+        };
+        let dimensions = datum
+            .dimensions
+            .iter()
+            .flatten()
+            .map(|dimension| (dimension.name.clone(), dimension.value.clone()))
+            .collect::<Vec<_>>();
+        let key = (
+            datum.metric_name.clone(),
+            dimensions,
+            datum.storage_resolution,
+        );
+
+        match aggregated.get_mut(&key) {
+            Some(existing) => {
+                let merged = existing
+                    .statistic_values
+                    .as_mut()
+                    .expect("aggregated data hold statistic sets");
+                merged.sample_count += statistics.sample_count;
+                merged.sum += statistics.sum;
+                merged.minimum = merged.minimum.min(statistics.minimum);
+                merged.maximum = merged.maximum.max(statistics.maximum);
+                if datum.timestamp > existing.timestamp {
+                    existing.timestamp = datum.timestamp;
                 }
+            }
+            None => {
+                datum.value = None;
+                datum.values = None;
+                datum.counts = None;
+                datum.statistic_values = Some(statistics);
+                aggregated.insert(key, datum);
+            }
+        }
+    }
+
+    aggregated.into_iter().map(|(_, datum)| datum).collect()
+}
+
+/// The statistic set of the single value, or the values and their counts, of
+/// a datum.
+fn statistic_set(datum: &MetricDatum) -> Option<StatisticSet> {
+    match (&datum.value, &datum.values, &datum.counts) {
+        (Some(value), _, _) => Some(StatisticSet {
+            sample_count: 1.0,
+            sum: *value,
+            minimum: *value,
+            maximum: *value,
+        }),
+        (None, Some(values), Some(counts)) => {
+            let samples = values
+                .iter()
+                .zip(counts)
+                .filter(|(_, count)| **count > 0.0)
+                .collect::<Vec<_>>();
+            if samples.is_empty() {
+                return None;
+            }
+            Some(StatisticSet {
+                sample_count: samples.iter().map(|(_, count)| **count).sum(),
+                sum: samples.iter().map(|(value, count)| **value * **count).sum(),
+                minimum: samples
+                    .iter()
+                    .map(|(value, _)| **value)
+                    .fold(f64::INFINITY, f64::min),
+                maximum: samples
+                    .iter()
+                    .map(|(value, _)| **value)
+                    .fold(f64::NEG_INFINITY, f64::max),
             })
-            .collect()
+        }
+        _ => None,
     }
 }
 
@@ -269,10 +418,13 @@ fn timestamp_to_string(timestamp: DateTime<Utc>) -> String {
     timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
 }
 
-fn tags_to_dimensions(tags: &BTreeMap<String, String>) -> Vec<Dimension> {
-    // according to the API, up to 10 dimensions per metric can be provided
+fn tags_to_dimensions(
+    tags: &BTreeMap<String, String>,
+    allowed: Option<&[String]>,
+) -> Vec<Dimension> {
     tags.iter()
-        .take(10)
+        .filter(|(k, _)| allowed.map_or(true, |allowed| allowed.contains(k)))
+        .take(MAX_DIMENSIONS)
         .map(|(k, v)| Dimension {
             name: k.to_string(),
             value: v.to_string(),
@@ -300,10 +452,13 @@ mod tests {
         }
     }
 
-    fn svc() -> CloudWatchMetricsSvc {
-        let config = config();
+    fn svc_with(config: CloudWatchMetricsSinkConfig) -> CloudWatchMetricsSvc {
         let client = config.create_client(&ProxyConfig::from_env()).unwrap();
-        CloudWatchMetricsSvc { client, config }
+        CloudWatchMetricsSvc::from_config(config, client).unwrap()
+    }
+
+    fn svc() -> CloudWatchMetricsSvc {
+        svc_with(config())
     }
 
     #[test]
@@ -424,6 +579,176 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn encode_events_high_resolution() {
+        let events = vec![
+            Metric::new(
+                "requests_total",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            ),
+            Metric::new(
+                "temperature",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 10.0 },
+            ),
+        ];
+
+        let mut svc = svc_with(CloudWatchMetricsSinkConfig {
+            high_resolution_metrics: vec!["requests_*".into()],
+            ..config()
+        });
+        assert_eq!(
+            svc.encode_events(events),
+            vec![
+                MetricDatum {
+                    metric_name: "requests_total".into(),
+                    value: Some(1.0),
+                    storage_resolution: Some(1),
+                    ..Default::default()
+                },
+                MetricDatum {
+                    metric_name: "temperature".into(),
+                    value: Some(10.0),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_high_resolution_pattern() {
+        let config = CloudWatchMetricsSinkConfig {
+            high_resolution_metrics: vec!["[".into()],
+            ..config()
+        };
+        let client = config.create_client(&ProxyConfig::from_env()).unwrap();
+        assert!(CloudWatchMetricsSvc::from_config(config, client).is_err());
+    }
+
+    #[test]
+    fn encode_events_allowed_dimensions() {
+        let events = vec![
+            Metric::new(
+                "healthcheck",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(
+                vec![
+                    ("region".to_owned(), "local".to_owned()),
+                    ("host".to_owned(), "localhost".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            )),
+            Metric::new(
+                "uptime",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 10.0 },
+            )
+            .with_tags(Some(
+                vec![("host".to_owned(), "localhost".to_owned())]
+                    .into_iter()
+                    .collect(),
+            )),
+        ];
+
+        let mut svc = svc_with(CloudWatchMetricsSinkConfig {
+            dimensions: Some(vec!["region".into()]),
+            ..config()
+        });
+        assert_eq!(
+            svc.encode_events(events),
+            vec![
+                MetricDatum {
+                    metric_name: "healthcheck".into(),
+                    value: Some(1.0),
+                    dimensions: Some(vec![Dimension {
+                        name: "region".into(),
+                        value: "local".into()
+                    }]),
+                    ..Default::default()
+                },
+                MetricDatum {
+                    metric_name: "uptime".into(),
+                    value: Some(10.0),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn limits_dimensions() {
+        let tags = (0..40)
+            .map(|i| (format!("tag{:02}", i), i.to_string()))
+            .collect();
+
+        assert_eq!(tags_to_dimensions(&tags, None).len(), MAX_DIMENSIONS);
+    }
+
+    #[test]
+    fn encode_events_statistic_sets() {
+        let timestamp = |second| Some(Utc.ymd(2018, 11, 14).and_hms(8, 9, second));
+        let events = vec![
+            Metric::new(
+                "latency",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: vector_core::samples![11.0 => 2, 15.0 => 1],
+                    statistic: StatisticKind::Histogram,
+                },
+            )
+            .with_timestamp(timestamp(10)),
+            Metric::new(
+                "latency",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: vector_core::samples![8.0 => 1],
+                    statistic: StatisticKind::Histogram,
+                },
+            )
+            .with_timestamp(timestamp(20)),
+            Metric::new(
+                "exception_total",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            ),
+        ];
+
+        let mut svc = svc_with(CloudWatchMetricsSinkConfig {
+            statistic_sets: true,
+            ..config()
+        });
+        assert_eq!(
+            svc.encode_events(events),
+            vec![
+                MetricDatum {
+                    metric_name: "latency".into(),
+                    statistic_values: Some(StatisticSet {
+                        sample_count: 4.0,
+                        sum: 45.0,
+                        minimum: 8.0,
+                        maximum: 15.0,
+                    }),
+                    timestamp: Some("2018-11-14T08:09:20.000Z".into()),
+                    ..Default::default()
+                },
+                MetricDatum {
+                    metric_name: "exception_total".into(),
+                    statistic_values: Some(StatisticSet {
+                        sample_count: 1.0,
+                        sum: 1.0,
+                        minimum: 1.0,
+                        maximum: 1.0,
+                    }),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
 }
 
 #[cfg(feature = "aws-cloudwatch-metrics-integration-tests")]
@@ -140,12 +140,16 @@ impl KinesisFirehoseSinkConfig {
     }
 
     fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<KinesisFirehoseClient> {
-        let region = (&self.region).try_into()?;
+        let region = self.region.region_for_service("firehose")?;
 
         let client = rusoto::client(proxy)?;
-        let creds = self.auth.build(&region, self.assume_role.clone())?;
+        let creds = self.auth.build(
+            &self.region.region_for_service("sts")?,
+            self.assume_role.clone(),
+        )?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(KinesisFirehoseClient::new_with_client(client, region))
     }
 }
@@ -13,8 +13,9 @@ use crate::{
             service::S3Service,
         },
         util::{
-            encoding::EncodingConfig, BatchConfig, Compression, ServiceBuilderExt,
-            TowerRequestConfig,
+            encoding::EncodingConfig,
+            parquet::{ParquetConfig, PARQUET_CONTENT_TYPE},
+            BatchConfig, Compression, ServiceBuilderExt, TowerRequestConfig,
         },
         Healthcheck,
     },
@@ -50,6 +51,11 @@ pub struct S3SinkConfig {
     pub encoding: EncodingConfig<StandardEncodings>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
+    /// Writes the batches as Parquet files instead of encoding each event.
+    /// The files' columns are compressed by the Parquet encoder, so
+    /// `compression` doesn't apply to them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet: Option<ParquetConfig>,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -72,6 +78,7 @@ impl GenerateConfig for S3SinkConfig {
             region: RegionOrEndpoint::default(),
             encoding: StandardEncodings::Text.into(),
             compression: Compression::gzip_default(),
+            parquet: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
             assume_role: None,
@@ -137,14 +144,25 @@ impl S3SinkConfig {
             .filename_append_uuid
             .unwrap_or(DEFAULT_FILENAME_APPEND_UUID);
 
+        let parquet = self
+            .parquet
+            .as_ref()
+            .map(ParquetConfig::build_encoder)
+            .transpose()?;
+        let mut api_options = self.options.clone();
+        if parquet.is_some() && api_options.content_type.is_none() {
+            api_options.content_type = Some(PARQUET_CONTENT_TYPE.into());
+        }
+
         let request_options = S3RequestOptions {
             bucket: self.bucket.clone(),
-            api_options: self.options.clone(),
+            api_options,
             filename_extension: self.filename_extension.clone(),
             filename_time_format,
             filename_append_uuid,
             encoding: self.encoding.clone(),
             compression: self.compression,
+            parquet,
         };
 
         let sink = S3Sink::new(cx, service, request_options, partitioner, batch_settings);
@@ -5,8 +5,9 @@ use crate::{
     sinks::{
         s3_common::{config::S3Options, service::S3Metadata, service::S3Request},
         util::{
-            encoding::{EncodingConfig, StandardEncodings},
-            Compression, RequestBuilder,
+            encoding::{Encoder, EncodingConfig, EncodingConfiguration, StandardEncodings},
+            parquet::ParquetEncoder,
+            Compression, Compressor, RequestBuilder,
         },
     },
 };
@@ -24,6 +25,7 @@ pub struct S3RequestOptions {
     pub api_options: S3Options,
     pub encoding: EncodingConfig<StandardEncodings>,
     pub compression: Compression,
+    pub parquet: Option<ParquetEncoder>,
 }
 
 impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
@@ -35,7 +37,12 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
     type Error = io::Error; // TODO: this is ugly.
 
     fn compression(&self) -> Compression {
-        self.compression
+        // Parquet files compress their columns themselves.
+        if self.parquet.is_some() {
+            Compression::None
+        } else {
+            self.compression
+        }
     }
 
     fn encoder(&self) -> &Self::Encoder {
@@ -55,6 +62,29 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
         (metadata, events)
     }
 
+    fn encode_events(&self, events: Self::Events) -> Result<Self::Payload, Self::Error> {
+        match &self.parquet {
+            Some(parquet) => {
+                let logs = events
+                    .into_iter()
+                    .map(|mut event| {
+                        self.encoding.apply_rules(&mut event);
+                        event.into_log()
+                    })
+                    .collect::<Vec<_>>();
+                parquet
+                    .encode(&logs)
+                    .map(Bytes::from)
+                    .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+            }
+            None => {
+                let mut compressor = Compressor::from(self.compression);
+                self.encoding.encode_input(events, &mut compressor)?;
+                Ok(compressor.into_inner().into())
+            }
+        }
+    }
+
     fn build_request(&self, mut metadata: Self::Metadata, payload: Self::Payload) -> Self::Request {
         let filename = {
             let formatted_ts = Utc::now().format(self.filename_time_format.as_str());
@@ -68,7 +98,13 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
             .filename_extension
             .as_ref()
             .cloned()
-            .unwrap_or_else(|| self.compression.extension().into());
+            .unwrap_or_else(|| {
+                if self.parquet.is_some() {
+                    "parquet".into()
+                } else {
+                    self.compression.extension().into()
+                }
+            });
         metadata.partition_key = format!("{}/{}.{}", metadata.partition_key, filename, extension);
 
         // TODO: move this into `.request_builder(...)` closure?
@@ -84,7 +120,7 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
             body: payload,
             bucket: self.bucket.clone(),
             metadata,
-            content_encoding: self.compression.content_encoding(),
+            content_encoding: self.compression().content_encoding(),
             options: self.api_options.clone(),
         }
     }
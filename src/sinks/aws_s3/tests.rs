@@ -274,6 +274,7 @@ mod integration_tests {
             region: RegionOrEndpoint::with_endpoint("http://localhost:4566".to_owned()),
             encoding: StandardEncodings::Text.into(),
             compression: Compression::None,
+            parquet: None,
             batch: BatchConfig {
                 max_events: Some(batch_size),
                 timeout_secs: Some(5),
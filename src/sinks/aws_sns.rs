@@ -0,0 +1,565 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, ProxyConfig, SinkConfig, SinkContext, SinkDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        AwsSnsEventSent, AwsSnsMessageTooLarge, AwsSnsMessageTruncated, TemplateRenderingFailed,
+    },
+    rusoto::{self, AwsAuthentication, RegionOrEndpoint},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::RetryLogic,
+        sink::{self, Response},
+        BatchSettings, EncodedEvent, EncodedLength, TowerRequestConfig, VecBuffer,
+    },
+    template::{Template, TemplateParseError},
+};
+use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt, TryFutureExt};
+use indexmap::IndexMap;
+use rusoto_core::RusotoError;
+use rusoto_sns::{
+    GetTopicAttributesError, GetTopicAttributesInput, MessageAttributeValue, PublishError,
+    PublishInput, PublishResponse, Sns, SnsClient,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    task::{Context, Poll},
+};
+use tower::Service;
+use tracing_futures::Instrument;
+use vector_core::ByteSizeOf;
+
+/// The largest payload SNS accepts, counting the message and its attributes.
+const MAX_PAYLOAD_BYTES: usize = 262_144;
+/// The most message attributes SNS accepts per message.
+const MAX_MESSAGE_ATTRIBUTES: usize = 10;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`message_group_id` should be defined for FIFO topic."))]
+    MessageGroupIdMissing,
+    #[snafu(display("`message_group_id` is not allowed with non-FIFO topic."))]
+    MessageGroupIdNotAllowed,
+    #[snafu(display("invalid message_group_id template: {}", source))]
+    MessageGroupIdTemplate { source: TemplateParseError },
+    #[snafu(display("invalid message_deduplication_id template: {}", source))]
+    MessageDeduplicationIdTemplate { source: TemplateParseError },
+    #[snafu(display(
+        "Too many message attributes, SNS accepts at most {}",
+        MAX_MESSAGE_ATTRIBUTES
+    ))]
+    TooManyMessageAttributes,
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("GetTopicAttributes failed: {}", source))]
+    GetTopicAttributes {
+        source: RusotoError<GetTopicAttributesError>,
+    },
+}
+
+#[derive(Clone)]
+pub struct SnsSink {
+    client: SnsClient,
+    topic_arn: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnsSinkConfig {
+    pub topic_arn: String,
+    #[serde(flatten)]
+    pub region: RegionOrEndpoint,
+    pub encoding: EncodingConfig<Encoding>,
+    /// The message attributes to set, by name, from the fields of events.
+    #[serde(default)]
+    pub message_attributes: IndexMap<String, String>,
+    pub message_group_id: Option<String>,
+    pub message_deduplication_id: Option<String>,
+    #[serde(default)]
+    pub overflow: Overflow,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    #[serde(default)]
+    pub auth: AwsAuthentication,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+/// What to do with events larger than SNS accepts.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Overflow {
+    #[derivative(Default)]
+    Reject,
+    Truncate,
+}
+
+inventory::submit! {
+    SinkDescription::new::<SnsSinkConfig>("aws_sns")
+}
+
+impl GenerateConfig for SnsSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"topic_arn = "arn:aws:sns:us-east-2:123456789012:MyTopic"
+            region = "us-east-2"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_sns")]
+impl SinkConfig for SnsSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let client = self.create_client(&cx.proxy)?;
+        let healthcheck = self.clone().healthcheck(client.clone());
+        let sink = SnsSink::new(self.clone(), cx, client)?;
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck.boxed()))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "aws_sns"
+    }
+}
+
+impl SnsSinkConfig {
+    pub async fn healthcheck(self, client: SnsClient) -> crate::Result<()> {
+        client
+            .get_topic_attributes(GetTopicAttributesInput {
+                topic_arn: self.topic_arn.clone(),
+            })
+            .await
+            .map(|_| ())
+            .context(GetTopicAttributes)
+            .map_err(Into::into)
+    }
+
+    pub fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<SnsClient> {
+        let region = self.region.region_for_service("sns")?;
+        let client = rusoto::client(proxy)?;
+
+        let creds = self
+            .auth
+            .build(&self.region.region_for_service("sts")?, None)?;
+
+        Ok(SnsClient::new_with(client, creds, region))
+    }
+}
+
+/// How to encode each event into a message.
+#[derive(Debug, Clone)]
+struct MessageEncoder {
+    encoding: EncodingConfig<Encoding>,
+    message_attributes: IndexMap<String, String>,
+    message_group_id: Option<Template>,
+    message_deduplication_id: Option<Template>,
+    overflow: Overflow,
+}
+
+impl SnsSink {
+    pub fn new(
+        config: SnsSinkConfig,
+        cx: SinkContext,
+        client: SnsClient,
+    ) -> crate::Result<impl Sink<Event, Error = ()>> {
+        // SNS publishes one message per request.
+        let batch = BatchSettings::default().events(1).bytes(MAX_PAYLOAD_BYTES);
+
+        let request = config.request.unwrap_with(&TowerRequestConfig {
+            timeout_secs: Some(30),
+            ..Default::default()
+        });
+        let encoder = MessageEncoder::new(&config)?;
+
+        let sns = SnsSink {
+            client,
+            topic_arn: config.topic_arn,
+        };
+
+        let sink = request
+            .batch_sink(
+                SnsRetryLogic,
+                sns,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+                sink::StdServiceLogic::default(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal sns sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encoder.encode_event(event)).map(Ok));
+
+        Ok(sink)
+    }
+}
+
+impl Service<Vec<PublishEntry>> for SnsSink {
+    type Response = PublishResponse;
+    type Error = RusotoError<PublishError>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut entries: Vec<PublishEntry>) -> Self::Future {
+        assert_eq!(entries.len(), 1, "Sending batch is not supported.");
+
+        let entry = entries.remove(0);
+        let byte_size = entry.encoded_length();
+
+        let client = self.client.clone();
+        let request = PublishInput {
+            message: entry.message,
+            message_attributes: Some(entry.message_attributes).filter(|a| !a.is_empty()),
+            message_group_id: entry.message_group_id,
+            message_deduplication_id: entry.message_deduplication_id,
+            topic_arn: Some(self.topic_arn.clone()),
+            ..Default::default()
+        };
+
+        Box::pin(async move {
+            client
+                .publish(request)
+                .inspect_ok(|response| {
+                    emit!(&AwsSnsEventSent {
+                        byte_size,
+                        message_id: response.message_id.as_ref()
+                    })
+                })
+                .instrument(info_span!("request"))
+                .await
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PublishEntry {
+    message: String,
+    message_attributes: HashMap<String, MessageAttributeValue>,
+    message_group_id: Option<String>,
+    message_deduplication_id: Option<String>,
+}
+
+impl EncodedLength for PublishEntry {
+    fn encoded_length(&self) -> usize {
+        self.message.len() + attributes_size(&self.message_attributes)
+    }
+}
+
+impl Response for PublishResponse {}
+
+#[derive(Debug, Clone)]
+struct SnsRetryLogic;
+
+impl RetryLogic for SnsRetryLogic {
+    type Error = RusotoError<PublishError>;
+    type Response = PublishResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        rusoto::is_retriable_error(error)
+    }
+}
+
+impl MessageEncoder {
+    fn new(config: &SnsSinkConfig) -> crate::Result<Self> {
+        if config.message_attributes.len() > MAX_MESSAGE_ATTRIBUTES {
+            return Err(Box::new(BuildError::TooManyMessageAttributes));
+        }
+
+        let fifo = config.topic_arn.ends_with(".fifo");
+        let message_group_id = match (&config.message_group_id, fifo) {
+            (Some(value), true) => {
+                Some(Template::try_from(value.as_str()).context(MessageGroupIdTemplate)?)
+            }
+            (Some(_), false) => return Err(Box::new(BuildError::MessageGroupIdNotAllowed)),
+            (None, true) => return Err(Box::new(BuildError::MessageGroupIdMissing)),
+            (None, false) => None,
+        };
+        let message_deduplication_id = config
+            .message_deduplication_id
+            .as_deref()
+            .map(Template::try_from)
+            .transpose()
+            .context(MessageDeduplicationIdTemplate)?;
+
+        Ok(Self {
+            encoding: config.encoding.clone(),
+            message_attributes: config.message_attributes.clone(),
+            message_group_id,
+            message_deduplication_id,
+            overflow: config.overflow,
+        })
+    }
+
+    fn encode_event(&self, mut event: Event) -> Option<EncodedEvent<PublishEntry>> {
+        let byte_size = event.size_of();
+        self.encoding.apply_rules(&mut event);
+
+        let message_group_id = render(&self.message_group_id, &event, "message_group_id")?;
+        let message_deduplication_id = render(
+            &self.message_deduplication_id,
+            &event,
+            "message_deduplication_id",
+        )?;
+
+        let log = event.into_log();
+        let message_attributes = self.message_attributes(&log);
+        let mut message = match self.encoding.codec() {
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|v| v.to_string_lossy())
+                .unwrap_or_else(|| "".into()),
+            Encoding::Json => serde_json::to_string(&log).expect("Error encoding event as json."),
+        };
+
+        let size = message.len() + attributes_size(&message_attributes);
+        if size > MAX_PAYLOAD_BYTES {
+            let available = MAX_PAYLOAD_BYTES.saturating_sub(size - message.len());
+            if self.overflow == Overflow::Reject || available == 0 {
+                emit!(&AwsSnsMessageTooLarge {
+                    size,
+                    max_size: MAX_PAYLOAD_BYTES,
+                });
+                return None;
+            }
+
+            let mut end = available;
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            message.truncate(end);
+            emit!(&AwsSnsMessageTruncated {
+                size,
+                max_size: MAX_PAYLOAD_BYTES,
+            });
+        }
+
+        Some(EncodedEvent::new(
+            PublishEntry {
+                message,
+                message_attributes,
+                message_group_id,
+                message_deduplication_id,
+            },
+            byte_size,
+        ))
+    }
+
+    /// The message attributes set from the fields of the event, skipping those
+    /// which are missing, null or empty, as SNS rejects empty attributes.
+    fn message_attributes(&self, log: &LogEvent) -> HashMap<String, MessageAttributeValue> {
+        self.message_attributes
+            .iter()
+            .filter_map(|(name, field)| {
+                let (data_type, value) = match log.get(field.as_str())? {
+                    Value::Null => return None,
+                    value @ Value::Integer(_) | value @ Value::Float(_) => {
+                        ("Number", value.to_string_lossy())
+                    }
+                    value @ Value::Array(_) => ("String.Array", serde_json::to_string(value).ok()?),
+                    value @ Value::Map(_) => ("String", serde_json::to_string(value).ok()?),
+                    value => ("String", value.to_string_lossy()),
+                };
+                if value.is_empty() {
+                    return None;
+                }
+
+                Some((
+                    name.clone(),
+                    MessageAttributeValue {
+                        data_type: data_type.to_owned(),
+                        string_value: Some(value),
+                        binary_value: None,
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+fn render(
+    template: &Option<Template>,
+    event: &Event,
+    field: &'static str,
+) -> Option<Option<String>> {
+    match template {
+        Some(template) => match template.render_string(event) {
+            Ok(value) => Some(Some(value)),
+            Err(error) => {
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some(field),
+                    drop_event: true
+                });
+                None
+            }
+        },
+        None => Some(None),
+    }
+}
+
+/// The size SNS counts for message attributes, that of their names, data
+/// types and values.
+fn attributes_size(attributes: &HashMap<String, MessageAttributeValue>) -> usize {
+    attributes
+        .iter()
+        .map(|(name, value)| {
+            name.len()
+                + value.data_type.len()
+                + value.string_value.as_ref().map_or(0, String::len)
+                + value.binary_value.as_ref().map_or(0, |value| value.len())
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnsSinkConfig>();
+    }
+
+    fn config(extra: &str) -> SnsSinkConfig {
+        toml::from_str(&format!(
+            r#"
+            topic_arn = "arn:aws:sns:us-east-2:123456789012:MyTopic"
+            region = "us-east-2"
+            encoding.codec = "text"
+            {}
+            "#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    fn encode(config: &SnsSinkConfig, event: Event) -> Option<PublishEntry> {
+        MessageEncoder::new(config)
+            .unwrap()
+            .encode_event(event)
+            .map(|event| event.item)
+    }
+
+    #[test]
+    fn sns_encode_event_message_attributes() {
+        let config = config(
+            r#"
+            message_attributes.severity = "level"
+            message_attributes.status = "http.status"
+            message_attributes.tags = "tags"
+            message_attributes.missing = "missing"
+            "#,
+        );
+        let mut log = LogEvent::from("hello world");
+        log.insert("level", "error");
+        log.insert("http.status", 503);
+        log.insert("tags", vec!["a", "b"]);
+
+        let entry = encode(&config, log.into()).unwrap();
+
+        assert_eq!(entry.message, "hello world");
+        assert_eq!(entry.message_attributes.len(), 3);
+        assert_eq!(
+            entry.message_attributes["severity"],
+            MessageAttributeValue {
+                data_type: "String".into(),
+                string_value: Some("error".into()),
+                binary_value: None,
+            }
+        );
+        assert_eq!(
+            entry.message_attributes["status"],
+            MessageAttributeValue {
+                data_type: "Number".into(),
+                string_value: Some("503".into()),
+                binary_value: None,
+            }
+        );
+        assert_eq!(
+            entry.message_attributes["tags"],
+            MessageAttributeValue {
+                data_type: "String.Array".into(),
+                string_value: Some(r#"["a","b"]"#.into()),
+                binary_value: None,
+            }
+        );
+    }
+
+    #[test]
+    fn sns_encode_event_fifo() {
+        let mut config = config(
+            r#"
+            message_group_id = "{{ tenant }}"
+            message_deduplication_id = "{{ id }}"
+            "#,
+        );
+        config.topic_arn.push_str(".fifo");
+        let mut log = LogEvent::from("hello world");
+        log.insert("tenant", "acme");
+        log.insert("id", "42");
+
+        let entry = encode(&config, log.into()).unwrap();
+
+        assert_eq!(entry.message_group_id, Some("acme".into()));
+        assert_eq!(entry.message_deduplication_id, Some("42".into()));
+        assert!(encode(&config, LogEvent::from("no tenant").into()).is_none());
+    }
+
+    #[test]
+    fn sns_requires_message_group_id_for_fifo() {
+        let mut fifo = config("");
+        fifo.topic_arn.push_str(".fifo");
+        assert!(MessageEncoder::new(&fifo).is_err());
+
+        let standard = config(r#"message_group_id = "vector""#);
+        assert!(MessageEncoder::new(&standard).is_err());
+    }
+
+    #[test]
+    fn sns_rejects_oversized_messages() {
+        let config = config("");
+        let event = Event::from("x".repeat(MAX_PAYLOAD_BYTES + 1));
+
+        assert!(encode(&config, event).is_none());
+    }
+
+    #[test]
+    fn sns_truncates_oversized_messages() {
+        let config = config(
+            r#"
+            overflow = "truncate"
+            message_attributes.severity = "level"
+            "#,
+        );
+        // A multi-byte character straddles the limit.
+        let mut log = LogEvent::from(format!("{}é", "x".repeat(MAX_PAYLOAD_BYTES - 20)));
+        log.insert("level", "error");
+
+        let entry = encode(&config, log.into()).unwrap();
+
+        assert_eq!(entry.message.len(), MAX_PAYLOAD_BYTES - 20);
+        assert!(entry.encoded_length() <= MAX_PAYLOAD_BYTES);
+    }
+}
@@ -22,7 +22,7 @@ use rusoto_sqs::{
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
-    convert::{TryFrom, TryInto},
+    convert::TryFrom,
     task::{Context, Poll},
 };
 use tower::Service;
@@ -130,10 +130,13 @@ impl SqsSinkConfig {
     }
 
     pub fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<SqsClient> {
-        let region = (&self.region).try_into()?;
+        let region = self.region.region_for_service("sqs")?;
         let client = rusoto::client(proxy)?;
 
-        let creds = self.auth.build(&region, self.assume_role.clone())?;
+        let creds = self.auth.build(
+            &self.region.region_for_service("sts")?,
+            self.assume_role.clone(),
+        )?;
 
         Ok(SqsClient::new_with(client, creds, region))
     }
@@ -0,0 +1,240 @@
+use super::service::{ingest_uri, AxiomRetry, AxiomService};
+use super::sink::{AxiomJsonEncoding, AxiomSink};
+use super::token::IngestToken;
+use crate::config::{DataType, GenerateConfig, SinkConfig, SinkContext};
+use crate::http::HttpClient;
+use crate::sinks::util::encoding::EncodingConfigFixed;
+use crate::sinks::util::service::ServiceBuilderExt;
+use crate::sinks::util::{BatchConfig, BatchSettings, Compression, TowerRequestConfig};
+use crate::sinks::{Healthcheck, VectorSink};
+use crate::template::Template;
+use crate::tls::{MaybeTlsSettings, TlsConfig};
+use futures::FutureExt;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use indexmap::IndexMap;
+use indoc::indoc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tower::ServiceBuilder;
+
+// Axiom rejects requests of more than 10,000 events. The uncompressed size of
+// requests is capped as well, to keep the memory used by the sink bounded
+// when events are large.
+pub const MAX_PAYLOAD_BYTES: usize = 10_000_000;
+pub const BATCH_GOAL_BYTES: usize = 5_000_000;
+pub const BATCH_MAX_EVENTS: usize = 10_000;
+pub const BATCH_DEFAULT_EVENTS: usize = 1_000;
+pub const BATCH_DEFAULT_TIMEOUT_SECS: u64 = 1;
+
+const DEFAULT_BATCH_SETTINGS: BatchSettings<()> = BatchSettings::const_default()
+    .bytes(BATCH_GOAL_BYTES)
+    .events(BATCH_DEFAULT_EVENTS)
+    .timeout(BATCH_DEFAULT_TIMEOUT_SECS);
+
+fn default_endpoint() -> String {
+    "https://api.axiom.co".to_owned()
+}
+
+const fn default_compression() -> Compression {
+    Compression::gzip_default()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AxiomConfig {
+    #[serde(default = "default_endpoint")]
+    endpoint: String,
+    dataset: Template,
+    token: Option<String>,
+    token_file: Option<PathBuf>,
+    /// The organization to ingest into, required with personal tokens.
+    org_id: Option<String>,
+    #[serde(default)]
+    field_mapping: IndexMap<String, String>,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    encoding: EncodingConfigFixed<AxiomJsonEncoding>,
+    #[serde(default = "default_compression")]
+    compression: Compression,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsConfig>,
+}
+
+impl GenerateConfig for AxiomConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(indoc! {r#"
+            dataset = "vector"
+            token = "${AXIOM_TOKEN}"
+        "#})
+        .unwrap()
+    }
+}
+
+impl AxiomConfig {
+    fn endpoint(&self) -> crate::Result<Uri> {
+        self.endpoint
+            .parse()
+            .map_err(|error| format!("Invalid `endpoint`: {}", error).into())
+    }
+
+    fn ingest_token(&self) -> crate::Result<IngestToken> {
+        match (&self.token, &self.token_file) {
+            (Some(token), None) => Ok(IngestToken::Static(Arc::from(token.as_str()))),
+            (None, Some(path)) => {
+                let token = IngestToken::from_file(path.clone());
+                // Fail early on a token that can't be read.
+                token.get()?;
+                Ok(token)
+            }
+            _ => Err("Exactly one of `token` and `token_file` must be set.".into()),
+        }
+    }
+
+    fn create_client(&self, cx: &SinkContext) -> crate::Result<HttpClient> {
+        let tls_settings = MaybeTlsSettings::from_config(
+            &Some(self.tls.clone().unwrap_or_else(TlsConfig::enabled)),
+            false,
+        )?;
+        Ok(HttpClient::new(tls_settings, &cx.proxy)?)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "axiom")]
+impl SinkConfig for AxiomConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let endpoint = self.endpoint()?;
+        let token = Arc::new(self.ingest_token()?);
+        let client = self.create_client(&cx)?;
+
+        let healthcheck = healthcheck(
+            client.clone(),
+            endpoint.clone(),
+            Arc::clone(&token),
+            self.org_id.clone(),
+            (!self.dataset.is_dynamic()).then(|| self.dataset.get_ref().to_owned()),
+        )
+        .boxed();
+
+        // The batch settings are capped to the limits of the ingest API, but
+        // can still be lowered.
+        let batch = DEFAULT_BATCH_SETTINGS
+            .parse_config(
+                self.batch
+                    .limit_max_bytes(BATCH_GOAL_BYTES)
+                    .limit_max_events(BATCH_MAX_EVENTS),
+            )?
+            .into_batcher_settings()?;
+        let request_limits = self.request.unwrap_with(&Default::default());
+        let service = ServiceBuilder::new()
+            .settings(request_limits, AxiomRetry)
+            .service(AxiomService::new(
+                client,
+                endpoint,
+                token,
+                self.org_id.clone(),
+            ));
+
+        let sink = AxiomSink {
+            service,
+            acker: cx.acker(),
+            dataset: self.dataset.clone(),
+            encoding: self.encoding.clone(),
+            field_mapping: self.field_mapping.clone(),
+            compression: self.compression,
+            batch_settings: batch,
+        };
+
+        Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "axiom"
+    }
+}
+
+/// The healthcheck ingests an empty batch of events into the dataset, which
+/// checks that the token is allowed to ingest into it. Templated datasets
+/// aren't known until events come in, so only the token is checked then.
+async fn healthcheck(
+    client: HttpClient,
+    endpoint: Uri,
+    token: Arc<IngestToken>,
+    org_id: Option<String>,
+    dataset: Option<String>,
+) -> crate::Result<()> {
+    let token = token.get()?;
+    let dataset = match dataset {
+        Some(dataset) => dataset,
+        None => return Ok(()),
+    };
+
+    let mut request = Request::post(ingest_uri(&endpoint, &dataset)?)
+        .header(CONTENT_TYPE, "application/json")
+        .header(AUTHORIZATION, format!("Bearer {}", token));
+    if let Some(org_id) = org_id {
+        request = request.header("X-Axiom-Org-Id", org_id);
+    }
+    let response = client.send(request.body(Body::from("[]"))?).await?;
+
+    match response.status() {
+        StatusCode::OK => Ok(()),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(format!(
+            "The token isn't allowed to ingest into dataset {:?}.",
+            dataset
+        )
+        .into()),
+        StatusCode::NOT_FOUND => Err(format!("Dataset {:?} doesn't exist.", dataset).into()),
+        status => Err(super::super::HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AxiomConfig>();
+    }
+
+    #[test]
+    fn requires_a_single_token() {
+        let config: AxiomConfig = toml::from_str(indoc! {r#"
+            dataset = "vector"
+        "#})
+        .unwrap();
+        assert!(config.ingest_token().is_err());
+
+        let config: AxiomConfig = toml::from_str(indoc! {r#"
+            dataset = "vector"
+            token = "xaat-1234"
+            token_file = "/run/secrets/axiom"
+        "#})
+        .unwrap();
+        assert!(config.ingest_token().is_err());
+    }
+
+    #[test]
+    fn defaults_to_gzip() {
+        let config: AxiomConfig = toml::from_str(indoc! {r#"
+            dataset = "vector"
+            token = "xaat-1234"
+        "#})
+        .unwrap();
+        assert_eq!(config.compression, Compression::gzip_default());
+        assert_eq!(config.endpoint().unwrap(), "https://api.axiom.co/");
+    }
+}
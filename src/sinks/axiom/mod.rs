@@ -0,0 +1,23 @@
+//! The Axiom [`VectorSink`]
+//!
+//! This module contains the [`VectorSink`] instance responsible for sending
+//! log events to the [ingest API](https://axiom.co/docs/restapi/ingest) of
+//! Axiom. Events are partitioned by the dataset they are ingested into, which
+//! may be templated, and sent as a JSON array of objects, with their
+//! timestamp in the `_time` field Axiom reads it from.
+//!
+//! Requests are authenticated by an API or personal token, which may be read
+//! from a file. Such a token is read again whenever the file changes, so
+//! that it can be rotated without reloading the configuration.
+
+mod config;
+mod service;
+mod sink;
+mod token;
+
+use crate::config::SinkDescription;
+use config::AxiomConfig;
+
+inventory::submit! {
+    SinkDescription::new::<AxiomConfig>("axiom")
+}
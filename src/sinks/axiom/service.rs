@@ -0,0 +1,240 @@
+use super::token::{IngestToken, TokenError};
+use crate::http::HttpClient;
+use crate::internal_events::AxiomIngestFailures;
+use crate::sinks::util::retries::RetryLogic;
+use crate::sinks::util::Compression;
+use futures::future::BoxFuture;
+use http::header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE};
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use snafu::Snafu;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::Service;
+use tracing::Instrument;
+use vector_core::buffers::Ackable;
+use vector_core::event::{EventFinalizers, EventStatus, Finalizable};
+
+#[derive(Debug, Clone)]
+pub struct AxiomRequest {
+    pub dataset: String,
+    pub batch_size: usize,
+    pub compression: Compression,
+    pub body: Vec<u8>,
+    pub finalizers: EventFinalizers,
+}
+
+impl Ackable for AxiomRequest {
+    fn ack_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+impl Finalizable for AxiomRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum AxiomError {
+    #[snafu(display("{}", source))]
+    Token { source: TokenError },
+    #[snafu(display("Failed to make HTTP(S) request: {}", error))]
+    HttpError { error: crate::http::HttpError },
+    #[snafu(display("Server responded with {}: {}", status, body))]
+    ServerError { status: StatusCode, body: String },
+}
+
+#[derive(Debug)]
+pub enum AxiomResponse {
+    /// The events were ingested, except for those Axiom reported as failed.
+    Ok,
+    /// The token is invalid, or isn't allowed to ingest into the dataset.
+    PermissionIssue,
+}
+
+impl AsRef<EventStatus> for AxiomResponse {
+    fn as_ref(&self) -> &EventStatus {
+        match self {
+            AxiomResponse::Ok => &EventStatus::Delivered,
+            AxiomResponse::PermissionIssue => &EventStatus::Errored,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AxiomRetry;
+
+impl RetryLogic for AxiomRetry {
+    type Error = AxiomError;
+    type Response = AxiomResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            // A token file being rotated may be briefly missing.
+            AxiomError::Token { .. } | AxiomError::HttpError { .. } => true,
+            AxiomError::ServerError { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+/// The summary of an ingest request returned by Axiom.
+#[derive(Debug, Default, Deserialize)]
+struct IngestStatus {
+    #[serde(default)]
+    failed: usize,
+    #[serde(default)]
+    failures: Vec<IngestFailure>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IngestFailure {
+    error: String,
+}
+
+/// Wrapper for the Axiom ingest API.
+#[derive(Debug, Clone)]
+pub struct AxiomService {
+    client: HttpClient,
+    endpoint: Uri,
+    token: Arc<IngestToken>,
+    org_id: Option<String>,
+}
+
+impl AxiomService {
+    pub const fn new(
+        client: HttpClient,
+        endpoint: Uri,
+        token: Arc<IngestToken>,
+        org_id: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            endpoint,
+            token,
+            org_id,
+        }
+    }
+}
+
+/// The characters escaped in dataset names, which may contain dashes,
+/// underscores and dots.
+const DATASET_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+
+/// The URI of the ingest API of a dataset.
+pub fn ingest_uri(endpoint: &Uri, dataset: &str) -> Result<Uri, http::uri::InvalidUri> {
+    format!(
+        "{}/v1/datasets/{}/ingest",
+        endpoint.to_string().trim_end_matches('/'),
+        utf8_percent_encode(dataset, DATASET_ENCODE_SET)
+    )
+    .parse()
+}
+
+impl Service<AxiomRequest> for AxiomService {
+    type Response = AxiomResponse;
+    type Error = AxiomError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: AxiomRequest) -> Self::Future {
+        let client = self.client.clone();
+        // The token is read for every request, so that retried requests pick
+        // up a rotated token.
+        let token = self.token.get();
+        let uri = ingest_uri(&self.endpoint, &request.dataset);
+        let org_id = self.org_id.clone();
+
+        Box::pin(async move {
+            let token = token.map_err(|source| AxiomError::Token { source })?;
+            let uri = uri.expect("dataset names are percent-encoded");
+
+            let mut http_request = Request::post(uri)
+                .header(CONTENT_TYPE, "application/json")
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header(CONTENT_LENGTH, request.body.len());
+            if let Some(ce) = request.compression.content_encoding() {
+                http_request = http_request.header(CONTENT_ENCODING, ce);
+            }
+            if let Some(org_id) = org_id {
+                http_request = http_request.header("X-Axiom-Org-Id", org_id);
+            }
+            let http_request = http_request
+                .body(Body::from(request.body))
+                .expect("building HTTP request failed unexpectedly");
+
+            let response = client
+                .send(http_request)
+                .in_current_span()
+                .await
+                .map_err(|error| AxiomError::HttpError { error })?;
+            let status = response.status();
+            let body = hyper::body::to_bytes(response.into_body())
+                .await
+                .unwrap_or_default();
+
+            match status {
+                StatusCode::OK => {
+                    let ingest_status: IngestStatus =
+                        serde_json::from_slice(&body).unwrap_or_default();
+                    if ingest_status.failed > 0 {
+                        emit!(&AxiomIngestFailures {
+                            dataset: &request.dataset,
+                            count: ingest_status.failed,
+                            error: ingest_status
+                                .failures
+                                .first()
+                                .map(|failure| failure.error.as_str()),
+                        });
+                    }
+                    Ok(AxiomResponse::Ok)
+                }
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    Ok(AxiomResponse::PermissionIssue)
+                }
+                _ => Err(AxiomError::ServerError {
+                    status,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                }),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_dataset_in_uri() {
+        let endpoint = "https://api.axiom.co/".parse().unwrap();
+        assert_eq!(
+            ingest_uri(&endpoint, "web-logs_v1.2").unwrap().to_string(),
+            "https://api.axiom.co/v1/datasets/web-logs_v1.2/ingest"
+        );
+        assert_eq!(
+            ingest_uri(&endpoint, "team/a b").unwrap().to_string(),
+            "https://api.axiom.co/v1/datasets/team%2Fa%20b/ingest"
+        );
+    }
+
+    #[test]
+    fn retries_server_errors_only() {
+        let error = |status| AxiomError::ServerError {
+            status,
+            body: String::new(),
+        };
+        assert!(AxiomRetry.is_retriable_error(&error(StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(AxiomRetry.is_retriable_error(&error(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!AxiomRetry.is_retriable_error(&error(StatusCode::BAD_REQUEST)));
+        assert!(!AxiomRetry.is_retriable_error(&error(StatusCode::PAYLOAD_TOO_LARGE)));
+    }
+}
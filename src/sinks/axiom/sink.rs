@@ -0,0 +1,254 @@
+use super::config::{BATCH_MAX_EVENTS, MAX_PAYLOAD_BYTES};
+use super::service::AxiomRequest;
+use crate::internal_events::TemplateRenderingFailed;
+use crate::sinks::util::encoding::{Encoder, EncodingConfigFixed, StandardEncodings};
+use crate::sinks::util::{
+    Compression, EncodedPayload, IncrementalRequestBuilder, PayloadFraming, RequestLimits,
+    SinkBuilderExt,
+};
+use crate::template::Template;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use indexmap::IndexMap;
+use std::fmt::Debug;
+use std::io;
+use std::num::NonZeroUsize;
+use tower::Service;
+use vector_core::buffers::Acker;
+use vector_core::config::{log_schema, LogSchema};
+use vector_core::event::{Event, EventStatus};
+use vector_core::partition::Partitioner;
+use vector_core::sink::StreamSink;
+use vector_core::stream::BatcherSettings;
+
+/// The field Axiom reads the time of events from.
+const TIME_FIELD: &str = "_time";
+
+/// Partitions events by the dataset they are ingested into.
+struct DatasetPartitioner(Template);
+
+impl Partitioner for DatasetPartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.0
+            .render_string(item)
+            .map_err(|error| {
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some("dataset"),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
+
+/// The encoding of the Axiom sink, which sends events as JSON objects with
+/// their timestamp in the `_time` field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxiomJsonEncoding {
+    log_schema: &'static LogSchema,
+    inner: StandardEncodings,
+}
+
+impl Default for AxiomJsonEncoding {
+    fn default() -> Self {
+        AxiomJsonEncoding {
+            log_schema: log_schema(),
+            inner: StandardEncodings::Json,
+        }
+    }
+}
+
+impl Encoder<Event> for AxiomJsonEncoding {
+    fn encode_input(&self, mut input: Event, writer: &mut dyn io::Write) -> io::Result<usize> {
+        let log = input.as_mut_log();
+        if let Some(timestamp) = log.remove(self.log_schema.timestamp_key()) {
+            log.insert_flat(TIME_FIELD, timestamp);
+        }
+        self.inner.encode_input(input, writer)
+    }
+}
+
+pub struct AxiomSink<S> {
+    pub service: S,
+    pub acker: Acker,
+    pub dataset: Template,
+    pub encoding: EncodingConfigFixed<AxiomJsonEncoding>,
+    pub field_mapping: IndexMap<String, String>,
+    pub compression: Compression,
+    pub batch_settings: BatcherSettings,
+}
+
+struct AxiomRequestBuilder {
+    encoding: EncodingConfigFixed<AxiomJsonEncoding>,
+    field_mapping: IndexMap<String, String>,
+    compression: Compression,
+}
+
+impl AxiomRequestBuilder {
+    /// Renames the fields of the event according to the field mapping, so
+    /// that they match the names used in the dataset.
+    fn map_fields(&self, event: &mut Event) {
+        let log = event.as_mut_log();
+        for (from, to) in &self.field_mapping {
+            if let Some(value) = log.remove(from) {
+                log.insert(to, value);
+            }
+        }
+    }
+}
+
+impl IncrementalRequestBuilder<(String, Vec<Event>)> for AxiomRequestBuilder {
+    type Metadata = String;
+    type Event = Event;
+    type Request = AxiomRequest;
+    type Error = io::Error;
+    type EventDetails = ();
+
+    fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    fn limits(&self) -> RequestLimits {
+        RequestLimits {
+            max_bytes: MAX_PAYLOAD_BYTES,
+            max_events: BATCH_MAX_EVENTS,
+        }
+    }
+
+    fn framing(&self) -> PayloadFraming {
+        PayloadFraming::JSON_ARRAY
+    }
+
+    fn split_input(&self, input: (String, Vec<Event>)) -> (String, Vec<Event>) {
+        input
+    }
+
+    fn event_details(&self, _event: &Event) {}
+
+    fn encode_event(&self, mut event: Event, writer: &mut Vec<u8>) -> io::Result<()> {
+        self.map_fields(&mut event);
+        self.encoding.encode_input(event, writer).map(|_| ())
+    }
+
+    fn build_request(&self, dataset: String, payload: EncodedPayload) -> AxiomRequest {
+        AxiomRequest {
+            dataset,
+            batch_size: payload.events_len,
+            compression: self.compression,
+            body: payload.body,
+            finalizers: payload.finalizers,
+        }
+    }
+}
+
+impl<S> AxiomSink<S>
+where
+    S: Service<AxiomRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: AsRef<EventStatus> + Send + 'static,
+    S::Error: Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let partitioner = DatasetPartitioner(self.dataset);
+        let builder_limit = NonZeroUsize::new(64);
+        let request_builder = AxiomRequestBuilder {
+            encoding: self.encoding,
+            field_mapping: self.field_mapping,
+            compression: self.compression,
+        };
+
+        let sink = input
+            .batched(partitioner, self.batch_settings)
+            .filter_map(|(dataset, batch)| async move { dataset.map(move |d| (d, batch)) })
+            .incremental_request_builder(builder_limit, request_builder)
+            .filter_map(|request| async move {
+                match request {
+                    Err(e) => {
+                        error!("Failed to build Axiom request: {:?}.", e);
+                        None
+                    }
+                    Ok(req) => Some(req),
+                }
+            })
+            .into_driver(self.service, self.acker);
+
+        sink.run().await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink for AxiomSink<S>
+where
+    S: Service<AxiomRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: AsRef<EventStatus> + Send + 'static,
+    S::Error: Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::convert::TryFrom;
+
+    fn builder(field_mapping: IndexMap<String, String>) -> AxiomRequestBuilder {
+        AxiomRequestBuilder {
+            encoding: Default::default(),
+            field_mapping,
+            compression: Compression::None,
+        }
+    }
+
+    #[test]
+    fn encodes_events_for_axiom() {
+        let mut event = Event::from("hello");
+        let log = event.as_mut_log();
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.ymd(2021, 11, 2).and_hms(8, 30, 0),
+        );
+        log.insert("level", "info");
+        log.insert("kubernetes.pod_name", "api-0");
+        log.insert("kubernetes.namespace", "default");
+
+        let mut field_mapping = IndexMap::new();
+        field_mapping.insert("level".to_owned(), "severity".to_owned());
+        field_mapping.insert("kubernetes.pod_name".to_owned(), "pod".to_owned());
+        let requests = builder(field_mapping).build_requests(("logs".into(), vec![event]));
+        assert_eq!(requests.len(), 1);
+        let request = requests.into_iter().next().unwrap().unwrap();
+        assert_eq!(request.dataset, "logs");
+        assert_eq!(request.batch_size, 1);
+
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!([{
+                "_time": "2021-11-02T08:30:00Z",
+                "message": "hello",
+                "severity": "info",
+                "kubernetes": { "namespace": "default" },
+                "pod": "api-0",
+            }])
+        );
+    }
+
+    #[test]
+    fn partitions_by_dataset() {
+        let partitioner = DatasetPartitioner(Template::try_from("{{ app }}-logs").unwrap());
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("app", "billing");
+        assert_eq!(partitioner.partition(&event), Some("billing-logs".into()));
+        assert_eq!(partitioner.partition(&Event::from("hello")), None);
+    }
+}
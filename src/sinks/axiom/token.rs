@@ -0,0 +1,102 @@
+use snafu::{ResultExt, Snafu};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+#[derive(Debug, Snafu)]
+pub enum TokenError {
+    #[snafu(display("Failed to read the ingest token from {:?}: {}", path, source))]
+    ReadToken { path: PathBuf, source: io::Error },
+    #[snafu(display("The ingest token file {:?} is empty", path))]
+    EmptyToken { path: PathBuf },
+}
+
+/// The token authenticating ingest requests.
+///
+/// A token read from a file is read again whenever the file is modified, so
+/// that tokens can be rotated, e.g. by updating a mounted Kubernetes secret,
+/// without reloading Vector.
+#[derive(Debug)]
+pub enum IngestToken {
+    Static(Arc<str>),
+    File {
+        path: PathBuf,
+        /// The token, along with the modification time and length of the
+        /// file it was read from.
+        cached: Mutex<Option<((SystemTime, u64), Arc<str>)>>,
+    },
+}
+
+impl IngestToken {
+    pub fn from_file(path: PathBuf) -> Self {
+        Self::File {
+            path,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The current token.
+    pub fn get(&self) -> Result<Arc<str>, TokenError> {
+        match self {
+            Self::Static(token) => Ok(Arc::clone(token)),
+            Self::File { path, cached } => {
+                let version = fs::metadata(path)
+                    .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                    .context(ReadToken { path })?;
+                let mut cached = cached.lock().expect("poisoned lock");
+                match &*cached {
+                    Some((read, token)) if *read == version => Ok(Arc::clone(token)),
+                    _ => {
+                        let token = read_token(path)?;
+                        *cached = Some((version, Arc::clone(&token)));
+                        Ok(token)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn read_token(path: &Path) -> Result<Arc<str>, TokenError> {
+    let token = fs::read_to_string(path).context(ReadToken { path })?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(TokenError::EmptyToken { path: path.into() });
+    }
+    Ok(Arc::from(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rotated_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        fs::write(&path, "xaat-first\n").unwrap();
+
+        let token = IngestToken::from_file(path.clone());
+        assert_eq!(&*token.get().unwrap(), "xaat-first");
+
+        // The length of the token changes too, in case the modification time
+        // doesn't on filesystems with a coarse resolution.
+        fs::write(&path, "xaat-rotated\n").unwrap();
+        assert_eq!(&*token.get().unwrap(), "xaat-rotated");
+    }
+
+    #[test]
+    fn rejects_missing_and_empty_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+
+        let token = IngestToken::from_file(path.clone());
+        assert!(matches!(token.get(), Err(TokenError::ReadToken { .. })));
+
+        fs::write(&path, "\n").unwrap();
+        assert!(matches!(token.get(), Err(TokenError::EmptyToken { .. })));
+    }
+}
@@ -1,13 +1,15 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext},
-    event::Event,
+    event::{Event, LogEvent},
     internal_events::{
         azure_blob::{AzureBlobErrorResponse, AzureBlobEventSent, AzureBlobHttpError},
         TemplateRenderingFailed,
     },
     sinks::{
+        azure_credentials::AzureCredentials,
         util::{
             encoding::{EncodingConfig, EncodingConfiguration},
+            parquet::{ParquetBuffer, ParquetConfig, PARQUET_CONTENT_TYPE},
             retries::RetryLogic,
             sink::Response,
             BatchConfig, BatchSettings, Buffer, Compression, EncodedEvent, PartitionBatchSink,
@@ -20,7 +22,6 @@ use crate::{
 };
 use azure_core::prelude::*;
 use azure_core::HttpError;
-use azure_storage::blob::blob::responses::PutBlockBlobResponse;
 use azure_storage::blob::prelude::*;
 use azure_storage::core::prelude::*;
 use bytes::Bytes;
@@ -35,27 +36,82 @@ use std::{
     sync::Arc,
     task::{Context, Poll},
 };
+use tokio::sync::Mutex;
 use tower::{Service, ServiceBuilder};
 use tracing_futures::Instrument;
 use uuid::Uuid;
 use vector_core::ByteSizeOf;
 
+/// The resource of the Azure AD tokens of storage accounts.
+const STORAGE_RESOURCE: &str = "https://storage.azure.com/";
+
+/// The maximum size of a block appended to an append blob.
+const MAX_APPEND_BLOCK_BYTES: usize = 4 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct AzureBlobSink {
-    client: Arc<ContainerClient>,
+    client: AzureBlobClient,
+    /// Held while creating append blobs, so that concurrent requests don't
+    /// create the same blob twice, the second one erasing the first one's
+    /// block.
+    create_lock: Arc<Mutex<()>>,
+}
+
+/// Creates the container clients of the sink, authenticated with either the
+/// connection string or the current Azure AD token.
+#[derive(Clone)]
+pub enum AzureBlobClient {
+    ConnectionString(Arc<ContainerClient>),
+    Identity {
+        storage_account: String,
+        container_name: String,
+        credentials: AzureCredentials,
+    },
+}
+
+impl AzureBlobClient {
+    pub fn container(&self) -> Arc<ContainerClient> {
+        match self {
+            Self::ConnectionString(client) => Arc::clone(client),
+            Self::Identity {
+                storage_account,
+                container_name,
+                credentials,
+            } => StorageAccountClient::new_bearer_token(
+                new_http_client(),
+                storage_account,
+                credentials.access_token(),
+            )
+            .as_storage_client()
+            .as_container_client(container_name),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct AzureBlobSinkConfig {
-    pub connection_string: String,
+    pub connection_string: Option<String>,
+    /// The storage account to authenticate to with the Azure AD token of a
+    /// managed identity or workload identity, if `connection_string` isn't set.
+    pub storage_account: Option<String>,
+    /// The client ID of the identity, if it isn't the system-assigned one.
+    pub client_id: Option<String>,
     pub container_name: String,
+    #[serde(default)]
+    pub blob_type: BlobType,
+    pub access_tier: Option<BlobAccessTier>,
     pub blob_prefix: Option<String>,
     pub blob_time_format: Option<String>,
     pub blob_append_uuid: Option<bool>,
     pub encoding: EncodingConfig<Encoding>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
+    /// Writes the batches as Parquet files instead of encoding each event.
+    /// The files' columns are compressed by the Parquet encoder, so
+    /// `compression` doesn't apply to them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet: Option<ParquetConfig>,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -72,6 +128,47 @@ struct AzureBlobSinkRequest {
     blob_data: Vec<u8>,
     content_encoding: Option<&'static str>,
     content_type: &'static str,
+    blob_type: BlobType,
+    access_tier: Option<BlobAccessTier>,
+}
+
+#[derive(Debug)]
+pub struct AzureBlobResponse {
+    request_id: Uuid,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BlobType {
+    /// Each batch is written to a blob of its own.
+    Block,
+    /// Batches are appended to the blob named after their time, which is
+    /// created by the first one.
+    Append,
+}
+
+impl Default for BlobType {
+    fn default() -> Self {
+        Self::Block
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BlobAccessTier {
+    Hot,
+    Cool,
+    Archive,
+}
+
+impl From<BlobAccessTier> for AccessTier {
+    fn from(tier: BlobAccessTier) -> Self {
+        match tier {
+            BlobAccessTier::Hot => Self::Hot,
+            BlobAccessTier::Cool => Self::Cool,
+            BlobAccessTier::Archive => Self::Archive,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
@@ -81,6 +178,25 @@ pub enum Encoding {
     Text,
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Exactly one of `connection_string` or `storage_account` must be set"))]
+    InvalidAuth,
+    #[snafu(display("Append blobs can't be written as Parquet files"))]
+    AppendParquet,
+    #[snafu(display(
+        "Append blobs can't be named with a UUID, as batches must be appended to the same blob"
+    ))]
+    AppendUuid,
+    #[snafu(display("Append blobs don't have access tiers"))]
+    AppendAccessTier,
+    #[snafu(display(
+        "Batches of append blobs can't be larger than {} bytes",
+        MAX_APPEND_BLOCK_BYTES
+    ))]
+    AppendBatchTooLarge,
+}
+
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
     #[snafu(display("Invalid connection string specified"))]
@@ -94,13 +210,18 @@ enum HealthcheckError {
 impl GenerateConfig for AzureBlobSinkConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
-            connection_string: String::from("DefaultEndpointsProtocol=https;AccountName=some-account-name;AccountKey=some-account-key;"),
+            connection_string: Some(String::from("DefaultEndpointsProtocol=https;AccountName=some-account-name;AccountKey=some-account-key;")),
+            storage_account: None,
+            client_id: None,
             container_name: String::from("logs"),
+            blob_type: BlobType::Block,
+            access_tier: None,
             blob_prefix: Some(String::from("blob")),
             blob_time_format: Some(String::from("%s")),
             blob_append_uuid: Some(true),
             encoding: Encoding::Ndjson.into(),
             compression: Compression::gzip_default(),
+            parquet: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
         })
@@ -112,8 +233,11 @@ impl GenerateConfig for AzureBlobSinkConfig {
 #[typetag::serde(name = "azure_blob")]
 impl SinkConfig for AzureBlobSinkConfig {
     async fn build(&self, cx: SinkContext) -> Result<(VectorSink, Healthcheck)> {
-        let client = self.create_client()?;
-        let healthcheck = self.clone().healthcheck(Arc::clone(&client)).boxed();
+        let client = self.create_client().await?;
+        if let AzureBlobClient::Identity { credentials, .. } = &client {
+            credentials.spawn_regenerate_token();
+        }
+        let healthcheck = self.clone().healthcheck(client.clone()).boxed();
         let sink = self.new(client, cx)?;
         Ok((sink, healthcheck))
     }
@@ -128,29 +252,57 @@ impl SinkConfig for AzureBlobSinkConfig {
 }
 
 impl AzureBlobSinkConfig {
-    pub fn new(&self, client: Arc<ContainerClient>, cx: SinkContext) -> Result<VectorSink> {
+    pub fn new(&self, client: AzureBlobClient, cx: SinkContext) -> Result<VectorSink> {
+        let append = self.blob_type == BlobType::Append;
+        if append {
+            if self.parquet.is_some() {
+                return Err(BuildError::AppendParquet.into());
+            }
+            if self.blob_append_uuid == Some(true) {
+                return Err(BuildError::AppendUuid.into());
+            }
+            if self.access_tier.is_some() {
+                return Err(BuildError::AppendAccessTier.into());
+            }
+            if self.batch.max_bytes.unwrap_or(0) > MAX_APPEND_BLOCK_BYTES {
+                return Err(BuildError::AppendBatchTooLarge.into());
+            }
+        }
+
         let request = self.request.unwrap_with(&TowerRequestConfig {
             rate_limit_num: Some(250),
             ..Default::default()
         });
-        let batch = BatchSettings::default()
-            .bytes(10 * 1024 * 1024)
-            .timeout(300)
-            .parse_config(self.batch)?;
         let compression = self.compression;
+        let parquet = self.parquet.is_some();
         let container_name = self.container_name.clone();
-        let blob_time_format = self.blob_time_format.clone().unwrap_or_else(|| "%s".into());
-        let blob_append_uuid = self.blob_append_uuid.unwrap_or(true);
-        let blob = AzureBlobSink { client };
+        // Append blobs are named after the hour by default, so that they keep
+        // growing for an hour.
+        let default_time_format = if append { "%Y-%m-%d-%H" } else { "%s" };
+        let blob_time_format = self
+            .blob_time_format
+            .clone()
+            .unwrap_or_else(|| default_time_format.into());
+        let blob_append_uuid = self.blob_append_uuid.unwrap_or(!append);
+        let blob_type = self.blob_type;
+        let access_tier = self.access_tier;
+        let blob = AzureBlobSink {
+            client,
+            create_lock: Arc::default(),
+        };
         let svc = ServiceBuilder::new()
             .map(move |partition| {
-                build_request(
+                let mut request = build_request(
                     partition,
                     compression,
+                    parquet,
                     container_name.clone(),
                     blob_time_format.clone(),
                     blob_append_uuid,
-                )
+                );
+                request.blob_type = blob_type;
+                request.access_tier = access_tier;
+                request
             })
             .settings(request, AzureBlobRetryLogic)
             .service(blob);
@@ -158,19 +310,46 @@ impl AzureBlobSinkConfig {
         let encoding = self.encoding.clone();
         let blob_prefix = self.blob_prefix.as_deref().unwrap_or("blob/%F/");
         let blob_prefix = Template::try_from(blob_prefix)?;
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .with_flat_map(move |event| {
-                stream::iter(encode_event(event, &blob_prefix, &encoding)).map(Ok)
-            })
-            .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
 
-        Ok(super::VectorSink::Sink(Box::new(sink)))
+        Ok(match &self.parquet {
+            Some(parquet) => {
+                let batch = BatchSettings::default()
+                    .bytes(10 * 1024 * 1024)
+                    .timeout(300)
+                    .parse_config(self.batch)?;
+                let buffer =
+                    PartitionBuffer::new(ParquetBuffer::new(batch.size, parquet.build_encoder()?));
+                let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                    .with_flat_map(move |event| {
+                        stream::iter(encode_parquet_event(event, &blob_prefix, &encoding)).map(Ok)
+                    })
+                    .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+                super::VectorSink::Sink(Box::new(sink))
+            }
+            None => {
+                let max_bytes = if append {
+                    MAX_APPEND_BLOCK_BYTES
+                } else {
+                    10 * 1024 * 1024
+                };
+                let batch = BatchSettings::default()
+                    .bytes(max_bytes)
+                    .timeout(300)
+                    .parse_config(self.batch)?;
+                let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
+                let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                    .with_flat_map(move |event| {
+                        stream::iter(encode_event(event, &blob_prefix, &encoding)).map(Ok)
+                    })
+                    .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+                super::VectorSink::Sink(Box::new(sink))
+            }
+        })
     }
 
-    pub async fn healthcheck(self, client: Arc<ContainerClient>) -> Result<()> {
+    pub async fn healthcheck(self, client: AzureBlobClient) -> Result<()> {
         let container_name = self.container_name.clone();
-        let request = client.get_properties().execute().await;
+        let request = client.container().get_properties().execute().await;
 
         match request {
             Ok(_) => Ok(()),
@@ -188,20 +367,95 @@ impl AzureBlobSinkConfig {
         }
     }
 
-    pub fn create_client(&self) -> Result<Arc<ContainerClient>> {
-        let connection_string = self.connection_string.as_str();
+    pub async fn create_client(&self) -> Result<AzureBlobClient> {
         let container_name = self.container_name.as_str();
-        let client =
-            StorageAccountClient::new_connection_string(new_http_client(), connection_string)?
+        match (&self.connection_string, &self.storage_account) {
+            (Some(connection_string), None) => {
+                let client = StorageAccountClient::new_connection_string(
+                    new_http_client(),
+                    connection_string,
+                )?
                 .as_storage_client()
                 .as_container_client(container_name);
+                Ok(AzureBlobClient::ConnectionString(client))
+            }
+            (None, Some(storage_account)) => {
+                let credentials =
+                    AzureCredentials::new(STORAGE_RESOURCE, self.client_id.clone()).await?;
+                Ok(AzureBlobClient::Identity {
+                    storage_account: storage_account.clone(),
+                    container_name: container_name.into(),
+                    credentials,
+                })
+            }
+            _ => Err(BuildError::InvalidAuth.into()),
+        }
+    }
+}
+
+impl AzureBlobSink {
+    async fn put_block_blob(
+        client: Arc<BlobClient>,
+        request: AzureBlobSinkRequest,
+    ) -> StdResult<AzureBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let blob = client
+            .put_block_blob(Bytes::from(request.blob_data))
+            .content_type(request.content_type);
+        let blob = match request.content_encoding {
+            Some(encoding) => blob.content_encoding(encoding),
+            None => blob,
+        };
+        let blob = match request.access_tier {
+            Some(tier) => blob.access_tier(tier.into()),
+            None => blob,
+        };
 
-        Ok(client)
+        let response = blob.execute().await?;
+        Ok(AzureBlobResponse {
+            request_id: response.request_id,
+        })
+    }
+
+    async fn append_block(
+        client: Arc<BlobClient>,
+        request: AzureBlobSinkRequest,
+        create_lock: Arc<Mutex<()>>,
+    ) -> StdResult<AzureBlobResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let data = Bytes::from(request.blob_data);
+        match client.append_block(data.clone()).execute().await {
+            Err(reason) if is_not_found(&*reason) => {
+                let _guard = create_lock.lock().await;
+                // Another request may have created the blob in the meantime.
+                match client.append_block(data.clone()).execute().await {
+                    Err(reason) if is_not_found(&*reason) => {
+                        let blob = client.put_append_blob().content_type(request.content_type);
+                        let blob = match request.content_encoding {
+                            Some(encoding) => blob.content_encoding(encoding),
+                            None => blob,
+                        };
+                        blob.execute().await?;
+                        client.append_block(data).execute().await
+                    }
+                    result => result,
+                }
+            }
+            result => result,
+        }
+        .map(|response| AzureBlobResponse {
+            request_id: response.request_id,
+        })
     }
 }
 
+fn is_not_found(reason: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    matches!(
+        reason.downcast_ref::<HttpError>(),
+        Some(HttpError::UnexpectedStatusCode { received, .. }) if *received == StatusCode::NOT_FOUND
+    )
+}
+
 impl Service<AzureBlobSinkRequest> for AzureBlobSink {
-    type Response = PutBlockBlobResponse;
+    type Response = AzureBlobResponse;
     type Error = Box<dyn std::error::Error + std::marker::Send + std::marker::Sync>;
     type Future = BoxFuture<'static, StdResult<Self::Response, Self::Error>>;
 
@@ -210,19 +464,20 @@ impl Service<AzureBlobSinkRequest> for AzureBlobSink {
     }
 
     fn call(&mut self, request: AzureBlobSinkRequest) -> Self::Future {
-        let client = Arc::clone(&self.client).as_blob_client(request.blob_name.as_str());
+        let client = self
+            .client
+            .container()
+            .as_blob_client(request.blob_name.as_str());
+        let create_lock = Arc::clone(&self.create_lock);
 
         Box::pin(async move {
             let byte_size = request.blob_data.len();
-            let blob = client
-                .put_block_blob(Bytes::from(request.blob_data))
-                .content_type(request.content_type);
-            let blob = match request.content_encoding {
-                Some(encoding) => blob.content_encoding(encoding),
-                None => blob,
+            let response = match request.blob_type {
+                BlobType::Block => Self::put_block_blob(client, request).boxed(),
+                BlobType::Append => Self::append_block(client, request, create_lock).boxed(),
             };
 
-            blob.execute()
+            response
                 .inspect_err(|reason| {
                     match reason.downcast_ref::<HttpError>() {
                         Some(HttpError::UnexpectedStatusCode { received, .. }) => {
@@ -250,15 +505,16 @@ impl Compression {
         match self {
             Self::None => "text/plain",
             Self::Gzip(_) => "application/gzip",
+            Self::Zstd { .. } => "application/zstd",
         }
     }
 }
 
-impl Response for PutBlockBlobResponse {}
+impl Response for AzureBlobResponse {}
 
 impl RetryLogic for AzureBlobRetryLogic {
     type Error = HttpError;
-    type Response = PutBlockBlobResponse;
+    type Response = AzureBlobResponse;
 
     fn is_retriable_error(&self, error: &Self::Error) -> bool {
         match error {
@@ -270,13 +526,9 @@ impl RetryLogic for AzureBlobRetryLogic {
     }
 }
 
-fn encode_event(
-    mut event: Event,
-    blob_prefix: &Template,
-    encoding: &EncodingConfig<Encoding>,
-) -> Option<EncodedEvent<PartitionInnerBuffer<Vec<u8>, Bytes>>> {
-    let key = blob_prefix
-        .render_string(&event)
+fn render_blob_prefix(event: &Event, blob_prefix: &Template) -> Option<String> {
+    blob_prefix
+        .render_string(event)
         .map_err(|error| {
             emit!(&TemplateRenderingFailed {
                 error,
@@ -284,7 +536,15 @@ fn encode_event(
                 drop_event: true,
             });
         })
-        .ok()?;
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    blob_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<EncodedEvent<PartitionInnerBuffer<Vec<u8>, Bytes>>> {
+    let key = render_blob_prefix(&event, blob_prefix)?;
 
     let byte_size = event.size_of();
     encoding.apply_rules(&mut event);
@@ -313,9 +573,25 @@ fn encode_event(
     ))
 }
 
+fn encode_parquet_event(
+    mut event: Event,
+    blob_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<EncodedEvent<PartitionInnerBuffer<LogEvent, Bytes>>> {
+    let key = render_blob_prefix(&event, blob_prefix)?;
+    let byte_size = event.size_of();
+    encoding.apply_rules(&mut event);
+
+    Some(EncodedEvent::new(
+        PartitionInnerBuffer::new(event.into_log(), key.into()),
+        byte_size,
+    ))
+}
+
 fn build_request(
     partition: PartitionInnerBuffer<Vec<u8>, Bytes>,
     compression: Compression,
+    parquet: bool,
     container_name: String,
     blob_time_format: String,
     blob_append_uuid: bool,
@@ -332,7 +608,17 @@ fn build_request(
         }
     };
     let blob = String::from_utf8_lossy(&key[..]).into_owned();
-    let blob = format!("{}{}.{}", blob, filename, compression.extension());
+    // Parquet files compress their columns themselves.
+    let (extension, content_encoding, content_type) = if parquet {
+        ("parquet", None, PARQUET_CONTENT_TYPE)
+    } else {
+        (
+            compression.extension(),
+            compression.content_encoding(),
+            compression.content_type(),
+        )
+    };
+    let blob = format!("{}{}.{}", blob, filename, extension);
 
     debug!(
         message = "Sending events.",
@@ -345,8 +631,10 @@ fn build_request(
         container_name,
         blob_data: inner,
         blob_name: blob,
-        content_encoding: compression.content_encoding(),
-        content_type: compression.content_type(),
+        content_encoding,
+        content_type,
+        blob_type: BlobType::Block,
+        access_tier: None,
     }
 }
 
@@ -361,6 +649,25 @@ mod tests {
         crate::test_util::test_generate_config::<AzureBlobSinkConfig>();
     }
 
+    #[tokio::test]
+    async fn azure_blob_requires_one_auth() {
+        let config: AzureBlobSinkConfig = toml::from_str(
+            r#"
+            container_name = "logs"
+            encoding = "ndjson"
+            "#,
+        )
+        .unwrap();
+        assert!(config.create_client().await.is_err());
+
+        let config = AzureBlobSinkConfig {
+            connection_string: Some("UseDevelopmentStorage=true;".into()),
+            storage_account: Some("account".into()),
+            ..config
+        };
+        assert!(config.create_client().await.is_err());
+    }
+
     #[test]
     fn azure_blob_encode_event_text() {
         let message = String::from("hello world");
@@ -436,6 +743,7 @@ mod tests {
         let request = build_request(
             partition,
             compression,
+            false,
             container_name,
             blob_time_format,
             blob_append_uuid,
@@ -458,6 +766,7 @@ mod tests {
         let request = build_request(
             partition,
             compression,
+            false,
             container_name,
             blob_time_format,
             blob_append_uuid,
@@ -480,6 +789,7 @@ mod tests {
         let request = build_request(
             partition,
             compression,
+            false,
             container_name,
             blob_time_format,
             blob_append_uuid,
@@ -505,6 +815,7 @@ mod tests {
         let request = build_request(
             partition,
             compression,
+            false,
             container_name,
             blob_time_format,
             blob_append_uuid,
@@ -515,6 +826,28 @@ mod tests {
         assert_eq!(request.content_encoding, None);
         assert_eq!(request.content_type, "text/plain");
     }
+
+    #[test]
+    fn azure_blob_build_request_with_parquet() {
+        let partition = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("blob"));
+        let compression = Compression::gzip_default();
+        let container_name = String::from("logs");
+        let blob_time_format = String::from("");
+        let blob_append_uuid = false;
+
+        let request = build_request(
+            partition,
+            compression,
+            true,
+            container_name,
+            blob_time_format,
+            blob_append_uuid,
+        );
+
+        assert_eq!(request.blob_name, "blob.parquet".to_string());
+        assert_eq!(request.content_encoding, None);
+        assert_eq!(request.content_type, PARQUET_CONTENT_TYPE);
+    }
 }
 
 #[cfg(feature = "azure-blob-integration-tests")]
@@ -535,7 +868,10 @@ mod integration_tests {
     #[tokio::test]
     async fn azure_blob_healthcheck_passed() {
         let config = AzureBlobSinkConfig::new_emulator().await;
-        let client = config.create_client().expect("Failed to create client");
+        let client = config
+            .create_client()
+            .await
+            .expect("Failed to create client");
 
         let response = config.healthcheck(client).await;
 
@@ -549,7 +885,10 @@ mod integration_tests {
             container_name: String::from("other-container-name"),
             ..config
         };
-        let client = config.create_client().expect("Failed to create client");
+        let client = config
+            .create_client()
+            .await
+            .expect("Failed to create client");
 
         assert_downcast_matches!(
             config.healthcheck(client).await.unwrap_err(),
@@ -566,7 +905,7 @@ mod integration_tests {
             blob_prefix: Some(blob_prefix.clone()),
             ..config
         };
-        let sink = config.to_sink();
+        let sink = config.to_sink().await;
         let (lines, input) = random_lines_with_stream(100, 10, None);
 
         sink.run(input).await.expect("Failed to run sink");
@@ -588,7 +927,7 @@ mod integration_tests {
             encoding: Encoding::Ndjson.into(),
             ..config
         };
-        let sink = config.to_sink();
+        let sink = config.to_sink().await;
         let (events, input) = random_events_with_stream(100, 10, None);
 
         sink.run(input).await.expect("Failed to run sink");
@@ -617,7 +956,7 @@ mod integration_tests {
             compression: Compression::gzip_default(),
             ..config
         };
-        let sink = config.to_sink();
+        let sink = config.to_sink().await;
         let (lines, events) = random_lines_with_stream(100, 10, None);
 
         sink.run(events).await.expect("Failed to run sink");
@@ -646,7 +985,7 @@ mod integration_tests {
             compression: Compression::gzip_default(),
             ..config
         };
-        let sink = config.to_sink();
+        let sink = config.to_sink().await;
         let (events, input) = random_events_with_stream(100, 10, None);
 
         sink.run(input).await.expect("Failed to run sink");
@@ -679,7 +1018,7 @@ mod integration_tests {
             },
             ..config
         };
-        let sink = config.to_sink();
+        let sink = config.to_sink().await;
         let groups = 3;
         let (lines, input) = random_lines_with_stream_with_group_key(100, 30, groups);
 
@@ -703,13 +1042,18 @@ mod integration_tests {
     impl AzureBlobSinkConfig {
         pub async fn new_emulator() -> AzureBlobSinkConfig {
             let config = AzureBlobSinkConfig {
-                connection_string: String::from("UseDevelopmentStorage=true;DefaultEndpointsProtocol=http;AccountName=devstoreaccount1;AccountKey=Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==;BlobEndpoint=http://127.0.0.1:10000/devstoreaccount1;QueueEndpoint=http://127.0.0.1:10001/devstoreaccount1;TableEndpoint=http://127.0.0.1:10002/devstoreaccount1;"),
+                connection_string: Some(String::from("UseDevelopmentStorage=true;DefaultEndpointsProtocol=http;AccountName=devstoreaccount1;AccountKey=Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==;BlobEndpoint=http://127.0.0.1:10000/devstoreaccount1;QueueEndpoint=http://127.0.0.1:10001/devstoreaccount1;TableEndpoint=http://127.0.0.1:10002/devstoreaccount1;")),
+                storage_account: None,
+                client_id: None,
                 container_name: "logs".to_string(),
+                blob_type: BlobType::Block,
+                access_tier: None,
                 blob_prefix: None,
                 blob_time_format: None,
                 blob_append_uuid: None,
                 encoding: Encoding::Text.into(),
                 compression: Compression::None,
+                parquet: None,
                 batch: Default::default(),
                 request: TowerRequestConfig::default(),
             };
@@ -719,15 +1063,15 @@ mod integration_tests {
             config
         }
 
-        pub fn to_sink(&self) -> VectorSink {
+        pub async fn to_sink(&self) -> VectorSink {
             let cx = SinkContext::new_test();
-            let client = self.create_client().expect("Failed to create client");
+            let client = self.create_client().await.expect("Failed to create client");
 
             self.new(client, cx).expect("Failed to create sink")
         }
 
         pub async fn list_blobs(&self, prefix: &str) -> Vec<String> {
-            let client = self.create_client().unwrap();
+            let client = self.create_client().await.unwrap().container();
             let response = client
                 .list_blobs()
                 .prefix(prefix)
@@ -749,7 +1093,7 @@ mod integration_tests {
         }
 
         pub async fn get_blob(&self, blob: String) -> (Blob, Vec<String>) {
-            let client = self.create_client().unwrap();
+            let client = self.create_client().await.unwrap().container();
             let response = client
                 .as_blob_client(blob.as_str())
                 .get()
@@ -775,7 +1119,7 @@ mod integration_tests {
         }
 
         async fn ensure_container(&self) {
-            let client = self.create_client().unwrap();
+            let client = self.create_client().await.unwrap().container();
             let request = client.create().public_access(PublicAccess::None).execute();
 
             let response = match request.await {
@@ -0,0 +1,266 @@
+//! Azure Active Directory tokens of managed identities and workload identities.
+//!
+//! Workload identities are used when the `AZURE_FEDERATED_TOKEN_FILE`,
+//! `AZURE_TENANT_ID` and `AZURE_CLIENT_ID` environment variables are set, as
+//! they are in AKS pods using Azure AD workload identity, and managed
+//! identities from the instance metadata service otherwise.
+
+use crate::{
+    config::ProxyConfig,
+    http::{HttpClient, HttpError},
+};
+use futures::StreamExt;
+use hyper::{header::CONTENT_TYPE, StatusCode};
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio_stream::wrappers::IntervalStream;
+
+const IMDS_TOKEN_URL: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+const DEFAULT_AUTHORITY_HOST: &str = "https://login.microsoftonline.com/";
+
+#[derive(Debug, Snafu)]
+enum AzureCredentialsError {
+    #[snafu(display("Failed to build HTTP client"))]
+    BuildHttpClient { source: HttpError },
+    #[snafu(display("Failed to request Azure AD token"))]
+    RequestToken { source: HttpError },
+    #[snafu(display("Failed to read Azure AD token"))]
+    ReadToken { source: hyper::Error },
+    #[snafu(display("Azure AD token request failed with status {}: {}", status, body))]
+    TokenStatus { status: StatusCode, body: String },
+    #[snafu(display("Failed to parse Azure AD token"))]
+    ParseToken { source: serde_json::Error },
+    #[snafu(display("Failed to read federated token file {:?}", path))]
+    ReadFederatedToken {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Clone, Debug)]
+enum TokenSource {
+    ManagedIdentity {
+        client_id: Option<String>,
+    },
+    WorkloadIdentity {
+        authority_host: String,
+        tenant_id: String,
+        client_id: String,
+        token_file: PathBuf,
+    },
+}
+
+impl TokenSource {
+    fn from_env(client_id: Option<String>) -> Self {
+        let env = |name| std::env::var(name).ok();
+        match (
+            env("AZURE_FEDERATED_TOKEN_FILE"),
+            env("AZURE_TENANT_ID"),
+            client_id.clone().or_else(|| env("AZURE_CLIENT_ID")),
+        ) {
+            (Some(token_file), Some(tenant_id), Some(client_id)) => Self::WorkloadIdentity {
+                authority_host: env("AZURE_AUTHORITY_HOST")
+                    .unwrap_or_else(|| DEFAULT_AUTHORITY_HOST.into()),
+                tenant_id,
+                client_id,
+                token_file: token_file.into(),
+            },
+            _ => Self::ManagedIdentity { client_id },
+        }
+    }
+
+    fn request(&self, resource: &str) -> Result<http::Request<hyper::Body>, AzureCredentialsError> {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        Ok(match self {
+            Self::ManagedIdentity { client_id } => {
+                query
+                    .append_pair("api-version", "2018-02-01")
+                    .append_pair("resource", resource);
+                if let Some(client_id) = client_id {
+                    query.append_pair("client_id", client_id);
+                }
+                http::Request::get(format!("{}?{}", IMDS_TOKEN_URL, query.finish()))
+                    .header("Metadata", "true")
+                    .body(hyper::Body::empty())
+                    .unwrap()
+            }
+            Self::WorkloadIdentity {
+                authority_host,
+                tenant_id,
+                client_id,
+                token_file,
+            } => {
+                // The token file is rotated by the kubelet, so it's read again
+                // for every request.
+                let assertion = std::fs::read_to_string(token_file)
+                    .with_context(|| ReadFederatedToken { path: token_file })?;
+                query
+                    .append_pair(
+                        "client_assertion_type",
+                        "urn:ietf:params:oauth:client-assertion-type:jwt-bearer",
+                    )
+                    .append_pair("client_assertion", assertion.trim())
+                    .append_pair("client_id", client_id)
+                    .append_pair("grant_type", "client_credentials")
+                    .append_pair("scope", &format!("{}.default", resource));
+                http::Request::post(format!(
+                    "{}/{}/oauth2/v2.0/token",
+                    authority_host.trim_end_matches('/'),
+                    tenant_id
+                ))
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(hyper::Body::from(query.finish()))
+                .unwrap()
+            }
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    // The instance metadata service returns numbers as strings.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    expires_in: u64,
+}
+
+fn deserialize_seconds<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Number(u64),
+        String(String),
+    }
+
+    match Seconds::deserialize(deserializer)? {
+        Seconds::Number(seconds) => Ok(seconds),
+        Seconds::String(seconds) => seconds.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AzureToken {
+    access_token: String,
+    expires_in: Duration,
+}
+
+/// The Azure AD token of an identity, for a given resource.
+#[derive(Clone, Debug)]
+pub struct AzureCredentials {
+    source: TokenSource,
+    resource: String,
+    token: Arc<RwLock<AzureToken>>,
+}
+
+impl AzureCredentials {
+    /// Gets a token for `resource`, such as `https://storage.azure.com/`, with
+    /// the identity of `client_id`, or the system-assigned or only identity.
+    pub async fn new(resource: &str, client_id: Option<String>) -> crate::Result<Self> {
+        let source = TokenSource::from_env(client_id);
+        let token = get_token(&source, resource).await?;
+        Ok(Self {
+            source,
+            resource: resource.into(),
+            token: Arc::new(RwLock::new(token)),
+        })
+    }
+
+    pub fn access_token(&self) -> String {
+        self.token.read().unwrap().access_token.clone()
+    }
+
+    async fn regenerate_token(&self) -> crate::Result<()> {
+        let token = get_token(&self.source, &self.resource).await?;
+        *self.token.write().unwrap() = token;
+        Ok(())
+    }
+
+    pub fn spawn_regenerate_token(&self) {
+        let this = self.clone();
+
+        let period = (this.token.read().unwrap().expires_in / 2).max(Duration::from_secs(60));
+        let interval = IntervalStream::new(tokio::time::interval_at(
+            tokio::time::Instant::now() + period,
+            period,
+        ));
+        let task = interval.for_each(move |_| {
+            let this = this.clone();
+            async move {
+                debug!("Renewing Azure AD token.");
+                if let Err(error) = this.regenerate_token().await {
+                    error!(message = "Failed to update Azure AD token.", %error);
+                }
+            }
+        });
+        tokio::spawn(task);
+    }
+}
+
+async fn get_token(source: &TokenSource, resource: &str) -> crate::Result<AzureToken> {
+    let request = source.request(resource)?;
+
+    let proxy = ProxyConfig::from_env();
+    let response = HttpClient::new(None, &proxy)
+        .context(BuildHttpClient)?
+        .send(request)
+        .await
+        .context(RequestToken)?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .context(ReadToken)?;
+    if !status.is_success() {
+        return Err(AzureCredentialsError::TokenStatus {
+            status,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        }
+        .into());
+    }
+
+    let token: TokenResponse = serde_json::from_slice(&body).context(ParseToken)?;
+    Ok(AzureToken {
+        access_token: token.access_token,
+        expires_in: Duration::from_secs(token.expires_in),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_token_responses() {
+        let imds: TokenResponse = serde_json::from_str(
+            r#"{"access_token":"a","expires_in":"3599","expires_on":"1636000000","resource":"https://storage.azure.com/","token_type":"Bearer"}"#,
+        )
+        .unwrap();
+        assert_eq!(imds.access_token, "a");
+        assert_eq!(imds.expires_in, 3599);
+
+        let aad: TokenResponse = serde_json::from_str(
+            r#"{"token_type":"Bearer","expires_in":3599,"ext_expires_in":3599,"access_token":"b"}"#,
+        )
+        .unwrap();
+        assert_eq!(aad.access_token, "b");
+        assert_eq!(aad.expires_in, 3599);
+    }
+
+    #[test]
+    fn builds_managed_identity_request() {
+        let source = TokenSource::ManagedIdentity {
+            client_id: Some("id".into()),
+        };
+        let request = source.request("https://storage.azure.com/").unwrap();
+        assert_eq!(
+            request.uri(),
+            "http://169.254.169.254/metadata/identity/oauth2/token?api-version=2018-02-01&resource=https%3A%2F%2Fstorage.azure.com%2F&client_id=id"
+        );
+        assert_eq!(request.headers()["Metadata"], "true");
+    }
+}
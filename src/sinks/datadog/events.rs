@@ -37,6 +37,10 @@ pub struct DatadogEventsConfig {
 
     #[serde(default)]
     request: TowerRequestConfig,
+
+    /// The shared rate limiter applied to requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rate_limiter: Option<String>,
 }
 
 fn default_site() -> String {
@@ -88,7 +92,9 @@ impl DatadogEventsConfig {
         // Since we are sending only one event per request we should try to send as much
         // requests in parallel as possible.
         request_opts.concurrency = request_opts.concurrency.if_none(Concurrency::Adaptive);
-        let request_settings = request_opts.unwrap_with(&TowerRequestConfig::default());
+        let request_settings = request_opts
+            .unwrap_with(&TowerRequestConfig::default())
+            .with_shared_rate_limiter(cx.shared_rate_limiter(self.rate_limiter.as_deref())?);
 
         let tls_settings = MaybeTlsSettings::from_config(
             &Some(self.tls.clone().unwrap_or_else(TlsConfig::enabled)),
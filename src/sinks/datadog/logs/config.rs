@@ -1,5 +1,7 @@
-use super::service::LogApiRetry;
+use super::isolation::{ApiKeyIsolation, ApiKeyIsolationConfig};
+use super::service::{LogApiRequest, LogApiRetry};
 use super::sink::{DatadogLogsJsonEncoding, LogSinkBuilder};
+use super::spool::{Replayer, SpoolConfig};
 use crate::config::{DataType, GenerateConfig, SinkConfig, SinkContext};
 use crate::http::HttpClient;
 use crate::sinks::datadog::logs::healthcheck::healthcheck;
@@ -15,9 +17,12 @@ use futures::FutureExt;
 use indoc::indoc;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
+use std::fmt::Debug;
 use std::sync::Arc;
-use tower::ServiceBuilder;
+use tower::{Service, ServiceBuilder};
 use vector_core::config::proxy::ProxyConfig;
+use vector_core::event::EventStatus;
+use vector_core::stream::BatcherSettings;
 
 // The Datadog API has a hard limit of 5MB for uncompressed payloads. Above this
 // threshold the API will toss results. We previously serialized Events as they
@@ -61,6 +66,17 @@ pub struct DatadogLogsConfig {
 
     #[serde(default)]
     request: TowerRequestConfig,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_key_isolation: Option<ApiKeyIsolationConfig>,
+
+    /// The shared rate limiter applied to requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rate_limiter: Option<String>,
+
+    /// Where payloads rejected for good are written, to be replayed later.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    spool: Option<SpoolConfig>,
 }
 
 impl GenerateConfig for DatadogLogsConfig {
@@ -90,6 +106,21 @@ impl DatadogLogsConfig {
             });
         http::Uri::try_from(endpoint).expect("URI not valid")
     }
+
+    pub fn default_api_key(&self) -> &str {
+        &self.default_api_key
+    }
+
+    pub fn spool(&self) -> Option<&SpoolConfig> {
+        self.spool.as_ref()
+    }
+
+    /// Builds a replayer submitting spooled payloads with the current settings of the sink.
+    pub fn replayer(&self, proxy: &ProxyConfig) -> crate::Result<Replayer> {
+        let client = self.create_client(proxy)?;
+        let service = LogApiService::new(client, self.get_uri(), false);
+        Ok(Replayer::new(service, self.compression.unwrap_or_default()))
+    }
 }
 
 impl DatadogLogsConfig {
@@ -99,7 +130,10 @@ impl DatadogLogsConfig {
         cx: SinkContext,
     ) -> crate::Result<VectorSink> {
         let default_api_key: Arc<str> = Arc::from(self.default_api_key.clone().as_str());
-        let request_limits = self.request.unwrap_with(&Default::default());
+        let request_limits = self
+            .request
+            .unwrap_with(&Default::default())
+            .with_shared_rate_limiter(cx.shared_rate_limiter(self.rate_limiter.as_deref())?);
 
         // We forcefully cap the provided batch configuration to the size/log line limits imposed by
         // the Datadog Logs API, but we still allow them to be lowered if need be.
@@ -111,19 +145,54 @@ impl DatadogLogsConfig {
             .parse_config(limited_batch)?
             .into_batcher_settings()?;
 
-        let service = ServiceBuilder::new()
-            .settings(request_limits, LogApiRetry)
-            .service(LogApiService::new(
-                client,
-                self.get_uri(),
-                cx.globals.enterprise,
-            ));
+        let uri = self.get_uri();
+        let spool = self
+            .spool
+            .as_ref()
+            .map(|spool| spool.build(&uri, Arc::clone(&default_api_key)))
+            .transpose()?;
+        let service = LogApiService::new(client, uri, cx.globals.enterprise).with_spool(spool);
+
+        // With isolation, each API key gets its own copy of the request settings, so that
+        // one key's failures can't use up the concurrency and retries of the others.
+        match self.api_key_isolation {
+            Some(isolation) => {
+                isolation.validate()?;
+                let service = ApiKeyIsolation::new(isolation, move || {
+                    ServiceBuilder::new()
+                        .settings(request_limits.clone(), LogApiRetry)
+                        .service(service.clone())
+                });
+                Ok(self.build_sink(service, cx, default_api_key, batch))
+            }
+            None => {
+                let service = ServiceBuilder::new()
+                    .settings(request_limits, LogApiRetry)
+                    .service(service);
+                Ok(self.build_sink(service, cx, default_api_key, batch))
+            }
+        }
+    }
+
+    fn build_sink<S>(
+        &self,
+        service: S,
+        cx: SinkContext,
+        default_api_key: Arc<str>,
+        batch: BatcherSettings,
+    ) -> VectorSink
+    where
+        S: Service<LogApiRequest> + Send + 'static,
+        S::Future: Send + 'static,
+        S::Response: AsRef<EventStatus> + Send + 'static,
+        S::Error: Debug + Into<crate::Error> + Send,
+    {
         let sink = LogSinkBuilder::new(service, cx, default_api_key, batch)
             .encoding(self.encoding.clone())
             .compression(self.compression.unwrap_or_default())
             .build();
 
-        Ok(VectorSink::Stream(Box::new(sink)))
+        VectorSink::Stream(Box::new(sink))
     }
 
     pub fn build_healthcheck(&self, client: HttpClient) -> crate::Result<Healthcheck> {
@@ -0,0 +1,252 @@
+//! Isolation of the requests of each API key.
+//!
+//! Without isolation, every partition shares a single service, so requests
+//! for an API key that keeps failing hold concurrency slots and retries that
+//! the other keys then wait on. With isolation, each API key gets a service of
+//! its own, with its own concurrency limit, rate limit and retries, and a
+//! circuit breaker failing its requests fast once it has failed repeatedly.
+
+use super::service::{LogApiError, LogApiRequest, LogApiResponse};
+use crate::internal_events::{DatadogLogsApiKeyCircuitOpened, DatadogLogsApiKeyRejected};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+use tower::{util::BoxService, Service, ServiceExt};
+
+type KeyService = BoxService<LogApiRequest, LogApiResponse, crate::Error>;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyIsolationConfig {
+    /// The number of consecutive failed requests after which an API key's
+    /// requests are failed without being sent.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// How long an API key's requests are failed for once it trips.
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+const fn default_failure_threshold() -> u32 {
+    5
+}
+
+const fn default_cooldown_secs() -> u64 {
+    30
+}
+
+impl ApiKeyIsolationConfig {
+    pub(super) fn validate(&self) -> crate::Result<()> {
+        if self.failure_threshold == 0 {
+            return Err("`api_key_isolation.failure_threshold` must be at least 1".into());
+        }
+        Ok(())
+    }
+}
+
+struct KeyState {
+    service: Arc<tokio::sync::Mutex<KeyService>>,
+    failures: AtomicU32,
+    tripped_until: Mutex<Option<Instant>>,
+}
+
+impl KeyState {
+    fn is_tripped(&self) -> bool {
+        let mut tripped_until = self.tripped_until.lock().unwrap();
+        match *tripped_until {
+            Some(until) if until > Instant::now() => true,
+            Some(_) => {
+                // Let requests through again. The failure count is left just
+                // under the threshold, so one more failure trips it again.
+                *tripped_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn record(&self, succeeded: bool, failure_threshold: u32, cooldown: Duration) {
+        if succeeded {
+            self.failures.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= failure_threshold {
+            self.failures
+                .store(failure_threshold - 1, Ordering::Relaxed);
+            *self.tripped_until.lock().unwrap() = Some(Instant::now() + cooldown);
+            emit!(&DatadogLogsApiKeyCircuitOpened {
+                consecutive_failures: failures,
+                cooldown,
+            });
+        }
+    }
+}
+
+/// A service routing each request to the service of its API key, building
+/// those as new API keys come in.
+pub struct ApiKeyIsolation<F> {
+    make_service: F,
+    keys: Arc<Mutex<HashMap<Arc<str>, Arc<KeyState>>>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl<F> ApiKeyIsolation<F>
+where
+    F: Fn() -> KeyService,
+{
+    pub fn new(config: ApiKeyIsolationConfig, make_service: F) -> Self {
+        Self {
+            make_service,
+            keys: Arc::default(),
+            failure_threshold: config.failure_threshold,
+            cooldown: Duration::from_secs(config.cooldown_secs),
+        }
+    }
+
+    fn key_state(&self, api_key: &Arc<str>) -> Arc<KeyState> {
+        let mut keys = self.keys.lock().unwrap();
+        let state = keys.entry(Arc::clone(api_key)).or_insert_with(|| {
+            Arc::new(KeyState {
+                service: Arc::new(tokio::sync::Mutex::new((self.make_service)())),
+                failures: AtomicU32::new(0),
+                tripped_until: Mutex::new(None),
+            })
+        });
+        Arc::clone(state)
+    }
+}
+
+impl<F> Service<LogApiRequest> for ApiKeyIsolation<F>
+where
+    F: Fn() -> KeyService,
+{
+    type Response = LogApiResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Readiness is per API key, so it's awaited in `call` where the key is
+    // known. Waiting here would hold up every key behind the busiest one.
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: LogApiRequest) -> Self::Future {
+        let state = self.key_state(&request.api_key);
+        let failure_threshold = self.failure_threshold;
+        let cooldown = self.cooldown;
+
+        Box::pin(async move {
+            if state.is_tripped() {
+                emit!(&DatadogLogsApiKeyRejected {
+                    count: request.batch_size,
+                });
+                return Err(LogApiError::CircuitOpen.into());
+            }
+
+            let response = {
+                let mut service = Arc::clone(&state.service).lock_owned().await;
+                service.ready().await?.call(request)
+            }
+            .await;
+
+            let succeeded = matches!(response, Ok(LogApiResponse::Ok));
+            state.record(succeeded, failure_threshold, cooldown);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::util::Compression;
+    use std::sync::atomic::AtomicUsize;
+    use vector_core::event::EventFinalizers;
+
+    fn request(api_key: &str) -> LogApiRequest {
+        LogApiRequest {
+            batch_size: 1,
+            api_key: Arc::from(api_key),
+            compression: Compression::None,
+            body: Vec::new(),
+            finalizers: EventFinalizers::default(),
+            uncompressed_size: 0,
+            largest_event: None,
+        }
+    }
+
+    /// Answers requests for the key "bad" with a permission issue, and counts
+    /// the requests that made it through.
+    fn isolation(sent: Arc<AtomicUsize>) -> ApiKeyIsolation<impl Fn() -> KeyService> {
+        let config = ApiKeyIsolationConfig {
+            failure_threshold: 2,
+            cooldown_secs: 10,
+        };
+        ApiKeyIsolation::new(config, move || {
+            let sent = Arc::clone(&sent);
+            BoxService::new(tower::service_fn(move |request: LogApiRequest| {
+                sent.fetch_add(1, Ordering::Relaxed);
+                async move {
+                    Ok::<_, crate::Error>(match &*request.api_key {
+                        "bad" => LogApiResponse::PermissionIssue,
+                        _ => LogApiResponse::Ok,
+                    })
+                }
+            }))
+        })
+    }
+
+    #[tokio::test]
+    async fn failing_key_trips_alone() {
+        tokio::time::pause();
+        let sent = Arc::new(AtomicUsize::new(0));
+        let mut service = isolation(Arc::clone(&sent));
+
+        for _ in 0..2 {
+            let response = service.call(request("bad")).await.unwrap();
+            assert!(matches!(response, LogApiResponse::PermissionIssue));
+        }
+        assert_eq!(sent.load(Ordering::Relaxed), 2);
+
+        // The failing key is now tripped, and its requests aren't sent.
+        assert!(service.call(request("bad")).await.is_err());
+        assert_eq!(sent.load(Ordering::Relaxed), 2);
+
+        // Other keys aren't affected.
+        let response = service.call(request("good")).await.unwrap();
+        assert!(matches!(response, LogApiResponse::Ok));
+        assert_eq!(sent.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn tripped_key_recovers_after_cooldown() {
+        tokio::time::pause();
+        let sent = Arc::new(AtomicUsize::new(0));
+        let mut service = isolation(Arc::clone(&sent));
+
+        for _ in 0..2 {
+            service.call(request("bad")).await.unwrap();
+        }
+        assert!(service.call(request("bad")).await.is_err());
+
+        tokio::time::advance(Duration::from_secs(11)).await;
+
+        // A single request is let through, and trips it again on failure.
+        assert!(service.call(request("bad")).await.is_ok());
+        assert_eq!(sent.load(Ordering::Relaxed), 3);
+        assert!(service.call(request("bad")).await.is_err());
+        assert_eq!(sent.load(Ordering::Relaxed), 3);
+    }
+}
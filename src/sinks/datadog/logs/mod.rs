@@ -24,11 +24,14 @@ mod tests;
 
 mod config;
 mod healthcheck;
+mod isolation;
 mod service;
 mod sink;
+mod spool;
 
 use crate::config::SinkDescription;
-use crate::sinks::datadog::logs::config::DatadogLogsConfig;
+pub(crate) use crate::sinks::datadog::logs::config::DatadogLogsConfig;
+pub(crate) use crate::sinks::datadog::logs::spool::{entries as spool_entries, SpoolEntry};
 
 inventory::submit! {
     SinkDescription::new::<DatadogLogsConfig>("datadog_logs")
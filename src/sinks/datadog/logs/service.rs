@@ -1,6 +1,9 @@
+use super::spool::{RejectedPayload, Spool};
 use crate::http::HttpClient;
+use crate::internal_events::DatadogLogsPayloadTooLarge;
 use crate::sinks::util::retries::RetryLogic;
 use crate::sinks::util::Compression;
+use bytes::Bytes;
 use futures::future::BoxFuture;
 use http::header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE};
 use http::{Request, StatusCode, Uri};
@@ -24,12 +27,35 @@ impl RetryLogic for LogApiRetry {
         match *error {
             LogApiError::HttpError { .. }
             | LogApiError::BadRequest
-            | LogApiError::PayloadTooLarge => false,
+            | LogApiError::PayloadTooLarge
+            | LogApiError::CircuitOpen => false,
             LogApiError::ServerError => true,
         }
     }
 }
 
+/// The fields identifying the producer of a log event, kept to point at where oversized events
+/// come from once they have been encoded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogEventDetails {
+    pub service: Option<Bytes>,
+    pub source: Option<Bytes>,
+}
+
+impl LogEventDetails {
+    pub fn service(&self) -> Option<String> {
+        self.service
+            .as_ref()
+            .map(|service| String::from_utf8_lossy(service).into_owned())
+    }
+
+    pub fn source(&self) -> Option<String> {
+        self.source
+            .as_ref()
+            .map(|source| String::from_utf8_lossy(source).into_owned())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogApiRequest {
     pub batch_size: usize,
@@ -37,6 +63,10 @@ pub struct LogApiRequest {
     pub compression: Compression,
     pub body: Vec<u8>,
     pub finalizers: EventFinalizers,
+    /// The size of the body before compression.
+    pub uncompressed_size: usize,
+    /// The encoded size and details of the largest event in the body.
+    pub largest_event: Option<(usize, LogEventDetails)>,
 }
 
 impl Ackable for LogApiRequest {
@@ -61,6 +91,10 @@ pub enum LogApiError {
     PayloadTooLarge,
     #[snafu(display("Client request was not valid for unknown reasons."))]
     BadRequest,
+    #[snafu(display(
+        "API key failed too many requests in a row, not sending until it cools down."
+    ))]
+    CircuitOpen,
 }
 
 #[derive(Debug)]
@@ -90,6 +124,7 @@ pub struct LogApiService {
     client: HttpClient,
     uri: Uri,
     enterprise: bool,
+    spool: Option<Spool>,
 }
 
 impl LogApiService {
@@ -98,8 +133,15 @@ impl LogApiService {
             client,
             uri,
             enterprise,
+            spool: None,
         }
     }
+
+    /// Writes the payloads rejected for good to the spool.
+    pub fn with_spool(mut self, spool: Option<Spool>) -> Self {
+        self.spool = spool;
+        self
+    }
 }
 
 impl Service<LogApiRequest> for LogApiService {
@@ -132,9 +174,23 @@ impl Service<LogApiRequest> for LogApiService {
             http_request
         };
 
+        let batch_size = request.batch_size;
+        let uncompressed_size = request.uncompressed_size;
+        let largest_event = request.largest_event;
+        let body = Bytes::from(request.body);
+        let rejected = self.spool.clone().map(|spool| {
+            let payload = RejectedPayload {
+                api_key: Arc::clone(&request.api_key),
+                compression: request.compression,
+                events: batch_size,
+                uncompressed_bytes: uncompressed_size,
+                body: body.clone(),
+            };
+            (spool, payload)
+        });
         let http_request = http_request
-            .header(CONTENT_LENGTH, request.body.len())
-            .body(Body::from(request.body))
+            .header(CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
             .expect("building HTTP request failed unexpectedly");
 
         Box::pin(async move {
@@ -154,9 +210,28 @@ impl Service<LogApiRequest> for LogApiService {
                     //      time
                     match status {
                         StatusCode::BAD_REQUEST => Err(LogApiError::BadRequest),
-                        StatusCode::FORBIDDEN => Ok(LogApiResponse::PermissionIssue),
+                        StatusCode::FORBIDDEN => {
+                            if let Some((spool, payload)) = rejected {
+                                spool.write(status, payload).await;
+                            }
+                            Ok(LogApiResponse::PermissionIssue)
+                        }
                         StatusCode::OK | StatusCode::ACCEPTED => Ok(LogApiResponse::Ok),
-                        StatusCode::PAYLOAD_TOO_LARGE => Err(LogApiError::PayloadTooLarge),
+                        StatusCode::PAYLOAD_TOO_LARGE => {
+                            let (largest_event_byte_size, details) =
+                                largest_event.unwrap_or_default();
+                            emit!(&DatadogLogsPayloadTooLarge {
+                                service: details.service().as_deref(),
+                                source: details.source().as_deref(),
+                                largest_event_byte_size,
+                                payload_byte_size: uncompressed_size,
+                                count: batch_size,
+                            });
+                            if let Some((spool, payload)) = rejected {
+                                spool.write(status, payload).await;
+                            }
+                            Err(LogApiError::PayloadTooLarge)
+                        }
                         _ => Err(LogApiError::ServerError),
                     }
                 }
@@ -1,20 +1,23 @@
-use super::config::MAX_PAYLOAD_BYTES;
-use super::service::LogApiRequest;
+use super::config::{BATCH_MAX_EVENTS, MAX_PAYLOAD_BYTES};
+use super::service::{LogApiRequest, LogEventDetails};
 use crate::config::SinkContext;
+use crate::internal_events::{DatadogLogsEventTooLarge, DatadogLogsRequestEncoded};
 use crate::sinks::util::encoding::{Encoder, EncodingConfigFixed, StandardEncodings};
-use crate::sinks::util::{Compression, Compressor, RequestBuilder, SinkBuilderExt};
+use crate::sinks::util::{
+    Compression, EncodedPayload, IncrementalRequestBuilder, PayloadFraming, RequestLimits,
+    SinkBuilderExt,
+};
 use async_trait::async_trait;
 use futures::stream::BoxStream;
 use futures::StreamExt;
-use snafu::Snafu;
 use std::fmt::Debug;
-use std::io::{self, Write};
+use std::io;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use tower::Service;
 use vector_core::buffers::Acker;
 use vector_core::config::{log_schema, LogSchema};
-use vector_core::event::{Event, EventFinalizers, EventStatus, Finalizable, Value};
+use vector_core::event::{Event, EventStatus, Value};
 use vector_core::partition::Partitioner;
 use vector_core::sink::StreamSink;
 use vector_core::stream::BatcherSettings;
@@ -117,32 +120,21 @@ impl Default for DatadogLogsJsonEncoding {
     }
 }
 
-impl Encoder<Vec<Event>> for DatadogLogsJsonEncoding {
-    fn encode_input(&self, mut input: Vec<Event>, writer: &mut dyn io::Write) -> io::Result<usize> {
-        for event in input.iter_mut() {
-            let log = event.as_mut_log();
-            log.rename_key_flat(self.log_schema.message_key(), "message");
-            log.rename_key_flat(self.log_schema.host_key(), "host");
-            if let Some(Value::Timestamp(ts)) = log.remove(self.log_schema.timestamp_key()) {
-                log.insert_flat("timestamp", Value::Integer(ts.timestamp_millis()));
-            }
+impl DatadogLogsJsonEncoding {
+    fn normalize(&self, event: &mut Event) {
+        let log = event.as_mut_log();
+        log.rename_key_flat(self.log_schema.message_key(), "message");
+        log.rename_key_flat(self.log_schema.host_key(), "host");
+        if let Some(Value::Timestamp(ts)) = log.remove(self.log_schema.timestamp_key()) {
+            log.insert_flat("timestamp", Value::Integer(ts.timestamp_millis()));
         }
-
-        self.inner.encode_input(input, writer)
     }
 }
 
-#[derive(Debug, Snafu)]
-pub enum RequestBuildError {
-    #[snafu(display("Encoded payload is greater than the max limit."))]
-    PayloadTooBig,
-    #[snafu(display("Failed to build payload with error: {}", error))]
-    Io { error: std::io::Error },
-}
-
-impl From<io::Error> for RequestBuildError {
-    fn from(error: io::Error) -> RequestBuildError {
-        RequestBuildError::Io { error }
+impl Encoder<Event> for DatadogLogsJsonEncoding {
+    fn encode_input(&self, mut input: Event, writer: &mut dyn io::Write) -> io::Result<usize> {
+        self.normalize(&mut input);
+        self.inner.encode_input(input, writer)
     }
 }
 
@@ -152,56 +144,81 @@ struct LogRequestBuilder {
     compression: Compression,
 }
 
-impl RequestBuilder<(Option<Arc<str>>, Vec<Event>)> for LogRequestBuilder {
-    type Metadata = (Arc<str>, usize, EventFinalizers);
-    type Events = Vec<Event>;
-    type Encoder = EncodingConfigFixed<DatadogLogsJsonEncoding>;
-    type Payload = Vec<u8>;
+impl IncrementalRequestBuilder<(Option<Arc<str>>, Vec<Event>)> for LogRequestBuilder {
+    type Metadata = Arc<str>;
+    type Event = Event;
     type Request = LogApiRequest;
-    type Error = RequestBuildError;
+    type Error = io::Error;
+    type EventDetails = LogEventDetails;
 
     fn compression(&self) -> Compression {
         self.compression
     }
 
-    fn encoder(&self) -> &Self::Encoder {
-        &self.encoding
+    // The Datadog Logs API has a limit on uncompressed data, which we can only know we respect
+    // once events are encoded, so requests are split as needed while encoding.
+    fn limits(&self) -> RequestLimits {
+        RequestLimits {
+            max_bytes: MAX_PAYLOAD_BYTES,
+            max_events: BATCH_MAX_EVENTS,
+        }
     }
 
-    fn split_input(&self, input: (Option<Arc<str>>, Vec<Event>)) -> (Self::Metadata, Self::Events) {
-        let (api_key, mut events) = input;
-        let events_len = events.len();
-        let finalizers = events.take_finalizers();
+    fn framing(&self) -> PayloadFraming {
+        PayloadFraming::JSON_ARRAY
+    }
 
+    fn split_input(&self, input: (Option<Arc<str>>, Vec<Event>)) -> (Arc<str>, Vec<Event>) {
+        let (api_key, events) = input;
         let api_key = api_key.unwrap_or_else(|| Arc::clone(&self.default_api_key));
-        ((api_key, events_len, finalizers), events)
+        (api_key, events)
     }
 
-    fn encode_events(&self, events: Self::Events) -> Result<Self::Payload, Self::Error> {
-        // We need to first serialize the payload separately so that we can figure out how big it is
-        // before compression.  The Datadog Logs API has a limit on uncompressed data, so we can't
-        // use the default implementation of this method.
-        let mut buf = Vec::new();
-        let n = self.encoder().encode_input(events, &mut buf)?;
-        if n > MAX_PAYLOAD_BYTES {
-            return Err(RequestBuildError::PayloadTooBig);
+    // Only the reserved attributes the Logs API attributes logs with are kept, the source falling
+    // back to the type of the Vector source the event came from.
+    fn event_details(&self, event: &Event) -> LogEventDetails {
+        let log = event.as_log();
+        LogEventDetails {
+            service: log.get_flat("service").map(Value::as_bytes),
+            source: log
+                .get_flat("ddsource")
+                .or_else(|| log.get(log_schema().source_type_key()))
+                .map(Value::as_bytes),
         }
+    }
 
-        // Now just compress it like normal.
-        let mut compressor = Compressor::from(self.compression);
-        let _ = compressor.write_all(&buf)?;
+    fn encode_event(&self, event: Event, writer: &mut Vec<u8>) -> io::Result<()> {
+        self.encoding.encode_input(event, writer).map(|_| ())
+    }
 
-        Ok(compressor.into_inner())
+    fn event_too_large(&self, details: LogEventDetails, length: usize, max_length: usize) {
+        emit!(&DatadogLogsEventTooLarge {
+            service: details.service().as_deref(),
+            source: details.source().as_deref(),
+            byte_size: length,
+            max_bytes: max_length,
+        });
     }
 
-    fn build_request(&self, metadata: Self::Metadata, payload: Self::Payload) -> Self::Request {
-        let (api_key, batch_size, finalizers) = metadata;
+    fn build_request(
+        &self,
+        api_key: Arc<str>,
+        payload: EncodedPayload<LogEventDetails>,
+    ) -> LogApiRequest {
+        emit!(&DatadogLogsRequestEncoded {
+            events: payload.events_len,
+            uncompressed_bytes: payload.uncompressed_len,
+            compressed_bytes: payload.body.len(),
+            duration: payload.encoding_duration,
+        });
         LogApiRequest {
-            batch_size,
+            batch_size: payload.events_len,
             api_key,
             compression: self.compression,
-            body: payload,
-            finalizers,
+            body: payload.body,
+            finalizers: payload.finalizers,
+            uncompressed_size: payload.uncompressed_len,
+            largest_event: payload.largest_event,
         }
     }
 }
@@ -227,7 +244,7 @@ where
 
         let sink = input
             .batched(partitioner, self.batch_settings)
-            .request_builder(builder_limit, request_builder)
+            .incremental_request_builder(builder_limit, request_builder)
             .filter_map(|request| async move {
                 match request {
                     Err(e) => {
@@ -255,3 +272,64 @@ where
         self.run_inner(input).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> LogRequestBuilder {
+        LogRequestBuilder {
+            default_api_key: Arc::from("default"),
+            encoding: Default::default(),
+            compression: Compression::None,
+        }
+    }
+
+    fn log_event(message: &str, service: &str) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert_flat("service", service);
+        event.as_mut_log().insert_flat("ddsource", "nginx");
+        event
+    }
+
+    #[test]
+    fn captures_event_details() {
+        let mut event = Event::from("message");
+        event
+            .as_mut_log()
+            .insert(log_schema().source_type_key(), "file");
+        assert_eq!(
+            builder().event_details(&event),
+            LogEventDetails {
+                service: None,
+                source: Some("file".into()),
+            }
+        );
+
+        assert_eq!(
+            builder().event_details(&log_event("message", "api")),
+            LogEventDetails {
+                service: Some("api".into()),
+                source: Some("nginx".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn requests_carry_their_largest_event() {
+        let events = vec![
+            log_event("short", "api"),
+            log_event(&"long".repeat(10), "billing"),
+            log_event("tiny", "web"),
+        ];
+
+        let requests = builder().build_requests((None, events));
+        assert_eq!(requests.len(), 1);
+        let request = requests.into_iter().next().unwrap().unwrap();
+        assert_eq!(request.batch_size, 3);
+        assert_eq!(request.uncompressed_size, request.body.len());
+        let (_, details) = request.largest_event.unwrap();
+        assert_eq!(details.service(), Some("billing".to_owned()));
+        assert_eq!(details.source(), Some("nginx".to_owned()));
+    }
+}
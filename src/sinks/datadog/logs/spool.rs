@@ -0,0 +1,574 @@
+//! Spooling of the payloads the Logs API rejected for good.
+//!
+//! Payloads rejected with a `403`, as their API key isn't valid, or with a `413`, as they are too
+//! large, are rejected again however many times they are retried. With a spool configured, they
+//! are written to its directory along with metadata describing them instead of being lost, so that
+//! `vector replay` can submit them again once the configuration is fixed.
+//!
+//! Each payload is written as `<id>.payload`, holding its body as it was sent, and `<id>.json`,
+//! holding its metadata. The metadata is written last, so payloads without it weren't written
+//! completely and are ignored.
+
+use super::config::{BATCH_MAX_EVENTS, MAX_PAYLOAD_BYTES};
+use super::service::{LogApiRequest, LogApiResponse, LogApiService};
+use crate::internal_events::{
+    DatadogLogsPayloadSpooled, DatadogLogsSpoolFull, DatadogLogsSpoolWriteFailed,
+};
+use crate::sinks::util::{Compression, Compressor};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use flate2::read::MultiGzDecoder;
+use http::{StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tower::Service;
+use vector_core::event::EventFinalizers;
+
+const PAYLOAD_EXTENSION: &str = "payload";
+const METADATA_EXTENSION: &str = "json";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpoolConfig {
+    path: PathBuf,
+    #[serde(default = "default_max_bytes")]
+    max_bytes: u64,
+}
+
+const fn default_max_bytes() -> u64 {
+    1024 * 1024 * 1024
+}
+
+impl SpoolConfig {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn build(&self, endpoint: &Uri, default_api_key: Arc<str>) -> crate::Result<Spool> {
+        if self.max_bytes == 0 {
+            return Err("`spool.max_bytes` must be greater than 0".into());
+        }
+        fs::create_dir_all(&self.path).map_err(|error| {
+            format!(
+                "Unable to create the spool directory {:?}: {}",
+                self.path, error
+            )
+        })?;
+        let used_bytes = spool_size(&self.path)?;
+
+        Ok(Spool {
+            inner: Arc::new(Inner {
+                dir: self.path.clone(),
+                max_bytes: self.max_bytes,
+                used_bytes: Mutex::new(used_bytes),
+                sequence: AtomicU64::new(0),
+                endpoint: endpoint.to_string(),
+                default_api_key,
+            }),
+        })
+    }
+}
+
+/// The metadata written along with a spooled payload.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpooledMetadata {
+    /// The status the payload was rejected with.
+    pub status: u16,
+    pub rejected_at: DateTime<Utc>,
+    pub endpoint: String,
+    /// Whether the payload was sent with the default API key of the sink, rather than with one
+    /// set on its events.
+    pub default_api_key: bool,
+    /// The end of the API key the payload was sent with, to tell keys apart without writing them
+    /// to disk.
+    pub api_key_suffix: String,
+    pub content_encoding: Option<String>,
+    pub events: usize,
+    pub uncompressed_bytes: usize,
+}
+
+/// A payload rejected for good, as it was sent.
+#[derive(Debug)]
+pub struct RejectedPayload {
+    pub api_key: Arc<str>,
+    pub compression: Compression,
+    pub events: usize,
+    pub uncompressed_bytes: usize,
+    pub body: Bytes,
+}
+
+#[derive(Clone, Debug)]
+pub struct Spool {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    dir: PathBuf,
+    max_bytes: u64,
+    used_bytes: Mutex<u64>,
+    sequence: AtomicU64,
+    endpoint: String,
+    default_api_key: Arc<str>,
+}
+
+impl Spool {
+    /// Writes a rejected payload to the spool, unless it would grow the spool beyond its maximum
+    /// size.
+    pub async fn write(&self, status: StatusCode, payload: RejectedPayload) {
+        let metadata = SpooledMetadata {
+            status: status.as_u16(),
+            rejected_at: Utc::now(),
+            endpoint: self.inner.endpoint.clone(),
+            default_api_key: payload.api_key == self.inner.default_api_key,
+            api_key_suffix: api_key_suffix(&payload.api_key).to_owned(),
+            content_encoding: payload.compression.content_encoding().map(Into::into),
+            events: payload.events,
+            uncompressed_bytes: payload.uncompressed_bytes,
+        };
+
+        match self.try_write(&metadata, &payload.body).await {
+            Ok(true) => emit!(&DatadogLogsPayloadSpooled {
+                status: metadata.status,
+                count: metadata.events,
+                byte_size: payload.body.len(),
+            }),
+            Ok(false) => emit!(&DatadogLogsSpoolFull {
+                count: metadata.events,
+                max_bytes: self.inner.max_bytes,
+            }),
+            Err(error) => emit!(&DatadogLogsSpoolWriteFailed {
+                error,
+                count: metadata.events,
+            }),
+        }
+    }
+
+    async fn try_write(&self, metadata: &SpooledMetadata, body: &[u8]) -> io::Result<bool> {
+        let metadata_json = serde_json::to_vec_pretty(metadata)?;
+        let size = (body.len() + metadata_json.len()) as u64;
+        if !self.reserve(size)? {
+            return Ok(false);
+        }
+
+        let id = format!(
+            "{}-{}-{:06}",
+            metadata.rejected_at.format("%Y%m%dT%H%M%S%.6fZ"),
+            std::process::id(),
+            self.inner.sequence.fetch_add(1, Ordering::Relaxed)
+        );
+        let payload_path = self.inner.dir.join(format!("{}.{}", id, PAYLOAD_EXTENSION));
+        let metadata_path = self
+            .inner
+            .dir
+            .join(format!("{}.{}", id, METADATA_EXTENSION));
+        let partial_path = self.inner.dir.join(format!("{}.partial", id));
+
+        let result = async {
+            tokio::fs::write(&payload_path, body).await?;
+            tokio::fs::write(&partial_path, &metadata_json).await?;
+            tokio::fs::rename(&partial_path, &metadata_path).await
+        }
+        .await;
+
+        if let Err(error) = result {
+            let _ = tokio::fs::remove_file(&payload_path).await;
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            let mut used_bytes = self.inner.used_bytes.lock().expect("poisoned lock");
+            *used_bytes = used_bytes.saturating_sub(size);
+            return Err(error);
+        }
+        Ok(true)
+    }
+
+    fn reserve(&self, size: u64) -> io::Result<bool> {
+        let mut used_bytes = self.inner.used_bytes.lock().expect("poisoned lock");
+        if *used_bytes + size > self.inner.max_bytes {
+            // Payloads may have been replayed since the spool was measured.
+            *used_bytes = spool_size(&self.inner.dir)?;
+            if *used_bytes + size > self.inner.max_bytes {
+                return Ok(false);
+            }
+        }
+        *used_bytes += size;
+        Ok(true)
+    }
+}
+
+fn spool_size(dir: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+fn api_key_suffix(api_key: &str) -> &str {
+    api_key
+        .get(api_key.len().saturating_sub(4)..)
+        .unwrap_or_default()
+}
+
+/// The metadata files of the payloads in a spool, oldest first.
+pub fn entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension().and_then(|ext| ext.to_str()) == Some(METADATA_EXTENSION)
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// A payload written to a spool.
+#[derive(Debug)]
+pub struct SpoolEntry {
+    metadata_path: PathBuf,
+    pub metadata: SpooledMetadata,
+}
+
+impl SpoolEntry {
+    pub fn load(metadata_path: &Path) -> io::Result<Self> {
+        let metadata = serde_json::from_slice(&fs::read(metadata_path)?)?;
+        Ok(Self {
+            metadata_path: metadata_path.to_owned(),
+            metadata,
+        })
+    }
+
+    pub fn id(&self) -> Cow<'_, str> {
+        self.metadata_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+    }
+
+    fn payload_path(&self) -> PathBuf {
+        self.metadata_path.with_extension(PAYLOAD_EXTENSION)
+    }
+
+    fn read_payload(&self) -> io::Result<Vec<u8>> {
+        fs::read(self.payload_path())
+    }
+
+    /// Replaces the payload with the events that are left to replay.
+    fn replace_payload(&mut self, events: &[Vec<u8>]) -> io::Result<()> {
+        let body = json_array(events);
+        self.metadata.content_encoding = None;
+        self.metadata.events = events.len();
+        self.metadata.uncompressed_bytes = body.len();
+
+        fs::write(self.payload_path(), &body)?;
+        fs::write(
+            &self.metadata_path,
+            serde_json::to_vec_pretty(&self.metadata)?,
+        )
+    }
+
+    pub fn remove(self) -> io::Result<()> {
+        fs::remove_file(&self.metadata_path)?;
+        fs::remove_file(self.payload_path())
+    }
+}
+
+/// Submits spooled payloads to the Logs API again.
+///
+/// The events of each payload are decoded and sent again in as many requests as the payload size
+/// limits require, so that payloads rejected for being too large go through once replayed.
+pub struct Replayer {
+    service: LogApiService,
+    compression: Compression,
+}
+
+impl Replayer {
+    pub const fn new(service: LogApiService, compression: Compression) -> Self {
+        Self {
+            service,
+            compression,
+        }
+    }
+
+    /// Replays the payload of an entry with the given API key, removing the entry once all of its
+    /// events were accepted, and returning their number. Should only some of them be accepted, the
+    /// entry is left with those that weren't.
+    pub async fn replay(&mut self, mut entry: SpoolEntry, api_key: &str) -> crate::Result<usize> {
+        let body = entry.read_payload()?;
+        let events = decode_events(
+            &body,
+            entry.metadata.content_encoding.as_deref(),
+            self.compression,
+        )?;
+        let count = events.len();
+        let chunks = chunk_events(&events)?;
+
+        let api_key = Arc::from(api_key);
+        let mut sent = 0;
+        for chunk in chunks {
+            let uncompressed = json_array(chunk);
+            let mut compressor = Compressor::from(self.compression);
+            compressor.write_all(&uncompressed)?;
+            let request = LogApiRequest {
+                batch_size: chunk.len(),
+                api_key: Arc::clone(&api_key),
+                compression: self.compression,
+                body: compressor.into_inner(),
+                finalizers: EventFinalizers::default(),
+                uncompressed_size: uncompressed.len(),
+                largest_event: None,
+            };
+
+            let error: crate::Error = match self.service.call(request).await {
+                Ok(LogApiResponse::Ok) => {
+                    sent += chunk.len();
+                    continue;
+                }
+                Ok(LogApiResponse::PermissionIssue) => "The API key was rejected.".into(),
+                Err(error) => error.into(),
+            };
+            if sent > 0 {
+                entry.replace_payload(&events[sent..])?;
+            }
+            return Err(error);
+        }
+
+        entry.remove()?;
+        Ok(count)
+    }
+}
+
+/// Decodes the events of a payload, each as its own JSON document.
+fn decode_events(
+    body: &[u8],
+    content_encoding: Option<&str>,
+    compression: Compression,
+) -> crate::Result<Vec<Vec<u8>>> {
+    let body = match content_encoding {
+        None => Cow::Borrowed(body),
+        Some("gzip") => {
+            let mut decoded = Vec::new();
+            MultiGzDecoder::new(body).read_to_end(&mut decoded)?;
+            Cow::Owned(decoded)
+        }
+        Some("zstd") => {
+            let mut decoded = Vec::new();
+            match compression {
+                Compression::Zstd {
+                    dictionary: Some(dictionary),
+                    ..
+                } => zstd::stream::read::Decoder::with_dictionary(body, dictionary.as_bytes())?
+                    .read_to_end(&mut decoded)?,
+                _ => zstd::stream::read::Decoder::new(body)?.read_to_end(&mut decoded)?,
+            };
+            Cow::Owned(decoded)
+        }
+        Some(encoding) => {
+            return Err(format!("Unsupported content encoding {:?}.", encoding).into())
+        }
+    };
+
+    let events: Vec<Box<serde_json::value::RawValue>> = serde_json::from_slice(&body)?;
+    Ok(events
+        .into_iter()
+        .map(|event| event.get().as_bytes().to_vec())
+        .collect())
+}
+
+/// Splits events into chunks that fit in a payload.
+fn chunk_events(events: &[Vec<u8>]) -> crate::Result<Vec<&[Vec<u8>]>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    // The brackets of the array.
+    let mut size = 2;
+    for (index, event) in events.iter().enumerate() {
+        if event.len() + 2 > MAX_PAYLOAD_BYTES {
+            return Err(format!(
+                "An event of {} bytes is larger than the maximum payload size.",
+                event.len()
+            )
+            .into());
+        }
+        // Each event but the first is preceded by a comma.
+        let event_size = event.len() + usize::from(index > start);
+        if size + event_size > MAX_PAYLOAD_BYTES || index - start == BATCH_MAX_EVENTS {
+            chunks.push(&events[start..index]);
+            start = index;
+            size = 2 + event.len();
+        } else {
+            size += event_size;
+        }
+    }
+    if start < events.len() {
+        chunks.push(&events[start..]);
+    }
+    Ok(chunks)
+}
+
+fn json_array(events: &[Vec<u8>]) -> Vec<u8> {
+    let mut body =
+        Vec::with_capacity(events.iter().map(|event| event.len() + 1).sum::<usize>() + 2);
+    body.push(b'[');
+    for (index, event) in events.iter().enumerate() {
+        if index > 0 {
+            body.push(b',');
+        }
+        body.extend_from_slice(event);
+    }
+    body.push(b']');
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spool(dir: &Path, max_bytes: u64) -> Spool {
+        SpoolConfig {
+            path: dir.to_owned(),
+            max_bytes,
+        }
+        .build(
+            &Uri::from_static("https://example.com/"),
+            Arc::from("default-1234"),
+        )
+        .unwrap()
+    }
+
+    fn payload(api_key: &str, compression: Compression, events: &[&str]) -> RejectedPayload {
+        let uncompressed = json_array(
+            &events
+                .iter()
+                .map(|event| event.as_bytes().to_vec())
+                .collect::<Vec<_>>(),
+        );
+        let mut compressor = Compressor::from(compression);
+        compressor.write_all(&uncompressed).unwrap();
+        RejectedPayload {
+            api_key: Arc::from(api_key),
+            compression,
+            events: events.len(),
+            uncompressed_bytes: uncompressed.len(),
+            body: compressor.into_inner().into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_rejected_payloads_with_their_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool = spool(dir.path(), default_max_bytes());
+
+        let rejected = payload(
+            "default-1234",
+            Compression::gzip_default(),
+            &[r#"{"message":"a"}"#, r#"{"message":"b"}"#],
+        );
+        let body = rejected.body.clone();
+        spool.write(StatusCode::FORBIDDEN, rejected).await;
+        spool
+            .write(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                payload("event-5678", Compression::None, &["{}"]),
+            )
+            .await;
+
+        let entries = entries(dir.path())
+            .unwrap()
+            .iter()
+            .map(|path| SpoolEntry::load(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+
+        let metadata = &entries[0].metadata;
+        assert_eq!(metadata.status, 403);
+        assert_eq!(metadata.endpoint, "https://example.com/");
+        assert!(metadata.default_api_key);
+        assert_eq!(metadata.api_key_suffix, "1234");
+        assert_eq!(metadata.content_encoding.as_deref(), Some("gzip"));
+        assert_eq!(metadata.events, 2);
+        assert_eq!(entries[0].read_payload().unwrap(), body);
+
+        let metadata = &entries[1].metadata;
+        assert_eq!(metadata.status, 413);
+        assert!(!metadata.default_api_key);
+        assert_eq!(metadata.api_key_suffix, "5678");
+        assert_eq!(metadata.content_encoding, None);
+    }
+
+    #[tokio::test]
+    async fn drops_payloads_beyond_the_maximum_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool = spool(dir.path(), 1024);
+
+        let events = [r#"{"message":"hello"}"#; 100];
+        spool
+            .write(
+                StatusCode::FORBIDDEN,
+                payload("default-1234", Compression::None, &events),
+            )
+            .await;
+        assert!(entries(dir.path()).unwrap().is_empty());
+
+        spool
+            .write(
+                StatusCode::FORBIDDEN,
+                payload("default-1234", Compression::None, &events[..2]),
+            )
+            .await;
+        assert_eq!(entries(dir.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn decodes_spooled_events() {
+        let rejected = payload(
+            "default-1234",
+            Compression::gzip_default(),
+            &[r#"{"message":"a"}"#, r#"{"message":"b"}"#],
+        );
+        let events = decode_events(&rejected.body, Some("gzip"), Compression::None).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                br#"{"message":"a"}"#.to_vec(),
+                br#"{"message":"b"}"#.to_vec()
+            ]
+        );
+        assert!(decode_events(b"[]", Some("br"), Compression::None).is_err());
+    }
+
+    #[test]
+    fn chunks_events_to_the_payload_limits() {
+        let events = vec![b"{}".to_vec(); BATCH_MAX_EVENTS + 1];
+        let chunks = chunk_events(&events).unwrap();
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+            vec![BATCH_MAX_EVENTS, 1]
+        );
+
+        let event = vec![b'x'; MAX_PAYLOAD_BYTES / 2];
+        let events = vec![event.clone(), event.clone(), event];
+        let chunks = chunk_events(&events).unwrap();
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+            vec![1, 1, 1]
+        );
+        for chunk in chunks {
+            assert!(json_array(chunk).len() <= MAX_PAYLOAD_BYTES);
+        }
+
+        assert!(chunk_events(&[vec![b'x'; MAX_PAYLOAD_BYTES]]).is_err());
+    }
+}
@@ -4,21 +4,25 @@ use crate::{
     event::metric::{Metric, MetricKind, MetricValue, Sample, StatisticKind},
     event::Event,
     http::HttpClient,
+    internal_events::TemplateRenderingFailed,
     sinks::{
         util::{
             batch::{BatchConfig, BatchSettings},
             buffer::metrics::{MetricNormalize, MetricNormalizer, MetricSet, MetricsBuffer},
             encode_namespace,
             http::{HttpBatchService, HttpRetryLogic},
-            EncodedEvent, PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer,
-            TowerRequestConfig,
+            statistic::{validate_quantile_error, validate_quantiles},
+            EncodedEvent, MultiValueTagsConfig, PartitionBatchSink, PartitionBuffer,
+            PartitionInnerBuffer, TowerRequestConfig,
         },
         Healthcheck, UriParseError, VectorSink,
     },
+    template::Template,
 };
 use chrono::{DateTime, Utc};
 use futures::{stream, FutureExt, SinkExt};
 use http::{uri::InvalidUri, Request, Uri};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
@@ -56,8 +60,33 @@ pub struct DatadogConfig {
     pub batch: BatchConfig,
     #[serde(default)]
     pub request: TowerRequestConfig,
+    /// The shared rate limiter applied to requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limiter: Option<String>,
+    #[serde(default)]
+    pub multi_value_tags: MultiValueTagsConfig,
+    pub source_type_name: Option<String>,
+    /// Tags added to every series, unless the metric already has them.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub tags: IndexMap<String, Template>,
+    /// Tags added to the series of the given namespaces, taking precedence
+    /// over the ones in `tags`.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub namespace_tags: IndexMap<String, IndexMap<String, Template>>,
+    /// The quantiles sent for histograms, as `<quantile>percentile` series.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantiles: Option<Vec<f64>>,
+    #[serde(default)]
+    pub quantile_error: f64,
+    /// Sends histogram distributions to the distribution endpoint as raw
+    /// samples, like summary ones, rather than as series of statistics, and
+    /// leaves the samples of all distributions unmerged.
+    #[serde(default)]
+    pub distribution_passthrough: bool,
 }
 
+const DEFAULT_QUANTILES: &[f64] = &[0.95];
+
 struct DatadogSink {
     config: DatadogConfig,
     /// Endpoint -> (uri_path, last_sent_timestamp)
@@ -112,6 +141,8 @@ struct DatadogDistributionMetric {
     interval: Option<i64>,
     points: Vec<DatadogPoint<Vec<f64>>>,
     tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_type_name: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -121,6 +152,8 @@ struct DatadogMetric {
     interval: Option<i64>,
     points: Vec<DatadogPoint<f64>>,
     tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_type_name: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -162,12 +195,16 @@ impl DatadogEndpoint {
         ])
     }
 
-    fn from_metric(metric: &Metric) -> Self {
+    fn from_metric(metric: &Metric, distribution_passthrough: bool) -> Self {
         match metric.value() {
             MetricValue::Distribution {
                 statistic: StatisticKind::Summary,
                 ..
             } => Self::Distribution,
+            MetricValue::Distribution {
+                statistic: StatisticKind::Histogram,
+                ..
+            } if distribution_passthrough => Self::Distribution,
             _ => Self::Series,
         }
     }
@@ -183,6 +220,11 @@ impl_generate_config_from_default!(DatadogConfig);
 #[typetag::serde(name = "datadog_metrics")]
 impl SinkConfig for DatadogConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if let Some(quantiles) = &self.quantiles {
+            validate_quantiles(quantiles)?;
+        }
+        validate_quantile_error(self.quantile_error)?;
+
         let client = HttpClient::new(None, cx.proxy())?;
         let healthcheck = healthcheck(
             self.get_api_endpoint(),
@@ -195,10 +237,13 @@ impl SinkConfig for DatadogConfig {
             .events(20)
             .timeout(1)
             .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&TowerRequestConfig {
-            retry_attempts: Some(5),
-            ..Default::default()
-        });
+        let request = self
+            .request
+            .unwrap_with(&TowerRequestConfig {
+                retry_attempts: Some(5),
+                ..Default::default()
+            })
+            .with_shared_rate_limiter(cx.shared_rate_limiter(self.rate_limiter.as_deref())?);
 
         let uri = DatadogEndpoint::build_uri(&self.get_endpoint())?;
         let timestamp = Utc::now().timestamp();
@@ -216,13 +261,25 @@ impl SinkConfig for DatadogConfig {
             HttpBatchService::new(client, move |request| ready(sink.build_request(request))),
         );
 
-        let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
+        let distribution_passthrough = self.distribution_passthrough;
+        let quantile_error = if distribution_passthrough {
+            0.0
+        } else {
+            self.quantile_error
+        };
+        let buffer = PartitionBuffer::new(
+            MetricsBuffer::new(batch.size).with_quantile_error(quantile_error),
+        );
         let mut normalizer = MetricNormalizer::<DatadogMetricNormalize>::default();
 
         let svc_sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
             .sink_map_err(|error| error!(message = "Fatal datadog metric sink error.", %error))
             .with_flat_map(move |event: Event| {
-                stream::iter(normalizer.apply(event).map(encode_metric))
+                stream::iter(
+                    normalizer
+                        .apply(event)
+                        .map(|metric| encode_metric(metric, distribution_passthrough)),
+                )
             });
 
         Ok((VectorSink::Sink(Box::new(svc_sink)), healthcheck))
@@ -239,8 +296,9 @@ impl SinkConfig for DatadogConfig {
 
 fn encode_metric(
     mut metric: Metric,
+    distribution_passthrough: bool,
 ) -> Result<EncodedEvent<PartitionInnerBuffer<Metric, DatadogEndpoint>>, ()> {
-    let endpoint = DatadogEndpoint::from_metric(&metric);
+    let endpoint = DatadogEndpoint::from_metric(&metric, distribution_passthrough);
     let byte_size = metric.size_of();
     let finalizers = metric.metadata_mut().take_finalizers();
     let item = PartitionInnerBuffer::new(metric, endpoint);
@@ -268,16 +326,11 @@ impl DatadogSink {
 
         let body = match endpoint {
             DatadogEndpoint::Series => {
-                let input =
-                    encode_events(events, self.config.default_namespace.as_deref(), interval);
+                let input = encode_events(events, interval, &self.config);
                 serde_json::to_vec(&input).unwrap()
             }
             DatadogEndpoint::Distribution => {
-                let input = encode_distribution_events(
-                    events,
-                    self.config.default_namespace.as_deref(),
-                    interval,
-                );
+                let input = encode_distribution_events(events, interval, &self.config);
                 serde_json::to_vec(&input).unwrap()
             }
         };
@@ -298,13 +351,43 @@ fn build_uri(host: &str, endpoint: &'static str) -> crate::Result<Uri> {
     Ok(uri)
 }
 
-fn encode_tags(tags: &BTreeMap<String, String>) -> Vec<String> {
-    let mut pairs: Vec<_> = tags
-        .iter()
+fn encode_tags(
+    metric: &Metric,
+    namespace: Option<&str>,
+    config: &DatadogConfig,
+) -> Option<Vec<String>> {
+    let mut pairs: Vec<_> = config
+        .multi_value_tags
+        .encode(metric)
+        .into_iter()
         .map(|(name, value)| format!("{}:{}", name, value))
         .collect();
-    pairs.sort();
-    pairs
+
+    let mut injected = BTreeMap::new();
+    injected.extend(config.tags.iter());
+    if let Some(tags) = namespace.and_then(|namespace| config.namespace_tags.get(namespace)) {
+        injected.extend(tags.iter());
+    }
+    for (name, template) in injected {
+        if metric.tag_value(name).is_some() {
+            continue;
+        }
+        match template.render_string(metric) {
+            Ok(value) => pairs.push(format!("{}:{}", name, value)),
+            Err(error) => emit!(&TemplateRenderingFailed {
+                error,
+                field: Some(name),
+                drop_event: false,
+            }),
+        }
+    }
+
+    if pairs.is_empty() && metric.tags().is_none() {
+        None
+    } else {
+        pairs.sort();
+        Some(pairs)
+    }
 }
 
 fn encode_timestamp(timestamp: Option<DateTime<Utc>>) -> i64 {
@@ -315,7 +398,7 @@ fn encode_timestamp(timestamp: Option<DateTime<Utc>>) -> i64 {
     }
 }
 
-fn stats(source: &[Sample]) -> Option<DatadogStats> {
+fn stats(source: &[Sample], quantiles: &[f64]) -> Option<DatadogStats> {
     let mut samples = Vec::new();
     for sample in source {
         for _ in 0..sample.rate {
@@ -336,7 +419,7 @@ fn stats(source: &[Sample]) -> Option<DatadogStats> {
             avg: val,
             sum: val,
             count: 1.0,
-            quantiles: vec![(0.95, val)],
+            quantiles: quantiles.iter().map(|&q| (q, val)).collect(),
         });
     }
 
@@ -346,8 +429,9 @@ fn stats(source: &[Sample]) -> Option<DatadogStats> {
     let min = samples.first().unwrap();
     let max = samples.last().unwrap();
 
-    let p50 = samples[(0.50 * length - 1.0).round() as usize];
-    let p95 = samples[(0.95 * length - 1.0).round() as usize];
+    let quantile =
+        |q: f64| samples[((q * length - 1.0).round().max(0.0) as usize).min(samples.len() - 1)];
+    let p50 = quantile(0.50);
 
     let sum = samples.iter().sum();
     let avg = sum / length;
@@ -359,7 +443,7 @@ fn stats(source: &[Sample]) -> Option<DatadogStats> {
         avg,
         sum,
         count: length,
-        quantiles: vec![(0.95, p95)],
+        quantiles: quantiles.iter().map(|&q| (q, quantile(q))).collect(),
     })
 }
 
@@ -376,17 +460,19 @@ impl MetricNormalize for DatadogMetricNormalize {
 
 fn encode_events(
     events: Vec<Metric>,
-    default_namespace: Option<&str>,
     interval: i64,
+    config: &DatadogConfig,
 ) -> DatadogRequest<DatadogMetric> {
     debug!(message = "Series.", count = events.len());
     let series = events
         .into_iter()
         .filter_map(|event| {
-            let fullname =
-                encode_namespace(event.namespace().or(default_namespace), '.', event.name());
+            let namespace = event
+                .namespace()
+                .or_else(|| config.default_namespace.as_deref());
+            let fullname = encode_namespace(namespace, '.', event.name());
             let ts = encode_timestamp(event.timestamp());
-            let tags = event.tags().map(encode_tags);
+            let tags = encode_tags(&event, namespace, config);
             // DatadogMetricNormalize converts these to the right MetricKind
             match event.value() {
                 MetricValue::Counter { value } => Some(vec![DatadogMetric {
@@ -395,13 +481,15 @@ fn encode_events(
                     interval: Some(interval),
                     points: vec![DatadogPoint(ts, *value)],
                     tags,
+                    source_type_name: config.source_type_name.clone(),
                 }]),
                 MetricValue::Distribution {
                     samples,
                     statistic: StatisticKind::Histogram,
                 } => {
                     // https://docs.datadoghq.com/developers/metrics/metrics_type/?tab=histogram#metric-type-definition
-                    if let Some(s) = stats(samples) {
+                    let quantiles = config.quantiles.as_deref().unwrap_or(DEFAULT_QUANTILES);
+                    if let Some(s) = stats(samples, quantiles) {
                         let mut result = vec![
                             DatadogMetric {
                                 metric: format!("{}.min", &fullname),
@@ -409,6 +497,7 @@ fn encode_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, s.min)],
                                 tags: tags.clone(),
+                                source_type_name: config.source_type_name.clone(),
                             },
                             DatadogMetric {
                                 metric: format!("{}.avg", &fullname),
@@ -416,6 +505,7 @@ fn encode_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, s.avg)],
                                 tags: tags.clone(),
+                                source_type_name: config.source_type_name.clone(),
                             },
                             DatadogMetric {
                                 metric: format!("{}.count", &fullname),
@@ -423,6 +513,7 @@ fn encode_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, s.count)],
                                 tags: tags.clone(),
+                                source_type_name: config.source_type_name.clone(),
                             },
                             DatadogMetric {
                                 metric: format!("{}.median", &fullname),
@@ -430,6 +521,7 @@ fn encode_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, s.median)],
                                 tags: tags.clone(),
+                                source_type_name: config.source_type_name.clone(),
                             },
                             DatadogMetric {
                                 metric: format!("{}.max", &fullname),
@@ -437,6 +529,7 @@ fn encode_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, s.max)],
                                 tags: tags.clone(),
+                                source_type_name: config.source_type_name.clone(),
                             },
                         ];
                         for (q, v) in s.quantiles {
@@ -446,6 +539,7 @@ fn encode_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, v)],
                                 tags: tags.clone(),
+                                source_type_name: config.source_type_name.clone(),
                             })
                         }
                         Some(result)
@@ -459,6 +553,7 @@ fn encode_events(
                     interval: None,
                     points: vec![DatadogPoint(ts, values.len() as f64)],
                     tags,
+                    source_type_name: config.source_type_name.clone(),
                 }]),
                 MetricValue::Gauge { value } => Some(vec![DatadogMetric {
                     metric: fullname,
@@ -466,6 +561,7 @@ fn encode_events(
                     interval: None,
                     points: vec![DatadogPoint(ts, *value)],
                     tags,
+                    source_type_name: config.source_type_name.clone(),
                 }]),
                 _ => None,
             }
@@ -478,23 +574,24 @@ fn encode_events(
 
 fn encode_distribution_events(
     events: Vec<Metric>,
-    default_namespace: Option<&str>,
     interval: i64,
+    config: &DatadogConfig,
 ) -> DatadogRequest<DatadogDistributionMetric> {
     debug!(message = "Distribution.", count = events.len());
     let series = events
         .into_iter()
         .filter_map(|event| {
-            let fullname =
-                encode_namespace(event.namespace().or(default_namespace), '.', event.name());
+            let namespace = event
+                .namespace()
+                .or_else(|| config.default_namespace.as_deref());
+            let fullname = encode_namespace(namespace, '.', event.name());
             let ts = encode_timestamp(event.timestamp());
-            let tags = event.tags().map(encode_tags);
+            let tags = encode_tags(&event, namespace, config);
+            // Only the distributions picked by `DatadogEndpoint::from_metric`
+            // make it here, whatever their statistic.
             match event.kind() {
                 MetricKind::Incremental => match event.value() {
-                    MetricValue::Distribution {
-                        samples,
-                        statistic: StatisticKind::Summary,
-                    } => {
+                    MetricValue::Distribution { samples, .. } => {
                         let samples = samples
                             .iter()
                             .map(|sample| (0..sample.rate).map(move |_| sample.value))
@@ -509,6 +606,7 @@ fn encode_distribution_events(
                                 interval: Some(interval),
                                 points: vec![DatadogPoint(ts, samples)],
                                 tags,
+                                source_type_name: config.source_type_name.clone(),
                             })
                         }
                     }
@@ -530,7 +628,7 @@ mod tests {
     use http::Method;
     use pretty_assertions::assert_eq;
     use regex::Regex;
-    use std::sync::atomic::AtomicI64;
+    use std::{collections::BTreeMap, convert::TryFrom, sync::atomic::AtomicI64};
 
     #[test]
     fn generate_config() {
@@ -608,10 +706,99 @@ mod tests {
 
     #[test]
     fn test_encode_tags() {
+        let metric = Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(tags()));
+        assert_eq!(
+            encode_tags(&metric, None, &Default::default()),
+            Some(vec![
+                "empty_tag:".to_owned(),
+                "normal_tag:value".to_owned(),
+                "true_tag:true".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_encode_multi_value_tags() {
+        let mut metric = Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        );
+        metric.add_tag_value("host_group".into(), "web".into());
+        metric.add_tag_value("host_group".into(), "frontend".into());
+        let config = DatadogConfig {
+            multi_value_tags: MultiValueTagsConfig::Full,
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_tags(&metric, None, &config),
+            Some(vec![
+                "host_group:frontend".to_owned(),
+                "host_group:web".to_owned()
+            ])
+        );
+        let config = DatadogConfig {
+            multi_value_tags: MultiValueTagsConfig::Single,
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_tags(&metric, None, &config),
+            Some(vec!["host_group:frontend".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_encode_injected_tags() {
+        let config: DatadogConfig = toml::from_str(
+            r#"
+            api_key = "foo"
+            source_type_name = "vector"
+
+            [tags]
+            env = "production"
+            service = "{{ name }}"
+            region = "{{ tags.missing }}"
+
+            [namespace_tags.app]
+            env = "staging"
+            team = "web"
+            "#,
+        )
+        .unwrap();
+
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(
+            vec![("service".to_owned(), "api".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
         assert_eq!(
-            encode_tags(&tags()),
-            vec!["empty_tag:", "normal_tag:value", "true_tag:true"]
+            encode_tags(&metric, Some("app"), &config),
+            Some(vec![
+                "env:staging".to_owned(),
+                "service:api".to_owned(),
+                "team:web".to_owned()
+            ])
         );
+
+        let metric = metric.with_tags(None);
+        assert_eq!(
+            encode_tags(&metric, Some("other"), &config),
+            Some(vec![
+                "env:production".to_owned(),
+                "service:requests".to_owned()
+            ])
+        );
+        assert_eq!(encode_tags(&metric, None, &Default::default()), None);
     }
 
     #[test]
@@ -640,7 +827,7 @@ mod tests {
             .with_tags(Some(tags()))
             .with_timestamp(Some(ts())),
         ];
-        let input = encode_events(events, None, interval);
+        let input = encode_events(events, interval, &Default::default());
         let json = serde_json::to_string(&input).unwrap();
 
         assert_eq!(
@@ -657,7 +844,7 @@ mod tests {
             MetricValue::Gauge { value: -1.1 },
         )
         .with_timestamp(Some(ts()))];
-        let input = encode_events(events, None, 60);
+        let input = encode_events(events, 60, &Default::default());
         let json = serde_json::to_string(&input).unwrap();
 
         assert_eq!(
@@ -676,7 +863,11 @@ mod tests {
             },
         )
         .with_timestamp(Some(ts()))];
-        let input = encode_events(events, Some("ns"), 60);
+        let config = DatadogConfig {
+            default_namespace: Some("ns".into()),
+            ..Default::default()
+        };
+        let input = encode_events(events, 60, &config);
         let json = serde_json::to_string(&input).unwrap();
 
         assert_eq!(
@@ -696,7 +887,7 @@ mod tests {
             .collect();
 
         assert_eq!(
-            stats(&samples),
+            stats(&samples, DEFAULT_QUANTILES),
             Some(DatadogStats {
                 min: 0.0,
                 max: 19.0,
@@ -709,6 +900,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_configured_quantiles_stats() {
+        let samples: Vec<_> = (0..20)
+            .map(|v| Sample {
+                value: f64::from(v),
+                rate: 1,
+            })
+            .collect();
+
+        let stats = stats(&samples, &[0.0, 0.5, 0.99]).unwrap();
+        assert_eq!(stats.quantiles, vec![(0.0, 0.0), (0.5, 9.0), (0.99, 19.0)]);
+    }
+
     #[test]
     fn test_sparse_stats() {
         let samples: Vec<_> = (1..5)
@@ -719,7 +923,7 @@ mod tests {
             .collect();
 
         assert_eq!(
-            stats(&samples),
+            stats(&samples, DEFAULT_QUANTILES),
             Some(DatadogStats {
                 min: 1.0,
                 max: 4.0,
@@ -737,7 +941,7 @@ mod tests {
         let samples = vector_core::samples![10.0 => 1];
 
         assert_eq!(
-            stats(&samples),
+            stats(&samples, DEFAULT_QUANTILES),
             Some(DatadogStats {
                 min: 10.0,
                 max: 10.0,
@@ -752,19 +956,19 @@ mod tests {
     #[test]
     fn test_nan_stats() {
         let samples = vector_core::samples![1.0 => 1, std::f64::NAN => 1];
-        assert!(stats(&samples).is_some());
+        assert!(stats(&samples, DEFAULT_QUANTILES).is_some());
     }
 
     #[test]
     fn test_empty_stats() {
         let samples = vec![];
-        assert!(stats(&samples).is_none());
+        assert!(stats(&samples, DEFAULT_QUANTILES).is_none());
     }
 
     #[test]
     fn test_zero_counts_stats() {
         let samples = vector_core::samples![1.0 => 0, 2.0 => 0];
-        assert!(stats(&samples).is_none());
+        assert!(stats(&samples, DEFAULT_QUANTILES).is_none());
     }
 
     #[test]
@@ -779,7 +983,7 @@ mod tests {
             },
         )
         .with_timestamp(Some(ts()))];
-        let input = encode_events(events, None, 60);
+        let input = encode_events(events, 60, &Default::default());
         let json = serde_json::to_string(&input).unwrap();
 
         assert_eq!(
@@ -800,7 +1004,7 @@ mod tests {
             },
         )
         .with_timestamp(Some(ts()))];
-        let input = encode_distribution_events(events, None, 60);
+        let input = encode_distribution_events(events, 60, &Default::default());
         let json = serde_json::to_string(&input).unwrap();
 
         assert_eq!(
@@ -808,4 +1012,67 @@ mod tests {
             r#"{"series":[{"metric":"requests","interval":60,"points":[[1542182950,[1.0,1.0,1.0,2.0,2.0,2.0,3.0,3.0]]],"tags":null}]}"#
         );
     }
+
+    #[test]
+    fn passes_distributions_through() {
+        let histogram = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 2, 2.5 => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_timestamp(Some(ts()));
+        assert_eq!(
+            DatadogEndpoint::from_metric(&histogram, false),
+            DatadogEndpoint::Series
+        );
+        assert_eq!(
+            DatadogEndpoint::from_metric(&histogram, true),
+            DatadogEndpoint::Distribution
+        );
+
+        let config = DatadogConfig {
+            distribution_passthrough: true,
+            ..Default::default()
+        };
+        let input = encode_distribution_events(vec![histogram], 60, &config);
+        let json = serde_json::to_string(&input).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"series":[{"metric":"requests","interval":60,"points":[[1542182950,[1.0,1.0,2.5]]],"tags":null}]}"#
+        );
+    }
+
+    #[test]
+    fn encode_source_type_name() {
+        let events = vec![Metric::new(
+            "volume",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: -1.1 },
+        )
+        .with_namespace(Some("app"))
+        .with_timestamp(Some(ts()))];
+        let config = DatadogConfig {
+            source_type_name: Some("vector".into()),
+            namespace_tags: vec![(
+                "app".to_owned(),
+                vec![("team".to_owned(), Template::try_from("web").unwrap())]
+                    .into_iter()
+                    .collect(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let input = encode_events(events, 60, &config);
+        let json = serde_json::to_string(&input).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"series":[{"metric":"app.volume","type":"gauge","interval":null,"points":[[1542182950,-1.1]],"tags":["team:web"],"source_type_name":"vector"}]}"#
+        );
+    }
 }
@@ -13,7 +13,6 @@ use http::{StatusCode, Uri};
 use hyper::Body;
 use rusoto_signature::SignedRequest;
 use snafu::ResultExt;
-use std::convert::TryFrom;
 
 use super::{InvalidHost, Request};
 use crate::rusoto;
@@ -66,14 +65,20 @@ impl ElasticSearchCommon {
         let authorization = authorization.choose_one(&uri.auth)?;
         let base_url = uri.uri.to_string().trim_end_matches('/').to_owned();
 
-        let region = match &config.aws {
-            Some(region) => Region::try_from(region)?,
-            None => region_from_endpoint(&base_url)?,
+        let (region, sts_region) = match &config.aws {
+            Some(region) => (
+                region.region_for_service("es")?,
+                region.region_for_service("sts")?,
+            ),
+            None => {
+                let region = region_from_endpoint(&base_url)?;
+                (region.clone(), region)
+            }
         };
 
         let credentials = match &config.auth {
             Some(ElasticSearchAuth::Basic { .. }) | None => None,
-            Some(ElasticSearchAuth::Aws(aws)) => Some(aws.build(&region, None)?),
+            Some(ElasticSearchAuth::Aws(aws)) => Some(aws.build(&sts_region, None)?),
         };
 
         let compression = config.compression;
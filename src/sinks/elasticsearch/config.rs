@@ -6,9 +6,12 @@ use crate::internal_events::TemplateRenderingFailed;
 use crate::rusoto::RegionOrEndpoint;
 use crate::sinks::elasticsearch::request_builder::ElasticsearchRequestBuilder;
 use crate::sinks::elasticsearch::sink::ElasticSearchSink;
-use crate::sinks::elasticsearch::{BatchActionTemplate, IndexTemplate};
+use crate::sinks::elasticsearch::{
+    BatchActionTemplate, IndexTemplate, PipelineTemplate, RoutingTemplate,
+};
 use crate::sinks::elasticsearch::{
     ElasticSearchAuth, ElasticSearchCommon, ElasticSearchCommonMode, ElasticSearchMode,
+    PartialFailureConfig,
 };
 use crate::sinks::util::encoding::EncodingConfigFixed;
 use crate::sinks::util::http::RequestConfig;
@@ -28,6 +31,7 @@ use std::convert::TryFrom;
 use vector_core::stream::BatcherSettings;
 
 use crate::sinks::elasticsearch::encoder::ElasticSearchEncoder;
+use crate::sinks::elasticsearch::partial_failures::PartialFailureHandler;
 use crate::sinks::elasticsearch::retry::ElasticSearchRetryLogic;
 use crate::sinks::elasticsearch::service::{ElasticSearchService, HttpRequestBuilder};
 use std::num::NonZeroUsize;
@@ -78,6 +82,9 @@ pub struct ElasticSearchConfig {
     pub bulk: Option<BulkConfig>,
     pub data_stream: Option<DataStreamConfig>,
     pub metrics: Option<MetricToLogConfig>,
+
+    #[serde(default)]
+    pub partial_failures: PartialFailureConfig,
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
@@ -116,14 +123,36 @@ impl ElasticSearchConfig {
         Ok(Template::try_from(index.as_str()).context(IndexTemplate)?)
     }
 
+    pub fn bulk_pipeline(&self) -> crate::Result<Option<Template>> {
+        Ok(self
+            .bulk
+            .as_ref()
+            .and_then(|n| n.pipeline.as_deref())
+            .map(|value| Template::try_from(value).context(PipelineTemplate))
+            .transpose()?)
+    }
+
+    pub fn bulk_routing(&self) -> crate::Result<Option<Template>> {
+        Ok(self
+            .bulk
+            .as_ref()
+            .and_then(|n| n.routing.as_deref())
+            .map(|value| Template::try_from(value).context(RoutingTemplate))
+            .transpose()?)
+    }
+
     pub fn common_mode(&self) -> crate::Result<ElasticSearchCommonMode> {
         match self.mode {
             ElasticSearchMode::Bulk => {
                 let index = self.index()?;
                 let bulk_action = self.bulk_action()?;
+                let pipeline = self.bulk_pipeline()?;
+                let routing = self.bulk_routing()?;
                 Ok(ElasticSearchCommonMode::Bulk {
                     index,
                     action: bulk_action,
+                    pipeline,
+                    routing,
                 })
             }
             ElasticSearchMode::DataStream => Ok(ElasticSearchCommonMode::DataStream(
@@ -139,6 +168,9 @@ pub struct BulkConfig {
     #[serde(alias = "bulk_action")]
     action: Option<String>,
     index: Option<String>,
+    // Per-event ingest pipeline, overrides the request-wide `pipeline` option.
+    pipeline: Option<String>,
+    routing: Option<String>,
 }
 
 impl BulkConfig {
@@ -318,6 +350,25 @@ impl SinkConfig for ElasticSearchConfig {
             NonZeroUsize::new(batch_settings.size.events).expect("Batch events should not be 0"),
         );
 
+        let request_limits = self
+            .request
+            .tower
+            .unwrap_with(&TowerRequestConfig::default());
+
+        let partial_failures = PartialFailureHandler {
+            compression: self.compression,
+            doc_type: common.doc_type.clone(),
+            retry_attempts: self.partial_failures.retry_attempts,
+            retry_backoff: request_limits.retry_initial_backoff_secs,
+            dead_letter_index: self
+                .partial_failures
+                .dead_letter_index
+                .as_deref()
+                .map(Template::try_from)
+                .transpose()
+                .context(IndexTemplate)?,
+        };
+
         // This is a bit ugly, but removes a String allocation on every event
         let mut encoding = self.encoding.clone();
         encoding.codec.doc_type = common.doc_type;
@@ -327,11 +378,6 @@ impl SinkConfig for ElasticSearchConfig {
             encoder: encoding,
         };
 
-        let request_limits = self
-            .request
-            .tower
-            .unwrap_with(&TowerRequestConfig::default());
-
         let http_request_builder = HttpRequestBuilder {
             bulk_uri: common.bulk_uri,
             http_request_config: self.request.clone(),
@@ -344,7 +390,11 @@ impl SinkConfig for ElasticSearchConfig {
 
         let service = ServiceBuilder::new()
             .settings(request_limits, ElasticSearchRetryLogic)
-            .service(ElasticSearchService::new(http_client, http_request_builder));
+            .service(ElasticSearchService::new(
+                http_client,
+                http_request_builder,
+                partial_failures,
+            ));
 
         let sink = ElasticSearchSink {
             batch_settings,
@@ -415,4 +465,22 @@ mod tests {
         assert!(matches!(config.mode, ElasticSearchMode::DataStream));
         assert!(config.data_stream.is_some());
     }
+
+    #[test]
+    fn parse_partial_failures() {
+        let config = toml::from_str::<ElasticSearchConfig>(
+            r#"
+            endpoint = ""
+            partial_failures.dead_letter_index = "dead-letter-%Y.%m.%d"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.partial_failures,
+            PartialFailureConfig {
+                retry_attempts: 5,
+                dead_letter_index: Some("dead-letter-%Y.%m.%d".into()),
+            }
+        );
+    }
 }
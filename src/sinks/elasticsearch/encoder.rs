@@ -14,6 +14,8 @@ pub struct ProcessedEvent {
     pub bulk_action: BulkAction,
     pub log: LogEvent,
     pub id: Option<String>,
+    pub pipeline: Option<String>,
+    pub routing: Option<String>,
 }
 
 impl Finalizable for ProcessedEvent {
@@ -24,7 +26,11 @@ impl Finalizable for ProcessedEvent {
 
 impl ByteSizeOf for ProcessedEvent {
     fn allocated_bytes(&self) -> usize {
-        self.index.allocated_bytes() + self.log.allocated_bytes() + self.id.allocated_bytes()
+        self.index.allocated_bytes()
+            + self.log.allocated_bytes()
+            + self.id.allocated_bytes()
+            + self.pipeline.allocated_bytes()
+            + self.routing.allocated_bytes()
     }
 }
 
@@ -47,6 +53,8 @@ impl Encoder<Vec<ProcessedEvent>> for ElasticSearchEncoder {
                 &event.index,
                 &self.doc_type,
                 &event.id,
+                &event.routing,
+                &event.pipeline,
             )?;
             written_bytes +=
                 as_tracked_write::<_, _, io::Error>(writer, &event.log, |mut writer, log| {
@@ -65,30 +73,34 @@ impl Encoder<Vec<ProcessedEvent>> for ElasticSearchEncoder {
     }
 }
 
-fn write_bulk_action(
+pub fn write_bulk_action(
     writer: &mut dyn Write,
     bulk_action: &str,
     index: &str,
     doc_type: &str,
     id: &Option<String>,
+    routing: &Option<String>,
+    pipeline: &Option<String>,
 ) -> std::io::Result<usize> {
     as_tracked_write(
         writer,
-        (bulk_action, index, doc_type, id),
-        |writer, (bulk_action, index, doc_type, id)| {
+        (bulk_action, index, doc_type, id, routing, pipeline),
+        |writer, (bulk_action, index, doc_type, id, routing, pipeline)| {
+            write!(
+                writer,
+                r#"{{"{}":{{"_index":"{}","_type":"{}""#,
+                bulk_action, index, doc_type
+            )?;
             if let Some(id) = id {
-                write!(
-                    writer,
-                    r#"{{"{}":{{"_index":"{}","_type":"{}","_id":"{}"}}}}"#,
-                    bulk_action, index, doc_type, id
-                )
-            } else {
-                write!(
-                    writer,
-                    r#"{{"{}":{{"_index":"{}","_type":"{}"}}}}"#,
-                    bulk_action, index, doc_type
-                )
+                write!(writer, r#","_id":"{}""#, id)?;
+            }
+            if let Some(routing) = routing {
+                write!(writer, r#","routing":"{}""#, routing)?;
+            }
+            if let Some(pipeline) = pipeline {
+                write!(writer, r#","pipeline":"{}""#, pipeline)?;
             }
+            write!(writer, "}}}}")
         },
     )
 }
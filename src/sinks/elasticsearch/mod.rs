@@ -1,6 +1,7 @@
 mod common;
 mod config;
 mod encoder;
+mod partial_failures;
 mod request_builder;
 mod retry;
 mod service;
@@ -16,6 +17,7 @@ mod integration_tests;
 pub use common::*;
 pub use config::*;
 pub use encoder::ElasticSearchEncoder;
+pub use partial_failures::PartialFailureConfig;
 
 use crate::{
     config::SinkDescription,
@@ -109,6 +111,8 @@ pub enum ElasticSearchCommonMode {
     Bulk {
         index: Template,
         action: Option<Template>,
+        pipeline: Option<Template>,
+        routing: Option<Template>,
     },
     DataStream(DataStreamConfig),
 }
@@ -154,6 +158,24 @@ impl ElasticSearchCommonMode {
         }
     }
 
+    /// Renders the per-event ingest pipeline. Returns `None` when rendering
+    /// failed and the event should be dropped.
+    fn pipeline(&self, log: &LogEvent) -> Option<Option<String>> {
+        match self {
+            Self::Bulk { pipeline, .. } => render_bulk_field(pipeline, log, "bulk.pipeline"),
+            Self::DataStream(_) => Some(None),
+        }
+    }
+
+    /// Renders the per-event routing value. Returns `None` when rendering
+    /// failed and the event should be dropped.
+    fn routing(&self, log: &LogEvent) -> Option<Option<String>> {
+        match self {
+            Self::Bulk { routing, .. } => render_bulk_field(routing, log, "bulk.routing"),
+            Self::DataStream(_) => Some(None),
+        }
+    }
+
     const fn as_data_stream_config(&self) -> Option<&DataStreamConfig> {
         match self {
             Self::DataStream(value) => Some(value),
@@ -162,6 +184,27 @@ impl ElasticSearchCommonMode {
     }
 }
 
+fn render_bulk_field(
+    template: &Option<Template>,
+    log: &LogEvent,
+    field: &'static str,
+) -> Option<Option<String>> {
+    match template {
+        Some(template) => template
+            .render_string(log)
+            .map(|value| (!value.is_empty()).then(|| value))
+            .map_err(|error| {
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some(field),
+                    drop_event: true,
+                });
+            })
+            .ok(),
+        None => Some(None),
+    }
+}
+
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
 pub enum ParseError {
@@ -175,6 +218,10 @@ pub enum ParseError {
     IndexTemplate { source: TemplateParseError },
     #[snafu(display("Batch action template parse error: {}", source))]
     BatchActionTemplate { source: TemplateParseError },
+    #[snafu(display("Pipeline template parse error: {}", source))]
+    PipelineTemplate { source: TemplateParseError },
+    #[snafu(display("Routing template parse error: {}", source))]
+    RoutingTemplate { source: TemplateParseError },
 }
 
 async fn finish_signer(
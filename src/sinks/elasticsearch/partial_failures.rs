@@ -0,0 +1,557 @@
+//! Handling of bulk requests that were only partially successful.
+//!
+//! Elasticsearch accepts or rejects each item of a bulk request individually,
+//! so a request can succeed as a whole while some of its documents were
+//! rejected. The items rejected for transient reasons, such as full write
+//! queues, are sent again on their own, while the ones rejected for good, such
+//! as documents not matching the index mapping, are written to a dead letter
+//! index along with the rejection reason, if one is configured.
+
+use crate::{
+    config::log_schema,
+    event::{EventFinalizers, LogEvent},
+    internal_events::{
+        ElasticSearchBulkItemsDeadLettered, ElasticSearchBulkItemsRejected,
+        ElasticSearchBulkItemsRetried, TemplateRenderingFailed,
+    },
+    sinks::{
+        elasticsearch::{
+            encoder::write_bulk_action,
+            retry::{parse_bulk_items, BulkItemError, BulkItemOutcome},
+            service::ElasticSearchRequest,
+            BulkAction,
+        },
+        util::{Compression, Compressor},
+    },
+    template::Template,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use flate2::read::MultiGzDecoder;
+use http::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Write},
+    time::Duration,
+};
+use tower::{Service, ServiceExt};
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PartialFailureConfig {
+    /// How many times the items rejected for a transient reason are sent
+    /// again before being dropped.
+    #[serde(default = "PartialFailureConfig::default_retry_attempts")]
+    pub retry_attempts: usize,
+    /// The index the permanently rejected documents are written to.
+    pub dead_letter_index: Option<String>,
+}
+
+impl Default for PartialFailureConfig {
+    fn default() -> Self {
+        Self {
+            retry_attempts: Self::default_retry_attempts(),
+            dead_letter_index: None,
+        }
+    }
+}
+
+impl PartialFailureConfig {
+    const fn default_retry_attempts() -> usize {
+        5
+    }
+}
+
+/// The fate of the items of a partially successful bulk request.
+#[derive(Debug, Default, PartialEq)]
+pub struct PartialFailureOutcome {
+    pub indexed: usize,
+    pub dead_lettered: usize,
+    pub dropped: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BulkItem<'a> {
+    action: &'a [u8],
+    document: &'a [u8],
+}
+
+impl<'a> BulkItem<'a> {
+    fn index(&self) -> Option<String> {
+        let action = serde_json::from_slice::<serde_json::Value>(self.action).ok()?;
+        action
+            .as_object()?
+            .values()
+            .next()?
+            .get("_index")?
+            .as_str()
+            .map(Into::into)
+    }
+}
+
+pub struct PartialFailureHandler {
+    pub compression: Compression,
+    pub doc_type: String,
+    pub retry_attempts: usize,
+    pub retry_backoff: Duration,
+    pub dead_letter_index: Option<Template>,
+}
+
+impl PartialFailureHandler {
+    /// Retries and dead-letters the failed items of the bulk request that was
+    /// sent with the given payload. Returns `None` if the request and response
+    /// couldn't be matched up item by item.
+    pub async fn handle<S>(
+        &self,
+        service: &mut S,
+        payload: &[u8],
+        response: &Response<Bytes>,
+    ) -> Option<PartialFailureOutcome>
+    where
+        S: Service<ElasticSearchRequest, Response = Response<Bytes>, Error = crate::Error>,
+    {
+        let body = decompress(self.compression, payload)
+            .map_err(|error| error!(message = "Failed to decompress bulk request.", %error))
+            .ok()?;
+        let mut pending = split_items(&body)?;
+        let mut outcomes = parse_bulk_items(response.body())
+            .map_err(|error| error!(message = "Failed to parse bulk response.", %error))
+            .ok()?;
+        if outcomes.len() != pending.len() {
+            error!(
+                message = "Bulk response doesn't match the request.",
+                items = pending.len(),
+                results = outcomes.len()
+            );
+            return None;
+        }
+
+        let mut outcome = PartialFailureOutcome::default();
+        let mut rejected = Vec::new();
+        let mut attempt = 0;
+        loop {
+            let mut retriable = Vec::new();
+            for (item, item_outcome) in pending.into_iter().zip(outcomes) {
+                match item_outcome {
+                    BulkItemOutcome::Indexed => outcome.indexed += 1,
+                    BulkItemOutcome::Retriable(error) => retriable.push((item, error)),
+                    BulkItemOutcome::Rejected(error) => rejected.push((item, error)),
+                }
+            }
+
+            if retriable.is_empty() {
+                break;
+            }
+            if attempt >= self.retry_attempts {
+                outcome.dropped += retriable.len();
+                emit_rejected(&retriable);
+                break;
+            }
+
+            attempt += 1;
+            emit!(&ElasticSearchBulkItemsRetried {
+                count: retriable.len(),
+                attempt,
+            });
+            tokio::time::sleep(self.retry_backoff * (1 << (attempt - 1).min(6))).await;
+
+            pending = retriable.into_iter().map(|(item, _)| item).collect();
+            outcomes = self.resend(service, &pending).await;
+        }
+
+        if !rejected.is_empty() {
+            let dead_lettered = self.dead_letter(service, &rejected).await;
+            outcome.dead_lettered += dead_lettered;
+            outcome.dropped += rejected.len() - dead_lettered;
+        }
+
+        Some(outcome)
+    }
+
+    async fn send<S>(
+        &self,
+        service: &mut S,
+        items: usize,
+        body: &[u8],
+    ) -> crate::Result<Response<Bytes>>
+    where
+        S: Service<ElasticSearchRequest, Response = Response<Bytes>, Error = crate::Error>,
+    {
+        let mut compressor = Compressor::from(self.compression);
+        compressor.write_all(body)?;
+        let request = ElasticSearchRequest {
+            payload: compressor.into_inner(),
+            finalizers: EventFinalizers::default(),
+            batch_size: items,
+            events_byte_size: body.len(),
+        };
+        service.ready().await?.call(request).await
+    }
+
+    /// Sends the given items again, returning their outcomes.
+    async fn resend<S>(&self, service: &mut S, items: &[BulkItem<'_>]) -> Vec<BulkItemOutcome>
+    where
+        S: Service<ElasticSearchRequest, Response = Response<Bytes>, Error = crate::Error>,
+    {
+        let mut body = Vec::new();
+        for item in items {
+            body.extend_from_slice(item.action);
+            body.push(b'\n');
+            body.extend_from_slice(item.document);
+            body.push(b'\n');
+        }
+
+        let failure = match self.send(service, items.len(), &body).await {
+            Ok(response) if response.status().is_success() => {
+                match parse_bulk_items(response.body()) {
+                    Ok(outcomes) if outcomes.len() == items.len() => return outcomes,
+                    _ => BulkItemOutcome::Retriable(BulkItemError {
+                        status: response.status().as_u16(),
+                        error_type: "invalid_response".into(),
+                        reason: "bulk response doesn't match the request".into(),
+                    }),
+                }
+            }
+            Ok(response) => {
+                let status = response.status();
+                let error = BulkItemError {
+                    status: status.as_u16(),
+                    error_type: format!("http_{}", status.as_u16()),
+                    reason: String::from_utf8_lossy(response.body()).into_owned(),
+                };
+                if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                    BulkItemOutcome::Retriable(error)
+                } else {
+                    BulkItemOutcome::Rejected(error)
+                }
+            }
+            Err(error) => BulkItemOutcome::Retriable(BulkItemError {
+                status: 0,
+                error_type: "request_failed".into(),
+                reason: error.to_string(),
+            }),
+        };
+        vec![failure; items.len()]
+    }
+
+    /// Writes the rejected items to the dead letter index, returning how many
+    /// of them were written.
+    async fn dead_letter<S>(
+        &self,
+        service: &mut S,
+        rejected: &[(BulkItem<'_>, BulkItemError)],
+    ) -> usize
+    where
+        S: Service<ElasticSearchRequest, Response = Response<Bytes>, Error = crate::Error>,
+    {
+        let template = match &self.dead_letter_index {
+            Some(template) => template,
+            None => {
+                emit_rejected(rejected);
+                return 0;
+            }
+        };
+
+        let mut body = Vec::new();
+        let mut written = Vec::new();
+        for (item, error) in rejected {
+            let log = dead_letter_event(item, error);
+            let index = match template.render_string(&log) {
+                Ok(index) => index,
+                Err(error) => {
+                    emit!(&TemplateRenderingFailed {
+                        error,
+                        field: Some("partial_failures.dead_letter_index"),
+                        drop_event: true,
+                    });
+                    continue;
+                }
+            };
+
+            let encoded = write_bulk_action(
+                &mut body,
+                BulkAction::Create.as_str(),
+                &index,
+                &self.doc_type,
+                &None,
+                &None,
+                &None,
+            )
+            .and_then(|_| {
+                body.push(b'\n');
+                serde_json::to_writer(&mut body, &log)?;
+                body.push(b'\n');
+                Ok(())
+            });
+            if let Err(error) = encoded {
+                error!(message = "Failed to encode dead letter document.", %error);
+                continue;
+            }
+            written.push((item, error));
+        }
+
+        if written.is_empty() {
+            return 0;
+        }
+
+        let outcomes = match self.send(service, written.len(), &body).await {
+            Ok(response) if response.status().is_success() => {
+                parse_bulk_items(response.body()).unwrap_or_default()
+            }
+            Ok(response) => {
+                error!(
+                    message = "Failed to write to the dead letter index.",
+                    status = %response.status()
+                );
+                Vec::new()
+            }
+            Err(error) => {
+                error!(message = "Failed to write to the dead letter index.", %error);
+                Vec::new()
+            }
+        };
+
+        let mut dead_lettered = Vec::new();
+        let mut dropped = Vec::new();
+        for (i, (item, error)) in written.into_iter().enumerate() {
+            if outcomes.get(i) == Some(&BulkItemOutcome::Indexed) {
+                dead_lettered.push((*item, error.clone()));
+            } else {
+                dropped.push((*item, error.clone()));
+            }
+        }
+        for (error_type, (count, reason)) in group_by_error_type(&dead_lettered) {
+            emit!(&ElasticSearchBulkItemsDeadLettered {
+                error_type,
+                reason,
+                count,
+            });
+        }
+        emit_rejected(&dropped);
+        // The items the index couldn't be rendered for were already reported
+        // as dropped when the rendering failed.
+        dead_lettered.len()
+    }
+}
+
+fn dead_letter_event(item: &BulkItem<'_>, error: &BulkItemError) -> LogEvent {
+    let mut log = LogEvent::default();
+    log.insert(log_schema().timestamp_key(), Utc::now());
+    log.insert(
+        "document",
+        String::from_utf8_lossy(item.document).into_owned(),
+    );
+    if let Some(index) = item.index() {
+        log.insert("index", index);
+    }
+    log.insert("error.type", error.error_type.clone());
+    log.insert("error.reason", error.reason.clone());
+    log.insert("error.status", error.status as i64);
+    log
+}
+
+fn group_by_error_type<'a>(
+    items: &'a [(BulkItem<'_>, BulkItemError)],
+) -> BTreeMap<&'a str, (usize, &'a str)> {
+    let mut groups = BTreeMap::new();
+    for (_, error) in items {
+        groups
+            .entry(error.error_type.as_str())
+            .or_insert((0, error.reason.as_str()))
+            .0 += 1;
+    }
+    groups
+}
+
+fn emit_rejected(items: &[(BulkItem<'_>, BulkItemError)]) {
+    for (error_type, (count, reason)) in group_by_error_type(items) {
+        emit!(&ElasticSearchBulkItemsRejected {
+            error_type,
+            reason,
+            count,
+        });
+    }
+}
+
+fn decompress(compression: Compression, payload: &[u8]) -> io::Result<Vec<u8>> {
+    match compression {
+        Compression::None => Ok(payload.to_vec()),
+        Compression::Gzip(_) => {
+            let mut body = Vec::new();
+            MultiGzDecoder::new(payload).read_to_end(&mut body)?;
+            Ok(body)
+        }
+        Compression::Zstd { dictionary, .. } => {
+            let mut body = Vec::new();
+            match dictionary {
+                Some(dictionary) => {
+                    zstd::stream::Decoder::with_dictionary(payload, dictionary.as_bytes())?
+                        .read_to_end(&mut body)?
+                }
+                None => zstd::stream::Decoder::new(payload)?.read_to_end(&mut body)?,
+            };
+            Ok(body)
+        }
+    }
+}
+
+/// Splits a bulk request body into its items, each made of an action line
+/// followed by a document line.
+fn split_items(body: &[u8]) -> Option<Vec<BulkItem<'_>>> {
+    let mut lines = body.split(|byte| *byte == b'\n');
+    let mut items = Vec::new();
+    loop {
+        match (lines.next(), lines.next()) {
+            (Some(action), Some(document)) if !action.is_empty() => {
+                items.push(BulkItem { action, document })
+            }
+            (Some(action), None) if action.is_empty() => return Some(items),
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::{self, Ready};
+    use std::{
+        convert::TryFrom,
+        sync::{Arc, Mutex},
+        task::{Context, Poll},
+    };
+
+    /// Answers each request with the next canned response, recording the
+    /// request bodies.
+    #[derive(Clone, Default)]
+    struct MockService {
+        responses: Arc<Mutex<Vec<&'static str>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Service<ElasticSearchRequest> for MockService {
+        type Response = Response<Bytes>;
+        type Error = crate::Error;
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: ElasticSearchRequest) -> Self::Future {
+            self.requests
+                .lock()
+                .unwrap()
+                .push(String::from_utf8(request.payload).unwrap());
+            let body = self.responses.lock().unwrap().remove(0);
+            future::ok(Response::new(Bytes::from(body)))
+        }
+    }
+
+    fn handler(dead_letter_index: Option<&str>) -> PartialFailureHandler {
+        PartialFailureHandler {
+            compression: Compression::None,
+            doc_type: "_doc".into(),
+            retry_attempts: 2,
+            retry_backoff: Duration::from_millis(1),
+            dead_letter_index: dead_letter_index.map(|index| Template::try_from(index).unwrap()),
+        }
+    }
+
+    const PAYLOAD: &str = concat!(
+        r#"{"index":{"_index":"logs","_type":"_doc"}}"#,
+        "\n",
+        r#"{"message":"one"}"#,
+        "\n",
+        r#"{"index":{"_index":"logs","_type":"_doc"}}"#,
+        "\n",
+        r#"{"message":"two"}"#,
+        "\n",
+        r#"{"index":{"_index":"logs","_type":"_doc"}}"#,
+        "\n",
+        r#"{"message":3}"#,
+        "\n",
+    );
+
+    const FIRST_RESPONSE: &str = r#"{"errors":true,"items":[
+        {"index":{"status":201}},
+        {"index":{"status":429,"error":{"type":"es_rejected_execution_exception","reason":"queue full"}}},
+        {"index":{"status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [message]"}}}
+    ]}"#;
+
+    #[test]
+    fn splits_items() {
+        let items = split_items(PAYLOAD.as_bytes()).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].document, br#"{"message":"two"}"#);
+        assert_eq!(items[1].index(), Some("logs".into()));
+
+        assert!(split_items(b"{\"index\":{}}\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn retries_only_retriable_items_and_dead_letters_rejected_ones() {
+        let mut service = MockService::default();
+        *service.responses.lock().unwrap() = vec![
+            r#"{"errors":false,"items":[{"index":{"status":201}}]}"#,
+            r#"{"errors":false,"items":[{"create":{"status":201}}]}"#,
+        ];
+
+        let outcome = handler(Some("dead-letter"))
+            .handle(
+                &mut service,
+                PAYLOAD.as_bytes(),
+                &Response::new(Bytes::from(FIRST_RESPONSE)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            outcome,
+            PartialFailureOutcome {
+                indexed: 2,
+                dead_lettered: 1,
+                dropped: 0,
+            }
+        );
+
+        let requests = service.requests.lock().unwrap();
+        assert_eq!(
+            requests[0],
+            concat!(
+                r#"{"index":{"_index":"logs","_type":"_doc"}}"#,
+                "\n",
+                r#"{"message":"two"}"#,
+                "\n"
+            )
+        );
+        assert!(requests[1].starts_with(r#"{"create":{"_index":"dead-letter","_type":"_doc"}}"#));
+        assert!(requests[1].contains(r#""type":"mapper_parsing_exception""#));
+        assert!(requests[1].contains(r#""document":"{\"message\":3}""#));
+    }
+
+    #[tokio::test]
+    async fn drops_items_once_retries_are_exhausted() {
+        let mut service = MockService::default();
+        let still_full = r#"{"errors":true,"items":[{"index":{"status":429,"error":{"type":"es_rejected_execution_exception","reason":"queue full"}}}]}"#;
+        *service.responses.lock().unwrap() = vec![still_full, still_full];
+
+        let outcome = handler(None)
+            .handle(
+                &mut service,
+                PAYLOAD.as_bytes(),
+                &Response::new(Bytes::from(FIRST_RESPONSE)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            outcome,
+            PartialFailureOutcome {
+                indexed: 1,
+                dead_lettered: 0,
+                dropped: 2,
+            }
+        );
+        assert_eq!(service.requests.lock().unwrap().len(), 2);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    event::EventStatus,
     http::HttpError,
     sinks::util::retries::{RetryAction, RetryLogic},
 };
@@ -33,6 +34,7 @@ impl EsResultItem {
 
 #[derive(Deserialize, Debug)]
 struct EsIndexResult {
+    status: Option<u16>,
     error: Option<EsErrorDetails>,
 }
 
@@ -43,6 +45,62 @@ struct EsErrorDetails {
     err_type: String,
 }
 
+/// Why an item of a bulk request was rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkItemError {
+    pub status: u16,
+    pub error_type: String,
+    pub reason: String,
+}
+
+/// How an item of a bulk request fared.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BulkItemOutcome {
+    Indexed,
+    /// Rejected for a transient reason, such as a full write queue, so it can
+    /// be sent again.
+    Retriable(BulkItemError),
+    /// Rejected for good, such as a document not matching the index mapping.
+    Rejected(BulkItemError),
+}
+
+impl From<EsIndexResult> for BulkItemOutcome {
+    fn from(result: EsIndexResult) -> Self {
+        let status = result.status.unwrap_or(200);
+        match result.error {
+            None if status < 300 => BulkItemOutcome::Indexed,
+            error => {
+                let (error_type, reason) = error.map_or_else(
+                    || ("unknown".into(), format!("status {}", status)),
+                    |error| (error.err_type, error.reason),
+                );
+                let error = BulkItemError {
+                    status,
+                    error_type,
+                    reason,
+                };
+                if status == StatusCode::TOO_MANY_REQUESTS.as_u16() || status >= 500 {
+                    BulkItemOutcome::Retriable(error)
+                } else {
+                    BulkItemOutcome::Rejected(error)
+                }
+            }
+        }
+    }
+}
+
+/// Parses the outcome of each item of a bulk request, in the order the items
+/// were sent.
+pub fn parse_bulk_items(body: &[u8]) -> Result<Vec<BulkItemOutcome>, serde_json::Error> {
+    serde_json::from_slice::<EsResultResponse>(body).map(|response| {
+        response
+            .items
+            .into_iter()
+            .map(|item| item.result().into())
+            .collect()
+    })
+}
+
 #[derive(Clone)]
 pub struct ElasticSearchRetryLogic;
 
@@ -74,7 +132,11 @@ impl RetryLogic for ElasticSearchRetryLogic {
             _ if status.is_success() => {
                 let body = String::from_utf8_lossy(response.http_response.body());
 
-                if body.contains("\"errors\":true") {
+                // Partial failures are retried item by item by the service,
+                // which reports whether all the items were eventually handled.
+                if body.contains("\"errors\":true")
+                    && response.event_status != EventStatus::Delivered
+                {
                     RetryAction::DontRetry(get_error_reason(&body))
                 } else {
                     RetryAction::Successful
@@ -101,7 +163,6 @@ fn get_error_reason(body: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::EventStatus;
     use bytes::Bytes;
     use http::Response;
     use pretty_assertions::assert_eq;
@@ -123,6 +184,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn classifies_bulk_items() {
+        let json = r#"{"took":3,"errors":true,"items":[
+            {"index":{"_index":"test","_id":"1","status":201}},
+            {"index":{"_index":"test","status":429,"error":{"type":"es_rejected_execution_exception","reason":"rejected execution"}}},
+            {"create":{"_index":"test","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [host]"}}}
+        ]}"#;
+        assert_eq!(
+            parse_bulk_items(json.as_bytes()).unwrap(),
+            vec![
+                BulkItemOutcome::Indexed,
+                BulkItemOutcome::Retriable(BulkItemError {
+                    status: 429,
+                    error_type: "es_rejected_execution_exception".into(),
+                    reason: "rejected execution".into(),
+                }),
+                BulkItemOutcome::Rejected(BulkItemError {
+                    status: 400,
+                    error_type: "mapper_parsing_exception".into(),
+                    reason: "failed to parse field [host]".into(),
+                }),
+            ]
+        );
+    }
+
     #[test]
     fn get_index_error_reason() {
         let json = "{\"took\":185,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test-hgw28jv10u\",\"_type\":\"log_lines\",\"_id\":\"3GhQLXEBE62DvOOUKdFH\",\"status\":400,\"error\":{\"type\":\"illegal_argument_exception\",\"reason\":\"mapper [message] of different type, current_type [long], merged_type [text]\"}}}]}";
@@ -13,6 +13,7 @@ use tower::ServiceExt;
 
 use crate::internal_events::EventsSent;
 use crate::rusoto::AwsCredentialsProvider;
+use crate::sinks::elasticsearch::partial_failures::PartialFailureHandler;
 use crate::sinks::util::{Compression, ElementCount};
 use http::header::HeaderName;
 use hyper::header::HeaderValue;
@@ -61,12 +62,14 @@ pub struct ElasticSearchService {
         BoxFuture<'static, Result<http::Request<Vec<u8>>, crate::Error>>,
         ElasticSearchRequest,
     >,
+    partial_failures: Arc<PartialFailureHandler>,
 }
 
 impl ElasticSearchService {
     pub fn new(
         http_client: HttpClient<Body>,
         http_request_builder: HttpRequestBuilder,
+        partial_failures: PartialFailureHandler,
     ) -> ElasticSearchService {
         let http_request_builder = Arc::new(http_request_builder);
         let batch_service = HttpBatchService::new(http_client, move |req| {
@@ -75,7 +78,10 @@ impl ElasticSearchService {
                 Box::pin(async move { request_builder.build_request(req).await });
             future
         });
-        ElasticSearchService { batch_service }
+        ElasticSearchService {
+            batch_service,
+            partial_failures: Arc::new(partial_failures),
+        }
     }
 }
 
@@ -198,16 +204,36 @@ impl Service<ElasticSearchRequest> for ElasticSearchService {
 
     fn call(&mut self, req: ElasticSearchRequest) -> Self::Future {
         let mut http_service = self.batch_service.clone();
+        let partial_failures = Arc::clone(&self.partial_failures);
         Box::pin(async move {
             http_service.ready().await?;
             let batch_size = req.batch_size;
             let byte_size = req.events_byte_size;
+            let payload = req.payload.clone();
             let http_response = http_service.call(req).await?;
-            let event_status = get_event_status(&http_response);
-            if event_status == EventStatus::Delivered {
+            let mut event_status = get_event_status(&http_response);
+            let mut sent = if event_status == EventStatus::Delivered {
+                batch_size
+            } else {
+                0
+            };
+
+            if event_status == EventStatus::Failed && http_response.status().is_success() {
+                if let Some(outcome) = partial_failures
+                    .handle(&mut http_service, &payload, &http_response)
+                    .await
+                {
+                    sent = outcome.indexed;
+                    if outcome.dropped == 0 {
+                        event_status = EventStatus::Delivered;
+                    }
+                }
+            }
+
+            if sent > 0 {
                 emit!(&EventsSent {
-                    count: batch_size,
-                    byte_size
+                    count: sent,
+                    byte_size: byte_size * sent / batch_size.max(1),
                 });
             }
             Ok(ElasticSearchResponse {
@@ -222,7 +248,7 @@ fn get_event_status(response: &Response<Bytes>) -> EventStatus {
     if response.status().is_success() {
         let body = String::from_utf8_lossy(response.body());
         if body.contains("\"errors\":true") {
-            error!(message = "Response contained errors.", ?response);
+            debug!(message = "Response contained errors.", ?response);
             EventStatus::Failed
         } else {
             trace!(message = "Response successful.", ?response);
@@ -88,6 +88,8 @@ pub fn process_log(
 ) -> Option<ProcessedEvent> {
     let index = mode.index(&log)?;
     let bulk_action = mode.bulk_action(&log)?;
+    let pipeline = mode.pipeline(&log)?;
+    let routing = mode.routing(&log)?;
 
     if let Some(cfg) = mode.as_data_stream_config() {
         cfg.sync_fields(&mut log);
@@ -104,6 +106,8 @@ pub fn process_log(
         bulk_action,
         log,
         id,
+        pipeline,
+        routing,
     })
 }
 
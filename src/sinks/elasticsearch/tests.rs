@@ -202,6 +202,63 @@ fn decode_bulk_action() {
     assert!(matches!(action, BulkAction::Create));
 }
 
+#[test]
+fn encode_bulk_pipeline_and_routing() {
+    let config: ElasticSearchConfig = toml::from_str(
+        r#"
+        endpoint = "https://example.com"
+        bulk.index = "vector"
+        bulk.pipeline = "{{ ingest }}"
+        bulk.routing = "{{ tenant }}"
+        "#,
+    )
+    .unwrap();
+    let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+    let mut log = LogEvent::from("hello there");
+    log.insert("ingest", "geoip");
+    log.insert("tenant", "acme");
+    let mut other = LogEvent::from("hello again");
+    other.insert("ingest", "");
+    other.insert("tenant", "umbrella");
+
+    let mut encoded = vec![];
+    let encoded_size = es
+        .encoding
+        .encode_input(
+            vec![
+                process_log(log, &es.mode, &None).unwrap(),
+                process_log(other, &es.mode, &None).unwrap(),
+            ],
+            &mut encoded,
+        )
+        .unwrap();
+
+    let expected = r#"{"index":{"_index":"vector","_type":"","routing":"acme","pipeline":"geoip"}}
+{"ingest":"geoip","message":"hello there","tenant":"acme"}
+{"index":{"_index":"vector","_type":"","routing":"umbrella"}}
+{"ingest":"","message":"hello again","tenant":"umbrella"}
+"#;
+    assert_eq!(std::str::from_utf8(&encoded).unwrap(), expected);
+    assert_eq!(encoded.len(), encoded_size);
+}
+
+#[test]
+fn drops_event_when_routing_fails_to_render() {
+    let config: ElasticSearchConfig = toml::from_str(
+        r#"
+        endpoint = "https://example.com"
+        bulk.index = "vector"
+        bulk.routing = "{{ tenant }}"
+        "#,
+    )
+    .unwrap();
+    let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+    let log = LogEvent::from("hello there");
+    assert!(process_log(log, &es.mode, &None).is_none());
+}
+
 #[test]
 fn encode_datastream_mode_no_sync() {
     use crate::config::log_schema;
@@ -0,0 +1,545 @@
+use crate::{
+    buffers::Acker,
+    codecs::FramingEncoder,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    internal_events::{
+        EventsSent, ExecSinkExited, ExecSinkFieldNotFound, ExecSinkSpawnFailed,
+        ExecSinkUnexpectedMarker, ExecSinkWriteFailed,
+    },
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::ExponentialBackoff,
+        StreamSink,
+    },
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{
+    future,
+    stream::{BoxStream, StreamExt},
+    FutureExt, SinkExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::VecDeque,
+    io,
+    path::PathBuf,
+    process::{ExitStatus, Stdio},
+    time::Duration,
+};
+use tokio::{
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::mpsc,
+    time::sleep,
+};
+use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec};
+use vector_core::ByteSizeOf;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSinkConfig {
+    pub command: Vec<String>,
+    pub working_directory: Option<PathBuf>,
+    pub encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    pub framing: FramingEncoder,
+    #[serde(default)]
+    pub acknowledge_on_stdout: bool,
+    #[serde(default = "default_max_pending_events")]
+    pub max_pending_events: usize,
+    #[serde(default = "default_restart_initial_backoff_secs")]
+    pub restart_initial_backoff_secs: u64,
+    #[serde(default = "default_restart_max_backoff_secs")]
+    pub restart_max_backoff_secs: u64,
+}
+
+const fn default_max_pending_events() -> usize {
+    1000
+}
+
+const fn default_restart_initial_backoff_secs() -> u64 {
+    1
+}
+
+const fn default_restart_max_backoff_secs() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+enum BuildError {
+    #[snafu(display("A non-empty list for command must be provided"))]
+    CommandEmpty,
+    #[snafu(display("The maximum number of pending events must be greater than zero"))]
+    ZeroPendingEvents,
+}
+
+inventory::submit! {
+    SinkDescription::new::<ExecSinkConfig>("exec")
+}
+
+impl GenerateConfig for ExecSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            command: vec!["cat".to_owned()],
+            working_directory: None,
+            encoding: Encoding::Json.into(),
+            framing: FramingEncoder::default(),
+            acknowledge_on_stdout: false,
+            max_pending_events: default_max_pending_events(),
+            restart_initial_backoff_secs: default_restart_initial_backoff_secs(),
+            restart_max_backoff_secs: default_restart_max_backoff_secs(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exec")]
+impl SinkConfig for ExecSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if self.command.is_empty() {
+            return Err(Box::new(BuildError::CommandEmpty));
+        }
+        if self.max_pending_events == 0 {
+            return Err(Box::new(BuildError::ZeroPendingEvents));
+        }
+
+        let sink = ExecSink {
+            config: self.clone(),
+            command: self.command.join(" "),
+            acker: cx.acker(),
+        };
+
+        Ok((
+            super::VectorSink::Stream(Box::new(sink)),
+            future::ok(()).boxed(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "exec"
+    }
+}
+
+fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<Bytes> {
+    encoding.apply_rules(&mut event);
+    match event {
+        Event::Log(log) => match encoding.codec() {
+            Encoding::Json => serde_json::to_vec(&log)
+                .map_err(|error| {
+                    error!(message = "Error encoding json.", %error);
+                })
+                .ok()
+                .map(Into::into),
+            Encoding::Text => {
+                let field = log_schema().message_key();
+                match log.get(field) {
+                    Some(value) => Some(value.as_bytes()),
+                    None => {
+                        emit!(&ExecSinkFieldNotFound {
+                            missing_field: field,
+                        });
+                        None
+                    }
+                }
+            }
+        },
+        Event::Metric(metric) => match encoding.codec() {
+            Encoding::Json => serde_json::to_vec(&metric)
+                .map_err(|error| {
+                    error!(message = "Error encoding json.", %error);
+                })
+                .ok()
+                .map(Into::into),
+            Encoding::Text => Some(metric.to_string().into()),
+        },
+    }
+}
+
+/// An event written, or to be written, to the subprocess.
+struct PendingEvent {
+    frame: Bytes,
+    byte_size: usize,
+    finalizers: EventFinalizers,
+}
+
+/// Why the sink stopped feeding a subprocess.
+enum Outcome {
+    /// The subprocess exited, or its stdin was closed, and must be restarted.
+    Exited,
+    /// There are no more events to write.
+    Finished,
+}
+
+struct ExecSink {
+    config: ExecSinkConfig,
+    command: String,
+    acker: Acker,
+}
+
+impl ExecSink {
+    fn spawn(&self) -> io::Result<Child> {
+        let mut command = Command::new(&self.config.command[0]);
+        command.args(&self.config.command[1..]);
+        if let Some(dir) = &self.config.working_directory {
+            command.current_dir(dir);
+        }
+        command
+            .stdin(Stdio::piped())
+            .stdout(if self.config.acknowledge_on_stdout {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+    }
+
+    fn backoff(&self) -> ExponentialBackoff {
+        ExponentialBackoff::from_millis(2)
+            .factor(self.config.restart_initial_backoff_secs * 500)
+            .max_delay(Duration::from_secs(self.config.restart_max_backoff_secs))
+    }
+
+    fn deliver(&self, event: PendingEvent) {
+        event.finalizers.update_status(EventStatus::Delivered);
+        self.acker.ack(1);
+        emit!(&EventsSent {
+            count: 1,
+            byte_size: event.byte_size,
+        });
+    }
+
+    fn fail(&self, pending: &mut VecDeque<PendingEvent>) {
+        let count = pending.len();
+        for event in pending.drain(..) {
+            event.finalizers.update_status(EventStatus::Errored);
+        }
+        self.acker.ack(count);
+    }
+
+    async fn wait(&self, child: &mut Child) {
+        let status = child.wait().await;
+        self.exited(status);
+    }
+
+    fn exited(&self, status: io::Result<ExitStatus>) {
+        emit!(&ExecSinkExited {
+            command: &self.command,
+            status,
+        });
+    }
+
+    /// Feeds events to a freshly spawned subprocess, starting with the ones the
+    /// previous subprocess did not acknowledge, until it exits or the input
+    /// ends.
+    async fn feed(
+        &self,
+        child: &mut Child,
+        input: &mut BoxStream<'_, Event>,
+        pending: &mut VecDeque<PendingEvent>,
+        backoff: &mut ExponentialBackoff,
+    ) -> Outcome {
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let mut writer = FramedWrite::new(stdin, self.config.framing.clone());
+        let mut markers = child.stdout.take().map(read_markers);
+
+        if let Err(error) = self.replay(&mut writer, pending).await {
+            emit!(&ExecSinkWriteFailed {
+                command: &self.command,
+                error,
+            });
+            self.wait(child).await;
+            return Outcome::Exited;
+        }
+
+        loop {
+            tokio::select! {
+                event = input.next(), if pending.len() < self.config.max_pending_events => {
+                    let mut event = match event {
+                        Some(event) => event,
+                        None => break,
+                    };
+                    let finalizers = event.take_finalizers();
+                    let byte_size = event.size_of();
+                    let frame = match encode_event(event, &self.config.encoding) {
+                        Some(frame) => frame,
+                        None => {
+                            self.acker.ack(1);
+                            continue;
+                        }
+                    };
+                    pending.push_back(PendingEvent {
+                        frame: frame.clone(),
+                        byte_size,
+                        finalizers,
+                    });
+
+                    // Writing waits for the subprocess to read from its stdin
+                    // once the pipe is full, which holds the next events back.
+                    if let Err(error) = writer.send(frame).await {
+                        emit!(&ExecSinkWriteFailed {
+                            command: &self.command,
+                            error,
+                        });
+                        self.drain_markers(&mut markers, pending).await;
+                        self.wait(child).await;
+                        return Outcome::Exited;
+                    }
+                    *backoff = self.backoff();
+
+                    if !self.config.acknowledge_on_stdout {
+                        let event = pending.pop_front().expect("event was just added");
+                        self.deliver(event);
+                    }
+                }
+                marker = next_marker(&mut markers) => match marker {
+                    Some(()) => self.acknowledge(pending),
+                    None => markers = None,
+                },
+                status = child.wait() => {
+                    self.drain_markers(&mut markers, pending).await;
+                    self.exited(status);
+                    return Outcome::Exited;
+                }
+            }
+        }
+
+        // Closing stdin lets the subprocess know there are no more events.
+        if let Err(error) = writer.close().await {
+            emit!(&ExecSinkWriteFailed {
+                command: &self.command,
+                error,
+            });
+        }
+        self.drain_markers(&mut markers, pending).await;
+        self.wait(child).await;
+        Outcome::Finished
+    }
+
+    async fn replay(
+        &self,
+        writer: &mut FramedWrite<ChildStdin, FramingEncoder>,
+        pending: &mut VecDeque<PendingEvent>,
+    ) -> io::Result<()> {
+        for event in pending.iter() {
+            writer.feed(event.frame.clone()).await?;
+        }
+        writer.flush().await?;
+
+        if !self.config.acknowledge_on_stdout {
+            for event in pending.drain(..) {
+                self.deliver(event);
+            }
+        }
+        Ok(())
+    }
+
+    fn acknowledge(&self, pending: &mut VecDeque<PendingEvent>) {
+        match pending.pop_front() {
+            Some(event) => self.deliver(event),
+            None => emit!(&ExecSinkUnexpectedMarker {
+                command: &self.command,
+            }),
+        }
+    }
+
+    /// Reads the markers written by the subprocess before it exited.
+    async fn drain_markers(
+        &self,
+        markers: &mut Option<mpsc::UnboundedReceiver<()>>,
+        pending: &mut VecDeque<PendingEvent>,
+    ) {
+        if let Some(markers) = markers {
+            while markers.recv().await.is_some() {
+                self.acknowledge(pending);
+            }
+        }
+    }
+}
+
+/// Reads the markers the subprocess writes to its stdout in a separate task,
+/// so that the subprocess never blocks on a full stdout pipe while the sink is
+/// waiting for it to read its stdin.
+fn read_markers(stdout: ChildStdout) -> mpsc::UnboundedReceiver<()> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut markers = FramedRead::new(stdout, LinesCodec::new());
+        while let Some(marker) = markers.next().await {
+            if let Err(error) = marker {
+                error!(message = "Failed reading acknowledgement markers.", %error);
+                break;
+            }
+            if tx.send(()).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+async fn next_marker(markers: &mut Option<mpsc::UnboundedReceiver<()>>) -> Option<()> {
+    match markers {
+        Some(markers) => markers.recv().await,
+        None => future::pending().await,
+    }
+}
+
+#[async_trait]
+impl StreamSink for ExecSink {
+    async fn run(self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let mut pending = VecDeque::new();
+        let mut backoff = self.backoff();
+
+        loop {
+            let outcome = match self.spawn() {
+                Ok(mut child) => {
+                    self.feed(&mut child, &mut input, &mut pending, &mut backoff)
+                        .await
+                }
+                Err(error) => {
+                    emit!(&ExecSinkSpawnFailed {
+                        command: &self.command,
+                        error,
+                    });
+                    Outcome::Exited
+                }
+            };
+
+            match outcome {
+                Outcome::Finished => {
+                    // Events the last subprocess did not acknowledge before
+                    // exiting can't be delivered anymore.
+                    self.fail(&mut pending);
+                    return Ok(());
+                }
+                Outcome::Exited => {
+                    let delay = backoff.next().expect("backoff never ends");
+                    debug!(message = "Restarting subprocess.", command = %self.command, ?delay);
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{BatchNotifier, BatchStatus, LogEvent},
+        test_util::random_lines,
+    };
+    use futures::stream;
+    use tokio::time::timeout;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExecSinkConfig>();
+    }
+
+    #[test]
+    fn parses_framing() {
+        let config: ExecSinkConfig = toml::from_str(
+            r#"
+            command = ["cat"]
+            encoding = "text"
+            framing.method = "character_delimited"
+            framing.delimiter = "\t"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.framing,
+            FramingEncoder::CharacterDelimited { delimiter: '\t' }
+        );
+    }
+
+    fn config(command: &str, acknowledge_on_stdout: bool) -> ExecSinkConfig {
+        ExecSinkConfig {
+            command: vec!["sh".to_owned(), "-c".to_owned(), command.to_owned()],
+            working_directory: None,
+            encoding: Encoding::Text.into(),
+            framing: FramingEncoder::default(),
+            acknowledge_on_stdout,
+            max_pending_events: default_max_pending_events(),
+            restart_initial_backoff_secs: default_restart_initial_backoff_secs(),
+            restart_max_backoff_secs: default_restart_max_backoff_secs(),
+        }
+    }
+
+    async fn run(config: ExecSinkConfig, lines: Vec<String>) -> BatchStatus {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let events = lines
+            .into_iter()
+            .map(|line| Event::from(LogEvent::from(line).with_batch_notifier(&batch)))
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        timeout(Duration::from_secs(30), sink.run(stream::iter(events)))
+            .await
+            .expect("sink didn't finish")
+            .unwrap();
+        timeout(Duration::from_secs(5), receiver).await.unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn writes_events_to_stdin() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("out");
+        let lines = random_lines(20).take(10).collect::<Vec<_>>();
+
+        let status = run(
+            config(&format!("cat > {}", path.display()), false),
+            lines.clone(),
+        )
+        .await;
+
+        assert_eq!(status, BatchStatus::Delivered);
+        let output = std::fs::read_to_string(path).unwrap();
+        assert_eq!(output.lines().collect::<Vec<_>>(), lines);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn acknowledges_events_from_stdout() {
+        let lines = random_lines(20).take(10).collect::<Vec<_>>();
+
+        let status = run(config("cat", true), lines.clone()).await;
+        assert_eq!(status, BatchStatus::Delivered);
+
+        // The subprocess reads the events without acknowledging them.
+        let status = run(config("cat > /dev/null", true), lines).await;
+        assert_eq!(status, BatchStatus::Errored);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn acknowledges_large_events_from_stdout() {
+        // Echoing the events back fills the stdout pipe long before they were
+        // all written to stdin.
+        let lines = random_lines(64 * 1024).take(50).collect::<Vec<_>>();
+
+        let status = run(config("cat", true), lines).await;
+        assert_eq!(status, BatchStatus::Delivered);
+    }
+}
@@ -3,7 +3,10 @@ use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
-    internal_events::{EventsSent, FileBytesSent, FileOpen, TemplateRenderingFailed},
+    internal_events::{
+        EventsSent, FileBytesSent, FileOpen, FileRotated, FileRotationFailed,
+        TemplateRenderingFailed,
+    },
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         StreamSink,
@@ -13,13 +16,14 @@ use crate::{
 use async_compression::tokio::write::GzipEncoder;
 use async_trait::async_trait;
 use bytes::Bytes;
+use chrono::Utc;
 use futures::{
     future,
     stream::{BoxStream, StreamExt},
     FutureExt,
 };
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use vector_core::ByteSizeOf;
 
 use tokio::{
@@ -27,7 +31,9 @@ use tokio::{
     io::AsyncWriteExt,
 };
 mod bytes_path;
+mod rotation;
 use bytes_path::BytesPath;
+pub use rotation::{RotatedCompression, RotationConfig};
 use std::convert::TryFrom;
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -41,6 +47,8 @@ pub struct FileSinkConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub compression: Compression,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate: Option<RotationConfig>,
 }
 
 inventory::submit! {
@@ -54,6 +62,7 @@ impl GenerateConfig for FileSinkConfig {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Default::default(),
+            rotate: None,
         })
         .unwrap()
     }
@@ -79,38 +88,60 @@ impl Default for Compression {
     }
 }
 
-enum OutFile {
+enum FileWriter {
     Regular(File),
     Gzip(GzipEncoder<File>),
 }
 
+struct OutFile {
+    writer: FileWriter,
+    /// The amount of bytes written to the file, including the bytes that were
+    /// in it when it was opened.
+    bytes_written: u64,
+    /// When the file was created, or opened if the creation time is
+    /// unavailable.
+    opened_at: SystemTime,
+}
+
 impl OutFile {
-    fn new(file: File, compression: Compression) -> Self {
-        match compression {
-            Compression::None => OutFile::Regular(file),
-            Compression::Gzip => OutFile::Gzip(GzipEncoder::new(file)),
+    async fn new(file: File, compression: Compression) -> Self {
+        let metadata = file.metadata().await.ok();
+        let bytes_written = metadata.as_ref().map_or(0, |metadata| metadata.len());
+        let opened_at = metadata
+            .and_then(|metadata| metadata.created().ok())
+            .unwrap_or_else(SystemTime::now);
+        let writer = match compression {
+            Compression::None => FileWriter::Regular(file),
+            Compression::Gzip => FileWriter::Gzip(GzipEncoder::new(file)),
+        };
+        Self {
+            writer,
+            bytes_written,
+            opened_at,
         }
     }
 
     async fn sync_all(&mut self) -> Result<(), std::io::Error> {
-        match self {
-            OutFile::Regular(file) => file.sync_all().await,
-            OutFile::Gzip(gzip) => gzip.get_mut().sync_all().await,
+        match &mut self.writer {
+            FileWriter::Regular(file) => file.sync_all().await,
+            FileWriter::Gzip(gzip) => gzip.get_mut().sync_all().await,
         }
     }
 
     async fn shutdown(&mut self) -> Result<(), std::io::Error> {
-        match self {
-            OutFile::Regular(file) => file.shutdown().await,
-            OutFile::Gzip(gzip) => gzip.shutdown().await,
+        match &mut self.writer {
+            FileWriter::Regular(file) => file.shutdown().await,
+            FileWriter::Gzip(gzip) => gzip.shutdown().await,
         }
     }
 
     async fn write_all(&mut self, src: &[u8]) -> Result<(), std::io::Error> {
-        match self {
-            OutFile::Regular(file) => file.write_all(src).await,
-            OutFile::Gzip(gzip) => gzip.write_all(src).await,
+        match &mut self.writer {
+            FileWriter::Regular(file) => file.write_all(src).await?,
+            FileWriter::Gzip(gzip) => gzip.write_all(src).await?,
         }
+        self.bytes_written += src.len() as u64;
+        Ok(())
     }
 
     /// Shutdowns by flushing data, writing headers, and syncing all of that
@@ -128,6 +159,16 @@ impl SinkConfig for FileSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if let Some(rotate) = &self.rotate {
+            rotate.validate()?;
+            if self.compression != Compression::None
+                && rotate.compression != RotatedCompression::None
+            {
+                return Err(
+                    "`rotate.compression` can't be used together with `compression`".into(),
+                );
+            }
+        }
         let sink = FileSink::new(self, cx.acker());
         Ok((
             super::VectorSink::Stream(Box::new(sink)),
@@ -152,6 +193,7 @@ pub struct FileSink {
     idle_timeout: Duration,
     files: ExpiringHashMap<Bytes, OutFile>,
     compression: Compression,
+    rotate: Option<RotationConfig>,
 }
 
 impl FileSink {
@@ -163,6 +205,7 @@ impl FileSink {
             idle_timeout: Duration::from_secs(config.idle_timeout_secs.unwrap_or(30)),
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            rotate: config.rotate.clone(),
         }
     }
 
@@ -280,7 +323,7 @@ impl FileSink {
                 }
             };
 
-            let outfile = OutFile::new(file, self.compression);
+            let outfile = OutFile::new(file, self.compression).await;
 
             self.files.insert_at(path.clone(), outfile, next_deadline);
             emit!(&FileOpen {
@@ -304,6 +347,42 @@ impl FileSink {
             }
             Err(error) => error!(message = "Failed to write file.", path = ?path, %error),
         }
+
+        let should_rotate = self.rotate.as_ref().map_or(false, |rotate| {
+            rotate.should_rotate(file.bytes_written, file.opened_at, SystemTime::now())
+        });
+        if should_rotate {
+            self.rotate_file(&path).await;
+        }
+    }
+
+    /// Closes the file at `path` and rotates it. The next event for this
+    /// path opens a fresh file.
+    async fn rotate_file(&mut self, path: &Bytes) {
+        let rotate = match &self.rotate {
+            Some(rotate) => rotate,
+            None => return,
+        };
+        if let Some((mut file, _)) = self.files.remove(path) {
+            if let Err(error) = file.close().await {
+                error!(message = "Failed to close file.", ?path, %error);
+            }
+            emit!(&FileOpen {
+                count: self.files.len()
+            });
+        }
+
+        let bytes_path = BytesPath::new(path.clone());
+        match rotate.rotate(bytes_path.as_ref(), Utc::now()).await {
+            Ok(rotated) => emit!(&FileRotated {
+                file: String::from_utf8_lossy(path),
+                rotated: &rotated,
+            }),
+            Err(error) => emit!(&FileRotationFailed {
+                file: String::from_utf8_lossy(path),
+                error,
+            }),
+        }
     }
 }
 
@@ -384,6 +463,7 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotate: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -411,6 +491,7 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::Gzip,
+            rotate: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -443,6 +524,7 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotate: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -512,6 +594,49 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn rotates_by_size() {
+        components::init_test();
+        trace_init();
+
+        let directory = temp_dir();
+        let path = directory.join("out.log");
+
+        let config = FileSinkConfig {
+            path: path.to_str().unwrap().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::None,
+            rotate: Some(RotationConfig {
+                max_size_bytes: Some(100),
+                max_age_secs: None,
+                suffix_format: "%Y%m%dT%H%M%S%.9f".into(),
+                compression: RotatedCompression::None,
+                keep_files: Some(3),
+            }),
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _events) = random_lines_with_stream(10, 64, None);
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+        SINK_TESTS.assert(&FILE_SINK_TAGS);
+
+        // Every second line crosses the size limit, and only the most recent
+        // rotated files are kept.
+        let mut rotated = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|entry| entry != &path)
+            .collect::<Vec<_>>();
+        rotated.sort();
+        assert_eq!(rotated.len(), 3);
+
+        let output = rotated.iter().flat_map(lines_from_file).collect::<Vec<_>>();
+        assert_eq!(output, input[4..].to_vec());
+    }
+
     #[tokio::test]
     async fn reopening() {
         components::init_test();
@@ -524,6 +649,7 @@ mod tests {
             idle_timeout_secs: Some(1),
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotate: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -0,0 +1,401 @@
+//! Rotation of the files written by the `file` sink.
+
+use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
+use chrono::{
+    format::{self, Parsed, StrftimeItems},
+    DateTime, Utc,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    fs::{self, File},
+    io::{self, AsyncWriteExt},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RotationConfig {
+    /// Rotate the file once it grows past this many bytes.
+    pub max_size_bytes: Option<u64>,
+    /// Rotate the file once it has been open for this many seconds.
+    pub max_age_secs: Option<u64>,
+    /// The `strftime` format of the suffix appended to the name of the rotated
+    /// files.
+    #[serde(default = "default_suffix_format")]
+    pub suffix_format: String,
+    /// How to compress the rotated files.
+    #[serde(default)]
+    pub compression: RotatedCompression,
+    /// How many rotated files to keep around, per file path.
+    pub keep_files: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RotatedCompression {
+    Gzip,
+    Zstd,
+    None,
+}
+
+impl Default for RotatedCompression {
+    fn default() -> Self {
+        RotatedCompression::None
+    }
+}
+
+impl RotatedCompression {
+    const fn extension(self) -> Option<&'static str> {
+        match self {
+            RotatedCompression::Gzip => Some("gz"),
+            RotatedCompression::Zstd => Some("zst"),
+            RotatedCompression::None => None,
+        }
+    }
+}
+
+fn default_suffix_format() -> String {
+    "%Y%m%dT%H%M%S".into()
+}
+
+impl RotationConfig {
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.max_size_bytes.is_none() && self.max_age_secs.is_none() {
+            return Err(
+                "at least one of `rotate.max_size_bytes` and `rotate.max_age_secs` must be set"
+                    .into(),
+            );
+        }
+        if self.max_size_bytes == Some(0) || self.max_age_secs == Some(0) {
+            return Err(
+                "`rotate.max_size_bytes` and `rotate.max_age_secs` must be positive".into(),
+            );
+        }
+        if self.suffix_format.is_empty() || self.suffix_format.contains('/') {
+            return Err(
+                "`rotate.suffix_format` must be non-empty and not contain path separators".into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether a file with the given amount of written bytes, opened at the
+    /// given time, should be rotated.
+    pub fn should_rotate(
+        &self,
+        bytes_written: u64,
+        opened_at: SystemTime,
+        now: SystemTime,
+    ) -> bool {
+        let too_big = self
+            .max_size_bytes
+            .map_or(false, |max_size| bytes_written >= max_size);
+        let too_old = self.max_age_secs.map_or(false, |max_age| {
+            now.duration_since(opened_at).unwrap_or_default() >= Duration::from_secs(max_age)
+        });
+        too_big || too_old
+    }
+
+    /// Rotates the closed file at `path`: renames it, compresses it if
+    /// configured to, and then removes rotated files past the retention limit.
+    pub async fn rotate(&self, path: &Path, now: DateTime<Utc>) -> io::Result<PathBuf> {
+        let rotated = rotated_path(path, &now.format(&self.suffix_format).to_string());
+        let rotated = unique_path(rotated).await;
+        fs::rename(path, &rotated).await?;
+
+        let rotated = match self.compression.extension() {
+            None => rotated,
+            Some(extension) => {
+                let compressed = append_extension(&rotated, extension);
+                compress_file(&rotated, &compressed, self.compression).await?;
+                fs::remove_file(&rotated).await?;
+                compressed
+            }
+        };
+
+        if let Some(keep_files) = self.keep_files {
+            self.enforce_retention(path, keep_files).await?;
+        }
+
+        Ok(rotated)
+    }
+
+    /// Removes the oldest files rotated out of `path`, keeping at most
+    /// `keep_files` of them.
+    async fn enforce_retention(&self, path: &Path, keep_files: usize) -> io::Result<()> {
+        let (directory, file_name) = match (path.parent(), path.file_name()) {
+            (Some(directory), Some(file_name)) => (directory, file_name.to_string_lossy()),
+            _ => return Ok(()),
+        };
+        let prefix = format!("{}.", file_name);
+
+        let mut rotated = Vec::new();
+        let mut entries = fs::read_dir(directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // Other files the sink writes to can share the prefix, such as
+            // `app.log` next to `app`, so only names this rotation produces
+            // count.
+            match name.strip_prefix(&prefix) {
+                Some(suffix) if self.is_rotated_suffix(suffix) => (),
+                _ => continue,
+            }
+            let modified = entry.metadata().await?.modified()?;
+            rotated.push((modified, entry.path()));
+        }
+
+        // Newest first.
+        rotated.sort_by(|a, b| b.cmp(a));
+        for (_, path) in rotated.into_iter().skip(keep_files) {
+            debug!(message = "Removing rotated file past retention.", ?path);
+            fs::remove_file(path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether `suffix`, following the name of the file and a dot, is one
+    /// appended by a rotation: the formatted rotation time, optionally
+    /// followed by the counter of `unique_path`, and then by the extension of
+    /// a compression.
+    fn is_rotated_suffix(&self, suffix: &str) -> bool {
+        let suffix = [RotatedCompression::Gzip, RotatedCompression::Zstd]
+            .iter()
+            .filter_map(|compression| compression.extension())
+            .find_map(|extension| suffix.strip_suffix(extension)?.strip_suffix('.'))
+            .unwrap_or(suffix);
+
+        self.is_formatted_time(suffix)
+            || suffix.rsplit_once('.').map_or(false, |(suffix, counter)| {
+                !counter.is_empty()
+                    && counter.bytes().all(|byte| byte.is_ascii_digit())
+                    && self.is_formatted_time(suffix)
+            })
+    }
+
+    fn is_formatted_time(&self, value: &str) -> bool {
+        let mut parsed = Parsed::new();
+        format::parse(&mut parsed, value, StrftimeItems::new(&self.suffix_format)).is_ok()
+    }
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+fn rotated_path(path: &Path, suffix: &str) -> PathBuf {
+    append_extension(path, suffix)
+}
+
+/// Avoids clobbering a previously rotated file when the suffix doesn't change
+/// between two rotations.
+async fn unique_path(path: PathBuf) -> PathBuf {
+    let mut candidate = path.clone();
+    let mut n = 1;
+    while fs::metadata(&candidate).await.is_ok() {
+        candidate = append_extension(&path, &n.to_string());
+        n += 1;
+    }
+    candidate
+}
+
+async fn compress_file(
+    source: &Path,
+    destination: &Path,
+    compression: RotatedCompression,
+) -> io::Result<()> {
+    let mut input = File::open(source).await?;
+    let output = File::create(destination).await?;
+    match compression {
+        RotatedCompression::Gzip => {
+            let mut encoder = GzipEncoder::new(output);
+            io::copy(&mut input, &mut encoder).await?;
+            encoder.shutdown().await?;
+            encoder.get_mut().sync_all().await
+        }
+        RotatedCompression::Zstd => {
+            let mut encoder = ZstdEncoder::new(output);
+            io::copy(&mut input, &mut encoder).await?;
+            encoder.shutdown().await?;
+            encoder.get_mut().sync_all().await
+        }
+        RotatedCompression::None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use chrono::TimeZone;
+
+    fn config() -> RotationConfig {
+        RotationConfig {
+            max_size_bytes: Some(10),
+            max_age_secs: None,
+            suffix_format: default_suffix_format(),
+            compression: RotatedCompression::None,
+            keep_files: None,
+        }
+    }
+
+    #[test]
+    fn validates() {
+        assert!(config().validate().is_ok());
+        assert!(RotationConfig {
+            max_size_bytes: None,
+            ..config()
+        }
+        .validate()
+        .is_err());
+        assert!(RotationConfig {
+            suffix_format: "%Y/%m".into(),
+            ..config()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn should_rotate() {
+        let now = SystemTime::now();
+        let config = RotationConfig {
+            max_age_secs: Some(60),
+            ..config()
+        };
+
+        assert!(!config.should_rotate(5, now, now));
+        assert!(config.should_rotate(10, now, now));
+        assert!(config.should_rotate(5, now - Duration::from_secs(60), now));
+    }
+
+    #[tokio::test]
+    async fn rotates_with_suffix() {
+        let directory = temp_dir();
+        let path = directory.join("out.log");
+        fs::write(&path, "data\n").await.unwrap();
+
+        let now = Utc.ymd(2021, 10, 1).and_hms(12, 0, 0);
+        let rotated = config().rotate(&path, now).await.unwrap();
+        assert_eq!(rotated, directory.join("out.log.20211001T120000"));
+        assert!(fs::metadata(&path).await.is_err());
+
+        // Rotating again within the same suffix doesn't clobber the first one.
+        fs::write(&path, "more\n").await.unwrap();
+        let rotated = config().rotate(&path, now).await.unwrap();
+        assert_eq!(rotated, directory.join("out.log.20211001T120000.1"));
+    }
+
+    #[tokio::test]
+    async fn compresses_rotated_file() {
+        let directory = temp_dir();
+        let path = directory.join("out.log");
+        fs::write(&path, "data\n").await.unwrap();
+
+        let config = RotationConfig {
+            compression: RotatedCompression::Gzip,
+            ..config()
+        };
+        let rotated = config
+            .rotate(&path, Utc.ymd(2021, 10, 1).and_hms(12, 0, 0))
+            .await
+            .unwrap();
+        assert_eq!(rotated, directory.join("out.log.20211001T120000.gz"));
+        assert_eq!(
+            crate::test_util::lines_from_gzip_file(&rotated),
+            vec!["data".to_string()]
+        );
+    }
+
+    #[test]
+    fn matches_rotated_suffixes() {
+        let config = config();
+        assert!(config.is_rotated_suffix("20211001T120000"));
+        assert!(config.is_rotated_suffix("20211001T120000.1"));
+        assert!(config.is_rotated_suffix("20211001T120000.gz"));
+        assert!(config.is_rotated_suffix("20211001T120000.12.zst"));
+
+        assert!(!config.is_rotated_suffix("log"));
+        assert!(!config.is_rotated_suffix("log.20211001T120000"));
+        assert!(!config.is_rotated_suffix("20211001T120000.old"));
+        assert!(!config.is_rotated_suffix("gz"));
+    }
+
+    #[tokio::test]
+    async fn keeps_bounded_number_of_files() {
+        let directory = temp_dir();
+        let path = directory.join("out.log");
+        let config = RotationConfig {
+            keep_files: Some(2),
+            ..config()
+        };
+
+        for hour in 0..4 {
+            fs::write(&path, "data\n").await.unwrap();
+            config
+                .rotate(&path, Utc.ymd(2021, 10, 1).and_hms(hour, 0, 0))
+                .await
+                .unwrap();
+            // Make sure modification times differ.
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let mut remaining = Vec::new();
+        let mut entries = fs::read_dir(&directory).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            remaining.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec!["out.log.20211001T020000", "out.log.20211001T030000"]
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_unrelated_sibling_files() {
+        let directory = temp_dir();
+        let path = directory.join("app");
+        // Files sharing the prefix, such as ones written through the same
+        // templated path.
+        for name in &["app.log", "app.log.20211001T000000", "app.backup"] {
+            fs::write(directory.join(name), "other\n").await.unwrap();
+        }
+        let config = RotationConfig {
+            keep_files: Some(1),
+            ..config()
+        };
+
+        for _ in 0..3 {
+            fs::write(&path, "data\n").await.unwrap();
+            config
+                .rotate(&path, Utc.ymd(2021, 10, 1).and_hms(1, 0, 0))
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let mut remaining = Vec::new();
+        let mut entries = fs::read_dir(&directory).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            remaining.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "app.20211001T010000.2",
+                "app.backup",
+                "app.log",
+                "app.log.20211001T000000"
+            ]
+        );
+    }
+}
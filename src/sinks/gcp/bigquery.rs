@@ -0,0 +1,1062 @@
+//! The `gcp_bigquery` sink, writing rows to a BigQuery table through the
+//! Storage Write API.
+//!
+//! Events are turned into rows according to the configured field-to-column
+//! mapping, which is also used to generate the protobuf schema the rows are
+//! serialized with. Rows are written either to the table's default stream, at
+//! least once, or to a dedicated committed stream, where appends carry
+//! offsets so that retried appends aren't written twice.
+
+use super::{GcpAuthConfig, GcpCredentials, Scope};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{GcpBigqueryColumnConversionFailed, GcpBigqueryRowsRejected},
+    proto::bigquery::{self as proto, append_rows_request, append_rows_response, write_stream},
+    sinks::{
+        util::{
+            grpc::{new_client, with_default_scheme, HyperSvc},
+            retries::RetryLogic,
+            BatchConfig, BatchSettings, BatchSink, EncodedEvent, EncodedLength, ServiceBuilderExt,
+            TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsOptions, TlsSettings},
+};
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use prost::encoding;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::ServiceBuilder;
+use vector_core::ByteSizeOf;
+
+const NAME: &str = "gcp_bigquery";
+const DEFAULT_ENDPOINT: &str = "https://bigquerystorage.googleapis.com";
+const ROW_MESSAGE_NAME: &str = "VectorRow";
+
+type Client = proto::Client<HyperSvc>;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BigqueryConfig {
+    pub project: String,
+    pub dataset: String,
+    pub table: String,
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub skip_authentication: bool,
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+    #[serde(default)]
+    pub write_mode: WriteMode,
+    #[serde(default)]
+    pub row_encoding: RowEncoding,
+    pub columns: Vec<ColumnConfig>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteMode {
+    /// Append to the table's default stream, rows are available right away
+    /// and delivered at least once.
+    Default,
+    /// Append to a committed stream created by the sink, tracking offsets to
+    /// avoid duplicating rows when appends are retried.
+    Committed,
+}
+
+impl Default for WriteMode {
+    fn default() -> Self {
+        WriteMode::Default
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowEncoding {
+    /// Columns are encoded as protobuf fields of the configured column types.
+    Proto,
+    /// Columns are encoded as the JSON representation of the event fields, for
+    /// tables whose columns are of the `JSON` type.
+    Json,
+}
+
+impl Default for RowEncoding {
+    fn default() -> Self {
+        RowEncoding::Proto
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// The name of the column in the table.
+    pub name: String,
+    /// The event field holding the column value, the column name by default.
+    pub field: Option<String>,
+    #[serde(default, rename = "type")]
+    pub column_type: ColumnType,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    String,
+    Int64,
+    Float64,
+    Bool,
+    Bytes,
+    Timestamp,
+    Json,
+}
+
+impl Default for ColumnType {
+    fn default() -> Self {
+        ColumnType::String
+    }
+}
+
+impl ColumnType {
+    const fn as_str(self) -> &'static str {
+        match self {
+            ColumnType::String => "string",
+            ColumnType::Int64 => "int64",
+            ColumnType::Float64 => "float64",
+            ColumnType::Bool => "bool",
+            ColumnType::Bytes => "bytes",
+            ColumnType::Timestamp => "timestamp",
+            ColumnType::Json => "json",
+        }
+    }
+
+    /// The protobuf type columns of this type are written as. `TIMESTAMP`
+    /// columns take microseconds since the Unix epoch, and `JSON` columns take
+    /// the JSON text.
+    const fn proto_type(self) -> Type {
+        match self {
+            ColumnType::String | ColumnType::Json => Type::String,
+            ColumnType::Int64 | ColumnType::Timestamp => Type::Int64,
+            ColumnType::Float64 => Type::Double,
+            ColumnType::Bool => Type::Bool,
+            ColumnType::Bytes => Type::Bytes,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BigqueryError {
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: tonic::Status },
+    #[snafu(display("Append failed with code {}: {}", code, message))]
+    Append { code: i32, message: String },
+    #[snafu(display("The append stream ended without a response"))]
+    NoResponse,
+    #[snafu(display("The committed write stream is no longer usable: {}", message))]
+    StreamInvalidated { message: String },
+    #[snafu(display("Table {:?} not found", table))]
+    TableNotFound { table: String },
+    #[snafu(display("Invalid request metadata {:?}", value))]
+    InvalidMetadata { value: String },
+}
+
+#[derive(Debug, Snafu)]
+enum ConfigError {
+    #[snafu(display("`columns` must map at least one column"))]
+    NoColumns,
+    #[snafu(display("Invalid column name {:?}, column names may only contain letters, digits and underscores and must not start with a digit", name))]
+    InvalidColumnName { name: String },
+    #[snafu(display("Column {:?} is mapped more than once", name))]
+    DuplicateColumn { name: String },
+}
+
+inventory::submit! {
+    SinkDescription::new::<BigqueryConfig>(NAME)
+}
+
+impl GenerateConfig for BigqueryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"project = "my-project"
+            dataset = "my-dataset"
+            table = "my-table"
+            credentials_path = "/path/to/credentials.json"
+
+            [[columns]]
+            name = "message"
+
+            [[columns]]
+            name = "timestamp"
+            type = "timestamp""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_bigquery")]
+impl SinkConfig for BigqueryConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let schema = Arc::new(RowSchema::new(&self.columns, self.row_encoding)?);
+
+        let creds = if self.skip_authentication {
+            None
+        } else {
+            self.auth.make_credentials(Scope::CloudPlatform).await?
+        };
+        if let Some(creds) = &creds {
+            creds.spawn_regenerate_token();
+        }
+
+        let tls = MaybeTlsSettings::Tls(TlsSettings::from_options(&self.tls)?);
+        let endpoint = self.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        let uri = with_default_scheme(endpoint, true)?;
+        let client = proto::Client::new(HyperSvc::new(uri, new_client(&tls)?));
+
+        let service = BigqueryService {
+            client,
+            creds,
+            api_key: self.auth.api_key.clone(),
+            table: format!(
+                "projects/{}/datasets/{}/tables/{}",
+                self.project, self.dataset, self.table
+            ),
+            schema: Arc::clone(&schema),
+            committed: match self.write_mode {
+                WriteMode::Default => None,
+                WriteMode::Committed => Some(Arc::default()),
+            },
+        };
+
+        let healthcheck = healthcheck(service.clone()).boxed();
+
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+        let batch = BatchSettings::default()
+            .bytes(9_000_000)
+            .events(1000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+
+        let svc = ServiceBuilder::new()
+            .settings(request, BigqueryRetryLogic)
+            .service(service);
+
+        let buffer = VecBuffer::new(batch.size);
+        let sink = BatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .sink_map_err(|error| error!(message = "Fatal gcp_bigquery sink error.", %error))
+            .with_flat_map(move |event| stream::iter(Some(encode_event(&schema, event))).map(Ok));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        NAME
+    }
+}
+
+/// A column of the rows written to the table.
+#[derive(Debug)]
+struct Column {
+    name: String,
+    field: String,
+    column_type: ColumnType,
+    number: u32,
+}
+
+/// The mapping of events to rows, and the protobuf schema they're written
+/// with.
+#[derive(Debug)]
+struct RowSchema {
+    columns: Vec<Column>,
+    encoding: RowEncoding,
+    descriptor: DescriptorProto,
+}
+
+impl RowSchema {
+    fn new(columns: &[ColumnConfig], encoding: RowEncoding) -> Result<Self, ConfigError> {
+        if columns.is_empty() {
+            return Err(ConfigError::NoColumns);
+        }
+
+        let mut names = HashSet::new();
+        let mut fields = Vec::with_capacity(columns.len());
+        let mut descriptor_fields = Vec::with_capacity(columns.len());
+        for (index, column) in columns.iter().enumerate() {
+            if !is_valid_column_name(&column.name) {
+                return Err(ConfigError::InvalidColumnName {
+                    name: column.name.clone(),
+                });
+            }
+            // BigQuery column names are case insensitive.
+            if !names.insert(column.name.to_lowercase()) {
+                return Err(ConfigError::DuplicateColumn {
+                    name: column.name.clone(),
+                });
+            }
+
+            let column_type = match encoding {
+                RowEncoding::Proto => column.column_type,
+                RowEncoding::Json => ColumnType::Json,
+            };
+            let number = index as u32 + 1;
+            descriptor_fields.push(FieldDescriptorProto {
+                name: Some(column.name.clone()),
+                number: Some(number as i32),
+                label: Some(Label::Optional as i32),
+                r#type: Some(column_type.proto_type() as i32),
+                ..Default::default()
+            });
+            fields.push(Column {
+                name: column.name.clone(),
+                field: column.field.clone().unwrap_or_else(|| column.name.clone()),
+                column_type,
+                number,
+            });
+        }
+
+        Ok(Self {
+            columns: fields,
+            encoding,
+            descriptor: DescriptorProto {
+                name: Some(ROW_MESSAGE_NAME.to_owned()),
+                field: descriptor_fields,
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Serializes the row for `log`. Missing and null fields are left out,
+    /// which BigQuery stores as `NULL`.
+    fn encode(&self, log: &LogEvent) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for column in &self.columns {
+            let value = match log.get(&column.field) {
+                None | Some(Value::Null) => continue,
+                Some(value) => value,
+            };
+
+            if !encode_column(column, value, &mut buf) {
+                emit!(&GcpBigqueryColumnConversionFailed {
+                    column: &column.name,
+                    column_type: column.column_type.as_str(),
+                });
+            }
+        }
+        buf
+    }
+}
+
+fn is_valid_column_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+/// Writes the column value to `buf`, returns `false` if the value can't be
+/// converted to the column type.
+fn encode_column(column: &Column, value: &Value, buf: &mut Vec<u8>) -> bool {
+    let tag = column.number;
+    match column.column_type {
+        ColumnType::String => {
+            let value = match value {
+                Value::Map(_) | Value::Array(_) => serde_json::to_string(value).ok(),
+                value => Some(value.to_string_lossy()),
+            };
+            value.map(|value| encoding::string::encode(tag, &value, buf))
+        }
+        ColumnType::Json => serde_json::to_string(value)
+            .ok()
+            .map(|value| encoding::string::encode(tag, &value, buf)),
+        ColumnType::Int64 => {
+            let value = match value {
+                Value::Integer(value) => Some(*value),
+                Value::Float(value) if value.fract() == 0.0 => Some(*value as i64),
+                Value::Boolean(value) => Some(*value as i64),
+                Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|value| value.trim().parse().ok()),
+                _ => None,
+            };
+            value.map(|value| encoding::int64::encode(tag, &value, buf))
+        }
+        ColumnType::Float64 => {
+            let value = match value {
+                Value::Float(value) => Some(*value),
+                Value::Integer(value) => Some(*value as f64),
+                Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|value| value.trim().parse().ok()),
+                _ => None,
+            };
+            value.map(|value| encoding::double::encode(tag, &value, buf))
+        }
+        ColumnType::Bool => {
+            let value = match value {
+                Value::Boolean(value) => Some(*value),
+                Value::Bytes(bytes) => match bytes.as_ref() {
+                    b"true" => Some(true),
+                    b"false" => Some(false),
+                    _ => None,
+                },
+                _ => None,
+            };
+            value.map(|value| encoding::bool::encode(tag, &value, buf))
+        }
+        ColumnType::Bytes => {
+            let value = match value {
+                Value::Bytes(bytes) => bytes.to_vec(),
+                value => value.to_string_lossy().into_bytes(),
+            };
+            Some(encoding::bytes::encode(tag, &value, buf))
+        }
+        ColumnType::Timestamp => {
+            let value = match value {
+                Value::Timestamp(timestamp) => Some(timestamp_micros(timestamp)),
+                Value::Integer(micros) => Some(*micros),
+                Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|value| DateTime::parse_from_rfc3339(value.trim()).ok())
+                    .map(|timestamp| timestamp_micros(&timestamp.with_timezone(&Utc))),
+                _ => None,
+            };
+            value.map(|value| encoding::int64::encode(tag, &value, buf))
+        }
+    }
+    .is_some()
+}
+
+fn timestamp_micros(timestamp: &DateTime<Utc>) -> i64 {
+    timestamp.timestamp() * 1_000_000 + i64::from(timestamp.timestamp_subsec_micros())
+}
+
+/// A serialized row.
+#[derive(Debug, Clone)]
+struct EncodedRow(Vec<u8>);
+
+impl EncodedLength for EncodedRow {
+    fn encoded_length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+fn encode_event(schema: &RowSchema, mut event: Event) -> EncodedEvent<EncodedRow> {
+    let byte_size = event.size_of();
+    let finalizers = event.metadata_mut().take_finalizers();
+    let item = EncodedRow(schema.encode(event.as_log()));
+
+    EncodedEvent {
+        item,
+        finalizers,
+        byte_size,
+    }
+}
+
+/// The committed stream the sink appends to, created on the first append.
+#[derive(Debug)]
+struct CommittedStream {
+    name: String,
+    /// The offset of the next row appended to the stream.
+    offset: i64,
+}
+
+#[derive(Clone)]
+struct BigqueryService {
+    client: Client,
+    creds: Option<GcpCredentials>,
+    api_key: Option<String>,
+    /// The resource name of the table.
+    table: String,
+    schema: Arc<RowSchema>,
+    /// Only set in the `committed` write mode. Appends to a committed stream
+    /// are serialized so that offsets are assigned in order.
+    committed: Option<Arc<tokio::sync::Mutex<Option<CommittedStream>>>>,
+}
+
+impl BigqueryService {
+    fn default_stream(&self) -> String {
+        format!("{}/streams/_default", self.table)
+    }
+
+    /// Adds the authentication and the routing headers to the request. The
+    /// routing header lets the frontend route the request to the region
+    /// hosting the resource.
+    fn prepare<T>(
+        &self,
+        message: T,
+        routing_param: &str,
+        resource: &str,
+    ) -> Result<tonic::Request<T>, BigqueryError> {
+        let mut request = tonic::Request::new(message);
+        let metadata = request.metadata_mut();
+
+        let routing = format!(
+            "{}={}",
+            routing_param,
+            utf8_percent_encode(resource, NON_ALPHANUMERIC)
+        );
+        metadata.insert(
+            "x-goog-request-params",
+            routing
+                .parse()
+                .map_err(|_| BigqueryError::InvalidMetadata { value: routing })?,
+        );
+
+        if let Some(creds) = &self.creds {
+            let token = creds.make_token();
+            metadata.insert(
+                "authorization",
+                token.parse().map_err(|_| BigqueryError::InvalidMetadata {
+                    value: "authorization".to_owned(),
+                })?,
+            );
+        } else if let Some(api_key) = &self.api_key {
+            metadata.insert(
+                "x-goog-api-key",
+                api_key
+                    .parse()
+                    .map_err(|_| BigqueryError::InvalidMetadata {
+                        value: "api_key".to_owned(),
+                    })?,
+            );
+        }
+
+        Ok(request)
+    }
+
+    async fn append(mut self, rows: Vec<Vec<u8>>) -> Result<(), BigqueryError> {
+        let committed = match self.committed.clone() {
+            None => {
+                let stream = self.default_stream();
+                return self.append_rows(&stream, None, rows).await.map(drop);
+            }
+            Some(committed) => committed,
+        };
+
+        let mut committed = committed.lock().await;
+        if committed.is_none() {
+            *committed = Some(self.create_committed_stream().await?);
+        }
+        let stream = committed
+            .as_mut()
+            .expect("committed stream was just created");
+
+        match self
+            .append_rows(&stream.name, Some(stream.offset), rows)
+            .await
+        {
+            Ok(appended) => {
+                stream.offset += appended as i64;
+                Ok(())
+            }
+            Err(error) => {
+                if let BigqueryError::StreamInvalidated { .. } = error {
+                    // Start over with a new stream on the next attempt.
+                    *committed = None;
+                }
+                Err(error)
+            }
+        }
+    }
+
+    async fn create_committed_stream(&mut self) -> Result<CommittedStream, BigqueryError> {
+        let request = self.prepare(
+            proto::CreateWriteStreamRequest {
+                parent: self.table.clone(),
+                write_stream: Some(proto::WriteStream {
+                    r#type: write_stream::Type::Committed as i32,
+                    ..Default::default()
+                }),
+            },
+            "parent",
+            &self.table,
+        )?;
+        let stream = self
+            .client
+            .create_write_stream(request)
+            .await
+            .context(Request)?
+            .into_inner();
+        debug!(message = "Created committed write stream.", stream = %stream.name);
+
+        Ok(CommittedStream {
+            name: stream.name,
+            offset: 0,
+        })
+    }
+
+    /// Appends the rows to the stream, returning how many were appended. Rows
+    /// BigQuery rejects are dropped, and the remaining ones appended again.
+    async fn append_rows(
+        &mut self,
+        write_stream: &str,
+        offset: Option<i64>,
+        mut rows: Vec<Vec<u8>>,
+    ) -> Result<usize, BigqueryError> {
+        loop {
+            let count = rows.len();
+            let message = proto::AppendRowsRequest {
+                write_stream: write_stream.to_owned(),
+                offset,
+                rows: Some(append_rows_request::Rows::ProtoRows(
+                    append_rows_request::ProtoData {
+                        writer_schema: Some(proto::ProtoSchema {
+                            proto_descriptor: Some(self.schema.descriptor.clone()),
+                        }),
+                        rows: Some(proto::ProtoRows {
+                            serialized_rows: rows.clone(),
+                        }),
+                    },
+                )),
+                trace_id: String::new(),
+            };
+            let request =
+                self.prepare(stream::iter(vec![message]), "write_stream", write_stream)?;
+
+            let mut responses = self
+                .client
+                .append_rows(request)
+                .await
+                .context(Request)?
+                .into_inner();
+            let response = responses
+                .message()
+                .await
+                .context(Request)?
+                .ok_or(BigqueryError::NoResponse)?;
+
+            // When any row is invalid, none of them are appended.
+            if !response.row_errors.is_empty() {
+                let rejected = response
+                    .row_errors
+                    .iter()
+                    .map(|error| error.index as usize)
+                    .collect::<HashSet<_>>();
+                let remaining = rows
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| !rejected.contains(index))
+                    .map(|(_, row)| row)
+                    .collect::<Vec<_>>();
+                if remaining.len() == count {
+                    return Err(BigqueryError::Append {
+                        code: tonic::Code::InvalidArgument as i32,
+                        message: "row errors don't match any appended row".to_owned(),
+                    });
+                }
+
+                emit!(&GcpBigqueryRowsRejected {
+                    count: count - remaining.len(),
+                    reason: &response.row_errors[0].message,
+                });
+                if remaining.is_empty() {
+                    return Ok(0);
+                }
+                rows = remaining;
+                continue;
+            }
+
+            return match response.response {
+                Some(append_rows_response::Response::Error(status)) => {
+                    match tonic::Code::from_i32(status.code) {
+                        // The rows at this offset were already appended by a
+                        // previous attempt.
+                        tonic::Code::AlreadyExists if offset.is_some() => Ok(count),
+                        tonic::Code::NotFound | tonic::Code::FailedPrecondition
+                            if offset.is_some() =>
+                        {
+                            Err(BigqueryError::StreamInvalidated {
+                                message: status.message,
+                            })
+                        }
+                        _ => Err(BigqueryError::Append {
+                            code: status.code,
+                            message: status.message,
+                        }),
+                    }
+                }
+                Some(append_rows_response::Response::AppendResult(_)) | None => Ok(count),
+            };
+        }
+    }
+}
+
+impl tower::Service<Vec<EncodedRow>> for BigqueryService {
+    type Response = ();
+    type Error = BigqueryError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, rows: Vec<EncodedRow>) -> Self::Future {
+        let service = self.clone();
+        let rows = rows.into_iter().map(|row| row.0).collect();
+        Box::pin(service.append(rows))
+    }
+}
+
+async fn healthcheck(mut service: BigqueryService) -> crate::Result<()> {
+    let name = service.default_stream();
+    let request = service.prepare(
+        proto::GetWriteStreamRequest { name: name.clone() },
+        "name",
+        &name,
+    )?;
+
+    match service.client.get_write_stream(request).await {
+        Ok(_) => Ok(()),
+        Err(status) if status.code() == tonic::Code::NotFound => {
+            Err(BigqueryError::TableNotFound {
+                table: service.table,
+            }
+            .into())
+        }
+        Err(source) => Err(BigqueryError::Request { source }.into()),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BigqueryRetryLogic;
+
+/// Whether a request failing with `code` may succeed when retried.
+const fn is_retriable_code(code: tonic::Code) -> bool {
+    use tonic::Code::*;
+
+    matches!(
+        code,
+        Cancelled
+            | Unknown
+            | DeadlineExceeded
+            | ResourceExhausted
+            | Aborted
+            | Internal
+            | Unavailable
+    )
+}
+
+impl RetryLogic for BigqueryRetryLogic {
+    type Error = BigqueryError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            BigqueryError::Request { source } => is_retriable_code(source.code()),
+            BigqueryError::Append { code, .. } => is_retriable_code(tonic::Code::from_i32(*code)),
+            BigqueryError::NoResponse | BigqueryError::StreamInvalidated { .. } => true,
+            BigqueryError::TableNotFound { .. } | BigqueryError::InvalidMetadata { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::SinkContext,
+        sinks::util::test::build_test_server_generic,
+        test_util::{next_addr, random_lines_with_stream},
+    };
+    use bytes::{BufMut, Bytes};
+    use chrono::TimeZone;
+    use futures::channel::mpsc;
+    use prost::Message;
+    use vector_core::event::{BatchNotifier, BatchStatus};
+
+    #[derive(Clone, PartialEq, Message)]
+    struct TestRow {
+        #[prost(string, optional, tag = "1")]
+        message: Option<String>,
+        #[prost(int64, optional, tag = "2")]
+        count: Option<i64>,
+        #[prost(double, optional, tag = "3")]
+        ratio: Option<f64>,
+        #[prost(bool, optional, tag = "4")]
+        ok: Option<bool>,
+        #[prost(int64, optional, tag = "5")]
+        timestamp: Option<i64>,
+        #[prost(string, optional, tag = "6")]
+        attributes: Option<String>,
+    }
+
+    fn columns() -> Vec<ColumnConfig> {
+        toml::from_str::<BigqueryConfig>(
+            r#"
+            project = "project"
+            dataset = "dataset"
+            table = "table"
+
+            [[columns]]
+            name = "message"
+
+            [[columns]]
+            name = "count"
+            field = "stats.count"
+            type = "int64"
+
+            [[columns]]
+            name = "ratio"
+            type = "float64"
+
+            [[columns]]
+            name = "ok"
+            type = "bool"
+
+            [[columns]]
+            name = "timestamp"
+            type = "timestamp"
+
+            [[columns]]
+            name = "attributes"
+            type = "json"
+            "#,
+        )
+        .unwrap()
+        .columns
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<BigqueryConfig>();
+    }
+
+    #[test]
+    fn generates_row_descriptor() {
+        let schema = RowSchema::new(&columns(), RowEncoding::Proto).unwrap();
+        let fields = &schema.descriptor.field;
+
+        assert_eq!(schema.descriptor.name(), ROW_MESSAGE_NAME);
+        assert_eq!(fields.len(), 6);
+        assert_eq!(fields[1].name(), "count");
+        assert_eq!(fields[1].number(), 2);
+        assert_eq!(fields[1].r#type(), Type::Int64);
+        assert_eq!(fields[4].r#type(), Type::Int64);
+        assert_eq!(fields[5].r#type(), Type::String);
+
+        let schema = RowSchema::new(&columns(), RowEncoding::Json).unwrap();
+        assert!(schema
+            .descriptor
+            .field
+            .iter()
+            .all(|field| field.r#type() == Type::String));
+    }
+
+    #[test]
+    fn rejects_invalid_columns() {
+        let column = |name: &str| ColumnConfig {
+            name: name.to_owned(),
+            field: None,
+            column_type: ColumnType::String,
+        };
+
+        assert!(matches!(
+            RowSchema::new(&[], RowEncoding::Proto),
+            Err(ConfigError::NoColumns)
+        ));
+        assert!(matches!(
+            RowSchema::new(&[column("1st")], RowEncoding::Proto),
+            Err(ConfigError::InvalidColumnName { .. })
+        ));
+        assert!(matches!(
+            RowSchema::new(&[column("message"), column("Message")], RowEncoding::Proto),
+            Err(ConfigError::DuplicateColumn { .. })
+        ));
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let schema = RowSchema::new(&columns(), RowEncoding::Proto).unwrap();
+        let timestamp = Utc.ymd(2021, 10, 1).and_hms_micro(12, 0, 0, 42);
+
+        let mut log = LogEvent::from("hello");
+        log.insert("stats.count", "12");
+        log.insert("ratio", 2);
+        log.insert("ok", true);
+        log.insert("timestamp", timestamp);
+        log.insert("attributes.name", "vector");
+
+        let row = TestRow::decode(schema.encode(&log).as_slice()).unwrap();
+        assert_eq!(
+            row,
+            TestRow {
+                message: Some("hello".into()),
+                count: Some(12),
+                ratio: Some(2.0),
+                ok: Some(true),
+                timestamp: Some(1_633_089_600_000_042),
+                attributes: Some(r#"{"name":"vector"}"#.into()),
+            }
+        );
+    }
+
+    #[test]
+    fn leaves_missing_and_unconvertible_columns_empty() {
+        let schema = RowSchema::new(&columns(), RowEncoding::Proto).unwrap();
+
+        let mut log = LogEvent::from("hello");
+        log.insert("stats.count", "many");
+        log.insert("ok", Value::Null);
+
+        let row = TestRow::decode(schema.encode(&log).as_slice()).unwrap();
+        assert_eq!(
+            row,
+            TestRow {
+                message: Some("hello".into()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn encodes_json_rows() {
+        let schema = RowSchema::new(&columns(), RowEncoding::Json).unwrap();
+
+        let mut log = LogEvent::from("hello");
+        log.insert("ok", true);
+
+        #[derive(Clone, PartialEq, Message)]
+        struct JsonRow {
+            #[prost(string, optional, tag = "1")]
+            message: Option<String>,
+            #[prost(string, optional, tag = "4")]
+            ok: Option<String>,
+        }
+
+        let row = JsonRow::decode(schema.encode(&log).as_slice()).unwrap();
+        assert_eq!(row.message.as_deref(), Some(r#""hello""#));
+        assert_eq!(row.ok.as_deref(), Some("true"));
+    }
+
+    fn encode_body(message: impl Message) -> Bytes {
+        // A gRPC frame: the compression flag, the length, and the message.
+        let mut buf = Vec::new();
+        buf.put_u8(0);
+        buf.put_u32(message.encoded_len() as u32);
+        message.encode(&mut buf).unwrap();
+        buf.into()
+    }
+
+    async fn received_requests(
+        rx: mpsc::Receiver<(http::request::Parts, Bytes)>,
+    ) -> Vec<proto::AppendRowsRequest> {
+        rx.map(|(parts, body)| {
+            assert_eq!(
+                parts.uri.path(),
+                "/google.cloud.bigquery.storage.v1.BigQueryWrite/AppendRows"
+            );
+            assert_eq!(
+                parts.headers["x-goog-request-params"],
+                "write_stream=projects%2Fproject%2Fdatasets%2Fdataset%2Ftables%2Ftable%2Fstreams%2F_default"
+            );
+            proto::AppendRowsRequest::decode(body.slice(5..)).unwrap()
+        })
+        .collect()
+        .await
+    }
+
+    #[tokio::test]
+    async fn appends_rows_to_default_stream() {
+        let address = next_addr();
+        let config: BigqueryConfig = toml::from_str(&format!(
+            r#"
+            project = "project"
+            dataset = "dataset"
+            table = "table"
+            endpoint = "http://{}"
+            skip_authentication = true
+
+            [[columns]]
+            name = "message"
+            "#,
+            address
+        ))
+        .unwrap();
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (rx, trigger, server) = build_test_server_generic(address, || {
+            hyper::Response::builder()
+                .header("grpc-status", "0")
+                .header("content-type", "application/grpc")
+                .body(hyper::Body::from(encode_body(proto::AppendRowsResponse {
+                    response: Some(append_rows_response::Response::AppendResult(
+                        append_rows_response::AppendResult { offset: None },
+                    )),
+                    ..Default::default()
+                })))
+                .unwrap()
+        });
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (lines, events) = random_lines_with_stream(8, 10, Some(batch));
+        sink.run(events).await.unwrap();
+        drop(trigger);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let requests = received_requests(rx).await;
+        let rows = requests
+            .iter()
+            .flat_map(|request| match &request.rows {
+                Some(append_rows_request::Rows::ProtoRows(data)) => {
+                    let schema = data.writer_schema.as_ref().unwrap();
+                    assert_eq!(
+                        schema.proto_descriptor.as_ref().unwrap().name(),
+                        ROW_MESSAGE_NAME
+                    );
+                    data.rows.as_ref().unwrap().serialized_rows.clone()
+                }
+                None => panic!("request without rows"),
+            })
+            .map(|row| {
+                #[derive(Clone, PartialEq, Message)]
+                struct MessageRow {
+                    #[prost(string, optional, tag = "1")]
+                    message: Option<String>,
+                }
+                MessageRow::decode(row.as_slice()).unwrap().message.unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, lines);
+        assert!(requests.iter().all(|request| request.offset.is_none()));
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let logic = BigqueryRetryLogic;
+        assert!(logic.is_retriable_error(&BigqueryError::Append {
+            code: tonic::Code::Unavailable as i32,
+            message: String::new(),
+        }));
+        assert!(!logic.is_retriable_error(&BigqueryError::Append {
+            code: tonic::Code::InvalidArgument as i32,
+            message: String::new(),
+        }));
+        assert!(logic.is_retriable_error(&BigqueryError::StreamInvalidated {
+            message: String::new(),
+        }));
+    }
+}
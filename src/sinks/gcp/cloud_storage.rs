@@ -1,7 +1,7 @@
 use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{Event, LogEvent},
     http::{HttpClient, HttpClientFuture, HttpError},
     internal_events::TemplateRenderingFailed,
     serde::to_string,
@@ -9,6 +9,7 @@ use crate::{
         util::{
             batch::{BatchConfig, BatchSettings},
             encoding::{EncodingConfig, EncodingConfiguration},
+            parquet::{ParquetBuffer, ParquetConfig, PARQUET_CONTENT_TYPE},
             retries::{RetryAction, RetryLogic},
             Buffer, Compression, EncodedEvent, PartitionBatchSink, PartitionBuffer,
             PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
@@ -66,6 +67,9 @@ pub struct GcsSinkConfig {
     encoding: EncodingConfig<Encoding>,
     #[serde(default)]
     compression: Compression,
+    /// Writes the batches as Parquet files instead of encoding each event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parquet: Option<ParquetConfig>,
     #[serde(default)]
     batch: BatchConfig,
     #[serde(default)]
@@ -88,6 +92,7 @@ fn default_config(e: Encoding) -> GcsSinkConfig {
         filename_extension: Default::default(),
         encoding: e.into(),
         compression: Compression::gzip_default(),
+        parquet: Default::default(),
         batch: Default::default(),
         request: Default::default(),
         auth: Default::default(),
@@ -207,11 +212,6 @@ impl GcsSink {
         });
         let encoding = config.encoding.clone();
 
-        let batch = BatchSettings::default()
-            .bytes(10_000_000)
-            .timeout(300)
-            .parse_config(config.batch)?;
-
         let key_prefix = config.key_prefix.as_deref().unwrap_or("date=%F/");
         let key_prefix = Template::try_from(key_prefix).context(KeyPrefixTemplate)?;
 
@@ -222,15 +222,41 @@ impl GcsSink {
             .settings(request, GcsRetryLogic)
             .service(self);
 
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
-
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
-            .with_flat_map(move |event| {
-                stream::iter(encode_event(event, &key_prefix, &encoding)).map(Ok)
-            });
-
-        Ok(VectorSink::Sink(Box::new(sink)))
+        Ok(match &config.parquet {
+            Some(parquet) => {
+                let batch = BatchSettings::default()
+                    .bytes(10_000_000)
+                    .timeout(300)
+                    .parse_config(config.batch)?;
+                let buffer =
+                    PartitionBuffer::new(ParquetBuffer::new(batch.size, parquet.build_encoder()?));
+
+                let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                    .sink_map_err(
+                        |error| error!(message = "Fatal gcp_cloud_storage error.", %error),
+                    )
+                    .with_flat_map(move |event| {
+                        stream::iter(encode_parquet_event(event, &key_prefix, &encoding)).map(Ok)
+                    });
+                VectorSink::Sink(Box::new(sink))
+            }
+            None => {
+                let batch = BatchSettings::default()
+                    .bytes(10_000_000)
+                    .timeout(300)
+                    .parse_config(config.batch)?;
+                let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
+
+                let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                    .sink_map_err(
+                        |error| error!(message = "Fatal gcp_cloud_storage error.", %error),
+                    )
+                    .with_flat_map(move |event| {
+                        stream::iter(encode_event(event, &key_prefix, &encoding)).map(Ok)
+                    });
+                VectorSink::Sink(Box::new(sink))
+            }
+        })
     }
 
     async fn healthcheck(self) -> crate::Result<()> {
@@ -349,10 +375,16 @@ impl RequestSettings {
         let acl = config
             .acl
             .map(|acl| HeaderValue::from_str(&to_string(acl)).unwrap());
-        let content_type = HeaderValue::from_str(config.encoding.codec().content_type()).unwrap();
+        let content_type = match config.parquet {
+            Some(_) => PARQUET_CONTENT_TYPE,
+            None => config.encoding.codec().content_type(),
+        };
+        let content_type = HeaderValue::from_str(content_type).unwrap();
+        // Parquet files compress their columns themselves.
         let content_encoding = config
             .compression
             .content_encoding()
+            .filter(|_| config.parquet.is_none())
             .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
         let storage_class = config.storage_class.unwrap_or_default();
         let storage_class = HeaderValue::from_str(&to_string(storage_class)).unwrap();
@@ -369,7 +401,10 @@ impl RequestSettings {
         let extension = config
             .filename_extension
             .clone()
-            .unwrap_or_else(|| config.compression.extension().into());
+            .unwrap_or_else(|| match config.parquet {
+                Some(_) => "parquet".into(),
+                None => config.compression.extension().into(),
+            });
         let time_format = config
             .filename_time_format
             .clone()
@@ -396,13 +431,9 @@ fn make_header((name, value): (&String, &String)) -> crate::Result<(HeaderName,
     ))
 }
 
-fn encode_event(
-    mut event: Event,
-    key_prefix: &Template,
-    encoding: &EncodingConfig<Encoding>,
-) -> Option<EncodedEvent<PartitionInnerBuffer<Vec<u8>, Bytes>>> {
-    let key = key_prefix
-        .render_string(&event)
+fn render_key(event: &Event, key_prefix: &Template) -> Option<String> {
+    key_prefix
+        .render_string(event)
         .map_err(|error| {
             emit!(&TemplateRenderingFailed {
                 error,
@@ -410,7 +441,15 @@ fn encode_event(
                 drop_event: true,
             });
         })
-        .ok()?;
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<EncodedEvent<PartitionInnerBuffer<Vec<u8>, Bytes>>> {
+    let key = render_key(&event, key_prefix)?;
     let byte_size = event.size_of();
     encoding.apply_rules(&mut event);
     let log = event.into_log();
@@ -437,6 +476,21 @@ fn encode_event(
     ))
 }
 
+fn encode_parquet_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<EncodedEvent<PartitionInnerBuffer<LogEvent, Bytes>>> {
+    let key = render_key(&event, key_prefix)?;
+    let byte_size = event.size_of();
+    encoding.apply_rules(&mut event);
+
+    Some(EncodedEvent::new(
+        PartitionInnerBuffer::new(event.into_log(), key.into()),
+        byte_size,
+    ))
+}
+
 #[derive(Clone)]
 struct GcsRetryLogic;
 
@@ -567,4 +621,22 @@ mod tests {
         );
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
+
+    #[test]
+    fn gcs_build_parquet_request() {
+        let settings = RequestSettings::new(&GcsSinkConfig {
+            key_prefix: Some("key/".into()),
+            filename_time_format: Some("date".into()),
+            filename_append_uuid: Some(false),
+            parquet: Some(toml::from_str(r#"schema.message = "string""#).unwrap()),
+            ..default_config(Encoding::Ndjson)
+        })
+        .unwrap();
+        assert_eq!(settings.content_type, PARQUET_CONTENT_TYPE);
+        assert_eq!(settings.content_encoding, None);
+
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
+        let req = RequestWrapper::new(buf, settings);
+        assert_eq!(req.key, "key/date.parquet".to_string());
+    }
 }
@@ -18,6 +18,7 @@ use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio_stream::wrappers::IntervalStream;
 
+pub mod bigquery;
 pub mod cloud_storage;
 pub mod pubsub;
 pub mod stackdriver_logs;
@@ -123,11 +124,15 @@ impl GcpCredentials {
     }
 
     pub fn apply<T>(&self, request: &mut http::Request<T>) {
-        let token = self.token.read().unwrap();
-        let value = format!("{} {}", token.token_type(), token.access_token());
         request
             .headers_mut()
-            .insert(AUTHORIZATION, value.parse().unwrap());
+            .insert(AUTHORIZATION, self.make_token().parse().unwrap());
+    }
+
+    /// The value of the `Authorization` header for the current token.
+    pub fn make_token(&self) -> String {
+        let token = self.token.read().unwrap();
+        format!("{} {}", token.token_type(), token.access_token())
     }
 
     async fn regenerate_token(&self) -> crate::Result<()> {
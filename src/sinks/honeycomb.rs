@@ -1,28 +1,47 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::{Event, Value},
+    event::{Event, LogEvent, Value},
     http::HttpClient,
+    internal_events::TemplateRenderingFailed,
     sinks::util::{
-        http::{BatchedHttpSink, HttpSink},
-        BatchConfig, BatchSettings, BoxedRawValue, JsonArrayBuffer, TowerRequestConfig,
+        http::{HttpSink, PartitionHttpSink},
+        BatchConfig, BatchSettings, BoxedRawValue, JsonArrayBuffer, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, UriSerde,
     },
+    template::Template,
 };
+use chrono::{DateTime, Utc};
 use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 lazy_static::lazy_static! {
-    static ref HOST: Uri = Uri::from_static("https://api.honeycomb.io/1/batch");
+    static ref HOST: Uri = Uri::from_static("https://api.honeycomb.io");
 }
 
+/// Encodes datasets into the path, keeping unreserved characters such as the
+/// underscores of `__all__`, the dataset of environment wide markers.
+const DATASET_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HoneycombConfig {
     api_key: String,
 
-    // TODO: we probably want to make this a template
-    // but this limits us in how we can do our healthcheck.
-    dataset: String,
+    endpoint: Option<UriSerde>,
+
+    dataset: Template,
+
+    #[serde(default)]
+    api: HoneycombApi,
+
+    #[serde(default = "default_sample_rate_key")]
+    sample_rate_key: String,
 
     #[serde(default)]
     batch: BatchConfig,
@@ -31,6 +50,22 @@ pub struct HoneycombConfig {
     request: TowerRequestConfig,
 }
 
+/// The Honeycomb API events are sent to.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum HoneycombApi {
+    /// Events are sent in batches to the dataset.
+    #[derivative(Default)]
+    Events,
+    /// Each event is sent as a marker annotating the dataset, such as a deploy.
+    Markers,
+}
+
+fn default_sample_rate_key() -> String {
+    "sample_rate".to_owned()
+}
+
 inventory::submit! {
     SinkDescription::new::<HoneycombConfig>("honeycomb")
 }
@@ -53,16 +88,24 @@ impl SinkConfig for HoneycombConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let batch = match self.api {
+            HoneycombApi::Events => self.batch,
+            // The markers API takes a single marker per request.
+            HoneycombApi::Markers => BatchConfig {
+                max_events: Some(1),
+                ..self.batch
+            },
+        };
         let batch_settings = BatchSettings::default()
             .bytes(100_000)
             .timeout(1)
-            .parse_config(self.batch)?;
+            .parse_config(batch)?;
 
         let client = HttpClient::new(None, cx.proxy())?;
 
-        let sink = BatchedHttpSink::new(
+        let sink = PartitionHttpSink::new(
             self.clone(),
-            JsonArrayBuffer::new(batch_settings.size),
+            PartitionBuffer::new(JsonArrayBuffer::new(batch_settings.size)),
             request_settings,
             batch_settings.timeout,
             client.clone(),
@@ -86,10 +129,22 @@ impl SinkConfig for HoneycombConfig {
 
 #[async_trait::async_trait]
 impl HttpSink for HoneycombConfig {
-    type Input = serde_json::Value;
-    type Output = Vec<BoxedRawValue>;
+    type Input = PartitionInnerBuffer<serde_json::Value, String>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, String>;
 
     fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let dataset = self
+            .dataset
+            .render_string(&event)
+            .map_err(|error| {
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some("dataset"),
+                    drop_event: true,
+                });
+            })
+            .ok()?;
+
         let mut log = event.into_log();
 
         let timestamp = if let Some(Value::Timestamp(ts)) = log.remove(log_schema().timestamp_key())
@@ -99,45 +154,127 @@ impl HttpSink for HoneycombConfig {
             chrono::Utc::now()
         };
 
-        let data = json!({
-            "timestamp": timestamp.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
-            "data": log.all_fields(),
-        });
+        let data = match self.api {
+            HoneycombApi::Events => {
+                // Events already sampled upstream keep their weight in Honeycomb.
+                let sample_rate = log.get(&self.sample_rate_key).and_then(sample_rate);
+                if sample_rate.is_some() {
+                    log.remove(&self.sample_rate_key);
+                }
+
+                let mut data = json!({
+                    "timestamp": timestamp.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+                    "data": log.all_fields(),
+                });
+                if let Some(sample_rate) = sample_rate {
+                    data["samplerate"] = json!(sample_rate);
+                }
+                data
+            }
+            HoneycombApi::Markers => encode_marker(log, timestamp),
+        };
 
-        Some(data)
+        Some(PartitionInnerBuffer::new(data, dataset))
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let uri = self.build_uri();
-        let request = Request::post(uri).header("X-Honeycomb-Team", self.api_key.clone());
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (events, dataset) = output.into_parts();
 
-        let buf = serde_json::to_vec(&events).unwrap();
+        let (uri, body) = match self.api {
+            HoneycombApi::Events => (
+                self.build_uri("batch", &dataset),
+                serde_json::to_vec(&events)?,
+            ),
+            HoneycombApi::Markers => {
+                let marker = events.first().ok_or("Batch of markers is empty.")?;
+                (
+                    self.build_uri("markers", &dataset),
+                    serde_json::to_vec(marker)?,
+                )
+            }
+        };
 
-        request.body(buf).map_err(Into::into)
+        Request::post(uri)
+            .header("X-Honeycomb-Team", self.api_key.clone())
+            .header("Content-Type", "application/json")
+            .body(body)
+            .map_err(Into::into)
     }
 }
 
 impl HoneycombConfig {
-    fn build_uri(&self) -> Uri {
-        let uri = format!("{}/{}", HOST.clone(), self.dataset);
+    fn host(&self) -> Uri {
+        self.endpoint
+            .clone()
+            .map(|endpoint| endpoint.uri)
+            .unwrap_or_else(|| HOST.clone())
+    }
+
+    fn build_uri(&self, api: &str, dataset: &str) -> Uri {
+        let uri = format!(
+            "{}/1/{}/{}",
+            self.host().to_string().trim_end_matches('/'),
+            api,
+            utf8_percent_encode(dataset, DATASET_ENCODE_SET)
+        );
 
         uri.parse::<http::Uri>()
             .expect("This should be a valid uri")
     }
 }
 
+/// The sample rate of an event, as a positive integer or a string of one.
+fn sample_rate(value: &Value) -> Option<u64> {
+    match value {
+        Value::Integer(rate) if *rate > 0 => Some(*rate as u64),
+        Value::Bytes(rate) => std::str::from_utf8(rate)
+            .ok()?
+            .parse()
+            .ok()
+            .filter(|rate| *rate > 0),
+        _ => None,
+    }
+}
+
+/// A marker from the `message`, `type`, `url` and `end_time` fields of an
+/// event, starting at its timestamp.
+fn encode_marker(mut log: LogEvent, start_time: DateTime<Utc>) -> serde_json::Value {
+    let mut marker = serde_json::Map::new();
+    marker.insert("start_time".to_owned(), json!(start_time.timestamp()));
+
+    if let Some(message) = log.remove(log_schema().message_key()) {
+        marker.insert("message".to_owned(), json!(message.to_string_lossy()));
+    }
+    for field in &["type", "url"] {
+        if let Some(value) = log.remove(field) {
+            marker.insert((*field).to_owned(), json!(value.to_string_lossy()));
+        }
+    }
+    match log.remove("end_time") {
+        Some(Value::Timestamp(end_time)) => {
+            marker.insert("end_time".to_owned(), json!(end_time.timestamp()));
+        }
+        Some(Value::Integer(end_time)) => {
+            marker.insert("end_time".to_owned(), json!(end_time));
+        }
+        _ => {}
+    }
+
+    marker.into()
+}
+
 async fn healthcheck(config: HoneycombConfig, client: HttpClient) -> crate::Result<()> {
-    let req = config
-        .build_request(Vec::new())
-        .await?
-        .map(hyper::Body::from);
+    let uri = format!("{}/1/auth", config.host().to_string().trim_end_matches('/'));
+    let req = Request::get(uri)
+        .header("X-Honeycomb-Team", config.api_key.clone())
+        .body(hyper::Body::empty())?;
 
     let res = client.send(req).await?;
 
     let status = res.status();
     let body = hyper::body::to_bytes(res.into_body()).await?;
 
-    if status == StatusCode::BAD_REQUEST {
+    if status.is_success() {
         Ok(())
     } else if status == StatusCode::UNAUTHORIZED {
         let json: serde_json::Value = serde_json::from_slice(&body[..])?;
@@ -163,10 +300,130 @@ async fn healthcheck(config: HoneycombConfig, client: HttpClient) -> crate::Resu
         .into())
     }
 }
+
 #[cfg(test)]
 mod test {
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::next_addr,
+    };
+    use chrono::TimeZone;
+    use futures::{stream, StreamExt};
+
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<super::HoneycombConfig>();
     }
+
+    #[test]
+    fn passes_sample_rate_through() {
+        let (config, _cx) = load_sink::<HoneycombConfig>(
+            r#"
+            api_key = "my-api-key"
+            dataset = "{{ service }}"
+        "#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("service", "api");
+        event.as_mut_log().insert("sample_rate", "10");
+
+        let (data, dataset) = config.encode_event(event).unwrap().into_parts();
+        assert_eq!(dataset, "api");
+        assert_eq!(data["samplerate"], json!(10));
+        assert_eq!(data["data"]["message"], json!("hello world"));
+        assert!(data["data"].get("sample_rate").is_none());
+
+        // Sample rates which aren't positive integers are left in the data.
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("service", "api");
+        event.as_mut_log().insert("sample_rate", "often");
+
+        let (data, _) = config.encode_event(event).unwrap().into_parts();
+        assert!(data.get("samplerate").is_none());
+        assert_eq!(data["data"]["sample_rate"], json!("often"));
+    }
+
+    #[test]
+    fn encodes_markers() {
+        let (config, _cx) = load_sink::<HoneycombConfig>(
+            r#"
+            api_key = "my-api-key"
+            dataset = "__all__"
+            api = "markers"
+        "#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("deploy #42");
+        let log = event.as_mut_log();
+        log.insert(
+            log_schema().timestamp_key(),
+            chrono::Utc.timestamp(1_600_000_000, 0),
+        );
+        log.insert("type", "deploy");
+        log.insert("url", "https://example.com/deploys/42");
+        log.insert("end_time", 1_600_000_060);
+
+        let (marker, dataset) = config.encode_event(event).unwrap().into_parts();
+        assert_eq!(dataset, "__all__");
+        assert_eq!(
+            marker,
+            json!({
+                "message": "deploy #42",
+                "type": "deploy",
+                "url": "https://example.com/deploys/42",
+                "start_time": 1_600_000_000,
+                "end_time": 1_600_000_060,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn batches_per_dataset() {
+        let addr = next_addr();
+        let (config, cx) = load_sink::<HoneycombConfig>(&format!(
+            r#"
+            api_key = "my-api-key"
+            endpoint = "http://{}"
+            dataset = "{{{{ service }}}}"
+        "#,
+            addr
+        ))
+        .unwrap();
+
+        let (rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let events = (0..10).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            let service = if i % 2 == 0 { "api" } else { "web app" };
+            event.as_mut_log().insert("service", service);
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut requests = rx
+            .take(2)
+            .map(|(parts, body)| {
+                let events: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+                assert_eq!(parts.headers["X-Honeycomb-Team"], "my-api-key");
+                (parts.uri.path().to_owned(), events.len())
+            })
+            .collect::<Vec<_>>()
+            .await;
+        requests.sort();
+
+        assert_eq!(
+            requests,
+            vec![
+                ("/1/batch/api".to_owned(), 5),
+                ("/1/batch/web%20app".to_owned(), 5),
+            ]
+        );
+    }
 }
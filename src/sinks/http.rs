@@ -2,15 +2,18 @@ use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
     http::{Auth, HttpClient, MaybeAuth},
-    internal_events::{HttpEventEncoded, HttpEventMissingMessage},
+    internal_events::{
+        HttpEventEncoded, HttpEventInvalidUri, HttpEventMissingMessage, TemplateRenderingFailed,
+    },
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink, RequestConfig},
-        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig, UriSerde,
+        http::{HttpSink, PartitionHttpSink, RequestConfig},
+        BatchConfig, BatchSettings, Buffer, Compression, Compressor, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, UriSerde,
     },
+    template::Template,
     tls::{TlsOptions, TlsSettings},
 };
-use flate2::write::GzEncoder;
 use futures::{future, FutureExt, SinkExt};
 use http::{
     header::{self, HeaderName, HeaderValue},
@@ -18,9 +21,9 @@ use http::{
 };
 use hyper::Body;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use snafu::{ResultExt, Snafu};
-use std::io::Write;
+use std::{convert::TryFrom, fmt, io::Write};
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -39,7 +42,7 @@ enum BuildError {
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct HttpSinkConfig {
-    pub uri: UriSerde,
+    pub uri: HttpSinkUri,
     pub method: Option<HttpMethod>,
     pub auth: Option<Auth>,
     // Deprecated, moved to request.
@@ -69,6 +72,101 @@ fn default_config(e: Encoding) -> HttpSinkConfig {
     }
 }
 
+/// The URI of the requests, which is rendered for each event if it contains
+/// `{{ field }}` placeholders. Events are then batched by their rendered URI.
+#[derive(Clone, Debug)]
+pub enum HttpSinkUri {
+    Static(UriSerde),
+    Template(Template),
+}
+
+impl HttpSinkUri {
+    fn render(&self, event: &Event) -> Option<Uri> {
+        match self {
+            Self::Static(uri) => Some(uri.uri.clone()),
+            Self::Template(template) => {
+                let uri = template
+                    .render_string(event)
+                    .map_err(|error| {
+                        emit!(&TemplateRenderingFailed {
+                            error,
+                            field: Some("uri"),
+                            drop_event: true,
+                        })
+                    })
+                    .ok()?;
+                // Credentials can't be part of templated URIs, they are set
+                // with `auth` instead.
+                match uri.parse::<Uri>() {
+                    Ok(parsed)
+                        if parsed
+                            .authority()
+                            .map_or(true, |a| !a.as_str().contains('@')) =>
+                    {
+                        Some(UriSerde::from(parsed).with_default_parts().uri)
+                    }
+                    Ok(_) => {
+                        emit!(&HttpEventInvalidUri {
+                            uri: &uri,
+                            error: "credentials must be set with the `auth` option",
+                        });
+                        None
+                    }
+                    Err(error) => {
+                        emit!(&HttpEventInvalidUri {
+                            uri: &uri,
+                            error: &error.to_string(),
+                        });
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for HttpSinkUri {
+    fn default() -> Self {
+        Self::Static(UriSerde::default())
+    }
+}
+
+impl From<Uri> for HttpSinkUri {
+    fn from(uri: Uri) -> Self {
+        Self::Static(uri.into())
+    }
+}
+
+impl fmt::Display for HttpSinkUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static(uri) => uri.fmt(f),
+            Self::Template(template) => f.write_str(template.get_ref()),
+        }
+    }
+}
+
+impl Serialize for HttpSinkUri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpSinkUri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uri = String::deserialize(deserializer)?;
+        if uri.contains("{{") {
+            Template::try_from(uri)
+                .map(Self::Template)
+                .map_err(de::Error::custom)
+        } else {
+            uri.parse::<UriSerde>()
+                .map(Self::Static)
+                .map_err(de::Error::custom)
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
 #[serde(rename_all = "snake_case")]
 #[derivative(Default)]
@@ -84,6 +182,21 @@ pub enum HttpMethod {
     Patch,
 }
 
+impl From<&HttpMethod> for Method {
+    fn from(method: &HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => Self::GET,
+            HttpMethod::Head => Self::HEAD,
+            HttpMethod::Post => Self::POST,
+            HttpMethod::Put => Self::PUT,
+            HttpMethod::Delete => Self::DELETE,
+            HttpMethod::Options => Self::OPTIONS,
+            HttpMethod::Trace => Self::TRACE,
+            HttpMethod::Patch => Self::PATCH,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum Encoding {
@@ -129,10 +242,13 @@ impl SinkConfig for HttpSinkConfig {
             None => future::ok(()).boxed(),
         };
 
-        let mut config = HttpSinkConfig {
-            auth: self.auth.choose_one(&self.uri.auth)?,
-            uri: self.uri.with_default_parts(),
-            ..self.clone()
+        let mut config = match &self.uri {
+            HttpSinkUri::Static(uri) => HttpSinkConfig {
+                auth: self.auth.choose_one(&uri.auth)?,
+                uri: HttpSinkUri::Static(uri.with_default_parts()),
+                ..self.clone()
+            },
+            HttpSinkUri::Template(_) => self.clone(),
         };
 
         config.request.add_old_option(config.headers.take());
@@ -146,9 +262,9 @@ impl SinkConfig for HttpSinkConfig {
             .request
             .tower
             .unwrap_with(&TowerRequestConfig::default());
-        let sink = BatchedHttpSink::new(
+        let sink = PartitionHttpSink::new(
             config,
-            Buffer::new(batch.size, Compression::None),
+            PartitionBuffer::new(Buffer::new(batch.size, Compression::None)),
             request,
             batch.timeout,
             client,
@@ -172,10 +288,14 @@ impl SinkConfig for HttpSinkConfig {
 
 #[async_trait::async_trait]
 impl HttpSink for HttpSinkConfig {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
+    type Input = PartitionInnerBuffer<Vec<u8>, Uri>;
+    type Output = PartitionInnerBuffer<Vec<u8>, Uri>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        // The URI is rendered before the encoding rules so that the fields it
+        // is rendered from can be left out of the body.
+        let uri = self.uri.render(&event)?;
+
         self.encoding.apply_rules(&mut event);
         let event = event.into_log();
 
@@ -212,22 +332,12 @@ impl HttpSink for HttpSinkConfig {
             byte_size: body.len(),
         });
 
-        Some(body)
+        Some(PartitionInnerBuffer::new(body, uri))
     }
 
-    async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let method = match &self.method.clone().unwrap_or(HttpMethod::Post) {
-            HttpMethod::Get => Method::GET,
-            HttpMethod::Head => Method::HEAD,
-            HttpMethod::Post => Method::POST,
-            HttpMethod::Put => Method::PUT,
-            HttpMethod::Delete => Method::DELETE,
-            HttpMethod::Options => Method::OPTIONS,
-            HttpMethod::Trace => Method::TRACE,
-            HttpMethod::Patch => Method::PATCH,
-        };
-        let uri: Uri = self.uri.uri.clone();
-
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let (mut body, uri) = output.into_parts();
+        let method = Method::from(self.method.as_ref().unwrap_or(&HttpMethod::Post));
         let ct = match self.encoding.codec() {
             Encoding::Text => "text/plain",
             Encoding::Ndjson => "application/x-ndjson",
@@ -244,15 +354,12 @@ impl HttpSink for HttpSinkConfig {
             .uri(uri)
             .header("Content-Type", ct);
 
-        match self.compression {
-            Compression::Gzip(level) => {
-                builder = builder.header("Content-Encoding", "gzip");
+        if let Some(content_encoding) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", content_encoding);
 
-                let mut w = GzEncoder::new(Vec::new(), level);
-                w.write_all(&body).expect("Writing to Vec can't fail");
-                body = w.finish().expect("Writing to Vec can't fail");
-            }
-            Compression::None => {}
+            let mut w = Compressor::from(self.compression);
+            w.write_all(&body).expect("Writing to Vec can't fail");
+            body = w.into_inner();
         }
 
         for (header, value) in self.request.headers.iter() {
@@ -337,7 +444,7 @@ mod tests {
 
         let mut config = default_config(Encoding::Text);
         config.encoding = encoding;
-        let bytes = config.encode_event(event).unwrap();
+        let (bytes, _) = config.encode_event(event).unwrap().into_parts();
 
         assert_eq!(bytes, Vec::from("hello world\n"));
     }
@@ -349,7 +456,7 @@ mod tests {
 
         let mut config = default_config(Encoding::Json);
         config.encoding = encoding;
-        let bytes = config.encode_event(event).unwrap();
+        let (bytes, _) = config.encode_event(event).unwrap().into_parts();
 
         #[derive(Deserialize, Debug)]
         #[serde(deny_unknown_fields)]
@@ -363,6 +470,35 @@ mod tests {
         assert_eq!(output.message, "hello world".to_string());
     }
 
+    #[test]
+    fn http_encode_event_templated_uri() {
+        let mut config = default_config(Encoding::Ndjson);
+        config.uri = toml::from_str::<HttpSinkConfig>(
+            r#"
+            uri = "https://{{ tenant }}.example.com/logs/{{ index }}/_bulk"
+            encoding = "ndjson"
+            "#,
+        )
+        .unwrap()
+        .uri;
+        assert!(matches!(config.uri, HttpSinkUri::Template(_)));
+
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("tenant", "acme");
+        event.as_mut_log().insert("index", "app");
+        let (_, uri) = config.encode_event(event).unwrap().into_parts();
+        assert_eq!(uri, "https://acme.example.com/logs/app/_bulk");
+
+        // Events missing the fields of the template are dropped.
+        assert!(config.encode_event(Event::from("hello world")).is_none());
+
+        // So are events rendering an invalid URI.
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("tenant", "ac me");
+        event.as_mut_log().insert("index", "app");
+        assert!(config.encode_event(event).is_none());
+    }
+
     #[test]
     fn http_validates_normal_headers() {
         let config = r#"
@@ -484,6 +620,48 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn http_partitions_by_templated_uri() {
+        let in_addr = next_addr();
+        let config = format!(
+            r#"
+                uri = "http://{addr}/{{{{ tenant }}}}/frames"
+                encoding = "ndjson"
+            "#,
+            addr = in_addr,
+        );
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        let events = (0..10).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            let tenant = if i % 2 == 0 { "even" } else { "odd" };
+            event.as_mut_log().insert("tenant", tenant);
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let mut received = rx
+            .map(|(parts, body)| {
+                let lines = BufReader::new(body.reader()).lines().count();
+                (parts.uri.path().to_owned(), lines)
+            })
+            .collect::<Vec<_>>()
+            .await;
+        received.sort();
+        assert_eq!(
+            received,
+            vec![
+                ("/even/frames".to_owned(), 5),
+                ("/odd/frames".to_owned(), 5)
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn retries_on_no_connection() {
         let num_lines = 10;
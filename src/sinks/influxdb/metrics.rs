@@ -15,7 +15,7 @@ use crate::{
             encode_namespace,
             http::{HttpBatchService, HttpRetryLogic},
             sink,
-            statistic::{validate_quantiles, DistributionStatistic},
+            statistic::{validate_quantile_error, validate_quantiles, DistributionStatistic},
             BatchConfig, BatchSettings, EncodedEvent, TowerRequestConfig,
         },
         Healthcheck, VectorSink,
@@ -58,6 +58,8 @@ pub struct InfluxDbConfig {
     pub tls: Option<TlsOptions>,
     #[serde(default = "default_summary_quantiles")]
     pub quantiles: Vec<f64>,
+    #[serde(default)]
+    pub quantile_error: f64,
 }
 
 pub fn default_summary_quantiles() -> Vec<f64> {
@@ -89,6 +91,7 @@ impl SinkConfig for InfluxDbConfig {
             client.clone(),
         )?;
         validate_quantiles(&self.quantiles)?;
+        validate_quantile_error(self.quantile_error)?;
         let sink = InfluxDbSvc::new(self.clone(), cx, client)?;
         Ok((sink, healthcheck))
     }
@@ -130,6 +133,7 @@ impl InfluxDbSvc {
 
         let http_service = HttpBatchService::new(client, create_build_request(uri, token));
 
+        let quantile_error = config.quantile_error;
         let influxdb_http_service = InfluxDbSvc {
             config,
             protocol_version,
@@ -141,7 +145,7 @@ impl InfluxDbSvc {
             .batch_sink(
                 HttpRetryLogic,
                 influxdb_http_service,
-                MetricsBuffer::new(batch.size),
+                MetricsBuffer::new(batch.size).with_quantile_error(quantile_error),
                 batch.timeout,
                 cx.acker(),
                 sink::StdServiceLogic::default(),
@@ -888,6 +892,7 @@ mod integration_tests {
             request: Default::default(),
             tls,
             quantiles: default_summary_quantiles(),
+            quantile_error: 0.0,
             tags: None,
             default_namespace: None,
         };
@@ -976,6 +981,7 @@ mod integration_tests {
                 token: TOKEN.to_string(),
             }),
             quantiles: default_summary_quantiles(),
+            quantile_error: 0.0,
             batch: Default::default(),
             request: Default::default(),
             tags: None,
@@ -1,5 +1,6 @@
 use crate::config::{DataType, GenerateConfig, SinkConfig, SinkContext};
 use crate::kafka::{KafkaAuthConfig, KafkaCompression};
+use crate::schema_registry::SchemaRegistryEncodingConfig;
 use crate::serde::to_string;
 use crate::sinks::kafka::sink::{healthcheck, KafkaSink};
 use crate::sinks::util::encoding::{EncodingConfig, StandardEncodings};
@@ -32,6 +33,8 @@ pub(crate) struct KafkaSinkConfig {
     #[serde(default)]
     pub librdkafka_options: HashMap<String, String>,
     pub headers_field: Option<String>,
+    /// Encode events with a schema from the registry instead of `encoding.codec`.
+    pub schema_registry: Option<SchemaRegistryEncodingConfig>,
 }
 
 const fn default_socket_timeout_ms() -> u64 {
@@ -149,6 +152,7 @@ impl GenerateConfig for KafkaSinkConfig {
             message_timeout_ms: default_message_timeout_ms(),
             librdkafka_options: Default::default(),
             headers_field: None,
+            schema_registry: None,
         })
         .unwrap()
     }
@@ -158,7 +162,11 @@ impl GenerateConfig for KafkaSinkConfig {
 #[typetag::serde(name = "kafka")]
 impl SinkConfig for KafkaSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let sink = KafkaSink::new(self.clone(), cx.acker())?;
+        let schema = match &self.schema_registry {
+            Some(registry) => Some(registry.resolve(cx.proxy()).await?),
+            None => None,
+        };
+        let sink = KafkaSink::new(self.clone(), cx.acker(), schema)?;
         let hc = healthcheck(self.clone()).boxed();
         Ok((VectorSink::Stream(Box::new(sink)), hc))
     }
@@ -1,10 +1,14 @@
 use crate::event::{Event, Finalizable, Value};
-use crate::internal_events::KafkaHeaderExtractionFailed;
+use crate::internal_events::{KafkaHeaderExtractionFailed, SchemaRegistryEncodeFailed};
+use crate::schema_registry::{Schema, SchemaRegistryError};
 use crate::sinks::kafka::service::{KafkaRequest, KafkaRequestMetadata};
-use crate::sinks::util::encoding::{Encoder, EncodingConfig, StandardEncodings};
+use crate::sinks::util::encoding::{
+    Encoder, EncodingConfig, EncodingConfiguration, StandardEncodings,
+};
 use crate::template::Template;
 use bytes::Bytes;
 use rdkafka::message::OwnedHeaders;
+use std::sync::Arc;
 use vector_core::config::LogSchema;
 
 pub struct KafkaRequestBuilder {
@@ -12,6 +16,7 @@ pub struct KafkaRequestBuilder {
     pub headers_field: Option<String>,
     pub topic_template: Template,
     pub encoder: EncodingConfig<StandardEncodings>,
+    pub schema: Option<Arc<Schema>>,
     pub log_schema: &'static LogSchema,
 }
 
@@ -25,11 +30,28 @@ impl KafkaRequestBuilder {
             headers: get_headers(&event, &self.headers_field),
             topic,
         };
-        let mut body = vec![];
-        self.encoder.encode_input(event, &mut body).ok()?;
+        let body = match &self.schema {
+            Some(schema) => self.encode_with_schema(schema, event)?,
+            None => {
+                let mut body = vec![];
+                self.encoder.encode_input(event, &mut body).ok()?;
+                body
+            }
+        };
 
         Some(KafkaRequest { body, metadata })
     }
+
+    fn encode_with_schema(&self, schema: &Schema, mut event: Event) -> Option<Vec<u8>> {
+        self.encoder.apply_rules(&mut event);
+        let encoded = match event {
+            Event::Log(log) => schema.encode(&log),
+            Event::Metric(_) => Err(SchemaRegistryError::UnsupportedEvent),
+        };
+        encoded
+            .map_err(|error| emit!(&SchemaRegistryEncodeFailed { error: &error }))
+            .ok()
+    }
 }
 
 fn get_key(event: &Event, key_field: &Option<String>) -> Option<Bytes> {
@@ -86,9 +108,10 @@ fn get_headers(event: &Event, headers_field: &Option<String>) -> Option<OwnedHea
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema_registry::SchemaType;
     use bytes::Bytes;
     use rdkafka::message::Headers;
-    use std::collections::BTreeMap;
+    use std::{collections::BTreeMap, convert::TryFrom};
 
     #[test]
     fn kafka_get_headers() {
@@ -106,4 +129,21 @@ mod tests {
         assert_eq!(headers.get(1).unwrap().0, "b-key");
         assert_eq!(headers.get(1).unwrap().1, "b-value".as_bytes());
     }
+
+    #[test]
+    fn kafka_encode_with_schema() {
+        let request_builder = KafkaRequestBuilder {
+            key_field: None,
+            headers_field: None,
+            topic_template: Template::try_from("logs").unwrap(),
+            encoder: StandardEncodings::Text.into(),
+            schema: Some(Arc::new(Schema::new(5, SchemaType::Json, "{}").unwrap())),
+            log_schema: vector_core::config::log_schema(),
+        };
+
+        let request = request_builder.build_request(Event::from("hello")).unwrap();
+        assert_eq!(&request.body[..5], &[0, 0, 0, 0, 5]);
+        let record: serde_json::Value = serde_json::from_slice(&request.body[5..]).unwrap();
+        assert_eq!(record["message"], "hello");
+    }
 }
@@ -1,13 +1,15 @@
 use crate::buffers::Ackable;
 use crate::event::{EventFinalizers, EventStatus, Finalizable};
+use crate::internal_events::{KafkaDeliveryFailed, KafkaDeliveryReported};
 use crate::kafka::KafkaStatisticsContext;
 use bytes::Bytes;
 use futures::future::BoxFuture;
 use rdkafka::error::KafkaError;
-use rdkafka::message::OwnedHeaders;
+use rdkafka::message::{Message, OwnedHeaders};
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::util::Timeout;
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tower::Service;
 
 pub struct KafkaRequest {
@@ -80,9 +82,24 @@ impl Service<KafkaRequest> for KafkaService {
             }
 
             //rdkafka will internally retry forever if the queue is full
+            let start = Instant::now();
             let result = match kafka_producer.send(record, Timeout::Never).await {
-                Ok((_partition, _offset)) => Ok(KafkaResponse {}),
-                Err((kafka_err, _original_record)) => Err(kafka_err),
+                Ok((partition, _offset)) => {
+                    emit!(&KafkaDeliveryReported {
+                        topic: &request.metadata.topic,
+                        partition,
+                        latency: start.elapsed(),
+                    });
+                    Ok(KafkaResponse {})
+                }
+                Err((kafka_err, original_record)) => {
+                    emit!(&KafkaDeliveryFailed {
+                        topic: &request.metadata.topic,
+                        partition: original_record.partition(),
+                        error: &kafka_err,
+                    });
+                    Err(kafka_err)
+                }
             };
             result
         })
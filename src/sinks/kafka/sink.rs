@@ -2,6 +2,7 @@ use super::config::KafkaRole;
 use super::config::KafkaSinkConfig;
 use crate::event::Event;
 use crate::kafka::KafkaStatisticsContext;
+use crate::schema_registry::Schema;
 use crate::sinks::kafka::config::QUEUED_MIN_MESSAGES;
 use crate::sinks::kafka::request_builder::KafkaRequestBuilder;
 use crate::sinks::kafka::service::KafkaService;
@@ -18,6 +19,7 @@ use rdkafka::producer::FutureProducer;
 use rdkafka::ClientConfig;
 use snafu::{ResultExt, Snafu};
 use std::convert::TryFrom;
+use std::sync::Arc;
 use tokio::time::Duration;
 use tower::limit::ConcurrencyLimit;
 use vector_core::buffers::Acker;
@@ -38,6 +40,7 @@ pub struct KafkaSink {
     topic: Template,
     key_field: Option<String>,
     headers_field: Option<String>,
+    schema: Option<Arc<Schema>>,
 }
 
 pub fn create_producer(
@@ -50,7 +53,11 @@ pub fn create_producer(
 }
 
 impl KafkaSink {
-    pub(crate) fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
+    pub(crate) fn new(
+        config: KafkaSinkConfig,
+        acker: Acker,
+        schema: Option<Arc<Schema>>,
+    ) -> crate::Result<Self> {
         let producer_config = config.to_rdkafka(KafkaRole::Producer)?;
         let producer = create_producer(producer_config)?;
 
@@ -61,6 +68,7 @@ impl KafkaSink {
             service: KafkaService::new(producer),
             topic: Template::try_from(config.topic).context(TopicTemplate)?,
             key_field: config.key_field,
+            schema,
         })
     }
 
@@ -72,6 +80,7 @@ impl KafkaSink {
             headers_field: self.headers_field,
             topic_template: self.topic,
             encoder: self.encoding,
+            schema: self.schema,
             log_schema: log_schema(),
         };
         let sink = input
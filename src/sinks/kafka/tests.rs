@@ -42,6 +42,7 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_field: None,
+            schema_registry: None,
         };
 
         self::sink::healthcheck(config).await.unwrap();
@@ -97,12 +98,13 @@ mod integration_test {
             batch,
             librdkafka_options,
             headers_field: None,
+            schema_registry: None,
         };
         let (acker, _ack_counter) = Acker::new_for_testing();
         config.clone().to_rdkafka(KafkaRole::Consumer)?;
         config.clone().to_rdkafka(KafkaRole::Producer)?;
         self::sink::healthcheck(config.clone()).await?;
-        KafkaSink::new(config, acker)
+        KafkaSink::new(config, acker, None)
     }
 
     #[tokio::test]
@@ -245,11 +247,12 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_field: Some(headers_key.clone()),
+            schema_registry: None,
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
         println!("Topic name generated in test: {:?}", topic);
         let (acker, ack_counter) = Acker::new_for_testing();
-        let sink = Box::new(KafkaSink::new(config, acker).unwrap());
+        let sink = Box::new(KafkaSink::new(config, acker, None).unwrap());
 
         let num_events = 1000;
         let (batch, mut receiver) = BatchNotifier::new_with_receiver();
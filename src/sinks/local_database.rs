@@ -0,0 +1,871 @@
+use crate::{
+    buffers::Acker,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, EventFinalizers, EventStatus, Finalizable, LogEvent, Value},
+    internal_events::{
+        EventsSent, LocalDatabaseFileRotated, LocalDatabaseRetentionApplied,
+        LocalDatabaseWriteFailed,
+    },
+    sinks::util::StreamSink,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use futures::{future, stream::BoxStream, FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+use vector_core::ByteSizeOf;
+
+/// How often rows are checked against `retention.max_age_secs`.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one column must be configured"))]
+    NoColumns,
+    #[snafu(display(
+        "Invalid identifier {:?}, only letters, digits and underscores are allowed",
+        name
+    ))]
+    InvalidIdentifier { name: String },
+    #[snafu(display("`retention.max_age_secs` requires a column of type `timestamp`"))]
+    NoTimestampColumn,
+    #[snafu(display(
+        "The `duckdb` engine requires Vector to be built with the `sinks-local_database-duckdb` feature"
+    ))]
+    DuckDbUnavailable,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LocalDatabaseSinkConfig {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub engine: Engine,
+    #[serde(default = "default_table")]
+    pub table: String,
+    #[serde(default = "default_columns")]
+    pub columns: Vec<ColumnConfig>,
+    #[serde(default = "crate::serde::default_true")]
+    pub wal: bool,
+    pub max_file_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default = "default_transaction_max_events")]
+    pub transaction_max_events: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Engine {
+    Sqlite,
+    #[serde(rename = "duckdb")]
+    DuckDb,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::Sqlite
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    pub name: String,
+    /// The event field stored in the column, `.` stores the whole event.
+    /// Defaults to the column name.
+    pub field: Option<String>,
+    #[serde(rename = "type", default)]
+    pub column_type: ColumnType,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Real,
+    Boolean,
+    Timestamp,
+    Json,
+}
+
+impl Default for ColumnType {
+    fn default() -> Self {
+        Self::Text
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionConfig {
+    pub max_age_secs: Option<u64>,
+    pub max_files: Option<usize>,
+}
+
+fn default_table() -> String {
+    "events".into()
+}
+
+fn default_columns() -> Vec<ColumnConfig> {
+    vec![
+        ColumnConfig {
+            name: "timestamp".into(),
+            field: Some(log_schema().timestamp_key().into()),
+            column_type: ColumnType::Timestamp,
+        },
+        ColumnConfig {
+            name: "event".into(),
+            field: Some(".".into()),
+            column_type: ColumnType::Json,
+        },
+    ]
+}
+
+const fn default_transaction_max_events() -> usize {
+    1000
+}
+
+inventory::submit! {
+    SinkDescription::new::<LocalDatabaseSinkConfig>("local_database")
+}
+
+impl GenerateConfig for LocalDatabaseSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/var/lib/vector/events.db".into(),
+            engine: Engine::Sqlite,
+            table: default_table(),
+            columns: default_columns(),
+            wal: true,
+            max_file_size_bytes: None,
+            retention: RetentionConfig::default(),
+            transaction_max_events: default_transaction_max_events(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "local_database")]
+impl SinkConfig for LocalDatabaseSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let writer = Writer::new(self)?;
+        let sink = LocalDatabaseSink {
+            writer,
+            transaction_max_events: self.transaction_max_events.max(1),
+            acker: cx.acker(),
+        };
+        Ok((
+            super::VectorSink::Stream(Box::new(sink)),
+            future::ok(()).boxed(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "local_database"
+    }
+}
+
+fn validate_identifier(name: &str) -> crate::Result<()> {
+    let mut chars = name.chars();
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(BuildError::InvalidIdentifier { name: name.into() }.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+impl rusqlite::ToSql for SqlValue {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        use rusqlite::types::{ToSqlOutput, ValueRef};
+
+        Ok(ToSqlOutput::Borrowed(match self {
+            Self::Null => ValueRef::Null,
+            Self::Integer(value) => ValueRef::Integer(*value),
+            Self::Real(value) => ValueRef::Real(*value),
+            Self::Text(value) => ValueRef::Text(value.as_bytes()),
+        }))
+    }
+}
+
+#[cfg(feature = "sinks-local_database-duckdb")]
+impl duckdb::ToSql for SqlValue {
+    fn to_sql(&self) -> duckdb::Result<duckdb::types::ToSqlOutput<'_>> {
+        use duckdb::types::{ToSqlOutput, ValueRef};
+
+        Ok(ToSqlOutput::Borrowed(match self {
+            Self::Null => ValueRef::Null,
+            Self::Integer(value) => ValueRef::BigInt(*value),
+            Self::Real(value) => ValueRef::Double(*value),
+            Self::Text(value) => ValueRef::Text(value.as_bytes()),
+        }))
+    }
+}
+
+fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+    // Fixed width, so timestamps also compare correctly as text.
+    timestamp.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn column_value(value: Option<&Value>, column_type: ColumnType) -> SqlValue {
+    let value = match value {
+        None | Some(Value::Null) => return SqlValue::Null,
+        Some(value) => value,
+    };
+    match (column_type, value) {
+        (ColumnType::Json, value) => serde_json::to_string(value)
+            .map(SqlValue::Text)
+            .unwrap_or(SqlValue::Null),
+        (ColumnType::Text, value) => SqlValue::Text(value.to_string_lossy()),
+        (ColumnType::Integer, Value::Integer(value)) => SqlValue::Integer(*value),
+        (ColumnType::Integer, Value::Float(value)) => SqlValue::Integer(*value as i64),
+        (ColumnType::Integer, Value::Timestamp(value)) => SqlValue::Integer(value.timestamp()),
+        (ColumnType::Integer, Value::Bytes(value)) => String::from_utf8_lossy(value)
+            .trim()
+            .parse()
+            .map(SqlValue::Integer)
+            .unwrap_or(SqlValue::Null),
+        (ColumnType::Real, Value::Float(value)) => SqlValue::Real(*value),
+        (ColumnType::Real, Value::Integer(value)) => SqlValue::Real(*value as f64),
+        (ColumnType::Real, Value::Bytes(value)) => String::from_utf8_lossy(value)
+            .trim()
+            .parse()
+            .map(SqlValue::Real)
+            .unwrap_or(SqlValue::Null),
+        (ColumnType::Integer | ColumnType::Boolean, Value::Boolean(value)) => {
+            SqlValue::Integer(*value as i64)
+        }
+        (ColumnType::Boolean, Value::Bytes(value)) => match value.as_ref() {
+            b"true" => SqlValue::Integer(1),
+            b"false" => SqlValue::Integer(0),
+            _ => SqlValue::Null,
+        },
+        (ColumnType::Timestamp, Value::Timestamp(value)) => SqlValue::Text(format_timestamp(value)),
+        (ColumnType::Timestamp, Value::Integer(value)) => {
+            SqlValue::Text(format_timestamp(&Utc.timestamp(*value, 0)))
+        }
+        (ColumnType::Timestamp, Value::Bytes(value)) => {
+            DateTime::parse_from_rfc3339(String::from_utf8_lossy(value).trim())
+                .map(|value| SqlValue::Text(format_timestamp(&value.with_timezone(&Utc))))
+                .unwrap_or(SqlValue::Null)
+        }
+        _ => SqlValue::Null,
+    }
+}
+
+enum Connection {
+    Sqlite(rusqlite::Connection),
+    #[cfg(feature = "sinks-local_database-duckdb")]
+    DuckDb(duckdb::Connection),
+}
+
+impl Connection {
+    fn open(engine: Engine, path: &Path) -> crate::Result<Self> {
+        match engine {
+            Engine::Sqlite => {
+                let connection = rusqlite::Connection::open(path)?;
+                // Readers querying the file on the device may briefly lock it.
+                connection.busy_timeout(Duration::from_secs(5))?;
+                Ok(Self::Sqlite(connection))
+            }
+            #[cfg(feature = "sinks-local_database-duckdb")]
+            Engine::DuckDb => Ok(Self::DuckDb(duckdb::Connection::open(path)?)),
+            #[cfg(not(feature = "sinks-local_database-duckdb"))]
+            Engine::DuckDb => Err(BuildError::DuckDbUnavailable.into()),
+        }
+    }
+
+    fn execute_batch(&self, sql: &str) -> crate::Result<()> {
+        match self {
+            Self::Sqlite(connection) => connection.execute_batch(sql)?,
+            #[cfg(feature = "sinks-local_database-duckdb")]
+            Self::DuckDb(connection) => connection.execute_batch(sql)?,
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, sql: &str, rows: &[Vec<SqlValue>]) -> crate::Result<()> {
+        match self {
+            Self::Sqlite(connection) => {
+                let transaction = connection.transaction()?;
+                {
+                    let mut statement = transaction.prepare_cached(sql)?;
+                    for row in rows {
+                        let params = row
+                            .iter()
+                            .map(|value| value as &dyn rusqlite::ToSql)
+                            .collect::<Vec<_>>();
+                        statement.execute(&params[..])?;
+                    }
+                }
+                transaction.commit()?;
+            }
+            #[cfg(feature = "sinks-local_database-duckdb")]
+            Self::DuckDb(connection) => {
+                let transaction = connection.transaction()?;
+                {
+                    let mut statement = transaction.prepare(sql)?;
+                    for row in rows {
+                        let params = row
+                            .iter()
+                            .map(|value| value as &dyn duckdb::ToSql)
+                            .collect::<Vec<_>>();
+                        statement.execute(&params[..])?;
+                    }
+                }
+                transaction.commit()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_before(&self, sql: &str, before: &str) -> crate::Result<usize> {
+        Ok(match self {
+            Self::Sqlite(connection) => connection.execute(sql, [before])?,
+            #[cfg(feature = "sinks-local_database-duckdb")]
+            Self::DuckDb(connection) => connection.execute(sql, [before])?,
+        })
+    }
+
+    fn close(self) -> crate::Result<()> {
+        match self {
+            Self::Sqlite(connection) => connection.close().map_err(|(_, error)| error)?,
+            // DuckDB checkpoints its write-ahead log when dropped.
+            #[cfg(feature = "sinks-local_database-duckdb")]
+            Self::DuckDb(connection) => drop(connection),
+        }
+        Ok(())
+    }
+}
+
+/// Owns the database file and does all the blocking work.
+struct Writer {
+    path: PathBuf,
+    engine: Engine,
+    columns: Vec<(String, ColumnType)>,
+    setup_sql: String,
+    insert_sql: String,
+    prune_sql: Option<String>,
+    max_file_size_bytes: Option<u64>,
+    retention: RetentionConfig,
+    connection: Option<Connection>,
+    last_prune: Option<Instant>,
+}
+
+impl Writer {
+    fn new(config: &LocalDatabaseSinkConfig) -> crate::Result<Self> {
+        if config.columns.is_empty() {
+            return Err(BuildError::NoColumns.into());
+        }
+        if config.engine == Engine::DuckDb && !cfg!(feature = "sinks-local_database-duckdb") {
+            return Err(BuildError::DuckDbUnavailable.into());
+        }
+        validate_identifier(&config.table)?;
+        for column in &config.columns {
+            validate_identifier(&column.name)?;
+        }
+
+        let table = &config.table;
+        let definitions = config
+            .columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "\"{}\" {}",
+                    column.name,
+                    sql_type(config.engine, column.column_type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut setup_sql = String::new();
+        if config.engine == Engine::Sqlite {
+            setup_sql.push_str(if config.wal {
+                "PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; "
+            } else {
+                "PRAGMA journal_mode = DELETE; "
+            });
+        }
+        setup_sql.push_str(&format!(
+            "CREATE TABLE IF NOT EXISTS \"{}\" ({});",
+            table, definitions
+        ));
+
+        let prune_sql = match config.retention.max_age_secs {
+            Some(_) => {
+                let column = config
+                    .columns
+                    .iter()
+                    .find(|column| column.column_type == ColumnType::Timestamp)
+                    .ok_or(BuildError::NoTimestampColumn)?;
+                setup_sql.push_str(&format!(
+                    " CREATE INDEX IF NOT EXISTS \"{table}_{column}_idx\" ON \"{table}\" (\"{column}\");",
+                    table = table,
+                    column = column.name
+                ));
+                Some(format!(
+                    "DELETE FROM \"{}\" WHERE \"{}\" < ?",
+                    table, column.name
+                ))
+            }
+            None => None,
+        };
+
+        let insert_sql = format!(
+            "INSERT INTO \"{}\" ({}) VALUES ({})",
+            table,
+            config
+                .columns
+                .iter()
+                .map(|column| format!("\"{}\"", column.name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; config.columns.len()].join(", ")
+        );
+
+        Ok(Self {
+            path: config.path.clone(),
+            engine: config.engine,
+            columns: config
+                .columns
+                .iter()
+                .map(|column| {
+                    (
+                        column.field.clone().unwrap_or_else(|| column.name.clone()),
+                        column.column_type,
+                    )
+                })
+                .collect(),
+            setup_sql,
+            insert_sql,
+            prune_sql,
+            max_file_size_bytes: config.max_file_size_bytes,
+            retention: config.retention.clone(),
+            connection: None,
+            last_prune: None,
+        })
+    }
+
+    fn row(&self, log: &LogEvent) -> Vec<SqlValue> {
+        self.columns
+            .iter()
+            .map(|(field, column_type)| {
+                if field == "." {
+                    serde_json::to_string(log)
+                        .map(SqlValue::Text)
+                        .unwrap_or(SqlValue::Null)
+                } else {
+                    column_value(log.get(field), *column_type)
+                }
+            })
+            .collect()
+    }
+
+    fn connection(&mut self) -> crate::Result<&mut Connection> {
+        if self.connection.is_none() {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let connection = Connection::open(self.engine, &self.path)?;
+            connection.execute_batch(&self.setup_sql)?;
+            self.connection = Some(connection);
+        }
+        Ok(self
+            .connection
+            .as_mut()
+            .expect("connection was just opened"))
+    }
+
+    fn write(&mut self, events: &[Event]) -> crate::Result<()> {
+        let rows = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Log(log) => Some(self.row(log)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let insert_sql = self.insert_sql.clone();
+        self.connection()?.insert(&insert_sql, &rows)?;
+
+        self.prune_rows()?;
+        self.rotate_if_needed()
+    }
+
+    fn prune_rows(&mut self) -> crate::Result<()> {
+        let (sql, max_age_secs) = match (&self.prune_sql, self.retention.max_age_secs) {
+            (Some(sql), Some(max_age_secs)) => (sql.clone(), max_age_secs),
+            _ => return Ok(()),
+        };
+        if matches!(self.last_prune, Some(last) if last.elapsed() < PRUNE_INTERVAL) {
+            return Ok(());
+        }
+        self.last_prune = Some(Instant::now());
+
+        let before = Utc::now() - chrono::Duration::seconds(max_age_secs as i64);
+        let rows = self
+            .connection()?
+            .delete_before(&sql, &format_timestamp(&before))?;
+        let files = self.remove_rotated_files()?;
+        if rows > 0 || files > 0 {
+            emit!(&LocalDatabaseRetentionApplied { rows, files });
+        }
+        Ok(())
+    }
+
+    fn wal_path(&self) -> PathBuf {
+        let suffix = match self.engine {
+            Engine::Sqlite => "-wal",
+            Engine::DuckDb => ".wal",
+        };
+        let mut path = self.path.clone().into_os_string();
+        path.push(suffix);
+        path.into()
+    }
+
+    fn file_size(&self) -> u64 {
+        [self.path.clone(), self.wal_path()]
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    fn rotate_if_needed(&mut self) -> crate::Result<()> {
+        match self.max_file_size_bytes {
+            Some(max) if self.file_size() >= max => {}
+            _ => return Ok(()),
+        }
+
+        if let Some(connection) = self.connection.take() {
+            connection.close()?;
+        }
+        let rotated = self.rotated_path(Utc::now());
+        fs::rename(&self.path, &rotated)?;
+        emit!(&LocalDatabaseFileRotated { path: &rotated });
+
+        let files = self.remove_rotated_files()?;
+        if files > 0 {
+            emit!(&LocalDatabaseRetentionApplied { rows: 0, files });
+        }
+        Ok(())
+    }
+
+    fn name_parts(&self) -> (String, String) {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = self
+            .path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        (stem, extension)
+    }
+
+    fn rotated_path(&self, now: DateTime<Utc>) -> PathBuf {
+        let (stem, extension) = self.name_parts();
+        self.path.with_file_name(format!(
+            "{}-{}{}",
+            stem,
+            now.format("%Y%m%dT%H%M%S%6fZ"),
+            extension
+        ))
+    }
+
+    /// Rotated files, oldest first.
+    fn rotated_files(&self) -> io::Result<Vec<PathBuf>> {
+        let (stem, extension) = self.name_parts();
+        let prefix = format!("{}-", stem);
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut files = fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default();
+                name.strip_prefix(&prefix)
+                    .and_then(|name| name.strip_suffix(&extension))
+                    .map(|timestamp| timestamp.len() == 22 && timestamp.ends_with('Z'))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        // The timestamp in the name sorts chronologically.
+        files.sort();
+        Ok(files)
+    }
+
+    fn remove_rotated_files(&self) -> io::Result<usize> {
+        let mut files = self.rotated_files()?;
+        let mut removed = 0;
+
+        if let Some(max_age_secs) = self.retention.max_age_secs {
+            let cutoff = SystemTime::now() - Duration::from_secs(max_age_secs);
+            let mut kept = Vec::with_capacity(files.len());
+            for file in files {
+                let modified = fs::metadata(&file).and_then(|metadata| metadata.modified())?;
+                if modified < cutoff {
+                    fs::remove_file(&file)?;
+                    removed += 1;
+                } else {
+                    kept.push(file);
+                }
+            }
+            files = kept;
+        }
+
+        if let Some(max_files) = self.retention.max_files {
+            let excess = files.len().saturating_sub(max_files);
+            for file in &files[..excess] {
+                fs::remove_file(file)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn close(&mut self) -> crate::Result<()> {
+        match self.connection.take() {
+            Some(connection) => connection.close(),
+            None => Ok(()),
+        }
+    }
+}
+
+const fn sql_type(engine: Engine, column_type: ColumnType) -> &'static str {
+    match (engine, column_type) {
+        (Engine::Sqlite, ColumnType::Text | ColumnType::Json | ColumnType::Timestamp) => "TEXT",
+        (Engine::Sqlite, ColumnType::Integer | ColumnType::Boolean) => "INTEGER",
+        (Engine::Sqlite, ColumnType::Real) => "REAL",
+        (Engine::DuckDb, ColumnType::Text | ColumnType::Json) => "VARCHAR",
+        (Engine::DuckDb, ColumnType::Integer) => "BIGINT",
+        (Engine::DuckDb, ColumnType::Real) => "DOUBLE",
+        (Engine::DuckDb, ColumnType::Boolean) => "BOOLEAN",
+        (Engine::DuckDb, ColumnType::Timestamp) => "TIMESTAMP",
+    }
+}
+
+struct LocalDatabaseSink {
+    writer: Writer,
+    transaction_max_events: usize,
+    acker: Acker,
+}
+
+#[async_trait]
+impl StreamSink for LocalDatabaseSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let mut writer = self.writer;
+        let mut chunks = input.ready_chunks(self.transaction_max_events);
+
+        while let Some(mut events) = chunks.next().await {
+            let count = events.len();
+            let byte_size = events.size_of();
+            let mut finalizers = EventFinalizers::default();
+            for event in events.iter_mut() {
+                finalizers.merge(event.take_finalizers());
+            }
+
+            let (returned, result) = tokio::task::spawn_blocking(move || {
+                let result = writer.write(&events);
+                (writer, result)
+            })
+            .await
+            .expect("local database writer panicked");
+            writer = returned;
+
+            match result {
+                Ok(()) => {
+                    finalizers.update_status(EventStatus::Delivered);
+                    emit!(&EventsSent { count, byte_size });
+                }
+                Err(error) => {
+                    finalizers.update_status(EventStatus::Errored);
+                    emit!(&LocalDatabaseWriteFailed {
+                        error: &error,
+                        count
+                    });
+                    // Reopen the database for the next batch, in case the
+                    // file was removed or replaced.
+                    let _ = writer.close();
+                }
+            }
+            self.acker.ack(count);
+        }
+
+        tokio::task::spawn_blocking(move || writer.close())
+            .await
+            .expect("local database writer panicked")
+            .map_err(|error| error!(message = "Failed to close database.", %error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, temp_dir, trace_init};
+
+    fn config(path: PathBuf) -> LocalDatabaseSinkConfig {
+        toml::from_str(&format!(r#"path = {:?}"#, path)).unwrap()
+    }
+
+    fn count_rows(path: &Path, table: &str) -> i64 {
+        let connection = rusqlite::Connection::open(path).unwrap();
+        connection
+            .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LocalDatabaseSinkConfig>();
+    }
+
+    #[test]
+    fn rejects_invalid_identifiers() {
+        let mut config = config(temp_dir().join("events.db"));
+        config.table = "events; DROP TABLE events".into();
+        assert!(Writer::new(&config).is_err());
+    }
+
+    #[test]
+    fn converts_column_values() {
+        let timestamp = Utc.ymd(2021, 10, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            column_value(Some(&Value::from(timestamp)), ColumnType::Timestamp),
+            SqlValue::Text("2021-10-01T12:00:00.000000Z".into())
+        );
+        assert_eq!(
+            column_value(Some(&Value::from("42")), ColumnType::Integer),
+            SqlValue::Integer(42)
+        );
+        assert_eq!(
+            column_value(Some(&Value::from(true)), ColumnType::Boolean),
+            SqlValue::Integer(1)
+        );
+        assert_eq!(
+            column_value(Some(&Value::from("nope")), ColumnType::Real),
+            SqlValue::Null
+        );
+        assert_eq!(column_value(None, ColumnType::Text), SqlValue::Null);
+    }
+
+    #[test]
+    fn writes_configured_columns() {
+        let path = temp_dir().join("events.db");
+        let config: LocalDatabaseSinkConfig = toml::from_str(&format!(
+            r#"
+            path = {:?}
+            table = "requests"
+            columns = [
+              {{ name = "host" }},
+              {{ name = "status", type = "integer" }},
+              {{ name = "duration", field = "timings.total", type = "real" }},
+            ]
+            "#,
+            path
+        ))
+        .unwrap();
+        let mut writer = Writer::new(&config).unwrap();
+
+        let mut event = Event::from("GET /");
+        event.as_mut_log().insert("host", "edge-1");
+        event.as_mut_log().insert("status", 200);
+        event.as_mut_log().insert("timings.total", 0.25);
+        writer.write(&[event]).unwrap();
+        writer.close().unwrap();
+
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        let row: (String, i64, f64) = connection
+            .query_row("SELECT host, status, duration FROM requests", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(row, ("edge-1".to_owned(), 200, 0.25));
+    }
+
+    #[test]
+    fn rotates_and_prunes_files() {
+        let path = temp_dir().join("events.db");
+        let mut config = config(path.clone());
+        config.max_file_size_bytes = Some(1);
+        config.retention.max_files = Some(2);
+        let mut writer = Writer::new(&config).unwrap();
+
+        for _ in 0..4 {
+            writer.write(&[Event::from("hello")]).unwrap();
+        }
+
+        assert!(!path.exists());
+        let rotated = writer.rotated_files().unwrap();
+        assert_eq!(rotated.len(), 2);
+        for file in rotated {
+            assert_eq!(count_rows(&file, "events"), 1);
+        }
+    }
+
+    #[test]
+    fn prunes_expired_rows() {
+        let path = temp_dir().join("events.db");
+        let mut config = config(path.clone());
+        config.retention.max_age_secs = Some(3600);
+        let mut writer = Writer::new(&config).unwrap();
+
+        let mut old = Event::from("old");
+        old.as_mut_log().insert(
+            log_schema().timestamp_key(),
+            Utc::now() - chrono::Duration::hours(2),
+        );
+        writer.write(&[old, Event::from("new")]).unwrap();
+        writer.close().unwrap();
+
+        assert_eq!(count_rows(&path, "events"), 1);
+    }
+
+    #[tokio::test]
+    async fn writes_events() {
+        trace_init();
+
+        let path = temp_dir().join("events.db");
+        let (sink, _) = config(path.clone())
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+
+        let (_, events) = random_lines_with_stream(32, 100, None);
+        sink.run(events).await.unwrap();
+
+        assert_eq!(count_rows(&path, "events"), 100);
+    }
+}
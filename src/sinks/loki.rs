@@ -10,14 +10,22 @@
 //!
 //! If an event produces no labels, this can happen if the template
 //! does not match, we will add a default label `{agent="vector"}`.
+//!
+//! Events are also partitioned by their rendered `tenant_id`, so that
+//! every request carries the `X-Scope-OrgID` of a single tenant. When
+//! `label_cardinality` is set, label sets beyond the limit of a tenant
+//! are replaced with a fallback label set until the next flush.
 
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::{self, Event, Value},
     http::{Auth, HttpClient, MaybeAuth},
-    internal_events::{LokiEventUnlabeled, LokiEventsProcessed, TemplateRenderingFailed},
+    internal_events::{
+        LokiEventUnlabeled, LokiEventsProcessed, LokiLabelCardinalityExceeded,
+        TemplateRenderingFailed,
+    },
     sinks::util::{
-        buffer::loki::{GlobalTimestamps, LokiBuffer, LokiEvent, LokiRecord, PartitionKey},
+        buffer::loki::{GlobalTimestamps, Labels, LokiBuffer, LokiEvent, LokiRecord, PartitionKey},
         encoding::{EncodingConfig, EncodingConfiguration},
         http::{HttpSink, PartitionHttpSink},
         BatchConfig, BatchSettings, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
@@ -29,7 +37,13 @@ use crate::{
 use futures::{FutureExt, SinkExt};
 use serde::{Deserialize, Serialize};
 use shared::encode_logfmt;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_BATCH_TIMEOUT_SECS: u64 = 1;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -47,6 +61,8 @@ pub struct LokiConfig {
     #[serde(default)]
     out_of_order_action: OutOfOrderAction,
 
+    label_cardinality: Option<LabelCardinalityConfig>,
+
     auth: Option<Auth>,
 
     #[serde(default)]
@@ -58,6 +74,22 @@ pub struct LokiConfig {
     tls: Option<TlsOptions>,
 }
 
+/// Caps the number of distinct label sets, and so streams, each tenant gets
+/// per flush.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LabelCardinalityConfig {
+    max_label_sets: usize,
+    #[serde(default = "default_fallback_labels")]
+    fallback_labels: HashMap<String, String>,
+}
+
+fn default_fallback_labels() -> HashMap<String, String> {
+    vec![("agent".to_owned(), "vector".to_owned())]
+        .into_iter()
+        .collect()
+}
+
 #[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(rename_all = "snake_case")]
@@ -107,6 +139,10 @@ impl SinkConfig for LokiConfig {
             }
         }
 
+        if let Some(label_cardinality) = &self.label_cardinality {
+            label_cardinality.validate()?;
+        }
+
         let request_settings = self.request.unwrap_with(&TowerRequestConfig {
             ..Default::default()
         });
@@ -114,7 +150,7 @@ impl SinkConfig for LokiConfig {
         let batch_settings = BatchSettings::default()
             .bytes(102_400)
             .events(100_000)
-            .timeout(1)
+            .timeout(DEFAULT_BATCH_TIMEOUT_SECS)
             .parse_config(self.batch)?;
         let tls = TlsSettings::from_options(&self.tls)?;
         let client = HttpClient::new(tls, cx.proxy())?;
@@ -164,13 +200,21 @@ struct LokiSink {
 
     remove_label_fields: bool,
     remove_timestamp: bool,
+    label_cardinality: Option<LabelCardinalityGuard>,
 
     auth: Option<Auth>,
 }
 
 impl LokiSink {
-    #[allow(clippy::missing_const_for_fn)] // const cannot run destructor
     fn new(config: LokiConfig) -> Self {
+        // Label sets are counted over the batch timeout, the longest any
+        // of them waits to be flushed.
+        let window = Duration::from_secs(
+            config
+                .batch
+                .timeout_secs
+                .unwrap_or(DEFAULT_BATCH_TIMEOUT_SECS),
+        );
         Self {
             endpoint: config.endpoint,
             encoding: config.encoding,
@@ -178,11 +222,83 @@ impl LokiSink {
             labels: config.labels,
             remove_label_fields: config.remove_label_fields,
             remove_timestamp: config.remove_timestamp,
+            label_cardinality: config
+                .label_cardinality
+                .map(|config| LabelCardinalityGuard::new(config, window)),
             auth: config.auth,
         }
     }
 }
 
+impl LabelCardinalityConfig {
+    fn validate(&self) -> crate::Result<()> {
+        if self.max_label_sets == 0 {
+            return Err("`label_cardinality.max_label_sets` must be greater than zero.".into());
+        }
+        if self.fallback_labels.is_empty() {
+            return Err(
+                "`label_cardinality.fallback_labels` must include at least one label.".into(),
+            );
+        }
+        for label in self.fallback_labels.keys() {
+            if !valid_static_label_name(label) {
+                return Err(format!("Invalid fallback label name {:?}", label).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+struct LabelCardinalityGuard {
+    max_label_sets: usize,
+    fallback_labels: Labels,
+    window: Duration,
+    state: Mutex<LabelSets>,
+}
+
+struct LabelSets {
+    since: Instant,
+    seen: HashSet<PartitionKey>,
+    per_tenant: HashMap<Option<String>, usize>,
+}
+
+impl LabelCardinalityGuard {
+    fn new(config: LabelCardinalityConfig, window: Duration) -> Self {
+        Self {
+            max_label_sets: config.max_label_sets,
+            fallback_labels: config.fallback_labels.into_iter().collect(),
+            window,
+            state: Mutex::new(LabelSets {
+                since: Instant::now(),
+                seen: HashSet::new(),
+                per_tenant: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Whether events with the labels of `key` can be sent as is, rather than
+    /// with the fallback labels.
+    fn admit(&self, key: &PartitionKey) -> bool {
+        let mut state = self.state.lock().expect("label sets lock poisoned");
+        if state.since.elapsed() >= self.window {
+            state.since = Instant::now();
+            state.seen.clear();
+            state.per_tenant.clear();
+        }
+
+        if state.seen.contains(key) {
+            return true;
+        }
+        let count = state.per_tenant.entry(key.tenant_id.clone()).or_default();
+        if *count >= self.max_label_sets {
+            return false;
+        }
+        *count += 1;
+        state.seen.insert(key.clone());
+        true
+    }
+}
+
 #[async_trait::async_trait]
 impl HttpSink for LokiSink {
     type Input = PartitionInnerBuffer<LokiRecord, PartitionKey>;
@@ -255,7 +371,17 @@ impl HttpSink for LokiSink {
             labels = vec![("agent".to_string(), "vector".to_string())]
         }
 
-        let key = PartitionKey::new(tenant_id, &mut labels);
+        let mut key = PartitionKey::new(tenant_id, &mut labels);
+
+        if let Some(guard) = &self.label_cardinality {
+            if !guard.admit(&key) {
+                emit!(&LokiLabelCardinalityExceeded {
+                    limit: guard.max_label_sets,
+                });
+                labels = guard.fallback_labels.clone();
+                key = PartitionKey::new(key.tenant_id, &mut labels);
+            }
+        }
 
         let event = LokiEvent { timestamp, event };
         Some(PartitionInnerBuffer::new(
@@ -341,20 +467,22 @@ async fn fetch_status(
 }
 
 fn valid_label_name(label: &Template) -> bool {
-    label.is_dynamic() || {
-        // Loki follows prometheus on this https://prometheus.io/docs/concepts/data_model/#metric-names-and-labels
-        // Although that isn't explicitly said anywhere besides what's in the code.
-        // The closest mention is in section about Parser Expression https://grafana.com/docs/loki/latest/logql/
-        //
-        // [a-zA-Z_][a-zA-Z0-9_]*
-        let label_trim = label.get_ref().trim();
-        let mut label_chars = label_trim.chars();
-        if let Some(ch) = label_chars.next() {
-            (ch.is_ascii_alphabetic() || ch == '_')
-                && label_chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
-        } else {
-            false
-        }
+    label.is_dynamic() || valid_static_label_name(label.get_ref())
+}
+
+fn valid_static_label_name(label: &str) -> bool {
+    // Loki follows prometheus on this https://prometheus.io/docs/concepts/data_model/#metric-names-and-labels
+    // Although that isn't explicitly said anywhere besides what's in the code.
+    // The closest mention is in section about Parser Expression https://grafana.com/docs/loki/latest/logql/
+    //
+    // [a-zA-Z_][a-zA-Z0-9_]*
+    let label_trim = label.trim();
+    let mut label_chars = label_trim.chars();
+    if let Some(ch) = label_chars.next() {
+        (ch.is_ascii_alphabetic() || ch == '_')
+            && label_chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+    } else {
+        false
     }
 }
 
@@ -443,6 +571,39 @@ mod tests {
         assert_eq!(record.labels[0], ("bar".to_string(), "bar".to_string()));
     }
 
+    #[test]
+    fn caps_label_sets_per_tenant() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {host = "{{ host }}"}
+            tenant_id = "{{ tenant }}"
+            encoding = "json"
+            label_cardinality.max_label_sets = 2
+            label_cardinality.fallback_labels = {overflow = "true"}
+        "#,
+        )
+        .unwrap();
+        let sink = LokiSink::new(config);
+
+        let labels = |tenant: &str, host: &str| {
+            let mut event = Event::from("hello world");
+            event.as_mut_log().insert("tenant", tenant);
+            event.as_mut_log().insert("host", host);
+            let (record, key) = sink.encode_event(event).unwrap().into_parts();
+            assert_eq!(key.tenant_id.as_deref(), Some(tenant));
+            record.labels
+        };
+
+        let host = |host: &str| vec![("host".to_owned(), host.to_owned())];
+        let overflow = vec![("overflow".to_owned(), "true".to_owned())];
+        assert_eq!(labels("a", "1"), host("1"));
+        assert_eq!(labels("a", "2"), host("2"));
+        assert_eq!(labels("a", "3"), overflow);
+        assert_eq!(labels("a", "1"), host("1"));
+        assert_eq!(labels("b", "3"), host("3"));
+    }
+
     #[tokio::test]
     async fn healthcheck_includes_auth() {
         let (mut config, _cx) = load_sink::<LokiConfig>(
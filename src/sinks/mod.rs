@@ -13,14 +13,22 @@ pub mod aws_kinesis_firehose;
 pub mod aws_kinesis_streams;
 #[cfg(feature = "sinks-aws_s3")]
 pub mod aws_s3;
+#[cfg(feature = "sinks-aws_sns")]
+pub mod aws_sns;
 #[cfg(feature = "sinks-aws_sqs")]
 pub mod aws_sqs;
+#[cfg(feature = "sinks-axiom")]
+pub mod axiom;
 #[cfg(feature = "sinks-azure_blob")]
 pub mod azure_blob;
+#[cfg(feature = "sinks-azure_blob")]
+pub mod azure_credentials;
 #[cfg(feature = "sinks-azure_monitor_logs")]
 pub mod azure_monitor_logs;
 #[cfg(feature = "sinks-blackhole")]
 pub mod blackhole;
+#[cfg(feature = "sinks-chat_webhook")]
+pub mod chat_webhook;
 #[cfg(feature = "sinks-clickhouse")]
 pub mod clickhouse;
 #[cfg(feature = "sinks-console")]
@@ -31,6 +39,8 @@ pub mod datadog;
 pub mod datadog_archives;
 #[cfg(feature = "sinks-elasticsearch")]
 pub mod elasticsearch;
+#[cfg(feature = "sinks-exec")]
+pub mod exec;
 #[cfg(feature = "sinks-file")]
 pub mod file;
 #[cfg(feature = "sinks-gcp")]
@@ -45,6 +55,8 @@ pub mod humio;
 pub mod influxdb;
 #[cfg(all(feature = "sinks-kafka", feature = "rdkafka"))]
 pub mod kafka;
+#[cfg(feature = "sinks-local_database")]
+pub mod local_database;
 #[cfg(feature = "sinks-logdna")]
 pub mod logdna;
 #[cfg(feature = "sinks-loki")]
@@ -53,6 +65,8 @@ pub mod loki;
 pub mod nats;
 #[cfg(feature = "sinks-new_relic_logs")]
 pub mod new_relic_logs;
+#[cfg(feature = "sinks-opentelemetry")]
+pub mod opentelemetry;
 #[cfg(feature = "sinks-papertrail")]
 pub mod papertrail;
 #[cfg(feature = "sinks-prometheus")]
@@ -65,6 +79,8 @@ pub mod redis;
 pub mod s3_common;
 #[cfg(feature = "sinks-sematext")]
 pub mod sematext;
+#[cfg(feature = "sinks-sentry")]
+pub mod sentry;
 #[cfg(feature = "sinks-socket")]
 pub mod socket;
 #[cfg(feature = "sinks-splunk_hec")]
@@ -73,6 +89,8 @@ pub mod splunk_hec;
 pub mod statsd;
 #[cfg(feature = "sinks-vector")]
 pub mod vector;
+#[cfg(feature = "sinks-webhook_fanout")]
+pub mod webhook_fanout;
 
 pub use vector_core::sink::VectorSink;
 
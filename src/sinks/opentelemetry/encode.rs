@@ -0,0 +1,635 @@
+//! Conversion of Vector events into OpenTelemetry protocol messages.
+
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
+    internal_events::TemplateRenderingFailed,
+    proto::opentelemetry as proto,
+    sinks::util::{EncodedEvent, EncodedLength, PartitionInnerBuffer},
+    template::Template,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use indexmap::IndexMap;
+use prost::Message;
+use vector_core::ByteSizeOf;
+
+/// The kind of telemetry a record belongs to. Each kind is exported through
+/// its own service, so records of different kinds are batched separately.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Signal {
+    Logs,
+    Metrics,
+}
+
+impl Signal {
+    /// The path the records are exported to with the HTTP protocol.
+    pub const fn http_path(self) -> &'static str {
+        match self {
+            Signal::Logs => "/v1/logs",
+            Signal::Metrics => "/v1/metrics",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Record {
+    Log(proto::LogRecord),
+    Metric(proto::Metric),
+}
+
+/// A record along with the resource it was produced by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OtlpRecord {
+    pub resource: proto::Resource,
+    pub record: Record,
+}
+
+impl EncodedLength for OtlpRecord {
+    fn encoded_length(&self) -> usize {
+        let record = match &self.record {
+            Record::Log(log) => log.encoded_len(),
+            Record::Metric(metric) => metric.encoded_len(),
+        };
+        self.resource.encoded_len() + record
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OtlpEncoder {
+    pub resource_attributes: IndexMap<String, Template>,
+    pub remove_resource_fields: bool,
+}
+
+impl OtlpEncoder {
+    pub fn encode_event(
+        &self,
+        mut event: Event,
+    ) -> EncodedEvent<PartitionInnerBuffer<OtlpRecord, Signal>> {
+        let byte_size = event.size_of();
+        let finalizers = event.metadata_mut().take_finalizers();
+        let resource = self.render_resource(&event);
+
+        if self.remove_resource_fields {
+            self.remove_fields(&mut event);
+        }
+
+        let (record, signal) = match event {
+            Event::Log(log) => (Record::Log(encode_log(log)), Signal::Logs),
+            Event::Metric(metric) => (Record::Metric(encode_metric(&metric)), Signal::Metrics),
+        };
+
+        EncodedEvent {
+            item: PartitionInnerBuffer::new(OtlpRecord { resource, record }, signal),
+            finalizers,
+            byte_size,
+        }
+    }
+
+    fn render_resource(&self, event: &Event) -> proto::Resource {
+        let attributes = self
+            .resource_attributes
+            .iter()
+            .filter_map(|(key, template)| {
+                template
+                    .render_string(event)
+                    .map_err(|error| {
+                        emit!(&TemplateRenderingFailed {
+                            error,
+                            field: Some(key.as_str()),
+                            drop_event: false,
+                        })
+                    })
+                    .ok()
+                    .map(|value| string_key_value(key, value))
+            })
+            .collect();
+
+        proto::Resource {
+            attributes,
+            dropped_attributes_count: 0,
+        }
+    }
+
+    /// Removes the fields, or tags, the resource attributes are rendered from.
+    fn remove_fields(&self, event: &mut Event) {
+        for template in self.resource_attributes.values() {
+            for field in template.get_fields().unwrap_or_default() {
+                match event {
+                    Event::Log(log) => {
+                        log.remove(&field);
+                    }
+                    Event::Metric(metric) => {
+                        metric.remove_tag(&field);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The fields of a log event mapped to the fields of the OTLP log record,
+/// instead of its attributes.
+const SEVERITY_TEXT_FIELD: &str = "severity_text";
+const SEVERITY_NUMBER_FIELD: &str = "severity_number";
+const TRACE_ID_FIELD: &str = "trace_id";
+const SPAN_ID_FIELD: &str = "span_id";
+
+pub fn encode_log(mut log: LogEvent) -> proto::LogRecord {
+    let time_unix_nano = take_field(
+        &mut log,
+        log_schema().timestamp_key(),
+        |value| match value {
+            Value::Timestamp(timestamp) => Some(unix_nanos(*timestamp)),
+            _ => None,
+        },
+    )
+    .unwrap_or(0);
+    let body = log.remove(log_schema().message_key()).map(any_value);
+    let severity_text = take_field(&mut log, SEVERITY_TEXT_FIELD, |value| match value {
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        _ => None,
+    })
+    .unwrap_or_default();
+    let severity_number = take_field(&mut log, SEVERITY_NUMBER_FIELD, |value| match value {
+        Value::Integer(number) => proto::SeverityNumber::from_i32(*number as i32),
+        _ => None,
+    })
+    .unwrap_or(proto::SeverityNumber::Unspecified);
+    let trace_id = take_field(&mut log, TRACE_ID_FIELD, |value| decode_id(value, 16));
+    let span_id = take_field(&mut log, SPAN_ID_FIELD, |value| decode_id(value, 8));
+
+    let (fields, _) = log.into_parts();
+
+    proto::LogRecord {
+        time_unix_nano,
+        observed_time_unix_nano: unix_nanos(Utc::now()),
+        severity_number: severity_number as i32,
+        severity_text,
+        body,
+        attributes: fields.into_iter().map(key_value).collect(),
+        dropped_attributes_count: 0,
+        flags: 0,
+        trace_id: trace_id.unwrap_or_default(),
+        span_id: span_id.unwrap_or_default(),
+    }
+}
+
+pub fn encode_metric(metric: &Metric) -> proto::Metric {
+    use proto::metric::Data;
+
+    let name = match metric.namespace() {
+        Some(namespace) => format!("{}.{}", namespace, metric.name()),
+        None => metric.name().to_owned(),
+    };
+    let attributes: Vec<_> = metric
+        .tags()
+        .map(|tags| {
+            tags.iter()
+                .map(|(key, value)| string_key_value(key, value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let time_unix_nano = unix_nanos(metric.timestamp().unwrap_or_else(Utc::now));
+    let aggregation_temporality = match metric.kind() {
+        MetricKind::Incremental => proto::AggregationTemporality::Delta,
+        MetricKind::Absolute => proto::AggregationTemporality::Cumulative,
+    } as i32;
+
+    let number = |value: f64| {
+        vec![proto::NumberDataPoint {
+            attributes: attributes.clone(),
+            time_unix_nano,
+            value: Some(proto::number_data_point::Value::AsDouble(value)),
+            ..Default::default()
+        }]
+    };
+
+    let data = match (metric.kind(), metric.value()) {
+        (_, MetricValue::Counter { value }) => Data::Sum(proto::Sum {
+            data_points: number(*value),
+            aggregation_temporality,
+            is_monotonic: true,
+        }),
+        (MetricKind::Absolute, MetricValue::Gauge { value }) => Data::Gauge(proto::Gauge {
+            data_points: number(*value),
+        }),
+        (MetricKind::Incremental, MetricValue::Gauge { value }) => Data::Sum(proto::Sum {
+            data_points: number(*value),
+            aggregation_temporality,
+            is_monotonic: false,
+        }),
+        (_, MetricValue::Set { values }) => Data::Gauge(proto::Gauge {
+            data_points: number(values.len() as f64),
+        }),
+        (_, MetricValue::Distribution { samples, .. }) => {
+            // Without bounds, the histogram has a single bucket counting
+            // all the samples.
+            let count = samples.iter().map(|sample| sample.rate as u64).sum();
+            let sum = samples
+                .iter()
+                .map(|sample| sample.value * sample.rate as f64)
+                .sum();
+            Data::Histogram(proto::Histogram {
+                data_points: vec![proto::HistogramDataPoint {
+                    attributes,
+                    time_unix_nano,
+                    count,
+                    sum,
+                    bucket_counts: vec![count],
+                    ..Default::default()
+                }],
+                aggregation_temporality,
+            })
+        }
+        (
+            _,
+            MetricValue::AggregatedHistogram {
+                buckets,
+                count,
+                sum,
+            },
+        ) => {
+            let mut explicit_bounds = Vec::with_capacity(buckets.len());
+            let mut bucket_counts = Vec::with_capacity(buckets.len() + 1);
+            for bucket in buckets
+                .iter()
+                .filter(|bucket| bucket.upper_limit.is_finite())
+            {
+                explicit_bounds.push(bucket.upper_limit);
+                bucket_counts.push(bucket.count as u64);
+            }
+            // The last bucket counts the observations above all the bounds.
+            let bucketed: u64 = bucket_counts.iter().sum();
+            bucket_counts.push((*count as u64).saturating_sub(bucketed));
+
+            Data::Histogram(proto::Histogram {
+                data_points: vec![proto::HistogramDataPoint {
+                    attributes,
+                    time_unix_nano,
+                    count: *count as u64,
+                    sum: *sum,
+                    bucket_counts,
+                    explicit_bounds,
+                    ..Default::default()
+                }],
+                aggregation_temporality,
+            })
+        }
+        (
+            _,
+            MetricValue::AggregatedSummary {
+                quantiles,
+                count,
+                sum,
+            },
+        ) => Data::Summary(proto::Summary {
+            data_points: vec![proto::SummaryDataPoint {
+                attributes,
+                time_unix_nano,
+                count: *count as u64,
+                sum: *sum,
+                quantile_values: quantiles
+                    .iter()
+                    .map(|quantile| proto::summary_data_point::ValueAtQuantile {
+                        quantile: quantile.upper_limit,
+                        value: quantile.value,
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+        }),
+    };
+
+    proto::Metric {
+        name,
+        description: String::new(),
+        unit: String::new(),
+        data: Some(data),
+    }
+}
+
+/// Removes a field from the log event if `convert` accepts its value.
+fn take_field<T>(
+    log: &mut LogEvent,
+    field: &str,
+    convert: impl FnOnce(&Value) -> Option<T>,
+) -> Option<T> {
+    let converted = log.get(field).and_then(convert)?;
+    log.remove(field);
+    Some(converted)
+}
+
+/// Decodes a trace or span ID from its hexadecimal representation.
+fn decode_id(value: &Value, len: usize) -> Option<Vec<u8>> {
+    let hex = match value {
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?,
+        _ => return None,
+    };
+    if hex.len() != len * 2 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn unix_nanos(timestamp: DateTime<Utc>) -> u64 {
+    timestamp.timestamp_nanos().max(0) as u64
+}
+
+fn string_key_value(key: impl Into<String>, value: String) -> proto::KeyValue {
+    proto::KeyValue {
+        key: key.into(),
+        value: Some(proto::AnyValue {
+            value: Some(proto::any_value::Value::StringValue(value)),
+        }),
+    }
+}
+
+fn key_value((key, value): (String, Value)) -> proto::KeyValue {
+    proto::KeyValue {
+        key,
+        value: Some(any_value(value)),
+    }
+}
+
+fn any_value(value: Value) -> proto::AnyValue {
+    use proto::any_value::Value as AnyValue;
+
+    let value = match value {
+        Value::Bytes(bytes) => Some(match String::from_utf8(bytes.to_vec()) {
+            Ok(string) => AnyValue::StringValue(string),
+            Err(error) => AnyValue::BytesValue(error.into_bytes()),
+        }),
+        Value::Integer(integer) => Some(AnyValue::IntValue(integer)),
+        Value::Float(float) => Some(AnyValue::DoubleValue(float)),
+        Value::Boolean(boolean) => Some(AnyValue::BoolValue(boolean)),
+        Value::Timestamp(timestamp) => Some(AnyValue::StringValue(
+            timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        )),
+        Value::Map(map) => Some(AnyValue::KvlistValue(proto::KeyValueList {
+            values: map.into_iter().map(key_value).collect(),
+        })),
+        Value::Array(array) => Some(AnyValue::ArrayValue(proto::ArrayValue {
+            values: array.into_iter().map(any_value).collect(),
+        })),
+        Value::Null => None,
+    };
+
+    proto::AnyValue { value }
+}
+
+/// Builds an export request out of a batch of records of the same kind,
+/// grouping them by resource.
+pub fn build_request(records: Vec<OtlpRecord>, signal: Signal) -> OtlpRequest {
+    let mut groups: Vec<(proto::Resource, Vec<Record>)> = Vec::new();
+    for OtlpRecord { resource, record } in records {
+        match groups.iter_mut().find(|(other, _)| *other == resource) {
+            Some((_, records)) => records.push(record),
+            None => groups.push((resource, vec![record])),
+        }
+    }
+
+    let scope = proto::InstrumentationScope {
+        name: "vector".to_owned(),
+        version: crate::get_version(),
+    };
+
+    match signal {
+        Signal::Logs => OtlpRequest::Logs(proto::ExportLogsServiceRequest {
+            resource_logs: groups
+                .into_iter()
+                .map(|(resource, records)| proto::ResourceLogs {
+                    resource: Some(resource),
+                    scope_logs: vec![proto::ScopeLogs {
+                        scope: Some(scope.clone()),
+                        log_records: records
+                            .into_iter()
+                            .filter_map(|record| match record {
+                                Record::Log(log) => Some(log),
+                                Record::Metric(_) => None,
+                            })
+                            .collect(),
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                })
+                .collect(),
+        }),
+        Signal::Metrics => OtlpRequest::Metrics(proto::ExportMetricsServiceRequest {
+            resource_metrics: groups
+                .into_iter()
+                .map(|(resource, records)| proto::ResourceMetrics {
+                    resource: Some(resource),
+                    scope_metrics: vec![proto::ScopeMetrics {
+                        scope: Some(scope.clone()),
+                        metrics: records
+                            .into_iter()
+                            .filter_map(|record| match record {
+                                Record::Metric(metric) => Some(metric),
+                                Record::Log(_) => None,
+                            })
+                            .collect(),
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                })
+                .collect(),
+        }),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum OtlpRequest {
+    Logs(proto::ExportLogsServiceRequest),
+    Metrics(proto::ExportMetricsServiceRequest),
+}
+
+impl OtlpRequest {
+    pub fn encode_to_vec(&self) -> Vec<u8> {
+        match self {
+            OtlpRequest::Logs(request) => request.encode_to_vec(),
+            OtlpRequest::Metrics(request) => request.encode_to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{Bucket, Quantile};
+    use chrono::TimeZone;
+    use std::convert::TryFrom;
+
+    fn any_string(value: &str) -> Option<proto::AnyValue> {
+        Some(proto::AnyValue {
+            value: Some(proto::any_value::Value::StringValue(value.to_owned())),
+        })
+    }
+
+    #[test]
+    fn encodes_logs() {
+        let timestamp = Utc.ymd(2021, 11, 2).and_hms(10, 0, 0);
+        let mut log = LogEvent::from("hello world");
+        log.insert(log_schema().timestamp_key(), timestamp);
+        log.insert("severity_text", "WARN");
+        log.insert("severity_number", 13);
+        log.insert("trace_id", "5b8efff798038103d269b633813fc60c");
+        log.insert("span_id", "not an id");
+        log.insert("http.status", 404);
+
+        let record = encode_log(log);
+
+        assert_eq!(record.time_unix_nano, 1_635_847_200_000_000_000);
+        assert_eq!(record.body, any_string("hello world"));
+        assert_eq!(record.severity_text, "WARN");
+        assert_eq!(record.severity_number, proto::SeverityNumber::Warn as i32);
+        assert_eq!(record.trace_id.len(), 16);
+        assert_eq!(record.trace_id[0], 0x5b);
+        assert!(record.span_id.is_empty());
+        assert_eq!(
+            record.attributes,
+            vec![
+                proto::KeyValue {
+                    key: "http".to_owned(),
+                    value: Some(proto::AnyValue {
+                        value: Some(proto::any_value::Value::KvlistValue(proto::KeyValueList {
+                            values: vec![proto::KeyValue {
+                                key: "status".to_owned(),
+                                value: Some(proto::AnyValue {
+                                    value: Some(proto::any_value::Value::IntValue(404)),
+                                }),
+                            }],
+                        })),
+                    }),
+                },
+                proto::KeyValue {
+                    key: "span_id".to_owned(),
+                    value: any_string("not an id"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_metrics() {
+        use proto::metric::Data;
+
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 3.0 },
+        )
+        .with_namespace(Some("nginx"))
+        .with_tags(Some(
+            vec![("host".to_owned(), "a".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
+        let encoded = encode_metric(&metric);
+        assert_eq!(encoded.name, "nginx.requests");
+        match encoded.data {
+            Some(Data::Sum(sum)) => {
+                assert!(sum.is_monotonic);
+                assert_eq!(
+                    sum.aggregation_temporality,
+                    proto::AggregationTemporality::Delta as i32
+                );
+                assert_eq!(sum.data_points[0].attributes[0].value, any_string("a"));
+            }
+            data => panic!("unexpected data: {:?}", data),
+        }
+
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2,
+                    },
+                    Bucket {
+                        upper_limit: 2.0,
+                        count: 3,
+                    },
+                ],
+                count: 6,
+                sum: 8.5,
+            },
+        );
+        match encode_metric(&metric).data {
+            Some(Data::Histogram(histogram)) => {
+                let point = &histogram.data_points[0];
+                assert_eq!(point.explicit_bounds, vec![1.0, 2.0]);
+                assert_eq!(point.bucket_counts, vec![2, 3, 1]);
+                assert_eq!(point.count, 6);
+            }
+            data => panic!("unexpected data: {:?}", data),
+        }
+
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::AggregatedSummary {
+                quantiles: vec![Quantile {
+                    upper_limit: 0.5,
+                    value: 1.5,
+                }],
+                count: 6,
+                sum: 8.5,
+            },
+        );
+        match encode_metric(&metric).data {
+            Some(Data::Summary(summary)) => {
+                let quantile = &summary.data_points[0].quantile_values[0];
+                assert_eq!((quantile.quantile, quantile.value), (0.5, 1.5));
+            }
+            data => panic!("unexpected data: {:?}", data),
+        }
+    }
+
+    #[test]
+    fn groups_records_by_resource() {
+        let encoder = OtlpEncoder {
+            resource_attributes: vec![(
+                "service.name".to_owned(),
+                Template::try_from("{{ service }}").unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+            remove_resource_fields: true,
+        };
+
+        let records = ["api", "web", "api"]
+            .iter()
+            .map(|service| {
+                let mut log = LogEvent::from("message");
+                log.insert("service", *service);
+                let (record, signal) = encoder.encode_event(log.into()).item.into_parts();
+                assert_eq!(signal, Signal::Logs);
+                record
+            })
+            .collect::<Vec<_>>();
+
+        match build_request(records, Signal::Logs) {
+            OtlpRequest::Logs(request) => {
+                assert_eq!(request.resource_logs.len(), 2);
+                let api = &request.resource_logs[0];
+                assert_eq!(
+                    api.resource.as_ref().unwrap().attributes[0].value,
+                    any_string("api")
+                );
+                let records = &api.scope_logs[0].log_records;
+                assert_eq!(records.len(), 2);
+                assert!(records[0].attributes.is_empty());
+            }
+            request => panic!("unexpected request: {:?}", request),
+        }
+    }
+}
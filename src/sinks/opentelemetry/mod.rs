@@ -0,0 +1,317 @@
+mod encode;
+mod service;
+
+use self::{
+    encode::{OtlpEncoder, Signal},
+    service::{OtlpError, OtlpRetryLogic, OtlpService},
+};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    http::HttpClient,
+    proto::opentelemetry as proto,
+    sinks::util::{
+        buffer::PartitionBuffer,
+        grpc::{new_client, with_default_scheme, HyperSvc},
+        http::RequestConfig,
+        BatchConfig, BatchSettings, Compression, PartitionBatchSink, PartitionInnerBuffer,
+        ServiceBuilderExt, TowerRequestConfig, VecBuffer,
+    },
+    sinks::{Healthcheck, HealthcheckError, VectorSink},
+    template::Template,
+    tls::{MaybeTlsSettings, TlsConfig},
+};
+use futures::{stream, FutureExt, SinkExt, StreamExt};
+use http::{
+    header::{HeaderName, HeaderValue},
+    Uri,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tower::{ServiceBuilder, ServiceExt};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid header {:?}.", name))]
+    InvalidHeader { name: String },
+    #[snafu(display("Invalid endpoint: {}", source))]
+    InvalidEndpoint { source: http::uri::InvalidUri },
+    #[snafu(display("The gRPC protocol only supports gzip compression."))]
+    UnsupportedCompression,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpenTelemetryConfig {
+    endpoint: String,
+    #[serde(default)]
+    protocol: Protocol,
+    #[serde(default)]
+    compression: Compression,
+    /// Resource attributes rendered from each event, grouping the records
+    /// sharing the same resource.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    resource_attributes: IndexMap<String, Template>,
+    #[serde(default)]
+    remove_resource_fields: bool,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: RequestConfig,
+    tls: Option<TlsConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    #[derivative(Default)]
+    Grpc,
+    Http,
+}
+
+inventory::submit! {
+    SinkDescription::new::<OpenTelemetryConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpenTelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            resource_attributes."service.name" = "{{ service }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SinkConfig for OpenTelemetryConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let service = self.build_service(&cx)?;
+        let healthcheck = healthcheck(service.clone()).boxed();
+
+        let request = self
+            .request
+            .tower
+            .unwrap_with(&TowerRequestConfig::default());
+        // gRPC servers reject messages over 4MiB by default.
+        let batch = BatchSettings::default()
+            .bytes(4_000_000)
+            .events(1000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+
+        let svc = ServiceBuilder::new()
+            .settings(request, OtlpRetryLogic)
+            .service(service);
+
+        let encoder = OtlpEncoder {
+            resource_attributes: self.resource_attributes.clone(),
+            remove_resource_fields: self.remove_resource_fields,
+        };
+        let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .sink_map_err(|error| error!(message = "Fatal OpenTelemetry sink error.", %error))
+            .with_flat_map(move |event| stream::iter(Some(encoder.encode_event(event))).map(Ok));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+}
+
+impl OpenTelemetryConfig {
+    fn build_service(&self, cx: &SinkContext) -> crate::Result<OtlpService> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+
+        Ok(match self.protocol {
+            Protocol::Grpc => {
+                let gzip = match self.compression {
+                    Compression::None => false,
+                    Compression::Gzip(_) => true,
+                    Compression::Zstd { .. } => {
+                        return Err(BuildError::UnsupportedCompression.into())
+                    }
+                };
+
+                let uri = with_default_scheme(&self.endpoint, tls.is_tls())?;
+                let svc = HyperSvc::new(uri, new_client(&tls)?);
+                let mut logs = proto::LogsServiceClient::new(svc.clone());
+                let mut metrics = proto::MetricsServiceClient::new(svc);
+                if gzip {
+                    logs = logs.send_gzip();
+                    metrics = metrics.send_gzip();
+                }
+
+                OtlpService::Grpc {
+                    logs,
+                    metrics,
+                    metadata: self.grpc_metadata()?,
+                }
+            }
+            Protocol::Http => OtlpService::Http {
+                client: HttpClient::new(tls, cx.proxy())?,
+                logs_uri: self.http_uri(Signal::Logs)?,
+                metrics_uri: self.http_uri(Signal::Metrics)?,
+                compression: self.compression,
+                headers: self.http_headers()?,
+            },
+        })
+    }
+
+    fn http_uri(&self, signal: Signal) -> crate::Result<Uri> {
+        let uri = format!(
+            "{}{}",
+            self.endpoint.trim_end_matches('/'),
+            signal.http_path()
+        );
+        Ok(uri.parse::<Uri>().context(InvalidEndpoint)?)
+    }
+
+    fn http_headers(&self) -> crate::Result<Vec<(HeaderName, HeaderValue)>> {
+        self.request
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                match (
+                    HeaderName::from_bytes(name.as_bytes()),
+                    HeaderValue::from_str(value),
+                ) {
+                    (Ok(name), Ok(value)) => Ok((name, value)),
+                    _ => Err(BuildError::InvalidHeader { name: name.clone() }.into()),
+                }
+            })
+            .collect()
+    }
+
+    fn grpc_metadata(&self) -> crate::Result<MetadataMap> {
+        let mut metadata = MetadataMap::new();
+        for (name, value) in &self.request.headers {
+            // gRPC metadata keys are lowercase.
+            match (
+                MetadataKey::from_bytes(name.to_lowercase().as_bytes()),
+                MetadataValue::from_str(value),
+            ) {
+                (Ok(key), Ok(value)) => {
+                    metadata.insert(key, value);
+                }
+                _ => return Err(BuildError::InvalidHeader { name: name.clone() }.into()),
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// OTLP doesn't define a health check, so an empty batch of logs is exported
+/// instead.
+async fn healthcheck(service: OtlpService) -> crate::Result<()> {
+    let batch = PartitionInnerBuffer::new(Vec::new(), Signal::Logs);
+    match service.oneshot(batch).await {
+        Ok(()) => Ok(()),
+        Err(OtlpError::UnexpectedStatus { status, .. }) => {
+            Err(HealthcheckError::UnexpectedStatus { status }.into())
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{Event, Metric, MetricKind, MetricValue},
+        sinks::util::test::build_test_server_generic,
+        test_util::next_addr,
+    };
+    use futures::channel::mpsc;
+    use prost::Message;
+    use vector_core::event::{BatchNotifier, BatchStatus};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpenTelemetryConfig>();
+    }
+
+    #[test]
+    fn rejects_zstd_with_grpc() {
+        let config: OpenTelemetryConfig = toml::from_str(
+            r#"endpoint = "http://localhost:4317"
+            compression = "zstd""#,
+        )
+        .unwrap();
+
+        assert!(config.build_service(&SinkContext::new_test()).is_err());
+    }
+
+    #[tokio::test]
+    async fn exports_over_http() {
+        let address = next_addr();
+        let config: OpenTelemetryConfig = toml::from_str(&format!(
+            r#"endpoint = "http://{}"
+            protocol = "http"
+            resource_attributes."service.name" = "{{{{ service }}}}"
+            remove_resource_fields = true
+            request.headers.x-api-key = "secret""#,
+            address
+        ))
+        .unwrap();
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) =
+            build_test_server_generic(address, || hyper::Response::new(hyper::Body::empty()));
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let mut log = Event::from("hello world");
+        log.as_mut_log().insert("service", "api");
+        let metric = Event::from(Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        ));
+        let events = vec![log.with_batch_notifier(&batch), metric];
+        drop(batch);
+
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let mut requests = received(rx).await;
+        requests.sort_by(|(a, _), (b, _)| a.uri.path().cmp(b.uri.path()));
+        assert_eq!(requests.len(), 2);
+
+        let (parts, body) = &requests[0];
+        assert_eq!(parts.uri.path(), "/v1/logs");
+        assert_eq!(parts.headers["content-type"], "application/x-protobuf");
+        assert_eq!(parts.headers["x-api-key"], "secret");
+        let request = proto::ExportLogsServiceRequest::decode(body.clone()).unwrap();
+        let resource_logs = &request.resource_logs[0];
+        assert_eq!(
+            resource_logs.resource.as_ref().unwrap().attributes[0].key,
+            "service.name"
+        );
+        let record = &resource_logs.scope_logs[0].log_records[0];
+        assert!(record.attributes.is_empty());
+
+        let (parts, body) = &requests[1];
+        assert_eq!(parts.uri.path(), "/v1/metrics");
+        let request = proto::ExportMetricsServiceRequest::decode(body.clone()).unwrap();
+        let metric = &request.resource_metrics[0].scope_metrics[0].metrics[0];
+        assert_eq!(metric.name, "requests");
+    }
+
+    async fn received(
+        rx: mpsc::Receiver<(http::request::Parts, bytes::Bytes)>,
+    ) -> Vec<(http::request::Parts, bytes::Bytes)> {
+        rx.collect().await
+    }
+}
@@ -0,0 +1,215 @@
+use super::encode::{build_request, OtlpRecord, OtlpRequest, Signal};
+use crate::{
+    http::{HttpClient, HttpError},
+    internal_events::OpenTelemetryRecordsRejected,
+    proto::opentelemetry as proto,
+    sinks::util::{
+        grpc::HyperSvc, retries::RetryLogic, Compression, Compressor, PartitionInnerBuffer,
+    },
+};
+use futures::future::BoxFuture;
+use http::{
+    header::{HeaderName, HeaderValue, CONTENT_ENCODING, CONTENT_TYPE},
+    StatusCode, Uri,
+};
+use hyper::Body;
+use prost::Message;
+use snafu::{ResultExt, Snafu};
+use std::{
+    io::Write,
+    task::{Context, Poll},
+};
+use tonic::metadata::MetadataMap;
+
+#[derive(Debug, Snafu)]
+pub enum OtlpError {
+    #[snafu(display("Request failed: {}", source))]
+    Grpc { source: tonic::Status },
+    #[snafu(display("Request failed: {}", source))]
+    Http { source: HttpError },
+    #[snafu(display("Failed to read the response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Endpoint responded with status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+}
+
+/// Exports batches of records, over gRPC or HTTP.
+#[derive(Clone)]
+pub enum OtlpService {
+    Grpc {
+        logs: proto::LogsServiceClient<HyperSvc>,
+        metrics: proto::MetricsServiceClient<HyperSvc>,
+        metadata: MetadataMap,
+    },
+    Http {
+        client: HttpClient,
+        logs_uri: Uri,
+        metrics_uri: Uri,
+        compression: Compression,
+        headers: Vec<(HeaderName, HeaderValue)>,
+    },
+}
+
+impl tower::Service<PartitionInnerBuffer<Vec<OtlpRecord>, Signal>> for OtlpService {
+    type Response = ();
+    type Error = OtlpError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Both clients wait to be ready when sending the request.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, batch: PartitionInnerBuffer<Vec<OtlpRecord>, Signal>) -> Self::Future {
+        let (records, signal) = batch.into_parts();
+        let request = build_request(records, signal);
+        let service = self.clone();
+
+        Box::pin(async move {
+            let rejected = match service {
+                OtlpService::Grpc {
+                    logs,
+                    metrics,
+                    metadata,
+                } => export_grpc(logs, metrics, metadata, request).await?,
+                OtlpService::Http {
+                    client,
+                    logs_uri,
+                    metrics_uri,
+                    compression,
+                    headers,
+                } => {
+                    let uri = match signal {
+                        Signal::Logs => logs_uri,
+                        Signal::Metrics => metrics_uri,
+                    };
+                    export_http(client, uri, compression, headers, request).await?
+                }
+            };
+
+            // The endpoint may accept the request but still drop some of its
+            // records, which isn't worth retrying.
+            if let Some((count, reason)) = rejected.filter(|(count, _)| *count > 0) {
+                emit!(&OpenTelemetryRecordsRejected {
+                    count,
+                    reason: &reason,
+                });
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// The number of records rejected by the endpoint, and why.
+type Rejected = Option<(i64, String)>;
+
+async fn export_grpc(
+    mut logs: proto::LogsServiceClient<HyperSvc>,
+    mut metrics: proto::MetricsServiceClient<HyperSvc>,
+    metadata: MetadataMap,
+    request: OtlpRequest,
+) -> Result<Rejected, OtlpError> {
+    Ok(match request {
+        OtlpRequest::Logs(request) => {
+            let mut request = tonic::Request::new(request);
+            *request.metadata_mut() = metadata;
+            let response = logs.export(request).await.context(Grpc)?.into_inner();
+            response
+                .partial_success
+                .map(|partial| (partial.rejected_log_records, partial.error_message))
+        }
+        OtlpRequest::Metrics(request) => {
+            let mut request = tonic::Request::new(request);
+            *request.metadata_mut() = metadata;
+            let response = metrics.export(request).await.context(Grpc)?.into_inner();
+            response
+                .partial_success
+                .map(|partial| (partial.rejected_data_points, partial.error_message))
+        }
+    })
+}
+
+async fn export_http(
+    client: HttpClient,
+    uri: Uri,
+    compression: Compression,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    request: OtlpRequest,
+) -> Result<Rejected, OtlpError> {
+    let mut body = Compressor::from(compression);
+    body.write_all(&request.encode_to_vec())
+        .expect("Writing to a Vec can't fail.");
+
+    let mut builder = http::Request::post(uri).header(CONTENT_TYPE, "application/x-protobuf");
+    if let Some(encoding) = compression.content_encoding() {
+        builder = builder.header(CONTENT_ENCODING, encoding);
+    }
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    let http_request = builder
+        .body(Body::from(body.into_inner()))
+        .expect("Request parts are valid.");
+
+    let response = client.send(http_request).await.context(Http)?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .context(ReadResponse)?;
+
+    if !status.is_success() {
+        return Err(OtlpError::UnexpectedStatus {
+            status,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        });
+    }
+
+    // Endpoints may respond with an empty body instead of an empty message.
+    Ok(match request {
+        OtlpRequest::Logs(_) => proto::ExportLogsServiceResponse::decode(body)
+            .ok()
+            .and_then(|response| response.partial_success)
+            .map(|partial| (partial.rejected_log_records, partial.error_message)),
+        OtlpRequest::Metrics(_) => proto::ExportMetricsServiceResponse::decode(body)
+            .ok()
+            .and_then(|response| response.partial_success)
+            .map(|partial| (partial.rejected_data_points, partial.error_message)),
+    })
+}
+
+/// Retries the failures the OTLP specification considers transient.
+///
+/// See: <https://opentelemetry.io/docs/specs/otlp/#failures>
+#[derive(Debug, Clone)]
+pub struct OtlpRetryLogic;
+
+impl RetryLogic for OtlpRetryLogic {
+    type Error = OtlpError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        match error {
+            OtlpError::Grpc { source } => matches!(
+                source.code(),
+                Cancelled
+                    | DeadlineExceeded
+                    | ResourceExhausted
+                    | Aborted
+                    | OutOfRange
+                    | Unavailable
+                    | DataLoss
+            ),
+            OtlpError::UnexpectedStatus { status, .. } => matches!(
+                *status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            OtlpError::Http { .. } | OtlpError::ReadResponse { .. } => true,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    event::metric::{Metric, MetricKind, MetricValue, Sample, StatisticKind},
     sinks::util::{encode_namespace, statistic::DistributionStatistic},
 };
 use chrono::Utc;
@@ -239,15 +239,26 @@ impl StringCollector {
     ) {
         match (tags, extra) {
             (None, None) => Ok(()),
-            (None, Some(tag)) => write!(result, "{{{}=\"{}\"}}", tag.0, tag.1),
+            (None, Some(tag)) => write!(
+                result,
+                "{{{}=\"{}\"}}",
+                tag.0,
+                Self::escape_label_value(&tag.1)
+            ),
             (Some(tags), ref tag) => {
                 let mut parts = tags
                     .iter()
-                    .map(|(name, value)| format!("{}=\"{}\"", name, value))
+                    .map(|(name, value)| {
+                        format!("{}=\"{}\"", name, Self::escape_label_value(value))
+                    })
                     .collect::<Vec<_>>();
 
                 if let Some(tag) = tag {
-                    parts.push(format!("{}=\"{}\"", tag.0, tag.1));
+                    parts.push(format!(
+                        "{}=\"{}\"",
+                        tag.0,
+                        Self::escape_label_value(&tag.1)
+                    ));
                 }
 
                 parts.sort();
@@ -257,6 +268,21 @@ impl StringCollector {
         .ok();
     }
 
+    /// Label values may hold any UTF-8, but backslashes, double quotes and
+    /// line feeds must be escaped in the text format.
+    fn escape_label_value(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '\\' => escaped.push_str(r"\\"),
+                '"' => escaped.push_str(r#"\""#),
+                '\n' => escaped.push_str(r"\n"),
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
     fn encode_header(name: &str, fullname: &str, value: &MetricValue) -> String {
         let r#type = prometheus_metric_type(value).as_str();
         format!(
@@ -268,8 +294,19 @@ impl StringCollector {
 
 type Labels = Vec<proto::Label>;
 
+/// The breadth of the zero bucket of native histograms, as used by the
+/// Prometheus client libraries.
+const NATIVE_HISTOGRAM_ZERO_THRESHOLD: f64 = 2.938735877055719e-39; // 2^-128
+
+#[derive(Default)]
+struct Series {
+    samples: Vec<proto::Sample>,
+    exemplars: Vec<proto::Exemplar>,
+    histograms: Vec<proto::Histogram>,
+}
+
 pub(super) struct TimeSeries {
-    buffer: IndexMap<Labels, Vec<proto::Sample>>,
+    buffer: IndexMap<Labels, Series>,
     metadata: IndexMap<String, proto::MetricMetadata>,
     timestamp: Option<i64>,
 }
@@ -306,6 +343,153 @@ impl TimeSeries {
             .timestamp
             .get_or_insert_with(|| Utc::now().timestamp_millis())
     }
+
+    /// Encodes histogram distributions as native histograms, whose buckets
+    /// are exponential with a resolution set by `schema`, returning whether
+    /// the metric was one.
+    pub(super) fn encode_native_histogram(
+        &mut self,
+        default_namespace: Option<&str>,
+        schema: i32,
+        metric: &Metric,
+    ) -> bool {
+        let samples = match metric.value() {
+            MetricValue::Distribution {
+                samples,
+                statistic: StatisticKind::Histogram,
+            } if metric.kind() == MetricKind::Absolute => samples,
+            _ => return false,
+        };
+
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        self.emit_metadata(metric.name(), &name, metric.value());
+        let timestamp = match metric.timestamp() {
+            Some(timestamp) => timestamp.timestamp_millis(),
+            None => self.default_timestamp(),
+        };
+        self.buffer
+            .entry(Self::make_labels(metric.tags(), &name, "", None))
+            .or_default()
+            .histograms
+            .push(native_histogram(samples, schema, timestamp));
+        true
+    }
+
+    /// Attaches an exemplar to the series a metric was encoded into, which
+    /// for classic histograms is the bucket holding the exemplar value.
+    pub(super) fn encode_exemplar(
+        &mut self,
+        default_namespace: Option<&str>,
+        buckets: &[f64],
+        native: bool,
+        metric: &Metric,
+        exemplar: proto::Exemplar,
+    ) {
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let le = match metric.value() {
+            MetricValue::Distribution {
+                statistic: StatisticKind::Histogram,
+                ..
+            } if !native => Some(bucket_le(buckets.iter().copied(), exemplar.value)),
+            MetricValue::AggregatedHistogram { buckets, .. } => Some(bucket_le(
+                buckets.iter().map(|bucket| bucket.upper_limit),
+                exemplar.value,
+            )),
+            _ => None,
+        };
+        let labels = match le {
+            Some(le) => Self::make_labels(metric.tags(), &name, "_bucket", Some(("le", le))),
+            None => Self::make_labels(metric.tags(), &name, "", None),
+        };
+
+        if let Some(series) = self.buffer.get_mut(&labels) {
+            series.exemplars.push(exemplar);
+        }
+    }
+}
+
+/// The `le` label of the lowest bucket holding `value`.
+fn bucket_le(mut upper_limits: impl Iterator<Item = f64>, value: f64) -> String {
+    upper_limits
+        .find(|upper_limit| value <= *upper_limit)
+        .map_or_else(|| "+Inf".to_string(), |upper_limit| upper_limit.to_string())
+}
+
+fn native_histogram(samples: &[Sample], schema: i32, timestamp: i64) -> proto::Histogram {
+    let mut positive = BTreeMap::new();
+    let mut negative = BTreeMap::new();
+    let mut count = 0;
+    let mut zero_count = 0;
+    let mut sum = 0.0;
+    for sample in samples.iter().filter(|sample| !sample.value.is_nan()) {
+        let rate = u64::from(sample.rate);
+        count += rate;
+        sum += sample.value * rate as f64;
+
+        if sample.value.abs() <= NATIVE_HISTOGRAM_ZERO_THRESHOLD {
+            zero_count += rate;
+        } else {
+            let buckets = if sample.value > 0.0 {
+                &mut positive
+            } else {
+                &mut negative
+            };
+            *buckets
+                .entry(native_bucket_index(sample.value.abs(), schema))
+                .or_insert(0) += rate;
+        }
+    }
+
+    let (positive_spans, positive_deltas) = native_buckets(&positive);
+    let (negative_spans, negative_deltas) = native_buckets(&negative);
+    proto::Histogram {
+        count: Some(proto::histogram::Count::CountInt(count)),
+        sum,
+        schema,
+        zero_threshold: NATIVE_HISTOGRAM_ZERO_THRESHOLD,
+        zero_count: Some(proto::histogram::ZeroCount::ZeroCountInt(zero_count)),
+        negative_spans,
+        negative_deltas,
+        positive_spans,
+        positive_deltas,
+        timestamp,
+        ..Default::default()
+    }
+}
+
+/// Bucket `i` holds the values in `(base^(i-1), base^i]`, where the base is
+/// `2^(2^-schema)`.
+fn native_bucket_index(value: f64, schema: i32) -> i32 {
+    (value.log2() * 2f64.powi(schema)).ceil() as i32
+}
+
+/// Encodes the buckets as spans of consecutive indexes, and the count of each
+/// bucket as its difference with the previous one.
+fn native_buckets(buckets: &BTreeMap<i32, u64>) -> (Vec<proto::BucketSpan>, Vec<i64>) {
+    let mut spans: Vec<proto::BucketSpan> = Vec::new();
+    let mut deltas = Vec::with_capacity(buckets.len());
+    let mut previous: Option<(i32, i64)> = None;
+    for (&index, &count) in buckets {
+        let count = count as i64;
+        match previous {
+            Some((previous_index, _)) if index == previous_index + 1 => {
+                if let Some(span) = spans.last_mut() {
+                    span.length += 1;
+                }
+            }
+            Some((previous_index, _)) => spans.push(proto::BucketSpan {
+                offset: index - previous_index - 1,
+                length: 1,
+            }),
+            None => spans.push(proto::BucketSpan {
+                offset: index,
+                length: 1,
+            }),
+        }
+        deltas.push(count - previous.map_or(0, |(_, count)| count));
+        previous = Some((index, count));
+    }
+    (spans, deltas)
 }
 
 impl MetricCollector for TimeSeries {
@@ -345,6 +529,7 @@ impl MetricCollector for TimeSeries {
         self.buffer
             .entry(Self::make_labels(tags, name, suffix, extra))
             .or_default()
+            .samples
             .push(proto::Sample { value, timestamp });
     }
 
@@ -352,7 +537,12 @@ impl MetricCollector for TimeSeries {
         let timeseries = self
             .buffer
             .into_iter()
-            .map(|(labels, samples)| proto::TimeSeries { labels, samples })
+            .map(|(labels, series)| proto::TimeSeries {
+                labels,
+                samples: series.samples,
+                exemplars: series.exemplars,
+                histograms: series.histograms,
+            })
             .collect::<Vec<_>>();
         let metadata = self
             .metadata
@@ -438,6 +628,7 @@ mod tests {
                                 value: $svalue,
                                 timestamp: $timestamp,
                             }],
+                            ..Default::default()
                         },
                     )*
                 ],
@@ -482,6 +673,28 @@ mod tests {
         encode_one::<T>(Some("vector"), &[], &[], false, &metric)
     }
 
+    #[test]
+    fn encodes_escaped_labels_text() {
+        let metric = Metric::new(
+            "hits".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 10.0 },
+        )
+        .with_tags(Some(
+            vec![("path".to_owned(), "C:\\\"données\"\n".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
+        assert_eq!(
+            encode_one::<StringCollector>(None, &[], &[], false, &metric),
+            indoc! { r#"
+                # HELP hits hits
+                # TYPE hits counter
+                hits{path="C:\\\"données\"\n"} 10
+            "#}
+        );
+    }
+
     #[test]
     fn encodes_gauge_text() {
         assert_eq!(
@@ -822,6 +1035,112 @@ mod tests {
         assert!(encoded.timeseries[0].samples[0].timestamp >= now);
     }
 
+    #[test]
+    fn encodes_native_histogram_request() {
+        let metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Distribution {
+                samples: vector_core::samples![
+                    -1.0 => 1, 0.0 => 2, 1.0 => 1, 1.5 => 2, 3.0 => 1, 100.0 => 1
+                ],
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(timestamp()));
+
+        let mut series = TimeSeries::new();
+        assert!(series.encode_native_histogram(Some("ns"), 0, &metric));
+        let request = series.finish();
+
+        assert_eq!(request.metadata.len(), 1);
+        assert_eq!(
+            request.metadata[0].r#type,
+            proto::MetricType::Histogram as i32
+        );
+        assert_eq!(request.timeseries.len(), 1);
+        assert!(request.timeseries[0].samples.is_empty());
+        assert_eq!(
+            request.timeseries[0].histograms,
+            vec![proto::Histogram {
+                count: Some(proto::histogram::Count::CountInt(8)),
+                sum: 106.0,
+                schema: 0,
+                zero_threshold: NATIVE_HISTOGRAM_ZERO_THRESHOLD,
+                zero_count: Some(proto::histogram::ZeroCount::ZeroCountInt(2)),
+                negative_spans: vec![proto::BucketSpan {
+                    offset: 0,
+                    length: 1
+                }],
+                negative_deltas: vec![1],
+                positive_spans: vec![
+                    proto::BucketSpan {
+                        offset: 0,
+                        length: 3
+                    },
+                    proto::BucketSpan {
+                        offset: 4,
+                        length: 1
+                    }
+                ],
+                positive_deltas: vec![1, 1, -1, 0],
+                timestamp: 1612325106789,
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn computes_native_bucket_indexes() {
+        assert_eq!(native_bucket_index(1.0, 0), 0);
+        assert_eq!(native_bucket_index(2.0, 0), 1);
+        assert_eq!(native_bucket_index(2.5, 0), 2);
+        assert_eq!(native_bucket_index(0.5, 0), -1);
+        // Each power of two is divided into 8 buckets.
+        assert_eq!(native_bucket_index(2.0, 3), 8);
+        assert_eq!(native_bucket_index(2.1, 3), 9);
+    }
+
+    #[test]
+    fn attaches_exemplars_to_histogram_buckets() {
+        let exemplar = proto::Exemplar {
+            labels: vec![proto::Label {
+                name: "trace_id".into(),
+                value: "abc".into(),
+            }],
+            value: 2.0,
+            timestamp: 1612325106789,
+        };
+
+        let metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 1, 2.1 => 2, 3.0 => 3],
+                count: 6,
+                sum: 12.5,
+            },
+        )
+        .with_timestamp(Some(timestamp()));
+        let mut series = TimeSeries::new();
+        series.encode_metric(None, &[], &[], false, &metric);
+        series.encode_exemplar(None, &[], false, &metric, exemplar.clone());
+
+        let request = series.finish();
+        let with_exemplars = request
+            .timeseries
+            .iter()
+            .filter(|series| !series.exemplars.is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(with_exemplars.len(), 1);
+        assert!(with_exemplars[0].labels.contains(&proto::Label {
+            name: "le".into(),
+            value: "2.1".into(),
+        }));
+        assert_eq!(with_exemplars[0].exemplars, vec![exemplar]);
+    }
+
     fn timestamp() -> DateTime<Utc> {
         Utc.ymd(2021, 2, 3).and_hms_milli(4, 5, 6, 789)
     }
@@ -3,18 +3,19 @@ use crate::{
     config::{DataType, GenerateConfig, Resource, SinkConfig, SinkContext, SinkDescription},
     event::metric::{Metric, MetricData, MetricKind, MetricValue},
     event::Event,
+    http::Auth,
     internal_events::PrometheusServerRequestComplete,
     sinks::{
-        util::{statistic::validate_quantiles, StreamSink},
+        util::{statistic::validate_quantiles, MultiValueTagsConfig, StreamSink},
         Healthcheck, VectorSink,
     },
-    tls::{MaybeTlsSettings, TlsConfig},
+    tls::{MaybeTls, MaybeTlsSettings, TlsConfig},
 };
 use async_trait::async_trait;
 use chrono::Utc;
 use futures::{future, stream::BoxStream, FutureExt, StreamExt};
 use hyper::{
-    header::HeaderValue,
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE},
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
@@ -39,6 +40,12 @@ const MIN_FLUSH_PERIOD_SECS: u64 = 1;
 enum BuildError {
     #[snafu(display("Flush period for sets must be greater or equal to {} secs", min))]
     FlushPeriodTooShort { min: u64 },
+    #[snafu(display("Path {:?} must start with a slash", path))]
+    InvalidPath { path: String },
+    #[snafu(display("Invalid bearer token"))]
+    InvalidBearerToken,
+    #[snafu(display("Allowed client names require TLS to be enabled"))]
+    ClientNamesWithoutTls,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -48,13 +55,19 @@ pub struct PrometheusExporterConfig {
     pub default_namespace: Option<String>,
     #[serde(default = "default_address")]
     pub address: SocketAddr,
+    #[serde(default = "default_path")]
+    pub path: String,
+    pub auth: Option<Auth>,
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub allowed_client_names: Vec<String>,
     #[serde(default = "super::default_histogram_buckets")]
     pub buckets: Vec<f64>,
     #[serde(default = "super::default_summary_quantiles")]
     pub quantiles: Vec<f64>,
     #[serde(default = "default_flush_period_secs")]
     pub flush_period_secs: u64,
+    pub multi_value_tags: Option<MultiValueTagsConfig>,
 }
 
 impl std::default::Default for PrometheusExporterConfig {
@@ -62,10 +75,14 @@ impl std::default::Default for PrometheusExporterConfig {
         Self {
             default_namespace: None,
             address: default_address(),
+            path: default_path(),
+            auth: None,
             tls: None,
+            allowed_client_names: Vec::new(),
             buckets: super::default_histogram_buckets(),
             quantiles: super::default_summary_quantiles(),
             flush_period_secs: default_flush_period_secs(),
+            multi_value_tags: None,
         }
     }
 }
@@ -76,6 +93,10 @@ fn default_address() -> SocketAddr {
     SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9598)
 }
 
+fn default_path() -> String {
+    "/metrics".into()
+}
+
 const fn default_flush_period_secs() -> u64 {
     60
 }
@@ -104,7 +125,25 @@ impl SinkConfig for PrometheusExporterConfig {
             }));
         }
 
+        if !self.path.starts_with('/') {
+            return Err(Box::new(BuildError::InvalidPath {
+                path: self.path.clone(),
+            }));
+        }
+
+        if !self.allowed_client_names.is_empty()
+            && !self
+                .tls
+                .as_ref()
+                .and_then(|tls| tls.enabled)
+                .unwrap_or(false)
+        {
+            return Err(Box::new(BuildError::ClientNamesWithoutTls));
+        }
+
+        self.auth.as_ref().map(ScrapeAuth::new).transpose()?;
         validate_quantiles(&self.quantiles)?;
+        super::multi_value_tags(self.multi_value_tags.as_ref())?;
 
         let sink = PrometheusExporter::new(self.clone(), cx.acker());
         let healthcheck = future::ok(()).boxed();
@@ -165,8 +204,37 @@ struct ExpiringMetrics {
     last_flush_timestamp: i64,
 }
 
+/// The credentials scrapers must present, and the challenge returned to
+/// those which don't.
+#[derive(Clone)]
+struct ScrapeAuth {
+    authorization: HeaderValue,
+    challenge: HeaderValue,
+}
+
+impl ScrapeAuth {
+    fn new(auth: &Auth) -> Result<Self, BuildError> {
+        let mut headers = HeaderMap::new();
+        auth.apply_headers_map(&mut headers);
+        let authorization = headers
+            .remove(AUTHORIZATION)
+            .ok_or(BuildError::InvalidBearerToken)?;
+        let challenge = match auth {
+            Auth::Basic { .. } => HeaderValue::from_static(r#"Basic realm="vector""#),
+            Auth::Bearer { .. } => HeaderValue::from_static("Bearer"),
+        };
+
+        Ok(Self {
+            authorization,
+            challenge,
+        })
+    }
+}
+
 fn handle(
     req: Request<Body>,
+    path: &str,
+    auth: Option<&ScrapeAuth>,
     default_namespace: Option<&str>,
     buckets: &[f64],
     quantiles: &[f64],
@@ -175,8 +243,18 @@ fn handle(
 ) -> Response<Body> {
     let mut response = Response::new(Body::empty());
 
+    if let Some(auth) = auth {
+        if req.headers().get(AUTHORIZATION) != Some(&auth.authorization) {
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, auth.challenge.clone());
+            return response;
+        }
+    }
+
     match (req.method(), req.uri().path()) {
-        (&Method::GET, "/metrics") => {
+        (&Method::GET, request_path) if request_path == path => {
             let mut s = collector::StringCollector::new();
 
             for (MetricEntry(metric), _) in metrics {
@@ -217,6 +295,13 @@ impl PrometheusExporter {
         }
 
         let metrics = Arc::clone(&self.metrics);
+        let path = self.config.path.clone();
+        // Validated when building the sink.
+        let auth = self
+            .config
+            .auth
+            .as_ref()
+            .and_then(|auth| ScrapeAuth::new(auth).ok());
         let default_namespace = self.config.default_namespace.clone();
         let buckets = self.config.buckets.clone();
         let quantiles = self.config.quantiles.clone();
@@ -224,6 +309,8 @@ impl PrometheusExporter {
 
         let new_service = make_service_fn(move |_| {
             let metrics = Arc::clone(&metrics);
+            let path = path.clone();
+            let auth = auth.clone();
             let default_namespace = default_namespace.clone();
             let buckets = buckets.clone();
             let quantiles = quantiles.clone();
@@ -243,6 +330,8 @@ impl PrometheusExporter {
                     .in_scope(|| {
                         handle(
                             req,
+                            &path,
+                            auth.as_ref(),
                             default_namespace.as_deref(),
                             &buckets,
                             &quantiles,
@@ -263,11 +352,18 @@ impl PrometheusExporter {
         let (trigger, tripwire) = Tripwire::new();
 
         let tls = self.config.tls.clone();
+        let allowed_client_names = self.config.allowed_client_names.clone();
         let address = self.config.address;
 
         tokio::spawn(async move {
-            let tls = MaybeTlsSettings::from_config(&tls, true)
-                .map_err(|error| eprintln!("Server TLS error: {}", error))?;
+            let tls = match MaybeTlsSettings::from_config(&tls, true)
+                .map_err(|error| eprintln!("Server TLS error: {}", error))?
+            {
+                MaybeTls::Tls(tls) if !allowed_client_names.is_empty() => {
+                    MaybeTls::Tls(tls.with_allowed_peer_names(allowed_client_names))
+                }
+                tls => tls,
+            };
             let listener = tls
                 .bind(&address)
                 .await
@@ -290,8 +386,10 @@ impl PrometheusExporter {
 impl StreamSink for PrometheusExporter {
     async fn run(mut self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         self.start_server_if_needed().await;
+        let multi_value_tags = super::multi_value_tags(self.config.multi_value_tags.as_ref())
+            .unwrap_or(MultiValueTagsConfig::Single);
         while let Some(event) = input.next().await {
-            let item = event.into_metric();
+            let item = multi_value_tags.collapse(event.into_metric());
             let mut metrics = self.metrics.write().unwrap();
 
             // sets need to be expired from time to time
@@ -488,6 +586,76 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn prometheus_auth_and_path() {
+        trace_init();
+
+        let address = next_addr();
+        let auth = Auth::Basic {
+            user: "user".into(),
+            password: "password".into(),
+        };
+        let config = PrometheusExporterConfig {
+            address,
+            path: "/custom".into(),
+            auth: Some(auth.clone()),
+            ..Default::default()
+        };
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (_tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(sink.run(Box::pin(UnboundedReceiverStream::new(rx))));
+        time::sleep(time::Duration::from_millis(100)).await;
+
+        let fetch = |path: &'static str, auth: Option<Auth>| async move {
+            let mut request = Request::get(format!("http://{}{}", address, path))
+                .body(Body::empty())
+                .expect("Error creating request.");
+            if let Some(auth) = auth {
+                auth.apply(&mut request);
+            }
+            HttpClient::new(None, &ProxyConfig::default())
+                .unwrap()
+                .send(request)
+                .await
+                .expect("Could not fetch query")
+        };
+
+        let response = fetch("/custom", None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[WWW_AUTHENTICATE],
+            r#"Basic realm="vector""#
+        );
+
+        let wrong = Auth::Basic {
+            user: "user".into(),
+            password: "wrong".into(),
+        };
+        let response = fetch("/custom", Some(wrong)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = fetch("/metrics", Some(auth.clone())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = fetch("/custom", Some(auth)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_scrape_settings() {
+        let config = PrometheusExporterConfig {
+            path: "metrics".into(),
+            ..Default::default()
+        };
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+
+        let config = PrometheusExporterConfig {
+            allowed_client_names: vec!["localhost".into()],
+            ..Default::default()
+        };
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
     async fn export_and_fetch(tls_config: Option<TlsConfig>, events: Vec<Event>) -> String {
         trace_init();
 
@@ -1,7 +1,18 @@
+use crate::sinks::util::MultiValueTagsConfig;
+use snafu::Snafu;
+
 mod collector;
 pub(crate) mod exporter;
 pub(crate) mod remote_write;
 
+#[derive(Debug, Snafu)]
+enum MultiValueTagsError {
+    #[snafu(display(
+        r#"Prometheus labels cannot be repeated, the "full" multi_value_tags strategy is not supported"#
+    ))]
+    RepeatedLabels,
+}
+
 fn default_histogram_buckets() -> Vec<f64> {
     vec![
         0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
@@ -11,3 +22,15 @@ fn default_histogram_buckets() -> Vec<f64> {
 fn default_summary_quantiles() -> Vec<f64> {
     vec![0.5, 0.75, 0.9, 0.95, 0.99]
 }
+
+/// Tags with more than one value keep their last value by default, as
+/// Prometheus labels can't be repeated.
+fn multi_value_tags(
+    config: Option<&MultiValueTagsConfig>,
+) -> Result<MultiValueTagsConfig, MultiValueTagsError> {
+    match config {
+        None => Ok(MultiValueTagsConfig::Single),
+        Some(MultiValueTagsConfig::Full) => Err(MultiValueTagsError::RepeatedLabels),
+        Some(config) => Ok(config.clone()),
+    }
+}
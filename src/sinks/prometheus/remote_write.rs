@@ -1,7 +1,10 @@
 use super::collector::{self, MetricCollector as _};
 use crate::{
     config::{self, SinkConfig, SinkDescription},
-    event::{Event, Metric},
+    event::{
+        metric::{MetricSeries, MetricValue, StatisticKind},
+        Event, Metric,
+    },
     http::{Auth, HttpClient},
     internal_events::TemplateRenderingFailed,
     sinks::{
@@ -10,20 +13,27 @@ use crate::{
             batch::{BatchConfig, BatchSettings},
             buffer::metrics::{MetricNormalize, MetricNormalizer, MetricSet, MetricsBuffer},
             http::HttpRetryLogic,
-            EncodedEvent, PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer,
-            TowerRequestConfig,
+            statistic::validate_quantile_error,
+            EncodedEvent, MultiValueTagsConfig, PartitionBatchSink, PartitionBuffer,
+            PartitionInnerBuffer, TowerRequestConfig,
         },
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::{Bytes, BytesMut};
+use chrono::Utc;
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt};
 use http::Uri;
+use prometheus_parser::proto;
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::task;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    task,
+};
 use tower::ServiceBuilder;
 use vector_core::ByteSizeOf;
 
@@ -31,9 +41,12 @@ use vector_core::ByteSizeOf;
 enum Errors {
     #[snafu(display(r#"Prometheus remote_write sink cannot accept "set" metrics"#))]
     SetMetricInvalid,
+    #[snafu(display("Native histogram schema must be between -4 and 8, got {}", schema))]
+    InvalidNativeHistogramSchema { schema: i32 },
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
 #[serde(deny_unknown_fields)]
 pub struct RemoteWriteConfig {
     pub endpoint: String,
@@ -44,6 +57,8 @@ pub struct RemoteWriteConfig {
     pub buckets: Vec<f64>,
     #[serde(default = "super::default_summary_quantiles")]
     pub quantiles: Vec<f64>,
+    #[serde(default)]
+    pub quantile_error: f64,
 
     #[serde(default)]
     pub batch: BatchConfig,
@@ -56,6 +71,33 @@ pub struct RemoteWriteConfig {
     pub tls: Option<TlsOptions>,
 
     pub auth: Option<Auth>,
+
+    pub multi_value_tags: Option<MultiValueTagsConfig>,
+
+    /// The tag holding the trace ID histograms are exemplified with.
+    pub exemplar_tag: Option<String>,
+
+    #[serde(default)]
+    pub histogram_encoding: HistogramEncoding,
+    #[serde(default = "default_native_histogram_schema")]
+    #[derivative(Default(value = "default_native_histogram_schema()"))]
+    pub native_histogram_schema: i32,
+}
+
+/// How histogram distributions are encoded.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramEncoding {
+    /// As cumulative `_bucket` series, with the upper limits set by `buckets`.
+    #[derivative(Default)]
+    Classic,
+    /// As native histograms, with exponential buckets.
+    Native,
+}
+
+const fn default_native_histogram_schema() -> i32 {
+    3
 }
 
 inventory::submit! {
@@ -72,6 +114,18 @@ impl SinkConfig for RemoteWriteConfig {
         cx: config::SinkContext,
     ) -> crate::Result<(sinks::VectorSink, sinks::Healthcheck)> {
         let endpoint = self.endpoint.parse::<Uri>().context(sinks::UriParseError)?;
+        let native_histogram_schema = match self.histogram_encoding {
+            HistogramEncoding::Classic => None,
+            HistogramEncoding::Native if (-4..=8).contains(&self.native_histogram_schema) => {
+                Some(self.native_histogram_schema)
+            }
+            HistogramEncoding::Native => {
+                return Err(Errors::InvalidNativeHistogramSchema {
+                    schema: self.native_histogram_schema,
+                }
+                .into())
+            }
+        };
         let tls_settings = TlsSettings::from_options(&self.tls)?;
         let batch = BatchSettings::default()
             .events(1_000)
@@ -79,11 +133,15 @@ impl SinkConfig for RemoteWriteConfig {
             .parse_config(self.batch)?;
         let request = self.request.unwrap_with(&TowerRequestConfig::default());
         let buckets = self.buckets.clone();
+        validate_quantile_error(self.quantile_error)?;
         let quantiles = self.quantiles.clone();
 
         let client = HttpClient::new(tls_settings, cx.proxy())?;
         let tenant_id = self.tenant_id.clone();
         let auth = self.auth.clone();
+        let multi_value_tags = super::multi_value_tags(self.multi_value_tags.as_ref())?;
+        let exemplar_tag = self.exemplar_tag.clone();
+        let exemplars = Exemplars::default();
 
         let healthcheck = healthcheck(endpoint.clone(), client.clone()).boxed();
         let service = RemoteWriteService {
@@ -93,17 +151,26 @@ impl SinkConfig for RemoteWriteConfig {
             buckets,
             quantiles,
             auth,
+            native_histogram_schema,
+            exemplars: exemplars.clone(),
         };
 
         let sink = {
             let service = request.service(HttpRetryLogic, service);
             let service = ServiceBuilder::new().service(service);
-            let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
+            let buffer = PartitionBuffer::new(
+                MetricsBuffer::new(batch.size).with_quantile_error(self.quantile_error),
+            );
             let mut normalizer = MetricNormalizer::<PrometheusMetricNormalize>::default();
 
             PartitionBatchSink::new(service, buffer, batch.timeout, cx.acker())
                 .with_flat_map(move |event: Event| {
                     let byte_size = event.size_of();
+                    let mut metric = multi_value_tags.collapse(event.into_metric());
+                    if let Some(tag) = &exemplar_tag {
+                        exemplars.extract(&mut metric, tag);
+                    }
+                    let event = Event::Metric(metric);
                     stream::iter(normalizer.apply(event).map(|event| {
                         let tenant_id = tenant_id.as_ref().and_then(|template| {
                             template
@@ -146,6 +213,58 @@ struct PartitionKey {
     tenant_id: Option<String>,
 }
 
+/// The latest exemplar of each histogram series. The tag holding trace IDs is
+/// taken off metrics before they are normalized and batched, so that each
+/// trace doesn't make a series of its own, and exemplars are attached back
+/// when their batch is encoded.
+#[derive(Clone, Default)]
+struct Exemplars(Arc<Mutex<HashMap<MetricSeries, proto::Exemplar>>>);
+
+impl Exemplars {
+    fn extract(&self, metric: &mut Metric, tag: &str) {
+        // Exemplars of distributions are their latest sample, while aggregated
+        // histograms only tell the mean of their samples.
+        let value = match metric.value() {
+            MetricValue::Distribution {
+                samples,
+                statistic: StatisticKind::Histogram,
+            } => samples.last().map(|sample| sample.value),
+            MetricValue::AggregatedHistogram { count, sum, .. } if *count > 0 => {
+                Some(sum / *count as f64)
+            }
+            _ => None,
+        };
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        let trace_id = match metric.remove_tag(tag) {
+            Some(trace_id) => trace_id,
+            None => return,
+        };
+
+        let exemplar = proto::Exemplar {
+            labels: vec![proto::Label {
+                name: tag.to_owned(),
+                value: trace_id,
+            }],
+            value,
+            timestamp: metric
+                .timestamp()
+                .unwrap_or_else(Utc::now)
+                .timestamp_millis(),
+        };
+        self.0
+            .lock()
+            .expect("poisoned lock")
+            .insert(metric.series().clone(), exemplar);
+    }
+
+    fn take(&self, series: &MetricSeries) -> Option<proto::Exemplar> {
+        self.0.lock().expect("poisoned lock").remove(series)
+    }
+}
+
 async fn healthcheck(endpoint: Uri, client: HttpClient) -> crate::Result<()> {
     let request = http::Request::get(endpoint)
         .body(hyper::Body::empty())
@@ -174,19 +293,36 @@ struct RemoteWriteService {
     buckets: Vec<f64>,
     quantiles: Vec<f64>,
     auth: Option<Auth>,
+    native_histogram_schema: Option<i32>,
+    exemplars: Exemplars,
 }
 
 impl RemoteWriteService {
     fn encode_events(&self, metrics: Vec<Metric>) -> Bytes {
         let mut time_series = collector::TimeSeries::new();
+        let default_namespace = self.default_namespace.as_deref();
         for metric in metrics {
-            time_series.encode_metric(
-                self.default_namespace.as_deref(),
-                &self.buckets,
-                &self.quantiles,
-                false,
-                &metric,
-            );
+            let native = self.native_histogram_schema.map_or(false, |schema| {
+                time_series.encode_native_histogram(default_namespace, schema, &metric)
+            });
+            if !native {
+                time_series.encode_metric(
+                    default_namespace,
+                    &self.buckets,
+                    &self.quantiles,
+                    false,
+                    &metric,
+                );
+            }
+            if let Some(exemplar) = self.exemplars.take(metric.series()) {
+                time_series.encode_exemplar(
+                    default_namespace,
+                    &self.buckets,
+                    native,
+                    &metric,
+                    exemplar,
+                );
+            }
         }
         let request = time_series.finish();
 
@@ -244,7 +380,7 @@ mod tests {
     use super::*;
     use crate::{
         config::SinkContext,
-        event::{MetricKind, MetricValue},
+        event::{MetricKind, MetricValue, StatisticKind},
         sinks::util::test::build_test_server,
         test_util,
     };
@@ -374,6 +510,69 @@ mod tests {
         check_output(2, "counter-1", 26.0);
     }
 
+    #[tokio::test]
+    async fn sends_native_histograms_with_exemplars() {
+        let event = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::Distribution {
+                samples: vector_core::samples![0.5 => 1, 2.0 => 3],
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_tags(Some(
+            vec![
+                ("region".to_owned(), "us-west-1".to_owned()),
+                ("trace_id".to_owned(), "4bf92f3577b34da6".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+        .into();
+
+        let outputs = send_request(
+            indoc! {r#"
+                exemplar_tag = "trace_id"
+                histogram_encoding = "native"
+                native_histogram_schema = 0
+            "#},
+            vec![event],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 1);
+        let (_headers, req) = &outputs[0];
+        assert_eq!(req.timeseries.len(), 1);
+        let series = &req.timeseries[0];
+        assert_eq!(
+            series.labels,
+            labels!("__name__" => "latency", "region" => "us-west-1")
+        );
+        assert!(series.samples.is_empty());
+        assert_eq!(series.histograms.len(), 1);
+        assert_eq!(
+            series.histograms[0].count,
+            Some(proto::histogram::Count::CountInt(4))
+        );
+        assert_eq!(series.exemplars.len(), 1);
+        assert_eq!(
+            series.exemplars[0].labels,
+            labels!("trace_id" => "4bf92f3577b34da6")
+        );
+        assert_eq!(series.exemplars[0].value, 2.0);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_native_histogram_schema() {
+        let config: RemoteWriteConfig = toml::from_str(indoc! {r#"
+            endpoint = "http://localhost:9090/write"
+            histogram_encoding = "native"
+            native_histogram_schema = 9
+        "#})
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
     async fn send_request(
         config: &str,
         events: Vec<Event>,
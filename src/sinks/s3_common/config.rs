@@ -11,7 +11,7 @@ use rusoto_core::RusotoError;
 use rusoto_s3::{HeadBucketRequest, PutObjectError, S3Client, S3};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
-use std::{collections::BTreeMap, convert::TryInto, time::Duration};
+use std::{collections::BTreeMap, time::Duration};
 
 use super::service::S3Response;
 
@@ -120,7 +120,8 @@ pub fn create_service(
     assume_role: Option<String>,
     proxy: &ProxyConfig,
 ) -> crate::Result<S3Service> {
-    let region = region.try_into()?;
+    let sts_region = region.region_for_service("sts")?;
+    let region = region.region_for_service("s3")?;
     let client = rusoto::custom_client(
         proxy,
         // S3 closes idle connections after 20 seconds,
@@ -128,7 +129,7 @@ pub fn create_service(
         client::Client::builder().pool_idle_timeout(Duration::from_secs(15)),
     )?;
 
-    let creds = auth.build(&region, assume_role)?;
+    let creds = auth.build(&sts_region, assume_role)?;
 
     let client = S3Client::new_with(client, creds, region.clone());
     Ok(S3Service::new(client, region))
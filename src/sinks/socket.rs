@@ -1,11 +1,14 @@
 #[cfg(unix)]
 use crate::sinks::util::unix::UnixSinkConfig;
 use crate::{
+    codecs::FramingEncoder,
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     sinks::util::{
-        encode_log, encoding::EncodingConfig, tcp::TcpSinkConfig, udp::UdpSinkConfig, Encoding,
+        encode_log_unframed, encoding::EncodingConfig, tcp::TcpSinkConfig, udp::UdpSinkConfig,
+        Encoding,
     },
 };
+use bytes::{Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -15,6 +18,8 @@ pub struct SocketSinkConfig {
     #[serde(flatten)]
     pub mode: Mode,
     pub encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    pub framing: FramingEncoder,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -43,7 +48,11 @@ impl GenerateConfig for SocketSinkConfig {
 
 impl SocketSinkConfig {
     pub const fn new(mode: Mode, encoding: EncodingConfig<Encoding>) -> Self {
-        SocketSinkConfig { mode, encoding }
+        SocketSinkConfig {
+            mode,
+            encoding,
+            framing: FramingEncoder::NewlineDelimited,
+        }
     }
 
     pub fn make_basic_tcp_config(address: String) -> Self {
@@ -62,7 +71,16 @@ impl SinkConfig for SocketSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let encoding = self.encoding.clone();
-        let encode_event = move |event| encode_log(event, &encoding);
+        let framing = self.framing.clone();
+        let encode_event = move |event| {
+            let message = encode_log_unframed(event, &encoding)?;
+            let mut buffer = BytesMut::new();
+            framing
+                .frame(Bytes::from(message), &mut buffer)
+                .map_err(|error| error!(message = "Unable to frame event.", %error))
+                .ok()?;
+            Some(buffer.freeze())
+        };
         match &self.mode {
             Mode::Tcp(config) => config.build(cx, encode_event),
             Mode::Udp(config) => config.build(cx, encode_event),
@@ -84,6 +102,7 @@ impl SinkConfig for SocketSinkConfig {
 mod test {
     use super::*;
     use crate::{
+        codecs::VarintLengthDelimitedCodec,
         config::SinkContext,
         event::Event,
         test_util::{next_addr, next_addr_v6, random_lines_with_stream, trace_init, CountReceiver},
@@ -112,6 +131,7 @@ mod test {
         let config = SocketSinkConfig {
             mode: Mode::Udp(UdpSinkConfig::from_address(addr.to_string())),
             encoding: Encoding::Json.into(),
+            framing: FramingEncoder::default(),
         };
         let context = SinkContext::new_test();
         let (sink, _healthcheck) = config.build(context).await.unwrap();
@@ -154,6 +174,7 @@ mod test {
         let config = SocketSinkConfig {
             mode: Mode::Tcp(TcpSinkConfig::from_address(addr.to_string())),
             encoding: Encoding::Json.into(),
+            framing: FramingEncoder::default(),
         };
 
         let context = SinkContext::new_test();
@@ -176,6 +197,33 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn tcp_stream_varint_framing() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = SocketSinkConfig {
+            mode: Mode::Tcp(TcpSinkConfig::from_address(addr.to_string())),
+            encoding: Encoding::Text.into(),
+            framing: FramingEncoder::VarintLengthDelimited,
+        };
+
+        let context = SinkContext::new_test();
+        let (sink, _healthcheck) = config.build(context).await.unwrap();
+
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let (lines, events) = random_lines_with_stream(200, 10, None);
+        tokio::spawn(sink.run(events));
+
+        let (socket, _) = listener.accept().await.unwrap();
+        let frames = FramedRead::new(socket, VarintLengthDelimitedCodec::new())
+            .take(lines.len())
+            .map(|frame| String::from_utf8(frame.unwrap().to_vec()).unwrap())
+            .collect::<Vec<_>>();
+        let frames = timeout(Duration::from_secs(5), frames).await.unwrap();
+        assert_eq!(frames, lines);
+    }
+
     // This is a test that checks that we properly receive all events in the
     // case of a proper server side write side shutdown.
     //
@@ -225,6 +273,7 @@ mod test {
                 None,
             )),
             encoding: Encoding::Text.into(),
+            framing: FramingEncoder::default(),
         };
         let context = SinkContext::new_test();
         let (sink, _healthcheck) = config.build(context).await.unwrap();
@@ -341,6 +390,7 @@ mod test {
         let config = SocketSinkConfig {
             mode: Mode::Tcp(TcpSinkConfig::from_address(addr.to_string())),
             encoding: Encoding::Text.into(),
+            framing: FramingEncoder::default(),
         };
 
         let context = SinkContext::new_test();
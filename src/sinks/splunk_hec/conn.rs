@@ -1,22 +1,23 @@
+use super::sink::{HecRequestBuilder, HecRetryLogic, HecService, HecSink};
 use crate::{
     buffers::Acker,
     config::ProxyConfig,
     http::HttpClient,
     sinks,
     sinks::util::buffer::Compression,
-    sinks::util::http::BatchedHttpSink,
     sinks::util::http::HttpSink,
-    sinks::util::service::TowerRequestConfig,
-    sinks::util::{BatchConfig, BatchSettings, Buffer},
+    sinks::util::service::{ServiceBuilderExt, TowerRequestConfig},
+    sinks::util::{BatchConfig, BatchSettings, RequestLimits},
     sinks::UriParseError,
     sinks::{Healthcheck, VectorSink},
     tls::{TlsOptions, TlsSettings},
 };
-use futures::{FutureExt, SinkExt};
+use futures::FutureExt;
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
 use snafu::{ResultExt, Snafu};
-use std::convert::TryFrom;
+use std::{convert::TryFrom, sync::Arc};
+use tower::{util::BoxService, ServiceBuilder};
 
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
@@ -48,27 +49,39 @@ where
 {
     validate_host(endpoint)?;
 
-    let batch_settings = BatchSettings::default()
+    let batch_settings = BatchSettings::<()>::default()
         .bytes(1_000_000)
         .timeout(1)
         .parse_config(batch_config)?;
+    // The batch size limits the encoded size of requests, which is only known once the events
+    // are encoded, so batches are split into as many requests as needed.
+    let limits = RequestLimits {
+        max_bytes: batch_settings.size.bytes,
+        max_events: batch_settings.size.events,
+    };
     let request_settings = request_config.unwrap_with(&TowerRequestConfig::default());
     let tls_settings = TlsSettings::from_options(tls_options)?;
     let client = HttpClient::new(tls_settings, proxy_config)?;
 
-    let sink = BatchedHttpSink::new(
-        sink,
-        Buffer::new(batch_settings.size, compression),
-        request_settings,
-        batch_settings.timeout,
-        client.clone(),
+    let sink = Arc::new(sink);
+    let service = ServiceBuilder::new()
+        .settings(request_settings, HecRetryLogic)
+        .service(HecService::new(client.clone(), Arc::clone(&sink)));
+
+    let sink = HecSink {
+        service: BoxService::new(service),
+        request_builder: HecRequestBuilder {
+            sink,
+            compression,
+            limits,
+        },
+        batch_settings: batch_settings.into_batcher_settings()?,
         acker,
-    )
-    .sink_map_err(|error| error!(message = "Fatal splunk_hec sink error.", %error));
+    };
 
     let healthcheck = healthcheck(endpoint.to_string(), token.to_string(), client).boxed();
 
-    Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
 }
 
 pub async fn build_request(
@@ -125,6 +138,7 @@ fn validate_host(host: &str) -> crate::Result<()> {
 mod tests {
     use super::*;
     use crate::event::Event;
+    use futures::{future::ready, stream};
     use http::HeaderValue;
     use std::path::PathBuf;
     use wiremock::matchers::{body_string, header, method, path};
@@ -236,10 +250,9 @@ mod tests {
         )
         .unwrap();
 
-        let mut sink = sink.into_sink();
-
-        sink.send(Event::from("test event")).await.unwrap();
-        sink.flush().await.unwrap();
+        sink.run(stream::once(ready(Event::from("test event"))))
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
@@ -435,6 +448,7 @@ mod tests {
             crt_file: None,
             key_file: Some(PathBuf::from("test_value")),
             key_pass: None,
+            spiffe: None,
         });
 
         let err = build_sink(
@@ -466,6 +480,7 @@ mod tests {
             crt_file: Some(PathBuf::from("invalid_path")),
             key_file: Some(PathBuf::from("invalid_path")),
             key_pass: None,
+            spiffe: None,
         });
 
         let err = build_sink(
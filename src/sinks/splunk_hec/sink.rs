@@ -0,0 +1,274 @@
+use crate::{
+    buffers::{Ackable, Acker},
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    http::{HttpClient, HttpError},
+    sinks::util::{
+        http::{HttpBatchService, HttpRetryLogic, HttpSink},
+        retries::{RetryAction, RetryLogic},
+        Compression, ElementCount, EncodedPayload, IncrementalRequestBuilder, RequestLimits,
+        SinkBuilderExt, StreamSink,
+    },
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
+use http::Response;
+use std::{
+    io,
+    num::NonZeroUsize,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{util::BoxService, Service, ServiceExt};
+use vector_core::{partition::NullPartitioner, stream::BatcherSettings, ByteSizeOf};
+
+#[derive(Clone)]
+pub struct HecRequest {
+    pub body: Vec<u8>,
+    pub finalizers: EventFinalizers,
+    pub events_len: usize,
+}
+
+impl ByteSizeOf for HecRequest {
+    fn allocated_bytes(&self) -> usize {
+        self.body.allocated_bytes() + self.finalizers.allocated_bytes()
+    }
+}
+
+impl ElementCount for HecRequest {
+    fn element_count(&self) -> usize {
+        self.events_len
+    }
+}
+
+impl Ackable for HecRequest {
+    fn ack_size(&self) -> usize {
+        self.events_len
+    }
+}
+
+impl Finalizable for HecRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+/// Builds HEC requests out of the events encoded by the sink, which are sent as concatenated JSON
+/// objects.
+pub struct HecRequestBuilder<T> {
+    pub sink: Arc<T>,
+    pub compression: Compression,
+    pub limits: RequestLimits,
+}
+
+impl<T> IncrementalRequestBuilder<Vec<Event>> for HecRequestBuilder<T>
+where
+    T: HttpSink<Input = Vec<u8>, Output = Vec<u8>>,
+{
+    type Metadata = ();
+    type Event = Event;
+    type Request = HecRequest;
+    type Error = io::Error;
+
+    fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    fn limits(&self) -> RequestLimits {
+        self.limits
+    }
+
+    fn split_input(&self, events: Vec<Event>) -> ((), Vec<Event>) {
+        ((), events)
+    }
+
+    fn encode_event(&self, event: Event, writer: &mut Vec<u8>) -> io::Result<()> {
+        // Events the sink fails to encode were already reported by it.
+        if let Some(encoded) = self.sink.encode_event(event) {
+            writer.extend_from_slice(&encoded);
+        }
+        Ok(())
+    }
+
+    fn build_request(&self, _: (), payload: EncodedPayload) -> HecRequest {
+        HecRequest {
+            body: payload.body,
+            finalizers: payload.finalizers,
+            events_len: payload.events_len,
+        }
+    }
+}
+
+pub struct HecResponse {
+    pub http_response: Response<Bytes>,
+    pub event_status: EventStatus,
+}
+
+impl AsRef<EventStatus> for HecResponse {
+    fn as_ref(&self) -> &EventStatus {
+        &self.event_status
+    }
+}
+
+#[derive(Clone)]
+pub struct HecService {
+    batch_service:
+        HttpBatchService<BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>>, HecRequest>,
+}
+
+impl HecService {
+    pub fn new<T>(client: HttpClient, sink: Arc<T>) -> Self
+    where
+        T: HttpSink<Input = Vec<u8>, Output = Vec<u8>>,
+    {
+        let batch_service = HttpBatchService::new(client, move |request: HecRequest| {
+            let sink = Arc::clone(&sink);
+            let future: BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> =
+                Box::pin(async move { sink.build_request(request.body).await });
+            future
+        });
+        Self { batch_service }
+    }
+}
+
+impl Service<HecRequest> for HecService {
+    type Response = HecResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HecRequest) -> Self::Future {
+        let mut http_service = self.batch_service.clone();
+        Box::pin(async move {
+            http_service.ready().await?;
+            let http_response = http_service.call(request).await?;
+            let event_status = if http_response.status().is_success() {
+                EventStatus::Delivered
+            } else if http_response.status().is_server_error() {
+                EventStatus::Errored
+            } else {
+                EventStatus::Failed
+            };
+            Ok(HecResponse {
+                http_response,
+                event_status,
+            })
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HecRetryLogic;
+
+impl RetryLogic for HecRetryLogic {
+    type Error = HttpError;
+    type Response = HecResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        HttpRetryLogic.is_retriable_error(error)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        HttpRetryLogic.should_retry_response(&response.http_response)
+    }
+}
+
+pub struct HecSink<T> {
+    pub service: BoxService<HecRequest, HecResponse, crate::Error>,
+    pub request_builder: HecRequestBuilder<T>,
+    pub batch_settings: BatcherSettings,
+    pub acker: Acker,
+}
+
+impl<T> HecSink<T>
+where
+    T: HttpSink<Input = Vec<u8>, Output = Vec<u8>>,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let builder_limit = NonZeroUsize::new(64);
+
+        let sink = input
+            .batched(NullPartitioner::new(), self.batch_settings)
+            .map(|(_, batch)| batch)
+            .incremental_request_builder(builder_limit, self.request_builder)
+            .filter_map(|request| async move {
+                match request {
+                    Err(error) => {
+                        error!("Failed to build Splunk HEC request: {:?}.", error);
+                        None
+                    }
+                    Ok(request) => Some(request),
+                }
+            })
+            .into_driver(self.service, self.acker);
+
+        sink.run().await
+    }
+}
+
+#[async_trait]
+impl<T> StreamSink for HecSink<T>
+where
+    T: HttpSink<Input = Vec<u8>, Output = Vec<u8>>,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubSink;
+
+    #[async_trait]
+    impl HttpSink for StubSink {
+        type Input = Vec<u8>;
+        type Output = Vec<u8>;
+
+        fn encode_event(&self, event: Event) -> Option<Self::Input> {
+            let message = event.as_log()["message"].to_string_lossy();
+            (message != "skip").then(|| format!(r#"{{"event":"{}"}}"#, message).into_bytes())
+        }
+
+        async fn build_request(&self, body: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+            Ok(http::Request::new(body))
+        }
+    }
+
+    #[test]
+    fn request_builder_splits_batches_on_encoded_size() {
+        let builder = HecRequestBuilder {
+            sink: Arc::new(StubSink),
+            compression: Compression::None,
+            limits: RequestLimits {
+                max_bytes: 30,
+                max_events: 100,
+            },
+        };
+        let events = vec!["a", "skip", "b", "c"]
+            .into_iter()
+            .map(Event::from)
+            .collect();
+
+        let requests = builder
+            .build_requests(events)
+            .into_iter()
+            .map(|request| {
+                let request = request.unwrap();
+                (String::from_utf8(request.body).unwrap(), request.events_len)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            vec![
+                (r#"{"event":"a"}{"event":"b"}"#.to_owned(), 2),
+                (r#"{"event":"c"}"#.to_owned(), 1),
+            ]
+        );
+    }
+}
@@ -2,7 +2,7 @@
 use crate::sinks::util::unix::UnixSinkConfig;
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::metric::{Metric, MetricKind, MetricTags, MetricValue, StatisticKind},
+    event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
     event::Event,
     internal_events::StatsdInvalidMetricReceived,
     sinks::util::{
@@ -10,6 +10,7 @@ use crate::{
         tcp::TcpSinkConfig,
         udp::{UdpService, UdpSinkConfig},
         BatchConfig, BatchSettings, BatchSink, Buffer, Compression, EncodedEvent,
+        MultiValueTagsConfig,
     },
 };
 use futures::{future, stream, FutureExt, SinkExt, TryFutureExt};
@@ -34,6 +35,8 @@ pub struct StatsdSinkConfig {
     pub default_namespace: Option<String>,
     #[serde(flatten)]
     pub mode: Mode,
+    #[serde(default)]
+    pub multi_value_tags: MultiValueTagsConfig,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -70,6 +73,7 @@ impl GenerateConfig for StatsdSinkConfig {
                 batch: Default::default(),
                 udp: UdpSinkConfig::from_address(default_address().to_string()),
             }),
+            multi_value_tags: Default::default(),
         })
         .unwrap()
     }
@@ -83,10 +87,13 @@ impl SinkConfig for StatsdSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let default_namespace = self.default_namespace.clone();
+        let multi_value_tags = self.multi_value_tags.clone();
         match &self.mode {
             Mode::Tcp(config) => {
-                let encode_event =
-                    move |event| encode_event(event, default_namespace.as_deref()).map(Into::into);
+                let encode_event = move |event| {
+                    encode_event(event, default_namespace.as_deref(), &multi_value_tags)
+                        .map(Into::into)
+                };
                 config.build(cx, encode_event)
             }
             Mode::Udp(config) => {
@@ -112,7 +119,7 @@ impl SinkConfig for StatsdSinkConfig {
                 .with_flat_map(move |event: Event| {
                     stream::iter({
                         let byte_size = event.size_of();
-                        encode_event(event, default_namespace.as_deref())
+                        encode_event(event, default_namespace.as_deref(), &multi_value_tags)
                             .map(|encoded| Ok(EncodedEvent::new(encoded, byte_size)))
                     })
                 });
@@ -121,8 +128,10 @@ impl SinkConfig for StatsdSinkConfig {
             }
             #[cfg(unix)]
             Mode::Unix(config) => {
-                let encode_event =
-                    move |event| encode_event(event, default_namespace.as_deref()).map(Into::into);
+                let encode_event = move |event| {
+                    encode_event(event, default_namespace.as_deref(), &multi_value_tags)
+                        .map(Into::into)
+                };
                 config.build(cx, encode_event)
             }
         }
@@ -137,9 +146,10 @@ impl SinkConfig for StatsdSinkConfig {
     }
 }
 
-fn encode_tags(tags: &MetricTags) -> String {
-    let parts: Vec<_> = tags
-        .iter()
+fn encode_tags(metric: &Metric, multi_value_tags: &MultiValueTagsConfig) -> String {
+    let parts: Vec<_> = multi_value_tags
+        .encode(metric)
+        .into_iter()
         .map(|(name, value)| {
             if value == "true" {
                 name.to_string()
@@ -148,7 +158,7 @@ fn encode_tags(tags: &MetricTags) -> String {
             }
         })
         .collect();
-    // `parts` is already sorted by key because of BTreeMap
+    // `parts` is already sorted by key
     parts.join(",")
 }
 
@@ -158,6 +168,7 @@ fn push_event<V: Display>(
     val: V,
     metric_type: &str,
     sample_rate: Option<u32>,
+    multi_value_tags: &MultiValueTagsConfig,
 ) {
     buf.push(format!("{}:{}|{}", metric.name(), val, metric_type));
 
@@ -167,25 +178,36 @@ fn push_event<V: Display>(
         }
     };
 
-    if let Some(t) = metric.tags() {
-        buf.push(format!("#{}", encode_tags(t)));
+    if metric.tags().is_some() {
+        buf.push(format!("#{}", encode_tags(metric, multi_value_tags)));
     };
 }
 
-fn encode_event(event: Event, default_namespace: Option<&str>) -> Option<Vec<u8>> {
+fn encode_event(
+    event: Event,
+    default_namespace: Option<&str>,
+    multi_value_tags: &MultiValueTagsConfig,
+) -> Option<Vec<u8>> {
     let mut buf = Vec::new();
 
     let metric = event.as_metric();
     match metric.value() {
         MetricValue::Counter { value } => {
-            push_event(&mut buf, metric, value, "c", None);
+            push_event(&mut buf, metric, value, "c", None, multi_value_tags);
         }
         MetricValue::Gauge { value } => {
             match metric.kind() {
-                MetricKind::Incremental => {
-                    push_event(&mut buf, metric, format!("{:+}", value), "g", None)
+                MetricKind::Incremental => push_event(
+                    &mut buf,
+                    metric,
+                    format!("{:+}", value),
+                    "g",
+                    None,
+                    multi_value_tags,
+                ),
+                MetricKind::Absolute => {
+                    push_event(&mut buf, metric, value, "g", None, multi_value_tags)
                 }
-                MetricKind::Absolute => push_event(&mut buf, metric, value, "g", None),
             };
         }
         MetricValue::Distribution { samples, statistic } => {
@@ -200,12 +222,13 @@ fn encode_event(event: Event, default_namespace: Option<&str>) -> Option<Vec<u8>
                     sample.value,
                     metric_type,
                     Some(sample.rate),
+                    multi_value_tags,
                 );
             }
         }
         MetricValue::Set { values } => {
             for val in values {
-                push_event(&mut buf, metric, val, "s", None);
+                push_event(&mut buf, metric, val, "s", None, multi_value_tags);
             }
         }
         _ => {
@@ -257,7 +280,7 @@ mod test {
         crate::test_util::test_generate_config::<StatsdSinkConfig>();
     }
 
-    fn tags() -> MetricTags {
+    fn tags() -> crate::event::metric::MetricTags {
         vec![
             ("normal_tag".to_owned(), "value".to_owned()),
             ("true_tag".to_owned(), "true".to_owned()),
@@ -267,10 +290,19 @@ mod test {
         .collect()
     }
 
+    fn tagged(tags: crate::event::metric::MetricTags) -> Metric {
+        Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(tags))
+    }
+
     #[test]
     fn test_encode_tags() {
         assert_eq!(
-            &encode_tags(&tags()),
+            &encode_tags(&tagged(tags()), &Default::default()),
             "empty_tag:,normal_tag:value,true_tag"
         );
     }
@@ -279,21 +311,44 @@ mod test {
     fn tags_order() {
         assert_eq!(
             &encode_tags(
-                &vec![
-                    ("a", "value"),
-                    ("b", "value"),
-                    ("c", "value"),
-                    ("d", "value"),
-                    ("e", "value"),
-                ]
-                .into_iter()
-                .map(|(k, v)| (k.to_owned(), v.to_owned()))
-                .collect()
+                &tagged(
+                    vec![
+                        ("a", "value"),
+                        ("b", "value"),
+                        ("c", "value"),
+                        ("d", "value"),
+                        ("e", "value"),
+                    ]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                    .collect()
+                ),
+                &Default::default()
             ),
             "a:value,b:value,c:value,d:value,e:value"
         );
     }
 
+    #[test]
+    fn encodes_multi_value_tags() {
+        let mut metric = tagged(tags());
+        metric.add_tag_value("host_group".into(), "web".into());
+        metric.add_tag_value("host_group".into(), "frontend".into());
+        assert_eq!(
+            &encode_tags(&metric, &MultiValueTagsConfig::Full),
+            "empty_tag:,host_group:web,host_group:frontend,normal_tag:value,true_tag"
+        );
+        assert_eq!(
+            &encode_tags(
+                &metric,
+                &MultiValueTagsConfig::Joined {
+                    separator: ";".into()
+                }
+            ),
+            "empty_tag:,host_group:web;frontend,normal_tag:value,true_tag"
+        );
+    }
+
     #[cfg(feature = "sources-statsd")]
     #[test]
     fn test_encode_counter() {
@@ -304,7 +359,7 @@ mod test {
         )
         .with_tags(Some(tags()));
         let event = Event::Metric(metric1.clone());
-        let frame = &encode_event(event, None).unwrap();
+        let frame = &encode_event(event, None, &Default::default()).unwrap();
         let metric2 = parse(from_utf8(frame).unwrap().trim()).unwrap();
         shared::assert_event_data_eq!(metric1, metric2);
     }
@@ -318,7 +373,7 @@ mod test {
             MetricValue::Counter { value: 1.5 },
         );
         let event = Event::Metric(metric1);
-        let frame = &encode_event(event, None).unwrap();
+        let frame = &encode_event(event, None, &Default::default()).unwrap();
         // The statsd parser will parse the counter as Incremental,
         // so we can't compare it with the parsed value.
         assert_eq!("counter:1.5|c\n", from_utf8(frame).unwrap());
@@ -334,7 +389,7 @@ mod test {
         )
         .with_tags(Some(tags()));
         let event = Event::Metric(metric1.clone());
-        let frame = &encode_event(event, None).unwrap();
+        let frame = &encode_event(event, None, &Default::default()).unwrap();
         let metric2 = parse(from_utf8(frame).unwrap().trim()).unwrap();
         shared::assert_event_data_eq!(metric1, metric2);
     }
@@ -349,7 +404,7 @@ mod test {
         )
         .with_tags(Some(tags()));
         let event = Event::Metric(metric1.clone());
-        let frame = &encode_event(event, None).unwrap();
+        let frame = &encode_event(event, None, &Default::default()).unwrap();
         let metric2 = parse(from_utf8(frame).unwrap().trim()).unwrap();
         shared::assert_event_data_eq!(metric1, metric2);
     }
@@ -367,7 +422,7 @@ mod test {
         )
         .with_tags(Some(tags()));
         let event = Event::Metric(metric1.clone());
-        let frame = &encode_event(event, None).unwrap();
+        let frame = &encode_event(event, None, &Default::default()).unwrap();
         let metric2 = parse(from_utf8(frame).unwrap().trim()).unwrap();
         shared::assert_event_data_eq!(metric1, metric2);
     }
@@ -384,7 +439,7 @@ mod test {
         )
         .with_tags(Some(tags()));
         let event = Event::Metric(metric1.clone());
-        let frame = &encode_event(event, None).unwrap();
+        let frame = &encode_event(event, None, &Default::default()).unwrap();
         let metric2 = parse(from_utf8(frame).unwrap().trim()).unwrap();
         shared::assert_event_data_eq!(metric1, metric2);
     }
@@ -405,6 +460,7 @@ mod test {
                 },
                 udp: UdpSinkConfig::from_address(addr.to_string()),
             }),
+            multi_value_tags: Default::default(),
         };
 
         let context = SinkContext::new_test();
@@ -1,18 +1,33 @@
+use lazy_static::lazy_static;
 use serde::{de, ser};
 use serde_json::Value;
-use std::fmt;
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 pub const GZIP_NONE: u32 = 0;
 pub const GZIP_FAST: u32 = 1;
 pub const GZIP_DEFAULT: u32 = 6;
 pub const GZIP_BEST: u32 = 9;
 
+pub const ZSTD_FAST: i32 = 1;
+pub const ZSTD_DEFAULT: i32 = 3;
+pub const ZSTD_BEST: i32 = 19;
+pub const ZSTD_MAX: i32 = 21;
+
 #[derive(Debug, Derivative, Copy, Clone, Eq, PartialEq)]
 #[derivative(Default)]
 pub enum Compression {
     #[derivative(Default)]
     None,
     Gzip(flate2::Compression),
+    Zstd {
+        level: i32,
+        dictionary: Option<ZstdDictionary>,
+    },
 }
 
 impl Compression {
@@ -23,10 +38,18 @@ impl Compression {
         Compression::Gzip(flate2::Compression::new(6))
     }
 
+    pub const fn zstd_default() -> Compression {
+        Compression::Zstd {
+            level: ZSTD_DEFAULT,
+            dictionary: None,
+        }
+    }
+
     pub const fn content_encoding(&self) -> Option<&'static str> {
         match self {
             Self::None => None,
             Self::Gzip(_) => Some("gzip"),
+            Self::Zstd { .. } => Some("zstd"),
         }
     }
 
@@ -34,30 +57,145 @@ impl Compression {
         match self {
             Self::None => "log",
             Self::Gzip(_) => "log.gz",
+            Self::Zstd { .. } => "log.zst",
         }
     }
 }
+
 impl fmt::Display for Compression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Compression::None => write!(f, "none"),
             Compression::Gzip(ref level) => write!(f, "gzip({})", level.level()),
+            Compression::Zstd {
+                level,
+                dictionary: None,
+            } => write!(f, "zstd({})", level),
+            Compression::Zstd {
+                level,
+                dictionary: Some(dictionary),
+            } => write!(f, "zstd({}, {:?})", level, dictionary.path()),
+        }
+    }
+}
+
+/// A Zstandard dictionary, shared with the receiver of the compressed payloads
+/// to improve the ratio of small payloads with a common structure.
+///
+/// Loaded dictionaries live as long as the process so that `Compression` stays
+/// `Copy`. Loading a file again only allocates when its contents changed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ZstdDictionary {
+    path: &'static Path,
+    data: &'static [u8],
+}
+
+impl ZstdDictionary {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        lazy_static! {
+            static ref LOADED: Mutex<HashMap<PathBuf, ZstdDictionary>> = Default::default();
+        }
+
+        let data = fs::read(path)?;
+        if data.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "dictionary is empty",
+            ));
+        }
+
+        let mut loaded = LOADED.lock().unwrap();
+        match loaded.get(path) {
+            Some(dictionary) if dictionary.data == data.as_slice() => Ok(*dictionary),
+            _ => {
+                let dictionary = ZstdDictionary {
+                    path: Box::leak(path.to_path_buf().into_boxed_path()),
+                    data: Box::leak(data.into_boxed_slice()),
+                };
+                loaded.insert(path.to_path_buf(), dictionary);
+                Ok(dictionary)
+            }
         }
     }
+
+    pub const fn path(&self) -> &'static Path {
+        self.path
+    }
+
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        self.data
+    }
 }
 
 #[cfg(feature = "rusoto_core")]
-impl From<Compression> for rusoto_core::encoding::ContentEncoding {
-    fn from(compression: Compression) -> Self {
+impl std::convert::TryFrom<Compression> for rusoto_core::encoding::ContentEncoding {
+    type Error = crate::Error;
+
+    fn try_from(compression: Compression) -> Result<Self, Self::Error> {
         match compression {
-            Compression::None => rusoto_core::encoding::ContentEncoding::Identity,
-            Compression::Gzip(level) => {
-                rusoto_core::encoding::ContentEncoding::Gzip(None, level.level())
+            Compression::None => Ok(rusoto_core::encoding::ContentEncoding::Identity),
+            Compression::Gzip(level) => Ok(rusoto_core::encoding::ContentEncoding::Gzip(
+                None,
+                level.level(),
+            )),
+            Compression::Zstd { .. } => {
+                Err("zstd compression is not supported by this sink".into())
             }
         }
     }
 }
 
+fn gzip_level<E: de::Error>(level: &Value) -> Result<flate2::Compression, E> {
+    match level {
+        Value::Number(level) => match level.as_u64() {
+            Some(value) if value <= 9 => Ok(flate2::Compression::new(value as u32)),
+            Some(_) | None => Err(de::Error::invalid_value(
+                de::Unexpected::Other(&level.to_string()),
+                &"0, 1, 2, 3, 4, 5, 6, 7, 8 or 9",
+            )),
+        },
+        Value::String(level) => match level.as_str() {
+            "none" => Ok(flate2::Compression::none()),
+            "fast" => Ok(flate2::Compression::fast()),
+            "default" => Ok(flate2::Compression::default()),
+            "best" => Ok(flate2::Compression::best()),
+            level => Err(de::Error::invalid_value(
+                de::Unexpected::Str(level),
+                &r#""none", "fast", "best" or "default""#,
+            )),
+        },
+        value => Err(de::Error::invalid_type(
+            de::Unexpected::Other(&value.to_string()),
+            &"integer or string",
+        )),
+    }
+}
+
+fn zstd_level<E: de::Error>(level: &Value) -> Result<i32, E> {
+    match level {
+        Value::Number(level) => match level.as_i64() {
+            Some(value) if (1..=ZSTD_MAX as i64).contains(&value) => Ok(value as i32),
+            Some(_) | None => Err(de::Error::invalid_value(
+                de::Unexpected::Other(&level.to_string()),
+                &"an integer from 1 to 21",
+            )),
+        },
+        Value::String(level) => match level.as_str() {
+            "fast" => Ok(ZSTD_FAST),
+            "default" => Ok(ZSTD_DEFAULT),
+            "best" => Ok(ZSTD_BEST),
+            level => Err(de::Error::invalid_value(
+                de::Unexpected::Str(level),
+                &r#""fast", "best" or "default""#,
+            )),
+        },
+        value => Err(de::Error::invalid_type(
+            de::Unexpected::Other(&value.to_string()),
+            &"integer or string",
+        )),
+    }
+}
+
 impl<'de> de::Deserialize<'de> for Compression {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -79,9 +217,10 @@ impl<'de> de::Deserialize<'de> for Compression {
                 match s {
                     "none" => Ok(Compression::None),
                     "gzip" => Ok(Compression::gzip_default()),
+                    "zstd" => Ok(Compression::zstd_default()),
                     _ => Err(de::Error::invalid_value(
                         de::Unexpected::Str(s),
-                        &r#""none" or "gzip""#,
+                        &r#""none", "gzip" or "zstd""#,
                     )),
                 }
             }
@@ -92,6 +231,7 @@ impl<'de> de::Deserialize<'de> for Compression {
             {
                 let mut algorithm = None;
                 let mut level = None;
+                let mut dictionary = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -105,49 +245,72 @@ impl<'de> de::Deserialize<'de> for Compression {
                             if level.is_some() {
                                 return Err(de::Error::duplicate_field("level"));
                             }
-                            level = Some(match map.next_value::<Value>()? {
-                                Value::Number(level) => match level.as_u64() {
-                                    Some(value) if value <= 9 => {
-                                        flate2::Compression::new(value as u32)
-                                    }
-                                    Some(_) | None => {
-                                        return Err(de::Error::invalid_value(
-                                            de::Unexpected::Other(&level.to_string()),
-                                            &"0, 1, 2, 3, 4, 5, 6, 7, 8 or 9",
-                                        ))
-                                    }
-                                },
-                                Value::String(level) => match level.as_str() {
-                                    "none" => flate2::Compression::none(),
-                                    "fast" => flate2::Compression::fast(),
-                                    "default" => flate2::Compression::default(),
-                                    "best" => flate2::Compression::best(),
-                                    level => {
-                                        return Err(de::Error::invalid_value(
-                                            de::Unexpected::Str(level),
-                                            &r#""none", "fast", "best" or "default""#,
-                                        ))
-                                    }
-                                },
-                                value => {
-                                    return Err(de::Error::invalid_type(
-                                        de::Unexpected::Other(&value.to_string()),
-                                        &"integer or string",
-                                    ));
+                            let value = map.next_value::<Value>()?;
+                            // Report invalid levels where they are when the
+                            // algorithm is already known.
+                            match algorithm {
+                                Some("gzip") => {
+                                    gzip_level::<A::Error>(&value)?;
+                                }
+                                Some("zstd") => {
+                                    zstd_level::<A::Error>(&value)?;
                                 }
-                            });
+                                _ => {}
+                            }
+                            level = Some(value);
+                        }
+                        "dictionary" => {
+                            if dictionary.is_some() {
+                                return Err(de::Error::duplicate_field("dictionary"));
+                            }
+                            dictionary = Some(map.next_value::<PathBuf>()?);
+                        }
+                        _ => {
+                            return Err(de::Error::unknown_field(
+                                key,
+                                &["algorithm", "level", "dictionary"],
+                            ))
                         }
-                        _ => return Err(de::Error::unknown_field(key, &["algorithm", "level"])),
                     };
                 }
 
                 match algorithm.ok_or_else(|| de::Error::missing_field("algorithm"))? {
-                    "none" => match level {
-                        Some(_) => Err(de::Error::unknown_field("level", &[])),
-                        None => Ok(Compression::None),
+                    "none" => match (level, dictionary) {
+                        (Some(_), _) => Err(de::Error::unknown_field("level", &[])),
+                        (_, Some(_)) => Err(de::Error::unknown_field("dictionary", &[])),
+                        (None, None) => Ok(Compression::None),
+                    },
+                    "gzip" => match dictionary {
+                        Some(_) => Err(de::Error::unknown_field("dictionary", &["level"])),
+                        None => Ok(Compression::Gzip(
+                            level
+                                .as_ref()
+                                .map(gzip_level::<A::Error>)
+                                .transpose()?
+                                .unwrap_or_default(),
+                        )),
                     },
-                    "gzip" => Ok(Compression::Gzip(level.unwrap_or_default())),
-                    algorithm => Err(de::Error::unknown_variant(algorithm, &["none", "gzip"])),
+                    "zstd" => {
+                        let level = level.as_ref().map(zstd_level::<A::Error>).transpose()?;
+                        let dictionary = dictionary
+                            .map(|path| {
+                                ZstdDictionary::load(&path).map_err(|error| {
+                                    de::Error::custom(format!(
+                                        "could not load zstd dictionary {:?}: {}",
+                                        path, error
+                                    ))
+                                })
+                            })
+                            .transpose()?;
+                        Ok(Compression::Zstd {
+                            level: level.unwrap_or(ZSTD_DEFAULT),
+                            dictionary,
+                        })
+                    }
+                    algorithm => Err(de::Error::unknown_variant(
+                        algorithm,
+                        &["none", "gzip", "zstd"],
+                    )),
                 }
             }
         }
@@ -178,6 +341,18 @@ impl ser::Serialize for Compression {
                     level => map.serialize_entry("level", &level)?,
                 };
             }
+            Compression::Zstd { level, dictionary } => {
+                map.serialize_entry("algorithm", "zstd")?;
+                match *level {
+                    ZSTD_FAST => map.serialize_entry("level", "fast")?,
+                    ZSTD_DEFAULT => {}
+                    ZSTD_BEST => map.serialize_entry("level", "best")?,
+                    level => map.serialize_entry("level", &level)?,
+                };
+                if let Some(dictionary) = dictionary {
+                    map.serialize_entry("dictionary", dictionary.path())?;
+                }
+            }
         };
         map.end()
     }
@@ -185,7 +360,9 @@ impl ser::Serialize for Compression {
 
 #[cfg(test)]
 mod test {
-    use super::Compression;
+    use super::{Compression, ZstdDictionary, ZSTD_DEFAULT};
+    use crate::sinks::util::Compressor;
+    use std::io::Write;
 
     #[test]
     fn deserialization() {
@@ -204,6 +381,21 @@ mod test {
                 r#"{"algorithm": "gzip", "level": 8}"#,
                 Compression::Gzip(flate2::Compression::new(8)),
             ),
+            (r#""zstd""#, Compression::zstd_default()),
+            (
+                r#"{"algorithm": "zstd", "level": "best"}"#,
+                Compression::Zstd {
+                    level: 19,
+                    dictionary: None,
+                },
+            ),
+            (
+                r#"{"level": 12, "algorithm": "zstd"}"#,
+                Compression::Zstd {
+                    level: 12,
+                    dictionary: None,
+                },
+            ),
         ];
         for (sources, result) in fixtures_valid.iter() {
             let deserialized: Result<Compression, _> = serde_json::from_str(sources);
@@ -217,11 +409,11 @@ mod test {
             ),
             (
                 r#""b42""#,
-                r#"invalid value: string "b42", expected "none" or "gzip" at line 1 column 5"#,
+                r#"invalid value: string "b42", expected "none", "gzip" or "zstd" at line 1 column 5"#,
             ),
             (
                 r#"{"algorithm": "b42"}"#,
-                r#"unknown variant `b42`, expected `none` or `gzip` at line 1 column 20"#,
+                r#"unknown variant `b42`, expected one of `none`, `gzip`, `zstd` at line 1 column 20"#,
             ),
             (
                 r#"{"algorithm": "none", "level": "default"}"#,
@@ -241,7 +433,15 @@ mod test {
             ),
             (
                 r#"{"algorithm": "gzip", "level": "default", "key": 42}"#,
-                r#"unknown field `key`, expected `algorithm` or `level` at line 1 column 47"#,
+                r#"unknown field `key`, expected one of `algorithm`, `level`, `dictionary` at line 1 column 47"#,
+            ),
+            (
+                r#"{"algorithm": "zstd", "level": 22}"#,
+                r#"invalid value: 22, expected an integer from 1 to 21 at line 1 column 34"#,
+            ),
+            (
+                r#"{"algorithm": "gzip", "dictionary": "/tmp/d"}"#,
+                r#"unknown field `dictionary`, expected `level` at line 1 column 45"#,
             ),
         ];
         for (source, result) in fixtures_invalid.iter() {
@@ -250,4 +450,38 @@ mod test {
             assert_eq!(error.to_string().as_str(), *result);
         }
     }
+
+    #[test]
+    fn zstd_dictionary_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dictionary");
+        std::fs::write(&path, br#"{"message":"","service":"","status":"info"}"#).unwrap();
+
+        let source = serde_json::json!({ "algorithm": "zstd", "dictionary": path });
+        let compression: Compression = serde_json::from_value(source.clone()).unwrap();
+        let dictionary = match compression {
+            Compression::Zstd {
+                level: ZSTD_DEFAULT,
+                dictionary: Some(dictionary),
+            } => dictionary,
+            compression => panic!("unexpected compression {}", compression),
+        };
+        assert_eq!(serde_json::to_value(compression).unwrap(), source);
+        // Loading the same contents again reuses the dictionary.
+        assert!(std::ptr::eq(
+            ZstdDictionary::load(&path).unwrap().as_bytes(),
+            dictionary.as_bytes()
+        ));
+
+        let payload = br#"{"message":"hello","service":"api","status":"info"}"#;
+        let mut compressor = Compressor::from(compression);
+        compressor.write_all(payload).unwrap();
+        let compressed = compressor.into_inner();
+
+        let mut decoder =
+            zstd::stream::Decoder::with_dictionary(&compressed[..], dictionary.as_bytes()).unwrap();
+        let mut decompressed = Vec::new();
+        std::io::Read::read_to_end(&mut decoder, &mut decompressed).unwrap();
+        assert_eq!(decompressed, payload);
+    }
 }
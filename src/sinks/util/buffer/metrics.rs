@@ -21,19 +21,29 @@ use vector_core::event::{
 pub struct MetricsBuffer {
     metrics: Option<MetricSet>,
     max_events: usize,
+    quantile_error: f64,
 }
 
 impl MetricsBuffer {
     pub const fn new(settings: BatchSize<Self>) -> Self {
-        Self::with_capacity(settings.events)
+        Self::with_capacity(settings.events, 0.0)
     }
 
-    const fn with_capacity(max_events: usize) -> Self {
+    const fn with_capacity(max_events: usize, quantile_error: f64) -> Self {
         Self {
             metrics: None,
             max_events,
+            quantile_error,
         }
     }
+
+    /// Compresses the samples of distributions further, as long as the
+    /// quantiles computed from them are off by at most `quantile_error` times
+    /// their count in rank. Zero keeps every distinct sample, so that
+    /// quantiles are exact.
+    pub const fn with_quantile_error(self, quantile_error: f64) -> Self {
+        Self::with_capacity(self.max_events, quantile_error)
+    }
 }
 
 impl Batch for MetricsBuffer {
@@ -66,15 +76,16 @@ impl Batch for MetricsBuffer {
     }
 
     fn fresh(&self) -> Self {
-        Self::with_capacity(self.max_events)
+        Self::with_capacity(self.max_events, self.quantile_error)
     }
 
     fn finish(self) -> Self::Output {
+        let quantile_error = self.quantile_error;
         self.metrics
             .unwrap_or_else(|| MetricSet::with_capacity(0))
             .0
             .into_iter()
-            .map(finish_metric)
+            .map(|item| finish_metric(item, quantile_error))
             .collect()
     }
 
@@ -242,15 +253,61 @@ impl MetricSet {
     }
 }
 
-fn finish_metric(item: (MetricSeries, MetricEntry)) -> Metric {
+fn finish_metric(item: (MetricSeries, MetricEntry), quantile_error: f64) -> Metric {
     let (series, (mut data, metadata)) = item;
     if let MetricValue::Distribution { samples, statistic } = data.value {
-        let samples = compress_distribution(samples);
+        let mut samples = compress_distribution(samples);
+        if quantile_error > 0.0 {
+            samples = merge_distribution(samples, quantile_error);
+        }
         data.value = MetricValue::Distribution { samples, statistic };
     }
     Metric::from_parts(series, data, metadata)
 }
 
+/// Merges adjacent samples of a sorted and compressed distribution into
+/// samples holding at most `quantile_error` of its count each, valued at their
+/// mean. Any quantile then falls within a merged sample, so that its rank is
+/// off by at most that many samples, while the sum and count of the
+/// distribution are preserved. The smallest and largest samples are kept as
+/// they are, preserving its minimum and maximum.
+fn merge_distribution(samples: Vec<Sample>, quantile_error: f64) -> Vec<Sample> {
+    if samples.len() <= 3 {
+        return samples;
+    }
+
+    let count = samples.iter().map(|sample| sample.rate as u64).sum::<u64>();
+    let max_rate = (quantile_error * count as f64).floor() as u64;
+
+    let last = samples.len() - 1;
+    let mut result = Vec::with_capacity(samples.len());
+    result.push(samples[0]);
+
+    let mut rate = 0u64;
+    let mut sum = 0.0;
+    for sample in &samples[1..last] {
+        if rate > 0 && rate + sample.rate as u64 > max_rate {
+            result.push(Sample {
+                value: sum / rate as f64,
+                rate: rate as u32,
+            });
+            rate = 0;
+            sum = 0.0;
+        }
+        rate += sample.rate as u64;
+        sum += sample.value * sample.rate as f64;
+    }
+    if rate > 0 {
+        result.push(Sample {
+            value: sum / rate as f64,
+            rate: rate as u32,
+        });
+    }
+
+    result.push(samples[last]);
+    result
+}
+
 fn compress_distribution(mut samples: Vec<Sample>) -> Vec<Sample> {
     if samples.is_empty() {
         return Vec::new();
@@ -677,6 +734,55 @@ mod test {
         );
     }
 
+    #[test]
+    fn merge_distributions() {
+        let samples = (1..=100)
+            .map(|value| Sample {
+                value: value as f64,
+                rate: 1,
+            })
+            .collect::<Vec<_>>();
+
+        let merged = merge_distribution(samples, 0.1);
+        assert_eq!(merged.len(), 12);
+        assert_eq!(
+            merged[0],
+            Sample {
+                value: 1.0,
+                rate: 1
+            }
+        );
+        assert_eq!(
+            merged[1],
+            Sample {
+                value: 6.5,
+                rate: 10
+            }
+        );
+        assert_eq!(
+            merged[10],
+            Sample {
+                value: 95.5,
+                rate: 8
+            }
+        );
+        assert_eq!(
+            merged[11],
+            Sample {
+                value: 100.0,
+                rate: 1
+            }
+        );
+        assert!(merged.iter().all(|sample| sample.rate <= 10));
+        assert_eq!(
+            merged
+                .iter()
+                .map(|sample| sample.value * sample.rate as f64)
+                .sum::<f64>(),
+            5050.0
+        );
+    }
+
     fn rebuffer_absolute_aggregated_histograms<State: MetricNormalize>() -> Buffer {
         let mut events = Vec::new();
         for _ in 2..5 {
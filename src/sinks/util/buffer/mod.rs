@@ -1,8 +1,9 @@
 use super::batch::{
     err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
 };
+use super::compressor::zstd_encoder;
 use flate2::write::GzEncoder;
-use std::io::Write;
+use std::{fmt, io::Write};
 
 pub mod compression;
 pub mod json;
@@ -12,7 +13,7 @@ pub mod metrics;
 pub mod partition;
 pub mod vec;
 
-pub use compression::{Compression, GZIP_FAST};
+pub use compression::{Compression, ZstdDictionary, GZIP_FAST};
 pub use partition::{Partition, PartitionBuffer, PartitionInnerBuffer};
 
 #[derive(Debug)]
@@ -24,10 +25,20 @@ pub struct Buffer {
     compression: Compression,
 }
 
-#[derive(Debug)]
 pub enum InnerBuffer {
     Plain(Vec<u8>),
     Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<Vec<u8>>),
+}
+
+impl fmt::Debug for InnerBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InnerBuffer::Plain(inner) => f.debug_tuple("Plain").field(inner).finish(),
+            InnerBuffer::Gzip(inner) => f.debug_tuple("Gzip").field(inner).finish(),
+            InnerBuffer::Zstd(inner) => f.debug_tuple("Zstd").field(inner.get_ref()).finish(),
+        }
+    }
 }
 
 impl Buffer {
@@ -49,6 +60,10 @@ impl Buffer {
             match compression {
                 Compression::None => InnerBuffer::Plain(buffer),
                 Compression::Gzip(level) => InnerBuffer::Gzip(GzEncoder::new(buffer, level)),
+                Compression::Zstd { level, dictionary } => InnerBuffer::Zstd(
+                    zstd_encoder(buffer, level, dictionary)
+                        .expect("This can't fail because the inner writer is a Vec"),
+                ),
             }
         })
     }
@@ -62,6 +77,9 @@ impl Buffer {
             InnerBuffer::Gzip(inner) => {
                 inner.write_all(input).unwrap();
             }
+            InnerBuffer::Zstd(inner) => {
+                inner.write_all(input).unwrap();
+            }
         }
     }
 
@@ -71,6 +89,7 @@ impl Buffer {
             .map(|inner| match inner {
                 InnerBuffer::Plain(inner) => inner.is_empty(),
                 InnerBuffer::Gzip(inner) => inner.get_ref().is_empty(),
+                InnerBuffer::Zstd(inner) => inner.get_ref().is_empty(),
             })
             .unwrap_or(true)
     }
@@ -119,6 +138,9 @@ impl Batch for Buffer {
             Some(InnerBuffer::Gzip(inner)) => inner
                 .finish()
                 .expect("This can't fail because the inner writer is a Vec"),
+            Some(InnerBuffer::Zstd(inner)) => inner
+                .finish()
+                .expect("This can't fail because the inner writer is a Vec"),
             None => Vec::new(),
         }
     }
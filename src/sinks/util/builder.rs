@@ -1,6 +1,9 @@
 use std::{fmt, future::Future, hash::Hash, num::NonZeroUsize, pin::Pin, sync::Arc};
 
-use futures_util::Stream;
+use futures_util::{
+    stream::{self, FlatMap, Iter},
+    Stream, StreamExt,
+};
 use tower::Service;
 use vector_core::{
     buffers::{Ackable, Acker},
@@ -10,7 +13,7 @@ use vector_core::{
     ByteSizeOf,
 };
 
-use super::RequestBuilder;
+use super::{IncrementalRequestBuilder, RequestBuilder};
 
 impl<T: ?Sized> SinkBuilderExt for T where T: Stream {}
 
@@ -92,6 +95,40 @@ pub trait SinkBuilderExt: Stream {
         })
     }
 
+    /// Constructs a [`Stream`] which transforms the input into as many requests as needed to keep
+    /// each of them within the size limits of the builder.
+    ///
+    /// Each input is transformed concurrently, up to the given limit, and the requests built from
+    /// it are yielded in order.
+    #[allow(clippy::type_complexity)]
+    fn incremental_request_builder<B>(
+        self,
+        limit: Option<NonZeroUsize>,
+        builder: B,
+    ) -> FlatMap<
+        ConcurrentMap<Self, Vec<Result<B::Request, B::Error>>>,
+        Iter<std::vec::IntoIter<Result<B::Request, B::Error>>>,
+        fn(
+            Vec<Result<B::Request, B::Error>>,
+        ) -> Iter<std::vec::IntoIter<Result<B::Request, B::Error>>>,
+    >
+    where
+        Self: Sized,
+        Self::Item: Send + 'static,
+        B: IncrementalRequestBuilder<<Self as Stream>::Item> + Send + Sync + 'static,
+        B::Error: Send,
+        B::Request: Send,
+    {
+        let builder = Arc::new(builder);
+
+        self.concurrent_map(limit, move |input| {
+            let builder = Arc::clone(&builder);
+
+            Box::pin(async move { builder.build_requests(input) })
+        })
+        .flat_map(stream::iter as fn(_) -> _)
+    }
+
     /// Creates a [`Driver`] that uses the configured event stream as the input to the given
     /// service.
     ///
@@ -2,11 +2,26 @@ use std::io;
 
 use flate2::write::GzEncoder;
 
-use super::Compression;
+use super::{buffer::compression::ZstdDictionary, Compression};
+
+/// Creates a zstd encoder writing to `buffer`, primed with the dictionary if any.
+pub(crate) fn zstd_encoder(
+    buffer: Vec<u8>,
+    level: i32,
+    dictionary: Option<ZstdDictionary>,
+) -> io::Result<zstd::stream::write::Encoder<Vec<u8>>> {
+    match dictionary {
+        Some(dictionary) => {
+            zstd::stream::write::Encoder::with_dictionary(buffer, level, dictionary.as_bytes())
+        }
+        None => zstd::stream::write::Encoder::new(buffer, level),
+    }
+}
 
 enum Writer {
     Plain(Vec<u8>),
     Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<Vec<u8>>),
 }
 
 impl From<Compression> for Writer {
@@ -15,6 +30,10 @@ impl From<Compression> for Writer {
         match compression {
             Compression::None => Writer::Plain(buffer),
             Compression::Gzip(level) => Writer::Gzip(GzEncoder::new(buffer, level)),
+            Compression::Zstd { level, dictionary } => Writer::Zstd(
+                zstd_encoder(buffer, level, dictionary)
+                    .expect("zstd writer should not fail to initialize"),
+            ),
         }
     }
 }
@@ -24,6 +43,7 @@ impl io::Write for Writer {
         match self {
             Writer::Plain(inner_buf) => inner_buf.write(buf),
             Writer::Gzip(writer) => writer.write(buf),
+            Writer::Zstd(writer) => writer.write(buf),
         }
     }
 
@@ -31,6 +51,7 @@ impl io::Write for Writer {
         match self {
             Writer::Plain(_) => Ok(()),
             Writer::Gzip(writer) => writer.flush(),
+            Writer::Zstd(writer) => writer.flush(),
         }
     }
 }
@@ -50,6 +71,9 @@ impl Compressor {
             Writer::Gzip(writer) => writer
                 .finish()
                 .expect("gzip writer should not fail to finish"),
+            Writer::Zstd(writer) => writer
+                .finish()
+                .expect("zstd writer should not fail to finish"),
         }
     }
 }
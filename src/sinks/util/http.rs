@@ -12,7 +12,7 @@ use crate::{
 };
 use bytes::{Buf, Bytes};
 use futures::{future::BoxFuture, ready, Sink};
-use http::{uri::PathAndQuery, uri::Scheme, StatusCode, Uri};
+use http::{header::RETRY_AFTER, uri::PathAndQuery, uri::Scheme, StatusCode, Uri};
 use hyper::{body, Body};
 use indexmap::IndexMap;
 use pin_project::pin_project;
@@ -483,6 +483,22 @@ impl RetryLogic for HttpRetryLogic {
     }
 }
 
+/// The delay a `429 Too Many Requests` response asks for in its `Retry-After`
+/// header, if it's given in seconds.
+pub fn retry_after(response: &hyper::Response<Bytes>) -> Option<Duration> {
+    if response.status() != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
 /// A helper config struct
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -4,13 +4,26 @@ pub mod buffer;
 pub mod builder;
 pub mod compressor;
 pub mod encoding;
+#[cfg(any(
+    feature = "sinks-vector",
+    feature = "sinks-gcp",
+    feature = "sinks-opentelemetry"
+))]
+pub mod grpc;
 pub mod http;
+#[cfg(any(
+    feature = "sinks-aws_s3",
+    feature = "sinks-azure_blob",
+    feature = "sinks-gcp"
+))]
+pub mod parquet;
 pub mod request_builder;
 pub mod retries;
 pub mod service;
 pub mod sink;
 pub mod socket_bytes_sink;
 pub mod statistic;
+pub mod tags;
 pub mod tcp;
 #[cfg(test)]
 pub mod test;
@@ -33,12 +46,15 @@ pub use buffer::vec::{EncodedLength, VecBuffer};
 pub use buffer::{Buffer, Compression, PartitionBuffer, PartitionInnerBuffer};
 pub use builder::SinkBuilderExt;
 pub use compressor::Compressor;
-pub use request_builder::RequestBuilder;
+pub use request_builder::{
+    EncodedPayload, IncrementalRequestBuilder, PayloadFraming, RequestBuilder, RequestLimits,
+};
 pub use service::{
     Concurrency, ServiceBuilderExt, TowerBatchedSink, TowerPartitionSink, TowerRequestConfig,
     TowerRequestLayer, TowerRequestSettings,
 };
 pub use sink::{BatchSink, PartitionBatchSink, StreamSink};
+pub use tags::MultiValueTagsConfig;
 pub use uri::UriSerde;
 
 #[derive(Debug, Snafu)]
@@ -108,11 +124,22 @@ pub enum Encoding {
 * the given encoding. If there are any errors encoding the event, logs a warning
 * and returns None.
 **/
-pub fn encode_log(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<Bytes> {
+pub fn encode_log(event: Event, encoding: &EncodingConfig<Encoding>) -> Option<Bytes> {
+    encode_log_unframed(event, encoding).map(|mut b| {
+        b.push(b'\n');
+        Bytes::from(b)
+    })
+}
+
+/// Encodes the event like `encode_log`, without the trailing newline.
+pub fn encode_log_unframed(
+    mut event: Event,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<Vec<u8>> {
     encoding.apply_rules(&mut event);
     let log = event.into_log();
 
-    let b = match encoding.codec() {
+    match encoding.codec() {
         Encoding::Json => serde_json::to_vec(&log),
         Encoding::Text => {
             let bytes = log
@@ -121,12 +148,7 @@ pub fn encode_log(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Opti
                 .unwrap_or_default();
             Ok(bytes)
         }
-    };
-
-    b.map(|mut b| {
-        b.push(b'\n');
-        Bytes::from(b)
-    })
+    }
     .map_err(|error| error!(message = "Unable to encode.", %error))
     .ok()
 }
@@ -0,0 +1,486 @@
+//! Encoding of batches of log events into Parquet files, for the sinks
+//! writing to object stores.
+//!
+//! Columns are typed by the configured schema rather than inferred from the
+//! events, so every file of a sink shares the same schema and can be queried
+//! as a single external table. All the columns are optional: a field missing
+//! from an event, or holding a value not matching its column's type, is
+//! written as a null.
+
+use super::batch::{
+    err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
+};
+use crate::{
+    event::{LogEvent, Value},
+    internal_events::ParquetValuesMismatched,
+};
+use ::parquet::{
+    basic::{Compression, ConvertedType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    errors::ParquetError,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, InMemoryWriteableCursor, RowGroupWriter, SerializedFileWriter},
+    },
+    schema::types::Type,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::sync::Arc;
+use vector_core::ByteSizeOf;
+
+/// The MIME type of Parquet files.
+pub const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+#[derive(Debug, Snafu)]
+enum ParquetConfigError {
+    #[snafu(display("The Parquet schema must have at least one column."))]
+    EmptySchema,
+    #[snafu(display("The Parquet row group size must be greater than zero."))]
+    ZeroRowGroupSize,
+    #[snafu(display("Invalid Parquet schema: {}", source))]
+    InvalidSchema { source: ParquetError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetConfig {
+    /// The columns of the files, named after the fields they're read from.
+    pub schema: IndexMap<String, ColumnType>,
+    /// The maximum number of rows of a row group. Batches with more events
+    /// are split into several row groups.
+    #[serde(default = "default_row_group_size")]
+    pub row_group_size: usize,
+    #[serde(default)]
+    pub compression: ParquetCompression,
+}
+
+const fn default_row_group_size() -> usize {
+    100_000
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    String,
+    Int64,
+    Double,
+    Boolean,
+    /// Microseconds since the Unix epoch, in UTC.
+    Timestamp,
+    /// Any value, serialized as JSON.
+    Json,
+}
+
+impl ColumnType {
+    const fn as_str(self) -> &'static str {
+        match self {
+            ColumnType::String => "string",
+            ColumnType::Int64 => "int64",
+            ColumnType::Double => "double",
+            ColumnType::Boolean => "boolean",
+            ColumnType::Timestamp => "timestamp",
+            ColumnType::Json => "json",
+        }
+    }
+
+    const fn physical_type(self) -> PhysicalType {
+        match self {
+            ColumnType::String | ColumnType::Json => PhysicalType::BYTE_ARRAY,
+            ColumnType::Int64 | ColumnType::Timestamp => PhysicalType::INT64,
+            ColumnType::Double => PhysicalType::DOUBLE,
+            ColumnType::Boolean => PhysicalType::BOOLEAN,
+        }
+    }
+
+    const fn converted_type(self) -> ConvertedType {
+        match self {
+            ColumnType::String => ConvertedType::UTF8,
+            ColumnType::Json => ConvertedType::JSON,
+            ColumnType::Timestamp => ConvertedType::TIMESTAMP_MICROS,
+            ColumnType::Int64 | ColumnType::Double | ColumnType::Boolean => ConvertedType::NONE,
+        }
+    }
+}
+
+/// The compression of the columns of the files.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    None,
+    #[derivative(Default)]
+    Snappy,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Zstd => Compression::ZSTD,
+        }
+    }
+}
+
+impl ParquetConfig {
+    pub fn build_encoder(&self) -> crate::Result<ParquetEncoder> {
+        if self.schema.is_empty() {
+            return Err(ParquetConfigError::EmptySchema.into());
+        }
+        if self.row_group_size == 0 {
+            return Err(ParquetConfigError::ZeroRowGroupSize.into());
+        }
+
+        let mut fields = self
+            .schema
+            .iter()
+            .map(|(name, column_type)| {
+                Type::primitive_type_builder(name, column_type.physical_type())
+                    .with_converted_type(column_type.converted_type())
+                    .with_repetition(Repetition::OPTIONAL)
+                    .build()
+                    .map(Arc::new)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|source| ParquetConfigError::InvalidSchema { source })?;
+        let schema = Type::group_type_builder("vector")
+            .with_fields(&mut fields)
+            .build()
+            .map_err(|source| ParquetConfigError::InvalidSchema { source })?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(self.compression.into())
+            .set_created_by(format!("vector {}", crate::get_version()))
+            .build();
+
+        Ok(ParquetEncoder {
+            columns: Arc::new(
+                self.schema
+                    .iter()
+                    .map(|(name, column_type)| (name.clone(), *column_type))
+                    .collect(),
+            ),
+            schema: Arc::new(schema),
+            properties: Arc::new(properties),
+            row_group_size: self.row_group_size,
+        })
+    }
+}
+
+/// Writes batches of log events as Parquet files.
+#[derive(Clone, Debug)]
+pub struct ParquetEncoder {
+    columns: Arc<Vec<(String, ColumnType)>>,
+    schema: Arc<Type>,
+    properties: Arc<WriterProperties>,
+    row_group_size: usize,
+}
+
+impl ParquetEncoder {
+    pub fn encode(&self, logs: &[LogEvent]) -> Result<Vec<u8>, ParquetError> {
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer = SerializedFileWriter::new(
+            cursor.clone(),
+            Arc::clone(&self.schema),
+            Arc::clone(&self.properties),
+        )?;
+
+        for rows in logs.chunks(self.row_group_size) {
+            let mut row_group = writer.next_row_group()?;
+            for (field, column_type) in self.columns.iter() {
+                let mut column = row_group
+                    .next_column()?
+                    .expect("The schema has a column per configured field.");
+                let mismatched = write_column(&mut column, field, *column_type, rows)?;
+                row_group.close_column(column)?;
+
+                if mismatched > 0 {
+                    emit!(&ParquetValuesMismatched {
+                        field,
+                        column_type: column_type.as_str(),
+                        count: mismatched,
+                    });
+                }
+            }
+            writer.close_row_group(row_group)?;
+        }
+        writer.close()?;
+
+        Ok(cursor.data())
+    }
+}
+
+/// Batches log events, and writes them as a Parquet file once full. The
+/// size of the batch is the size of the events in memory, as the size of the
+/// file is only known once written.
+#[derive(Clone, Debug)]
+pub struct ParquetBuffer {
+    logs: Vec<LogEvent>,
+    bytes: usize,
+    settings: BatchSize<Self>,
+    encoder: ParquetEncoder,
+}
+
+impl ParquetBuffer {
+    pub const fn new(settings: BatchSize<Self>, encoder: ParquetEncoder) -> Self {
+        Self {
+            logs: Vec::new(),
+            bytes: 0,
+            settings,
+            encoder,
+        }
+    }
+}
+
+impl Batch for ParquetBuffer {
+    type Input = LogEvent;
+    type Output = Vec<u8>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        Ok(config.get_settings_or_default(defaults))
+    }
+
+    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+        let size = item.size_of();
+        let new_bytes = self.bytes + size;
+        if self.is_empty() && size > self.settings.bytes {
+            err_event_too_large(size, self.settings.bytes)
+        } else if self.logs.len() >= self.settings.events || new_bytes > self.settings.bytes {
+            PushResult::Overflow(item)
+        } else {
+            self.logs.push(item);
+            self.bytes = new_bytes;
+            PushResult::Ok(
+                self.logs.len() >= self.settings.events || new_bytes >= self.settings.bytes,
+            )
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.logs.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        Self::new(self.settings, self.encoder.clone())
+    }
+
+    fn finish(self) -> Self::Output {
+        self.encoder
+            .encode(&self.logs)
+            .expect("This can't fail because the columns follow the schema and the writer is a Vec")
+    }
+
+    fn num_items(&self) -> usize {
+        self.logs.len()
+    }
+}
+
+/// Writes the values of `field` in `rows`, returning how many of them didn't
+/// match the column's type and were written as nulls.
+fn write_column(
+    column: &mut ColumnWriter,
+    field: &str,
+    column_type: ColumnType,
+    rows: &[LogEvent],
+) -> Result<usize, ParquetError> {
+    match (column, column_type) {
+        (ColumnWriter::ByteArrayColumnWriter(writer), ColumnType::String) => {
+            let column = Column::collect(rows, field, |value| match value {
+                Value::Bytes(bytes) => Some(ByteArray::from(bytes.to_vec())),
+                Value::Map(_) | Value::Array(_) => None,
+                value => Some(ByteArray::from(value.to_string_lossy().into_bytes())),
+            });
+            writer.write_batch(&column.values, Some(&column.def_levels), None)?;
+            Ok(column.mismatched)
+        }
+        (ColumnWriter::ByteArrayColumnWriter(writer), ColumnType::Json) => {
+            let column = Column::collect(rows, field, |value| {
+                serde_json::to_vec(value).ok().map(ByteArray::from)
+            });
+            writer.write_batch(&column.values, Some(&column.def_levels), None)?;
+            Ok(column.mismatched)
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ColumnType::Int64) => {
+            let column = Column::collect(rows, field, |value| match value {
+                Value::Integer(value) => Some(*value),
+                _ => None,
+            });
+            writer.write_batch(&column.values, Some(&column.def_levels), None)?;
+            Ok(column.mismatched)
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ColumnType::Timestamp) => {
+            let column = Column::collect(rows, field, |value| match value {
+                Value::Timestamp(timestamp) => Some(
+                    timestamp.timestamp() * 1_000_000
+                        + i64::from(timestamp.timestamp_subsec_micros()),
+                ),
+                _ => None,
+            });
+            writer.write_batch(&column.values, Some(&column.def_levels), None)?;
+            Ok(column.mismatched)
+        }
+        (ColumnWriter::DoubleColumnWriter(writer), ColumnType::Double) => {
+            let column = Column::collect(rows, field, |value| match value {
+                Value::Float(value) => Some(*value),
+                Value::Integer(value) => Some(*value as f64),
+                _ => None,
+            });
+            writer.write_batch(&column.values, Some(&column.def_levels), None)?;
+            Ok(column.mismatched)
+        }
+        (ColumnWriter::BoolColumnWriter(writer), ColumnType::Boolean) => {
+            let column = Column::collect(rows, field, |value| match value {
+                Value::Boolean(value) => Some(*value),
+                _ => None,
+            });
+            writer.write_batch(&column.values, Some(&column.def_levels), None)?;
+            Ok(column.mismatched)
+        }
+        _ => unreachable!("The column writers follow the schema."),
+    }
+}
+
+/// The non-null values of a column, and the definition levels telling which
+/// rows they belong to.
+struct Column<T> {
+    values: Vec<T>,
+    def_levels: Vec<i16>,
+    mismatched: usize,
+}
+
+impl<T> Column<T> {
+    fn collect(rows: &[LogEvent], field: &str, convert: impl Fn(&Value) -> Option<T>) -> Self {
+        let mut column = Column {
+            values: Vec::with_capacity(rows.len()),
+            def_levels: Vec::with_capacity(rows.len()),
+            mismatched: 0,
+        };
+
+        for log in rows {
+            let value = match log.get(field) {
+                None | Some(Value::Null) => None,
+                Some(value) => {
+                    let converted = convert(value);
+                    if converted.is_none() {
+                        column.mismatched += 1;
+                    }
+                    converted
+                }
+            };
+
+            match value {
+                Some(value) => {
+                    column.values.push(value);
+                    column.def_levels.push(1);
+                }
+                None => column.def_levels.push(0),
+            }
+        }
+
+        column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::parquet::{
+        file::{
+            reader::{FileReader, SerializedFileReader},
+            serialized_reader::SliceableCursor,
+        },
+        record::Field,
+    };
+    use chrono::{TimeZone, Utc};
+
+    fn encoder(row_group_size: usize) -> ParquetEncoder {
+        toml::from_str::<ParquetConfig>(&format!(
+            r#"row_group_size = {}
+            compression = "zstd"
+            schema.message = "string"
+            schema.status = "int64"
+            schema.duration = "double"
+            schema.cached = "boolean"
+            schema.timestamp = "timestamp"
+            schema.tags = "json""#,
+            row_group_size
+        ))
+        .unwrap()
+        .build_encoder()
+        .unwrap()
+    }
+
+    fn log(message: &str, status: Value) -> LogEvent {
+        let mut log = LogEvent::from(message);
+        log.insert("status", status);
+        log.insert("duration", 1.5);
+        log.insert("cached", true);
+        log.insert("timestamp", Utc.timestamp(1_600_000_000, 250_000_000));
+        log.insert("tags.env", "prod");
+        log
+    }
+
+    #[test]
+    fn encodes_typed_columns() {
+        let logs = vec![
+            log("first", Value::Integer(200)),
+            log("second", Value::from("oops")),
+        ];
+        let bytes = encoder(10).encode(&logs).unwrap();
+
+        let reader = SerializedFileReader::new(SliceableCursor::new(bytes)).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert_eq!(reader.metadata().num_row_groups(), 1);
+
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        let columns = rows[0].get_column_iter().collect::<Vec<_>>();
+        assert_eq!(columns[0], &("message".into(), Field::Str("first".into())));
+        assert_eq!(columns[1], &("status".into(), Field::Long(200)));
+        assert_eq!(columns[2], &("duration".into(), Field::Double(1.5)));
+        assert_eq!(columns[3], &("cached".into(), Field::Bool(true)));
+        assert_eq!(
+            columns[4],
+            &(
+                "timestamp".into(),
+                Field::TimestampMicros(1_600_000_000_250_000)
+            )
+        );
+        assert_eq!(
+            columns[5],
+            &("tags".into(), Field::Str(r#"{"env":"prod"}"#.into()))
+        );
+
+        // Values not matching their column's type are nulls.
+        let columns = rows[1].get_column_iter().collect::<Vec<_>>();
+        assert_eq!(columns[1], &("status".into(), Field::Null));
+    }
+
+    #[test]
+    fn splits_batches_into_row_groups() {
+        let logs = (0..5)
+            .map(|i| log(&i.to_string(), Value::Integer(i)))
+            .collect::<Vec<_>>();
+        let bytes = encoder(2).encode(&logs).unwrap();
+
+        let reader = SerializedFileReader::new(SliceableCursor::new(bytes)).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 3);
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 5);
+    }
+
+    #[test]
+    fn rejects_empty_schema() {
+        let config = ParquetConfig {
+            schema: IndexMap::new(),
+            row_group_size: 10,
+            compression: ParquetCompression::Snappy,
+        };
+        assert!(config.build_encoder().is_err());
+    }
+}
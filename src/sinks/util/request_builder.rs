@@ -1,6 +1,15 @@
-use std::io;
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use vector_core::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    ByteSizeOf,
+};
 
 use super::{encoding::Encoder, Compression, Compressor};
+use crate::internal_events::LargeEventDropped;
 
 /// Generalized interface for defining how a batch of events will be turned into an request.
 pub trait RequestBuilder<Input> {
@@ -32,3 +41,348 @@ pub trait RequestBuilder<Input> {
     /// Builds a request for the given metadata and payload.
     fn build_request(&self, metadata: Self::Metadata, payload: Self::Payload) -> Self::Request;
 }
+
+/// Limits on the number of events and uncompressed bytes of a single request.
+#[derive(Clone, Copy, Debug)]
+pub struct RequestLimits {
+    pub max_bytes: usize,
+    pub max_events: usize,
+}
+
+/// How the encoded events of a request are framed into its payload: `[`, `,` and `]` for a JSON
+/// array, or no framing at all for concatenated JSON objects.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PayloadFraming {
+    pub prefix: &'static [u8],
+    pub delimiter: &'static [u8],
+    pub suffix: &'static [u8],
+}
+
+impl PayloadFraming {
+    pub const JSON_ARRAY: Self = Self {
+        prefix: b"[",
+        delimiter: b",",
+        suffix: b"]",
+    };
+}
+
+/// The payload of one of the requests built by an [`IncrementalRequestBuilder`].
+#[derive(Debug)]
+pub struct EncodedPayload<D = ()> {
+    /// The framed, and then compressed, events.
+    pub body: Vec<u8>,
+    pub finalizers: EventFinalizers,
+    pub events_len: usize,
+    pub events_byte_size: usize,
+    /// The length of the framed events, before compression.
+    pub uncompressed_len: usize,
+    /// The time spent encoding, framing and compressing the events.
+    pub encoding_duration: Duration,
+    /// The encoded length and details of the largest event in the payload.
+    pub largest_event: Option<(usize, D)>,
+}
+
+impl<D> Default for EncodedPayload<D> {
+    fn default() -> Self {
+        Self {
+            body: Vec::new(),
+            finalizers: EventFinalizers::default(),
+            events_len: 0,
+            events_byte_size: 0,
+            uncompressed_len: 0,
+            encoding_duration: Duration::default(),
+            largest_event: None,
+        }
+    }
+}
+
+/// Generalized interface for defining how a batch of events will be turned into requests, when
+/// requests are limited in size.
+///
+/// Events are encoded one at a time, and a new request is started whenever adding the next event
+/// would take the current one over the limits, so a batch turns into as many requests as needed.
+pub trait IncrementalRequestBuilder<Input> {
+    type Metadata: Clone;
+    type Event: Finalizable + ByteSizeOf;
+    type Request;
+    type Error: From<io::Error>;
+    /// What is kept of each event to identify it in diagnostics, as events are consumed by their
+    /// encoding.
+    type EventDetails;
+
+    fn compression(&self) -> Compression;
+
+    fn limits(&self) -> RequestLimits;
+
+    fn framing(&self) -> PayloadFraming {
+        PayloadFraming::default()
+    }
+
+    /// Splits apart the input into the metadata shared by all of its requests, such as the
+    /// partition key, and the events to encode.
+    fn split_input(&self, input: Input) -> (Self::Metadata, Vec<Self::Event>);
+
+    /// Captures the details identifying an event, right before it is encoded.
+    fn event_details(&self, event: &Self::Event) -> Self::EventDetails;
+
+    /// Encodes a single event, without any framing. Events encoding to nothing are skipped.
+    fn encode_event(&self, event: Self::Event, writer: &mut Vec<u8>) -> io::Result<()>;
+
+    /// Called with an event too large to fit in a request on its own, before it is dropped.
+    fn event_too_large(&self, _details: Self::EventDetails, _length: usize, _max_length: usize) {}
+
+    /// Builds a request for the given metadata and payload.
+    fn build_request(
+        &self,
+        metadata: Self::Metadata,
+        payload: EncodedPayload<Self::EventDetails>,
+    ) -> Self::Request;
+
+    /// Encodes the input into requests within the limits.
+    ///
+    /// Events too large to fit in a request on their own are rejected.
+    fn build_requests(&self, input: Input) -> Vec<Result<Self::Request, Self::Error>> {
+        let (metadata, events) = self.split_input(input);
+        let limits = self.limits();
+        let framing = self.framing();
+
+        let mut requests = Vec::new();
+        let mut current = EncodedPayload::default();
+        let mut encoded = Vec::new();
+        for mut event in events {
+            let finalizers = event.take_finalizers();
+            let byte_size = event.size_of();
+            let details = self.event_details(&event);
+
+            let start = Instant::now();
+            encoded.clear();
+            if let Err(error) = self.encode_event(event, &mut encoded) {
+                finalizers.update_status(EventStatus::Failed);
+                requests.push(Err(error.into()));
+                continue;
+            }
+            if encoded.is_empty() {
+                continue;
+            }
+            let elapsed = start.elapsed();
+
+            let length = framing.prefix.len() + encoded.len() + framing.suffix.len();
+            if length > limits.max_bytes {
+                emit!(&LargeEventDropped {
+                    length,
+                    max_length: limits.max_bytes,
+                });
+                self.event_too_large(details, length, limits.max_bytes);
+                finalizers.update_status(EventStatus::Failed);
+                continue;
+            }
+
+            if current.events_len > 0
+                && (current.events_len >= limits.max_events
+                    || current.body.len() + framing.delimiter.len() + length - framing.prefix.len()
+                        > limits.max_bytes)
+            {
+                let payload = std::mem::take(&mut current);
+                requests.push(
+                    finish_payload(payload, framing, self.compression())
+                        .map(|payload| self.build_request(metadata.clone(), payload))
+                        .map_err(Into::into),
+                );
+            }
+
+            if current.events_len == 0 {
+                current.body.extend_from_slice(framing.prefix);
+            } else {
+                current.body.extend_from_slice(framing.delimiter);
+            }
+            current.body.extend_from_slice(&encoded);
+            current.finalizers.merge(finalizers);
+            current.events_len += 1;
+            current.events_byte_size += byte_size;
+            current.encoding_duration += elapsed;
+            if current
+                .largest_event
+                .as_ref()
+                .map_or(true, |(largest, _)| encoded.len() > *largest)
+            {
+                current.largest_event = Some((encoded.len(), details));
+            }
+        }
+
+        if current.events_len > 0 {
+            requests.push(
+                finish_payload(current, framing, self.compression())
+                    .map(|payload| self.build_request(metadata, payload))
+                    .map_err(Into::into),
+            );
+        }
+
+        requests
+    }
+}
+
+fn finish_payload<D>(
+    mut payload: EncodedPayload<D>,
+    framing: PayloadFraming,
+    compression: Compression,
+) -> io::Result<EncodedPayload<D>> {
+    let start = Instant::now();
+    payload.body.extend_from_slice(framing.suffix);
+    payload.uncompressed_len = payload.body.len();
+    if compression != Compression::None {
+        let mut compressor = Compressor::from(compression);
+        compressor.write_all(&payload.body)?;
+        payload.body = compressor.into_inner();
+    }
+    payload.encoding_duration += start.elapsed();
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{BatchNotifier, BatchStatus, Event};
+    use std::sync::Mutex;
+
+    struct TestBuilder {
+        limits: RequestLimits,
+        too_large: Mutex<Vec<(String, usize)>>,
+    }
+
+    impl TestBuilder {
+        fn new(limits: RequestLimits) -> Self {
+            Self {
+                limits,
+                too_large: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl IncrementalRequestBuilder<Vec<Event>> for TestBuilder {
+        type Metadata = ();
+        type Event = Event;
+        type Request = (String, usize, EventFinalizers, String);
+        type Error = io::Error;
+        type EventDetails = String;
+
+        fn compression(&self) -> Compression {
+            Compression::None
+        }
+
+        fn limits(&self) -> RequestLimits {
+            self.limits
+        }
+
+        fn framing(&self) -> PayloadFraming {
+            PayloadFraming::JSON_ARRAY
+        }
+
+        fn split_input(&self, input: Vec<Event>) -> ((), Vec<Event>) {
+            ((), input)
+        }
+
+        fn event_details(&self, event: &Event) -> String {
+            event.as_log()["message"].to_string_lossy()
+        }
+
+        fn encode_event(&self, event: Event, writer: &mut Vec<u8>) -> io::Result<()> {
+            serde_json::to_writer(writer, &event.as_log()["message"].to_string_lossy())
+                .map_err(Into::into)
+        }
+
+        fn event_too_large(&self, details: String, length: usize, _max_length: usize) {
+            self.too_large.lock().unwrap().push((details, length));
+        }
+
+        fn build_request(&self, _: (), payload: EncodedPayload<String>) -> Self::Request {
+            assert_eq!(payload.uncompressed_len, payload.body.len());
+            let (_, largest) = payload.largest_event.unwrap();
+            (
+                String::from_utf8(payload.body).unwrap(),
+                payload.events_len,
+                payload.finalizers,
+                largest,
+            )
+        }
+    }
+
+    fn build(limits: RequestLimits, messages: &[&str]) -> Vec<(String, usize)> {
+        let events = messages
+            .iter()
+            .map(|message| Event::from(*message))
+            .collect();
+        TestBuilder::new(limits)
+            .build_requests(events)
+            .into_iter()
+            .map(|request| {
+                let (body, events_len, _, _) = request.unwrap();
+                (body, events_len)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_on_max_events() {
+        let limits = RequestLimits {
+            max_bytes: 1000,
+            max_events: 2,
+        };
+        assert_eq!(
+            build(limits, &["a", "b", "c"]),
+            vec![(r#"["a","b"]"#.into(), 2), (r#"["c"]"#.into(), 1)]
+        );
+    }
+
+    #[test]
+    fn splits_on_max_bytes() {
+        let limits = RequestLimits {
+            max_bytes: 11,
+            max_events: 100,
+        };
+        // `["aa","bb"]` is exactly 11 bytes.
+        assert_eq!(
+            build(limits, &["aa", "bb", "cc"]),
+            vec![(r#"["aa","bb"]"#.into(), 2), (r#"["cc"]"#.into(), 1)]
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_events() {
+        let limits = RequestLimits {
+            max_bytes: 6,
+            max_events: 100,
+        };
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let events = vec![
+            Event::from("a"),
+            Event::from("far too long").with_batch_notifier(&batch),
+        ];
+        drop(batch);
+
+        let builder = TestBuilder::new(limits);
+        let requests = builder.build_requests(events);
+        assert_eq!(requests.len(), 1);
+        let (body, events_len, _, _) = requests.into_iter().next().unwrap().unwrap();
+        assert_eq!((body.as_str(), events_len), (r#"["a"]"#, 1));
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Failed));
+        assert_eq!(
+            *builder.too_large.lock().unwrap(),
+            vec![("far too long".to_owned(), 16)]
+        );
+    }
+
+    #[test]
+    fn tracks_largest_event() {
+        let limits = RequestLimits {
+            max_bytes: 1000,
+            max_events: 100,
+        };
+        let events = vec![Event::from("a"), Event::from("ccc"), Event::from("bb")];
+
+        let mut requests = TestBuilder::new(limits).build_requests(events);
+        assert_eq!(requests.len(), 1);
+        let (body, _, _, largest) = requests.pop().unwrap().unwrap();
+        assert_eq!(body, r#"["a","ccc","bb"]"#);
+        assert_eq!(largest, "ccc");
+    }
+}
@@ -29,6 +29,12 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
         // Treat the default as the request is successful
         RetryAction::Successful
     }
+
+    /// How long the response asks to wait before retrying, overriding the
+    /// backoff of the retry policy.
+    fn retry_delay(&self, _response: &Self::Response) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,12 +83,15 @@ impl<L: RetryLogic> FixedRetryPolicy<L> {
         self.current_duration
     }
 
-    fn build_retry(&self) -> RetryPolicyFuture<L> {
+    fn build_retry(&self, delay: Option<Duration>) -> RetryPolicyFuture<L> {
         let policy = self.advance();
-        let delay = Box::pin(sleep(self.backoff()));
+        let delay = delay.unwrap_or_else(|| self.backoff());
 
-        debug!(message = "Retrying request.", delay_ms = %self.backoff().as_millis());
-        RetryPolicyFuture { delay, policy }
+        debug!(message = "Retrying request.", delay_ms = %delay.as_millis());
+        RetryPolicyFuture {
+            delay: Box::pin(sleep(delay)),
+            policy,
+        }
     }
 }
 
@@ -106,7 +115,7 @@ where
                     }
 
                     warn!(message = "Retrying after response.", reason = %reason);
-                    Some(self.build_retry())
+                    Some(self.build_retry(self.logic.retry_delay(response)))
                 }
 
                 RetryAction::DontRetry(reason) => {
@@ -125,7 +134,7 @@ where
                 if let Some(expected) = error.downcast_ref::<L::Error>() {
                     if self.logic.is_retriable_error(expected) {
                         warn!(message = "Retrying after error.", error = %expected);
-                        Some(self.build_retry())
+                        Some(self.build_retry(None))
                     } else {
                         error!(
                             message = "Non-retriable error; dropping the request.",
@@ -135,7 +144,7 @@ where
                     }
                 } else if error.downcast_ref::<Elapsed>().is_some() {
                     warn!("Request timed out. If this happens often while the events are actually reaching their destination, try decreasing `batch.max_bytes` and/or using `compression` if applicable. Alternatively `request.timeout_secs` can be increased.");
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
                 } else {
                     error!(
                         message = "Unexpected error type; dropping the request.",
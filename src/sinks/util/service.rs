@@ -6,6 +6,9 @@ use crate::sinks::util::retries::{FixedRetryPolicy, RetryLogic};
 pub use crate::sinks::util::service::concurrency::{concurrency_is_none, Concurrency};
 pub use crate::sinks::util::service::map::Map;
 use crate::sinks::util::service::map::MapLayer;
+pub use crate::sinks::util::service::shared_rate_limit::{
+    RateLimiterConfig, SharedRateLimit, SharedRateLimiter, SharedRateLimiters,
+};
 use crate::sinks::util::sink::{Response, ServiceLogic};
 use crate::sinks::util::{Batch, BatchSink, Partition, PartitionBatchSink};
 use serde::{Deserialize, Serialize};
@@ -21,8 +24,10 @@ use tower::{
 
 mod concurrency;
 mod map;
+mod shared_rate_limit;
 
-pub type Svc<S, L> = RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L>>;
+pub type Svc<S, L> =
+    RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, SharedRateLimit<Timeout<S>>>, L>>;
 pub type TowerBatchedSink<S, B, RL, SL> = BatchSink<Svc<S, RL>, B, SL>;
 pub type TowerPartitionSink<S, B, RL, K, SL> = PartitionBatchSink<Svc<S, RL>, B, K, SL>;
 
@@ -145,6 +150,7 @@ impl TowerRequestConfig {
                     .unwrap_or(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             ),
             adaptive_concurrency: self.adaptive_concurrency,
+            shared_rate_limiter: None,
         }
     }
 }
@@ -159,9 +165,17 @@ pub struct TowerRequestSettings {
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    /// A rate limit shared with other sinks, applied to every request
+    /// including retries.
+    pub shared_rate_limiter: Option<SharedRateLimiter>,
 }
 
 impl TowerRequestSettings {
+    pub fn with_shared_rate_limiter(mut self, limiter: Option<SharedRateLimiter>) -> Self {
+        self.shared_rate_limiter = limiter;
+        self
+    }
+
     pub fn retry_policy<L: RetryLogic>(&self, logic: L) -> FixedRetryPolicy<L> {
         FixedRetryPolicy::new(
             self.retry_attempts,
@@ -247,6 +261,7 @@ impl TowerRequestSettings {
                 retry_logic,
             ))
             .retry(policy)
+            .layer_fn(|service| SharedRateLimit::new(service, self.shared_rate_limiter.clone()))
             .timeout(self.timeout)
             .service(service)
     }
@@ -280,6 +295,9 @@ where
                 self.settings.rate_limit_duration,
             )
             .retry(policy)
+            .layer_fn(|service| {
+                SharedRateLimit::new(service, self.settings.shared_rate_limiter.clone())
+            })
             .timeout(self.settings.timeout)
             .service(inner);
 
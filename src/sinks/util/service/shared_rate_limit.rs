@@ -0,0 +1,270 @@
+//! Rate limits shared between sinks.
+//!
+//! Each sink's `request` settings limit the rate of that sink's requests only,
+//! but several sinks often send to the same vendor under a single, account-wide
+//! rate limit. Rate limiters configured in the top-level `rate_limiters` table
+//! can be referenced by any number of sinks, and limit the aggregate rate of
+//! their requests, retries included.
+
+use crate::internal_events::SharedRateLimitThrottled;
+use futures::ready;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{sleep_until, Instant, Sleep};
+use tower::Service;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimiterConfig {
+    /// The number of requests allowed per `duration_secs`.
+    pub num: u64,
+    #[serde(default = "default_duration_secs")]
+    pub duration_secs: u64,
+}
+
+const fn default_duration_secs() -> u64 {
+    1
+}
+
+lazy_static::lazy_static! {
+    /// Every rate limiter configured so far, so that sinks rebuilt by a reload
+    /// share the state of the sinks left running.
+    static ref REGISTRY: Mutex<HashMap<String, SharedRateLimiter>> = Mutex::default();
+}
+
+/// The rate limiters of a config, by name.
+#[derive(Clone, Debug, Default)]
+pub struct SharedRateLimiters(Arc<HashMap<String, SharedRateLimiter>>);
+
+impl SharedRateLimiters {
+    /// Gets the rate limiters of the config, creating those that are new and
+    /// updating those that changed.
+    pub fn from_config(configs: &IndexMap<String, RateLimiterConfig>) -> Self {
+        let mut registry = REGISTRY.lock().unwrap();
+        let limiters = configs
+            .iter()
+            .map(|(name, config)| {
+                let limiter = registry
+                    .entry(name.clone())
+                    .or_insert_with(|| SharedRateLimiter::new(name, *config));
+                limiter.configure(*config);
+                (name.clone(), limiter.clone())
+            })
+            .collect();
+        Self(Arc::new(limiters))
+    }
+
+    pub fn get(&self, name: &str) -> crate::Result<SharedRateLimiter> {
+        self.0.get(name).cloned().ok_or_else(|| {
+            format!("Rate limiter {:?} isn't defined in `rate_limiters`.", name).into()
+        })
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    config: RateLimiterConfig,
+    window_ends: Instant,
+    remaining: u64,
+}
+
+/// A rate limit shared between all the services it's applied to.
+#[derive(Clone, Debug)]
+pub struct SharedRateLimiter {
+    name: Arc<str>,
+    state: Arc<Mutex<State>>,
+}
+
+impl SharedRateLimiter {
+    pub fn new(name: &str, config: RateLimiterConfig) -> Self {
+        Self {
+            name: Arc::from(name),
+            state: Arc::new(Mutex::new(State {
+                config,
+                window_ends: Instant::now(),
+                remaining: 0,
+            })),
+        }
+    }
+
+    fn configure(&self, config: RateLimiterConfig) {
+        let mut state = self.state.lock().unwrap();
+        if state.config != config {
+            // Start over with a new window under the new limit.
+            state.config = config;
+            state.window_ends = Instant::now();
+            state.remaining = 0;
+        }
+    }
+
+    /// Takes a permit to send a request, or returns when the next one is
+    /// available.
+    fn try_acquire(&self) -> Result<(), Instant> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if now >= state.window_ends {
+            state.window_ends = now + Duration::from_secs(state.config.duration_secs);
+            state.remaining = state.config.num;
+        }
+
+        if state.remaining > 0 {
+            state.remaining -= 1;
+            Ok(())
+        } else {
+            Err(state.window_ends)
+        }
+    }
+}
+
+/// Applies an optional `SharedRateLimiter` to a service.
+pub struct SharedRateLimit<S> {
+    inner: S,
+    limiter: Option<SharedRateLimiter>,
+    has_permit: bool,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> SharedRateLimit<S> {
+    pub const fn new(inner: S, limiter: Option<SharedRateLimiter>) -> Self {
+        Self {
+            inner,
+            limiter,
+            has_permit: false,
+            sleep: None,
+        }
+    }
+}
+
+impl<S: Clone> Clone for SharedRateLimit<S> {
+    /// Clones don't share the permit this service may hold.
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone(), self.limiter.clone())
+    }
+}
+
+impl<S, Request> Service<Request> for SharedRateLimit<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(limiter) = &self.limiter {
+            while !self.has_permit {
+                if let Some(sleep) = &mut self.sleep {
+                    ready!(sleep.as_mut().poll(cx));
+                    self.sleep = None;
+                }
+
+                match limiter.try_acquire() {
+                    Ok(()) => self.has_permit = true,
+                    Err(next_window) => {
+                        emit!(&SharedRateLimitThrottled {
+                            rate_limiter: &limiter.name,
+                        });
+                        self.sleep = Some(Box::pin(sleep_until(next_window)));
+                    }
+                }
+            }
+        }
+
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if self.limiter.is_some() {
+            assert!(self.has_permit, "Service called before it was ready.");
+            self.has_permit = false;
+        }
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    fn counting_service(
+        sent: &Arc<AtomicUsize>,
+    ) -> impl Service<(), Response = (), Error = (), Future = future::Ready<Result<(), ()>>> + Clone
+    {
+        let sent = Arc::clone(sent);
+        tower::service_fn(move |()| {
+            sent.fetch_add(1, Ordering::Relaxed);
+            future::ready(Ok(()))
+        })
+    }
+
+    #[tokio::test]
+    async fn limits_requests_across_services() {
+        tokio::time::pause();
+        let limiter = SharedRateLimiter::new(
+            "test",
+            RateLimiterConfig {
+                num: 2,
+                duration_secs: 1,
+            },
+        );
+        let sent = Arc::new(AtomicUsize::new(0));
+        let mut first = SharedRateLimit::new(counting_service(&sent), Some(limiter.clone()));
+        let mut second = SharedRateLimit::new(counting_service(&sent), Some(limiter));
+
+        first.ready().await.unwrap().call(()).await.unwrap();
+        second.ready().await.unwrap().call(()).await.unwrap();
+        assert_eq!(sent.load(Ordering::Relaxed), 2);
+
+        // The limit is spent, so the next request waits for the next window.
+        let early = tokio::time::timeout(Duration::from_millis(500), first.ready()).await;
+        assert!(early.is_err());
+
+        first.ready().await.unwrap().call(()).await.unwrap();
+        assert_eq!(sent.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn passes_through_without_limiter() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let mut service = SharedRateLimit::new(counting_service(&sent), None);
+        for _ in 0..10 {
+            service.ready().await.unwrap().call(()).await.unwrap();
+        }
+        assert_eq!(sent.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn reuses_limiters_across_configs() {
+        let mut configs = IndexMap::new();
+        configs.insert(
+            "reused".to_owned(),
+            RateLimiterConfig {
+                num: 1,
+                duration_secs: 60,
+            },
+        );
+
+        let first = SharedRateLimiters::from_config(&configs)
+            .get("reused")
+            .unwrap();
+        let second = SharedRateLimiters::from_config(&configs)
+            .get("reused")
+            .unwrap();
+        assert!(Arc::ptr_eq(&first.state, &second.state));
+
+        assert!(SharedRateLimiters::from_config(&configs)
+            .get("missing")
+            .is_err());
+    }
+}
@@ -6,6 +6,8 @@ use std::cmp::Ordering;
 pub enum ValidationError {
     #[snafu(display("Quantiles must be in range [0.0,1.0]"))]
     QuantileOutOfRange,
+    #[snafu(display("Quantile error must be in range [0.0,0.5)"))]
+    QuantileErrorOutOfRange,
 }
 
 #[derive(Debug)]
@@ -116,6 +118,14 @@ pub fn validate_quantiles(quantiles: &[f64]) -> Result<(), ValidationError> {
     }
 }
 
+pub fn validate_quantile_error(quantile_error: f64) -> Result<(), ValidationError> {
+    if (0.0..0.5).contains(&quantile_error) {
+        Ok(())
+    } else {
+        Err(ValidationError::QuantileErrorOutOfRange)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
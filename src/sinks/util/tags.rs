@@ -0,0 +1,152 @@
+use crate::event::metric::Metric;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// How a sink encodes the tags of a metric that were set more than once.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MultiValueTagsConfig {
+    /// Repeat the tag for each of its values.
+    Full,
+    /// Only keep the last value of the tag.
+    Single,
+    /// Join all the values of the tag into one value.
+    Joined {
+        #[serde(default = "default_separator")]
+        separator: String,
+    },
+}
+
+impl Default for MultiValueTagsConfig {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
+fn default_separator() -> String {
+    ",".into()
+}
+
+impl MultiValueTagsConfig {
+    /// The tags of `metric`, sorted by key. Keys are only repeated with the
+    /// `full` strategy.
+    pub fn encode<'a>(&self, metric: &'a Metric) -> Vec<(&'a str, Cow<'a, str>)> {
+        match self {
+            Self::Full => metric
+                .iter_tag_values()
+                .map(|(key, value)| (key, Cow::Borrowed(value)))
+                .collect(),
+            Self::Single => metric
+                .tags()
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| (key.as_str(), Cow::Borrowed(value.as_str())))
+                .collect(),
+            Self::Joined { separator } => metric
+                .tags()
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| match metric.tag_values(key) {
+                    values if values.len() > 1 => {
+                        (key.as_str(), Cow::Owned(values.join(separator)))
+                    }
+                    _ => (key.as_str(), Cow::Borrowed(value.as_str())),
+                })
+                .collect(),
+        }
+    }
+
+    /// Rewrites the tags of `metric` to one value per key, for sinks that
+    /// can't repeat a key. The `full` strategy keeps the last value.
+    pub fn collapse(&self, metric: Metric) -> Metric {
+        if metric.series().multi_value_tags.is_none() {
+            return metric;
+        }
+        let tags = match self {
+            Self::Joined { .. } => Some(
+                self.encode(&metric)
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), value.into_owned()))
+                    .collect(),
+            ),
+            Self::Full | Self::Single => metric.tags().cloned(),
+        };
+        metric.with_tags(tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{MetricKind, MetricValue};
+
+    fn metric() -> Metric {
+        let mut metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        );
+        metric.insert_tag("code".into(), "200".into());
+        metric.add_tag_value("host_group".into(), "web".into());
+        metric.add_tag_value("host_group".into(), "frontend".into());
+        metric
+    }
+
+    fn encode(config: &MultiValueTagsConfig) -> Vec<(String, String)> {
+        config
+            .encode(&metric())
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.into_owned()))
+            .collect()
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_owned(), value.to_owned())
+    }
+
+    #[test]
+    fn encodes_full() {
+        assert_eq!(
+            encode(&MultiValueTagsConfig::Full),
+            vec![
+                pair("code", "200"),
+                pair("host_group", "web"),
+                pair("host_group", "frontend")
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_single() {
+        assert_eq!(
+            encode(&MultiValueTagsConfig::Single),
+            vec![pair("code", "200"), pair("host_group", "frontend")]
+        );
+    }
+
+    #[test]
+    fn encodes_joined() {
+        let config = MultiValueTagsConfig::Joined {
+            separator: "|".into(),
+        };
+        assert_eq!(
+            encode(&config),
+            vec![pair("code", "200"), pair("host_group", "web|frontend")]
+        );
+
+        let collapsed = config.collapse(metric());
+        assert_eq!(collapsed.tag_values("host_group"), vec!["web|frontend"]);
+        assert_eq!(collapsed.series().multi_value_tags, None);
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: MultiValueTagsConfig = toml::from_str(r#"strategy = "joined""#).unwrap();
+        assert_eq!(
+            config,
+            MultiValueTagsConfig::Joined {
+                separator: ",".into()
+            }
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! Load balancing of the `vector` sink across several downstream Vector
+//! instances.
+//!
+//! Endpoints failing repeatedly are ejected from the rotation, and put back
+//! once they report being healthy again. When every endpoint is ejected,
+//! requests are sent regardless of health rather than failing outright.
+
+use super::v2::{Client, Error, VectorGrpcRetryLogic};
+use crate::{
+    event::{proto::EventWrapper, Event},
+    internal_events::{TemplateRenderingFailed, VectorEndpointEjected, VectorEndpointReinstated},
+    proto::vector as proto,
+    sinks::util::{buffer::PartitionInnerBuffer, retries::RetryLogic},
+    template::Template,
+};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    hash::Hasher,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower::Service;
+use twox_hash::XxHash64;
+
+/// The number of points each endpoint gets on the hash ring, spreading keys
+/// evenly across endpoints.
+const VIRTUAL_NODES: usize = 64;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    /// Send batches to each endpoint in turn.
+    RoundRobin,
+    /// Send batches to the endpoint with the fewest requests in flight.
+    LeastInFlight,
+    /// Send events to the endpoint their rendered `key` hashes to.
+    ConsistentHash,
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Strategy::RoundRobin
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LoadBalancingConfig {
+    #[serde(default)]
+    pub strategy: Strategy,
+    /// The template rendering the key events are hashed by with the
+    /// `consistent_hash` strategy.
+    pub key: Option<Template>,
+    /// The number of consecutive failed requests after which an endpoint is
+    /// ejected.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// The interval at which ejected endpoints are checked for health.
+    #[serde(default = "default_ejection_secs")]
+    pub ejection_secs: u64,
+}
+
+const fn default_failure_threshold() -> u32 {
+    3
+}
+
+const fn default_ejection_secs() -> u64 {
+    30
+}
+
+impl Default for LoadBalancingConfig {
+    fn default() -> Self {
+        Self {
+            strategy: Strategy::default(),
+            key: None,
+            failure_threshold: default_failure_threshold(),
+            ejection_secs: default_ejection_secs(),
+        }
+    }
+}
+
+impl LoadBalancingConfig {
+    pub(super) fn validate(&self) -> crate::Result<()> {
+        match (self.strategy, &self.key) {
+            (Strategy::ConsistentHash, None) => {
+                Err("`load_balancing.key` must be set with the `consistent_hash` strategy".into())
+            }
+            (Strategy::RoundRobin | Strategy::LeastInFlight, Some(_)) => {
+                Err("`load_balancing.key` is only used with the `consistent_hash` strategy".into())
+            }
+            _ if self.failure_threshold == 0 => {
+                Err("`load_balancing.failure_threshold` must be at least 1".into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    address: String,
+    client: Client,
+    in_flight: AtomicUsize,
+    failures: AtomicU32,
+    ejected: AtomicBool,
+}
+
+impl Endpoint {
+    fn is_healthy(&self) -> bool {
+        !self.ejected.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    endpoints: Vec<Endpoint>,
+    strategy: Strategy,
+    key: Option<Template>,
+    /// Sorted points of the hash ring, and the endpoints they belong to.
+    ring: Vec<(u64, usize)>,
+    next: AtomicUsize,
+    failure_threshold: u32,
+    ejection_duration: Duration,
+}
+
+/// A service spreading batches of events across endpoints.
+///
+/// Batches are keyed by the endpoint they're meant for, which is only set
+/// with the `consistent_hash` strategy. Keyed batches go to the next healthy
+/// endpoint when theirs is ejected, so that all the keys of an endpoint move
+/// together.
+#[derive(Debug, Clone)]
+pub(super) struct Balancer {
+    inner: Arc<Inner>,
+}
+
+impl Balancer {
+    pub(super) fn new(endpoints: Vec<(String, Client)>, config: &LoadBalancingConfig) -> Self {
+        let mut ring = endpoints
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (address, _))| {
+                (0..VIRTUAL_NODES).map(move |node| (hash(&format!("{}-{}", address, node)), index))
+            })
+            .collect::<Vec<_>>();
+        ring.sort_unstable();
+
+        let endpoints = endpoints
+            .into_iter()
+            .map(|(address, client)| Endpoint {
+                address,
+                client,
+                in_flight: AtomicUsize::new(0),
+                failures: AtomicU32::new(0),
+                ejected: AtomicBool::new(false),
+            })
+            .collect();
+
+        Self {
+            inner: Arc::new(Inner {
+                endpoints,
+                strategy: config.strategy,
+                key: config.key.clone(),
+                ring,
+                next: AtomicUsize::new(0),
+                failure_threshold: config.failure_threshold,
+                ejection_duration: Duration::from_secs(config.ejection_secs),
+            }),
+        }
+    }
+
+    /// The endpoint the event belongs to, if the strategy pins events to
+    /// endpoints.
+    pub(super) fn partition(&self, event: &Event) -> Option<usize> {
+        let key = self.inner.key.as_ref()?;
+        match key.render_string(event) {
+            Ok(key) => Some(self.ring_lookup(hash(&key))),
+            Err(error) => {
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some("load_balancing.key"),
+                    drop_event: false,
+                });
+                None
+            }
+        }
+    }
+
+    fn ring_lookup(&self, hash: u64) -> usize {
+        let ring = &self.inner.ring;
+        let position = ring.partition_point(|(point, _)| *point < hash);
+        ring[position % ring.len()].1
+    }
+
+    /// Picks the endpoint to send a batch to.
+    fn select(&self, partition: Option<usize>) -> usize {
+        let endpoints = &self.inner.endpoints;
+        let count = endpoints.len();
+
+        // Walk the endpoints from `start`, skipping ejected ones unless all
+        // of them are.
+        let first_healthy = |start: usize| {
+            (0..count)
+                .map(|offset| (start + offset) % count)
+                .find(|&index| endpoints[index].is_healthy())
+                .unwrap_or(start % count)
+        };
+
+        match (partition, self.inner.strategy) {
+            (Some(index), _) => first_healthy(index),
+            (None, Strategy::LeastInFlight) => {
+                let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+                let healthy = (0..count)
+                    .map(|offset| (start + offset) % count)
+                    .filter(|&index| endpoints[index].is_healthy())
+                    .min_by_key(|&index| endpoints[index].in_flight.load(Ordering::Relaxed));
+                healthy.unwrap_or(start % count)
+            }
+            (None, _) => first_healthy(self.inner.next.fetch_add(1, Ordering::Relaxed)),
+        }
+    }
+
+    fn record(&self, index: usize, result: &Result<(), Error>) {
+        let endpoint = &self.inner.endpoints[index];
+        match result {
+            Ok(()) => endpoint.failures.store(0, Ordering::Relaxed),
+            // Only failures that may be transient reflect the endpoint's
+            // health, others are caused by the events themselves.
+            Err(error) if VectorGrpcRetryLogic.is_retriable_error(error) => {
+                let failures = endpoint.failures.fetch_add(1, Ordering::Relaxed) + 1;
+                if failures >= self.inner.failure_threshold
+                    && self.inner.endpoints.len() > 1
+                    && !endpoint.ejected.swap(true, Ordering::Relaxed)
+                {
+                    emit!(&VectorEndpointEjected {
+                        endpoint: &endpoint.address,
+                        failures,
+                    });
+                    tokio::spawn(self.clone().probe(index));
+                }
+            }
+            Err(_) => (),
+        }
+    }
+
+    /// Checks the health of an ejected endpoint until it can be reinstated.
+    async fn probe(self, index: usize) {
+        let endpoint = &self.inner.endpoints[index];
+        loop {
+            tokio::time::sleep(self.inner.ejection_duration).await;
+
+            let mut client = endpoint.client.clone();
+            let serving = client
+                .health_check(proto::HealthCheckRequest {})
+                .await
+                .ok()
+                .and_then(|response| proto::ServingStatus::from_i32(response.into_inner().status))
+                == Some(proto::ServingStatus::Serving);
+            if serving {
+                endpoint.failures.store(0, Ordering::Relaxed);
+                endpoint.ejected.store(false, Ordering::Relaxed);
+                emit!(&VectorEndpointReinstated {
+                    endpoint: &endpoint.address,
+                });
+                return;
+            }
+        }
+    }
+}
+
+/// Decrements the in-flight request count of an endpoint when dropped.
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl<'a> InFlight<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl<'a> Drop for InFlight<'a> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<EventWrapper>, Option<usize>>> for Balancer {
+    type Response = ();
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(
+        &mut self,
+        request: PartitionInnerBuffer<Vec<EventWrapper>, Option<usize>>,
+    ) -> Self::Future {
+        let (events, partition) = request.into_parts();
+        let index = self.select(partition);
+        let balancer = self.clone();
+
+        async move {
+            let endpoint = &balancer.inner.endpoints[index];
+            let result = {
+                let _in_flight = InFlight::new(&endpoint.in_flight);
+                endpoint.client.clone().call(events).await
+            };
+            balancer.record(index, &result);
+            result
+        }
+        .boxed()
+    }
+}
+
+fn hash(value: &str) -> u64 {
+    // A fixed seed keeps keys on the same endpoints across Vector instances.
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(value.as_bytes());
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::LogEvent,
+        sinks::util::grpc::{new_client, HyperSvc},
+        tls::MaybeTlsSettings,
+    };
+    use std::{collections::HashSet, convert::TryFrom};
+
+    fn balancer(count: usize, config: &LoadBalancingConfig) -> Balancer {
+        let client = new_client(&MaybeTlsSettings::Raw(())).unwrap();
+        let endpoints = (0..count)
+            .map(|index| {
+                let address = format!("http://10.0.0.{}:6000", index);
+                let svc = HyperSvc::new(address.parse().unwrap(), client.clone());
+                (address, proto::Client::new(svc))
+            })
+            .collect();
+        Balancer::new(endpoints, config)
+    }
+
+    fn consistent_hash() -> LoadBalancingConfig {
+        LoadBalancingConfig {
+            strategy: Strategy::ConsistentHash,
+            key: Some(Template::try_from("{{ host }}").unwrap()),
+            ..Default::default()
+        }
+    }
+
+    fn event(host: &str) -> Event {
+        let mut log = LogEvent::from("message");
+        log.insert("host", host);
+        log.into()
+    }
+
+    fn eject(balancer: &Balancer, index: usize) {
+        balancer.inner.endpoints[index]
+            .ejected
+            .store(true, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn validates_config() {
+        assert!(LoadBalancingConfig::default().validate().is_ok());
+        assert!(consistent_hash().validate().is_ok());
+        assert!(LoadBalancingConfig {
+            strategy: Strategy::ConsistentHash,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(LoadBalancingConfig {
+            key: consistent_hash().key,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn round_robin_skips_ejected_endpoints() {
+        let balancer = balancer(3, &LoadBalancingConfig::default());
+        let selected = (0..3).map(|_| balancer.select(None)).collect::<Vec<_>>();
+        assert_eq!(selected, vec![0, 1, 2]);
+
+        eject(&balancer, 1);
+        let selected = (0..3).map(|_| balancer.select(None)).collect::<Vec<_>>();
+        assert_eq!(selected, vec![0, 2, 2]);
+    }
+
+    #[test]
+    fn least_in_flight_picks_idle_endpoint() {
+        let balancer = balancer(
+            3,
+            &LoadBalancingConfig {
+                strategy: Strategy::LeastInFlight,
+                ..Default::default()
+            },
+        );
+        let _first = InFlight::new(&balancer.inner.endpoints[0].in_flight);
+        let _second = InFlight::new(&balancer.inner.endpoints[2].in_flight);
+
+        for _ in 0..3 {
+            assert_eq!(balancer.select(None), 1);
+        }
+    }
+
+    #[test]
+    fn consistent_hash_pins_keys_to_endpoints() {
+        let balancer = balancer(4, &consistent_hash());
+
+        let partitions = (0..100)
+            .map(|host| {
+                balancer
+                    .partition(&event(&format!("host-{}", host)))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(partitions.iter().collect::<HashSet<_>>().len(), 4);
+
+        let again = (0..100)
+            .map(|host| {
+                balancer
+                    .partition(&event(&format!("host-{}", host)))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(partitions, again);
+    }
+
+    #[test]
+    fn consistent_hash_fails_over_to_next_endpoint() {
+        let balancer = balancer(3, &consistent_hash());
+        assert_eq!(balancer.select(Some(1)), 1);
+
+        eject(&balancer, 1);
+        assert_eq!(balancer.select(Some(1)), 2);
+        assert_eq!(balancer.select(Some(0)), 0);
+
+        // With every endpoint ejected, events keep going to their own.
+        eject(&balancer, 0);
+        eject(&balancer, 2);
+        assert_eq!(balancer.select(Some(1)), 1);
+    }
+
+    #[tokio::test]
+    async fn ejects_failing_endpoints() {
+        let balancer = balancer(2, &LoadBalancingConfig::default());
+        let unavailable = || {
+            Err(Error::Request {
+                source: tonic::Status::unavailable("down"),
+            })
+        };
+
+        balancer.record(0, &unavailable());
+        balancer.record(0, &Ok(()));
+        balancer.record(0, &unavailable());
+        balancer.record(0, &unavailable());
+        assert!(balancer.inner.endpoints[0].is_healthy());
+
+        balancer.record(0, &unavailable());
+        assert!(!balancer.inner.endpoints[0].is_healthy());
+
+        // Errors caused by the events don't affect health.
+        for _ in 0..3 {
+            balancer.record(
+                1,
+                &Err(Error::Request {
+                    source: tonic::Status::invalid_argument("bad"),
+                }),
+            );
+        }
+        assert!(balancer.inner.endpoints[1].is_healthy());
+    }
+}
@@ -1,3 +1,4 @@
+mod balance;
 pub mod v1;
 pub mod v2;
 
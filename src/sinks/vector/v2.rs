@@ -1,32 +1,39 @@
+use super::balance::{Balancer, LoadBalancingConfig};
 use crate::{
     config::{DataType, GenerateConfig, Resource, SinkContext, SinkHealthcheckOptions},
     event::{proto::EventWrapper, Event},
     proto::vector as proto,
     sinks::util::{
-        retries::RetryLogic, BatchConfig, BatchSettings, BatchSink, EncodedEvent, EncodedLength,
+        buffer::{PartitionBuffer, PartitionInnerBuffer},
+        grpc::{new_client, with_default_scheme, HyperSvc},
+        retries::RetryLogic,
+        BatchConfig, BatchSettings, EncodedEvent, EncodedLength, PartitionBatchSink,
         ServiceBuilderExt, TowerRequestConfig, VecBuffer,
     },
     sinks::{Healthcheck, VectorSink},
-    tls::{tls_connector_builder, MaybeTlsSettings, TlsConfig},
+    tls::{MaybeTlsSettings, TlsConfig},
 };
 use futures::{future::BoxFuture, stream, SinkExt, StreamExt, TryFutureExt};
-use http::uri::Uri;
-use hyper::client::HttpConnector;
-use hyper_openssl::HttpsConnector;
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::task::{Context, Poll};
-use tonic::{body::BoxBody, IntoRequest};
+use tonic::IntoRequest;
 use tower::ServiceBuilder;
 use vector_core::ByteSizeOf;
 
-type Client = proto::Client<HyperSvc>;
+pub(super) type Client = proto::Client<HyperSvc>;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct VectorConfig {
-    address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    /// Several downstream Vector instances to spread events across.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    addresses: Vec<String>,
+    #[serde(default)]
+    load_balancing: LoadBalancingConfig,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -43,117 +50,51 @@ impl GenerateConfig for VectorConfig {
 
 fn default_config(address: &str) -> VectorConfig {
     VectorConfig {
-        address: address.to_owned(),
+        address: Some(address.to_owned()),
+        addresses: Vec::new(),
+        load_balancing: LoadBalancingConfig::default(),
         batch: BatchConfig::default(),
         request: TowerRequestConfig::default(),
         tls: None,
     }
 }
 
-/// grpc doesn't like an address without a scheme, so we default to http or https if one isn't
-/// specified in the address.
-fn with_default_scheme(address: &str, tls: bool) -> crate::Result<Uri> {
-    let uri: Uri = address.parse()?;
-    if uri.scheme().is_none() {
-        // Default the scheme to http or https.
-        let mut parts = uri.into_parts();
-
-        parts.scheme = if tls {
-            Some(
-                "https"
-                    .parse()
-                    .unwrap_or_else(|_| unreachable!("https should be valid")),
-            )
-        } else {
-            Some(
-                "http"
-                    .parse()
-                    .unwrap_or_else(|_| unreachable!("http should be valid")),
-            )
-        };
-
-        if parts.path_and_query.is_none() {
-            parts.path_and_query = Some(
-                "/".parse()
-                    .unwrap_or_else(|_| unreachable!("root should be valid")),
-            );
-        }
-        Ok(Uri::from_parts(parts)?)
-    } else {
-        Ok(uri)
-    }
-}
-
-fn new_client(
-    tls_settings: &MaybeTlsSettings,
-) -> crate::Result<hyper::Client<HttpsConnector<HttpConnector>, BoxBody>> {
-    let mut http = HttpConnector::new();
-    http.enforce_http(false);
-
-    let tls = tls_connector_builder(tls_settings)?;
-    let mut https = HttpsConnector::with_connector(http, tls)?;
-
-    let settings = tls_settings.tls().cloned();
-    https.set_callback(move |c, _uri| {
-        if let Some(settings) = &settings {
-            settings.apply_connect_configuration(c);
+impl VectorConfig {
+    fn addresses(&self) -> crate::Result<Vec<&str>> {
+        match (&self.address, self.addresses.as_slice()) {
+            (Some(address), []) => Ok(vec![address.as_str()]),
+            (None, []) => Err("Either `address` or `addresses` must be set.".into()),
+            (None, addresses) => Ok(addresses.iter().map(String::as_str).collect()),
+            (Some(_), _) => Err("Only one of `address` and `addresses` can be set.".into()),
         }
-
-        Ok(())
-    });
-
-    Ok(hyper::Client::builder().http2_only(true).build(https))
-}
-
-#[derive(Clone)]
-struct HyperSvc {
-    uri: Uri,
-    client: hyper::Client<HttpsConnector<HttpConnector>, BoxBody>,
-}
-
-impl tower::Service<hyper::Request<BoxBody>> for HyperSvc {
-    type Response = hyper::Response<hyper::Body>;
-    type Error = hyper::Error;
-    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
-
-    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
-    }
-
-    fn call(&mut self, mut req: hyper::Request<BoxBody>) -> Self::Future {
-        let uri = Uri::builder()
-            .scheme(self.uri.scheme().unwrap().clone())
-            .authority(self.uri.authority().unwrap().clone())
-            .path_and_query(req.uri().path_and_query().unwrap().clone())
-            .build()
-            .unwrap();
-
-        *req.uri_mut() = uri;
-
-        Box::pin(self.client.request(req))
     }
-}
 
-impl VectorConfig {
     pub(crate) async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
-        let uri = with_default_scheme(&self.address, tls.is_tls())?;
+        self.load_balancing.validate()?;
 
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
         let client = new_client(&tls)?;
 
-        let healthcheck_uri = cx
-            .healthcheck
-            .uri
-            .clone()
-            .map(|uri| uri.uri)
-            .unwrap_or_else(|| uri.clone());
-        let healthcheck_client = proto::Client::new(HyperSvc {
-            uri: healthcheck_uri,
-            client: client.clone(),
-        });
+        let endpoints = self
+            .addresses()?
+            .into_iter()
+            .map(|address| {
+                let uri = with_default_scheme(address, tls.is_tls())?;
+                let client = proto::Client::new(HyperSvc::new(uri, client.clone()));
+                Ok((address.to_owned(), client))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let healthcheck_clients = match cx.healthcheck.uri.clone() {
+            Some(uri) => vec![(
+                uri.uri.to_string(),
+                proto::Client::new(HyperSvc::new(uri.uri, client)),
+            )],
+            None => endpoints.clone(),
+        };
+        let healthcheck = healthcheck(healthcheck_clients, cx.healthcheck.clone());
 
-        let healthcheck = healthcheck(healthcheck_client, cx.healthcheck.clone());
-        let client = proto::Client::new(HyperSvc { uri, client });
+        let balancer = Balancer::new(endpoints, &self.load_balancing);
         let request = self.request.unwrap_with(&TowerRequestConfig::default());
         let batch = BatchSettings::default()
             .events(1000)
@@ -162,12 +103,12 @@ impl VectorConfig {
 
         let svc = ServiceBuilder::new()
             .settings(request, VectorGrpcRetryLogic)
-            .service(client);
+            .service(balancer.clone());
 
-        let buffer = VecBuffer::new(batch.size);
-        let sink = BatchSink::new(svc, buffer, batch.timeout, cx.acker())
+        let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
             .sink_map_err(|error| error!(message = "Fatal Vector GRPC sink error.", %error))
-            .with_flat_map(move |event| stream::iter(Some(encode_event(event))).map(Ok));
+            .with_flat_map(move |event| stream::iter(Some(encode_event(&balancer, event))).map(Ok));
 
         Ok((VectorSink::Sink(Box::new(sink)), Box::pin(healthcheck)))
     }
@@ -185,23 +126,41 @@ impl VectorConfig {
     }
 }
 
-/// Check to see if the remote service accepts new events.
-async fn healthcheck(mut client: Client, options: SinkHealthcheckOptions) -> crate::Result<()> {
+/// Check to see if the remote services accept new events. With several
+/// endpoints, the check passes as long as one of them is healthy.
+async fn healthcheck(
+    clients: Vec<(String, Client)>,
+    options: SinkHealthcheckOptions,
+) -> crate::Result<()> {
     if !options.enabled {
         return Ok(());
     }
 
-    let request = client.health_check(proto::HealthCheckRequest {});
+    let count = clients.len();
+    let mut healthy = false;
+    for (address, mut client) in clients {
+        let request = client.health_check(proto::HealthCheckRequest {});
 
-    if let Ok(response) = request.await {
-        let status = proto::ServingStatus::from_i32(response.into_inner().status);
+        let serving = match request.await {
+            Ok(response) => {
+                proto::ServingStatus::from_i32(response.into_inner().status)
+                    == Some(proto::ServingStatus::Serving)
+            }
+            Err(_) => false,
+        };
 
-        if let Some(proto::ServingStatus::Serving) = status {
-            return Ok(());
+        if serving {
+            healthy = true;
+        } else if count > 1 {
+            warn!(message = "Vector endpoint unhealthy.", endpoint = %address);
         }
     }
 
-    Err(Box::new(Error::Health))
+    if healthy {
+        Ok(())
+    } else {
+        Err(Box::new(Error::Health))
+    }
 }
 
 impl tower::Service<Vec<EventWrapper>> for Client {
@@ -234,10 +193,14 @@ impl tower::Service<Vec<EventWrapper>> for Client {
     }
 }
 
-fn encode_event(mut event: Event) -> EncodedEvent<EventWrapper> {
+fn encode_event(
+    balancer: &Balancer,
+    mut event: Event,
+) -> EncodedEvent<PartitionInnerBuffer<EventWrapper, Option<usize>>> {
     let byte_size = event.size_of();
     let finalizers = event.metadata_mut().take_finalizers();
-    let item = event.into();
+    let partition = balancer.partition(&event);
+    let item = PartitionInnerBuffer::new(event.into(), partition);
 
     EncodedEvent {
         item,
@@ -265,7 +228,7 @@ pub enum Error {
 }
 
 #[derive(Debug, Clone)]
-struct VectorGrpcRetryLogic;
+pub(super) struct VectorGrpcRetryLogic;
 
 impl RetryLogic for VectorGrpcRetryLogic {
     type Error = Error;
@@ -359,6 +322,74 @@ mod tests {
         assert_eq!(input_lines, output_lines);
     }
 
+    #[tokio::test]
+    async fn deliver_message_to_multiple_addresses() {
+        let num_lines = 10;
+
+        let addrs = [next_addr(), next_addr()];
+
+        let config = format!(
+            r#"
+            addresses = ["http://{}/", "http://{}/"]
+            batch.max_events = 1
+            "#,
+            addrs[0], addrs[1]
+        );
+        let config: VectorConfig = toml::from_str(&config).unwrap();
+
+        let cx = SinkContext::new_test();
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let mut receivers = Vec::new();
+        let mut triggers = Vec::new();
+        for addr in addrs {
+            let (rx, trigger, server) = build_test_server_generic(addr, move || {
+                hyper::Response::builder()
+                    .header("grpc-status", "0") // OK
+                    .header("content-type", "application/grpc")
+                    .body(hyper::Body::from(encode_body(proto::PushEventsResponse {})))
+                    .unwrap()
+            });
+            tokio::spawn(server);
+            receivers.push(rx);
+            triggers.push(trigger);
+        }
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (mut input_lines, events) = random_lines_with_stream(8, num_lines, Some(batch));
+
+        sink.run(events).await.unwrap();
+        drop(triggers);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let mut output_lines = Vec::new();
+        for rx in receivers {
+            let lines = get_received(rx, |_| {}).await;
+            assert!(!lines.is_empty());
+            output_lines.extend(lines);
+        }
+
+        input_lines.sort();
+        output_lines.sort();
+        assert_eq!(input_lines, output_lines);
+    }
+
+    #[tokio::test]
+    async fn requires_one_kind_of_address() {
+        let config: VectorConfig = toml::from_str(
+            r#"
+            address = "127.0.0.1:6000"
+            addresses = ["127.0.0.1:6001"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+
+        let config: VectorConfig = toml::from_str("").unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
     #[tokio::test]
     async fn acknowledges_error() {
         let num_lines = 10;
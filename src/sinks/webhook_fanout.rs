@@ -0,0 +1,884 @@
+//! Delivery of every event to several webhooks.
+//!
+//! Each endpoint gets a batch sink of its own, with its own body template,
+//! request settings and retries, along with a circuit breaker failing its
+//! requests fast once it has failed repeatedly. Events are buffered for each
+//! endpoint, so that a slow endpoint only holds the others back once its
+//! buffer is full, and are only acknowledged once every endpoint is done with
+//! them.
+
+use crate::{
+    buffers::Acker,
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, EventFinalizers, EventStatus},
+    http::{Auth, HttpClient, MaybeAuth},
+    internal_events::{
+        EndpointBytesSent, TemplateRenderingFailed, WebhookFanoutCircuitOpened,
+        WebhookFanoutDeliveryFailed, WebhookFanoutEventsDelivered, WebhookFanoutRequestRejected,
+    },
+    sinks::{
+        http::HttpMethod,
+        util::{
+            http::{HttpRetryLogic, RequestConfig},
+            sink::{ServiceLogic, StdServiceLogic},
+            BatchConfig, BatchSettings, EncodedEvent, EncodedLength, StreamSink, TowerBatchedSink,
+            TowerRequestConfig, UriSerde, VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+use bytes::{Buf, Bytes};
+use futures::{
+    channel::mpsc,
+    future::{self, BoxFuture, Either},
+    stream::BoxStream,
+    task::AtomicWaker,
+    FutureExt, SinkExt, StreamExt,
+};
+use http::{
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+    Method, Request, StatusCode, Uri,
+};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+use tower::Service;
+use vector_core::ByteSizeOf;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one endpoint must be configured"))]
+    NoEndpoints,
+    #[snafu(display("Endpoint name {:?} is used more than once", name))]
+    DuplicateEndpoint { name: String },
+    #[snafu(display(
+        "Endpoint {:?}: `circuit_breaker.failure_threshold` must be at least 1",
+        name
+    ))]
+    InvalidFailureThreshold { name: String },
+    #[snafu(display(
+        "Endpoint {:?}: the Authorization header can't be used along with `auth`",
+        name
+    ))]
+    AuthorizationHeader { name: String },
+    #[snafu(display("Endpoint {:?}: {}: {}", name, source, header))]
+    InvalidHeaderName {
+        name: String,
+        header: String,
+        source: header::InvalidHeaderName,
+    },
+    #[snafu(display("Endpoint {:?}: {}: {}", name, source, value))]
+    InvalidHeaderValue {
+        name: String,
+        value: String,
+        source: header::InvalidHeaderValue,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum EndpointError {
+    #[snafu(display(
+        "Endpoint {:?} is cooling down after failing too many requests in a row",
+        name
+    ))]
+    CircuitOpen { name: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookFanoutConfig {
+    endpoints: Vec<EndpointConfig>,
+    #[serde(default)]
+    batch: BatchConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EndpointConfig {
+    /// Identifies the endpoint in logs and metrics.
+    name: String,
+    uri: UriSerde,
+    method: Option<HttpMethod>,
+    auth: Option<Auth>,
+    /// The body sent for each event. Events are encoded as JSON if it isn't
+    /// set.
+    body: Option<Template>,
+    #[serde(default = "default_content_type")]
+    content_type: String,
+    #[serde(default)]
+    request: RequestConfig,
+    #[serde(default)]
+    circuit_breaker: CircuitBreakerConfig,
+    /// The number of events buffered for the endpoint while its requests are
+    /// slower than the ones of the other endpoints.
+    #[serde(default = "default_max_pending_events")]
+    max_pending_events: usize,
+    tls: Option<TlsOptions>,
+}
+
+fn default_content_type() -> String {
+    "application/json".to_owned()
+}
+
+const fn default_max_pending_events() -> usize {
+    1000
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct CircuitBreakerConfig {
+    /// The number of consecutive failed requests after which the endpoint's
+    /// requests are failed without being sent.
+    failure_threshold: u32,
+    /// How long the endpoint's requests are failed for once it trips.
+    cooldown_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_secs: 30,
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<WebhookFanoutConfig>("webhook_fanout")
+}
+
+impl GenerateConfig for WebhookFanoutConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[[endpoints]]
+            name = "alerting"
+            uri = "https://alerting.example.com/hooks/vector"
+            body = '{"summary": "{{ message }}"}'
+
+            [[endpoints]]
+            name = "archive"
+            uri = "https://archive.example.com/events""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "webhook_fanout")]
+impl SinkConfig for WebhookFanoutConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.endpoints.is_empty() {
+            return Err(BuildError::NoEndpoints.into());
+        }
+
+        let batch = BatchSettings::default()
+            .events(1)
+            .bytes(10_000_000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+
+        // Each endpoint acknowledges the events it's done with to an acker of
+        // its own, and the events all of them are done with are acknowledged
+        // in turn.
+        let mut acks = AckForwarder {
+            acker: cx.acker(),
+            waker: Arc::new(AtomicWaker::new()),
+            counters: Vec::with_capacity(self.endpoints.len()),
+            acked: 0,
+        };
+        let mut names = HashSet::new();
+        let mut endpoints = Vec::with_capacity(self.endpoints.len());
+        for config in &self.endpoints {
+            if !names.insert(config.name.as_str()) {
+                return Err(BuildError::DuplicateEndpoint {
+                    name: config.name.clone(),
+                }
+                .into());
+            }
+
+            let counter = Arc::new(AtomicUsize::new(0));
+            let acker = Acker::Disk(Arc::clone(&counter), Arc::clone(&acks.waker));
+            endpoints.push(config.build(&cx, batch, acker)?);
+            acks.counters.push(counter);
+        }
+
+        let sink = WebhookFanoutSink { endpoints, acks };
+
+        // Webhooks have no way to be checked without posting an event.
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "webhook_fanout"
+    }
+}
+
+impl EndpointConfig {
+    fn build(
+        &self,
+        cx: &SinkContext,
+        batch: BatchSettings<VecBuffer<EventBody>>,
+        acker: Acker,
+    ) -> crate::Result<EndpointSink> {
+        if self.circuit_breaker.failure_threshold == 0 {
+            return Err(BuildError::InvalidFailureThreshold {
+                name: self.name.clone(),
+            }
+            .into());
+        }
+
+        let auth = self.auth.choose_one(&self.uri.auth)?;
+        // Headers configured along with `content_type` take precedence.
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(&self.content_type).with_context(|| InvalidHeaderValue {
+                name: &self.name,
+                value: &self.content_type,
+            })?,
+        );
+        for (header, value) in &self.request.headers {
+            if auth.is_some() && header.eq_ignore_ascii_case("Authorization") {
+                return Err(BuildError::AuthorizationHeader {
+                    name: self.name.clone(),
+                }
+                .into());
+            }
+            headers.insert(
+                HeaderName::from_bytes(header.as_bytes()).with_context(|| InvalidHeaderName {
+                    name: &self.name,
+                    header,
+                })?,
+                HeaderValue::from_str(value).with_context(|| InvalidHeaderValue {
+                    name: &self.name,
+                    value,
+                })?,
+            );
+        }
+
+        let uri = self.uri.with_default_parts().uri;
+        let endpoint = Arc::new(Endpoint {
+            name: self.name.clone(),
+            sent_to: format!(
+                "{}://{}{}",
+                uri.scheme_str().unwrap_or("http"),
+                uri.authority().map_or("", |authority| authority.as_str()),
+                uri.path()
+            ),
+            uri,
+            method: Method::from(self.method.as_ref().unwrap_or(&HttpMethod::Post)),
+            auth,
+            headers,
+            breaker: CircuitBreaker::new(self.circuit_breaker),
+        });
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, cx.proxy())?;
+        let service = EndpointService {
+            endpoint: Arc::clone(&endpoint),
+            client,
+        };
+
+        let sink = self
+            .request
+            .tower
+            .unwrap_with(&TowerRequestConfig::default())
+            .batch_sink(
+                HttpRetryLogic,
+                service,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                acker,
+                EndpointServiceLogic { endpoint },
+            );
+
+        Ok(EndpointSink {
+            body: self.body.clone(),
+            max_pending_events: self.max_pending_events,
+            sink,
+        })
+    }
+}
+
+/// The body of an event, or `None` if it couldn't be rendered for an
+/// endpoint. Such events still take a place in the endpoint's batches, so
+/// that it acknowledges them in order.
+#[derive(Clone, Debug)]
+struct EventBody(Option<Bytes>);
+
+impl EncodedLength for EventBody {
+    fn encoded_length(&self) -> usize {
+        self.0.as_ref().map_or(0, Bytes::len)
+    }
+}
+
+struct EndpointSink {
+    body: Option<Template>,
+    max_pending_events: usize,
+    sink: TowerBatchedSink<
+        EndpointService,
+        VecBuffer<EventBody>,
+        HttpRetryLogic,
+        EndpointServiceLogic,
+    >,
+}
+
+fn render_body(template: Option<&Template>, event: &Event) -> Option<Bytes> {
+    match template {
+        Some(template) => template
+            .render(event)
+            .map_err(|error| {
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some("body"),
+                    drop_event: true,
+                });
+            })
+            .ok(),
+        None => serde_json::to_vec(event.as_log())
+            .map(Bytes::from)
+            .map_err(|error| error!(message = "Unable to encode event as JSON.", %error))
+            .ok(),
+    }
+}
+
+struct WebhookFanoutSink {
+    endpoints: Vec<EndpointSink>,
+    acks: AckForwarder,
+}
+
+#[async_trait::async_trait]
+impl StreamSink for WebhookFanoutSink {
+    async fn run(self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let WebhookFanoutSink {
+            endpoints,
+            mut acks,
+        } = *self;
+
+        let mut senders = Vec::with_capacity(endpoints.len());
+        let mut deliveries = Vec::with_capacity(endpoints.len());
+        for EndpointSink {
+            body,
+            max_pending_events,
+            sink,
+        } in endpoints
+        {
+            let (tx, rx) = mpsc::channel(max_pending_events);
+            senders.push((body, tx));
+            deliveries.push(rx.map(Ok).forward(sink));
+        }
+
+        let fan_out = async move {
+            while let Some(mut event) = input.next().await {
+                let byte_size = event.size_of();
+                let finalizers = event.metadata_mut().take_finalizers();
+                // The event is buffered for every endpoint at once, so that
+                // only endpoints with a full buffer hold the next one back.
+                let sends = senders.iter_mut().map(|(template, tx)| {
+                    let body = render_body(template.as_ref(), &event);
+                    // Events left out of an endpoint's requests get no status
+                    // from it, as with any sink dropping an event.
+                    let finalizers = match body {
+                        Some(_) => finalizers.clone(),
+                        None => EventFinalizers::default(),
+                    };
+                    tx.send(EncodedEvent {
+                        item: EventBody(body),
+                        finalizers,
+                        byte_size,
+                    })
+                });
+                if future::join_all(sends)
+                    .await
+                    .iter()
+                    .any(|result| result.is_err())
+                {
+                    // An endpoint stopped on a fatal error, which is reported
+                    // once the deliveries are joined.
+                    return;
+                }
+            }
+        };
+        let delivery = future::join(fan_out, future::try_join_all(deliveries));
+
+        let forward_acks = future::poll_fn(|cx| {
+            acks.waker.register(cx.waker());
+            acks.forward();
+            Poll::<()>::Pending
+        });
+
+        let result = match future::select(Box::pin(delivery), Box::pin(forward_acks)).await {
+            Either::Left((((), result), _)) => result,
+            Either::Right(_) => unreachable!("Acknowledgements are forwarded until the end."),
+        };
+        acks.forward();
+
+        result
+            .map(|_| ())
+            .map_err(|error| error!(message = "Fatal webhook_fanout sink error.", %error))
+    }
+}
+
+struct AckForwarder {
+    acker: Acker,
+    waker: Arc<AtomicWaker>,
+    counters: Vec<Arc<AtomicUsize>>,
+    acked: usize,
+}
+
+impl AckForwarder {
+    /// Acknowledges the events every endpoint is done with.
+    fn forward(&mut self) {
+        let done = self
+            .counters
+            .iter()
+            .map(|counter| counter.load(Ordering::Acquire))
+            .min()
+            .unwrap_or(0);
+        if done > self.acked {
+            self.acker.ack(done - self.acked);
+            self.acked = done;
+        }
+    }
+}
+
+struct Endpoint {
+    name: String,
+    /// The URI requests are sent to, without its query, as reported in
+    /// metrics.
+    sent_to: String,
+    uri: Uri,
+    method: Method,
+    auth: Option<Auth>,
+    headers: HeaderMap,
+    breaker: CircuitBreaker,
+}
+
+impl Endpoint {
+    fn build_request(&self, body: Vec<u8>) -> Request<Body> {
+        let mut request = Request::new(Body::from(body));
+        *request.method_mut() = self.method.clone();
+        *request.uri_mut() = self.uri.clone();
+        *request.headers_mut() = self.headers.clone();
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+        request
+    }
+}
+
+struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    failures: AtomicU32,
+    tripped_until: Mutex<Option<Instant>>,
+}
+
+impl CircuitBreaker {
+    fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            failure_threshold: config.failure_threshold,
+            cooldown: Duration::from_secs(config.cooldown_secs),
+            failures: AtomicU32::new(0),
+            tripped_until: Mutex::new(None),
+        }
+    }
+
+    fn is_tripped(&self) -> bool {
+        let mut tripped_until = self.tripped_until.lock().unwrap();
+        match *tripped_until {
+            Some(until) if until > Instant::now() => true,
+            Some(_) => {
+                // Let requests through again. The failure count is left just
+                // under the threshold, so one more failure trips it again.
+                *tripped_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn record(&self, endpoint: &str, succeeded: bool) {
+        if succeeded {
+            self.failures.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= self.failure_threshold {
+            self.failures
+                .store(self.failure_threshold - 1, Ordering::Relaxed);
+            *self.tripped_until.lock().unwrap() = Some(Instant::now() + self.cooldown);
+            emit!(&WebhookFanoutCircuitOpened {
+                endpoint,
+                consecutive_failures: failures,
+                cooldown: self.cooldown,
+            });
+        }
+    }
+}
+
+#[derive(Clone)]
+struct EndpointService {
+    endpoint: Arc<Endpoint>,
+    client: HttpClient,
+}
+
+impl Service<Vec<EventBody>> for EndpointService {
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, bodies: Vec<EventBody>) -> Self::Future {
+        let endpoint = Arc::clone(&self.endpoint);
+        let client = self.client.clone();
+
+        Box::pin(async move {
+            let bodies = bodies
+                .into_iter()
+                .filter_map(|body| body.0)
+                .collect::<Vec<_>>();
+            if bodies.is_empty() {
+                // None of the events could be rendered, there's nothing to
+                // send.
+                let mut response = http::Response::new(Bytes::new());
+                *response.status_mut() = StatusCode::NO_CONTENT;
+                return Ok(response);
+            }
+
+            if endpoint.breaker.is_tripped() {
+                emit!(&WebhookFanoutRequestRejected {
+                    endpoint: &endpoint.name,
+                    count: bodies.len(),
+                });
+                return Err(EndpointError::CircuitOpen {
+                    name: endpoint.name.clone(),
+                }
+                .into());
+            }
+
+            let count = bodies.len();
+            let mut body = Vec::with_capacity(bodies.iter().map(|body| body.len() + 1).sum());
+            for (index, event) in bodies.iter().enumerate() {
+                if index > 0 {
+                    body.push(b'\n');
+                }
+                body.extend_from_slice(event);
+            }
+            let byte_size = body.len();
+
+            let response = match client.send(endpoint.build_request(body)).await {
+                Ok(response) => response,
+                Err(error) => {
+                    endpoint.breaker.record(&endpoint.name, false);
+                    return Err(error.into());
+                }
+            };
+            let (parts, body) = response.into_parts();
+            let mut body = hyper::body::aggregate(body).await?;
+            let response = http::Response::from_parts(parts, body.copy_to_bytes(body.remaining()));
+
+            let succeeded = response.status().is_success();
+            endpoint.breaker.record(&endpoint.name, succeeded);
+            if succeeded {
+                emit!(&WebhookFanoutEventsDelivered {
+                    endpoint: &endpoint.name,
+                    count,
+                    byte_size,
+                });
+                emit!(&EndpointBytesSent {
+                    byte_size,
+                    protocol: endpoint.uri.scheme_str().unwrap_or("http"),
+                    endpoint: &endpoint.sent_to,
+                });
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+#[derive(Clone)]
+struct EndpointServiceLogic {
+    endpoint: Arc<Endpoint>,
+}
+
+impl ServiceLogic for EndpointServiceLogic {
+    type Response = http::Response<Bytes>;
+
+    fn result_status(&self, result: crate::Result<Self::Response>) -> EventStatus {
+        // Rejected requests are already reported as such.
+        let rejected = matches!(
+            &result,
+            Err(error) if error.downcast_ref::<EndpointError>().is_some()
+        );
+        let status = StdServiceLogic::default().result_status(result);
+        if status != EventStatus::Delivered && !rejected {
+            emit!(&WebhookFanoutDeliveryFailed {
+                endpoint: &self.endpoint.name,
+            });
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{BatchNotifier, BatchStatus, LogEvent},
+        sinks::util::test::{build_test_server_generic, build_test_server_status, load_sink},
+        test_util::{
+            components::{self, HTTP_SINK_TAGS},
+            next_addr,
+        },
+    };
+    use futures::stream;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value as JsonValue};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebhookFanoutConfig>();
+    }
+
+    #[test]
+    fn circuit_breaker_trips_and_resets() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown_secs: 0,
+        });
+
+        breaker.record("test", false);
+        assert!(!breaker.is_tripped());
+        breaker.record("test", true);
+        breaker.record("test", false);
+        assert!(!breaker.is_tripped());
+        breaker.record("test", false);
+        assert!(breaker.tripped_until.lock().unwrap().is_some());
+
+        // Once cooled down, a single failure trips it again.
+        assert!(!breaker.is_tripped());
+        breaker.record("test", false);
+        assert!(breaker.tripped_until.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_configs() {
+        for (config, error) in &[
+            ("endpoints = []", "At least one endpoint"),
+            (
+                indoc! {r#"
+                    [[endpoints]]
+                    name = "a"
+                    uri = "http://localhost/a"
+
+                    [[endpoints]]
+                    name = "a"
+                    uri = "http://localhost/b"
+                "#},
+                "used more than once",
+            ),
+            (
+                indoc! {r#"
+                    [[endpoints]]
+                    name = "a"
+                    uri = "http://localhost/a"
+                    auth.strategy = "bearer"
+                    auth.token = "secret"
+                    request.headers.Authorization = "Bearer other"
+                "#},
+                "Authorization header",
+            ),
+        ] {
+            let (config, cx) = load_sink::<WebhookFanoutConfig>(config).unwrap();
+            let message = config.build(cx).await.err().unwrap().to_string();
+            assert!(message.contains(error), "{}", message);
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_to_every_endpoint() {
+        let alerting = next_addr();
+        let archive = next_addr();
+        let config = format!(
+            indoc! {r#"
+                batch.max_events = 2
+
+                [[endpoints]]
+                name = "alerting"
+                uri = "http://{}/alerts"
+                body = "{{{{ host }}}}: {{{{ message }}}}"
+                content_type = "text/plain"
+                request.headers.X-Source = "vector"
+
+                [[endpoints]]
+                name = "archive"
+                uri = "http://{}/events"
+                method = "put"
+            "#},
+            alerting, archive
+        );
+        let (config, cx) = load_sink::<WebhookFanoutConfig>(&config).unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (alerting_rx, _alerting_trigger, server) =
+            build_test_server_status(alerting, StatusCode::OK);
+        tokio::spawn(server);
+        let (archive_rx, _archive_trigger, server) =
+            build_test_server_status(archive, StatusCode::OK);
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let events = ["disk full", "load high"]
+            .iter()
+            .map(|message| {
+                let mut log = LogEvent::from(*message).with_batch_notifier(&batch);
+                log.insert("host", "web-1");
+                Event::from(log)
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        components::init_test();
+        sink.run(stream::iter(events)).await.unwrap();
+        components::SINK_TESTS.assert(&HTTP_SINK_TAGS);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let (parts, body) = alerting_rx.take(1).collect::<Vec<_>>().await.remove(0);
+        assert_eq!(parts.method, Method::POST);
+        assert_eq!(parts.uri.path(), "/alerts");
+        assert_eq!(parts.headers["content-type"], "text/plain");
+        assert_eq!(parts.headers["x-source"], "vector");
+        assert_eq!(body, "web-1: disk full\nweb-1: load high");
+
+        let (parts, body) = archive_rx.take(1).collect::<Vec<_>>().await.remove(0);
+        assert_eq!(parts.method, Method::PUT);
+        assert_eq!(parts.headers["content-type"], "application/json");
+        let messages = String::from_utf8(body.to_vec())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<JsonValue>(line).unwrap()["message"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec![json!("disk full"), json!("load high")]);
+    }
+
+    #[tokio::test]
+    async fn failing_endpoint_trips_without_holding_up_others() {
+        let healthy = next_addr();
+        let failing = next_addr();
+        let config = format!(
+            indoc! {r#"
+                [[endpoints]]
+                name = "healthy"
+                uri = "http://{}/"
+
+                [[endpoints]]
+                name = "failing"
+                uri = "http://{}/"
+                request.concurrency = 1
+                request.retry_attempts = 0
+                circuit_breaker.failure_threshold = 2
+            "#},
+            healthy, failing
+        );
+        let (config, cx) = load_sink::<WebhookFanoutConfig>(&config).unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (healthy_rx, _healthy_trigger, server) =
+            build_test_server_status(healthy, StatusCode::OK);
+        tokio::spawn(server);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let (_failing_rx, _failing_trigger, server) = build_test_server_generic(failing, {
+            let requests = Arc::clone(&requests);
+            move || {
+                requests.fetch_add(1, Ordering::Relaxed);
+                http::Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(Body::empty())
+                    .unwrap()
+            }
+        });
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let events = (0..5)
+            .map(|i| {
+                Event::from(LogEvent::from(format!("event {}", i)).with_batch_notifier(&batch))
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        sink.run(stream::iter(events)).await.unwrap();
+
+        // Only the requests up to the threshold reached the failing endpoint.
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+        assert_eq!(healthy_rx.take(5).collect::<Vec<_>>().await.len(), 5);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Failed));
+    }
+
+    #[tokio::test]
+    async fn slow_endpoint_does_not_hold_up_others() {
+        let healthy = next_addr();
+        let slow = next_addr();
+        let config = format!(
+            indoc! {r#"
+                [[endpoints]]
+                name = "healthy"
+                uri = "http://{}/"
+
+                [[endpoints]]
+                name = "slow"
+                uri = "http://{}/"
+                request.concurrency = 1
+                circuit_breaker.failure_threshold = 1000
+            "#},
+            healthy, slow
+        );
+        let (config, cx) = load_sink::<WebhookFanoutConfig>(&config).unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (healthy_rx, _healthy_trigger, server) =
+            build_test_server_status(healthy, StatusCode::OK);
+        tokio::spawn(server);
+        // Requests to the slow endpoint are retried until it recovers.
+        let (slow_rx, _slow_trigger, server) =
+            build_test_server_status(slow, StatusCode::SERVICE_UNAVAILABLE);
+        tokio::spawn(server);
+
+        let events = (0..20)
+            .map(|i| Event::from(LogEvent::from(format!("event {}", i))))
+            .collect::<Vec<_>>();
+
+        let healthy_requests = healthy_rx.take(20).collect::<Vec<_>>();
+        tokio::select! {
+            _ = sink.run(stream::iter(events)) => panic!("Sink finished while an endpoint was down."),
+            requests = healthy_requests => assert_eq!(requests.len(), 20),
+            _ = tokio::time::sleep(Duration::from_secs(10)) => panic!("Healthy endpoint was held up."),
+        }
+        assert_eq!(slow_rx.take(1).collect::<Vec<_>>().await.len(), 1);
+    }
+}
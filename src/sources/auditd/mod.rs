@@ -0,0 +1,439 @@
+//! The `auditd` source, reading the events of the Linux audit framework.
+//!
+//! Records are read either from the audit netlink socket, alongside `auditd`,
+//! or from the unix socket of the `af_unix` plugin of `audispd`, and the
+//! records of each audit event are reassembled into a single log event.
+
+use crate::{
+    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{AuditdEventReceived, AuditdParseFailed, AuditdReadFailed},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use futures::{stream, SinkExt, StreamExt};
+use nix::unistd::{Gid, Group, Uid, User};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::net::UnixStream;
+use tokio_util::codec::{FramedRead, LinesCodec};
+use vector_core::ByteSizeOf;
+
+mod netlink;
+mod parser;
+
+use netlink::AuditSocket;
+use parser::{is_argument, parse_record, Reassembler, Record};
+
+/// The longest line accepted from the `audispd` socket.
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+/// The `auid` of processes that were not started by a login.
+const UNSET_ID: &str = "4294967295";
+const USER_FIELDS: &[&str] = &["uid", "auid", "euid", "suid", "fsuid", "ouid"];
+const GROUP_FIELDS: &[&str] = &["gid", "egid", "sgid", "fsgid", "ogid"];
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Subscribe to the audit netlink socket.
+    Netlink,
+    /// Connect to the unix socket of the `audispd` `af_unix` plugin.
+    Unix,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Netlink
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct AuditdConfig {
+    pub mode: Mode,
+    pub socket_path: PathBuf,
+    pub reassembly_timeout_ms: u64,
+    pub resolve_ids: bool,
+}
+
+impl Default for AuditdConfig {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Netlink,
+            socket_path: PathBuf::from("/var/run/audispd_events"),
+            reassembly_timeout_ms: 2000,
+            resolve_ids: true,
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<AuditdConfig>("auditd")
+}
+
+impl_generate_config_from_default!(AuditdConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "auditd")]
+impl SourceConfig for AuditdConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.reassembly_timeout_ms == 0 {
+            return Err("`reassembly_timeout_ms` must be greater than zero".into());
+        }
+
+        let reader = match self.mode {
+            Mode::Netlink => RecordReader::Netlink(
+                AuditSocket::open()
+                    .map_err(|error| format!("Unable to open the audit socket: {}", error))?,
+            ),
+            Mode::Unix => RecordReader::Unix {
+                path: self.socket_path.clone(),
+                lines: None,
+            },
+        };
+        let builder = EventBuilder::new(self.resolve_ids);
+        let reassembler = Reassembler::new(Duration::from_millis(self.reassembly_timeout_ms));
+
+        Ok(Box::pin(run(
+            reader,
+            reassembler,
+            builder,
+            cx.out,
+            cx.shutdown,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "auditd"
+    }
+}
+
+enum RecordReader {
+    Netlink(AuditSocket),
+    Unix {
+        path: PathBuf,
+        lines: Option<FramedRead<UnixStream, LinesCodec>>,
+    },
+}
+
+impl RecordReader {
+    async fn next_records(&mut self) -> io::Result<Vec<String>> {
+        match self {
+            RecordReader::Netlink(socket) => socket.next_records().await,
+            RecordReader::Unix { path, lines } => {
+                if lines.is_none() {
+                    let stream = UnixStream::connect(&path).await?;
+                    *lines = Some(FramedRead::new(
+                        stream,
+                        LinesCodec::new_with_max_length(MAX_LINE_LENGTH),
+                    ));
+                }
+                let reader = lines.as_mut().expect("connected above");
+                let result = match reader.next().await {
+                    Some(Ok(line)) => return Ok(vec![line]),
+                    Some(Err(error)) => io::Error::new(io::ErrorKind::InvalidData, error),
+                    None => {
+                        io::Error::new(io::ErrorKind::UnexpectedEof, "audispd closed the socket")
+                    }
+                };
+                // Reconnect on the next call.
+                *lines = None;
+                Err(result)
+            }
+        }
+    }
+}
+
+async fn run(
+    mut reader: RecordReader,
+    mut reassembler: Reassembler,
+    mut builder: EventBuilder,
+    out: Pipeline,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+    let mut interval = tokio::time::interval(Duration::from_millis(250));
+
+    loop {
+        let completed = tokio::select! {
+            _ = &mut shutdown => break,
+            _ = interval.tick() => reassembler.flush_expired(Instant::now()),
+            lines = reader.next_records() => match lines {
+                Ok(lines) => {
+                    let now = Instant::now();
+                    lines
+                        .iter()
+                        .filter_map(|line| match parse_record(line) {
+                            Ok(record) => Some(record),
+                            Err(error) => {
+                                emit!(&AuditdParseFailed { error, line });
+                                None
+                            }
+                        })
+                        .flat_map(|record| reassembler.push(record, now))
+                        .collect()
+                }
+                Err(error) => {
+                    emit!(&AuditdReadFailed { error });
+                    // Don't spin on an unavailable socket.
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            },
+        };
+
+        send(&mut out, &mut builder, completed).await?;
+    }
+
+    // Send the events whose records were all received, but not yet
+    // terminated by an `EOE` record.
+    let completed = reassembler.flush_all();
+    send(&mut out, &mut builder, completed).await
+}
+
+async fn send(
+    out: &mut (impl futures::Sink<Event, Error = ()> + Unpin),
+    builder: &mut EventBuilder,
+    completed: Vec<Vec<Record>>,
+) -> Result<(), ()> {
+    if completed.is_empty() {
+        return Ok(());
+    }
+    let events = completed
+        .into_iter()
+        .map(|records| {
+            let event = builder.build(records);
+            emit!(&AuditdEventReceived {
+                byte_size: event.size_of(),
+            });
+            Ok(event)
+        })
+        .collect::<Vec<_>>();
+    out.send_all(&mut stream::iter(events)).await
+}
+
+/// Builds log events out of the records of audit events, resolving the ids
+/// of users and groups to their names.
+struct EventBuilder {
+    resolve_ids: bool,
+    users: HashMap<String, Option<String>>,
+    groups: HashMap<String, Option<String>>,
+}
+
+impl EventBuilder {
+    fn new(resolve_ids: bool) -> Self {
+        Self {
+            resolve_ids,
+            users: HashMap::new(),
+            groups: HashMap::new(),
+        }
+    }
+
+    fn build(&mut self, records: Vec<Record>) -> Event {
+        let first = &records[0];
+        let mut log = LogEvent::default();
+        log.insert(log_schema().source_type_key(), "auditd");
+        log.insert(log_schema().timestamp_key(), first.timestamp);
+        log.insert(
+            log_schema().message_key(),
+            records
+                .iter()
+                .map(|record| record.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        log.insert("sequence", first.serial as i64);
+        log.insert("record_type", first.record_type.clone());
+
+        let mut rule_keys = Vec::new();
+        for key in records.iter().flat_map(Record::rule_keys) {
+            if !rule_keys.contains(&key) {
+                rule_keys.push(key);
+            }
+        }
+        log.insert(
+            "rule_keys",
+            rule_keys.into_iter().map(Value::from).collect::<Vec<_>>(),
+        );
+
+        let records = records
+            .into_iter()
+            .map(|record| self.record_value(record))
+            .collect::<Vec<_>>();
+        log.insert("records", records);
+
+        log.into()
+    }
+
+    fn record_value(&mut self, record: Record) -> Value {
+        let mut fields = BTreeMap::new();
+        fields.insert("type".to_owned(), Value::from(record.record_type.as_str()));
+
+        if record.record_type == "EXECVE" {
+            let mut args = record
+                .fields
+                .iter()
+                .filter(|(key, _)| is_argument(key))
+                .filter_map(|(key, value)| Some((key[1..].parse::<usize>().ok()?, value)))
+                .collect::<Vec<_>>();
+            args.sort_by_key(|(index, _)| *index);
+            fields.insert(
+                "args".to_owned(),
+                args.into_iter()
+                    .map(|(_, value)| Value::from(value.as_str()))
+                    .collect::<Vec<_>>()
+                    .into(),
+            );
+        }
+
+        for (key, value) in record.fields {
+            if self.resolve_ids {
+                let name = if USER_FIELDS.contains(&key.as_str()) {
+                    self.user_name(&value)
+                } else if GROUP_FIELDS.contains(&key.as_str()) {
+                    self.group_name(&value)
+                } else {
+                    None
+                };
+                if let Some(name) = name {
+                    fields.insert(format!("{}_name", key), name.into());
+                }
+            }
+            fields.insert(key, value.into());
+        }
+
+        Value::Map(fields)
+    }
+
+    fn user_name(&mut self, id: &str) -> Option<String> {
+        if id == UNSET_ID {
+            return Some("unset".to_owned());
+        }
+        self.users
+            .entry(id.to_owned())
+            .or_insert_with(|| {
+                let uid = Uid::from_raw(id.parse().ok()?);
+                User::from_uid(uid).ok().flatten().map(|user| user.name)
+            })
+            .clone()
+    }
+
+    fn group_name(&mut self, id: &str) -> Option<String> {
+        if id == UNSET_ID {
+            return Some("unset".to_owned());
+        }
+        self.groups
+            .entry(id.to_owned())
+            .or_insert_with(|| {
+                let gid = Gid::from_raw(id.parse().ok()?);
+                Group::from_gid(gid).ok().flatten().map(|group| group.name)
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::collect_ready;
+    use tokio::{io::AsyncWriteExt, net::UnixListener};
+
+    fn records(lines: &[&str]) -> Vec<Record> {
+        lines
+            .iter()
+            .map(|line| parse_record(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AuditdConfig>();
+    }
+
+    #[test]
+    fn builds_events() {
+        let event = EventBuilder::new(true).build(records(&[
+            r#"type=SYSCALL msg=audit(1364481363.243:24287): arch=c000003e syscall=59 success=yes exit=0 ppid=1 pid=42 auid=4294967295 uid=0 gid=0 comm="cat" exe="/usr/bin/cat" key="exec""#,
+            r#"type=EXECVE msg=audit(1364481363.243:24287): argc=2 a0="cat" a1="/etc/shadow""#,
+        ]));
+        let log = event.as_log();
+
+        assert_eq!(log["source_type"], "auditd".into());
+        assert_eq!(log["sequence"], 24287.into());
+        assert_eq!(log["record_type"], "SYSCALL".into());
+        assert_eq!(log["rule_keys[0]"], "exec".into());
+        assert_eq!(log["records[0].type"], "SYSCALL".into());
+        assert_eq!(log["records[0].comm"], "cat".into());
+        assert_eq!(log["records[0].auid_name"], "unset".into());
+        assert_eq!(log["records[0].uid_name"], "root".into());
+        assert_eq!(log["records[0].gid_name"], "root".into());
+        assert_eq!(log["records[1].type"], "EXECVE".into());
+        assert_eq!(log["records[1].args[0]"], "cat".into());
+        assert_eq!(log["records[1].args[1]"], "/etc/shadow".into());
+        assert!(log["message"]
+            .to_string_lossy()
+            .starts_with("type=SYSCALL msg=audit(1364481363.243:24287): "));
+    }
+
+    #[test]
+    fn skips_id_resolution() {
+        let event = EventBuilder::new(false).build(records(&[
+            "type=USER_LOGIN msg=audit(1364481363.243:24288): pid=42 uid=0 auid=1000 msg='op=login res=success'",
+        ]));
+        let log = event.as_log();
+
+        assert_eq!(log["records[0].uid"], "0".into());
+        assert!(!log.contains("records[0].uid_name"));
+    }
+
+    #[tokio::test]
+    async fn reads_from_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audispd_events");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let config = AuditdConfig {
+            mode: Mode::Unix,
+            socket_path: path,
+            ..AuditdConfig::default()
+        };
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = config
+            .build(SourceContext {
+                shutdown,
+                ..SourceContext::new_test(tx)
+            })
+            .await
+            .unwrap();
+        let handle = tokio::spawn(source);
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        stream
+            .write_all(
+                b"type=SYSCALL msg=audit(1364481363.243:24287): syscall=2 key=\"files\"\n\
+                  type=CWD msg=audit(1364481363.243:24287): cwd=\"/root\"\n\
+                  type=EOE msg=audit(1364481363.243:24287): \n",
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        drop(trigger);
+        handle.await.unwrap().unwrap();
+
+        let events = collect_ready(rx).await;
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["records[1].cwd"], "/root".into());
+        assert_eq!(log["rule_keys[0]"], "files".into());
+    }
+}
@@ -0,0 +1,178 @@
+//! A reader of the audit netlink socket.
+//!
+//! Rather than taking over the socket from `auditd`, the reader joins the
+//! read-only multicast group of the audit log, so it can run alongside it.
+//! This requires `CAP_AUDIT_READ` and Linux 3.16 or later.
+//!
+//! See `include/uapi/linux/audit.h` and `include/uapi/linux/netlink.h` for
+//! the wire format.
+
+use super::parser::record_type_name;
+use std::{
+    convert::TryInto,
+    io, mem,
+    os::unix::io::{AsRawFd, RawFd},
+};
+use tokio::io::unix::AsyncFd;
+
+const AUDIT_NLGRP_READLOG: u32 = 1;
+const NLMSG_HDRLEN: usize = mem::size_of::<libc::nlmsghdr>();
+/// Room for the longest audit message, `MAX_AUDIT_MESSAGE_LENGTH`, and then
+/// some.
+const RECEIVE_BUFFER_LENGTH: usize = 16 * 1024;
+
+/// A socket subscribed to the audit log multicast group.
+pub struct AuditSocket {
+    fd: AsyncFd<Socket>,
+}
+
+struct Socket(RawFd);
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.0) };
+    }
+}
+
+impl AuditSocket {
+    /// Open an audit netlink socket and subscribe to the audit log.
+    pub fn open() -> io::Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                libc::NETLINK_AUDIT,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let socket = Socket(fd);
+
+        let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        address.nl_pid = 0; // let the kernel assign the port id
+        address.nl_groups = AUDIT_NLGRP_READLOG;
+        let result = unsafe {
+            libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            fd: AsyncFd::new(socket)?,
+        })
+    }
+
+    /// Wait for the next batch of records, formatted as in the audit log.
+    pub async fn next_records(&mut self) -> io::Result<Vec<String>> {
+        let mut buf = vec![0u8; RECEIVE_BUFFER_LENGTH];
+        loop {
+            let mut guard = self.fd.readable().await?;
+            let result = guard.try_io(|fd| {
+                let read = unsafe {
+                    libc::recv(
+                        fd.as_raw_fd(),
+                        buf.as_mut_ptr() as *mut libc::c_void,
+                        buf.len(),
+                        0,
+                    )
+                };
+                if read < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(read as usize)
+                }
+            });
+            let read = match result {
+                Ok(result) => result?,
+                Err(_would_block) => continue,
+            };
+
+            return Ok(parse_messages(&buf[..read]));
+        }
+    }
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset + 4)
+        .map(|bytes| u32::from_ne_bytes(bytes.try_into().expect("slice is 4 bytes")))
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+    buf.get(offset..offset + 2)
+        .map(|bytes| u16::from_ne_bytes(bytes.try_into().expect("slice is 2 bytes")))
+}
+
+/// Extract the records from a datagram, which may contain multiple netlink
+/// messages. Their payload is the text of the record following the
+/// `msg=` of the audit log, as the type is given in the message header.
+fn parse_messages(mut buf: &[u8]) -> Vec<String> {
+    let mut records = Vec::new();
+    while buf.len() >= NLMSG_HDRLEN {
+        let len = read_u32(buf, 0).unwrap_or(0) as usize;
+        if len < NLMSG_HDRLEN || len > buf.len() {
+            break;
+        }
+
+        if let Some(record_type) = read_u16(buf, 4) {
+            let payload = String::from_utf8_lossy(&buf[NLMSG_HDRLEN..len]);
+            records.push(format!(
+                "type={} msg={}",
+                record_type_name(record_type),
+                payload.trim_end_matches('\0')
+            ));
+        }
+
+        // Messages are aligned to 4 bytes.
+        let aligned = (len + 3) & !3;
+        buf = buf.get(aligned..).unwrap_or_default();
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(record_type: u16, payload: &str) -> Vec<u8> {
+        let total_len = NLMSG_HDRLEN + payload.len();
+        let mut buf = vec![0u8; (total_len + 3) & !3];
+        buf[0..4].copy_from_slice(&(total_len as u32).to_ne_bytes());
+        buf[4..6].copy_from_slice(&record_type.to_ne_bytes());
+        buf[NLMSG_HDRLEN..total_len].copy_from_slice(payload.as_bytes());
+        buf
+    }
+
+    #[test]
+    fn parses_audit_messages() {
+        let mut buf = message(1300, "audit(1364481363.243:24287): arch=c000003e syscall=2");
+        buf.extend(message(1320, "audit(1364481363.243:24287): "));
+
+        assert_eq!(
+            parse_messages(&buf),
+            vec![
+                "type=SYSCALL msg=audit(1364481363.243:24287): arch=c000003e syscall=2",
+                "type=EOE msg=audit(1364481363.243:24287): ",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_truncated_messages() {
+        let buf = message(1300, "audit(1364481363.243:24287): arch=c000003e");
+        assert!(parse_messages(&buf[..NLMSG_HDRLEN - 1]).is_empty());
+        assert!(parse_messages(&buf[..buf.len() - 4]).is_empty());
+    }
+}
@@ -0,0 +1,511 @@
+//! Parsing of audit records and reassembly of the records of an audit event.
+//!
+//! Records look like the lines of `/var/log/audit/audit.log`, as in
+//! `type=SYSCALL msg=audit(1364481363.243:24287): arch=c000003e syscall=2 ...`.
+//! The kernel sends most events as several records sharing the same
+//! timestamp and serial, ending with an `EOE` record.
+
+use chrono::{DateTime, TimeZone, Utc};
+use indexmap::IndexMap;
+use std::time::{Duration, Instant};
+
+/// The names of the record types, from `include/uapi/linux/audit.h` and
+/// `lib/libaudit.h` of the audit userspace.
+const RECORD_TYPES: &[(u16, &str)] = &[
+    (1006, "LOGIN"),
+    (1100, "USER_AUTH"),
+    (1101, "USER_ACCT"),
+    (1102, "USER_MGMT"),
+    (1103, "CRED_ACQ"),
+    (1104, "CRED_DISP"),
+    (1105, "USER_START"),
+    (1106, "USER_END"),
+    (1107, "USER_AVC"),
+    (1108, "USER_CHAUTHTOK"),
+    (1109, "USER_ERR"),
+    (1110, "CRED_REFR"),
+    (1111, "USYS_CONFIG"),
+    (1112, "USER_LOGIN"),
+    (1113, "USER_LOGOUT"),
+    (1114, "ADD_USER"),
+    (1115, "DEL_USER"),
+    (1116, "ADD_GROUP"),
+    (1117, "DEL_GROUP"),
+    (1123, "USER_CMD"),
+    (1124, "USER_TTY"),
+    (1130, "SERVICE_START"),
+    (1131, "SERVICE_STOP"),
+    (1200, "DAEMON_START"),
+    (1201, "DAEMON_END"),
+    (1203, "DAEMON_CONFIG"),
+    (1300, "SYSCALL"),
+    (1302, "PATH"),
+    (1303, "IPC"),
+    (1304, "SOCKETCALL"),
+    (1305, "CONFIG_CHANGE"),
+    (1306, "SOCKADDR"),
+    (1307, "CWD"),
+    (1309, "EXECVE"),
+    (1311, "IPC_SET_PERM"),
+    (1312, "MQ_OPEN"),
+    (1313, "MQ_SENDRECV"),
+    (1314, "MQ_NOTIFY"),
+    (1315, "MQ_GETSETATTR"),
+    (1316, "KERNEL_OTHER"),
+    (1317, "FD_PAIR"),
+    (1318, "OBJ_PID"),
+    (1319, "TTY"),
+    (1320, "EOE"),
+    (1321, "BPRM_FCAPS"),
+    (1322, "CAPSET"),
+    (1323, "MMAP"),
+    (1324, "NETFILTER_PKT"),
+    (1325, "NETFILTER_CFG"),
+    (1326, "SECCOMP"),
+    (1327, "PROCTITLE"),
+    (1328, "FEATURE_CHANGE"),
+    (1329, "REPLACE"),
+    (1330, "KERN_MODULE"),
+    (1331, "FANOTIFY"),
+    (1332, "TIME_INJOFFSET"),
+    (1333, "TIME_ADJNTPVAL"),
+    (1334, "BPF"),
+    (1335, "EVENT_LISTENER"),
+    (1400, "AVC"),
+    (1401, "SELINUX_ERR"),
+    (1402, "AVC_PATH"),
+    (1700, "ANOM_PROMISCUOUS"),
+    (1701, "ANOM_ABEND"),
+    (1702, "ANOM_LINK"),
+    (1703, "ANOM_CREAT"),
+];
+
+/// The fields whose unquoted values are hex encoded, as their contents may
+/// hold spaces or other special characters.
+const ENCODED_FIELDS: &[&str] = &[
+    "acct",
+    "cmd",
+    "comm",
+    "cwd",
+    "data",
+    "exe",
+    "key",
+    "name",
+    "path",
+    "proctitle",
+];
+
+/// Separates the multiple keys of the rules matching a syscall.
+const KEY_SEPARATOR: char = '\x01';
+
+/// Returns the name of a record type, named as `auditd` does when unknown.
+pub fn record_type_name(record_type: u16) -> String {
+    RECORD_TYPES
+        .iter()
+        .find(|(number, _)| *number == record_type)
+        .map(|(_, name)| (*name).to_owned())
+        .unwrap_or_else(|| format!("UNKNOWN[{}]", record_type))
+}
+
+fn record_type_number(name: &str) -> Option<u16> {
+    RECORD_TYPES
+        .iter()
+        .find(|(_, known)| *known == name)
+        .map(|(number, _)| *number)
+        .or_else(|| {
+            name.strip_prefix("UNKNOWN[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok()
+        })
+}
+
+/// One record of an audit event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub record_type: String,
+    pub timestamp: DateTime<Utc>,
+    pub serial: u64,
+    pub fields: IndexMap<String, String>,
+    /// The record as received.
+    pub text: String,
+}
+
+impl Record {
+    /// Whether the record makes up an event on its own, as the messages
+    /// logged by userspace programs do, rather than being followed by more
+    /// records of the same event.
+    fn is_standalone(&self) -> bool {
+        matches!(
+            record_type_number(&self.record_type),
+            Some(1100..=1299) | Some(2100..=2999)
+        )
+    }
+
+    /// The keys of the audit rules that matched the event, if any.
+    pub fn rule_keys(&self) -> impl Iterator<Item = &str> {
+        self.fields
+            .get("key")
+            .into_iter()
+            .flat_map(|keys| keys.split(KEY_SEPARATOR))
+            .filter(|key| !key.is_empty())
+    }
+}
+
+/// Parses a record, given in the format of the audit log.
+pub fn parse_record(line: &str) -> Result<Record, &'static str> {
+    let line = line.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+    let start = line.find("type=").ok_or("Record is missing its type.")?;
+    let rest = &line[start + "type=".len()..];
+    let (record_type, rest) = rest.split_at(rest.find(' ').ok_or("Record is truncated.")?);
+    let rest = rest
+        .trim_start()
+        .strip_prefix("msg=audit(")
+        .ok_or("Record is missing its timestamp.")?;
+    let end = rest.find("):").ok_or("Record has an invalid timestamp.")?;
+    let (timestamp, serial) =
+        parse_header(&rest[..end]).ok_or("Record has an invalid timestamp.")?;
+
+    let mut fields = IndexMap::new();
+    // Records relayed by the audit dispatcher may be prefixed by the name of
+    // the host they come from.
+    if let Some(node) = line[..start].trim().strip_prefix("node=") {
+        fields.insert("node".to_owned(), node.to_owned());
+    }
+    parse_fields(record_type, &rest[end + 2..], &mut fields);
+
+    Ok(Record {
+        record_type: record_type.to_owned(),
+        timestamp,
+        serial,
+        fields,
+        text: line.to_owned(),
+    })
+}
+
+/// Parses the `<seconds>.<milliseconds>:<serial>` header of a record.
+fn parse_header(header: &str) -> Option<(DateTime<Utc>, u64)> {
+    let (time, serial) = header.split_at(header.find(':')?);
+    let (seconds, millis) = time.split_at(time.find('.')?);
+    let timestamp = Utc
+        .timestamp_opt(
+            seconds.parse().ok()?,
+            millis[1..].parse::<u32>().ok()? * 1_000_000,
+        )
+        .single()?;
+    Some((timestamp, serial[1..].parse().ok()?))
+}
+
+/// Parses the `key=value` fields of a record. The fields of the messages of
+/// userspace programs, quoted in a `msg` field, are parsed as if they were
+/// fields of the record.
+fn parse_fields(record_type: &str, mut text: &str, fields: &mut IndexMap<String, String>) {
+    loop {
+        // Enriched records separate their resolved fields with a group
+        // separator.
+        text = text.trim_start_matches(|c: char| c.is_whitespace() || c == '\x1d');
+        let equals = match text.find('=') {
+            Some(equals) => equals,
+            None => break,
+        };
+        // Any text before the key, which shouldn't be there, is skipped.
+        let key = text[..equals]
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let value = &text[equals + 1..];
+
+        let (value, quote, rest) = match value.chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => {
+                let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                (
+                    &value[1..end],
+                    Some(quote),
+                    value.get(end + 1..).unwrap_or_default(),
+                )
+            }
+            _ => {
+                let end = value
+                    .find(|c: char| c.is_whitespace() || c == '\x1d')
+                    .unwrap_or(value.len());
+                (&value[..end], None, &value[end..])
+            }
+        };
+        text = rest;
+
+        if key.is_empty() {
+            continue;
+        }
+        match quote {
+            Some('\'') if key == "msg" => parse_fields(record_type, value, fields),
+            Some(_) => {
+                fields.insert(key.to_owned(), value.to_owned());
+            }
+            None if value == "(null)" || value == "(none)" => {}
+            None => {
+                fields.insert(key.to_owned(), decode_value(record_type, key, value));
+            }
+        }
+    }
+}
+
+/// Decodes the hex encoded values of the fields that may be encoded.
+fn decode_value(record_type: &str, key: &str, value: &str) -> String {
+    // The arguments of `EXECVE` records are encoded, unlike the ones of
+    // `SYSCALL` records, which are numbers.
+    let encoded = ENCODED_FIELDS.contains(&key) || (record_type == "EXECVE" && is_argument(key));
+    if !encoded {
+        return value.to_owned();
+    }
+    match decode_hex(value) {
+        Some(bytes) => {
+            let decoded = String::from_utf8_lossy(&bytes);
+            if key == "proctitle" {
+                // The arguments of the command line are separated by NULs.
+                decoded.trim_end_matches('\0').replace('\0', " ")
+            } else {
+                decoded.into_owned()
+            }
+        }
+        None => value.to_owned(),
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.is_empty() || value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// Whether the field is one of the `a<n>` arguments of an `EXECVE` record.
+pub fn is_argument(key: &str) -> bool {
+    key.len() > 1 && key.starts_with('a') && key[1..].bytes().all(|c| c.is_ascii_digit())
+}
+
+/// Groups records by the event they belong to.
+pub struct Reassembler {
+    timeout: Duration,
+    pending: IndexMap<u64, (Instant, Vec<Record>)>,
+}
+
+impl Reassembler {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            pending: IndexMap::new(),
+        }
+    }
+
+    /// Adds a record, returning the events it completes.
+    pub fn push(&mut self, record: Record, now: Instant) -> Vec<Vec<Record>> {
+        if record.record_type == "EOE" {
+            return self
+                .pending
+                .shift_remove(&record.serial)
+                .map(|(_, records)| records)
+                .into_iter()
+                .collect();
+        }
+        if record.is_standalone() && !self.pending.contains_key(&record.serial) {
+            return vec![vec![record]];
+        }
+
+        self.pending
+            .entry(record.serial)
+            .or_insert_with(|| (now, Vec::new()))
+            .1
+            .push(record);
+        Vec::new()
+    }
+
+    /// Returns the events whose `EOE` record hasn't come within the timeout,
+    /// as the events of some record types don't end with one.
+    pub fn flush_expired(&mut self, now: Instant) -> Vec<Vec<Record>> {
+        let mut expired = Vec::new();
+        // Events are pending in the order they started, so only the oldest
+        // ones need to be checked.
+        while let Some((_, (started, _))) = self.pending.get_index(0) {
+            if now.duration_since(*started) < self.timeout {
+                break;
+            }
+            let (_, (_, records)) = self.pending.shift_remove_index(0).expect("pending event");
+            expired.push(records);
+        }
+        expired
+    }
+
+    pub fn flush_all(&mut self) -> Vec<Vec<Record>> {
+        self.pending
+            .drain(..)
+            .map(|(_, (_, records))| records)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn record(line: &str) -> Record {
+        parse_record(line).unwrap()
+    }
+
+    fn fields(record: &Record) -> Vec<(&str, &str)> {
+        record
+            .fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_syscall_records() {
+        let record = record(
+            r#"type=SYSCALL msg=audit(1364481363.243:24287): arch=c000003e syscall=2 success=no exit=-13 a0=7fffd19c5592 ppid=2686 pid=3538 auid=1000 uid=1000 comm="cat" exe="/bin/cat" subj=(null) key="power""#,
+        );
+
+        assert_eq!(record.record_type, "SYSCALL");
+        assert_eq!(record.serial, 24287);
+        assert_eq!(record.timestamp, Utc.timestamp(1364481363, 243_000_000));
+        assert_eq!(
+            fields(&record),
+            vec![
+                ("arch", "c000003e"),
+                ("syscall", "2"),
+                ("success", "no"),
+                ("exit", "-13"),
+                ("a0", "7fffd19c5592"),
+                ("ppid", "2686"),
+                ("pid", "3538"),
+                ("auid", "1000"),
+                ("uid", "1000"),
+                ("comm", "cat"),
+                ("exe", "/bin/cat"),
+                ("key", "power"),
+            ]
+        );
+        assert_eq!(record.rule_keys().collect::<Vec<_>>(), vec!["power"]);
+    }
+
+    #[test]
+    fn parses_user_messages() {
+        let record = record(
+            "node=web-1 type=USER_LOGIN msg=audit(1364475353.159:24270): pid=3280 uid=0 auid=1000 ses=8 msg='op=login id=1000 exe=\"/usr/sbin/sshd\" hostname=10.0.0.1 addr=10.0.0.1 terminal=/dev/pts/1 res=success'",
+        );
+
+        assert_eq!(record.record_type, "USER_LOGIN");
+        assert_eq!(
+            fields(&record),
+            vec![
+                ("node", "web-1"),
+                ("pid", "3280"),
+                ("uid", "0"),
+                ("auid", "1000"),
+                ("ses", "8"),
+                ("op", "login"),
+                ("id", "1000"),
+                ("exe", "/usr/sbin/sshd"),
+                ("hostname", "10.0.0.1"),
+                ("addr", "10.0.0.1"),
+                ("terminal", "/dev/pts/1"),
+                ("res", "success"),
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_hex_values() {
+        let record = record(
+            "type=PROCTITLE msg=audit(1364481363.243:24287): proctitle=2F62696E2F6C73002D6C61",
+        );
+        assert_eq!(record.fields["proctitle"], "/bin/ls -la");
+
+        let record = record(
+            "type=EXECVE msg=audit(1364481363.243:24287): argc=2 a0=\"echo\" a1=68656C6C6F20776F726C64",
+        );
+        assert_eq!(record.fields["a0"], "echo");
+        assert_eq!(record.fields["a1"], "hello world");
+        assert_eq!(record.fields["argc"], "2");
+        assert!(is_argument("a1"));
+        assert!(!is_argument("arch"));
+
+        let record = record(
+            "type=SYSCALL msg=audit(1364481363.243:24287): syscall=59 key=706F77657201657865637574696F6E",
+        );
+        assert_eq!(
+            record.rule_keys().collect::<Vec<_>>(),
+            vec!["power", "execution"]
+        );
+    }
+
+    #[test]
+    fn parses_enriched_records() {
+        let record = record(
+            "type=SYSCALL msg=audit(1364481363.243:24287): syscall=2 uid=0\x1dARCH=x86_64 UID=\"root\"",
+        );
+        assert_eq!(
+            fields(&record),
+            vec![
+                ("syscall", "2"),
+                ("uid", "0"),
+                ("ARCH", "x86_64"),
+                ("UID", "root")
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_records() {
+        assert!(parse_record("").is_err());
+        assert!(parse_record("type=SYSCALL arch=c000003e").is_err());
+        assert!(parse_record("type=SYSCALL msg=audit(yesterday:1): arch=c000003e").is_err());
+    }
+
+    #[test]
+    fn names_record_types() {
+        assert_eq!(record_type_name(1300), "SYSCALL");
+        assert_eq!(record_type_name(9999), "UNKNOWN[9999]");
+        assert_eq!(record_type_number("UNKNOWN[9999]"), Some(9999));
+    }
+
+    #[test]
+    fn reassembles_events() {
+        let now = Instant::now();
+        let mut reassembler = Reassembler::new(Duration::from_secs(2));
+
+        assert!(reassembler
+            .push(record("type=SYSCALL msg=audit(1.000:1): syscall=59"), now)
+            .is_empty());
+        assert!(reassembler
+            .push(record("type=SYSCALL msg=audit(1.000:2): syscall=2"), now)
+            .is_empty());
+        assert!(reassembler
+            .push(record("type=CWD msg=audit(1.000:1): cwd=\"/\""), now)
+            .is_empty());
+
+        let user = reassembler.push(record("type=USER_CMD msg=audit(1.001:3): pid=1"), now);
+        assert_eq!(user.len(), 1);
+        assert_eq!(user[0][0].serial, 3);
+
+        let events = reassembler.push(record("type=EOE msg=audit(1.000:1): "), now);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0]
+                .iter()
+                .map(|record| record.record_type.as_str())
+                .collect::<Vec<_>>(),
+            vec!["SYSCALL", "CWD"]
+        );
+
+        assert!(reassembler.flush_expired(now).is_empty());
+        let expired = reassembler.flush_expired(now + Duration::from_secs(2));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0][0].serial, 2);
+        assert!(reassembler.flush_all().is_empty());
+    }
+}
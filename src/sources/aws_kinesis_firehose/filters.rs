@@ -20,6 +20,7 @@ use warp::{http::StatusCode, Filter};
 pub fn firehose(
     access_key: Option<String>,
     record_compression: Compression,
+    unwrap_cloudwatch_logs: bool,
     decoder: codecs::Decoder,
     out: Pipeline,
 ) -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
@@ -42,6 +43,7 @@ pub fn firehose(
         )
         .and(parse_body())
         .and(warp::any().map(move || record_compression))
+        .and(warp::any().map(move || unwrap_cloudwatch_logs))
         .and(warp::any().map(move || decoder.clone()))
         .and(warp::any().map(move || out.clone()))
         .and_then(handlers::firehose)
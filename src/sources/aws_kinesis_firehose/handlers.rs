@@ -5,16 +5,19 @@ use crate::codecs;
 use crate::sources::util::TcpError;
 use crate::{
     config::log_schema,
-    event::Event,
+    event::{Event, LogEvent},
     internal_events::{
         AwsKinesisFirehoseAutomaticRecordDecodeError, AwsKinesisFirehoseEventsReceived,
     },
     Pipeline,
 };
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use flate2::read::MultiGzDecoder;
 use futures::{SinkExt, StreamExt, TryFutureExt};
+use shared::aws_cloudwatch_logs_subscription::{
+    AwsCloudWatchLogsSubscriptionMessage, AwsCloudWatchLogsSubscriptionMessageType,
+};
 use snafu::{ResultExt, Snafu};
 use std::io::Read;
 use tokio_util::codec::FramedRead;
@@ -26,6 +29,7 @@ pub async fn firehose(
     source_arn: String,
     request: FirehoseRequest,
     compression: Compression,
+    unwrap_cloudwatch_logs: bool,
     decoder: codecs::Decoder,
     mut out: Pipeline,
 ) -> Result<impl warp::Reply, reject::Rejection> {
@@ -36,6 +40,26 @@ pub async fn firehose(
             })
             .map_err(reject::custom)?;
 
+        if unwrap_cloudwatch_logs {
+            if let Some(messages) = parse_cloudwatch_logs(&bytes) {
+                let events = cloudwatch_logs_events(messages);
+                emit!(&AwsKinesisFirehoseEventsReceived {
+                    count: events.len(),
+                    byte_size: bytes.len(),
+                });
+
+                send_events(
+                    &mut out,
+                    events,
+                    &request_id,
+                    &source_arn,
+                    request.timestamp,
+                )
+                .await?;
+                continue;
+            }
+        }
+
         let mut stream = FramedRead::new(bytes.as_ref(), decoder.clone());
         loop {
             match stream.next().await {
@@ -45,31 +69,14 @@ pub async fn firehose(
                         byte_size
                     });
 
-                    for mut event in events {
-                        if let Event::Log(ref mut log) = event {
-                            log.try_insert(
-                                log_schema().source_type_key(),
-                                Bytes::from("aws_kinesis_firehose"),
-                            );
-                            log.try_insert(log_schema().timestamp_key(), request.timestamp);
-                            log.try_insert_flat("request_id", request_id.to_string());
-                            log.try_insert_flat("source_arn", source_arn.to_string());
-                        }
-
-                        out.send(event)
-                            .map_err(|error| {
-                                let error = RequestError::ShuttingDown {
-                                    request_id: request_id.clone(),
-                                    source: error,
-                                };
-                                // can only fail if receiving end disconnected, so we are shutting
-                                // down, probably not gracefully.
-                                error!(message = "Failed to forward events, downstream is closed.");
-                                error!(message = "Tried to send the following event.", %error);
-                                warp::reject::custom(error)
-                            })
-                            .await?;
-                    }
+                    send_events(
+                        &mut out,
+                        events,
+                        &request_id,
+                        &source_arn,
+                        request.timestamp,
+                    )
+                    .await?;
                 }
                 Some(Err(error)) => {
                     // Error is logged by `crate::codecs::Decoder`, no further
@@ -90,6 +97,83 @@ pub async fn firehose(
     }))
 }
 
+async fn send_events(
+    out: &mut Pipeline,
+    events: impl IntoIterator<Item = Event>,
+    request_id: &str,
+    source_arn: &str,
+    timestamp: DateTime<Utc>,
+) -> Result<(), reject::Rejection> {
+    for mut event in events {
+        if let Event::Log(ref mut log) = event {
+            log.try_insert(
+                log_schema().source_type_key(),
+                Bytes::from("aws_kinesis_firehose"),
+            );
+            log.try_insert(log_schema().timestamp_key(), timestamp);
+            log.try_insert_flat("request_id", request_id.to_string());
+            log.try_insert_flat("source_arn", source_arn.to_string());
+        }
+
+        out.send(event)
+            .map_err(|error| {
+                let error = RequestError::ShuttingDown {
+                    request_id: request_id.to_string(),
+                    source: error,
+                };
+                // can only fail if receiving end disconnected, so we are shutting
+                // down, probably not gracefully.
+                error!(message = "Failed to forward events, downstream is closed.");
+                error!(message = "Tried to send the following event.", %error);
+                warp::reject::custom(error)
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+/// Parses the record as the envelopes CloudWatch Logs subscriptions deliver
+/// log events in, returning `None` if the record is in any other format.
+fn parse_cloudwatch_logs(bytes: &[u8]) -> Option<Vec<AwsCloudWatchLogsSubscriptionMessage>> {
+    let messages = serde_json::Deserializer::from_slice(bytes)
+        .into_iter::<AwsCloudWatchLogsSubscriptionMessage>()
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    (!messages.is_empty()).then(|| messages)
+}
+
+/// Builds one event per log event of the envelopes, dropping the control
+/// messages CloudWatch Logs sends to check that the destination is reachable.
+fn cloudwatch_logs_events(messages: Vec<AwsCloudWatchLogsSubscriptionMessage>) -> Vec<Event> {
+    messages
+        .into_iter()
+        .filter(|message| {
+            matches!(
+                message.message_type,
+                AwsCloudWatchLogsSubscriptionMessageType::DataMessage
+            )
+        })
+        .flat_map(|message| {
+            let log_group = message.log_group;
+            let log_stream = message.log_stream;
+            let owner = message.owner;
+            let subscription_filters = message.subscription_filters;
+
+            message.log_events.into_iter().map(move |log_event| {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().message_key(), log_event.message);
+                log.insert(log_schema().timestamp_key(), log_event.timestamp);
+                log.insert_flat("id", log_event.id);
+                log.insert_flat("log_group", log_group.clone());
+                log.insert_flat("log_stream", log_stream.clone());
+                log.insert_flat("owner", owner.clone());
+                log.insert_flat("subscription_filters", subscription_filters.clone());
+                Event::from(log)
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug, Snafu)]
 pub enum RecordDecodeError {
     #[snafu(display("Could not base64 decode request data: {}", source))]
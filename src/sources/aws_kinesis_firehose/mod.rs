@@ -1,5 +1,5 @@
 use crate::{
-    codecs::{DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{DecodingConfig, FramingConfig, ParserSettings},
     config::{DataType, GenerateConfig, Resource, SourceConfig, SourceContext, SourceDescription},
     serde::{default_decoding, default_framing_message_based},
     tls::{MaybeTlsSettings, TlsConfig},
@@ -20,10 +20,12 @@ pub struct AwsKinesisFirehoseConfig {
     access_key: Option<String>,
     tls: Option<TlsConfig>,
     record_compression: Option<Compression>,
+    #[serde(default)]
+    unwrap_cloudwatch_logs: bool,
     #[serde(default = "default_framing_message_based")]
     framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 #[derive(Derivative, Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -55,6 +57,7 @@ impl SourceConfig for AwsKinesisFirehoseConfig {
         let svc = filters::firehose(
             self.access_key.clone(),
             self.record_compression.unwrap_or_default(),
+            self.unwrap_cloudwatch_logs,
             decoder,
             cx.out,
         );
@@ -99,6 +102,7 @@ impl GenerateConfig for AwsKinesisFirehoseConfig {
             access_key: None,
             tls: None,
             record_compression: None,
+            unwrap_cloudwatch_logs: false,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
         })
@@ -116,7 +120,7 @@ mod tests {
         Pipeline,
     };
     use bytes::Bytes;
-    use chrono::{DateTime, SubsecRound, Utc};
+    use chrono::{DateTime, SubsecRound, TimeZone, Utc};
     use flate2::read::GzEncoder;
     use futures::channel::mpsc;
     use pretty_assertions::assert_eq;
@@ -134,6 +138,7 @@ mod tests {
     async fn source(
         access_key: Option<String>,
         record_compression: Option<Compression>,
+        unwrap_cloudwatch_logs: bool,
     ) -> (mpsc::Receiver<Event>, SocketAddr) {
         let (sender, recv) = Pipeline::new_test();
         let address = next_addr();
@@ -143,6 +148,7 @@ mod tests {
                 tls: None,
                 access_key,
                 record_compression,
+                unwrap_cloudwatch_logs,
                 framing: default_framing_message_based(),
                 decoding: default_decoding(),
             }
@@ -321,7 +327,7 @@ mod tests {
                 &source_record_compression, &record_compression
             );
 
-            let (rx, addr) = source(None, Some(source_record_compression)).await;
+            let (rx, addr) = source(None, Some(source_record_compression), false).await;
 
             let source_arn = "arn:aws:firehose:us-east-1:111111111111:deliverystream/test";
             let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
@@ -387,7 +393,7 @@ mod tests {
                 ]
         }"#;
 
-        let (rx, addr) = source(None, None).await;
+        let (rx, addr) = source(None, None, false).await;
 
         let source_arn = "arn:aws:firehose:us-east-1:111111111111:deliverystream/test";
         let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
@@ -423,9 +429,108 @@ mod tests {
         assert_eq!(response.request_id, request_id);
     }
 
+    #[tokio::test]
+    async fn aws_kinesis_firehose_unwraps_cloudwatch_logs() {
+        let record = r#"
+            {
+                "messageType": "DATA_MESSAGE",
+                "owner": "071959437513",
+                "logGroup": "/jesse/test",
+                "logStream": "test",
+                "subscriptionFilters": ["Destination"],
+                "logEvents": [
+                    {
+                        "id": "35683658089614582423604394983260738922885519999578275840",
+                        "timestamp": 1600110569039,
+                        "message": "first"
+                    },
+                    {
+                        "id": "35683658089659183914001456229543810359430816722590236673",
+                        "timestamp": 1600110569041,
+                        "message": "second"
+                    }
+                ]
+            }
+        "#;
+        let control = r#"
+            {
+                "messageType": "CONTROL_MESSAGE",
+                "owner": "CloudwatchLogs",
+                "logGroup": "",
+                "logStream": "",
+                "subscriptionFilters": [],
+                "logEvents": [
+                    {
+                        "id": "",
+                        "timestamp": 1600110003794,
+                        "message": "CWL CONTROL MESSAGE: Checking health of destination Firehose."
+                    }
+                ]
+            }
+        "#;
+
+        let (rx, addr) = source(None, None, true).await;
+
+        let source_arn = "arn:aws:firehose:us-east-1:111111111111:deliverystream/test";
+        let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
+        let timestamp: DateTime<Utc> = Utc::now();
+
+        let res = send(
+            addr,
+            timestamp,
+            vec![record.as_bytes(), control.as_bytes(), b"not cloudwatch"],
+            None,
+            request_id,
+            source_arn,
+            false,
+            Compression::Gzip,
+        )
+        .await
+        .unwrap();
+        assert_eq!(200, res.status().as_u16());
+
+        let events = collect_ready(rx).await;
+        assert_event_data_eq!(
+            events,
+            vec![
+                log_event! {
+                    "source_type" => Bytes::from("aws_kinesis_firehose"),
+                    "timestamp" => Utc.timestamp(1600110569, 39000000),
+                    "message" => "first",
+                    "id" => "35683658089614582423604394983260738922885519999578275840",
+                    "log_group" => "/jesse/test",
+                    "log_stream" => "test",
+                    "owner" => "071959437513",
+                    "subscription_filters" => vec!["Destination"],
+                    "request_id" => request_id,
+                    "source_arn" => source_arn,
+                },
+                log_event! {
+                    "source_type" => Bytes::from("aws_kinesis_firehose"),
+                    "timestamp" => Utc.timestamp(1600110569, 41000000),
+                    "message" => "second",
+                    "id" => "35683658089659183914001456229543810359430816722590236673",
+                    "log_group" => "/jesse/test",
+                    "log_stream" => "test",
+                    "owner" => "071959437513",
+                    "subscription_filters" => vec!["Destination"],
+                    "request_id" => request_id,
+                    "source_arn" => source_arn,
+                },
+                log_event! {
+                    "source_type" => Bytes::from("aws_kinesis_firehose"),
+                    "timestamp" => timestamp.trunc_subsecs(3),
+                    "message" => Bytes::from("not cloudwatch"),
+                    "request_id" => request_id,
+                    "source_arn" => source_arn,
+                },
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn aws_kinesis_firehose_rejects_bad_access_key() {
-        let (_rx, addr) = source(Some("an access key".to_string()), None).await;
+        let (_rx, addr) = source(Some("an access key".to_string()), None, false).await;
 
         let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
 
@@ -96,18 +96,23 @@ impl AwsS3Config {
     ) -> Result<sqs::Ingestor, CreateSqsIngestorError> {
         use std::sync::Arc;
 
-        let region: Region = (&self.region).try_into().context(RegionParse {})?;
+        let region = |service| {
+            self.region
+                .region_for_service(service)
+                .context(RegionParse {})
+        };
+        let sqs_region: Region = region("sqs")?;
 
         let client = rusoto::client(proxy).with_context(|| Client {})?;
         let creds: Arc<rusoto::AwsCredentialsProvider> = self
             .auth
-            .build(&region, self.assume_role.clone())
+            .build(&region("sts")?, self.assume_role.clone())
             .context(Credentials {})?
             .into();
         let s3_client = S3Client::new_with(
             client.clone(),
             Arc::<rusoto::AwsCredentialsProvider>::clone(&creds),
-            region.clone(),
+            region("s3")?,
         );
 
         match self.sqs {
@@ -115,11 +120,11 @@ impl AwsS3Config {
                 let sqs_client = SqsClient::new_with(
                     client.clone(),
                     Arc::<rusoto::AwsCredentialsProvider>::clone(&creds),
-                    region.clone(),
+                    sqs_region.clone(),
                 );
 
                 sqs::Ingestor::new(
-                    region.clone(),
+                    sqs_region,
                     sqs_client,
                     s3_client,
                     sqs.clone(),
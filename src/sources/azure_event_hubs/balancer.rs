@@ -0,0 +1,167 @@
+use super::store::Ownership;
+use chrono::{DateTime, Duration, Utc};
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+
+/// Plans the ownership claims of `owner_id` for one load balancing round:
+/// renewals of the partitions it owns, plus at most one more partition if it
+/// owns less than its fair share. The fair share is the number of partitions
+/// divided by the number of active owners, with the remainder spread one per
+/// owner. Unowned partitions, or those whose owner stopped renewing them
+/// within `expiration`, are claimed first, otherwise a partition is taken over
+/// from the owner with the most partitions.
+///
+/// Claiming one partition per round lets the instances converge on a balanced
+/// assignment without all of them racing for the same partitions.
+pub(super) fn plan_claims(
+    partition_ids: &[String],
+    ownership: &[Ownership],
+    owner_id: &str,
+    now: DateTime<Utc>,
+    expiration: Duration,
+) -> Vec<Ownership> {
+    let listed = ownership
+        .iter()
+        .filter(|ownership| partition_ids.contains(&ownership.partition_id))
+        .map(|ownership| (ownership.partition_id.as_str(), ownership))
+        .collect::<HashMap<_, _>>();
+    let is_active = |ownership: &&Ownership| {
+        !ownership.owner_id.is_empty() && now - ownership.last_modified < expiration
+    };
+
+    let mut owned = HashMap::<&str, Vec<&Ownership>>::new();
+    owned.entry(owner_id).or_default();
+    for ownership in listed.values().copied().filter(is_active) {
+        owned
+            .entry(ownership.owner_id.as_str())
+            .or_default()
+            .push(ownership);
+    }
+
+    let claim = |partition_id: &str| Ownership {
+        partition_id: partition_id.to_owned(),
+        owner_id: owner_id.to_owned(),
+        last_modified: now,
+        etag: listed
+            .get(partition_id)
+            .and_then(|ownership| ownership.etag.clone()),
+    };
+    let mut claims = owned[owner_id]
+        .iter()
+        .map(|ownership| claim(&ownership.partition_id))
+        .collect::<Vec<_>>();
+
+    let minimum = partition_ids.len() / owned.len();
+    let remainder = partition_ids.len() % owned.len();
+    let owns = owned[owner_id].len();
+    let above_minimum = owned.values().filter(|owned| owned.len() > minimum).count();
+    if owns > minimum || (owns == minimum && above_minimum >= remainder) {
+        return claims;
+    }
+
+    let mut rng = rand::thread_rng();
+    let unowned = partition_ids
+        .iter()
+        .filter(|id| !listed.get(id.as_str()).map_or(false, is_active))
+        .collect::<Vec<_>>();
+    if let Some(partition_id) = unowned.choose(&mut rng) {
+        claims.push(claim(partition_id));
+        return claims;
+    }
+
+    let busiest = owned
+        .iter()
+        .filter(|(owner, _)| **owner != owner_id)
+        .max_by_key(|(_, owned)| owned.len())
+        .map(|(_, owned)| owned);
+    if let Some(busiest) = busiest.filter(|busiest| busiest.len() > owns + 1) {
+        if let Some(ownership) = busiest.choose(&mut rng) {
+            claims.push(claim(&ownership.partition_id));
+        }
+    }
+    claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partitions(count: usize) -> Vec<String> {
+        (0..count).map(|id| id.to_string()).collect()
+    }
+
+    fn owned_by(owner_id: &str, partition_id: &str, last_modified: DateTime<Utc>) -> Ownership {
+        Ownership {
+            partition_id: partition_id.to_owned(),
+            owner_id: owner_id.to_owned(),
+            last_modified,
+            etag: Some(format!("{}-{}", owner_id, partition_id)),
+        }
+    }
+
+    fn claimed(claims: &[Ownership]) -> Vec<&str> {
+        let mut claimed = claims
+            .iter()
+            .map(|claim| claim.partition_id.as_str())
+            .collect::<Vec<_>>();
+        claimed.sort_unstable();
+        claimed
+    }
+
+    #[test]
+    fn claims_one_unowned_partition_per_round() {
+        let now = Utc::now();
+        let claims = plan_claims(&partitions(4), &[], "a", now, Duration::seconds(60));
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].owner_id, "a");
+        assert_eq!(claims[0].etag, None);
+    }
+
+    #[test]
+    fn renews_owned_partitions() {
+        let now = Utc::now();
+        let ownership = vec![owned_by("a", "0", now), owned_by("b", "1", now)];
+        let claims = plan_claims(&partitions(2), &ownership, "a", now, Duration::seconds(60));
+        assert_eq!(claimed(&claims), vec!["0"]);
+        assert_eq!(claims[0].etag.as_deref(), Some("a-0"));
+    }
+
+    #[test]
+    fn claims_expired_partitions() {
+        let now = Utc::now();
+        let ownership = vec![
+            owned_by("a", "0", now),
+            owned_by("b", "1", now - Duration::seconds(120)),
+        ];
+        let claims = plan_claims(&partitions(2), &ownership, "a", now, Duration::seconds(60));
+        assert_eq!(claimed(&claims), vec!["0", "1"]);
+        assert_eq!(claims[1].etag.as_deref(), Some("b-1"));
+    }
+
+    #[test]
+    fn takes_over_from_the_busiest_owner() {
+        let now = Utc::now();
+        let ownership = (0..4)
+            .map(|id| owned_by("b", &id.to_string(), now))
+            .collect::<Vec<_>>();
+        let claims = plan_claims(&partitions(4), &ownership, "a", now, Duration::seconds(60));
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].owner_id, "a");
+        assert!(claims[0].etag.as_deref().unwrap().starts_with("b-"));
+    }
+
+    #[test]
+    fn stops_claiming_at_fair_share() {
+        let now = Utc::now();
+        let ownership = vec![
+            owned_by("a", "0", now),
+            owned_by("a", "1", now),
+            owned_by("b", "2", now),
+        ];
+        let claims = plan_claims(&partitions(3), &ownership, "a", now, Duration::seconds(60));
+        assert_eq!(claimed(&claims), vec!["0", "1"]);
+
+        let claims = plan_claims(&partitions(3), &ownership, "b", now, Duration::seconds(60));
+        assert_eq!(claimed(&claims), vec!["2"]);
+    }
+}
@@ -0,0 +1,424 @@
+use crate::event::Value;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use fe2o3_amqp::{
+    connection::ConnectionHandle,
+    sasl_profile::SaslProfile,
+    session::SessionHandle,
+    types::{
+        messaging::{ApplicationProperties, Body, Message, Properties, Source},
+        primitives::{SimpleValue, Symbol, Value as AmqpValue},
+    },
+    Connection, Delivery, Receiver, Sender, Session,
+};
+use serde_amqp::described::Described;
+use serde_amqp::descriptor::Descriptor;
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+const AMQPS_PORT: u16 = 5671;
+const MANAGEMENT_ADDRESS: &str = "$management";
+const MANAGEMENT_REPLY_TO: &str = "vector-management";
+const SELECTOR_FILTER: &str = "apache.org:selector-filter:string";
+const OFFSET_ANNOTATION: &str = "x-opt-offset";
+const SEQUENCE_NUMBER_ANNOTATION: &str = "x-opt-sequence-number";
+const ENQUEUED_TIME_ANNOTATION: &str = "x-opt-enqueued-time";
+
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum ConnectionStringError {
+    #[snafu(display("Connection string is missing `{}`", key))]
+    MissingKey { key: &'static str },
+    #[snafu(display("Connection string has an invalid endpoint: {:?}", endpoint))]
+    InvalidEndpoint { endpoint: String },
+    #[snafu(display(
+        "No event hub name given, set `event_hub_name` or `EntityPath` in the connection string"
+    ))]
+    MissingEventHubName,
+}
+
+#[derive(Debug, Snafu)]
+enum ManagementError {
+    #[snafu(display("The management response has no partition IDs"))]
+    MissingPartitionIds,
+}
+
+/// The parts of an Event Hubs connection string needed to connect to an
+/// event hub with a shared access key.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct ConnectionConfig {
+    pub(super) namespace: String,
+    pub(super) event_hub_name: String,
+    key_name: String,
+    key: String,
+}
+
+impl ConnectionConfig {
+    /// Parses a connection string such as
+    /// `Endpoint=sb://<namespace>/;SharedAccessKeyName=<name>;SharedAccessKey=<key>;EntityPath=<event hub>`,
+    /// where `event_hub_name` takes precedence over the `EntityPath`.
+    pub(super) fn parse(
+        connection_string: &str,
+        event_hub_name: Option<&str>,
+    ) -> Result<Self, ConnectionStringError> {
+        let parts = connection_string
+            .split(';')
+            .filter_map(|part| part.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect::<BTreeMap<_, _>>();
+        let get = |key: &'static str| {
+            parts
+                .get(key)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+                .ok_or(ConnectionStringError::MissingKey { key })
+        };
+
+        let endpoint = get("Endpoint")?;
+        let namespace = endpoint
+            .strip_prefix("sb://")
+            .map(|namespace| namespace.trim_end_matches('/'))
+            .filter(|namespace| !namespace.is_empty() && !namespace.contains('/'))
+            .ok_or_else(|| ConnectionStringError::InvalidEndpoint {
+                endpoint: endpoint.clone(),
+            })?
+            .to_owned();
+        let event_hub_name = match event_hub_name {
+            Some(name) => name.to_owned(),
+            None => get("EntityPath").map_err(|_| ConnectionStringError::MissingEventHubName)?,
+        };
+
+        Ok(Self {
+            namespace,
+            event_hub_name,
+            key_name: get("SharedAccessKeyName")?,
+            key: get("SharedAccessKey")?,
+        })
+    }
+
+    fn url(&self) -> String {
+        format!("amqps://{}:{}", self.namespace, AMQPS_PORT)
+    }
+}
+
+/// Where to start receiving the events of a partition.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum EventPosition {
+    /// After the event at this offset.
+    After(String),
+    Earliest,
+    Latest,
+}
+
+impl EventPosition {
+    fn selector(&self) -> String {
+        let offset = match self {
+            Self::After(offset) => offset.as_str(),
+            Self::Earliest => "-1",
+            Self::Latest => "@latest",
+        };
+        format!("amqp.annotation.{} > '{}'", OFFSET_ANNOTATION, offset)
+    }
+}
+
+/// An event received from a partition.
+#[derive(Debug)]
+pub(super) struct EventData {
+    pub(super) body: Bytes,
+    pub(super) offset: String,
+    pub(super) sequence_number: i64,
+    pub(super) enqueued_time: Option<DateTime<Utc>>,
+    pub(super) properties: BTreeMap<String, Value>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Client {
+    config: ConnectionConfig,
+}
+
+impl Client {
+    pub(super) const fn new(config: ConnectionConfig) -> Self {
+        Self { config }
+    }
+
+    async fn connect(&self, container_id: &str) -> crate::Result<ConnectionHandle<()>> {
+        let connection = Connection::builder()
+            .container_id(container_id)
+            .hostname(self.config.namespace.as_str())
+            .sasl_profile(SaslProfile::Plain {
+                username: self.config.key_name.clone(),
+                password: self.config.key.clone(),
+            })
+            .open(self.config.url().as_str())
+            .await?;
+        Ok(connection)
+    }
+
+    /// Reads the IDs of the partitions of the event hub from its management
+    /// node.
+    pub(super) async fn partition_ids(&self) -> crate::Result<Vec<String>> {
+        let mut connection = self.connect("vector-management").await?;
+        let mut session = Session::begin(&mut connection).await?;
+        let mut sender =
+            Sender::attach(&mut session, "vector-management-sender", MANAGEMENT_ADDRESS).await?;
+        let mut receiver = Receiver::builder()
+            .name("vector-management-receiver")
+            .source(MANAGEMENT_ADDRESS)
+            .target(MANAGEMENT_REPLY_TO)
+            .attach(&mut session)
+            .await?;
+
+        let request = Message::builder()
+            .properties(
+                Properties::builder()
+                    .message_id(String::from("partition-ids"))
+                    .reply_to(MANAGEMENT_REPLY_TO)
+                    .build(),
+            )
+            .application_properties(
+                ApplicationProperties::builder()
+                    .insert("operation", "READ")
+                    .insert("name", self.config.event_hub_name.as_str())
+                    .insert("type", "com.microsoft:eventhub")
+                    .build(),
+            )
+            .value(())
+            .build();
+        sender.send(request).await?;
+
+        let response: Delivery<AmqpValue> = receiver.recv().await?;
+        receiver.accept(&response).await?;
+        let partition_ids = match response.body() {
+            Body::Value(value) => partition_ids_from_response(&value.0),
+            _ => None,
+        };
+
+        sender.close().await?;
+        receiver.close().await?;
+        session.end().await?;
+        connection.close().await?;
+
+        partition_ids.ok_or_else(|| ManagementError::MissingPartitionIds.into())
+    }
+
+    /// Opens a receiver on a partition of the event hub for the consumer
+    /// group, starting at `position`.
+    pub(super) async fn receive(
+        &self,
+        consumer_group: &str,
+        partition_id: &str,
+        position: &EventPosition,
+    ) -> crate::Result<PartitionReceiver> {
+        let name = format!("vector-{}-{}", consumer_group, partition_id);
+        let mut connection = self.connect(&name).await?;
+        let mut session = Session::begin(&mut connection).await?;
+
+        let address = format!(
+            "{}/ConsumerGroups/{}/Partitions/{}",
+            self.config.event_hub_name, consumer_group, partition_id
+        );
+        let filter = Described {
+            descriptor: Descriptor::Name(Symbol::from(SELECTOR_FILTER)),
+            value: AmqpValue::String(position.selector()),
+        };
+        let receiver = Receiver::builder()
+            .name(name.as_str())
+            .source(
+                Source::builder()
+                    .address(address)
+                    .add_to_filter(Symbol::from(SELECTOR_FILTER), Some(filter))
+                    .build(),
+            )
+            .attach(&mut session)
+            .await?;
+
+        Ok(PartitionReceiver {
+            connection,
+            session,
+            receiver,
+        })
+    }
+}
+
+pub(super) struct PartitionReceiver {
+    connection: ConnectionHandle<()>,
+    session: SessionHandle<()>,
+    receiver: Receiver,
+}
+
+impl PartitionReceiver {
+    pub(super) async fn next(&mut self) -> crate::Result<EventData> {
+        let delivery: Delivery<AmqpValue> = self.receiver.recv().await?;
+        self.receiver.accept(&delivery).await?;
+        Ok(event_data(delivery.message()))
+    }
+
+    pub(super) async fn close(mut self) {
+        // The partition is being released, errors closing the link don't
+        // matter anymore.
+        let _ = self.receiver.close().await;
+        let _ = self.session.end().await;
+        let _ = self.connection.close().await;
+    }
+}
+
+fn partition_ids_from_response(value: &AmqpValue) -> Option<Vec<String>> {
+    match value {
+        AmqpValue::Map(map) => map.iter().find_map(|(key, value)| match (key, value) {
+            (AmqpValue::String(key), AmqpValue::Array(ids)) if key == "partition_ids" => ids
+                .iter()
+                .map(|id| match id {
+                    AmqpValue::String(id) => Some(id.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn event_data(message: &Message<AmqpValue>) -> EventData {
+    let body = match &message.body {
+        Body::Data(data) => Bytes::from(data.0.to_vec()),
+        Body::Value(value) => match &value.0 {
+            AmqpValue::String(string) => Bytes::from(string.clone()),
+            AmqpValue::Binary(bytes) => Bytes::from(bytes.to_vec()),
+            value => Bytes::from(format!("{:?}", value)),
+        },
+        _ => Bytes::new(),
+    };
+
+    let mut offset = String::new();
+    let mut sequence_number = 0;
+    let mut enqueued_time = None;
+    if let Some(annotations) = &message.message_annotations {
+        for (key, value) in annotations.0.iter() {
+            match (key.to_string().as_str(), value) {
+                (OFFSET_ANNOTATION, AmqpValue::String(value)) => offset = value.clone(),
+                (SEQUENCE_NUMBER_ANNOTATION, AmqpValue::Long(value)) => sequence_number = *value,
+                (ENQUEUED_TIME_ANNOTATION, AmqpValue::Timestamp(value)) => {
+                    enqueued_time = Utc.timestamp_millis_opt(value.milliseconds()).single()
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let properties = message
+        .application_properties
+        .as_ref()
+        .map(|properties| {
+            properties
+                .0
+                .iter()
+                .map(|(key, value)| (key.clone(), simple_value(value)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    EventData {
+        body,
+        offset,
+        sequence_number,
+        enqueued_time,
+        properties,
+    }
+}
+
+fn simple_value(value: &SimpleValue) -> Value {
+    match value {
+        SimpleValue::Null => Value::Null,
+        SimpleValue::Bool(value) => Value::from(*value),
+        SimpleValue::UByte(value) => Value::from(*value as i64),
+        SimpleValue::UShort(value) => Value::from(*value as i64),
+        SimpleValue::UInt(value) => Value::from(*value as i64),
+        SimpleValue::ULong(value) => Value::from(*value as i64),
+        SimpleValue::Byte(value) => Value::from(*value as i64),
+        SimpleValue::Short(value) => Value::from(*value as i64),
+        SimpleValue::Int(value) => Value::from(*value as i64),
+        SimpleValue::Long(value) => Value::from(*value),
+        SimpleValue::Float(value) => Value::from(f64::from(value.into_inner())),
+        SimpleValue::Double(value) => Value::from(value.into_inner()),
+        SimpleValue::Timestamp(value) => Utc
+            .timestamp_millis_opt(value.milliseconds())
+            .single()
+            .map(Value::from)
+            .unwrap_or(Value::Null),
+        SimpleValue::String(value) => Value::from(value.clone()),
+        SimpleValue::Symbol(value) => Value::from(value.to_string()),
+        SimpleValue::Binary(value) => Value::from(Bytes::from(value.to_vec())),
+        value => Value::from(format!("{:?}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_connection_string() {
+        let config = ConnectionConfig::parse(
+            "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=c2VjcmV0;EntityPath=logs",
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.namespace, "example.servicebus.windows.net");
+        assert_eq!(config.event_hub_name, "logs");
+        assert_eq!(config.key_name, "listen");
+        assert_eq!(config.key, "c2VjcmV0");
+        assert_eq!(config.url(), "amqps://example.servicebus.windows.net:5671");
+    }
+
+    #[test]
+    fn event_hub_name_overrides_entity_path() {
+        let config = ConnectionConfig::parse(
+            "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=c2VjcmV0;EntityPath=logs",
+            Some("metrics"),
+        )
+        .unwrap();
+        assert_eq!(config.event_hub_name, "metrics");
+    }
+
+    #[test]
+    fn rejects_incomplete_connection_strings() {
+        assert_eq!(
+            ConnectionConfig::parse(
+                "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=c2VjcmV0",
+                None,
+            ),
+            Err(ConnectionStringError::MissingEventHubName)
+        );
+        assert_eq!(
+            ConnectionConfig::parse(
+                "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen",
+                Some("logs"),
+            ),
+            Err(ConnectionStringError::MissingKey {
+                key: "SharedAccessKey"
+            })
+        );
+        assert!(matches!(
+            ConnectionConfig::parse(
+                "Endpoint=https://example.servicebus.windows.net/logs;SharedAccessKeyName=listen;SharedAccessKey=c2VjcmV0",
+                Some("logs"),
+            ),
+            Err(ConnectionStringError::InvalidEndpoint { .. })
+        ));
+    }
+
+    #[test]
+    fn selects_events_after_position() {
+        assert_eq!(
+            EventPosition::After("4096".into()).selector(),
+            "amqp.annotation.x-opt-offset > '4096'"
+        );
+        assert_eq!(
+            EventPosition::Earliest.selector(),
+            "amqp.annotation.x-opt-offset > '-1'"
+        );
+        assert_eq!(
+            EventPosition::Latest.selector(),
+            "amqp.annotation.x-opt-offset > '@latest'"
+        );
+    }
+}
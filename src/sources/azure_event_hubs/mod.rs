@@ -0,0 +1,651 @@
+//! Consumes events from Azure Event Hubs over AMQP 1.0.
+//!
+//! Every Vector instance consuming an event hub with the same consumer group
+//! and checkpoint store takes part in balancing its partitions: each round,
+//! an instance renews its claims on the partitions it owns and claims at most
+//! one more if it owns less than its fair share. Claims are conditional
+//! writes to the store, so only one instance wins a partition. A partition
+//! whose owner stops renewing it is picked up by the others once its
+//! ownership expires.
+//!
+//! Consumption resumes after the checkpointed offset of each partition. With
+//! acknowledgements enabled, the checkpoint only advances past an event once
+//! its sinks acknowledged it, and in the order events were received.
+
+use crate::{
+    codecs::{self, DecodingConfig, FramingConfig, ParserSettings},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, Event, Value},
+    internal_events::{
+        AzureEventHubsCheckpointFailed, AzureEventHubsEventReceived,
+        AzureEventHubsLoadBalancingFailed, AzureEventHubsOwnershipChanged,
+        AzureEventHubsReceiveFailed,
+    },
+    serde::{default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sources::util::finalizer::OrderedFinalizer,
+    Pipeline,
+};
+use bytes::BytesMut;
+use chrono::Utc;
+use futures::{future::Shared, stream, FutureExt, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+    time::sleep,
+};
+use tokio_util::codec::Decoder as _;
+use vector_core::checkpoint::CheckpointStore;
+
+mod balancer;
+mod client;
+mod store;
+
+use self::client::{Client, ConnectionConfig, ConnectionStringError, EventData, EventPosition};
+pub use self::store::CheckpointStoreConfig;
+use self::store::{BlobStore, Checkpoint, LocalStore, Ownership, PartitionStore};
+
+const BACKOFF_DURATION: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid connection string: {}", source))]
+    InvalidConnectionString { source: ConnectionStringError },
+    #[snafu(display(
+        "`ownership_expiration_secs` must be greater than `load_balancing_interval_secs`"
+    ))]
+    OwnershipExpiresTooSoon,
+    #[snafu(display("Could not load checkpoints: {}", source))]
+    LoadCheckpoints { source: std::io::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartPosition {
+    Earliest,
+    Latest,
+}
+
+impl Default for StartPosition {
+    fn default() -> Self {
+        Self::Latest
+    }
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AzureEventHubsConfig {
+    connection_string: String,
+    event_hub_name: Option<String>,
+    #[serde(default = "default_consumer_group")]
+    #[derivative(Default(value = "default_consumer_group()"))]
+    consumer_group: String,
+    #[serde(default)]
+    start_position: StartPosition,
+    #[serde(default = "default_load_balancing_interval_secs")]
+    #[derivative(Default(value = "default_load_balancing_interval_secs()"))]
+    load_balancing_interval_secs: u64,
+    #[serde(default = "default_ownership_expiration_secs")]
+    #[derivative(Default(value = "default_ownership_expiration_secs()"))]
+    ownership_expiration_secs: u64,
+    #[serde(default)]
+    checkpoint_store: CheckpointStoreConfig,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: Box<dyn FramingConfig>,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: ParserSettings,
+}
+
+fn default_consumer_group() -> String {
+    "$Default".into()
+}
+
+const fn default_load_balancing_interval_secs() -> u64 {
+    10
+}
+
+const fn default_ownership_expiration_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<AzureEventHubsConfig>("azure_event_hubs")
+}
+
+impl GenerateConfig for AzureEventHubsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            connection_string = "Endpoint=sb://mynamespace.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=${EVENT_HUBS_KEY};EntityPath=logs"
+            consumer_group = "$Default""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_event_hubs")]
+impl SourceConfig for AzureEventHubsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let connection =
+            ConnectionConfig::parse(&self.connection_string, self.event_hub_name.as_deref())
+                .context(InvalidConnectionString)?;
+        if self.ownership_expiration_secs <= self.load_balancing_interval_secs {
+            return Err(BuildError::OwnershipExpiresTooSoon.into());
+        }
+
+        let store: Arc<dyn PartitionStore> = match &self.checkpoint_store {
+            CheckpointStoreConfig::Local { data_dir } => {
+                let data_dir = cx
+                    .globals
+                    .resolve_and_validate_data_dir(data_dir.as_ref())?;
+                Arc::new(
+                    LocalStore::new(
+                        &CheckpointStore::new(data_dir),
+                        cx.key.id(),
+                        &self.consumer_group,
+                    )
+                    .context(LoadCheckpoints)?,
+                )
+            }
+            CheckpointStoreConfig::AzureBlob {
+                connection_string,
+                container_name,
+            } => Arc::new(BlobStore::new(
+                connection_string,
+                container_name,
+                &connection.namespace,
+                &connection.event_hub_name,
+                &self.consumer_group,
+            )?),
+        };
+
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+        let start_position = match self.start_position {
+            StartPosition::Earliest => EventPosition::Earliest,
+            StartPosition::Latest => EventPosition::Latest,
+        };
+
+        let source = EventHubsSource {
+            client: Client::new(connection),
+            store,
+            owner_id: uuid::Uuid::new_v4().to_string(),
+            consumer: Arc::new(PartitionConsumer {
+                consumer_group: self.consumer_group.clone(),
+                start_position,
+                decoder,
+                acknowledgements: cx.acknowledgements,
+            }),
+            load_balancing_interval: Duration::from_secs(self.load_balancing_interval_secs),
+            ownership_expiration: chrono::Duration::seconds(self.ownership_expiration_secs as i64),
+        };
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "azure_event_hubs"
+    }
+}
+
+struct EventHubsSource {
+    client: Client,
+    store: Arc<dyn PartitionStore>,
+    /// Identifies this instance in the ownership of partitions.
+    owner_id: String,
+    consumer: Arc<PartitionConsumer>,
+    load_balancing_interval: Duration,
+    ownership_expiration: chrono::Duration,
+}
+
+/// A partition this instance consumes, which stops once `stop` is dropped.
+struct RunningPartition {
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl EventHubsSource {
+    async fn run(self, out: Pipeline, shutdown: ShutdownSignal) -> Result<(), ()> {
+        let shutdown = shutdown.shared();
+        let (checkpoints, checkpoint_receiver) = mpsc::unbounded_channel();
+        let checkpointer = tokio::spawn(write_checkpoints(
+            Arc::clone(&self.store),
+            checkpoint_receiver,
+        ));
+
+        let mut partition_ids = Vec::new();
+        let mut owned = HashMap::<String, Ownership>::new();
+        let mut running = HashMap::<String, RunningPartition>::new();
+        let mut interval = tokio::time::interval(self.load_balancing_interval);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.clone() => break,
+                _ = interval.tick() => (),
+            }
+
+            if partition_ids.is_empty() {
+                match self.client.partition_ids().await {
+                    Ok(ids) => partition_ids = ids,
+                    Err(error) => {
+                        emit!(&AzureEventHubsLoadBalancingFailed { error: &error });
+                        continue;
+                    }
+                }
+            }
+
+            match self.balance(&partition_ids).await {
+                Ok(claimed) => owned = claimed,
+                Err(error) => {
+                    emit!(&AzureEventHubsLoadBalancingFailed { error: &error });
+                    // Keep consuming the partitions whose ownership didn't
+                    // expire yet, the next round may succeed.
+                    let now = Utc::now();
+                    owned.retain(|_, ownership| {
+                        now - ownership.last_modified < self.ownership_expiration
+                    });
+                }
+            }
+
+            let released = running
+                .keys()
+                .filter(|id| !owned.contains_key(*id))
+                .cloned()
+                .collect::<Vec<_>>();
+            for partition_id in &released {
+                if let Some(partition) = running.remove(partition_id) {
+                    drop(partition.stop);
+                }
+            }
+
+            let mut claimed = Vec::new();
+            for partition_id in owned.keys() {
+                if !running.contains_key(partition_id) {
+                    let (stop, stopped) = oneshot::channel();
+                    let handle = tokio::spawn(Arc::clone(&self.consumer).consume(
+                        self.client.clone(),
+                        Arc::clone(&self.store),
+                        partition_id.clone(),
+                        out.clone(),
+                        checkpoints.clone(),
+                        shutdown.clone(),
+                        stopped,
+                    ));
+                    running.insert(partition_id.clone(), RunningPartition { stop, handle });
+                    claimed.push(partition_id.clone());
+                }
+            }
+
+            if !claimed.is_empty() || !released.is_empty() {
+                emit!(&AzureEventHubsOwnershipChanged {
+                    claimed: &claimed,
+                    released: &released,
+                    owned: running.len(),
+                });
+            }
+        }
+
+        for (_, partition) in running.drain() {
+            drop(partition.stop);
+            let _ = partition.handle.await;
+        }
+        drop(checkpoints);
+        let _ = checkpointer.await;
+
+        // Let the other instances take over right away instead of waiting
+        // for the ownership to expire.
+        for ownership in owned.into_values() {
+            let relinquished = Ownership {
+                owner_id: String::new(),
+                ..ownership
+            };
+            if let Err(error) = self.store.claim_ownership(&relinquished).await {
+                emit!(&AzureEventHubsLoadBalancingFailed { error: &error });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs a load balancing round, returning the partitions now owned.
+    async fn balance(&self, partition_ids: &[String]) -> crate::Result<HashMap<String, Ownership>> {
+        let ownership = self.store.list_ownership().await?;
+        let claims = balancer::plan_claims(
+            partition_ids,
+            &ownership,
+            &self.owner_id,
+            Utc::now(),
+            self.ownership_expiration,
+        );
+
+        let mut owned = HashMap::new();
+        for claim in claims {
+            if let Some(ownership) = self.store.claim_ownership(&claim).await? {
+                owned.insert(ownership.partition_id.clone(), ownership);
+            }
+        }
+        Ok(owned)
+    }
+}
+
+struct PartitionConsumer {
+    consumer_group: String,
+    start_position: EventPosition,
+    decoder: codecs::Decoder,
+    acknowledgements: bool,
+}
+
+impl PartitionConsumer {
+    #[allow(clippy::too_many_arguments)]
+    async fn consume(
+        self: Arc<Self>,
+        client: Client,
+        store: Arc<dyn PartitionStore>,
+        partition_id: String,
+        mut out: Pipeline,
+        checkpoints: mpsc::UnboundedSender<Checkpoint>,
+        shutdown: Shared<ShutdownSignal>,
+        mut stopped: oneshot::Receiver<()>,
+    ) {
+        let mut position = loop {
+            match store.list_checkpoints().await {
+                Ok(offsets) => {
+                    break offsets
+                        .get(&partition_id)
+                        .map(|offset| EventPosition::After(offset.clone()))
+                        .unwrap_or_else(|| self.start_position.clone())
+                }
+                Err(error) => emit!(&AzureEventHubsCheckpointFailed {
+                    partition_id: &partition_id,
+                    error: &error,
+                }),
+            }
+            tokio::select! {
+                _ = &mut stopped => return,
+                _ = sleep(BACKOFF_DURATION) => (),
+            }
+        };
+
+        let finalizer = self.acknowledgements.then(|| {
+            let checkpoints = checkpoints.clone();
+            OrderedFinalizer::new(shutdown, move |checkpoint| {
+                let _ = checkpoints.send(checkpoint);
+            })
+        });
+
+        loop {
+            let receiver = tokio::select! {
+                _ = &mut stopped => return,
+                receiver = client.receive(&self.consumer_group, &partition_id, &position) => receiver,
+            };
+
+            match receiver {
+                Ok(mut receiver) => loop {
+                    let data = tokio::select! {
+                        _ = &mut stopped => {
+                            receiver.close().await;
+                            return;
+                        }
+                        data = receiver.next() => data,
+                    };
+
+                    match data {
+                        Ok(data) => {
+                            emit!(&AzureEventHubsEventReceived {
+                                partition_id: &partition_id,
+                                byte_size: data.body.len(),
+                            });
+                            position = EventPosition::After(data.offset.clone());
+
+                            let checkpoint = Checkpoint {
+                                partition_id: partition_id.clone(),
+                                offset: data.offset.clone(),
+                                sequence_number: data.sequence_number,
+                            };
+                            let events = self.decode(&data, &partition_id);
+                            let (events, receiver) = match &finalizer {
+                                Some(_) => {
+                                    let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                    let events = events
+                                        .into_iter()
+                                        .map(|event| event.with_batch_notifier(&batch))
+                                        .collect();
+                                    (events, Some(receiver))
+                                }
+                                None => (events, None),
+                            };
+
+                            let mut events = stream::iter(events).map(Ok);
+                            if let Err(error) = out.send_all(&mut events).await {
+                                error!(message = "Error sending to sink.", %error);
+                                return;
+                            }
+                            match (&finalizer, receiver) {
+                                (Some(finalizer), Some(receiver)) => {
+                                    finalizer.add(checkpoint, receiver)
+                                }
+                                _ => {
+                                    let _ = checkpoints.send(checkpoint);
+                                }
+                            }
+                        }
+                        Err(error) => {
+                            emit!(&AzureEventHubsReceiveFailed {
+                                partition_id: &partition_id,
+                                error: &error,
+                            });
+                            receiver.close().await;
+                            break;
+                        }
+                    }
+                },
+                Err(error) => emit!(&AzureEventHubsReceiveFailed {
+                    partition_id: &partition_id,
+                    error: &error,
+                }),
+            }
+
+            tokio::select! {
+                _ = &mut stopped => return,
+                _ = sleep(BACKOFF_DURATION) => (),
+            }
+        }
+    }
+
+    fn decode(&self, data: &EventData, partition_id: &str) -> Vec<Event> {
+        let mut decoder = self.decoder.clone();
+        let mut buffer = BytesMut::from(&data.body[..]);
+        let mut events = Vec::new();
+
+        loop {
+            match decoder.decode_eof(&mut buffer) {
+                Ok(Some((next, _))) => events.extend(next),
+                Ok(None) => break,
+                Err(error) => {
+                    // Error is logged by `crate::codecs::Decoder`, no further
+                    // handling is needed here.
+                    if !error.can_continue() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let schema = log_schema();
+        let timestamp = data.enqueued_time.unwrap_or_else(Utc::now);
+        for event in &mut events {
+            let log = event.as_mut_log();
+            log.try_insert(schema.source_type_key(), "azure_event_hubs");
+            log.try_insert(schema.timestamp_key(), timestamp);
+            log.try_insert("partition_id", partition_id);
+            log.try_insert("offset", data.offset.as_str());
+            log.try_insert("sequence_number", data.sequence_number);
+            log.try_insert("properties", Value::from(data.properties.clone()));
+        }
+        events
+    }
+}
+
+/// Writes the checkpoints sent by the partition consumers until they all
+/// stopped, skipping over the checkpoints superseded by later ones of the
+/// same partition while a write is in progress.
+async fn write_checkpoints(
+    store: Arc<dyn PartitionStore>,
+    mut checkpoints: mpsc::UnboundedReceiver<Checkpoint>,
+) {
+    while let Some(checkpoint) = checkpoints.recv().await {
+        let mut latest = HashMap::new();
+        latest.insert(checkpoint.partition_id.clone(), checkpoint);
+        while let Ok(checkpoint) = checkpoints.try_recv() {
+            latest.insert(checkpoint.partition_id.clone(), checkpoint);
+        }
+
+        for checkpoint in latest.values() {
+            if let Err(error) = store.update_checkpoint(checkpoint).await {
+                emit!(&AzureEventHubsCheckpointFailed {
+                    partition_id: &checkpoint.partition_id,
+                    error: &error,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codecs::{BytesParser, NewlineDelimitedCodec};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AzureEventHubsConfig>();
+    }
+
+    #[test]
+    fn parses_checkpoint_store() {
+        let config: AzureEventHubsConfig = toml::from_str(
+            r#"
+            connection_string = "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=c2VjcmV0;EntityPath=logs"
+
+            [checkpoint_store]
+            type = "azure_blob"
+            connection_string = "DefaultEndpointsProtocol=https;AccountName=example;AccountKey=c2VjcmV0"
+            container_name = "checkpoints"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.consumer_group, "$Default");
+        assert_eq!(config.start_position, StartPosition::Latest);
+        assert_eq!(
+            config.checkpoint_store,
+            CheckpointStoreConfig::AzureBlob {
+                connection_string:
+                    "DefaultEndpointsProtocol=https;AccountName=example;AccountKey=c2VjcmV0".into(),
+                container_name: "checkpoints".into(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_ownership_expiring_before_renewal() {
+        let config: AzureEventHubsConfig = toml::from_str(
+            r#"
+            connection_string = "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=c2VjcmV0;EntityPath=logs"
+            load_balancing_interval_secs = 30
+            ownership_expiration_secs = 30
+            "#,
+        )
+        .unwrap();
+        let (tx, _rx) = Pipeline::new_test();
+        let error = config
+            .build(SourceContext::new_test(tx))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "`ownership_expiration_secs` must be greater than `load_balancing_interval_secs`"
+        );
+    }
+
+    #[test]
+    fn decodes_events_with_metadata() {
+        let consumer = PartitionConsumer {
+            consumer_group: default_consumer_group(),
+            start_position: EventPosition::Latest,
+            decoder: codecs::Decoder::new(
+                Box::new(NewlineDelimitedCodec::new()),
+                Box::new(BytesParser::new()),
+            ),
+            acknowledgements: false,
+        };
+        let enqueued_time = Utc::now();
+        let mut properties = BTreeMap::new();
+        properties.insert("origin".to_owned(), Value::from("web"));
+        let data = EventData {
+            body: "one\ntwo".into(),
+            offset: "4096".into(),
+            sequence_number: 12,
+            enqueued_time: Some(enqueued_time),
+            properties,
+        };
+
+        let events = consumer.decode(&data, "3");
+        assert_eq!(events.len(), 2);
+        for (event, message) in events.iter().zip(&["one", "two"]) {
+            let log = event.as_log();
+            assert_eq!(log[log_schema().message_key()], (*message).into());
+            assert_eq!(log[log_schema().timestamp_key()], enqueued_time.into());
+            assert_eq!(
+                log[log_schema().source_type_key()],
+                "azure_event_hubs".into()
+            );
+            assert_eq!(log["partition_id"], "3".into());
+            assert_eq!(log["offset"], "4096".into());
+            assert_eq!(log["sequence_number"], 12_i64.into());
+            assert_eq!(log["properties.origin"], "web".into());
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_latest_checkpoint_per_partition() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(
+            LocalStore::new(
+                &CheckpointStore::new(tempdir.path()),
+                "event_hubs",
+                "$Default",
+            )
+            .unwrap(),
+        );
+        let (checkpoints, receiver) = mpsc::unbounded_channel();
+        for (partition_id, offset) in &[("0", "10"), ("1", "20"), ("0", "30")] {
+            checkpoints
+                .send(Checkpoint {
+                    partition_id: (*partition_id).into(),
+                    offset: (*offset).into(),
+                    sequence_number: 0,
+                })
+                .unwrap();
+        }
+        drop(checkpoints);
+
+        write_checkpoints(Arc::clone(&store) as Arc<dyn PartitionStore>, receiver).await;
+
+        let offsets = store.list_checkpoints().await.unwrap();
+        assert_eq!(offsets["0"], "30");
+        assert_eq!(offsets["1"], "20");
+    }
+}
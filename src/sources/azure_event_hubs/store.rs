@@ -0,0 +1,356 @@
+use azure_core::{prelude::*, HttpError};
+use azure_storage::blob::prelude::*;
+use azure_storage::core::prelude::*;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use vector_core::checkpoint::{CheckpointStore, Checkpoints};
+
+const OWNER_ID_METADATA: &str = "ownerid";
+const OFFSET_METADATA: &str = "offset";
+const SEQUENCE_NUMBER_METADATA: &str = "sequencenumber";
+
+/// Where the source keeps partition ownership and checkpoints.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CheckpointStoreConfig {
+    /// Checkpoints in the data directory. Ownership is only tracked within
+    /// this Vector instance, which then consumes every partition.
+    Local { data_dir: Option<PathBuf> },
+    /// Ownership and checkpoints in an Azure Blob Storage container, shared
+    /// by all the Vector instances consuming the event hub with the same
+    /// consumer group.
+    AzureBlob {
+        connection_string: String,
+        container_name: String,
+    },
+}
+
+impl Default for CheckpointStoreConfig {
+    fn default() -> Self {
+        Self::Local { data_dir: None }
+    }
+}
+
+/// The claim of an instance on a partition.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Ownership {
+    pub(super) partition_id: String,
+    /// Empty if the partition was relinquished.
+    pub(super) owner_id: String,
+    pub(super) last_modified: DateTime<Utc>,
+    /// The version of the ownership when it was listed, `None` if the
+    /// partition was never claimed.
+    pub(super) etag: Option<String>,
+}
+
+/// The position of the last event of a partition that was processed.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Checkpoint {
+    pub(super) partition_id: String,
+    pub(super) offset: String,
+    pub(super) sequence_number: i64,
+}
+
+#[async_trait::async_trait]
+pub(super) trait PartitionStore: Send + Sync {
+    async fn list_ownership(&self) -> crate::Result<Vec<Ownership>>;
+
+    /// Claims a partition, returning the updated ownership, or `None` if the
+    /// ownership changed since it was listed.
+    async fn claim_ownership(&self, claim: &Ownership) -> crate::Result<Option<Ownership>>;
+
+    /// The offsets checkpointed for each partition.
+    async fn list_checkpoints(&self) -> crate::Result<HashMap<String, String>>;
+
+    async fn update_checkpoint(&self, checkpoint: &Checkpoint) -> crate::Result<()>;
+}
+
+/// Keeps checkpoints in the shared checkpoint store of the data directory,
+/// under keys of the form `<consumer group>/<partition ID>`, and ownership in
+/// memory.
+pub(super) struct LocalStore {
+    consumer_group: String,
+    checkpoints: Mutex<Checkpoints>,
+    ownership: Mutex<HashMap<String, Ownership>>,
+}
+
+impl LocalStore {
+    pub(super) fn new(
+        store: &CheckpointStore,
+        component: &str,
+        consumer_group: &str,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            consumer_group: consumer_group.to_owned(),
+            checkpoints: Mutex::new(store.namespace(component)?),
+            ownership: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn key(&self, partition_id: &str) -> String {
+        format!("{}/{}", self.consumer_group, partition_id)
+    }
+}
+
+#[async_trait::async_trait]
+impl PartitionStore for LocalStore {
+    async fn list_ownership(&self) -> crate::Result<Vec<Ownership>> {
+        Ok(self.ownership.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn claim_ownership(&self, claim: &Ownership) -> crate::Result<Option<Ownership>> {
+        let mut ownership = self.ownership.lock().unwrap();
+        let current = ownership
+            .get(&claim.partition_id)
+            .and_then(|ownership| ownership.etag.clone());
+        if current != claim.etag {
+            return Ok(None);
+        }
+
+        let version = current.map_or(0, |etag| etag.parse::<u64>().unwrap_or(0) + 1);
+        let claimed = Ownership {
+            last_modified: Utc::now(),
+            etag: Some(version.to_string()),
+            ..claim.clone()
+        };
+        ownership.insert(claim.partition_id.clone(), claimed.clone());
+        Ok(Some(claimed))
+    }
+
+    async fn list_checkpoints(&self) -> crate::Result<HashMap<String, String>> {
+        let prefix = self.key("");
+        Ok(self
+            .checkpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(key, checkpoint)| {
+                key.strip_prefix(&prefix)
+                    .map(|partition_id| (partition_id.to_owned(), checkpoint.position.clone()))
+            })
+            .collect())
+    }
+
+    async fn update_checkpoint(&self, checkpoint: &Checkpoint) -> crate::Result<()> {
+        self.checkpoints.lock().unwrap().set(
+            self.key(&checkpoint.partition_id),
+            checkpoint.offset.clone(),
+        )?;
+        Ok(())
+    }
+}
+
+/// Keeps ownership and checkpoints as blob metadata, in the same layout as
+/// the blob checkpoint store of the Azure Event Hubs SDKs:
+/// `<namespace>/<event hub>/<consumer group>/{ownership,checkpoint}/<partition ID>`.
+pub(super) struct BlobStore {
+    client: Arc<ContainerClient>,
+    prefix: String,
+}
+
+impl BlobStore {
+    pub(super) fn new(
+        connection_string: &str,
+        container_name: &str,
+        namespace: &str,
+        event_hub_name: &str,
+        consumer_group: &str,
+    ) -> crate::Result<Self> {
+        let client =
+            StorageAccountClient::new_connection_string(new_http_client(), connection_string)?
+                .as_storage_client()
+                .as_container_client(container_name);
+
+        Ok(Self {
+            client,
+            prefix: format!(
+                "{}/{}/{}",
+                namespace.to_lowercase(),
+                event_hub_name.to_lowercase(),
+                consumer_group.to_lowercase()
+            ),
+        })
+    }
+
+    fn ownership_prefix(&self) -> String {
+        format!("{}/ownership/", self.prefix)
+    }
+
+    fn checkpoint_prefix(&self) -> String {
+        format!("{}/checkpoint/", self.prefix)
+    }
+
+    async fn list(&self, prefix: &str) -> crate::Result<Vec<Blob>> {
+        let mut blobs = Vec::new();
+        let mut marker = None;
+        loop {
+            let request = self
+                .client
+                .list_blobs()
+                .prefix(prefix)
+                .include_metadata(true);
+            let request = match marker.take() {
+                Some(marker) => request.next_marker(marker),
+                None => request,
+            };
+            let response = request.execute().await?;
+            blobs.extend(response.blobs.blobs);
+            match response.next_marker {
+                Some(next) => marker = Some(next),
+                None => return Ok(blobs),
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PartitionStore for BlobStore {
+    async fn list_ownership(&self) -> crate::Result<Vec<Ownership>> {
+        let prefix = self.ownership_prefix();
+        Ok(self
+            .list(&prefix)
+            .await?
+            .into_iter()
+            .filter_map(|blob| {
+                let partition_id = blob.name.strip_prefix(&prefix)?.to_owned();
+                let owner_id = blob
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.get(OWNER_ID_METADATA))
+                    .cloned()
+                    .unwrap_or_default();
+                Some(Ownership {
+                    partition_id,
+                    owner_id,
+                    last_modified: blob.properties.last_modified,
+                    etag: Some(blob.properties.etag.to_string()),
+                })
+            })
+            .collect())
+    }
+
+    async fn claim_ownership(&self, claim: &Ownership) -> crate::Result<Option<Ownership>> {
+        let name = format!("{}{}", self.ownership_prefix(), claim.partition_id);
+        let mut metadata = Metadata::new();
+        metadata.insert(OWNER_ID_METADATA, claim.owner_id.as_str());
+
+        let request = self
+            .client
+            .as_blob_client(name.as_str())
+            .put_block_blob(Bytes::new())
+            .metadata(&metadata);
+        // Only one of the instances racing for a partition wins, the others
+        // see that the blob changed since they listed it.
+        let request = match &claim.etag {
+            Some(etag) => request.if_match_condition(IfMatchCondition::Match(etag)),
+            None => request.if_match_condition(IfMatchCondition::NotMatch("*")),
+        };
+
+        match request.execute().await {
+            Ok(response) => Ok(Some(Ownership {
+                last_modified: response.last_modified,
+                etag: Some(response.etag.to_string()),
+                ..claim.clone()
+            })),
+            Err(error) => match error.downcast_ref::<HttpError>() {
+                Some(HttpError::UnexpectedStatusCode { received, .. })
+                    if *received == StatusCode::PRECONDITION_FAILED
+                        || *received == StatusCode::CONFLICT =>
+                {
+                    Ok(None)
+                }
+                _ => Err(error),
+            },
+        }
+    }
+
+    async fn list_checkpoints(&self) -> crate::Result<HashMap<String, String>> {
+        let prefix = self.checkpoint_prefix();
+        Ok(self
+            .list(&prefix)
+            .await?
+            .into_iter()
+            .filter_map(|blob| {
+                let partition_id = blob.name.strip_prefix(&prefix)?.to_owned();
+                let offset = blob.metadata?.remove(OFFSET_METADATA)?;
+                Some((partition_id, offset))
+            })
+            .collect())
+    }
+
+    async fn update_checkpoint(&self, checkpoint: &Checkpoint) -> crate::Result<()> {
+        let name = format!("{}{}", self.checkpoint_prefix(), checkpoint.partition_id);
+        let sequence_number = checkpoint.sequence_number.to_string();
+        let mut metadata = Metadata::new();
+        metadata.insert(OFFSET_METADATA, checkpoint.offset.as_str());
+        metadata.insert(SEQUENCE_NUMBER_METADATA, sequence_number.as_str());
+
+        self.client
+            .as_blob_client(name.as_str())
+            .put_block_blob(Bytes::new())
+            .metadata(&metadata)
+            .execute()
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_store(data_dir: &std::path::Path) -> LocalStore {
+        LocalStore::new(&CheckpointStore::new(data_dir), "event_hubs", "$Default").unwrap()
+    }
+
+    #[tokio::test]
+    async fn local_store_persists_checkpoints() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = local_store(tempdir.path());
+        store
+            .update_checkpoint(&Checkpoint {
+                partition_id: "1".into(),
+                offset: "4096".into(),
+                sequence_number: 12,
+            })
+            .await
+            .unwrap();
+
+        let checkpoints = local_store(tempdir.path())
+            .list_checkpoints()
+            .await
+            .unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints["1"], "4096");
+
+        let other_group =
+            LocalStore::new(&CheckpointStore::new(tempdir.path()), "event_hubs", "other").unwrap();
+        assert!(other_group.list_checkpoints().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn local_store_rejects_stale_claims() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = local_store(tempdir.path());
+        let claim = Ownership {
+            partition_id: "0".into(),
+            owner_id: "a".into(),
+            last_modified: Utc::now(),
+            etag: None,
+        };
+
+        let claimed = store.claim_ownership(&claim).await.unwrap().unwrap();
+        assert!(store.claim_ownership(&claim).await.unwrap().is_none());
+
+        let renewed = store.claim_ownership(&claimed).await.unwrap().unwrap();
+        assert_ne!(renewed.etag, claimed.etag);
+        assert_eq!(store.list_ownership().await.unwrap(), vec![renewed]);
+    }
+}
@@ -1,5 +1,6 @@
+use super::api_keys::{ApiKeyValidationConfig, ApiKeyValidator};
 use crate::{
-    codecs::{self, DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{self, DecodingConfig, FramingConfig, ParserSettings},
     config::{
         log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
         SourceDescription,
@@ -9,15 +10,22 @@ use crate::{
     serde::{default_decoding, default_framing_message_based},
     sources::{
         self,
-        util::{ErrorMessage, TcpError},
+        util::{
+            http_server::{self, Http2Config, HttpKeepaliveConfig, PermitOrigin},
+            DuplicateRequest, ErrorMessage, Idempotency, IdempotencyClaim, IdempotencyConfig,
+            Quota, QuotaConfig, QuotaExceeded, QuotaIdentity, TcpError,
+        },
     },
-    tls::{MaybeTlsSettings, TlsConfig},
+    tls::{MaybeTls, MaybeTlsSettings, TlsConfig},
     Pipeline,
 };
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use chrono::Utc;
 use flate2::read::{DeflateDecoder, MultiGzDecoder};
-use futures::{FutureExt, SinkExt, StreamExt, TryFutureExt};
+use futures::{
+    future::{self, Either},
+    FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
 use http::StatusCode;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -26,9 +34,12 @@ use std::{io::Read, net::SocketAddr, sync::Arc};
 use tokio_util::codec::Decoder;
 use vector_core::event::{BatchNotifier, BatchStatus};
 use warp::{
-    filters::BoxedFilter, path, path::FullPath, reject::Rejection, reply::Response, Filter, Reply,
+    filters::BoxedFilter, http::HeaderMap, path, path::FullPath, reject::Rejection,
+    reply::Response, Filter, Reply,
 };
 
+const SOURCE_SUBTYPE_KEY: &str = "source_subtype";
+
 #[derive(Clone, Copy, Debug, Snafu)]
 pub(crate) enum ApiError {
     BadRequest,
@@ -38,16 +49,88 @@ pub(crate) enum ApiError {
 
 impl warp::reject::Reject for ApiError {}
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Allowed client names require TLS to be enabled"))]
+    ClientNamesWithoutTls,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct DatadogAgentConfig {
     address: SocketAddr,
     tls: Option<TlsConfig>,
+    /// The names of the agents allowed to connect, matched against their
+    /// client certificate.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allowed_client_names: Vec<String>,
+    /// The networks agents are allowed to connect from, as CIDR blocks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    permit_origin: Vec<String>,
     #[serde(default = "crate::serde::default_true")]
     store_api_key: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_key_validation: Option<ApiKeyValidationConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quota: Option<QuotaConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    idempotency: Option<IdempotencyConfig>,
     #[serde(default = "default_framing_message_based")]
     framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
+    #[serde(default)]
+    outputs: OutputRoutes,
+    #[serde(default)]
+    http2: Http2Config,
+    #[serde(default)]
+    keepalive: HttpKeepaliveConfig,
+}
+
+/// The kind of data received from the agent, recorded on every event in the
+/// `source_subtype` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceSubtype {
+    Logs,
+}
+
+impl SourceSubtype {
+    const fn as_str(self) -> &'static str {
+        match self {
+            SourceSubtype::Logs => "logs",
+        }
+    }
+}
+
+/// Routes each kind of data received from the agent to a named output of the
+/// source. Kinds without a route are sent to the default output, and kinds
+/// routed to the same name share that output.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OutputRoutes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logs: Option<String>,
+}
+
+impl OutputRoutes {
+    fn route(&self, subtype: SourceSubtype) -> Option<&str> {
+        match subtype {
+            SourceSubtype::Logs => self.logs.as_deref(),
+        }
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        let mut names = self.logs.iter().cloned().collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn validate(&self) -> crate::Result<()> {
+        match self.named_outputs().iter().find(|name| name.is_empty()) {
+            Some(_) => Err("`outputs` can't route to an output with an empty name".into()),
+            None => Ok(()),
+        }
+    }
 }
 
 inventory::submit! {
@@ -65,9 +148,17 @@ impl GenerateConfig for DatadogAgentConfig {
         toml::Value::try_from(Self {
             address: "0.0.0.0:8080".parse().unwrap(),
             tls: None,
+            allowed_client_names: Vec::new(),
+            permit_origin: Vec::new(),
             store_api_key: true,
+            api_key_validation: None,
+            quota: None,
+            idempotency: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            outputs: OutputRoutes::default(),
+            http2: Http2Config::default(),
+            keepalive: HttpKeepaliveConfig::default(),
         })
         .unwrap()
     }
@@ -76,14 +167,64 @@ impl GenerateConfig for DatadogAgentConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "datadog_agent")]
 impl SourceConfig for DatadogAgentConfig {
-    async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
+    async fn build(&self, mut cx: SourceContext) -> crate::Result<sources::Source> {
+        self.outputs.validate()?;
+        if !self.allowed_client_names.is_empty()
+            && !self
+                .tls
+                .as_ref()
+                .and_then(|tls| tls.enabled)
+                .unwrap_or(false)
+        {
+            return Err(Box::new(BuildError::ClientNamesWithoutTls));
+        }
+        let permit_origin = PermitOrigin::new(&self.permit_origin)?;
         let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
-        let source = DatadogAgentSource::new(self.store_api_key, decoder);
-
-        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
-        let listener = tls.bind(&self.address).await?;
-        let service = source.event_service(cx.acknowledgements, cx.out.clone());
+        let api_key_validator = self
+            .api_key_validation
+            .as_ref()
+            .map(ApiKeyValidationConfig::build)
+            .transpose()?;
+        let api_keys_watcher = self
+            .api_key_validation
+            .clone()
+            .zip(api_key_validator.clone())
+            .map(|(config, validator)| tokio::spawn(config.watch(validator)));
+        let quota = self
+            .quota
+            .as_ref()
+            .map(|quota| quota.build(QuotaIdentity::ApiKey))
+            .transpose()?;
+        let idempotency = self
+            .idempotency
+            .as_ref()
+            .map(IdempotencyConfig::build)
+            .transpose()?;
+        let source = DatadogAgentSource::new(
+            self.store_api_key,
+            api_key_validator,
+            quota,
+            idempotency,
+            decoder,
+        );
 
+        let tls = match MaybeTlsSettings::from_config(&self.tls, true)? {
+            MaybeTls::Tls(tls) if !self.allowed_client_names.is_empty() => {
+                MaybeTls::Tls(tls.with_allowed_peer_names(self.allowed_client_names.clone()))
+            }
+            tls => tls,
+        };
+        let listener = http_server::bind(&tls, &self.address, &self.http2).await?;
+        let logs_out = match self.outputs.route(SourceSubtype::Logs) {
+            Some(name) => cx
+                .take_named_output(name)
+                .ok_or_else(|| format!("Missing pipeline for the {:?} output.", name))?,
+            None => cx.out.clone(),
+        };
+        let service = source.event_service(cx.acknowledgements, logs_out);
+
+        let http2 = self.http2.clone();
+        let keepalive = self.keepalive.clone();
         let shutdown = cx.shutdown;
         Ok(Box::pin(async move {
             let span = crate::trace::current_span();
@@ -92,18 +233,29 @@ impl SourceConfig for DatadogAgentConfig {
                 .recover(|r: Rejection| async move {
                     if let Some(e_msg) = r.find::<ErrorMessage>() {
                         let json = warp::reply::json(e_msg);
-                        Ok(warp::reply::with_status(json, e_msg.status_code()))
+                        Ok(warp::reply::with_status(json, e_msg.status_code()).into_response())
+                    } else if let Some(exceeded) = r.find::<QuotaExceeded>() {
+                        Ok(exceeded.reply())
+                    } else if let Some(duplicate) = r.find::<DuplicateRequest>() {
+                        Ok(duplicate.reply())
                     } else {
                         // other internal error - will return 500 internal server error
                         Err(r)
                     }
-                });
-            warp::serve(routes)
-                .serve_incoming_with_graceful_shutdown(
-                    listener.accept_stream(),
-                    shutdown.map(|_| ()),
-                )
-                .await;
+                })
+                .boxed();
+            http_server::serve(
+                routes,
+                listener,
+                &http2,
+                &keepalive,
+                permit_origin,
+                shutdown.map(|_| ()),
+            )
+            .await;
+            if let Some(watcher) = api_keys_watcher {
+                watcher.abort();
+            }
 
             Ok(())
         }))
@@ -113,6 +265,10 @@ impl SourceConfig for DatadogAgentConfig {
         DataType::Log
     }
 
+    fn named_outputs(&self) -> Vec<String> {
+        self.outputs.named_outputs()
+    }
+
     fn source_type(&self) -> &'static str {
         "datadog_agent"
     }
@@ -125,6 +281,9 @@ impl SourceConfig for DatadogAgentConfig {
 #[derive(Clone)]
 struct DatadogAgentSource {
     store_api_key: bool,
+    api_key_validator: Option<ApiKeyValidator>,
+    quota: Option<Quota>,
+    idempotency: Option<Idempotency>,
     api_key_matcher: Regex,
     log_schema_timestamp_key: &'static str,
     log_schema_source_type_key: &'static str,
@@ -132,9 +291,18 @@ struct DatadogAgentSource {
 }
 
 impl DatadogAgentSource {
-    fn new(store_api_key: bool, decoder: codecs::Decoder) -> Self {
+    fn new(
+        store_api_key: bool,
+        api_key_validator: Option<ApiKeyValidator>,
+        quota: Option<Quota>,
+        idempotency: Option<Idempotency>,
+        decoder: codecs::Decoder,
+    ) -> Self {
         Self {
             store_api_key,
+            api_key_validator,
+            quota,
+            idempotency,
             api_key_matcher: Regex::new(r"^/v1/input/(?P<api_key>[[:alnum:]]{32})/??")
                 .expect("static regex always compiles"),
             log_schema_source_type_key: log_schema().source_type_key(),
@@ -159,10 +327,21 @@ impl DatadogAgentSource {
             .or_else(|| header.map(Arc::from))
     }
 
+    fn validate_api_key(&self, api_key: Option<&str>) -> Result<(), ErrorMessage> {
+        match &self.api_key_validator {
+            Some(validator) if !validator.is_allowed(api_key) => Err(ErrorMessage::new(
+                StatusCode::FORBIDDEN,
+                "Invalid API key".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
     async fn handle_request(
         events: Result<Vec<Event>, ErrorMessage>,
         acknowledgements: bool,
         mut out: Pipeline,
+        claim: Option<IdempotencyClaim>,
     ) -> Result<Response, Rejection> {
         match events {
             Ok(mut events) => {
@@ -184,7 +363,7 @@ impl DatadogAgentSource {
                         warp::reject::custom(ApiError::ServerShutdown)
                     })
                     .await?;
-                match receiver {
+                let reply = match receiver {
                     None => Ok(warp::reply().into_response()),
                     Some(receiver) => match receiver.await {
                         BatchStatus::Delivered => Ok(warp::reply().into_response()),
@@ -197,7 +376,13 @@ impl DatadogAgentSource {
                             "Contents failed to deliver to sink".into(),
                         ))),
                     },
+                };
+                // Retransmissions of the request are only dropped once its
+                // events were accepted.
+                if let (Ok(_), Some(claim)) = (&reply, claim) {
+                    claim.accept();
                 }
+                reply
             }
             Err(err) => Err(warp::reject::custom(err)),
         }
@@ -210,6 +395,8 @@ impl DatadogAgentSource {
             .and(warp::header::optional::<String>("content-encoding"))
             .and(warp::header::optional::<String>("dd-api-key"))
             .and(warp::query::<ApiKeyQueryParams>())
+            .and(http_server::remote_addr())
+            .and(warp::header::headers_cloned())
             .and(warp::body::bytes())
             .and_then(
                 move |_,
@@ -217,16 +404,60 @@ impl DatadogAgentSource {
                       encoding_header: Option<String>,
                       api_token: Option<String>,
                       query_params: ApiKeyQueryParams,
+                      remote: Option<SocketAddr>,
+                      headers: HeaderMap,
                       body: Bytes| {
+                    let api_key =
+                        self.extract_api_key(path.as_str(), api_token, query_params.dd_api_key);
                     let token: Option<Arc<str>> = if self.store_api_key {
-                        self.extract_api_key(path.as_str(), api_token, query_params.dd_api_key)
+                        api_key.clone()
                     } else {
                         None
                     };
 
-                    let events = decode(&encoding_header, body)
-                        .and_then(|body| self.decode_body(body, token));
-                    Self::handle_request(events, acknowledgements, out.clone())
+                    let validated = self.validate_api_key(api_key.as_deref());
+                    let claim = match (&validated, &self.idempotency) {
+                        (Ok(()), Some(idempotency)) => {
+                            match idempotency.claim(path.as_str(), &headers, &body) {
+                                Ok(claim) => claim,
+                                Err(duplicate) => {
+                                    return Either::Left(future::err(warp::reject::custom(
+                                        duplicate,
+                                    )))
+                                }
+                            }
+                        }
+                        _ => None,
+                    };
+                    let permit = match (&validated, &self.quota) {
+                        (Ok(()), Some(quota)) => {
+                            match quota.admit(api_key.as_deref(), remote, body.len()) {
+                                Ok(permit) => Some(permit),
+                                Err(exceeded) => {
+                                    return Either::Left(future::err(warp::reject::custom(
+                                        exceeded,
+                                    )))
+                                }
+                            }
+                        }
+                        _ => None,
+                    };
+
+                    let events = validated
+                        .and_then(|()| decode(&encoding_header, body))
+                        .and_then(|body| self.decode_body(body, token))
+                        .map(|events| {
+                            if let Some(permit) = &permit {
+                                permit.consume_events(events.len());
+                            }
+                            events
+                        });
+                    Either::Right(Self::handle_request(
+                        events,
+                        acknowledgements,
+                        out.clone(),
+                        claim,
+                    ))
                 },
             )
             .boxed()
@@ -276,6 +507,10 @@ impl DatadogAgentSource {
                                     Bytes::from("datadog_agent"),
                                 );
                                 log.try_insert_flat(self.log_schema_timestamp_key, now);
+                                log.try_insert_flat(
+                                    SOURCE_SUBTYPE_KEY,
+                                    SourceSubtype::Logs.as_str(),
+                                );
                                 if let Some(k) = &api_key {
                                     log.metadata_mut().set_datadog_api_key(Some(Arc::clone(k)));
                                 }
@@ -358,14 +593,14 @@ struct LogMsg {
 
 #[cfg(test)]
 mod tests {
-    use super::{DatadogAgentConfig, LogMsg};
+    use super::{DatadogAgentConfig, LogMsg, OutputRoutes};
     use crate::{
         codecs::{self, BytesCodec, BytesParser},
         config::{log_schema, SourceConfig, SourceContext},
         event::{Event, EventStatus},
         serde::{default_decoding, default_framing_message_based},
         sources::datadog::agent::DatadogAgentSource,
-        test_util::{next_addr, spawn_collect_n, trace_init, wait_for_tcp},
+        test_util::{collect_ready, next_addr, spawn_collect_n, trace_init, wait_for_tcp},
         Pipeline,
     };
     use bytes::Bytes;
@@ -401,7 +636,7 @@ mod tests {
 
             let decoder =
                 codecs::Decoder::new(Box::new(BytesCodec::new()), Box::new(BytesParser::new()));
-            let source = DatadogAgentSource::new(true, decoder);
+            let source = DatadogAgentSource::new(true, None, None, decoder);
             let events = source.decode_body(body, api_key).unwrap();
             assert_eq!(events.len(), msgs.len());
             for (msg, event) in msgs.into_iter().zip(events.into_iter()) {
@@ -439,9 +674,17 @@ mod tests {
             DatadogAgentConfig {
                 address,
                 tls: None,
+                allowed_client_names: Vec::new(),
+                permit_origin: Vec::new(),
                 store_api_key,
+                api_key_validation: None,
+                quota: None,
+                idempotency: None,
                 framing: default_framing_message_based(),
                 decoding: default_decoding(),
+                outputs: OutputRoutes::default(),
+                http2: Http2Config::default(),
+                keepalive: HttpKeepaliveConfig::default(),
             }
             .build(context)
             .await
@@ -514,6 +757,7 @@ mod tests {
             assert_eq!(log["ddtags"], "one,two,three".into());
             assert!(event.metadata().datadog_api_key().is_none());
             assert_eq!(log[log_schema().source_type_key()], "datadog_agent".into());
+            assert_eq!(log["source_subtype"], "logs".into());
         }
     }
 
@@ -887,4 +1131,207 @@ mod tests {
             assert!(event.metadata().datadog_api_key().is_none());
         }
     }
+
+    #[tokio::test]
+    async fn validates_api_key() {
+        trace_init();
+        let (sender, rx) = Pipeline::new_test_finalize(EventStatus::Delivered);
+        let address = next_addr();
+        let config: DatadogAgentConfig = toml::from_str(&format!(
+            r#"address = "{}"
+            api_key_validation.allowed_keys = ["12345678abcdefgh12345678abcdefgh"]"#,
+            address
+        ))
+        .unwrap();
+        tokio::spawn(async move {
+            config
+                .build(SourceContext::new_test(sender))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let body = serde_json::to_string(&[LogMsg {
+            message: Bytes::from("foo"),
+            timestamp: 123,
+            hostname: Bytes::from("festeburg"),
+            status: Bytes::from("notice"),
+            service: Bytes::from("vector"),
+            ddsource: Bytes::from("curl"),
+            ddtags: Bytes::from("one,two,three"),
+        }])
+        .unwrap();
+
+        let events = spawn_collect_n(
+            async move {
+                assert_eq!(
+                    403,
+                    send_with_path(address, &body, HeaderMap::new(), "/v1/input/").await
+                );
+                assert_eq!(
+                    403,
+                    send_with_path(
+                        address,
+                        &body,
+                        HeaderMap::new(),
+                        "/v1/input/abcdefgh12345678abcdefgh12345678"
+                    )
+                    .await
+                );
+                assert_eq!(
+                    200,
+                    send_with_path(
+                        address,
+                        &body,
+                        HeaderMap::new(),
+                        "/v1/input/12345678abcdefgh12345678abcdefgh"
+                    )
+                    .await
+                );
+            },
+            rx,
+            1,
+        )
+        .await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            &events[0].metadata().datadog_api_key().as_ref().unwrap()[..],
+            "12345678abcdefgh12345678abcdefgh"
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_logs_to_named_output() {
+        trace_init();
+        let (default_out, default_rx) = Pipeline::new_test();
+        let (logs_out, logs_rx) = Pipeline::new_test_finalize(EventStatus::Delivered);
+        let address = next_addr();
+        let mut context = SourceContext::new_test(default_out);
+        context.named_outputs.insert("agent_logs".into(), logs_out);
+
+        let config = DatadogAgentConfig {
+            address,
+            tls: None,
+            allowed_client_names: Vec::new(),
+            permit_origin: Vec::new(),
+            store_api_key: true,
+            api_key_validation: None,
+            quota: None,
+            idempotency: None,
+            framing: default_framing_message_based(),
+            decoding: default_decoding(),
+            outputs: OutputRoutes {
+                logs: Some("agent_logs".into()),
+            },
+            http2: Http2Config::default(),
+            keepalive: HttpKeepaliveConfig::default(),
+        };
+        assert_eq!(config.named_outputs(), vec!["agent_logs".to_owned()]);
+        tokio::spawn(async move {
+            config.build(context).await.unwrap().await.unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let events = spawn_collect_n(
+            async move {
+                assert_eq!(
+                    200,
+                    send_with_path(
+                        address,
+                        &serde_json::to_string(&[LogMsg {
+                            message: Bytes::from("foo"),
+                            timestamp: 123,
+                            hostname: Bytes::from("festeburg"),
+                            status: Bytes::from("notice"),
+                            service: Bytes::from("vector"),
+                            ddsource: Bytes::from("curl"),
+                            ddtags: Bytes::from("one,two,three"),
+                        }])
+                        .unwrap(),
+                        HeaderMap::new(),
+                        "/api/v2/logs"
+                    )
+                    .await
+                );
+            },
+            logs_rx,
+            1,
+        )
+        .await;
+
+        assert_eq!(events[0].as_log()["message"], "foo".into());
+        assert_eq!(events[0].as_log()["source_subtype"], "logs".into());
+        assert!(collect_ready(default_rx).await.is_empty());
+    }
+
+    #[test]
+    fn parses_output_routes() {
+        let config: DatadogAgentConfig = toml::from_str(
+            r#"
+            address = "0.0.0.0:8080"
+            outputs.logs = "agent_logs"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.outputs.logs.as_deref(), Some("agent_logs"));
+
+        let config: DatadogAgentConfig = toml::from_str(
+            r#"
+            address = "0.0.0.0:8080"
+            outputs.logs = ""
+            "#,
+        )
+        .unwrap();
+        assert!(config.outputs.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_origins_not_permitted() {
+        trace_init();
+        let (sender, _rx) = Pipeline::new_test();
+        let address = next_addr();
+        let config = DatadogAgentConfig {
+            address,
+            tls: None,
+            allowed_client_names: Vec::new(),
+            permit_origin: vec!["192.0.2.0/24".into()],
+            store_api_key: true,
+            api_key_validation: None,
+            quota: None,
+            idempotency: None,
+            framing: default_framing_message_based(),
+            decoding: default_decoding(),
+            outputs: OutputRoutes::default(),
+            http2: Http2Config::default(),
+            keepalive: HttpKeepaliveConfig::default(),
+        };
+        let context = SourceContext::new_test(sender);
+        tokio::spawn(async move {
+            config.build(context).await.unwrap().await.unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        assert!(reqwest::Client::new()
+            .post(&format!("http://{}/api/v2/logs", address))
+            .body("[]")
+            .send()
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn client_names_require_tls() {
+        let config: DatadogAgentConfig = toml::from_str(
+            r#"
+            address = "0.0.0.0:8080"
+            allowed_client_names = ["agent.example.org"]
+            "#,
+        )
+        .unwrap();
+        let (sender, _rx) = Pipeline::new_test();
+        assert!(config.build(SourceContext::new_test(sender)).await.is_err());
+    }
 }
@@ -0,0 +1,188 @@
+use crate::internal_events::{
+    DatadogAgentApiKeyRejected, DatadogAgentApiKeysReloadFailed, DatadogAgentApiKeysReloaded,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+/// Restricts the API keys the source accepts payloads for. Requests without
+/// an API key, or with a key not on the allowlist, are rejected with a 403.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyValidationConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allowed_keys: Vec<String>,
+    /// A file listing allowed keys, one per line, reloaded when it changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_keys_file: Option<PathBuf>,
+    #[serde(default = "default_reload_interval_secs")]
+    reload_interval_secs: u64,
+}
+
+const fn default_reload_interval_secs() -> u64 {
+    10
+}
+
+impl ApiKeyValidationConfig {
+    pub(super) fn build(&self) -> crate::Result<ApiKeyValidator> {
+        if self.allowed_keys.is_empty() && self.allowed_keys_file.is_none() {
+            return Err(
+                "`api_key_validation` requires `allowed_keys` or `allowed_keys_file`.".into(),
+            );
+        }
+        if self.reload_interval_secs == 0 {
+            return Err("`reload_interval_secs` must be greater than zero.".into());
+        }
+
+        let validator = ApiKeyValidator {
+            keys: Arc::default(),
+        };
+        match &self.allowed_keys_file {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path).map_err(|error| {
+                    format!("Could not read API keys file {:?}: {}", path, error)
+                })?;
+                validator.replace(&self.allowed_keys, &contents);
+            }
+            None => validator.replace(&self.allowed_keys, ""),
+        }
+        Ok(validator)
+    }
+
+    /// Reloads the keys file whenever it's modified, until dropped.
+    pub(super) async fn watch(self, validator: ApiKeyValidator) {
+        let path = match self.allowed_keys_file {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut modified = modified_at(&path).await.ok();
+        let mut interval = tokio::time::interval(Duration::from_secs(self.reload_interval_secs));
+        loop {
+            interval.tick().await;
+
+            let reloaded = match modified_at(&path).await {
+                Ok(time) if Some(time) == modified => continue,
+                Ok(time) => tokio::fs::read_to_string(&path)
+                    .await
+                    .map(|contents| (time, contents)),
+                Err(error) => Err(error),
+            };
+            match reloaded {
+                Ok((time, contents)) => {
+                    modified = Some(time);
+                    let count = validator.replace(&self.allowed_keys, &contents);
+                    emit!(&DatadogAgentApiKeysReloaded { path: &path, count });
+                }
+                Err(error) => emit!(&DatadogAgentApiKeysReloadFailed { path: &path, error }),
+            }
+        }
+    }
+}
+
+async fn modified_at(path: &Path) -> io::Result<SystemTime> {
+    tokio::fs::metadata(path).await?.modified()
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ApiKeyValidator {
+    keys: Arc<RwLock<HashSet<String>>>,
+}
+
+impl ApiKeyValidator {
+    pub(super) fn is_allowed(&self, api_key: Option<&str>) -> bool {
+        let allowed = match api_key {
+            Some(api_key) => self.keys.read().expect("poisoned lock").contains(api_key),
+            None => false,
+        };
+
+        if !allowed {
+            emit!(&DatadogAgentApiKeyRejected {
+                reason: if api_key.is_some() {
+                    "invalid_api_key"
+                } else {
+                    "missing_api_key"
+                },
+            });
+        }
+        allowed
+    }
+
+    /// Replaces the allowed keys with `inline` ones and those listed in
+    /// `file`, ignoring blank lines and `#` comments. Returns the number of
+    /// keys now allowed.
+    fn replace(&self, inline: &[String], file: &str) -> usize {
+        let keys = inline
+            .iter()
+            .map(|key| key.trim())
+            .chain(
+                file.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#')),
+            )
+            .map(String::from)
+            .collect::<HashSet<_>>();
+        let count = keys.len();
+        *self.keys.write().expect("poisoned lock") = keys;
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn allows_only_listed_keys() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "# team a\nfile-key\n\n").unwrap();
+
+        let config = ApiKeyValidationConfig {
+            allowed_keys: vec!["inline-key".into()],
+            allowed_keys_file: Some(file.path().into()),
+            reload_interval_secs: 1,
+        };
+        let validator = config.build().unwrap();
+
+        assert!(validator.is_allowed(Some("inline-key")));
+        assert!(validator.is_allowed(Some("file-key")));
+        assert!(!validator.is_allowed(Some("# team a")));
+        assert!(!validator.is_allowed(Some("other-key")));
+        assert!(!validator.is_allowed(None));
+    }
+
+    #[test]
+    fn requires_keys() {
+        assert!(ApiKeyValidationConfig::default().build().is_err());
+    }
+
+    #[tokio::test]
+    async fn reloads_modified_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "old-key").unwrap();
+
+        let config = ApiKeyValidationConfig {
+            allowed_keys: Vec::new(),
+            allowed_keys_file: Some(file.path().into()),
+            reload_interval_secs: 1,
+        };
+        let validator = config.build().unwrap();
+        let watcher = tokio::spawn(config.watch(validator.clone()));
+
+        // Make sure the modification time changes, whatever the resolution of
+        // the file system's timestamps.
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        std::fs::write(file.path(), "new-key\n").unwrap();
+        tokio::time::sleep(Duration::from_millis(2100)).await;
+
+        assert!(validator.is_allowed(Some("new-key")));
+        assert!(!validator.is_allowed(Some("old-key")));
+        watcher.abort();
+    }
+}
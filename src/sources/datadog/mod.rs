@@ -1 +1,2 @@
 pub mod agent;
+mod api_keys;
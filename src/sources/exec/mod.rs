@@ -1,6 +1,6 @@
 use crate::{
     async_read::VecAsyncReadExt,
-    codecs::{self, DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{self, DecodingConfig, FramingConfig, ParserSettings},
     config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
     event::Event,
     internal_events::{ExecCommandExecuted, ExecEventsReceived, ExecFailed, ExecTimeout},
@@ -42,7 +42,7 @@ pub struct ExecConfig {
     #[serde(default = "default_framing_stream_based")]
     framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 // TODO: Would be nice to combine the scheduled and streaming config with the mode enum once
@@ -1,11 +1,13 @@
 use super::util::finalizer::OrderedFinalizer;
 use super::util::{EncodingConfig, MultilineConfig};
 use crate::{
+    codecs::{BoxedParser, Parser, ParserSettings},
     config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
     encoding_transcode::{Decoder, Encoder},
     event::{BatchNotifier, Event, LogEvent},
     internal_events::{
-        FileBytesReceived, FileEventsReceived, FileOpen, FileSourceInternalEventsEmitter,
+        DecoderParseFailed, FileBytesReceived, FileEventsReceived, FileOpen,
+        FileSourceInternalEventsEmitter,
     },
     line_agg::{self, LineAgg},
     shutdown::ShutdownSignal,
@@ -25,11 +27,14 @@ use futures::{
 };
 use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
 use snafu::{ResultExt, Snafu};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio::task::spawn_blocking;
+use vector_core::checkpoint::CheckpointStore;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -60,7 +65,8 @@ enum BuildError {
     },
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Derivative)]
+#[derivative(PartialEq)]
 #[serde(deny_unknown_fields, default)]
 pub struct FileConfig {
     pub include: Vec<PathBuf>,
@@ -79,7 +85,7 @@ pub struct FileConfig {
     #[serde(alias = "glob_minimum_cooldown")]
     pub glob_minimum_cooldown_ms: u64,
     // Deprecated name
-    #[serde(alias = "fingerprinting")]
+    #[serde(alias = "fingerprinting", alias = "file_identity")]
     pub fingerprint: FingerprintConfig,
     pub ignore_not_found: bool,
     pub message_start_indicator: Option<String>,
@@ -90,7 +96,13 @@ pub struct FileConfig {
     #[serde(alias = "remove_after")]
     pub remove_after_secs: Option<u64>,
     pub line_delimiter: String,
+    /// Whether the files are on a network filesystem such as NFS or SMB.
+    pub network_filesystem: bool,
     pub encoding: Option<EncodingConfig>,
+    // Parses the lines of each file with a parser of its own, as parsers such
+    // as `csv` read the names of the fields from the file.
+    #[derivative(PartialEq = "ignore")]
+    pub decoding: Option<ParserSettings>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -106,6 +118,7 @@ pub enum FingerprintConfig {
     },
     #[serde(rename = "device_and_inode")]
     DevInode,
+    Path,
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
@@ -146,6 +159,7 @@ impl From<FingerprintConfig> for FingerprintStrategy {
                 }
             }
             FingerprintConfig::DevInode => FingerprintStrategy::DevInode,
+            FingerprintConfig::Path => FingerprintStrategy::Path,
         }
     }
 }
@@ -191,7 +205,9 @@ impl Default for FileConfig {
             oldest_first: false,
             remove_after_secs: None,
             line_delimiter: "\n".to_string(),
+            network_filesystem: false,
             encoding: None,
+            decoding: None,
         }
     }
 }
@@ -214,6 +230,13 @@ impl SourceConfig for FileConfig {
             .globals
             // source are only global, name can be used for subdir
             .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        // The checkpoints themselves are kept in the shared store, the subdir
+        // only still holds the ones written before it.
+        let checkpoint_store = CheckpointStore::new(
+            cx.globals
+                .resolve_and_validate_data_dir(self.data_dir.as_ref())?,
+        );
+        let checkpointer = Checkpointer::new(&checkpoint_store, cx.key.id(), &data_dir);
 
         // Clippy rule, because async_trait?
         #[allow(clippy::suspicious_else_formatting)]
@@ -226,11 +249,26 @@ impl SourceConfig for FileConfig {
                 Regex::new(indicator)
                     .with_context(|| InvalidMessageStartIndicator { indicator })?;
             }
+
+            if let Some(ref decoding) = self.decoding {
+                // Lines would be transcoded twice.
+                if self.encoding.is_some() && decoding.charset().is_some() {
+                    return Err(
+                        "`encoding.charset` and `decoding.charset` can't both be set.".into(),
+                    );
+                }
+                decoding.build()?;
+            }
+        }
+
+        if self.network_filesystem && self.fingerprint == FingerprintConfig::DevInode {
+            warn!(message = "Device and inode numbers may not be stable on network filesystems, consider the `checksum` or `path` fingerprint strategies instead.");
         }
 
         Ok(file_source(
             self,
             data_dir,
+            checkpointer,
             cx.shutdown,
             cx.out,
             cx.acknowledgements,
@@ -249,6 +287,7 @@ impl SourceConfig for FileConfig {
 pub fn file_source(
     config: &FileConfig,
     data_dir: PathBuf,
+    checkpointer: Checkpointer,
     shutdown: ShutdownSignal,
     mut out: Pipeline,
     acknowledgements: bool,
@@ -280,7 +319,6 @@ pub fn file_source(
         None => Bytes::from(config.line_delimiter.clone()),
     };
 
-    let checkpointer = Checkpointer::new(&data_dir);
     let file_server = FileServer {
         paths_provider,
         max_read_bytes: config.max_read_bytes,
@@ -298,6 +336,8 @@ pub fn file_source(
         },
         oldest_first: config.oldest_first,
         remove_after: config.remove_after_secs.map(Duration::from_secs),
+        network_filesystem: config.network_filesystem
+            || config.fingerprint == FingerprintConfig::Path,
         emitter: FileSourceInternalEventsEmitter,
         handle: tokio::runtime::Handle::current(),
     };
@@ -314,6 +354,11 @@ pub fn file_source(
     let multiline_config = config.multiline.clone();
     let message_start_indicator = config.message_start_indicator.clone();
     let multi_line_timeout = config.multi_line_timeout;
+    let parser: Option<BoxedParser> = config
+        .decoding
+        .as_ref()
+        .map(|decoding| decoding.build().unwrap()); // validated in build
+    let mut file_parsers: HashMap<String, BoxedParser> = HashMap::new();
     let checkpoints = checkpointer.view();
     let shutdown = shutdown.shared();
     let finalizer = acknowledgements.then(|| {
@@ -369,13 +414,38 @@ pub fn file_source(
         let span = current_span();
         let span2 = span.clone();
         let mut messages = messages
-            .map(move |line| {
+            .flat_map(move |line| {
                 let _enter = span2.enter();
-                let mut event =
-                    create_event(line.text, line.filename, &host_key, &hostname, &file_key);
+                let mut events = match &parser {
+                    Some(parser) => {
+                        let parser = file_parsers
+                            .entry(line.filename.clone())
+                            .or_insert_with(|| parser.clone());
+                        parse_events(
+                            parser,
+                            line.text,
+                            line.filename,
+                            &host_key,
+                            &hostname,
+                            &file_key,
+                        )
+                    }
+                    None => smallvec![create_event(
+                        line.text,
+                        line.filename,
+                        &host_key,
+                        &hostname,
+                        &file_key
+                    )],
+                };
                 if let Some(finalizer) = &finalizer {
+                    // Lines without events, such as header rows, are
+                    // acknowledged as soon as the batch is dropped.
                     let (batch, receiver) = BatchNotifier::new_with_receiver();
-                    event = event.with_batch_notifier(&batch);
+                    events = events
+                        .into_iter()
+                        .map(|event| event.with_batch_notifier(&batch))
+                        .collect();
                     let entry = FinalizerEntry {
                         file_id: line.file_id,
                         offset: line.offset,
@@ -384,7 +454,7 @@ pub fn file_source(
                 } else {
                     checkpoints.update(line.file_id, line.offset);
                 }
-                event
+                futures::stream::iter(events)
             })
             .map(Ok);
         tokio::spawn(async move { out.send_all(&mut messages).instrument(span).await });
@@ -460,7 +530,52 @@ fn create_event(
     });
 
     let mut event = LogEvent::from(line);
+    decorate_event(&mut event, file, host_key, hostname, file_key);
 
+    event.into()
+}
+
+fn parse_events(
+    parser: &BoxedParser,
+    line: Bytes,
+    file: String,
+    host_key: &str,
+    hostname: &Option<String>,
+    file_key: &Option<String>,
+) -> SmallVec<[Event; 1]> {
+    let byte_size = line.len();
+    let mut events = match parser.parse(line) {
+        Ok(events) => events,
+        Err(error) => {
+            emit!(&DecoderParseFailed { error: &error });
+            return smallvec![];
+        }
+    };
+
+    if !events.is_empty() {
+        emit!(&FileEventsReceived {
+            count: events.len(),
+            file: &file,
+            byte_size,
+        });
+    }
+
+    for event in &mut events {
+        if let Event::Log(log) = event {
+            decorate_event(log, file.clone(), host_key, hostname, file_key);
+        }
+    }
+
+    events
+}
+
+fn decorate_event(
+    event: &mut LogEvent,
+    file: String,
+    host_key: &str,
+    hostname: &Option<String>,
+    file_key: &Option<String>,
+) {
     // Add source type
     event.insert(log_schema().source_type_key(), Bytes::from("file"));
 
@@ -471,8 +586,6 @@ fn create_event(
     if let Some(hostname) = &hostname {
         event.insert(host_key, hostname.clone());
     }
-
-    event.into()
 }
 
 #[cfg(test)]
@@ -556,6 +669,17 @@ mod tests {
         .unwrap();
         assert_eq!(config.fingerprint, FingerprintConfig::DevInode);
 
+        let config: FileConfig = toml::from_str(
+            r#"
+        network_filesystem = true
+        [file_identity]
+        strategy = "path"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.fingerprint, FingerprintConfig::Path);
+        assert!(config.network_filesystem);
+
         let config: FileConfig = toml::from_str(
             r#"
         [fingerprint]
@@ -1660,9 +1784,21 @@ mod tests {
 
         let (trigger_shutdown, shutdown, shutdown_done) = ShutdownSignal::new_wired();
         let data_dir = config.data_dir.clone().unwrap();
+        let checkpointer = Checkpointer::new(
+            &CheckpointStore::new(&data_dir),
+            "file",
+            &data_dir.join("file"),
+        );
         let acks = !matches!(acking_mode, NoAcks);
 
-        tokio::spawn(file::file_source(config, data_dir, shutdown, tx, acks));
+        tokio::spawn(file::file_source(
+            config,
+            data_dir,
+            checkpointer,
+            shutdown,
+            tx,
+            acks,
+        ));
 
         inner.await;
 
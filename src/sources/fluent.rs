@@ -1,4 +1,4 @@
-use super::util::{SocketListenAddr, TcpError, TcpSource};
+use super::util::{SocketListenAddr, TcpConnectionLimits, TcpError, TcpSource};
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
@@ -31,6 +31,8 @@ pub struct FluentConfig {
     tls: Option<TlsConfig>,
     keepalive: Option<TcpKeepaliveConfig>,
     receive_buffer_bytes: Option<usize>,
+    #[serde(default)]
+    connection_limits: TcpConnectionLimits,
 }
 
 inventory::submit! {
@@ -44,6 +46,7 @@ impl GenerateConfig for FluentConfig {
             keepalive: None,
             tls: None,
             receive_buffer_bytes: None,
+            connection_limits: TcpConnectionLimits::default(),
         })
         .unwrap()
     }
@@ -62,6 +65,7 @@ impl SourceConfig for FluentConfig {
             shutdown_secs,
             tls,
             self.receive_buffer_bytes,
+            self.connection_limits,
             cx.shutdown,
             cx.out,
         )
@@ -925,6 +929,7 @@ mod integration_tests {
                 tls: None,
                 keepalive: None,
                 receive_buffer_bytes: None,
+                connection_limits: Default::default(),
             }
             .build(SourceContext::new_test(sender))
             .await
@@ -1,5 +1,5 @@
 use crate::{
-    codecs::{self, DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{self, DecodingConfig, FramingConfig, ParserSettings},
     config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
     internal_events::GeneratorEventProcessed,
     serde::{default_decoding, default_framing_message_based},
@@ -32,7 +32,7 @@ pub struct GeneratorConfig {
     #[derivative(Default(value = "default_framing_message_based()"))]
     framing: Box<dyn FramingConfig>,
     #[derivative(Default(value = "default_decoding()"))]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 const fn default_interval() -> f64 {
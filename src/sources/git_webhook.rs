@@ -0,0 +1,520 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    sources::util::{
+        http_server::{Http2Config, HttpKeepaliveConfig},
+        ErrorMessage, HttpSource,
+    },
+    tls::TlsConfig,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryFrom, net::SocketAddr};
+use warp::http::{HeaderMap, StatusCode};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GitWebhookConfig {
+    address: SocketAddr,
+    provider: Provider,
+    #[serde(default = "default_path")]
+    path: String,
+    secret: Option<String>,
+    split_field: Option<String>,
+    tls: Option<TlsConfig>,
+    #[serde(default)]
+    http2: Http2Config,
+    #[serde(default)]
+    keepalive: HttpKeepaliveConfig,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    Github,
+    Gitlab,
+}
+
+impl Provider {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Provider::Github => "github",
+            Provider::Gitlab => "gitlab",
+        }
+    }
+
+    /// The header carrying the type of the event a delivery is about.
+    const fn event_header(self) -> &'static str {
+        match self {
+            Provider::Github => "x-github-event",
+            Provider::Gitlab => "x-gitlab-event",
+        }
+    }
+
+    /// The header carrying the unique identifier of a delivery.
+    const fn delivery_header(self) -> &'static str {
+        match self {
+            Provider::Github => "x-github-delivery",
+            Provider::Gitlab => "x-gitlab-event-uuid",
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<GitWebhookConfig>("git_webhook")
+}
+
+impl GenerateConfig for GitWebhookConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:8080".parse().unwrap(),
+            provider: Provider::Github,
+            path: default_path(),
+            secret: Some("${WEBHOOK_SECRET}".to_string()),
+            split_field: None,
+            tls: None,
+            http2: Http2Config::default(),
+            keepalive: HttpKeepaliveConfig::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[derive(Clone)]
+struct GitWebhookSource {
+    provider: Provider,
+    secret: Option<String>,
+    split_field: Option<String>,
+}
+
+impl GitWebhookSource {
+    /// Checks that the delivery was sent by the provider, through the
+    /// `X-Hub-Signature-256` HMAC of GitHub or the `X-Gitlab-Token` of GitLab.
+    fn verify(&self, body: &[u8], header_map: &HeaderMap) -> Result<(), ErrorMessage> {
+        let secret = match &self.secret {
+            Some(secret) => secret,
+            None => return Ok(()),
+        };
+
+        let verified = match self.provider {
+            Provider::Github => header_map
+                .get("x-hub-signature-256")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("sha256="))
+                .map_or(false, |signature| {
+                    hmac_sha256(secret.as_bytes(), body).map_or(false, |expected| {
+                        constant_time_eq(
+                            expected.as_bytes(),
+                            signature.to_ascii_lowercase().as_bytes(),
+                        )
+                    })
+                }),
+            Provider::Gitlab => header_map.get("x-gitlab-token").map_or(false, |token| {
+                constant_time_eq(token.as_bytes(), secret.as_bytes())
+            }),
+        };
+
+        if verified {
+            Ok(())
+        } else {
+            Err(ErrorMessage::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid webhook signature".to_string(),
+            ))
+        }
+    }
+
+    fn split(&self, log: LogEvent) -> Vec<Event> {
+        let field = match &self.split_field {
+            Some(field) => field,
+            None => return vec![log.into()],
+        };
+
+        match log.get(field) {
+            Some(Value::Array(elements)) if !elements.is_empty() => elements
+                .iter()
+                .map(|element| {
+                    let mut log = log.clone();
+                    log.insert(field, element.clone());
+                    log.into()
+                })
+                .collect(),
+            _ => vec![log.into()],
+        }
+    }
+}
+
+impl HttpSource for GitWebhookSource {
+    fn build_events(
+        &self,
+        body: Bytes,
+        header_map: HeaderMap,
+        _query_parameters: HashMap<String, String>,
+        _path: &str,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        self.verify(&body, &header_map)?;
+
+        let event_type = header(&header_map, self.provider.event_header()).ok_or_else(|| {
+            ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Missing {:?} header", self.provider.event_header()),
+            )
+        })?;
+
+        let mut log = decode_payload(&body, &header_map)?;
+        log.insert("webhook.provider", self.provider.as_str());
+        log.insert("webhook.event", event_type);
+        if let Some(delivery_id) = header(&header_map, self.provider.delivery_header()) {
+            log.insert("webhook.delivery_id", delivery_id);
+        }
+        log.try_insert(log_schema().source_type_key(), Bytes::from("git_webhook"));
+        log.try_insert(log_schema().timestamp_key(), Utc::now());
+
+        Ok(self.split(log))
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "git_webhook")]
+impl SourceConfig for GitWebhookConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.secret.is_none() {
+            warn!(
+                message = "No `secret` is set, deliveries will be accepted without being verified.",
+                provider = self.provider.as_str()
+            );
+        }
+
+        let source = GitWebhookSource {
+            provider: self.provider,
+            secret: self.secret.clone(),
+            split_field: self.split_field.clone(),
+        };
+        source.run(
+            self.address,
+            &self.path,
+            true,
+            &self.tls,
+            &None,
+            &None,
+            &None,
+            &self.http2,
+            &self.keepalive,
+            cx,
+        )
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "git_webhook"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+}
+
+fn header(header_map: &HeaderMap, name: &str) -> Option<String> {
+    header_map
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(Into::into)
+}
+
+/// Decodes the JSON payload of a delivery, which GitHub sends as the `payload`
+/// parameter of a form when the webhook uses the `x-www-form-urlencoded`
+/// content type.
+fn decode_payload(body: &[u8], header_map: &HeaderMap) -> Result<LogEvent, ErrorMessage> {
+    let form = header_map
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+            value.starts_with("application/x-www-form-urlencoded")
+        });
+
+    let json = if form {
+        let payload = url::form_urlencoded::parse(body)
+            .find(|(key, _)| key == "payload")
+            .map(|(_, payload)| payload.into_owned())
+            .ok_or_else(|| {
+                ErrorMessage::new(
+                    StatusCode::BAD_REQUEST,
+                    "Missing \"payload\" form parameter".to_string(),
+                )
+            })?;
+        serde_json::from_str::<serde_json::Value>(&payload)
+    } else {
+        serde_json::from_slice::<serde_json::Value>(body)
+    }
+    .map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Error parsing payload: {}", error),
+        )
+    })?;
+
+    LogEvent::try_from(json).map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Error parsing payload: {}", error),
+        )
+    })
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<String, openssl::error::ErrorStack> {
+    let key = PKey::hmac(key)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(data)?;
+    Ok(signer
+        .sign_to_vec()?
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && openssl::memcmp::eq(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{components, next_addr, spawn_collect_n, wait_for_tcp},
+        Pipeline,
+    };
+    use futures::Stream;
+    use pretty_assertions::assert_eq;
+    use warp::http::HeaderValue;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GitWebhookConfig>();
+    }
+
+    fn webhook(provider: Provider, split_field: Option<&str>) -> GitWebhookSource {
+        GitWebhookSource {
+            provider,
+            secret: Some("It's a Secret to Everybody".to_string()),
+            split_field: split_field.map(Into::into),
+        }
+    }
+
+    fn headers(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        header_map
+    }
+
+    #[test]
+    fn verifies_github_signatures() {
+        let source = webhook(Provider::Github, None);
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+        let header_map = headers(&[("x-hub-signature-256", signature)]);
+        assert!(source.verify(b"Hello, World!", &header_map).is_ok());
+        assert!(source.verify(b"Hello, World?", &header_map).is_err());
+        assert!(source.verify(b"Hello, World!", &HeaderMap::new()).is_err());
+    }
+
+    #[test]
+    fn verifies_gitlab_tokens() {
+        let source = webhook(Provider::Gitlab, None);
+
+        let header_map = headers(&[("x-gitlab-token", "It's a Secret to Everybody")]);
+        assert!(source.verify(b"{}", &header_map).is_ok());
+        let header_map = headers(&[("x-gitlab-token", "It's a Secret")]);
+        assert!(source.verify(b"{}", &header_map).is_err());
+    }
+
+    #[test]
+    fn flattens_deliveries() {
+        let source = GitWebhookSource {
+            secret: None,
+            ..webhook(Provider::Gitlab, None)
+        };
+        let header_map = headers(&[
+            ("x-gitlab-event", "Push Hook"),
+            (
+                "x-gitlab-event-uuid",
+                "13792a34-cac6-4fda-95a8-c58e00a3954e",
+            ),
+        ]);
+
+        let events = source
+            .build_events(
+                Bytes::from(r#"{"object_kind":"push","ref":"refs/heads/main"}"#),
+                header_map,
+                HashMap::new(),
+                "/",
+            )
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["object_kind"], "push".into());
+        assert_eq!(log["webhook.provider"], "gitlab".into());
+        assert_eq!(log["webhook.event"], "Push Hook".into());
+        assert_eq!(
+            log["webhook.delivery_id"],
+            "13792a34-cac6-4fda-95a8-c58e00a3954e".into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "git_webhook".into());
+    }
+
+    #[test]
+    fn rejects_deliveries_without_event_type() {
+        let source = GitWebhookSource {
+            secret: None,
+            ..webhook(Provider::Github, None)
+        };
+        let error = source
+            .build_events(Bytes::from("{}"), HeaderMap::new(), HashMap::new(), "/")
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn splits_array_payloads() {
+        let source = GitWebhookSource {
+            secret: None,
+            ..webhook(Provider::Github, Some("commits"))
+        };
+        let body = r#"{"ref":"refs/heads/main","commits":[{"id":"a"},{"id":"b"}]}"#;
+
+        let events = source
+            .build_events(
+                Bytes::from(body),
+                headers(&[("x-github-event", "push")]),
+                HashMap::new(),
+                "/",
+            )
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        for (event, id) in events.iter().zip(&["a", "b"]) {
+            let log = event.as_log();
+            assert_eq!(log["commits.id"], (*id).into());
+            assert_eq!(log["ref"], "refs/heads/main".into());
+            assert_eq!(log["webhook.event"], "push".into());
+        }
+
+        // Deliveries without elements to split are forwarded as they are.
+        let events = source
+            .build_events(
+                Bytes::from(r#"{"zen":"Keep it logically awesome."}"#),
+                headers(&[("x-github-event", "ping")]),
+                HashMap::new(),
+                "/",
+            )
+            .unwrap();
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn decodes_form_payloads() {
+        let header_map = headers(&[("content-type", "application/x-www-form-urlencoded")]);
+        let log =
+            decode_payload(b"payload=%7B%22action%22%3A%22opened%22%7D", &header_map).unwrap();
+        assert_eq!(log["action"], "opened".into());
+    }
+
+    async fn source(
+        provider: Provider,
+        secret: Option<&str>,
+    ) -> (impl Stream<Item = Event>, SocketAddr) {
+        components::init_test();
+        let (sender, recv) = Pipeline::new_test_finalize(EventStatus::Delivered);
+        let address = next_addr();
+        let secret = secret.map(Into::into);
+        tokio::spawn(async move {
+            GitWebhookConfig {
+                address,
+                provider,
+                path: default_path(),
+                secret,
+                split_field: None,
+                tls: None,
+                http2: Http2Config::default(),
+                keepalive: HttpKeepaliveConfig::default(),
+            }
+            .build(SourceContext::new_test(sender))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    async fn send(address: SocketAddr, body: &str, header_map: HeaderMap) -> u16 {
+        reqwest::Client::new()
+            .post(&format!("http://{}/", address))
+            .headers(header_map)
+            .body(body.to_owned())
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn github_deliveries() {
+        let (rx, addr) = source(Provider::Github, Some("It's a Secret to Everybody")).await;
+        let body = r#"{"action":"opened","number":1}"#;
+        let signature = format!(
+            "sha256={}",
+            hmac_sha256(b"It's a Secret to Everybody", body.as_bytes()).unwrap()
+        );
+
+        assert_eq!(
+            401,
+            send(addr, body, headers(&[("x-github-event", "pull_request")])).await
+        );
+
+        let events = spawn_collect_n(
+            async move {
+                assert_eq!(
+                    200,
+                    send(
+                        addr,
+                        body,
+                        headers(&[
+                            ("x-github-event", "pull_request"),
+                            ("x-github-delivery", "72d3162e-cc78-11e3-81ab-4c9367dc0958"),
+                            ("x-hub-signature-256", signature.as_str()),
+                        ])
+                    )
+                    .await
+                );
+            },
+            rx,
+            1,
+        )
+        .await;
+
+        let log = events[0].as_log();
+        assert_eq!(log["action"], "opened".into());
+        assert_eq!(log["number"], 1.into());
+        assert_eq!(log["webhook.provider"], "github".into());
+        assert_eq!(log["webhook.event"], "pull_request".into());
+        assert_eq!(
+            log["webhook.delivery_id"],
+            "72d3162e-cc78-11e3-81ab-4c9367dc0958".into()
+        );
+    }
+}
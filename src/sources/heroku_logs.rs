@@ -1,5 +1,5 @@
 use crate::{
-    codecs::{self, DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{self, DecodingConfig, FramingConfig, ParserSettings},
     config::{
         log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
         SourceDescription,
@@ -8,7 +8,9 @@ use crate::{
     internal_events::{HerokuLogplexRequestReadError, HerokuLogplexRequestReceived},
     serde::{default_decoding, default_framing_message_based},
     sources::util::{
-        add_query_parameters, ErrorMessage, HttpSource, HttpSourceAuthConfig, TcpError,
+        add_query_parameters,
+        http_server::{Http2Config, HttpKeepaliveConfig},
+        ErrorMessage, HttpSource, HttpSourceAuthConfig, TcpError,
     },
     tls::TlsConfig,
 };
@@ -36,7 +38,7 @@ pub struct LogplexConfig {
     #[serde(default = "default_framing_message_based")]
     framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 inventory::submit! {
@@ -90,7 +92,18 @@ impl SourceConfig for LogplexConfig {
             query_parameters: self.query_parameters.clone(),
             decoder,
         };
-        source.run(self.address, "events", true, &self.tls, &self.auth, cx)
+        source.run(
+            self.address,
+            "events",
+            true,
+            &self.tls,
+            &self.auth,
+            &None,
+            &None,
+            &Http2Config::default(),
+            &HttpKeepaliveConfig::default(),
+            cx,
+        )
     }
 
     fn output_type(&self) -> DataType {
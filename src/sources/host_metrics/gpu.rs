@@ -0,0 +1,132 @@
+use super::{filter_result_sync, HostMetrics};
+use crate::event::metric::Metric;
+use chrono::Utc;
+use nvml_wrapper::{enum_wrappers::device::TemperatureSensor, error::NvmlError, NVML};
+use once_cell::sync::OnceCell;
+use shared::btreemap;
+use std::collections::BTreeMap;
+
+impl HostMetrics {
+    pub async fn gpu_metrics(&self) -> Vec<Metric> {
+        let nvml = match nvml() {
+            Some(nvml) => nvml,
+            None => return vec![],
+        };
+        let count = match filter_result_sync(nvml.device_count(), "Failed to count GPUs.") {
+            Some(count) => count,
+            None => return vec![],
+        };
+
+        let timestamp = Utc::now();
+        let mut result = Vec::new();
+        for index in 0..count {
+            let device =
+                match filter_result_sync(nvml.device_by_index(index), "Failed to load GPU device.")
+                {
+                    Some(device) => device,
+                    None => continue,
+                };
+            let mut tags: BTreeMap<String, String> = btreemap! { "gpu" => index.to_string() };
+            if let Some(name) = query(device.name(), "Failed to load GPU name.") {
+                tags.insert("name".into(), name);
+            }
+            if let Some(uuid) = query(device.uuid(), "Failed to load GPU UUID.") {
+                tags.insert("uuid".into(), uuid);
+            }
+
+            if let Some(utilization) = query(
+                device.utilization_rates(),
+                "Failed to load GPU utilization.",
+            ) {
+                result.push(self.gauge(
+                    "gpu_utilization_ratio",
+                    timestamp,
+                    utilization.gpu as f64 / 100.0,
+                    tags.clone(),
+                ));
+                result.push(self.gauge(
+                    "gpu_memory_utilization_ratio",
+                    timestamp,
+                    utilization.memory as f64 / 100.0,
+                    tags.clone(),
+                ));
+            }
+            if let Some(memory) = query(device.memory_info(), "Failed to load GPU memory info.") {
+                result.push(self.gauge(
+                    "gpu_memory_used_bytes",
+                    timestamp,
+                    memory.used as f64,
+                    tags.clone(),
+                ));
+                result.push(self.gauge(
+                    "gpu_memory_total_bytes",
+                    timestamp,
+                    memory.total as f64,
+                    tags.clone(),
+                ));
+            }
+            // NVML reports the power draw in milliwatts.
+            if let Some(power) = query(device.power_usage(), "Failed to load GPU power usage.") {
+                result.push(self.gauge(
+                    "gpu_power_watts",
+                    timestamp,
+                    power as f64 / 1000.0,
+                    tags.clone(),
+                ));
+            }
+            if let Some(temperature) = query(
+                device.temperature(TemperatureSensor::Gpu),
+                "Failed to load GPU temperature.",
+            ) {
+                result.push(self.gauge(
+                    "gpu_temperature_celsius",
+                    timestamp,
+                    temperature as f64,
+                    tags,
+                ));
+            }
+        }
+
+        result
+    }
+}
+
+/// Loads the NVIDIA driver library on first use, so that hosts without it
+/// only log its absence once.
+fn nvml() -> Option<&'static NVML> {
+    static NVML_INSTANCE: OnceCell<Option<NVML>> = OnceCell::new();
+    NVML_INSTANCE
+        .get_or_init(|| {
+            filter_result_sync(
+                NVML::init(),
+                "Failed to load NVML, no GPU metrics will be collected.",
+            )
+        })
+        .as_ref()
+}
+
+/// Queries a GPU, ignoring the queries it doesn't support, as consumer GPUs
+/// don't report some of them.
+fn query<T>(result: Result<T, NvmlError>, message: &'static str) -> Option<T> {
+    match result {
+        Err(NvmlError::NotSupported) => None,
+        result => filter_result_sync(result, message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{all_gauges, count_tag};
+    use super::super::{HostMetrics, HostMetricsConfig};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn generates_gpu_metrics() {
+        // Hosts without a GPU, like most CI runners, don't generate any.
+        let metrics = HostMetrics::new(HostMetricsConfig::default())
+            .gpu_metrics()
+            .await;
+        assert!(all_gauges(&metrics));
+        assert_eq!(count_tag(&metrics, "gpu"), metrics.len());
+    }
+}
@@ -31,8 +31,14 @@ mod cgroups;
 mod cpu;
 mod disk;
 mod filesystem;
+#[cfg(feature = "sources-host_metrics-gpu")]
+mod gpu;
 mod memory;
 mod network;
+#[cfg(target_os = "linux")]
+mod network_sockets;
+#[cfg(target_os = "linux")]
+mod sensors;
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -42,12 +48,28 @@ enum Collector {
     Cpu,
     Disk,
     Filesystem,
+    #[cfg(feature = "sources-host_metrics-gpu")]
+    Gpu,
     Load,
     Host,
     Memory,
     Network,
+    #[cfg(target_os = "linux")]
+    #[serde(rename = "network_sockets")]
+    NetworkSockets,
+    #[cfg(target_os = "linux")]
+    Sensors,
 }
 
+/// The collectors that only run when picked explicitly in `collectors`, as
+/// they need drivers or sensors most hosts don't have.
+const OPT_IN_COLLECTORS: &[Collector] = &[
+    #[cfg(feature = "sources-host_metrics-gpu")]
+    Collector::Gpu,
+    #[cfg(target_os = "linux")]
+    Collector::Sensors,
+];
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub(self) struct FilterList {
     includes: Option<Vec<PatternWrapper>>,
@@ -82,6 +104,12 @@ pub struct HostMetricsConfig {
     filesystem: filesystem::FilesystemConfig,
     #[serde(default)]
     network: network::NetworkConfig,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    network_sockets: network_sockets::NetworkSocketsConfig,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    sensors: sensors::SensorsConfig,
 }
 
 const fn default_scrape_interval() -> u64 {
@@ -135,7 +163,7 @@ impl HostMetricsConfig {
 
     fn has_collector(&self, collector: Collector) -> bool {
         match &self.collectors {
-            None => true,
+            None => !OPT_IN_COLLECTORS.contains(&collector),
             Some(collectors) => collectors.iter().any(|&c| c == collector),
         }
     }
@@ -178,6 +206,10 @@ impl HostMetrics {
         if self.config.has_collector(Collector::Filesystem) {
             metrics.extend(add_collector("filesystem", self.filesystem_metrics().await));
         }
+        #[cfg(feature = "sources-host_metrics-gpu")]
+        if self.config.has_collector(Collector::Gpu) {
+            metrics.extend(add_collector("gpu", self.gpu_metrics().await));
+        }
         if self.config.has_collector(Collector::Load) {
             metrics.extend(add_collector("load", self.loadavg_metrics().await));
         }
@@ -191,6 +223,17 @@ impl HostMetrics {
         if self.config.has_collector(Collector::Network) {
             metrics.extend(add_collector("network", self.network_metrics().await));
         }
+        #[cfg(target_os = "linux")]
+        if self.config.has_collector(Collector::NetworkSockets) {
+            metrics.extend(add_collector(
+                "network_sockets",
+                self.network_sockets_metrics().await,
+            ));
+        }
+        #[cfg(target_os = "linux")]
+        if self.config.has_collector(Collector::Sensors) {
+            metrics.extend(add_collector("sensors", self.sensors_metrics().await));
+        }
         if let Ok(hostname) = &hostname {
             for metric in &mut metrics {
                 metric.insert_tag("host".into(), hostname.into());
@@ -533,6 +576,8 @@ pub(self) mod tests {
             Collector::Host,
             Collector::Memory,
             Collector::Network,
+            #[cfg(target_os = "linux")]
+            Collector::NetworkSockets,
         ] {
             let some_metrics = HostMetrics::new(HostMetricsConfig {
                 collectors: Some(vec![*collector]),
@@ -549,6 +594,23 @@ pub(self) mod tests {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn skips_opt_in_collectors_by_default() {
+        let config = HostMetricsConfig::default();
+        assert!(config.has_collector(Collector::Cpu));
+        assert!(!config.has_collector(Collector::Sensors));
+
+        let config: HostMetricsConfig = toml::from_str(
+            r#"
+            collectors = ["cpu", "sensors"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.has_collector(Collector::Sensors));
+        assert!(!config.has_collector(Collector::Memory));
+    }
+
     #[tokio::test]
     async fn are_taged_with_hostname() {
         let mut metrics = HostMetrics::new(HostMetricsConfig::default())
@@ -0,0 +1,392 @@
+use super::{filter_result_sync, FilterList, HostMetrics};
+use crate::event::metric::Metric;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::btreemap;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+use tokio::fs;
+
+/// TCP states as numbered in `include/net/tcp_states.h`, starting at 1.
+const TCP_STATES: [&str; 12] = [
+    "established",
+    "syn_sent",
+    "syn_recv",
+    "fin_wait1",
+    "fin_wait2",
+    "time_wait",
+    "close",
+    "close_wait",
+    "last_ack",
+    "listen",
+    "closing",
+    "new_syn_recv",
+];
+
+const FAMILIES: [(&str, &str, &str); 2] = [
+    ("ipv4", "net/tcp", "net/udp"),
+    ("ipv6", "net/tcp6", "net/udp6"),
+];
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct NetworkSocketsConfig {
+    per_process: bool,
+    processes: FilterList,
+}
+
+/// A row of one of the `/proc/net/{tcp,udp}*` socket tables.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SocketEntry {
+    state: u8,
+    inode: u64,
+}
+
+impl HostMetrics {
+    pub async fn network_sockets_metrics(&self) -> Vec<Metric> {
+        let timestamp = Utc::now();
+        let root = heim::os::linux::procfs_root();
+        let mut result = Vec::new();
+        let mut tcp_sockets = Vec::new();
+        let mut udp_sockets = Vec::new();
+
+        for (family, tcp_file, udp_file) in FAMILIES.iter() {
+            if let Some(table) = load_socket_table(&root, tcp_file).await {
+                let mut states = [0usize; TCP_STATES.len()];
+                for entry in &table {
+                    if let Some(count) = states.get_mut(entry.state.wrapping_sub(1) as usize) {
+                        *count += 1;
+                    }
+                }
+                result.extend(states.iter().zip(TCP_STATES.iter()).map(|(count, state)| {
+                    self.gauge(
+                        "network_tcp_connections",
+                        timestamp,
+                        *count as f64,
+                        btreemap! { "family" => *family, "state" => *state },
+                    )
+                }));
+                tcp_sockets.extend(table);
+            }
+
+            if let Some(table) = load_socket_table(&root, udp_file).await {
+                result.push(self.gauge(
+                    "network_udp_sockets",
+                    timestamp,
+                    table.len() as f64,
+                    btreemap! { "family" => *family },
+                ));
+                udp_sockets.extend(table);
+            }
+        }
+
+        if let Some(text) = filter_result_sync(
+            fs::read_to_string(root.join("net/netstat")).await,
+            "Failed to load TCP extended statistics.",
+        ) {
+            let tcp = parse_snmp_table(&text, "TcpExt:");
+            for (field, name) in &[
+                ("ListenOverflows", "network_tcp_listen_overflows_total"),
+                ("ListenDrops", "network_tcp_listen_drops_total"),
+            ] {
+                if let Some(value) = tcp.get(field) {
+                    result.push(self.counter(name, timestamp, *value as f64, btreemap! {}));
+                }
+            }
+        }
+
+        let udp_ipv4 = filter_result_sync(
+            fs::read_to_string(root.join("net/snmp")).await,
+            "Failed to load UDP statistics.",
+        )
+        .map(|text| {
+            parse_snmp_table(&text, "Udp:")
+                .into_iter()
+                .map(|(field, value)| (field.to_owned(), value))
+                .collect::<HashMap<_, _>>()
+        });
+        let udp_ipv6 = read_optional(&root.join("net/snmp6"))
+            .await
+            .map(|text| parse_snmp6_udp(&text));
+        for (family, udp) in [("ipv4", udp_ipv4), ("ipv6", udp_ipv6)].iter() {
+            let udp = match udp {
+                Some(udp) => udp,
+                None => continue,
+            };
+            for (field, name) in &[
+                ("InErrors", "network_udp_receive_errors_total"),
+                ("RcvbufErrors", "network_udp_receive_buffer_errors_total"),
+                ("SndbufErrors", "network_udp_send_buffer_errors_total"),
+                ("NoPorts", "network_udp_no_ports_total"),
+            ] {
+                if let Some(value) = udp.get(*field) {
+                    result.push(self.counter(
+                        name,
+                        timestamp,
+                        *value as f64,
+                        btreemap! { "family" => *family },
+                    ));
+                }
+            }
+        }
+
+        if self.config.network_sockets.per_process {
+            let owners = tokio::task::spawn_blocking(move || socket_owners(&root))
+                .await
+                .expect("Socket owner scan panicked.");
+
+            let mut tcp_counts = BTreeMap::<(&str, &str), usize>::new();
+            for entry in &tcp_sockets {
+                let state = TCP_STATES.get(entry.state.wrapping_sub(1) as usize);
+                if let (Some(process), Some(state)) = (owners.get(&entry.inode), state) {
+                    *tcp_counts.entry((process.as_str(), *state)).or_default() += 1;
+                }
+            }
+            let mut udp_counts = BTreeMap::<&str, usize>::new();
+            for entry in &udp_sockets {
+                if let Some(process) = owners.get(&entry.inode) {
+                    *udp_counts.entry(process.as_str()).or_default() += 1;
+                }
+            }
+
+            let processes = &self.config.network_sockets.processes;
+            result.extend(
+                tcp_counts
+                    .into_iter()
+                    .filter(|((process, _), _)| processes.contains_str(Some(*process)))
+                    .map(|((process, state), count)| {
+                        self.gauge(
+                            "network_process_tcp_connections",
+                            timestamp,
+                            count as f64,
+                            btreemap! { "process" => process, "state" => state },
+                        )
+                    }),
+            );
+            result.extend(
+                udp_counts
+                    .into_iter()
+                    .filter(|(process, _)| processes.contains_str(Some(*process)))
+                    .map(|(process, count)| {
+                        self.gauge(
+                            "network_process_udp_sockets",
+                            timestamp,
+                            count as f64,
+                            btreemap! { "process" => process },
+                        )
+                    }),
+            );
+        }
+
+        result
+    }
+}
+
+/// Loads a socket table, treating a missing table as disabled rather than
+/// as an error, since the IPv6 ones don't exist when IPv6 is turned off.
+async fn load_socket_table(root: &Path, filename: &str) -> Option<Vec<SocketEntry>> {
+    read_optional(&root.join(filename))
+        .await
+        .map(|text| parse_socket_table(&text))
+}
+
+async fn read_optional(path: &Path) -> Option<String> {
+    match fs::read_to_string(path).await {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        result => filter_result_sync(result, "Failed to load network socket data."),
+    }
+}
+
+fn parse_socket_table(text: &str) -> Vec<SocketEntry> {
+    // The first line holds the column headers.
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            Some(SocketEntry {
+                state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+                inode: fields.get(9)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Parses one protocol out of `/proc/net/snmp` or `/proc/net/netstat`, which
+/// list each protocol as a line of field names followed by a line of values.
+fn parse_snmp_table<'a>(text: &'a str, prefix: &str) -> HashMap<&'a str, u64> {
+    let mut lines = text
+        .lines()
+        .filter(|line| line.split_whitespace().next() == Some(prefix));
+    match (lines.next(), lines.next()) {
+        (Some(names), Some(values)) => names
+            .split_whitespace()
+            .zip(values.split_whitespace())
+            .skip(1)
+            .filter_map(|(name, value)| Some((name, value.parse().ok()?)))
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+/// Parses the UDP counters out of `/proc/net/snmp6`, which lists one
+/// `Udp6<Field> <value>` pair per line.
+fn parse_snmp6_udp(text: &str) -> HashMap<String, u64> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.strip_prefix("Udp6")?;
+            Some((name.to_owned(), fields.next()?.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Maps socket inodes to the name of the process holding them open.
+fn socket_owners(root: &Path) -> HashMap<u64, String> {
+    let mut owners = HashMap::new();
+    let processes = match filter_result_sync(
+        std::fs::read_dir(root),
+        "Failed to list processes for socket owners.",
+    ) {
+        Some(processes) => processes,
+        None => return owners,
+    };
+
+    for process in processes.flatten() {
+        let pid_dir = process.path();
+        if !is_pid(&pid_dir) {
+            continue;
+        }
+        // Processes may exit or deny access to their file descriptors at any
+        // point, so failures here just skip the process.
+        let fds = match std::fs::read_dir(pid_dir.join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        let inodes = fds
+            .flatten()
+            .filter_map(|fd| std::fs::read_link(fd.path()).ok())
+            .filter_map(|target| parse_socket_link(&target))
+            .collect::<Vec<_>>();
+        if inodes.is_empty() {
+            continue;
+        }
+        let name = match std::fs::read_to_string(pid_dir.join("comm")) {
+            Ok(name) => name.trim_end().to_owned(),
+            Err(_) => continue,
+        };
+        for inode in inodes {
+            owners.entry(inode).or_insert_with(|| name.clone());
+        }
+    }
+
+    owners
+}
+
+fn is_pid(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| name.bytes().all(|c| c.is_ascii_digit()))
+}
+
+fn parse_socket_link(target: &Path) -> Option<u64> {
+    target
+        .to_str()?
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{all_gauges, count_name, count_tag};
+    use super::super::{HostMetrics, HostMetricsConfig};
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_socket_tables() {
+        let text = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 24614 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:0016 0202000A:C2D4 01 00000000:00000000 02:0009FB02 00000000     0        0 40125 4 0000000000000000 20 4 31 10 -1
+   2: 0F02000A:A05E 22D8A8C0:01BB 06 00000000:00000000 03:00000CE8 00000000     0        0 0 3 0000000000000000
+";
+        assert_eq!(
+            parse_socket_table(text),
+            vec![
+                SocketEntry {
+                    state: 10,
+                    inode: 24614
+                },
+                SocketEntry {
+                    state: 1,
+                    inode: 40125
+                },
+                SocketEntry { state: 6, inode: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_snmp_tables() {
+        let text = "Tcp: RtoAlgorithm RtoMin
+Tcp: 1 200
+Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors
+Udp: 1250 12 3 1300 2 0
+UdpLite: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors
+UdpLite: 0 0 0 0 0 0
+";
+        let udp = parse_snmp_table(text, "Udp:");
+        assert_eq!(udp.len(), 6);
+        assert_eq!(udp["NoPorts"], 12);
+        assert_eq!(udp["InErrors"], 3);
+        assert_eq!(udp["RcvbufErrors"], 2);
+        assert!(parse_snmp_table(text, "TcpExt:").is_empty());
+    }
+
+    #[test]
+    fn parses_snmp6_udp() {
+        let text = "Ip6InReceives                   	1337
+Udp6InDatagrams                 	42
+Udp6NoPorts                     	7
+Udp6RcvbufErrors                	1
+UdpLite6InDatagrams             	0
+";
+        let udp = parse_snmp6_udp(text);
+        assert_eq!(udp.len(), 3);
+        assert_eq!(udp["NoPorts"], 7);
+        assert_eq!(udp["RcvbufErrors"], 1);
+    }
+
+    #[test]
+    fn parses_socket_links() {
+        assert_eq!(parse_socket_link(Path::new("socket:[40125]")), Some(40125));
+        assert_eq!(parse_socket_link(Path::new("pipe:[40125]")), None);
+        assert_eq!(parse_socket_link(Path::new("/dev/null")), None);
+    }
+
+    #[tokio::test]
+    async fn generates_network_sockets_metrics() {
+        let config: HostMetricsConfig = toml::from_str(
+            r#"
+            collectors = ["network_sockets"]
+            network_sockets.per_process = true
+            "#,
+        )
+        .unwrap();
+        let metrics = HostMetrics::new(config).network_sockets_metrics().await;
+
+        let connections = metrics
+            .iter()
+            .filter(|metric| metric.name() == "network_tcp_connections")
+            .cloned()
+            .collect::<Vec<_>>();
+        // Every state is reported for each address family, even if it's zero.
+        assert!(connections.len() >= TCP_STATES.len());
+        assert!(all_gauges(&connections));
+        assert_eq!(count_tag(&connections, "family"), connections.len());
+        assert_eq!(count_tag(&connections, "state"), connections.len());
+        assert!(count_name(&metrics, "network_udp_sockets") > 0);
+    }
+}
@@ -0,0 +1,275 @@
+use super::{filter_result_sync, FilterList, HostMetrics};
+use crate::event::metric::Metric;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::btreemap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct SensorsConfig {
+    chips: FilterList,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SensorKind {
+    Temperature,
+    Fan,
+    Power,
+}
+
+impl SensorKind {
+    const fn metric_name(self) -> &'static str {
+        match self {
+            Self::Temperature => "sensors_temperature_celsius",
+            Self::Fan => "sensors_fan_speed_rpm",
+            Self::Power => "sensors_power_watts",
+        }
+    }
+
+    /// Converts a raw hwmon value, in millidegrees Celsius, RPM or
+    /// microwatts, to the unit of the metric.
+    fn scale(self, value: f64) -> f64 {
+        match self {
+            Self::Temperature => value / 1_000.0,
+            Self::Fan => value,
+            Self::Power => value / 1_000_000.0,
+        }
+    }
+}
+
+/// A reading of one of the sensors of a `/sys/class/hwmon` device.
+#[derive(Clone, Debug, PartialEq)]
+struct SensorReading {
+    kind: SensorKind,
+    device: String,
+    chip: String,
+    sensor: String,
+    value: f64,
+}
+
+impl HostMetrics {
+    pub async fn sensors_metrics(&self) -> Vec<Metric> {
+        let timestamp = Utc::now();
+        let root = heim::os::linux::sysfs_root().join("class/hwmon");
+        let readings = tokio::task::spawn_blocking(move || read_sensors(&root))
+            .await
+            .expect("Sensors scan panicked.");
+
+        let chips = &self.config.sensors.chips;
+        readings
+            .into_iter()
+            .filter(|reading| chips.contains_str(Some(&reading.chip)))
+            .map(|reading| {
+                self.gauge(
+                    reading.kind.metric_name(),
+                    timestamp,
+                    reading.value,
+                    btreemap! {
+                        "device" => reading.device,
+                        "chip" => reading.chip,
+                        "sensor" => reading.sensor,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+fn read_sensors(root: &Path) -> Vec<SensorReading> {
+    let devices = match fs::read_dir(root) {
+        // Containers and virtual machines often have no hwmon devices at all.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return vec![],
+        result => match filter_result_sync(result, "Failed to list hardware sensors.") {
+            Some(devices) => devices,
+            None => return vec![],
+        },
+    };
+
+    let mut readings = Vec::new();
+    for device in devices.flatten() {
+        let path = device.path();
+        let device = device.file_name().to_string_lossy().into_owned();
+        let chip = fs::read_to_string(path.join("name"))
+            .map(|name| name.trim_end().to_owned())
+            .unwrap_or_else(|_| device.clone());
+        let attributes = match fs::read_dir(&path) {
+            Ok(attributes) => attributes,
+            Err(_) => continue,
+        };
+
+        let mut attributes = attributes
+            .flatten()
+            .filter_map(|attribute| attribute.file_name().into_string().ok())
+            .collect::<Vec<_>>();
+        attributes.sort();
+        for attribute in &attributes {
+            let (kind, sensor) = match parse_attribute(attribute) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+            // Prefer the instantaneous power draw when both are reported.
+            if attribute.ends_with("_average") && attributes.contains(&format!("{}_input", sensor))
+            {
+                continue;
+            }
+            // Sensors may fail to read when their hardware is powered down,
+            // so such failures just skip the sensor.
+            let value = match fs::read_to_string(path.join(attribute))
+                .ok()
+                .and_then(|value| value.trim().parse::<f64>().ok())
+            {
+                Some(value) => value,
+                None => continue,
+            };
+            let label = fs::read_to_string(path.join(format!("{}_label", sensor)))
+                .map(|label| label.trim_end().to_owned())
+                .unwrap_or_else(|_| sensor.to_owned());
+            readings.push(SensorReading {
+                kind,
+                device: device.clone(),
+                chip: chip.clone(),
+                sensor: label,
+                value: kind.scale(value),
+            });
+        }
+    }
+
+    readings
+}
+
+/// Parses the name of an hwmon attribute holding a reading, such as
+/// `temp1_input`, returning the kind of sensor along with its name.
+fn parse_attribute(attribute: &str) -> Option<(SensorKind, &str)> {
+    let sensor = attribute
+        .strip_suffix("_input")
+        .or_else(|| attribute.strip_suffix("_average"))?;
+    let (kind, number) = if let Some(number) = sensor.strip_prefix("temp") {
+        (SensorKind::Temperature, number)
+    } else if let Some(number) = sensor.strip_prefix("fan") {
+        (SensorKind::Fan, number)
+    } else if let Some(number) = sensor.strip_prefix("power") {
+        (SensorKind::Power, number)
+    } else {
+        return None;
+    };
+    // Only power sensors report averages.
+    if attribute.ends_with("_average") && kind != SensorKind::Power {
+        return None;
+    }
+
+    (!number.is_empty() && number.bytes().all(|c| c.is_ascii_digit())).then(|| (kind, sensor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{all_gauges, count_tag};
+    use super::super::{HostMetrics, HostMetricsConfig};
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn write_device(root: &Path, device: &str, files: &[(&str, &str)]) {
+        let path = root.join(device);
+        fs::create_dir(&path).unwrap();
+        for (name, contents) in files {
+            fs::write(path.join(name), contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn parses_attributes() {
+        assert_eq!(
+            parse_attribute("temp1_input"),
+            Some((SensorKind::Temperature, "temp1"))
+        );
+        assert_eq!(
+            parse_attribute("fan12_input"),
+            Some((SensorKind::Fan, "fan12"))
+        );
+        assert_eq!(
+            parse_attribute("power1_average"),
+            Some((SensorKind::Power, "power1"))
+        );
+        assert_eq!(parse_attribute("temp1_average"), None);
+        assert_eq!(parse_attribute("temp1_max"), None);
+        assert_eq!(parse_attribute("in0_input"), None);
+        assert_eq!(parse_attribute("temp_input"), None);
+    }
+
+    #[test]
+    fn reads_sensors() {
+        let root = tempfile::tempdir().unwrap();
+        write_device(
+            root.path(),
+            "hwmon0",
+            &[
+                ("name", "coretemp\n"),
+                ("temp1_input", "45000\n"),
+                ("temp1_label", "Package id 0\n"),
+                ("temp1_max", "100000\n"),
+            ],
+        );
+        write_device(
+            root.path(),
+            "hwmon1",
+            &[
+                ("name", "amdgpu\n"),
+                ("fan1_input", "1200\n"),
+                ("power1_average", "35000000\n"),
+            ],
+        );
+        write_device(
+            root.path(),
+            "hwmon2",
+            &[
+                ("power1_average", "1000000\n"),
+                ("power1_input", "2500000\n"),
+                ("temp1_input", "not a number\n"),
+            ],
+        );
+
+        let mut readings = read_sensors(root.path());
+        readings.sort_by(|a, b| (&a.device, &a.sensor).cmp(&(&b.device, &b.sensor)));
+        let reading = |kind, device: &str, chip: &str, sensor: &str, value| SensorReading {
+            kind,
+            device: device.into(),
+            chip: chip.into(),
+            sensor: sensor.into(),
+            value,
+        };
+        assert_eq!(
+            readings,
+            vec![
+                reading(
+                    SensorKind::Temperature,
+                    "hwmon0",
+                    "coretemp",
+                    "Package id 0",
+                    45.0
+                ),
+                reading(SensorKind::Fan, "hwmon1", "amdgpu", "fan1", 1200.0),
+                reading(SensorKind::Power, "hwmon1", "amdgpu", "power1", 35.0),
+                reading(SensorKind::Power, "hwmon2", "hwmon2", "power1", 2.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_missing_sensors() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(read_sensors(&root.path().join("hwmon")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn generates_sensors_metrics() {
+        let metrics = HostMetrics::new(HostMetricsConfig::default())
+            .sensors_metrics()
+            .await;
+        assert!(all_gauges(&metrics));
+        assert_eq!(count_tag(&metrics, "chip"), metrics.len());
+        assert_eq!(count_tag(&metrics, "device"), metrics.len());
+        assert_eq!(count_tag(&metrics, "sensor"), metrics.len());
+    }
+}
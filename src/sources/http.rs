@@ -1,7 +1,7 @@
 use crate::{
     codecs::{
         self, BytesDecoderConfig, BytesParserConfig, DecodingConfig, FramingConfig,
-        JsonParserConfig, NewlineDelimitedDecoderConfig, ParserConfig,
+        JsonParserConfig, NewlineDelimitedDecoderConfig, ParserSettings,
     },
     config::{
         log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
@@ -10,7 +10,9 @@ use crate::{
     event::{Event, Value},
     serde::{default_decoding, default_framing_stream_based},
     sources::util::{
-        add_query_parameters, Encoding, ErrorMessage, HttpSource, HttpSourceAuthConfig,
+        add_query_parameters,
+        http_server::{Http2Config, HttpKeepaliveConfig},
+        Encoding, ErrorMessage, HttpSource, HttpSourceAuthConfig, IdempotencyConfig, QuotaConfig,
     },
     tls::TlsConfig,
 };
@@ -34,6 +36,10 @@ pub struct SimpleHttpConfig {
     query_parameters: Vec<String>,
     tls: Option<TlsConfig>,
     auth: Option<HttpSourceAuthConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quota: Option<QuotaConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    idempotency: Option<IdempotencyConfig>,
     #[serde(default = "crate::serde::default_true")]
     strict_path: bool,
     #[serde(default = "default_path")]
@@ -41,7 +47,11 @@ pub struct SimpleHttpConfig {
     #[serde(default = "default_path_key")]
     path_key: String,
     framing: Option<Box<dyn FramingConfig>>,
-    decoding: Option<Box<dyn ParserConfig>>,
+    decoding: Option<ParserSettings>,
+    #[serde(default)]
+    http2: Http2Config,
+    #[serde(default)]
+    keepalive: HttpKeepaliveConfig,
 }
 
 inventory::submit! {
@@ -57,11 +67,15 @@ impl GenerateConfig for SimpleHttpConfig {
             query_parameters: Vec::new(),
             tls: None,
             auth: None,
+            quota: None,
+            idempotency: None,
             path_key: "path".to_string(),
             path: "/".to_string(),
             strict_path: true,
             framing: Some(default_framing_stream_based()),
             decoding: Some(default_decoding()),
+            http2: Http2Config::default(),
+            keepalive: HttpKeepaliveConfig::default(),
         })
         .unwrap()
     }
@@ -139,19 +153,19 @@ impl SourceConfig for SimpleHttpConfig {
             match encoding {
                 Encoding::Text => (
                     Box::new(NewlineDelimitedDecoderConfig::new()) as Box<dyn FramingConfig>,
-                    Box::new(BytesParserConfig::new()) as Box<dyn ParserConfig>,
+                    BytesParserConfig::new().into(),
                 ),
                 Encoding::Json => (
                     Box::new(BytesDecoderConfig::new()) as Box<dyn FramingConfig>,
-                    Box::new(JsonParserConfig::new()) as Box<dyn ParserConfig>,
+                    JsonParserConfig::new().into(),
                 ),
                 Encoding::Ndjson => (
                     Box::new(NewlineDelimitedDecoderConfig::new()) as Box<dyn FramingConfig>,
-                    Box::new(JsonParserConfig::new()) as Box<dyn ParserConfig>,
+                    JsonParserConfig::new().into(),
                 ),
                 Encoding::Binary => (
                     Box::new(BytesDecoderConfig::new()) as Box<dyn FramingConfig>,
-                    Box::new(BytesParserConfig::new()) as Box<dyn ParserConfig>,
+                    BytesParserConfig::new().into(),
                 ),
             }
         } else {
@@ -163,7 +177,7 @@ impl SourceConfig for SimpleHttpConfig {
                 match self.decoding.as_ref() {
                     Some(decoding) => decoding.clone(),
                     None => default_decoding(),
-                } as Box<dyn ParserConfig>,
+                },
             )
         };
 
@@ -180,6 +194,10 @@ impl SourceConfig for SimpleHttpConfig {
             self.strict_path,
             &self.tls,
             &self.auth,
+            &self.quota,
+            &self.idempotency,
+            &self.http2,
+            &self.keepalive,
             cx,
         )
     }
@@ -222,10 +240,10 @@ fn add_headers(events: &mut [Event], headers_config: &[String], headers: HeaderM
 mod tests {
     use super::SimpleHttpConfig;
     use crate::{
-        codecs::{BytesDecoderConfig, FramingConfig, JsonParserConfig, ParserConfig},
+        codecs::{BytesDecoderConfig, FramingConfig, JsonParserConfig, ParserSettings},
         config::{log_schema, SourceConfig, SourceContext},
         event::{Event, EventStatus, Value},
-        test_util::{components, next_addr, spawn_collect_n, trace_init, wait_for_tcp},
+        test_util::{collect_n, components, next_addr, spawn_collect_n, trace_init, wait_for_tcp},
         Pipeline,
     };
     use flate2::{
@@ -253,7 +271,7 @@ mod tests {
         status: EventStatus,
         acknowledgements: bool,
         framing: Option<Box<dyn FramingConfig>>,
-        decoding: Option<Box<dyn ParserConfig>>,
+        decoding: Option<ParserSettings>,
     ) -> (impl Stream<Item = Event> + 'a, SocketAddr) {
         components::init_test();
         let (sender, recv) = Pipeline::new_test_finalize(status);
@@ -270,11 +288,15 @@ mod tests {
                 query_parameters,
                 tls: None,
                 auth: None,
+                quota: None,
+                idempotency: None,
                 strict_path,
                 path_key,
                 path,
                 framing,
                 decoding,
+                http2: Http2Config::default(),
+                keepalive: HttpKeepaliveConfig::default(),
             }
             .build(context)
             .await
@@ -472,7 +494,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -513,7 +535,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -557,7 +579,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -600,7 +622,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -675,7 +697,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -714,7 +736,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -789,7 +811,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -821,7 +843,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -871,7 +893,7 @@ mod tests {
             EventStatus::Delivered,
             true,
             None,
-            Some(Box::new(JsonParserConfig::new())),
+            Some(JsonParserConfig::new().into()),
         )
         .await;
 
@@ -934,4 +956,134 @@ mod tests {
 
         assert_eq!(events.len(), 1);
     }
+
+    #[tokio::test]
+    async fn http_quota_exceeded() {
+        trace_init();
+
+        let (sender, _recv) = Pipeline::new_test();
+        let address = next_addr();
+        let config = toml::from_str::<SimpleHttpConfig>(&format!(
+            r#"
+            address = "{}"
+            quota.identity = "auth_token"
+            quota.max_events = 2
+            "#,
+            address
+        ))
+        .unwrap();
+        tokio::spawn(async move {
+            config
+                .build(SourceContext::new_test(sender))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let send = |token: &'static str| {
+            reqwest::Client::new()
+                .post(&format!("http://{}/", address))
+                .header("Authorization", format!("Bearer {}", token))
+                .body("one\ntwo\n")
+                .send()
+        };
+
+        assert_eq!(send("first").await.unwrap().status(), 200);
+        let response = send("first").await.unwrap();
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()["x-quota-remaining-events"], "0");
+        assert!(response.headers().contains_key("retry-after"));
+        assert_eq!(send("second").await.unwrap().status(), 200);
+    }
+
+    // Other loopback addresses than 127.0.0.1 are only routed on Linux.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn http_quota_per_peer_ip() {
+        trace_init();
+
+        let (sender, _recv) = Pipeline::new_test();
+        let address = next_addr();
+        let config = toml::from_str::<SimpleHttpConfig>(&format!(
+            r#"
+            address = "{}"
+            quota.identity = "peer_ip"
+            quota.max_events = 2
+            "#,
+            address
+        ))
+        .unwrap();
+        tokio::spawn(async move {
+            config
+                .build(SourceContext::new_test(sender))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let send = |peer: [u8; 4]| {
+            reqwest::Client::builder()
+                .local_address(std::net::IpAddr::from(peer))
+                .build()
+                .unwrap()
+                .post(&format!("http://{}/", address))
+                .body("one\ntwo\n")
+                .send()
+        };
+
+        assert_eq!(send([127, 0, 0, 1]).await.unwrap().status(), 200);
+        assert_eq!(send([127, 0, 0, 1]).await.unwrap().status(), 429);
+        // Another peer has a quota of its own.
+        assert_eq!(send([127, 0, 0, 2]).await.unwrap().status(), 200);
+        assert_eq!(send([127, 0, 0, 2]).await.unwrap().status(), 429);
+    }
+
+    #[tokio::test]
+    async fn http_drops_retransmitted_requests() {
+        trace_init();
+
+        let (sender, recv) = Pipeline::new_test();
+        let address = next_addr();
+        let config = toml::from_str::<SimpleHttpConfig>(&format!(
+            r#"
+            address = "{}"
+            idempotency.key = "header"
+            "#,
+            address
+        ))
+        .unwrap();
+        tokio::spawn(async move {
+            config
+                .build(SourceContext::new_test(sender))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let send = |key: &'static str, body: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("Idempotency-Key", key.parse().unwrap());
+            send_with_headers(address, body, headers)
+        };
+
+        assert_eq!(200, send("a", "first").await);
+        assert_eq!(200, send("a", "first").await);
+        assert_eq!(200, send("b", "second").await);
+
+        let events = collect_n(recv, 2).await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "first".into()
+        );
+        assert_eq!(
+            events[1].as_log()[log_schema().message_key()],
+            "second".into()
+        );
+    }
 }
@@ -0,0 +1,754 @@
+//! Polls an IMAP mailbox, emitting one event per new message.
+//!
+//! Each poll fetches the messages matching the `search` criteria whose UID is
+//! above the checkpointed one, without marking them as seen. Once the event of
+//! a message was acknowledged by the sinks, or right away with
+//! acknowledgements disabled, the message is marked as seen, moved or deleted,
+//! and the checkpoint advances past it. UIDs are only meaningful for a given
+//! UIDVALIDITY of the mailbox, so the checkpoint is reset when it changes.
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, LogEvent, Value},
+    internal_events::{
+        ImapCheckpointFailed, ImapMessageParseFailed, ImapMessageReceived, ImapRequestFailed,
+        ImapUidValidityChanged,
+    },
+    pipeline::ClosedError,
+    shutdown::ShutdownSignal,
+    tls::{tls_connector_builder, MaybeTlsSettings, TlsOptions, TlsSettings},
+    Pipeline,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{SinkExt, StreamExt};
+use http::Uri;
+use mailparse::{DispositionType, MailHeaderMap, MailParseError, ParsedMail};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    io::{self, Read, Write},
+    net::TcpStream,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{task::spawn_blocking, time};
+use tokio_stream::wrappers::IntervalStream;
+use vector_core::checkpoint::{CheckpointStore, Checkpoints};
+
+/// How long to wait on the server before giving up on a poll.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid endpoint: {}", source))]
+    InvalidEndpoint { source: http::uri::InvalidUri },
+    #[snafu(display("Endpoint scheme must be `imap` or `imaps`, got {:?}.", scheme))]
+    UnsupportedScheme { scheme: String },
+    #[snafu(display("Endpoint {:?} has no host.", endpoint))]
+    MissingHost { endpoint: String },
+    #[snafu(display("`{}` must be greater than zero.", option))]
+    ZeroOption { option: &'static str },
+    #[snafu(display("Could not load checkpoints: {}", source))]
+    LoadCheckpoints { source: io::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ImapConfig {
+    endpoint: String,
+    username: String,
+    password: String,
+    #[serde(default = "default_mailbox")]
+    mailbox: String,
+    #[serde(default = "default_search")]
+    search: String,
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+    #[serde(default = "default_max_messages_per_poll")]
+    max_messages_per_poll: usize,
+    #[serde(default)]
+    attachments: AttachmentsMode,
+    #[serde(default)]
+    after_ack: AfterAck,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsOptions>,
+}
+
+fn default_mailbox() -> String {
+    "INBOX".into()
+}
+
+fn default_search() -> String {
+    "UNSEEN".into()
+}
+
+const fn default_poll_interval_secs() -> u64 {
+    60
+}
+
+const fn default_max_messages_per_poll() -> usize {
+    100
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentsMode {
+    /// Attachments are left out of events.
+    #[derivative(Default)]
+    Drop,
+    /// Attachments are added to events, with their content base64 encoded.
+    Base64,
+}
+
+/// What to do with a message once its event was acknowledged.
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AfterAck {
+    #[derivative(Default)]
+    MarkSeen,
+    /// Requires the server to support the `MOVE` extension.
+    Move { mailbox: String },
+    /// Requires the server to support the `UIDPLUS` extension.
+    Delete,
+}
+
+inventory::submit! {
+    SourceDescription::new::<ImapConfig>("imap")
+}
+
+impl GenerateConfig for ImapConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "imaps://imap.example.com"
+            username = "alerts@example.com"
+            password = "${IMAP_PASSWORD}"
+            mailbox = "INBOX""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "imap")]
+impl SourceConfig for ImapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.poll_interval_secs == 0 {
+            return Err(BuildError::ZeroOption {
+                option: "poll_interval_secs",
+            }
+            .into());
+        }
+        if self.max_messages_per_poll == 0 {
+            return Err(BuildError::ZeroOption {
+                option: "max_messages_per_poll",
+            }
+            .into());
+        }
+
+        let connection = self.connection()?;
+        let data_dir = cx
+            .globals
+            .resolve_and_validate_data_dir(self.data_dir.as_ref())?;
+        let checkpoints = CheckpointStore::new(data_dir)
+            .namespace(cx.key.id())
+            .context(LoadCheckpoints)?;
+
+        let source = ImapSource {
+            connection,
+            mailbox: self.mailbox.clone(),
+            search: self.search.clone(),
+            max_messages_per_poll: self.max_messages_per_poll,
+            attachments: self.attachments,
+            after_ack: self.after_ack.clone(),
+            checkpoints: Mutex::new(checkpoints),
+            acknowledgements: cx.acknowledgements,
+        };
+        let poll_interval = Duration::from_secs(self.poll_interval_secs);
+        Ok(Box::pin(source.run(cx.out, cx.shutdown, poll_interval)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "imap"
+    }
+}
+
+impl ImapConfig {
+    fn connection(&self) -> crate::Result<Connection> {
+        let endpoint = self.endpoint.parse::<Uri>().context(InvalidEndpoint)?;
+        let (tls, default_port) = match endpoint.scheme_str() {
+            Some("imaps") => (Some(TlsSettings::from_options(&self.tls)?.into()), 993),
+            Some("imap") => (None, 143),
+            scheme => {
+                return Err(BuildError::UnsupportedScheme {
+                    scheme: scheme.unwrap_or_default().into(),
+                }
+                .into())
+            }
+        };
+        let host = endpoint.host().ok_or_else(|| BuildError::MissingHost {
+            endpoint: self.endpoint.clone(),
+        })?;
+
+        Ok(Connection {
+            host: host.into(),
+            port: endpoint.port_u16().unwrap_or(default_port),
+            tls,
+            username: self.username.clone(),
+            password: self.password.clone(),
+        })
+    }
+}
+
+trait ImapStream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> ImapStream for T {}
+
+type Session = imap::Session<Box<dyn ImapStream>>;
+
+struct Connection {
+    host: String,
+    port: u16,
+    tls: Option<MaybeTlsSettings>,
+    username: String,
+    password: String,
+}
+
+impl Connection {
+    /// Connects and logs in to the server, blocking until done.
+    fn open(&self) -> crate::Result<Session> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        let stream: Box<dyn ImapStream> = match &self.tls {
+            Some(tls) => {
+                let mut configuration = tls_connector_builder(tls)?.build().configure()?;
+                if let Some(settings) = tls.tls() {
+                    settings.apply_connect_configuration(&mut configuration);
+                }
+                let stream = configuration
+                    .connect(&self.host, stream)
+                    .map_err(|error| error.to_string())?;
+                Box::new(stream)
+            }
+            None => Box::new(stream),
+        };
+
+        let mut client = imap::Client::new(stream);
+        client.read_greeting()?;
+        client
+            .login(&self.username, &self.password)
+            .map_err(|(error, _)| error.into())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct UidCheckpoint {
+    uid_validity: u32,
+    uid: u32,
+}
+
+struct FetchedMessage {
+    uid: u32,
+    body: Vec<u8>,
+    internal_date: Option<DateTime<Utc>>,
+}
+
+struct Fetched {
+    session: Session,
+    /// The checkpoint the messages were fetched after, in the mailbox's
+    /// current UIDVALIDITY.
+    checkpoint: UidCheckpoint,
+    messages: Vec<FetchedMessage>,
+}
+
+enum Outcome {
+    Sent(Option<BatchStatusReceiver>),
+    Unparsable,
+}
+
+struct ImapSource {
+    connection: Connection,
+    mailbox: String,
+    search: String,
+    max_messages_per_poll: usize,
+    attachments: AttachmentsMode,
+    after_ack: AfterAck,
+    checkpoints: Mutex<Checkpoints>,
+    acknowledgements: bool,
+}
+
+impl ImapSource {
+    async fn run(
+        self,
+        mut out: Pipeline,
+        shutdown: ShutdownSignal,
+        poll_interval: Duration,
+    ) -> Result<(), ()> {
+        let source = Arc::new(self);
+        let mut interval = IntervalStream::new(time::interval(poll_interval)).take_until(shutdown);
+
+        while interval.next().await.is_some() {
+            match Arc::clone(&source).poll(&mut out).await {
+                Ok(()) => (),
+                Err(error) if error.is::<ClosedError>() => {
+                    error!(message = "Failed to forward events, downstream is closed.");
+                    return Err(());
+                }
+                Err(error) => emit!(&ImapRequestFailed { error: &error }),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn poll(self: Arc<Self>, out: &mut Pipeline) -> crate::Result<()> {
+        let source = Arc::clone(&self);
+        let Fetched {
+            session,
+            checkpoint,
+            messages,
+        } = spawn_blocking(move || source.fetch()).await??;
+
+        let mut outcomes = Vec::with_capacity(messages.len());
+        for message in messages {
+            emit!(&ImapMessageReceived {
+                uid: message.uid,
+                byte_size: message.body.len(),
+            });
+
+            match self.parse(&message) {
+                Ok(event) => {
+                    let (event, receiver) = if self.acknowledgements {
+                        let (batch, receiver) = BatchNotifier::new_with_receiver();
+                        (event.with_batch_notifier(&batch), Some(receiver))
+                    } else {
+                        (event, None)
+                    };
+                    out.send(event).await?;
+                    outcomes.push((message.uid, Outcome::Sent(receiver)));
+                }
+                Err(error) => {
+                    emit!(&ImapMessageParseFailed {
+                        uid: message.uid,
+                        error: &error,
+                    });
+                    outcomes.push((message.uid, Outcome::Unparsable));
+                }
+            }
+        }
+
+        // The checkpoint only advances past messages that were all processed,
+        // the others are fetched again by the next poll.
+        let mut processed = Vec::with_capacity(outcomes.len());
+        let mut next_checkpoint = checkpoint;
+        let mut in_order = true;
+        for (uid, outcome) in outcomes {
+            let done = match outcome {
+                Outcome::Sent(None) => true,
+                Outcome::Sent(Some(receiver)) => receiver.await == BatchStatus::Delivered,
+                // Fetching it again wouldn't help, but the message is left
+                // untouched in the mailbox.
+                Outcome::Unparsable => {
+                    if in_order {
+                        next_checkpoint.uid = uid;
+                    }
+                    continue;
+                }
+            };
+            if done {
+                processed.push(uid);
+            }
+            in_order &= done;
+            if in_order {
+                next_checkpoint.uid = uid;
+            }
+        }
+
+        let source = Arc::clone(&self);
+        spawn_blocking(move || source.complete(session, &processed, checkpoint, next_checkpoint))
+            .await?
+    }
+
+    /// Fetches the messages after the checkpoint, blocking until done.
+    fn fetch(&self) -> crate::Result<Fetched> {
+        let mut session = self.connection.open()?;
+        let mailbox = session.select(&self.mailbox)?;
+        let uid_validity = mailbox.uid_validity.unwrap_or_default();
+
+        let uid = match self.checkpoint() {
+            Some(checkpoint) if checkpoint.uid_validity == uid_validity => checkpoint.uid,
+            Some(checkpoint) => {
+                emit!(&ImapUidValidityChanged {
+                    mailbox: &self.mailbox,
+                    previous: checkpoint.uid_validity,
+                    current: uid_validity,
+                });
+                0
+            }
+            None => 0,
+        };
+
+        // `<uid>:*` always includes the last message, even if its UID is
+        // lower.
+        let mut uids = session
+            .uid_search(format!("UID {}:* {}", uid + 1, self.search))?
+            .into_iter()
+            .filter(|found| *found > uid)
+            .collect::<Vec<_>>();
+        uids.sort_unstable();
+        uids.truncate(self.max_messages_per_poll);
+
+        let mut messages = Vec::with_capacity(uids.len());
+        if !uids.is_empty() {
+            // `BODY.PEEK` doesn't mark the messages as seen.
+            let fetches = session.uid_fetch(uid_set(&uids), "(UID INTERNALDATE BODY.PEEK[])")?;
+            for fetch in fetches.iter() {
+                if let (Some(uid), Some(body)) = (fetch.uid, fetch.body()) {
+                    messages.push(FetchedMessage {
+                        uid,
+                        body: body.to_vec(),
+                        internal_date: fetch.internal_date().map(|date| date.with_timezone(&Utc)),
+                    });
+                }
+            }
+            messages.sort_unstable_by_key(|message| message.uid);
+        }
+
+        Ok(Fetched {
+            session,
+            checkpoint: UidCheckpoint { uid_validity, uid },
+            messages,
+        })
+    }
+
+    /// Checkpoints the processed messages, then applies the `after_ack`
+    /// action to them, blocking until done.
+    fn complete(
+        &self,
+        mut session: Session,
+        processed: &[u32],
+        previous: UidCheckpoint,
+        checkpoint: UidCheckpoint,
+    ) -> crate::Result<()> {
+        if checkpoint != previous || self.checkpoint().is_none() {
+            if let Err(error) = self.save_checkpoint(checkpoint) {
+                emit!(&ImapCheckpointFailed { error });
+            }
+        }
+
+        if !processed.is_empty() {
+            let uids = uid_set(processed);
+            match &self.after_ack {
+                AfterAck::MarkSeen => {
+                    session.uid_store(&uids, "+FLAGS.SILENT (\\Seen)")?;
+                }
+                AfterAck::Move { mailbox } => session.uid_mv(&uids, mailbox)?,
+                AfterAck::Delete => {
+                    session.uid_store(&uids, "+FLAGS.SILENT (\\Deleted)")?;
+                    session.uid_expunge(&uids)?;
+                }
+            }
+        }
+
+        session.logout()?;
+        Ok(())
+    }
+
+    fn checkpoint_keys(&self) -> (String, String) {
+        (
+            format!("{}/uid_validity", self.mailbox),
+            format!("{}/uid", self.mailbox),
+        )
+    }
+
+    fn checkpoint(&self) -> Option<UidCheckpoint> {
+        let (uid_validity_key, uid_key) = self.checkpoint_keys();
+        let checkpoints = self.checkpoints.lock().expect("checkpoints mutex poisoned");
+        Some(UidCheckpoint {
+            uid_validity: checkpoints.get(&uid_validity_key)?.parse().ok()?,
+            uid: checkpoints.get(&uid_key)?.parse().ok()?,
+        })
+    }
+
+    fn save_checkpoint(&self, checkpoint: UidCheckpoint) -> io::Result<()> {
+        let (uid_validity_key, uid_key) = self.checkpoint_keys();
+        let mut checkpoints = self.checkpoints.lock().expect("checkpoints mutex poisoned");
+        let mut transaction = checkpoints.transaction();
+        transaction
+            .set(uid_validity_key, checkpoint.uid_validity.to_string())
+            .set(uid_key, checkpoint.uid.to_string());
+        transaction.commit()
+    }
+
+    fn parse(&self, message: &FetchedMessage) -> Result<Event, MailParseError> {
+        let mail = mailparse::parse_mail(&message.body)?;
+        let mut parts = Parts::default();
+        collect_parts(&mail, self.attachments, &mut parts)?;
+
+        let mut headers = BTreeMap::new();
+        for header in &mail.headers {
+            let value = Value::from(header.get_value());
+            match headers.entry(header.get_key().to_lowercase()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+                // Repeated headers, such as `Received`, are kept in order.
+                Entry::Occupied(mut entry) => match entry.get_mut() {
+                    Value::Array(values) => values.push(value),
+                    first => {
+                        *first = Value::Array(vec![std::mem::replace(first, Value::Null), value])
+                    }
+                },
+            }
+        }
+
+        let timestamp = mail
+            .headers
+            .get_first_value("Date")
+            .and_then(|date| mailparse::dateparse(&date).ok())
+            .map(|seconds| Utc.timestamp(seconds, 0))
+            .or(message.internal_date)
+            .unwrap_or_else(Utc::now);
+
+        let schema = log_schema();
+        let mut log = LogEvent::default();
+        log.insert(
+            schema.message_key(),
+            parts.text.or(parts.html).unwrap_or_default(),
+        );
+        log.insert(schema.timestamp_key(), timestamp);
+        log.insert(schema.source_type_key(), "imap");
+        log.insert("headers", headers);
+        log.insert("mailbox", self.mailbox.as_str());
+        log.insert("uid", message.uid);
+        if self.attachments == AttachmentsMode::Base64 {
+            log.insert("attachments", Value::Array(parts.attachments));
+        }
+        Ok(log.into())
+    }
+}
+
+#[derive(Default)]
+struct Parts {
+    text: Option<String>,
+    html: Option<String>,
+    attachments: Vec<Value>,
+}
+
+/// Walks the MIME tree of a message, keeping its first plain text and HTML
+/// bodies, and its attachments if they aren't dropped.
+fn collect_parts(
+    part: &ParsedMail,
+    mode: AttachmentsMode,
+    parts: &mut Parts,
+) -> Result<(), MailParseError> {
+    if !part.subparts.is_empty() {
+        for subpart in &part.subparts {
+            collect_parts(subpart, mode, parts)?;
+        }
+        return Ok(());
+    }
+
+    let disposition = part.get_content_disposition();
+    let filename = disposition
+        .params
+        .get("filename")
+        .or_else(|| part.ctype.params.get("name"));
+    if disposition.disposition == DispositionType::Attachment || filename.is_some() {
+        if mode == AttachmentsMode::Base64 {
+            let content = part.get_body_raw()?;
+            let mut attachment = BTreeMap::new();
+            attachment.insert(
+                "filename".to_owned(),
+                filename.map_or(Value::Null, |filename| filename.as_str().into()),
+            );
+            attachment.insert(
+                "content_type".to_owned(),
+                part.ctype.mimetype.as_str().into(),
+            );
+            attachment.insert("size".to_owned(), (content.len() as i64).into());
+            attachment.insert("content".to_owned(), base64::encode(&content).into());
+            parts.attachments.push(Value::Map(attachment));
+        }
+    } else if part.ctype.mimetype == "text/plain" && parts.text.is_none() {
+        parts.text = Some(part.get_body()?);
+    } else if part.ctype.mimetype == "text/html" && parts.html.is_none() {
+        parts.html = Some(part.get_body()?);
+    }
+    Ok(())
+}
+
+/// Formats UIDs as an IMAP sequence set, collapsing consecutive UIDs into
+/// ranges.
+fn uid_set(uids: &[u32]) -> String {
+    let mut ranges = Vec::<(u32, u32)>::new();
+    for &uid in uids {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == uid => *end = uid,
+            _ => ranges.push((uid, uid)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}:{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    const MESSAGE: &str = "Date: Tue, 1 Nov 2022 10:00:00 +0000\r\n\
+        From: Vendor Alerts <alerts@vendor.example>\r\n\
+        To: oncall@example.com\r\n\
+        Subject: Disk usage above 90%\r\n\
+        Received: from a.example\r\n\
+        Received: from b.example\r\n\
+        MIME-Version: 1.0\r\n\
+        Content-Type: multipart/mixed; boundary=\"boundary\"\r\n\
+        \r\n\
+        --boundary\r\n\
+        Content-Type: text/plain; charset=utf-8\r\n\
+        \r\n\
+        Disk /dev/sda1 is 93% full.\r\n\
+        --boundary\r\n\
+        Content-Type: text/csv\r\n\
+        Content-Disposition: attachment; filename=\"usage.csv\"\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        \r\n\
+        ZGlzayx1c2FnZQo=\r\n\
+        --boundary--\r\n";
+
+    fn config(endpoint: &str) -> ImapConfig {
+        toml::from_str(&format!(
+            r#"endpoint = "{}"
+            username = "alerts@example.com"
+            password = "secret""#,
+            endpoint
+        ))
+        .unwrap()
+    }
+
+    fn source(attachments: AttachmentsMode, data_dir: &Path) -> ImapSource {
+        let config = config("imaps://imap.example.com");
+        let store = CheckpointStore::new(data_dir);
+
+        ImapSource {
+            connection: config.connection().unwrap(),
+            mailbox: config.mailbox,
+            search: config.search,
+            max_messages_per_poll: config.max_messages_per_poll,
+            attachments,
+            after_ack: config.after_ack,
+            checkpoints: Mutex::new(store.namespace("imap").unwrap()),
+            acknowledgements: false,
+        }
+    }
+
+    fn message() -> FetchedMessage {
+        FetchedMessage {
+            uid: 42,
+            body: MESSAGE.as_bytes().to_vec(),
+            internal_date: None,
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ImapConfig>();
+    }
+
+    #[test]
+    fn parses_endpoint() {
+        let connection = config("imaps://imap.example.com").connection().unwrap();
+        assert_eq!(connection.host, "imap.example.com");
+        assert_eq!(connection.port, 993);
+        assert!(connection.tls.is_some());
+
+        let connection = config("imap://localhost:1143").connection().unwrap();
+        assert_eq!(connection.port, 1143);
+        assert!(connection.tls.is_none());
+
+        assert!(config("pop3://mail.example.com").connection().is_err());
+    }
+
+    #[test]
+    fn parses_message() {
+        let data_dir = tempdir().unwrap();
+        let event = source(AttachmentsMode::Drop, data_dir.path())
+            .parse(&message())
+            .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log["message"], "Disk /dev/sda1 is 93% full.".into());
+        assert_eq!(
+            log["timestamp"],
+            Utc.ymd(2022, 11, 1).and_hms(10, 0, 0).into()
+        );
+        assert_eq!(log["headers.subject"], "Disk usage above 90%".into());
+        assert_eq!(
+            log["headers.from"],
+            "Vendor Alerts <alerts@vendor.example>".into()
+        );
+        assert_eq!(log["headers.received[1]"], "from b.example".into());
+        assert_eq!(log["mailbox"], "INBOX".into());
+        assert_eq!(log["uid"], 42.into());
+        assert_eq!(log["source_type"], "imap".into());
+        assert!(!log.contains("attachments"));
+    }
+
+    #[test]
+    fn encodes_attachments() {
+        let data_dir = tempdir().unwrap();
+        let event = source(AttachmentsMode::Base64, data_dir.path())
+            .parse(&message())
+            .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log["attachments[0].filename"], "usage.csv".into());
+        assert_eq!(log["attachments[0].content_type"], "text/csv".into());
+        assert_eq!(log["attachments[0].size"], 11.into());
+        assert_eq!(log["attachments[0].content"], "ZGlzayx1c2FnZQo=".into());
+    }
+
+    #[test]
+    fn checkpoints_uids() {
+        let data_dir = tempdir().unwrap();
+        let source = source(AttachmentsMode::Drop, data_dir.path());
+        assert_eq!(source.checkpoint(), None);
+
+        let checkpoint = UidCheckpoint {
+            uid_validity: 7,
+            uid: 42,
+        };
+        source.save_checkpoint(checkpoint).unwrap();
+        assert_eq!(source.checkpoint(), Some(checkpoint));
+    }
+
+    #[test]
+    fn collapses_uid_ranges() {
+        assert_eq!(uid_set(&[3]), "3");
+        assert_eq!(uid_set(&[1, 2, 3, 5, 7, 8]), "1:3,5,7:8");
+    }
+}
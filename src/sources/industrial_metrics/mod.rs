@@ -0,0 +1,575 @@
+//! Polls industrial equipment, emitting a gauge per Modbus register or OPC UA
+//! node.
+//!
+//! Modbus registers are read over TCP on every scrape. OPC UA nodes are
+//! subscribed to, and every scrape emits the latest value the server published
+//! for each of them. Either way, lost connections are reestablished with an
+//! exponential backoff, scrapes only reporting the `up` gauge as `0` meanwhile.
+
+use crate::{
+    config::{self, GenerateConfig, SourceConfig, SourceContext, SourceDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
+    internal_events::{
+        IndustrialMetricsConnected, IndustrialMetricsConnectionFailed,
+        IndustrialMetricsEventsReceived, IndustrialMetricsReadFailed,
+    },
+    shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
+    Pipeline,
+};
+use chrono::Utc;
+use futures::{stream, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::BTreeMap,
+    iter,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time;
+use tokio_stream::wrappers::IntervalStream;
+use vector_core::ByteSizeOf;
+
+mod modbus;
+mod opc_ua;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`{}` must be greater than zero.", option))]
+    ZeroOption { option: &'static str },
+    #[snafu(display("At least one entry of `{}` must be configured.", option))]
+    NothingToRead { option: &'static str },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+struct IndustrialMetricsConfig {
+    #[serde(flatten)]
+    protocol: ProtocolConfig,
+    #[serde(default = "default_scrape_interval_secs")]
+    scrape_interval_secs: u64,
+    #[serde(default = "default_namespace")]
+    namespace: String,
+    #[serde(default = "default_retry_initial_backoff_secs")]
+    retry_initial_backoff_secs: u64,
+    #[serde(default = "default_retry_max_backoff_secs")]
+    retry_max_backoff_secs: u64,
+    data_dir: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(tag = "protocol", rename_all = "snake_case")]
+enum ProtocolConfig {
+    Modbus(modbus::ModbusConfig),
+    #[serde(rename = "opcua")]
+    OpcUa(opc_ua::OpcUaConfig),
+}
+
+/// The metric a register or node is reported as.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct MetricConfig {
+    name: String,
+    #[serde(default = "default_scale")]
+    scale: f64,
+    #[serde(default)]
+    offset: f64,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+}
+
+const fn default_scrape_interval_secs() -> u64 {
+    15
+}
+
+fn default_namespace() -> String {
+    "industrial".to_string()
+}
+
+const fn default_retry_initial_backoff_secs() -> u64 {
+    1
+}
+
+const fn default_retry_max_backoff_secs() -> u64 {
+    60
+}
+
+const fn default_scale() -> f64 {
+    1.0
+}
+
+inventory::submit! {
+    SourceDescription::new::<IndustrialMetricsConfig>("industrial_metrics")
+}
+
+impl GenerateConfig for IndustrialMetricsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"protocol = "modbus"
+            address = "127.0.0.1:502"
+
+            [[registers]]
+            name = "temperature_celsius"
+            address = 0
+            scale = 0.1"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "industrial_metrics")]
+impl SourceConfig for IndustrialMetricsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        for (option, value) in [
+            ("scrape_interval_secs", self.scrape_interval_secs),
+            (
+                "retry_initial_backoff_secs",
+                self.retry_initial_backoff_secs,
+            ),
+            ("retry_max_backoff_secs", self.retry_max_backoff_secs),
+        ] {
+            if value == 0 {
+                return Err(BuildError::ZeroOption { option }.into());
+            }
+        }
+
+        let interval = Duration::from_secs(self.scrape_interval_secs);
+        let protocol = match &self.protocol {
+            ProtocolConfig::Modbus(config) if config.registers.is_empty() => {
+                return Err(BuildError::NothingToRead {
+                    option: "registers",
+                }
+                .into())
+            }
+            ProtocolConfig::Modbus(config) => Protocol::Modbus(modbus::Client::new(config)?),
+            ProtocolConfig::OpcUa(config) if config.nodes.is_empty() => {
+                return Err(BuildError::NothingToRead { option: "nodes" }.into())
+            }
+            ProtocolConfig::OpcUa(config) => {
+                let data_dir = cx
+                    .globals
+                    .resolve_and_validate_data_dir(self.data_dir.as_ref())?
+                    .join(cx.key.id());
+                Protocol::OpcUa(Arc::new(opc_ua::Client::new(config, data_dir, interval)?))
+            }
+        };
+
+        let source = IndustrialMetrics {
+            metrics: protocol.metrics(),
+            protocol,
+            namespace: Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty()),
+            interval,
+            initial_backoff: Duration::from_secs(self.retry_initial_backoff_secs),
+            max_backoff: Duration::from_secs(self.retry_max_backoff_secs),
+        };
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn output_type(&self) -> config::DataType {
+        config::DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        "industrial_metrics"
+    }
+}
+
+enum Protocol {
+    Modbus(modbus::Client),
+    OpcUa(Arc<opc_ua::Client>),
+}
+
+enum Connection {
+    Modbus(modbus::Connection),
+    OpcUa(opc_ua::Subscription),
+}
+
+impl Protocol {
+    fn endpoint(&self) -> &str {
+        match self {
+            Self::Modbus(client) => client.endpoint(),
+            Self::OpcUa(client) => client.endpoint(),
+        }
+    }
+
+    fn metrics(&self) -> Vec<MetricConfig> {
+        match self {
+            Self::Modbus(client) => client.metrics(),
+            Self::OpcUa(client) => client.metrics(),
+        }
+    }
+
+    async fn connect(&self) -> crate::Result<Connection> {
+        Ok(match self {
+            Self::Modbus(client) => Connection::Modbus(client.connect().await?),
+            Self::OpcUa(client) => Connection::OpcUa(client.connect().await?),
+        })
+    }
+
+    /// The raw value of every metric, if it could be read.
+    async fn poll(&self, connection: &mut Connection) -> crate::Result<Vec<Option<f64>>> {
+        match (self, connection) {
+            (Self::Modbus(client), Connection::Modbus(connection)) => client.poll(connection).await,
+            (Self::OpcUa(_), Connection::OpcUa(subscription)) => subscription.poll(),
+            _ => unreachable!("Connections are opened by their protocol."),
+        }
+    }
+}
+
+struct IndustrialMetrics {
+    protocol: Protocol,
+    metrics: Vec<MetricConfig>,
+    namespace: Option<String>,
+    interval: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl IndustrialMetrics {
+    async fn run(self, mut out: Pipeline, shutdown: ShutdownSignal) -> Result<(), ()> {
+        let endpoint = self.protocol.endpoint().to_owned();
+        let mut interval = IntervalStream::new(time::interval(self.interval)).take_until(shutdown);
+        let mut connection = None;
+        let mut backoff = self.backoff();
+        let mut retry_at = Instant::now();
+
+        while interval.next().await.is_some() {
+            if connection.is_none() && Instant::now() >= retry_at {
+                match self.protocol.connect().await {
+                    Ok(connected) => {
+                        emit!(&IndustrialMetricsConnected {
+                            endpoint: &endpoint
+                        });
+                        connection = Some(connected);
+                        backoff = self.backoff();
+                    }
+                    Err(error) => {
+                        let retry_in = backoff.next().expect("Backoff never ends.");
+                        emit!(&IndustrialMetricsConnectionFailed {
+                            error: &error,
+                            endpoint: &endpoint,
+                            retry_in,
+                        });
+                        retry_at = Instant::now() + retry_in;
+                    }
+                }
+            }
+
+            let values = match connection.as_mut() {
+                Some(opened) => match self.protocol.poll(opened).await {
+                    Ok(values) => Some(values),
+                    Err(error) => {
+                        emit!(&IndustrialMetricsReadFailed {
+                            error: &error,
+                            endpoint: &endpoint,
+                            metric: None,
+                        });
+                        // Reconnect on the next scrape.
+                        connection = None;
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            let metrics = self.metrics(values.as_deref(), &endpoint);
+            emit!(&IndustrialMetricsEventsReceived {
+                count: metrics.len(),
+                byte_size: metrics.size_of(),
+                endpoint: &endpoint,
+            });
+
+            let mut metrics = stream::iter(metrics).map(Event::Metric).map(Ok);
+            if let Err(error) = out.send_all(&mut metrics).await {
+                error!(message = "Error sending metric.", %error);
+                return Err(());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn backoff(&self) -> ExponentialBackoff {
+        // Delays are the factor times successive powers of 2, starting at 2.
+        ExponentialBackoff::from_millis(2)
+            .factor(self.initial_backoff.as_millis() as u64 / 2)
+            .max_delay(self.max_backoff)
+    }
+
+    fn metrics(&self, values: Option<&[Option<f64>]>, endpoint: &str) -> Vec<Metric> {
+        let timestamp = Some(Utc::now());
+        let mut tags = BTreeMap::new();
+        tags.insert("endpoint".to_owned(), endpoint.to_owned());
+
+        let up = Metric::new(
+            "up",
+            MetricKind::Absolute,
+            MetricValue::Gauge {
+                value: if values.is_some() { 1.0 } else { 0.0 },
+            },
+        )
+        .with_namespace(self.namespace.clone())
+        .with_tags(Some(tags.clone()))
+        .with_timestamp(timestamp);
+
+        values
+            .into_iter()
+            .flatten()
+            .zip(&self.metrics)
+            .filter_map(|(value, config)| {
+                let mut tags = tags.clone();
+                tags.extend(config.tags.clone());
+                value.map(|value| {
+                    Metric::new(
+                        config.name.clone(),
+                        MetricKind::Absolute,
+                        MetricValue::Gauge {
+                            value: value * config.scale + config.offset,
+                        },
+                    )
+                    .with_namespace(self.namespace.clone())
+                    .with_tags(Some(tags))
+                    .with_timestamp(timestamp)
+                })
+            })
+            .chain(iter::once(up))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::SourceContext,
+        test_util::{collect_ready, next_addr, trace_init},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<IndustrialMetricsConfig>();
+    }
+
+    #[test]
+    fn parses_both_protocols() {
+        let config = toml::from_str::<IndustrialMetricsConfig>(
+            r#"
+            protocol = "modbus"
+            address = "10.0.0.5:502"
+            unit_id = 3
+            scrape_interval_secs = 5
+
+            [[registers]]
+            name = "flow_rate"
+            address = 100
+            kind = "input"
+            data_type = "float32"
+            word_order = "little_endian"
+            tags = { line = "2" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.scrape_interval_secs, 5);
+        match config.protocol {
+            ProtocolConfig::Modbus(config) => {
+                assert_eq!(config.unit_id, 3);
+                let register = &config.registers[0];
+                assert_eq!(register.kind, modbus::RegisterKind::Input);
+                assert_eq!(register.data_type, modbus::ValueType::Float32);
+                assert_eq!(register.word_order, modbus::WordOrder::LittleEndian);
+                assert_eq!(register.metric.name, "flow_rate");
+                assert_eq!(register.metric.scale, 1.0);
+                assert_eq!(register.metric.tags["line"], "2");
+            }
+            _ => panic!("Expected a Modbus config."),
+        }
+
+        let config = toml::from_str::<IndustrialMetricsConfig>(
+            r#"
+            protocol = "opcua"
+            endpoint = "opc.tcp://10.0.0.6:4840"
+
+            [[nodes]]
+            name = "spindle_speed_rpm"
+            node_id = "ns=2;s=Machine.Spindle.Speed"
+            scale = 60
+            "#,
+        )
+        .unwrap();
+        match config.protocol {
+            ProtocolConfig::OpcUa(config) => {
+                assert_eq!(config.nodes[0].node_id, "ns=2;s=Machine.Spindle.Speed");
+                assert_eq!(config.nodes[0].metric.scale, 60.0);
+            }
+            _ => panic!("Expected an OPC UA config."),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_single_bits_with_data_types() {
+        let config = toml::from_str::<IndustrialMetricsConfig>(
+            r#"
+            protocol = "modbus"
+            address = "10.0.0.5:502"
+
+            [[registers]]
+            name = "pump_running"
+            address = 1
+            kind = "coil"
+            data_type = "int32"
+            "#,
+        )
+        .unwrap();
+        assert!(config
+            .build(SourceContext::new_test(Pipeline::new_test().0))
+            .await
+            .is_err());
+    }
+
+    /// Serves holding registers holding their own address, refusing to read
+    /// those above 1000.
+    async fn serve_modbus(listener: TcpListener) {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0; 12];
+        while socket.read_exact(&mut request).await.is_ok() {
+            let address = u16::from_be_bytes([request[8], request[9]]);
+            let count = u16::from_be_bytes([request[10], request[11]]);
+            let pdu = if address > 1000 {
+                vec![0x83, 0x02]
+            } else {
+                let mut pdu = vec![0x03, count as u8 * 2];
+                for _ in 0..count {
+                    pdu.extend_from_slice(&address.to_be_bytes());
+                }
+                pdu
+            };
+
+            let mut response = request[0..4].to_vec();
+            response.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+            response.push(request[6]);
+            response.extend(pdu);
+            socket.write_all(&response).await.unwrap();
+        }
+    }
+
+    fn gauges(events: Vec<Event>) -> BTreeMap<String, f64> {
+        events
+            .into_iter()
+            .map(|event| {
+                let metric = event.into_metric();
+                match metric.value() {
+                    MetricValue::Gauge { value } => (metric.name().to_owned(), *value),
+                    value => panic!("Unexpected metric value {:?}.", value),
+                }
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn polls_modbus_registers() {
+        trace_init();
+
+        let address = next_addr();
+        let listener = TcpListener::bind(address).await.unwrap();
+        tokio::spawn(serve_modbus(listener));
+
+        let config = toml::from_str::<IndustrialMetricsConfig>(&format!(
+            r#"
+            protocol = "modbus"
+            address = "{}"
+
+            [[registers]]
+            name = "temperature_celsius"
+            address = 215
+            scale = 0.1
+            offset = -20
+
+            [[registers]]
+            name = "energy_watt_hours"
+            address = 1
+            data_type = "uint32"
+
+            [[registers]]
+            name = "missing"
+            address = 2000
+            "#,
+            address
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        time::sleep(Duration::from_millis(500)).await;
+
+        let events = collect_ready(rx).await;
+        let metric = events[0].as_metric();
+        assert_eq!(metric.namespace(), Some("industrial"));
+        assert_eq!(metric.tags().unwrap()["endpoint"], address.to_string());
+
+        let gauges = gauges(events);
+        assert_eq!(gauges.len(), 3);
+        assert!((gauges["temperature_celsius"] - 1.5).abs() < 1e-9);
+        assert_eq!(gauges["energy_watt_hours"], 65537.0);
+        assert_eq!(gauges["up"], 1.0);
+    }
+
+    #[tokio::test]
+    async fn reports_down_endpoints() {
+        trace_init();
+
+        let config = toml::from_str::<IndustrialMetricsConfig>(&format!(
+            r#"
+            protocol = "modbus"
+            address = "{}"
+
+            [[registers]]
+            name = "temperature_celsius"
+            address = 0
+            "#,
+            next_addr()
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(source);
+        time::sleep(Duration::from_millis(500)).await;
+
+        let gauges = gauges(collect_ready(rx).await);
+        assert_eq!(gauges.len(), 1);
+        assert_eq!(gauges["up"], 0.0);
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        let source = IndustrialMetrics {
+            protocol: Protocol::Modbus(
+                modbus::Client::new(&modbus::ModbusConfig {
+                    address: "127.0.0.1:502".to_owned(),
+                    unit_id: 1,
+                    timeout_secs: 1,
+                    registers: Vec::new(),
+                })
+                .unwrap(),
+            ),
+            metrics: Vec::new(),
+            namespace: None,
+            interval: Duration::from_secs(1),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+        };
+        assert_eq!(
+            source.backoff().take(5).collect::<Vec<_>>(),
+            [1, 2, 4, 5, 5].map(Duration::from_secs)
+        );
+    }
+}
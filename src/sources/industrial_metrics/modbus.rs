@@ -0,0 +1,467 @@
+//! A minimal Modbus TCP client, reading coils and registers one value at a
+//! time.
+
+use super::MetricConfig;
+use crate::internal_events::IndustrialMetricsReadFailed;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{io, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time,
+};
+
+/// The largest Modbus TCP frame, header included.
+const MAX_FRAME_LENGTH: usize = 260;
+const MBAP_HEADER_LENGTH: usize = 7;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ModbusConfig {
+    pub address: String,
+    #[serde(default = "default_unit_id")]
+    pub unit_id: u8,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    pub registers: Vec<RegisterConfig>,
+}
+
+const fn default_unit_id() -> u8 {
+    1
+}
+
+const fn default_timeout_secs() -> u64 {
+    5
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RegisterConfig {
+    pub address: u16,
+    #[serde(default)]
+    pub kind: RegisterKind,
+    #[serde(default)]
+    pub data_type: ValueType,
+    #[serde(default)]
+    pub word_order: WordOrder,
+    #[serde(flatten)]
+    pub metric: MetricConfig,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterKind {
+    Coil,
+    DiscreteInput,
+    Holding,
+    Input,
+}
+
+impl Default for RegisterKind {
+    fn default() -> Self {
+        Self::Holding
+    }
+}
+
+impl RegisterKind {
+    const fn function(self) -> u8 {
+        match self {
+            Self::Coil => 0x01,
+            Self::DiscreteInput => 0x02,
+            Self::Holding => 0x03,
+            Self::Input => 0x04,
+        }
+    }
+
+    const fn is_bit(self) -> bool {
+        matches!(self, Self::Coil | Self::DiscreteInput)
+    }
+}
+
+/// How the value of a register, or of consecutive registers, is encoded.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    Uint16,
+    Int16,
+    Uint32,
+    Int32,
+    Float32,
+    Uint64,
+    Int64,
+    Float64,
+}
+
+impl Default for ValueType {
+    fn default() -> Self {
+        Self::Uint16
+    }
+}
+
+impl ValueType {
+    /// The number of registers the value spans.
+    const fn words(self) -> u16 {
+        match self {
+            Self::Uint16 | Self::Int16 => 1,
+            Self::Uint32 | Self::Int32 | Self::Float32 => 2,
+            Self::Uint64 | Self::Int64 | Self::Float64 => 4,
+        }
+    }
+
+    fn decode(self, words: &[u16], order: WordOrder) -> f64 {
+        let push = |bits: u64, word: &u16| bits << 16 | u64::from(*word);
+        let bits = match order {
+            WordOrder::BigEndian => words.iter().fold(0, push),
+            WordOrder::LittleEndian => words.iter().rev().fold(0, push),
+        };
+        match self {
+            Self::Uint16 => f64::from(bits as u16),
+            Self::Int16 => f64::from(bits as u16 as i16),
+            Self::Uint32 => f64::from(bits as u32),
+            Self::Int32 => f64::from(bits as u32 as i32),
+            Self::Float32 => f64::from(f32::from_bits(bits as u32)),
+            Self::Uint64 => bits as f64,
+            Self::Int64 => bits as i64 as f64,
+            Self::Float64 => f64::from_bits(bits),
+        }
+    }
+}
+
+/// The order of the registers of values spanning several of them, each
+/// register being big-endian.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    /// The first register holds the most significant word.
+    BigEndian,
+    /// The first register holds the least significant word.
+    LittleEndian,
+}
+
+impl Default for WordOrder {
+    fn default() -> Self {
+        Self::BigEndian
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ModbusError {
+    #[snafu(display("I/O error: {}", source))]
+    Io { source: io::Error },
+    #[snafu(display("No response within {:?}.", timeout))]
+    Timeout { timeout: Duration },
+    #[snafu(display("Server replied with exception {:#04x} ({}).", code, exception_name(*code)))]
+    Exception { code: u8 },
+    #[snafu(display("Invalid response: {}.", reason))]
+    InvalidResponse { reason: &'static str },
+}
+
+const fn exception_name(code: u8) -> &'static str {
+    match code {
+        0x01 => "illegal function",
+        0x02 => "illegal data address",
+        0x03 => "illegal data value",
+        0x04 => "server device failure",
+        0x05 => "acknowledge",
+        0x06 => "server device busy",
+        0x0A => "gateway path unavailable",
+        0x0B => "gateway target device failed to respond",
+        _ => "unknown exception",
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    address: String,
+    unit_id: u8,
+    timeout: Duration,
+    registers: Vec<RegisterConfig>,
+}
+
+impl Client {
+    pub fn new(config: &ModbusConfig) -> crate::Result<Self> {
+        if config.timeout_secs == 0 {
+            return Err("`timeout_secs` must be greater than zero.".into());
+        }
+        if let Some(register) = config
+            .registers
+            .iter()
+            .find(|register| register.kind.is_bit() && register.data_type != ValueType::Uint16)
+        {
+            return Err(format!(
+                "Register {:?} is a single bit, so it can't have a `data_type`.",
+                register.metric.name
+            )
+            .into());
+        }
+
+        Ok(Self {
+            address: config.address.clone(),
+            unit_id: config.unit_id,
+            timeout: Duration::from_secs(config.timeout_secs),
+            registers: config.registers.clone(),
+        })
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.address
+    }
+
+    pub fn metrics(&self) -> Vec<MetricConfig> {
+        self.registers
+            .iter()
+            .map(|register| register.metric.clone())
+            .collect()
+    }
+
+    pub async fn connect(&self) -> Result<Connection, ModbusError> {
+        let stream = time::timeout(self.timeout, TcpStream::connect(&self.address))
+            .await
+            .map_err(|_| ModbusError::Timeout {
+                timeout: self.timeout,
+            })?
+            .context(Io)?;
+        Ok(Connection {
+            stream,
+            unit_id: self.unit_id,
+            timeout: self.timeout,
+            transaction_id: 0,
+        })
+    }
+
+    /// Reads every register, in the order they were configured.
+    ///
+    /// Registers the server refuses to read, replying with an exception, have
+    /// no value, while other errors are likely to affect the following reads
+    /// too and fail the whole poll.
+    pub async fn poll(&self, connection: &mut Connection) -> crate::Result<Vec<Option<f64>>> {
+        let mut values = Vec::with_capacity(self.registers.len());
+        for register in &self.registers {
+            let count = if register.kind.is_bit() {
+                1
+            } else {
+                register.data_type.words()
+            };
+            match connection
+                .read(register.kind, register.address, count)
+                .await
+            {
+                Ok(words) if register.kind.is_bit() => values.push(Some(f64::from(words[0]))),
+                Ok(words) => {
+                    values.push(Some(register.data_type.decode(&words, register.word_order)))
+                }
+                Err(error @ ModbusError::Exception { .. }) => {
+                    emit!(&IndustrialMetricsReadFailed {
+                        error: &error.into(),
+                        endpoint: &self.address,
+                        metric: Some(&register.metric.name),
+                    });
+                    values.push(None);
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(values)
+    }
+}
+
+#[derive(Debug)]
+pub struct Connection {
+    stream: TcpStream,
+    unit_id: u8,
+    timeout: Duration,
+    transaction_id: u16,
+}
+
+impl Connection {
+    /// Reads `count` registers, or bits, starting at `address`.
+    async fn read(
+        &mut self,
+        kind: RegisterKind,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.transaction_id = self.transaction_id.wrapping_add(1);
+        let transaction_id = self.transaction_id;
+        let request = encode_request(transaction_id, self.unit_id, kind, address, count);
+        let stream = &mut self.stream;
+
+        let exchange = async {
+            stream.write_all(&request).await.context(Io)?;
+
+            let mut header = [0; MBAP_HEADER_LENGTH];
+            stream.read_exact(&mut header).await.context(Io)?;
+            // The length covers the unit identifier, which is part of the header.
+            let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+            if length < 2 || length + MBAP_HEADER_LENGTH - 1 > MAX_FRAME_LENGTH {
+                return InvalidResponse {
+                    reason: "frame length out of bounds",
+                }
+                .fail();
+            }
+            let mut pdu = vec![0; length - 1];
+            stream.read_exact(&mut pdu).await.context(Io)?;
+
+            decode_response(&header, &pdu, transaction_id, kind, count)
+        };
+
+        time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| ModbusError::Timeout {
+                timeout: self.timeout,
+            })?
+    }
+}
+
+fn encode_request(
+    transaction_id: u16,
+    unit_id: u8,
+    kind: RegisterKind,
+    address: u16,
+    count: u16,
+) -> [u8; 12] {
+    let mut request = [0; 12];
+    request[0..2].copy_from_slice(&transaction_id.to_be_bytes());
+    // Bytes 2 and 3 hold the protocol identifier, always 0 for Modbus.
+    request[4..6].copy_from_slice(&6u16.to_be_bytes());
+    request[6] = unit_id;
+    request[7] = kind.function();
+    request[8..10].copy_from_slice(&address.to_be_bytes());
+    request[10..12].copy_from_slice(&count.to_be_bytes());
+    request
+}
+
+fn decode_response(
+    header: &[u8; MBAP_HEADER_LENGTH],
+    pdu: &[u8],
+    transaction_id: u16,
+    kind: RegisterKind,
+    count: u16,
+) -> Result<Vec<u16>, ModbusError> {
+    if u16::from_be_bytes([header[0], header[1]]) != transaction_id {
+        return InvalidResponse {
+            reason: "mismatched transaction identifier",
+        }
+        .fail();
+    }
+    if header[2..4] != [0, 0] {
+        return InvalidResponse {
+            reason: "unknown protocol identifier",
+        }
+        .fail();
+    }
+
+    match pdu {
+        [function, code] if *function == kind.function() | 0x80 => Exception { code: *code }.fail(),
+        [function, byte_count, data @ ..] if *function == kind.function() => {
+            if usize::from(*byte_count) != data.len() {
+                return InvalidResponse {
+                    reason: "mismatched byte count",
+                }
+                .fail();
+            }
+
+            if kind.is_bit() {
+                if data.len() != (usize::from(count) + 7) / 8 {
+                    return InvalidResponse {
+                        reason: "unexpected number of bits",
+                    }
+                    .fail();
+                }
+                Ok((0..usize::from(count))
+                    .map(|bit| u16::from(data[bit / 8] >> (bit % 8) & 1))
+                    .collect())
+            } else {
+                if data.len() != usize::from(count) * 2 {
+                    return InvalidResponse {
+                        reason: "unexpected number of registers",
+                    }
+                    .fail();
+                }
+                Ok(data
+                    .chunks_exact(2)
+                    .map(|word| u16::from_be_bytes([word[0], word[1]]))
+                    .collect())
+            }
+        }
+        _ => InvalidResponse {
+            reason: "unexpected function code",
+        }
+        .fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(transaction_id: u16, pdu: &[u8]) -> [u8; MBAP_HEADER_LENGTH] {
+        let mut header = [0; MBAP_HEADER_LENGTH];
+        header[0..2].copy_from_slice(&transaction_id.to_be_bytes());
+        header[4..6].copy_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+        header[6] = 1;
+        header
+    }
+
+    #[test]
+    fn encodes_requests() {
+        assert_eq!(
+            encode_request(0x0102, 17, RegisterKind::Input, 0x0203, 2),
+            [0x01, 0x02, 0, 0, 0, 6, 17, 0x04, 0x02, 0x03, 0, 2]
+        );
+    }
+
+    #[test]
+    fn decodes_registers() {
+        let pdu = [0x03, 4, 0x12, 0x34, 0xab, 0xcd];
+        assert_eq!(
+            decode_response(&header(7, &pdu), &pdu, 7, RegisterKind::Holding, 2).unwrap(),
+            vec![0x1234, 0xabcd]
+        );
+        assert!(matches!(
+            decode_response(&header(8, &pdu), &pdu, 7, RegisterKind::Holding, 2),
+            Err(ModbusError::InvalidResponse { .. })
+        ));
+        assert!(matches!(
+            decode_response(&header(7, &pdu), &pdu, 7, RegisterKind::Holding, 1),
+            Err(ModbusError::InvalidResponse { .. })
+        ));
+    }
+
+    #[test]
+    fn decodes_bits() {
+        let pdu = [0x01, 2, 0b1000_0101, 0b0000_0001];
+        assert_eq!(
+            decode_response(&header(1, &pdu), &pdu, 1, RegisterKind::Coil, 9).unwrap(),
+            vec![1, 0, 1, 0, 0, 0, 0, 1, 1]
+        );
+    }
+
+    #[test]
+    fn decodes_exceptions() {
+        let pdu = [0x84, 0x02];
+        let error = decode_response(&header(1, &pdu), &pdu, 1, RegisterKind::Input, 1).unwrap_err();
+        assert!(matches!(error, ModbusError::Exception { code: 2 }));
+        assert_eq!(
+            error.to_string(),
+            "Server replied with exception 0x02 (illegal data address)."
+        );
+    }
+
+    #[test]
+    fn decodes_values() {
+        use ValueType::*;
+        use WordOrder::*;
+
+        assert_eq!(Uint16.decode(&[0xffff], BigEndian), 65535.0);
+        assert_eq!(Int16.decode(&[0xffff], BigEndian), -1.0);
+        assert_eq!(Uint32.decode(&[0x0001, 0x0002], BigEndian), 65538.0);
+        assert_eq!(Uint32.decode(&[0x0002, 0x0001], LittleEndian), 65538.0);
+        assert_eq!(Int32.decode(&[0xffff, 0xfffe], BigEndian), -2.0);
+        assert_eq!(
+            Float32.decode(&[0xc2f6, 0xe979], BigEndian),
+            -123.456_001_281_738_28
+        );
+        assert_eq!(Int64.decode(&[0xffff; 4], BigEndian), -1.0);
+        assert_eq!(Float64.decode(&[0x3ff8, 0, 0, 0], BigEndian), 1.5);
+    }
+}
@@ -0,0 +1,321 @@
+//! Subscriptions to the nodes of an OPC UA server.
+//!
+//! The client of the `opcua` crate runs its own runtime, so it's only driven
+//! from blocking tasks. Data changes the server publishes are recorded as they
+//! come, and each poll reads back the latest value of every node.
+
+use super::MetricConfig;
+use crate::internal_events::IndustrialMetricsReadFailed;
+use opcua::{client::prelude::*, sync::RwLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::oneshot, task::spawn_blocking};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct OpcUaConfig {
+    pub endpoint: String,
+    #[serde(default)]
+    pub security_policy: SecurityPolicyConfig,
+    #[serde(default)]
+    pub security_mode: SecurityModeConfig,
+    #[serde(default)]
+    pub trust_server_certs: bool,
+    pub auth: Option<OpcUaAuth>,
+    pub nodes: Vec<NodeConfig>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OpcUaAuth {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct NodeConfig {
+    pub node_id: String,
+    #[serde(flatten)]
+    pub metric: MetricConfig,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityPolicyConfig {
+    None,
+    Basic256Sha256,
+    Aes128Sha256RsaOaep,
+    Aes256Sha256RsaPss,
+}
+
+impl Default for SecurityPolicyConfig {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl SecurityPolicyConfig {
+    const fn policy(self) -> SecurityPolicy {
+        match self {
+            Self::None => SecurityPolicy::None,
+            Self::Basic256Sha256 => SecurityPolicy::Basic256Sha256,
+            Self::Aes128Sha256RsaOaep => SecurityPolicy::Aes128Sha256RsaOaep,
+            Self::Aes256Sha256RsaPss => SecurityPolicy::Aes256Sha256RsaPss,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityModeConfig {
+    None,
+    Sign,
+    SignAndEncrypt,
+}
+
+impl Default for SecurityModeConfig {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl SecurityModeConfig {
+    const fn mode(self) -> MessageSecurityMode {
+        match self {
+            Self::None => MessageSecurityMode::None,
+            Self::Sign => MessageSecurityMode::Sign,
+            Self::SignAndEncrypt => MessageSecurityMode::SignAndEncrypt,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    endpoint: String,
+    security_policy: SecurityPolicyConfig,
+    security_mode: SecurityModeConfig,
+    trust_server_certs: bool,
+    auth: Option<OpcUaAuth>,
+    nodes: Vec<(NodeId, MetricConfig)>,
+    pki_dir: PathBuf,
+    publishing_interval: Duration,
+}
+
+impl Client {
+    pub fn new(
+        config: &OpcUaConfig,
+        data_dir: PathBuf,
+        publishing_interval: Duration,
+    ) -> crate::Result<Self> {
+        if (config.security_policy == SecurityPolicyConfig::None)
+            != (config.security_mode == SecurityModeConfig::None)
+        {
+            return Err(
+                "`security_policy` and `security_mode` must either both be \"none\" or neither."
+                    .into(),
+            );
+        }
+
+        let nodes = config
+            .nodes
+            .iter()
+            .map(|node| {
+                NodeId::from_str(&node.node_id)
+                    .map(|node_id| (node_id, node.metric.clone()))
+                    .map_err(|_| format!("Invalid node identifier {:?}.", node.node_id))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            endpoint: config.endpoint.clone(),
+            security_policy: config.security_policy,
+            security_mode: config.security_mode,
+            trust_server_certs: config.trust_server_certs,
+            auth: config.auth.clone(),
+            nodes,
+            pki_dir: data_dir.join("opcua_pki"),
+            publishing_interval,
+        })
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn metrics(&self) -> Vec<MetricConfig> {
+        self.nodes
+            .iter()
+            .map(|(_, metric)| metric.clone())
+            .collect()
+    }
+
+    pub async fn connect(self: &Arc<Self>) -> crate::Result<Subscription> {
+        let client = Arc::clone(self);
+        spawn_blocking(move || client.subscribe()).await?
+    }
+
+    fn subscribe(&self) -> crate::Result<Subscription> {
+        let mut client = ClientBuilder::new()
+            .application_name("Vector")
+            .application_uri("urn:vector")
+            .product_uri("urn:vector")
+            .pki_dir(&self.pki_dir)
+            .create_sample_keypair(true)
+            .trust_server_certs(self.trust_server_certs)
+            // Reconnections are backed off by the source itself.
+            .session_retry_limit(0)
+            .client()
+            .ok_or("Invalid OPC UA client configuration.")?;
+
+        let identity = match &self.auth {
+            Some(auth) => IdentityToken::UserName(auth.username.clone(), auth.password.clone()),
+            None => IdentityToken::Anonymous,
+        };
+        let session = client
+            .connect_to_endpoint(
+                (
+                    self.endpoint.as_str(),
+                    self.security_policy.policy().to_str(),
+                    self.security_mode.mode(),
+                    UserTokenPolicy::anonymous(),
+                ),
+                identity,
+            )
+            .map_err(|status| format!("Failed to open a session: {}", status))?;
+
+        let values = Arc::new(Mutex::new(vec![None; self.nodes.len()]));
+        let callback = {
+            let values = Arc::clone(&values);
+            // The same node may back several metrics.
+            let mut indices = HashMap::<NodeId, Vec<usize>>::new();
+            for (index, (node_id, _)) in self.nodes.iter().enumerate() {
+                indices.entry(node_id.clone()).or_default().push(index);
+            }
+
+            DataChangeCallback::new(move |items| {
+                let mut values = values.lock().expect("poisoned lock");
+                for item in items.iter() {
+                    if let Some(indices) = indices.get(&item.item_to_monitor().node_id) {
+                        let value = numeric_value(item.last_value());
+                        for &index in indices {
+                            values[index] = value;
+                        }
+                    }
+                }
+            })
+        };
+
+        {
+            let session = session.read();
+            let subscription_id = session
+                .create_subscription(
+                    self.publishing_interval.as_secs_f64() * 1000.0,
+                    10,
+                    30,
+                    0,
+                    0,
+                    true,
+                    callback,
+                )
+                .map_err(|status| format!("Failed to create a subscription: {}", status))?;
+
+            let requests = self
+                .nodes
+                .iter()
+                .map(|(node_id, _)| node_id.clone().into())
+                .collect::<Vec<MonitoredItemCreateRequest>>();
+            let results = session
+                .create_monitored_items(subscription_id, TimestampsToReturn::Neither, &requests)
+                .map_err(|status| format!("Failed to monitor nodes: {}", status))?;
+            for ((node_id, metric), result) in self.nodes.iter().zip(results) {
+                if !result.status_code.is_good() {
+                    emit!(&IndustrialMetricsReadFailed {
+                        error: &format!(
+                            "Failed to monitor node {}: {}",
+                            node_id, result.status_code
+                        )
+                        .into(),
+                        endpoint: &self.endpoint,
+                        metric: Some(&metric.name),
+                    });
+                }
+            }
+        }
+
+        let stop = Session::run_async(Arc::clone(&session));
+        Ok(Subscription {
+            session,
+            stop: Some(stop),
+            values,
+        })
+    }
+}
+
+/// The value of a node as a number, if it's a numeric or boolean one.
+fn numeric_value(value: &DataValue) -> Option<f64> {
+    if value.status.map_or(false, |status| !status.is_good()) {
+        return None;
+    }
+    match value.value.as_ref()? {
+        Variant::Boolean(value) => Some(f64::from(u8::from(*value))),
+        Variant::SByte(value) => Some(f64::from(*value)),
+        Variant::Byte(value) => Some(f64::from(*value)),
+        Variant::Int16(value) => Some(f64::from(*value)),
+        Variant::UInt16(value) => Some(f64::from(*value)),
+        Variant::Int32(value) => Some(f64::from(*value)),
+        Variant::UInt32(value) => Some(f64::from(*value)),
+        Variant::Int64(value) => Some(*value as f64),
+        Variant::UInt64(value) => Some(*value as f64),
+        Variant::Float(value) => Some(f64::from(*value)),
+        Variant::Double(value) => Some(*value),
+        _ => None,
+    }
+}
+
+pub struct Subscription {
+    session: Arc<RwLock<Session>>,
+    stop: Option<oneshot::Sender<SessionCommand>>,
+    values: Arc<Mutex<Vec<Option<f64>>>>,
+}
+
+impl Subscription {
+    /// The latest value of every node, in the order they were configured.
+    pub fn poll(&self) -> crate::Result<Vec<Option<f64>>> {
+        if !self.session.read().is_connected() {
+            return Err("Lost the session to the server.".into());
+        }
+        Ok(self.values.lock().expect("poisoned lock").clone())
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(SessionCommand::Stop);
+        }
+        let session = Arc::clone(&self.session);
+        spawn_blocking(move || session.read().disconnect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_numeric_values() {
+        assert_eq!(numeric_value(&DataValue::new_now(12.5f64)), Some(12.5));
+        assert_eq!(numeric_value(&DataValue::new_now(-3i16)), Some(-3.0));
+        assert_eq!(numeric_value(&DataValue::new_now(true)), Some(1.0));
+        assert_eq!(numeric_value(&DataValue::new_now("running")), None);
+
+        let mut bad = DataValue::new_now(1u32);
+        bad.status = Some(StatusCode::BadSensorFailure);
+        assert_eq!(numeric_value(&bad), None);
+    }
+}
@@ -20,24 +20,24 @@ use snafu::{ResultExt, Snafu};
 use std::path::{Path, PathBuf};
 use std::{
     collections::{HashMap, HashSet},
-    io::SeekFrom,
+    convert::{TryFrom, TryInto},
+    fmt,
     iter::FromIterator,
     process::Stdio,
     str::FromStr,
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use tokio_util::codec::FramedRead;
+use vector_core::checkpoint::{CheckpointStore, Checkpoints};
 
-use tokio::{
-    fs::{File, OpenOptions},
-    io::{self, AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
-    process::Command,
-    time::sleep,
-};
+use tokio::{io, process::Command, time::sleep};
 
 const DEFAULT_BATCH_SIZE: usize = 16;
 
-const CHECKPOINT_FILENAME: &str = "checkpoint.txt";
+/// The file checkpoints were kept in before the shared checkpoint store.
+const LEGACY_CHECKPOINT_FILENAME: &str = "checkpoint.txt";
+const CHECKPOINT_KEY: &str = "cursor";
 const CURSOR: &str = "__CURSOR";
 const HOSTNAME: &str = "_HOSTNAME";
 const MESSAGE: &str = "MESSAGE";
@@ -47,6 +47,15 @@ const RECEIVED_TIMESTAMP: &str = "__REALTIME_TIMESTAMP";
 
 const BACKOFF_DURATION: Duration = Duration::from_secs(1);
 
+/// How often the cursor is checkpointed while reading. It's also checkpointed
+/// when the source shuts down.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The names of the syslog priorities, from the most to the least severe.
+const PRIORITY_NAMES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
 lazy_static! {
     static ref JOURNALCTL: PathBuf = "journalctl".into();
 }
@@ -83,11 +92,108 @@ pub struct JournaldConfig {
     pub batch_size: Option<usize>,
     pub journalctl_path: Option<PathBuf>,
     pub journal_directory: Option<PathBuf>,
+    pub include_priority: Option<PriorityRange>,
+    pub boot_offset: Option<i32>,
     /// Deprecated
     #[serde(default)]
     remap_priority: bool,
 }
 
+/// A range of syslog priorities, given as either a single priority, which
+/// includes it and all of the more severe ones, or as the two ends of the
+/// range, each either a name or a number.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "PriorityRangeConfig", into = "String")]
+pub struct PriorityRange {
+    most_severe: u8,
+    least_severe: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PriorityRangeConfig {
+    Single(PriorityConfig),
+    Range(PriorityConfig, PriorityConfig),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PriorityConfig {
+    Number(u8),
+    Name(String),
+}
+
+impl PriorityRange {
+    fn new(a: u8, b: u8) -> Self {
+        Self {
+            most_severe: a.min(b),
+            least_severe: a.max(b),
+        }
+    }
+}
+
+impl TryFrom<PriorityConfig> for u8 {
+    type Error = String;
+
+    fn try_from(priority: PriorityConfig) -> Result<Self, Self::Error> {
+        match priority {
+            PriorityConfig::Number(number) if (number as usize) < PRIORITY_NAMES.len() => {
+                Ok(number)
+            }
+            PriorityConfig::Number(number) => Err(format!(
+                "Priority {} is out of range, it must be between 0 and 7",
+                number
+            )),
+            PriorityConfig::Name(name) => PRIORITY_NAMES
+                .iter()
+                .position(|priority| priority.eq_ignore_ascii_case(name.trim()))
+                .or_else(|| name.trim().parse().ok().filter(|number| *number < 8))
+                .map(|number| number as u8)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown priority {:?}, expected one of {}",
+                        name,
+                        PRIORITY_NAMES.join(", ")
+                    )
+                }),
+        }
+    }
+}
+
+impl TryFrom<PriorityRangeConfig> for PriorityRange {
+    type Error = String;
+
+    fn try_from(range: PriorityRangeConfig) -> Result<Self, Self::Error> {
+        match range {
+            PriorityRangeConfig::Single(PriorityConfig::Name(name)) if name.contains("..") => {
+                let (a, b) = name.split_at(name.find("..").expect("contains a range"));
+                Ok(Self::new(
+                    PriorityConfig::Name(a.into()).try_into()?,
+                    PriorityConfig::Name(b[2..].into()).try_into()?,
+                ))
+            }
+            PriorityRangeConfig::Single(priority) => Ok(Self::new(0, priority.try_into()?)),
+            PriorityRangeConfig::Range(a, b) => Ok(Self::new(a.try_into()?, b.try_into()?)),
+        }
+    }
+}
+
+impl fmt::Display for PriorityRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}..{}",
+            PRIORITY_NAMES[self.most_severe as usize], PRIORITY_NAMES[self.least_severe as usize]
+        )
+    }
+}
+
+impl From<PriorityRange> for String {
+    fn from(range: PriorityRange) -> Self {
+        range.to_string()
+    }
+}
+
 impl JournaldConfig {
     fn merged_include_matches(&self) -> crate::Result<Matches> {
         let include_units = match (!self.units.is_empty(), !self.include_units.is_empty()) {
@@ -135,8 +241,7 @@ impl SourceConfig for JournaldConfig {
 
         let data_dir = cx
             .globals
-            // source are only global, name can be used for subdir
-            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+            .resolve_and_validate_data_dir(self.data_dir.as_ref())?;
 
         if let Some(unit) = self
             .include_units
@@ -154,8 +259,9 @@ impl SourceConfig for JournaldConfig {
             return Err(BuildError::DuplicatedMatches { field, value }.into());
         }
 
-        let mut checkpoint_path = data_dir;
-        checkpoint_path.push(CHECKPOINT_FILENAME);
+        // Sources are only global, so their name was used for the subdirectory.
+        let legacy_checkpoint_path = data_dir.join(cx.key.id()).join(LEGACY_CHECKPOINT_FILENAME);
+        let checkpoint_store = CheckpointStore::new(data_dir);
 
         let journalctl_path = self
             .journalctl_path
@@ -163,14 +269,20 @@ impl SourceConfig for JournaldConfig {
             .unwrap_or_else(|| JOURNALCTL.clone());
 
         let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
-        let current_boot_only = self.current_boot_only.unwrap_or(true);
+        let boot = match (self.boot_offset, self.current_boot_only) {
+            (Some(offset), _) => Some(offset),
+            (None, Some(true)) | (None, None) => Some(0),
+            (None, Some(false)) => None,
+        };
         let journal_dir = self.journal_directory.clone();
+        let include_priority = self.include_priority;
 
         let start: StartJournalctlFn = Box::new(move |cursor| {
             let mut command = create_command(
                 &journalctl_path,
                 journal_dir.as_ref(),
-                current_boot_only,
+                boot,
+                include_priority,
                 cursor,
             );
             start_journalctl(&mut command)
@@ -180,7 +292,9 @@ impl SourceConfig for JournaldConfig {
             JournaldSource {
                 include_matches,
                 exclude_matches,
-                checkpoint_path,
+                checkpoint_store,
+                component: cx.key.to_string(),
+                legacy_checkpoint_path,
                 batch_size,
                 remap_priority: self.remap_priority,
                 out: cx.out,
@@ -201,7 +315,9 @@ impl SourceConfig for JournaldConfig {
 struct JournaldSource {
     include_matches: Matches,
     exclude_matches: Matches,
-    checkpoint_path: PathBuf,
+    checkpoint_store: CheckpointStore,
+    component: String,
+    legacy_checkpoint_path: PathBuf,
     batch_size: usize,
     remap_priority: bool,
     out: Pipeline,
@@ -213,26 +329,21 @@ impl JournaldSource {
         shutdown: ShutdownSignal,
         start_journalctl: StartJournalctlFn,
     ) -> Result<(), ()> {
-        let mut checkpointer = Checkpointer::new(self.checkpoint_path.clone())
-            .await
-            .map_err(|error| {
-                error!(
-                    message = "Unable to open checkpoint file.",
-                    path = ?self.checkpoint_path,
-                    %error,
-                );
-            })?;
+        let mut checkpointer = Checkpointer::new(
+            &self.checkpoint_store,
+            &self.component,
+            &self.legacy_checkpoint_path,
+        )
+        .await
+        .map_err(|error| {
+            error!(
+                message = "Unable to load journald checkpoint.",
+                path = ?self.checkpoint_store.root(),
+                %error,
+            );
+        })?;
 
-        let mut cursor = match checkpointer.get().await {
-            Ok(cursor) => cursor,
-            Err(error) => {
-                error!(
-                    message = "Could not retrieve saved journald checkpoint.",
-                    %error
-                );
-                None
-            }
-        };
+        let mut cursor = checkpointer.get();
 
         let mut on_stop = None;
         let run = Box::pin(self.run(
@@ -291,6 +402,8 @@ impl JournaldSource {
         checkpointer: &'a mut Checkpointer,
         cursor: &'a mut Option<String>,
     ) -> bool {
+        let mut checkpointed_at = Instant::now();
+
         loop {
             let mut saw_record = false;
 
@@ -344,8 +457,11 @@ impl JournaldSource {
                 }
             }
 
-            if saw_record {
+            // Checkpointing syncs the checkpoint file to disk, which is too
+            // slow to do for every batch.
+            if saw_record && checkpointed_at.elapsed() >= CHECKPOINT_INTERVAL {
                 Self::save_checkpoint(checkpointer, &*cursor).await;
+                checkpointed_at = Instant::now();
             }
         }
     }
@@ -356,7 +472,7 @@ impl JournaldSource {
                 error!(
                     message = "Could not set journald checkpoint.",
                     %error,
-                    filename = ?checkpointer.filename,
+                    filename = ?checkpointer.path,
                 );
             }
         }
@@ -402,10 +518,13 @@ fn start_journalctl(
     Ok((stream, stop))
 }
 
+/// Creates the journalctl command, reading only the entries of the boot at the
+/// given offset from the current one, if any, and of the given priorities.
 fn create_command(
     path: &Path,
     journal_dir: Option<&PathBuf>,
-    current_boot_only: bool,
+    boot: Option<i32>,
+    priority: Option<PriorityRange>,
     cursor: &Option<String>,
 ) -> Command {
     let mut command = Command::new(path);
@@ -419,8 +538,18 @@ fn create_command(
         command.arg(format!("--directory={}", dir.display()));
     }
 
-    if current_boot_only {
-        command.arg("--boot");
+    match boot {
+        Some(0) => {
+            command.arg("--boot");
+        }
+        Some(offset) => {
+            command.arg(format!("--boot={}", offset));
+        }
+        None => {}
+    }
+
+    if let Some(priority) = priority {
+        command.arg(format!("--priority={}", priority));
     }
 
     if let Some(cursor) = cursor {
@@ -562,43 +691,61 @@ fn find_duplicate_match(a_matches: &Matches, b_matches: &Matches) -> Option<(Str
 }
 
 struct Checkpointer {
-    file: File,
-    filename: PathBuf,
+    checkpoints: Arc<Mutex<Checkpoints>>,
+    path: PathBuf,
 }
 
 impl Checkpointer {
-    async fn new(filename: PathBuf) -> Result<Self, io::Error> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(&filename)
-            .await?;
-        Ok(Checkpointer { file, filename })
+    /// Loads the component's checkpoints from the store, migrating the cursor
+    /// from the legacy checkpoint file if the store doesn't have one yet.
+    async fn new(
+        store: &CheckpointStore,
+        component: &str,
+        legacy_path: &Path,
+    ) -> Result<Self, io::Error> {
+        let mut checkpoints = store.namespace(component)?;
+
+        if checkpoints.get(CHECKPOINT_KEY).is_none() {
+            if let Some(cursor) = read_legacy_checkpoint(legacy_path).await? {
+                checkpoints.set(CHECKPOINT_KEY, cursor)?;
+                tokio::fs::remove_file(legacy_path).await?;
+            }
+        }
+
+        Ok(Checkpointer {
+            path: checkpoints.path().to_path_buf(),
+            checkpoints: Arc::new(Mutex::new(checkpoints)),
+        })
     }
 
     async fn set(&mut self, token: &str) -> Result<(), io::Error> {
-        self.file.seek(SeekFrom::Start(0)).await?;
-        self.file
-            .write_all(format!("{}\n", token).as_bytes())
-            .await?;
-        Ok(())
+        let checkpoints = Arc::clone(&self.checkpoints);
+        let token = token.to_owned();
+        tokio::task::spawn_blocking(move || {
+            checkpoints
+                .lock()
+                .expect("checkpoints mutex poisoned")
+                .set(CHECKPOINT_KEY, token)
+        })
+        .await?
     }
 
-    async fn get(&mut self) -> Result<Option<String>, io::Error> {
-        let mut buf = Vec::<u8>::new();
-        self.file.seek(SeekFrom::Start(0)).await?;
-        self.file.read_to_end(&mut buf).await?;
-        match buf.len() {
-            0 => Ok(None),
-            _ => {
-                let text = String::from_utf8_lossy(&buf);
-                match text.find('\n') {
-                    Some(nl) => Ok(Some(String::from(&text[..nl]))),
-                    None => Ok(None), // Maybe return an error?
-                }
-            }
-        }
+    fn get(&self) -> Option<String> {
+        self.checkpoints
+            .lock()
+            .expect("checkpoints mutex poisoned")
+            .get(CHECKPOINT_KEY)
+            .map(Into::into)
+    }
+}
+
+async fn read_legacy_checkpoint(path: &Path) -> Result<Option<String>, io::Error> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(text) => Ok(text
+            .find('\n')
+            .map(|newline| String::from(&text[..newline]))),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
     }
 }
 
@@ -606,7 +753,7 @@ impl Checkpointer {
 mod checkpointer_tests {
     use super::*;
     use tempfile::tempdir;
-    use tokio::fs::read_to_string;
+    use tokio::fs::write;
 
     #[test]
     fn generate_config() {
@@ -616,33 +763,43 @@ mod checkpointer_tests {
     #[tokio::test]
     async fn journald_checkpointer_works() {
         let tempdir = tempdir().unwrap();
-        let mut filename = tempdir.path().to_path_buf();
-        filename.push(CHECKPOINT_FILENAME);
-        let mut checkpointer = Checkpointer::new(filename.clone())
+        let store = CheckpointStore::new(tempdir.path());
+        let legacy_path = tempdir.path().join(LEGACY_CHECKPOINT_FILENAME);
+        let mut checkpointer = Checkpointer::new(&store, "journald", &legacy_path)
             .await
             .expect("Creating checkpointer failed!");
 
-        assert!(checkpointer.get().await.unwrap().is_none());
+        assert!(checkpointer.get().is_none());
 
         checkpointer
             .set("first test")
             .await
             .expect("Setting checkpoint failed");
-        assert_eq!(checkpointer.get().await.unwrap().unwrap(), "first test");
-        let contents = read_to_string(filename.clone())
-            .await
-            .unwrap_or_else(|_| panic!("Failed to read: {:?}", filename));
-        assert!(contents.starts_with("first test\n"));
+        assert_eq!(checkpointer.get().unwrap(), "first test");
 
         checkpointer
             .set("second")
             .await
             .expect("Setting checkpoint failed");
-        assert_eq!(checkpointer.get().await.unwrap().unwrap(), "second");
-        let contents = read_to_string(filename.clone())
+        assert_eq!(checkpointer.get().unwrap(), "second");
+
+        let checkpoints = store.namespace("journald").unwrap();
+        assert_eq!(checkpoints.get(CHECKPOINT_KEY), Some("second"));
+    }
+
+    #[tokio::test]
+    async fn journald_checkpointer_migrates_legacy_file() {
+        let tempdir = tempdir().unwrap();
+        let store = CheckpointStore::new(tempdir.path());
+        let legacy_path = tempdir.path().join(LEGACY_CHECKPOINT_FILENAME);
+        write(&legacy_path, "legacy cursor\n").await.unwrap();
+
+        let checkpointer = Checkpointer::new(&store, "journald", &legacy_path)
             .await
-            .unwrap_or_else(|_| panic!("Failed to read: {:?}", filename));
-        assert!(contents.starts_with("second\n"));
+            .expect("Creating checkpointer failed!");
+
+        assert_eq!(checkpointer.get().unwrap(), "legacy cursor");
+        assert!(!legacy_path.exists());
     }
 }
 
@@ -732,12 +889,13 @@ mod tests {
         let (trigger, shutdown, _) = ShutdownSignal::new_wired();
 
         let tempdir = tempdir().unwrap();
-        let mut checkpoint_path = tempdir.path().to_path_buf();
-        checkpoint_path.push(CHECKPOINT_FILENAME);
+        let checkpoint_store = CheckpointStore::new(tempdir.path());
+        let legacy_checkpoint_path = tempdir.path().join(LEGACY_CHECKPOINT_FILENAME);
 
-        let mut checkpointer = Checkpointer::new(checkpoint_path.clone())
-            .await
-            .expect("Creating checkpointer failed!");
+        let mut checkpointer =
+            Checkpointer::new(&checkpoint_store, "journald", &legacy_checkpoint_path)
+                .await
+                .expect("Creating checkpointer failed!");
 
         if let Some(cursor) = cursor {
             checkpointer
@@ -749,7 +907,9 @@ mod tests {
         let source = JournaldSource {
             include_matches,
             exclude_matches,
-            checkpoint_path,
+            checkpoint_store,
+            component: "journald".to_owned(),
+            legacy_checkpoint_path,
             batch_size: DEFAULT_BATCH_SIZE,
             remap_priority: true,
             out: tx,
@@ -1004,24 +1164,51 @@ mod tests {
         let path = PathBuf::from("jornalctl");
 
         let journal_dir = None;
-        let current_boot_only = false;
+        let boot = None;
         let cursor = None;
 
-        let command = create_command(&path, journal_dir, current_boot_only, &cursor);
+        let command = create_command(&path, journal_dir, boot, None, &cursor);
         let cmd_line = format!("{:?}", command);
         assert!(!cmd_line.contains("--directory="));
         assert!(!cmd_line.contains("--boot"));
+        assert!(!cmd_line.contains("--priority"));
         assert!(cmd_line.contains("--since=2000-01-01"));
 
         let journal_dir = Some(PathBuf::from("/tmp/journal-dir"));
-        let current_boot_only = true;
+        let boot = Some(0);
         let cursor = Some(String::from("2021-01-01"));
 
-        let command = create_command(&path, journal_dir.as_ref(), current_boot_only, &cursor);
+        let command = create_command(&path, journal_dir.as_ref(), boot, None, &cursor);
         let cmd_line = format!("{:?}", command);
         assert!(cmd_line.contains("--directory=/tmp/journal-dir"));
-        assert!(cmd_line.contains("--boot"));
+        assert!(cmd_line.contains("\"--boot\""));
         assert!(cmd_line.contains("--after-cursor="));
+
+        let priority = Some(PriorityRange::new(0, 4));
+        let command = create_command(&path, None, Some(-1), priority, &None);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--boot=-1"));
+        assert!(cmd_line.contains("--priority=emerg..warning"));
+    }
+
+    #[test]
+    fn parses_priority_ranges() {
+        let parse = |value: &str| {
+            toml::from_str::<JournaldConfig>(&format!("include_priority = {}", value))
+                .map(|config| config.include_priority.unwrap())
+        };
+
+        assert_eq!(parse(r#""warning""#).unwrap(), PriorityRange::new(0, 4));
+        assert_eq!(parse("3").unwrap(), PriorityRange::new(0, 3));
+        assert_eq!(parse(r#""ERR..debug""#).unwrap(), PriorityRange::new(3, 7));
+        assert_eq!(parse(r#""1..5""#).unwrap(), PriorityRange::new(1, 5));
+        assert_eq!(
+            parse(r#"["notice", "crit"]"#).unwrap(),
+            PriorityRange::new(2, 5)
+        );
+        assert_eq!(parse("[0, 4]").unwrap(), PriorityRange::new(0, 4));
+        assert!(parse(r#""verbose""#).is_err());
+        assert!(parse("8").is_err());
     }
 
     fn message(event: &Event) -> Value {
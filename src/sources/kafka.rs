@@ -1,29 +1,32 @@
 use super::util::finalizer::OrderedFinalizer;
 use crate::{
-    codecs::{self, DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{self, DecodingConfig, FramingConfig, ParserSettings},
     config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
-    event::{BatchNotifier, Event, Value},
-    internal_events::{KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed},
+    event::{BatchNotifier, Event, LogEvent, Value},
+    internal_events::{
+        KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed, SchemaRegistryDecodeFailed,
+    },
     kafka::{KafkaAuthConfig, KafkaStatisticsContext},
+    schema_registry::{SchemaRegistryClient, SchemaRegistryConfig},
     serde::{default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     sources::util::TcpError,
     Pipeline,
 };
 use bytes::Bytes;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use futures::{FutureExt, SinkExt, StreamExt, TryStreamExt};
 use futures_util::future::ready;
 use rdkafka::{
     config::ClientConfig,
     consumer::{Consumer, StreamConsumer},
-    message::{BorrowedMessage, Headers, Message},
+    message::{BorrowedMessage, Headers, Message, Timestamp},
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::sync::Arc;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     io::Cursor,
 };
 use tokio_util::codec::FramedRead;
@@ -63,6 +66,13 @@ pub struct KafkaSourceConfig {
     offset_key: String,
     #[serde(default = "default_headers_key")]
     headers_key: String,
+    /// The headers captured into `headers_key`, all of them when unset.
+    include_headers: Option<Vec<String>>,
+    /// Headers captured into their own fields, keyed by header name.
+    #[serde(default)]
+    header_fields: HashMap<String, String>,
+    #[serde(default)]
+    timestamp_type: TimestampType,
     librdkafka_options: Option<HashMap<String, String>>,
     #[serde(flatten)]
     auth: KafkaAuthConfig,
@@ -71,7 +81,10 @@ pub struct KafkaSourceConfig {
     framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
     #[derivative(Default(value = "default_decoding()"))]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
+    /// Decode messages with the schema referenced by their embedded schema ID,
+    /// instead of using `framing` and `decoding`.
+    schema_registry: Option<SchemaRegistryConfig>,
 }
 
 const fn default_session_timeout_ms() -> u64 {
@@ -114,6 +127,105 @@ fn default_headers_key() -> String {
     "headers".into()
 }
 
+/// Which timestamp of Kafka messages populates the timestamp of events.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampType {
+    /// Whichever timestamp the message carries, as set by the
+    /// `message.timestamp.type` of its topic.
+    #[derivative(Default)]
+    Any,
+    /// The time the producer created the message.
+    CreateTime,
+    /// The time the broker appended the message to its log.
+    LogAppendTime,
+    /// The time the message was consumed.
+    Ingestion,
+}
+
+impl TimestampType {
+    /// Messages which don't carry the selected timestamp get the current
+    /// time instead.
+    fn timestamp(self, timestamp: Timestamp) -> DateTime<Utc> {
+        let millis = match (self, timestamp) {
+            (Self::Any, timestamp) => timestamp.to_millis(),
+            (Self::CreateTime, Timestamp::CreateTime(millis))
+            | (Self::LogAppendTime, Timestamp::LogAppendTime(millis)) => Some(millis),
+            _ => None,
+        };
+        millis
+            .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
+            .unwrap_or_else(Utc::now)
+    }
+}
+
+/// The fields the properties of messages are inserted into.
+#[derive(Clone, Debug)]
+struct MessageFields {
+    key_field: String,
+    topic_key: String,
+    partition_key: String,
+    offset_key: String,
+    headers_key: String,
+    include_headers: Option<HashSet<String>>,
+    header_fields: HashMap<String, String>,
+    timestamp_type: TimestampType,
+}
+
+impl MessageFields {
+    fn new(config: &KafkaSourceConfig) -> Self {
+        Self {
+            key_field: config.key_field.clone(),
+            topic_key: config.topic_key.clone(),
+            partition_key: config.partition_key.clone(),
+            offset_key: config.offset_key.clone(),
+            headers_key: config.headers_key.clone(),
+            include_headers: config
+                .include_headers
+                .as_ref()
+                .map(|headers| headers.iter().cloned().collect()),
+            header_fields: config.header_fields.clone(),
+            timestamp_type: config.timestamp_type,
+        }
+    }
+
+    /// Splits the headers of a message into those captured into
+    /// `headers_key`, if any is, and those captured into their own fields.
+    fn capture_headers(
+        &self,
+        headers: Option<&impl Headers>,
+    ) -> (Option<BTreeMap<String, Value>>, Vec<(&str, Value)>) {
+        let mut headers_map = self
+            .include_headers
+            .as_ref()
+            .map_or(true, |include| !include.is_empty())
+            .then(BTreeMap::new);
+        let mut fields = Vec::new();
+
+        if let Some(headers) = headers {
+            // Using index-based for loop because rdkafka's `Headers` trait
+            // does not provide Iterator-based API
+            for i in 0..headers.count() {
+                if let Some((name, value)) = headers.get(i) {
+                    if let Some(field) = self.header_fields.get(name) {
+                        fields.push((field.as_str(), Bytes::copy_from_slice(value).into()));
+                    }
+                    let included = self
+                        .include_headers
+                        .as_ref()
+                        .map_or(true, |include| include.contains(name));
+                    if let Some(headers_map) = headers_map.as_mut().filter(|_| included) {
+                        headers_map.insert(name.to_string(), Bytes::copy_from_slice(value).into());
+                    }
+                }
+            }
+        }
+
+        (headers_map, fields)
+    }
+}
+
 inventory::submit! {
     SourceDescription::new::<KafkaSourceConfig>("kafka")
 }
@@ -126,15 +238,17 @@ impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let consumer = create_consumer(self)?;
         let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+        let schema_registry = self
+            .schema_registry
+            .as_ref()
+            .map(|config| SchemaRegistryClient::new(config, &cx.proxy))
+            .transpose()?;
 
         Ok(Box::pin(kafka_source(
             consumer,
-            self.key_field.clone(),
-            self.topic_key.clone(),
-            self.partition_key.clone(),
-            self.offset_key.clone(),
-            self.headers_key.clone(),
+            MessageFields::new(self),
             decoder,
+            schema_registry,
             cx.shutdown,
             cx.out,
             cx.acknowledgements,
@@ -152,12 +266,9 @@ impl SourceConfig for KafkaSourceConfig {
 
 async fn kafka_source(
     consumer: StreamConsumer<KafkaStatisticsContext>,
-    key_field: String,
-    topic_key: String,
-    partition_key: String,
-    offset_key: String,
-    headers_key: String,
+    fields: MessageFields,
     decoder: codecs::Decoder,
+    schema_registry: Option<SchemaRegistryClient>,
     shutdown: ShutdownSignal,
     mut out: Pipeline,
     acknowledgements: bool,
@@ -185,71 +296,79 @@ async fn kafka_source(
                 };
 
                 // Extract timestamp from kafka message
-                let timestamp = msg
-                    .timestamp()
-                    .to_millis()
-                    .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
-                    .unwrap_or_else(Utc::now);
+                let timestamp = fields.timestamp_type.timestamp(msg.timestamp());
 
                 let msg_key = msg
                     .key()
                     .map(|key| Value::from(String::from_utf8_lossy(key).to_string()))
                     .unwrap_or(Value::Null);
 
-                let mut headers_map = BTreeMap::new();
-                if let Some(headers) = msg.headers() {
-                    // Using index-based for loop because rdkafka's `Headers` trait
-                    // does not provide Iterator-based API
-                    for i in 0..headers.count() {
-                        if let Some(header) = headers.get(i) {
-                            headers_map.insert(
-                                header.0.to_string(),
-                                Bytes::from(header.1.to_owned()).into(),
-                            );
-                        }
-                    }
-                }
+                let (headers_map, header_fields) = fields.capture_headers(msg.headers());
 
                 let msg_topic = Bytes::copy_from_slice(msg.topic().as_bytes());
                 let msg_partition = msg.partition();
                 let msg_offset = msg.offset();
 
-                let key_field = &key_field;
-                let topic_key = &topic_key;
-                let partition_key = &partition_key;
-                let offset_key = &offset_key;
-                let headers_key = &headers_key;
-
-                let payload = Cursor::new(Bytes::copy_from_slice(payload));
-
-                let mut stream = FramedRead::new(payload, decoder.clone())
-                    .map(|input| match input {
-                        Ok((mut events, _)) => {
-                            let mut event = events.pop().expect("event must exist");
-                            if let Event::Log(ref mut log) = event {
-                                log.try_insert(schema.source_type_key(), Bytes::from("kafka"));
-                                log.try_insert(schema.timestamp_key(), timestamp);
-                                log.try_insert(key_field, msg_key.clone());
-                                log.try_insert(topic_key, Value::from(msg_topic.clone()));
-                                log.try_insert(partition_key, Value::from(msg_partition));
-                                log.try_insert(offset_key, Value::from(msg_offset));
-                                log.try_insert(headers_key, Value::from(headers_map.clone()));
-                            }
-
-                            Some(Some(Ok(event)))
+                let fields = &fields;
+
+                let enrich = |mut event: Event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.try_insert(schema.source_type_key(), Bytes::from("kafka"));
+                        log.try_insert(schema.timestamp_key(), timestamp);
+                        log.try_insert(&fields.key_field, msg_key.clone());
+                        log.try_insert(&fields.topic_key, Value::from(msg_topic.clone()));
+                        log.try_insert(&fields.partition_key, Value::from(msg_partition));
+                        log.try_insert(&fields.offset_key, Value::from(msg_offset));
+                        if let Some(headers_map) = &headers_map {
+                            log.try_insert(&fields.headers_key, Value::from(headers_map.clone()));
+                        }
+                        for (field, value) in &header_fields {
+                            log.try_insert(*field, value.clone());
                         }
-                        Err(e) => {
-                            // Error is logged by `crate::codecs::Decoder`, no further handling
-                            // is needed here.
-                            if !e.can_continue() {
-                                Some(None)
-                            } else {
+                    }
+                    event
+                };
+
+                let mut stream = match &schema_registry {
+                    Some(registry) => {
+                        let event = match registry.decode(payload).await {
+                            Ok(Value::Map(fields)) => Some(Event::from(LogEvent::from(fields))),
+                            Ok(value) => {
+                                let mut log = LogEvent::default();
+                                log.insert(schema.message_key(), value);
+                                Some(Event::from(log))
+                            }
+                            Err(error) => {
+                                emit!(&SchemaRegistryDecodeFailed { error: &error });
                                 None
                             }
-                        }
-                    })
-                    .take_while(|x| ready(x.is_some()))
-                    .filter_map(|x| ready(x.expect("should have inner value")));
+                        };
+                        futures::stream::iter(event.map(|event| Ok(enrich(event)))).boxed()
+                    }
+                    None => {
+                        let payload = Cursor::new(Bytes::copy_from_slice(payload));
+
+                        FramedRead::new(payload, decoder.clone())
+                            .map(|input| match input {
+                                Ok((mut events, _)) => {
+                                    let event = events.pop().expect("event must exist");
+                                    Some(Some(Ok(enrich(event))))
+                                }
+                                Err(e) => {
+                                    // Error is logged by `crate::codecs::Decoder`, no further
+                                    // handling is needed here.
+                                    if !e.can_continue() {
+                                        Some(None)
+                                    } else {
+                                        None
+                                    }
+                                }
+                            })
+                            .take_while(|x| ready(x.is_some()))
+                            .filter_map(|x| ready(x.expect("should have inner value")))
+                            .boxed()
+                    }
+                };
 
                 match &mut finalizer {
                     Some(finalizer) => {
@@ -349,6 +468,7 @@ fn create_consumer(
 #[cfg(test)]
 mod test {
     use super::*;
+    use rdkafka::message::OwnedHeaders;
 
     pub(super) const BOOTSTRAP_SERVER: &str = "localhost:9091";
 
@@ -390,6 +510,54 @@ mod test {
         };
         assert!(create_consumer(&config).is_err());
     }
+
+    #[test]
+    fn selects_timestamp_type() {
+        let created = Utc.timestamp_millis(1_000);
+        let message = Timestamp::CreateTime(created.timestamp_millis());
+        assert_eq!(TimestampType::Any.timestamp(message), created);
+        assert_eq!(TimestampType::CreateTime.timestamp(message), created);
+
+        // Falls back to the current time.
+        let now = Utc::now();
+        assert!(TimestampType::LogAppendTime.timestamp(message) >= now);
+        assert!(TimestampType::Ingestion.timestamp(message) >= now);
+        assert!(TimestampType::Any.timestamp(Timestamp::NotAvailable) >= now);
+    }
+
+    #[test]
+    fn captures_headers() {
+        let headers = OwnedHeaders::new()
+            .add("trace-id", "abc")
+            .add("tenant", "acme")
+            .add("content-type", "json");
+
+        let config = make_config("topic", "group");
+        let (headers_map, fields) = MessageFields::new(&config).capture_headers(Some(&headers));
+        assert_eq!(headers_map.unwrap().len(), 3);
+        assert!(fields.is_empty());
+
+        let config = KafkaSourceConfig {
+            include_headers: Some(vec!["trace-id".into(), "missing".into()]),
+            header_fields: vec![("tenant".into(), "routing.tenant".into())]
+                .into_iter()
+                .collect(),
+            ..make_config("topic", "group")
+        };
+        let message_fields = MessageFields::new(&config);
+        let (headers_map, fields) = message_fields.capture_headers(Some(&headers));
+        let mut expected = BTreeMap::new();
+        expected.insert("trace-id".to_string(), Value::from("abc"));
+        assert_eq!(headers_map, Some(expected));
+        assert_eq!(fields, vec![("routing.tenant", Value::from("acme"))]);
+
+        let config = KafkaSourceConfig {
+            include_headers: Some(Vec::new()),
+            ..make_config("topic", "group")
+        };
+        let (headers_map, _) = MessageFields::new(&config).capture_headers(Some(&headers));
+        assert_eq!(headers_map, None);
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -483,12 +651,9 @@ mod integration_test {
         let (tx, rx) = Pipeline::new_test_finalize(EventStatus::Delivered);
         tokio::spawn(kafka_source(
             create_consumer(&config).unwrap(),
-            config.key_field,
-            config.topic_key,
-            config.partition_key,
-            config.offset_key,
-            config.headers_key,
+            MessageFields::new(&config),
             codecs::Decoder::default(),
+            None,
             shutdown,
             tx,
             acknowledgements,
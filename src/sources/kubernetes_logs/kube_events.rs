@@ -0,0 +1,440 @@
+//! Turns Kubernetes `Event` resources into log events.
+
+#![deny(missing_docs)]
+
+use super::COMPONENT_ID;
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent},
+    internal_events::{KubernetesLogsKubeEventReceived, KubernetesLogsKubeEventSendFailed},
+    kubernetes as k8s, Pipeline,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use evmap::ReadHandle;
+use futures::{future::BoxFuture, SinkExt};
+use k8s_openapi::api::core::v1::{Event as KubeEvent, ObjectReference, Pod};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The name of the source output the Kubernetes events are sent to.
+pub const OUTPUT: &str = "kube_events";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct FieldsSpec {
+    pub event_name: String,
+    pub event_namespace: String,
+    pub event_uid: String,
+    pub event_type: String,
+    pub event_reason: String,
+    pub event_count: String,
+    pub event_reporting_component: String,
+    pub event_reporting_host: String,
+    pub object_kind: String,
+    pub object_name: String,
+    pub object_namespace: String,
+    pub object_uid: String,
+    pub object_api_version: String,
+    pub object_field_path: String,
+}
+
+impl Default for FieldsSpec {
+    fn default() -> Self {
+        Self {
+            event_name: "kubernetes.event.name".to_owned(),
+            event_namespace: "kubernetes.event.namespace".to_owned(),
+            event_uid: "kubernetes.event.uid".to_owned(),
+            event_type: "kubernetes.event.type".to_owned(),
+            event_reason: "kubernetes.event.reason".to_owned(),
+            event_count: "kubernetes.event.count".to_owned(),
+            event_reporting_component: "kubernetes.event.reporting_component".to_owned(),
+            event_reporting_host: "kubernetes.event.reporting_host".to_owned(),
+            object_kind: "kubernetes.involved_object.kind".to_owned(),
+            object_name: "kubernetes.involved_object.name".to_owned(),
+            object_namespace: "kubernetes.involved_object.namespace".to_owned(),
+            object_uid: "kubernetes.involved_object.uid".to_owned(),
+            object_api_version: "kubernetes.involved_object.api_version".to_owned(),
+            object_field_path: "kubernetes.involved_object.field_path".to_owned(),
+        }
+    }
+}
+
+/// A [`k8s::state::Write`] implementation that, instead of maintaining a
+/// local state, forwards every added or updated Kubernetes `Event` as a log
+/// event.
+///
+/// Only the events that relate to the objects local to the current node are
+/// forwarded - the `Pod`s currently known to the pod state, and the `Node`
+/// itself. This keeps a `DaemonSet` deployment from emitting every cluster
+/// event once per node.
+///
+/// The watch relists every `Event` after it desyncs, and updates an `Event`
+/// on changes other than repeated occurrences too, so the last seen `count`
+/// and `resourceVersion` of each forwarded `Event` are kept to only forward
+/// new `Event`s and the ones with the `count` bumped.
+pub struct Writer {
+    out: Pipeline,
+    pods_state_reader: ReadHandle<String, k8s::state::evmap::Value<Pod>>,
+    self_node_name: String,
+    fields_spec: FieldsSpec,
+    pod_fields_spec: super::pod_metadata_annotator::FieldsSpec,
+    seen: HashMap<String, Occurrence>,
+}
+
+/// The last seen occurrence of an `Event`.
+#[derive(Debug, PartialEq)]
+struct Occurrence {
+    count: Option<i32>,
+    resource_version: Option<String>,
+}
+
+impl Occurrence {
+    fn of(kube_event: &KubeEvent) -> Self {
+        Self {
+            count: kube_event.count,
+            resource_version: kube_event.metadata.resource_version.clone(),
+        }
+    }
+
+    /// Whether this occurrence is a repetition of the `seen` one. The
+    /// `resourceVersion` is only compared for `Event`s that don't report the
+    /// `count`.
+    fn follows(&self, seen: &Self) -> bool {
+        match (self.count, seen.count) {
+            (Some(count), Some(seen_count)) => count > seen_count,
+            _ => self.resource_version != seen.resource_version,
+        }
+    }
+}
+
+impl Writer {
+    /// Create a new [`Writer`].
+    pub fn new(
+        out: Pipeline,
+        pods_state_reader: ReadHandle<String, k8s::state::evmap::Value<Pod>>,
+        self_node_name: String,
+        fields_spec: FieldsSpec,
+        pod_fields_spec: super::pod_metadata_annotator::FieldsSpec,
+    ) -> Self {
+        Self {
+            out,
+            pods_state_reader,
+            self_node_name,
+            fields_spec,
+            pod_fields_spec,
+            seen: HashMap::new(),
+        }
+    }
+
+    fn is_local(&self, object: &ObjectReference) -> bool {
+        match object.kind.as_deref() {
+            Some("Pod") => object
+                .uid
+                .as_ref()
+                .map_or(false, |uid| self.pods_state_reader.contains_key(uid)),
+            Some("Node") => object.name.as_deref() == Some(self.self_node_name.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Records the occurrence of the `Event`, returning whether it wasn't
+    /// seen before.
+    fn is_new_occurrence(&mut self, kube_event: &KubeEvent) -> bool {
+        let uid = match kube_event.metadata.uid.as_ref() {
+            Some(uid) => uid,
+            None => return true,
+        };
+        let occurrence = Occurrence::of(kube_event);
+        match self.seen.get(uid) {
+            Some(seen) if !occurrence.follows(seen) => false,
+            _ => {
+                self.seen.insert(uid.clone(), occurrence);
+                true
+            }
+        }
+    }
+
+    async fn forward(&mut self, kube_event: KubeEvent) {
+        if !self.is_local(&kube_event.involved_object) || !self.is_new_occurrence(&kube_event) {
+            return;
+        }
+
+        let event = create_event(&kube_event, &self.fields_spec, &self.pod_fields_spec);
+
+        emit!(&KubernetesLogsKubeEventReceived {
+            reason: kube_event.reason.as_deref(),
+        });
+
+        if let Err(error) = self.out.send(event).await {
+            emit!(&KubernetesLogsKubeEventSendFailed { error });
+        }
+    }
+}
+
+#[async_trait]
+impl k8s::state::Write for Writer {
+    type Item = KubeEvent;
+
+    async fn add(&mut self, item: Self::Item) {
+        self.forward(item).await;
+    }
+
+    async fn update(&mut self, item: Self::Item) {
+        // Repeated occurrences of the same event are reported as updates with
+        // the `count` bumped, so they're forwarded too.
+        self.forward(item).await;
+    }
+
+    async fn delete(&mut self, item: Self::Item) {
+        // Events expiring from the API server is not something to report, but
+        // there's no need to remember them anymore.
+        if let Some(uid) = item.metadata.uid {
+            self.seen.remove(&uid);
+        }
+    }
+
+    async fn resync(&mut self) {
+        // The seen occurrences are kept, as the relist adds the same `Event`s
+        // again.
+    }
+}
+
+#[async_trait]
+impl k8s::state::MaintainedWrite for Writer {
+    fn maintenance_request(&mut self) -> Option<BoxFuture<'_, ()>> {
+        None
+    }
+
+    async fn perform_maintenance(&mut self) {}
+}
+
+/// Build a log event out of a Kubernetes `Event`.
+fn create_event(
+    kube_event: &KubeEvent,
+    fields_spec: &FieldsSpec,
+    pod_fields_spec: &super::pod_metadata_annotator::FieldsSpec,
+) -> Event {
+    let mut log = LogEvent::default();
+
+    log.insert(
+        log_schema().message_key(),
+        kube_event.message.clone().unwrap_or_default(),
+    );
+    log.insert(log_schema().source_type_key(), COMPONENT_ID.to_owned());
+
+    let timestamp = kube_event
+        .last_timestamp
+        .as_ref()
+        .map(|time| time.0)
+        .or_else(|| kube_event.event_time.as_ref().map(|time| time.0))
+        .or_else(|| kube_event.first_timestamp.as_ref().map(|time| time.0))
+        .unwrap_or_else(Utc::now);
+    log.insert(log_schema().timestamp_key(), timestamp);
+
+    let metadata = &kube_event.metadata;
+    let reporting_component = kube_event.reporting_component.as_ref().or_else(|| {
+        kube_event
+            .source
+            .as_ref()
+            .and_then(|source| source.component.as_ref())
+    });
+    let reporting_host = kube_event
+        .source
+        .as_ref()
+        .and_then(|source| source.host.as_ref());
+    let object = &kube_event.involved_object;
+
+    for (key, val) in [
+        (&fields_spec.event_name, metadata.name.as_ref()),
+        (&fields_spec.event_namespace, metadata.namespace.as_ref()),
+        (&fields_spec.event_uid, metadata.uid.as_ref()),
+        (&fields_spec.event_type, kube_event.type_.as_ref()),
+        (&fields_spec.event_reason, kube_event.reason.as_ref()),
+        (&fields_spec.event_reporting_component, reporting_component),
+        (&fields_spec.event_reporting_host, reporting_host),
+        (&fields_spec.object_kind, object.kind.as_ref()),
+        (&fields_spec.object_name, object.name.as_ref()),
+        (&fields_spec.object_namespace, object.namespace.as_ref()),
+        (&fields_spec.object_uid, object.uid.as_ref()),
+        (&fields_spec.object_api_version, object.api_version.as_ref()),
+        (&fields_spec.object_field_path, object.field_path.as_ref()),
+    ]
+    .iter()
+    {
+        if let Some(val) = val {
+            log.insert(key, (*val).to_owned());
+        }
+    }
+
+    if let Some(count) = kube_event.count {
+        log.insert(&fields_spec.event_count, count);
+    }
+
+    // Use the same fields the pod logs are annotated with, so that the events
+    // can be correlated with the logs of the pod they are about.
+    if object.kind.as_deref() == Some("Pod") {
+        for (key, val) in [
+            (&pod_fields_spec.pod_name, &object.name),
+            (&pod_fields_spec.pod_namespace, &object.namespace),
+            (&pod_fields_spec.pod_uid, &object.uid),
+        ]
+        .iter()
+        {
+            // The fields left out of the pod annotation fields are empty.
+            if let Some(val) = val.as_ref().filter(|_| !key.is_empty()) {
+                log.insert(key, val.to_owned());
+            }
+        }
+    }
+
+    log.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kubernetes::state::Write, test_util::collect_ready};
+    use chrono::TimeZone;
+    use k8s_openapi::{
+        api::core::v1::EventSource,
+        apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time},
+    };
+
+    #[test]
+    fn test_create_event() {
+        let kube_event = KubeEvent {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name.16a5b8d0".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some("event-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            involved_object: ObjectReference {
+                kind: Some("Pod".to_owned()),
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                api_version: Some("v1".to_owned()),
+                ..ObjectReference::default()
+            },
+            type_: Some("Warning".to_owned()),
+            reason: Some("BackOff".to_owned()),
+            message: Some("Back-off restarting failed container".to_owned()),
+            count: Some(3),
+            last_timestamp: Some(Time(Utc.ymd(2021, 10, 1).and_hms(12, 0, 0))),
+            source: Some(EventSource {
+                component: Some("kubelet".to_owned()),
+                host: Some("node0".to_owned()),
+            }),
+            ..KubeEvent::default()
+        };
+
+        let event = create_event(
+            &kube_event,
+            &FieldsSpec::default(),
+            &super::super::pod_metadata_annotator::FieldsSpec::default(),
+        );
+        let log = event.as_log();
+
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Back-off restarting failed container".into()
+        );
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2021, 10, 1).and_hms(12, 0, 0).into()
+        );
+        assert_eq!(log["kubernetes.event.type"], "Warning".into());
+        assert_eq!(log["kubernetes.event.reason"], "BackOff".into());
+        assert_eq!(log["kubernetes.event.count"], 3.into());
+        assert_eq!(
+            log["kubernetes.event.reporting_component"],
+            "kubelet".into()
+        );
+        assert_eq!(log["kubernetes.event.reporting_host"], "node0".into());
+        assert_eq!(log["kubernetes.involved_object.kind"], "Pod".into());
+        assert_eq!(log["kubernetes.pod_name"], "sandbox0-name".into());
+        assert_eq!(log["kubernetes.pod_namespace"], "sandbox0-ns".into());
+        assert_eq!(log["kubernetes.pod_uid"], "sandbox0-uid".into());
+    }
+
+    #[test]
+    fn test_create_event_non_pod_object() {
+        let kube_event = KubeEvent {
+            involved_object: ObjectReference {
+                kind: Some("Node".to_owned()),
+                name: Some("node0".to_owned()),
+                ..ObjectReference::default()
+            },
+            type_: Some("Normal".to_owned()),
+            reason: Some("NodeReady".to_owned()),
+            ..KubeEvent::default()
+        };
+
+        let event = create_event(
+            &kube_event,
+            &FieldsSpec::default(),
+            &super::super::pod_metadata_annotator::FieldsSpec::default(),
+        );
+        let log = event.as_log();
+
+        assert_eq!(log["kubernetes.involved_object.name"], "node0".into());
+        assert!(!log.contains("kubernetes.pod_name"));
+        assert!(log.contains(log_schema().timestamp_key()));
+    }
+
+    fn node_event(count: i32, resource_version: &str) -> KubeEvent {
+        KubeEvent {
+            metadata: ObjectMeta {
+                name: Some("node0.16a5b8d0".to_owned()),
+                uid: Some("event-uid".to_owned()),
+                resource_version: Some(resource_version.to_owned()),
+                ..ObjectMeta::default()
+            },
+            involved_object: ObjectReference {
+                kind: Some("Node".to_owned()),
+                name: Some("node0".to_owned()),
+                ..ObjectReference::default()
+            },
+            reason: Some("NodeNotReady".to_owned()),
+            count: Some(count),
+            ..KubeEvent::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relisted_event_is_forwarded_once() {
+        let (out, rx) = Pipeline::new_test();
+        let (pods_state_reader, _pods_state_writer) = evmap::new();
+        let mut writer = Writer::new(
+            out,
+            pods_state_reader,
+            "node0".to_owned(),
+            FieldsSpec::default(),
+            super::super::pod_metadata_annotator::FieldsSpec::default(),
+        );
+
+        writer.add(node_event(1, "100")).await;
+        // Relisted after a desync.
+        writer.resync().await;
+        writer.add(node_event(1, "100")).await;
+        // Updated without occurring again.
+        writer.update(node_event(1, "101")).await;
+        // Occurred again.
+        writer.update(node_event(2, "102")).await;
+        writer.resync().await;
+        writer.add(node_event(2, "102")).await;
+        // Expired and created again.
+        writer.delete(node_event(2, "102")).await;
+        writer.add(node_event(1, "200")).await;
+
+        drop(writer);
+        let counts = collect_ready(rx)
+            .await
+            .into_iter()
+            .map(|event| event.as_log()["kubernetes.event.count"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1.into(), 2.into(), 1.into()]);
+    }
+}
@@ -20,6 +20,7 @@ use crate::{
     shutdown::ShutdownSignal,
     sources,
     transforms::{FunctionTransform, TaskTransform},
+    Pipeline,
 };
 use bytes::Bytes;
 use chrono::Utc;
@@ -27,20 +28,26 @@ use file_source::{
     Checkpointer, FileServer, FileServerShutdown, FingerprintStrategy, Fingerprinter, Line,
     ReadFrom,
 };
-use k8s_openapi::api::core::v1::{Namespace, Pod};
+use k8s_openapi::api::{
+    apps::v1::ReplicaSet,
+    core::v1::{Event as KubeEvent, Namespace, Pod},
+};
 use serde::{Deserialize, Serialize};
 use shared::TimeZone;
 use std::convert::TryInto;
 use std::path::PathBuf;
 use std::time::Duration;
+use vector_core::checkpoint::CheckpointStore;
 
 mod k8s_paths_provider;
+mod kube_events;
 mod lifecycle;
 mod namespace_metadata_annotator;
 mod parser;
 mod partial_events_merger;
 mod path_helpers;
 mod pod_metadata_annotator;
+mod pod_owners;
 mod transform_utils;
 mod util;
 
@@ -49,6 +56,7 @@ use k8s_paths_provider::K8sPathsProvider;
 use lifecycle::Lifecycle;
 use namespace_metadata_annotator::NamespaceMetadataAnnotator;
 use pod_metadata_annotator::PodMetadataAnnotator;
+use pod_owners::DeploymentCache;
 
 /// The key we use for `file` field.
 const FILE_KEY: &str = "file";
@@ -83,6 +91,14 @@ pub struct Config {
     #[serde(alias = "annotation_fields")]
     pod_annotation_fields: pod_metadata_annotator::FieldsSpec,
 
+    /// The names of the Pod metadata annotation fields to add to the events,
+    /// all of them if not set.
+    pod_annotation_fields_allowlist: Option<Vec<String>>,
+
+    /// The maximum number of `ReplicaSet`s whose owning `Deployment` is kept
+    /// to annotate the events with.
+    owner_cache_capacity: usize,
+
     /// Specifies the field names for Namespace metadata annotation.
     namespace_annotation_fields: namespace_metadata_annotator::FieldsSpec,
 
@@ -122,6 +138,14 @@ pub struct Config {
     /// Optional path to a kubeconfig file readable by Vector. If not set,
     /// Vector will try to connect to Kubernetes using in-cluster configuration.
     kube_config_file: Option<PathBuf>,
+
+    /// Watch the Kubernetes Events API and emit the events related to the
+    /// `Pod`s and the `Node` local to this Vector instance to the
+    /// `kube_events` output.
+    kube_events: bool,
+
+    /// Specifies the field names for Kubernetes event annotation.
+    kube_event_fields: kube_events::FieldsSpec,
 }
 
 inventory::submit! {
@@ -148,6 +172,8 @@ impl Default for Config {
             auto_partial_merge: true,
             data_dir: None,
             pod_annotation_fields: pod_metadata_annotator::FieldsSpec::default(),
+            pod_annotation_fields_allowlist: None,
+            owner_cache_capacity: pod_owners::default_owner_cache_capacity(),
             namespace_annotation_fields: namespace_metadata_annotator::FieldsSpec::default(),
             exclude_paths_glob_patterns: default_path_exclusion(),
             max_read_bytes: default_max_read_bytes(),
@@ -157,6 +183,8 @@ impl Default for Config {
             ingestion_timestamp_field: None,
             timezone: None,
             kube_config_file: None,
+            kube_events: false,
+            kube_event_fields: kube_events::FieldsSpec::default(),
         }
     }
 }
@@ -166,19 +194,32 @@ const COMPONENT_ID: &str = "kubernetes_logs";
 #[async_trait::async_trait]
 #[typetag::serde(name = "kubernetes_logs")]
 impl SourceConfig for Config {
-    async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
+    async fn build(&self, mut cx: SourceContext) -> crate::Result<sources::Source> {
+        let kube_events_out = cx.take_named_output(kube_events::OUTPUT);
         let source = Source::new(self, &cx.globals, &cx.key, &cx.proxy)?;
-        Ok(Box::pin(source.run(cx.out, cx.shutdown).map(|result| {
-            result.map_err(|error| {
-                error!(message = "Source future failed.", %error);
-            })
-        })))
+        Ok(Box::pin(
+            source
+                .run(cx.out, kube_events_out, cx.shutdown)
+                .map(|result| {
+                    result.map_err(|error| {
+                        error!(message = "Source future failed.", %error);
+                    })
+                }),
+        ))
     }
 
     fn output_type(&self) -> DataType {
         DataType::Log
     }
 
+    fn named_outputs(&self) -> Vec<String> {
+        if self.kube_events {
+            vec![kube_events::OUTPUT.to_owned()]
+        } else {
+            Vec::new()
+        }
+    }
+
     fn source_type(&self) -> &'static str {
         COMPONENT_ID
     }
@@ -187,9 +228,13 @@ impl SourceConfig for Config {
 #[derive(Clone)]
 struct Source {
     client: k8s::client::Client,
+    self_node_name: String,
     data_dir: PathBuf,
+    checkpoint_store: CheckpointStore,
+    component: String,
     auto_partial_merge: bool,
     pod_fields_spec: pod_metadata_annotator::FieldsSpec,
+    owner_cache_capacity: usize,
     namespace_fields_spec: namespace_metadata_annotator::FieldsSpec,
     field_selector: String,
     label_selector: String,
@@ -200,6 +245,7 @@ struct Source {
     glob_minimum_cooldown: Duration,
     ingestion_timestamp_field: Option<String>,
     timezone: TimeZone,
+    kube_event_fields: kube_events::FieldsSpec,
 }
 
 impl Source {
@@ -209,7 +255,8 @@ impl Source {
         key: &ComponentKey,
         proxy: &ProxyConfig,
     ) -> crate::Result<Self> {
-        let field_selector = prepare_field_selector(config)?;
+        let self_node_name = prepare_self_node_name(config)?;
+        let field_selector = prepare_field_selector(config, &self_node_name);
         let label_selector = prepare_label_selector(config);
 
         let k8s_config = match &config.kube_config_file {
@@ -219,10 +266,17 @@ impl Source {
         let client = k8s::client::Client::new(k8s_config, proxy)?;
 
         let data_dir = globals.resolve_and_make_data_subdir(config.data_dir.as_ref(), key.id())?;
+        let checkpoint_store =
+            CheckpointStore::new(globals.resolve_and_validate_data_dir(config.data_dir.as_ref())?);
         let timezone = config.timezone.unwrap_or(globals.timezone);
 
         let exclude_paths = prepare_exclude_paths(config)?;
 
+        let mut pod_fields_spec = config.pod_annotation_fields.clone();
+        if let Some(allowlist) = &config.pod_annotation_fields_allowlist {
+            pod_fields_spec.retain(allowlist)?;
+        }
+
         let glob_minimum_cooldown =
             Duration::from_millis(config.glob_minimum_cooldown_ms.try_into().expect(
                 "unable to convert glob_minimum_cooldown_ms from usize to u64 without data loss",
@@ -230,9 +284,13 @@ impl Source {
 
         Ok(Self {
             client,
+            self_node_name,
             data_dir,
+            checkpoint_store,
+            component: key.id().to_owned(),
             auto_partial_merge: config.auto_partial_merge,
-            pod_fields_spec: config.pod_annotation_fields.clone(),
+            pod_fields_spec,
+            owner_cache_capacity: config.owner_cache_capacity,
             namespace_fields_spec: config.namespace_annotation_fields.clone(),
             field_selector,
             label_selector,
@@ -243,19 +301,29 @@ impl Source {
             glob_minimum_cooldown,
             ingestion_timestamp_field: config.ingestion_timestamp_field.clone(),
             timezone,
+            kube_event_fields: config.kube_event_fields.clone(),
         })
     }
 
-    async fn run<O>(self, out: O, global_shutdown: ShutdownSignal) -> crate::Result<()>
+    async fn run<O>(
+        self,
+        out: O,
+        kube_events_out: Option<Pipeline>,
+        global_shutdown: ShutdownSignal,
+    ) -> crate::Result<()>
     where
         O: Sink<Event> + Send + 'static + Unpin,
         <O as Sink<Event>>::Error: std::error::Error,
     {
         let Self {
             client,
+            self_node_name,
             data_dir,
+            checkpoint_store,
+            component,
             auto_partial_merge,
             pod_fields_spec,
+            owner_cache_capacity,
             namespace_fields_spec,
             field_selector,
             label_selector,
@@ -266,6 +334,7 @@ impl Source {
             glob_minimum_cooldown,
             ingestion_timestamp_field,
             timezone,
+            kube_event_fields,
         } = self;
 
         let watcher =
@@ -314,14 +383,69 @@ impl Source {
         );
         let ns_reflector_process = ns_reflector.run();
 
+        // -----------------------------------------------------------------
+
+        // -----------------------------------------------------------------
+
+        // Pods are owned by `Deployment`s through `ReplicaSet`s, which are
+        // only watched if the `Deployment`s are annotated.
+        let deployments = (!pod_fields_spec.deployment_name.is_empty() && owner_cache_capacity > 0)
+            .then(|| DeploymentCache::new(owner_cache_capacity));
+        let rs_reflector_process = deployments.clone().map(|deployments| {
+            let rs_watcher = k8s::api_watcher::ApiWatcher::new(
+                client.clone(),
+                ReplicaSet::watch_replica_set_for_all_namespaces,
+            );
+            let rs_watcher = k8s::instrumenting_watcher::InstrumentingWatcher::new(rs_watcher);
+            let rs_writer = pod_owners::Writer::new(deployments);
+            let rs_writer = k8s::state::instrumenting::Writer::new(rs_writer);
+
+            let mut rs_reflector = k8s::reflector::Reflector::new(
+                rs_watcher,
+                rs_writer,
+                None,
+                None,
+                Duration::from_secs(1),
+            );
+            async move { rs_reflector.run().await }
+        });
+
+        // -----------------------------------------------------------------
+
+        let events_reflector_process = kube_events_out.map(|kube_events_out| {
+            let events_watcher = k8s::api_watcher::ApiWatcher::new(
+                client.clone(),
+                KubeEvent::watch_event_for_all_namespaces,
+            );
+            let events_watcher =
+                k8s::instrumenting_watcher::InstrumentingWatcher::new(events_watcher);
+            let events_writer = kube_events::Writer::new(
+                kube_events_out,
+                state_reader.clone(),
+                self_node_name,
+                kube_event_fields,
+                pod_fields_spec.clone(),
+            );
+            let events_writer = k8s::state::instrumenting::Writer::new(events_writer);
+
+            let mut events_reflector = k8s::reflector::Reflector::new(
+                events_watcher,
+                events_writer,
+                None,
+                None,
+                Duration::from_secs(1),
+            );
+            async move { events_reflector.run().await }
+        });
+
         let paths_provider =
             K8sPathsProvider::new(state_reader.clone(), ns_state_reader.clone(), exclude_paths);
-        let annotator = PodMetadataAnnotator::new(state_reader, pod_fields_spec);
+        let annotator = PodMetadataAnnotator::new(state_reader, pod_fields_spec, deployments);
         let ns_annotator = NamespaceMetadataAnnotator::new(ns_state_reader, namespace_fields_spec);
 
         // TODO: maybe more of the parameters have to be configurable.
 
-        let checkpointer = Checkpointer::new(&data_dir);
+        let checkpointer = Checkpointer::new(&checkpoint_store, &component, &data_dir);
         let file_server = FileServer {
             // Use our special paths provider.
             paths_provider,
@@ -373,6 +497,8 @@ impl Source {
             oldest_first: false,
             // We do not remove the log files, `kubelet` is responsible for it.
             remove_after: None,
+            // The log files are on the local filesystem of the node.
+            network_filesystem: false,
             // The standard emitter.
             emitter: FileSourceInternalEventsEmitter,
             // A handle to the current tokio runtime
@@ -453,6 +579,30 @@ impl Source {
                 });
             slot.bind(Box::pin(fut));
         }
+        if let Some(rs_reflector_process) = rs_reflector_process {
+            let (slot, shutdown) = lifecycle.add();
+            let fut = util::cancel_on_signal(rs_reflector_process, shutdown).map(|result| {
+                match result {
+                    Ok(()) => info!(message = "ReplicaSet reflector process completed gracefully."),
+                    Err(error) => {
+                        error!(message = "ReplicaSet reflector process exited with an error.", %error)
+                    }
+                }
+            });
+            slot.bind(Box::pin(fut));
+        }
+        if let Some(events_reflector_process) = events_reflector_process {
+            let (slot, shutdown) = lifecycle.add();
+            let fut = util::cancel_on_signal(events_reflector_process, shutdown).map(|result| {
+                match result {
+                    Ok(()) => info!(message = "Events reflector process completed gracefully."),
+                    Err(error) => {
+                        error!(message = "Events reflector process exited with an error.", %error)
+                    }
+                }
+            });
+            slot.bind(Box::pin(fut));
+        }
         {
             let (slot, shutdown) = lifecycle.add();
             let fut = util::run_file_server(file_server, file_source_tx, shutdown, checkpointer)
@@ -570,9 +720,9 @@ fn prepare_exclude_paths(config: &Config) -> crate::Result<Vec<glob::Pattern>> {
     Ok(exclude_paths)
 }
 
-// This function constructs the effective field selector to use, based on
-// the specified configuration.
-fn prepare_field_selector(config: &Config) -> crate::Result<String> {
+// This function obtains the name of the `Node` Vector runs at, from the
+// specified configuration or the environment.
+fn prepare_self_node_name(config: &Config) -> crate::Result<String> {
     let self_node_name = if config.self_node_name.is_empty()
         || config.self_node_name == default_self_node_name_env_template()
     {
@@ -590,16 +740,19 @@ fn prepare_field_selector(config: &Config) -> crate::Result<String> {
         ?self_node_name
     );
 
+    Ok(self_node_name)
+}
+
+// This function constructs the effective field selector to use, based on
+// the specified configuration.
+fn prepare_field_selector(config: &Config, self_node_name: &str) -> String {
     let field_selector = format!("spec.nodeName={}", self_node_name);
 
     if config.extra_field_selector.is_empty() {
-        return Ok(field_selector);
+        return field_selector;
     }
 
-    Ok(format!(
-        "{},{}",
-        field_selector, config.extra_field_selector
-    ))
+    format!("{},{}", field_selector, config.extra_field_selector)
 }
 
 // This function constructs the effective label selector to use, based on
@@ -696,7 +849,8 @@ mod tests {
         ];
 
         for (input, expected) in cases {
-            let output = super::prepare_field_selector(&input).unwrap();
+            let self_node_name = super::prepare_self_node_name(&input).unwrap();
+            let output = super::prepare_field_selector(&input, &self_node_name);
             assert_eq!(expected, output, "expected left, actual right");
         }
     }
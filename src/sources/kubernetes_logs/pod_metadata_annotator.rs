@@ -2,15 +2,18 @@
 
 #![deny(missing_docs)]
 
-use super::path_helpers::{parse_log_file_path, LogFileInfo};
+use super::{
+    path_helpers::{parse_log_file_path, LogFileInfo},
+    pod_owners::DeploymentCache,
+};
 use crate::{
-    event::{Event, LogEvent, PathComponent, PathIter},
+    event::{Event, LogEvent, PathComponent, PathIter, Value},
     kubernetes as k8s,
 };
 use evmap::ReadHandle;
 use k8s_openapi::{
     api::core::v1::{Container, ContainerStatus, Pod, PodSpec, PodStatus},
-    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+    apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference},
 };
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +29,11 @@ pub struct FieldsSpec {
     pub pod_annotations: String,
     pub pod_node_name: String,
     pub pod_owner: String,
+    pub replicaset_name: String,
+    pub deployment_name: String,
+    pub statefulset_name: String,
+    pub daemonset_name: String,
+    pub job_name: String,
     pub container_name: String,
     pub container_id: String,
     pub container_image: String,
@@ -43,6 +51,11 @@ impl Default for FieldsSpec {
             pod_annotations: "kubernetes.pod_annotations".to_owned(),
             pod_node_name: "kubernetes.pod_node_name".to_owned(),
             pod_owner: "kubernetes.pod_owner".to_owned(),
+            replicaset_name: "kubernetes.replicaset_name".to_owned(),
+            deployment_name: "kubernetes.deployment_name".to_owned(),
+            statefulset_name: "kubernetes.statefulset_name".to_owned(),
+            daemonset_name: "kubernetes.daemonset_name".to_owned(),
+            job_name: "kubernetes.job_name".to_owned(),
             container_name: "kubernetes.container_name".to_owned(),
             container_id: "kubernetes.container_id".to_owned(),
             container_image: "kubernetes.container_image".to_owned(),
@@ -50,21 +63,67 @@ impl Default for FieldsSpec {
     }
 }
 
+impl FieldsSpec {
+    /// Clears the fields not named in `allowlist`, so that they are not
+    /// annotated.
+    pub fn retain(&mut self, allowlist: &[String]) -> crate::Result<()> {
+        let mut fields = [
+            ("pod_name", &mut self.pod_name),
+            ("pod_namespace", &mut self.pod_namespace),
+            ("pod_uid", &mut self.pod_uid),
+            ("pod_ip", &mut self.pod_ip),
+            ("pod_ips", &mut self.pod_ips),
+            ("pod_labels", &mut self.pod_labels),
+            ("pod_annotations", &mut self.pod_annotations),
+            ("pod_node_name", &mut self.pod_node_name),
+            ("pod_owner", &mut self.pod_owner),
+            ("replicaset_name", &mut self.replicaset_name),
+            ("deployment_name", &mut self.deployment_name),
+            ("statefulset_name", &mut self.statefulset_name),
+            ("daemonset_name", &mut self.daemonset_name),
+            ("job_name", &mut self.job_name),
+            ("container_name", &mut self.container_name),
+            ("container_id", &mut self.container_id),
+            ("container_image", &mut self.container_image),
+        ];
+
+        if let Some(unknown) = allowlist
+            .iter()
+            .find(|allowed| !fields.iter().any(|(name, _)| name == allowed))
+        {
+            return Err(format!("Unknown pod annotation field {:?}.", unknown).into());
+        }
+
+        for (name, field) in fields.iter_mut() {
+            if !allowlist.iter().any(|allowed| allowed == name) {
+                field.clear();
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Annotate the event with pod metadata.
 pub struct PodMetadataAnnotator {
     pods_state_reader: ReadHandle<String, k8s::state::evmap::Value<Pod>>,
     fields_spec: FieldsSpec,
+    deployments: Option<DeploymentCache>,
 }
 
 impl PodMetadataAnnotator {
     /// Create a new [`PodMetadataAnnotator`].
+    ///
+    /// Without `deployments`, the `Deployment`s owning the pods are not
+    /// annotated.
     pub fn new(
         pods_state_reader: ReadHandle<String, k8s::state::evmap::Value<Pod>>,
         fields_spec: FieldsSpec,
+        deployments: Option<DeploymentCache>,
     ) -> Self {
         Self {
             pods_state_reader,
             fields_spec,
+            deployments,
         }
     }
 }
@@ -82,6 +141,14 @@ impl PodMetadataAnnotator {
 
         annotate_from_file_info(log, &self.fields_spec, &file_info);
         annotate_from_metadata(log, &self.fields_spec, &pod.metadata);
+        if let Some(owner_references) = &pod.metadata.owner_references {
+            annotate_from_owner_references(
+                log,
+                &self.fields_spec,
+                owner_references,
+                self.deployments.as_ref(),
+            );
+        }
 
         let container;
         if let Some(ref pod_spec) = pod.spec {
@@ -111,12 +178,20 @@ impl PodMetadataAnnotator {
     }
 }
 
+/// Inserts the field, unless it was left out of the annotated fields.
+fn insert(log: &mut LogEvent, key: &str, value: impl Into<Value>) {
+    if !key.is_empty() {
+        log.insert(key, value);
+    }
+}
+
 fn annotate_from_file_info(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     file_info: &LogFileInfo<'_>,
 ) {
-    log.insert(
+    insert(
+        log,
         &fields_spec.container_name,
         file_info.container_name.to_owned(),
     );
@@ -131,18 +206,20 @@ fn annotate_from_metadata(log: &mut LogEvent, fields_spec: &FieldsSpec, metadata
     .iter()
     {
         if let Some(val) = val {
-            log.insert(key, val.to_owned());
+            insert(log, key, val.to_owned());
         }
     }
 
     if let Some(owner_references) = &metadata.owner_references {
-        log.insert(
+        insert(
+            log,
             &fields_spec.pod_owner,
             format!("{}/{}", owner_references[0].kind, owner_references[0].name),
         );
     }
 
-    if let Some(labels) = &metadata.labels {
+    let labels = metadata.labels.as_ref();
+    if let Some(labels) = labels.filter(|_| !fields_spec.pod_labels.is_empty()) {
         // Calculate and cache the prefix path.
         let prefix_path = PathIter::new(fields_spec.pod_labels.as_ref()).collect::<Vec<_>>();
         for (key, val) in labels.iter() {
@@ -152,7 +229,8 @@ fn annotate_from_metadata(log: &mut LogEvent, fields_spec: &FieldsSpec, metadata
         }
     }
 
-    if let Some(annotations) = &metadata.annotations {
+    let annotations = metadata.annotations.as_ref();
+    if let Some(annotations) = annotations.filter(|_| !fields_spec.pod_annotations.is_empty()) {
         let prefix_path = PathIter::new(fields_spec.pod_annotations.as_ref()).collect::<Vec<_>>();
         for (key, val) in annotations.iter() {
             let mut path = prefix_path.clone();
@@ -162,10 +240,37 @@ fn annotate_from_metadata(log: &mut LogEvent, fields_spec: &FieldsSpec, metadata
     }
 }
 
+/// Annotates the names of the workloads owning the pod. `Deployment`s own pods
+/// through `ReplicaSet`s, so they're looked up in `deployments`.
+fn annotate_from_owner_references(
+    log: &mut LogEvent,
+    fields_spec: &FieldsSpec,
+    owner_references: &[OwnerReference],
+    deployments: Option<&DeploymentCache>,
+) {
+    for owner in owner_references {
+        let key = match owner.kind.as_str() {
+            "ReplicaSet" => {
+                if let Some(deployment) =
+                    deployments.and_then(|deployments| deployments.get(&owner.uid))
+                {
+                    insert(log, &fields_spec.deployment_name, deployment);
+                }
+                &fields_spec.replicaset_name
+            }
+            "StatefulSet" => &fields_spec.statefulset_name,
+            "DaemonSet" => &fields_spec.daemonset_name,
+            "Job" => &fields_spec.job_name,
+            _ => continue,
+        };
+        insert(log, key, owner.name.clone());
+    }
+}
+
 fn annotate_from_pod_spec(log: &mut LogEvent, fields_spec: &FieldsSpec, pod_spec: &PodSpec) {
     for (ref key, ref val) in [(&fields_spec.pod_node_name, &pod_spec.node_name)].iter() {
         if let Some(val) = val {
-            log.insert(key, val.to_owned());
+            insert(log, key, val.to_owned());
         }
     }
 }
@@ -173,7 +278,7 @@ fn annotate_from_pod_spec(log: &mut LogEvent, fields_spec: &FieldsSpec, pod_spec
 fn annotate_from_pod_status(log: &mut LogEvent, fields_spec: &FieldsSpec, pod_status: &PodStatus) {
     for (ref key, ref val) in [(&fields_spec.pod_ip, &pod_status.pod_ip)].iter() {
         if let Some(val) = val {
-            log.insert(key, val.to_owned());
+            insert(log, key, val.to_owned());
         }
     }
 
@@ -183,7 +288,7 @@ fn annotate_from_pod_status(log: &mut LogEvent, fields_spec: &FieldsSpec, pod_st
                 .iter()
                 .filter_map(|v| v.ip.clone())
                 .collect::<Vec<String>>();
-            log.insert(key, inner);
+            insert(log, key, inner);
         }
     }
 }
@@ -195,7 +300,7 @@ fn annotate_from_container_status(
 ) {
     for (ref key, ref val) in [(&fields_spec.container_id, &container_status.container_id)].iter() {
         if let Some(val) = val {
-            log.insert(key, val.to_owned());
+            insert(log, key, val.to_owned());
         }
     }
 }
@@ -203,7 +308,7 @@ fn annotate_from_container_status(
 fn annotate_from_container(log: &mut LogEvent, fields_spec: &FieldsSpec, container: &Container) {
     for (ref key, ref val) in [(&fields_spec.container_image, &container.image)].iter() {
         if let Some(val) = val {
-            log.insert(key, val.to_owned());
+            insert(log, key, val.to_owned());
         }
     }
 }
@@ -583,4 +688,105 @@ mod tests {
             assert_event_data_eq!(log, expected);
         }
     }
+
+    #[tokio::test]
+    async fn test_annotate_from_owner_references() {
+        let deployments = DeploymentCache::new(10);
+        let mut writer = super::super::pod_owners::Writer::new(deployments.clone());
+        k8s::state::Write::add(
+            &mut writer,
+            k8s_openapi::api::apps::v1::ReplicaSet {
+                metadata: ObjectMeta {
+                    uid: Some("rs-uid".to_owned()),
+                    owner_references: Some(vec![OwnerReference {
+                        kind: "Deployment".to_owned(),
+                        name: "web".to_owned(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let owner = |kind: &str, name: &str, uid: &str| OwnerReference {
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            uid: uid.to_owned(),
+            ..Default::default()
+        };
+        let cases = vec![
+            (vec![owner("ReplicaSet", "web-5d4f8", "rs-uid")], {
+                let mut log = LogEvent::default();
+                log.insert("kubernetes.replicaset_name", "web-5d4f8");
+                log.insert("kubernetes.deployment_name", "web");
+                log
+            }),
+            (vec![owner("ReplicaSet", "orphan-7c9a1", "other-uid")], {
+                let mut log = LogEvent::default();
+                log.insert("kubernetes.replicaset_name", "orphan-7c9a1");
+                log
+            }),
+            (vec![owner("StatefulSet", "db", "sts-uid")], {
+                let mut log = LogEvent::default();
+                log.insert("kubernetes.statefulset_name", "db");
+                log
+            }),
+            (vec![owner("Job", "migrate", "job-uid")], {
+                let mut log = LogEvent::default();
+                log.insert("kubernetes.job_name", "migrate");
+                log
+            }),
+            (
+                vec![owner("Node", "node-0", "node-uid")],
+                LogEvent::default(),
+            ),
+        ];
+
+        for (owner_references, expected) in cases.into_iter() {
+            let mut log = LogEvent::default();
+            annotate_from_owner_references(
+                &mut log,
+                &FieldsSpec::default(),
+                &owner_references,
+                Some(&deployments),
+            );
+            assert_event_data_eq!(log, expected);
+        }
+    }
+
+    #[test]
+    fn test_fields_spec_retain() {
+        let mut fields_spec = FieldsSpec::default();
+        fields_spec
+            .retain(&["pod_name".to_owned(), "deployment_name".to_owned()])
+            .unwrap();
+        assert_eq!(fields_spec.pod_name, "kubernetes.pod_name");
+        assert_eq!(fields_spec.deployment_name, "kubernetes.deployment_name");
+        assert_eq!(fields_spec.pod_labels, "");
+
+        let mut log = LogEvent::default();
+        annotate_from_metadata(
+            &mut log,
+            &fields_spec,
+            &ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                labels: Some(
+                    vec![("sandbox0-label0".to_owned(), "val0".to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+        );
+        let mut expected = LogEvent::default();
+        expected.insert("kubernetes.pod_name", "sandbox0-name");
+        assert_event_data_eq!(log, expected);
+
+        assert!(FieldsSpec::default()
+            .retain(&["pod_nmae".to_owned()])
+            .is_err());
+    }
 }
@@ -0,0 +1,172 @@
+//! Resolves the `Deployment`s owning pods, through the `ReplicaSet`s that
+//! create them.
+
+#![deny(missing_docs)]
+
+use crate::kubernetes as k8s;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use lru::LruCache;
+use std::sync::{Arc, Mutex};
+
+/// The default number of `ReplicaSet`s whose owner is kept.
+pub const fn default_owner_cache_capacity() -> usize {
+    10_000
+}
+
+/// The names of the `Deployment`s owning `ReplicaSet`s, by `ReplicaSet` UID.
+///
+/// Only the owner is kept rather than the whole `ReplicaSet`, and the least
+/// recently used entries are evicted once the capacity is reached.
+#[derive(Clone)]
+pub struct DeploymentCache(Arc<Mutex<LruCache<String, String>>>);
+
+impl DeploymentCache {
+    /// Create a new [`DeploymentCache`] holding up to `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    /// The name of the `Deployment` owning the `ReplicaSet` with the given
+    /// UID, if known.
+    pub fn get(&self, replica_set_uid: &str) -> Option<String> {
+        self.0.lock().unwrap().get(replica_set_uid).cloned()
+    }
+
+    fn insert(&self, replica_set: &ReplicaSet) {
+        let uid = match &replica_set.metadata.uid {
+            Some(uid) => uid,
+            None => return,
+        };
+        let deployment = replica_set
+            .metadata
+            .owner_references
+            .iter()
+            .flatten()
+            .find(|owner| owner.kind == "Deployment");
+
+        let mut cache = self.0.lock().unwrap();
+        match deployment {
+            Some(deployment) => {
+                cache.put(uid.clone(), deployment.name.clone());
+            }
+            None => {
+                cache.pop(uid);
+            }
+        }
+    }
+
+    fn remove(&self, replica_set: &ReplicaSet) {
+        if let Some(uid) = &replica_set.metadata.uid {
+            self.0.lock().unwrap().pop(uid);
+        }
+    }
+}
+
+/// A [`k8s::state::Write`] implementation that keeps the owners of the
+/// watched `ReplicaSet`s in a [`DeploymentCache`].
+pub struct Writer {
+    cache: DeploymentCache,
+}
+
+impl Writer {
+    /// Create a new [`Writer`].
+    pub const fn new(cache: DeploymentCache) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl k8s::state::Write for Writer {
+    type Item = ReplicaSet;
+
+    async fn add(&mut self, item: Self::Item) {
+        self.cache.insert(&item);
+    }
+
+    async fn update(&mut self, item: Self::Item) {
+        self.cache.insert(&item);
+    }
+
+    async fn delete(&mut self, item: Self::Item) {
+        self.cache.remove(&item);
+    }
+
+    // The `ReplicaSet`s are added again after a resync, and the ones that were
+    // deleted in the meantime are eventually evicted.
+    async fn resync(&mut self) {}
+}
+
+#[async_trait]
+impl k8s::state::MaintainedWrite for Writer {
+    fn maintenance_request(&mut self) -> Option<BoxFuture<'_, ()>> {
+        None
+    }
+
+    async fn perform_maintenance(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s::state::Write;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+
+    fn replica_set(uid: &str, owner: Option<(&str, &str)>) -> ReplicaSet {
+        ReplicaSet {
+            metadata: ObjectMeta {
+                uid: Some(uid.to_owned()),
+                owner_references: owner.map(|(kind, name)| {
+                    vec![OwnerReference {
+                        kind: kind.to_owned(),
+                        name: name.to_owned(),
+                        ..OwnerReference::default()
+                    }]
+                }),
+                ..ObjectMeta::default()
+            },
+            ..ReplicaSet::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_deployment_owners() {
+        let cache = DeploymentCache::new(2);
+        let mut writer = Writer::new(cache.clone());
+
+        writer
+            .add(replica_set("rs0", Some(("Deployment", "web"))))
+            .await;
+        writer.add(replica_set("rs1", None)).await;
+        writer
+            .add(replica_set("rs2", Some(("Deployment", "api"))))
+            .await;
+        assert_eq!(cache.get("rs0"), Some("web".to_owned()));
+        assert_eq!(cache.get("rs1"), None);
+        assert_eq!(cache.get("rs2"), Some("api".to_owned()));
+
+        writer
+            .update(replica_set("rs0", Some(("Deployment", "web-v2"))))
+            .await;
+        assert_eq!(cache.get("rs0"), Some("web-v2".to_owned()));
+
+        writer.delete(replica_set("rs2", None)).await;
+        assert_eq!(cache.get("rs2"), None);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        let cache = DeploymentCache::new(2);
+        let mut writer = Writer::new(cache.clone());
+
+        for (uid, name) in &[("rs0", "a"), ("rs1", "b"), ("rs2", "c")] {
+            writer
+                .add(replica_set(uid, Some(("Deployment", name))))
+                .await;
+        }
+        assert_eq!(cache.get("rs0"), None);
+        assert_eq!(cache.get("rs1"), Some("b".to_owned()));
+        assert_eq!(cache.get("rs2"), Some("c".to_owned()));
+    }
+}
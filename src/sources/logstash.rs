@@ -1,4 +1,4 @@
-use super::util::{SocketListenAddr, TcpError, TcpSource};
+use super::util::{SocketListenAddr, TcpConnectionLimits, TcpError, TcpSource};
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
@@ -27,6 +27,8 @@ pub struct LogstashConfig {
     keepalive: Option<TcpKeepaliveConfig>,
     tls: Option<TlsConfig>,
     receive_buffer_bytes: Option<usize>,
+    #[serde(default)]
+    connection_limits: TcpConnectionLimits,
 }
 
 inventory::submit! {
@@ -40,6 +42,7 @@ impl GenerateConfig for LogstashConfig {
             keepalive: None,
             tls: None,
             receive_buffer_bytes: None,
+            connection_limits: TcpConnectionLimits::default(),
         })
         .unwrap()
     }
@@ -60,6 +63,7 @@ impl SourceConfig for LogstashConfig {
             shutdown_secs,
             tls,
             self.receive_buffer_bytes,
+            self.connection_limits,
             cx.shutdown,
             cx.out,
         )
@@ -759,6 +763,7 @@ output {
                 tls,
                 keepalive: None,
                 receive_buffer_bytes: None,
+                connection_limits: Default::default(),
             }
             .build(SourceContext::new_test(sender))
             .await
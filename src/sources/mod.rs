@@ -8,6 +8,10 @@ pub mod aws_ecs_metrics;
 pub mod aws_kinesis_firehose;
 #[cfg(feature = "sources-aws_s3")]
 pub mod aws_s3;
+#[cfg(all(target_os = "linux", feature = "sources-auditd"))]
+pub mod auditd;
+#[cfg(feature = "sources-azure_event_hubs")]
+pub mod azure_event_hubs;
 #[cfg(feature = "sources-datadog")]
 pub mod datadog;
 #[cfg(all(unix, feature = "sources-dnstap"))]
@@ -24,12 +28,18 @@ pub mod file;
 pub mod fluent;
 #[cfg(feature = "sources-generator")]
 pub mod generator;
+#[cfg(feature = "sources-git_webhook")]
+pub mod git_webhook;
 #[cfg(feature = "sources-heroku_logs")]
 pub mod heroku_logs;
 #[cfg(feature = "sources-host_metrics")]
 pub mod host_metrics;
 #[cfg(feature = "sources-http")]
 pub mod http;
+#[cfg(feature = "sources-imap")]
+pub mod imap;
+#[cfg(feature = "sources-industrial_metrics")]
+pub mod industrial_metrics;
 #[cfg(feature = "sources-internal_logs")]
 pub mod internal_logs;
 #[cfg(feature = "sources-internal_metrics")]
@@ -46,10 +56,14 @@ pub mod logstash;
 pub mod mongodb_metrics;
 #[cfg(all(feature = "sources-nats"))]
 pub mod nats;
+#[cfg(feature = "sources-netflow")]
+pub mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 pub mod nginx_metrics;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub mod postgresql_metrics;
+#[cfg(all(target_os = "linux", feature = "sources-proc_connector"))]
+pub mod proc_connector;
 #[cfg(feature = "sources-prometheus")]
 pub mod prometheus;
 #[cfg(feature = "sources-socket")]
@@ -62,6 +76,8 @@ pub mod statsd;
 pub mod stdin;
 #[cfg(feature = "sources-syslog")]
 pub mod syslog;
+#[cfg(all(unix, feature = "sources-varnishlog"))]
+pub mod varnishlog;
 #[cfg(feature = "sources-vector")]
 pub mod vector;
 
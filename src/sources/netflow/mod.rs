@@ -0,0 +1,183 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::Event,
+    internal_events::{NetflowEventsReceived, NetflowParseError, NetflowSocketError},
+    shutdown::ShutdownSignal,
+    udp, Pipeline,
+};
+use bytes::Bytes;
+use futures::{FutureExt, SinkExt};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use tokio::net::UdpSocket;
+
+pub mod parser;
+
+use parser::FlowDecoder;
+
+/// The largest UDP payload.
+const MAX_DATAGRAM_LENGTH: usize = 65535;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    address: SocketAddr,
+    #[serde(default = "default_max_templates")]
+    max_templates: usize,
+    receive_buffer_bytes: Option<usize>,
+    host_key: Option<String>,
+}
+
+const fn default_max_templates() -> usize {
+    10_000
+}
+
+inventory::submit! {
+    SourceDescription::new::<NetflowConfig>("netflow")
+}
+
+impl GenerateConfig for NetflowConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "0.0.0.0:2055"
+            max_templates = 10000"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.max_templates == 0 {
+            return Err("`max_templates` must be at least 1.".into());
+        }
+
+        Ok(Box::pin(netflow(self.clone(), cx.shutdown, cx.out)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "netflow"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+}
+
+async fn netflow(
+    config: NetflowConfig,
+    mut shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+    let host_key = config
+        .host_key
+        .unwrap_or_else(|| log_schema().host_key().to_string());
+
+    let socket = UdpSocket::bind(&config.address)
+        .await
+        .map_err(|error| emit!(&NetflowSocketError { error }))?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %config.address, r#type = "udp");
+
+    let mut decoder = FlowDecoder::new(config.max_templates);
+    let mut buf = vec![0; MAX_DATAGRAM_LENGTH];
+    loop {
+        let (byte_size, exporter) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(recv) => recv,
+                Err(error) => {
+                    emit!(&NetflowSocketError { error });
+                    continue;
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+
+        let flows = match decoder.decode(exporter, &buf[..byte_size]) {
+            Ok(flows) => flows,
+            Err(error) => {
+                emit!(&NetflowParseError {
+                    error: &error,
+                    exporter
+                });
+                continue;
+            }
+        };
+
+        emit!(&NetflowEventsReceived {
+            count: flows.len(),
+            byte_size,
+            exporter,
+        });
+
+        let host = exporter.ip().to_string();
+        for mut log in flows {
+            log.insert(log_schema().source_type_key(), Bytes::from("netflow"));
+            log.insert(host_key.as_str(), host.clone());
+
+            tokio::select! {
+                result = out.send(Event::Log(log)) => if result.is_err() {
+                    return Ok(());
+                },
+                _ = &mut shutdown => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{collect_ready, next_addr_v6};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    #[tokio::test]
+    async fn receives_flows() {
+        let address = next_addr_v6();
+        let config = NetflowConfig {
+            address,
+            max_templates: default_max_templates(),
+            receive_buffer_bytes: None,
+            host_key: None,
+        };
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        tokio::spawn(netflow(config, shutdown, tx).map(|_| ()));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let mut datagram = vec![0, 5, 0, 2, 0, 0, 0, 0, 0x61, 0x80, 0, 0, 0, 0, 0, 0];
+        datagram.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0]);
+        datagram.extend_from_slice(&[0; 96]);
+        let socket = UdpSocket::bind("[::1]:0").await.unwrap();
+        socket.send_to(&datagram, address).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        drop(trigger);
+
+        let events = collect_ready(rx).await;
+        assert_eq!(events.len(), 2);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().source_type_key()], "netflow".into());
+        assert_eq!(log[log_schema().host_key()], "::1".into());
+        assert_eq!(log["src_addr"], "0.0.0.0".into());
+    }
+}
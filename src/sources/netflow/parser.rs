@@ -0,0 +1,856 @@
+//! Decoders of NetFlow v5, NetFlow v9, IPFIX and sFlow v5 datagrams.
+//!
+//! Flow fields are named after their IPFIX information element, in snake
+//! case and shortened the way collectors usually do, so that flows read the
+//! same whatever version their exporter speaks. Fields unknown to the
+//! decoder are named after their ID, such as `field_300`, or
+//! `enterprise_9_field_12235` for enterprise-specific fields.
+
+use crate::{
+    event::{LogEvent, Value},
+    internal_events::NetflowTemplateMissing,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use lru::LruCache;
+use snafu::Snafu;
+use std::{
+    convert::{TryFrom, TryInto},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+const NETFLOW_V5: u16 = 5;
+const NETFLOW_V9: u16 = 9;
+const IPFIX: u16 = 10;
+const SFLOW_V5: u32 = 5;
+
+/// Field lengths of IPFIX fields with a variable length.
+const VARIABLE_LENGTH: u16 = 65535;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("Datagram is truncated"))]
+    Truncated,
+    #[snafu(display("Unsupported version {}", version))]
+    UnsupportedVersion { version: u32 },
+    #[snafu(display("Invalid set length {}", length))]
+    InvalidSetLength { length: u16 },
+    #[snafu(display("Invalid template {}", template_id))]
+    InvalidTemplate { template_id: u16 },
+}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    const fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(ParseError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn ipv4(&mut self) -> Result<Ipv4Addr> {
+        Ok(Ipv4Addr::from(self.u32()?))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldKind {
+    Unsigned,
+    Address,
+    Mac,
+}
+
+/// The name and kind of the IPFIX information elements, which share their IDs
+/// with the NetFlow v9 field types.
+const fn information_element(id: u16) -> Option<(&'static str, FieldKind)> {
+    use FieldKind::*;
+
+    Some(match id {
+        1 => ("bytes", Unsigned),
+        2 => ("packets", Unsigned),
+        3 => ("flows", Unsigned),
+        4 => ("protocol", Unsigned),
+        5 => ("tos", Unsigned),
+        6 => ("tcp_flags", Unsigned),
+        7 => ("src_port", Unsigned),
+        8 => ("src_addr", Address),
+        9 => ("src_mask", Unsigned),
+        10 => ("input_interface", Unsigned),
+        11 => ("dst_port", Unsigned),
+        12 => ("dst_addr", Address),
+        13 => ("dst_mask", Unsigned),
+        14 => ("output_interface", Unsigned),
+        15 => ("next_hop", Address),
+        16 => ("src_as", Unsigned),
+        17 => ("dst_as", Unsigned),
+        18 => ("bgp_next_hop", Address),
+        21 => ("last_switched", Unsigned),
+        22 => ("first_switched", Unsigned),
+        23 => ("out_bytes", Unsigned),
+        24 => ("out_packets", Unsigned),
+        27 => ("src_addr", Address),
+        28 => ("dst_addr", Address),
+        29 => ("src_mask", Unsigned),
+        30 => ("dst_mask", Unsigned),
+        31 => ("ipv6_flow_label", Unsigned),
+        32 => ("icmp_type_code", Unsigned),
+        34 => ("sampling_interval", Unsigned),
+        35 => ("sampling_algorithm", Unsigned),
+        56 => ("src_mac", Mac),
+        57 => ("post_dst_mac", Mac),
+        58 => ("vlan", Unsigned),
+        59 => ("post_vlan", Unsigned),
+        60 => ("ip_version", Unsigned),
+        61 => ("direction", Unsigned),
+        62 => ("next_hop", Address),
+        63 => ("bgp_next_hop", Address),
+        80 => ("dst_mac", Mac),
+        81 => ("post_src_mac", Mac),
+        85 => ("total_bytes", Unsigned),
+        86 => ("total_packets", Unsigned),
+        136 => ("end_reason", Unsigned),
+        148 => ("flow_id", Unsigned),
+        150 => ("flow_start_seconds", Unsigned),
+        151 => ("flow_end_seconds", Unsigned),
+        152 => ("flow_start_milliseconds", Unsigned),
+        153 => ("flow_end_milliseconds", Unsigned),
+        176 => ("icmp_type", Unsigned),
+        177 => ("icmp_code", Unsigned),
+        225 => ("post_nat_src_addr", Address),
+        226 => ("post_nat_dst_addr", Address),
+        227 => ("post_nat_src_port", Unsigned),
+        228 => ("post_nat_dst_port", Unsigned),
+        _ => return None,
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn field_value(kind: FieldKind, bytes: &[u8]) -> Value {
+    match (kind, bytes.len()) {
+        (FieldKind::Address, 4) => Ipv4Addr::from(<[u8; 4]>::try_from(bytes).unwrap())
+            .to_string()
+            .into(),
+        (FieldKind::Address, 16) => Ipv6Addr::from(<[u8; 16]>::try_from(bytes).unwrap())
+            .to_string()
+            .into(),
+        (FieldKind::Mac, 6) => mac(bytes).into(),
+        (FieldKind::Unsigned, 1..=8) => {
+            let value = bytes
+                .iter()
+                .fold(0u64, |value, byte| value << 8 | u64::from(*byte));
+            Value::Integer(value as i64)
+        }
+        _ => hex(bytes).into(),
+    }
+}
+
+fn mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct TemplateField {
+    id: u16,
+    enterprise: Option<u32>,
+    length: u16,
+}
+
+impl TemplateField {
+    fn insert(&self, log: &mut LogEvent, bytes: &[u8]) {
+        match (self.enterprise, information_element(self.id)) {
+            (None, Some((name, kind))) => {
+                log.insert(name, field_value(kind, bytes));
+            }
+            (None, None) => {
+                log.insert(
+                    format!("field_{}", self.id),
+                    field_value(FieldKind::Unsigned, bytes),
+                );
+            }
+            (Some(enterprise), _) => {
+                log.insert(
+                    format!("enterprise_{}_field_{}", enterprise, self.id),
+                    field_value(FieldKind::Unsigned, bytes),
+                );
+            }
+        }
+    }
+}
+
+/// Options templates describe records about the exporter rather than flows,
+/// so their records are skipped.
+enum Template {
+    Flows(Vec<TemplateField>),
+    Options,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct TemplateKey {
+    exporter: SocketAddr,
+    version: u16,
+    domain: u32,
+    template_id: u16,
+}
+
+/// Decodes the datagrams of flow exporters, caching the templates of NetFlow
+/// v9 and IPFIX exporters to decode the records following them.
+pub struct FlowDecoder {
+    templates: LruCache<TemplateKey, Template>,
+}
+
+impl FlowDecoder {
+    pub fn new(max_templates: usize) -> Self {
+        Self {
+            templates: LruCache::new(max_templates),
+        }
+    }
+
+    /// Decodes a datagram of `exporter` into one event per flow.
+    pub fn decode(&mut self, exporter: SocketAddr, datagram: &[u8]) -> Result<Vec<LogEvent>> {
+        let mut reader = Reader::new(datagram);
+        match reader.u16()? {
+            NETFLOW_V5 => decode_v5(reader),
+            NETFLOW_V9 => self.decode_v9(exporter, reader),
+            IPFIX => self.decode_ipfix(exporter, reader),
+            // sFlow versions are 32 bits long.
+            0 => match reader.u16()?.into() {
+                SFLOW_V5 => decode_sflow(reader),
+                version => Err(ParseError::UnsupportedVersion { version }),
+            },
+            version => Err(ParseError::UnsupportedVersion {
+                version: version.into(),
+            }),
+        }
+    }
+
+    fn decode_v9(&mut self, exporter: SocketAddr, mut reader: Reader) -> Result<Vec<LogEvent>> {
+        let _count = reader.u16()?;
+        let _sys_uptime = reader.u32()?;
+        let unix_secs = reader.u32()?;
+        let _sequence = reader.u32()?;
+        let source_id = reader.u32()?;
+
+        let mut flows = Vec::new();
+        while reader.remaining() >= 4 {
+            let set_id = reader.u16()?;
+            let length = reader.u16()?;
+            let mut set = Reader::new(
+                reader.take(
+                    usize::from(length)
+                        .checked_sub(4)
+                        .ok_or(ParseError::InvalidSetLength { length })?,
+                )?,
+            );
+            let key = |template_id| TemplateKey {
+                exporter,
+                version: NETFLOW_V9,
+                domain: source_id,
+                template_id,
+            };
+
+            match set_id {
+                0 => {
+                    while set.remaining() >= 4 {
+                        let template_id = set.u16()?;
+                        let field_count = set.u16()?;
+                        let fields = (0..field_count)
+                            .map(|_| read_v9_field(&mut set))
+                            .collect::<Result<_>>()?;
+                        self.templates
+                            .put(key(template_id), Template::Flows(fields));
+                    }
+                }
+                1 => {
+                    while set.remaining() >= 6 {
+                        let template_id = set.u16()?;
+                        let scope_length = set.u16()?;
+                        let option_length = set.u16()?;
+                        set.take(usize::from(scope_length) + usize::from(option_length))?;
+                        self.templates.put(key(template_id), Template::Options);
+                    }
+                }
+                template_id if template_id >= 256 => {
+                    let fields = match self.templates.get(&key(template_id)) {
+                        Some(Template::Flows(fields)) => fields,
+                        Some(Template::Options) => continue,
+                        None => {
+                            emit!(&NetflowTemplateMissing {
+                                exporter,
+                                template_id
+                            });
+                            continue;
+                        }
+                    };
+                    decode_records(&mut set, template_id, fields, &mut flows, |log| {
+                        log.insert("flow_type", "netflow_v9");
+                        log.insert("source_id", source_id);
+                        log.insert(
+                            crate::config::log_schema().timestamp_key(),
+                            export_time(unix_secs, 0),
+                        );
+                    })?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(flows)
+    }
+
+    fn decode_ipfix(&mut self, exporter: SocketAddr, mut reader: Reader) -> Result<Vec<LogEvent>> {
+        let length = reader.u16()?;
+        let export_time_secs = reader.u32()?;
+        let _sequence = reader.u32()?;
+        let domain = reader.u32()?;
+        let mut reader = Reader::new(
+            reader.take(
+                usize::from(length)
+                    .checked_sub(16)
+                    .ok_or(ParseError::InvalidSetLength { length })?,
+            )?,
+        );
+
+        let mut flows = Vec::new();
+        while reader.remaining() >= 4 {
+            let set_id = reader.u16()?;
+            let length = reader.u16()?;
+            let mut set = Reader::new(
+                reader.take(
+                    usize::from(length)
+                        .checked_sub(4)
+                        .ok_or(ParseError::InvalidSetLength { length })?,
+                )?,
+            );
+            let key = |template_id| TemplateKey {
+                exporter,
+                version: IPFIX,
+                domain,
+                template_id,
+            };
+
+            match set_id {
+                2 | 3 => {
+                    while set.remaining() >= 4 {
+                        let template_id = set.u16()?;
+                        let field_count = set.u16()?;
+                        if field_count == 0 {
+                            // Withdraws the template.
+                            self.templates.pop(&key(template_id));
+                            continue;
+                        }
+                        if set_id == 3 {
+                            let _scope_field_count = set.u16()?;
+                        }
+                        let fields = (0..field_count)
+                            .map(|_| read_ipfix_field(&mut set))
+                            .collect::<Result<_>>()?;
+                        let template = match set_id {
+                            2 => Template::Flows(fields),
+                            _ => Template::Options,
+                        };
+                        self.templates.put(key(template_id), template);
+                    }
+                }
+                template_id if template_id >= 256 => {
+                    let fields = match self.templates.get(&key(template_id)) {
+                        Some(Template::Flows(fields)) => fields,
+                        Some(Template::Options) => continue,
+                        None => {
+                            emit!(&NetflowTemplateMissing {
+                                exporter,
+                                template_id
+                            });
+                            continue;
+                        }
+                    };
+                    decode_records(&mut set, template_id, fields, &mut flows, |log| {
+                        log.insert("flow_type", "ipfix");
+                        log.insert("observation_domain_id", domain);
+                        log.insert(
+                            crate::config::log_schema().timestamp_key(),
+                            export_time(export_time_secs, 0),
+                        );
+                    })?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(flows)
+    }
+}
+
+fn export_time(secs: u32, nsecs: u32) -> DateTime<Utc> {
+    Utc.timestamp_opt(secs.into(), nsecs)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+fn read_v9_field(reader: &mut Reader) -> Result<TemplateField> {
+    Ok(TemplateField {
+        id: reader.u16()?,
+        enterprise: None,
+        length: reader.u16()?,
+    })
+}
+
+fn read_ipfix_field(reader: &mut Reader) -> Result<TemplateField> {
+    let id = reader.u16()?;
+    let length = reader.u16()?;
+    let enterprise = if id & 0x8000 != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    Ok(TemplateField {
+        id: id & 0x7fff,
+        enterprise,
+        length,
+    })
+}
+
+/// Decodes the records of a data set, which may end with padding shorter
+/// than a record.
+fn decode_records(
+    set: &mut Reader,
+    template_id: u16,
+    fields: &[TemplateField],
+    flows: &mut Vec<LogEvent>,
+    common: impl Fn(&mut LogEvent),
+) -> Result<()> {
+    let min_length = fields
+        .iter()
+        .map(|field| match field.length {
+            VARIABLE_LENGTH => 1,
+            length => usize::from(length),
+        })
+        .sum::<usize>();
+    if min_length == 0 {
+        return Err(ParseError::InvalidTemplate { template_id });
+    }
+
+    while set.remaining() >= min_length {
+        let mut log = LogEvent::default();
+        for field in fields {
+            let length = match field.length {
+                VARIABLE_LENGTH => match set.u8()? {
+                    255 => usize::from(set.u16()?),
+                    length => usize::from(length),
+                },
+                length => usize::from(length),
+            };
+            field.insert(&mut log, set.take(length)?);
+        }
+        common(&mut log);
+        flows.push(log);
+    }
+
+    Ok(())
+}
+
+fn decode_v5(mut reader: Reader) -> Result<Vec<LogEvent>> {
+    let count = reader.u16()?;
+    let _sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let _sequence = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    let sampling = reader.u16()?;
+    let timestamp = export_time(unix_secs, unix_nsecs);
+
+    (0..count)
+        .map(|_| {
+            let mut log = LogEvent::default();
+            log.insert("flow_type", "netflow_v5");
+            log.insert("src_addr", reader.ipv4()?.to_string());
+            log.insert("dst_addr", reader.ipv4()?.to_string());
+            log.insert("next_hop", reader.ipv4()?.to_string());
+            log.insert("input_interface", reader.u16()?);
+            log.insert("output_interface", reader.u16()?);
+            log.insert("packets", reader.u32()?);
+            log.insert("bytes", reader.u32()?);
+            log.insert("first_switched", reader.u32()?);
+            log.insert("last_switched", reader.u32()?);
+            log.insert("src_port", reader.u16()?);
+            log.insert("dst_port", reader.u16()?);
+            let _pad = reader.u8()?;
+            log.insert("tcp_flags", reader.u8()?);
+            log.insert("protocol", reader.u8()?);
+            log.insert("tos", reader.u8()?);
+            log.insert("src_as", reader.u16()?);
+            log.insert("dst_as", reader.u16()?);
+            log.insert("src_mask", reader.u8()?);
+            log.insert("dst_mask", reader.u8()?);
+            let _pad = reader.u16()?;
+            log.insert("engine_type", engine_type);
+            log.insert("engine_id", engine_id);
+            // The top two bits are the sampling mode.
+            log.insert("sampling_interval", sampling & 0x3fff);
+            log.insert(crate::config::log_schema().timestamp_key(), timestamp);
+            Ok(log)
+        })
+        .collect()
+}
+
+fn decode_sflow(mut reader: Reader) -> Result<Vec<LogEvent>> {
+    let agent_address = match reader.u32()? {
+        1 => IpAddr::from(reader.ipv4()?),
+        2 => IpAddr::from(<[u8; 16]>::try_from(reader.take(16)?).unwrap()),
+        _ => return Err(ParseError::Truncated),
+    };
+    let _sub_agent_id = reader.u32()?;
+    let _sequence = reader.u32()?;
+    let _uptime = reader.u32()?;
+    let sample_count = reader.u32()?;
+
+    let mut flows = Vec::new();
+    for _ in 0..sample_count {
+        let format = reader.u32()?;
+        let length = reader.u32()?;
+        let mut sample = Reader::new(reader.take(length as usize)?);
+
+        // Only flow samples describe flows, counter samples describe
+        // interfaces.
+        let (sampling_rate, input, output) = match format {
+            1 => {
+                let _sequence = sample.u32()?;
+                let _source_id = sample.u32()?;
+                let sampling_rate = sample.u32()?;
+                let _sample_pool = sample.u32()?;
+                let _drops = sample.u32()?;
+                let input = sample.u32()? & 0x3fff_ffff;
+                let output = sample.u32()? & 0x3fff_ffff;
+                (sampling_rate, input, output)
+            }
+            3 => {
+                let _sequence = sample.u32()?;
+                let _source_id_type = sample.u32()?;
+                let _source_id_index = sample.u32()?;
+                let sampling_rate = sample.u32()?;
+                let _sample_pool = sample.u32()?;
+                let _drops = sample.u32()?;
+                let _input_format = sample.u32()?;
+                let input = sample.u32()?;
+                let _output_format = sample.u32()?;
+                let output = sample.u32()?;
+                (sampling_rate, input, output)
+            }
+            _ => continue,
+        };
+
+        let mut log = LogEvent::default();
+        log.insert("flow_type", "sflow");
+        log.insert("agent_address", agent_address.to_string());
+        log.insert("sampling_rate", sampling_rate);
+        log.insert("input_interface", input);
+        log.insert("output_interface", output);
+        log.insert(crate::config::log_schema().timestamp_key(), Utc::now());
+
+        let record_count = sample.u32()?;
+        for _ in 0..record_count {
+            let format = sample.u32()?;
+            let length = sample.u32()?;
+            let mut record = Reader::new(sample.take(length as usize)?);
+            match format {
+                1 => decode_sflow_raw_header(&mut record, &mut log)?,
+                3 => {
+                    log.insert("bytes", record.u32()?);
+                    log.insert("protocol", record.u32()?);
+                    log.insert("src_addr", record.ipv4()?.to_string());
+                    log.insert("dst_addr", record.ipv4()?.to_string());
+                    log.insert("src_port", record.u32()?);
+                    log.insert("dst_port", record.u32()?);
+                    log.insert("tcp_flags", record.u32()?);
+                    log.insert("tos", record.u32()?);
+                }
+                _ => {}
+            }
+        }
+        flows.push(log);
+    }
+
+    Ok(flows)
+}
+
+/// Decodes the Ethernet, IP and transport headers of a sampled packet, as far
+/// as it was captured.
+fn decode_sflow_raw_header(record: &mut Reader, log: &mut LogEvent) -> Result<()> {
+    const ETHERNET: u32 = 1;
+
+    let protocol = record.u32()?;
+    log.insert("bytes", record.u32()?);
+    log.insert("packets", 1);
+    let _stripped = record.u32()?;
+    let header_length = record.u32()?;
+    if protocol != ETHERNET {
+        return Ok(());
+    }
+    let mut header = Reader::new(record.take(header_length as usize)?);
+
+    // Headers are often cut short, keeping whatever was decoded.
+    let _ = decode_ethernet(&mut header, log);
+    Ok(())
+}
+
+fn decode_ethernet(header: &mut Reader, log: &mut LogEvent) -> Result<()> {
+    log.insert("dst_mac", mac(header.take(6)?));
+    log.insert("src_mac", mac(header.take(6)?));
+    let mut ether_type = header.u16()?;
+    if ether_type == 0x8100 {
+        log.insert("vlan", header.u16()? & 0x0fff);
+        ether_type = header.u16()?;
+    }
+
+    let (protocol, ip_options_length) = match ether_type {
+        0x0800 => {
+            let version_and_length = header.u8()?;
+            log.insert("ip_version", 4);
+            log.insert("tos", header.u8()?);
+            let _total_length = header.u16()?;
+            let _identification_and_fragment = header.u32()?;
+            let _ttl = header.u8()?;
+            let protocol = header.u8()?;
+            let _checksum = header.u16()?;
+            log.insert("src_addr", header.ipv4()?.to_string());
+            log.insert("dst_addr", header.ipv4()?.to_string());
+            let header_length = usize::from(version_and_length & 0x0f) * 4;
+            (protocol, header_length.saturating_sub(20))
+        }
+        0x86dd => {
+            let _version_class_label = header.u32()?;
+            let _payload_length = header.u16()?;
+            let protocol = header.u8()?;
+            let _hop_limit = header.u8()?;
+            let src = <[u8; 16]>::try_from(header.take(16)?).unwrap();
+            let dst = <[u8; 16]>::try_from(header.take(16)?).unwrap();
+            log.insert("ip_version", 6);
+            log.insert("src_addr", Ipv6Addr::from(src).to_string());
+            log.insert("dst_addr", Ipv6Addr::from(dst).to_string());
+            (protocol, 0)
+        }
+        _ => return Ok(()),
+    };
+    log.insert("protocol", protocol);
+
+    header.take(ip_options_length)?;
+    const TCP: u8 = 6;
+    const UDP: u8 = 17;
+    if protocol == TCP || protocol == UDP {
+        log.insert("src_port", header.u16()?);
+        log.insert("dst_port", header.u16()?);
+    }
+    if protocol == TCP {
+        let _sequence = header.u32()?;
+        let _acknowledgement = header.u32()?;
+        let _offset = header.u8()?;
+        log.insert("tcp_flags", header.u8()?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exporter() -> SocketAddr {
+        "192.0.2.1:2055".parse().unwrap()
+    }
+
+    fn v9_template_set() -> Vec<u8> {
+        let mut set = vec![0, 0, 0, 20, 1, 0, 0, 3];
+        // src_addr, dst_port and bytes.
+        set.extend_from_slice(&[0, 8, 0, 4, 0, 11, 0, 2, 0, 1, 0, 4]);
+        set
+    }
+
+    fn v9_data_set() -> Vec<u8> {
+        let mut set = vec![1, 0, 0, 16];
+        set.extend_from_slice(&[10, 0, 0, 1, 0, 53, 0, 0, 1, 0]);
+        // Padding.
+        set.extend_from_slice(&[0, 0]);
+        set
+    }
+
+    fn v9_datagram(sets: &[Vec<u8>]) -> Vec<u8> {
+        let mut datagram = vec![
+            0, 9, 0, 1, 0, 0, 0, 0, 0x61, 0x80, 0, 0, 0, 0, 0, 1, 0, 0, 0, 7,
+        ];
+        for set in sets {
+            datagram.extend_from_slice(set);
+        }
+        datagram
+    }
+
+    #[test]
+    fn decodes_netflow_v5() {
+        let mut datagram = vec![0, 5, 0, 1, 0, 0, 0, 0, 0x61, 0x80, 0, 0, 0, 0, 0, 0];
+        datagram.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 10]);
+        datagram.extend_from_slice(&[
+            10, 0, 0, 1, 10, 0, 0, 2, 10, 0, 0, 254, 0, 1, 0, 2, 0, 0, 0, 3, 0, 0, 1, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0x30, 0x39, 0, 80, 0, 0x12, 6, 0, 0, 0, 0, 0, 24, 24, 0, 0,
+        ]);
+
+        let flows = FlowDecoder::new(10).decode(exporter(), &datagram).unwrap();
+        assert_eq!(flows.len(), 1);
+        let flow = &flows[0];
+        assert_eq!(flow["flow_type"], "netflow_v5".into());
+        assert_eq!(flow["src_addr"], "10.0.0.1".into());
+        assert_eq!(flow["dst_addr"], "10.0.0.2".into());
+        assert_eq!(flow["packets"], 3.into());
+        assert_eq!(flow["bytes"], 256.into());
+        assert_eq!(flow["src_port"], 12345.into());
+        assert_eq!(flow["dst_port"], 80.into());
+        assert_eq!(flow["tcp_flags"], 0x12.into());
+        assert_eq!(flow["protocol"], 6.into());
+        assert_eq!(flow["sampling_interval"], 10.into());
+    }
+
+    #[test]
+    fn decodes_netflow_v9_with_cached_templates() {
+        let mut decoder = FlowDecoder::new(10);
+
+        // Data before its template is dropped.
+        let flows = decoder
+            .decode(exporter(), &v9_datagram(&[v9_data_set()]))
+            .unwrap();
+        assert!(flows.is_empty());
+
+        let flows = decoder
+            .decode(
+                exporter(),
+                &v9_datagram(&[v9_template_set(), v9_data_set()]),
+            )
+            .unwrap();
+        assert_eq!(flows.len(), 1);
+        assert_eq!(flows[0]["src_addr"], "10.0.0.1".into());
+        assert_eq!(flows[0]["dst_port"], 53.into());
+        assert_eq!(flows[0]["bytes"], 256.into());
+        assert_eq!(flows[0]["source_id"], 7.into());
+
+        let flows = decoder
+            .decode(exporter(), &v9_datagram(&[v9_data_set()]))
+            .unwrap();
+        assert_eq!(flows.len(), 1);
+
+        // Templates are per exporter.
+        let flows = decoder
+            .decode(
+                "192.0.2.2:2055".parse().unwrap(),
+                &v9_datagram(&[v9_data_set()]),
+            )
+            .unwrap();
+        assert!(flows.is_empty());
+    }
+
+    #[test]
+    fn decodes_ipfix_variable_length_and_enterprise_fields() {
+        let mut datagram = vec![0, 10, 0, 0, 0x61, 0x80, 0, 0, 0, 0, 0, 1, 0, 0, 0, 3];
+        // Template 256: dst_addr (IPv6), an enterprise field and a variable
+        // length field.
+        datagram.extend_from_slice(&[0, 2, 0, 24, 1, 0, 0, 3]);
+        datagram.extend_from_slice(&[0, 28, 0, 16]);
+        datagram.extend_from_slice(&[0x80, 12, 0, 2, 0, 0, 0, 9]);
+        datagram.extend_from_slice(&[0, 100, 0xff, 0xff]);
+        datagram.extend_from_slice(&[1, 0, 0, 26]);
+        datagram.extend_from_slice(&[0x20, 1, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        datagram.extend_from_slice(&[0, 42]);
+        datagram.extend_from_slice(&[3, 0xab, 0xcd, 0xef]);
+        let length = datagram.len() as u16;
+        datagram[2..4].copy_from_slice(&length.to_be_bytes());
+
+        let flows = FlowDecoder::new(10).decode(exporter(), &datagram).unwrap();
+        assert_eq!(flows.len(), 1);
+        let flow = &flows[0];
+        assert_eq!(flow["flow_type"], "ipfix".into());
+        assert_eq!(flow["dst_addr"], "2001:db8::1".into());
+        assert_eq!(flow["enterprise_9_field_12"], 42.into());
+        assert_eq!(flow["field_100"], 0xabcdef.into());
+        assert_eq!(flow["observation_domain_id"], 3.into());
+    }
+
+    #[test]
+    fn decodes_sflow_raw_packet_headers() {
+        let mut header = vec![0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 1, 0x81, 0, 0, 10, 0x08, 0];
+        header.extend_from_slice(&[0x45, 0, 0, 40, 0, 0, 0, 0, 64, 17, 0, 0]);
+        header.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2, 0x30, 0x39, 0, 53, 0, 8, 0, 0]);
+
+        let mut record = vec![0, 0, 0, 1, 0, 0, 5, 0xdc, 0, 0, 0, 4];
+        record.extend_from_slice(&(header.len() as u32).to_be_bytes());
+        record.extend_from_slice(&header);
+
+        let mut sample = vec![0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        sample.extend_from_slice(&[0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, 1]);
+        sample.extend_from_slice(&[0, 0, 0, 1]);
+        sample.extend_from_slice(&(record.len() as u32).to_be_bytes());
+        sample.extend_from_slice(&record);
+
+        let mut datagram = vec![0, 0, 0, 5, 0, 0, 0, 1, 192, 0, 2, 9, 0, 0, 0, 0];
+        datagram.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+        // A counter sample, which is skipped.
+        datagram.extend_from_slice(&[0, 0, 0, 2, 0, 0, 0, 0]);
+        datagram.extend_from_slice(&[0, 0, 0, 1]);
+        datagram.extend_from_slice(&(sample.len() as u32).to_be_bytes());
+        datagram.extend_from_slice(&sample);
+
+        let flows = FlowDecoder::new(10).decode(exporter(), &datagram).unwrap();
+        assert_eq!(flows.len(), 1);
+        let flow = &flows[0];
+        assert_eq!(flow["flow_type"], "sflow".into());
+        assert_eq!(flow["agent_address"], "192.0.2.9".into());
+        assert_eq!(flow["sampling_rate"], 256.into());
+        assert_eq!(flow["input_interface"], 3.into());
+        assert_eq!(flow["bytes"], 1500.into());
+        assert_eq!(flow["vlan"], 10.into());
+        assert_eq!(flow["src_mac"], "00:00:00:00:00:01".into());
+        assert_eq!(flow["src_addr"], "10.0.0.1".into());
+        assert_eq!(flow["protocol"], 17.into());
+        assert_eq!(flow["dst_port"], 53.into());
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        assert_eq!(
+            FlowDecoder::new(10).decode(exporter(), &[0, 7, 0, 0]),
+            Err(ParseError::UnsupportedVersion { version: 7 })
+        );
+        assert_eq!(
+            FlowDecoder::new(10).decode(exporter(), &[0, 9, 0]),
+            Err(ParseError::Truncated)
+        );
+    }
+}
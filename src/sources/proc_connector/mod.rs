@@ -0,0 +1,189 @@
+//! The `proc_connector` source, reporting process executions on the local
+//! Linux host.
+
+use crate::{
+    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{ProcConnectorEventReceived, ProcConnectorReadFailed},
+    shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
+    Pipeline,
+};
+use chrono::Utc;
+use futures::{stream, SinkExt};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, time::Duration};
+use vector_core::ByteSizeOf;
+
+mod netlink;
+
+use netlink::ProcConnector;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct ProcConnectorConfig {
+    pub proc_root: PathBuf,
+}
+
+impl Default for ProcConnectorConfig {
+    fn default() -> Self {
+        Self {
+            proc_root: PathBuf::from("/proc"),
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<ProcConnectorConfig>("proc_connector")
+}
+
+impl_generate_config_from_default!(ProcConnectorConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "proc_connector")]
+impl SourceConfig for ProcConnectorConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let connector = ProcConnector::open(self.proc_root.clone())
+            .map_err(|error| format!("Unable to subscribe to process events: {}", error))?;
+
+        Ok(Box::pin(run(connector, cx.out, cx.shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "proc_connector"
+    }
+}
+
+async fn run(
+    mut connector: ProcConnector,
+    out: Pipeline,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let mut out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+    let mut backoff = read_backoff();
+
+    loop {
+        let events = tokio::select! {
+            _ = &mut shutdown => break,
+            events = connector.next_events() => events,
+        };
+
+        match events {
+            Ok(events) => {
+                backoff = read_backoff();
+                let mut events = stream::iter(events.into_iter().map(|event| {
+                    let event = event.into_event();
+                    emit!(&ProcConnectorEventReceived {
+                        byte_size: event.size_of(),
+                    });
+                    Ok(event)
+                }));
+                out.send_all(&mut events).await?;
+            }
+            Err(error) => {
+                emit!(&ProcConnectorReadFailed { error });
+                // Errors reading the socket tend to persist, so wait before
+                // reading it again rather than spinning on them.
+                let delay = backoff.next().expect("Backoff never ends.");
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_backoff() -> ExponentialBackoff {
+    // Delays start at 100ms and double, up to 30 seconds.
+    ExponentialBackoff::from_millis(2)
+        .factor(50)
+        .max_delay(Duration::from_secs(30))
+}
+
+/// A process execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecEvent {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub uid: Option<u32>,
+    pub comm: String,
+    pub filename: Option<String>,
+    pub argv: Vec<String>,
+}
+
+impl ExecEvent {
+    fn into_event(self) -> Event {
+        let mut log = LogEvent::default();
+        log.insert(log_schema().source_type_key(), "proc_connector");
+        log.insert(log_schema().timestamp_key(), Utc::now());
+
+        log.insert(
+            log_schema().message_key(),
+            format!("exec {}", self.argv.join(" ")),
+        );
+        log.insert("event_type", "exec");
+        log.insert("pid", self.pid);
+        log.insert("ppid", self.ppid);
+        log.insert("uid", self.uid);
+        log.insert("comm", self.comm);
+        log.insert("filename", self.filename);
+        log.insert(
+            "argv",
+            self.argv.into_iter().map(Value::from).collect::<Vec<_>>(),
+        );
+
+        log.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ProcConnectorConfig>();
+    }
+
+    #[test]
+    fn read_backoff_is_capped() {
+        let delays = read_backoff().take(12).collect::<Vec<_>>();
+        assert_eq!(delays[0], Duration::from_millis(100));
+        assert_eq!(delays[1], Duration::from_millis(200));
+        assert_eq!(delays[11], Duration::from_secs(30));
+    }
+
+    #[test]
+    fn exec_event_fields() {
+        let event = ExecEvent {
+            pid: 1234,
+            ppid: Some(1),
+            uid: Some(1000),
+            comm: "curl".into(),
+            filename: Some("/usr/bin/curl".into()),
+            argv: vec!["curl".into(), "https://example.com".into()],
+        }
+        .into_event();
+        let log = event.as_log();
+
+        assert_eq!(log["event_type"], "exec".into());
+        assert_eq!(log["pid"], 1234.into());
+        assert_eq!(log["ppid"], 1.into());
+        assert_eq!(log["uid"], 1000.into());
+        assert_eq!(log["comm"], "curl".into());
+        assert_eq!(log["filename"], "/usr/bin/curl".into());
+        assert_eq!(log["argv[1]"], "https://example.com".into());
+        assert_eq!(
+            log[log_schema().message_key()],
+            "exec curl https://example.com".into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "proc_connector".into());
+    }
+}
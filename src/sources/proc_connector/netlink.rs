@@ -0,0 +1,302 @@
+//! A reader of the Linux process events connector.
+//!
+//! The proc connector is available on practically every kernel, and reports
+//! process lifecycle events, of which the source reports executions.
+//! Subscribing to it requires `CAP_NET_ADMIN`.
+//!
+//! See `include/uapi/linux/cn_proc.h` and `include/uapi/linux/connector.h`
+//! for the wire format.
+
+use super::ExecEvent;
+use std::{
+    convert::TryInto,
+    io, mem,
+    os::unix::io::{AsRawFd, RawFd},
+    path::{Path, PathBuf},
+};
+use tokio::io::unix::AsyncFd;
+
+const CN_IDX_PROC: u32 = 0x1;
+const CN_VAL_PROC: u32 = 0x1;
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const PROC_CN_MCAST_IGNORE: u32 = 2;
+const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+
+const NLMSG_HDRLEN: usize = mem::size_of::<libc::nlmsghdr>();
+// struct cn_msg { struct cb_id id; __u32 seq; __u32 ack; __u16 len; __u16 flags; }
+const CN_MSG_LEN: usize = 20;
+// struct proc_event { __u32 what; __u32 cpu; __u64 timestamp_ns; union { ... } }
+const PROC_EVENT_HEADER_LEN: usize = 16;
+
+/// A socket subscribed to the process events multicast group.
+pub struct ProcConnector {
+    fd: AsyncFd<Socket>,
+    proc_root: PathBuf,
+}
+
+struct Socket(RawFd);
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        // Best effort, the kernel stops sending events once the socket is
+        // closed either way.
+        let _ = send_control(self.0, PROC_CN_MCAST_IGNORE);
+        unsafe { libc::close(self.0) };
+    }
+}
+
+impl ProcConnector {
+    /// Open a netlink connector socket and subscribe to process events.
+    pub fn open(proc_root: PathBuf) -> io::Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                libc::NETLINK_CONNECTOR,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let socket = Socket(fd);
+
+        let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        address.nl_pid = 0; // let the kernel assign the port id
+        address.nl_groups = CN_IDX_PROC;
+        let result = unsafe {
+            libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        send_control(fd, PROC_CN_MCAST_LISTEN)?;
+
+        Ok(Self {
+            fd: AsyncFd::new(socket)?,
+            proc_root,
+        })
+    }
+
+    /// Wait for the next batch of process events.
+    pub async fn next_events(&mut self) -> io::Result<Vec<ExecEvent>> {
+        let mut buf = [0u8; 4096];
+        loop {
+            let mut guard = self.fd.readable().await?;
+            let result = guard.try_io(|fd| {
+                let read = unsafe {
+                    libc::recv(
+                        fd.as_raw_fd(),
+                        buf.as_mut_ptr() as *mut libc::c_void,
+                        buf.len(),
+                        0,
+                    )
+                };
+                if read < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(read as usize)
+                }
+            });
+            let read = match result {
+                Ok(result) => result?,
+                Err(_would_block) => continue,
+            };
+
+            return Ok(parse_messages(&buf[..read])
+                .into_iter()
+                .filter_map(|(pid, tgid)| {
+                    // Only report the thread group leaders, threads calling
+                    // `exec` are reported through their leader.
+                    if pid != tgid {
+                        return None;
+                    }
+                    read_exec_event(&self.proc_root, pid)
+                })
+                .collect());
+        }
+    }
+}
+
+fn send_control(fd: RawFd, op: u32) -> io::Result<()> {
+    let payload_len = CN_MSG_LEN + mem::size_of::<u32>();
+    let total_len = NLMSG_HDRLEN + payload_len;
+    let mut buf = Vec::with_capacity(total_len);
+
+    // struct nlmsghdr
+    buf.extend_from_slice(&(total_len as u32).to_ne_bytes());
+    buf.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+    buf.extend_from_slice(&0u16.to_ne_bytes()); // flags
+    buf.extend_from_slice(&0u32.to_ne_bytes()); // seq
+    buf.extend_from_slice(&std::process::id().to_ne_bytes());
+    // struct cn_msg
+    buf.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+    buf.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+    buf.extend_from_slice(&0u32.to_ne_bytes()); // seq
+    buf.extend_from_slice(&0u32.to_ne_bytes()); // ack
+    buf.extend_from_slice(&(mem::size_of::<u32>() as u16).to_ne_bytes());
+    buf.extend_from_slice(&0u16.to_ne_bytes()); // flags
+                                                // enum proc_cn_mcast_op
+    buf.extend_from_slice(&op.to_ne_bytes());
+
+    let sent = unsafe { libc::send(fd, buf.as_ptr() as *const libc::c_void, buf.len(), 0) };
+    if sent < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset + 4)
+        .map(|bytes| u32::from_ne_bytes(bytes.try_into().expect("slice is 4 bytes")))
+}
+
+/// Extract the `(pid, tgid)` pairs of the exec events from a datagram, which
+/// may contain multiple netlink messages.
+fn parse_messages(mut buf: &[u8]) -> Vec<(u32, u32)> {
+    let mut execs = Vec::new();
+    while buf.len() >= NLMSG_HDRLEN {
+        let len = read_u32(buf, 0).unwrap_or(0) as usize;
+        if len < NLMSG_HDRLEN || len > buf.len() {
+            break;
+        }
+
+        let event = &buf[NLMSG_HDRLEN..len];
+        if let Some(event) = event.get(CN_MSG_LEN..) {
+            if read_u32(event, 0) == Some(PROC_EVENT_EXEC) {
+                // struct exec_proc_event { pid_t process_pid; pid_t process_tgid; }
+                if let (Some(pid), Some(tgid)) = (
+                    read_u32(event, PROC_EVENT_HEADER_LEN),
+                    read_u32(event, PROC_EVENT_HEADER_LEN + 4),
+                ) {
+                    execs.push((pid, tgid));
+                }
+            }
+        }
+
+        // Messages are aligned to 4 bytes.
+        let aligned = (len + 3) & !3;
+        buf = buf.get(aligned..).unwrap_or_default();
+    }
+    execs
+}
+
+/// Collect the details of an `exec`ed process from `procfs`. The process may
+/// be gone by the time we look, in which case there's nothing to report.
+fn read_exec_event(proc_root: &Path, pid: u32) -> Option<ExecEvent> {
+    let dir = proc_root.join(pid.to_string());
+    let comm = std::fs::read_to_string(dir.join("comm")).ok()?;
+    let cmdline = std::fs::read(dir.join("cmdline")).ok()?;
+    let status = std::fs::read_to_string(dir.join("status")).ok()?;
+    let filename = std::fs::read_link(dir.join("exe"))
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned());
+
+    Some(ExecEvent {
+        pid,
+        ppid: parse_status_field(&status, "PPid:"),
+        uid: parse_status_field(&status, "Uid:"),
+        comm: comm.trim_end().to_owned(),
+        filename,
+        argv: parse_cmdline(&cmdline),
+    })
+}
+
+fn parse_cmdline(cmdline: &[u8]) -> Vec<String> {
+    cmdline
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+/// Parse the first numeric value of a `/proc/<pid>/status` field. For `Uid:`
+/// that is the real user ID.
+fn parse_status_field(status: &str, field: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(field))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn exec_message(pid: u32, tgid: u32) -> Vec<u8> {
+        let payload_len = CN_MSG_LEN + PROC_EVENT_HEADER_LEN + 8;
+        let total_len = NLMSG_HDRLEN + payload_len;
+        let mut buf = vec![0u8; total_len];
+        buf[0..4].copy_from_slice(&(total_len as u32).to_ne_bytes());
+        let event = NLMSG_HDRLEN + CN_MSG_LEN;
+        buf[event..event + 4].copy_from_slice(&PROC_EVENT_EXEC.to_ne_bytes());
+        let data = event + PROC_EVENT_HEADER_LEN;
+        buf[data..data + 4].copy_from_slice(&pid.to_ne_bytes());
+        buf[data + 4..data + 8].copy_from_slice(&tgid.to_ne_bytes());
+        buf
+    }
+
+    #[test]
+    fn parses_exec_messages() {
+        let mut buf = exec_message(42, 42);
+        buf.extend(exec_message(43, 42));
+        assert_eq!(parse_messages(&buf), vec![(42, 42), (43, 42)]);
+    }
+
+    #[test]
+    fn ignores_other_and_truncated_messages() {
+        let mut fork = exec_message(42, 42);
+        let event = NLMSG_HDRLEN + CN_MSG_LEN;
+        fork[event..event + 4].copy_from_slice(&1u32.to_ne_bytes());
+        assert!(parse_messages(&fork).is_empty());
+
+        let exec = exec_message(42, 42);
+        assert!(parse_messages(&exec[..exec.len() - 1]).is_empty());
+    }
+
+    #[test]
+    fn parses_cmdline() {
+        assert_eq!(
+            parse_cmdline(b"/bin/ls\0-la\0/tmp\0"),
+            vec!["/bin/ls", "-la", "/tmp"]
+        );
+        assert!(parse_cmdline(b"").is_empty());
+    }
+
+    #[test]
+    fn reads_exec_event_from_procfs() {
+        let root = temp_dir();
+        let dir = root.join("1234");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("comm"), "curl\n").unwrap();
+        std::fs::write(dir.join("cmdline"), b"curl\0https://example.com\0").unwrap();
+        std::fs::write(
+            dir.join("status"),
+            "Name:\tcurl\nPPid:\t1\nUid:\t1000\t1000\t1000\t1000\n",
+        )
+        .unwrap();
+
+        let event = read_exec_event(&root, 1234).unwrap();
+        assert_eq!(event.pid, 1234);
+        assert_eq!(event.ppid, Some(1));
+        assert_eq!(event.uid, Some(1000));
+        assert_eq!(event.comm, "curl");
+        assert_eq!(event.argv, vec!["curl", "https://example.com"]);
+
+        assert!(read_exec_event(&root, 4321).is_none());
+    }
+}
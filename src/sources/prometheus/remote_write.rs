@@ -5,7 +5,11 @@ use crate::{
     internal_events::PrometheusRemoteWriteParseError,
     sources::{
         self,
-        util::{decode, ErrorMessage, HttpSource, HttpSourceAuthConfig},
+        util::{
+            decode,
+            http_server::{Http2Config, HttpKeepaliveConfig},
+            ErrorMessage, HttpSource, HttpSourceAuthConfig,
+        },
     },
     tls::TlsConfig,
 };
@@ -47,7 +51,18 @@ impl GenerateConfig for PrometheusRemoteWriteConfig {
 impl SourceConfig for PrometheusRemoteWriteConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
         let source = RemoteWriteSource;
-        source.run(self.address, "", true, &self.tls, &self.auth, cx)
+        source.run(
+            self.address,
+            "",
+            true,
+            &self.tls,
+            &self.auth,
+            &None,
+            &None,
+            &Http2Config::default(),
+            &HttpKeepaliveConfig::default(),
+            cx,
+        )
     }
 
     fn output_type(&self) -> crate::config::DataType {
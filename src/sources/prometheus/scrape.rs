@@ -567,6 +567,7 @@ mod test {
                 buckets: vec![1.0, 2.0, 4.0],
                 quantiles: vec![],
                 flush_period_secs: 1,
+                multi_value_tags: None,
             },
         );
 
@@ -108,6 +108,7 @@ impl SourceConfig for SocketConfig {
                     config.shutdown_timeout_secs(),
                     tls,
                     config.receive_buffer_bytes(),
+                    config.connection_limits(),
                     cx.shutdown,
                     cx.out,
                 )
@@ -614,6 +615,7 @@ mod test {
                 globals: GlobalOptions::default(),
                 shutdown: shutdown_signal,
                 out: sender,
+                named_outputs: Default::default(),
                 acknowledgements: false,
                 proxy: Default::default(),
             })
@@ -701,6 +703,43 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn udp_gelf_chunked_message() {
+        let (tx, rx) = Pipeline::new_test();
+        let address = next_addr();
+
+        let config: SocketConfig = toml::from_str(&format!(
+            r#"
+            mode = "udp"
+            address = "{}"
+            framing.method = "chunked_gelf"
+            decoding.codec = "gelf"
+            "#,
+            address
+        ))
+        .unwrap();
+        let server = config.build(SourceContext::new_test(tx)).await.unwrap();
+        tokio::spawn(server);
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let message = r#"{"version":"1.1","host":"example.org","short_message":"foo","_bar":1}"#;
+        let (first, second) = message.split_at(message.len() / 2);
+        // Chunks start with the magic bytes, the message ID, then the sequence
+        // number and count.
+        let chunk = |sequence_number: u8, data: &str| {
+            let mut chunk = vec![0x1e, 0x0f, 0, 0, 0, 0, 0, 0, 0, 42, sequence_number, 2];
+            chunk.extend_from_slice(data.as_bytes());
+            String::from_utf8(chunk).unwrap()
+        };
+        send_lines_udp(address, vec![chunk(1, second), chunk(0, first)]);
+        let events = collect_n(rx, 1).await;
+
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "foo".into());
+        assert_eq!(log[log_schema().host_key()], "example.org".into());
+        assert_eq!(log["_bar"], 1.into());
+    }
+
     #[tokio::test]
     async fn udp_shutdown_simple() {
         let (tx, rx) = Pipeline::new_test();
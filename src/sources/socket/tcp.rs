@@ -1,10 +1,10 @@
 use crate::{
-    codecs::{self, FramingConfig, ParserConfig},
+    codecs::{self, FramingConfig, ParserSettings},
     config::log_schema,
     event::Event,
     internal_events::{SocketEventsReceived, SocketMode},
     serde::default_decoding,
-    sources::util::{SocketListenAddr, TcpSource},
+    sources::util::{SocketListenAddr, TcpConnectionLimits, TcpSource},
     tcp::TcpKeepaliveConfig,
     tls::TlsConfig,
 };
@@ -31,11 +31,14 @@ pub struct TcpConfig {
     tls: Option<TlsConfig>,
     #[get_copy = "pub"]
     receive_buffer_bytes: Option<usize>,
+    #[serde(default)]
+    #[get_copy = "pub"]
+    connection_limits: TcpConnectionLimits,
     #[getset(get = "pub", set = "pub")]
     framing: Option<Box<dyn FramingConfig>>,
     #[serde(default = "default_decoding")]
     #[getset(get = "pub", set = "pub")]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 const fn default_shutdown_timeout_secs() -> u64 {
@@ -52,7 +55,7 @@ impl TcpConfig {
         tls: Option<TlsConfig>,
         receive_buffer_bytes: Option<usize>,
         framing: Option<Box<dyn FramingConfig>>,
-        decoding: Box<dyn ParserConfig>,
+        decoding: ParserSettings,
     ) -> Self {
         Self {
             address,
@@ -62,6 +65,7 @@ impl TcpConfig {
             host_key,
             tls,
             receive_buffer_bytes,
+            connection_limits: TcpConnectionLimits::default(),
             framing,
             decoding,
         }
@@ -76,6 +80,7 @@ impl TcpConfig {
             host_key: None,
             tls: None,
             receive_buffer_bytes: None,
+            connection_limits: TcpConnectionLimits::default(),
             framing: None,
             decoding: default_decoding(),
         }
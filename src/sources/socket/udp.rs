@@ -1,5 +1,5 @@
 use crate::{
-    codecs::{self, Decoder, FramingConfig, ParserConfig},
+    codecs::{self, Decoder, FramingConfig, ParserSettings},
     config::log_schema,
     event::Event,
     internal_events::{SocketEventsReceived, SocketMode, SocketReceiveError},
@@ -35,7 +35,7 @@ pub struct UdpConfig {
     framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
     #[get = "pub"]
-    decoding: Box<dyn ParserConfig>,
+    decoding: ParserSettings,
 }
 
 impl UdpConfig {
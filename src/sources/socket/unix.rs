@@ -1,5 +1,5 @@
 use crate::{
-    codecs::{Decoder, FramingConfig, ParserConfig},
+    codecs::{Decoder, FramingConfig, ParserSettings},
     config::log_schema,
     event::Event,
     internal_events::{SocketEventsReceived, SocketMode},
@@ -25,7 +25,7 @@ pub struct UnixConfig {
     #[serde(default)]
     pub framing: Option<Box<dyn FramingConfig>>,
     #[serde(default = "default_decoding")]
-    pub decoding: Box<dyn ParserConfig>,
+    pub decoding: ParserSettings,
 }
 
 impl UnixConfig {
@@ -1,10 +1,18 @@
 use crate::{
     config::{log_schema, DataType, Resource, SourceConfig, SourceContext, SourceDescription},
-    event::{Event, LogEvent, Value},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
     internal_events::{
         EventsReceived, HttpBytesReceived, SplunkHecRequestBodyInvalidError, SplunkHecRequestError,
         SplunkHecRequestReceived,
     },
+    sources::util::{
+        http_server::{self, Http2Config, HttpKeepaliveConfig},
+        DuplicateRequest, Idempotency, IdempotencyClaim, IdempotencyConfig, Quota, QuotaConfig,
+        QuotaExceeded, QuotaIdentity, QuotaPermit,
+    },
     tls::{MaybeTlsSettings, TlsConfig},
     Pipeline,
 };
@@ -17,14 +25,16 @@ use serde::{Deserialize, Serialize};
 use serde_json::{de::Read as JsonRead, json, Deserializer, Value as JsonValue};
 use snafu::Snafu;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     future,
     io::Read,
     net::{Ipv4Addr, SocketAddr},
 };
 use vector_core::ByteSizeOf;
 
-use warp::{filters::BoxedFilter, path, reject::Rejection, reply::Response, Filter, Reply};
+use warp::{
+    filters::BoxedFilter, http::HeaderMap, path, reject::Rejection, reply::Response, Filter, Reply,
+};
 
 // Event fields unique to splunk_hec source
 pub const CHANNEL: &str = "splunk_channel";
@@ -32,6 +42,11 @@ pub const INDEX: &str = "splunk_index";
 pub const SOURCE: &str = "splunk_source";
 pub const SOURCETYPE: &str = "splunk_sourcetype";
 
+// Fields of metric events
+const METRIC_NAME: &str = "metric_name";
+const METRIC_NAME_PREFIX: &str = "metric_name:";
+const METRIC_VALUE: &str = "_value";
+
 /// Accepts HTTP requests.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields, default)]
@@ -43,7 +58,17 @@ pub struct SplunkConfig {
     token: Option<String>,
     /// A list of tokens to accept. Omit this to accept any token
     valid_tokens: Option<Vec<String>>,
+    /// Limits the events and bytes accepted per token or peer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quota: Option<QuotaConfig>,
+    /// Drops retransmissions of requests that were already accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idempotency: Option<IdempotencyConfig>,
     tls: Option<TlsConfig>,
+    http2: Http2Config,
+    keepalive: HttpKeepaliveConfig,
+    /// Reject requests whose data channel isn't a GUID, as Splunk does
+    validate_channel: bool,
 }
 
 inventory::submit! {
@@ -68,7 +93,12 @@ impl Default for SplunkConfig {
             address: default_socket_address(),
             token: None,
             valid_tokens: None,
+            quota: None,
+            idempotency: None,
             tls: None,
+            http2: Http2Config::default(),
+            keepalive: HttpKeepaliveConfig::default(),
+            validate_channel: false,
         }
     }
 }
@@ -82,7 +112,17 @@ fn default_socket_address() -> SocketAddr {
 impl SourceConfig for SplunkConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
-        let source = SplunkSource::new(self, tls.http_protocol_name());
+        let quota = self
+            .quota
+            .as_ref()
+            .map(|quota| quota.build(QuotaIdentity::AuthToken))
+            .transpose()?;
+        let idempotency = self
+            .idempotency
+            .as_ref()
+            .map(IdempotencyConfig::build)
+            .transpose()?;
+        let source = SplunkSource::new(self, tls.http_protocol_name(), quota, idempotency);
 
         let event_service = source.event_service(cx.out.clone());
         let raw_service = source.raw_service(cx.out);
@@ -110,24 +150,31 @@ impl SourceConfig for SplunkConfig {
             )
             .or_else(finish_err);
 
-        let listener = tls.bind(&self.address).await?;
+        let listener = http_server::bind(&tls, &self.address, &self.http2).await?;
 
+        let http2 = self.http2.clone();
+        let keepalive = self.keepalive.clone();
         let shutdown = cx.shutdown;
         Ok(Box::pin(async move {
             let span = crate::trace::current_span();
-            warp::serve(services.with(warp::trace(move |_info| span.clone())))
-                .serve_incoming_with_graceful_shutdown(
-                    listener.accept_stream(),
-                    shutdown.map(|_| ()),
-                )
-                .await;
+            http_server::serve(
+                services
+                    .with(warp::trace(move |_info| span.clone()))
+                    .boxed(),
+                listener,
+                &http2,
+                &keepalive,
+                None,
+                shutdown.map(|_| ()),
+            )
+            .await;
 
             Ok(())
         }))
     }
 
     fn output_type(&self) -> DataType {
-        DataType::Log
+        DataType::Any
     }
 
     fn source_type(&self) -> &'static str {
@@ -143,10 +190,18 @@ impl SourceConfig for SplunkConfig {
 struct SplunkSource {
     valid_credentials: Vec<String>,
     protocol: &'static str,
+    validate_channel: bool,
+    quota: Option<Quota>,
+    idempotency: Option<Idempotency>,
 }
 
 impl SplunkSource {
-    fn new(config: &SplunkConfig, protocol: &'static str) -> Self {
+    fn new(
+        config: &SplunkConfig,
+        protocol: &'static str,
+        quota: Option<Quota>,
+        idempotency: Option<Idempotency>,
+    ) -> Self {
         let valid_tokens = config
             .valid_tokens
             .iter()
@@ -157,35 +212,36 @@ impl SplunkSource {
                 .map(|token| format!("Splunk {}", token))
                 .collect(),
             protocol,
+            validate_channel: config.validate_channel,
+            quota,
+            idempotency,
         }
     }
 
     fn event_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
-        let splunk_channel_query_param = warp::query::<HashMap<String, String>>()
-            .map(|qs: HashMap<String, String>| qs.get("channel").map(|v| v.to_owned()));
-        let splunk_channel_header = warp::header::optional::<String>("x-splunk-request-channel");
-
-        let splunk_channel = splunk_channel_header
-            .and(splunk_channel_query_param)
-            .map(|header: Option<String>, query_param| header.or(query_param));
-
         let protocol = self.protocol;
+        let quota = self.quota.clone();
+        let idempotency = self.idempotency.clone();
         warp::post()
             .and(path!("event").or(path!("event" / "1.0")))
             .and(self.authorization())
-            .and(splunk_channel)
-            .and(warp::addr::remote())
+            .and(warp::header::optional::<String>("Authorization"))
+            .and(self.channel())
+            .and(http_server::remote_addr())
             .and(warp::header::optional::<String>("X-Forwarded-For"))
             .and(self.gzip())
+            .and(warp::header::headers_cloned())
             .and(warp::body::bytes())
             .and(warp::path::full())
             .and_then(
                 move |_,
                       _,
+                      token: Option<String>,
                       channel: Option<String>,
                       remote: Option<SocketAddr>,
                       xff: Option<String>,
                       gzip: bool,
+                      headers: HeaderMap,
                       body: Bytes,
                       path: warp::path::FullPath| {
                     let mut out = out
@@ -196,27 +252,40 @@ impl SplunkSource {
                         http_path: path.as_str(),
                         protocol,
                     });
+                    let claim = claim_key(&idempotency, path.as_str(), &headers, &body);
+                    let permit = admit(&quota, token, remote, &body);
                     async move {
+                        let claim = claim?;
+                        let permit = permit?;
                         let reader: Box<dyn Read + Send> = if gzip {
                             Box::new(MultiGzDecoder::new(body.reader()))
                         } else {
                             Box::new(body.reader())
                         };
 
+                        let mut count = 0;
                         let events = stream::iter(EventIterator::new(
                             Deserializer::from_reader(reader).into_iter::<JsonValue>(),
                             channel,
                             remote,
                             xff,
-                        ));
+                        ))
+                        .inspect(|event| count += event.is_ok() as usize);
 
                         // `fn send_all` can be used once https://github.com/rust-lang/futures-rs/issues/2402
                         // is resolved.
                         let res = events.forward(&mut out).await;
+                        if let Some(permit) = permit {
+                            permit.consume_events(count);
+                        }
 
                         out.flush().await?;
 
-                        res
+                        res.map(|()| {
+                            if let Some(claim) = claim {
+                                claim.accept();
+                            }
+                        })
                     }
                 },
             )
@@ -225,35 +294,37 @@ impl SplunkSource {
     }
 
     fn raw_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
-        let splunk_channel_query_param = warp::query::<HashMap<String, String>>()
-            .map(|qs: HashMap<String, String>| qs.get("channel").map(|v| v.to_owned()));
-        let splunk_channel_header = warp::header::optional::<String>("x-splunk-request-channel");
-
-        let splunk_channel = splunk_channel_header
-            .and(splunk_channel_query_param)
-            .and_then(|header: Option<String>, query_param| async move {
-                header
-                    .or(query_param)
-                    .ok_or_else(|| Rejection::from(ApiError::MissingChannel))
+        let splunk_channel = self
+            .channel()
+            .and_then(|channel: Option<String>| async move {
+                channel.ok_or_else(|| Rejection::from(ApiError::MissingChannel))
             });
 
         let protocol = self.protocol;
+        let quota = self.quota.clone();
+        let idempotency = self.idempotency.clone();
         warp::post()
             .and(path!("raw" / "1.0").or(path!("raw")))
             .and(self.authorization())
+            .and(warp::header::optional::<String>("Authorization"))
             .and(splunk_channel)
-            .and(warp::addr::remote())
+            .and(warp::query::<HashMap<String, String>>())
+            .and(http_server::remote_addr())
             .and(warp::header::optional::<String>("X-Forwarded-For"))
             .and(self.gzip())
+            .and(warp::header::headers_cloned())
             .and(warp::body::bytes())
             .and(warp::path::full())
             .and_then(
                 move |_,
                       _,
+                      token: Option<String>,
                       channel: String,
+                      params: HashMap<String, String>,
                       remote: Option<SocketAddr>,
                       xff: Option<String>,
                       gzip: bool,
+                      headers: HeaderMap,
                       body: Bytes,
                       path: warp::path::FullPath| {
                     let out = out.clone();
@@ -262,13 +333,25 @@ impl SplunkSource {
                         http_path: path.as_str(),
                         protocol,
                     });
+                    let claim = claim_key(&idempotency, path.as_str(), &headers, &body);
+                    let permit = admit(&quota, token, remote, &body);
                     async move {
-                        let event = future::ready(raw_event(body, gzip, channel, remote, xff));
+                        let claim = claim?;
+                        let permit = permit?;
+                        let event =
+                            future::ready(raw_event(body, gzip, channel, params, remote, xff));
                         futures::stream::once(event)
                             .forward(
                                 out.sink_map_err(|_| Rejection::from(ApiError::ServerShutdown)),
                             )
-                            .map_ok(|_| ())
+                            .map_ok(|_| {
+                                if let Some(permit) = permit {
+                                    permit.consume_events(1);
+                                }
+                                if let Some(claim) = claim {
+                                    claim.accept();
+                                }
+                            })
                             .await
                     }
                 },
@@ -317,6 +400,25 @@ impl SplunkSource {
         post.or(get).unify().boxed()
     }
 
+    /// Data channel of the request, from the `x-splunk-request-channel`
+    /// header or the `channel` query parameter, in that order
+    fn channel(&self) -> BoxedFilter<(Option<String>,)> {
+        let validate = self.validate_channel;
+        warp::header::optional::<String>("x-splunk-request-channel")
+            .and(warp::query::<HashMap<String, String>>())
+            .and_then(
+                move |header: Option<String>, mut query: HashMap<String, String>| async move {
+                    match header.or_else(|| query.remove("channel")) {
+                        Some(channel) if validate && !is_guid(&channel) => {
+                            Err(Rejection::from(ApiError::InvalidChannel))
+                        }
+                        channel => Ok(channel),
+                    }
+                },
+            )
+            .boxed()
+    }
+
     /// Authorize request
     fn authorization(&self) -> BoxedFilter<((),)> {
         let valid_credentials = self.valid_credentials.clone();
@@ -348,6 +450,41 @@ impl SplunkSource {
             .boxed()
     }
 }
+
+/// Claims the idempotency key of a request, if retransmissions are dropped
+fn claim_key(
+    idempotency: &Option<Idempotency>,
+    path: &str,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Result<Option<IdempotencyClaim>, Rejection> {
+    match idempotency {
+        Some(idempotency) => idempotency
+            .claim(path, headers, body)
+            .map_err(Rejection::from),
+        None => Ok(None),
+    }
+}
+
+/// Admits a request within the quota of its token or peer, if any
+fn admit(
+    quota: &Option<Quota>,
+    token: Option<String>,
+    remote: Option<SocketAddr>,
+    body: &Bytes,
+) -> Result<Option<QuotaPermit>, Rejection> {
+    quota
+        .as_ref()
+        .map(|quota| {
+            let token = token
+                .as_deref()
+                .map(|token| token.trim_start_matches("Splunk "));
+            quota.admit(token, remote, body.len())
+        })
+        .transpose()
+        .map_err(Rejection::from)
+}
+
 /// Constructs one or more events from json-s coming from reader.
 /// If errors, it's done with input.
 struct EventIterator<'de, R: JsonRead<'de>> {
@@ -355,6 +492,8 @@ struct EventIterator<'de, R: JsonRead<'de>> {
     deserializer: serde_json::StreamDeserializer<'de, R, JsonValue>,
     /// Count of sent events
     events: usize,
+    /// Metrics built from the last metric event that are yet to be sent
+    metrics: VecDeque<Event>,
     /// Optional channel from headers
     channel: Option<Value>,
     /// Default time
@@ -373,6 +512,7 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
         EventIterator {
             deserializer,
             events: 0,
+            metrics: VecDeque::new(),
             channel: channel.map(Value::from),
             time: Time::Now(Utc::now()),
             extractors: [
@@ -395,6 +535,10 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
     }
 
     fn build_event(&mut self, mut json: JsonValue) -> Result<Event, Rejection> {
+        if is_metric_event(&json) {
+            return self.build_metrics(json);
+        }
+
         // Construct Event from parsed json event
         let mut event = Event::new_empty_log();
         let log = event.as_mut_log();
@@ -452,7 +596,103 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
             }
         }
 
-        // Process time field
+        // Add time field
+        let time = self.parse_time(&mut json)?;
+        log.insert(log_schema().timestamp_key(), time);
+
+        // Extract default extracted fields
+        for de in self.extractors.iter_mut() {
+            de.extract(log, &mut json);
+        }
+
+        emit!(&EventsReceived {
+            count: 1,
+            byte_size: event.size_of(),
+        });
+        self.events += 1;
+
+        Ok(event)
+    }
+    /// Builds a metric for each measurement of a metric event, either the
+    /// `metric_name` and `_value` fields or `metric_name:<name>` fields
+    /// holding the values, with the other fields as tags.
+    fn build_metrics(&mut self, mut json: JsonValue) -> Result<Event, Rejection> {
+        let fields = match json.get_mut("fields").map(JsonValue::take) {
+            Some(JsonValue::Object(fields)) => fields,
+            _ => return Err(ApiError::InvalidDataFormat { event: self.events }.into()),
+        };
+
+        let time = self.parse_time(&mut json)?;
+
+        let mut tags = Vec::new();
+        for de in self.extractors.iter_mut() {
+            if let Some(value) = de.update(&mut json) {
+                tags.push((de.to_field.to_owned(), value.to_string_lossy()));
+            }
+        }
+        match json.get_mut("channel").map(JsonValue::take) {
+            Some(JsonValue::String(guid)) => tags.push((CHANNEL.to_owned(), guid)),
+            _ => {
+                if let Some(guid) = self.channel.as_ref() {
+                    tags.push((CHANNEL.to_owned(), guid.to_string_lossy()));
+                }
+            }
+        }
+
+        let mut measurements = Vec::new();
+        let mut name = None;
+        let mut value = None;
+        for (key, field) in fields {
+            if let Some(metric_name) = key.strip_prefix(METRIC_NAME_PREFIX) {
+                measurements.push((metric_name.to_owned(), field));
+            } else if key == METRIC_NAME {
+                name = Some(field);
+            } else if key == METRIC_VALUE {
+                value = Some(field);
+            } else {
+                match field {
+                    JsonValue::Array(values) => tags.extend(
+                        values
+                            .into_iter()
+                            .filter_map(json_tag_value)
+                            .map(|value| (key.clone(), value)),
+                    ),
+                    field => tags.extend(json_tag_value(field).map(|value| (key, value))),
+                }
+            }
+        }
+        match (name, value) {
+            (Some(JsonValue::String(name)), Some(value)) => measurements.push((name, value)),
+            (None, None) => (),
+            _ => return Err(ApiError::InvalidDataFormat { event: self.events }.into()),
+        }
+
+        for (name, value) in measurements {
+            let value = json_metric_value(&value)
+                .ok_or(ApiError::InvalidDataFormat { event: self.events })?;
+            let mut metric = Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+                .with_timestamp(Some(time));
+            for (key, value) in &tags {
+                metric.add_tag_value(key.clone(), value.clone());
+            }
+            self.metrics.push_back(metric.into());
+        }
+
+        emit!(&EventsReceived {
+            count: self.metrics.len(),
+            byte_size: self.metrics.iter().map(ByteSizeOf::size_of).sum(),
+        });
+        let event = self.events;
+        self.events += 1;
+
+        self.metrics
+            .pop_front()
+            .ok_or_else(|| ApiError::InvalidDataFormat { event }.into())
+    }
+
+    /// Parses the time field of the event, falling back to the time of the
+    /// previous event of the request.
+    fn parse_time(&mut self, json: &mut JsonValue) -> Result<DateTime<Utc>, Rejection> {
         let parsed_time = match json.get_mut("time").map(JsonValue::take) {
             Some(JsonValue::Number(time)) => Some(Some(time)),
             Some(JsonValue::String(time)) => Some(time.parse::<serde_json::Number>().ok()),
@@ -478,24 +718,9 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
             Some(None) => return Err(ApiError::InvalidDataFormat { event: self.events }.into()),
         }
 
-        // Add time field
-        match self.time.clone() {
-            Time::Provided(time) => log.insert(log_schema().timestamp_key(), time),
-            Time::Now(time) => log.insert(log_schema().timestamp_key(), time),
-        };
-
-        // Extract default extracted fields
-        for de in self.extractors.iter_mut() {
-            de.extract(log, &mut json);
+        match self.time {
+            Time::Provided(time) | Time::Now(time) => Ok(time),
         }
-
-        emit!(&EventsReceived {
-            count: 1,
-            byte_size: event.size_of(),
-        });
-        self.events += 1;
-
-        Ok(event)
     }
 }
 
@@ -503,6 +728,10 @@ impl<'de, R: JsonRead<'de>> Iterator for EventIterator<'de, R> {
     type Item = Result<Event, Rejection>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(metric) = self.metrics.pop_front() {
+            return Some(Ok(metric));
+        }
+
         match self.deserializer.next() {
             Some(Ok(json)) => Some(self.build_event(json)),
             None => {
@@ -556,6 +785,45 @@ fn parse_timestamp(t: i64) -> Option<DateTime<Utc>> {
     Some(ts)
 }
 
+/// Whether the event is in the metrics format, with the measurements in its
+/// fields rather than its event field.
+fn is_metric_event(json: &JsonValue) -> bool {
+    json.get("fields")
+        .and_then(JsonValue::as_object)
+        .map_or(false, |fields| {
+            fields
+                .keys()
+                .any(|key| key == METRIC_NAME || key.starts_with(METRIC_NAME_PREFIX))
+        })
+}
+
+/// Measurements can be numbers or numeric strings.
+fn json_metric_value(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(number) => number.as_f64(),
+        JsonValue::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Dimensions are strings, other scalars are converted and nulls are dropped.
+fn json_tag_value(value: JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::String(string) => Some(string),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Whether the channel is a GUID such as `0C4FA8AC-5CF6-4E3A-B5D9-5CA2C1F9B2BF`.
+fn is_guid(channel: &str) -> bool {
+    channel.len() == 36
+        && channel.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
 /// Maintains last known extracted value of field and uses it in the absence of field.
 struct DefaultExtractor {
     field: &'static str,
@@ -584,14 +852,18 @@ impl DefaultExtractor {
         }
     }
 
-    fn extract(&mut self, log: &mut LogEvent, value: &mut JsonValue) {
-        // Process json_field
+    /// Updates the last known value from json_field, returning it.
+    fn update(&mut self, value: &mut JsonValue) -> Option<&Value> {
         if let Some(JsonValue::String(new_value)) = value.get_mut(self.field).map(JsonValue::take) {
             self.value = Some(new_value.into());
         }
 
+        self.value.as_ref()
+    }
+
+    fn extract(&mut self, log: &mut LogEvent, value: &mut JsonValue) {
         // Add data field
-        if let Some(index) = self.value.as_ref() {
+        if let Some(index) = self.update(value) {
             log.insert(self.to_field, index.clone());
         }
     }
@@ -611,6 +883,7 @@ fn raw_event(
     bytes: Bytes,
     gzip: bool,
     channel: String,
+    mut params: HashMap<String, String>,
     remote: Option<SocketAddr>,
     xff: Option<String>,
 ) -> Result<Event, Rejection> {
@@ -640,16 +913,32 @@ fn raw_event(
     log.insert(CHANNEL, channel);
 
     // host-field priority for raw endpoint:
-    // - x-forwarded-for is set to `host` field first, if present. If not present:
+    // - the `host` query parameter is set to `host` field first, if present. If not present:
+    // - x-forwarded-for is set to `host` field, if present. If not present:
     // - set remote addr to host field
-    if let Some(remote_address) = xff {
-        log.insert(log_schema().host_key(), remote_address);
+    if let Some(host) = params.remove("host").or(xff) {
+        log.insert(log_schema().host_key(), host);
     } else if let Some(remote) = remote {
         log.insert(log_schema().host_key(), remote.to_string());
     }
 
+    // Add the metadata given as query parameters
+    for (param, field) in [
+        ("index", INDEX),
+        ("source", SOURCE),
+        ("sourcetype", SOURCETYPE),
+    ] {
+        if let Some(value) = params.remove(param) {
+            log.insert(field, value);
+        }
+    }
+
     // Add timestamp
-    log.insert(log_schema().timestamp_key(), Utc::now());
+    let time = match params.get("time") {
+        Some(time) => parse_raw_time(time).ok_or(ApiError::InvalidDataFormat { event: 0 })?,
+        None => Utc::now(),
+    };
+    log.insert(log_schema().timestamp_key(), time);
 
     // Add source type
     event
@@ -664,12 +953,26 @@ fn raw_event(
     Ok(event)
 }
 
+/// Parses the `time` query parameter of the raw endpoint, epoch seconds with
+/// optional fractional part.
+fn parse_raw_time(time: &str) -> Option<DateTime<Utc>> {
+    match time.parse::<i64>() {
+        Ok(t) => parse_timestamp(t),
+        Err(_) => time
+            .parse::<f64>()
+            .ok()
+            .filter(|t| t.is_finite() && *t >= 0.0)
+            .map(|t| Utc.timestamp(t.floor() as i64, (t.fract() * 1_000_000_000.0) as u32)),
+    }
+}
+
 #[derive(Clone, Copy, Debug, Snafu)]
 pub(crate) enum ApiError {
     MissingAuthorization,
     InvalidAuthorization,
     UnsupportedEncoding,
     MissingChannel,
+    InvalidChannel,
     NoData,
     InvalidDataFormat { event: usize },
     ServerShutdown,
@@ -705,6 +1008,8 @@ mod splunk_response {
             json_to_bytes(json!({"text":"unsupported content encoding"}));
         pub static ref NO_CHANNEL: Bytes =
             json_to_bytes(json!({"text":"Data channel is missing","code":10}));
+        pub static ref INVALID_CHANNEL: Bytes =
+            json_to_bytes(json!({"text":"Invalid data channel","code":11}));
     }
 }
 
@@ -732,6 +1037,10 @@ async fn finish_err(rejection: Rejection) -> Result<(Response,), Rejection> {
                 StatusCode::BAD_REQUEST,
                 splunk_response::NO_CHANNEL.as_ref(),
             ),
+            ApiError::InvalidChannel => response_json(
+                StatusCode::BAD_REQUEST,
+                splunk_response::INVALID_CHANNEL.as_ref(),
+            ),
             ApiError::NoData => {
                 response_json(StatusCode::BAD_REQUEST, splunk_response::NO_DATA.as_ref())
             }
@@ -748,6 +1057,16 @@ async fn finish_err(rejection: Rejection) -> Result<(Response,), Rejection> {
             }
             ApiError::BadRequest => empty_response(StatusCode::BAD_REQUEST),
         },))
+    } else if let Some(exceeded) = rejection.find::<QuotaExceeded>() {
+        Ok((exceeded.reply(),))
+    } else if let Some(duplicate) = rejection.find::<DuplicateRequest>() {
+        // Retransmissions of accepted requests get the reply the requests
+        // got when they were accepted.
+        Ok((if duplicate.in_flight() {
+            duplicate.reply()
+        } else {
+            finish_ok(())
+        },))
     } else {
         Err(rejection)
     }
@@ -791,7 +1110,10 @@ mod tests {
     use super::{parse_timestamp, SplunkConfig};
     use crate::{
         config::{log_schema, SinkConfig, SinkContext, SourceConfig, SourceContext},
-        event::Event,
+        event::{
+            metric::{MetricKind, MetricValue},
+            Event,
+        },
         sinks::{
             splunk_hec::logs::{Encoding, HecSinkLogsConfig},
             util::{encoding::EncodingConfig, BatchConfig, Compression, TowerRequestConfig},
@@ -825,23 +1147,27 @@ mod tests {
         token: Option<String>,
         valid_tokens: Option<&[&str]>,
     ) -> (mpsc::Receiver<Event>, SocketAddr) {
-        components::init_test();
-        let (sender, recv) = Pipeline::new_test();
-        let address = next_addr();
         let valid_tokens =
             valid_tokens.map(|tokens| tokens.iter().map(|&token| String::from(token)).collect());
+        source_from(SplunkConfig {
+            token,
+            valid_tokens,
+            ..SplunkConfig::on(next_addr())
+        })
+        .await
+    }
+
+    async fn source_from(config: SplunkConfig) -> (mpsc::Receiver<Event>, SocketAddr) {
+        components::init_test();
+        let (sender, recv) = Pipeline::new_test();
+        let address = config.address;
         tokio::spawn(async move {
-            SplunkConfig {
-                address,
-                token,
-                valid_tokens,
-                tls: None,
-            }
-            .build(SourceContext::new_test(sender))
-            .await
-            .unwrap()
-            .await
-            .unwrap()
+            config
+                .build(SourceContext::new_test(sender))
+                .await
+                .unwrap()
+                .await
+                .unwrap()
         });
         wait_for_tcp(address).await;
         (recv, address)
@@ -1170,6 +1496,38 @@ mod tests {
         assert_eq!(event.as_log()[log_schema().host_key()], "10.0.0.1".into());
     }
 
+    // Without a `host` field or an x-forwarded-for header, the address of the peer is the `host`
+    #[tokio::test]
+    async fn peer_address_is_host_without_xff_header() {
+        let (source, address) = source().await;
+
+        let opts = SendWithOpts {
+            channel: Some(Channel::Header("guid")),
+            forwarded_for: None,
+        };
+
+        assert_eq!(
+            200,
+            send_with(address, "services/collector/raw", "raw", TOKEN, &opts).await
+        );
+        assert_eq!(
+            200,
+            send_with(
+                address,
+                "services/collector/event",
+                r#"{"event":"first"}"#,
+                TOKEN,
+                &opts
+            )
+            .await
+        );
+
+        for event in collect_n(source, 2).await {
+            let host = event.as_log()[log_schema().host_key()].to_string_lossy();
+            assert!(host.starts_with("127.0.0.1:"), "unexpected host {:?}", host);
+        }
+    }
+
     #[tokio::test]
     async fn channel_query_param() {
         let message = "raw";
@@ -1190,6 +1548,181 @@ mod tests {
         assert_eq!(event.as_log()[&super::CHANNEL], "guid".into());
     }
 
+    #[tokio::test]
+    async fn raw_query_params() {
+        let message = "raw";
+        let (source, address) = source().await;
+
+        assert_eq!(
+            200,
+            post(
+                address,
+                "services/collector/raw?host=web-1&source=app&sourcetype=access&index=main&time=1638366107",
+                message
+            )
+            .await
+        );
+
+        let event = collect_n(source, 1).await.remove(0);
+        SOURCE_TESTS.assert(&HTTP_PUSH_SOURCE_TAGS);
+        let log = event.as_log();
+        assert_eq!(log[log_schema().host_key()], "web-1".into());
+        assert_eq!(log[&super::SOURCE], "app".into());
+        assert_eq!(log[&super::SOURCETYPE], "access".into());
+        assert_eq!(log[&super::INDEX], "main".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1638366107, 0).into()
+        );
+    }
+
+    #[tokio::test]
+    async fn validated_channel() {
+        let (source, address) = source_from(SplunkConfig {
+            validate_channel: true,
+            token: Some(TOKEN.to_owned()),
+            ..SplunkConfig::on(next_addr())
+        })
+        .await;
+        let guid = "0C4FA8AC-5CF6-4E3A-B5D9-5CA2C1F9B2BF";
+
+        let opts = SendWithOpts {
+            channel: Some(Channel::Header("guid")),
+            forwarded_for: None,
+        };
+        assert_eq!(
+            400,
+            send_with(address, "services/collector/raw", "raw", TOKEN, &opts).await
+        );
+
+        let opts = SendWithOpts {
+            channel: Some(Channel::QueryParam(guid)),
+            forwarded_for: None,
+        };
+        assert_eq!(
+            200,
+            send_with(address, "services/collector/raw", "raw", TOKEN, &opts).await
+        );
+
+        let event = collect_n(source, 1).await.remove(0);
+        assert_eq!(event.as_log()[&super::CHANNEL], guid.into());
+    }
+
+    #[tokio::test]
+    async fn quota_exceeded() {
+        let (source, address) = source_from(SplunkConfig {
+            quota: Some(toml::from_str("identity = \"auth_token\"\nmax_events = 1").unwrap()),
+            ..SplunkConfig::on(next_addr())
+        })
+        .await;
+
+        let opts = SendWithOpts {
+            channel: Some(Channel::Header("channel")),
+            forwarded_for: None,
+        };
+        let api = "services/collector/raw";
+        assert_eq!(200, send_with(address, api, "first", "a", &opts).await);
+        assert_eq!(429, send_with(address, api, "second", "a", &opts).await);
+        assert_eq!(200, send_with(address, api, "third", "b", &opts).await);
+
+        let events = collect_n(source, 2).await;
+        assert_eq!(
+            events[1].as_log()[log_schema().message_key()],
+            "third".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_retransmitted_requests() {
+        let (source, address) = source_from(SplunkConfig {
+            idempotency: Some(toml::from_str("key = \"body_hash\"").unwrap()),
+            ..SplunkConfig::on(next_addr())
+        })
+        .await;
+
+        let api = "services/collector/event";
+        assert_eq!(200, post(address, api, r#"{"event":"first"}"#).await);
+        assert_eq!(200, post(address, api, r#"{"event":"first"}"#).await);
+        assert_eq!(200, post(address, api, r#"{"event":"second"}"#).await);
+
+        let events = collect_n(source, 2).await;
+        assert_eq!(
+            events[1].as_log()[log_schema().message_key()],
+            "second".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn metric_event() {
+        let message = r#"{"time":1638366107,"event":"metric","host":"web-1","source":"disk","fields":{"path":"/dev/sda1","metric_name":"disk.total","_value":1099511627776}}"#;
+        let (source, address) = source().await;
+
+        assert_eq!(
+            200,
+            post(address, "services/collector/event", message).await
+        );
+
+        let event = collect_n(source, 1).await.remove(0);
+        SOURCE_TESTS.assert(&HTTP_PUSH_SOURCE_TAGS);
+        let metric = event.as_metric();
+        assert_eq!(metric.name(), "disk.total");
+        assert_eq!(metric.kind(), MetricKind::Absolute);
+        assert_eq!(
+            metric.value(),
+            &MetricValue::Gauge {
+                value: 1099511627776.0
+            }
+        );
+        assert_eq!(metric.timestamp(), Some(Utc.timestamp(1638366107, 0)));
+        assert_eq!(metric.tag_value("path"), Some("/dev/sda1".to_owned()));
+        assert_eq!(metric.tag_value("host"), Some("web-1".to_owned()));
+        assert_eq!(metric.tag_value(super::SOURCE), Some("disk".to_owned()));
+        assert_eq!(metric.tag_value(super::CHANNEL), Some("channel".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn multiple_metric_event() {
+        let message = r#"{"event":"metric","fields":{"region":"us-west-1","zones":["a","b"],"metric_name:cpu.usr":11.12,"metric_name:cpu.sys":"12.23"}}{"event":"second"}"#;
+        let (source, address) = source().await;
+
+        assert_eq!(
+            200,
+            post(address, "services/collector/event", message).await
+        );
+
+        let events = collect_n(source, 3).await;
+        SOURCE_TESTS.assert(&HTTP_PUSH_SOURCE_TAGS);
+        let mut metrics = events[..2]
+            .iter()
+            .map(|event| event.as_metric().clone())
+            .collect::<Vec<_>>();
+        metrics.sort_by(|a, b| a.name().cmp(b.name()));
+
+        assert_eq!(metrics[0].name(), "cpu.sys");
+        assert_eq!(metrics[0].value(), &MetricValue::Gauge { value: 12.23 });
+        assert_eq!(metrics[1].name(), "cpu.usr");
+        assert_eq!(metrics[1].value(), &MetricValue::Gauge { value: 11.12 });
+        for metric in &metrics {
+            assert_eq!(metric.tag_value("region"), Some("us-west-1".to_owned()));
+            assert_eq!(metric.tag_values("zones"), vec!["a", "b"]);
+        }
+        assert_eq!(
+            events[2].as_log()[log_schema().message_key()],
+            "second".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_metric_value() {
+        let message = r#"{"event":"metric","fields":{"metric_name":"cpu.usr","_value":"high"}}"#;
+        let (_source, address) = source().await;
+
+        assert_eq!(
+            400,
+            post(address, "services/collector/event", message).await
+        );
+    }
+
     #[tokio::test]
     async fn no_data() {
         let (_source, address) = source().await;
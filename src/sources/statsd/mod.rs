@@ -6,7 +6,7 @@ use crate::{
     event::Event,
     internal_events::{StatsdEventReceived, StatsdInvalidRecord, StatsdSocketError},
     shutdown::ShutdownSignal,
-    sources::util::{SocketListenAddr, TcpSource},
+    sources::util::{SocketListenAddr, TcpConnectionLimits, TcpSource},
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsSettings, TlsConfig},
     Pipeline,
@@ -60,6 +60,8 @@ struct TcpConfig {
     #[serde(default = "default_shutdown_timeout_secs")]
     shutdown_timeout_secs: u64,
     receive_buffer_bytes: Option<usize>,
+    #[serde(default)]
+    connection_limits: TcpConnectionLimits,
 }
 
 impl TcpConfig {
@@ -72,6 +74,7 @@ impl TcpConfig {
             tls: None,
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             receive_buffer_bytes: None,
+            connection_limits: TcpConnectionLimits::default(),
         }
     }
 }
@@ -109,6 +112,7 @@ impl SourceConfig for StatsdConfig {
                     config.shutdown_timeout_secs,
                     tls,
                     config.receive_buffer_bytes,
+                    config.connection_limits,
                     cx.shutdown,
                     cx.out,
                 )
@@ -327,6 +331,7 @@ mod test {
                 buckets: vec![1.0, 2.0, 4.0],
                 quantiles: vec![],
                 flush_period_secs: 1,
+                multi_value_tags: None,
             },
         );
 
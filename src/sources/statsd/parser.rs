@@ -2,7 +2,6 @@ use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{
-    collections::BTreeMap,
     error, fmt,
     num::{ParseFloatError, ParseIntError},
     str::Utf8Error,
@@ -54,7 +53,7 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
         None
     };
 
-    let metric = match metric_type {
+    let mut metric = match metric_type {
         "c" => {
             let val: f64 = parts[0].parse()?;
             Metric::new(
@@ -64,16 +63,17 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
                     value: val * sample_rate,
                 },
             )
-            .with_tags(tags)
         }
         unit @ "h" | unit @ "ms" | unit @ "d" => {
             let val: f64 = parts[0].parse()?;
             Metric::new(
-                name, MetricKind::Incremental, MetricValue::Distribution {
+                name,
+                MetricKind::Incremental,
+                MetricValue::Distribution {
                     samples: vector_core::samples![convert_to_base_units(unit, val) => sample_rate as u32],
                     statistic: convert_to_statistic(unit),
                 },
-            ).with_tags(tags)
+            )
         }
         "g" => {
             let value = if parts[0]
@@ -88,16 +88,14 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
             };
 
             match parse_direction(parts[0])? {
-                None => Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
-                    .with_tags(tags),
+                None => Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value }),
                 Some(sign) => Metric::new(
                     name,
                     MetricKind::Incremental,
                     MetricValue::Gauge {
                         value: value * sign,
                     },
-                )
-                .with_tags(tags),
+                ),
             }
         }
         "s" => Metric::new(
@@ -106,10 +104,13 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
             MetricValue::Set {
                 values: vec![parts[0].into()].into_iter().collect(),
             },
-        )
-        .with_tags(tags),
+        ),
         other => return Err(ParseError::UnknownMetricType(other.into())),
     };
+    // Tags that are repeated keep all their values
+    for (key, value) in tags.into_iter().flatten() {
+        metric.add_tag_value(key, value);
+    }
     Ok(metric)
 }
 
@@ -128,14 +129,14 @@ fn parse_sampling(input: &str) -> Result<f64, ParseError> {
     }
 }
 
-fn parse_tags(input: &str) -> Result<BTreeMap<String, String>, ParseError> {
+fn parse_tags(input: &str) -> Result<Vec<(String, String)>, ParseError> {
     if !input.starts_with('#') || input.len() < 2 {
         return Err(ParseError::Malformed(
             "expected non empty '#'-prefixed tags component",
         ));
     }
 
-    let mut result = BTreeMap::new();
+    let mut result = Vec::new();
 
     let chunks = input[1..].split(',').collect::<Vec<_>>();
     for chunk in chunks {
@@ -145,7 +146,7 @@ fn parse_tags(input: &str) -> Result<BTreeMap<String, String>, ParseError> {
         // if tag value is not provided, use "true"
         // https://github.com/influxdata/telegraf/blob/master/plugins/inputs/statsd/datadog.go#L152
         let value = pair.get(1).unwrap_or(&"true");
-        result.insert((*key).to_owned(), (*value).to_owned());
+        result.push(((*key).to_owned(), (*value).to_owned()));
     }
 
     Ok(result)
@@ -262,6 +263,14 @@ mod test {
         );
     }
 
+    #[test]
+    fn repeated_tags() {
+        let metric = parse("foo:1|c|#host_group:web,tag1,host_group:frontend").unwrap();
+        assert_eq!(metric.tag_values("host_group"), vec!["web", "frontend"]);
+        assert_eq!(metric.tag_values("tag1"), vec!["true"]);
+        assert_eq!(metric.tag_value("host_group"), Some("frontend".into()));
+    }
+
     #[test]
     fn sampled_counter() {
         assert_event_data_eq!(
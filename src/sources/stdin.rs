@@ -1,19 +1,27 @@
 use crate::{
-    codecs::{DecodingConfig, FramingConfig, ParserConfig},
+    codecs::{DecodingConfig, FramingConfig, ParserSettings},
     config::{log_schema, DataType, Resource, SourceConfig, SourceContext, SourceDescription},
-    internal_events::StdinEventsReceived,
+    event::{BatchNotifier, BatchStatus},
+    internal_events::{StdinEventsAcknowledgementFailed, StdinEventsReceived},
     serde::{default_decoding, default_framing_stream_based},
     shutdown::ShutdownSignal,
     sources::util::TcpError,
     Pipeline,
 };
 use async_stream::stream;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use chrono::Utc;
-use futures::{channel::mpsc, executor, SinkExt, StreamExt};
+use futures::{channel::mpsc, executor, stream::FuturesOrdered, FutureExt, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::{io, thread};
-use tokio_util::{codec::FramedRead, io::StreamReader};
+use tokio_util::{
+    codec::{Decoder, FramedRead},
+    io::StreamReader,
+};
+
+/// The number of frames whose events may be in flight at once when
+/// acknowledgements are enabled, before the source stops reading its input.
+const MAX_PENDING_FRAMES: usize = 128;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields, default)]
@@ -21,10 +29,11 @@ pub struct StdinConfig {
     #[serde(default = "crate::serde::default_max_length")]
     pub max_length: usize,
     pub host_key: Option<String>,
+    pub offset_key: Option<String>,
     #[serde(default = "default_framing_stream_based")]
     pub framing: Box<dyn FramingConfig>,
     #[serde(default = "default_decoding")]
-    pub decoding: Box<dyn ParserConfig>,
+    pub decoding: ParserSettings,
 }
 
 impl Default for StdinConfig {
@@ -32,6 +41,7 @@ impl Default for StdinConfig {
         StdinConfig {
             max_length: crate::serde::default_max_length(),
             host_key: Default::default(),
+            offset_key: Default::default(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
         }
@@ -53,6 +63,7 @@ impl SourceConfig for StdinConfig {
             self.clone(),
             cx.shutdown,
             cx.out,
+            cx.acknowledgements,
         )
     }
 
@@ -74,6 +85,7 @@ pub fn stdin_source<R>(
     config: StdinConfig,
     shutdown: ShutdownSignal,
     out: Pipeline,
+    acknowledgements: bool,
 ) -> crate::Result<super::Source>
 where
     R: Send + io::BufRead + 'static,
@@ -81,6 +93,7 @@ where
     let host_key = config
         .host_key
         .unwrap_or_else(|| log_schema().host_key().to_string());
+    let offset_key = config.offset_key;
     let hostname = crate::get_hostname().ok();
     let decoder = DecodingConfig::new(config.framing.clone(), config.decoding.clone()).build()?;
 
@@ -116,19 +129,40 @@ where
             out.sink_map_err(|error| error!(message = "Unable to send event to out.", %error));
 
         let stream = StreamReader::new(receiver);
-        let mut stream = FramedRead::new(stream, decoder).take_until(shutdown);
+        let mut stream = FramedRead::new(stream, OffsetDecoder::new(decoder)).take_until(shutdown);
         let result = stream! {
+            let mut pending = FuturesOrdered::new();
+            let mut acknowledged_offset = 0;
+
             loop {
+                // Stop reading the input while too many frames wait for
+                // acknowledgement, so that the writer of the pipe is blocked
+                // rather than having its output pile up in Vector.
+                while pending.len() >= MAX_PENDING_FRAMES {
+                    if let Some((status, offset)) = pending.next().await {
+                        acknowledged_offset = acknowledge(status, offset);
+                    }
+                }
+
                 match stream.next().await {
-                    Some(Ok((events, byte_size))) => {
+                    Some(Ok(((events, byte_size), offset))) => {
                         emit!(&StdinEventsReceived {
                             byte_size,
                             count: events.len()
                         });
 
                         let now = Utc::now();
+                        let batch = acknowledgements.then(|| {
+                            let (batch, receiver) = BatchNotifier::new_with_receiver();
+                            pending.push(receiver.map(move |status| (status, offset)));
+                            batch
+                        });
 
                         for mut event in events {
+                            if let Some(batch) = &batch {
+                                event = event.with_batch_notifier(batch);
+                            }
+
                             let log = event.as_mut_log();
 
                             log.try_insert(log_schema().source_type_key(), Bytes::from("stdin"));
@@ -138,6 +172,10 @@ where
                                 log.try_insert(&host_key, hostname.clone());
                             }
 
+                            if let Some(offset_key) = &offset_key {
+                                log.try_insert(offset_key.as_str(), offset as i64);
+                            }
+
                             yield event;
                         }
                     }
@@ -151,6 +189,14 @@ where
                     None => break,
                 }
             }
+
+            while let Some((status, offset)) = pending.next().await {
+                acknowledged_offset = acknowledge(status, offset);
+            }
+
+            if acknowledgements {
+                debug!(message = "Acknowledged input.", offset = acknowledged_offset);
+            }
         }
         .map(Ok)
         .forward(&mut out)
@@ -164,11 +210,67 @@ where
     }))
 }
 
+/// Reports a failed delivery of the events of a frame, returning the offset
+/// the input was acknowledged up to.
+fn acknowledge(status: BatchStatus, offset: u64) -> u64 {
+    if status != BatchStatus::Delivered {
+        emit!(&StdinEventsAcknowledgementFailed { status, offset });
+    }
+    offset
+}
+
+/// Wraps a decoder to pair each of its frames with the byte offset in the input
+/// just past that frame, i.e. where reading would resume once the frame has
+/// been processed.
+struct OffsetDecoder<D> {
+    inner: D,
+    offset: u64,
+}
+
+impl<D> OffsetDecoder<D> {
+    const fn new(inner: D) -> Self {
+        Self { inner, offset: 0 }
+    }
+
+    /// Advances the offset by the bytes the inner decoder consumed from
+    /// `src`, including those of discarded frames.
+    fn track<T, E>(
+        &mut self,
+        src: &BytesMut,
+        len: usize,
+        result: Result<Option<T>, E>,
+    ) -> Result<Option<(T, u64)>, E> {
+        self.offset += (len - src.len()) as u64;
+        let offset = self.offset;
+        result.map(|frame| frame.map(|frame| (frame, offset)))
+    }
+}
+
+impl<D: Decoder> Decoder for OffsetDecoder<D> {
+    type Item = (D::Item, u64);
+    type Error = D::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let len = src.len();
+        let result = self.inner.decode(src);
+        self.track(src, len, result)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let len = src.len();
+        let result = self.inner.decode_eof(src);
+        self.track(src, len, result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{test_util::trace_init, Pipeline};
-    use std::io::Cursor;
+    use crate::{
+        codecs::LengthDelimitedDecoderConfig, event::Value, test_util::trace_init, Pipeline,
+    };
+    use std::{io::Cursor, time::Duration};
+    use tokio::time::timeout;
 
     #[test]
     fn generate_config() {
@@ -183,7 +285,7 @@ mod tests {
         let config = StdinConfig::default();
         let buf = Cursor::new("hello world\nhello world again");
 
-        stdin_source(buf, config, ShutdownSignal::noop(), tx)
+        stdin_source(buf, config, ShutdownSignal::noop(), tx, false)
             .unwrap()
             .await
             .unwrap();
@@ -205,4 +307,69 @@ mod tests {
         let event = stream.next().await;
         assert!(event.is_none());
     }
+
+    #[tokio::test]
+    async fn stdin_tags_length_delimited_frames_with_offset() {
+        trace_init();
+
+        let (tx, rx) = Pipeline::new_test();
+        let config = StdinConfig {
+            offset_key: Some("offset".into()),
+            framing: Box::new(LengthDelimitedDecoderConfig::default()),
+            ..Default::default()
+        };
+        let buf = Cursor::new("\x00\x00\x00\x03foo\x00\x00\x00\x05hello");
+
+        stdin_source(buf, config, ShutdownSignal::noop(), tx, false)
+            .unwrap()
+            .await
+            .unwrap();
+
+        let events = rx
+            .map(|event| {
+                let log = event.as_log();
+                (
+                    log[log_schema().message_key()].to_string_lossy(),
+                    log["offset"].clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            events,
+            vec![
+                ("foo".into(), Value::Integer(7)),
+                ("hello".into(), Value::Integer(16)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stdin_waits_for_acknowledgements() {
+        trace_init();
+
+        let (tx, mut rx) = Pipeline::new_test();
+        let config = StdinConfig {
+            offset_key: Some("offset".into()),
+            ..Default::default()
+        };
+        let buf = Cursor::new("hello\nworld");
+
+        let mut source =
+            tokio::spawn(stdin_source(buf, config, ShutdownSignal::noop(), tx, true).unwrap());
+
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            events.push(rx.next().await.unwrap());
+        }
+        assert_eq!(events[0].as_log()["offset"], Value::Integer(6));
+        assert_eq!(events[1].as_log()["offset"], Value::Integer(11));
+        // The source holds on until the events are delivered.
+        assert!(timeout(Duration::from_millis(100), &mut source)
+            .await
+            .is_err());
+
+        drop(events);
+        source.await.unwrap().unwrap();
+    }
 }
@@ -10,7 +10,7 @@ use crate::{
     internal_events::SyslogEventReceived,
     internal_events::SyslogUdpReadError,
     shutdown::ShutdownSignal,
-    sources::util::{SocketListenAddr, TcpSource},
+    sources::util::{SocketListenAddr, TcpConnection, TcpConnectionLimits, TcpSource},
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsSettings, TlsConfig},
     udp, Pipeline,
@@ -48,6 +48,8 @@ pub enum Mode {
         keepalive: Option<TcpKeepaliveConfig>,
         tls: Option<TlsConfig>,
         receive_buffer_bytes: Option<usize>,
+        #[serde(default)]
+        connection_limits: TcpConnectionLimits,
     },
     Udp {
         address: SocketAddr,
@@ -79,6 +81,7 @@ impl GenerateConfig for SyslogConfig {
                 keepalive: None,
                 tls: None,
                 receive_buffer_bytes: None,
+                connection_limits: TcpConnectionLimits::default(),
             },
             host_key: None,
             max_length: crate::serde::default_max_length(),
@@ -102,6 +105,7 @@ impl SourceConfig for SyslogConfig {
                 keepalive,
                 tls,
                 receive_buffer_bytes,
+                connection_limits,
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
@@ -115,6 +119,7 @@ impl SourceConfig for SyslogConfig {
                     shutdown_secs,
                     tls,
                     receive_buffer_bytes,
+                    connection_limits,
                     cx.shutdown,
                     cx.out,
                 )
@@ -141,7 +146,12 @@ impl SourceConfig for SyslogConfig {
                     path,
                     decoder,
                     move |events, host, byte_size| {
-                        handle_events(events, &host_key, host, byte_size)
+                        let received_from = ReceivedFrom {
+                            transport: Some("unix"),
+                            host,
+                            ..ReceivedFrom::default()
+                        };
+                        handle_events(events, &host_key, received_from, byte_size)
                     },
                     cx.shutdown,
                     cx.out,
@@ -186,8 +196,20 @@ impl TcpSource for SyslogTcpSource {
         )
     }
 
-    fn handle_events(&self, events: &mut [Event], host: Bytes, byte_size: usize) {
-        handle_events(events, &self.host_key, Some(host), byte_size);
+    fn handle_connection_events(
+        &self,
+        events: &mut [Event],
+        connection: &TcpConnection,
+        byte_size: usize,
+    ) {
+        let received_from = ReceivedFrom {
+            transport: Some(if connection.tls { "tls" } else { "tcp" }),
+            host: Some(connection.host.clone()),
+            source_port: Some(connection.peer_addr.port()),
+            listen_port: connection.local_addr.map(|addr| addr.port()),
+            tls_server_name: connection.tls_server_name.clone(),
+        };
+        handle_events(events, &self.host_key, received_from, byte_size);
     }
 }
 
@@ -218,6 +240,9 @@ pub fn udp(
             r#type = "udp"
         );
 
+        // The bound port differs from the configured one when that is 0.
+        let listen_port = socket.local_addr().map(|addr| addr.port()).ok();
+
         UdpFramed::new(
             socket,
             codecs::Decoder::new(Box::new(BytesCodec::new()), Box::new(SyslogParser)),
@@ -227,9 +252,15 @@ pub fn udp(
             let host_key = host_key.clone();
             async move {
                 match frame {
-                    Ok(((mut events, byte_size), received_from)) => {
-                        let received_from = received_from.ip().to_string().into();
-                        handle_events(&mut events, &host_key, Some(received_from), byte_size);
+                    Ok(((mut events, byte_size), peer_addr)) => {
+                        let received_from = ReceivedFrom {
+                            transport: Some("udp"),
+                            host: Some(peer_addr.ip().to_string().into()),
+                            source_port: Some(peer_addr.port()),
+                            listen_port,
+                            tls_server_name: None,
+                        };
+                        handle_events(&mut events, &host_key, received_from, byte_size);
                         Some(events.remove(0))
                     }
                     Err(error) => {
@@ -246,10 +277,21 @@ pub fn udp(
     })
 }
 
+/// The listener and peer a message was received from, which tells apart
+/// the devices sending to different listeners of the same source.
+#[derive(Debug, Default)]
+struct ReceivedFrom {
+    transport: Option<&'static str>,
+    host: Option<Bytes>,
+    source_port: Option<u16>,
+    listen_port: Option<u16>,
+    tls_server_name: Option<String>,
+}
+
 fn handle_events(
     events: &mut [Event],
     host_key: &str,
-    default_host: Option<Bytes>,
+    received_from: ReceivedFrom,
     byte_size: usize,
 ) {
     assert_eq!(
@@ -258,22 +300,35 @@ fn handle_events(
         "Syslog parser parses exactly one message from a byte string.",
     );
 
-    enrich_syslog_event(&mut events[0], host_key, default_host, byte_size);
+    enrich_syslog_event(&mut events[0], host_key, received_from, byte_size);
 }
 
 fn enrich_syslog_event(
     event: &mut Event,
     host_key: &str,
-    default_host: Option<Bytes>,
+    received_from: ReceivedFrom,
     byte_size: usize,
 ) {
     let log = event.as_mut_log();
 
     log.insert(log_schema().source_type_key(), Bytes::from("syslog"));
 
+    let default_host = received_from.host;
     if let Some(default_host) = &default_host {
         log.insert("source_ip", default_host.clone());
     }
+    if let Some(transport) = received_from.transport {
+        log.insert("transport", transport);
+    }
+    if let Some(source_port) = received_from.source_port {
+        log.insert("source_port", i64::from(source_port));
+    }
+    if let Some(listen_port) = received_from.listen_port {
+        log.insert("listen_port", i64::from(listen_port));
+    }
+    if let Some(tls_server_name) = received_from.tls_server_name {
+        log.insert("tls_server_name", tls_server_name);
+    }
 
     let parsed_hostname = log.get("hostname").map(|hostname| hostname.as_bytes());
     if let Some(parsed_host) = parsed_hostname.or(default_host) {
@@ -309,7 +364,11 @@ mod test {
         let byte_size = bytes.len();
         let parser = SyslogParser;
         let mut events = parser.parse(bytes).ok()?;
-        handle_events(&mut events, host_key, default_host, byte_size);
+        let received_from = ReceivedFrom {
+            host: default_host,
+            ..ReceivedFrom::default()
+        };
+        handle_events(&mut events, host_key, received_from, byte_size);
         Some(events.remove(0))
     }
 
@@ -670,4 +729,26 @@ mod test {
             expected
         );
     }
+
+    #[test]
+    fn adds_received_from_metadata() {
+        let raw = r#"<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - - i am foobar"#;
+        let mut events = SyslogParser.parse(raw.into()).unwrap();
+        let received_from = ReceivedFrom {
+            transport: Some("tls"),
+            host: Some("10.0.0.1".into()),
+            source_port: Some(50514),
+            listen_port: Some(6514),
+            tls_server_name: Some("firewalls.example.com".to_owned()),
+        };
+        handle_events(&mut events, "host", received_from, raw.len());
+
+        let log = events[0].as_log();
+        assert_eq!(log["source_ip"], "10.0.0.1".into());
+        assert_eq!(log["transport"], "tls".into());
+        assert_eq!(log["source_port"], 50514.into());
+        assert_eq!(log["listen_port"], 6514.into());
+        assert_eq!(log["tls_server_name"], "firewalls.example.com".into());
+        assert_eq!(log["host"], "74794bfb6795".into());
+    }
 }
@@ -0,0 +1,371 @@
+//! Deduplication of requests retransmitted to push-based sources.
+//!
+//! Clients retry requests that timed out, even when the source did accept
+//! them, which duplicates their events downstream. Requests are identified by
+//! an idempotency key, taken either from a header or from a hash of their
+//! body, and a request whose key was already accepted within `ttl_secs` is
+//! replied to with a success without its events being forwarded again.
+//!
+//! Keys are only remembered once their request was accepted, so that a client
+//! can retry a request that failed. A request arriving while another with the
+//! same key is still in flight is rejected with a `409`, to be retried later.
+
+use crate::internal_events::{
+    IdempotencyKeysExhausted, IdempotencyKeysTracked, IdempotentRequestDuplicate,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    hash::Hasher,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use twox_hash::XxHash64;
+use warp::{
+    http::{header::HeaderName, HeaderMap, StatusCode},
+    reply::Response,
+    Reply,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct IdempotencyConfig {
+    key: IdempotencyKey,
+    #[serde(default = "default_header")]
+    header: String,
+    #[serde(default = "default_ttl_secs")]
+    ttl_secs: u64,
+    #[serde(default = "default_max_keys")]
+    max_keys: usize,
+}
+
+fn default_header() -> String {
+    "idempotency-key".to_owned()
+}
+
+const fn default_ttl_secs() -> u64 {
+    300
+}
+
+const fn default_max_keys() -> usize {
+    100_000
+}
+
+/// What identifies retransmissions of a request.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdempotencyKey {
+    /// The value of the idempotency header of the request. Requests without
+    /// that header aren't deduplicated.
+    Header,
+    /// A hash of the body of the request, along with its path.
+    BodyHash,
+}
+
+impl IdempotencyConfig {
+    pub fn build(&self) -> crate::Result<Idempotency> {
+        let header = HeaderName::from_bytes(self.header.as_bytes())
+            .map_err(|_| format!("`idempotency.header` {:?} is invalid", self.header))?;
+        if self.ttl_secs == 0 {
+            return Err("`idempotency.ttl_secs` must be greater than 0".into());
+        }
+        if self.max_keys == 0 {
+            return Err("`idempotency.max_keys` must be greater than 0".into());
+        }
+
+        Ok(Idempotency {
+            key: self.key,
+            header,
+            ttl: Duration::from_secs(self.ttl_secs),
+            max_keys: self.max_keys,
+            keys: Arc::new(Mutex::new(Keys {
+                states: HashMap::new(),
+                swept_at: Instant::now(),
+            })),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Idempotency {
+    key: IdempotencyKey,
+    header: HeaderName,
+    ttl: Duration,
+    max_keys: usize,
+    keys: Arc<Mutex<Keys>>,
+}
+
+#[derive(Debug)]
+struct Keys {
+    states: HashMap<String, KeyState>,
+    swept_at: Instant,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeyState {
+    /// A request with the key is being handled.
+    InFlight,
+    /// A request with the key was accepted, and its retransmissions are
+    /// dropped until `expires_at`.
+    Accepted { expires_at: Instant },
+}
+
+impl Keys {
+    fn sweep(&mut self, now: Instant) {
+        self.states.retain(|_, state| match state {
+            KeyState::InFlight => true,
+            KeyState::Accepted { expires_at } => *expires_at > now,
+        });
+        self.swept_at = now;
+    }
+}
+
+impl Idempotency {
+    fn key_of(&self, path: &str, headers: &HeaderMap, body: &[u8]) -> Option<String> {
+        match self.key {
+            IdempotencyKey::Header => headers
+                .get(&self.header)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(Into::into),
+            IdempotencyKey::BodyHash => {
+                let mut hasher = XxHash64::with_seed(0);
+                hasher.write(path.as_bytes());
+                hasher.write_u8(0);
+                hasher.write(body);
+                Some(format!("{:016x}", hasher.finish()))
+            }
+        }
+    }
+
+    /// Claims the key of a request, unless a request with the same key was
+    /// already accepted or is in flight. Requests without a key, or arriving
+    /// while the maximum number of keys are tracked, aren't claimed.
+    pub fn claim(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Option<IdempotencyClaim>, DuplicateRequest> {
+        let key = match self.key_of(path, headers, body) {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        let now = Instant::now();
+        let mut keys = self.keys.lock().expect("poisoned lock");
+        if now.saturating_duration_since(keys.swept_at) >= self.ttl
+            || keys.states.len() >= self.max_keys
+        {
+            keys.sweep(now);
+        }
+
+        match keys.states.get(&key) {
+            Some(KeyState::Accepted { expires_at }) if *expires_at > now => {
+                emit!(&IdempotentRequestDuplicate { in_flight: false });
+                return Err(DuplicateRequest { in_flight: false });
+            }
+            Some(KeyState::InFlight) => {
+                emit!(&IdempotentRequestDuplicate { in_flight: true });
+                return Err(DuplicateRequest { in_flight: true });
+            }
+            Some(KeyState::Accepted { .. }) => (),
+            None if keys.states.len() >= self.max_keys => {
+                emit!(&IdempotencyKeysExhausted {
+                    max_keys: self.max_keys
+                });
+                return Ok(None);
+            }
+            None => (),
+        }
+
+        keys.states.insert(key.clone(), KeyState::InFlight);
+        emit!(&IdempotencyKeysTracked {
+            count: keys.states.len()
+        });
+        Ok(Some(IdempotencyClaim {
+            idempotency: self.clone(),
+            key,
+            accepted: false,
+        }))
+    }
+}
+
+/// The claim of a request on its key, released when dropped unless the
+/// request was accepted.
+#[derive(Debug)]
+pub struct IdempotencyClaim {
+    idempotency: Idempotency,
+    key: String,
+    accepted: bool,
+}
+
+impl IdempotencyClaim {
+    /// Records that the request was accepted, so that its retransmissions are
+    /// dropped.
+    pub fn accept(mut self) {
+        let expires_at = Instant::now() + self.idempotency.ttl;
+        let mut keys = self.idempotency.keys.lock().expect("poisoned lock");
+        keys.states
+            .insert(self.key.clone(), KeyState::Accepted { expires_at });
+        self.accepted = true;
+    }
+}
+
+impl Drop for IdempotencyClaim {
+    fn drop(&mut self) {
+        if !self.accepted {
+            let mut keys = self.idempotency.keys.lock().expect("poisoned lock");
+            if keys.states.get(&self.key) == Some(&KeyState::InFlight) {
+                keys.states.remove(&self.key);
+            }
+            emit!(&IdempotencyKeysTracked {
+                count: keys.states.len()
+            });
+        }
+    }
+}
+
+/// The rejection of a request whose key was already claimed.
+#[derive(Debug)]
+pub struct DuplicateRequest {
+    in_flight: bool,
+}
+
+impl warp::reject::Reject for DuplicateRequest {}
+
+impl DuplicateRequest {
+    /// Whether the request carrying the key is still in flight, rather than
+    /// accepted.
+    pub const fn in_flight(&self) -> bool {
+        self.in_flight
+    }
+
+    /// Replies to retransmissions of accepted requests as if they were
+    /// accepted again, and with a `409` while the original request is in
+    /// flight.
+    pub fn reply(&self) -> Response {
+        if self.in_flight {
+            let body = warp::reply::json(&serde_json::json!({
+                "code": StatusCode::CONFLICT.as_u16(),
+                "message": "A request with the same idempotency key is in progress",
+            }));
+            warp::reply::with_status(body, StatusCode::CONFLICT).into_response()
+        } else {
+            warp::reply().into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idempotency(config: &str) -> Idempotency {
+        toml::from_str::<IdempotencyConfig>(config)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn headers(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("idempotency-key", key.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let build = |config| toml::from_str::<IdempotencyConfig>(config).unwrap().build();
+        assert!(build("key = \"header\"\nheader = \"not a header\"").is_err());
+        assert!(build("key = \"header\"\nttl_secs = 0").is_err());
+        assert!(build("key = \"body_hash\"\nmax_keys = 0").is_err());
+        assert!(build("key = \"body_hash\"").is_ok());
+    }
+
+    #[test]
+    fn drops_retransmissions_of_accepted_requests() {
+        let idempotency = idempotency("key = \"header\"");
+
+        let claim = idempotency.claim("/", &headers("a"), b"").unwrap().unwrap();
+        let duplicate = idempotency.claim("/", &headers("a"), b"").unwrap_err();
+        assert!(duplicate.in_flight());
+        assert_eq!(duplicate.reply().status(), StatusCode::CONFLICT);
+
+        claim.accept();
+        let duplicate = idempotency.claim("/", &headers("a"), b"").unwrap_err();
+        assert!(!duplicate.in_flight());
+        assert_eq!(duplicate.reply().status(), StatusCode::OK);
+
+        assert!(idempotency
+            .claim("/", &headers("b"), b"")
+            .unwrap()
+            .is_some());
+        // Requests without the header aren't deduplicated.
+        assert!(idempotency
+            .claim("/", &HeaderMap::new(), b"")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn releases_keys_of_failed_requests() {
+        let idempotency = idempotency("key = \"header\"");
+
+        drop(idempotency.claim("/", &headers("a"), b"").unwrap());
+        assert!(idempotency
+            .claim("/", &headers("a"), b"")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn hashes_bodies_along_with_their_path() {
+        let idempotency = idempotency("key = \"body_hash\"");
+
+        idempotency
+            .claim("/logs", &HeaderMap::new(), b"hello")
+            .unwrap()
+            .unwrap()
+            .accept();
+        assert!(idempotency
+            .claim("/logs", &HeaderMap::new(), b"hello")
+            .is_err());
+        assert!(idempotency
+            .claim("/metrics", &HeaderMap::new(), b"hello")
+            .is_ok());
+        assert!(idempotency
+            .claim("/logs", &HeaderMap::new(), b"world")
+            .is_ok());
+    }
+
+    #[test]
+    fn expires_keys() {
+        let idempotency = idempotency("key = \"header\"\nmax_keys = 1");
+        idempotency
+            .claim("/", &headers("a"), b"")
+            .unwrap()
+            .unwrap()
+            .accept();
+
+        // Keys beyond the maximum aren't tracked.
+        assert!(idempotency
+            .claim("/", &headers("b"), b"")
+            .unwrap()
+            .is_none());
+
+        let mut keys = idempotency.keys.lock().unwrap();
+        keys.states.insert(
+            "a".into(),
+            KeyState::Accepted {
+                expires_at: Instant::now(),
+            },
+        );
+        drop(keys);
+        assert!(idempotency
+            .claim("/", &headers("a"), b"")
+            .unwrap()
+            .is_some());
+    }
+}
@@ -4,6 +4,8 @@ mod auth;
 mod encoding;
 #[cfg(feature = "sources-utils-http-error")]
 mod error;
+#[cfg(feature = "sources-utils-http-idempotency")]
+mod idempotency;
 #[cfg(feature = "sources-utils-http-prelude")]
 mod prelude;
 #[cfg(any(
@@ -12,6 +14,10 @@ mod prelude;
     feature = "sources-utils-http-query"
 ))]
 mod query;
+#[cfg(feature = "sources-utils-http-quota")]
+mod quota;
+#[cfg(feature = "sources-utils-http-server")]
+pub(crate) mod server;
 
 #[cfg(feature = "sources-utils-http-auth")]
 pub use auth::{HttpSourceAuth, HttpSourceAuthConfig};
@@ -19,7 +25,11 @@ pub use auth::{HttpSourceAuth, HttpSourceAuthConfig};
 pub use encoding::decode;
 #[cfg(feature = "sources-utils-http-error")]
 pub use error::ErrorMessage;
+#[cfg(feature = "sources-utils-http-idempotency")]
+pub use idempotency::{DuplicateRequest, Idempotency, IdempotencyClaim, IdempotencyConfig};
 #[cfg(feature = "sources-utils-http-prelude")]
 pub use prelude::HttpSource;
 #[cfg(feature = "sources-utils-http-query")]
 pub use query::add_query_parameters;
+#[cfg(feature = "sources-utils-http-quota")]
+pub use quota::{Quota, QuotaConfig, QuotaExceeded, QuotaIdentity, QuotaPermit};
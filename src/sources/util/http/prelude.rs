@@ -2,6 +2,9 @@ use super::{
     auth::{HttpSourceAuth, HttpSourceAuthConfig},
     encoding::decode,
     error::ErrorMessage,
+    idempotency::{DuplicateRequest, IdempotencyClaim, IdempotencyConfig},
+    quota::{QuotaConfig, QuotaExceeded, QuotaIdentity},
+    server::{self, Http2Config, HttpKeepaliveConfig},
 };
 use crate::{
     config::SourceContext,
@@ -11,7 +14,10 @@ use crate::{
 };
 use async_trait::async_trait;
 use bytes::Bytes;
-use futures::{FutureExt, SinkExt, StreamExt, TryFutureExt};
+use futures::{
+    future::{self, Either},
+    FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
 use std::{collections::HashMap, convert::TryFrom, fmt, net::SocketAddr, sync::Arc};
 use vector_core::event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event};
 use vector_core::ByteSizeOf;
@@ -19,7 +25,7 @@ use warp::{
     filters::{path::FullPath, path::Tail, BoxedFilter},
     http::{HeaderMap, StatusCode},
     reject::Rejection,
-    Filter,
+    Filter, Reply,
 };
 
 #[async_trait]
@@ -32,6 +38,7 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         path: &str,
     ) -> Result<Vec<Event>, ErrorMessage>;
 
+    #[allow(clippy::too_many_arguments)]
     fn run(
         self,
         address: SocketAddr,
@@ -39,11 +46,25 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         strict_path: bool,
         tls: &Option<TlsConfig>,
         auth: &Option<HttpSourceAuthConfig>,
+        quota: &Option<QuotaConfig>,
+        idempotency: &Option<IdempotencyConfig>,
+        http2: &Http2Config,
+        keepalive: &HttpKeepaliveConfig,
         cx: SourceContext,
     ) -> crate::Result<crate::sources::Source> {
         let tls = MaybeTlsSettings::from_config(tls, true)?;
+        let http2 = http2.clone();
+        let keepalive = keepalive.clone();
         let protocol = tls.http_protocol_name();
         let auth = HttpSourceAuth::try_from(auth.as_ref())?;
+        let quota = quota
+            .as_ref()
+            .map(|quota| quota.build(QuotaIdentity::AuthToken))
+            .transpose()?;
+        let idempotency = idempotency
+            .as_ref()
+            .map(IdempotencyConfig::build)
+            .transpose()?;
         let path = path.to_owned();
         let out = cx.out;
         let shutdown = cx.shutdown;
@@ -72,13 +93,15 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                 .and(warp::header::optional::<String>("authorization"))
                 .and(warp::header::optional::<String>("content-encoding"))
                 .and(warp::header::headers_cloned())
+                .and(server::remote_addr())
                 .and(warp::body::bytes())
                 .and(warp::query::<HashMap<String, String>>())
                 .and_then(
                     move |path: FullPath,
-                          auth_header,
+                          auth_header: Option<String>,
                           encoding_header,
                           headers: HeaderMap,
+                          remote: Option<SocketAddr>,
                           body: Bytes,
                           query_parameters: HashMap<String, String>| {
                         debug!(message = "Handling HTTP request.", headers = ?headers);
@@ -89,8 +112,36 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                             protocol,
                         });
 
-                        let events = auth
-                            .is_valid(&auth_header)
+                        let authorized = auth.is_valid(&auth_header);
+                        let claim = match (&authorized, &idempotency) {
+                            (Ok(()), Some(idempotency)) => {
+                                match idempotency.claim(http_path, &headers, &body) {
+                                    Ok(claim) => claim,
+                                    Err(duplicate) => {
+                                        return Either::Left(future::err(warp::reject::custom(
+                                            duplicate,
+                                        )))
+                                    }
+                                }
+                            }
+                            _ => None,
+                        };
+                        let permit = match (&authorized, &quota) {
+                            (Ok(()), Some(quota)) => {
+                                let token = auth_header.as_deref().map(auth_token);
+                                match quota.admit(token, remote, body.len()) {
+                                    Ok(permit) => Some(permit),
+                                    Err(exceeded) => {
+                                        return Either::Left(future::err(warp::reject::custom(
+                                            exceeded,
+                                        )))
+                                    }
+                                }
+                            }
+                            _ => None,
+                        };
+
+                        let events = authorized
                             .and_then(|()| decode(&encoding_header, body))
                             .and_then(|body| {
                                 self.build_events(body, headers, query_parameters, path.as_str())
@@ -102,39 +153,59 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                                     http_path,
                                     protocol,
                                 });
+                                if let Some(permit) = &permit {
+                                    permit.consume_events(events.len());
+                                }
                                 events
                             });
 
-                        handle_request(events, acknowledgements, out.clone())
+                        Either::Right(handle_request(events, acknowledgements, out.clone(), claim))
                     },
                 )
                 .with(warp::trace(move |_info| span.clone()));
 
             let ping = warp::get().and(warp::path("ping")).map(|| "pong");
-            let routes = svc.or(ping).recover(|r: Rejection| async move {
-                if let Some(e_msg) = r.find::<ErrorMessage>() {
-                    let json = warp::reply::json(e_msg);
-                    Ok(warp::reply::with_status(json, e_msg.status_code()))
-                } else {
-                    //other internal error - will return 500 internal server error
-                    Err(r)
-                }
-            });
+            let routes = svc
+                .or(ping)
+                .recover(|r: Rejection| async move {
+                    if let Some(e_msg) = r.find::<ErrorMessage>() {
+                        let json = warp::reply::json(e_msg);
+                        Ok(warp::reply::with_status(json, e_msg.status_code()).into_response())
+                    } else if let Some(exceeded) = r.find::<QuotaExceeded>() {
+                        Ok(exceeded.reply())
+                    } else if let Some(duplicate) = r.find::<DuplicateRequest>() {
+                        Ok(duplicate.reply())
+                    } else {
+                        //other internal error - will return 500 internal server error
+                        Err(r)
+                    }
+                })
+                .boxed();
 
             info!(message = "Building HTTP server.", address = %address);
 
-            let listener = tls.bind(&address).await.unwrap();
-            warp::serve(routes)
-                .serve_incoming_with_graceful_shutdown(
-                    listener.accept_stream(),
-                    shutdown.map(|_| ()),
-                )
-                .await;
+            let listener = server::bind(&tls, &address, &http2).await.unwrap();
+            server::serve(
+                routes,
+                listener,
+                &http2,
+                &keepalive,
+                None,
+                shutdown.map(|_| ()),
+            )
+            .await;
             Ok(())
         }))
     }
 }
 
+/// The token of an `Authorization` header, without its scheme.
+fn auth_token(header: &str) -> &str {
+    header
+        .split_once(' ')
+        .map_or(header, |(_scheme, token)| token.trim())
+}
+
 struct RejectShuttingDown;
 
 impl fmt::Debug for RejectShuttingDown {
@@ -149,6 +220,7 @@ async fn handle_request(
     events: Result<Vec<Event>, ErrorMessage>,
     acknowledgements: bool,
     mut out: Pipeline,
+    claim: Option<IdempotencyClaim>,
 ) -> Result<impl warp::Reply, Rejection> {
     match events {
         Ok(mut events) => {
@@ -170,6 +242,14 @@ async fn handle_request(
                 })
                 .and_then(|_| handle_batch_status(receiver))
                 .await
+                .map(|reply| {
+                    // Retransmissions of the request are only dropped once
+                    // its events were accepted.
+                    if let Some(claim) = claim {
+                        claim.accept();
+                    }
+                    reply
+                })
         }
         Err(error) => {
             emit!(&HttpBadRequest {
@@ -0,0 +1,362 @@
+//! Ingestion quotas of push-based sources.
+//!
+//! Requests are accounted to an identity, either the credential they are
+//! authenticated with or the address of their peer, and rejected with a `429`
+//! once that identity used up its quota. Usage is measured over a rolling
+//! window of `period_secs`, estimated from the usage of the current and
+//! previous periods, weighted by how much of the previous period still falls
+//! within the window.
+
+use crate::internal_events::{QuotaConsumed, QuotaLimitExceeded};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use warp::{
+    http::{HeaderValue, StatusCode},
+    reply::Response,
+    Reply,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaConfig {
+    identity: QuotaIdentity,
+    max_events: Option<u64>,
+    max_bytes: Option<u64>,
+    #[serde(default = "default_period_secs")]
+    period_secs: u64,
+}
+
+const fn default_period_secs() -> u64 {
+    60
+}
+
+/// What requests are accounted to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaIdentity {
+    /// The API key of the request.
+    ApiKey,
+    /// The token of the `Authorization` header of the request.
+    AuthToken,
+    /// The IP address of the peer.
+    PeerIp,
+}
+
+impl QuotaIdentity {
+    const fn as_str(self) -> &'static str {
+        match self {
+            QuotaIdentity::ApiKey => "api_key",
+            QuotaIdentity::AuthToken => "auth_token",
+            QuotaIdentity::PeerIp => "peer_ip",
+        }
+    }
+}
+
+impl QuotaConfig {
+    /// Builds the quota of a source whose requests carry a `credential`.
+    pub fn build(&self, credential: QuotaIdentity) -> crate::Result<Quota> {
+        if self.identity != QuotaIdentity::PeerIp && self.identity != credential {
+            return Err(format!(
+                "`quota.identity` must be either {:?} or \"peer_ip\" for this source",
+                credential.as_str()
+            )
+            .into());
+        }
+        if self.max_events.is_none() && self.max_bytes.is_none() {
+            return Err("`quota` must set `max_events`, `max_bytes` or both".into());
+        }
+        if self.period_secs == 0 {
+            return Err("`quota.period_secs` must be greater than 0".into());
+        }
+
+        Ok(Quota {
+            identity: self.identity,
+            max_events: self.max_events,
+            max_bytes: self.max_bytes,
+            period: Duration::from_secs(self.period_secs),
+            usages: Arc::new(Mutex::new(Usages {
+                identities: HashMap::new(),
+                swept_at: Instant::now(),
+            })),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Quota {
+    identity: QuotaIdentity,
+    max_events: Option<u64>,
+    max_bytes: Option<u64>,
+    period: Duration,
+    usages: Arc<Mutex<Usages>>,
+}
+
+#[derive(Debug)]
+struct Usages {
+    identities: HashMap<String, Usage>,
+    swept_at: Instant,
+}
+
+/// The usage of an identity over its current and previous periods.
+#[derive(Debug)]
+struct Usage {
+    period_start: Instant,
+    current: Consumption,
+    previous: Consumption,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Consumption {
+    events: u64,
+    bytes: u64,
+}
+
+impl Usage {
+    fn new(now: Instant) -> Self {
+        Self {
+            period_start: now,
+            current: Consumption::default(),
+            previous: Consumption::default(),
+        }
+    }
+
+    /// Moves to the period containing `now`.
+    fn advance(&mut self, now: Instant, period: Duration) {
+        let elapsed = now.saturating_duration_since(self.period_start);
+        let periods = (elapsed.as_nanos() / period.as_nanos()) as u32;
+        if periods > 0 {
+            self.previous = if periods == 1 {
+                self.current
+            } else {
+                Consumption::default()
+            };
+            self.current = Consumption::default();
+            self.period_start += period * periods;
+        }
+    }
+
+    /// The consumption over the rolling window ending at `now`.
+    fn estimate(&self, now: Instant, period: Duration) -> Consumption {
+        let elapsed = now.saturating_duration_since(self.period_start);
+        let weight = 1.0 - elapsed.as_secs_f64() / period.as_secs_f64();
+        Consumption {
+            events: self.current.events + (self.previous.events as f64 * weight) as u64,
+            bytes: self.current.bytes + (self.previous.bytes as f64 * weight) as u64,
+        }
+    }
+}
+
+impl Quota {
+    /// Admits a request of `bytes`, unless its identity exceeded its quota.
+    /// The events it carries are accounted through the returned permit.
+    pub fn admit(
+        &self,
+        credential: Option<&str>,
+        peer: Option<SocketAddr>,
+        bytes: usize,
+    ) -> Result<QuotaPermit, QuotaExceeded> {
+        let identity = match self.identity {
+            QuotaIdentity::PeerIp => peer.map(|peer| peer.ip().to_string()),
+            _ => credential.map(Into::into),
+        }
+        // Requests missing their identity share a single quota.
+        .unwrap_or_default();
+
+        let now = Instant::now();
+        let mut usages = self.usages.lock().expect("poisoned lock");
+        if now.saturating_duration_since(usages.swept_at) >= self.period {
+            let period = self.period;
+            usages
+                .identities
+                .retain(|_, usage| now.saturating_duration_since(usage.period_start) < period * 2);
+            usages.swept_at = now;
+        }
+
+        let usage = usages
+            .identities
+            .entry(identity.clone())
+            .or_insert_with(|| Usage::new(now));
+        usage.advance(now, self.period);
+        let used = usage.estimate(now, self.period);
+
+        let events_exceeded = self.max_events.map_or(false, |max| used.events >= max);
+        let bytes_exceeded = self
+            .max_bytes
+            .map_or(false, |max| used.bytes + bytes as u64 > max);
+        if events_exceeded || bytes_exceeded {
+            let reset = self.period - now.saturating_duration_since(usage.period_start);
+            emit!(&QuotaLimitExceeded {
+                identity: self.identity.as_str(),
+                reset_secs: reset.as_secs_f64(),
+            });
+            return Err(QuotaExceeded {
+                max_events: self.max_events,
+                max_bytes: self.max_bytes,
+                used,
+                reset,
+            });
+        }
+
+        usage.current.bytes += bytes as u64;
+        emit!(&QuotaConsumed { events: 0, bytes });
+        Ok(QuotaPermit {
+            quota: self.clone(),
+            identity,
+        })
+    }
+}
+
+/// An admitted request, through which its events are accounted.
+#[derive(Debug)]
+pub struct QuotaPermit {
+    quota: Quota,
+    identity: String,
+}
+
+impl QuotaPermit {
+    pub fn consume_events(&self, events: usize) {
+        let now = Instant::now();
+        let mut usages = self.quota.usages.lock().expect("poisoned lock");
+        let usage = usages
+            .identities
+            .entry(self.identity.clone())
+            .or_insert_with(|| Usage::new(now));
+        usage.advance(now, self.quota.period);
+        usage.current.events += events as u64;
+        emit!(&QuotaConsumed { events, bytes: 0 });
+    }
+}
+
+/// The rejection of a request whose identity exceeded its quota, replied to
+/// with a `429` along with the state of the quota.
+#[derive(Debug)]
+pub struct QuotaExceeded {
+    max_events: Option<u64>,
+    max_bytes: Option<u64>,
+    used: Consumption,
+    reset: Duration,
+}
+
+impl warp::reject::Reject for QuotaExceeded {}
+
+impl QuotaExceeded {
+    /// Seconds until the current period ends, rounded up.
+    fn reset_secs(&self) -> u64 {
+        self.reset.as_secs() + (self.reset.subsec_nanos() > 0) as u64
+    }
+
+    pub fn reply(&self) -> Response {
+        let body = warp::reply::json(&serde_json::json!({
+            "code": StatusCode::TOO_MANY_REQUESTS.as_u16(),
+            "message": "Quota exceeded",
+        }));
+        let mut response =
+            warp::reply::with_status(body, StatusCode::TOO_MANY_REQUESTS).into_response();
+
+        let headers = response.headers_mut();
+        let reset = HeaderValue::from(self.reset_secs());
+        headers.insert("retry-after", reset.clone());
+        headers.insert("x-quota-reset", reset);
+        if let Some(max) = self.max_events {
+            headers.insert("x-quota-limit-events", HeaderValue::from(max));
+            headers.insert(
+                "x-quota-remaining-events",
+                HeaderValue::from(max.saturating_sub(self.used.events)),
+            );
+        }
+        if let Some(max) = self.max_bytes {
+            headers.insert("x-quota-limit-bytes", HeaderValue::from(max));
+            headers.insert(
+                "x-quota-remaining-bytes",
+                HeaderValue::from(max.saturating_sub(self.used.bytes)),
+            );
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quota(identity: &str, limits: &str) -> Quota {
+        toml::from_str::<QuotaConfig>(&format!("identity = {:?}\n{}", identity, limits))
+            .unwrap()
+            .build(QuotaIdentity::AuthToken)
+            .unwrap()
+    }
+
+    #[test]
+    fn rejects_unsupported_identities() {
+        let config =
+            toml::from_str::<QuotaConfig>("identity = \"api_key\"\nmax_events = 1").unwrap();
+        assert!(config.build(QuotaIdentity::AuthToken).is_err());
+        assert!(config.build(QuotaIdentity::ApiKey).is_ok());
+
+        let config = toml::from_str::<QuotaConfig>("identity = \"peer_ip\"").unwrap();
+        assert!(config.build(QuotaIdentity::ApiKey).is_err());
+        let config =
+            toml::from_str::<QuotaConfig>("identity = \"peer_ip\"\nmax_bytes = 1").unwrap();
+        assert!(config.build(QuotaIdentity::ApiKey).is_ok());
+    }
+
+    #[test]
+    fn limits_events_per_identity() {
+        let quota = quota("auth_token", "max_events = 3");
+
+        let permit = quota.admit(Some("a"), None, 10).unwrap();
+        permit.consume_events(3);
+
+        let exceeded = quota.admit(Some("a"), None, 10).unwrap_err();
+        assert_eq!(exceeded.used.events, 3);
+        assert!(quota.admit(Some("b"), None, 10).is_ok());
+    }
+
+    #[test]
+    fn limits_bytes_per_peer() {
+        let quota = quota("peer_ip", "max_bytes = 100");
+        let peer = |port| Some(SocketAddr::from(([10, 0, 0, 1], port)));
+
+        assert!(quota.admit(None, peer(1000), 60).is_ok());
+        // Peers are accounted by address, whatever their port.
+        assert!(quota.admit(None, peer(2000), 60).is_err());
+        assert!(quota.admit(None, peer(2000), 40).is_ok());
+    }
+
+    #[test]
+    fn rolls_usage_over_periods() {
+        let period = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut usage = Usage::new(start);
+        usage.current.events = 100;
+
+        // Half way through the next period, half of the previous one is
+        // still within the window.
+        let now = start + period + period / 2;
+        usage.advance(now, period);
+        assert_eq!(usage.estimate(now, period).events, 50);
+
+        let now = start + period * 3;
+        usage.advance(now, period);
+        assert_eq!(usage.estimate(now, period).events, 0);
+    }
+
+    #[test]
+    fn replies_with_quota_headers() {
+        let quota = quota("auth_token", "max_events = 1\nmax_bytes = 1000");
+        quota.admit(None, None, 10).unwrap().consume_events(1);
+
+        let response = quota.admit(None, None, 10).unwrap_err().reply();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let headers = response.headers();
+        assert_eq!(headers["x-quota-limit-events"], "1");
+        assert_eq!(headers["x-quota-remaining-events"], "0");
+        assert_eq!(headers["x-quota-remaining-bytes"], "990");
+        assert_eq!(headers["retry-after"], "60");
+    }
+}
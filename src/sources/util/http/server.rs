@@ -0,0 +1,443 @@
+use crate::{
+    internal_events::TcpConnectionRejected,
+    tls::{MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
+};
+use cidr_utils::cidr::IpCidr;
+use futures::{future, TryStreamExt};
+use hyper::{
+    service::{make_service_fn, service_fn, Service},
+    Body, Request, Server,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::Infallible,
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+    time::{sleep, Instant, Sleep},
+};
+use warp::{filters::BoxedFilter, Filter, Reply};
+
+/// The protocols advertised through ALPN when HTTP/2 is enabled, in wire
+/// format.
+const ALPN_H2_HTTP1: &[u8] = b"\x02h2\x08http/1.1";
+
+/// HTTP/2 options of a source's HTTP server.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Http2Config {
+    /// Accept HTTP/2 along with HTTP/1.1, negotiated through ALPN over TLS,
+    /// or with prior knowledge (h2c) over cleartext.
+    #[serde(default)]
+    pub enabled: bool,
+    /// The maximum number of concurrent streams per connection.
+    #[serde(default)]
+    pub max_concurrent_streams: Option<u32>,
+    /// How often to ping clients to keep connections alive. Pings are disabled
+    /// if unset.
+    #[serde(default)]
+    pub keepalive_interval_secs: Option<u64>,
+    /// How long to wait for a ping to be acknowledged before closing the
+    /// connection.
+    #[serde(default = "default_keepalive_timeout_secs")]
+    pub keepalive_timeout_secs: u64,
+}
+
+const fn default_keepalive_timeout_secs() -> u64 {
+    20
+}
+
+impl Default for Http2Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_concurrent_streams: None,
+            keepalive_interval_secs: None,
+            keepalive_timeout_secs: default_keepalive_timeout_secs(),
+        }
+    }
+}
+
+/// Connection reuse options of a source's HTTP server.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HttpKeepaliveConfig {
+    /// Keep HTTP/1.1 connections open between requests.
+    #[serde(default = "crate::serde::default_true")]
+    pub enabled: bool,
+    /// Close connections that neither send nor receive data for this long.
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+}
+
+impl Default for HttpKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_timeout_secs: None,
+        }
+    }
+}
+
+/// The networks an HTTP server accepts connections from.
+#[derive(Clone, Debug)]
+pub struct PermitOrigin(Vec<IpCidr>);
+
+impl PermitOrigin {
+    /// Parses the CIDR blocks of `networks`, permitting every origin if there
+    /// are none.
+    pub fn new(networks: &[String]) -> crate::Result<Option<Self>> {
+        if networks.is_empty() {
+            return Ok(None);
+        }
+
+        networks
+            .iter()
+            .map(|network| {
+                IpCidr::from_str(network.as_str()).map_err(|error| {
+                    format!(
+                        "Invalid network {:?} in `permit_origin`: {}",
+                        network, error
+                    )
+                    .into()
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()
+            .map(|networks| Some(Self(networks)))
+    }
+
+    fn permits(&self, address: IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(address))
+    }
+}
+
+/// The address of the peer a request was received from. warp only knows the
+/// peers of the connections it accepts itself, so `serve` passes them along in
+/// the extensions of requests instead.
+#[derive(Clone, Copy, Debug)]
+struct PeerAddr(SocketAddr);
+
+/// Extracts the address of the peer of requests received by `serve`, in place
+/// of `warp::addr::remote`.
+pub(crate) fn remote_addr(
+) -> impl Filter<Extract = (Option<SocketAddr>,), Error = Infallible> + Clone {
+    warp::ext::optional::<PeerAddr>().map(|peer: Option<PeerAddr>| peer.map(|peer| peer.0))
+}
+
+/// Binds the listener of an HTTP server, advertising HTTP/2 over TLS if it's
+/// enabled.
+pub(crate) async fn bind(
+    tls: &MaybeTlsSettings,
+    address: &SocketAddr,
+    http2: &Http2Config,
+) -> crate::tls::Result<MaybeTlsListener> {
+    let alpn = http2.enabled.then(|| ALPN_H2_HTTP1);
+    tls.bind_with_alpn(address, alpn).await
+}
+
+/// Serves `routes` on the connections of `listener` until `shutdown`
+/// resolves. Connections from outside of `permit_origin` are closed as soon as
+/// they are accepted, before any TLS handshake or request.
+pub(crate) async fn serve<R>(
+    routes: BoxedFilter<(R,)>,
+    listener: MaybeTlsListener,
+    http2: &Http2Config,
+    keepalive: &HttpKeepaliveConfig,
+    permit_origin: Option<PermitOrigin>,
+    shutdown: impl Future<Output = ()>,
+) where
+    R: Reply + Send + 'static,
+{
+    let idle_timeout = keepalive.idle_timeout_secs.map(Duration::from_secs);
+    let incoming = hyper::server::accept::from_stream(
+        listener
+            .accept_stream()
+            .try_filter(move |stream| {
+                let peer_addr = stream.peer_addr().ip();
+                let permitted = permit_origin
+                    .as_ref()
+                    .map_or(true, |permit_origin| permit_origin.permits(peer_addr));
+                if !permitted {
+                    emit!(&TcpConnectionRejected {
+                        peer_addr,
+                        reason: "permit_origin",
+                    });
+                }
+                future::ready(permitted)
+            })
+            .map_ok(move |stream| IdleTimeout::new(stream, idle_timeout)),
+    );
+
+    let builder = Server::builder(incoming).http1_keepalive(keepalive.enabled);
+    let builder = if http2.enabled {
+        builder
+            .http2_max_concurrent_streams(http2.max_concurrent_streams)
+            .http2_keep_alive_interval(http2.keepalive_interval_secs.map(Duration::from_secs))
+            .http2_keep_alive_timeout(Duration::from_secs(http2.keepalive_timeout_secs))
+    } else {
+        builder.http1_only(true)
+    };
+
+    let service = warp::service(routes);
+    let make_service = make_service_fn(
+        move |connection: &IdleTimeout<MaybeTlsIncomingStream<TcpStream>>| {
+            let peer_addr = PeerAddr(connection.inner.peer_addr());
+            let mut service = service.clone();
+            future::ok::<_, Infallible>(service_fn(move |mut request: Request<Body>| {
+                request.extensions_mut().insert(peer_addr);
+                service.call(request)
+            }))
+        },
+    );
+
+    if let Err(error) = builder
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await
+    {
+        error!(message = "HTTP server failed.", %error);
+    }
+}
+
+/// Wraps a connection, failing it once no data has been read from or written
+/// to it for the timeout.
+struct IdleTimeout<S> {
+    inner: S,
+    timeout: Option<(Duration, Pin<Box<Sleep>>)>,
+}
+
+impl<S> IdleTimeout<S> {
+    fn new(inner: S, timeout: Option<Duration>) -> Self {
+        Self {
+            inner,
+            timeout: timeout.map(|timeout| (timeout, Box::pin(sleep(timeout)))),
+        }
+    }
+
+    fn reset(&mut self) {
+        if let Some((timeout, sleep)) = &mut self.timeout {
+            sleep.as_mut().reset(Instant::now() + *timeout);
+        }
+    }
+
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<io::Error> {
+        match &mut self.timeout {
+            Some((_, sleep)) => sleep
+                .as_mut()
+                .poll(cx)
+                .map(|()| io::Error::new(io::ErrorKind::TimedOut, "connection idle")),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleTimeout<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match Pin::new(&mut self.inner).poll_read(cx, buf) {
+            Poll::Ready(result) => {
+                self.reset();
+                Poll::Ready(result)
+            }
+            Poll::Pending => self.poll_idle(cx).map(Err),
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleTimeout<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match Pin::new(&mut self.inner).poll_write(cx, buf) {
+            Poll::Ready(result) => {
+                self.reset();
+                Poll::Ready(result)
+            }
+            Poll::Pending => self.poll_idle(cx).map(Err),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use futures::FutureExt;
+    use tokio::{io::AsyncReadExt, net::TcpStream, sync::oneshot};
+    use warp::Filter;
+
+    async fn start_server(
+        http2: Http2Config,
+        keepalive: HttpKeepaliveConfig,
+    ) -> (SocketAddr, oneshot::Sender<()>) {
+        start_server_permitting(http2, keepalive, None).await
+    }
+
+    async fn start_server_permitting(
+        http2: Http2Config,
+        keepalive: HttpKeepaliveConfig,
+        permit_origin: Option<PermitOrigin>,
+    ) -> (SocketAddr, oneshot::Sender<()>) {
+        let address = next_addr();
+        let listener = bind(&MaybeTlsSettings::Raw(()), &address, &http2)
+            .await
+            .unwrap();
+        let routes = warp::any().map(|| "ok").boxed();
+        let (trigger, tripwire) = oneshot::channel();
+        tokio::spawn(async move {
+            serve(
+                routes,
+                listener,
+                &http2,
+                &keepalive,
+                permit_origin,
+                tripwire.map(|_| ()),
+            )
+            .await
+        });
+        (address, trigger)
+    }
+
+    #[tokio::test]
+    async fn serves_http2_with_prior_knowledge() {
+        let (address, _trigger) = start_server(
+            Http2Config {
+                enabled: true,
+                ..Default::default()
+            },
+            HttpKeepaliveConfig::default(),
+        )
+        .await;
+
+        let client = hyper::Client::builder()
+            .http2_only(true)
+            .build_http::<hyper::Body>();
+        let response = client
+            .get(format!("http://{}/", address).parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.version(), http::Version::HTTP_2);
+        assert_eq!(
+            hyper::body::to_bytes(response.into_body()).await.unwrap(),
+            "ok"
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_peer_addresses_to_routes() {
+        let address = next_addr();
+        let http2 = Http2Config::default();
+        let listener = bind(&MaybeTlsSettings::Raw(()), &address, &http2)
+            .await
+            .unwrap();
+        let routes = remote_addr()
+            .map(|peer: Option<SocketAddr>| {
+                peer.map(|peer| peer.ip().to_string()).unwrap_or_default()
+            })
+            .boxed();
+        let (_trigger, tripwire) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            serve(
+                routes,
+                listener,
+                &http2,
+                &HttpKeepaliveConfig::default(),
+                None,
+                tripwire.map(|_| ()),
+            )
+            .await
+        });
+
+        let response = hyper::Client::new()
+            .get(format!("http://{}/", address).parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            hyper::body::to_bytes(response.into_body()).await.unwrap(),
+            "127.0.0.1"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_http2_unless_enabled() {
+        let (address, _trigger) =
+            start_server(Http2Config::default(), HttpKeepaliveConfig::default()).await;
+
+        let client = hyper::Client::builder()
+            .http2_only(true)
+            .build_http::<hyper::Body>();
+        assert!(client
+            .get(format!("http://{}/", address).parse().unwrap())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn closes_idle_connections() {
+        let (address, _trigger) = start_server(
+            Http2Config::default(),
+            HttpKeepaliveConfig {
+                enabled: true,
+                idle_timeout_secs: Some(1),
+            },
+        )
+        .await;
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        let mut buf = [0; 1];
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .expect("connection wasn't closed");
+        assert!(matches!(read, Ok(0) | Err(_)));
+    }
+
+    #[test]
+    fn parses_permitted_origins() {
+        assert!(PermitOrigin::new(&[]).unwrap().is_none());
+        assert!(PermitOrigin::new(&["10.0.0.0/33".to_string()]).is_err());
+
+        let permit_origin = PermitOrigin::new(&["10.0.0.0/8".to_string(), "::1/128".to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(permit_origin.permits([10, 1, 2, 3].into()));
+        assert!(permit_origin.permits(std::net::Ipv6Addr::LOCALHOST.into()));
+        assert!(!permit_origin.permits([192, 168, 0, 1].into()));
+    }
+
+    #[tokio::test]
+    async fn closes_connections_from_other_origins() {
+        let permit_origin = PermitOrigin::new(&["10.0.0.0/8".to_string()]).unwrap();
+        let (address, _trigger) = start_server_permitting(
+            Http2Config::default(),
+            HttpKeepaliveConfig::default(),
+            permit_origin,
+        )
+        .await;
+
+        let client = hyper::Client::new();
+        assert!(client
+            .get(format!("http://{}/", address).parse().unwrap())
+            .await
+            .is_err());
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(any(feature = "sources-http"))]
 mod body_decoding;
 mod encoding_config;
-#[cfg(any(feature = "sources-file", feature = "sources-kafka"))]
+#[cfg(any(
+    feature = "sources-azure_event_hubs",
+    feature = "sources-file",
+    feature = "sources-kafka"
+))]
 pub mod finalizer;
 #[cfg(all(unix, feature = "sources-dnstap"))]
 pub mod framestream;
@@ -9,8 +13,11 @@ pub mod framestream;
     feature = "sources-utils-http-auth",
     feature = "sources-utils-http-encoding",
     feature = "sources-utils-http-error",
+    feature = "sources-utils-http-idempotency",
     feature = "sources-utils-http-prelude",
-    feature = "sources-utils-http-query"
+    feature = "sources-utils-http-query",
+    feature = "sources-utils-http-quota",
+    feature = "sources-utils-http-server"
 ))]
 mod http;
 pub mod multiline_config;
@@ -21,6 +28,8 @@ mod tcp;
     feature = "codecs",
 ))]
 mod tcp_error;
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+mod tcp_limits;
 #[cfg(all(unix, feature = "sources-socket"))]
 mod unix_datagram;
 #[cfg(all(unix, feature = "sources-utils-unix"))]
@@ -35,21 +44,29 @@ pub use self::http::add_query_parameters;
     feature = "sources-utils-http-encoding"
 ))]
 pub use self::http::decode;
+#[cfg(feature = "sources-utils-http-server")]
+pub(crate) use self::http::server as http_server;
 #[cfg(feature = "sources-utils-http-error")]
 pub use self::http::ErrorMessage;
 #[cfg(feature = "sources-utils-http-prelude")]
 pub use self::http::HttpSource;
 #[cfg(feature = "sources-utils-http-auth")]
 pub use self::http::HttpSourceAuthConfig;
+#[cfg(feature = "sources-utils-http-idempotency")]
+pub use self::http::{DuplicateRequest, Idempotency, IdempotencyClaim, IdempotencyConfig};
+#[cfg(feature = "sources-utils-http-quota")]
+pub use self::http::{Quota, QuotaConfig, QuotaExceeded, QuotaIdentity, QuotaPermit};
 pub use encoding_config::EncodingConfig;
 pub use multiline_config::MultilineConfig;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
-pub use tcp::{SocketListenAddr, TcpSource};
+pub use tcp::{SocketListenAddr, TcpConnection, TcpSource};
 #[cfg(any(
     all(feature = "sources-utils-tls", feature = "listenfd"),
     feature = "codecs",
 ))]
 pub use tcp_error::TcpError;
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+pub use tcp_limits::TcpConnectionLimits;
 #[cfg(all(unix, feature = "sources-socket",))]
 pub use unix_datagram::build_unix_datagram_source;
 #[cfg(all(unix, feature = "sources-utils-unix",))]
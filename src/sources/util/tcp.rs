@@ -1,3 +1,4 @@
+use super::tcp_limits::{ConnectionLimiter, ConnectionPermit, TcpConnectionLimits};
 use crate::{
     config::Resource,
     event::Event,
@@ -13,6 +14,7 @@ use crate::{
 use bytes::Bytes;
 use futures::{future::BoxFuture, FutureExt, Sink, SinkExt, StreamExt};
 use listenfd::ListenFd;
+use openssl::ssl::NameType;
 use pin_project::pin_project;
 use serde::{de, Deserialize, Deserializer, Serialize};
 use smallvec::SmallVec;
@@ -61,6 +63,19 @@ async fn make_listener(
     }
 }
 
+/// The connection a batch of events was received over.
+#[derive(Clone, Debug)]
+pub struct TcpConnection {
+    /// The peer IP address, as passed to `TcpSource::handle_events`.
+    pub host: Bytes,
+    pub peer_addr: SocketAddr,
+    /// The local address the connection was accepted on.
+    pub local_addr: Option<SocketAddr>,
+    pub tls: bool,
+    /// The server name the peer requested through TLS SNI, if any.
+    pub tls_server_name: Option<String>,
+}
+
 pub trait TcpSource: Clone + Send + Sync + 'static
 where
     <<Self as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
@@ -75,6 +90,17 @@ where
 
     fn handle_events(&self, _events: &mut [Event], _host: Bytes, _byte_size: usize) {}
 
+    /// Like `handle_events`, for sources that need more than the peer IP
+    /// address of the connection.
+    fn handle_connection_events(
+        &self,
+        events: &mut [Event],
+        connection: &TcpConnection,
+        byte_size: usize,
+    ) {
+        self.handle_events(events, connection.host.clone(), byte_size)
+    }
+
     fn build_ack(&self, _item: &Self::Item) -> Bytes {
         Bytes::new()
     }
@@ -86,10 +112,14 @@ where
         shutdown_timeout_secs: u64,
         tls: MaybeTlsSettings,
         receive_buffer_bytes: Option<usize>,
+        connection_limits: TcpConnectionLimits,
         shutdown_signal: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<crate::sources::Source> {
+        connection_limits.validate()?;
+
         let out = out.sink_map_err(|error| error!(message = "Error sending event.", %error));
+        let limiter = ConnectionLimiter::new(connection_limits);
 
         let listenfd = ListenFd::from_env();
 
@@ -126,6 +156,7 @@ where
                     let source = self.clone();
                     let out = out.clone();
                     let connection_gauge = connection_gauge.clone();
+                    let limiter = limiter.clone();
 
                     async move {
                         let socket = match connection {
@@ -140,6 +171,11 @@ where
                         };
 
                         let peer_addr = socket.peer_addr();
+                        // Dropping the socket closes connections over the limits.
+                        let permit = match limiter.accept(peer_addr.ip()) {
+                            Some(permit) => permit,
+                            None => return,
+                        };
                         let span = info_span!("connection", %peer_addr);
 
                         let tripwire = tripwire
@@ -165,6 +201,7 @@ where
                                 source,
                                 tripwire,
                                 peer_addr.ip(),
+                                permit,
                                 out,
                             );
 
@@ -188,6 +225,7 @@ async fn handle_stream<T>(
     source: T,
     mut tripwire: BoxFuture<'static, ()>,
     peer_addr: IpAddr,
+    permit: ConnectionPermit,
     mut out: impl Sink<Event> + Send + 'static + Unpin,
 ) where
     <<T as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
@@ -217,9 +255,19 @@ async fn handle_stream<T>(
         }
     }
 
+    let ssl_stream = socket.ssl_stream();
+    let connection = TcpConnection {
+        host: Bytes::from(peer_addr.to_string()),
+        peer_addr: socket.peer_addr(),
+        local_addr: socket.get_ref().and_then(|stream| stream.local_addr().ok()),
+        tls: ssl_stream.is_some(),
+        tls_server_name: ssl_stream
+            .and_then(|stream| stream.ssl().servername(NameType::HOST_NAME))
+            .map(Into::into),
+    };
+
     let socket = TcpSocketWrapper::new(socket, peer_addr);
     let mut reader = FramedRead::new(socket, source.decoder());
-    let host = Bytes::from(peer_addr.to_string());
 
     loop {
         tokio::select! {
@@ -245,7 +293,8 @@ async fn handle_stream<T>(
                     Some(Ok((item, byte_size))) => {
                         let ack = source.build_ack(&item);
                         let mut events = item.into();
-                        source.handle_events(&mut events, host.clone(), byte_size);
+                        source.handle_connection_events(&mut events, &connection, byte_size);
+                        permit.throttle(events.len()).await;
                         for event in events {
                             match out.send(event).await {
                                 Ok(_) => {
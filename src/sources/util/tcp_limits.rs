@@ -0,0 +1,252 @@
+//! Limits keeping a single peer of a TCP source from starving the others,
+//! by opening too many connections or sending events too quickly.
+//!
+//! Connections over the limits are closed right away, while connections
+//! sending events too quickly are throttled, by not reading from them until
+//! the rate goes back under the limit.
+
+use crate::internal_events::{TcpConnectionRejected, TcpConnectionThrottled};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TcpConnectionLimits {
+    /// The maximum number of connections open at once.
+    pub max_connections: Option<usize>,
+    /// The maximum number of connections open at once from a single peer IP.
+    pub max_connections_per_ip: Option<usize>,
+    /// The maximum number of events per second read from a single connection.
+    pub max_events_per_sec_per_connection: Option<u32>,
+    /// The maximum number of events per second read from all the connections
+    /// of a single peer IP.
+    pub max_events_per_sec_per_ip: Option<u32>,
+}
+
+impl TcpConnectionLimits {
+    pub fn validate(&self) -> crate::Result<()> {
+        let limits = [
+            self.max_connections.map(|limit| limit as u64),
+            self.max_connections_per_ip.map(|limit| limit as u64),
+            self.max_events_per_sec_per_connection.map(Into::into),
+            self.max_events_per_sec_per_ip.map(Into::into),
+        ];
+        if limits.contains(&Some(0)) {
+            return Err("Connection limits must be greater than zero.".into());
+        }
+        Ok(())
+    }
+}
+
+/// Tracks the connections open by each peer, to enforce the limits.
+#[derive(Clone, Debug)]
+pub(super) struct ConnectionLimiter {
+    limits: TcpConnectionLimits,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    connections: usize,
+    peers: HashMap<IpAddr, Peer>,
+}
+
+#[derive(Debug)]
+struct Peer {
+    connections: usize,
+    rate: Option<Arc<Mutex<TokenBucket>>>,
+}
+
+impl ConnectionLimiter {
+    pub fn new(limits: TcpConnectionLimits) -> Self {
+        Self {
+            limits,
+            state: Arc::default(),
+        }
+    }
+
+    /// Registers a new connection from `peer_addr`, unless it's over the
+    /// limits.
+    pub fn accept(&self, peer_addr: IpAddr) -> Option<ConnectionPermit> {
+        let mut state = self.state.lock().expect("poisoned lock");
+
+        let peer_connections = state
+            .peers
+            .get(&peer_addr)
+            .map_or(0, |peer| peer.connections);
+        let reached = |limit: Option<usize>, count: usize| limit.map_or(false, |max| count >= max);
+        let rejection = if reached(self.limits.max_connections, state.connections) {
+            Some("max_connections")
+        } else if reached(self.limits.max_connections_per_ip, peer_connections) {
+            Some("max_connections_per_ip")
+        } else {
+            None
+        };
+        if let Some(reason) = rejection {
+            emit!(&TcpConnectionRejected { peer_addr, reason });
+            return None;
+        }
+
+        state.connections += 1;
+        let max_events_per_sec_per_ip = self.limits.max_events_per_sec_per_ip;
+        let peer = state.peers.entry(peer_addr).or_insert_with(|| Peer {
+            connections: 0,
+            rate: max_events_per_sec_per_ip
+                .map(|rate| Arc::new(Mutex::new(TokenBucket::new(rate)))),
+        });
+        peer.connections += 1;
+
+        let mut rates = Vec::with_capacity(2);
+        if let Some(rate) = self.limits.max_events_per_sec_per_connection {
+            rates.push(Arc::new(Mutex::new(TokenBucket::new(rate))));
+        }
+        rates.extend(peer.rate.clone());
+
+        Some(ConnectionPermit {
+            peer_addr,
+            rates,
+            limiter: self.clone(),
+        })
+    }
+
+    fn release(&self, peer_addr: IpAddr) {
+        let mut state = self.state.lock().expect("poisoned lock");
+        state.connections -= 1;
+        if let Some(peer) = state.peers.get_mut(&peer_addr) {
+            peer.connections -= 1;
+            if peer.connections == 0 {
+                state.peers.remove(&peer_addr);
+            }
+        }
+    }
+}
+
+/// An open connection, counted against the limits until it's dropped.
+#[derive(Debug)]
+pub(super) struct ConnectionPermit {
+    peer_addr: IpAddr,
+    rates: Vec<Arc<Mutex<TokenBucket>>>,
+    limiter: ConnectionLimiter,
+}
+
+impl ConnectionPermit {
+    /// Waits until `events` more events can be read from the connection
+    /// without going over the rate limits.
+    pub async fn throttle(&self, events: usize) {
+        let delay = self
+            .rates
+            .iter()
+            .map(|rate| rate.lock().expect("poisoned lock").take(events))
+            .max()
+            .unwrap_or_default();
+
+        if delay > Duration::from_secs(0) {
+            emit!(&TcpConnectionThrottled {
+                peer_addr: self.peer_addr,
+                delay,
+            });
+            sleep(delay).await;
+        }
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.limiter.release(self.peer_addr);
+    }
+}
+
+/// A token bucket refilled with `rate` tokens per second, allowing bursts of
+/// up to `rate` tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32) -> Self {
+        Self {
+            rate: rate.into(),
+            tokens: rate.into(),
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Takes `count` tokens, returning how long to wait until they would have
+    /// been available. Tokens taken in advance are paid back by later
+    /// refills, so concurrent takers are throttled in turn.
+    fn take(&mut self, count: usize) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.refilled_at = now;
+
+        self.tokens -= count as f64;
+        if self.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn rejects_connections_over_the_limits() {
+        let limiter = ConnectionLimiter::new(TcpConnectionLimits {
+            max_connections: Some(3),
+            max_connections_per_ip: Some(2),
+            ..Default::default()
+        });
+        let first = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let second = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        let permit = limiter.accept(first).unwrap();
+        let _other = limiter.accept(first).unwrap();
+        assert!(limiter.accept(first).is_none());
+
+        let _third = limiter.accept(second).unwrap();
+        assert!(limiter.accept(second).is_none());
+
+        drop(permit);
+        assert!(limiter.accept(first).is_some());
+    }
+
+    #[test]
+    fn token_bucket_delays_bursts() {
+        let mut bucket = TokenBucket::new(10);
+
+        assert_eq!(bucket.take(10), Duration::from_secs(0));
+        let delay = bucket.take(5);
+        assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn shares_rate_limit_between_connections_of_a_peer() {
+        let limiter = ConnectionLimiter::new(TcpConnectionLimits {
+            max_events_per_sec_per_ip: Some(100),
+            ..Default::default()
+        });
+        let peer = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        let first = limiter.accept(peer).unwrap();
+        let second = limiter.accept(peer).unwrap();
+
+        let start = Instant::now();
+        first.throttle(100).await;
+        second.throttle(10).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
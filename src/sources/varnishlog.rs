@@ -0,0 +1,543 @@
+use crate::{
+    codecs::CharacterDelimitedCodec,
+    config::{log_schema, DataType, SourceConfig, SourceContext, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::VarnishlogEventsReceived,
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{collections::BTreeMap, path::PathBuf, process::Stdio, time::Duration};
+use tokio::{process::Command, time::sleep};
+use tokio_util::codec::FramedRead;
+
+const VARNISHLOG: &str = "varnishlog";
+
+const BACKOFF_DURATION: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("The tag {:?} is in both include_tags and exclude_tags", tag))]
+    DuplicatedTag { tag: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct VarnishlogConfig {
+    pub varnishlog_path: Option<PathBuf>,
+    pub instance: Option<String>,
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    pub query: Option<String>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<VarnishlogConfig>("varnishlog")
+}
+
+impl_generate_config_from_default!(VarnishlogConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "varnishlog")]
+impl SourceConfig for VarnishlogConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if let Some(tag) = self
+            .include_tags
+            .iter()
+            .find(|tag| self.exclude_tags.contains(tag))
+        {
+            let tag = tag.clone();
+            return Err(BuildError::DuplicatedTag { tag }.into());
+        }
+
+        Ok(Box::pin(varnishlog(self.clone(), cx.shutdown, cx.out)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "varnishlog"
+    }
+}
+
+/// Runs `varnishlog`, restarting it whenever it stops, and sends an event for
+/// each transaction group it outputs.
+async fn varnishlog(
+    config: VarnishlogConfig,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    loop {
+        info!("Starting varnishlog.");
+        match create_command(&config).spawn() {
+            Ok(mut child) => {
+                let stdout = child.stdout.take().expect("stdout is piped");
+                let mut lines = FramedRead::new(stdout, CharacterDelimitedCodec::new('\n'));
+                let mut grouper = TransactionGrouper::default();
+                let mut byte_size = 0;
+
+                loop {
+                    let line = tokio::select! {
+                        _ = &mut shutdown => return Ok(()),
+                        line = lines.next() => line,
+                    };
+                    let line = match line {
+                        Some(Ok(line)) => line,
+                        Some(Err(error)) => {
+                            error!(message = "Could not read from varnishlog.", %error);
+                            break;
+                        }
+                        None => {
+                            warn!("Varnishlog process stopped.");
+                            break;
+                        }
+                    };
+
+                    byte_size += line.len();
+                    let transactions = match grouper.push(&String::from_utf8_lossy(&line)) {
+                        Some(transactions) => transactions,
+                        None => continue,
+                    };
+                    emit!(&VarnishlogEventsReceived {
+                        count: transactions.len(),
+                        byte_size,
+                    });
+                    byte_size = 0;
+
+                    let mut events =
+                        futures::stream::iter(transactions.into_iter().map(create_event).map(Ok));
+                    if let Err(error) = out.send_all(&mut events).await {
+                        error!(message = "Could not send varnishlog events.", %error);
+                        return Err(());
+                    }
+                }
+                // `child` is killed on drop.
+            }
+            Err(error) => {
+                error!(message = "Error starting varnishlog process.", %error);
+            }
+        }
+
+        // varnishlog should never stop, so it is an error if we reach here.
+        tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            _ = sleep(BACKOFF_DURATION) => {}
+        }
+    }
+}
+
+/// Creates the varnishlog command, grouping the records by client request, so
+/// that each group holds a request along with the backend requests and ESI
+/// subrequests it started.
+fn create_command(config: &VarnishlogConfig) -> Command {
+    let mut command = Command::new(
+        config
+            .varnishlog_path
+            .clone()
+            .unwrap_or_else(|| VARNISHLOG.into()),
+    );
+    command.stdout(Stdio::piped());
+    command.kill_on_drop(true);
+    command.args(&["-g", "request"]);
+
+    if let Some(instance) = &config.instance {
+        command.arg("-n").arg(instance);
+    }
+    if !config.include_tags.is_empty() {
+        command.arg("-i").arg(config.include_tags.join(","));
+    }
+    if !config.exclude_tags.is_empty() {
+        command.arg("-x").arg(config.exclude_tags.join(","));
+    }
+    if let Some(query) = &config.query {
+        command.arg("-q").arg(query);
+    }
+
+    command
+}
+
+/// A transaction from the varnishlog output, along with the transactions it
+/// started.
+#[derive(Debug, Default, PartialEq)]
+struct Transaction {
+    kind: String,
+    vxid: Option<i64>,
+    records: Vec<(String, String)>,
+    children: Vec<Transaction>,
+}
+
+/// Groups the lines of `varnishlog -g request` into transactions. Each
+/// transaction starts with a header line, such as `**  << BeReq    >> 32771`,
+/// followed by its records, such as `--  BereqMethod    GET`, where the number
+/// of `*` or `-` is its nesting level. Groups end with an empty line.
+#[derive(Debug, Default)]
+struct TransactionGrouper {
+    /// The transactions being read, from the outermost to the innermost.
+    open: Vec<Transaction>,
+    /// The outermost transactions of the group being read.
+    closed: Vec<Transaction>,
+}
+
+impl TransactionGrouper {
+    /// Reads a line, returning the outermost transactions of the group it
+    /// ends, if any.
+    fn push(&mut self, line: &str) -> Option<Vec<Transaction>> {
+        let line = line.trim_end();
+        if line.is_empty() {
+            self.close(0);
+            return (!self.closed.is_empty()).then(|| std::mem::take(&mut self.closed));
+        }
+
+        let level = line.chars().take_while(|c| *c == '*').count();
+        if level > 0 {
+            let header = line[level..].trim().trim_start_matches("<<");
+            let (kind, vxid) = match header.split_once(">>") {
+                Some((kind, vxid)) => (kind.trim(), vxid.trim().parse().ok()),
+                None => (header.trim(), None),
+            };
+            self.close(level - 1);
+            self.open.push(Transaction {
+                kind: kind.to_owned(),
+                vxid,
+                ..Default::default()
+            });
+            return None;
+        }
+
+        let level = line.chars().take_while(|c| *c == '-').count();
+        if level > 0 {
+            let record = line[level..].trim_start();
+            let (tag, value) = match record.split_once(char::is_whitespace) {
+                Some((tag, value)) => (tag, value.trim()),
+                None => (record, ""),
+            };
+            self.close(level);
+            if let Some(transaction) = self.open.last_mut() {
+                transaction.records.push((tag.to_owned(), value.to_owned()));
+            }
+        }
+
+        None
+    }
+
+    /// Closes the transactions nested deeper than the level.
+    fn close(&mut self, level: usize) {
+        while self.open.len() > level {
+            let transaction = self.open.pop().expect("length was checked");
+            match self.open.last_mut() {
+                Some(parent) => parent.children.push(transaction),
+                None => self.closed.push(transaction),
+            }
+        }
+    }
+}
+
+fn create_event(transaction: Transaction) -> Event {
+    let (fields, started_at) = transaction_fields(transaction);
+    let mut log = LogEvent::from(fields);
+    log.insert(
+        log_schema().timestamp_key(),
+        started_at.unwrap_or_else(Utc::now),
+    );
+    log.insert(log_schema().source_type_key(), Bytes::from("varnishlog"));
+    log.into()
+}
+
+/// The fields of a transaction and of the transactions it started, along with
+/// the time it started at.
+fn transaction_fields(
+    transaction: Transaction,
+) -> (BTreeMap<String, Value>, Option<chrono::DateTime<Utc>>) {
+    let mut fields = BTreeMap::new();
+    let mut client = BTreeMap::new();
+    let mut request = BTreeMap::new();
+    let mut request_headers = BTreeMap::new();
+    let mut response = BTreeMap::new();
+    let mut response_headers = BTreeMap::new();
+    let mut timings = BTreeMap::new();
+    let mut vcl_calls = Vec::new();
+    let mut vcl_logs = Vec::new();
+    let mut errors = Vec::new();
+    let mut started_at = None;
+
+    fields.insert("type".into(), transaction.kind.to_lowercase().into());
+    if let Some(vxid) = transaction.vxid {
+        fields.insert("vxid".into(), vxid.into());
+    }
+
+    for (tag, value) in transaction.records {
+        match tag.as_str() {
+            "ReqMethod" | "BereqMethod" => {
+                request.insert("method".into(), value.into());
+            }
+            "ReqURL" | "BereqURL" => {
+                request.insert("url".into(), value.into());
+            }
+            "ReqProtocol" | "BereqProtocol" => {
+                request.insert("protocol".into(), value.into());
+            }
+            "ReqHeader" | "BereqHeader" => set_header(&mut request_headers, &value),
+            "ReqUnset" | "BereqUnset" => unset_header(&mut request_headers, &value),
+            "RespStatus" | "BerespStatus" => {
+                let status = value
+                    .parse::<i64>()
+                    .map_or_else(|_| value.into(), Into::into);
+                response.insert("status".into(), status);
+            }
+            "RespReason" | "BerespReason" => {
+                response.insert("reason".into(), value.into());
+            }
+            "RespProtocol" | "BerespProtocol" => {
+                response.insert("protocol".into(), value.into());
+            }
+            "RespHeader" | "BerespHeader" => set_header(&mut response_headers, &value),
+            "RespUnset" | "BerespUnset" => unset_header(&mut response_headers, &value),
+            "ReqStart" => {
+                // `<client ip> <client port> <listener>`
+                let mut parts = value.split_whitespace();
+                if let Some(ip) = parts.next() {
+                    client.insert("ip".into(), ip.into());
+                }
+                if let Some(port) = parts.next().and_then(|port| port.parse::<i64>().ok()) {
+                    client.insert("port".into(), port.into());
+                }
+            }
+            "BackendOpen" => {
+                // `<fd> <backend name> <remote ip> <remote port> ...`
+                if let Some(name) = value.split_whitespace().nth(1) {
+                    fields.insert("backend".into(), name.into());
+                }
+            }
+            "Timestamp" => {
+                // `<label>: <absolute> <since start> <since last>`
+                if let Some((label, times)) = value.split_once(':') {
+                    let mut times = times.split_whitespace().map(str::parse::<f64>);
+                    if let (Some(Ok(absolute)), Some(Ok(since_start))) =
+                        (times.next(), times.next())
+                    {
+                        if started_at.is_none() {
+                            started_at = Some(Utc.timestamp(
+                                absolute.trunc() as i64,
+                                (absolute.fract() * 1e9) as u32,
+                            ));
+                        }
+                        timings.insert(label.to_lowercase(), since_start.into());
+                    }
+                }
+            }
+            "VCL_call" => vcl_calls.push(Value::from(value)),
+            "VCL_Log" => vcl_logs.push(Value::from(value)),
+            "Error" | "FetchError" => errors.push(Value::from(value)),
+            _ => {}
+        }
+    }
+
+    if !request_headers.is_empty() {
+        request.insert("headers".into(), request_headers.into());
+    }
+    if !response_headers.is_empty() {
+        response.insert("headers".into(), response_headers.into());
+    }
+    for (name, map) in [
+        ("client", client),
+        ("request", request),
+        ("response", response),
+        ("timings", timings),
+    ] {
+        if !map.is_empty() {
+            fields.insert(name.into(), map.into());
+        }
+    }
+    for (name, list) in [
+        ("vcl_calls", vcl_calls),
+        ("vcl_logs", vcl_logs),
+        ("errors", errors),
+    ] {
+        if !list.is_empty() {
+            fields.insert(name.into(), Value::Array(list));
+        }
+    }
+    if let Some(started_at) = started_at {
+        fields.insert("started_at".into(), started_at.into());
+    }
+
+    let (backend_requests, subrequests) = transaction
+        .children
+        .into_iter()
+        .partition::<Vec<_>, _>(|child| child.kind == "BeReq");
+    for (name, children) in [
+        ("backend_requests", backend_requests),
+        ("subrequests", subrequests),
+    ] {
+        if !children.is_empty() {
+            let children = children
+                .into_iter()
+                .map(|child| Value::from(transaction_fields(child).0))
+                .collect();
+            fields.insert(name.into(), Value::Array(children));
+        }
+    }
+
+    (fields, started_at)
+}
+
+/// Sets a header from a `<name>: <value>` record, by its lowercased name.
+fn set_header(headers: &mut BTreeMap<String, Value>, header: &str) {
+    if let Some((name, value)) = header.split_once(':') {
+        headers.insert(name.trim().to_lowercase(), value.trim().into());
+    }
+}
+
+/// Removes a header unset by VCL.
+fn unset_header(headers: &mut BTreeMap<String, Value>, header: &str) {
+    let name = header.split(':').next().unwrap_or_default();
+    headers.remove(&name.trim().to_lowercase());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<VarnishlogConfig>();
+    }
+
+    const OUTPUT: &str = r#"*   << Request  >> 32770
+-   Begin          req 32769 rxreq
+-   Timestamp      Start: 1470403413.664824 0.000000 0.000000
+-   ReqStart       127.0.0.1 39470 a0
+-   ReqMethod      GET
+-   ReqURL         /index.html
+-   ReqProtocol    HTTP/1.1
+-   ReqHeader      Host: localhost
+-   ReqHeader      X-Debug: 1
+-   ReqUnset       X-Debug: 1
+-   VCL_call       RECV
+-   VCL_Log        cache miss
+-   Link           bereq 32771 fetch
+-   Timestamp      Fetch: 1470403413.666315 0.001491 0.001491
+-   RespProtocol   HTTP/1.1
+-   RespStatus     200
+-   RespReason     OK
+-   RespHeader     Content-Type: text/html
+-   Timestamp      Resp: 1470403413.666515 0.001691 0.000200
+-   End
+**  << BeReq    >> 32771
+--  Begin          bereq 32770 fetch
+--  Timestamp      Start: 1470403413.664934 0.000000 0.000000
+--  BereqMethod    GET
+--  BereqURL       /index.html
+--  BereqHeader    Host: localhost
+--  BackendOpen    26 boot.default 127.0.0.1 8080 127.0.0.1 51058
+--  BerespStatus   200
+--  BerespHeader   Content-Length: 42
+--  Timestamp      Beresp: 1470403413.666127 0.001193 0.001193
+--  End
+
+"#;
+
+    fn group(output: &str) -> Vec<Transaction> {
+        let mut grouper = TransactionGrouper::default();
+        let mut groups = output.lines().filter_map(|line| grouper.push(line));
+        let transactions = groups.next().expect("output should hold a group");
+        assert!(groups.next().is_none());
+        transactions
+    }
+
+    #[test]
+    fn groups_transactions() {
+        let transactions = group(OUTPUT);
+        assert_eq!(transactions.len(), 1);
+
+        let request = &transactions[0];
+        assert_eq!(request.kind, "Request");
+        assert_eq!(request.vxid, Some(32770));
+        assert_eq!(
+            request.records[0],
+            ("Begin".into(), "req 32769 rxreq".into())
+        );
+        assert_eq!(request.records.last(), Some(&("End".into(), "".into())));
+        assert_eq!(request.children.len(), 1);
+        assert_eq!(request.children[0].kind, "BeReq");
+        assert_eq!(request.children[0].vxid, Some(32771));
+        assert!(request.children[0].children.is_empty());
+    }
+
+    #[test]
+    fn creates_transaction_events() {
+        let event = create_event(group(OUTPUT).remove(0));
+        let log = event.as_log();
+
+        assert_eq!(log["type"], "request".into());
+        assert_eq!(log["vxid"], 32770.into());
+        assert_eq!(log["client.ip"], "127.0.0.1".into());
+        assert_eq!(log["client.port"], 39470.into());
+        assert_eq!(log["request.method"], "GET".into());
+        assert_eq!(log["request.url"], "/index.html".into());
+        assert_eq!(log["request.headers.host"], "localhost".into());
+        assert!(!log.contains("request.headers.x-debug"));
+        assert_eq!(log["response.status"], 200.into());
+        assert_eq!(log["response.reason"], "OK".into());
+        assert_eq!(log["response.headers.content-type"], "text/html".into());
+        assert_eq!(log["timings.resp"], 0.001691.into());
+        assert_eq!(log["vcl_calls"], Value::Array(vec!["RECV".into()]));
+        assert_eq!(log["vcl_logs"], Value::Array(vec!["cache miss".into()]));
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1470403413, 664824000).into()
+        );
+
+        assert_eq!(log["backend_requests[0].type"], "bereq".into());
+        assert_eq!(log["backend_requests[0].backend"], "boot.default".into());
+        assert_eq!(log["backend_requests[0].request.url"], "/index.html".into());
+        assert_eq!(log["backend_requests[0].response.status"], 200.into());
+        assert_eq!(
+            log["backend_requests[0].response.headers.content-length"],
+            "42".into()
+        );
+        assert_eq!(log["backend_requests[0].timings.beresp"], 0.001193.into());
+    }
+
+    #[test]
+    fn groups_filtered_records() {
+        // Filtering tags can leave groups without their `Begin` and `End`
+        // records, or with nothing but headers.
+        let transactions =
+            group("*   << Request  >> 5\n-   ReqURL         /\n**  << BeReq    >> 6\n\n");
+        assert_eq!(transactions[0].records, vec![("ReqURL".into(), "/".into())]);
+        assert_eq!(transactions[0].children[0].records, vec![]);
+    }
+
+    #[test]
+    fn command_options() {
+        let command = format!("{:?}", create_command(&VarnishlogConfig::default()));
+        assert!(command.starts_with("\"varnishlog\""));
+        assert!(command.contains("\"-g\" \"request\""));
+        assert!(!command.contains("\"-n\""));
+        assert!(!command.contains("\"-i\""));
+        assert!(!command.contains("\"-q\""));
+
+        let command = format!(
+            "{:?}",
+            create_command(&VarnishlogConfig {
+                varnishlog_path: Some("/opt/varnish/bin/varnishlog".into()),
+                instance: Some("edge".into()),
+                include_tags: vec!["Req*".into(), "Resp*".into()],
+                exclude_tags: vec!["ReqHeader".into()],
+                query: Some("RespStatus >= 500".into()),
+            })
+        );
+        assert!(command.starts_with("\"/opt/varnish/bin/varnishlog\""));
+        assert!(command.contains("\"-n\" \"edge\""));
+        assert!(command.contains("\"-i\" \"Req*,Resp*\""));
+        assert!(command.contains("\"-x\" \"ReqHeader\""));
+        assert!(command.contains("\"-q\" \"RespStatus >= 500\""));
+    }
+}
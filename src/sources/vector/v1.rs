@@ -4,7 +4,7 @@ use crate::{
     event::{proto, Event},
     internal_events::{VectorEventReceived, VectorProtoDecodeError},
     sources::{
-        util::{SocketListenAddr, TcpSource},
+        util::{SocketListenAddr, TcpConnectionLimits, TcpSource},
         Source,
     },
     tcp::TcpKeepaliveConfig,
@@ -26,6 +26,8 @@ pub struct VectorConfig {
     #[set = "pub"]
     tls: Option<TlsConfig>,
     receive_buffer_bytes: Option<usize>,
+    #[serde(default)]
+    connection_limits: TcpConnectionLimits,
 }
 
 const fn default_shutdown_timeout_secs() -> u64 {
@@ -33,13 +35,14 @@ const fn default_shutdown_timeout_secs() -> u64 {
 }
 
 impl VectorConfig {
-    pub const fn from_address(address: SocketListenAddr) -> Self {
+    pub fn from_address(address: SocketListenAddr) -> Self {
         Self {
             address,
             keepalive: None,
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             tls: None,
             receive_buffer_bytes: None,
+            connection_limits: TcpConnectionLimits::default(),
         }
     }
 }
@@ -63,6 +66,7 @@ impl VectorConfig {
             self.shutdown_timeout_secs,
             tls,
             self.receive_buffer_bytes,
+            self.connection_limits,
             cx.shutdown,
             cx.out,
         )
@@ -242,6 +246,7 @@ mod test {
                 globals: GlobalOptions::default(),
                 shutdown,
                 out: tx,
+                named_outputs: Default::default(),
                 acknowledgements: true,
                 proxy: Default::default(),
             })
@@ -280,6 +285,7 @@ mod test {
                 globals: GlobalOptions::default(),
                 shutdown,
                 out: tx,
+                named_outputs: Default::default(),
                 acknowledgements: true,
                 proxy: Default::default(),
             })
@@ -1,18 +1,19 @@
 use super::{
-    CreateAcceptor, Handshake, IncomingListener, MaybeTlsSettings, MaybeTlsStream, SslBuildError,
-    TcpBind, TlsError, TlsSettings,
+    spiffe::Svid, CreateAcceptor, Handshake, IncomingListener, MaybeTlsSettings, MaybeTlsStream,
+    SslBuildError, TcpBind, TlsError, TlsSettings,
 };
 #[cfg(feature = "sources-utils-tcp-socket")]
 use crate::tcp;
 #[cfg(feature = "sources-utils-tcp-keepalive")]
 use crate::tcp::TcpKeepaliveConfig;
 use futures::{future::BoxFuture, stream, FutureExt, Stream};
-use openssl::ssl::{Ssl, SslAcceptor, SslMethod};
+use openssl::ssl::{select_next_proto, AlpnError, Ssl, SslAcceptor, SslMethod};
 use snafu::ResultExt;
 use std::{
     future::Future,
     net::SocketAddr,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 use tokio::{
@@ -22,46 +23,107 @@ use tokio::{
 use tokio_openssl::SslStream;
 
 impl TlsSettings {
-    pub(crate) fn acceptor(&self) -> crate::tls::Result<SslAcceptor> {
-        match self.identity {
-            None => Err(TlsError::MissingRequiredIdentity),
-            Some(_) => {
-                let mut acceptor =
-                    SslAcceptor::mozilla_intermediate(SslMethod::tls()).context(CreateAcceptor)?;
-                self.apply_context(&mut acceptor)?;
-                Ok(acceptor.build())
-            }
+    /// Builds an acceptor, selecting one of the `alpn` protocols, given in
+    /// wire format, if the client offers any.
+    pub(crate) fn acceptor(&self, alpn: Option<&'static [u8]>) -> crate::tls::Result<SslAcceptor> {
+        if !self.has_identity() {
+            return Err(TlsError::MissingRequiredIdentity);
+        }
+
+        let mut acceptor =
+            SslAcceptor::mozilla_intermediate(SslMethod::tls()).context(CreateAcceptor)?;
+        self.apply_context(&mut acceptor)?;
+        if let Some(protocols) = alpn {
+            acceptor.set_alpn_select_callback(move |_, client| {
+                select_next_proto(protocols, client).ok_or(AlpnError::NOACK)
+            });
         }
+        Ok(acceptor.build())
     }
 }
 
 impl MaybeTlsSettings {
     pub(crate) async fn bind(&self, addr: &SocketAddr) -> crate::tls::Result<MaybeTlsListener> {
+        self.bind_with_alpn(addr, None).await
+    }
+
+    /// Binds a listener whose TLS connections negotiate one of the `alpn`
+    /// protocols, given in wire format.
+    pub(crate) async fn bind_with_alpn(
+        &self,
+        addr: &SocketAddr,
+        alpn: Option<&'static [u8]>,
+    ) -> crate::tls::Result<MaybeTlsListener> {
         let listener = TcpListener::bind(addr).await.context(TcpBind)?;
 
-        let acceptor = match self {
-            Self::Tls(tls) => Some(tls.acceptor()?),
-            Self::Raw(()) => None,
+        let (acceptor, rotation) = match self {
+            Self::Tls(tls) => {
+                // Listeners don't start until their SPIFFE identity is available.
+                tls.spiffe_ready().await?;
+                let rotation = tls.spiffe_svid().map(|svid| SpiffeRotation {
+                    settings: tls.clone(),
+                    alpn,
+                    svid,
+                });
+                (Some(tls.acceptor(alpn)?), rotation)
+            }
+            Self::Raw(()) => (None, None),
         };
 
-        Ok(MaybeTlsListener { listener, acceptor })
+        Ok(MaybeTlsListener {
+            listener,
+            acceptor,
+            rotation,
+        })
     }
 }
 
 pub struct MaybeTlsListener {
     listener: TcpListener,
     acceptor: Option<SslAcceptor>,
+    rotation: Option<SpiffeRotation>,
+}
+
+/// Tracks the SPIFFE identity a listener's acceptor was built with, to rebuild it when the
+/// identity rotates.
+struct SpiffeRotation {
+    settings: TlsSettings,
+    alpn: Option<&'static [u8]>,
+    svid: Arc<Svid>,
 }
 
 impl MaybeTlsListener {
     pub(crate) async fn accept(&mut self) -> crate::tls::Result<MaybeTlsIncomingStream<TcpStream>> {
-        self.listener
-            .accept()
-            .await
-            .map(|(stream, peer_addr)| {
-                MaybeTlsIncomingStream::new(stream, peer_addr, self.acceptor.clone())
-            })
-            .context(IncomingListener)
+        let (stream, peer_addr) = self.listener.accept().await.context(IncomingListener)?;
+        self.rotate_acceptor();
+        Ok(MaybeTlsIncomingStream::new(
+            stream,
+            peer_addr,
+            self.acceptor.clone(),
+        ))
+    }
+
+    fn rotate_acceptor(&mut self) {
+        let rotation = match &mut self.rotation {
+            Some(rotation) => rotation,
+            None => return,
+        };
+        let svid = match rotation.settings.spiffe_svid() {
+            Some(svid) if !Arc::ptr_eq(&svid, &rotation.svid) => svid,
+            _ => return,
+        };
+
+        match rotation.settings.acceptor(rotation.alpn) {
+            Ok(acceptor) => {
+                self.acceptor = Some(acceptor);
+                rotation.svid = svid;
+                debug!(message = "Rotated TLS identity of listener.");
+            }
+            Err(error) => {
+                // Keep the previous identity, and try again on the next connection.
+                warn!(message = "Failed to rotate TLS identity of listener.", %error);
+            }
+        }
     }
 
     async fn into_accept(
@@ -94,6 +156,7 @@ impl From<TcpListener> for MaybeTlsListener {
         Self {
             listener,
             acceptor: None,
+            rotation: None,
         }
     }
 }
@@ -139,7 +202,7 @@ impl<S> MaybeTlsIncomingStream<S> {
         }
     }
 
-    #[cfg(feature = "sources-vector")]
+    #[cfg(feature = "listenfd")]
     pub(crate) const fn ssl_stream(&self) -> Option<&SslStream<S>> {
         use super::MaybeTls;
 
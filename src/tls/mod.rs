@@ -13,6 +13,7 @@ mod incoming;
 mod maybe_tls;
 mod outgoing;
 mod settings;
+mod spiffe;
 
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 pub(crate) use incoming::{MaybeTlsIncomingStream, MaybeTlsListener};
@@ -20,6 +21,7 @@ pub(crate) use maybe_tls::MaybeTls;
 pub use settings::{MaybeTlsSettings, TlsConfig, TlsOptions, TlsSettings};
 #[cfg(test)]
 pub use settings::{TEST_PEM_CA_PATH, TEST_PEM_CRT_PATH, TEST_PEM_KEY_PATH};
+pub use spiffe::SpiffeOptions;
 
 pub type Result<T> = std::result::Result<T, TlsError>;
 
@@ -118,6 +120,19 @@ pub enum TlsError {
     NewCaStack { source: ErrorStack },
     #[snafu(display("Could not push intermediate certificate onto stack"))]
     CaStackPush { source: ErrorStack },
+    #[snafu(display(
+        "SPIFFE identities require Vector to be built with the `tls-spiffe` feature, on Unix"
+    ))]
+    SpiffeUnsupported,
+    #[snafu(display(
+        "SPIFFE identities can't be combined with `ca_file`, `crt_file` or `key_file`"
+    ))]
+    SpiffeConflict,
+    #[snafu(display(
+        "No X.509-SVID received from the SPIFFE Workload API at {:?}",
+        socket_path
+    ))]
+    SpiffeSvidUnavailable { socket_path: PathBuf },
 }
 
 impl MaybeTlsStream<TcpStream> {
@@ -14,7 +14,8 @@ impl MaybeTlsSettings {
 
         match self {
             MaybeTlsSettings::Raw(()) => Ok(MaybeTlsStream::Raw(stream)),
-            MaybeTlsSettings::Tls(_) => {
+            MaybeTlsSettings::Tls(tls) => {
+                tls.spiffe_ready().await?;
                 let config = tls_connector(self)?;
                 let ssl = config.into_ssl(host).context(SslBuildError)?;
 
@@ -1,19 +1,22 @@
 use super::{
+    spiffe::{SpiffeOptions, SpiffeSettings, Svid},
     AddCertToStore, AddExtraChainCert, CaStackPush, DerExportError, FileOpenFailed, FileReadFailed,
     MaybeTls, NewCaStack, NewStoreBuilder, ParsePkcs12, Pkcs12Error, PrivateKeyParseError, Result,
-    SetCertificate, SetPrivateKey, SetVerifyCert, TlsError, TlsIdentityError, X509ParseError,
+    SetCertificate, SetPrivateKey, SetVerifyCert, SslBuildError, TlsError, TlsIdentityError,
+    X509ParseError,
 };
 use openssl::{
+    nid::Nid,
     pkcs12::{ParsedPkcs12, Pkcs12},
     pkey::{PKey, Private},
-    ssl::{ConnectConfiguration, SslContextBuilder, SslVerifyMode},
+    ssl::{ConnectConfiguration, SslContext, SslContextBuilder, SslMethod, SslVerifyMode},
     stack::Stack,
-    x509::{store::X509StoreBuilder, X509},
+    x509::{store::X509StoreBuilder, X509Ref, X509},
 };
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::path::{Path, PathBuf};
-use std::{fmt, fs::File, io::Read};
+use std::{fmt, fs::File, io::Read, sync::Arc};
 
 const PEM_START_MARKER: &str = "-----BEGIN ";
 
@@ -60,6 +63,7 @@ pub struct TlsOptions {
     #[serde(alias = "key_path")]
     pub key_file: Option<PathBuf>,
     pub key_pass: Option<String>,
+    pub spiffe: Option<SpiffeOptions>,
 }
 
 impl TlsOptions {
@@ -81,6 +85,8 @@ pub struct TlsSettings {
     pub(super) verify_hostname: bool,
     authorities: Vec<X509>,
     pub(super) identity: Option<IdentityStore>, // openssl::pkcs12::ParsedPkcs12 doesn't impl Clone yet
+    allowed_peer_names: Vec<String>,
+    pub(super) spiffe: Option<SpiffeSettings>,
 }
 
 #[derive(Clone)]
@@ -101,25 +107,47 @@ impl TlsSettings {
         let default = TlsOptions::default();
         let options = options.as_ref().unwrap_or(&default);
 
+        let spiffe = match &options.spiffe {
+            Some(_) if options.has_files() => return Err(TlsError::SpiffeConflict),
+            Some(spiffe) => Some(SpiffeSettings::new(spiffe)?),
+            None => None,
+        };
+
         if !for_server {
             if options.verify_certificate == Some(false) {
                 warn!(
                     "The `verify_certificate` option is DISABLED, this may lead to security vulnerabilities."
                 );
             }
-            if options.verify_hostname == Some(false) {
+            // SPIFFE peers are authorized by their SPIFFE ID rather than their host name.
+            if options.verify_hostname == Some(false) && spiffe.is_none() {
                 warn!("The `verify_hostname` option is DISABLED, this may lead to security vulnerabilities.");
             }
         }
 
         Ok(Self {
-            verify_certificate: options.verify_certificate.unwrap_or(!for_server),
-            verify_hostname: options.verify_hostname.unwrap_or(!for_server),
+            // Servers using SPIFFE identities authenticate their clients too, by default.
+            verify_certificate: options
+                .verify_certificate
+                .unwrap_or(!for_server || spiffe.is_some()),
+            verify_hostname: options
+                .verify_hostname
+                .unwrap_or(!for_server && spiffe.is_none()),
             authorities: options.load_authorities()?,
             identity: options.load_identity()?,
+            allowed_peer_names: Vec::new(),
+            spiffe,
         })
     }
 
+    /// Only accepts peers with a certificate naming one of `names`, in its
+    /// common name or its DNS subject alternative names. Peer certificates
+    /// are verified whenever there are allowed names.
+    pub fn with_allowed_peer_names(mut self, names: Vec<String>) -> Self {
+        self.allowed_peer_names = names;
+        self
+    }
+
     fn identity(&self) -> Option<ParsedPkcs12> {
         // This data was test-built previously, so we can just use it
         // here and expect the results will not fail. This can all be
@@ -133,12 +161,58 @@ impl TlsSettings {
         })
     }
 
+    /// Waits for the first SPIFFE identity to be available, if one is used.
+    pub(super) async fn spiffe_ready(&self) -> Result<()> {
+        if let Some(spiffe) = &self.spiffe {
+            spiffe.ready().await?;
+        }
+        Ok(())
+    }
+
+    /// The current SPIFFE identity, if one is used. It changes whenever the identity rotates.
+    #[cfg(feature = "sources-utils-tls")]
+    pub(super) fn spiffe_svid(&self) -> Option<Arc<Svid>> {
+        self.spiffe.as_ref().and_then(|spiffe| spiffe.current())
+    }
+
+    pub(super) fn has_identity(&self) -> bool {
+        self.identity.is_some() || self.spiffe.is_some()
+    }
+
     pub(super) fn apply_context(&self, context: &mut SslContextBuilder) -> Result<()> {
-        context.set_verify(if self.verify_certificate {
-            SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT
+        if let Some(spiffe) = &self.spiffe {
+            return spiffe.apply_context(context, self.verify_certificate);
+        }
+
+        if self.allowed_peer_names.is_empty() {
+            context.set_verify(if self.verify_certificate {
+                SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT
+            } else {
+                SslVerifyMode::NONE
+            });
         } else {
-            SslVerifyMode::NONE
-        });
+            let names = self.allowed_peer_names.clone();
+            context.set_verify_callback(
+                SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+                move |verified, store| {
+                    // Only the certificate of the peer itself names it, the
+                    // others being its issuers.
+                    if !verified || store.error_depth() != 0 {
+                        return verified;
+                    }
+                    let allowed = store
+                        .current_cert()
+                        .map_or(false, |cert| peer_name_allowed(cert, &names));
+                    if !allowed {
+                        warn!(
+                            message = "Rejected TLS peer not in the allowed peer names.",
+                            internal_log_rate_secs = 10
+                        );
+                    }
+                    allowed
+                },
+            );
+        }
         if let Some(identity) = self.identity() {
             context
                 .set_certificate(&identity.cert)
@@ -177,10 +251,46 @@ impl TlsSettings {
 
     pub fn apply_connect_configuration(&self, connection: &mut ConnectConfiguration) {
         connection.set_verify_hostname(self.verify_hostname);
+
+        // Connectors are built once, so each connection picks up the latest SPIFFE identity.
+        if self.spiffe.is_some() {
+            let context = SslContext::builder(SslMethod::tls())
+                .context(SslBuildError)
+                .and_then(|mut context| {
+                    self.apply_context(&mut context)?;
+                    Ok(context.build())
+                });
+            if let Err(error) = context
+                .and_then(|context| connection.set_ssl_context(&context).context(SslBuildError))
+            {
+                warn!(message = "Failed to apply the SPIFFE identity to a connection.", %error);
+            }
+        }
     }
 }
 
+fn peer_name_allowed(cert: &X509Ref, names: &[String]) -> bool {
+    let common_names = cert
+        .subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .filter_map(|entry| entry.data().as_utf8().ok())
+        .map(|name| name.to_string());
+    let dns_names = cert
+        .subject_alt_names()
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.dnsname().map(Into::into));
+
+    common_names
+        .chain(dns_names)
+        .any(|name: String| names.iter().any(|allowed| *allowed == name))
+}
+
 impl TlsOptions {
+    fn has_files(&self) -> bool {
+        self.ca_file.is_some() || self.crt_file.is_some() || self.key_file.is_some()
+    }
+
     fn load_authorities(&self) -> Result<Vec<X509>> {
         match &self.ca_file {
             None => Ok(vec![]),
@@ -347,6 +457,8 @@ impl fmt::Debug for TlsSettings {
         f.debug_struct("TlsSettings")
             .field("verify_certificate", &self.verify_certificate)
             .field("verify_hostname", &self.verify_hostname)
+            .field("allowed_peer_names", &self.allowed_peer_names)
+            .field("spiffe", &self.spiffe)
             .finish()
     }
 }
@@ -372,10 +484,11 @@ impl MaybeTlsSettings {
                 if config.enabled.unwrap_or(false) {
                     let tls =
                         TlsSettings::from_options_base(&Some(config.options.clone()), for_server)?;
-                    match (for_server, &tls.identity) {
-                        // Servers require an identity certificate
-                        (true, None) => Err(TlsError::MissingRequiredIdentity),
-                        _ => Ok(Self::Tls(tls)),
+                    // Servers require an identity certificate
+                    if for_server && !tls.has_identity() {
+                        Err(TlsError::MissingRequiredIdentity)
+                    } else {
+                        Ok(Self::Tls(tls))
                     }
                 } else {
                     Ok(Self::Raw(())) // Explicitly disabled, still no TLS settings
@@ -558,6 +671,19 @@ mod test {
         assert_eq!(settings.authorities.len(), 0);
     }
 
+    #[test]
+    fn from_options_spiffe_with_files() {
+        let options = TlsOptions {
+            crt_file: Some(TEST_PEM_CRT_PATH.into()),
+            key_file: Some(TEST_PEM_KEY_PATH.into()),
+            spiffe: Some(SpiffeOptions::default()),
+            ..Default::default()
+        };
+        let error = TlsSettings::from_options(&Some(options))
+            .expect_err("from_options failed to detect SPIFFE conflict");
+        assert!(matches!(error, TlsError::SpiffeConflict));
+    }
+
     #[test]
     fn from_options_bad_certificate() {
         let options = TlsOptions {
@@ -607,6 +733,17 @@ mod test {
         assert!(config.is_tls());
     }
 
+    #[test]
+    fn allows_peer_names() {
+        let cert = X509::from_pem(TEST_PEM_CRT_BYTES).unwrap();
+        assert!(peer_name_allowed(
+            &cert,
+            &["example.org".into(), "localhost".into()]
+        ));
+        assert!(!peer_name_allowed(&cert, &["example.org".into()]));
+        assert!(!peer_name_allowed(&cert, &[]));
+    }
+
     fn settings_from_config(
         enabled: Option<bool>,
         set_crt: bool,
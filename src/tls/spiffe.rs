@@ -0,0 +1,464 @@
+//! TLS identities fetched from a [SPIFFE Workload API][workload_api], as served by the SPIRE
+//! agent. The API streams the X.509-SVID of the workload along with the trust bundles to verify
+//! peers with, and pushes new ones before the current SVID expires.
+//!
+//! [workload_api]: https://github.com/spiffe/spiffe/blob/main/standards/SPIFFE_Workload_API.md
+
+// SVIDs are only ever parsed when the Workload API client is built in.
+#![cfg_attr(not(all(unix, feature = "tls-spiffe")), allow(dead_code))]
+
+use super::{
+    AddCertToStore, AddExtraChainCert, NewStoreBuilder, Result, SetCertificate, SetPrivateKey,
+    SetVerifyCert, TlsError,
+};
+use once_cell::sync::Lazy;
+use openssl::{
+    pkey::{PKey, Private},
+    ssl::{SslContextBuilder, SslVerifyMode},
+    x509::{store::X509StoreBuilder, X509Ref, X509},
+};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::watch;
+
+/// The environment variable the SPIFFE specification reserves for the Workload API address.
+const SOCKET_ENV: &str = "SPIFFE_ENDPOINT_SOCKET";
+/// Where the SPIRE agent serves the Workload API by default.
+const DEFAULT_SOCKET_PATH: &str = "/tmp/spire-agent/public/api.sock";
+/// How long listeners and outgoing connections wait for the first SVID.
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+type SvidRx = watch::Receiver<Option<Arc<Svid>>>;
+
+/// Workload API streams, shared by every component using the same socket.
+static SOURCES: Lazy<Mutex<HashMap<PathBuf, SvidRx>>> = Lazy::new(Default::default);
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpiffeOptions {
+    pub socket_path: Option<PathBuf>,
+    #[serde(default)]
+    pub trust_domains: Vec<String>,
+    #[serde(default)]
+    pub allowed_ids: Vec<String>,
+}
+
+impl SpiffeOptions {
+    fn socket_path(&self) -> PathBuf {
+        self.socket_path.clone().unwrap_or_else(|| {
+            std::env::var(SOCKET_ENV)
+                .ok()
+                .map(|address| socket_path_from_address(&address))
+                .unwrap_or_else(|| DEFAULT_SOCKET_PATH.into())
+        })
+    }
+}
+
+/// Workload API addresses are URIs, `unix:///path/to/socket` or `unix:/path/to/socket`.
+fn socket_path_from_address(address: &str) -> PathBuf {
+    address
+        .strip_prefix("unix://")
+        .or_else(|| address.strip_prefix("unix:"))
+        .unwrap_or(address)
+        .into()
+}
+
+/// An X.509-SVID, along with the authorities of its own and federated trust domains.
+pub(super) struct Svid {
+    spiffe_id: String,
+    /// The SVID itself comes first, followed by its intermediates
+    chain: Vec<X509>,
+    key: PKey<Private>,
+    authorities: Vec<X509>,
+}
+
+impl Svid {
+    fn apply_context(&self, context: &mut SslContextBuilder) -> Result<()> {
+        let mut chain = self.chain.iter();
+        if let Some(cert) = chain.next() {
+            context.set_certificate(cert).context(SetCertificate)?;
+        }
+        context.set_private_key(&self.key).context(SetPrivateKey)?;
+        for cert in chain {
+            context
+                .add_extra_chain_cert(cert.clone())
+                .context(AddExtraChainCert)?;
+        }
+
+        let mut store = X509StoreBuilder::new().context(NewStoreBuilder)?;
+        for authority in &self.authorities {
+            store.add_cert(authority.clone()).context(AddCertToStore)?;
+        }
+        context
+            .set_verify_cert_store(store.build())
+            .context(SetVerifyCert)
+    }
+}
+
+/// Identity and peer authorization settings of a TLS configuration using SPIFFE.
+#[derive(Clone)]
+pub(super) struct SpiffeSettings {
+    socket_path: PathBuf,
+    svid: SvidRx,
+    trust_domains: Vec<String>,
+    allowed_ids: Vec<String>,
+}
+
+impl SpiffeSettings {
+    pub(super) fn new(options: &SpiffeOptions) -> Result<Self> {
+        let socket_path = options.socket_path();
+        let svid = {
+            let mut sources = SOURCES.lock().expect("poisoned lock");
+            match sources.get(&socket_path) {
+                Some(svid) => svid.clone(),
+                None => {
+                    let svid = watch_svid(socket_path.clone())?;
+                    sources.insert(socket_path.clone(), svid.clone());
+                    svid
+                }
+            }
+        };
+
+        Ok(Self {
+            socket_path,
+            svid,
+            trust_domains: options
+                .trust_domains
+                .iter()
+                .map(|domain| domain.trim_start_matches("spiffe://").to_owned())
+                .collect(),
+            allowed_ids: options.allowed_ids.clone(),
+        })
+    }
+
+    /// The latest SVID received from the Workload API, if any.
+    pub(super) fn current(&self) -> Option<Arc<Svid>> {
+        self.svid.borrow().clone()
+    }
+
+    /// Waits for the first SVID to be received from the Workload API.
+    pub(super) async fn ready(&self) -> Result<Arc<Svid>> {
+        let mut svid = self.svid.clone();
+        let wait = async {
+            loop {
+                if let Some(current) = svid.borrow().clone() {
+                    return Some(current);
+                }
+                if svid.changed().await.is_err() {
+                    return None;
+                }
+            }
+        };
+
+        tokio::time::timeout(READY_TIMEOUT, wait)
+            .await
+            .ok()
+            .flatten()
+            .ok_or_else(|| TlsError::SpiffeSvidUnavailable {
+                socket_path: self.socket_path.clone(),
+            })
+    }
+
+    /// Peers are authorized by the SPIFFE ID in their certificate. It's authorized if it's one
+    /// of `allowed_ids`, or if it belongs to one of `trust_domains`. When neither is set, peers
+    /// from the trust domain of our own SVID are authorized.
+    fn authorize(&self, cert: &X509Ref) -> bool {
+        let id = match spiffe_id(cert) {
+            Some(id) => id,
+            None => return false,
+        };
+        let domain = trust_domain(&id);
+
+        if self.allowed_ids.is_empty() && self.trust_domains.is_empty() {
+            return self.current().map_or(false, |svid| {
+                domain.is_some() && trust_domain(&svid.spiffe_id) == domain
+            });
+        }
+
+        self.allowed_ids.iter().any(|allowed| *allowed == id)
+            || self
+                .trust_domains
+                .iter()
+                .any(|allowed| Some(allowed.as_str()) == domain)
+    }
+
+    pub(super) fn apply_context(
+        &self,
+        context: &mut SslContextBuilder,
+        verify_certificate: bool,
+    ) -> Result<()> {
+        if verify_certificate {
+            let settings = self.clone();
+            context.set_verify_callback(
+                SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+                move |verified, store| {
+                    // Only the certificate of the peer itself carries its SPIFFE ID, the
+                    // others being its issuers.
+                    if !verified || store.error_depth() != 0 {
+                        return verified;
+                    }
+                    let allowed = store
+                        .current_cert()
+                        .map_or(false, |cert| settings.authorize(cert));
+                    if !allowed {
+                        warn!(
+                            message = "Rejected TLS peer with an unauthorized SPIFFE ID.",
+                            internal_log_rate_secs = 10
+                        );
+                    }
+                    allowed
+                },
+            );
+        } else {
+            context.set_verify(SslVerifyMode::NONE);
+        }
+
+        match self.current() {
+            Some(svid) => svid.apply_context(context),
+            // Until an SVID arrives, no peer is trusted, rather than falling back to the
+            // system roots.
+            None => context
+                .set_verify_cert_store(X509StoreBuilder::new().context(NewStoreBuilder)?.build())
+                .context(SetVerifyCert),
+        }
+    }
+}
+
+impl fmt::Debug for SpiffeSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpiffeSettings")
+            .field("socket_path", &self.socket_path)
+            .field("trust_domains", &self.trust_domains)
+            .field("allowed_ids", &self.allowed_ids)
+            .finish()
+    }
+}
+
+/// The trust domain of a SPIFFE ID, `spiffe://<trust domain>/<path>`.
+fn trust_domain(spiffe_id: &str) -> Option<&str> {
+    spiffe_id
+        .strip_prefix("spiffe://")?
+        .split('/')
+        .next()
+        .filter(|domain| !domain.is_empty())
+}
+
+/// The SPIFFE ID of a certificate is its URI subject alternative name.
+fn spiffe_id(cert: &X509Ref) -> Option<String> {
+    cert.subject_alt_names()?
+        .iter()
+        .filter_map(|name| name.uri())
+        .find(|uri| uri.starts_with("spiffe://"))
+        .map(Into::into)
+}
+
+/// Splits concatenated DER encoded certificates, as found in SVIDs and bundles.
+fn split_der(mut data: &[u8]) -> std::result::Result<Vec<&[u8]>, &'static str> {
+    let mut items = Vec::new();
+    while !data.is_empty() {
+        // Each certificate is an ASN.1 SEQUENCE, whose header holds its length.
+        let (header, length) = match data {
+            [0x30, length, ..] if *length < 0x80 => (2, *length as usize),
+            [0x30, length, rest @ ..] => {
+                let bytes = (*length & 0x7f) as usize;
+                if bytes == 0 || bytes > 4 || rest.len() < bytes {
+                    return Err("invalid certificate length");
+                }
+                let length = rest[..bytes]
+                    .iter()
+                    .fold(0, |length, byte| (length << 8) | *byte as usize);
+                (2 + bytes, length)
+            }
+            _ => return Err("expected a certificate"),
+        };
+        if data.len() < header + length {
+            return Err("truncated certificate");
+        }
+        let (item, rest) = data.split_at(header + length);
+        items.push(item);
+        data = rest;
+    }
+    Ok(items)
+}
+
+fn parse_certs(data: &[u8]) -> crate::Result<Vec<X509>> {
+    split_der(data)?
+        .into_iter()
+        .map(|der| X509::from_der(der).map_err(Into::into))
+        .collect()
+}
+
+#[cfg(all(unix, feature = "tls-spiffe"))]
+fn watch_svid(socket_path: PathBuf) -> Result<SvidRx> {
+    let (tx, rx) = watch::channel(None);
+    tokio::spawn(workload_api::run(socket_path, tx));
+    Ok(rx)
+}
+
+#[cfg(not(all(unix, feature = "tls-spiffe")))]
+fn watch_svid(_socket_path: PathBuf) -> Result<SvidRx> {
+    Err(TlsError::SpiffeUnsupported)
+}
+
+#[cfg(all(unix, feature = "tls-spiffe"))]
+mod workload_api {
+    use super::{parse_certs, Svid};
+    use crate::{
+        internal_events::{SpiffeSvidUpdated, SpiffeWorkloadApiError},
+        proto::spiffe::{
+            spiffe_workload_api_client::SpiffeWorkloadApiClient, X509svidRequest, X509svidResponse,
+        },
+    };
+    use http::Uri;
+    use openssl::pkey::PKey;
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    };
+    use tokio::{net::UnixStream, sync::watch};
+    use tonic::{metadata::MetadataValue, transport::Endpoint};
+    use tower::service_fn;
+
+    const MIN_BACKOFF: Duration = Duration::from_secs(1);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    /// Streams SVIDs from the Workload API into `tx`, reconnecting whenever the stream ends.
+    pub(super) async fn run(socket_path: PathBuf, tx: watch::Sender<Option<Arc<Svid>>>) {
+        let mut backoff = MIN_BACKOFF;
+        loop {
+            match fetch(&socket_path, &tx).await {
+                Ok(()) => backoff = MIN_BACKOFF,
+                Err(error) => {
+                    emit!(&SpiffeWorkloadApiError {
+                        error: &error,
+                        socket_path: &socket_path,
+                    });
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    async fn fetch(socket_path: &Path, tx: &watch::Sender<Option<Arc<Svid>>>) -> crate::Result<()> {
+        let path = socket_path.to_owned();
+        // The URI is required by `tonic`, but connections are made to the socket.
+        let channel = Endpoint::from_static("http://localhost")
+            .connect_with_connector(service_fn(move |_: Uri| UnixStream::connect(path.clone())))
+            .await?;
+
+        let mut request = tonic::Request::new(X509svidRequest {});
+        // The Workload API rejects requests without this header.
+        request
+            .metadata_mut()
+            .insert("workload.spiffe.io", MetadataValue::from_static("true"));
+
+        let mut stream = SpiffeWorkloadApiClient::new(channel)
+            .fetch_x509svid(request)
+            .await?
+            .into_inner();
+        while let Some(response) = stream.message().await? {
+            let svid = parse_response(response)?;
+            emit!(&SpiffeSvidUpdated {
+                spiffe_id: &svid.spiffe_id
+            });
+            let _ = tx.send(Some(Arc::new(svid)));
+        }
+
+        Ok(())
+    }
+
+    /// The first SVID of a response is the default identity of the workload.
+    fn parse_response(response: X509svidResponse) -> crate::Result<Svid> {
+        let svid = response
+            .svids
+            .into_iter()
+            .next()
+            .ok_or("the Workload API returned no X.509-SVID")?;
+
+        let chain = parse_certs(&svid.x509_svid)?;
+        if chain.is_empty() {
+            return Err("the X.509-SVID has no certificate".into());
+        }
+        let mut authorities = parse_certs(&svid.bundle)?;
+        for bundle in response.federated_bundles.values() {
+            authorities.extend(parse_certs(bundle)?);
+        }
+
+        Ok(Svid {
+            spiffe_id: svid.spiffe_id,
+            chain,
+            key: PKey::private_key_from_der(&svid.x509_svid_key)?,
+            authorities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tls::TEST_PEM_CRT_PATH;
+
+    #[test]
+    fn parses_socket_addresses() {
+        assert_eq!(
+            socket_path_from_address("unix:///run/spire/agent.sock"),
+            PathBuf::from("/run/spire/agent.sock")
+        );
+        assert_eq!(
+            socket_path_from_address("unix:/run/spire/agent.sock"),
+            PathBuf::from("/run/spire/agent.sock")
+        );
+    }
+
+    #[test]
+    fn parses_trust_domains() {
+        assert_eq!(
+            trust_domain("spiffe://example.org/ns/prod/sa/vector"),
+            Some("example.org")
+        );
+        assert_eq!(trust_domain("spiffe://example.org"), Some("example.org"));
+        assert_eq!(trust_domain("spiffe:///vector"), None);
+        assert_eq!(trust_domain("https://example.org/vector"), None);
+    }
+
+    #[test]
+    fn splits_concatenated_certificates() {
+        let pem = std::fs::read(TEST_PEM_CRT_PATH).unwrap();
+        let der = X509::from_pem(&pem).unwrap().to_der().unwrap();
+        let mut data = der.clone();
+        data.extend_from_slice(&der);
+
+        let certs = parse_certs(&data).unwrap();
+        assert_eq!(certs.len(), 2);
+        assert_eq!(certs[0].to_der().unwrap(), der);
+        assert_eq!(certs[1].to_der().unwrap(), der);
+
+        assert!(split_der(&data[..data.len() - 1]).is_err());
+        assert!(split_der(&[0x02, 0x01, 0x00]).is_err());
+        assert_eq!(split_der(&[]).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn rejects_peers_without_spiffe_id() {
+        let (_tx, svid) = watch::channel(None);
+        let settings = SpiffeSettings {
+            socket_path: DEFAULT_SOCKET_PATH.into(),
+            svid,
+            trust_domains: vec!["example.org".into()],
+            allowed_ids: Vec::new(),
+        };
+
+        let pem = std::fs::read(TEST_PEM_CRT_PATH).unwrap();
+        let cert = X509::from_pem(&pem).unwrap();
+        assert!(!settings.authorize(&cert));
+    }
+}
@@ -1,16 +1,19 @@
 use super::{
     dashboard::{init_dashboard, is_tty},
+    export::export,
     metrics, state,
 };
 use crate::config;
+use std::time::Duration;
 use url::Url;
 use vector_api_client::{connect_subscription_client, Client};
 
 /// CLI command func for displaying Vector components, and communicating with a local/remote
 /// Vector API server via HTTP/WebSockets
 pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
-    // Exit early if the terminal is not a teletype
-    if !is_tty() {
+    // Exit early if the terminal is not a teletype. Snapshots are written to stdout as-is,
+    // so they can be piped elsewhere
+    if opts.output.is_none() && !is_tty() {
         eprintln!("Terminal must be a teletype (TTY) to display a Vector dashboard.");
         return exitcode::IOERR;
     }
@@ -33,9 +36,16 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
     // Create a metrics state updater
     let (tx, rx) = tokio::sync::mpsc::channel(20);
 
+    // Keep enough samples to cover the sparkline window, or the whole sampling period when
+    // exporting a snapshot
+    let window = match opts.output {
+        Some(_) => opts.samples(opts.duration),
+        None => opts.samples(Duration::from_secs(opts.window.into())),
+    };
+
     // Get the initial component state
     let sender = match metrics::init_components(&client).await {
-        Ok(state) => state::updater(state, rx).await,
+        Ok(state) => state::updater(state, rx, window).await,
         _ => {
             eprintln!("Couldn't query Vector components.");
             return exitcode::UNAVAILABLE;
@@ -62,6 +72,10 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
     // Subscribe to updated metrics
     metrics::subscribe(subscription_client, tx.clone(), opts.interval as i64);
 
+    if let Some(format) = opts.output {
+        return export(sender, format, opts.duration).await;
+    }
+
     // Initialize the dashboard
     match init_dashboard(url.as_str(), opts, sender).await {
         Ok(_) => exitcode::OK,
@@ -9,7 +9,7 @@ use crossterm::{
 };
 use num_format::{Locale, ToFormattedString};
 use number_prefix::NumberPrefix;
-use std::io::stdout;
+use std::{collections::VecDeque, io::stdout};
 use tui::{
     backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Layout, Rect},
@@ -83,16 +83,51 @@ impl HumanFormatter for i64 {
     }
 }
 
-static HEADER: [&str; 7] = [
+static HEADER: [&str; 9] = [
     "ID",
     "Kind",
     "Type",
     "Events In",
     "Events Out",
+    "Out/s Trend",
     "Bytes",
+    "Memory",
     "Errors",
 ];
 
+/// Number of characters in a throughput sparkline
+const SPARKLINE_WIDTH: usize = 12;
+
+static SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders `samples` as a sparkline of at most `width` bars, scaled to the largest sample.
+/// Samples are grouped into buckets showing their maximum, so that short spikes stay visible
+/// however long the window is.
+fn sparkline(samples: &VecDeque<i64>, width: usize) -> String {
+    if samples.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    let samples = samples.iter().map(|v| (*v).max(0)).collect::<Vec<_>>();
+    let bucket_size = (samples.len() + width - 1) / width;
+    let buckets = samples
+        .chunks(bucket_size)
+        .map(|bucket| bucket.iter().copied().max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    let max = buckets.iter().copied().max().unwrap_or(0);
+
+    buckets
+        .into_iter()
+        .map(|v| match max {
+            0 => SPARKLINE_BARS[0],
+            max => {
+                let top = SPARKLINE_BARS.len() - 1;
+                SPARKLINE_BARS[((v as f64 / max as f64) * top as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
 struct Widgets<'a> {
     constraints: Vec<Constraint>,
     url_string: &'a str,
@@ -120,7 +155,11 @@ impl<'a> Widgets<'a> {
         let text = vec![Spans::from(vec![
             Span::from(self.url_string),
             Span::styled(
-                format!(" | Sampling @ {}ms", self.opts.interval.thousands_format()),
+                format!(
+                    " | Sampling @ {}ms | Trend over {}s",
+                    self.opts.interval.thousands_format(),
+                    self.opts.window.thousands_format()
+                ),
                 Style::default().fg(Color::Gray),
             ),
         ])];
@@ -148,9 +187,9 @@ impl<'a> Widgets<'a> {
         // Data columns
         let items = state.iter().map(|(_, r)| {
             let mut data = vec![
-                r.key.id().to_string(),
-                r.kind.clone(),
-                r.component_type.clone(),
+                Cell::from(r.key.id().to_string()),
+                Cell::from(r.kind.clone()),
+                Cell::from(r.component_type.clone()),
             ];
 
             let formatted_metrics = [
@@ -178,6 +217,7 @@ impl<'a> Widgets<'a> {
                         r.sent_events_throughput_sec.human_format()
                     ),
                 },
+                sparkline(&r.history.sent_events_throughput_sec, SPARKLINE_WIDTH),
                 match r.processed_bytes_total {
                     0 => "N/A".to_string(),
                     v => format!(
@@ -190,14 +230,28 @@ impl<'a> Widgets<'a> {
                         r.processed_bytes_throughput_sec.human_format_bytes()
                     ),
                 },
-                if self.opts.human_metrics {
-                    r.errors.human_format()
-                } else {
-                    r.errors.thousands_format()
+                match r.allocated_bytes {
+                    0 => "N/A".to_string(),
+                    v if self.opts.human_metrics => v.human_format_bytes(),
+                    v => v.thousands_format(),
                 },
             ];
 
-            data.extend_from_slice(&formatted_metrics);
+            let errors = if self.opts.human_metrics {
+                r.errors.human_format()
+            } else {
+                r.errors.thousands_format()
+            };
+            // Highlight components that have hit errors within the trend window
+            let errors_style = if r.history.errors.iter().any(|errors| *errors > 0) {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+
+            data.extend(formatted_metrics.iter().cloned().map(Cell::from));
+            data.push(Cell::from(errors).style(errors_style));
+
             Row::new(data).style(Style::default())
         });
 
@@ -206,14 +260,15 @@ impl<'a> Widgets<'a> {
             .block(Block::default().borders(Borders::ALL).title("Components"))
             .column_spacing(2)
             .widths(&[
-                Constraint::Percentage(19),
                 Constraint::Percentage(14),
                 Constraint::Percentage(8),
-                Constraint::Percentage(8),
-                Constraint::Percentage(18),
-                Constraint::Percentage(18),
-                Constraint::Percentage(18),
-                Constraint::Percentage(8),
+                Constraint::Percentage(10),
+                Constraint::Percentage(14),
+                Constraint::Percentage(14),
+                Constraint::Length(SPARKLINE_WIDTH as u16),
+                Constraint::Percentage(14),
+                Constraint::Percentage(9),
+                Constraint::Percentage(6),
             ]);
 
         f.render_widget(w, area);
@@ -408,6 +463,24 @@ mod tests {
         assert_eq!(N.human_format(), "1.10 T");
     }
 
+    #[test]
+    /// Sparklines scale to the largest sample
+    fn sparkline_scales() {
+        let samples = vec![0, 1, 2, 3, 4, 5, 6, 7].into();
+
+        assert_eq!(sparkline(&samples, 8), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&VecDeque::new(), 8), "");
+        assert_eq!(sparkline(&vec![0, 0].into(), 8), "▁▁");
+    }
+
+    #[test]
+    /// Long windows are bucketed, keeping the maximum of each bucket
+    fn sparkline_buckets() {
+        let samples = vec![0, 8, 0, 0, 4, 0].into();
+
+        assert_eq!(sparkline(&samples, 3), "█▁▅");
+    }
+
     #[test]
     /// Should format bytes
     fn format_bytes() {
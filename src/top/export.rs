@@ -0,0 +1,212 @@
+use super::state;
+use serde::Serialize;
+use std::{collections::VecDeque, io, time::Duration};
+
+/// Format of a metrics snapshot printed by `vector top --output`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            s => Err(format!(
+                "{} is not a valid option, expected `json` or `csv`",
+                s
+            )),
+        }
+    }
+}
+
+/// A component's metrics at the end of the sampling period, with its throughput and errors
+/// summarized over the period.
+#[derive(Debug, Serialize)]
+struct ComponentSummary<'a> {
+    component_id: &'a str,
+    kind: &'a str,
+    component_type: &'a str,
+    received_events_total: i64,
+    received_events_throughput_sec_avg: f64,
+    received_events_throughput_sec_max: i64,
+    sent_events_total: i64,
+    sent_events_throughput_sec_avg: f64,
+    sent_events_throughput_sec_max: i64,
+    processed_bytes_total: i64,
+    allocated_bytes: i64,
+    errors_total: i64,
+    errors: i64,
+}
+
+impl<'a> From<&'a state::ComponentRow> for ComponentSummary<'a> {
+    fn from(r: &'a state::ComponentRow) -> Self {
+        Self {
+            component_id: r.key.id(),
+            kind: &r.kind,
+            component_type: &r.component_type,
+            received_events_total: r.received_events_total,
+            received_events_throughput_sec_avg: average(&r.history.received_events_throughput_sec),
+            received_events_throughput_sec_max: max(&r.history.received_events_throughput_sec),
+            sent_events_total: r.sent_events_total,
+            sent_events_throughput_sec_avg: average(&r.history.sent_events_throughput_sec),
+            sent_events_throughput_sec_max: max(&r.history.sent_events_throughput_sec),
+            processed_bytes_total: r.processed_bytes_total,
+            allocated_bytes: r.allocated_bytes,
+            errors_total: r.errors,
+            errors: r.history.errors.iter().sum(),
+        }
+    }
+}
+
+/// JSON snapshots also carry the raw samples, which CSV can't represent.
+#[derive(Debug, Serialize)]
+struct ComponentSnapshot<'a> {
+    #[serde(flatten)]
+    summary: ComponentSummary<'a>,
+    received_events_throughput_sec: &'a VecDeque<i64>,
+    sent_events_throughput_sec: &'a VecDeque<i64>,
+    errors_per_sample: &'a VecDeque<i64>,
+}
+
+fn average(samples: &VecDeque<i64>) -> f64 {
+    match samples.len() {
+        0 => 0.0,
+        len => samples.iter().sum::<i64>() as f64 / len as f64,
+    }
+}
+
+fn max(samples: &VecDeque<i64>) -> i64 {
+    samples.iter().copied().max().unwrap_or(0)
+}
+
+/// Writes a snapshot of every component in `state` to `writer`.
+fn write<W: io::Write>(
+    state: &state::State,
+    format: OutputFormat,
+    mut writer: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        OutputFormat::Json => {
+            let snapshots = state
+                .values()
+                .map(|r| ComponentSnapshot {
+                    summary: r.into(),
+                    received_events_throughput_sec: &r.history.received_events_throughput_sec,
+                    sent_events_throughput_sec: &r.history.sent_events_throughput_sec,
+                    errors_per_sample: &r.history.errors,
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_writer(&mut writer, &snapshots)?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(writer);
+            for r in state.values() {
+                writer.serialize(ComponentSummary::from(r))?;
+            }
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Samples metrics from `state_rx` for `duration`, then prints a snapshot of every component
+/// to stdout. Used for scripting, in place of the dashboard.
+pub async fn export(
+    mut state_rx: state::StateRx,
+    format: OutputFormat,
+    duration: Duration,
+) -> exitcode::ExitCode {
+    let mut latest = state::State::new();
+
+    let deadline = tokio::time::sleep(duration);
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            state = state_rx.recv() => match state {
+                Some(state) => latest = state,
+                None => break,
+            },
+        }
+    }
+
+    match write(&latest, format, io::stdout().lock()) {
+        Ok(()) => exitcode::OK,
+        Err(error) => {
+            eprintln!("Couldn't write metrics snapshot: {}", error);
+            exitcode::IOERR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ComponentKey;
+
+    fn state() -> state::State {
+        let key = ComponentKey::from("in");
+        let mut history = state::History::default();
+        history.received_events_throughput_sec = vec![10, 20, 30].into();
+        history.sent_events_throughput_sec = vec![10, 20, 30].into();
+        history.errors = vec![0, 2, 1].into();
+
+        vec![(
+            key.clone(),
+            state::ComponentRow {
+                key,
+                kind: "source".into(),
+                component_type: "demo_logs".into(),
+                processed_bytes_total: 4096,
+                processed_bytes_throughput_sec: 0,
+                received_events_total: 60,
+                received_events_throughput_sec: 30,
+                sent_events_total: 60,
+                sent_events_throughput_sec: 30,
+                allocated_bytes: 0,
+                errors: 3,
+                history,
+            },
+        )]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn writes_csv() {
+        let mut out = Vec::new();
+        write(&state(), OutputFormat::Csv, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "component_id,kind,component_type,received_events_total,\
+             received_events_throughput_sec_avg,received_events_throughput_sec_max,\
+             sent_events_total,sent_events_throughput_sec_avg,sent_events_throughput_sec_max,\
+             processed_bytes_total,allocated_bytes,errors_total,errors\n\
+             in,source,demo_logs,60,20.0,30,60,20.0,30,4096,0,3,3\n"
+        );
+    }
+
+    #[test]
+    fn writes_json() {
+        let mut out = Vec::new();
+        write(&state(), OutputFormat::Json, &mut out).unwrap();
+
+        let snapshot: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(snapshot[0]["component_id"], "in");
+        assert_eq!(snapshot[0]["sent_events_throughput_sec_avg"], 20.0);
+        assert_eq!(
+            snapshot[0]["sent_events_throughput_sec"],
+            serde_json::json!([10, 20, 30])
+        );
+        assert_eq!(snapshot[0]["errors"], 3);
+    }
+}
@@ -30,7 +30,9 @@ async fn component_added(client: Arc<SubscriptionClient>, tx: state::EventTx) {
                     sent_events_throughput_sec: 0,
                     processed_bytes_total: 0,
                     processed_bytes_throughput_sec: 0,
+                    allocated_bytes: 0,
                     errors: 0,
+                    history: state::History::default(),
                 }))
                 .await;
         }
@@ -218,6 +220,58 @@ async fn processed_bytes_throughputs(
     }
 }
 
+async fn allocated_bytes(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    let res = client.component_allocated_bytes_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_allocated_bytes;
+            let _ = tx
+                .send(state::EventType::AllocatedBytes(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(&c.component_id),
+                                c.metric.allocated_bytes as i64,
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn errors_totals(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    let res = client.component_errors_totals_subscription(interval);
+
+    tokio::pin! {
+        let stream = res.stream();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors_totals;
+            let _ = tx
+                .send(state::EventType::ErrorsTotals(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(&c.component_id),
+                                c.metric.errors_total as i64,
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
 /// Subscribe to each metrics channel through a separate client. This is a temporary workaround
 /// until client multiplexing is fixed. In future, we should be able to use a single client
 pub fn subscribe(client: SubscriptionClient, tx: state::EventTx, interval: i64) {
@@ -252,9 +306,11 @@ pub fn subscribe(client: SubscriptionClient, tx: state::EventTx, interval: i64)
     ));
     tokio::spawn(processed_bytes_throughputs(
         Arc::clone(&client),
-        tx,
+        tx.clone(),
         interval,
     ));
+    tokio::spawn(allocated_bytes(Arc::clone(&client), tx.clone(), interval));
+    tokio::spawn(errors_totals(Arc::clone(&client), tx, interval));
 }
 
 /// Retrieve the initial components/metrics for first paint. Further updating the metrics
@@ -288,8 +344,9 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
                         sent_events_throughput_sec: 0,
                         processed_bytes_total: d.on.processed_bytes_total(),
                         processed_bytes_throughput_sec: 0,
-
+                        allocated_bytes: 0,
                         errors: 0,
+                        history: state::History::default(),
                     },
                 ))
             })
@@ -1,13 +1,16 @@
 mod cmd;
 mod dashboard;
 mod events;
+mod export;
 mod metrics;
 mod state;
 
+use std::time::Duration;
 use structopt::StructOpt;
 use url::Url;
 
 pub use cmd::cmd;
+use export::OutputFormat;
 
 #[derive(StructOpt, Debug, Clone)]
 #[structopt(rename_all = "kebab-case")]
@@ -23,4 +26,64 @@ pub struct Opts {
     /// Humanize metrics, using numeric suffixes - e.g. 1,100 = 1.10 k, 1,000,000 = 1.00 M
     #[structopt(short, long)]
     human_metrics: bool,
+
+    /// Length of the rolling window of metrics shown as sparklines, in seconds
+    #[structopt(default_value = "60", short = "w", long)]
+    window: u32,
+
+    /// Print a snapshot of component metrics in this format, instead of displaying the dashboard
+    #[structopt(short, long, possible_values = &["json", "csv"])]
+    output: Option<OutputFormat>,
+
+    /// How long to sample metrics for before printing a snapshot with `--output`, e.g. 500ms,
+    /// 60s or 5m
+    #[structopt(default_value = "60s", short, long, parse(try_from_str = parse_duration))]
+    duration: Duration,
+}
+
+impl Opts {
+    /// Number of samples that fit in a window of `window` at the sampling interval
+    fn samples(&self, window: Duration) -> usize {
+        (window.as_millis() / u128::from(self.interval.max(1))).max(1) as usize
+    }
+}
+
+/// Parses a duration with a unit suffix of `ms`, `s`, `m` or `h`. Seconds are assumed if the
+/// unit is omitted.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or_else(|| s.len());
+    let (value, unit) = s.split_at(split);
+    let value = value
+        .parse::<u64>()
+        .map_err(|_| format!("{} is not a valid duration", s))?;
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "m" => Ok(Duration::from_secs(value * 60)),
+        "h" => Ok(Duration::from_secs(value * 60 * 60)),
+        _ => Err(format!(
+            "{} is not a valid duration, expected a unit of `ms`, `s`, `m` or `h`",
+            s
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("60s"), Ok(Duration::from_secs(60)));
+        assert_eq!(parse_duration("60"), Ok(Duration::from_secs(60)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("s").is_err());
+    }
 }
@@ -1,5 +1,5 @@
 use crate::config::ComponentKey;
-use std::collections::btree_map::BTreeMap;
+use std::collections::{btree_map::BTreeMap, VecDeque};
 use tokio::sync::mpsc;
 
 type IdentifiedMetric = (ComponentKey, i64);
@@ -15,6 +15,8 @@ pub enum EventType {
     ProcessedBytesTotals(Vec<IdentifiedMetric>),
     /// Interval + identified metric
     ProcessedBytesThroughputs(i64, Vec<IdentifiedMetric>),
+    AllocatedBytes(Vec<IdentifiedMetric>),
+    ErrorsTotals(Vec<IdentifiedMetric>),
     ComponentAdded(ComponentRow),
     ComponentRemoved(ComponentKey),
 }
@@ -35,13 +37,47 @@ pub struct ComponentRow {
     pub received_events_throughput_sec: i64,
     pub sent_events_total: i64,
     pub sent_events_throughput_sec: i64,
+    pub allocated_bytes: i64,
     pub errors: i64,
+    pub history: History,
+}
+
+/// Rolling window of per-second throughput and error samples for a single component, oldest
+/// first. Each window holds at most the number of samples passed to `updater`.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub received_events_throughput_sec: VecDeque<i64>,
+    pub sent_events_throughput_sec: VecDeque<i64>,
+    /// Errors encountered between samples
+    pub errors: VecDeque<i64>,
+    errors_total: Option<i64>,
+}
+
+impl History {
+    fn push(samples: &mut VecDeque<i64>, value: i64, window: usize) {
+        if samples.len() >= window {
+            samples.pop_front();
+        }
+        samples.push_back(value);
+    }
+
+    /// Records a new errors total. The first total only sets a baseline, so errors that
+    /// happened before `vector top` started don't show up as a spike.
+    fn push_errors_total(&mut self, total: i64, window: usize) {
+        let delta = self
+            .errors_total
+            .map_or(0, |previous| (total - previous).max(0));
+        self.errors_total = Some(total);
+        Self::push(&mut self.errors, delta, window);
+    }
 }
 
 /// Takes the receiver `EventRx` channel, and returns a `StateTx` state transmitter. This
 /// represents the single destination for handling subscriptions and returning 'immutable' state
-/// for re-rendering the dashboard. This approach uses channels vs. mutexes.
-pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
+/// for re-rendering the dashboard. This approach uses channels vs. mutexes. Each component keeps
+/// up to `window` throughput and error samples in its `History`.
+pub async fn updater(mut state: State, mut event_rx: EventRx, window: usize) -> StateRx {
+    let window = window.max(1);
     let (tx, rx) = mpsc::channel(20);
 
     // Prime the receiver with the initial state
@@ -62,6 +98,11 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                         if let Some(r) = state.get_mut(&key) {
                             r.received_events_throughput_sec =
                                 (v as f64 * (1000.0 / interval as f64)) as i64;
+                            History::push(
+                                &mut r.history.received_events_throughput_sec,
+                                r.received_events_throughput_sec,
+                                window,
+                            );
                         }
                     }
                 }
@@ -77,6 +118,11 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                         if let Some(r) = state.get_mut(&key) {
                             r.sent_events_throughput_sec =
                                 (v as f64 * (1000.0 / interval as f64)) as i64;
+                            History::push(
+                                &mut r.history.sent_events_throughput_sec,
+                                r.sent_events_throughput_sec,
+                                window,
+                            );
                         }
                     }
                 }
@@ -95,6 +141,21 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
                         }
                     }
                 }
+                EventType::AllocatedBytes(rows) => {
+                    for (key, v) in rows {
+                        if let Some(r) = state.get_mut(&key) {
+                            r.allocated_bytes = v;
+                        }
+                    }
+                }
+                EventType::ErrorsTotals(rows) => {
+                    for (key, v) in rows {
+                        if let Some(r) = state.get_mut(&key) {
+                            r.errors = v;
+                            r.history.push_errors_total(v, window);
+                        }
+                    }
+                }
                 EventType::ComponentAdded(c) => {
                     let _ = state.insert(c.key.clone(), c);
                 }
@@ -110,3 +171,28 @@ pub async fn updater(mut state: State, mut event_rx: EventRx) -> StateRx {
 
     rx
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_keeps_window() {
+        let mut samples = VecDeque::new();
+        for v in 1..=5 {
+            History::push(&mut samples, v, 3);
+        }
+
+        assert_eq!(samples, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn history_errors_are_deltas() {
+        let mut history = History::default();
+        for total in [10, 12, 12, 15] {
+            history.push_errors_total(total, 10);
+        }
+
+        assert_eq!(history.errors, vec![0, 2, 0, 3]);
+    }
+}
@@ -0,0 +1,83 @@
+//! Acknowledgement deadlines of sinks.
+//!
+//! A sink hanging onto events would leave the batches they belong to pending
+//! forever, and sources waiting on them unable to advance. Once a sink has
+//! held the first event of a batch for its `acknowledgement_timeout_secs`,
+//! the batch is resolved as errored if it's still pending, so that its source
+//! can retry it rather than stall.
+
+use crate::{event::Event, internal_events::SinkAcknowledgementsExpired};
+use futures::{Stream, StreamExt};
+use tokio::{
+    sync::mpsc,
+    time::{self, Duration, Instant},
+};
+use vector_core::event::BatchExpiry;
+
+/// Tracks the batches of the events of `input`, expiring those still pending
+/// after `timeout`.
+pub(super) fn expire_pending<S>(input: S, timeout: Duration) -> impl Stream<Item = Event>
+where
+    S: Stream<Item = Event>,
+{
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(expire(rx, timeout));
+
+    // Events of a batch usually come in a row, so they are only tracked once.
+    let mut last = BatchExpiry::default();
+    input.map(move |event| {
+        let batches = event.metadata().batch_expiry();
+        if !batches.is_empty() && !batches.same_batches(&last) {
+            let _ = tx.send((Instant::now() + timeout, batches.clone()));
+            last = batches;
+        }
+        event
+    })
+}
+
+/// Expires the batches in the order they were tracked, which is the order of
+/// their deadlines since all of them share the same timeout. Runs until the
+/// input of the sink is dropped and all of its deadlines passed.
+async fn expire(mut rx: mpsc::UnboundedReceiver<(Instant, BatchExpiry)>, timeout: Duration) {
+    while let Some((deadline, batches)) = rx.recv().await {
+        time::sleep_until(deadline).await;
+        let count = batches.expire();
+        if count > 0 {
+            emit!(&SinkAcknowledgementsExpired { count, timeout });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{BatchNotifier, BatchStatus, LogEvent};
+    use futures::stream;
+
+    #[tokio::test]
+    async fn expires_pending_batches() {
+        time::pause();
+
+        let (batch1, mut receiver1) = BatchNotifier::new_with_receiver();
+        let (batch2, mut receiver2) = BatchNotifier::new_with_receiver();
+        let events = vec![
+            Event::from(LogEvent::from("a").with_batch_notifier(&batch1)),
+            Event::from(LogEvent::from("b").with_batch_notifier(&batch1)),
+            Event::from(LogEvent::from("c").with_batch_notifier(&batch2)),
+        ];
+        drop(batch1);
+        drop(batch2);
+
+        let mut events = expire_pending(stream::iter(events), Duration::from_secs(10))
+            .collect::<Vec<_>>()
+            .await;
+        // The sink delivers the last event, and hangs onto the others.
+        drop(events.pop());
+        assert_eq!(receiver2.try_recv(), Ok(BatchStatus::Delivered));
+        assert!(receiver1.try_recv().is_err());
+
+        time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(receiver1.try_recv(), Ok(BatchStatus::Errored));
+        drop(events);
+    }
+}
@@ -1,5 +1,9 @@
 use super::{
+    acknowledgements::expire_pending,
+    event_tracing::{self, EventTracer},
     fanout::{self, Fanout},
+    healthcheck::{pause_while_unhealthy, PeriodicHealthcheck, HEALTHCHECK_TIMEOUT},
+    limits::EventLimiter,
     task::{Task, TaskOutput},
     BuiltBuffer, ConfigDiff,
 };
@@ -11,10 +15,11 @@ use crate::{
     event::Event,
     internal_events::{EventsReceived, EventsSent},
     shutdown::SourceShutdownCoordinator,
-    transforms::Transform,
+    sinks::util::service::SharedRateLimiters,
+    transforms::{RoutedEvent, Transform},
     Pipeline,
 };
-use futures::{stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
+use futures::{future, stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
 use lazy_static::lazy_static;
 use std::pin::Pin;
 use std::{
@@ -25,6 +30,7 @@ use std::{
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
 use tokio::{
     select,
+    sync::watch,
     time::{timeout, Duration},
 };
 use vector_core::ByteSizeOf;
@@ -119,6 +125,8 @@ pub async fn build_pieces(
     let (enrichment_tables, enrichment_errors) = load_enrichment_tables(config, diff).await;
     errors.extend(enrichment_errors);
 
+    let tracer = EventTracer::new(config.event_tracing);
+
     // Build sources
     for (key, source) in config
         .sources
@@ -128,6 +136,14 @@ pub async fn build_pieces(
         let (tx, rx) = futures::channel::mpsc::channel(1000);
         let pipeline = Pipeline::from_sender(tx, vec![]);
 
+        let mut named_pipelines = HashMap::new();
+        let mut named_receivers = Vec::new();
+        for name in source.inner.named_outputs() {
+            let (tx, rx) = futures::channel::mpsc::channel(1000);
+            named_pipelines.insert(name.clone(), Pipeline::from_sender(tx, vec![]));
+            named_receivers.push((name, rx));
+        }
+
         let typetag = source.inner.source_type();
 
         let (shutdown_signal, force_shutdown_tripwire) = shutdown_coordinator.register_source(key);
@@ -137,6 +153,7 @@ pub async fn build_pieces(
             globals: config.global.clone(),
             shutdown: shutdown_signal,
             out: pipeline,
+            named_outputs: named_pipelines,
             acknowledgements: source.acknowledgements,
             proxy: ProxyConfig::merge_with_env(&config.global.proxy, &source.proxy),
         };
@@ -148,8 +165,28 @@ pub async fn build_pieces(
             Ok(server) => server,
         };
 
-        let (output, control) = Fanout::new();
-        let pump = rx.map(Ok).forward(output).map_ok(|_| TaskOutput::Source);
+        let limiter = EventLimiter::new(&config.limits.overridden_by(&source.limits));
+        let (output, control) = Fanout::new_with(source.distribution);
+        let mut pumps = vec![{
+            let limiter = limiter.clone();
+            rx.filter_map(move |event| ready(limiter.apply(event)))
+                .map(move |event| Ok(tracer.start(event)))
+                .forward(output)
+                .boxed()
+        }];
+        outputs.insert(OutputId::from(key), control);
+        for (name, rx) in named_receivers {
+            let (output, control) = Fanout::new_with(source.distribution);
+            let limiter = limiter.clone();
+            pumps.push(
+                rx.filter_map(move |event| ready(limiter.apply(event)))
+                    .map(move |event| Ok(tracer.start(event)))
+                    .forward(output)
+                    .boxed(),
+            );
+            outputs.insert(OutputId::from((key, name)), control);
+        }
+        let pump = future::try_join_all(pumps).map_ok(|_| TaskOutput::Source);
         let pump = Task::new(key.clone(), typetag, pump);
 
         // The force_shutdown_tripwire is a Future that when it resolves means that this source
@@ -177,23 +214,24 @@ pub async fn build_pieces(
         };
         let server = Task::new(key.clone(), typetag, server);
 
-        outputs.insert(OutputId::from(key), control);
         tasks.insert(key.clone(), pump);
         source_tasks.insert(key.clone(), server);
     }
 
-    let context = TransformContext {
-        globals: config.global.clone(),
-        enrichment_tables: enrichment_tables.clone(),
-    };
-
     // Build transforms
     for (key, transform) in config
         .transforms
         .iter()
         .filter(|(key, _)| diff.transforms.contains_new(key))
     {
+        let context = TransformContext {
+            key: Some(key.id().to_string()),
+            globals: config.global.clone(),
+            enrichment_tables: enrichment_tables.clone(),
+        };
+
         let trans_inputs = &transform.inputs;
+        let distribution = transform.distribution;
 
         let typetag = transform.inner.transform_type();
 
@@ -221,7 +259,7 @@ pub async fn build_pieces(
 
         let task = match transform {
             Transform::Function(mut t) => {
-                let (output, control) = Fanout::new();
+                let (output, control) = Fanout::new_with(distribution);
 
                 let transform = input_rx
                     .filter(move |event| ready(filter_event_type(event, input_type)))
@@ -236,10 +274,12 @@ pub async fn build_pieces(
                         let mut output = Vec::with_capacity(events.len());
                         let mut buf = Vec::with_capacity(4); // also an arbitrary,
                                                              // smallish constant
-                        for v in events {
+                        for mut v in events {
+                            event_tracing::dequeued(&mut v);
                             t.transform(&mut buf, v);
                             output.append(&mut buf);
                         }
+                        output.iter_mut().for_each(event_tracing::enqueued);
                         emit!(&EventsSent {
                             count: output.len(),
                             byte_size: output.size_of(),
@@ -258,14 +298,15 @@ pub async fn build_pieces(
                 Task::new(key.clone(), typetag, transform)
             }
             Transform::FallibleFunction(mut t) => {
-                let (mut output, control) = Fanout::new();
-                let (mut errors_output, errors_control) = Fanout::new();
+                let (mut output, control) = Fanout::new_with(distribution);
+                let (mut errors_output, errors_control) = Fanout::new_with(distribution);
 
                 let transform = async move {
-                    while let Some(event) = input_rx.next().await {
+                    while let Some(mut event) = input_rx.next().await {
                         if !filter_event_type(&event, input_type) {
                             continue;
                         }
+                        event_tracing::dequeued(&mut event);
                         emit!(&EventsReceived {
                             count: 1,
                             byte_size: event.size_of(),
@@ -275,6 +316,9 @@ pub async fn build_pieces(
                         let mut err_buf = Vec::with_capacity(1);
 
                         t.transform(&mut buf, &mut err_buf, event);
+                        buf.iter_mut()
+                            .chain(err_buf.iter_mut())
+                            .for_each(event_tracing::enqueued);
                         // TODO: account for error outputs separately?
                         emit!(&EventsSent {
                             count: buf.len() + err_buf.len(),
@@ -308,7 +352,7 @@ pub async fn build_pieces(
                 Task::new(key.clone(), typetag, transform)
             }
             Transform::Task(t) => {
-                let (output, control) = Fanout::new();
+                let (output, control) = Fanout::new_with(distribution);
 
                 let filtered = input_rx
                     .filter(move |event| ready(filter_event_type(event, input_type)))
@@ -317,11 +361,16 @@ pub async fn build_pieces(
                             count: 1,
                             byte_size: event.size_of(),
                         })
+                    })
+                    .map(|mut event| {
+                        event_tracing::dequeued(&mut event);
+                        event
                     });
                 let transform = t
                     .transform(Box::pin(filtered))
                     .map(Ok)
-                    .forward(output.with(|event: Event| async {
+                    .forward(output.with(|mut event: Event| async {
+                        event_tracing::enqueued(&mut event);
                         emit!(&EventsSent {
                             count: 1,
                             byte_size: event.size_of(),
@@ -336,6 +385,58 @@ pub async fn build_pieces(
 
                 outputs.insert(OutputId::from(key), control);
 
+                Task::new(key.clone(), typetag, transform)
+            }
+            Transform::MultiOutputTask(t) => {
+                let (mut output, control) = Fanout::new_with(distribution);
+                outputs.insert(OutputId::from(key), control);
+                let mut named_fanouts = HashMap::new();
+                for name in named_outputs {
+                    let (fanout, control) = Fanout::new_with(distribution);
+                    outputs.insert(OutputId::from((key, name.clone())), control);
+                    named_fanouts.insert(name, fanout);
+                }
+
+                let filtered = input_rx
+                    .filter(move |event| ready(filter_event_type(event, input_type)))
+                    .inspect(|event| {
+                        emit!(&EventsReceived {
+                            count: 1,
+                            byte_size: event.size_of(),
+                        })
+                    })
+                    .map(|mut event| {
+                        event_tracing::dequeued(&mut event);
+                        event
+                    });
+                let mut routed = t.transform(Box::pin(filtered));
+
+                let transform = async move {
+                    while let Some(RoutedEvent {
+                        output: name,
+                        mut event,
+                    }) = routed.next().await
+                    {
+                        let fanout = match name {
+                            None => &mut output,
+                            Some(name) => match named_fanouts.get_mut(&name) {
+                                Some(fanout) => fanout,
+                                None => continue,
+                            },
+                        };
+                        event_tracing::enqueued(&mut event);
+                        emit!(&EventsSent {
+                            count: 1,
+                            byte_size: event.size_of(),
+                        });
+                        fanout.send(event).await.expect("unit error");
+                    }
+
+                    debug!("Finished.");
+                    Ok(TaskOutput::Transform)
+                }
+                .boxed();
+
                 Task::new(key.clone(), typetag, transform)
             }
         };
@@ -345,6 +446,7 @@ pub async fn build_pieces(
     }
 
     // Build sinks
+    let rate_limiters = SharedRateLimiters::from_config(&config.rate_limiters);
     for (key, sink) in config
         .sinks
         .iter()
@@ -353,6 +455,23 @@ pub async fn build_pieces(
         let sink_inputs = &sink.inputs;
         let healthcheck = sink.healthcheck();
         let enable_healthcheck = healthcheck.enabled && config.healthchecks.enabled;
+        let healthcheck_interval = healthcheck.interval_secs.filter(|_| enable_healthcheck);
+        let healthcheck_failure_threshold = healthcheck.failure_threshold.max(1);
+        if healthcheck_interval == Some(0) {
+            errors.push(format!(
+                "Sink \"{}\": `healthcheck.interval_secs` must be greater than zero.",
+                key
+            ));
+            continue;
+        }
+        if sink.acknowledgement_timeout_secs == Some(0) {
+            errors.push(format!(
+                "Sink \"{}\": `acknowledgement_timeout_secs` must be greater than zero.",
+                key
+            ));
+            continue;
+        }
+        let acknowledgement_timeout = sink.acknowledgement_timeout_secs.map(Duration::from_secs);
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input_type();
@@ -380,7 +499,12 @@ pub async fn build_pieces(
                     errors.push(format!("Sink \"{}\": {}", key, error));
                     continue;
                 }
-                Ok((tx, rx, acker)) => (tx, Arc::new(Mutex::new(Some(rx.into()))), acker),
+                Ok((tx, rx, acker)) => {
+                    if let Some(usage) = tx.usage() {
+                        buffers::register_usage(key, usage);
+                    }
+                    (tx, Arc::new(Mutex::new(Some(rx.into()))), acker)
+                }
             }
         };
 
@@ -389,6 +513,25 @@ pub async fn build_pieces(
             healthcheck,
             globals: config.global.clone(),
             proxy: ProxyConfig::merge_with_env(&config.global.proxy, sink.proxy()),
+            rate_limiters: rate_limiters.clone(),
+        };
+
+        let (healthy_tx, healthy_rx) = watch::channel(true);
+        let periodic_healthcheck = match healthcheck_interval {
+            Some(interval_secs) => match PeriodicHealthcheck::clone_config(sink.inner.as_ref()) {
+                Ok(sink_config) => Some(PeriodicHealthcheck {
+                    config: sink_config,
+                    cx: cx.clone(),
+                    interval: Duration::from_secs(interval_secs),
+                    failure_threshold: healthcheck_failure_threshold,
+                    healthy: healthy_tx,
+                }),
+                Err(error) => {
+                    errors.push(format!("Sink \"{}\": {}", key, error));
+                    continue;
+                }
+            },
+            None => None,
         };
 
         let (sink, healthcheck) = match sink.inner.build(cx).await {
@@ -416,19 +559,35 @@ pub async fn build_pieces(
 
             let mut rx = crate::utilization::wrap(rx);
 
-            sink.run(
-                rx.by_ref()
-                    .filter(|event| ready(filter_event_type(event, input_type)))
-                    .inspect(|event| {
-                        emit!(&EventsReceived {
-                            count: 1,
-                            byte_size: event.size_of(),
-                        })
+            let input = rx
+                .by_ref()
+                .filter(|event| ready(filter_event_type(event, input_type)))
+                .inspect(|event| {
+                    emit!(&EventsReceived {
+                        count: 1,
+                        byte_size: event.size_of(),
                     })
-                    .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+                })
+                .map(|mut event| {
+                    event_tracing::delivered(&mut event);
+                    event
+                });
+            let input = match acknowledgement_timeout {
+                Some(timeout) => expire_pending(input, timeout).left_stream(),
+                None => input.right_stream(),
+            };
+            let periodic_healthcheck = async move {
+                match periodic_healthcheck {
+                    Some(periodic_healthcheck) => periodic_healthcheck.run().await,
+                    None => future::pending().await,
+                }
+            };
+
+            let result = select! {
+                result = sink.run(pause_while_unhealthy(input, healthy_rx).take_until_if(tripwire)) => result,
+                _ = periodic_healthcheck => unreachable!("Periodic healthchecks run until dropped."),
+            };
+            result.map(|_| {
                 debug!("Finished.");
                 TaskOutput::Sink(rx, acker)
             })
@@ -439,8 +598,7 @@ pub async fn build_pieces(
         let component_key = key.clone();
         let healthcheck_task = async move {
             if enable_healthcheck {
-                let duration = Duration::from_secs(10);
-                timeout(duration, healthcheck)
+                timeout(HEALTHCHECK_TIMEOUT, healthcheck)
                     .map(|result| match result {
                         Ok(Ok(_)) => {
                             info!("Healthcheck: Passed.");
@@ -0,0 +1,120 @@
+//! Topology-wide event tracing.
+//!
+//! When enabled, a sampled fraction of the events leaving each source get an
+//! `EventTrace` in their metadata. Every component the event passes through
+//! then reports how long it waited in that component's input buffer and, for
+//! transforms, how long the transform took to emit it. Sinks also report the
+//! total latency since the event left its source.
+
+use crate::{
+    config::EventTracingOptions,
+    event::{Event, EventTrace},
+    internal_events::{EventTraceHop, EventTraceStage, EventTraceStarted},
+};
+use rand::{thread_rng, Rng};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct EventTracer {
+    sample_rate: Option<f64>,
+}
+
+impl EventTracer {
+    pub(super) fn new(options: EventTracingOptions) -> Self {
+        Self {
+            sample_rate: options.enabled.then(|| options.sample_rate),
+        }
+    }
+
+    /// Starts a trace on the event if it's sampled.
+    pub(super) fn start(self, mut event: Event) -> Event {
+        if let Some(sample_rate) = self.sample_rate {
+            let mut rng = thread_rng();
+            if rng.gen::<f64>() < sample_rate {
+                let trace_id = rng.gen();
+                event
+                    .metadata_mut()
+                    .set_trace(Some(EventTrace::new(trace_id)));
+                emit!(&EventTraceStarted { trace_id });
+            }
+        }
+        event
+    }
+}
+
+/// Records the time a traced event spent in a component's input buffer.
+pub(super) fn dequeued(event: &mut Event) {
+    hop(event, EventTraceStage::Queued);
+}
+
+/// Records the time a transform took to emit a traced event.
+pub(super) fn enqueued(event: &mut Event) {
+    hop(event, EventTraceStage::Processing);
+}
+
+/// Records the time a traced event spent in a sink's input buffer, and the
+/// time since it left its source.
+pub(super) fn delivered(event: &mut Event) {
+    if let Some(trace) = hop(event, EventTraceStage::Queued) {
+        emit!(&EventTraceHop {
+            trace_id: trace.id,
+            stage: EventTraceStage::EndToEnd,
+            latency: trace.hop_at - trace.started_at,
+        });
+    }
+}
+
+fn hop(event: &mut Event, stage: EventTraceStage) -> Option<EventTrace> {
+    let metadata = event.metadata_mut();
+    let mut trace = (*metadata.trace())?;
+    let now = Instant::now();
+    emit!(&EventTraceHop {
+        trace_id: trace.id,
+        stage,
+        latency: now - trace.hop_at,
+    });
+    trace.hop_at = now;
+    metadata.set_trace(Some(trace));
+    Some(trace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracer(sample_rate: f64) -> EventTracer {
+        EventTracer::new(EventTracingOptions {
+            enabled: true,
+            sample_rate,
+        })
+    }
+
+    #[test]
+    fn disabled_tracer_leaves_events_alone() {
+        let event = EventTracer::new(EventTracingOptions::default()).start(Event::from("foo"));
+        assert!(event.metadata().trace().is_none());
+    }
+
+    #[test]
+    fn tracks_hops() {
+        let mut event = tracer(1.0).start(Event::from("foo"));
+        let started = event.metadata().trace().expect("event should be traced");
+        assert_eq!(started.started_at, started.hop_at);
+
+        dequeued(&mut event);
+        enqueued(&mut event);
+        delivered(&mut event);
+
+        let trace = event.metadata().trace().unwrap();
+        assert_eq!(trace.id, started.id);
+        assert_eq!(trace.started_at, started.started_at);
+        assert!(trace.hop_at >= started.hop_at);
+    }
+
+    #[test]
+    fn untraced_events_have_no_hops() {
+        let mut event = Event::from("foo");
+        delivered(&mut event);
+        assert!(event.metadata().trace().is_none());
+    }
+}
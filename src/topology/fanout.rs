@@ -1,4 +1,4 @@
-use crate::config::ComponentKey;
+use crate::config::{ComponentKey, Distribution};
 use crate::event::Event;
 use futures::{channel::mpsc, future, stream::Fuse, Sink, Stream, StreamExt};
 use std::{
@@ -32,16 +32,28 @@ pub type ControlChannel = mpsc::UnboundedSender<ControlMessage>;
 pub struct Fanout {
     sinks: Vec<(ComponentKey, Option<Pin<RouterSink>>)>,
     i: usize,
+    distribution: Distribution,
+    /// Sink the next event goes to, unless broadcasting.
+    target: Option<usize>,
+    /// Sink to start looking for the next target from, unless broadcasting.
+    next: usize,
     control_channel: Fuse<mpsc::UnboundedReceiver<ControlMessage>>,
 }
 
 impl Fanout {
     pub fn new() -> (Self, ControlChannel) {
+        Self::new_with(Distribution::Broadcast)
+    }
+
+    pub fn new_with(distribution: Distribution) -> (Self, ControlChannel) {
         let (control_tx, control_rx) = mpsc::unbounded();
 
         let fanout = Self {
             sinks: vec![],
             i: 0,
+            distribution,
+            target: None,
+            next: 0,
             control_channel: control_rx.fuse(),
         };
 
@@ -70,6 +82,14 @@ impl Fanout {
         if self.i > i {
             self.i -= 1;
         }
+        if self.next > i {
+            self.next -= 1;
+        }
+        self.target = match self.target {
+            Some(target) if target == i => None,
+            Some(target) if target > i => Some(target - 1),
+            target => target,
+        };
     }
 
     fn replace(&mut self, id: ComponentKey, sink: Option<RouterSink>) {
@@ -99,10 +119,48 @@ impl Fanout {
             Err(())
         } else {
             self.sinks.remove(index);
+            if self.next > index {
+                self.next -= 1;
+            }
             Ok(())
         }
     }
 
+    /// Picks the sink the next event is distributed to, once it's ready.
+    fn poll_target(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        'sinks: loop {
+            let len = self.sinks.len();
+            if len == 0 {
+                return Poll::Ready(Ok(()));
+            }
+
+            let candidates = match self.distribution {
+                Distribution::LeastLoaded => len,
+                _ => 1,
+            };
+            for offset in 0..candidates {
+                let index = (self.next + offset) % len;
+                // A sink being replaced is skipped, the control channel will
+                // notify the current task once it's back.
+                if let Some(sink) = self.sinks[index].1.as_mut() {
+                    match sink.as_mut().poll_ready(cx) {
+                        Poll::Pending => (),
+                        Poll::Ready(Ok(())) => {
+                            self.target = Some(index);
+                            return Poll::Ready(Ok(()));
+                        }
+                        Poll::Ready(Err(())) => {
+                            self.handle_sink_error(index)?;
+                            continue 'sinks;
+                        }
+                    }
+                }
+            }
+
+            return Poll::Pending;
+        }
+    }
+
     fn poll_sinks<F>(&mut self, cx: &mut Context<'_>, poll: F) -> Poll<Result<(), ()>>
     where
         F: Fn(&mut Pin<RouterSink>, &mut Context<'_>) -> Poll<Result<(), ()>>,
@@ -138,6 +196,10 @@ impl Sink<Event> for Fanout {
 
         this.process_control_messages(cx);
 
+        if this.distribution != Distribution::Broadcast {
+            return this.poll_target(cx);
+        }
+
         while let Some((_, sink)) = this.sinks.get_mut(this.i) {
             match sink.as_mut() {
                 Some(sink) => match sink.as_mut().poll_ready(cx) {
@@ -158,6 +220,18 @@ impl Sink<Event> for Fanout {
     }
 
     fn start_send(mut self: Pin<&mut Self>, item: Event) -> Result<(), ()> {
+        if self.distribution != Distribution::Broadcast {
+            if let Some(target) = self.target.take() {
+                self.next = (target + 1) % self.sinks.len();
+                if let Some(sink) = self.sinks[target].1.as_mut() {
+                    if sink.as_mut().start_send(item).is_err() {
+                        self.handle_sink_error(target)?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
         let mut i = 1;
         while let Some((_, sink)) = self.sinks.get_mut(i) {
             if let Some(sink) = sink.as_mut() {
@@ -192,7 +266,11 @@ impl Sink<Event> for Fanout {
 #[cfg(test)]
 mod tests {
     use super::{ControlMessage, Fanout};
-    use crate::{config::ComponentKey, event::Event, test_util::collect_ready};
+    use crate::{
+        config::{ComponentKey, Distribution},
+        event::Event,
+        test_util::collect_ready,
+    };
     use futures::{channel::mpsc, stream, FutureExt, Sink, SinkExt, StreamExt};
     use std::{
         pin::Pin,
@@ -611,6 +689,58 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn fanout_round_robin() {
+        let (tx_a, rx_a) = mpsc::unbounded();
+        let tx_a = Box::new(tx_a.sink_map_err(|_| unreachable!()));
+        let (tx_b, rx_b) = mpsc::unbounded();
+        let tx_b = Box::new(tx_b.sink_map_err(|_| unreachable!()));
+
+        let (mut fanout, _fanout_control) = Fanout::new_with(Distribution::RoundRobin);
+
+        fanout.add(ComponentKey::from("a"), tx_a);
+        fanout.add(ComponentKey::from("b"), tx_b);
+
+        let recs = make_events(4);
+        let send = stream::iter(recs.clone()).map(Ok).forward(fanout);
+        let _ = send.await.unwrap();
+
+        assert_eq!(
+            collect_ready(rx_a).await,
+            vec![recs[0].clone(), recs[2].clone()]
+        );
+        assert_eq!(
+            collect_ready(rx_b).await,
+            vec![recs[1].clone(), recs[3].clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn fanout_least_loaded_skips_notready() {
+        let (tx_a, rx_a) = mpsc::channel(0);
+        let tx_a = Box::new(tx_a.sink_map_err(|_| unreachable!()));
+        let (tx_b, rx_b) = mpsc::unbounded();
+        let tx_b = Box::new(tx_b.sink_map_err(|_| unreachable!()));
+
+        let (mut fanout, _fanout_control) = Fanout::new_with(Distribution::LeastLoaded);
+
+        fanout.add(ComponentKey::from("a"), tx_a);
+        fanout.add(ComponentKey::from("b"), tx_b);
+
+        let recs = make_events(4);
+        let send = stream::iter(recs.clone()).map(Ok).forward(fanout);
+        tokio::spawn(send);
+
+        sleep(Duration::from_millis(50)).await;
+        // a only had room for the first event, so the others went to b.
+
+        let collect_a = tokio::spawn(rx_a.collect::<Vec<_>>());
+        let collect_b = tokio::spawn(rx_b.collect::<Vec<_>>());
+
+        assert_eq!(collect_a.await.unwrap(), &recs[..1]);
+        assert_eq!(collect_b.await.unwrap(), &recs[1..]);
+    }
+
     fn make_events(count: usize) -> Vec<Event> {
         (0..count)
             .map(|i| Event::from(format!("line {}", i)))
@@ -0,0 +1,116 @@
+//! Periodic sink healthchecks.
+//!
+//! Healthchecks are one-shot futures, so each periodic check builds the sink
+//! again from its config and only runs the healthcheck it returns. The sink's
+//! input is paused while it's unhealthy, leaving its buffer to absorb events.
+
+use crate::{
+    config::{SinkConfig, SinkContext},
+    internal_events::{HealthcheckFailed, HealthcheckStateChanged},
+};
+use futures::{stream, Stream, StreamExt};
+use tokio::{
+    sync::watch,
+    time::{self, timeout, Duration},
+};
+
+/// How long a healthcheck may run before it's considered failed.
+pub(super) const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(super) struct PeriodicHealthcheck {
+    pub config: Box<dyn SinkConfig>,
+    pub cx: SinkContext,
+    pub interval: Duration,
+    pub failure_threshold: usize,
+    pub healthy: watch::Sender<bool>,
+}
+
+impl PeriodicHealthcheck {
+    /// Copies the sink config, since trait objects can't be cloned, by
+    /// serializing it into JSON and back.
+    pub fn clone_config(config: &dyn SinkConfig) -> crate::Result<Box<dyn SinkConfig>> {
+        let json = serde_json::to_value(config)?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Runs the healthcheck at every interval until dropped.
+    pub async fn run(self) {
+        let mut interval = time::interval_at(time::Instant::now() + self.interval, self.interval);
+        let mut consecutive_failures = 0;
+        let mut healthy = true;
+
+        loop {
+            interval.tick().await;
+
+            match self.check().await {
+                Ok(()) => {
+                    consecutive_failures = 0;
+                    if !healthy {
+                        healthy = true;
+                        let _ = self.healthy.send(true);
+                        emit!(&HealthcheckStateChanged { healthy: true });
+                    }
+                }
+                Err(error) => {
+                    consecutive_failures += 1;
+                    emit!(&HealthcheckFailed {
+                        error: &error,
+                        consecutive_failures,
+                    });
+                    if healthy && consecutive_failures >= self.failure_threshold {
+                        healthy = false;
+                        let _ = self.healthy.send(false);
+                        emit!(&HealthcheckStateChanged { healthy: false });
+                    }
+                }
+            }
+        }
+    }
+
+    async fn check(&self) -> crate::Result<()> {
+        let (_, healthcheck) = self.config.build(self.cx.clone()).await?;
+        timeout(HEALTHCHECK_TIMEOUT, healthcheck)
+            .await
+            .unwrap_or_else(|_| Err("Healthcheck timed out.".into()))
+    }
+}
+
+/// Stops reading from `input` while the sink is unhealthy.
+pub(super) fn pause_while_unhealthy<S>(
+    input: S,
+    healthy: watch::Receiver<bool>,
+) -> impl Stream<Item = S::Item>
+where
+    S: Stream + Unpin,
+{
+    stream::unfold((input, healthy), |(mut input, mut healthy)| async move {
+        while !*healthy.borrow() {
+            if healthy.changed().await.is_err() {
+                break;
+            }
+        }
+        input.next().await.map(|item| (item, (input, healthy)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn pauses_input_while_unhealthy() {
+        let (healthy_tx, healthy_rx) = watch::channel(true);
+        let mut output = Box::pin(pause_while_unhealthy(stream::iter(1..=3), healthy_rx));
+
+        assert_eq!(output.next().await, Some(1));
+
+        healthy_tx.send(false).unwrap();
+        assert_eq!(output.next().now_or_never(), None);
+
+        healthy_tx.send(true).unwrap();
+        assert_eq!(output.next().await, Some(2));
+        assert_eq!(output.next().await, Some(3));
+        assert_eq!(output.next().await, None);
+    }
+}
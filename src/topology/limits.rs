@@ -0,0 +1,200 @@
+//! Size limits on the events leaving sources.
+//!
+//! Applied right after a source emits an event, and so before it is fanned
+//! out, the limits cap the size of each string value of log events, either
+//! truncating the values over it or dropping their events, and then drop any
+//! event still over the limit on its total size.
+
+use crate::{
+    config::{EventLimitsOptions, LimitAction},
+    event::{Event, LogEvent, Value},
+    internal_events::{EventFieldTooLarge, EventFieldTruncated, EventTooLarge},
+};
+use vector_core::ByteSizeOf;
+
+const DEFAULT_ANNOTATION_FIELD: &str = "truncated_fields";
+
+#[derive(Clone, Debug)]
+pub(super) struct EventLimiter {
+    max_field_bytes: Option<usize>,
+    max_event_bytes: Option<usize>,
+    action: LimitAction,
+    annotation_field: String,
+}
+
+impl EventLimiter {
+    pub(super) fn new(options: &EventLimitsOptions) -> Self {
+        Self {
+            max_field_bytes: options.max_field_bytes,
+            max_event_bytes: options.max_event_bytes,
+            action: options.action.unwrap_or(LimitAction::Truncate),
+            annotation_field: options
+                .annotation_field
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ANNOTATION_FIELD.to_owned()),
+        }
+    }
+
+    /// Applies the limits to the event, returning it unless it's dropped.
+    pub(super) fn apply(&self, mut event: Event) -> Option<Event> {
+        if let (Some(max_size), Event::Log(log)) = (self.max_field_bytes, &mut event) {
+            match self.action {
+                LimitAction::Truncate => self.truncate_fields(log, max_size),
+                LimitAction::Drop => {
+                    let oversized = log.all_fields().find_map(|(field, value)| match value {
+                        Value::Bytes(bytes) if bytes.len() > max_size => Some((field, bytes.len())),
+                        _ => None,
+                    });
+                    if let Some((field, size)) = oversized {
+                        emit!(&EventFieldTooLarge {
+                            field: &field,
+                            size,
+                            max_size,
+                        });
+                        return None;
+                    }
+                }
+            }
+        }
+
+        if let Some(max_size) = self.max_event_bytes {
+            let size = event.size_of();
+            if size > max_size {
+                emit!(&EventTooLarge { size, max_size });
+                return None;
+            }
+        }
+
+        Some(event)
+    }
+
+    fn truncate_fields(&self, log: &mut LogEvent, max_size: usize) {
+        let mut truncated = Vec::new();
+        for (key, value) in log.as_map_mut().iter_mut() {
+            let mut path = key.clone();
+            truncate(value, &mut path, max_size, &mut truncated);
+        }
+
+        if !truncated.is_empty() {
+            let fields = truncated.into_iter().map(Value::from).collect::<Vec<_>>();
+            log.insert(&self.annotation_field, Value::Array(fields));
+        }
+    }
+}
+
+/// Truncates the string values over the limit, at a character boundary, and
+/// records the paths of their fields.
+fn truncate(value: &mut Value, path: &mut String, max_size: usize, truncated: &mut Vec<String>) {
+    match value {
+        Value::Bytes(bytes) if bytes.len() > max_size => {
+            let mut end = max_size;
+            while end > 0 && bytes[end] & 0xC0 == 0x80 {
+                end -= 1;
+            }
+            emit!(&EventFieldTruncated {
+                field: path,
+                size: bytes.len(),
+                max_size,
+            });
+            bytes.truncate(end);
+            truncated.push(path.clone());
+        }
+        Value::Map(map) => {
+            for (key, value) in map.iter_mut() {
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                truncate(value, path, max_size, truncated);
+                path.truncate(len);
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.iter_mut().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{}]", index));
+                truncate(value, path, max_size, truncated);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Metric;
+    use vector_core::event::{MetricKind, MetricValue};
+
+    fn limiter(max_field_bytes: usize, action: LimitAction) -> EventLimiter {
+        EventLimiter::new(&EventLimitsOptions {
+            max_field_bytes: Some(max_field_bytes),
+            action: Some(action),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn unlimited_limiter_leaves_events_alone() {
+        let limiter = EventLimiter::new(&EventLimitsOptions::default());
+
+        let event = Event::from("a very long message");
+        assert_eq!(limiter.apply(event.clone()), Some(event));
+    }
+
+    #[test]
+    fn truncates_fields_at_character_boundaries() {
+        let mut log = LogEvent::default();
+        log.insert("message", "abé!");
+        log.insert("nested.values", vec!["ok", "too long"]);
+        log.insert("short", "abc");
+
+        let event = limiter(3, LimitAction::Truncate)
+            .apply(Event::from(log))
+            .expect("event should be kept");
+        let log = event.as_log();
+
+        assert_eq!(log["message"], "ab".into());
+        assert_eq!(log["nested.values[0]"], "ok".into());
+        assert_eq!(log["nested.values[1]"], "too".into());
+        assert_eq!(log["short"], "abc".into());
+        assert_eq!(
+            log[DEFAULT_ANNOTATION_FIELD],
+            Value::Array(vec!["message".into(), "nested.values[1]".into()])
+        );
+    }
+
+    #[test]
+    fn drops_events_with_oversized_fields() {
+        let limiter = limiter(5, LimitAction::Drop);
+        assert_eq!(limiter.apply(Event::from("too long")), None);
+
+        let event = Event::from("short");
+        assert_eq!(limiter.apply(event.clone()), Some(event));
+    }
+
+    #[test]
+    fn drops_oversized_events() {
+        let limiter = EventLimiter::new(&EventLimitsOptions {
+            max_event_bytes: Some(256),
+            ..Default::default()
+        });
+        assert_eq!(limiter.apply(Event::from("x".repeat(512))), None);
+
+        let event = Event::from("short");
+        assert_eq!(limiter.apply(event.clone()), Some(event));
+    }
+
+    #[test]
+    fn leaves_metric_fields_alone() {
+        let event = Event::Metric(Metric::new(
+            "a_metric_with_a_long_name",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        ));
+        assert_eq!(
+            limiter(3, LimitAction::Drop).apply(event.clone()),
+            Some(event)
+        );
+    }
+}
@@ -6,8 +6,12 @@
 //! part contains config related items including config traits for
 //! each type of component.
 
+mod acknowledgements;
 pub mod builder;
+mod event_tracing;
 pub mod fanout;
+mod healthcheck;
+mod limits;
 mod running;
 mod task;
 
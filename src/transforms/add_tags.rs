@@ -6,7 +6,6 @@ use crate::{
 };
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::btree_map::Entry;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -71,18 +70,18 @@ impl FunctionTransform for AddTags {
             let metric = event.as_mut_metric();
 
             for (name, value) in &self.tags {
-                let entry = metric.tag_entry(name.to_string());
-                match (entry, self.overwrite) {
-                    (Entry::Vacant(entry), _) => {
-                        entry.insert(value.clone());
+                // Inserting a tag drops all of its previous values, which are
+                // kept as they are when it isn't overwritten.
+                let exists = metric.tags().map_or(false, |tags| tags.contains_key(name));
+                match (exists, self.overwrite) {
+                    (false, _) => {
+                        metric.insert_tag(name.clone(), value.clone());
                     }
-                    (Entry::Occupied(mut entry), true) => {
+                    (true, true) => {
                         emit!(&AddTagsTagOverwritten { tag: name.as_ref() });
-                        entry.insert(value.clone());
-                    }
-                    (Entry::Occupied(_entry), false) => {
-                        emit!(&AddTagsTagNotOverwritten { tag: name.as_ref() })
+                        metric.insert_tag(name.clone(), value.clone());
                     }
+                    (true, false) => emit!(&AddTagsTagNotOverwritten { tag: name.as_ref() }),
                 }
             }
         }
@@ -147,4 +146,33 @@ mod tests {
         let event = transform_one(&mut transform, metric.into()).unwrap();
         assert_eq!(event, expected.into());
     }
+
+    #[test]
+    fn add_tags_multi_value_tags() {
+        let mut metric = Metric::new(
+            "bar",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 10.0 },
+        );
+        metric.add_tag_value("region".into(), "us-east-1".into());
+        metric.add_tag_value("region".into(), "us-west-1".into());
+
+        let map = vec![("region".to_string(), "overridden".to_string())]
+            .into_iter()
+            .collect::<IndexMap<_, _>>();
+
+        let mut transform = AddTags::new(map.clone(), false);
+        let event = transform_one(&mut transform, metric.clone().into()).unwrap();
+        assert_eq!(
+            event.as_metric().tag_values("region"),
+            vec!["us-east-1", "us-west-1"]
+        );
+
+        let mut transform = AddTags::new(map, true);
+        let event = transform_one(&mut transform, metric.into()).unwrap();
+        assert_eq!(
+            event.as_metric().iter_tag_values().collect::<Vec<_>>(),
+            vec![("region", "overridden")]
+        );
+    }
 }
@@ -0,0 +1,717 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{AnonymizeFailed, AnonymizeValuesAnonymized},
+    transforms::{FunctionTransform, Transform},
+};
+use bytes::Bytes;
+use once_cell::sync::Lazy;
+use openssl::{
+    error::ErrorStack,
+    hash::MessageDigest,
+    pkey::PKey,
+    rand::rand_bytes,
+    sha::sha256,
+    sign::Signer,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::Ipv6Addr,
+    str::FromStr,
+};
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AnonymizeConfig {
+    /// The fields to scan. All string fields are scanned if unset.
+    #[serde(default)]
+    fields: Option<Vec<String>>,
+    #[serde(default = "default_detectors")]
+    detectors: Vec<Detector>,
+    #[serde(default)]
+    action: Action,
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default = "default_redaction")]
+    redaction: String,
+    #[serde(default = "default_audit_field")]
+    audit_field: String,
+    /// Overrides `action` for individual detectors.
+    #[serde(default)]
+    detector_actions: HashMap<Detector, Action>,
+}
+
+fn default_detectors() -> Vec<Detector> {
+    vec![
+        Detector::Email,
+        Detector::CreditCard,
+        Detector::UsSsn,
+        Detector::UkNino,
+        Detector::Ipv4,
+        Detector::Ipv6,
+    ]
+}
+
+fn default_redaction() -> String {
+    "[REDACTED]".to_owned()
+}
+
+fn default_audit_field() -> String {
+    "anonymized".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum Detector {
+    Email,
+    CreditCard,
+    UsSsn,
+    UkNino,
+    Ipv4,
+    Ipv6,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Redact,
+    Hash,
+    Encrypt,
+    Pseudonymize,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Self::Redact
+    }
+}
+
+impl Action {
+    const fn needs_key(self) -> bool {
+        matches!(self, Self::Encrypt | Self::Pseudonymize)
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("A `key` is required for the `{:?}` action", action))]
+    MissingKey { action: Action },
+    #[snafu(display("No detectors are enabled"))]
+    NoDetectors,
+}
+
+inventory::submit! {
+    TransformDescription::new::<AnonymizeConfig>("anonymize")
+}
+
+impl GenerateConfig for AnonymizeConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            fields: None,
+            detectors: default_detectors(),
+            action: Action::Redact,
+            key: None,
+            redaction: default_redaction(),
+            audit_field: default_audit_field(),
+            detector_actions: HashMap::new(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "anonymize")]
+impl TransformConfig for AnonymizeConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Anonymize::new(self).map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "anonymize"
+    }
+}
+
+/// Keys derived from the configured secret, so that the encryption key is
+/// never reused as the key deriving pseudonyms.
+#[derive(Clone)]
+struct Keys {
+    encryption: Vec<u8>,
+    pseudonym: Vec<u8>,
+}
+
+impl Keys {
+    fn derive(secret: &str) -> Result<Self, ErrorStack> {
+        Ok(Self {
+            encryption: hmac_sha256(secret.as_bytes(), b"vector anonymize encryption")?,
+            pseudonym: hmac_sha256(secret.as_bytes(), b"vector anonymize pseudonym")?,
+        })
+    }
+}
+
+impl std::fmt::Debug for Keys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Keys { .. }")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Anonymize {
+    fields: Option<Vec<String>>,
+    detectors: Vec<Detector>,
+    action: Action,
+    detector_actions: HashMap<Detector, Action>,
+    keys: Option<Keys>,
+    redaction: String,
+    audit_field: Option<String>,
+}
+
+impl Anonymize {
+    pub fn new(config: &AnonymizeConfig) -> crate::Result<Self> {
+        if config.detectors.is_empty() {
+            return Err(BuildError::NoDetectors.into());
+        }
+
+        let keys = config.key.as_deref().map(Keys::derive).transpose()?;
+        if keys.is_none() {
+            let actions = std::iter::once(config.action).chain(
+                config
+                    .detectors
+                    .iter()
+                    .filter_map(|detector| config.detector_actions.get(detector).copied()),
+            );
+            for action in actions {
+                if action.needs_key() {
+                    return Err(BuildError::MissingKey { action }.into());
+                }
+            }
+        }
+
+        Ok(Self {
+            fields: config.fields.clone(),
+            detectors: config.detectors.clone(),
+            action: config.action,
+            detector_actions: config.detector_actions.clone(),
+            keys,
+            redaction: config.redaction.clone(),
+            audit_field: Some(config.audit_field.clone()).filter(|field| !field.is_empty()),
+        })
+    }
+
+    fn should_scan(&self, path: &str) -> bool {
+        if self
+            .audit_field
+            .as_deref()
+            .map_or(false, |audit| path == audit || is_nested_path(path, audit))
+        {
+            return false;
+        }
+        match &self.fields {
+            None => true,
+            Some(fields) => fields
+                .iter()
+                .any(|field| path == field || is_nested_path(path, field)),
+        }
+    }
+
+    fn anonymize(&self, input: &str, counts: &mut BTreeMap<Detector, usize>) -> Option<String> {
+        let matches = find_matches(input, &self.detectors);
+        if matches.is_empty() {
+            return None;
+        }
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+        for (start, end, detector) in matches {
+            output.push_str(&input[last..start]);
+            output.push_str(&self.replace(&input[start..end], detector));
+            *counts.entry(detector).or_default() += 1;
+            last = end;
+        }
+        output.push_str(&input[last..]);
+        Some(output)
+    }
+
+    fn replace(&self, value: &str, detector: Detector) -> String {
+        let action = self
+            .detector_actions
+            .get(&detector)
+            .copied()
+            .unwrap_or(self.action);
+        let result = match (action, &self.keys) {
+            (Action::Redact, _) => return self.redaction.clone(),
+            (Action::Hash, _) => Ok(hex(&sha256(value.as_bytes()))),
+            (Action::Encrypt, Some(keys)) => encrypt(keys, value, None),
+            (Action::Pseudonymize, Some(keys)) => hmac_sha256(&keys.pseudonym, value.as_bytes())
+                .and_then(|mac| encrypt(keys, value, Some(&mac[..NONCE_LEN]))),
+            // Keys are checked for when building the transform.
+            (Action::Encrypt, None) | (Action::Pseudonymize, None) => unreachable!(),
+        };
+
+        // Never let a value through because it couldn't be transformed.
+        result.unwrap_or_else(|error| {
+            emit!(&AnonymizeFailed { error, detector });
+            self.redaction.clone()
+        })
+    }
+}
+
+impl FunctionTransform for Anonymize {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+
+        let paths = log
+            .all_fields()
+            .filter(|(path, value)| matches!(value, Value::Bytes(_)) && self.should_scan(path))
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+
+        let mut audit = Vec::new();
+        let mut anonymized_count = 0;
+        for path in paths {
+            let mut counts = BTreeMap::new();
+            let anonymized = match log.get(&path) {
+                Some(Value::Bytes(bytes)) => {
+                    self.anonymize(&String::from_utf8_lossy(bytes), &mut counts)
+                }
+                _ => None,
+            };
+            if let Some(anonymized) = anonymized {
+                log.insert(path.as_str(), Bytes::from(anonymized));
+                anonymized_count += counts.values().sum::<usize>();
+                audit.extend(
+                    counts
+                        .into_iter()
+                        .map(|(detector, count)| audit_entry(&path, detector, count)),
+                );
+            }
+        }
+
+        if !audit.is_empty() {
+            emit!(&AnonymizeValuesAnonymized {
+                count: anonymized_count
+            });
+            if let Some(audit_field) = &self.audit_field {
+                append_audit(log, audit_field, audit);
+            }
+        }
+
+        output.push(event);
+    }
+}
+
+fn is_nested_path(path: &str, parent: &str) -> bool {
+    path.strip_prefix(parent)
+        .map_or(false, |rest| rest.starts_with('.') || rest.starts_with('['))
+}
+
+fn audit_entry(field: &str, detector: Detector, count: usize) -> Value {
+    let mut entry = BTreeMap::new();
+    entry.insert("field".to_owned(), Value::from(field));
+    entry.insert("detector".to_owned(), Value::from(detector.as_str()));
+    entry.insert("count".to_owned(), Value::from(count as i64));
+    Value::Map(entry)
+}
+
+fn append_audit(log: &mut LogEvent, audit_field: &str, audit: Vec<Value>) {
+    match log.get_mut(audit_field) {
+        Some(Value::Array(existing)) => existing.extend(audit),
+        _ => {
+            log.insert(audit_field, Value::Array(audit));
+        }
+    }
+}
+
+impl Detector {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::CreditCard => "credit_card",
+            Self::UsSsn => "us_ssn",
+            Self::UkNino => "uk_nino",
+            Self::Ipv4 => "ipv4",
+            Self::Ipv6 => "ipv6",
+        }
+    }
+
+    fn regex(self) -> &'static Regex {
+        static EMAIL: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b").unwrap()
+        });
+        static CREDIT_CARD: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap());
+        static US_SSN: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"\b(\d{3})-(\d{2})-(\d{4})\b").unwrap());
+        static UK_NINO: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?i)\b([A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z]) ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b")
+                .unwrap()
+        });
+        static IPV4: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r"\b(?:(?:25[0-5]|2[0-4]\d|1\d{2}|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d{2}|[1-9]?\d)\b",
+            )
+            .unwrap()
+        });
+        static IPV6: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?i)[0-9a-f]{0,4}(?::[0-9a-f]{0,4}){2,7}(?:(?:\.\d{1,3}){3})?").unwrap()
+        });
+
+        match self {
+            Self::Email => &EMAIL,
+            Self::CreditCard => &CREDIT_CARD,
+            Self::UsSsn => &US_SSN,
+            Self::UkNino => &UK_NINO,
+            Self::Ipv4 => &IPV4,
+            Self::Ipv6 => &IPV6,
+        }
+    }
+
+    /// Checks a regex match, ruling out look-alikes the regex can't.
+    fn validate(self, input: &str, start: usize, end: usize) -> bool {
+        let value = &input[start..end];
+        match self {
+            Self::CreditCard => luhn(value),
+            Self::UsSsn => {
+                let area = &value[0..3];
+                area != "000"
+                    && area != "666"
+                    && !area.starts_with('9')
+                    && &value[4..6] != "00"
+                    && &value[7..11] != "0000"
+            }
+            Self::UkNino => {
+                let prefix = value[0..2].to_ascii_uppercase();
+                !matches!(
+                    prefix.as_str(),
+                    "BG" | "GB" | "KN" | "NK" | "NT" | "TN" | "ZZ"
+                )
+            }
+            Self::Ipv6 => {
+                // The regex also matches parts of longer runs of hex digits
+                // and colons, such as MAC addresses.
+                let bordered = |c: Option<char>| {
+                    c.map_or(true, |c| !(c.is_ascii_hexdigit() || c == ':' || c == '.'))
+                };
+                bordered(input[..start].chars().next_back())
+                    && bordered(input[end..].chars().next())
+                    && Ipv6Addr::from_str(value).is_ok()
+            }
+            Self::Email | Self::Ipv4 => true,
+        }
+    }
+}
+
+/// Finds the sensitive values in `input`, as non-overlapping
+/// `(start, end, detector)` ranges ordered by position. Where matches of
+/// several detectors overlap, the detector listed first wins.
+fn find_matches(input: &str, detectors: &[Detector]) -> Vec<(usize, usize, Detector)> {
+    let mut matches: Vec<(usize, usize, Detector)> = Vec::new();
+    for &detector in detectors {
+        for found in detector.regex().find_iter(input) {
+            let (start, end) = (found.start(), found.end());
+            if start == end
+                || matches.iter().any(|&(s, e, _)| start < e && s < end)
+                || !detector.validate(input, start, end)
+            {
+                continue;
+            }
+            matches.push((start, end, detector));
+        }
+    }
+    matches.sort_unstable_by_key(|&(start, _, _)| start);
+    matches
+}
+
+fn luhn(value: &str) -> bool {
+    let digits = value
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|digit| u32::from(digit - b'0'))
+        .collect::<Vec<_>>();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let key = PKey::hmac(key)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(data)?;
+    signer.sign_to_vec()
+}
+
+/// Encrypts `value` with AES-256-GCM, into the URL-safe base64 encoding of
+/// the nonce, ciphertext and tag. Pseudonyms pass a nonce derived from the
+/// value, so that equal values map to equal pseudonyms.
+fn encrypt(keys: &Keys, value: &str, nonce: Option<&[u8]>) -> Result<String, ErrorStack> {
+    let mut token = vec![0; NONCE_LEN];
+    match nonce {
+        Some(nonce) => token.copy_from_slice(nonce),
+        None => rand_bytes(&mut token)?,
+    }
+
+    let mut tag = [0; TAG_LEN];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &keys.encryption,
+        Some(&token),
+        &[],
+        value.as_bytes(),
+        &mut tag,
+    )?;
+    token.extend_from_slice(&ciphertext);
+    token.extend_from_slice(&tag);
+    Ok(base64::encode_config(&token, base64::URL_SAFE_NO_PAD))
+}
+
+/// Recovers the original value of a token produced by the `encrypt` or
+/// `pseudonymize` actions.
+pub fn reveal(key: &str, token: &str) -> Option<String> {
+    let keys = Keys::derive(key).ok()?;
+    let token = base64::decode_config(token, base64::URL_SAFE_NO_PAD).ok()?;
+    if token.len() < NONCE_LEN + TAG_LEN {
+        return None;
+    }
+
+    let (nonce, rest) = token.split_at(NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+    let value = decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &keys.encryption,
+        Some(nonce),
+        &[],
+        ciphertext,
+        tag,
+    )
+    .ok()?;
+    String::from_utf8(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::test::transform_one;
+
+    fn config(extra: &str) -> AnonymizeConfig {
+        toml::from_str(extra).unwrap()
+    }
+
+    fn anonymize(config: &AnonymizeConfig, log: LogEvent) -> LogEvent {
+        let mut transform = Anonymize::new(config).unwrap();
+        transform_one(&mut transform, log.into())
+            .unwrap()
+            .into_log()
+    }
+
+    fn audit(log: &LogEvent) -> Vec<(String, String, i64)> {
+        match log.get("anonymized") {
+            Some(Value::Array(entries)) => entries
+                .iter()
+                .map(|entry| match entry {
+                    Value::Map(entry) => (
+                        entry["field"].to_string_lossy(),
+                        entry["detector"].to_string_lossy(),
+                        match entry["count"] {
+                            Value::Integer(count) => count,
+                            _ => panic!("count is not an integer"),
+                        },
+                    ),
+                    _ => panic!("audit entry is not a map"),
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AnonymizeConfig>();
+    }
+
+    #[test]
+    fn detects_sensitive_values() {
+        let cases = vec![
+            (
+                "mail jane.doe+x@mail.example.com now",
+                Some(Detector::Email),
+            ),
+            ("card 4111 1111 1111 1111 used", Some(Detector::CreditCard)),
+            ("card 4111-1111-1111-1112 used", None),
+            ("ssn 123-45-6789", Some(Detector::UsSsn)),
+            ("ssn 666-45-6789", None),
+            ("nino AB 12 34 56 C", Some(Detector::UkNino)),
+            ("nino GB123456A", None),
+            ("from 192.168.0.1:8080", Some(Detector::Ipv4)),
+            ("from 999.168.0.1", None),
+            ("from 2001:db8::ff00:42:8329 ok", Some(Detector::Ipv6)),
+            ("from ::1", Some(Detector::Ipv6)),
+            ("at 12:30:45", None),
+            ("mac 00:1a:2b:3c:4d:5e", None),
+        ];
+
+        for (input, expected) in cases {
+            let found = find_matches(input, &default_detectors())
+                .into_iter()
+                .map(|(_, _, detector)| detector)
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected.into_iter().collect::<Vec<_>>(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn redacts_all_string_fields() {
+        let mut log = LogEvent::from("user jane@example.com from 10.0.0.1");
+        log.insert("client.ip", "10.0.0.2");
+        log.insert("status", 200);
+
+        let log = anonymize(&config(""), log);
+
+        assert_eq!(log["message"], "user [REDACTED] from [REDACTED]".into());
+        assert_eq!(log["client.ip"], "[REDACTED]".into());
+        assert_eq!(log["status"], 200.into());
+        assert_eq!(
+            audit(&log),
+            vec![
+                ("client.ip".to_owned(), "ipv4".to_owned(), 1),
+                ("message".to_owned(), "email".to_owned(), 1),
+                ("message".to_owned(), "ipv4".to_owned(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_scans_configured_fields() {
+        let mut log = LogEvent::from("jane@example.com");
+        log.insert("user.email", "john@example.com");
+        log.insert("user.name", "John <john@example.com>");
+
+        let log = anonymize(
+            &config(
+                r#"
+                fields = ["user"]
+                detectors = ["email"]
+                audit_field = ""
+                "#,
+            ),
+            log,
+        );
+
+        assert_eq!(log["message"], "jane@example.com".into());
+        assert_eq!(log["user.email"], "[REDACTED]".into());
+        assert_eq!(log["user.name"], "John <[REDACTED]>".into());
+        assert!(log.get("anonymized").is_none());
+    }
+
+    #[test]
+    fn hashes_values() {
+        let log = anonymize(
+            &config(r#"action = "hash""#),
+            LogEvent::from("ssn 123-45-6789"),
+        );
+
+        assert_eq!(
+            log["message"],
+            format!("ssn {}", hex(&sha256(b"123-45-6789"))).into()
+        );
+    }
+
+    #[test]
+    fn pseudonymizes_values_reversibly() {
+        let config = config(
+            r#"
+            action = "pseudonymize"
+            key = "secret"
+            "#,
+        );
+
+        let mut log = LogEvent::from("jane@example.com");
+        log.insert("other", "jane@example.com");
+        let log = anonymize(&config, log);
+
+        let pseudonym = log["message"].to_string_lossy();
+        assert_ne!(pseudonym, "jane@example.com");
+        assert_eq!(log["other"].to_string_lossy(), pseudonym);
+        assert_eq!(
+            reveal("secret", &pseudonym).as_deref(),
+            Some("jane@example.com")
+        );
+        assert_eq!(reveal("wrong", &pseudonym), None);
+    }
+
+    #[test]
+    fn encrypts_values() {
+        let config = config(
+            r#"
+            action = "redact"
+            key = "secret"
+            [detector_actions]
+            credit_card = "encrypt"
+            "#,
+        );
+
+        let mut log = LogEvent::from("4111111111111111");
+        log.insert("other", "4111111111111111 jane@example.com");
+        let log = anonymize(&config, log);
+
+        let token = log["message"].to_string_lossy();
+        let other = log["other"].to_string_lossy();
+        let (other_token, email) = other.split_once(' ').unwrap();
+        assert_ne!(token, other_token);
+        assert_eq!(email, "[REDACTED]");
+        assert_eq!(
+            reveal("secret", &token).as_deref(),
+            Some("4111111111111111")
+        );
+        assert_eq!(
+            reveal("secret", other_token).as_deref(),
+            Some("4111111111111111")
+        );
+    }
+
+    #[test]
+    fn requires_key_for_reversible_actions() {
+        let error = Anonymize::new(&config(
+            r#"
+            [detector_actions]
+            email = "pseudonymize"
+            "#,
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "A `key` is required for the `Pseudonymize` action"
+        );
+
+        assert!(Anonymize::new(&config(r#"detectors = []"#)).is_err());
+    }
+}
@@ -4,14 +4,15 @@ use crate::{
         TransformDescription,
     },
     event::{Event, Value},
-    internal_events::DedupeEventDiscarded,
+    internal_events::{ComponentAllocatedBytes, DedupeEventDiscarded},
     transforms::{TaskTransform, Transform},
 };
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
-use std::{future::ready, pin::Pin};
+use std::{future::ready, mem, pin::Pin};
+use vector_core::ByteSizeOf;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -61,6 +62,8 @@ impl DedupeConfig {
 pub struct Dedupe {
     fields: FieldMatchConfig,
     cache: LruCache<CacheEntry, bool>,
+    /// The approximate number of bytes held by the cache entries.
+    allocated_bytes: usize,
 }
 
 inventory::submit! {
@@ -128,6 +131,23 @@ enum CacheEntry {
     Ignore(Vec<(String, TypeId, Bytes)>),
 }
 
+impl ByteSizeOf for CacheEntry {
+    fn allocated_bytes(&self) -> usize {
+        match self {
+            CacheEntry::Match(entry) => entry
+                .iter()
+                .map(|field| {
+                    mem::size_of_val(field) + field.as_ref().map_or(0, |(_, data)| data.len())
+                })
+                .sum(),
+            CacheEntry::Ignore(entry) => entry
+                .iter()
+                .map(|field| mem::size_of_val(field) + field.0.len() + field.2.len())
+                .sum(),
+        }
+    }
+}
+
 /// Assigns a unique number to each of the types supported by Event::Value.
 const fn type_id_for_value(val: &Value) -> TypeId {
     match val {
@@ -149,15 +169,29 @@ impl Dedupe {
         Self {
             fields,
             cache: LruCache::new(num_entries),
+            allocated_bytes: 0,
         }
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
         let cache_entry = build_cache_entry(&event, &self.fields);
+        let byte_size = cache_entry.size_of();
+
+        // Evict the least recently used entry ourselves to account for its size.
+        if self.cache.len() == self.cache.cap() && !self.cache.contains(&cache_entry) {
+            if let Some((evicted, _)) = self.cache.pop_lru() {
+                self.allocated_bytes -= evicted.size_of();
+            }
+        }
+
         if self.cache.put(cache_entry, true).is_some() {
             emit!(&DedupeEventDiscarded { event });
             None
         } else {
+            self.allocated_bytes += byte_size;
+            emit!(&ComponentAllocatedBytes {
+                byte_size: self.allocated_bytes,
+            });
             Some(event)
         }
     }
@@ -236,6 +270,28 @@ mod tests {
         })
     }
 
+    #[test]
+    fn tracks_allocated_bytes() {
+        let mut transform = make_match_transform(1, vec!["matched".into()]);
+        assert_eq!(transform.allocated_bytes, 0);
+
+        let mut event1 = Event::from("message");
+        event1.as_mut_log().insert("matched", "some value");
+        assert!(transform.transform_one(event1.clone()).is_some());
+        let allocated_bytes = transform.allocated_bytes;
+        assert!(allocated_bytes > 0);
+
+        // Discarded duplicates don't take more room.
+        assert!(transform.transform_one(event1).is_none());
+        assert_eq!(transform.allocated_bytes, allocated_bytes);
+
+        // The entry of the first event is evicted to make room for this one.
+        let mut event2 = Event::from("message");
+        event2.as_mut_log().insert("matched", "more value");
+        assert!(transform.transform_one(event2).is_some());
+        assert_eq!(transform.allocated_bytes, allocated_bytes);
+    }
+
     #[test]
     fn dedupe_match_basic() {
         let transform = make_match_transform(5, vec!["matched".into()]);
@@ -0,0 +1,245 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{Event, Value},
+    internal_events::{DeriveSeverityFieldMissing, DeriveSeverityUnknown},
+    transforms::{FunctionTransform, Transform},
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use shared::severity::{NumericScale, Severity, SeverityMapper};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DeriveSeverityConfig {
+    #[serde(default = "default_fields")]
+    pub fields: Vec<String>,
+    #[serde(default = "default_target_field")]
+    pub target_field: String,
+    #[serde(default = "default_original_field")]
+    pub original_field: String,
+    pub number_field: Option<String>,
+    #[serde(default)]
+    pub mapping: IndexMap<String, Severity>,
+    #[serde(default)]
+    pub scale: NumericScale,
+    pub fallback: Option<Severity>,
+}
+
+fn default_fields() -> Vec<String> {
+    vec!["severity".into(), "level".into()]
+}
+
+fn default_target_field() -> String {
+    "severity".into()
+}
+
+fn default_original_field() -> String {
+    "severity_original".into()
+}
+
+inventory::submit! {
+    TransformDescription::new::<DeriveSeverityConfig>("derive_severity")
+}
+
+impl GenerateConfig for DeriveSeverityConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            fields: default_fields(),
+            target_field: default_target_field(),
+            original_field: default_original_field(),
+            number_field: None,
+            mapping: IndexMap::new(),
+            scale: NumericScale::default(),
+            fallback: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "derive_severity")]
+impl TransformConfig for DeriveSeverityConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.fields.is_empty() {
+            return Err("`fields` must contain at least one field".into());
+        }
+        Ok(Transform::function(DeriveSeverity::new(self.clone())))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "derive_severity"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DeriveSeverity {
+    fields: Vec<String>,
+    target_field: String,
+    original_field: String,
+    number_field: Option<String>,
+    mapper: SeverityMapper,
+    fallback: Option<Severity>,
+}
+
+impl DeriveSeverity {
+    pub fn new(config: DeriveSeverityConfig) -> Self {
+        Self {
+            fields: config.fields,
+            target_field: config.target_field,
+            original_field: config.original_field,
+            number_field: config.number_field,
+            mapper: SeverityMapper::new(config.mapping, config.scale),
+            fallback: config.fallback,
+        }
+    }
+
+    fn derive(&self, value: &Value) -> Option<Severity> {
+        match value {
+            Value::Integer(number) => self.mapper.map_integer(*number),
+            Value::Bytes(bytes) => self.mapper.map_str(&String::from_utf8_lossy(bytes)),
+            _ => None,
+        }
+    }
+}
+
+impl FunctionTransform for DeriveSeverity {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+
+        let original = self.fields.iter().find_map(|field| match log.get(field) {
+            None | Some(Value::Null) => None,
+            Some(value) => Some((field, value.clone())),
+        });
+
+        let severity = match original {
+            Some((field, value)) => {
+                let severity = self.derive(&value);
+                if severity.is_none() {
+                    emit!(&DeriveSeverityUnknown {
+                        field,
+                        value: &value
+                    });
+                }
+                log.insert(self.original_field.as_str(), value);
+                severity
+            }
+            None => {
+                emit!(&DeriveSeverityFieldMissing {
+                    fields: &self.fields
+                });
+                None
+            }
+        }
+        .or(self.fallback);
+
+        if let Some(severity) = severity {
+            log.insert(self.target_field.as_str(), severity.as_str());
+            if let Some(number_field) = &self.number_field {
+                log.insert(number_field.as_str(), severity.number() as i64);
+            }
+        }
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::LogEvent, transforms::test::transform_one};
+
+    fn derive_severity(config: &str, log: LogEvent) -> LogEvent {
+        let config = toml::from_str::<DeriveSeverityConfig>(config).unwrap();
+        let mut transform = DeriveSeverity::new(config);
+        transform_one(&mut transform, log.into())
+            .unwrap()
+            .into_log()
+    }
+
+    fn event(fields: &[(&str, Value)]) -> LogEvent {
+        let mut log = LogEvent::from("message");
+        for (field, value) in fields {
+            log.insert(*field, value.clone());
+        }
+        log
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<DeriveSeverityConfig>();
+    }
+
+    #[test]
+    fn derives_from_first_present_field() {
+        let log = derive_severity(
+            "number_field = \"severity_number\"",
+            event(&[("level", "WARN".into())]),
+        );
+        assert_eq!(log["severity"], "warning".into());
+        assert_eq!(log["severity_number"], 4.into());
+        assert_eq!(log["severity_original"], "WARN".into());
+
+        let log = derive_severity(
+            "",
+            event(&[("severity", "crit".into()), ("level", "debug".into())]),
+        );
+        assert_eq!(log["severity"], "critical".into());
+        assert_eq!(log["severity_original"], "crit".into());
+    }
+
+    #[test]
+    fn derives_numbers_by_scale() {
+        let log = derive_severity(
+            "fields = [\"levelno\"]\nscale = \"python\"",
+            event(&[("levelno", 40.into())]),
+        );
+        assert_eq!(log["severity"], "error".into());
+        assert_eq!(log["severity_original"], 40.into());
+
+        let log = derive_severity(
+            "fields = [\"EventLevel\"]\nscale = \"windows\"",
+            event(&[("EventLevel", "1".into())]),
+        );
+        assert_eq!(log["severity"], "critical".into());
+    }
+
+    #[test]
+    fn prefers_custom_mapping() {
+        let log = derive_severity(
+            "mapping.F = \"emergency\"\nmapping.\"6\" = \"debug\"",
+            event(&[("level", "f".into())]),
+        );
+        assert_eq!(log["severity"], "emergency".into());
+
+        let log = derive_severity("mapping.\"6\" = \"debug\"", event(&[("level", 6.into())]));
+        assert_eq!(log["severity"], "debug".into());
+    }
+
+    #[test]
+    fn rejects_non_canonical_mapping() {
+        assert!(toml::from_str::<DeriveSeverityConfig>("mapping.F = \"fatal\"").is_err());
+    }
+
+    #[test]
+    fn falls_back_on_unknown_severities() {
+        let log = derive_severity("", event(&[("level", "loud".into())]));
+        assert!(log.get("severity").is_none());
+        assert_eq!(log["severity_original"], "loud".into());
+
+        let log = derive_severity("fallback = \"notice\"", event(&[("level", "loud".into())]));
+        assert_eq!(log["severity"], "notice".into());
+        assert_eq!(log["severity_original"], "loud".into());
+
+        let log = derive_severity("fallback = \"info\"", event(&[]));
+        assert_eq!(log["severity"], "info".into());
+        assert!(log.get("severity_original").is_none());
+    }
+}
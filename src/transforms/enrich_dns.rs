@@ -0,0 +1,443 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        EnrichDnsCacheHit, EnrichDnsCacheMiss, EnrichDnsInvalidIpAddress, EnrichDnsLookupFailed,
+        EnrichDnsLookupTimedOut,
+    },
+    transforms::{TaskTransform, Transform},
+};
+use futures::{Stream, StreamExt};
+use indexmap::IndexMap;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    net::IpAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    sync::Semaphore,
+    task::spawn_blocking,
+    time::{timeout, Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EnrichDnsConfig {
+    /// The fields to resolve, and the fields to write their resolved value to.
+    pub fields: IndexMap<String, String>,
+    #[serde(default)]
+    pub mode: LookupMode,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_max_concurrent_lookups")]
+    pub max_concurrent_lookups: usize,
+    #[serde(default)]
+    pub cache: CacheConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupMode {
+    /// Resolve IP addresses to host names, with PTR records.
+    Reverse,
+    /// Resolve host names to IP addresses.
+    Forward,
+}
+
+impl Default for LookupMode {
+    fn default() -> Self {
+        Self::Reverse
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// Leave the target field unset.
+    Skip,
+    /// Set the target field to the value that couldn't be resolved.
+    UseSource,
+}
+
+impl Default for OnFailure {
+    fn default() -> Self {
+        Self::Skip
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+    #[serde(default = "default_cache_ttl_secs")]
+    pub ttl_secs: u64,
+    #[serde(default = "default_cache_negative_ttl_secs")]
+    pub negative_ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: default_cache_max_entries(),
+            ttl_secs: default_cache_ttl_secs(),
+            negative_ttl_secs: default_cache_negative_ttl_secs(),
+        }
+    }
+}
+
+const fn default_timeout_ms() -> u64 {
+    1000
+}
+
+const fn default_max_concurrent_lookups() -> usize {
+    32
+}
+
+const fn default_cache_max_entries() -> usize {
+    10_000
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+const fn default_cache_negative_ttl_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    TransformDescription::new::<EnrichDnsConfig>("enrich_dns")
+}
+
+impl GenerateConfig for EnrichDnsConfig {
+    fn generate_config() -> toml::Value {
+        let mut fields = IndexMap::new();
+        fields.insert("src_ip".to_owned(), "src_host".to_owned());
+        toml::Value::try_from(Self {
+            fields,
+            mode: LookupMode::default(),
+            on_failure: OnFailure::default(),
+            timeout_ms: default_timeout_ms(),
+            max_concurrent_lookups: default_max_concurrent_lookups(),
+            cache: CacheConfig::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "enrich_dns")]
+impl TransformConfig for EnrichDnsConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.fields.is_empty() {
+            return Err("At least one field to resolve must be set in `fields`.".into());
+        }
+        if self.max_concurrent_lookups == 0 {
+            return Err("`max_concurrent_lookups` must be greater than zero.".into());
+        }
+        if self.cache.max_entries == 0 {
+            return Err("`cache.max_entries` must be greater than zero.".into());
+        }
+
+        Ok(Transform::task(EnrichDns::new(self)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "enrich_dns"
+    }
+}
+
+/// A resolved value, or `None` for lookups that failed, with when to look it
+/// up again.
+struct CachedLookup {
+    value: Option<Value>,
+    expires_at: Instant,
+}
+
+struct Resolver {
+    mode: LookupMode,
+    timeout: Duration,
+    ttl: Duration,
+    negative_ttl: Duration,
+    /// Lookups block a thread, so their number is bounded.
+    permits: Arc<Semaphore>,
+    cache: Mutex<LruCache<String, CachedLookup>>,
+}
+
+impl Resolver {
+    async fn resolve(&self, name: &str) -> Option<Value> {
+        let cached = self
+            .cache
+            .lock()
+            .unwrap()
+            .get(name)
+            .filter(|cached| cached.expires_at > Instant::now())
+            .map(|cached| cached.value.clone());
+        if let Some(value) = cached {
+            emit!(&EnrichDnsCacheHit);
+            return value;
+        }
+        emit!(&EnrichDnsCacheMiss);
+
+        let value = self.lookup(name).await;
+        let ttl = if value.is_some() {
+            self.ttl
+        } else {
+            self.negative_ttl
+        };
+        self.cache.lock().unwrap().put(
+            name.to_owned(),
+            CachedLookup {
+                value: value.clone(),
+                expires_at: Instant::now() + ttl,
+            },
+        );
+        value
+    }
+
+    async fn lookup(&self, name: &str) -> Option<Value> {
+        let mode = self.mode;
+        let owned_name = name.to_owned();
+        let permits = Arc::clone(&self.permits);
+        let lookup = async move {
+            // The permit is released once the lookup returns, even if it
+            // outlives its timeout.
+            let permit = permits.acquire_owned().await.ok()?;
+            spawn_blocking(move || {
+                let _permit = permit;
+                mode.lookup(&owned_name)
+            })
+            .await
+            .ok()
+        };
+
+        match timeout(self.timeout, lookup).await {
+            Ok(Some(Ok(value))) => Some(value),
+            Ok(Some(Err(error))) => {
+                emit!(&EnrichDnsLookupFailed { name, error });
+                None
+            }
+            Ok(None) => None,
+            Err(_) => {
+                emit!(&EnrichDnsLookupTimedOut {
+                    name,
+                    timeout: self.timeout,
+                });
+                None
+            }
+        }
+    }
+}
+
+impl LookupMode {
+    fn lookup(self, name: &str) -> io::Result<Value> {
+        match self {
+            Self::Reverse => {
+                let address: IpAddr = name
+                    .parse()
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+                let host = dns_lookup::lookup_addr(&address)?;
+                // Addresses without a PTR record resolve to themselves.
+                if host.parse::<IpAddr>().ok() == Some(address) {
+                    Err(io::Error::new(io::ErrorKind::NotFound, "no PTR record"))
+                } else {
+                    Ok(host.into())
+                }
+            }
+            Self::Forward => {
+                let mut addresses = dns_lookup::lookup_host(name)?;
+                // There's an address per socket type.
+                let mut seen = Vec::with_capacity(addresses.len());
+                addresses.retain(|address| {
+                    let new = !seen.contains(address);
+                    seen.push(*address);
+                    new
+                });
+                Ok(addresses
+                    .into_iter()
+                    .map(|address| Value::from(address.to_string()))
+                    .collect::<Vec<_>>()
+                    .into())
+            }
+        }
+    }
+}
+
+pub struct EnrichDns {
+    fields: Vec<(String, String)>,
+    on_failure: OnFailure,
+    max_concurrent_lookups: usize,
+    resolver: Resolver,
+}
+
+impl EnrichDns {
+    pub fn new(config: &EnrichDnsConfig) -> Self {
+        Self {
+            fields: config
+                .fields
+                .iter()
+                .map(|(source, target)| (source.clone(), target.clone()))
+                .collect(),
+            on_failure: config.on_failure,
+            max_concurrent_lookups: config.max_concurrent_lookups,
+            resolver: Resolver {
+                mode: config.mode,
+                timeout: Duration::from_millis(config.timeout_ms),
+                ttl: Duration::from_secs(config.cache.ttl_secs),
+                negative_ttl: Duration::from_secs(config.cache.negative_ttl_secs),
+                permits: Arc::new(Semaphore::new(config.max_concurrent_lookups)),
+                cache: Mutex::new(LruCache::new(config.cache.max_entries)),
+            },
+        }
+    }
+
+    async fn enrich(&self, mut event: Event) -> Event {
+        let log = event.as_mut_log();
+        for (source, target) in &self.fields {
+            let name = match log.get(source.as_str()) {
+                Some(value) => value.to_string_lossy(),
+                None => continue,
+            };
+
+            if self.resolver.mode == LookupMode::Reverse && name.parse::<IpAddr>().is_err() {
+                emit!(&EnrichDnsInvalidIpAddress {
+                    field: source,
+                    address: &name,
+                });
+                self.fallback(log, target, name);
+                continue;
+            }
+
+            match self.resolver.resolve(&name).await {
+                Some(value) => {
+                    log.insert(target.as_str(), value);
+                }
+                None => self.fallback(log, target, name),
+            }
+        }
+        event
+    }
+
+    fn fallback(&self, log: &mut LogEvent, target: &str, name: String) {
+        match self.on_failure {
+            OnFailure::Skip => (),
+            OnFailure::UseSource => {
+                log.insert(target, name);
+            }
+        }
+    }
+}
+
+impl TaskTransform for EnrichDns {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let concurrency = self.max_concurrent_lookups;
+        let inner: Arc<Self> = Arc::from(self);
+        // Lookups of several events run at once, so that a slow lookup only
+        // holds back the events after it for up to its timeout, while events
+        // keep their order.
+        Box::pin(
+            task.map(move |event| {
+                let inner = Arc::clone(&inner);
+                async move { inner.enrich(event).await }
+            })
+            .buffered(concurrency),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: LookupMode, on_failure: OnFailure) -> EnrichDnsConfig {
+        let mut fields = IndexMap::new();
+        fields.insert("source".to_owned(), "target".to_owned());
+        EnrichDnsConfig {
+            fields,
+            mode,
+            on_failure,
+            timeout_ms: default_timeout_ms(),
+            max_concurrent_lookups: default_max_concurrent_lookups(),
+            cache: CacheConfig::default(),
+        }
+    }
+
+    fn event(source: &str) -> Event {
+        let mut log = LogEvent::from("message");
+        log.insert("source", source);
+        log.into()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EnrichDnsConfig>();
+    }
+
+    #[tokio::test]
+    async fn resolves_from_cache() {
+        let transform = EnrichDns::new(&config(LookupMode::Reverse, OnFailure::Skip));
+        transform.resolver.cache.lock().unwrap().put(
+            "192.0.2.1".to_owned(),
+            CachedLookup {
+                value: Some("gateway.example.org".into()),
+                expires_at: Instant::now() + Duration::from_secs(60),
+            },
+        );
+        transform.resolver.cache.lock().unwrap().put(
+            "192.0.2.2".to_owned(),
+            CachedLookup {
+                value: None,
+                expires_at: Instant::now() + Duration::from_secs(60),
+            },
+        );
+
+        let log = transform.enrich(event("192.0.2.1")).await.into_log();
+        assert_eq!(log["target"], "gateway.example.org".into());
+
+        let log = transform.enrich(event("192.0.2.2")).await.into_log();
+        assert!(!log.contains("target"));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_source() {
+        let transform = EnrichDns::new(&config(LookupMode::Reverse, OnFailure::UseSource));
+
+        let log = transform.enrich(event("not an address")).await.into_log();
+        assert_eq!(log["target"], "not an address".into());
+
+        let log = transform
+            .enrich(LogEvent::from("no source").into())
+            .await
+            .into_log();
+        assert!(!log.contains("target"));
+    }
+
+    #[tokio::test]
+    async fn resolves_forward() {
+        let transform = EnrichDns::new(&config(LookupMode::Forward, OnFailure::Skip));
+
+        let log = transform.enrich(event("localhost")).await.into_log();
+        let addresses = log["target"].as_array();
+        assert!(addresses.contains(&"127.0.0.1".into()) || addresses.contains(&"::1".into()));
+    }
+}
@@ -0,0 +1,460 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{Event, Value},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FlattenConfig {
+    #[serde(default)]
+    mode: Mode,
+    #[serde(default = "default_separator")]
+    separator: String,
+    /// The number of levels of nesting collapsed into a single key, or split
+    /// out of one. Unlimited if unset.
+    #[serde(default)]
+    max_depth: Option<usize>,
+    #[serde(default)]
+    arrays: Arrays,
+    #[serde(default = "default_array_separator")]
+    array_separator: String,
+}
+
+fn default_separator() -> String {
+    ".".to_owned()
+}
+
+fn default_array_separator() -> String {
+    ",".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Collapse nested objects into keys joined by the separator.
+    Flatten,
+    /// Split keys on the separator into nested objects.
+    Unflatten,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::Flatten
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Arrays {
+    /// Leave arrays as they are.
+    Keep,
+    /// Treat arrays as objects keyed by index.
+    Index,
+    /// Join arrays of scalars into a string, when flattening.
+    Join,
+}
+
+impl Default for Arrays {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`separator` must not be empty"))]
+    EmptySeparator,
+}
+
+inventory::submit! {
+    TransformDescription::new::<FlattenConfig>("flatten")
+}
+
+impl GenerateConfig for FlattenConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            mode: Mode::default(),
+            separator: default_separator(),
+            max_depth: None,
+            arrays: Arrays::default(),
+            array_separator: default_array_separator(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "flatten")]
+impl TransformConfig for FlattenConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.separator.is_empty() {
+            return Err(BuildError::EmptySeparator.into());
+        }
+        Ok(Transform::function(Flatten {
+            config: self.clone(),
+        }))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "flatten"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Flatten {
+    config: FlattenConfig,
+}
+
+impl Flatten {
+    fn flatten(&self, map: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+        let mut output = BTreeMap::new();
+        for (key, value) in map {
+            self.flatten_into(&mut output, key, value, 0);
+        }
+        output
+    }
+
+    fn flatten_into(
+        &self,
+        output: &mut BTreeMap<String, Value>,
+        key: String,
+        value: Value,
+        depth: usize,
+    ) {
+        let can_descend = self.config.max_depth.map_or(true, |max| depth < max);
+        match value {
+            Value::Map(map) if can_descend && !map.is_empty() => {
+                for (child, value) in map {
+                    let key = self.join(&key, &child);
+                    self.flatten_into(output, key, value, depth + 1);
+                }
+            }
+            Value::Array(array) if can_descend && !array.is_empty() => match self.config.arrays {
+                Arrays::Keep => {
+                    output.insert(key, Value::Array(array));
+                }
+                Arrays::Index => {
+                    for (index, value) in array.into_iter().enumerate() {
+                        let key = self.join(&key, &index.to_string());
+                        self.flatten_into(output, key, value, depth + 1);
+                    }
+                }
+                Arrays::Join => {
+                    output.insert(key, self.join_array(array));
+                }
+            },
+            value => {
+                output.insert(key, value);
+            }
+        }
+    }
+
+    fn join(&self, parent: &str, child: &str) -> String {
+        format!("{}{}{}", parent, self.config.separator, child)
+    }
+
+    /// Joins an array of scalars into a string. Arrays holding objects or
+    /// arrays are left as they are, since they can't be joined unambiguously.
+    fn join_array(&self, array: Vec<Value>) -> Value {
+        if array
+            .iter()
+            .any(|value| matches!(value, Value::Map(_) | Value::Array(_)))
+        {
+            return Value::Array(array);
+        }
+        array
+            .iter()
+            .map(Value::to_string_lossy)
+            .collect::<Vec<_>>()
+            .join(&self.config.array_separator)
+            .into()
+    }
+
+    fn unflatten(&self, map: BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+        let mut output = BTreeMap::new();
+        for (key, value) in map {
+            let value = match value {
+                Value::Map(map) => Value::Map(self.unflatten(map)),
+                value => value,
+            };
+            let segments = match self.config.max_depth {
+                Some(max) => key.splitn(max + 1, &self.config.separator).collect(),
+                None => key.split(&self.config.separator).collect::<Vec<_>>(),
+            };
+            if let Err(value) = insert_nested(&mut output, &segments, value) {
+                // The key conflicts with a value already at one of its
+                // parents, so it's kept as it is.
+                output.insert(key, value);
+            }
+        }
+
+        if self.config.arrays == Arrays::Index {
+            output
+                .into_iter()
+                .map(|(key, value)| (key, indexes_to_arrays(value)))
+                .collect()
+        } else {
+            output
+        }
+    }
+}
+
+/// Inserts the value at the path, creating objects along the way. Gives the
+/// value back if something other than an object is in the way.
+fn insert_nested(
+    map: &mut BTreeMap<String, Value>,
+    segments: &[&str],
+    value: Value,
+) -> Result<(), Value> {
+    match segments {
+        [] => Err(value),
+        [last] => match map.get_mut(*last) {
+            Some(Value::Map(existing)) => match value {
+                Value::Map(value) => {
+                    // Merge into the object already there, letting the
+                    // later value win where both hold the same key.
+                    for (key, value) in value {
+                        if let Err(value) = insert_nested(existing, &[key.as_str()], value) {
+                            existing.insert(key, value);
+                        }
+                    }
+                    Ok(())
+                }
+                value => Err(value),
+            },
+            Some(_) => Err(value),
+            None => {
+                map.insert((*last).to_owned(), value);
+                Ok(())
+            }
+        },
+        [first, rest @ ..] => match map
+            .entry((*first).to_owned())
+            .or_insert_with(|| Value::Map(BTreeMap::new()))
+        {
+            Value::Map(child) => insert_nested(child, rest, value),
+            _ => Err(value),
+        },
+    }
+}
+
+/// Converts objects keyed by every index from `0` up into arrays.
+fn indexes_to_arrays(value: Value) -> Value {
+    match value {
+        Value::Map(map) => {
+            let is_array = !map.is_empty()
+                && map.keys().all(|key| {
+                    key.parse::<usize>()
+                        .map_or(false, |index| index < map.len())
+                })
+                && map.keys().all(|key| !key.starts_with('0') || key == "0");
+            let values = map
+                .into_iter()
+                .map(|(key, value)| (key, indexes_to_arrays(value)));
+            if is_array {
+                let mut array = values
+                    .map(|(key, value)| (key.parse::<usize>().unwrap(), value))
+                    .collect::<Vec<_>>();
+                array.sort_by_key(|(index, _)| *index);
+                Value::Array(array.into_iter().map(|(_, value)| value).collect())
+            } else {
+                Value::Map(values.collect())
+            }
+        }
+        Value::Array(array) => Value::Array(array.into_iter().map(indexes_to_arrays).collect()),
+        value => value,
+    }
+}
+
+impl FunctionTransform for Flatten {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let log = event.as_mut_log();
+        let fields = std::mem::take(log.as_map_mut());
+        *log.as_map_mut() = match self.config.mode {
+            Mode::Flatten => self.flatten(fields),
+            Mode::Unflatten => self.unflatten(fields),
+        };
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+    use serde_json::json;
+
+    fn transform(config: &str, input: serde_json::Value) -> serde_json::Value {
+        let config = toml::from_str::<FlattenConfig>(config).unwrap();
+        let mut transform = Flatten { config };
+        let map = match Value::from(input) {
+            Value::Map(map) => map,
+            _ => panic!("input must be an object"),
+        };
+        let event = Event::from(LogEvent::from(map));
+        let mut output = Vec::new();
+        transform.transform(&mut output, event);
+        assert_eq!(output.len(), 1);
+        serde_json::to_value(output.remove(0).as_log().as_map()).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<FlattenConfig>();
+    }
+
+    #[test]
+    fn flattens_objects() {
+        let input = json!({
+            "message": "hello",
+            "http": { "request": { "method": "GET", "headers": {} }, "status": 200 },
+            "tags": ["a", "b"],
+        });
+        assert_eq!(
+            transform("", input.clone()),
+            json!({
+                "message": "hello",
+                "http.request.method": "GET",
+                "http.request.headers": {},
+                "http.status": 200,
+                "tags": ["a", "b"],
+            })
+        );
+        assert_eq!(
+            transform(r#"separator = "_""#, input),
+            json!({
+                "message": "hello",
+                "http_request_method": "GET",
+                "http_request_headers": {},
+                "http_status": 200,
+                "tags": ["a", "b"],
+            })
+        );
+    }
+
+    #[test]
+    fn flattens_up_to_max_depth() {
+        let input = json!({ "a": { "b": { "c": { "d": 1 } } }, "e": 2 });
+        assert_eq!(
+            transform("max_depth = 1", input.clone()),
+            json!({ "a.b": { "c": { "d": 1 } }, "e": 2 })
+        );
+        assert_eq!(transform("max_depth = 0", input.clone()), input);
+    }
+
+    #[test]
+    fn flattens_arrays() {
+        let input = json!({ "tags": ["a", 1, true], "items": [{ "id": 1 }, { "id": 2 }] });
+        assert_eq!(
+            transform(r#"arrays = "index""#, input.clone()),
+            json!({
+                "tags.0": "a",
+                "tags.1": 1,
+                "tags.2": true,
+                "items.0.id": 1,
+                "items.1.id": 2,
+            })
+        );
+        assert_eq!(
+            transform(
+                r#"
+                arrays = "join"
+                array_separator = "|"
+                "#,
+                input
+            ),
+            json!({ "tags": "a|1|true", "items": [{ "id": 1 }, { "id": 2 }] })
+        );
+    }
+
+    #[test]
+    fn unflattens_keys() {
+        let input = json!({
+            "message": "hello",
+            "http.request.method": "GET",
+            "http.status": 200,
+            "http": { "version": "1.1" },
+            "nested": { "a.b": 1 },
+        });
+        assert_eq!(
+            transform(r#"mode = "unflatten""#, input),
+            json!({
+                "message": "hello",
+                "http": { "request": { "method": "GET" }, "status": 200, "version": "1.1" },
+                "nested": { "a": { "b": 1 } },
+            })
+        );
+    }
+
+    #[test]
+    fn unflattens_up_to_max_depth() {
+        let input = json!({ "a_b_c_d": 1 });
+        assert_eq!(
+            transform(
+                r#"
+                mode = "unflatten"
+                separator = "_"
+                max_depth = 2
+                "#,
+                input
+            ),
+            json!({ "a": { "b": { "c_d": 1 } } })
+        );
+    }
+
+    #[test]
+    fn unflatten_keeps_conflicting_keys() {
+        let input = json!({ "a": 1, "a.b": 2 });
+        assert_eq!(transform(r#"mode = "unflatten""#, input.clone()), input);
+    }
+
+    #[test]
+    fn unflattens_indexes_into_arrays() {
+        let input = json!({
+            "tags.0": "a",
+            "tags.1": "b",
+            "items.0.id": 1,
+            "items.1.id": 2,
+            "sparse.0": 1,
+            "sparse.2": 2,
+        });
+        let config = r#"
+            mode = "unflatten"
+            arrays = "index"
+        "#;
+        assert_eq!(
+            transform(config, input),
+            json!({
+                "tags": ["a", "b"],
+                "items": [{ "id": 1 }, { "id": 2 }],
+                "sparse": { "0": 1, "2": 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let input = json!({
+            "http": { "request": { "method": "GET" }, "status": 200 },
+            "items": [{ "id": 1 }, { "id": 2 }],
+        });
+        let flattened = transform(r#"arrays = "index""#, input.clone());
+        let config = r#"
+            mode = "unflatten"
+            arrays = "index"
+        "#;
+        assert_eq!(transform(config, flattened), input);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod v1;
 pub mod v2;
+pub mod v3;
 
 use crate::{
     config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
@@ -35,11 +36,26 @@ pub struct LuaConfigV2 {
     config: v2::LuaConfig,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum V3 {
+    #[serde(rename = "3")]
+    V3,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LuaConfigV3 {
+    version: V3,
+    #[serde(flatten)]
+    config: v3::LuaConfig,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum LuaConfig {
     V1(LuaConfigV1),
     V2(LuaConfigV2),
+    V3(LuaConfigV3),
 }
 
 inventory::submit! {
@@ -63,6 +79,7 @@ impl TransformConfig for LuaConfig {
         match self {
             LuaConfig::V1(v1) => v1.config.build(),
             LuaConfig::V2(v2) => v2.config.build(),
+            LuaConfig::V3(v3) => v3.config.build(),
         }
     }
 
@@ -70,6 +87,7 @@ impl TransformConfig for LuaConfig {
         match self {
             LuaConfig::V1(v1) => v1.config.input_type(),
             LuaConfig::V2(v2) => v2.config.input_type(),
+            LuaConfig::V3(v3) => v3.config.input_type(),
         }
     }
 
@@ -77,6 +95,14 @@ impl TransformConfig for LuaConfig {
         match self {
             LuaConfig::V1(v1) => v1.config.output_type(),
             LuaConfig::V2(v2) => v2.config.output_type(),
+            LuaConfig::V3(v3) => v3.config.output_type(),
+        }
+    }
+
+    fn named_outputs(&self) -> Vec<String> {
+        match self {
+            LuaConfig::V3(v3) => v3.config.named_outputs(),
+            _ => Vec::new(),
         }
     }
 
@@ -84,6 +110,7 @@ impl TransformConfig for LuaConfig {
         match self {
             LuaConfig::V1(v1) => v1.config.transform_type(),
             LuaConfig::V2(v2) => v2.config.transform_type(),
+            LuaConfig::V3(v3) => v3.config.transform_type(),
         }
     }
 }
@@ -50,7 +50,7 @@ pub struct LuaConfig {
     source: Option<String>,
 }
 
-fn default_config_paths() -> Vec<PathBuf> {
+pub(super) fn default_config_paths() -> Vec<PathBuf> {
     match CONFIG_PATHS.lock().ok() {
         Some(config_paths) => config_paths
             .clone()
@@ -110,7 +110,7 @@ impl LuaConfig {
 //
 // This constant is larger than 1 because calling GC is an expensive operation, so doing it
 // after each transform would have significant footprint on the performance.
-const GC_INTERVAL: usize = 16;
+pub(super) const GC_INTERVAL: usize = 16;
 
 pub struct Lua {
     lua: mlua::Lua,
@@ -122,7 +122,10 @@ pub struct Lua {
 }
 
 // Helper to create `RegistryKey` from Lua function code
-fn make_registry_value(lua: &mlua::Lua, source: &str) -> mlua::Result<mlua::RegistryKey> {
+pub(super) fn make_registry_value(
+    lua: &mlua::Lua,
+    source: &str,
+) -> mlua::Result<mlua::RegistryKey> {
     lua.load(source)
         .eval::<mlua::Function>()
         .and_then(|f| lua.create_registry_value(f))
@@ -0,0 +1,624 @@
+//! The third version of the Lua transform API. Beyond what the second one
+//! offers, events can be emitted to named outputs, values can be kept in a
+//! `state` store whose size is bounded, and timer handlers run as coroutines,
+//! which can yield to be resumed on the next tick of their timer.
+
+use super::v2::{default_config_paths, make_registry_value, GC_INTERVAL};
+use crate::{
+    config::DataType,
+    event::{Event, Value},
+    internal_events::{LuaBuildError, LuaGcTriggered, LuaStateLimitReached, LuaStateUpdated},
+    transforms::{RoutedEvent, Transform},
+};
+use mlua::{FromLua, ToLua};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use vector_core::{
+    transform::runtime_transform::{MultiOutputRuntimeTransform, Timer},
+    ByteSizeOf,
+};
+
+#[derive(Debug, Snafu)]
+pub enum BuildError {
+    #[snafu(display("Invalid output name {:?}", name))]
+    InvalidOutputName { name: String },
+    #[snafu(display("Output {:?} is declared more than once", name))]
+    DuplicateOutput { name: String },
+    #[snafu(display("Cannot evaluate Lua code in \"source\": {}", source))]
+    InvalidSource { source: mlua::Error },
+
+    #[snafu(display("Cannot evaluate Lua code defining \"hooks.init\": {}", source))]
+    InvalidHooksInit { source: mlua::Error },
+    #[snafu(display("Cannot evaluate Lua code defining \"hooks.process\": {}", source))]
+    InvalidHooksProcess { source: mlua::Error },
+    #[snafu(display("Cannot evaluate Lua code defining \"hooks.shutdown\": {}", source))]
+    InvalidHooksShutdown { source: mlua::Error },
+    #[snafu(display("Cannot evaluate Lua code defining timer handler: {}", source))]
+    InvalidTimerHandler { source: mlua::Error },
+
+    #[snafu(display("Runtime error in \"hooks.init\" function: {}", source))]
+    RuntimeErrorHooksInit { source: mlua::Error },
+    #[snafu(display("Runtime error in \"hooks.process\" function: {}", source))]
+    RuntimeErrorHooksProcess { source: mlua::Error },
+    #[snafu(display("Runtime error in \"hooks.shutdown\" function: {}", source))]
+    RuntimeErrorHooksShutdown { source: mlua::Error },
+    #[snafu(display("Runtime error in timer handler: {}", source))]
+    RuntimeErrorTimerHandler { source: mlua::Error },
+
+    #[snafu(display("Cannot call GC in Lua runtime: {}", source))]
+    RuntimeErrorGc { source: mlua::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LuaConfig {
+    #[serde(default = "default_config_paths")]
+    search_dirs: Vec<PathBuf>,
+    hooks: HooksConfig,
+    #[serde(default)]
+    timers: Vec<TimerConfig>,
+    source: Option<String>,
+    /// Outputs events can be emitted to, besides the default one.
+    #[serde(default)]
+    outputs: Vec<String>,
+    #[serde(default = "default_max_state_bytes")]
+    max_state_bytes: usize,
+}
+
+const fn default_max_state_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct HooksConfig {
+    init: Option<String>,
+    process: String,
+    shutdown: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct TimerConfig {
+    interval_seconds: u64,
+    handler: String,
+}
+
+// Implementation of methods from `TransformConfig`, see the second version for
+// why they aren't implemented as a trait.
+impl LuaConfig {
+    pub fn build(&self) -> crate::Result<Transform> {
+        Lua::new(self).map(Transform::multi_output_task)
+    }
+
+    pub const fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    pub const fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    pub fn named_outputs(&self) -> Vec<String> {
+        self.outputs.clone()
+    }
+
+    pub const fn transform_type(&self) -> &'static str {
+        "lua"
+    }
+}
+
+/// A timer handler, along with the coroutine running it, when it yielded
+/// rather than returned on the previous tick.
+struct TimerHandler {
+    handler: mlua::RegistryKey,
+    coroutine: Option<mlua::RegistryKey>,
+}
+
+pub struct Lua {
+    lua: mlua::Lua,
+    invocations_after_gc: usize,
+    /// The `emit` function handed to hooks, which buffers events in `emitted`.
+    /// Unlike functions scoped to a single call, it stays valid in coroutines
+    /// resumed later on.
+    emit: mlua::RegistryKey,
+    emitted: Arc<Mutex<Vec<RoutedEvent>>>,
+    hook_init: Option<mlua::RegistryKey>,
+    hook_process: mlua::RegistryKey,
+    hook_shutdown: Option<mlua::RegistryKey>,
+    timers: Vec<(Timer, TimerHandler)>,
+}
+
+impl Lua {
+    pub fn new(config: &LuaConfig) -> crate::Result<Self> {
+        let mut outputs = HashSet::new();
+        for name in &config.outputs {
+            if name.is_empty() || name.contains('.') {
+                return Err(BuildError::InvalidOutputName { name: name.clone() }.into());
+            }
+            if !outputs.insert(name.clone()) {
+                return Err(BuildError::DuplicateOutput { name: name.clone() }.into());
+            }
+        }
+
+        // In order to support loading C modules in Lua, we need to create unsafe instance
+        // without debug library.
+        let lua = unsafe {
+            mlua::Lua::unsafe_new_with(mlua::StdLib::ALL_SAFE, mlua::LuaOptions::default())
+        };
+
+        let additional_paths = config
+            .search_dirs
+            .iter()
+            .map(|d| format!("{}/?.lua", d.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(";");
+
+        if !additional_paths.is_empty() {
+            let package = lua.globals().get::<_, mlua::Table<'_>>("package")?;
+            let current_paths = package
+                .get::<_, String>("path")
+                .unwrap_or_else(|_| ";".to_string());
+            let paths = format!("{};{}", additional_paths, current_paths);
+            package.set("path", paths)?;
+        }
+
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let emit = {
+            let emitted = Arc::clone(&emitted);
+            let emit =
+                lua.create_function(move |_, (event, output): (Event, Option<String>)| {
+                    if let Some(output) = output.as_ref().filter(|name| !outputs.contains(*name)) {
+                        return Err(mlua::Error::RuntimeError(format!(
+                            "Unknown output {:?}.",
+                            output
+                        )));
+                    }
+                    emitted
+                        .lock()
+                        .expect("poisoned lock")
+                        .push(RoutedEvent { output, event });
+                    Ok(())
+                })?;
+            lua.create_registry_value(emit)?
+        };
+
+        lua.globals()
+            .set("state", State::new(config.max_state_bytes))?;
+
+        if let Some(source) = &config.source {
+            lua.load(source).eval().context(InvalidSource)?;
+        }
+
+        let hook_init = config
+            .hooks
+            .init
+            .as_ref()
+            .map(|code| make_registry_value(&lua, code))
+            .transpose()
+            .context(InvalidHooksInit)?;
+
+        let hook_process =
+            make_registry_value(&lua, &config.hooks.process).context(InvalidHooksProcess)?;
+
+        let hook_shutdown = config
+            .hooks
+            .shutdown
+            .as_ref()
+            .map(|code| make_registry_value(&lua, code))
+            .transpose()
+            .context(InvalidHooksShutdown)?;
+
+        let mut timers = Vec::new();
+        for (id, timer) in config.timers.iter().enumerate() {
+            let handler = make_registry_value(&lua, &timer.handler).context(InvalidTimerHandler)?;
+            let timer = Timer {
+                id: id as u32,
+                interval_seconds: timer.interval_seconds,
+            };
+            timers.push((
+                timer,
+                TimerHandler {
+                    handler,
+                    coroutine: None,
+                },
+            ));
+        }
+
+        Ok(Self {
+            lua,
+            invocations_after_gc: 0,
+            emit,
+            emitted,
+            hook_init,
+            hook_process,
+            hook_shutdown,
+            timers,
+        })
+    }
+
+    /// Hands the events emitted by the last hook to `emit_fn`.
+    fn flush<F>(&self, mut emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+        let emitted = std::mem::take(&mut *self.emitted.lock().expect("poisoned lock"));
+        emitted.into_iter().for_each(|event| emit_fn(event));
+    }
+
+    fn attempt_gc(&mut self) {
+        self.invocations_after_gc += 1;
+        if self.invocations_after_gc % GC_INTERVAL == 0 {
+            emit!(&LuaGcTriggered {
+                used_memory: self.lua.used_memory()
+            });
+            let _ = self
+                .lua
+                .gc_collect()
+                .context(RuntimeErrorGc)
+                .map_err(|error| error!(%error, rate_limit = 30));
+            self.invocations_after_gc = 0;
+        }
+    }
+}
+
+/// Events are handed to hooks as tables holding either a `log` or a `metric`
+/// table, along with their `type`.
+fn event_to_lua(lua: &mlua::Lua, event: Event) -> mlua::Result<mlua::Value<'_>> {
+    let event_type = match event {
+        Event::Log(_) => "log",
+        Event::Metric(_) => "metric",
+    };
+    let value = event.to_lua(lua)?;
+    if let mlua::Value::Table(table) = &value {
+        table.raw_set("type", event_type)?;
+    }
+    Ok(value)
+}
+
+fn call_hook(
+    lua: &mlua::Lua,
+    hook: &mlua::RegistryKey,
+    emit: &mlua::RegistryKey,
+    event: Option<Event>,
+) -> mlua::Result<()> {
+    let hook = lua.registry_value::<mlua::Function>(hook)?;
+    let emit = lua.registry_value::<mlua::Function>(emit)?;
+    match event {
+        Some(event) => hook.call((event_to_lua(lua, event)?, emit)),
+        None => hook.call(emit),
+    }
+}
+
+/// Resumes the coroutine running a timer handler, starting a new one if the
+/// previous one returned, and keeps it for the next tick if it yielded.
+fn resume_timer(
+    lua: &mlua::Lua,
+    handler: &mut TimerHandler,
+    emit: &mlua::RegistryKey,
+) -> mlua::Result<()> {
+    let coroutine = match handler.coroutine.take() {
+        Some(key) => {
+            let coroutine = lua.registry_value::<mlua::Thread>(&key)?;
+            lua.remove_registry_value(key)?;
+            coroutine
+        }
+        None => lua.create_thread(lua.registry_value::<mlua::Function>(&handler.handler)?)?,
+    };
+
+    coroutine.resume::<_, mlua::MultiValue>(lua.registry_value::<mlua::Function>(emit)?)?;
+    if matches!(coroutine.status(), mlua::ThreadStatus::Resumable) {
+        handler.coroutine = Some(lua.create_registry_value(coroutine)?);
+    }
+    Ok(())
+}
+
+impl MultiOutputRuntimeTransform for Lua {
+    fn hook_process<F>(&mut self, event: Event, emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+        let _ = call_hook(&self.lua, &self.hook_process, &self.emit, Some(event))
+            .context(RuntimeErrorHooksProcess)
+            .map_err(|error| emit!(&LuaBuildError { error }));
+
+        self.flush(emit_fn);
+        self.attempt_gc();
+    }
+
+    fn hook_init<F>(&mut self, emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+        if let Some(hook) = &self.hook_init {
+            let _ = call_hook(&self.lua, hook, &self.emit, None)
+                .context(RuntimeErrorHooksInit)
+                .map_err(|error| error!(%error, rate_limit = 30));
+        }
+
+        self.flush(emit_fn);
+        self.attempt_gc();
+    }
+
+    fn hook_shutdown<F>(&mut self, emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+        if let Some(hook) = &self.hook_shutdown {
+            let _ = call_hook(&self.lua, hook, &self.emit, None)
+                .context(RuntimeErrorHooksShutdown)
+                .map_err(|error| error!(%error, rate_limit = 30));
+        }
+
+        self.flush(emit_fn);
+        self.attempt_gc();
+    }
+
+    fn timer_handler<F>(&mut self, timer: Timer, emit_fn: F)
+    where
+        F: FnMut(RoutedEvent),
+    {
+        let handler = &mut self.timers[timer.id as usize].1;
+        let _ = resume_timer(&self.lua, handler, &self.emit)
+            .context(RuntimeErrorTimerHandler)
+            .map_err(|error| error!(%error, rate_limit = 30));
+
+        self.flush(emit_fn);
+        self.attempt_gc();
+    }
+
+    fn timers(&self) -> Vec<Timer> {
+        self.timers.iter().map(|(timer, _)| *timer).collect()
+    }
+}
+
+/// Values kept across hooks, exposed as the `state` global. Unlike that of
+/// Lua globals, their size is accounted for, so that it can be bounded.
+struct State {
+    values: HashMap<String, Value>,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl State {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            values: HashMap::new(),
+            bytes: 0,
+            max_bytes,
+        }
+    }
+
+    /// Stores a value, unless the state would then outgrow its limit.
+    fn set(&mut self, key: String, value: Value) -> bool {
+        let replaced = self
+            .values
+            .get(&key)
+            .map_or(0, |old| key.len() + old.size_of());
+        let bytes = self.bytes - replaced + key.len() + value.size_of();
+        if bytes > self.max_bytes {
+            emit!(&LuaStateLimitReached {
+                key: &key,
+                max_bytes: self.max_bytes,
+            });
+            return false;
+        }
+
+        self.values.insert(key, value);
+        self.bytes = bytes;
+        emit!(&LuaStateUpdated { bytes });
+        true
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Value> {
+        let value = self.values.remove(key)?;
+        self.bytes -= key.len() + value.size_of();
+        emit!(&LuaStateUpdated { bytes: self.bytes });
+        Some(value)
+    }
+}
+
+impl mlua::UserData for State {
+    fn add_methods<'lua, M: mlua::UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("get", |_, this, key: String| {
+            Ok(this.values.get(&key).cloned())
+        });
+        methods.add_method_mut(
+            "set",
+            |lua, this, (key, value): (String, mlua::Value)| match value {
+                mlua::Value::Nil => Ok(this.remove(&key).is_some()),
+                value => Ok(this.set(key, Value::from_lua(value, lua)?)),
+            },
+        );
+        methods.add_method_mut("delete", |_, this, key: String| Ok(this.remove(&key)));
+        methods.add_method("bytes", |_, this, ()| Ok(this.bytes));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{
+            metric::{Metric, MetricKind, MetricValue},
+            Event,
+        },
+        test_util::trace_init,
+        transforms::MultiOutputTaskTransform,
+    };
+    use futures::{stream, StreamExt};
+
+    fn from_config(config: &str) -> crate::Result<Box<Lua>> {
+        Lua::new(&toml::from_str(config).unwrap()).map(Box::new)
+    }
+
+    #[tokio::test]
+    async fn routes_events_to_named_outputs() {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            outputs = ["slow"]
+            hooks.process = """function (event, emit)
+                if event.log.duration > 1 then
+                    emit(event, "slow")
+                else
+                    emit(event)
+                end
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let events = [0.5, 2.0].iter().map(|duration| {
+            let mut event = Event::new_empty_log();
+            event.as_mut_log().insert("duration", *duration);
+            event
+        });
+        let output = transform
+            .transform(Box::pin(stream::iter(events.collect::<Vec<_>>())))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].output, None);
+        assert_eq!(output[0].event.as_log()["duration"], 0.5.into());
+        assert_eq!(output[1].output, Some("slow".into()));
+        assert_eq!(output[1].event.as_log()["duration"], 2.0.into());
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_outputs() {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.process = """function (event, emit)
+                emit(event, "missing")
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let output = transform
+            .transform(Box::pin(stream::iter(vec![Event::new_empty_log()])))
+            .collect::<Vec<_>>()
+            .await;
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn rejects_duplicate_outputs() {
+        let config = r#"
+            outputs = ["slow", "slow"]
+            hooks.process = "function (event, emit) emit(event) end"
+        "#;
+        assert!(from_config(config).is_err());
+    }
+
+    #[tokio::test]
+    async fn exposes_typed_metrics() {
+        trace_init();
+
+        let transform = from_config(
+            r#"
+            hooks.process = """function (event, emit)
+                if event.type == "metric" and event.metric.counter then
+                    event.metric.counter.value = event.metric.counter.value * 2
+                end
+                emit(event)
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let metric = Metric::new(
+            "hits",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 21.0 },
+        );
+        let output = transform
+            .transform(Box::pin(stream::iter(vec![metric.clone().into()])))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            output[0].event.as_metric(),
+            &metric.with_value(MetricValue::Counter { value: 42.0 })
+        );
+    }
+
+    #[test]
+    fn bounds_state_size() {
+        let mut transform = from_config(
+            r#"
+            max_state_bytes = 128
+            hooks.process = """function (event, emit)
+                event.log.stored = state:set(event.log.key, event.log.value)
+                event.log.bytes = state:bytes()
+                emit(event)
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let mut process = |key: &str, value: &str| {
+            let mut event = Event::new_empty_log();
+            event.as_mut_log().insert("key", key);
+            event.as_mut_log().insert("value", value);
+            let mut output = Vec::new();
+            transform.hook_process(event, |event| output.push(event.event));
+            output.pop().unwrap()
+        };
+
+        let stored = process("small", "value");
+        assert_eq!(stored.as_log()["stored"], true.into());
+        let bytes = stored.as_log()["bytes"].clone();
+
+        let rejected = process("large", &"x".repeat(128));
+        assert_eq!(rejected.as_log()["stored"], false.into());
+        assert_eq!(rejected.as_log()["bytes"], bytes);
+    }
+
+    #[test]
+    fn resumes_yielding_timers() {
+        let mut transform = from_config(
+            r#"
+            hooks.process = "function (event, emit) emit(event) end"
+            [[timers]]
+            interval_seconds = 1
+            handler = """function (emit)
+                for i = 1, 2 do
+                    emit({ log = { step = i } })
+                    coroutine.yield()
+                end
+            end
+            """
+            "#,
+        )
+        .unwrap();
+
+        let timer = transform.timers()[0];
+        let mut steps = Vec::new();
+        for _ in 0..4 {
+            transform.timer_handler(timer, |event| {
+                steps.push(event.event.as_log()["step"].clone())
+            });
+        }
+
+        // The third tick finishes the first coroutine, so the fourth one
+        // starts a new one.
+        assert_eq!(
+            steps,
+            vec![Value::Integer(1), Value::Integer(2), Value::Integer(1)]
+        );
+    }
+}
@@ -6,6 +6,8 @@ pub mod add_fields;
 pub mod add_tags;
 #[cfg(feature = "transforms-aggregate")]
 pub mod aggregate;
+#[cfg(feature = "transforms-anonymize")]
+pub mod anonymize;
 #[cfg(feature = "transforms-ansi_stripper")]
 pub mod ansi_stripper;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
@@ -20,10 +22,16 @@ pub mod compound;
 pub mod concat;
 #[cfg(feature = "transforms-dedupe")]
 pub mod dedupe;
+#[cfg(feature = "transforms-derive_severity")]
+pub mod derive_severity;
+#[cfg(feature = "transforms-enrich_dns")]
+pub mod enrich_dns;
 #[cfg(feature = "transforms-field_filter")]
 pub mod field_filter;
 #[cfg(feature = "transforms-filter")]
 pub mod filter;
+#[cfg(feature = "transforms-flatten")]
+pub mod flatten;
 #[cfg(feature = "transforms-geoip")]
 pub mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
@@ -42,6 +50,8 @@ pub mod lua;
 pub mod merge;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
+#[cfg(feature = "transforms-pressure_sample")]
+pub mod pressure_sample;
 #[cfg(feature = "transforms-reduce")]
 pub mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
@@ -58,15 +68,22 @@ pub mod rename_fields;
 pub mod route;
 #[cfg(feature = "transforms-sample")]
 pub mod sample;
+#[cfg(feature = "transforms-sessionize")]
+pub mod sessionize;
+#[cfg(any(feature = "transforms-reduce", feature = "transforms-sessionize"))]
+mod snapshot;
 #[cfg(feature = "transforms-split")]
 pub mod split;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+pub mod tail_sampling;
 #[cfg(feature = "transforms-tokenizer")]
 pub mod tokenizer;
 
 pub use vector_core::transform::{
-    FallibleFunctionTransform, FunctionTransform, TaskTransform, Transform,
+    FallibleFunctionTransform, FunctionTransform, MultiOutputTaskTransform, RoutedEvent,
+    TaskTransform, Transform,
 };
 
 #[derive(Debug, Snafu)]
@@ -0,0 +1,301 @@
+use crate::{
+    buffers::{self, default_priority_field, event_priority},
+    config::{
+        ComponentKey, DataType, GenerateConfig, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::Event,
+    internal_events::{
+        PressureSampleEventDiscarded, PressureSamplingActivated, PressureSamplingDeactivated,
+    },
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`rate` must be at least 1."))]
+    InvalidRate,
+    #[snafu(display(
+        "`low_watermark` ({}) must be lower than `high_watermark` ({}), which must be between 0 and 1.",
+        low_watermark,
+        high_watermark
+    ))]
+    InvalidWatermarks {
+        low_watermark: f64,
+        high_watermark: f64,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PressureSampleConfig {
+    pub sink: ComponentKey,
+    pub rate: u64,
+    #[serde(default = "default_high_watermark")]
+    pub high_watermark: f64,
+    #[serde(default = "default_low_watermark")]
+    pub low_watermark: f64,
+    #[serde(default = "default_priority_field")]
+    pub priority_field: String,
+    #[serde(default = "default_min_priority")]
+    pub min_priority: usize,
+    #[serde(default = "default_check_interval_ms")]
+    pub check_interval_ms: u64,
+}
+
+const fn default_high_watermark() -> f64 {
+    0.8
+}
+
+const fn default_low_watermark() -> f64 {
+    0.5
+}
+
+const fn default_min_priority() -> usize {
+    1
+}
+
+const fn default_check_interval_ms() -> u64 {
+    1000
+}
+
+inventory::submit! {
+    TransformDescription::new::<PressureSampleConfig>("pressure_sample")
+}
+
+impl GenerateConfig for PressureSampleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            sink: ComponentKey::from("my_sink_id"),
+            rate: 10,
+            high_watermark: default_high_watermark(),
+            low_watermark: default_low_watermark(),
+            priority_field: default_priority_field(),
+            min_priority: default_min_priority(),
+            check_interval_ms: default_check_interval_ms(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pressure_sample")]
+impl TransformConfig for PressureSampleConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.rate == 0 {
+            return Err(BuildError::InvalidRate.into());
+        }
+        if !(0.0 <= self.low_watermark
+            && self.low_watermark < self.high_watermark
+            && self.high_watermark <= 1.0)
+        {
+            return Err(BuildError::InvalidWatermarks {
+                low_watermark: self.low_watermark,
+                high_watermark: self.high_watermark,
+            }
+            .into());
+        }
+
+        Ok(Transform::function(PressureSample::new(self)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "pressure_sample"
+    }
+}
+
+/// Samples low priority events while the buffer of a sink is filling up.
+///
+/// Sampling starts once the buffer is used past the high watermark, and stops
+/// once it has drained down to the low watermark, so that it doesn't flap
+/// while the buffer hovers around a single threshold.
+#[derive(Clone)]
+pub struct PressureSample {
+    sink: ComponentKey,
+    rate: u64,
+    high_watermark: f64,
+    low_watermark: f64,
+    priority_field: String,
+    min_priority: usize,
+    check_interval: Duration,
+    next_check: Instant,
+    active: bool,
+    count: u64,
+}
+
+impl PressureSample {
+    pub fn new(config: &PressureSampleConfig) -> Self {
+        Self {
+            sink: config.sink.clone(),
+            rate: config.rate,
+            high_watermark: config.high_watermark,
+            low_watermark: config.low_watermark,
+            priority_field: config.priority_field.clone(),
+            min_priority: config.min_priority,
+            check_interval: Duration::from_millis(config.check_interval_ms),
+            next_check: Instant::now(),
+            active: false,
+            count: 0,
+        }
+    }
+
+    fn check_pressure(&mut self) {
+        let now = Instant::now();
+        if now < self.next_check {
+            return;
+        }
+        self.next_check = now + self.check_interval;
+
+        // The sink isn't running yet while the topology is starting up.
+        let utilization = buffers::utilization(&self.sink).unwrap_or(0.0);
+        if !self.active && utilization >= self.high_watermark {
+            self.active = true;
+            self.count = 0;
+            emit!(&PressureSamplingActivated {
+                sink: &self.sink,
+                utilization,
+                rate: self.rate,
+            });
+        } else if self.active && utilization <= self.low_watermark {
+            self.active = false;
+            emit!(&PressureSamplingDeactivated {
+                sink: &self.sink,
+                utilization,
+            });
+        }
+    }
+}
+
+impl FunctionTransform for PressureSample {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        self.check_pressure();
+
+        if !self.active || event_priority(&event, &self.priority_field) >= self.min_priority {
+            output.push(event);
+            return;
+        }
+
+        let keep = self.count == 0;
+        self.count = (self.count + 1) % self.rate;
+
+        if keep {
+            let rate = self.rate.to_string();
+            match &mut event {
+                Event::Log(log) => {
+                    log.insert("sample_rate", rate);
+                }
+                Event::Metric(metric) => {
+                    metric.insert_tag("sample_rate".to_owned(), rate);
+                }
+            }
+            output.push(event);
+        } else {
+            emit!(&PressureSampleEventDiscarded);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        buffers::{BufferConfig, WhenFull},
+        event::LogEvent,
+    };
+    use futures::SinkExt;
+    use tracing::Span;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PressureSampleConfig>();
+    }
+
+    fn config(sink: &str) -> PressureSampleConfig {
+        PressureSampleConfig {
+            sink: ComponentKey::from(sink),
+            rate: 2,
+            high_watermark: 0.75,
+            low_watermark: 0.25,
+            priority_field: default_priority_field(),
+            min_priority: default_min_priority(),
+            check_interval_ms: 0,
+        }
+    }
+
+    fn log(priority: i64) -> Event {
+        let mut log = LogEvent::from("message");
+        log.insert("priority", priority);
+        log.into()
+    }
+
+    fn transform_all(transform: &mut PressureSample, events: Vec<Event>) -> Vec<Event> {
+        let mut output = Vec::new();
+        for event in events {
+            transform.transform(&mut output, event);
+        }
+        output
+    }
+
+    #[tokio::test]
+    async fn samples_low_priority_events_under_pressure() {
+        let sink = ComponentKey::from("pressure_sample_sink");
+        let buffer = BufferConfig::Memory {
+            max_events: 4,
+            when_full: WhenFull::Block,
+            priority: None,
+        };
+        let (tx, mut rx, _acker) = buffer.build(&None, &sink, Span::none()).unwrap();
+        buffers::register_usage(&sink, tx.usage().unwrap());
+        let mut transform = PressureSample::new(&config("pressure_sample_sink"));
+
+        let output = transform_all(&mut transform, vec![log(0), log(0)]);
+        assert_eq!(output.len(), 2);
+        assert!(!output[0].as_log().contains("sample_rate"));
+
+        let mut input = tx.get();
+        for _ in 0..3 {
+            input.send(log(0)).await.unwrap();
+        }
+
+        let output = transform_all(&mut transform, vec![log(0), log(0), log(0), log(1), log(0)]);
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0].as_log()["sample_rate"], "2".into());
+        assert_eq!(output[1].as_log()["sample_rate"], "2".into());
+        assert!(!output[2].as_log().contains("sample_rate"));
+
+        // Sampling continues until the buffer drains to the low watermark.
+        futures::StreamExt::next(&mut rx).await.unwrap();
+        assert_eq!(transform_all(&mut transform, vec![log(0), log(0)]).len(), 1);
+        futures::StreamExt::next(&mut rx).await.unwrap();
+        assert_eq!(transform_all(&mut transform, vec![log(0), log(0)]).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn passes_events_while_sink_is_missing() {
+        let mut transform = PressureSample::new(&config("missing_sink"));
+        assert_eq!(transform_all(&mut transform, vec![log(0), log(0)]).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_watermarks() {
+        let mut config = config("sink");
+        config.low_watermark = 0.9;
+        let context = TransformContext::default();
+        assert!(config.build(&context).await.is_err());
+
+        config.low_watermark = 0.5;
+        config.high_watermark = 1.5;
+        assert!(config.build(&context).await.is_err());
+    }
+}
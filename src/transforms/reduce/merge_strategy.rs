@@ -3,6 +3,7 @@ use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use vector_core::ByteSizeOf;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -33,11 +34,21 @@ impl DiscardMerger {
     }
 }
 
+impl ByteSizeOf for DiscardMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.allocated_bytes()
+    }
+}
+
 impl ReduceValueMerger for DiscardMerger {
     fn add(&mut self, _v: Value) -> Result<(), String> {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("discard", self.v.clone())
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, self.v);
         Ok(())
@@ -58,6 +69,12 @@ impl RetainMerger {
     }
 }
 
+impl ByteSizeOf for RetainMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.allocated_bytes()
+    }
+}
+
 impl ReduceValueMerger for RetainMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         if Value::Null != v {
@@ -66,6 +83,10 @@ impl ReduceValueMerger for RetainMerger {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("retain", self.v.clone())
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, self.v);
         Ok(())
@@ -89,6 +110,12 @@ impl ConcatMerger {
     }
 }
 
+impl ByteSizeOf for ConcatMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.capacity()
+    }
+}
+
 impl ReduceValueMerger for ConcatMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         if let Value::Bytes(b) = v {
@@ -103,6 +130,16 @@ impl ReduceValueMerger for ConcatMerger {
         }
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        (
+            "concat",
+            Value::Array(vec![
+                Value::Bytes(self.v.clone().freeze()),
+                self.join_by.to_string().into(),
+            ]),
+        )
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, Value::Bytes(self.v.into()));
         Ok(())
@@ -122,6 +159,12 @@ impl ConcatArrayMerger {
     }
 }
 
+impl ByteSizeOf for ConcatArrayMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.allocated_bytes()
+    }
+}
+
 impl ReduceValueMerger for ConcatArrayMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         if let Value::Array(a) = v {
@@ -132,6 +175,10 @@ impl ReduceValueMerger for ConcatArrayMerger {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("concat_array", Value::Array(self.v.clone()))
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, Value::Array(self.v));
         Ok(())
@@ -151,12 +198,22 @@ impl ArrayMerger {
     }
 }
 
+impl ByteSizeOf for ArrayMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.allocated_bytes()
+    }
+}
+
 impl ReduceValueMerger for ArrayMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         self.v.push(v);
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("array", Value::Array(self.v.clone()))
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, Value::Array(self.v));
         Ok(())
@@ -176,6 +233,12 @@ impl LongestArrayMerger {
     }
 }
 
+impl ByteSizeOf for LongestArrayMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.allocated_bytes()
+    }
+}
+
 impl ReduceValueMerger for LongestArrayMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         if let Value::Array(a) = v {
@@ -191,6 +254,10 @@ impl ReduceValueMerger for LongestArrayMerger {
         }
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("longest_array", Value::Array(self.v.clone()))
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, Value::Array(self.v));
         Ok(())
@@ -210,6 +277,12 @@ impl ShortestArrayMerger {
     }
 }
 
+impl ByteSizeOf for ShortestArrayMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.allocated_bytes()
+    }
+}
+
 impl ReduceValueMerger for ShortestArrayMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         if let Value::Array(a) = v {
@@ -225,6 +298,10 @@ impl ReduceValueMerger for ShortestArrayMerger {
         }
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("shortest_array", Value::Array(self.v.clone()))
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, Value::Array(self.v));
         Ok(())
@@ -265,12 +342,25 @@ impl FlatUniqueMerger {
     }
 }
 
+impl ByteSizeOf for FlatUniqueMerger {
+    fn allocated_bytes(&self) -> usize {
+        self.v.iter().map(ByteSizeOf::size_of).sum()
+    }
+}
+
 impl ReduceValueMerger for FlatUniqueMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         insert_value(&mut self.v, v);
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        (
+            "flat_unique",
+            Value::Array(self.v.iter().cloned().collect()),
+        )
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(k, Value::Array(self.v.into_iter().collect()));
         Ok(())
@@ -294,6 +384,12 @@ impl TimestampWindowMerger {
     }
 }
 
+impl ByteSizeOf for TimestampWindowMerger {
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
 impl ReduceValueMerger for TimestampWindowMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         if let Value::Timestamp(ts) = v {
@@ -307,6 +403,16 @@ impl ReduceValueMerger for TimestampWindowMerger {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        (
+            "timestamp_window",
+            Value::Array(vec![
+                Value::Timestamp(self.started),
+                Value::Timestamp(self.latest),
+            ]),
+        )
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         v.insert(format!("{}_end", k), Value::Timestamp(self.latest));
         v.insert(k, Value::Timestamp(self.started));
@@ -334,6 +440,15 @@ impl From<f64> for NumberMergerValue {
     }
 }
 
+impl From<&NumberMergerValue> for Value {
+    fn from(v: &NumberMergerValue) -> Self {
+        match v {
+            NumberMergerValue::Int(i) => Value::Integer(*i),
+            NumberMergerValue::Float(f) => Value::Float(*f),
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 
 #[derive(Debug, Clone)]
@@ -347,6 +462,12 @@ impl AddNumbersMerger {
     }
 }
 
+impl ByteSizeOf for AddNumbersMerger {
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
 impl ReduceValueMerger for AddNumbersMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         // Try and keep max precision with integer values, but once we've
@@ -370,6 +491,10 @@ impl ReduceValueMerger for AddNumbersMerger {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("sum", (&self.v).into())
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         match self.v {
             NumberMergerValue::Float(f) => v.insert(k, Value::Float(f)),
@@ -392,6 +517,12 @@ impl MaxNumberMerger {
     }
 }
 
+impl ByteSizeOf for MaxNumberMerger {
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
 impl ReduceValueMerger for MaxNumberMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         // Try and keep max precision with integer values, but once we've
@@ -431,6 +562,10 @@ impl ReduceValueMerger for MaxNumberMerger {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("max", (&self.v).into())
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         match self.v {
             NumberMergerValue::Float(f) => v.insert(k, Value::Float(f)),
@@ -453,6 +588,12 @@ impl MinNumberMerger {
     }
 }
 
+impl ByteSizeOf for MinNumberMerger {
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
 impl ReduceValueMerger for MinNumberMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         // Try and keep max precision with integer values, but once we've
@@ -492,6 +633,10 @@ impl ReduceValueMerger for MinNumberMerger {
         Ok(())
     }
 
+    fn snapshot(&self) -> (&'static str, Value) {
+        ("min", (&self.v).into())
+    }
+
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
         match self.v {
             NumberMergerValue::Float(f) => v.insert(k, Value::Float(f)),
@@ -503,8 +648,11 @@ impl ReduceValueMerger for MinNumberMerger {
 
 //------------------------------------------------------------------------------
 
-pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync {
+pub trait ReduceValueMerger: ByteSizeOf + std::fmt::Debug + Send + Sync {
     fn add(&mut self, v: Value) -> Result<(), String>;
+    /// The kind and state of the merger, which `restore_value_merger` builds
+    /// it back from.
+    fn snapshot(&self) -> (&'static str, Value);
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String>;
 }
 
@@ -585,6 +733,53 @@ pub fn get_value_merger(v: Value, m: &MergeStrategy) -> Result<Box<dyn ReduceVal
     }
 }
 
+/// Builds back a merger from the kind and state returned by its `snapshot`.
+pub fn restore_value_merger(
+    kind: &str,
+    state: Value,
+) -> Result<Box<dyn ReduceValueMerger>, String> {
+    let invalid = || format!("invalid state for {} merger", kind);
+    let number = |state: &Value| match state {
+        Value::Integer(i) => Ok(NumberMergerValue::Int(*i)),
+        Value::Float(f) => Ok(NumberMergerValue::Float(*f)),
+        _ => Err(invalid()),
+    };
+
+    let merger: Box<dyn ReduceValueMerger> = match (kind, state) {
+        ("discard", v) => Box::new(DiscardMerger::new(v)),
+        ("retain", v) => Box::new(RetainMerger::new(v)),
+        ("concat", Value::Array(mut a)) if a.len() == 2 => {
+            let join_by = a
+                .pop()
+                .and_then(|join_by| join_by.to_string_lossy().chars().next())
+                .ok_or_else(invalid)?;
+            match a.pop() {
+                Some(Value::Bytes(b)) => Box::new(ConcatMerger::new(b, join_by)),
+                _ => return Err(invalid()),
+            }
+        }
+        ("concat_array", Value::Array(a)) => Box::new(ConcatArrayMerger::new(a)),
+        ("array", Value::Array(a)) => Box::new(ArrayMerger { v: a }),
+        ("longest_array", Value::Array(a)) => Box::new(LongestArrayMerger::new(a)),
+        ("shortest_array", Value::Array(a)) => Box::new(ShortestArrayMerger::new(a)),
+        ("flat_unique", v @ Value::Array(_)) => Box::new(FlatUniqueMerger::new(v)),
+        ("timestamp_window", Value::Array(a)) => match a.as_slice() {
+            [Value::Timestamp(started), Value::Timestamp(latest)] => {
+                Box::new(TimestampWindowMerger {
+                    started: *started,
+                    latest: *latest,
+                })
+            }
+            _ => return Err(invalid()),
+        },
+        ("sum", v) => Box::new(AddNumbersMerger::new(number(&v)?)),
+        ("max", v) => Box::new(MaxNumberMerger::new(number(&v)?)),
+        ("min", v) => Box::new(MinNumberMerger::new(number(&v)?)),
+        _ => return Err(invalid()),
+    };
+    Ok(merger)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -825,6 +1020,62 @@ mod test {
         }
     }
 
+    #[test]
+    fn restoring_snapshots() {
+        let cases: Vec<(Value, Value, MergeStrategy)> = vec![
+            ("foo".into(), "bar".into(), MergeStrategy::Discard),
+            ("foo".into(), "bar".into(), MergeStrategy::Retain),
+            ("foo".into(), "bar".into(), MergeStrategy::Array),
+            ("foo".into(), "bar".into(), MergeStrategy::Concat),
+            ("foo".into(), "bar".into(), MergeStrategy::ConcatNewline),
+            (json!([1]).into(), json!([2]).into(), MergeStrategy::Concat),
+            (
+                json!([1]).into(),
+                json!([2, 3]).into(),
+                MergeStrategy::LongestArray,
+            ),
+            (
+                json!([1]).into(),
+                json!([2, 3]).into(),
+                MergeStrategy::ShortestArray,
+            ),
+            (
+                json!([1]).into(),
+                json!([1]).into(),
+                MergeStrategy::FlatUnique,
+            ),
+            (1.into(), 2.5.into(), MergeStrategy::Sum),
+            (1.into(), 2.into(), MergeStrategy::Max),
+            (1.into(), 2.into(), MergeStrategy::Min),
+        ];
+
+        for (initial, additional, strategy) in cases {
+            let merger = get_value_merger(initial.clone(), &strategy).unwrap();
+            let (kind, state) = merger.snapshot();
+            let mut restored = restore_value_merger(kind, state).unwrap();
+            restored.add(additional.clone()).unwrap();
+
+            let mut output = LogEvent::default();
+            restored.insert_into("out".into(), &mut output).unwrap();
+            assert_eq!(
+                output.remove("out"),
+                merge(initial, additional, &strategy).ok(),
+                "{:?}",
+                strategy
+            );
+        }
+
+        let now = Utc::now();
+        let mut merger: Box<dyn ReduceValueMerger> = Value::from(now).into();
+        merger.add(Value::from(now)).unwrap();
+        let (kind, state) = merger.snapshot();
+        assert_eq!(kind, "timestamp_window");
+        assert!(restore_value_merger(kind, state).is_ok());
+
+        assert!(restore_value_merger("sum", "foo".into()).is_err());
+        assert!(restore_value_merger("unknown", "foo".into()).is_err());
+    }
+
     fn merge(initial: Value, additional: Value, strategy: &MergeStrategy) -> Result<Value, String> {
         let mut merger = get_value_merger(initial, strategy)?;
         merger.add(additional)?;
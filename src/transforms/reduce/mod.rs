@@ -2,21 +2,27 @@ use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
     event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
-    internal_events::ReduceStaleEventFlushed,
+    internal_events::{ComponentAllocatedBytes, ReduceStaleEventFlushed, ReduceStatesRestored},
     transforms::{TaskTransform, Transform},
 };
 use async_stream::stream;
+use bytes::BytesMut;
 use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{hash_map, HashMap},
+    mem,
+    path::PathBuf,
     pin::Pin,
     time::{Duration, Instant},
 };
+use vector_core::ByteSizeOf;
 
 mod merge_strategy;
+mod snapshot;
 
+use crate::transforms::snapshot::{Snapshot, Snapshots};
 use merge_strategy::*;
 
 //------------------------------------------------------------------------------
@@ -40,6 +46,27 @@ pub struct ReduceConfig {
     /// reduce.
     pub ends_when: Option<AnyCondition>,
     pub starts_when: Option<AnyCondition>,
+
+    /// Snapshot the in-progress reduces into the data directory, and restore
+    /// them on startup, rather than flushing them on shutdown.
+    pub persistence: Option<PersistenceConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceConfig {
+    /// The directory the snapshots are kept in, overriding the global
+    /// `data_dir`.
+    pub data_dir: Option<PathBuf>,
+
+    /// How often the in-progress reduces are snapshotted, in addition to on
+    /// shutdown.
+    #[serde(default = "default_snapshot_interval_secs")]
+    pub snapshot_interval_secs: u64,
+}
+
+const fn default_snapshot_interval_secs() -> u64 {
+    60
 }
 
 inventory::submit! {
@@ -52,7 +79,26 @@ impl_generate_config_from_default!(ReduceConfig);
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Reduce::new(self, &context.enrichment_tables).map(Transform::task)
+        let mut reduce = Reduce::new(self, &context.enrichment_tables)?;
+
+        // Transforms built outside of a running topology, e.g. in unit tests,
+        // don't persist their state.
+        if let (Some(persistence), Some(key)) = (&self.persistence, &context.key) {
+            if persistence.snapshot_interval_secs == 0 {
+                return Err(
+                    "`persistence.snapshot_interval_secs` must be greater than zero".into(),
+                );
+            }
+            let data_dir = context
+                .globals
+                .resolve_and_make_data_subdir(persistence.data_dir.as_ref(), key)?;
+            reduce.restore_snapshot(Snapshots::new(
+                data_dir,
+                Duration::from_secs(persistence.snapshot_interval_secs),
+            ));
+        }
+
+        Ok(Transform::task(reduce))
     }
 
     fn input_type(&self) -> DataType {
@@ -142,6 +188,16 @@ impl ReduceState {
     }
 }
 
+impl ByteSizeOf for ReduceState {
+    fn allocated_bytes(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|(k, v)| k.size_of() + v.size_of())
+            .sum::<usize>()
+            + self.metadata.allocated_bytes()
+    }
+}
+
 //------------------------------------------------------------------------------
 
 pub struct Reduce {
@@ -152,6 +208,7 @@ pub struct Reduce {
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
     ends_when: Option<Box<dyn Condition>>,
     starts_when: Option<Box<dyn Condition>>,
+    snapshots: Option<Snapshots<ReduceState>>,
 }
 
 impl Reduce {
@@ -183,9 +240,31 @@ impl Reduce {
             reduce_merge_states: HashMap::new(),
             ends_when,
             starts_when,
+            snapshots: None,
         })
     }
 
+    /// Restores the states snapshotted by the previous run, and snapshots them
+    /// from now on.
+    fn restore_snapshot(&mut self, snapshots: Snapshots<ReduceState>) {
+        match snapshots.load(&self.group_by) {
+            Ok(states) => {
+                if !states.is_empty() {
+                    emit!(&ReduceStatesRestored {
+                        count: states.len()
+                    });
+                }
+                self.reduce_merge_states = states.into_iter().collect();
+            }
+            Err(error) => ReduceState::emit_snapshot_failed(error, "read"),
+        }
+        self.snapshots = Some(snapshots);
+    }
+
+    fn snapshot(&self) -> BytesMut {
+        Snapshots::encode(&self.reduce_merge_states, &self.group_by)
+    }
+
     fn flush_into(&mut self, output: &mut Vec<Event>) {
         let mut flush_discriminants = Vec::new();
         for (k, t) in &self.reduce_merge_states {
@@ -201,6 +280,14 @@ impl Reduce {
         }
     }
 
+    /// The approximate number of bytes held by the events being reduced.
+    fn allocated_bytes(&self) -> usize {
+        self.reduce_merge_states
+            .values()
+            .map(|state| mem::size_of::<Discriminant>() + state.size_of())
+            .sum()
+    }
+
     fn flush_all_into(&mut self, output: &mut Vec<Event>) {
         self.reduce_merge_states
             .drain()
@@ -271,6 +358,13 @@ impl TaskTransform for Reduce {
 
         let mut flush_stream = tokio::time::interval(poll_period);
 
+        let snapshots = me.snapshots.take();
+        let mut snapshot_stream = tokio::time::interval(
+            snapshots
+                .as_ref()
+                .map_or(poll_period, |snapshots| snapshots.interval),
+        );
+
         Box::pin(
             stream! {
               loop {
@@ -278,12 +372,30 @@ impl TaskTransform for Reduce {
                 let done = tokio::select! {
                     _ = flush_stream.tick() => {
                       me.flush_into(&mut output);
+                      emit!(&ComponentAllocatedBytes {
+                          byte_size: me.allocated_bytes(),
+                      });
+                      false
+                    }
+                    _ = snapshot_stream.tick(), if snapshots.is_some() => {
+                      if let Some(snapshots) = &snapshots {
+                        snapshots.save(me.snapshot()).await;
+                      }
                       false
                     }
                     maybe_event = input_rx.next() => {
                       match maybe_event {
                         None => {
-                          me.flush_all_into(&mut output);
+                          // Keep the in-progress states for the next run
+                          // rather than flushing them incomplete, unless they
+                          // can't be persisted.
+                          let persisted = match &snapshots {
+                            Some(snapshots) => snapshots.save(me.snapshot()).await,
+                            None => false,
+                          };
+                          if !persisted {
+                            me.flush_all_into(&mut output);
+                          }
                           true
                         }
                         Some(event) => {
@@ -306,7 +418,7 @@ impl TaskTransform for Reduce {
 mod test {
     use super::*;
     use crate::{
-        config::TransformConfig,
+        config::{GlobalOptions, TransformConfig},
         event::{LogEvent, Value},
     };
     use serde_json::json;
@@ -561,4 +673,87 @@ merge_strategies.bar = "concat"
         assert_eq!(output_2["bar"], json!([2, 4, 6, 8, "done"]).into());
         assert_eq!(output_2.metadata(), &metadata_2);
     }
+
+    #[test]
+    fn tracks_allocated_bytes() {
+        let config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+
+[merge_strategies]
+  message = "concat"
+"#,
+        )
+        .unwrap();
+        let mut reduce = Reduce::new(&config, &Default::default()).unwrap();
+        assert_eq!(reduce.allocated_bytes(), 0);
+
+        let mut output = Vec::new();
+        let mut event = LogEvent::from("test message 1");
+        event.insert("request_id", "1");
+        reduce.transform_one(&mut output, event.into());
+        let single = reduce.allocated_bytes();
+        assert!(single > 0);
+
+        let mut event = LogEvent::from("test message 2");
+        event.insert("request_id", "1");
+        reduce.transform_one(&mut output, event.into());
+        assert!(reduce.allocated_bytes() > single);
+
+        reduce.flush_all_into(&mut output);
+        assert_eq!(reduce.allocated_bytes(), 0);
+        assert_eq!(output.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn restores_states_after_restart() {
+        let config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request.id" ]
+
+[merge_strategies]
+  message = "concat"
+
+[ends_when]
+  type = "check_fields"
+  "test_end.exists" = true
+
+[persistence]
+"#,
+        )
+        .unwrap();
+        let context = TransformContext {
+            key: Some("reduce".to_owned()),
+            globals: GlobalOptions {
+                data_dir: Some(crate::test_util::temp_dir()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("counter", 1);
+        e_1.insert("request.id", "1");
+        let reduce = config.build(&context).await.unwrap().into_task();
+        let output = reduce
+            .transform(Box::pin(stream::iter(vec![e_1.into()])))
+            .collect::<Vec<_>>()
+            .await;
+        assert!(output.is_empty());
+
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("counter", 2);
+        e_2.insert("request.id", "1");
+        e_2.insert("test_end", "yep");
+        let reduce = config.build(&context).await.unwrap().into_task();
+        let output = reduce
+            .transform(Box::pin(stream::iter(vec![e_2.into()])))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(output.len(), 1);
+        let output = output[0].as_log();
+        assert_eq!(output["message"], "test message 1 test message 2".into());
+        assert_eq!(output["counter"], Value::from(3));
+        assert_eq!(output["request.id"], "1".into());
+    }
 }
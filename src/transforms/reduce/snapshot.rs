@@ -0,0 +1,73 @@
+//! Snapshots of the in-progress reduce states, so that they survive restarts.
+
+use super::{merge_strategy::restore_value_merger, ReduceState};
+use crate::{
+    event::{EventMetadata, Value},
+    internal_events::ReduceSnapshotFailed,
+    transforms::snapshot::Snapshot,
+};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+impl Snapshot for ReduceState {
+    const FILE_NAME: &'static str = "reduce_states.pb";
+
+    fn snapshot(&self) -> BTreeMap<String, Value> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, merger)| {
+                let (kind, state) = merger.snapshot();
+                let mut merger = BTreeMap::new();
+                merger.insert("kind".to_owned(), Value::from(kind));
+                merger.insert("state".to_owned(), state);
+                (name.clone(), Value::Map(merger))
+            })
+            .collect();
+
+        let mut snapshot = BTreeMap::new();
+        snapshot.insert("fields".to_owned(), Value::Map(fields));
+        snapshot.insert(
+            "stale_ms".to_owned(),
+            Value::Integer(self.stale_since.elapsed().as_millis() as i64),
+        );
+        snapshot
+    }
+
+    fn restore(mut snapshot: BTreeMap<String, Value>) -> crate::Result<Self> {
+        let (fields, stale_ms) = match (snapshot.remove("fields"), snapshot.remove("stale_ms")) {
+            (Some(Value::Map(fields)), Some(Value::Integer(stale_ms))) => (fields, stale_ms),
+            _ => return Err("Invalid reduce state snapshot.".into()),
+        };
+
+        let fields = fields
+            .into_iter()
+            .map(|(name, merger)| match merger {
+                Value::Map(mut merger) => {
+                    let kind = merger.remove("kind").unwrap_or(Value::Null);
+                    let state = merger.remove("state").unwrap_or(Value::Null);
+                    restore_value_merger(&kind.to_string_lossy(), state)
+                        .map(|merger| (name, merger))
+                        .map_err(Into::into)
+                }
+                _ => Err(format!("Invalid snapshot of field {:?}.", name).into()),
+            })
+            .collect::<crate::Result<_>>()?;
+
+        let stale_since = Instant::now()
+            .checked_sub(Duration::from_millis(stale_ms as u64))
+            .unwrap_or_else(Instant::now);
+
+        Ok(Self {
+            fields,
+            stale_since,
+            metadata: EventMetadata::default(),
+        })
+    }
+
+    fn emit_snapshot_failed(error: crate::Error, operation: &'static str) {
+        emit!(&ReduceSnapshotFailed { error, operation });
+    }
+}
@@ -1,11 +1,15 @@
 use crate::{
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
-    event::{Event, VrlTarget},
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Event, VrlTarget,
+    },
     internal_events::{RemapMappingAbort, RemapMappingError},
     transforms::{FunctionTransform, Transform},
     Result,
 };
 
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use shared::TimeZone;
 use snafu::{ResultExt, Snafu};
@@ -13,7 +17,7 @@ use std::fs::File;
 use std::io::{self, Read};
 use std::path::PathBuf;
 use vrl::diagnostic::Formatter;
-use vrl::{Program, Runtime, Terminate};
+use vrl::{state, Program, Runtime, Terminate};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
 #[serde(deny_unknown_fields, default)]
@@ -26,6 +30,8 @@ pub struct RemapConfig {
     pub drop_on_error: bool,
     #[serde(default = "crate::serde::default_true")]
     pub drop_on_abort: bool,
+    #[derivative(Default(value = "state::Cache::DEFAULT_CAPACITY"))]
+    pub cache_capacity: usize,
 }
 
 inventory::submit! {
@@ -61,6 +67,7 @@ pub struct Remap {
     timezone: TimeZone,
     drop_on_error: bool,
     drop_on_abort: bool,
+    cache_capacity: usize,
 }
 
 impl Remap {
@@ -95,10 +102,11 @@ impl Remap {
 
         Ok(Remap {
             program,
-            runtime: Runtime::default(),
+            runtime: Runtime::new(state::Runtime::with_cache_capacity(config.cache_capacity)),
             timezone: config.timezone,
             drop_on_error: config.drop_on_error,
             drop_on_abort: config.drop_on_abort,
+            cache_capacity: config.cache_capacity,
         })
     }
 
@@ -112,10 +120,11 @@ impl Clone for Remap {
     fn clone(&self) -> Self {
         Self {
             program: self.program.clone(),
-            runtime: Runtime::default(),
+            runtime: Runtime::new(state::Runtime::with_cache_capacity(self.cache_capacity)),
             timezone: self.timezone,
             drop_on_error: self.drop_on_error,
             drop_on_abort: self.drop_on_abort,
+            cache_capacity: self.cache_capacity,
         }
     }
 }
@@ -144,13 +153,19 @@ impl FunctionTransform for Remap {
         let result = self
             .runtime
             .resolve(&mut target, &self.program, &self.timezone);
+        let metrics = self.runtime.take_emitted_metrics();
         self.runtime.clear();
 
+        // Metrics emitted by `emit_metric` are sent along with the events,
+        // unless the program failed, as they may be derived from a partial
+        // mapping. They're also sent if it aborted, so that a program can emit
+        // metrics instead of the event.
         match result {
             Ok(_) => {
                 for event in target.into_events() {
                     output.push(event)
                 }
+                output.extend(metrics.into_iter().map(emitted_metric));
             }
             Err(Terminate::Abort(_)) => {
                 emit!(&RemapMappingAbort {
@@ -160,6 +175,7 @@ impl FunctionTransform for Remap {
                 if !self.drop_on_abort {
                     output.push(original_event.expect("event will be set"))
                 }
+                output.extend(metrics.into_iter().map(emitted_metric));
             }
             Err(Terminate::Error(error)) => {
                 emit!(&RemapMappingError {
@@ -175,6 +191,36 @@ impl FunctionTransform for Remap {
     }
 }
 
+fn emitted_metric(metric: state::EmittedMetric) -> Event {
+    let (kind, value) = match metric.kind {
+        state::EmittedMetricKind::Counter => (
+            MetricKind::Incremental,
+            MetricValue::Counter {
+                value: metric.value,
+            },
+        ),
+        state::EmittedMetricKind::Gauge => (
+            MetricKind::Absolute,
+            MetricValue::Gauge {
+                value: metric.value,
+            },
+        ),
+        state::EmittedMetricKind::Histogram => (
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                samples: vector_core::samples![metric.value => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        ),
+    };
+
+    Metric::new(metric.name, kind, value)
+        .with_namespace(metric.namespace)
+        .with_tags((!metric.tags.is_empty()).then(|| metric.tags))
+        .with_timestamp(Some(Utc::now()))
+        .into()
+}
+
 #[derive(Debug, Snafu)]
 pub enum BuildError {
     #[snafu(display("must provide exactly one of `source` or `file` configuration"))]
@@ -190,10 +236,7 @@ pub enum BuildError {
 mod tests {
     use super::*;
     use crate::{
-        event::{
-            metric::{MetricKind, MetricValue},
-            LogEvent, Metric, Value,
-        },
+        event::{LogEvent, Value},
         transforms::test::transform_one,
     };
     use indoc::{formatdoc, indoc};
@@ -251,6 +294,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
         assert!(tform.runtime().is_empty());
@@ -279,6 +323,30 @@ mod tests {
         assert!(tform.runtime().is_empty());
     }
 
+    #[test]
+    fn check_remap_keeps_cache_between_events() {
+        let conf = RemapConfig {
+            source: Some(
+                r#".cached = cache_get(string!(.key)) ?? cache_set(string!(.key), .message)"#
+                    .to_string(),
+            ),
+            drop_on_error: true,
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf, &Default::default()).unwrap();
+
+        let mut event1 = LogEvent::from("first");
+        event1.insert("key", "foo");
+        let result1 = transform_one(&mut tform, event1.into()).unwrap();
+        assert_eq!(get_field_string(&result1, "cached"), "first");
+
+        let mut event2 = LogEvent::from("second");
+        event2.insert("key", "foo");
+        let result2 = transform_one(&mut tform, event2.into()).unwrap();
+        assert_eq!(get_field_string(&result2, "cached"), "first");
+        assert!(tform.runtime().is_empty());
+    }
+
     #[test]
     fn check_remap_adds() {
         let event = {
@@ -300,6 +368,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -335,6 +404,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -365,6 +435,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -393,6 +464,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -416,6 +488,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -444,6 +517,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -472,6 +546,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: true,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -499,6 +574,7 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -521,4 +597,89 @@ mod tests {
             )
         );
     }
+
+    fn emit_metrics(source: &str, event: Event) -> Vec<Event> {
+        let conf = RemapConfig {
+            source: Some(source.to_owned()),
+            drop_on_error: false,
+            drop_on_abort: true,
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf, &Default::default()).unwrap();
+
+        let mut result = vec![];
+        tform.transform(&mut result, event);
+        assert!(tform.runtime().is_empty());
+        result
+    }
+
+    #[test]
+    fn check_remap_emits_metrics() {
+        let result = emit_metrics(
+            indoc! {r#"
+                emit_metric("requests_total", 1, tags: {"status": .status})
+                emit_metric("response_time_seconds", float!(.duration), type: "histogram", namespace: "app")
+            "#},
+            {
+                let mut event = LogEvent::from("augment me");
+                event.insert("status", 200);
+                event.insert("duration", 0.25);
+                Event::from(event)
+            },
+        );
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(get_field_string(&result[0], "message"), "augment me");
+
+        let counter = result[1].as_metric();
+        assert_eq!(counter.name(), "requests_total");
+        assert_eq!(counter.kind(), MetricKind::Incremental);
+        assert_eq!(counter.value(), &MetricValue::Counter { value: 1.0 });
+        assert_eq!(counter.tag_value("status"), Some("200".to_owned()));
+        assert!(counter.timestamp().is_some());
+
+        let histogram = result[2].as_metric();
+        assert_eq!(histogram.name(), "response_time_seconds");
+        assert_eq!(histogram.namespace(), Some("app"));
+        assert_eq!(
+            histogram.value(),
+            &MetricValue::Distribution {
+                samples: vector_core::samples![0.25 => 1],
+                statistic: StatisticKind::Histogram,
+            }
+        );
+        assert!(histogram.tags().is_none());
+    }
+
+    #[test]
+    fn check_remap_emits_metrics_instead_of_event() {
+        let result = emit_metrics(
+            indoc! {r#"
+                emit_metric("queue_depth", 12, type: "gauge")
+                abort
+            "#},
+            Event::from("augment me"),
+        );
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].as_metric().value(),
+            &MetricValue::Gauge { value: 12.0 }
+        );
+        assert_eq!(result[0].as_metric().kind(), MetricKind::Absolute);
+    }
+
+    #[test]
+    fn check_remap_error_discards_metrics() {
+        let result = emit_metrics(
+            indoc! {r#"
+                emit_metric("requests_total", 1)
+                .not_an_int = int!(.message)
+            "#},
+            Event::from("augment me"),
+        );
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(get_field_string(&result[0], "message"), "augment me");
+    }
 }
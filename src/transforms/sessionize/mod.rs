@@ -0,0 +1,658 @@
+//! Groups events into sessions of activity, such as the visits of a user to a
+//! website.
+//!
+//! A session lasts as long as its events are no further apart than the
+//! inactivity gap. Events are either passed through with the ID of their
+//! session, or consumed into a summary of the session once it ends.
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent, Value},
+    internal_events::{
+        ComponentAllocatedBytes, SessionizeSessionEnded, SessionizeSessionStarted,
+        SessionizeSessionsRestored,
+    },
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use bytes::BytesMut;
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{mem, path::PathBuf, pin::Pin};
+use tokio::time::{Duration, Instant};
+use vector_core::ByteSizeOf;
+
+mod snapshot;
+
+use crate::transforms::snapshot::{Snapshot, Snapshots};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SessionizeConfig {
+    /// The fields identifying whose session an event belongs to, such as a
+    /// user ID or a client IP.
+    pub group_by: Vec<String>,
+    /// How long a session lasts without events before it ends.
+    #[serde(default = "default_inactivity_gap_secs")]
+    pub inactivity_gap_secs: u64,
+    #[serde(default)]
+    pub mode: SessionizeMode,
+    #[serde(default = "default_session_id_field")]
+    pub session_id_field: String,
+    /// The field holding the page an event was recorded on, for the entry and
+    /// exit pages of session summaries.
+    pub page_field: Option<String>,
+    /// How many sessions are tracked at once, the least recently active ones
+    /// being ended early to make room for new ones.
+    #[serde(default = "default_max_sessions")]
+    pub max_sessions: usize,
+    /// Snapshot the active sessions into the data directory, and restore them
+    /// on startup, rather than ending them on shutdown.
+    pub persistence: Option<PersistenceConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionizeMode {
+    /// Passes events through, with the ID of their session.
+    Enrich,
+    /// Consumes events, emitting a summary of each session once it ends.
+    Summary,
+}
+
+impl Default for SessionizeMode {
+    fn default() -> Self {
+        Self::Enrich
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceConfig {
+    /// The directory the snapshots are kept in, overriding the global
+    /// `data_dir`.
+    pub data_dir: Option<PathBuf>,
+
+    /// How often the active sessions are snapshotted, in addition to on
+    /// shutdown.
+    #[serde(default = "default_snapshot_interval_secs")]
+    pub snapshot_interval_secs: u64,
+}
+
+const fn default_inactivity_gap_secs() -> u64 {
+    30 * 60
+}
+
+fn default_session_id_field() -> String {
+    "session_id".to_owned()
+}
+
+const fn default_max_sessions() -> usize {
+    100_000
+}
+
+const fn default_snapshot_interval_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    TransformDescription::new::<SessionizeConfig>("sessionize")
+}
+
+impl GenerateConfig for SessionizeConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            group_by: vec!["user_id".to_owned()],
+            inactivity_gap_secs: default_inactivity_gap_secs(),
+            mode: SessionizeMode::default(),
+            session_id_field: default_session_id_field(),
+            page_field: None,
+            max_sessions: default_max_sessions(),
+            persistence: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sessionize")]
+impl TransformConfig for SessionizeConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        if self.group_by.is_empty() {
+            return Err("`group_by` must contain at least one field.".into());
+        }
+        if self.inactivity_gap_secs == 0 {
+            return Err("`inactivity_gap_secs` must be greater than zero.".into());
+        }
+        if self.max_sessions == 0 {
+            return Err("`max_sessions` must be greater than zero.".into());
+        }
+
+        let mut sessionize = Sessionize::new(self);
+
+        // Transforms built outside of a running topology, e.g. in unit tests,
+        // don't persist their state.
+        if let (Some(persistence), Some(key)) = (&self.persistence, &context.key) {
+            if persistence.snapshot_interval_secs == 0 {
+                return Err(
+                    "`persistence.snapshot_interval_secs` must be greater than zero".into(),
+                );
+            }
+            let data_dir = context
+                .globals
+                .resolve_and_make_data_subdir(persistence.data_dir.as_ref(), key)?;
+            sessionize.restore_snapshot(Snapshots::new(
+                data_dir,
+                Duration::from_secs(persistence.snapshot_interval_secs),
+            ));
+        }
+
+        Ok(Transform::task(sessionize))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sessionize"
+    }
+}
+
+/// Why a session ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EndReason {
+    /// No event was received for the inactivity gap.
+    Inactive,
+    /// The session was ended early to make room for a new one.
+    Evicted,
+    /// Vector shut down without persisting the session.
+    Shutdown,
+}
+
+impl EndReason {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Inactive => "inactive",
+            Self::Evicted => "evicted",
+            Self::Shutdown => "shutdown",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Session {
+    id: String,
+    /// When the first and last events of the session happened, according to
+    /// their timestamps.
+    started_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    events: u64,
+    entry_page: Option<Value>,
+    exit_page: Option<Value>,
+    /// When the last event of the session was received, which it expires from.
+    last_active: Instant,
+    /// The metadata of the events consumed into the session's summary.
+    metadata: EventMetadata,
+}
+
+impl Session {
+    fn new(timestamp: DateTime<Utc>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            started_at: timestamp,
+            last_seen_at: timestamp,
+            events: 0,
+            entry_page: None,
+            exit_page: None,
+            last_active: Instant::now(),
+            metadata: EventMetadata::default(),
+        }
+    }
+
+    fn add(&mut self, timestamp: DateTime<Utc>, page: Option<Value>) {
+        self.started_at = self.started_at.min(timestamp);
+        self.last_seen_at = self.last_seen_at.max(timestamp);
+        self.events += 1;
+        if page.is_some() {
+            if self.entry_page.is_none() {
+                self.entry_page = page.clone();
+            }
+            self.exit_page = page;
+        }
+        self.last_active = Instant::now();
+    }
+
+    fn summarize(
+        self,
+        discriminant: &Discriminant,
+        group_by: &[String],
+        session_id_field: &str,
+    ) -> LogEvent {
+        let mut log = LogEvent::new_with_metadata(self.metadata);
+        for (field, value) in group_by.iter().zip(discriminant.values()) {
+            if let Some(value) = value {
+                log.insert(field.as_str(), value.clone());
+            }
+        }
+        log.insert(session_id_field, self.id);
+        log.insert("started_at", self.started_at);
+        log.insert("ended_at", self.last_seen_at);
+        log.insert(
+            "duration_secs",
+            (self.last_seen_at - self.started_at).num_milliseconds() as f64 / 1000.0,
+        );
+        log.insert("events", self.events as i64);
+        if let Some(page) = self.entry_page {
+            log.insert("entry_page", page);
+        }
+        if let Some(page) = self.exit_page {
+            log.insert("exit_page", page);
+        }
+        log.insert(log_schema().timestamp_key(), self.last_seen_at);
+        log
+    }
+}
+
+impl ByteSizeOf for Session {
+    fn allocated_bytes(&self) -> usize {
+        self.id.allocated_bytes()
+            + self.entry_page.size_of()
+            + self.exit_page.size_of()
+            + self.metadata.allocated_bytes()
+    }
+}
+
+pub struct Sessionize {
+    group_by: Vec<String>,
+    inactivity_gap: Duration,
+    mode: SessionizeMode,
+    session_id_field: String,
+    page_field: Option<String>,
+    max_sessions: usize,
+    /// The active sessions, from the most to the least recently active.
+    sessions: LruCache<Discriminant, Session>,
+    snapshots: Option<Snapshots<Session>>,
+}
+
+impl Sessionize {
+    fn new(config: &SessionizeConfig) -> Self {
+        Self {
+            group_by: config.group_by.clone(),
+            inactivity_gap: Duration::from_secs(config.inactivity_gap_secs),
+            mode: config.mode,
+            session_id_field: config.session_id_field.clone(),
+            page_field: config.page_field.clone(),
+            max_sessions: config.max_sessions,
+            sessions: LruCache::unbounded(),
+            snapshots: None,
+        }
+    }
+
+    /// Restores the sessions snapshotted by the previous run, and snapshots
+    /// them from now on.
+    fn restore_snapshot(&mut self, snapshots: Snapshots<Session>) {
+        match snapshots.load(&self.group_by) {
+            Ok(mut sessions) => {
+                // Restore from the least to the most recently active, so that
+                // the most recent sessions are the last to be evicted.
+                sessions.sort_by_key(|(_, session)| session.last_active);
+                if !sessions.is_empty() {
+                    emit!(&SessionizeSessionsRestored {
+                        count: sessions.len()
+                    });
+                }
+                for (discriminant, session) in sessions {
+                    self.sessions.put(discriminant, session);
+                }
+            }
+            Err(error) => Session::emit_snapshot_failed(error, "read"),
+        }
+        self.snapshots = Some(snapshots);
+    }
+
+    fn snapshot(&self) -> BytesMut {
+        Snapshots::encode(self.sessions.iter(), &self.group_by)
+    }
+
+    fn transform_one(&mut self, output: &mut Vec<Event>, event: Event) {
+        let mut log = event.into_log();
+        let discriminant = Discriminant::from_log_event(&log, &self.group_by);
+        // Events missing all of the fields can't be told apart.
+        if discriminant.values().iter().all(Option::is_none) {
+            output.push(log.into());
+            return;
+        }
+
+        let timestamp = match log.get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => Utc::now(),
+        };
+        let page = self
+            .page_field
+            .as_ref()
+            .and_then(|field| log.get(field.as_str()))
+            .cloned();
+
+        // Sessions also end once their events are further apart than the gap,
+        // for events replayed faster than they happened.
+        let gap = chrono::Duration::from_std(self.inactivity_gap)
+            .unwrap_or_else(|_| chrono::Duration::max_value());
+        let ended = matches!(
+            self.sessions.peek(&discriminant),
+            Some(session) if session.last_active.elapsed() >= self.inactivity_gap
+                || timestamp - session.last_seen_at > gap
+        );
+        if ended {
+            if let Some(session) = self.sessions.pop(&discriminant) {
+                self.end(output, discriminant.clone(), session, EndReason::Inactive);
+            }
+        }
+
+        if !self.sessions.contains(&discriminant) {
+            if self.sessions.len() >= self.max_sessions {
+                if let Some((evicted, session)) = self.sessions.pop_lru() {
+                    self.end(output, evicted, session, EndReason::Evicted);
+                }
+            }
+            emit!(&SessionizeSessionStarted);
+            self.sessions
+                .put(discriminant.clone(), Session::new(timestamp));
+        }
+
+        let session = self
+            .sessions
+            .get_mut(&discriminant)
+            .expect("session was just inserted");
+        session.add(timestamp, page);
+        match self.mode {
+            SessionizeMode::Enrich => {
+                log.insert(self.session_id_field.as_str(), session.id.clone());
+                output.push(log.into());
+            }
+            SessionizeMode::Summary => {
+                let (_, metadata) = log.into_parts();
+                session.metadata.merge(metadata);
+            }
+        }
+    }
+
+    fn end(
+        &self,
+        output: &mut Vec<Event>,
+        discriminant: Discriminant,
+        session: Session,
+        reason: EndReason,
+    ) {
+        emit!(&SessionizeSessionEnded {
+            reason: reason.as_str(),
+            events: session.events,
+        });
+        if self.mode == SessionizeMode::Summary {
+            output.push(
+                session
+                    .summarize(&discriminant, &self.group_by, &self.session_id_field)
+                    .into(),
+            );
+        }
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        while matches!(
+            self.sessions.peek_lru(),
+            Some((_, session)) if session.last_active.elapsed() >= self.inactivity_gap
+        ) {
+            if let Some((discriminant, session)) = self.sessions.pop_lru() {
+                self.end(output, discriminant, session, EndReason::Inactive);
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        while let Some((discriminant, session)) = self.sessions.pop_lru() {
+            self.end(output, discriminant, session, EndReason::Shutdown);
+        }
+    }
+
+    /// The approximate number of bytes held by the active sessions.
+    fn allocated_bytes(&self) -> usize {
+        self.sessions
+            .iter()
+            .map(|(discriminant, session)| {
+                mem::size_of::<Discriminant>()
+                    + discriminant
+                        .values()
+                        .iter()
+                        .map(ByteSizeOf::size_of)
+                        .sum::<usize>()
+                    + session.size_of()
+            })
+            .sum()
+    }
+}
+
+impl TaskTransform for Sessionize {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let poll_period = me.inactivity_gap.min(Duration::from_secs(1));
+        let mut flush_stream = tokio::time::interval(poll_period);
+
+        let snapshots = me.snapshots.take();
+        let mut snapshot_stream = tokio::time::interval(
+            snapshots
+                .as_ref()
+                .map_or(poll_period, |snapshots| snapshots.interval),
+        );
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.tick() => {
+                      me.flush_into(&mut output);
+                      emit!(&ComponentAllocatedBytes {
+                          byte_size: me.allocated_bytes(),
+                      });
+                      false
+                    }
+                    _ = snapshot_stream.tick(), if snapshots.is_some() => {
+                      if let Some(snapshots) = &snapshots {
+                        snapshots.save(me.snapshot()).await;
+                      }
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          // Keep the active sessions for the next run rather
+                          // than ending them early, unless they can't be
+                          // persisted.
+                          let persisted = match &snapshots {
+                            Some(snapshots) => snapshots.save(me.snapshot()).await,
+                            None => false,
+                          };
+                          if !persisted {
+                            me.flush_all_into(&mut output);
+                          }
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(&mut output, event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config(mode: &str) -> SessionizeConfig {
+        toml::from_str(&format!(
+            r#"
+            group_by = ["user_id"]
+            inactivity_gap_secs = 60
+            mode = "{}"
+            page_field = "url"
+            "#,
+            mode
+        ))
+        .unwrap()
+    }
+
+    fn click(user_id: &str, url: &str, secs: i64) -> Event {
+        let mut log = LogEvent::from("click");
+        log.insert("user_id", user_id);
+        log.insert("url", url);
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.timestamp(1_600_000_000 + secs, 0),
+        );
+        log.into()
+    }
+
+    fn session_ids(output: &[Event]) -> Vec<String> {
+        output
+            .iter()
+            .map(|event| event.as_log()["session_id"].to_string_lossy())
+            .collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SessionizeConfig>();
+    }
+
+    #[test]
+    fn enriches_events_with_session_ids() {
+        let mut sessionize = Sessionize::new(&config("enrich"));
+        let mut output = Vec::new();
+
+        sessionize.transform_one(&mut output, click("alice", "/", 0));
+        sessionize.transform_one(&mut output, click("bob", "/", 10));
+        sessionize.transform_one(&mut output, click("alice", "/cart", 50));
+        // Further from the previous event than the gap.
+        sessionize.transform_one(&mut output, click("alice", "/", 200));
+        sessionize.transform_one(&mut output, LogEvent::from("anonymous").into());
+
+        assert_eq!(output.len(), 5);
+        assert!(!output[4].as_log().contains("session_id"));
+        let ids = session_ids(&output[..4]);
+        assert_eq!(ids[0], ids[2]);
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[0], ids[3]);
+    }
+
+    #[test]
+    fn summarizes_ended_sessions() {
+        let mut sessionize = Sessionize::new(&config("summary"));
+        let mut output = Vec::new();
+
+        sessionize.transform_one(&mut output, click("alice", "/", 0));
+        sessionize.transform_one(&mut output, click("alice", "/products", 20));
+        sessionize.transform_one(&mut output, click("alice", "/cart", 45));
+        assert!(output.is_empty());
+
+        sessionize.transform_one(&mut output, click("alice", "/", 500));
+        assert_eq!(output.len(), 1);
+        let summary = output[0].as_log();
+        assert_eq!(summary["user_id"], "alice".into());
+        assert_eq!(summary["events"], 3.into());
+        assert_eq!(summary["duration_secs"], 45.0.into());
+        assert_eq!(summary["entry_page"], "/".into());
+        assert_eq!(summary["exit_page"], "/cart".into());
+        assert_eq!(
+            summary["started_at"],
+            Utc.timestamp(1_600_000_000, 0).into()
+        );
+
+        sessionize.flush_all_into(&mut output);
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[1].as_log()["events"], 1.into());
+        assert_ne!(session_ids(&output)[0], session_ids(&output)[1]);
+    }
+
+    #[tokio::test]
+    async fn ends_inactive_sessions() {
+        tokio::time::pause();
+        let mut sessionize = Sessionize::new(&config("summary"));
+        let mut output = Vec::new();
+
+        sessionize.transform_one(&mut output, click("alice", "/", 0));
+        tokio::time::advance(Duration::from_secs(30)).await;
+        sessionize.transform_one(&mut output, click("bob", "/", 30));
+        sessionize.flush_into(&mut output);
+        assert!(output.is_empty());
+
+        tokio::time::advance(Duration::from_secs(45)).await;
+        sessionize.flush_into(&mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["user_id"], "alice".into());
+    }
+
+    #[test]
+    fn evicts_least_recently_active_sessions() {
+        let mut config = config("summary");
+        config.max_sessions = 2;
+        let mut sessionize = Sessionize::new(&config);
+        let mut output = Vec::new();
+
+        sessionize.transform_one(&mut output, click("alice", "/", 0));
+        sessionize.transform_one(&mut output, click("bob", "/", 1));
+        sessionize.transform_one(&mut output, click("alice", "/cart", 2));
+        sessionize.transform_one(&mut output, click("carol", "/", 3));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["user_id"], "bob".into());
+    }
+
+    #[tokio::test]
+    async fn restores_sessions_after_restart() {
+        let data_dir = crate::test_util::temp_dir();
+        let snapshots = || Snapshots::new(data_dir.clone(), Duration::from_secs(60));
+
+        let mut sessionize = Sessionize::new(&config("summary"));
+        let mut output = Vec::new();
+        sessionize.transform_one(&mut output, click("alice", "/", 0));
+        sessionize.transform_one(&mut output, click("alice", "/cart", 30));
+        assert!(snapshots().save(sessionize.snapshot()).await);
+
+        let mut sessionize = Sessionize::new(&config("summary"));
+        sessionize.restore_snapshot(snapshots());
+        sessionize.transform_one(&mut output, click("alice", "/checkout", 40));
+        sessionize.flush_all_into(&mut output);
+
+        assert_eq!(output.len(), 1);
+        let summary = output[0].as_log();
+        assert_eq!(summary["events"], 3.into());
+        assert_eq!(summary["entry_page"], "/".into());
+        assert_eq!(summary["exit_page"], "/checkout".into());
+        assert_eq!(summary["duration_secs"], 40.0.into());
+    }
+}
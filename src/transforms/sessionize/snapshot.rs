@@ -0,0 +1,71 @@
+//! Snapshots of the active sessions, so that they survive restarts.
+
+use super::Session;
+use crate::{
+    event::{EventMetadata, Value},
+    internal_events::SessionizeSnapshotFailed,
+    transforms::snapshot::Snapshot,
+};
+use std::collections::BTreeMap;
+use tokio::time::{Duration, Instant};
+
+impl Snapshot for Session {
+    const FILE_NAME: &'static str = "sessions.pb";
+
+    fn snapshot(&self) -> BTreeMap<String, Value> {
+        let mut snapshot = BTreeMap::new();
+        snapshot.insert("id".to_owned(), Value::from(self.id.clone()));
+        snapshot.insert("started_at".to_owned(), Value::from(self.started_at));
+        snapshot.insert("last_seen_at".to_owned(), Value::from(self.last_seen_at));
+        snapshot.insert("events".to_owned(), Value::Integer(self.events as i64));
+        if let Some(page) = &self.entry_page {
+            snapshot.insert("entry_page".to_owned(), page.clone());
+        }
+        if let Some(page) = &self.exit_page {
+            snapshot.insert("exit_page".to_owned(), page.clone());
+        }
+        snapshot.insert(
+            "idle_ms".to_owned(),
+            Value::Integer(self.last_active.elapsed().as_millis() as i64),
+        );
+        snapshot
+    }
+
+    fn restore(mut snapshot: BTreeMap<String, Value>) -> crate::Result<Self> {
+        let (id, started_at, last_seen_at, events, idle_ms) = match (
+            snapshot.remove("id"),
+            snapshot.remove("started_at"),
+            snapshot.remove("last_seen_at"),
+            snapshot.remove("events"),
+            snapshot.remove("idle_ms"),
+        ) {
+            (
+                Some(Value::Bytes(id)),
+                Some(Value::Timestamp(started_at)),
+                Some(Value::Timestamp(last_seen_at)),
+                Some(Value::Integer(events)),
+                Some(Value::Integer(idle_ms)),
+            ) => (id, started_at, last_seen_at, events, idle_ms),
+            _ => return Err("Invalid session snapshot.".into()),
+        };
+
+        let last_active = Instant::now()
+            .checked_sub(Duration::from_millis(idle_ms as u64))
+            .unwrap_or_else(Instant::now);
+
+        Ok(Self {
+            id: String::from_utf8_lossy(&id).into_owned(),
+            started_at,
+            last_seen_at,
+            events: events as u64,
+            entry_page: snapshot.remove("entry_page"),
+            exit_page: snapshot.remove("exit_page"),
+            last_active,
+            metadata: EventMetadata::default(),
+        })
+    }
+
+    fn emit_snapshot_failed(error: crate::Error, operation: &'static str) {
+        emit!(&SessionizeSnapshotFailed { error, operation });
+    }
+}
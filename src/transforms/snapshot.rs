@@ -0,0 +1,124 @@
+//! Snapshots of the state transforms hold in memory, so that it survives
+//! restarts.
+//!
+//! Each state is written as a length-delimited protobuf log event, like events
+//! in disk buffers, since it preserves the types of their values. The values the
+//! state was grouped by are kept under `group`, so that it's grouped again by
+//! the `group_by` fields of the next run.
+
+use crate::event::{discriminant::Discriminant, proto, Event, LogEvent, Value};
+use bytes::{Buf, Bytes, BytesMut};
+use prost::Message;
+use std::{collections::BTreeMap, io, marker::PhantomData, path::PathBuf, time::Duration};
+
+/// State of a transform that can be snapshotted.
+pub(super) trait Snapshot: Sized {
+    /// The name of the snapshot file in the data directory.
+    const FILE_NAME: &'static str;
+
+    /// Writes the state into the fields of its snapshot.
+    fn snapshot(&self) -> BTreeMap<String, Value>;
+
+    /// Builds back a state from the fields of its snapshot.
+    fn restore(snapshot: BTreeMap<String, Value>) -> crate::Result<Self>;
+
+    /// Reports a failure to `operation` ("read" or "write") the snapshot.
+    fn emit_snapshot_failed(error: crate::Error, operation: &'static str);
+}
+
+#[derive(Debug)]
+pub(super) struct Snapshots<T> {
+    path: PathBuf,
+    pub interval: Duration,
+    _state: PhantomData<T>,
+}
+
+impl<T: Snapshot> Snapshots<T> {
+    pub fn new(data_dir: PathBuf, interval: Duration) -> Self {
+        Self {
+            path: data_dir.join(T::FILE_NAME),
+            interval,
+            _state: PhantomData,
+        }
+    }
+
+    /// Reads the states snapshotted by the previous run, if any, grouping them
+    /// by the current `group_by` fields.
+    pub fn load(&self, group_by: &[String]) -> crate::Result<Vec<(Discriminant, T)>> {
+        let mut bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => Bytes::from(bytes),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut states = Vec::new();
+        while bytes.has_remaining() {
+            let event = Event::from(proto::EventWrapper::decode_length_delimited(&mut bytes)?);
+            let (mut snapshot, _) = event.into_log().into_parts();
+            let group = match snapshot.remove("group") {
+                Some(Value::Map(group)) => group,
+                _ => {
+                    warn!(
+                        message = "Failed to restore snapshotted state.",
+                        error = "Missing group."
+                    );
+                    continue;
+                }
+            };
+            match T::restore(snapshot) {
+                Ok(state) => {
+                    let mut group_event = LogEvent::default();
+                    for (field, value) in group {
+                        group_event.insert(field, value);
+                    }
+                    states.push((Discriminant::from_log_event(&group_event, group_by), state));
+                }
+                Err(error) => warn!(message = "Failed to restore snapshotted state.", %error),
+            }
+        }
+        Ok(states)
+    }
+
+    pub fn encode<'a>(
+        states: impl IntoIterator<Item = (&'a Discriminant, &'a T)>,
+        group_by: &[String],
+    ) -> BytesMut
+    where
+        T: 'a,
+    {
+        let mut buffer = BytesMut::new();
+        for (discriminant, state) in states {
+            let group = group_by
+                .iter()
+                .zip(discriminant.values())
+                .filter_map(|(field, value)| value.clone().map(|value| (field.clone(), value)))
+                .collect();
+            let mut snapshot = state.snapshot();
+            snapshot.insert("group".to_owned(), Value::Map(group));
+
+            proto::EventWrapper::from(Event::from(LogEvent::from(snapshot)))
+                .encode_length_delimited(&mut buffer)
+                .expect("The buffer grows as needed.");
+        }
+        buffer
+    }
+
+    /// Replaces the snapshot, returning whether it succeeded.
+    pub async fn save(&self, snapshot: BytesMut) -> bool {
+        // Write to a temporary file first, so that a crash doesn't leave a
+        // truncated snapshot behind.
+        let temp_path = self.path.with_extension("tmp");
+        let result = match tokio::fs::write(&temp_path, snapshot).await {
+            Ok(()) => tokio::fs::rename(&temp_path, &self.path).await,
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(error) => {
+                T::emit_snapshot_failed(error.into(), "write");
+                false
+            }
+        }
+    }
+}
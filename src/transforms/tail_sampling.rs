@@ -0,0 +1,500 @@
+//! Tail-based sampling of traces.
+//!
+//! Spans are held per trace for a decision window, after which their whole
+//! trace is kept if any span matches one of the policies, and sampled by its
+//! trace ID otherwise, so that every span of a trace shares its fate.
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{Event, Value},
+    internal_events::{TailSamplingLateSpan, TailSamplingTraceDecided},
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use futures::{stream, Stream, StreamExt};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+};
+use tokio::time::{Duration, Instant};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TailSamplingConfig {
+    #[serde(default = "default_trace_id_field")]
+    pub trace_id_field: String,
+    #[serde(default = "default_decision_wait_ms")]
+    pub decision_wait_ms: u64,
+    #[serde(default = "default_max_traces")]
+    pub max_traces: usize,
+    /// Keep one in `rate` of the traces matching no policy.
+    #[serde(default = "default_rate")]
+    pub rate: u64,
+    #[serde(default)]
+    pub policies: Vec<PolicyConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PolicyConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: PolicyKind,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyKind {
+    /// Keeps traces with a span flagged as an error.
+    Error {
+        #[serde(default = "default_error_field")]
+        field: String,
+    },
+    /// Keeps traces with a span lasting at least `threshold_ms`.
+    Latency {
+        threshold_ms: u64,
+        /// The field holding the duration of spans, in nanoseconds.
+        #[serde(default = "default_duration_field")]
+        duration_field: String,
+    },
+    /// Keeps traces with a span matching a condition.
+    Condition { condition: AnyCondition },
+}
+
+fn default_trace_id_field() -> String {
+    "trace_id".to_owned()
+}
+
+const fn default_decision_wait_ms() -> u64 {
+    10_000
+}
+
+const fn default_max_traces() -> usize {
+    50_000
+}
+
+const fn default_rate() -> u64 {
+    10
+}
+
+fn default_error_field() -> String {
+    "error".to_owned()
+}
+
+fn default_duration_field() -> String {
+    "duration".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<TailSamplingConfig>("tail_sampling")
+}
+
+impl GenerateConfig for TailSamplingConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            trace_id_field: default_trace_id_field(),
+            decision_wait_ms: default_decision_wait_ms(),
+            max_traces: default_max_traces(),
+            rate: default_rate(),
+            policies: vec![
+                PolicyConfig {
+                    name: "errors".to_owned(),
+                    kind: PolicyKind::Error {
+                        field: default_error_field(),
+                    },
+                },
+                PolicyConfig {
+                    name: "slow".to_owned(),
+                    kind: PolicyKind::Latency {
+                        threshold_ms: 1000,
+                        duration_field: default_duration_field(),
+                    },
+                },
+            ],
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "tail_sampling")]
+impl TransformConfig for TailSamplingConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        if self.rate == 0 {
+            return Err("`rate` must be greater than zero.".into());
+        }
+        if self.max_traces == 0 {
+            return Err("`max_traces` must be greater than zero.".into());
+        }
+
+        let policies = self.build_policies(&context.enrichment_tables)?;
+        Ok(Transform::task(TailSampling::new(self, policies)))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "tail_sampling"
+    }
+}
+
+impl TailSamplingConfig {
+    fn build_policies(
+        &self,
+        enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Vec<Policy>> {
+        self.policies
+            .iter()
+            .map(|policy| {
+                let rule = match &policy.kind {
+                    PolicyKind::Error { field } => Rule::Error(field.clone()),
+                    PolicyKind::Latency {
+                        threshold_ms,
+                        duration_field,
+                    } => Rule::Latency {
+                        threshold_ns: threshold_ms.saturating_mul(1_000_000),
+                        duration_field: duration_field.clone(),
+                    },
+                    PolicyKind::Condition { condition } => {
+                        Rule::Condition(condition.build(enrichment_tables)?)
+                    }
+                };
+                Ok(Policy {
+                    name: policy.name.clone(),
+                    rule,
+                })
+            })
+            .collect()
+    }
+}
+
+enum Rule {
+    Error(String),
+    Latency {
+        threshold_ns: u64,
+        duration_field: String,
+    },
+    Condition(Box<dyn Condition>),
+}
+
+struct Policy {
+    name: String,
+    rule: Rule,
+}
+
+impl Policy {
+    fn matches(&self, span: &Event) -> bool {
+        match &self.rule {
+            Rule::Error(field) => match span.as_log().get(field.as_str()) {
+                Some(Value::Boolean(error)) => *error,
+                Some(Value::Integer(error)) => *error != 0,
+                _ => false,
+            },
+            Rule::Latency {
+                threshold_ns,
+                duration_field,
+            } => match span.as_log().get(duration_field.as_str()) {
+                Some(Value::Integer(duration)) => {
+                    *duration >= 0 && *duration as u64 >= *threshold_ns
+                }
+                Some(Value::Float(duration)) => *duration >= *threshold_ns as f64,
+                _ => false,
+            },
+            Rule::Condition(condition) => condition.check(span),
+        }
+    }
+}
+
+struct PendingTrace {
+    spans: Vec<Event>,
+    /// The first policy matched by a span of the trace.
+    policy: Option<usize>,
+}
+
+pub struct TailSampling {
+    trace_id_field: String,
+    decision_wait: Duration,
+    max_traces: usize,
+    rate: u64,
+    policies: Vec<Policy>,
+    pending: HashMap<String, PendingTrace>,
+    /// The traces of `pending`, in the order their decision is due.
+    deadlines: VecDeque<(Instant, String)>,
+    /// Whether recently decided traces were kept, for their late spans.
+    decided: LruCache<String, bool>,
+}
+
+impl TailSampling {
+    fn new(config: &TailSamplingConfig, policies: Vec<Policy>) -> Self {
+        Self {
+            trace_id_field: config.trace_id_field.clone(),
+            decision_wait: Duration::from_millis(config.decision_wait_ms),
+            max_traces: config.max_traces,
+            rate: config.rate,
+            policies,
+            pending: HashMap::new(),
+            deadlines: VecDeque::new(),
+            decided: LruCache::new(config.max_traces),
+        }
+    }
+
+    fn transform_one(&mut self, output: &mut Vec<Event>, span: Event) {
+        let trace_id = match span.as_log().get(self.trace_id_field.as_str()) {
+            Some(trace_id) => trace_id.to_string_lossy(),
+            // Events that aren't spans aren't sampled.
+            None => {
+                output.push(span);
+                return;
+            }
+        };
+
+        if let Some(kept) = self.decided.get(&trace_id) {
+            emit!(&TailSamplingLateSpan { kept: *kept });
+            if *kept {
+                output.push(span);
+            }
+            return;
+        }
+
+        if !self.pending.contains_key(&trace_id) {
+            if self.pending.len() >= self.max_traces {
+                // Make room by deciding the oldest trace early.
+                if let Some((_, oldest)) = self.deadlines.pop_front() {
+                    self.decide(output, oldest);
+                }
+            }
+            self.deadlines
+                .push_back((Instant::now() + self.decision_wait, trace_id.clone()));
+            self.pending.insert(
+                trace_id.clone(),
+                PendingTrace {
+                    spans: Vec::new(),
+                    policy: None,
+                },
+            );
+        }
+
+        let trace = self
+            .pending
+            .get_mut(&trace_id)
+            .expect("trace was just inserted");
+        if trace.policy.is_none() {
+            trace.policy = self
+                .policies
+                .iter()
+                .position(|policy| policy.matches(&span));
+        }
+        trace.spans.push(span);
+    }
+
+    fn decide(&mut self, output: &mut Vec<Event>, trace_id: String) {
+        let trace = match self.pending.remove(&trace_id) {
+            Some(trace) => trace,
+            None => return,
+        };
+
+        let (kept, sample_rate, policy) = match trace.policy {
+            Some(index) => (true, 1, self.policies[index].name.as_str()),
+            None => (
+                seahash::hash(trace_id.as_bytes()) % self.rate == 0,
+                self.rate,
+                "probabilistic",
+            ),
+        };
+        emit!(&TailSamplingTraceDecided {
+            policy,
+            kept,
+            spans: trace.spans.len(),
+        });
+
+        if kept {
+            output.extend(trace.spans.into_iter().map(|mut span| {
+                span.as_mut_log()
+                    .insert("sample_rate", sample_rate.to_string());
+                span
+            }));
+        }
+        self.decided.put(trace_id, kept);
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let now = Instant::now();
+        while matches!(self.deadlines.front(), Some((deadline, _)) if *deadline <= now) {
+            if let Some((_, trace_id)) = self.deadlines.pop_front() {
+                self.decide(output, trace_id);
+            }
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        while let Some((_, trace_id)) = self.deadlines.pop_front() {
+            self.decide(output, trace_id);
+        }
+    }
+}
+
+impl TaskTransform for TailSampling {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut me = self;
+
+        let poll_period = me
+            .decision_wait
+            .min(Duration::from_secs(1))
+            .max(Duration::from_millis(1));
+        let mut flush_stream = tokio::time::interval(poll_period);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = Vec::new();
+                let done = tokio::select! {
+                    _ = flush_stream.tick() => {
+                      me.flush_into(&mut output);
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.flush_all_into(&mut output);
+                          true
+                        }
+                        Some(event) => {
+                          me.transform_one(&mut output, event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn config(rate: u64) -> TailSamplingConfig {
+        toml::from_str(&format!(
+            r#"
+            decision_wait_ms = 100
+            rate = {}
+
+            [[policies]]
+            name = "errors"
+            type = "error"
+
+            [[policies]]
+            name = "slow"
+            type = "latency"
+            threshold_ms = 500
+
+            [[policies]]
+            name = "checkout"
+            type = "condition"
+            condition = '.service == "checkout"'
+            "#,
+            rate
+        ))
+        .unwrap()
+    }
+
+    fn build(config: &TailSamplingConfig) -> TailSampling {
+        let policies = config.build_policies(&Default::default()).unwrap();
+        TailSampling::new(config, policies)
+    }
+
+    fn span(trace_id: &str, fields: &[(&str, Value)]) -> Event {
+        let mut log = LogEvent::from("span");
+        log.insert("trace_id", trace_id);
+        for (field, value) in fields {
+            log.insert(*field, value.clone());
+        }
+        log.into()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TailSamplingConfig>();
+    }
+
+    #[test]
+    fn keeps_traces_matching_policies() {
+        // No trace is kept by chance with such a rate.
+        let mut sampling = build(&config(u64::MAX));
+        let mut output = Vec::new();
+
+        sampling.transform_one(&mut output, span("a", &[]));
+        sampling.transform_one(&mut output, span("a", &[("error", 1.into())]));
+        sampling.transform_one(&mut output, span("b", &[("duration", 600_000_000.into())]));
+        sampling.transform_one(&mut output, span("c", &[("service", "checkout".into())]));
+        sampling.transform_one(&mut output, span("d", &[("error", 0.into())]));
+        sampling.transform_one(&mut output, span("e", &[("duration", 1_000.into())]));
+        sampling.transform_one(&mut output, LogEvent::from("not a span").into());
+        assert_eq!(output.len(), 1);
+
+        sampling.flush_all_into(&mut output);
+        let kept: Vec<_> = output
+            .iter()
+            .filter_map(|event| event.as_log().get("trace_id"))
+            .map(Value::to_string_lossy)
+            .collect();
+        assert_eq!(kept, vec!["a", "a", "b", "c"]);
+        assert_eq!(output[1].as_log()["sample_rate"], "1".into());
+
+        // Late spans share the fate of their trace.
+        let mut late = Vec::new();
+        sampling.transform_one(&mut late, span("a", &[]));
+        sampling.transform_one(&mut late, span("d", &[]));
+        assert_eq!(late.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn decides_after_decision_wait() {
+        tokio::time::pause();
+        let mut sampling = build(&config(1));
+        let mut output = Vec::new();
+
+        sampling.transform_one(&mut output, span("a", &[]));
+        sampling.flush_into(&mut output);
+        assert!(output.is_empty());
+
+        tokio::time::advance(Duration::from_millis(150)).await;
+        sampling.flush_into(&mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["sample_rate"], "1".into());
+    }
+
+    #[test]
+    fn decides_oldest_traces_early() {
+        let mut config = config(1);
+        config.max_traces = 2;
+        let mut sampling = build(&config);
+        let mut output = Vec::new();
+
+        sampling.transform_one(&mut output, span("a", &[]));
+        sampling.transform_one(&mut output, span("b", &[]));
+        sampling.transform_one(&mut output, span("c", &[]));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["trace_id"], "a".into());
+    }
+}
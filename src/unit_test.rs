@@ -35,6 +35,11 @@ pub struct Opts {
         use_delimiter(true)
     )]
     pub config_dirs: Vec<PathBuf>,
+
+    /// Overwrite the golden files of the tests with the actual outputs instead
+    /// of comparing against them.
+    #[structopt(long)]
+    update_golden_files: bool,
 }
 
 impl Opts {
@@ -69,6 +74,7 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
     match config::build_unit_tests(&paths).await {
         Ok(mut tests) => {
             tests.iter_mut().for_each(|t| {
+                t.update_golden_files = opts.update_golden_files;
                 let (test_inspections, test_errors) = t.run();
                 if !test_inspections.is_empty() {
                     aggregated_test_inspections.push((t.name.clone(), test_inspections));
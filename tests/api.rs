@@ -134,7 +134,8 @@ mod tests {
 
     fn start_server_with_config(config: &Config) -> Server {
         let (_, shutdown_rx) = watch::channel(HashMap::new());
-        api::Server::start(&config, shutdown_rx)
+        let (signal_tx, _) = tokio::sync::mpsc::channel(1);
+        api::Server::start(&config, shutdown_rx, signal_tx)
     }
 
     fn make_client(addr: SocketAddr) -> Client {
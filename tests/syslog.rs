@@ -34,6 +34,7 @@ async fn test_tcp_syslog() {
             keepalive: None,
             tls: None,
             receive_buffer_bytes: None,
+            connection_limits: Default::default(),
         }),
     );
     config.add_sink("out", &["in"], tcp_json_sink(out_addr.to_string()));
@@ -62,8 +63,16 @@ async fn test_tcp_syslog() {
         .iter()
         .map(|s| {
             let mut value = Value::from_str(s).unwrap();
-            value.as_object_mut().unwrap().remove("hostname"); // Vector adds this field which will cause a parse error.
-            value.as_object_mut().unwrap().remove("source_ip"); // Vector adds this field which will cause a parse error.
+            // Vector adds these fields which will cause a parse error.
+            for field in &[
+                "hostname",
+                "source_ip",
+                "source_port",
+                "listen_port",
+                "transport",
+            ] {
+                value.as_object_mut().unwrap().remove(*field);
+            }
             serde_json::from_value(value).unwrap()
         })
         .collect();
@@ -127,8 +136,16 @@ async fn test_unix_stream_syslog() {
         .iter()
         .map(|s| {
             let mut value = Value::from_str(s).unwrap();
-            value.as_object_mut().unwrap().remove("hostname"); // Vector adds this field which will cause a parse error.
-            value.as_object_mut().unwrap().remove("source_ip"); // Vector adds this field which will cause a parse error.
+            // Vector adds these fields which will cause a parse error.
+            for field in &[
+                "hostname",
+                "source_ip",
+                "source_port",
+                "listen_port",
+                "transport",
+            ] {
+                value.as_object_mut().unwrap().remove(*field);
+            }
             serde_json::from_value(value).unwrap()
         })
         .collect();
@@ -152,6 +169,7 @@ async fn test_octet_counting_syslog() {
             keepalive: None,
             tls: None,
             receive_buffer_bytes: None,
+            connection_limits: Default::default(),
         }),
     );
     config.add_sink("out", &["in"], tcp_json_sink(out_addr.to_string()));
@@ -192,8 +210,16 @@ async fn test_octet_counting_syslog() {
         .iter()
         .map(|s| {
             let mut value = Value::from_str(s).unwrap();
-            value.as_object_mut().unwrap().remove("hostname"); // Vector adds this field which will cause a parse error.
-            value.as_object_mut().unwrap().remove("source_ip"); // Vector adds this field which will cause a parse error.
+            // Vector adds these fields which will cause a parse error.
+            for field in &[
+                "hostname",
+                "source_ip",
+                "source_port",
+                "listen_port",
+                "transport",
+            ] {
+                value.as_object_mut().unwrap().remove(*field);
+            }
             serde_json::from_value(value).unwrap()
         })
         .collect();
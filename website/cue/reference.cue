@@ -377,6 +377,7 @@ _values: {
 	{"*": {}} |
 	{"bool": #TypeBool & {_args: required: Args.required}} |
 	{"float": #TypeFloat & {_args: required: Args.required}} |
+	{"int": #TypeInt & {_args: required: Args.required}} |
 	{"object": #TypeObject & {_args: required: Args.required}} |
 	{"string": #TypeString & {_args: required: Args.required}} |
 	{"timestamp": #TypeTimestamp & {_args: required: Args.required}} |
@@ -426,6 +427,21 @@ _values: {
 	examples?: [float, ...float]
 }
 
+#TypeInt: {
+	_args: required: bool
+	let Args = _args
+
+	if !Args.required {
+		// `default` sets the default value.
+		default: int | null
+	}
+
+	// `examples` clarify values through examples. This should be used
+	// when examples cannot be derived from the `default` or `enum`
+	// options.
+	examples?: [int, ...int]
+}
+
 #TypeObject: {
 	// `examples` clarify values through examples. This should be used
 	// when examples cannot be derived from the `default` or `enum`
@@ -40,6 +40,22 @@ api: {
 				the API will not be exposed outside the container.
 				"""
 		}
+		auth_token: {
+			common:   false
+			required: false
+			type: string: {
+				default: null
+				examples: ["${VECTOR_API_TOKEN}"]
+				syntax: "literal"
+			}
+			description: """
+				The token requests must carry, as a bearer token of their `Authorization` header, to read or
+				manage the running config: the `runningConfig` and `configRevisions` queries, and the
+				`applyConfig` mutation, which reloads Vector into a candidate config and restores the previous
+				config if components fail to build or their healthchecks fail. These are disabled if unset.
+				A config applied through the API is replaced by the config files upon the next reload from disk.
+				"""
+		}
 		playground: {
 			common:   false
 			required: false
@@ -201,6 +201,77 @@ cli: {
 
 			options: _config_options
 		}
+		"checkpoints": {
+			description: """
+				Inspect and reset the positions sources resume from after a restart, kept in the
+				`checkpoints` directory of the data directory. Sources must not be running while their
+				checkpoints are reset.
+				"""
+
+			example: "vector checkpoints --config /etc/vector/vector.toml reset my_journald_source"
+
+			options: {
+				"config": _config_options.config
+				"config-dir": _config_options."config-dir"
+				"data-dir": {
+					description: "The data directory holding the checkpoints, instead of the `data_dir` of the configuration."
+					type:        "string"
+					example:     "/var/lib/vector"
+				}
+			}
+
+			args: {
+				command: {
+					description: "`list` to list the components with checkpoints, `show <component>` to print their positions (`--json` for JSON output), or `reset <component>` to remove them (`--key` to only remove one)."
+					type:        "string"
+				}
+			}
+		}
+		"config diff": {
+			description: """
+				Compile a candidate configuration and report which components reloading the running
+				configuration into it would add, remove, reconfigure, or restart, and whether rebuilt
+				sinks keep the events of their buffers. Nothing is applied. The same report is available
+				from a running instance through the `configDiff` query of the GraphQL API.
+				"""
+
+			example: "vector config diff --current /etc/vector/vector.toml /tmp/vector.toml"
+
+			flags: _default_flags & {
+				"json": {
+					description: "Output the report as JSON"
+				}
+				"exit-code": {
+					description: "Exit with a non-zero code if the reload would change anything"
+				}
+			}
+
+			options: {
+				"current": {
+					description: "The configuration files of the running instance"
+					type:        "list"
+					default:     "/etc/vector/vector.toml"
+					env_var:     "VECTOR_CONFIG"
+				}
+				"current-dir": {
+					description: "Directories holding the configuration files of the running instance"
+					type:        "list"
+					env_var:     "VECTOR_CONFIG_DIR"
+				}
+				"candidate-dir": {
+					description: "Directories holding the candidate configuration files"
+					type:        "list"
+				}
+			}
+
+			args: {
+				candidate: {
+					description: "The candidate configuration files"
+					type:        "list"
+				}
+			}
+		}
+
 		"generate": {
 			description: "Generate a Vector configuration containing a list of components"
 
@@ -256,6 +327,15 @@ cli: {
 				out the [unit testing documentation](\(urls.vector_unit_tests)).
 				"""
 
+			flags: _default_flags & {
+				"update-golden-files": {
+					description: """
+						Overwrite the golden files of the tests with the actual outputs instead of
+						comparing against them
+						"""
+				}
+			}
+
 			options: {
 				"config-toml": {
 					description: """
@@ -293,6 +373,39 @@ cli: {
 			}
 		}
 
+		"replay": {
+			description: """
+				Submit the payloads a `datadog_logs` sink wrote to its `spool` again, once the
+				configuration that got them rejected is fixed. Payloads are sent with the current
+				settings of the sink and removed from the spool once accepted. Payloads that were sent
+				with an API key set on their events are skipped unless `--api-key` is passed.
+				"""
+
+			example: "vector replay --config /etc/vector/vector.toml my_datadog_logs_sink"
+
+			flags: _default_flags & {
+				"dry-run": {
+					description: "List the spooled payloads without replaying them"
+				}
+			}
+
+			options: {
+				"config": _config_options.config
+				"config-dir": _config_options."config-dir"
+				"api-key": {
+					description: "Replay the payloads with this API key, instead of the default API key of the sink"
+					type:        "string"
+				}
+			}
+
+			args: {
+				sink: {
+					description: "The ID of the `datadog_logs` sink whose spooled payloads are replayed"
+					type:        "string"
+				}
+			}
+		}
+
 		"tap": {
 			description: """
 				Observe log events from topology components.
@@ -369,6 +482,33 @@ cli: {
 					description: "The URL for the GraphQL endpoint of the running Vector instance"
 					type:        "string"
 				}
+				"window": {
+					_short:      "w"
+					description: "Length of the rolling window of metrics shown as sparklines (in seconds)"
+					type:        "integer"
+					default:     60
+				}
+				"output": {
+					_short: "o"
+					description: """
+						Print a snapshot of component metrics in this format, instead of
+						displaying the dashboard. Doesn't require a TTY.
+						"""
+					type: "enum"
+					enum: {
+						json: "Output a JSON array with a summary and the raw throughput and error samples of each component"
+						csv:  "Output a CSV row with a summary of each component"
+					}
+				}
+				"duration": {
+					_short: "d"
+					description: """
+						How long to sample metrics for before printing a snapshot with
+						`--output`, e.g. `500ms`, `60s` or `5m`
+						"""
+					type:    "string"
+					default: "60s"
+				}
 			}
 		}
 
@@ -436,6 +576,13 @@ cli: {
 						The same result can be achieved by using `.` as the final expression.
 						"""
 				}
+				"explain-types": {
+					description: """
+						Print the type inferred for each event path queried by the program,
+						and the assignment or definition that type comes from, instead of
+						running the program.
+						"""
+				}
 			}
 
 			options: {
@@ -264,6 +264,11 @@ components: {
 		enabled: bool
 	}
 
+	#FeaturesConnectionLimits: {
+		enabled:        bool
+		relevant_when?: string
+	}
+
 	#FeaturesMultiline: {
 		enabled: bool
 	}
@@ -299,6 +304,8 @@ components: {
 			interface?: #Interface
 		}
 
+		connection_limits?: #FeaturesConnectionLimits
+
 		keepalive?: #FeaturesKeepalive
 
 		receive_buffer_bytes?: #FeaturesReceiveBufferBytes
@@ -544,6 +551,209 @@ components: {
 				type: bool: default: false
 			}
 
+			_http_server_http2: {
+				common:      false
+				description: "Configures HTTP/2 support of the HTTP server."
+				required:    false
+				type: object: options: {
+					enabled: {
+						common:      false
+						description: "Accept HTTP/2 connections along with HTTP/1.1. Over TLS, HTTP/2 is negotiated through ALPN. Over cleartext, clients must use HTTP/2 with prior knowledge (h2c), as upgrading HTTP/1.1 connections isn't supported."
+						required:    false
+						type: bool: default: false
+					}
+					keepalive_interval_secs: {
+						common:      false
+						description: "How often to send HTTP/2 pings to keep connections alive. No pings are sent if unset."
+						required:    false
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
+					keepalive_timeout_secs: {
+						common:      false
+						description: "How long to wait for a ping to be acknowledged before closing the connection."
+						required:    false
+						type: uint: {
+							default: 20
+							unit:    "seconds"
+						}
+					}
+					max_concurrent_streams: {
+						common:      false
+						description: "The maximum number of requests a client can make concurrently over a single HTTP/2 connection."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [250, 1000]
+							unit: "requests"
+						}
+					}
+				}
+			}
+
+			_http_server_keepalive: {
+				common:      false
+				description: "Configures how the HTTP server reuses connections."
+				required:    false
+				type: object: options: {
+					enabled: {
+						common:      false
+						description: "Keep HTTP/1.1 connections open between requests."
+						required:    false
+						type: bool: default: true
+					}
+					idle_timeout_secs: {
+						common:      false
+						description: "Close connections that neither send nor receive data for this long. This includes the time spent handling requests, so it should exceed the time it takes to respond, in particular when acknowledgements are enabled. Connections are never closed for being idle if unset."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [60]
+							unit: "seconds"
+						}
+					}
+				}
+			}
+
+			_http_server_idempotency: {
+				common:      false
+				description: "Drops retransmissions of requests that were already accepted, such as requests retried by clients after a timeout. A retransmission is replied to as if it was accepted again, without its events being forwarded, while a request arriving as another with the same key is still being handled is rejected with a `409 Conflict`. Keys are only remembered once their request is accepted, so that requests that failed can be retried."
+				required:    false
+				type: object: options: {
+					key: {
+						description: "What identifies retransmissions of a request."
+						required:    true
+						type: string: {
+							enum: {
+								header:    "The value of the `header` header of the request. Requests without that header aren't deduplicated."
+								body_hash: "A hash of the body of the request, as received, along with its path. Requests with identical bodies sent within `ttl_secs` of each other are deduplicated, even when they weren't retried."
+							}
+							syntax: "literal"
+						}
+					}
+					header: {
+						common:      false
+						description: "The header carrying the idempotency key of requests, when `key` is `header`."
+						required:    false
+						type: string: {
+							default: "idempotency-key"
+							syntax:  "literal"
+						}
+					}
+					max_keys: {
+						common:      false
+						description: "The maximum number of keys tracked at once. Requests arriving while that many keys are tracked aren't deduplicated."
+						required:    false
+						type: uint: {
+							default: 100000
+							unit:    null
+						}
+					}
+					ttl_secs: {
+						common:      false
+						description: "How long retransmissions of an accepted request are dropped for."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+
+			_http_server_quota: {
+				common:      false
+				description: "Limits the events and bytes accepted per identity over a rolling window. Requests of an identity that used up its quota are rejected with a `429 Too Many Requests`, along with the `Retry-After`, `X-Quota-Reset`, `X-Quota-Limit-Events`, `X-Quota-Remaining-Events`, `X-Quota-Limit-Bytes` and `X-Quota-Remaining-Bytes` headers. A request that is admitted counts in full, even when its events take the identity over its limit."
+				required:    false
+				type: object: options: {
+					identity: {
+						description: "What requests are accounted to. Requests missing their identity share a single quota."
+						required:    true
+						type: string: {
+							enum: {
+								api_key:    "The API key of the request. Only supported by the `datadog_agent` source."
+								auth_token: "The token of the `Authorization` header of the request. Not supported by the `datadog_agent` source."
+								peer_ip:    "The IP address of the peer."
+							}
+							syntax: "literal"
+						}
+					}
+					max_bytes: {
+						common:      true
+						description: "The maximum number of bytes an identity can send over the window. Bodies are measured as received, before being decompressed."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [104857600]
+							unit: "bytes"
+						}
+					}
+					max_events: {
+						common:      true
+						description: "The maximum number of events an identity can send over the window. At least one of `max_events` and `max_bytes` must be set."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [100000]
+							unit: "events"
+						}
+					}
+					period_secs: {
+						common:      false
+						description: "The length of the rolling window."
+						required:    false
+						type: uint: {
+							default: 60
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+
+			_tls_spiffe: {
+				common:      false
+				description: "Fetches the identity certificate and the trusted authorities from a [SPIFFE Workload API](\(urls.spiffe_workload_api)), such as the one served by the SPIRE agent, instead of files. Identities are rotated as the Workload API issues new ones. Peers are authorized by the SPIFFE ID in their certificate rather than their host name. Can't be combined with `ca_file`, `crt_file` or `key_file`."
+				required:    false
+				type: object: options: {
+					socket_path: {
+						common:      true
+						description: "The path of the Workload API Unix socket. Defaults to the socket given by the `SPIFFE_ENDPOINT_SOCKET` environment variable, or `/tmp/spire-agent/public/api.sock`."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["/run/spire/sockets/agent.sock"]
+							syntax: "literal"
+						}
+					}
+					trust_domains: {
+						common:      true
+						description: "Authorize peers whose SPIFFE ID belongs to one of these trust domains. If neither `trust_domains` nor `allowed_ids` is set, peers from the trust domain of Vector's own SPIFFE ID are authorized."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["example.org"]
+								syntax: "literal"
+							}
+						}
+					}
+					allowed_ids: {
+						common:      false
+						description: "Authorize peers with one of these SPIFFE IDs."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["spiffe://example.org/ns/prod/sa/collector"]
+								syntax: "literal"
+							}
+						}
+					}
+				}
+			}
+
 			_tls_accept: {
 				_args: {
 					can_enable:             bool
@@ -605,11 +815,12 @@ components: {
 							syntax: "literal"
 						}
 					}
+					spiffe: _tls_spiffe
 
 					if Args.can_verify_certificate {
 						verify_certificate: {
 							common:      false
-							description: "If `true`, Vector will require a TLS certificate from the connecting host and terminate the connection if the certificate is not valid. If `false` (the default), Vector will not request a certificate from the client."
+							description: "If `true`, Vector will require a TLS certificate from the connecting host and terminate the connection if the certificate is not valid. If `false` (the default), Vector will not request a certificate from the client. Defaults to `true` when `spiffe` is set."
 							required:    false
 							type: bool: default: false
 						}
@@ -679,6 +890,7 @@ components: {
 							syntax: "literal"
 						}
 					}
+					spiffe: _tls_spiffe
 
 					if Args.can_verify_certificate {
 						verify_certificate: {
@@ -692,7 +904,7 @@ components: {
 					if Args.can_verify_hostname {
 						verify_hostname: {
 							common:      false
-							description: "If `true` (the default), Vector will validate the configured remote host name against the remote host's TLS certificate. Do NOT set this to `false` unless you understand the risks of not verifying the remote hostname."
+							description: "If `true` (the default), Vector will validate the configured remote host name against the remote host's TLS certificate. Do NOT set this to `false` unless you understand the risks of not verifying the remote hostname. Defaults to `false` when `spiffe` is set, as peers are authorized by their SPIFFE ID instead."
 							required:    false
 							type: bool: default: true
 						}
@@ -756,6 +968,64 @@ components: {
 				}
 			}
 
+			_parquet: {
+				common:      false
+				description: "Writes each batch as a Parquet file, with the configured columns, instead of encoding each event. Files are named with the `parquet` extension, and the `compression` option doesn't apply to them as Parquet compresses their columns itself. The `encoding` field filters are still applied to the events."
+				required:    false
+				type: object: {
+					examples: []
+					options: {
+						compression: {
+							common:      false
+							description: "The compression of the columns of the files."
+							required:    false
+							type: string: {
+								default: "snappy"
+								enum: {
+									none:   "No compression."
+									snappy: "[Snappy](\(urls.snappy)) compression."
+									zstd:   "[Zstandard](\(urls.zstd)) compression."
+								}
+							}
+						}
+						row_group_size: {
+							common:      false
+							description: "The maximum number of rows of a row group. Batches with more events are split into several row groups."
+							required:    false
+							type: uint: {
+								default: 100000
+								unit:    "events"
+							}
+						}
+						schema: {
+							common:      true
+							description: "The columns of the files, named after the event fields they're read from. All the columns are optional: a missing field, or a value not matching its column's type, is written as a null."
+							required:    true
+							type: object: {
+								examples: [{"message": "string", "status": "int64", "timestamp": "timestamp"}]
+								options: {
+									"*": {
+										common:      true
+										description: "The type of the column."
+										required:    true
+										type: string: {
+											enum: {
+												boolean:   "A boolean."
+												double:    "A 64-bit floating point number. Integers are converted."
+												int64:     "A 64-bit signed integer."
+												json:      "Any value, serialized as JSON."
+												string:    "A UTF-8 string. Scalar values other than strings are converted."
+												timestamp: "A timestamp, stored as microseconds since the Unix epoch."
+											}
+										}
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+
 			_http_auth: {
 				_args: {
 					password_example: string
@@ -919,6 +1189,41 @@ components: {
 				}
 			}
 
+			if Kind != "sink" {
+				distribution: {
+					common:      false
+					description: """
+						How the events of this component are distributed among the components that
+						consume it. By default every consumer receives every event, the other modes
+						shard the events across identical downstream components instead.
+						"""
+					required: false
+					warnings: []
+					type: string: {
+						default: "broadcast"
+						enum: {
+							broadcast:    "Every consumer receives every event."
+							round_robin:  "Each event goes to the next consumer in turn, waiting for it if it's applying backpressure."
+							least_loaded: "Each event goes to the next consumer in turn that is ready to accept it, skipping the ones applying backpressure."
+						}
+						syntax: "literal"
+					}
+				}
+			}
+
+			if Kind == "source" {
+				limits: {
+					common:      false
+					description: "Overrides the global [`limits`](\(urls.vector_configuration)#limits) for this source, option by option."
+					required:    false
+					warnings: []
+					type: object: {
+						examples: []
+						options:  configuration.configuration.limits.type.object.options
+					}
+				}
+			}
+
 			if Kind != "source" {
 				inputs: {
 					description: """
@@ -939,6 +1244,25 @@ components: {
 				}
 			}
 
+			if Kind == "sink" {
+				acknowledgement_timeout_secs: {
+					common: false
+					description: """
+						Resolves the batches of the events this sink still holds after this long as errored, so that the
+						sources waiting on them, when their `acknowledgements` are enabled, can retry them rather than stall
+						behind a sink that hangs. The timeout starts when the sink receives the first event of a batch.
+						Batches are never resolved before the sink is done with their events if unset.
+						"""
+					required: false
+					warnings: []
+					type: uint: {
+						default: null
+						examples: [60, 300]
+						unit: "seconds"
+					}
+				}
+			}
+
 			"type": {
 				description: "The component type. This is a required field for all components and tells Vector which component to use."
 				required:    true
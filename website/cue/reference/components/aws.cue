@@ -81,6 +81,49 @@ components: _aws: {
 			}
 		}
 
+		endpoint_discovery: {
+			common:        false
+			description:   "How the endpoint of the service is derived from `region`, for example to use FIPS or dual-stack endpoints."
+			relevant_when: "endpoint = null"
+			required:      false
+			type: object: {
+				examples: [{fips: true}, {templates: {"*": "https://{service}.{region}.vpce.{dns_suffix}"}}]
+				options: {
+					fips: {
+						common:      true
+						description: "Use the FIPS 140-2 validated endpoints of the service, such as `sqs-fips.us-east-1.amazonaws.com`."
+						required:    false
+						type: bool: default: false
+					}
+					dual_stack: {
+						common:      true
+						description: "Use the dual-stack endpoints of the service, reachable over both IPv4 and IPv6, such as `sqs.us-east-1.api.aws`."
+						required:    false
+						type: bool: default: false
+					}
+					templates: {
+						common:      false
+						description: "Endpoint URL templates by service name, such as `s3` or `sts`, with `*` matching any service. The `{service}`, `{region}` and `{dns_suffix}` placeholders are replaced by the name of the service, the region and the DNS suffix of the region's partition, such as `amazonaws.com`. Templates take precedence over `fips` and `dual_stack`."
+						required:    false
+						type: object: {
+							examples: [{"s3": "https://bucket.vpce-0123456789abcdef0.s3.{region}.vpce.{dns_suffix}"}]
+							options: {
+								"*": {
+									common:      false
+									description: "The endpoint URL template of a service."
+									required:    false
+									type: string: {
+										default: null
+										syntax:  "literal"
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+
 		region: {
 			description:   "The [AWS region](\(urls.aws_regions)) of the target service. If `endpoint` is provided it will override this value since the endpoint includes the region."
 			required:      true
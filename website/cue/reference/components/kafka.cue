@@ -89,6 +89,28 @@ components: _kafka: {
 		}
 	}
 
+	_schema_registry: {
+		url: {
+			description: "The base URL of a schema registry speaking the [Confluent Schema Registry API](\(urls.confluent_schema_registry_api)), such as Confluent Schema Registry or the `ccompat` endpoint of [Apicurio Registry](\(urls.apicurio_registry))."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["http://localhost:8081", "https://registry.example.com/apis/ccompat/v6"]
+				syntax: "literal"
+			}
+		}
+		cache_ttl_secs: {
+			common:      false
+			description: "How long the latest version of a subject is cached. Schemas looked up by ID never change and are cached until Vector restarts."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+	}
+
 	how_it_works: {
 		librdkafka: {
 			title: "librdkafka"
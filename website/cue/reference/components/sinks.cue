@@ -85,6 +85,39 @@ components: sinks: [Name=string]: {
 								unit: "bytes"
 							}
 						}
+						priority: {
+							common:        false
+							description:   "Splits the buffer into lanes by the priority of events. Lanes are drained from the highest priority to the lowest, taking up to the weight of each lane from it in turn, and full buffers dropping the newest events drop those of the lowest priority first."
+							required:      false
+							relevant_when: "type = \"memory\""
+							type: object: {
+								examples: []
+								options: {
+									field: {
+										common:      true
+										description: "The log field or metric tag holding the priority of events, as an integer from zero, such as one set by a [`remap` transform](\(urls.vector_remap_transform)). Events without a priority have the lowest priority."
+										required:    false
+										type: string: {
+											default: "priority"
+											examples: ["priority", "routing.priority"]
+											syntax: "literal"
+										}
+									}
+									weights: {
+										common:      true
+										description: "The draining weight of each lane, from the lowest priority to the highest. Events with priorities past the last lane go in the last lane."
+										required:    false
+										type: array: {
+											default: [1, 2, 4]
+											items: type: uint: {
+												examples: [1, 10]
+												unit: "events"
+											}
+										}
+									}
+								}
+							}
+						}
 						type: {
 							common:      true
 							description: "The buffer's type and storage mechanism."
@@ -112,7 +145,7 @@ components: sinks: [Name=string]: {
 								default: "block"
 								enum: {
 									block:       "Applies back pressure when the buffer is full. This prevents data loss, but will cause data to pile up on the edge."
-									drop_newest: "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority."
+									drop_newest: "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority. With `priority` set, buffered data of a lower priority is dropped first to make room for new data."
 								}
 								syntax: "literal"
 							}
@@ -143,6 +176,13 @@ components: sinks: [Name=string]: {
 							if list.Contains(features.send.compression.algorithms, "gzip") {
 								gzip: "[Gzip](\(urls.gzip)) standard DEFLATE compression."
 							}
+							if list.Contains(features.send.compression.algorithms, "zstd") {
+								zstd: """
+									[Zstandard](\(urls.zstd)) compression. Small payloads sharing a common structure compress
+									better with a dictionary, set with the `{algorithm = "zstd", dictionary = "/path/to/dictionary"}`
+									form of this option. The receiver must decompress the payloads with the same dictionary.
+									"""
+							}
 						}
 						syntax: "literal"
 					}
@@ -259,6 +299,25 @@ components: sinks: [Name=string]: {
 								required:    false
 								type: bool: default: true
 							}
+							failure_threshold: {
+								common:      false
+								description: "The number of consecutive periodic health check failures after which the sink is paused, when `interval_secs` is set."
+								required:    false
+								type: uint: {
+									default: 3
+									unit:    null
+								}
+							}
+							interval_secs: {
+								common:      false
+								description: "Runs the health check again at this interval once the sink is started, pausing the sink while it's unhealthy. By default, the health check is only run upon Vector boot."
+								required:    false
+								type: uint: {
+									default: null
+									examples: [30, 300]
+									unit: "seconds"
+								}
+							}
 						}
 					}
 				}
@@ -516,6 +575,15 @@ components: sinks: [Name=string]: {
 							```
 							"""
 					},
+					{
+						title: "Periodic health checks"
+						body: """
+							If `healthcheck.interval_secs` is set, the health check is run again at that interval
+							once the sink is started. When it fails `healthcheck.failure_threshold` times in a row,
+							the sink stops reading events, leaving its buffer to absorb them, until the health check
+							passes again. State changes are reported by the `component_healthy` internal metric.
+							"""
+					},
 					{
 						title: "Disable health checks"
 						body: """
@@ -627,6 +695,7 @@ components: sinks: [Name=string]: {
 	}
 
 	telemetry: metrics: {
+		acknowledgement_timeouts_total:       components.sources.internal_metrics.output.metrics.acknowledgement_timeouts_total
 		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
@@ -80,8 +80,11 @@ components: sinks: aws_cloudwatch_metrics: components._aws & {
 				* Statistic Sets
 				* Data Points
 
-				In Vector only the latter is used to allow lossless statistics
-				calculations on CloudWatch side.
+				By default Vector only uses the latter to allow lossless statistics
+				calculations on CloudWatch side. With `statistic_sets` enabled,
+				the values of each metric in a batch are aggregated into a
+				statistic set instead, which records only their count, sum,
+				minimum and maximum.
 				""",
 		]
 	}
@@ -99,6 +102,48 @@ components: sinks: aws_cloudwatch_metrics: components._aws & {
 				syntax: "literal"
 			}
 		}
+		dimensions: {
+			common: false
+			description: """
+				The tags of a metric that are sent as its dimensions. All tags
+				are sent when unset. CloudWatch accepts at most 30 dimensions
+				per metric, and the rest are dropped.
+				"""
+			required: false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["host", "region"]
+					syntax: "literal"
+				}
+			}
+		}
+		high_resolution_metrics: {
+			common: false
+			description: """
+				Patterns of the names of metrics stored as
+				[high-resolution metrics](\(urls.aws_cloudwatch_high_resolution_metrics)),
+				at a one second rather than one minute resolution. Patterns can
+				use `*`, `?` and `[...]` wildcards.
+				"""
+			required: false
+			warnings: ["High-resolution metrics are billed at a higher rate by AWS."]
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["requests_*", "latency"]
+					syntax: "literal"
+				}
+			}
+		}
+		statistic_sets: {
+			common:      false
+			description: "Whether to aggregate the values of each metric, as identified by its name and dimensions, in a batch into a single statistic set, to send fewer data points."
+			required:    false
+			warnings: ["Statistic sets lose the individual values of distributions, so CloudWatch cannot compute percentiles from them."]
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -220,6 +220,7 @@ components: sinks: aws_s3: components._aws & {
 				syntax: "template"
 			}
 		}
+		parquet: configuration._parquet
 		server_side_encryption: {
 			category:    "Encryption"
 			common:      false
@@ -415,5 +416,6 @@ components: sinks: aws_s3: components._aws & {
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
 		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
 		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+		parquet_values_mismatched_total:  components.sources.internal_metrics.output.metrics.parquet_values_mismatched_total
 	}
 }
@@ -0,0 +1,191 @@
+package metadata
+
+components: sinks: aws_sns: components._aws & {
+	title: "Amazon Simple Notification Service (SNS)"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["AWS"]
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			proxy: enabled: true
+			request: {
+				enabled:                    true
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               30
+				headers:                    false
+			}
+			tls: enabled: false
+			to: {
+				service: services.aws_sns
+
+				interface: {
+					socket: {
+						api: {
+							title: "Amazon Simple Notification Service API"
+							url:   urls.aws_sns_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		topic_arn: {
+			description: "The ARN of the Amazon SNS topic to which messages are published."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["arn:aws:sns:us-east-2:123456789012:MyTopic"]
+				syntax: "literal"
+			}
+		}
+		message_attributes: {
+			common:      false
+			description: "The [message attributes](\(urls.aws_sns_message_attributes)) to set on each message, by name, each from a field of the event. Numbers are sent as `Number` attributes, arrays as `String.Array` attributes and other values as `String` attributes. Attributes are skipped when their field is missing, null or empty."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"severity": "level", "tenant": "customer.id"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The field to set the attribute from."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["level", "customer.id"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		message_group_id: {
+			common:      false
+			description: "The tag that specifies that a message belongs to a specific message group. Required for, and can be applied only to, FIFO topics, which have ARNs ending in `.fifo`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector", "{{ tenant }}"]
+				syntax: "template"
+			}
+		}
+		message_deduplication_id: {
+			common: false
+			description: """
+				The message deduplication ID value to allow AWS to identify duplicate messages published to
+				FIFO topics. This value is a template which should result in a unique string for each event.
+
+				See the [AWS documentation](\(urls.aws_sns_message_deduplication_id)) for more about how AWS
+				does message deduplication.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ transaction_id }}"]
+				syntax: "template"
+			}
+		}
+		overflow: {
+			common:      false
+			description: "What to do with events whose message and attributes are larger than the 256KB SNS accepts."
+			required:    false
+			warnings: ["Truncated JSON messages are no longer valid JSON."]
+			type: string: {
+				default: "reject"
+				enum: {
+					reject:   "Drop the event, reporting it in the `events_discarded_total` metric."
+					truncate: "Truncate the message to fit, keeping its attributes."
+				}
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		payload_size: {
+			title: "Payload size"
+			body: """
+				SNS accepts messages of up to 256KB, counting both the message and the names, types and
+				values of its attributes. Larger events are dropped, or have their message truncated when
+				`overflow` is set to `truncate`. As sinks have no outputs, events that may be too large to
+				publish can be routed elsewhere beforehand, for example with a `route` transform checking
+				`length(.message)`.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform:      "aws"
+			_service:      "sns"
+			_url_fragment: "api"
+
+			policies: [
+				{
+					_action: "GetTopicAttributes"
+					required_for: ["healthcheck"]
+				},
+				{
+					_action: "Publish"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
+		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:           components.sources.internal_metrics.output.metrics.processed_events_total
+		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,190 @@
+package metadata
+
+components: sinks: axiom: {
+	title: "Axiom"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Axiom"]
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    5000000
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "gzip"
+				algorithms: ["none", "gzip", "zstd"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			to: {
+				service: services.axiom
+
+				interface: {
+					socket: {
+						api: {
+							title: "Axiom ingest API"
+							url:   urls.axiom_ingest
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		dataset: {
+			description: "The dataset events are ingested into. Events are batched per dataset."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "{{ kubernetes.pod_namespace }}-logs"]
+				syntax: "template"
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The base URL of the Axiom API, for self-hosted deployments."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://api.axiom.co"
+				examples: ["https://axiom.example.com"]
+				syntax: "literal"
+			}
+		}
+		field_mapping: {
+			common:      false
+			description: "Fields to rename before events are ingested, mapping the path of each field in the event to its name in the dataset. Fields missing from an event are ignored."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"level": "severity", "kubernetes.pod_name": "pod"}]
+				options: {}
+			}
+		}
+		org_id: {
+			common:      false
+			description: "The ID of the organization the dataset belongs to. Only needed with personal tokens."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["acme-1a2b"]
+				syntax: "literal"
+			}
+		}
+		token: {
+			description:   "The [API or personal token](\(urls.axiom_tokens)) authenticating requests. Either `token` or `token_file` must be set."
+			required:      false
+			common:        true
+			relevant_when: "token_file is not set"
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${AXIOM_TOKEN}", "xaat-1234"]
+				syntax: "literal"
+			}
+		}
+		token_file: {
+			description:   "A file to read the token authenticating requests from, such as a mounted Kubernetes secret. The file is read again whenever it changes, so that the token can be rotated without reloading Vector."
+			required:      false
+			common:        false
+			relevant_when: "token is not set"
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/run/secrets/axiom-token"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		time_field: {
+			title: "Event time"
+			body:  """
+				The timestamp of events, at the `timestamp_key` of the global `log_schema`, is sent as the
+				`_time` field Axiom reads the time of events from. Events without a timestamp are timestamped
+				by Axiom when they are ingested.
+				"""
+		}
+		batch_limits: {
+			title: "Batch limits"
+			body:  """
+				Axiom accepts at most 10,000 events per request, so batches are capped to that many events,
+				and to 5MB of events. Batches are split into several requests when needed, and events too
+				large to fit in a request on their own are dropped.
+				"""
+		}
+		token_rotation: {
+			title: "Token rotation"
+			body:  """
+				A token read from `token_file` is read again whenever the modification time or size of the
+				file changes. Requests failing while the token file is briefly missing during a rotation are
+				retried. Requests rejected with a `401` or `403` status are not retried, and their events are
+				marked as errored.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
@@ -78,14 +78,67 @@ components: sinks: azure_blob: {
 
 	configuration: {
 		connection_string: {
-			description: "The Azure Blob Storage Account connection string. Only authentication with access key supported."
-			required:    true
+			common:      true
+			description: "The Azure Blob Storage Account connection string, authenticating with the access key of the account. Either this or `storage_account` must be set."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"]
 				syntax: "literal"
 			}
 		}
+		storage_account: {
+			common:      true
+			description: "The Azure Blob Storage Account name, authenticating with the Azure AD [managed identity](\(urls.azure_managed_identities)) or workload identity of Vector's host. Either this or `connection_string` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["mylogstorage"]
+				syntax: "literal"
+			}
+		}
+		client_id: {
+			common:      false
+			description: "The client ID of the user-assigned managed identity to authenticate with, when `storage_account` is set. The system-assigned identity, or the only user-assigned identity, is used if it isn't set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["00000000-0000-0000-0000-000000000000"]
+				syntax: "literal"
+			}
+		}
+		blob_type: {
+			common:      false
+			description: "The type of the blobs to write."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "block"
+				enum: {
+					block:  "Write a new block blob per batch."
+					append: "Append each batch to the append blob named by `blob_prefix` and `blob_time_format`, creating it if needed."
+				}
+				syntax: "literal"
+			}
+		}
+		access_tier: {
+			common:      false
+			description: "The [access tier](\(urls.azure_blob_access_tiers)) of the blobs. The default access tier of the account is used if it isn't set. Not supported with append blobs."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				enum: {
+					hot:     "Optimized for data that is accessed frequently."
+					cool:    "Optimized for data that is accessed infrequently and stored for at least 30 days."
+					archive: "Optimized for data that is rarely accessed and stored for at least 180 days."
+				}
+				syntax: "literal"
+			}
+		}
 		container_name: {
 			description: "The Azure Blob Storage Account container name."
 			required:    true
@@ -126,6 +179,7 @@ components: sinks: azure_blob: {
 				syntax:  "strftime"
 			}
 		}
+		parquet: configuration._parquet
 	}
 
 	input: {
@@ -134,6 +188,33 @@ components: sinks: azure_blob: {
 	}
 
 	how_it_works: {
+		authentication: {
+			title: "Authentication"
+			body:  """
+				Vector authenticates with the access key of the `connection_string`, or, when
+				`storage_account` is set instead, with an Azure AD token of the identity of its host.
+
+				The token is that of the [Azure AD workload identity](\(urls.azure_workload_identity))
+				of the pod when the `AZURE_FEDERATED_TOKEN_FILE`, `AZURE_TENANT_ID` and `AZURE_CLIENT_ID`
+				environment variables are set, as they are in AKS pods using workload identity, and that
+				of the [managed identity](\(urls.azure_managed_identities)) of the VM otherwise. The
+				identity needs the `Storage Blob Data Contributor` role on the container. Tokens are
+				renewed in the background before they expire.
+				"""
+		}
+		append_blobs: {
+			title: "Append blobs"
+			body:  """
+				With `blob_type` set to `append`, batches are appended to the blob named by
+				`blob_prefix` and `blob_time_format`, which is created by the first batch appended to
+				it. Blobs are named by the hour by default, and `blob_append_uuid` defaults to `false`,
+				so that each Vector instance appends to a single blob per hour.
+
+				Blocks appended to append blobs are limited to 4 MiB, which is the default and maximum
+				`batch.max_bytes` of append blobs. Append blobs don't support the `parquet` encoding
+				nor `access_tier`.
+				"""
+		}
 		object_naming: {
 			title: "Object naming"
 			body:  """
@@ -181,5 +262,6 @@ components: sinks: azure_blob: {
 		http_error_response_total:        components.sources.internal_metrics.output.metrics.http_error_response_total
 		http_request_errors_total:        components.sources.internal_metrics.output.metrics.http_request_errors_total
 		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+		parquet_values_mismatched_total:  components.sources.internal_metrics.output.metrics.parquet_values_mismatched_total
 	}
 }
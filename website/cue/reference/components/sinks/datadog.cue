@@ -47,6 +47,17 @@ components: sinks: _datadog: {
 				syntax: "literal"
 			}
 		}
+		rate_limiter: {
+			common:      false
+			description: "The name of a rate limiter defined in the top-level `rate_limiters` table. Requests, retries included, are delayed as needed to keep the combined request rate of every sink referencing the rate limiter within its limit. This applies on top of the `request` rate limit of this sink."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["datadog"]
+				syntax: "literal"
+			}
+		}
 		region: {
 			common:        false
 			description:   "The region to send data to."
@@ -60,8 +60,9 @@ components: sinks: datadog_events: {
 				syntax: "literal"
 			}
 		}
-		endpoint: sinks._datadog.configuration.endpoint
-		site:     sinks._datadog.configuration.site
+		endpoint:     sinks._datadog.configuration.endpoint
+		rate_limiter: sinks._datadog.configuration.rate_limiter
+		site:         sinks._datadog.configuration.site
 	}
 
 	input: {
@@ -70,9 +71,10 @@ components: sinks: datadog_events: {
 	}
 
 	telemetry: metrics: {
-		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
-		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
-		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
-		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+		component_sent_bytes_total:            components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_event_bytes_total:      components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		component_sent_events_total:           components.sources.internal_metrics.output.metrics.component_sent_events_total
+		events_out_total:                      components.sources.internal_metrics.output.metrics.events_out_total
+		rate_limiter_throttled_requests_total: components.sources.internal_metrics.output.metrics.rate_limiter_throttled_requests_total
 	}
 }
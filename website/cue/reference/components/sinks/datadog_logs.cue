@@ -57,6 +57,43 @@ components: sinks: datadog_logs: {
 	support: sinks._datadog.support
 
 	configuration: {
+		api_key_isolation: {
+			common: false
+			description: """
+				Isolates the requests of each API key. Each API key gets its own copy of the `request`
+				settings, so that its concurrency, rate limit and retries are separate from those of the
+				other keys, and a circuit breaker failing its requests without sending them once it has
+				failed repeatedly. This keeps an API key that keeps failing, for example with `403`
+				responses, from slowing down delivery for the others.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					cooldown_secs: {
+						common:      false
+						description: "How long an API key's requests are failed for once it trips. Afterwards a single failed request trips it again."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+					failure_threshold: {
+						common:      false
+						description: "The number of consecutive failed requests after which an API key trips."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 5
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		default_api_key: {
 			description: "Default Datadog [API key](https://docs.datadoghq.com/api/?lang=bash#authentication), if an event has a key set in its metadata it will prevail over the one set here."
 			required:    true
@@ -66,13 +103,65 @@ components: sinks: datadog_logs: {
 				syntax: "literal"
 			}
 		}
-		endpoint: sinks._datadog.configuration.endpoint
-		region:   sinks._datadog.configuration.region
-		site:     sinks._datadog.configuration.site
+		endpoint:     sinks._datadog.configuration.endpoint
+		rate_limiter: sinks._datadog.configuration.rate_limiter
+		region:       sinks._datadog.configuration.region
+		site:         sinks._datadog.configuration.site
+		spool: {
+			common: false
+			description: """
+				Writes the payloads the Logs API rejects for good to a local directory instead of dropping
+				them: those rejected with a `403`, as their API key isn't valid, and those rejected with a
+				`413`, as they are too large. Each payload is kept as it was sent, along with a JSON file
+				describing it. Once the configuration is fixed, `vector replay <sink>` submits them again
+				with the current settings of the sink, splitting payloads that are too large, and removes
+				them once accepted. API keys aren't written to the spool.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_bytes: {
+						common:      false
+						description: "The maximum size of the spool. Rejected payloads that don't fit are dropped."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1073741824
+							unit:    "bytes"
+						}
+					}
+					path: {
+						description: "The directory the rejected payloads are written to. It is created if it doesn't exist."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["/var/lib/vector/datadog_logs_spool"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
 		logs:    true
 		metrics: null
 	}
+
+	telemetry: metrics: {
+		api_key_circuit_breaks_total:          components.sources.internal_metrics.output.metrics.api_key_circuit_breaks_total
+		component_errors_total:                components.sources.internal_metrics.output.metrics.component_errors_total
+		encoded_request_bytes:                 components.sources.internal_metrics.output.metrics.encoded_request_bytes
+		encoded_request_compressed_bytes:      components.sources.internal_metrics.output.metrics.encoded_request_compressed_bytes
+		encoded_request_duration_seconds:      components.sources.internal_metrics.output.metrics.encoded_request_duration_seconds
+		encoded_request_events:                components.sources.internal_metrics.output.metrics.encoded_request_events
+		events_discarded_total:                components.sources.internal_metrics.output.metrics.events_discarded_total
+		oversized_events_total:                components.sources.internal_metrics.output.metrics.oversized_events_total
+		rate_limiter_throttled_requests_total: components.sources.internal_metrics.output.metrics.rate_limiter_throttled_requests_total
+		spooled_events_total:                  components.sources.internal_metrics.output.metrics.spooled_events_total
+		spooled_payloads_total:                components.sources.internal_metrics.output.metrics.spooled_payloads_total
+	}
 }
@@ -49,9 +49,10 @@ components: sinks: datadog_metrics: {
 	support: sinks._datadog.support
 
 	configuration: {
-		api_key:  sinks._datadog.configuration.api_key
-		endpoint: sinks._datadog.configuration.endpoint
-		region:   sinks._datadog.configuration.region
+		api_key:      sinks._datadog.configuration.api_key
+		endpoint:     sinks._datadog.configuration.endpoint
+		rate_limiter: sinks._datadog.configuration.rate_limiter
+		region:       sinks._datadog.configuration.region
 		default_namespace: {
 			common: true
 			description: """
@@ -66,6 +67,109 @@ components: sinks: datadog_metrics: {
 				syntax: "literal"
 			}
 		}
+		distribution_passthrough: {
+			common: false
+			description: """
+				Sends the histogram distributions to Datadog's distribution endpoint as raw samples,
+				as is done for the summary ones, rather than as `min`, `max`, `avg`, `median`,
+				`count`, and percentile series. The samples of the distributions are also left
+				unmerged, ignoring `quantile_error`, so that low-volume distributions keep their
+				fidelity.
+				"""
+			required: false
+			warnings: []
+			type: bool: default: false
+		}
+		multi_value_tags: sinks.statsd.configuration.multi_value_tags
+		namespace_tags: {
+			common: false
+			description: """
+				Tags added to the series of the metrics in the given namespaces, after applying
+				`default_namespace`. They take precedence over the ones set in `tags`.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"app": {
+							"team": "web"
+						}
+					},
+				]
+				options: {
+					"*": {
+						common:      false
+						description: "The tags added to the series of a namespace, templatable."
+						required:    false
+						type: object: {
+							examples: [{"team": "web"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
+		quantiles: {
+			common: false
+			description: """
+				The quantiles sent for histograms, each as a `<name>.<percentile>percentile` series.
+				Quantiles are truncated to whole percentiles in series names.
+				"""
+			required: false
+			warnings: []
+			type: array: {
+				default: [0.95]
+				items: type: float: examples: [0.5, 0.95, 0.99]
+			}
+		}
+		quantile_error: sinks.prometheus_remote_write.configuration.quantile_error
+		source_type_name: {
+			common: false
+			description: """
+				The source type name sent along with every series, which Datadog uses to attribute the
+				metrics to an integration.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector", "postgres"]
+				syntax: "literal"
+			}
+		}
+		tags: {
+			common: false
+			description: """
+				Tags added to every series. A metric's own tags are kept when it already has a tag with
+				the same name. Values are templatable, so they can be derived from the metric's name,
+				namespace, or tags, and environment variables can be used as in the rest of the
+				configuration.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"env":     "production"
+						"service": "{{ namespace }}"
+						"host":    "${HOSTNAME}"
+					},
+				]
+				options: {
+					"*": {
+						common:      false
+						description: "A tag added to every series, templatable."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["production", "{{ tags.host }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -81,7 +185,8 @@ components: sinks: datadog_metrics: {
 	}
 
 	telemetry: metrics: {
-		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
-		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		component_sent_event_bytes_total:      components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		component_sent_events_total:           components.sources.internal_metrics.output.metrics.component_sent_events_total
+		rate_limiter_throttled_requests_total: components.sources.internal_metrics.output.metrics.rate_limiter_throttled_requests_total
 	}
 }
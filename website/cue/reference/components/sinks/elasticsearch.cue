@@ -194,6 +194,35 @@ components: sinks: elasticsearch: {
 							syntax: "template"
 						}
 					}
+					pipeline: {
+						common:      false
+						description: """
+							Name of the [ingest pipeline](\(urls.elasticsearch_ingest_pipeline)) to apply to each event. This is
+							set in the bulk action metadata and takes precedence over the request-wide `pipeline` option. If the
+							template renders to an empty string, no pipeline is set for the event.
+							"""
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["geoip", "{{ pipeline }}"]
+							syntax: "template"
+						}
+					}
+					routing: {
+						common:      false
+						description: """
+							Custom [routing](\(urls.elasticsearch_routing)) value for each event, set in the bulk action
+							metadata. If the template renders to an empty string, no routing value is set for the event.
+							"""
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{{ tenant_id }}"]
+							syntax: "template"
+						}
+					}
 				}
 			}
 		}
@@ -334,9 +363,41 @@ components: sinks: elasticsearch: {
 				syntax: "literal"
 			}
 		}
+		partial_failures: {
+			common:      false
+			description: "Options for handling the items of bulk requests rejected by Elasticsearch while the rest of the request succeeded."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					dead_letter_index: {
+						common:      false
+						description: "The index the documents rejected for good, such as those not matching the index mapping, are written to, along with the rejection reason. If not set, these documents are dropped."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["vector-dead-letter-%Y.%m.%d"]
+							syntax: "template"
+						}
+					}
+					retry_attempts: {
+						common:      false
+						description: "How many times the items rejected for a transient reason, such as a full write queue, are sent again before being dropped."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 5
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		pipeline: {
 			common:      true
-			description: "Name of the pipeline to apply."
+			description: "Name of the pipeline to apply to all events in a request. Use `bulk.pipeline` to set it per event."
 			required:    false
 			warnings: []
 			type: string: {
@@ -391,6 +452,15 @@ components: sinks: elasticsearch: {
 				due to Elasticsearch index mapping errors, where data keys aren't consistently
 				typed. To change this behavior, refer to the Elasticsearch [`ignore_malformed`
 				setting](\(urls.elasticsearch_ignore_malformed)).
+
+				Vector inspects the result of each item of a partially failed bulk request. The
+				items rejected for a transient reason, such as a `429` status when the write queues
+				are full, are sent again on their own, up to `partial_failures.retry_attempts` times.
+				The items rejected for good, such as those failing with a
+				`mapper_parsing_exception`, are written to `partial_failures.dead_letter_index` if
+				it's set, with the original document as a string in the `document` field and the
+				rejection in the `error` field. Otherwise they are dropped and counted in the
+				`events_discarded_total` metric, tagged with the Elasticsearch error type.
 				"""
 		}
 
@@ -401,6 +471,8 @@ components: sinks: elasticsearch: {
 		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		bulk_items_retried_total:         components.sources.internal_metrics.output.metrics.bulk_items_retried_total
+		events_dead_lettered_total:       components.sources.internal_metrics.output.metrics.events_dead_lettered_total
 		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
 		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
 		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
@@ -0,0 +1,185 @@
+package metadata
+
+components: sinks: exec: {
+	title: "Exec"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: enabled:     false
+			to: {
+				service: services.exec
+				interface: stdin: {}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		acknowledge_on_stdout: {
+			common: false
+			description: """
+				Whether the subprocess acknowledges the events it processed by writing a line to its
+				stdout, each line acknowledging the oldest event not acknowledged yet. When disabled,
+				events are considered delivered once written to the stdin of the subprocess, and its
+				stdout is discarded.
+				"""
+			required: false
+			type: bool: default: false
+		}
+		command: {
+			required:    true
+			description: "The command to run, plus any arguments required."
+			type: array: items: type: string: {
+				examples: ["python3", "/path/to/consumer.py"]
+				syntax: "literal"
+			}
+		}
+		framing: {
+			common:      false
+			description: "How the encoded events are delimited on the stdin of the subprocess."
+			required:    false
+			type: object: options: {
+				method: {
+					description: "The framing method."
+					required:    false
+					common:      true
+					type: string: {
+						default: "newline_delimited"
+						enum: {
+							newline_delimited:       "Each event is followed by a newline."
+							character_delimited:     "Each event is followed by the `delimiter` character."
+							length_delimited:        "Each event is preceded by its length in bytes, as a 4 bytes big-endian integer."
+							varint_length_delimited: "Each event is preceded by its length in bytes, as a [varint](\(urls.protobuf_varint)), as when streaming protobuf messages."
+						}
+						syntax: "literal"
+					}
+				}
+				delimiter: {
+					description:   "The character following each event."
+					relevant_when: "method = `character_delimited`"
+					required:      true
+					type: string: {
+						examples: ["\\t", "\\u0000"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
+		max_pending_events: {
+			common: false
+			description: """
+				The maximum number of events written to the subprocess without being acknowledged.
+				No more events are written until the subprocess acknowledges some of them.
+				"""
+			relevant_when: "acknowledge_on_stdout = true"
+			required:      false
+			type: uint: {
+				default: 1000
+				unit:    "events"
+			}
+		}
+		restart_initial_backoff_secs: {
+			common:      false
+			description: "The time to wait before restarting the subprocess after it exits for the first time."
+			required:    false
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+		restart_max_backoff_secs: {
+			common:      false
+			description: "The maximum time to wait before restarting the subprocess, the wait doubling after each exit."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		working_directory: {
+			common:      false
+			required:    false
+			description: "The directory in which to run the command."
+			type: string: {
+				default: null
+				syntax:  "literal"
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		backpressure: {
+			title: "Backpressure"
+			body: """
+				Events are written to the stdin of the subprocess as it reads them. Once the pipe is
+				full, or `max_pending_events` events are waiting to be acknowledged, the sink stops
+				accepting events, which lets its buffer fill up.
+				"""
+		}
+		restarts: {
+			title: "Restarts"
+			body: """
+				The subprocess is restarted whenever it exits while there are events to deliver,
+				waiting `restart_initial_backoff_secs` seconds before the first restart and twice as
+				long after each consecutive exit, up to `restart_max_backoff_secs`. The wait is reset once
+				the new subprocess accepts an event. Events the previous subprocess did not
+				acknowledge are written again to the new one first.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+		subprocess_exits_total:           components.sources.internal_metrics.output.metrics.subprocess_exits_total
+	}
+}
@@ -62,6 +62,67 @@ components: sinks: file: {
 				unit:    null
 			}
 		}
+		rotate: {
+			common:      false
+			description: "Rotates the files written by this sink, so that Vector can be used as a local spooler without coordinating with `logrotate`. Once a file is rotated, the next event for its path is written to a fresh file."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_size_bytes: {
+						common:      true
+						description: "Rotate a file once it grows past this many bytes. At least one of `max_size_bytes` and `max_age_secs` must be set."
+						required:    false
+						type: uint: {
+							default: null
+							unit:    "bytes"
+						}
+					}
+					max_age_secs: {
+						common:      true
+						description: "Rotate a file once it has existed for this many seconds. The age is checked whenever an event is written to the file. At least one of `max_size_bytes` and `max_age_secs` must be set."
+						required:    false
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
+					suffix_format: {
+						common:      false
+						description: "The [strftime](\(urls.strptime_specifiers)) format of the suffix appended to the name of a rotated file, after a `.`. If a rotated file with the same name already exists, a numeric suffix is added."
+						required:    false
+						type: string: {
+							default: "%Y%m%dT%H%M%S"
+							syntax:  "literal"
+						}
+					}
+					compression: {
+						common:      false
+						description: "The compression applied to rotated files. Can't be used together with the sink-level `compression` option."
+						required:    false
+						type: string: {
+							default: "none"
+							enum: {
+								none: "Rotated files are left uncompressed."
+								gzip: "Rotated files are compressed with gzip, and get a `.gz` extension."
+								zstd: "Rotated files are compressed with zstd, and get a `.zst` extension."
+							}
+							syntax: "literal"
+						}
+					}
+					keep_files: {
+						common:      false
+						description: "The maximum number of rotated files to keep for each path. The oldest rotated files are removed first. Only files named after the path followed by a suffix in `suffix_format` count as rotated files, so other files in the same directory are left alone. By default all rotated files are kept."
+						required:    false
+						type: uint: {
+							default: null
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		path: {
 			description: "File name to write events to."
 			required:    true
@@ -0,0 +1,263 @@
+package metadata
+
+components: sinks: gcp_bigquery: {
+	title: "GCP BigQuery"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    9000000
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: false
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			to: {
+				service: services.gcp_bigquery
+
+				interface: {
+					socket: {
+						api: {
+							title: "BigQuery Storage Write API"
+							url:   urls.big_query_storage_write_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		api_key: {
+			common:      false
+			description: "A [Google Cloud API key](\(urls.gcp_authentication_api_key)) used to authenticate access to the table. Either this or `credentials_path` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${GCP_API_KEY}", "ef8d5de700e7989468166c40fc8a0ccd"]
+				syntax: "literal"
+			}
+		}
+		columns: {
+			description: "The columns of the rows written to the table, and the event fields they're taken from. Events missing a field, or whose field can't be converted to the column type, leave the column `NULL`."
+			required:    true
+			warnings: []
+			type: array: items: type: object: options: {
+				field: {
+					common:      false
+					description: "The event field holding the column value. Defaults to the column name."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["message", "parent.child"]
+						syntax: "literal"
+					}
+				}
+				name: {
+					description: "The name of the column in the table."
+					required:    true
+					warnings: []
+					type: string: {
+						examples: ["message", "timestamp"]
+						syntax: "literal"
+					}
+				}
+				type: {
+					common:      true
+					description: "The type of the column. Only used when `row_encoding` is `proto`."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "string"
+						enum: {
+							string:    "A `STRING` column, maps and arrays are written as JSON."
+							int64:     "An `INT64` column."
+							float64:   "A `FLOAT64` column."
+							bool:      "A `BOOL` column."
+							bytes:     "A `BYTES` column."
+							timestamp: "A `TIMESTAMP` column, from timestamps, RFC 3339 strings, or microseconds since the Unix epoch."
+							json:      "A `JSON` column."
+						}
+						syntax: "literal"
+					}
+				}
+			}
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the table. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+				syntax: "literal"
+			}
+		}
+		dataset: {
+			description: "The dataset within the project holding the table."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["my-dataset"]
+				syntax: "literal"
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint to which to send data."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://bigquerystorage.googleapis.com"
+				examples: ["https://bigquerystorage.googleapis.com"]
+				syntax: "literal"
+			}
+		}
+		project: {
+			description: "The project holding the dataset."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector-123456"]
+				syntax: "literal"
+			}
+		}
+		row_encoding: {
+			common:      false
+			description: "How column values are encoded."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "proto"
+				enum: {
+					proto: "Encode each column as its configured `type`."
+					json:  "Encode each column as the JSON representation of its event field, for tables whose columns are of the `JSON` type."
+				}
+				syntax: "literal"
+			}
+		}
+		skip_authentication: {
+			common:      false
+			description: "Skip all authentication handling. For use with integration tests only."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		table: {
+			description: "The table rows are written to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["my-table"]
+				syntax: "literal"
+			}
+		}
+		write_mode: {
+			common:      false
+			description: "The stream rows are appended to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "default"
+				enum: {
+					default:   "Append to the table's default stream. Rows are available right away, but may be duplicated when appends are retried."
+					committed: "Append to a committed stream created by Vector, using offsets so that retried appends don't duplicate rows."
+				}
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		row_errors: {
+			title: "Row errors"
+			body: """
+				When BigQuery rejects some rows of an append, for example because they don't
+				match the table schema, those rows are dropped and the remaining rows of the
+				batch are appended again. Dropped rows are reported by the
+				`events_discarded_total` metric.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "bigquery"
+
+			policies: [
+				{
+					_action: "tables.get"
+					required_for: ["healthcheck"]
+				},
+				{
+					_action: "tables.updateData"
+					required_for: ["operation"]
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
+		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
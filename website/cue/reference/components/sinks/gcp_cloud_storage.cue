@@ -177,6 +177,7 @@ components: sinks: gcp_cloud_storage: {
 				syntax: "literal"
 			}
 		}
+		parquet: configuration._parquet
 		storage_class: {
 			category:    "Storage"
 			common:      false
@@ -295,5 +296,6 @@ components: sinks: gcp_cloud_storage: {
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
 		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
 		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+		parquet_values_mismatched_total:  components.sources.internal_metrics.output.metrics.parquet_values_mismatched_total
 	}
 }
@@ -68,6 +68,20 @@ components: sinks: honeycomb: {
 	}
 
 	configuration: {
+		api: {
+			common:      false
+			description: "The Honeycomb API that events are sent to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "events"
+				enum: {
+					events:  "Events are sent in batches to the [batched events API](\(urls.honeycomb_batch))."
+					markers: "Each event is sent as a marker through the [markers API](\(urls.honeycomb_markers)), to annotate a dataset with deploys and the like."
+				}
+				syntax: "literal"
+			}
+		}
 		api_key: {
 			description: "The team key that will be used to authenticate against Honeycomb."
 			required:    true
@@ -78,14 +92,36 @@ components: sinks: honeycomb: {
 			}
 		}
 		dataset: {
-			description: "The dataset that Vector will send logs to."
+			description: "The dataset that Vector will send logs to. Events are batched per dataset."
 			required:    true
 			warnings: []
 			type: string: {
-				examples: ["my-honeycomb-dataset"]
+				examples: ["my-honeycomb-dataset", "{{ service }}", "__all__"]
+				syntax: "template"
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The base URL of the Honeycomb API."
+			required:    false
+			warnings: []
+			type: string: {
+				default:  "https://api.honeycomb.io"
+				examples: ["https://api.eu1.honeycomb.io"]
 				syntax: "literal"
 			}
 		}
+		sample_rate_key: {
+			common:        false
+			description:   "The field holding the rate events were sampled at upstream, sent as the Honeycomb sample rate of events so that they keep their weight. The field is removed from the event when it holds a positive integer."
+			relevant_when: "api = `events`"
+			required:      false
+			warnings: []
+			type: string: {
+				default: "sample_rate"
+				syntax:  "literal"
+			}
+		}
 	}
 
 	input: {
@@ -94,6 +130,25 @@ components: sinks: honeycomb: {
 	}
 
 	how_it_works: {
+		markers: {
+			title: "Markers"
+			body:  """
+				With `api` set to `markers`, each event is sent as a marker, the
+				`message`, `type` and `url` fields of the event becoming those of the
+				marker. The marker starts at the timestamp of the event, and ends at its
+				`end_time` field if present, as a timestamp or Unix seconds. Markers sent
+				to the `__all__` dataset annotate every dataset of the environment.
+				"""
+		}
+		sampling: {
+			title: "Sampling"
+			body:  """
+				Events sampled upstream, for example by the `sample` or `pressure_sample`
+				transforms, are sent with their sample rate, taken from the
+				`sample_rate_key` field, so that Honeycomb weighs each of them as the
+				events they stand for.
+				"""
+		}
 		setup: {
 			title: "Setup"
 			body:  """
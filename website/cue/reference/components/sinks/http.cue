@@ -25,7 +25,7 @@ components: sinks: http: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -96,8 +96,8 @@ components: sinks: http: {
 			required: true
 			warnings: []
 			type: string: {
-				examples: ["https://10.22.212.22:9000/endpoint"]
-				syntax: "literal"
+				examples: ["https://10.22.212.22:9000/endpoint", "https://{{ tenant }}.example.com/logs/{{ index }}/_bulk"]
+				syntax: "template"
 			}
 		}
 		healthcheck: type: object: options: uri: {
@@ -121,6 +121,23 @@ components: sinks: http: {
 		metrics: null
 	}
 
+	how_it_works: {
+		templated_uri: {
+			title: "Templated URIs"
+			body:  """
+				When the `uri` contains `{{ field }}` placeholders, it is rendered for each event
+				and events are batched by their rendered URI, so that a single sink can send
+				each tenant's or index's events to its own endpoint. The URI is rendered before
+				the `encoding` rules are applied, so the fields it is rendered from can be left
+				out of the requests with `encoding.except_fields`.
+
+				Events missing the fields of the template, or rendering an invalid URI, are
+				dropped. Credentials can't be part of templated URIs, and must be set with the
+				`auth` options instead.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
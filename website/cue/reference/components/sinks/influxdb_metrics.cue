@@ -53,6 +53,8 @@ components: sinks: influxdb_metrics: {
 	}
 
 	configuration: sinks._influxdb.configuration & {
+		quantiles:      sinks.prometheus_remote_write.configuration.quantiles
+		quantile_error: sinks.prometheus_remote_write.configuration.quantile_error
 		default_namespace: {
 			common: true
 			description: """
@@ -126,6 +126,66 @@ components: sinks: kafka: {
 				}
 			}
 		}
+		schema_registry: {
+			common:      false
+			description: """
+				Encode log events with a schema from a schema registry instead of `encoding.codec`. Events are
+				written in the registry wire format, which prefixes the payload with the schema ID. If `schema` is
+				set, it's registered under `subject` on startup, otherwise the latest version of `subject` is used.
+				"""
+			required:    false
+			warnings: ["Only log events can be encoded, and only with Avro and JSON schemas. Other events are dropped."]
+			type: object: {
+				examples: []
+				options: components._kafka._schema_registry & {
+					auth: configuration._http_auth & {_args: {
+						password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+						username_example: "${SCHEMA_REGISTRY_USERNAME}"
+					}}
+					schema: {
+						common:      false
+						description: "The schema to register and encode events with."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: [#"{"type": "record", "name": "log", "fields": [{"name": "message", "type": "string"}]}"#]
+							syntax: "literal"
+						}
+					}
+					schema_type: {
+						common:      false
+						description: "The type of `schema`."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "avro"
+							enum: {
+								avro:     "An [Avro](\(urls.apache_avro)) schema."
+								json:     "A JSON Schema. Events are encoded as JSON."
+								protobuf: "A Protobuf schema. Events are encoded as the first message type it defines."
+							}
+							syntax: "literal"
+						}
+					}
+					subject: {
+						description: "The subject the schema is registered under. With the default naming strategy, this is the topic name followed by `-value`."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["logs-value"]
+							syntax: "literal"
+						}
+					}
+					tls: configuration._tls_connect & {_args: {
+						can_enable:             false
+						can_verify_certificate: true
+						can_verify_hostname:    true
+						enabled_default:        false
+					}}
+				}
+			}
+		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
 		topic: {
 			description: "The Kafka topic name to write events to."
@@ -168,6 +228,11 @@ components: sinks: kafka: {
 		processing_errors_total:             components.sources.internal_metrics.output.metrics.processing_errors_total
 		kafka_queue_messages:                components.sources.internal_metrics.output.metrics.kafka_queue_messages
 		kafka_queue_messages_bytes:          components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
+		kafka_queue_messages_max:            components.sources.internal_metrics.output.metrics.kafka_queue_messages_max
+		kafka_queue_messages_bytes_max:      components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes_max
+		kafka_queue_utilization_ratio:       components.sources.internal_metrics.output.metrics.kafka_queue_utilization_ratio
+		kafka_delivery_latency_seconds:      components.sources.internal_metrics.output.metrics.kafka_delivery_latency_seconds
+		kafka_delivery_errors_total:         components.sources.internal_metrics.output.metrics.kafka_delivery_errors_total
 		kafka_requests_total:                components.sources.internal_metrics.output.metrics.kafka_requests_total
 		kafka_requests_bytes_total:          components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
 		kafka_responses_total:               components.sources.internal_metrics.output.metrics.kafka_responses_total
@@ -0,0 +1,220 @@
+package metadata
+
+components: sinks: local_database: {
+	title: "Local Database"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: enabled:    false
+			request: enabled:     false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		path: {
+			description: "The path of the database file. The file and its table are created if they don't exist."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["/var/lib/vector/events.db"]
+				syntax: "literal"
+			}
+		}
+		engine: {
+			common:      true
+			description: "The embedded database engine used to write the file."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "sqlite"
+				enum: {
+					sqlite: "Write a [SQLite](\(urls.sqlite)) database."
+					duckdb: "Write a [DuckDB](\(urls.duckdb)) database. Requires Vector to be built with the `sinks-local_database-duckdb` feature."
+				}
+				syntax: "literal"
+			}
+		}
+		table: {
+			common:      true
+			description: "The table events are inserted into. Only letters, digits and underscores are allowed."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "events"
+				syntax:  "literal"
+			}
+		}
+		columns: {
+			common:      true
+			description: "The columns of the table, and the event fields stored in them. By default the event timestamp is stored in a `timestamp` column and the whole event is stored as JSON in an `event` column."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: object: {
+					examples: []
+					options: {
+						name: {
+							description: "The column name. Only letters, digits and underscores are allowed."
+							required:    true
+							warnings: []
+							type: string: {
+								examples: ["message", "status"]
+								syntax: "literal"
+							}
+						}
+						field: {
+							common:      true
+							description: "The event field stored in the column. `.` stores the whole event. Defaults to the column name."
+							required:    false
+							warnings: []
+							type: string: {
+								default: null
+								examples: ["message", "http.status", "."]
+								syntax: "literal"
+							}
+						}
+						type: {
+							common:      true
+							description: "The type of the column. Values that can't be converted to this type are stored as `NULL`."
+							required:    false
+							warnings: []
+							type: string: {
+								default: "text"
+								enum: {
+									text:      "A string. Non-string values are stored as their JSON representation."
+									integer:   "A 64-bit signed integer."
+									real:      "A 64-bit floating point number."
+									boolean:   "A boolean."
+									timestamp: "An RFC 3339 timestamp, in UTC."
+									json:      "The value encoded as JSON."
+								}
+								syntax: "literal"
+							}
+						}
+					}
+				}
+			}
+		}
+		wal: {
+			common:      false
+			description: "Whether to open SQLite databases in [write-ahead logging](\(urls.sqlite_wal)) mode, so that the file can be queried while Vector writes to it. DuckDB always uses its own write-ahead log."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		max_file_size_bytes: {
+			common:      false
+			description: "Rotate the database once it, together with its write-ahead log, grows past this many bytes. The current file is renamed with a timestamp suffix and a fresh database is created at `path`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    "bytes"
+			}
+		}
+		retention: {
+			common:      false
+			description: "Limits how much data is kept on disk."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_age_secs: {
+						common:      true
+						description: "Delete rows whose timestamp column is older than this many seconds, and rotated files last modified longer ago. Requires a column of type `timestamp`, the first of which is indexed."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
+					max_files: {
+						common:      true
+						description: "The maximum number of rotated files to keep. The oldest rotated files are removed first."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    null
+						}
+					}
+				}
+			}
+		}
+		transaction_max_events: {
+			common:      false
+			description: "The maximum number of events inserted in a single transaction."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "events"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		querying: {
+			title: "Querying"
+			body: """
+				The database is a regular SQLite or DuckDB file, so it can be
+				queried on the edge host with the `sqlite3` or `duckdb` command
+				line tools, or shipped elsewhere for analysis. With `wal`
+				enabled, readers don't block Vector while it writes.
+				"""
+		}
+		rotation_and_retention: {
+			title: "Rotation & Retention"
+			body: """
+				Retention by age is enforced at most once a minute, by deleting
+				rows whose timestamp is older than `retention.max_age_secs`.
+				Rotated files are removed once they are older than
+				`retention.max_age_secs`, or when there are more than
+				`retention.max_files` of them.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
@@ -86,6 +86,45 @@ components: sinks: loki: {
 			password_example: "${LOKI_PASSWORD}"
 			username_example: "${LOKI_USERNAME}"
 		}}
+		label_cardinality: {
+			common:      false
+			description: """
+				Protects Loki from label cardinality explosions, such as those caused by a label templated
+				from a field with unbounded values. Once a tenant has used `max_label_sets` distinct label
+				sets since the last flush, events with other label sets are sent with `fallback_labels`
+				instead.
+				"""
+			required: false
+			warnings: []
+			type: object: options: {
+				fallback_labels: {
+					common:      false
+					description: "The labels of events beyond the limit, `{agent = \"vector\"}` by default. These aren't templatable."
+					required:    false
+					warnings: []
+					type: object: {
+						examples: [{agent: "vector", overflow: "true"}]
+						options: {
+							"*": {
+								description: "A fallback label."
+								required:    true
+								warnings: []
+								type: string: syntax: "literal"
+							}
+						}
+					}
+				}
+				max_label_sets: {
+					description: "The maximum number of distinct label sets per tenant between flushes."
+					required:    true
+					warnings: []
+					type: uint: {
+						examples: [1000]
+						unit: null
+					}
+				}
+			}
+		}
 		labels: {
 			description: """
 				A set of labels that are attached to each batch of events. Both keys and values are templatable, which
@@ -157,6 +196,9 @@ components: sinks: loki: {
 				The tenant id that's sent with every request, by default this is not required since a proxy should set
 				this header. When running Loki locally a tenant id is not required either.
 
+				When templated, events are batched per tenant, so that each request only holds the events of the
+				tenant in its `X-Scope-OrgID` header.
+
 				You can read more about tenant id's [here](\(urls.loki_multi_tenancy)).
 				"""
 			required:    false
@@ -206,6 +248,7 @@ components: sinks: loki: {
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
 		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
 		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+		label_set_limit_exceeded_total:   components.sources.internal_metrics.output.metrics.label_set_limit_exceeded_total
 		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
 		streams_total:                    components.sources.internal_metrics.output.metrics.streams_total
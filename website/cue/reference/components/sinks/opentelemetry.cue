@@ -0,0 +1,183 @@
+package metadata
+
+components: sinks: opentelemetry: {
+	title: "OpenTelemetry"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    4000000
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip", "zstd"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: enabled: false
+			proxy: enabled:    true
+			request: {
+				enabled: true
+				headers: true
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.opentelemetry
+
+				interface: {
+					socket: {
+						api: {
+							title: "OpenTelemetry protocol"
+							url:   urls.otlp
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		endpoint: {
+			description: "The endpoint records are exported to. With the `http` protocol, the `/v1/logs` and `/v1/metrics` paths are appended to it."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["http://localhost:4317", "https://otlp.example.com:4318"]
+				syntax: "literal"
+			}
+		}
+		protocol: {
+			common:      true
+			description: "The transport records are exported with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "grpc"
+				enum: {
+					grpc: "Export with OTLP/gRPC. Only `gzip` compression is supported."
+					http: "Export with OTLP/HTTP, encoding requests as binary protobuf."
+				}
+				syntax: "literal"
+			}
+		}
+		remove_resource_fields: {
+			common:      false
+			description: "If set to `true`, removes the event fields, or metric tags, that `resource_attributes` are rendered from."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		resource_attributes: {
+			common:      true
+			description: "The attributes of the resource producing the events, rendered from each event. Records sharing the same resource are grouped together."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"service.name": "{{ service }}", "deployment.environment": "production"}]
+				options: {
+					"*": {
+						description: "The value of the resource attribute."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["{{ service }}", "production"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			summary:      true
+			set:          true
+		}
+	}
+
+	how_it_works: {
+		log_records: {
+			title: "Log records"
+			body: """
+				Each log event is exported as a log record. The message becomes its body, the
+				timestamp its time, and the remaining fields its attributes, except for the
+				`severity_text`, `severity_number`, `trace_id` and `span_id` fields, which are
+				mapped to the record fields of the same name. Trace and span IDs must be
+				hexadecimal strings.
+				"""
+		}
+		metrics: {
+			title: "Metrics"
+			body: """
+				Metric tags become data point attributes, and the namespace is prepended to
+				the name, separated with a dot. Incremental metrics are exported with the
+				delta aggregation temporality, and absolute ones with the cumulative
+				temporality. Counters are exported as monotonic sums, gauges as gauges, or
+				non-monotonic sums when incremental, and sets as gauges of their size.
+				Aggregated histograms and summaries keep their buckets and quantiles, while
+				distributions are exported as histograms with a single bucket.
+				"""
+		}
+		traces: {
+			title: "Traces"
+			body: """
+				Vector doesn't have a trace event type yet, so spans can't be exported.
+				Log records do carry the trace and span IDs of their events, correlating them
+				with the traces exported by other components.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -78,6 +78,27 @@ components: sinks: prometheus_exporter: {
 				syntax: "literal"
 			}
 		}
+		allowed_client_names: {
+			common:      false
+			description: """
+				The names of the clients allowed to scrape, matched against the common name and the DNS
+				subject alternative names of their certificate. Requires `tls.enabled`, and clients are then
+				required to present a certificate signed by the authority of `tls.ca_file`.
+				"""
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["prometheus.example.org"]
+					syntax: "literal"
+				}
+			}
+		}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${SCRAPE_PASSWORD}"
+			username_example: "prometheus"
+		}}
 		buckets: {
 			common:      false
 			description: """
@@ -117,6 +138,17 @@ components: sinks: prometheus_exporter: {
 				syntax: "literal"
 			}
 		}
+		path: {
+			common:      false
+			description: "The path metrics are exposed at."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/metrics"
+				examples: ["/metrics", "/vector/metrics"]
+				syntax: "literal"
+			}
+		}
 		quantiles: {
 			common:      false
 			description: """
@@ -130,6 +162,39 @@ components: sinks: prometheus_exporter: {
 				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
 			}
 		}
+		multi_value_tags: {
+			common:      false
+			description: "How to encode the tags of a metric that were set more than once, as Prometheus labels cannot be repeated."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					strategy: {
+						description: "The encoding strategy of the tags with more than one value."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "single"
+							enum: {
+								joined: "Join the values of the tag into one value."
+								single: "Only keep the last value of the tag."
+							}
+							syntax: "literal"
+						}
+					}
+					separator: {
+						description: "The separator of the values when the `joined` strategy is used."
+						required:    false
+						warnings: []
+						type: string: {
+							default: ","
+							syntax:  "literal"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -364,6 +429,17 @@ components: sinks: prometheus_exporter: {
 			]
 		}
 
+		securing_the_endpoint: {
+			title: "Securing the Endpoint"
+			body:  """
+				The scrape endpoint is open to anyone reaching its address by default. Setting `auth`
+				requires scrapers to present basic authentication credentials or a bearer token, and
+				requests without them are answered with `401 Unauthorized`. With `tls` enabled and
+				`allowed_client_names` set, only scrapers with a client certificate signed by the
+				authority of `tls.ca_file` and naming one of the allowed names can connect.
+				"""
+		}
+
 		memory_usage: {
 			title: "Memory Usage"
 			body: """
@@ -124,6 +124,42 @@ components: sinks: prometheus_remote_write: {
 				items: type: float: examples: [0.005, 0.01]
 			}
 		}
+		exemplar_tag: {
+			common:      false
+			description: "The tag holding trace IDs, which [exemplars](\(urls.prometheus_exemplars)) of histograms and distributions are made of. The tag is removed from these metrics, so that each trace doesn't make a series of its own, and the latest trace ID of each series is sent as an exemplar, labeled with the tag name. Exemplars of distributions take the value of their latest sample, and those of aggregated histograms the mean of their samples. With classic histograms, exemplars are attached to the bucket holding their value."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["trace_id"]
+				syntax: "literal"
+			}
+		}
+		histogram_encoding: {
+			common:      false
+			description: "How [distribution](\(urls.vector_metric)/#distribution) metrics aggregated into histograms are sent. Aggregated histograms, whose buckets are already set, are always sent as classic histograms."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "classic"
+				enum: {
+					classic: "As cumulative `_bucket` series, with the upper limits set by `buckets`."
+					native:  "As [native histograms](\(urls.prometheus_native_histograms)), whose buckets are exponential, as accepted by Grafana Mimir and Prometheus with native histograms enabled."
+				}
+				syntax: "literal"
+			}
+		}
+		multi_value_tags: sinks.prometheus_exporter.configuration.multi_value_tags
+		native_histogram_schema: {
+			common:      false
+			description: "The resolution of native histograms, from -4 to 8, each power of two being divided into `2^schema` buckets. Only used when `histogram_encoding` is `native`."
+			required:    false
+			warnings: []
+			type: int: {
+				default: 3
+				examples: [0, 3, 8]
+			}
+		}
 		quantiles: {
 			common:      false
 			description: "Quantiles to use for aggregating [distribution](\(urls.vector_metric)/#distribution) metrics into a summary."
@@ -134,9 +170,25 @@ components: sinks: prometheus_remote_write: {
 				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
 			}
 		}
+		quantile_error: {
+			common:      false
+			description: """
+				The maximum error of the quantiles computed from [distribution](\(urls.vector_metric)/#distribution)
+				metrics, as a fraction of their count of samples: with `0.01`, the value of a quantile lies
+				within 1% of the samples from its exact rank. The samples of each distribution are merged
+				into batches accordingly, preserving its minimum, maximum, sum and count, which bounds the
+				size of the batches sent. By default, every distinct sample is kept and quantiles are exact.
+				"""
+			required: false
+			warnings: []
+			type: float: {
+				default: 0.0
+				examples: [0.001, 0.01]
+			}
+		}
 		tenant_id: {
 			common:      false
-			description: "If set, a header named `X-Scope-OrgID` will be added to outgoing requests with the text of this setting. This may be used by Cortex or other remote services to identify the tenant making the request."
+			description: "If set, a header named `X-Scope-OrgID` will be added to outgoing requests with the text of this setting. This may be used by Cortex, Grafana Mimir or other remote services to identify the tenant making the request. When templated, metrics are batched separately for each tenant."
 			required:    false
 			warnings: []
 			type: string: {
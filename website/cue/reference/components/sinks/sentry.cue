@@ -0,0 +1,200 @@
+package metadata
+
+components: sinks: sentry: {
+	title: "Sentry"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["Sentry"]
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled:        true
+				headers:        false
+				rate_limit_num: 10
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.sentry
+
+				interface: {
+					socket: {
+						api: {
+							title: "Sentry store API"
+							url:   urls.sentry_store
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		dsn: {
+			description: "The [DSN](\(urls.sentry_dsn)) of the Sentry project to send the events to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["https://public_key@o0.ingest.sentry.io/0", "${SENTRY_DSN}"]
+				syntax: "literal"
+			}
+		}
+		min_level: {
+			common:      true
+			description: "The lowest level of the events to send. Events below it are dropped, events without a known level are considered errors."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "error"
+				enum: {
+					debug:   "Send debug events and above."
+					info:    "Send info events and above."
+					warning: "Send warning events and above."
+					error:   "Send error events and above."
+					fatal:   "Only send fatal events."
+				}
+				syntax: "literal"
+			}
+		}
+		fields: {
+			common:      false
+			description: "The fields the Sentry events are built from."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					level: {
+						description: "The field holding the level of the event, such as `warn`, `error` or `critical`."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "log.level"
+							syntax:  "literal"
+						}
+					}
+					exception_type: {
+						description: "The field holding the type of the exception."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "error.type"
+							syntax:  "literal"
+						}
+					}
+					exception_value: {
+						description: "The field holding the value of the exception, the message of the event is used if it's missing."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "error.message"
+							syntax:  "literal"
+						}
+					}
+					stacktrace: {
+						description: "The field holding the stack trace of the exception, either as text or an array of lines. Python, JVM, JavaScript and Ruby stack traces are parsed into frames, stack traces that can't be parsed are sent as extra data."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "error.stack_trace"
+							syntax:  "literal"
+						}
+					}
+					release: {
+						description: "The field holding the release of the application."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "service.version"
+							syntax:  "literal"
+						}
+					}
+					environment: {
+						description: "The field holding the environment of the application."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "service.environment"
+							syntax:  "literal"
+						}
+					}
+				}
+			}
+		}
+		tags: {
+			common:      true
+			description: "The fields to send as Sentry tags. The other fields of the events are sent as extra data."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["region", "kubernetes.pod_name"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		rate_limits: {
+			title: "Rate limits"
+			body:  """
+				Requests rejected by Sentry with a `429 Too Many Requests` status are retried after the
+				number of seconds of their `Retry-After` header, or after the usual backoff if the header
+				is missing.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
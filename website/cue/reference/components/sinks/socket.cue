@@ -78,6 +78,37 @@ components: sinks: socket: {
 				syntax: "literal"
 			}
 		}
+		framing: {
+			common:      false
+			description: "How the encoded events are delimited when written to the socket."
+			required:    false
+			type: object: options: {
+				method: {
+					description: "The framing method."
+					required:    false
+					common:      true
+					type: string: {
+						default: "newline_delimited"
+						enum: {
+							newline_delimited:       "Each event is followed by a newline."
+							character_delimited:     "Each event is followed by the `delimiter` character."
+							length_delimited:        "Each event is preceded by its length in bytes, as a 4 bytes big-endian integer."
+							varint_length_delimited: "Each event is preceded by its length in bytes, as a [varint](\(urls.protobuf_varint)), as when streaming [protobuf](\(urls.protobuf)) messages."
+						}
+						syntax: "literal"
+					}
+				}
+				delimiter: {
+					description:   "The character following each event."
+					relevant_when: "method = `character_delimited`"
+					required:      true
+					type: string: {
+						examples: ["\\t", "\\u0000"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
 		mode: {
 			description: "The type of socket to use."
 			required:    true
@@ -61,6 +61,40 @@ components: sinks: statsd: {
 				syntax: "literal"
 			}
 		}
+		multi_value_tags: {
+			common:      false
+			description: "How to encode the tags of a metric that were set more than once, such as a repeated tag key in a statsd packet."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					strategy: {
+						description: "The encoding strategy of the tags with more than one value."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "full"
+							enum: {
+								full:   "Repeat the tag for each of its values."
+								joined: "Join the values of the tag into one value."
+								single: "Only keep the last value of the tag."
+							}
+							syntax: "literal"
+						}
+					}
+					separator: {
+						description: "The separator of the values when the `joined` strategy is used."
+						required:    false
+						warnings: []
+						type: string: {
+							default: ","
+							syntax:  "literal"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	telemetry: metrics: {
@@ -87,14 +87,83 @@ components: sinks: vector: {
 
 	configuration: {
 		address: {
-			description: "The downstream Vector address to connect to. The address _must_ include a port."
-			required:    true
+			description: "The downstream Vector address to connect to. The address _must_ include a port. Either this or `addresses` must be set."
+			common:      true
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["92.12.333.224:\(_port)"]
 				syntax: "literal"
 			}
 		}
+		addresses: {
+			description: "Several downstream Vector addresses to spread events across, according to `load_balancing`. Only supported by version 2 of the sink. Either this or `address` must be set."
+			common:      false
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["10.0.0.1:\(_port)", "10.0.0.2:\(_port)"]
+					syntax: "literal"
+				}
+			}
+		}
+		load_balancing: {
+			description: "Configures how events are spread across `addresses`, and when failing addresses are ejected. Only supported by version 2 of the sink."
+			common:      false
+			required:    false
+			warnings: []
+			type: object: options: {
+				ejection_secs: {
+					description: "The interval at which an ejected address is health checked, until it's healthy and reinstated."
+					common:      false
+					required:    false
+					warnings: []
+					type: uint: {
+						default: 30
+						unit:    "seconds"
+					}
+				}
+				failure_threshold: {
+					description: "The number of consecutive requests to an address that must fail with a transient error for it to be ejected. When every address is ejected, events are sent regardless."
+					common:      false
+					required:    false
+					warnings: []
+					type: uint: {
+						default: 3
+						unit:    null
+					}
+				}
+				key: {
+					description: "The key events are hashed by with the `consistent_hash` strategy. Events with the same key are sent to the same address for as long as it's healthy, preserving their order."
+					common:      false
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["{{ host }}", "{{ tenant_id }}"]
+						syntax: "template"
+					}
+				}
+				strategy: {
+					description: "How events are spread across addresses."
+					common:      false
+					required:    false
+					warnings: []
+					type: string: {
+						default: "round_robin"
+						enum: {
+							round_robin:     "Send each batch to the next healthy address."
+							least_in_flight: "Send each batch to the healthy address with the fewest requests in flight."
+							consistent_hash: "Send events to the address their `key` hashes to, failing over to the next healthy address."
+						}
+						syntax: "literal"
+					}
+				}
+			}
+		}
 		version: {
 			description: "Sink API version. Specifying this version ensures that Vector does not break backward compatibility."
 			common:      true
@@ -116,6 +185,7 @@ components: sinks: vector: {
 	telemetry: metrics: {
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		endpoint_ejections_total:         components.sources.internal_metrics.output.metrics.endpoint_ejections_total
 		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:           components.sources.internal_metrics.output.metrics.processed_events_total
 		protobuf_decode_errors_total:     components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
@@ -0,0 +1,233 @@
+package metadata
+
+components: sinks: webhook_fanout: {
+	title: "Webhook Fan-out"
+
+	description: """
+		Delivers every event to several webhooks, each with its own body template, authentication,
+		retries and circuit breaker, rather than requiring a separate `http` sink per endpoint.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10000000
+				max_events:   1
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: enabled: false
+			tls: enabled:     false
+			to: {
+				service: {
+					name:     "webhook"
+					thing:    "several \(name)s"
+					url:      urls.http_server
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		endpoints: {
+			description: "The endpoints every event is delivered to."
+			required:    true
+			warnings: []
+			type: array: items: type: object: {
+				examples: [{name: "alerting", uri: "https://alerting.example.com/hooks/vector", body: "{\"summary\": \"{{ message }}\"}"}]
+				options: {
+					auth: configuration._http_auth & {_args: {
+						password_example: "${WEBHOOK_PASSWORD}"
+						username_example: "${WEBHOOK_USERNAME}"
+					}}
+					body: {
+						common:      true
+						description: "The body sent for each event. Events are encoded as JSON if it isn't set. Events missing fields of the template aren't delivered to the endpoint."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{\"summary\": \"{{ message }}\", \"host\": \"{{ host }}\"}"]
+							syntax: "template"
+						}
+					}
+					circuit_breaker: {
+						common:      false
+						description: "Fails the requests to the endpoint without sending them for a while once it has failed too many requests in a row."
+						required:    false
+						warnings: []
+						type: object: options: {
+							cooldown_secs: {
+								common:      false
+								description: "How long the requests to the endpoint are failed for once it trips."
+								required:    false
+								warnings: []
+								type: uint: {
+									default: 30
+									unit:    "seconds"
+								}
+							}
+							failure_threshold: {
+								common:      false
+								description: "The number of consecutive failed requests, retries included, after which the endpoint trips."
+								required:    false
+								warnings: []
+								type: uint: {
+									default: 5
+									unit:    "requests"
+								}
+							}
+						}
+					}
+					content_type: {
+						common:      false
+						description: "The `Content-Type` header of the requests."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "application/json"
+							examples: ["text/plain"]
+							syntax: "literal"
+						}
+					}
+					max_pending_events: {
+						common:      false
+						description: "The number of events buffered for the endpoint while its requests are slower than the ones of the other endpoints. Once the buffer is full, the other endpoints are held back as well."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1000
+							unit:    "events"
+						}
+					}
+					method: {
+						common:      false
+						description: "The HTTP method of the requests."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "post"
+							enum: {
+								get:     "GET"
+								head:    "HEAD"
+								post:    "POST"
+								put:     "PUT"
+								delete:  "DELETE"
+								options: "OPTIONS"
+								trace:   "TRACE"
+								patch:   "PATCH"
+							}
+							syntax: "literal"
+						}
+					}
+					name: {
+						description: "Identifies the endpoint in logs and metrics. Names must be unique."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["alerting", "archive"]
+							syntax: "literal"
+						}
+					}
+					request: components.sinks.http.configuration.request
+					tls:     components.sinks.http.configuration.tls
+					uri: {
+						description: "The full URI to make HTTP requests to."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["https://alerting.example.com/hooks/vector"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body: """
+				By default, every event is sent in a request of its own. When `batch.max_events` is
+				raised, the bodies of the events of a batch are sent in a single request, separated by
+				newlines.
+				"""
+		}
+		independent_endpoints: {
+			title: "Independent endpoints"
+			body: """
+				Each endpoint batches, retries and limits the concurrency of its requests on its own,
+				with its `request` options. Once an endpoint fails `circuit_breaker.failure_threshold`
+				requests in a row, its requests are failed without being sent for
+				`circuit_breaker.cooldown_secs`, so that an endpoint that's down holds up the others as
+				little as possible. Events are buffered for each endpoint, up to its
+				`max_pending_events`, so that a slow endpoint doesn't hold up the others until its buffer
+				is full.
+
+				Events are acknowledged once every endpoint is done with them. When end-to-end
+				acknowledgements are enabled, an event failing to reach any of the endpoints is reported
+				as failed to its source.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		endpoint_circuit_breaks_total:    components.sources.internal_metrics.output.metrics.endpoint_circuit_breaks_total
+		endpoint_delivered_bytes_total:   components.sources.internal_metrics.output.metrics.endpoint_delivered_bytes_total
+		endpoint_delivered_events_total:  components.sources.internal_metrics.output.metrics.endpoint_delivered_events_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
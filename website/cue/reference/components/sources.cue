@@ -85,11 +85,13 @@ components: sources: [Name=string]: {
 							type: string: {
 								default: features.codecs.default_framing
 								enum: {
-									bytes:               "Byte frames are passed through as-is according to the underlying I/O boundaries (e.g. split between messages or stream segments)."
-									character_delimited: "Byte frames which are delimited by a chosen character."
-									length_delimited:    "Byte frames whose length is encoded in a header."
-									newline_delimited:   "Byte frames which are delimited by a newline character."
-									octet_counting:      "Byte frames according to the [octet counting](\(urls.rfc_6587_3_4_1)) format."
+									bytes:                   "Byte frames are passed through as-is according to the underlying I/O boundaries (e.g. split between messages or stream segments)."
+									character_delimited:     "Byte frames which are delimited by a chosen character."
+									chunked_gelf:            "Byte frames holding [GELF](\(urls.graylog_gelf)) messages, which are reassembled from their chunks and decompressed. Each byte message must hold a single chunk or an unchunked message, as sent over UDP."
+									length_delimited:        "Byte frames whose length is encoded in a header."
+									newline_delimited:       "Byte frames which are delimited by a newline character."
+									octet_counting:          "Byte frames according to the [octet counting](\(urls.rfc_6587_3_4_1)) format."
+									varint_length_delimited: "Byte frames whose length is encoded as a [varint](\(urls.protobuf_varint)) header, as when streaming [protobuf](\(urls.protobuf)) messages."
 								}
 								syntax: "literal"
 							}
@@ -119,6 +121,91 @@ components: sources: [Name=string]: {
 								}
 							}
 						}
+						chunked_gelf: {
+							description:   "Options for `chunked_gelf` framing."
+							required:      false
+							common:        false
+							relevant_when: "method = `chunked_gelf`"
+							type: object: options: {
+								decompression: {
+									description: "How messages are decompressed."
+									required:    false
+									common:      false
+									type: string: {
+										default: "auto"
+										enum: {
+											auto: "Detect gzip and zlib compression from the leading bytes of each message."
+											gzip: "Decompress messages with gzip."
+											zlib: "Decompress messages with zlib."
+											none: "Pass messages through as-is."
+										}
+										syntax: "literal"
+									}
+								}
+								max_length: {
+									description: "The maximum length of a message, once reassembled and decompressed. Longer messages are discarded."
+									required:    false
+									common:      false
+									type: uint: {
+										default: null
+										examples: [65535, 102400]
+										unit: "bytes"
+									}
+								}
+								pending_messages_limit: {
+									description: "The maximum number of messages waiting for their remaining chunks at a time. The chunks of further messages are discarded until some complete or time out."
+									required:    false
+									common:      false
+									type: uint: {
+										default: 1000
+										unit:    null
+									}
+								}
+								timeout_ms: {
+									description: "How long to wait for the remaining chunks of a message before discarding it."
+									required:    false
+									common:      false
+									type: uint: {
+										default: 5000
+										unit:    "milliseconds"
+									}
+								}
+							}
+						}
+						length_delimited: {
+							description:   "Options for `length_delimited` framing."
+							required:      false
+							common:        false
+							relevant_when: "method = `length_delimited`"
+							type: object: options: {
+								length_field_length: {
+									description: "The number of bytes of the length header."
+									required:    false
+									common:      false
+									type: uint: {
+										default: 4
+										examples: [2, 8]
+										unit: "bytes"
+									}
+								}
+								little_endian: {
+									description: "Whether the length header is encoded in little-endian rather than network byte order."
+									required:    false
+									common:      false
+									type: bool: default: false
+								}
+								max_length: {
+									description: "The maximum frame length limit. Frames announcing a longer length are rejected."
+									required:    false
+									common:      false
+									type: uint: {
+										default: null
+										examples: [65535, 102400]
+										unit: "bytes"
+									}
+								}
+							}
+						}
 						newline_delimited: {
 							description:   "Options for `newline_delimited` framing."
 							required:      false
@@ -155,6 +242,24 @@ components: sources: [Name=string]: {
 								}
 							}
 						}
+						varint_length_delimited: {
+							description:   "Options for `varint_length_delimited` framing."
+							required:      false
+							common:        false
+							relevant_when: "method = `varint_length_delimited`"
+							type: object: options: {
+								max_length: {
+									description: "The maximum frame length limit. Frames announcing a longer length are rejected, along with the rest of the stream. Defaults to 8 MiB."
+									required:    false
+									common:      false
+									type: uint: {
+										default: null
+										examples: [65535, 102400]
+										unit: "bytes"
+									}
+								}
+							}
+						}
 					}
 				}
 				decoding: {
@@ -162,6 +267,16 @@ components: sources: [Name=string]: {
 					description: "Configures in which way frames are decoded into events."
 					required:    false
 					type: object: options: {
+						charset: {
+							description: "The charset of the frames, taking one of the [label strings](\(urls.encoding_charset_labels)) defined as part of the [Encoding Standard](\(urls.encoding_standard)). When set, each frame is transcoded from it to UTF-8 before being decoded, rather than being taken as UTF-8, with any malformed sequence being replaced with the [replacement character](\(urls.unicode_replacement_character)). Frames are transcoded on their own, so with `newline_delimited` or `character_delimited` framing, the charset must encode the delimiter as in ASCII, which excludes UTF-16."
+							required:    false
+							common:      false
+							type: string: {
+								default: null
+								examples: ["shift_jis", "windows-1252", "gbk"]
+								syntax: "literal"
+							}
+						}
 						codec: {
 							description: "The decoding method."
 							required:    false
@@ -170,12 +285,89 @@ components: sources: [Name=string]: {
 								default: "bytes"
 								enum: {
 									bytes:  "Events containing the byte frame as-is."
+									cef:    "Events being parsed from a Common Event Format (CEF) message, optionally prefixed by a Syslog header."
+									csv:    "Events being parsed from a line of delimiter-separated values, with fields named after their column. Quoted values may contain the delimiter. The columns are read from the first line of the stream unless `columns` is set."
+									gelf:   "Events being parsed from a [GELF](\(urls.graylog_gelf)) 1.1 message. `short_message`, `host`, and `timestamp` are inserted at the message, host, and timestamp keys of the log schema, while other fields, including additional fields with their leading underscore, are kept as they are."
 									json:   "Events being parsed from a JSON string."
+									leef:   "Events being parsed from a Log Event Extended Format (LEEF) 1.0 or 2.0 message, optionally prefixed by a Syslog header."
 									syslog: "Events being parsed from a Syslog message."
 								}
 								syntax: "literal"
 							}
 						}
+						columns: {
+							description: "The names of the columns of the lines, when `codec` is `csv`. If empty, the first line is a header row naming them."
+							required:    false
+							common:      false
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["timestamp", "host", "message"]
+									syntax: "literal"
+								}
+							}
+						}
+						custom_labels: {
+							description: "Whether custom CEF extensions are renamed after their label, e.g. the value of `cs1` is inserted as `Rule` when the message contains `cs1Label=Rule`, when `codec` is `cef`."
+							required:    false
+							common:      false
+							type: bool: default: false
+						}
+						delimiter: {
+							description: "The character separating attributes of LEEF 1.0 messages, and LEEF 2.0 messages that don't define their own delimiter, when `codec` is `leef`, or separating the values of lines when `codec` is `csv`, in which case it defaults to `,`."
+							required:    false
+							common:      false
+							type: string: {
+								default: "\t"
+								examples: ["^", "|", ",", " "]
+								syntax: "literal"
+							}
+						}
+						dictionary: {
+							description: "Maps CEF extension keys or LEEF attribute keys to the field paths they're inserted at, when `codec` is `cef` or `leef`. Takes precedence over `standard_names`."
+							required:    false
+							common:      false
+							type: object: {
+								examples: [{"src": "source.ip", "cs1": "rule_name"}]
+								options: {
+									"*": {
+										common:      false
+										description: "The field path the key is inserted at."
+										required:    false
+										type: string: {
+											default: null
+											examples: ["source.ip"]
+											syntax: "literal"
+										}
+									}
+								}
+							}
+						}
+						engine: {
+							description: "The implementation used to parse JSON, when `codec` is `json`."
+							required:    false
+							common:      false
+							type: string: {
+								default: "serde"
+								enum: {
+									serde: "Parse JSON with `serde_json`."
+									simd:  "Parse JSON with `simd-json`, which uses SIMD instructions to speed up parsing large volumes of JSON. Requires Vector to be built with the `codecs-json-simd` feature, and falls back to `serde` unless it's also built with the `avx2` or `sse4.2` target features."
+								}
+								syntax: "literal"
+							}
+						}
+						standard_names: {
+							description: "Whether the extension keys defined by the CEF specification are renamed to their full names, e.g. `src` to `sourceAddress`, when `codec` is `cef`."
+							required:    false
+							common:      false
+							type: bool: default: false
+						}
+						w3c_directives: {
+							description: "Whether lines starting with `#` are [W3C extended log](\(urls.w3c_extended_log_format)) directives, when `codec` is `csv`. `#Fields:` directives name the columns of the lines after them, other directives are skipped, and `-` values are left out."
+							required:    false
+							common:      false
+							type: bool: default: false
+						}
 					}
 				}
 			}
@@ -261,6 +453,64 @@ components: sources: [Name=string]: {
 				}
 			}
 
+			if features.receive.connection_limits != _|_ {
+				if features.receive.connection_limits.enabled {
+					connection_limits: {
+						common:      false
+						description: "Limits keeping a single peer from starving the others. Connections over the connection limits are closed right away, while connections sending events faster than the rate limits stop being read from until their rate goes back under the limits."
+						required:    false
+						type: object: {
+							examples: []
+							options: {
+								max_connections: {
+									common:      false
+									description: "The maximum number of connections open at once."
+									required:    false
+									type: uint: {
+										default: null
+										examples: [1000]
+										unit: null
+									}
+								}
+								max_connections_per_ip: {
+									common:      false
+									description: "The maximum number of connections open at once from a single peer IP address."
+									required:    false
+									type: uint: {
+										default: null
+										examples: [10]
+										unit: null
+									}
+								}
+								max_events_per_sec_per_connection: {
+									common:      false
+									description: "The maximum number of events per second read from a single connection."
+									required:    false
+									type: uint: {
+										default: null
+										examples: [1000]
+										unit: "events"
+									}
+								}
+								max_events_per_sec_per_ip: {
+									common:      false
+									description: "The maximum number of events per second read from all the connections of a single peer IP address."
+									required:    false
+									type: uint: {
+										default: null
+										examples: [5000]
+										unit: "events"
+									}
+								}
+							}
+						}
+						if features.receive.connection_limits.relevant_when != _|_ {
+							relevant_when: features.receive.connection_limits.relevant_when
+						}
+					}
+				}
+			}
+
 			if features.receive.tls.enabled {
 				tls: configuration._tls_accept & {_args: {
 					can_enable:             features.receive.tls.can_enable
@@ -318,9 +568,11 @@ components: sources: [Name=string]: {
 						Vector checkpoints the current read position after each
 						successful read. This ensures that Vector resumes where it left
 						off if restarted, preventing data from being read twice. The
-						checkpoint positions are stored in the data directory which is
-						specified via the global `data_dir` option, but can be overridden
-						via the `data_dir` option in the file source directly.
+						checkpoint positions are stored in the `checkpoints` directory of
+						the data directory which is specified via the global `data_dir`
+						option, but can be overridden via the `data_dir` option in the
+						file source directly. Checkpoints can be inspected or reset with
+						`vector checkpoints`.
 						"""
 				}
 			}
@@ -0,0 +1,177 @@
+package metadata
+
+components: sources: auditd: {
+	title:       "Linux Audit"
+	description: "The auditd source reads the events of the Linux audit framework, reassembling the records of each event into a single structured log event."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service: {
+				name:     "Linux audit framework"
+				thing:    "the \(name)"
+				url:      "https://github.com/linux-audit/audit-documentation/wiki"
+				versions: ">= 3.16"
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            false
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: [
+			"""
+				In `netlink` mode, Vector must run with the `CAP_AUDIT_READ` capability to subscribe to the audit
+				log. In `unix` mode, the `af_unix` plugin of `audispd` must be enabled, and Vector must be able to
+				connect to its socket.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		mode: {
+			common:      true
+			description: "Where the audit records are read from."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "netlink"
+				enum: {
+					netlink: "Subscribe to the audit netlink socket. Audit rules are still managed by `auditd`, which keeps running alongside Vector."
+					unix:    "Connect to the unix socket of the `audispd` `af_unix` plugin, which must use the `string` format."
+				}
+				syntax: "literal"
+			}
+		}
+		socket_path: {
+			common:        false
+			description:   "The path of the `audispd` socket."
+			relevant_when: "mode = \"unix\""
+			required:      false
+			warnings: []
+			type: string: {
+				default: "/var/run/audispd_events"
+				syntax:  "literal"
+			}
+		}
+		reassembly_timeout_ms: {
+			common:      false
+			description: "How long to wait for the remaining records of an audit event before emitting the records received so far."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 2000
+				unit:    "milliseconds"
+			}
+		}
+		resolve_ids: {
+			common:      false
+			description: "Whether to resolve the user and group IDs of the records to their names, added as `<field>_name` fields, such as `auid_name`."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+	}
+
+	output: logs: event: {
+		description: "An audit event."
+		fields: {
+			message: {
+				description: "The records of the event, as they appear in the audit log, separated by newlines."
+				required:    true
+				type: string: {
+					examples: ["type=SYSCALL msg=audit(1364481363.243:24287): arch=c000003e syscall=2 success=no exit=-13 key=\"access\""]
+					syntax: "literal"
+				}
+			}
+			record_type: {
+				description: "The type of the first record of the event."
+				required:    true
+				type: string: {
+					examples: ["SYSCALL", "USER_LOGIN"]
+					syntax: "literal"
+				}
+			}
+			records: {
+				description: "The records of the event, with their `type` and parsed fields. Hex encoded fields are decoded, and the arguments of `EXECVE` records are collected into an `args` array."
+				required:    true
+				type: array: items: type: object: {
+					examples: [{type: "SYSCALL", syscall: "2", success: "no", exit: "-13", uid: "1000", uid_name: "alice", key: "access"}]
+					options: {}
+				}
+			}
+			rule_keys: {
+				description: "The keys of the audit rules that matched the event."
+				required:    true
+				type: array: items: type: string: {
+					examples: ["access"]
+					syntax: "literal"
+				}
+			}
+			sequence: {
+				description: "The serial number of the event, which is unique until the audit framework restarts."
+				required:    true
+				type: uint: {
+					examples: [24287]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["auditd"]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the audit event happened."
+			}
+		}
+	}
+
+	how_it_works: {
+		reassembly: {
+			title: "Event reassembly"
+			body:  """
+				The kernel reports most audit events as several records, such as `SYSCALL`, `PATH` and `CWD`,
+				sharing the same timestamp and serial number, and terminated by an `EOE` record. Vector groups
+				them into a single log event once the `EOE` record is received, or once `reassembly_timeout_ms`
+				has elapsed since the first record. The messages of userspace programs, such as `USER_LOGIN`, are
+				emitted on their own.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processing_errors_total:         components.sources.internal_metrics.output.metrics.processing_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -123,6 +123,16 @@ components: sources: aws_kinesis_firehose: {
 				syntax: "literal"
 			}
 		}
+		unwrap_cloudwatch_logs: {
+			common:      false
+			description: """
+				Detect records holding AWS CloudWatch Logs subscription messages and publish one event per log event
+				they contain, with the log group, log stream, owner, and subscription filters attached. Control
+				messages are dropped and records in any other format are decoded as usual.
+				"""
+			required:    false
+			type: bool: default: false
+		}
 	}
 
 	output: logs: {
@@ -156,6 +166,66 @@ components: sources: aws_kinesis_firehose: {
 				}
 			}
 		}
+		cloudwatch_log_event: {
+			description: "One event will be published per log event of CloudWatch Logs subscription messages if `unwrap_cloudwatch_logs` is enabled."
+			fields: {
+				timestamp: {
+					description: "The time of the log event."
+					required:    true
+					type: timestamp: {}
+				}
+				message: {
+					description: "The message of the log event."
+					required:    true
+					type: string: {
+						examples: ["Started GET / for 127.0.0.1 at 2012-03-10 14:28:14 +0100"]
+						syntax: "literal"
+					}
+				}
+				id: {
+					description: "The ID of the log event."
+					required:    true
+					type: string: {
+						examples: ["35683658089614582423604394983260738922885519999578275840"]
+						syntax: "literal"
+					}
+				}
+				log_group: {
+					description: "The log group the log event was sent to."
+					required:    true
+					type: string: {
+						examples: ["/lambda/test"]
+						syntax: "literal"
+					}
+				}
+				log_stream: {
+					description: "The log stream the log event was sent to."
+					required:    true
+					type: string: {
+						examples: ["2020/03/24/[$LATEST]794dbaf40a7846c4984ad80ebf110544"]
+						syntax: "literal"
+					}
+				}
+				owner: {
+					description: "The AWS account ID of the log group."
+					required:    true
+					type: string: {
+						examples: ["111111111111"]
+						syntax: "literal"
+					}
+				}
+				subscription_filters: {
+					description: "The names of the subscription filters that matched the log event."
+					required:    true
+					type: array: items: type: string: {
+						examples: ["Destination"]
+						syntax: "literal"
+					}
+				}
+				request_id: components.sources.aws_kinesis_firehose.output.logs.line.fields.request_id
+				source_arn: components.sources.aws_kinesis_firehose.output.logs.line.fields.source_arn
+			}
+		}
 	}
 
 	examples: [
@@ -199,11 +269,12 @@ components: sources: aws_kinesis_firehose: {
 				set this up:
 
 				1. Deploy vector with a publicly exposed HTTP endpoint using
-				   this source. You will likely also want to use the
+				   this source. Enable `unwrap_cloudwatch_logs` to
+				   extract the log events, or use the
 				   [`aws_cloudwatch_logs_subscription_parser`](\(urls.vector_transform_aws_cloudwatch_logs_subscription_parser))
-				   transform to extract the log events. Make sure to set
-				   the `access_key` to secure this endpoint. Your
-				   configuration might look something like:
+				   transform. Make sure to set the `access_key` to secure
+				   this endpoint. Your configuration might look something
+				   like:
 
 				   ```toml
 					[sources.firehose]
@@ -211,14 +282,11 @@ components: sources: aws_kinesis_firehose: {
 					type = "aws_kinesis_firehose"
 					address = "127.0.0.1:9000"
 					access_key = "secret"
-
-					[transforms.cloudwatch]
-					type = "aws_cloudwatch_logs_subscription_parser"
-					inputs = ["firehose"]
+					unwrap_cloudwatch_logs = true
 
 					[sinks.console]
 					type = "console"
-					inputs = ["cloudwatch"]
+					inputs = ["firehose"]
 					encoding.codec = "json"
 				   ```
 
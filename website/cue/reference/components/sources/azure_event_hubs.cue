@@ -0,0 +1,257 @@
+package metadata
+
+components: sources: azure_event_hubs: {
+	title: "Azure Event Hubs"
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: service:       services.azure_event_hubs
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: [
+			"""
+				The shared access policy of the connection string must have the `Listen` claim.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		checkpoint_store: {
+			common:      false
+			description: "Where to keep the ownership of partitions and the checkpointed offsets."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					type: {
+						description: "The kind of store."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								local:      "Keep checkpoints in the data directory. Partition ownership is not shared, so this Vector instance consumes every partition."
+								azure_blob: "Keep ownership and checkpoints in an Azure Blob Storage container, in the layout used by the Azure Event Hubs SDKs, to balance partitions across Vector instances."
+							}
+							syntax: "literal"
+						}
+					}
+					data_dir: {
+						common:        false
+						description:   "The directory used to persist checkpoints. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the Vector project has write permissions to this dir."
+						relevant_when: "type = \"local\""
+						required:      false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/var/lib/vector"]
+							syntax: "literal"
+						}
+					}
+					connection_string: {
+						description:   "The Azure Blob Storage account connection string."
+						relevant_when: "type = \"azure_blob\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"]
+							syntax: "literal"
+						}
+					}
+					container_name: {
+						description:   "The Azure Blob Storage container holding the ownership and checkpoint blobs."
+						relevant_when: "type = \"azure_blob\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["event-hubs-checkpoints"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		connection_string: {
+			description: "The Event Hubs connection string, with a shared access key. Its `EntityPath` names the event hub, unless `event_hub_name` is set."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["Endpoint=sb://mynamespace.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=${EVENT_HUBS_KEY};EntityPath=logs"]
+				syntax: "literal"
+			}
+		}
+		consumer_group: {
+			common:      true
+			description: "The consumer group to read the event hub as."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "$Default"
+				examples: ["vector"]
+				syntax: "literal"
+			}
+		}
+		event_hub_name: {
+			common:      false
+			description: "The event hub to consume, instead of the `EntityPath` of the connection string."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["logs"]
+				syntax: "literal"
+			}
+		}
+		load_balancing_interval_secs: {
+			common:      false
+			description: "How often to renew the ownership of partitions and rebalance them across Vector instances."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		ownership_expiration_secs: {
+			common:      false
+			description: "How long after its last renewal the ownership of a partition expires, letting other instances claim it. Must be greater than `load_balancing_interval_secs`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		start_position: {
+			common:      false
+			description: "Where to start consuming partitions without a checkpoint."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "latest"
+				enum: {
+					earliest: "Consume all the events retained by the partition."
+					latest:   "Only consume events enqueued from now on."
+				}
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Event Hubs event."
+		fields: {
+			message: {
+				description: "The body of the event."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+					syntax: "literal"
+				}
+			}
+			offset: {
+				description: "The offset of the event in its partition."
+				required:    true
+				type: string: {
+					examples: ["4096"]
+					syntax: "literal"
+				}
+			}
+			partition_id: {
+				description: "The partition that the event came from."
+				required:    true
+				type: string: {
+					examples: ["0"]
+					syntax: "literal"
+				}
+			}
+			properties: {
+				description: "The application properties of the event."
+				required:    true
+				type: object: {
+					examples: [{"origin": "web"}]
+					options: {}
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the event in its partition."
+				required:    true
+				type: uint: {
+					examples: [12]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the event was enqueued in the event hub."
+			}
+		}
+	}
+
+	how_it_works: {
+		load_balancing: {
+			title: "Partition load balancing"
+			body: """
+				Vector instances consuming the same event hub with the same consumer group and an
+				`azure_blob` checkpoint store share its partitions. Every `load_balancing_interval_secs`,
+				each instance renews the ownership of its partitions and, if it owns less than its fair
+				share, claims one more: a partition nobody owns first, or else one of the instance with
+				the most partitions. Ownership is claimed with conditional writes, so only one instance
+				wins each partition. The partitions of an instance that stops are taken over once their
+				ownership expires after `ownership_expiration_secs`, or right away if it shut down
+				gracefully.
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				Vector checkpoints the offset of the last event processed in each partition, and resumes
+				after it when it claims the partition again. With `acknowledgements` enabled, the
+				checkpoint only advances once the events were acknowledged by the sinks, in the order
+				they were received. Local checkpoints are kept under `<consumer group>/<partition ID>`
+				keys, and can be inspected or reset with `vector checkpoints`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		owned_partitions:                components.sources.internal_metrics.output.metrics.owned_partitions
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
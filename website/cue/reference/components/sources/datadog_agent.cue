@@ -69,6 +69,105 @@ components: sources: datadog_agent: {
 	configuration: {
 		acknowledgements: configuration._acknowledgements
 		address:          sources.http.configuration.address
+		allowed_client_names: {
+			common:      false
+			description: """
+				The names of the agents allowed to connect, matched against the common name and the DNS
+				subject alternative names of their certificate. Requires `tls.enabled`, and agents are then
+				required to present a certificate signed by the authority of `tls.ca_file`.
+				"""
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["agent.example.org"]
+					syntax: "literal"
+				}
+			}
+		}
+		api_key_validation: {
+			common:      false
+			description: "Restricts the Datadog API keys the source accepts payloads for. Requests without an API key, or with a key not on the allowlist, are rejected with a `403 Forbidden` response. At least one of `allowed_keys` or `allowed_keys_file` must be set."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					allowed_keys: {
+						common:      true
+						description: "The API keys accepted by the source."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["${DATADOG_API_KEY}"]
+								syntax: "literal"
+							}
+						}
+					}
+					allowed_keys_file: {
+						common:      true
+						description: "A file listing additional API keys accepted by the source, one per line. Blank lines and lines starting with `#` are ignored. The file is reloaded when it changes, without restarting the source; if it can't be read, the previously loaded keys are kept."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["/etc/vector/datadog_api_keys"]
+							syntax: "literal"
+						}
+					}
+					reload_interval_secs: {
+						common:      false
+						description: "How often to check `allowed_keys_file` for changes."
+						required:    false
+						type: uint: {
+							default: 10
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+		idempotency: configuration._http_server_idempotency
+		http2:       configuration._http_server_http2
+		keepalive:   configuration._http_server_keepalive
+		quota:       configuration._http_server_quota
+		outputs: {
+			common:      false
+			description: "Routes each kind of data received from the agent to a named output of this source, referenced as `<component_id>.<output_name>`. Kinds of data without a route are sent to the default output, and kinds routed to the same name share that output."
+			required:    false
+			type: object: {
+				examples: [{logs: "agent_logs"}]
+				options: {
+					logs: {
+						common:      false
+						description: "The name of the output to send logs to."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["agent_logs"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		permit_origin: {
+			common:      false
+			description: """
+				The networks agents are allowed to connect from, as CIDR blocks. Connections from other
+				addresses are closed as soon as they are accepted, before any TLS handshake or request
+				is read. If empty, connections are accepted from any address.
+				"""
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["10.0.0.0/8", "2001:db8::/32"]
+					syntax: "literal"
+				}
+			}
+		}
 		store_api_key: {
 			common:      false
 			description: "When incoming events contain a Datadog API key, if this setting is set to `true` the key will kept in the event metadata and will be used if the event is sent to a Datadog sink."
@@ -122,9 +221,32 @@ components: sources: datadog_agent: {
 					syntax: "literal"
 				}
 			}
+			source_subtype: {
+				description: "The kind of data the event was received as, so events stay distinguishable when different kinds share an output."
+				required:    true
+				type: string: {
+					enum: {
+						logs: "The event was received as a log."
+					}
+					syntax: "literal"
+				}
+			}
 		}
 	}
 
+	telemetry: metrics: {
+		api_key_allowlist_reload_errors_total: components.sources.internal_metrics.output.metrics.api_key_allowlist_reload_errors_total
+		api_key_allowlist_reloads_total:       components.sources.internal_metrics.output.metrics.api_key_allowlist_reloads_total
+		connection_rejected_total:             components.sources.internal_metrics.output.metrics.connection_rejected_total
+		idempotency_duplicates_total:          components.sources.internal_metrics.output.metrics.idempotency_duplicates_total
+		idempotency_keys_tracked:              components.sources.internal_metrics.output.metrics.idempotency_keys_tracked
+		idempotency_untracked_requests_total:  components.sources.internal_metrics.output.metrics.idempotency_untracked_requests_total
+		quota_consumed_bytes_total:            components.sources.internal_metrics.output.metrics.quota_consumed_bytes_total
+		quota_consumed_events_total:           components.sources.internal_metrics.output.metrics.quota_consumed_events_total
+		quota_exceeded_total:                  components.sources.internal_metrics.output.metrics.quota_exceeded_total
+		requests_rejected_total:               components.sources.internal_metrics.output.metrics.requests_rejected_total
+	}
+
 	how_it_works: {
 		decompression: {
 			title: "Configuring the Datadog Agent"
@@ -141,5 +263,16 @@ components: sources: datadog_agent: {
 				```
 				"""
 		}
+
+		restricting_agents: {
+			title: "Restricting Agents"
+			body:  """
+				As this source accepts data from anything able to reach its port, it can be restricted to
+				known agents. `permit_origin` closes the connections of peers outside of the given
+				networks right away. With `tls.verify_certificate` enabled, agents must present a client
+				certificate signed by the authority of `tls.ca_file` (mutual TLS), and
+				`allowed_client_names` further restricts the names these certificates must carry.
+				"""
+		}
 	}
 }
@@ -58,6 +58,33 @@ components: sources: file: {
 
 	configuration: {
 		acknowledgements: configuration._acknowledgements
+		decoding: {
+			common:      false
+			description: "Configures how the lines of the files are parsed into events. Each file is parsed with a parser of its own, so that the columns named by the header row of a CSV file only apply to that file."
+			required:    false
+			type: object: options: {
+				charset: {
+					description: "The charset the lines are transcoded to UTF-8 from before being parsed, as the `decoding.charset` option of other sources. It can't be set along with `encoding.charset`, which also transcodes the lines, and which supports charsets encoding newlines differently from ASCII, such as UTF-16."
+					required:    false
+					common:      false
+					type: string: {
+						default: null
+						examples: ["shift_jis", "windows-1252"]
+						syntax: "literal"
+					}
+				}
+				codec: {
+					description: "The decoding method. It takes the same values and options as the `decoding` option of other sources, such as `json` or `gelf`."
+					required:    false
+					common:      true
+					type: string: {
+						default: "bytes"
+						examples: ["csv", "json"]
+						syntax: "literal"
+					}
+				}
+			}
+		}
 		exclude: {
 			common:      false
 			description: "Array of file patterns to exclude. [Globbing](#globbing) is supported.*Takes precedence over the [`include` option](#include).*"
@@ -83,7 +110,7 @@ components: sources: file: {
 		}
 		fingerprint: {
 			common:      false
-			description: "Configuration for how the file source should identify files."
+			description: "Configuration for how the file source should identify files. Can also be set as `file_identity`."
 			required:    false
 			type: object: options: {
 				strategy: {
@@ -95,6 +122,7 @@ components: sources: file: {
 						enum: {
 							checksum:         "Read first N lines of the file, skipping the first `ignored_header_bytes` bytes, to uniquely identify files via a checksum."
 							device_and_inode: "Uses the [device and inode](\(urls.inode)) to unique identify files."
+							path:             "Uses the path of the file to uniquely identify files. This is stable on network filesystems, but doesn't follow files across renames. Enables `network_filesystem`."
 						}
 						syntax: "literal"
 					}
@@ -205,6 +233,12 @@ components: sources: file: {
 				unit: "bytes"
 			}
 		}
+		network_filesystem: {
+			common:      false
+			description: "Whether the files are on a network filesystem, such as NFS or SMB. See [network filesystems](#network_filesystems) for more info."
+			required:    false
+			type: bool: default: false
+		}
 		oldest_first: {
 			category:    "Reading"
 			common:      false
@@ -288,6 +322,27 @@ components: sources: file: {
 	]
 
 	how_it_works: {
+		structured_files: {
+			title: "Structured files"
+			body: """
+				Lines of CSV files, and of W3C extended log files such as those written by IIS, can be
+				parsed into structured events with the `csv` codec:
+
+				```toml
+				[sources.iis]
+				type = "file"
+				include = ["/mnt/iis/W3SVC1/*.log"]
+				decoding.codec = "csv"
+				decoding.delimiter = " "
+				decoding.w3c_directives = true
+				```
+
+				Without `columns`, the first line of each file is a header row naming the fields of the
+				lines after it. With `w3c_directives`, every `#Fields:` directive names the fields of
+				the lines after it instead, even in the middle of a file, while other directives are
+				skipped.
+				"""
+		}
 		autodiscovery: {
 			title: "Autodiscovery"
 			body: """
@@ -550,6 +605,27 @@ components: sources: file: {
 			]
 		}
 
+		network_filesystems: {
+			title: "Network Filesystems"
+			body:  """
+				Network filesystems such as NFS and SMB only guarantee that a client
+				sees the writes of other clients when it opens a file, so a file held
+				open by Vector may look like it stopped growing. With
+				`network_filesystem` enabled, Vector reopens files once it has read
+				them to their end, picking up new writes, noticing when a file was
+				truncated and starting over from its beginning. Files whose handles
+				became stale, because they were removed on the server, are treated
+				as deleted.
+
+				Device and inode numbers may change across remounts of a network
+				filesystem, so the `device_and_inode` fingerprint strategy should be
+				avoided there. The default `checksum` strategy works well, tuned
+				through its `lines` and `ignored_header_bytes` options if files share
+				their first lines, while the `path` strategy identifies files by
+				their path alone.
+				"""
+		}
+
 		permissions: {
 			title: "File permissions"
 			body:  """
@@ -33,6 +33,7 @@ components: sources: fluent: {
 			receive_buffer_bytes: {
 				enabled: true
 			}
+			connection_limits: enabled: true
 			keepalive: enabled: true
 			tls: sources.socket.features.receive.tls
 		}
@@ -213,5 +214,7 @@ components: sources: fluent: {
 		processed_events_total:          components.sources.internal_metrics.output.metrics.processed_events_total
 		component_received_bytes_total:  components.sources.internal_metrics.output.metrics.component_received_bytes_total
 		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_rejected_total:       components.sources.internal_metrics.output.metrics.connection_rejected_total
+		connection_throttled_total:      components.sources.internal_metrics.output.metrics.connection_throttled_total
 	}
 }
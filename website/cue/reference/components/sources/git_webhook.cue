@@ -0,0 +1,187 @@
+package metadata
+
+components: sources: git_webhook: {
+	_port: 8080
+
+	title: "GitHub/GitLab Webhooks"
+
+	description: """
+		Receives the webhook deliveries of GitHub or GitLab, verifying that
+		they were sent by the provider, and outputs one log event per delivery.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.git_webhooks
+
+				interface: socket: {
+					api: {
+						title: "GitHub webhooks"
+						url:   urls.github_webhooks
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		address:          sources.http.configuration.address
+		http2:            configuration._http_server_http2
+		keepalive:        configuration._http_server_keepalive
+		path:             sources.http.configuration.path
+		provider: {
+			description: "The provider sending the webhook deliveries."
+			required:    true
+			type: string: {
+				enum: {
+					github: "Deliveries of [GitHub webhooks](\(urls.github_webhooks)), verified through their `X-Hub-Signature-256` header."
+					gitlab: "Deliveries of [GitLab webhooks](\(urls.gitlab_webhooks)), verified through their `X-Gitlab-Token` header."
+				}
+				syntax: "literal"
+			}
+		}
+		secret: {
+			common:      true
+			description: """
+				The secret of the webhook. For GitHub, deliveries must carry an HMAC SHA-256
+				[signature](\(urls.github_webhook_signatures)) of their payload keyed by this secret,
+				for GitLab, their `X-Gitlab-Token` header must be equal to it. Deliveries failing
+				verification are rejected with a `401`. If unset, deliveries aren't verified.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${WEBHOOK_SECRET}"]
+				syntax: "literal"
+			}
+		}
+		split_field: {
+			common:      false
+			description: """
+				A field holding an array, such as the `commits` of push deliveries, to split
+				deliveries on. One event is output per element of the array, holding the element
+				in place of the array along with the rest of the delivery. Deliveries without
+				elements in that field are output as they are.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["commits"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: delivery: {
+		description: "A webhook delivery. The fields of its JSON payload are output at the root of the event."
+		fields: {
+			webhook: {
+				description: "The envelope of the delivery."
+				required:    true
+				type: object: options: {
+					delivery_id: {
+						description: "The unique identifier of the delivery, from the `X-GitHub-Delivery` or `X-Gitlab-Event-UUID` header."
+						required:    false
+						common:      true
+						type: string: {
+							default: null
+							examples: ["72d3162e-cc78-11e3-81ab-4c9367dc0958"]
+							syntax: "literal"
+						}
+					}
+					event: {
+						description: "The type of the event of the delivery, from the `X-GitHub-Event` or `X-Gitlab-Event` header."
+						required:    true
+						type: string: {
+							examples: ["push", "Merge Request Hook"]
+							syntax: "literal"
+						}
+					}
+					provider: {
+						description: "The provider of the delivery."
+						required:    true
+						type: string: {
+							enum: {
+								github: "GitHub"
+								gitlab: "GitLab"
+							}
+							syntax: "literal"
+						}
+					}
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		content_types: {
+			title: "Content Types"
+			body: """
+				Deliveries are expected to carry a JSON payload. GitHub webhooks using the
+				`application/x-www-form-urlencoded` content type are supported too, their
+				payload being read from the `payload` parameter of the form.
+				"""
+		}
+		verification: {
+			title: "Verification"
+			body: """
+				Signatures and tokens are compared in constant time. As the whole payload is
+				signed by GitHub, a delivery whose body was altered in transit fails verification.
+				Deliveries missing their event type header are rejected with a `400`, so that
+				requests which aren't webhook deliveries don't get through.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		http_bad_requests_total:              components.sources.internal_metrics.output.metrics.http_bad_requests_total
+	}
+}
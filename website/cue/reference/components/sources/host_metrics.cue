@@ -79,21 +79,24 @@ components: sources: host_metrics: {
 
 	configuration: {
 		collectors: {
-			description: "The list of host metric collector services to use. Defaults to all collectors."
+			description: "The list of host metric collector services to use. Defaults to all collectors, except for `gpu` and `sensors`, which have to be listed explicitly."
 			common:      true
 			required:    false
 			type: array: {
-				default: ["cgroups", "cpu", "disk", "filesystem", "load", "host", "memory", "network"]
+				default: ["cgroups", "cpu", "disk", "filesystem", "load", "host", "memory", "network", "network_sockets"]
 				items: type: string: {
 					enum: {
-						cgroups:    "Metrics related to Linux control groups."
-						cpu:        "Metrics related to CPU utilization."
-						disk:       "Metrics related to disk I/O utilization."
-						filesystem: "Metrics related to filesystem space utilization."
-						load:       "Load average metrics (UNIX only)."
-						host:       "Metrics related to host"
-						memory:     "Metrics related to memory utilization."
-						network:    "Metrics related to network utilization."
+						cgroups:         "Metrics related to Linux control groups."
+						cpu:             "Metrics related to CPU utilization."
+						disk:            "Metrics related to disk I/O utilization."
+						filesystem:      "Metrics related to filesystem space utilization."
+						gpu:             "Metrics related to NVIDIA GPUs, read through NVML. Requires Vector to be built with the `sources-host_metrics-gpu` feature and the NVIDIA driver to be installed."
+						load:            "Load average metrics (UNIX only)."
+						host:            "Metrics related to host"
+						memory:          "Metrics related to memory utilization."
+						network:         "Metrics related to network utilization."
+						network_sockets: "Metrics related to TCP and UDP sockets (Linux only)."
+						sensors:         "Metrics read from hardware temperature, fan and power sensors (Linux only)."
 					}
 					syntax: "literal"
 				}
@@ -429,6 +432,128 @@ components: sources: host_metrics: {
 		}
 	}
 
+		network_sockets: {
+			common: false
+			description: #"""
+				Options for the "network_sockets" metrics collector.
+
+				Note: this collector is only available on Linux systems, and reads the socket tables and
+				protocol statistics from `/proc/net`.
+				"""#
+			required: false
+			type: object: options: {
+				per_process: {
+					common:   false
+					required: false
+					description: """
+						Also count the TCP connections and UDP sockets held open by each process, aggregated
+						by process name. This requires permission to read the file descriptors of the other
+						processes on the host.
+						"""
+					type: bool: default: false
+				}
+				processes: {
+					common:      false
+					required:    false
+					description: "Lists of process name patterns to include or exclude from the per-process metrics."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of process name patterns for which to gather per-process socket metrics.
+
+								Defaults to including all processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["nginx", "java*"]
+									syntax: "literal"
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of process name patterns for which to gather per-process socket metrics.
+
+								Defaults to excluding no processes.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["sshd", "systemd-*"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+		sensors: {
+			common: false
+			description: #"""
+				Options for the "sensors" metrics collector.
+
+				Note: this collector is only available on Linux systems, and reads the sensors exposed by
+				the hardware monitoring drivers under `/sys/class/hwmon`.
+				"""#
+			required: false
+			type: object: options: {
+				chips: {
+					common:      false
+					required:    false
+					description: "Lists of sensor chip name patterns to include or exclude."
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of sensor chip name patterns for which to gather sensor metrics.
+
+								Defaults to including all chips.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["coretemp", "nct*"]
+									syntax: "literal"
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of sensor chip name patterns for which to gather sensor metrics.
+
+								Defaults to excluding no chips.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["acpitz", "nvme"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
 	output: metrics: {
 		_host_metrics_tags: {
 			collector: {
@@ -520,6 +645,51 @@ components: sources: host_metrics: {
 		network_transmit_packets_drop_total: _host & _network_nomac & {description: "The number of packets dropped during transmits on this interface."}
 		network_transmit_packets_total:      _host & _network_nomac & {description: "The number of packets transmitted on this interface."}
 
+		// Host network sockets
+		network_tcp_connections: _host & _network_sockets_gauge & {
+			description: "The number of TCP connections in each state."
+			tags: {
+				family: _network_sockets_family
+				state:  _network_sockets_state
+			}
+		}
+		network_tcp_listen_drops_total:     _host & _network_sockets_counter & {description: "The number of connection requests dropped by listening sockets."}
+		network_tcp_listen_overflows_total: _host & _network_sockets_counter & {description: "The number of times a listening socket's accept queue overflowed."}
+		network_udp_sockets: _host & _network_sockets_gauge & {
+			description: "The number of open UDP sockets."
+			tags: family: _network_sockets_family
+		}
+		network_udp_no_ports_total:              _host & _network_sockets_udp_counter & {description: "The number of UDP datagrams received for a port with no listening socket."}
+		network_udp_receive_buffer_errors_total: _host & _network_sockets_udp_counter & {description: "The number of UDP datagrams dropped because the receive buffer was full."}
+		network_udp_receive_errors_total:        _host & _network_sockets_udp_counter & {description: "The number of UDP datagrams that could not be delivered, other than for lack of a listening socket."}
+		network_udp_send_buffer_errors_total:    _host & _network_sockets_udp_counter & {description: "The number of UDP datagrams dropped because the send buffer was full."}
+		network_process_tcp_connections: _host & _network_sockets_gauge & {
+			description:   "The number of TCP connections in each state held open by processes with this name."
+			relevant_when: "`network_sockets.per_process` is `true`"
+			tags: {
+				process: _network_sockets_process
+				state:   _network_sockets_state
+			}
+		}
+		network_process_udp_sockets: _host & _network_sockets_gauge & {
+			description:   "The number of UDP sockets held open by processes with this name."
+			relevant_when: "`network_sockets.per_process` is `true`"
+			tags: process: _network_sockets_process
+		}
+
+		// Host GPUs
+		gpu_memory_total_bytes:       _host & _gpu_gauge & {description: "The total number of bytes of memory of the GPU."}
+		gpu_memory_used_bytes:        _host & _gpu_gauge & {description: "The number of bytes of memory of the GPU in use."}
+		gpu_memory_utilization_ratio: _host & _gpu_gauge & {description: "The fraction of the last sample period during which the memory of the GPU was read or written."}
+		gpu_power_watts:              _host & _gpu_gauge & {description: "The power drawn by the GPU, in watts."}
+		gpu_temperature_celsius:      _host & _gpu_gauge & {description: "The temperature of the GPU die, in degrees Celsius."}
+		gpu_utilization_ratio:        _host & _gpu_gauge & {description: "The fraction of the last sample period during which the GPU was running kernels."}
+
+		// Host sensors
+		sensors_fan_speed_rpm:       _host & _sensors_gauge & {description: "The speed of the fan, in revolutions per minute."}
+		sensors_power_watts:         _host & _sensors_gauge & {description: "The power drawn by the device, in watts."}
+		sensors_temperature_celsius: _host & _sensors_gauge & {description: "The temperature measured by the sensor, in degrees Celsius."}
+
 		// Helpers
 		_host: {
 			default_namespace: "host"
@@ -569,6 +739,28 @@ components: sources: host_metrics: {
 				}
 			}
 		}
+		_gpu_gauge: {
+			type:          "gauge"
+			relevant_when: "the `gpu` collector is enabled"
+			tags: _host_metrics_tags & {
+				collector: examples: ["gpu"]
+				gpu: {
+					description: "The index of the GPU."
+					required:    true
+					examples: ["0", "1"]
+				}
+				name: {
+					description: "The product name of the GPU."
+					required:    false
+					examples: ["NVIDIA A100-SXM4-40GB", "Tesla T4"]
+				}
+				uuid: {
+					description: "The UUID of the GPU."
+					required:    false
+					examples: ["GPU-6a2e3f5b-6c3d-8b4e-a7e1-0f7d9a1b2c3d"]
+				}
+			}
+		}
 		_loadavg: {
 			type: "gauge"
 			tags: _host_metrics_tags & {
@@ -609,6 +801,61 @@ components: sources: host_metrics: {
 			}
 		}
 		_network_nomac: _network_gauge & {relevant_when: "OS is not macOS"}
+		_network_sockets_counter: {
+			type: "counter"
+			tags: _host_metrics_tags & {
+				collector: examples: ["network_sockets"]
+			}
+		}
+		_network_sockets_gauge: {
+			type: "gauge"
+			tags: _host_metrics_tags & {
+				collector: examples: ["network_sockets"]
+			}
+		}
+		_network_sockets_udp_counter: _network_sockets_counter & {
+			tags: family: _network_sockets_family
+		}
+		_network_sockets_family: {
+			description: "The address family of the sockets."
+			required:    true
+			enum: {
+				ipv4: "IPv4 sockets."
+				ipv6: "IPv6 sockets."
+			}
+		}
+		_network_sockets_process: {
+			description: "The name of the process holding the sockets open."
+			required:    true
+			examples: ["nginx", "vector"]
+		}
+		_network_sockets_state: {
+			description: "The TCP connection state."
+			required:    true
+			examples: ["established", "time_wait", "listen", "close_wait"]
+		}
+		_sensors_gauge: {
+			type:          "gauge"
+			relevant_when: "the `sensors` collector is enabled"
+			tags: _host_metrics_tags & {
+				collector: examples: ["sensors"]
+				chip: {
+					description: "The name of the sensor chip, or of the hwmon device if the chip has no name."
+					required:    true
+					examples: ["coretemp", "nct6798", "amdgpu"]
+				}
+				device: {
+					description: "The hwmon device the sensor belongs to."
+					required:    true
+					examples: ["hwmon0", "hwmon3"]
+				}
+				sensor: {
+					description: "The label of the sensor, or its hwmon name if it has no label."
+					required:    true
+					examples: ["Package id 0", "Core 0", "fan1", "power1"]
+				}
+			}
+		}
 	}
 
 	telemetry: metrics: {
@@ -100,7 +100,11 @@ components: sources: http: {
 				}
 			}
 		}
-		auth: configuration._http_basic_auth
+		auth:        configuration._http_basic_auth
+		idempotency: configuration._http_server_idempotency
+		http2:       configuration._http_server_http2
+		keepalive:   configuration._http_server_keepalive
+		quota:       configuration._http_server_quota
 		query_parameters: {
 			common:      false
 			description: "A list of URL query parameters to include in the log event. These will override any values included in the body with conflicting names."
@@ -268,6 +272,12 @@ components: sources: http: {
 		http_bad_requests_total:              components.sources.internal_metrics.output.metrics.http_bad_requests_total
 		parse_errors_total:                   components.sources.internal_metrics.output.metrics.parse_errors_total
 		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		idempotency_duplicates_total:         components.sources.internal_metrics.output.metrics.idempotency_duplicates_total
+		idempotency_keys_tracked:             components.sources.internal_metrics.output.metrics.idempotency_keys_tracked
+		idempotency_untracked_requests_total: components.sources.internal_metrics.output.metrics.idempotency_untracked_requests_total
+		quota_consumed_bytes_total:           components.sources.internal_metrics.output.metrics.quota_consumed_bytes_total
+		quota_consumed_events_total:          components.sources.internal_metrics.output.metrics.quota_consumed_events_total
+		quota_exceeded_total:                 components.sources.internal_metrics.output.metrics.quota_exceeded_total
 	}
 
 	how_it_works: {
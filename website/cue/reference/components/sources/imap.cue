@@ -0,0 +1,271 @@
+package metadata
+
+components: sources: imap: {
+	title: "IMAP"
+
+	description: """
+		Polls an IMAP mailbox for new messages, emitting one event per message, for example to bridge alerts sent
+		by email into a pipeline.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.imap
+
+				interface: socket: {
+					direction: "outgoing"
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._acknowledgements
+		after_ack: {
+			common:      false
+			description: "What to do with a message once its event was acknowledged."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					action: {
+						description: "The action applied to processed messages."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "mark_seen"
+							enum: {
+								mark_seen: "Flag messages as seen."
+								move:      "Move messages to another mailbox. Requires the server to support the [`MOVE` extension](\(urls.imap_move))."
+								delete:    "Delete messages. Requires the server to support the `UIDPLUS` extension."
+							}
+							syntax: "literal"
+						}
+					}
+					mailbox: {
+						description:   "The mailbox to move processed messages to."
+						relevant_when: "action = \"move\""
+						required:      true
+						warnings: []
+						type: string: {
+							examples: ["Processed"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		attachments: {
+			common:      false
+			description: "How to handle message attachments."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "drop"
+				enum: {
+					drop:   "Leave attachments out of events."
+					base64: "Add attachments to the `attachments` field, with their content base64 encoded."
+				}
+				syntax: "literal"
+			}
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist checkpoints. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the Vector project has write permissions to this dir."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+				syntax: "literal"
+			}
+		}
+		endpoint: {
+			description: "The IMAP server to connect to. The `imaps` scheme connects over TLS, on port 993 by default, while the `imap` scheme connects in plain text, on port 143 by default."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["imaps://imap.example.com", "imap://localhost:1143"]
+				syntax: "literal"
+			}
+		}
+		mailbox: {
+			common:      true
+			description: "The mailbox to poll."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "INBOX"
+				examples: ["INBOX", "Alerts"]
+				syntax: "literal"
+			}
+		}
+		max_messages_per_poll: {
+			common:      false
+			description: "The maximum number of messages fetched by a poll. Remaining messages are fetched by the next polls."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100
+				unit:    "messages"
+			}
+		}
+		password: {
+			description: "The password to log in with."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["${IMAP_PASSWORD}"]
+				syntax: "literal"
+			}
+		}
+		poll_interval_secs: {
+			common:      true
+			description: "How often to poll the mailbox for new messages."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		search: {
+			common:      false
+			description: "The [IMAP search criteria](\(urls.imap_search)) selecting the messages to fetch, on top of their UID being after the checkpoint."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "UNSEEN"
+				examples: ["ALL", "UNSEEN FROM \"alerts@vendor.example\""]
+				syntax: "literal"
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		username: {
+			description: "The user to log in as."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["alerts@example.com"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: message: {
+		description: "An email message."
+		fields: {
+			attachments: {
+				description:   "The attachments of the message, with their `filename`, `content_type`, `size` in bytes and base64 encoded `content`."
+				relevant_when: "attachments = \"base64\""
+				required:      false
+				type: array: items: type: object: {
+					examples: [{filename: "usage.csv", content_type: "text/csv", size: 11, content: "ZGlzayx1c2FnZQo="}]
+					options: {}
+				}
+			}
+			headers: {
+				description: "The headers of the message, keyed by their lowercase name. Repeated headers, such as `received`, are arrays of values in order."
+				required:    true
+				type: object: {
+					examples: [{subject: "Disk usage above 90%", from: "Vendor Alerts <alerts@vendor.example>"}]
+					options: {}
+				}
+			}
+			mailbox: {
+				description: "The mailbox the message was fetched from."
+				required:    true
+				type: string: {
+					examples: ["INBOX"]
+					syntax: "literal"
+				}
+			}
+			message: {
+				description: "The plain text body of the message, or its HTML body if it has no plain text one."
+				required:    true
+				type: string: {
+					examples: ["Disk /dev/sda1 is 93% full."]
+					syntax: "literal"
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `Date` header of the message, or the time the server received it."
+			}
+			uid: {
+				description: "The UID of the message in its mailbox."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: null
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				Messages are fetched without being flagged as seen. Once the event of a message is acknowledged
+				by the sinks, or right away with `acknowledgements` disabled, the `after_ack` action is applied
+				to it and Vector checkpoints its UID, so it isn't fetched again after a restart. The checkpoint
+				only advances past messages that were all processed, and is reset if the mailbox's
+				`UIDVALIDITY` changes. Checkpoints can be inspected or reset with `vector checkpoints`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
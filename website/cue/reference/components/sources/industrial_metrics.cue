@@ -0,0 +1,414 @@
+package metadata
+
+components: sources: industrial_metrics: {
+	title: "Industrial Metrics"
+
+	description: """
+		Polls Modbus TCP registers or subscribes to OPC UA nodes, emitting a gauge per register or node, so
+		that PLC and sensor data reaches a pipeline without a separate gateway.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon", "aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		multiline: enabled: false
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.industrial_equipment
+
+				interface: socket: {
+					direction: "outgoing"
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	_metric_options: {
+		name: {
+			description: "The name of the gauge the value is reported as."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["temperature_celsius"]
+				syntax: "literal"
+			}
+		}
+		offset: {
+			common:      false
+			description: "Added to the value once scaled."
+			required:    false
+			warnings: []
+			type: float: {
+				default: 0.0
+				examples: [-40.0]
+			}
+		}
+		scale: {
+			common:      true
+			description: "The value is multiplied by this factor, for example to convert tenths of degrees to degrees."
+			required:    false
+			warnings: []
+			type: float: {
+				default: 1.0
+				examples: [0.1]
+			}
+		}
+		tags: {
+			common:      false
+			description: "Tags added to the gauge, besides `endpoint`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{line: "2", machine: "press_4"}]
+				options: {}
+			}
+		}
+	}
+
+	configuration: {
+		address: {
+			description:   "The `host:port` address of the Modbus TCP server."
+			relevant_when: "protocol = \"modbus\""
+			required:      true
+			warnings: []
+			type: string: {
+				examples: ["10.0.0.5:502"]
+				syntax: "literal"
+			}
+		}
+		auth: {
+			common:        false
+			description:   "The user to authenticate as. Sessions are anonymous by default."
+			relevant_when: "protocol = \"opcua\""
+			required:      false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					username: {
+						description: "The name of the user."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["vector"]
+							syntax: "literal"
+						}
+					}
+					password: {
+						description: "The password of the user."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${OPCUA_PASSWORD}"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		data_dir: {
+			common:        false
+			description:   "The directory the OPC UA client certificate, and the server certificates it trusts, are kept in, under an `opcua_pki` directory. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the Vector project has write permissions to this dir."
+			relevant_when: "protocol = \"opcua\""
+			required:      false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+				syntax: "literal"
+			}
+		}
+		endpoint: {
+			description:   "The URL of the OPC UA server."
+			relevant_when: "protocol = \"opcua\""
+			required:      true
+			warnings: []
+			type: string: {
+				examples: ["opc.tcp://10.0.0.6:4840"]
+				syntax: "literal"
+			}
+		}
+		namespace: {
+			common:      false
+			description: "The namespace of the metrics. Disabled if empty."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "industrial"
+				syntax:  "literal"
+			}
+		}
+		nodes: {
+			description:   "The nodes to subscribe to."
+			relevant_when: "protocol = \"opcua\""
+			required:      true
+			warnings: []
+			type: array: items: type: object: {
+				examples: [{name: "spindle_speed_rpm", node_id: "ns=2;s=Machine.Spindle.Speed"}]
+				options: _metric_options & {
+					node_id: {
+						description: "The identifier of the node, in the `ns=<namespace>;<type>=<identifier>` format."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["ns=2;s=Machine.Spindle.Speed", "ns=3;i=1001"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		protocol: {
+			description: "The protocol spoken by the equipment."
+			required:    true
+			warnings: []
+			type: string: {
+				enum: {
+					modbus: "Read registers from a Modbus TCP server, on every scrape."
+					opcua:  "Subscribe to nodes of an OPC UA server, every scrape reporting their latest values."
+				}
+				syntax: "literal"
+			}
+		}
+		registers: {
+			description:   "The registers to read."
+			relevant_when: "protocol = \"modbus\""
+			required:      true
+			warnings: []
+			type: array: items: type: object: {
+				examples: [{name: "temperature_celsius", address: 100, scale: 0.1}]
+				options: _metric_options & {
+					address: {
+						description: "The address of the register, starting at 0. Values spanning several registers start at this address."
+						required:    true
+						warnings: []
+						type: uint: {
+							examples: [0, 100]
+							unit: null
+						}
+					}
+					data_type: {
+						common:      true
+						description: "How the value is encoded. Doesn't apply to coils and discrete inputs, whose values are `0` or `1`."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "uint16"
+							enum: {
+								uint16:  "An unsigned integer, in one register."
+								int16:   "A signed integer, in one register."
+								uint32:  "An unsigned integer, in two registers."
+								int32:   "A signed integer, in two registers."
+								float32: "A single precision float, in two registers."
+								uint64:  "An unsigned integer, in four registers."
+								int64:   "A signed integer, in four registers."
+								float64: "A double precision float, in four registers."
+							}
+							syntax: "literal"
+						}
+					}
+					kind: {
+						common:      true
+						description: "The table the register belongs to."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "holding"
+							enum: {
+								coil:           "A read-write bit."
+								discrete_input: "A read-only bit."
+								holding:        "A read-write 16 bit register."
+								input:          "A read-only 16 bit register."
+							}
+							syntax: "literal"
+						}
+					}
+					word_order: {
+						common:      false
+						description: "The order of the registers of values spanning several of them."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "big_endian"
+							enum: {
+								big_endian:    "The first register holds the most significant word."
+								little_endian: "The first register holds the least significant word."
+							}
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		retry_initial_backoff_secs: {
+			common:      false
+			description: "How long to wait before reconnecting to the equipment the first time a connection fails. The wait doubles with every failure."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+		retry_max_backoff_secs: {
+			common:      false
+			description: "The longest wait between reconnections."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		scrape_interval_secs: {
+			description: "The interval between scrapes. With OPC UA, it's also the publishing interval requested for the subscription."
+			common:      true
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		security_mode: {
+			common:        false
+			description:   "How messages exchanged with the server are secured. Must be `none` if, and only if, `security_policy` is."
+			relevant_when: "protocol = \"opcua\""
+			required:      false
+			warnings: []
+			type: string: {
+				default: "none"
+				enum: {
+					none:             "Messages are neither signed nor encrypted."
+					sign:             "Messages are signed."
+					sign_and_encrypt: "Messages are signed and encrypted."
+				}
+				syntax: "literal"
+			}
+		}
+		security_policy: {
+			common:        false
+			description:   "The security policy of the session."
+			relevant_when: "protocol = \"opcua\""
+			required:      false
+			warnings: []
+			type: string: {
+				default: "none"
+				enum: {
+					none:                   "No security."
+					basic256_sha256:        "The `Basic256Sha256` policy."
+					aes128_sha256_rsa_oaep: "The `Aes128-Sha256-RsaOaep` policy."
+					aes256_sha256_rsa_pss:  "The `Aes256-Sha256-RsaPss` policy."
+				}
+				syntax: "literal"
+			}
+		}
+		timeout_secs: {
+			common:        false
+			description:   "How long to wait for the server to accept a connection, or to reply to a read."
+			relevant_when: "protocol = \"modbus\""
+			required:      false
+			warnings: []
+			type: uint: {
+				default: 5
+				unit:    "seconds"
+			}
+		}
+		trust_server_certs: {
+			common:        false
+			description:   "Whether to trust the certificates of servers, rather than only those moved to the `trusted` directory of the PKI directory."
+			relevant_when: "protocol = \"opcua\""
+			required:      false
+			warnings: []
+			type: bool: default: false
+		}
+		unit_id: {
+			common:        false
+			description:   "The unit identifier of the device to read from, for servers that are gateways to several devices."
+			relevant_when: "protocol = \"modbus\""
+			required:      false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
+	}
+
+	output: metrics: {
+		_industrial_metrics_tags: {
+			endpoint: {
+				description: "The `address` of the Modbus server, or the `endpoint` of the OPC UA server."
+				required:    true
+				examples: ["10.0.0.5:502"]
+			}
+		}
+
+		up: {
+			description:       "Whether the values could be read, `0` while Vector is reconnecting to the equipment."
+			type:              "gauge"
+			default_namespace: "industrial"
+			tags:              _industrial_metrics_tags
+		}
+	}
+
+	how_it_works: {
+		values: {
+			title: "Values"
+			body: """
+				Each register or node is reported as a gauge named after its `name`, whose value is the raw
+				value times `scale`, plus `offset`. Registers the Modbus server refuses to read, and OPC UA
+				nodes without a good numeric value yet, are left out of the scrape.
+				"""
+		}
+		reconnections: {
+			title: "Reconnections"
+			body: """
+				When the equipment can't be reached, or a read fails, Vector reconnects on the following
+				scrapes, waiting `retry_initial_backoff_secs` after the first failure, then twice as long
+				after each following one, up to `retry_max_backoff_secs`. Meanwhile, scrapes only report
+				the `up` gauge, as `0`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		connection_errors_total:              components.sources.internal_metrics.output.metrics.connection_errors_total
+		connection_established_total:         components.sources.internal_metrics.output.metrics.connection_established_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		request_errors_total:                 components.sources.internal_metrics.output.metrics.request_errors_total
+	}
+}
@@ -125,6 +125,12 @@ components: sources: internal_metrics: {
 		}
 
 		// Instance-level "process" metrics
+		acknowledgement_timeouts_total: {
+			description:       "The number of batches a sink resolved as errored because it held their events longer than its `acknowledgement_timeout_secs`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		aggregate_events_recorded_total: {
 			description:       "The number of events recorded by the aggregate transform."
 			type:              "counter"
@@ -143,12 +149,54 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		api_config_revisions_total: {
+			description:       "The total number of configs submitted through the `applyConfig` mutation of the Vector GraphQL API."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				outcome: {
+					description: "What became of the submitted config."
+					required:    true
+					enum: {
+						applied:     "Vector was reloaded into the config."
+						unchanged:   "The config doesn't change the running config."
+						rejected:    "The config failed to compile, or changes global options."
+						rolled_back: "Components of the config failed to build or their healthchecks failed, so the previous config was restored."
+						failed:      "The previous config couldn't be restored either, so Vector shut down."
+					}
+				}
+			}
+		}
+		api_key_allowlist_reload_errors_total: {
+			description:       "The total number of times the API key allowlist file couldn't be reloaded."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		api_key_allowlist_reloads_total: {
+			description:       "The total number of times the API key allowlist file has been reloaded."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		api_key_circuit_breaks_total: {
+			description:       "The total number of times an API key failed too many requests in a row, and had its requests failed without being sent for a while."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		api_started_total: {
 			description:       "The number of times the Vector GraphQL API has been started."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		bulk_items_retried_total: {
+			description:       "The total number of bulk request items sent again after being rejected for a transient reason."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		config_load_errors_total: {
 			description:       "The total number of errors loading the Vector configuration."
 			type:              "counter"
@@ -173,6 +221,29 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		connection_rejected_total: {
+			description:       "The total number of connections closed right away for being over the connection limits, or for coming from an address not permitted."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				mode: {
+					description: "The connection mode."
+					required:    true
+					enum: {
+						tcp: "Transmission Control Protocol"
+					}
+				}
+				reason: {
+					description: "The limit the connection was over."
+					required:    true
+					enum: {
+						max_connections:        "The source was at `connection_limits.max_connections` already."
+						max_connections_per_ip: "The peer IP was at `connection_limits.max_connections_per_ip` already."
+						permit_origin:          "The peer IP was outside of the networks of `permit_origin`."
+					}
+				}
+			}
+		}
 		connection_send_errors_total: {
 			description:       "The total number of errors sending data via the connection."
 			type:              "counter"
@@ -191,6 +262,20 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		connection_throttled_total: {
+			description:       "The total number of times a connection stopped being read from for sending events over the rate limits."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				mode: {
+					description: "The connection mode."
+					required:    true
+					enum: {
+						tcp: "Transmission Control Protocol"
+					}
+				}
+			}
+		}
 		quit_total: {
 			description:       "The total number of times the Vector instance has quit."
 			type:              "counter"
@@ -431,12 +516,130 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		dns_cache_hits_total: {
+			description:       "The number of DNS lookups answered from the cache."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		dns_cache_misses_total: {
+			description:       "The number of DNS lookups missing from the cache."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		dns_lookup_errors_total: {
+			description:       "The number of DNS lookups that failed or timed out."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				error_type: _error_type
+			}
+		}
+		encoded_request_bytes: {
+			description:       "The size in bytes of the requests encoded by this component, before compression."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		encoded_request_compressed_bytes: {
+			description:       "The size in bytes of the requests encoded by this component, after compression."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		encoded_request_events: {
+			description:       "The number of events in the requests encoded by this component."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		encoded_request_duration_seconds: {
+			description:       "The time spent encoding, framing and compressing the events of each request."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		encode_errors_total: {
 			description:       "The total number of errors encountered when encoding an event."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		endpoint_circuit_breaks_total: {
+			description:       "The total number of times an endpoint failed too many requests in a row, and had its requests failed without being sent for a while."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				endpoint: _endpoint_name
+			}
+		}
+		endpoint_delivered_bytes_total: {
+			description:       "The total number of bytes delivered to an endpoint."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				endpoint: _endpoint_name
+			}
+		}
+		endpoint_delivered_events_total: {
+			description:       "The total number of events delivered to an endpoint."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				endpoint: _endpoint_name
+			}
+		}
+		endpoint_ejections_total: {
+			description:       "The total number of times a downstream endpoint was ejected from load balancing after failing requests."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				endpoint: {
+					description: "The ejected endpoint."
+					required:    true
+					examples: ["10.0.0.1:6000"]
+				}
+			}
+		}
+		event_trace_end_to_end_latency_seconds: {
+			description:       "The time a traced event took to reach this sink after leaving its source."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		event_trace_processing_latency_seconds: {
+			description:       "The time this transform took to emit a traced event after receiving it."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		event_trace_queue_latency_seconds: {
+			description:       "The time a traced event spent in this component's input buffer."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		event_fields_truncated_total: {
+			description:       "The total number of event fields truncated for being over the `max_field_bytes` limit."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		event_traces_started_total: {
+			description:       "The total number of events sampled for event tracing by this source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		events_dead_lettered_total: {
+			description:       "The total number of events rejected by the downstream service and written to a dead letter destination instead."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				error_type: _error_type
+			}
+		}
 		events_discarded_total: {
 			description:       "The total number of events discarded by this component."
 			type:              "counter"
@@ -501,6 +704,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffer_evicted_events_total: {
+			description:       "The number of events dropped by this non-blocking buffer to make room for events of a higher priority."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		buffer_received_event_bytes_total: {
 			description:       "The number of bytes received by this buffer."
 			type:              "counter"
@@ -531,6 +740,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              component_received_events_total.tags
 		}
+		component_allocated_bytes: {
+			description:       "The approximate number of bytes held by the state of the component, such as cached or aggregated events."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		component_healthy: {
+			description:       "Whether the periodic healthcheck of a sink is passing (`1`) or the sink is paused until it passes again (`0`)."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		component_received_events_total: {
 			description: """
 				The number of events accepted by this component either from tagged
@@ -622,6 +843,54 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kafka_queue_messages_max: {
+			description:       "Maximum number of messages allowed in the producer queues, set by `queue.buffering.max.messages`."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		kafka_queue_messages_bytes_max: {
+			description:       "Maximum total size of messages allowed in the producer queues, set by `queue.buffering.max.kbytes`."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		kafka_queue_utilization_ratio: {
+			description:       "How full the producer queues are, between 0 and 1, by the number or the total size of their messages, whichever is closer to its limit."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		kafka_delivery_latency_seconds: {
+			description:       "The time between producing a message and its delivery being acknowledged by the brokers."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				topic: {
+					description: "The topic of the message."
+					required:    true
+				}
+			}
+		}
+		kafka_delivery_errors_total: {
+			description:       "The total number of messages that failed to be delivered."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				topic: {
+					description: "The topic of the message."
+					required:    true
+				}
+				partition: {
+					description: "The partition of the message, or `-1` if it wasn't assigned one."
+					required:    true
+				}
+				error_code: {
+					description: "The librdkafka error code, such as `MessageTimedOut` or `QueueFull`."
+					required:    true
+				}
+			}
+		}
 		kafka_requests_total: {
 			description:       "Total number of requests sent to Kafka brokers."
 			type:              "counter"
@@ -734,6 +1003,21 @@ components: sources: internal_metrics: {
 				path: _path
 			}
 		}
+		healthcheck_state_changes_total: {
+			description:       "The total number of times a sink was paused or resumed by its periodic healthcheck."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				state: {
+					description: "The state the sink changed to."
+					required:    true
+					enum: {
+						healthy:   "The healthcheck recovered and the sink was resumed."
+						unhealthy: "The healthcheck failed repeatedly and the sink was paused."
+					}
+				}
+			}
+		}
 		http_bad_requests_total: {
 			description:       "The total number of HTTP `400 Bad Request` errors encountered."
 			type:              "counter"
@@ -780,6 +1064,29 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		idempotency_duplicates_total: {
+			description:       "The total number of requests dropped because a request with the same idempotency key was already accepted or in flight."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				state: {
+					description: "The state of the request with the same key: `accepted` or `in_flight`."
+					required:    true
+				}
+			}
+		}
+		idempotency_keys_tracked: {
+			description:       "The number of idempotency keys currently tracked by the source."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		idempotency_untracked_requests_total: {
+			description:       "The total number of requests that couldn't be deduplicated because the maximum number of idempotency keys were tracked."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		invalid_record_total: {
 			description:       "The total number of invalid records that have been discarded."
 			type:              "counter"
@@ -792,6 +1099,15 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		label_set_limit_exceeded_total: {
+			description: """
+				The total number of events whose labels were replaced with the fallback labels because
+				the limit of distinct label sets was reached.
+				"""
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		logging_driver_errors_total: {
 			description: """
 				The total number of logging driver errors encountered caused by not using either
@@ -807,6 +1123,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		lua_state_bytes: {
+			description:       "The number of bytes currently taken by the values stored in the `state` of the Lua transform."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		lua_state_limit_reached_total: {
+			description:       "The total number of values the Lua transform did not store because its `state` was full."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		metadata_refresh_failed_total: {
 			description:       "The total number of failed efforts to refresh AWS EC2 metadata."
 			type:              "counter"
@@ -819,18 +1147,68 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		netflow_template_missing_total: {
+			description:       "The total number of NetFlow v9 and IPFIX records dropped for referencing a template the exporter hasn't sent yet."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		open_connections: {
 			description:       "The number of current open connections to Vector."
 			type:              "gauge"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		oversized_events_total: {
+			description:       "The total number of events identified as too large, either on their own or as the largest event of a payload the destination rejected as too large."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "Why the event was identified as too large."
+					required:    true
+					enum: {
+						"event_too_large":   "The event alone was larger than the maximum payload size, and was dropped."
+						"payload_too_large": "The event was the largest of a payload the destination rejected as too large."
+					}
+				}
+			}
+		}
+		owned_partitions: {
+			description:       "The number of partitions currently owned by this component."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		parquet_values_mismatched_total: {
+			description:       "The total number of values written as nulls to a Parquet file for not matching their column's type."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				column_type: {
+					description: "The type of the column, as configured in the Parquet schema."
+					required:    true
+				}
+			}
+		}
 		parse_errors_total: {
 			description:       "The total number of errors parsing metrics for this component."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		pressure_sampling_active: {
+			description:       "Whether the component is sampling low priority events because the buffer of its sink is under pressure, `1` while it is and `0` otherwise."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		pressure_sampling_activations_total: {
+			description:       "The total number of times the component started sampling low priority events because the buffer of its sink passed its high watermark."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		processed_bytes_total: {
 			description:       "The number of bytes processed by the component."
 			type:              "counter"
@@ -886,6 +1264,40 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		quota_consumed_bytes_total: {
+			description:       "The total number of bytes accounted to the ingestion quotas of the source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		quota_consumed_events_total: {
+			description:       "The total number of events accounted to the ingestion quotas of the source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		quota_exceeded_total: {
+			description:       "The total number of requests rejected because their identity exceeded its ingestion quota."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				identity: {
+					description: "What the quota is accounted to: `api_key`, `auth_token` or `peer_ip`."
+					required:    true
+				}
+			}
+		}
+		rate_limiter_throttled_requests_total: {
+			description:       "The total number of times a request was delayed by a shared rate limiter, waiting for the limit to allow more requests."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				rate_limiter: {
+					description: "The name of the rate limiter."
+					required:    true
+				}
+			}
+		}
 		request_errors_total: {
 			description:       "The total number of requests errors for this component."
 			type:              "counter"
@@ -922,12 +1334,104 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		requests_rejected_total: {
+			description:       "The total number of requests rejected for their API key."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "Why the request was rejected."
+					required:    true
+					enum: {
+						invalid_api_key: "The request's API key isn't on the allowlist."
+						missing_api_key: "The request has no API key."
+					}
+				}
+			}
+		}
+		sampled_late_spans_total: {
+			description:       "The number of spans received after the decision about their trace."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				decision: _sampling_decision
+			}
+		}
+		sampled_spans_total: {
+			description:       "The number of spans of the traces decided on."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				decision: _sampling_decision
+				policy:   _sampling_policy
+			}
+		}
+		sampled_traces_total: {
+			description:       "The number of traces decided on."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				decision: _sampling_decision
+				policy:   _sampling_policy
+			}
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		sessions_ended_total: {
+			description:       "The total number of sessions ended."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "Why the session ended."
+					required:    true
+					enum: {
+						"evicted":  "The session was ended early to make room for a new one."
+						"inactive": "No event was received for the session for the inactivity gap."
+						"shutdown": "Vector shut down without persisting the session."
+					}
+				}
+			}
+		}
+		sessions_started_total: {
+			description:       "The total number of sessions started."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		spiffe_svid_updates_total: {
+			description:       "The total number of X.509-SVIDs received from the SPIFFE Workload API."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		spooled_events_total: {
+			description:       "The total number of events in the payloads written to the spool of the `datadog_logs` sink."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		spooled_payloads_total: {
+			description:       "The total number of payloads the destination rejected for good and that were written to the spool of the `datadog_logs` sink."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				status: {
+					description: "The HTTP status the payload was rejected with, `403` or `413`."
+					required:    true
+				}
+			}
+		}
+		subprocess_exits_total: {
+			description:       "The total number of times a subprocess fed by the `exec` sink exited."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		streams_total: {
 			description:       "The total number of streams."
 			type:              "counter"
@@ -1053,6 +1557,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		values_anonymized_total: {
+			description:       "The total number of sensitive values found and anonymized."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 
 		// Windows metrics
 		windows_service_does_not_exist_total: {
@@ -1147,12 +1657,34 @@ components: sources: internal_metrics: {
 			required:    true
 			examples: ["http://localhost:8080/server-status?auto"]
 		}
+		_sampling_decision: {
+			description: "Whether the trace was kept or dropped."
+			required:    true
+			enum: {
+				"dropped": "The trace was dropped."
+				"kept":    "The trace was kept."
+			}
+		}
+		_sampling_policy: {
+			description: "The name of the policy the trace matched, or `probabilistic` for traces matching no policy."
+			required:    true
+			examples: ["errors", "probabilistic"]
+		}
+		_endpoint_name: {
+			description: "The `name` of the endpoint."
+			required:    true
+			examples: ["alerting"]
+		}
 		_error_type: {
 			description: "The type of the error"
 			required:    true
 			enum: {
+				"circuit_open":                "The request was failed without being sent, as its API key or endpoint is cooling down."
+				"connection_failed":           "The connection to the remote system failed."
 				"delete_failed":               "The file deletion failed."
+				"delivery_failed":             "The events couldn't be delivered to an endpoint."
 				"encode_failed":               "The encode operation failed."
+				"encryption_failed":           "The encryption operation failed."
 				"field_missing":               "The event field was missing."
 				"glob_failed":                 "The glob pattern match operation failed."
 				"http_error":                  "The HTTP request resulted in an error code."
@@ -1160,12 +1692,16 @@ components: sources: internal_metrics: {
 				"mapping_failed":              "The mapping failed."
 				"match_failed":                "The match operation failed."
 				"out_of_order":                "The event was out of order."
+				"ownership_failed":            "The partition ownership operation failed."
 				"parse_failed":                "The parsing operation failed."
 				"read_failed":                 "The file read operation failed."
 				"render_error":                "The rendering operation failed."
+				"request_failed":              "The request to the remote system failed."
+				"row_error":                   "The row was rejected by the destination."
 				"type_conversion_failed":      "The type conversion operating failed."
 				"type_field_does_not_exist":   "The type field does not exist."
 				"type_ip_address_parse_error": "The IP address did not parse."
+				"unknown_severity":            "The severity was not recognized."
 				"unlabeled_event":             "The event was not labeled."
 				"value_invalid":               "The value was invalid."
 				"watch_failed":                "The file watch operation failed."
@@ -1213,6 +1749,7 @@ components: sources: internal_metrics: {
 			enum: {
 				"out_of_order": "The event was out of order."
 				"oversized":    "The event was too large."
+				"row_error":    "The row was rejected by the destination."
 			}
 		}
 	}
@@ -51,7 +51,7 @@ components: sources: journald: {
 	configuration: {
 		batch_size: {
 			common:      false
-			description: "The systemd journal is read in batches, and a checkpoint is set at the end of each batch. This option limits the size of the batch."
+			description: "The systemd journal is read in batches, and a checkpoint is set at the end of a batch at most once per second, as well as when Vector shuts down. This option limits the size of the batch."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -59,6 +59,17 @@ components: sources: journald: {
 				unit:    null
 			}
 		}
+		boot_offset: {
+			common: false
+			description: """
+				Include only entries from the boot at this offset from the current one, such as `-1` for
+				the previous boot, as with `journalctl --boot=-1`. Takes precedence over
+				`current_boot_only`.
+				"""
+			required: false
+			warnings: []
+			type: "*": {}
+		}
 		current_boot_only: {
 			common:      true
 			description: "Include only entries from the current boot."
@@ -148,6 +159,19 @@ components: sources: journald: {
 				}
 			}
 		}
+		include_priority: {
+			common: false
+			description: """
+				Include only entries of these syslog priorities, passed to `journalctl --priority`. Either
+				a single priority, such as `"warning"`, which includes it and all of the more severe
+				priorities, or a range, such as `"emerg..warning"` or `["crit", "notice"]`. Priorities
+				are named `emerg`, `alert`, `crit`, `err`, `warning`, `notice`, `info` and `debug`, or
+				numbered from `0` to `7`.
+				"""
+			required: false
+			warnings: []
+			type: "*": {}
+		}
 		journalctl_path: {
 			common:      false
 			description: "The full path of the `journalctl` executable. If not set, Vector will search the path for `journalctl`."
@@ -141,7 +141,82 @@ components: sources: kafka: {
 				syntax: "literal"
 			}
 		}
+		include_headers: {
+			common:      false
+			description: "The names of the Kafka headers captured into the `headers_key` field. All headers are captured when unset, and none are when empty, in which case the field is left out."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["trace-id", "tenant"]
+					syntax: "literal"
+				}
+			}
+		}
+		header_fields: {
+			common:      false
+			description: "Kafka headers captured into their own log field, keyed by header name. This is independent from `include_headers`, so that headers needed for routing can be lifted to the top level of events."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"tenant": "tenant_id", "trace-id": "trace.id"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The log field the header is inserted into."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["tenant_id", "trace.id"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		timestamp_type: {
+			common:      false
+			description: "Which timestamp of Kafka messages populates the timestamp of events. Messages which don't carry the selected timestamp get the current time."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "any"
+				enum: {
+					any:             "Whichever timestamp the message carries, as set by the `message.timestamp.type` of its topic."
+					create_time:     "The time the producer created the message."
+					log_append_time: "The time the broker appended the message to its log."
+					ingestion:       "The time the message was consumed."
+				}
+				syntax: "literal"
+			}
+		}
 		librdkafka_options: components._kafka.configuration.librdkafka_options
+		schema_registry: {
+			common:      false
+			description: """
+				Decode messages with schemas from a schema registry. Messages must be in the registry wire format,
+				which prefixes the payload with the ID of the schema it was written with. Schemas are fetched on
+				first use and cached. If set, `framing` and `decoding` are ignored.
+				"""
+			required:    false
+			warnings: ["Protobuf schemas can't use message types imported from other schemas. Messages which can't be decoded are dropped."]
+			type: object: {
+				examples: []
+				options: components._kafka._schema_registry & {
+					auth: configuration._http_auth & {_args: {
+						password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+						username_example: "${SCHEMA_REGISTRY_USERNAME}"
+					}}
+					tls: configuration._tls_connect & {_args: {
+						can_enable:             false
+						can_verify_certificate: true
+						can_verify_hostname:    true
+						enabled_default:        false
+					}}
+				}
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -244,7 +319,7 @@ components: sources: kafka: {
 				}
 			}
 			timestamp: fields._current_timestamp & {
-				description: "The timestamp encoded in the Kafka message or the current time if it cannot be fetched."
+				description: "The timestamp encoded in the Kafka message, as selected by `timestamp_type`, or the current time if it cannot be fetched."
 			}
 			topic: {
 				description: "The Kafka topic that the record came from."
@@ -166,9 +166,82 @@ components: sources: kubernetes_logs: {
 							syntax:  "literal"
 						}
 					}
+					replicaset_name: {
+						common:      false
+						description: "Event field for the name of the ReplicaSet owning the Pod."
+						required:    false
+						type: string: {
+							default: "kubernetes.replicaset_name"
+							syntax:  "literal"
+						}
+					}
+					deployment_name: {
+						common:      false
+						description: "Event field for the name of the Deployment owning the Pod through a ReplicaSet."
+						required:    false
+						type: string: {
+							default: "kubernetes.deployment_name"
+							syntax:  "literal"
+						}
+					}
+					statefulset_name: {
+						common:      false
+						description: "Event field for the name of the StatefulSet owning the Pod."
+						required:    false
+						type: string: {
+							default: "kubernetes.statefulset_name"
+							syntax:  "literal"
+						}
+					}
+					daemonset_name: {
+						common:      false
+						description: "Event field for the name of the DaemonSet owning the Pod."
+						required:    false
+						type: string: {
+							default: "kubernetes.daemonset_name"
+							syntax:  "literal"
+						}
+					}
+					job_name: {
+						common:      false
+						description: "Event field for the name of the Job owning the Pod."
+						required:    false
+						type: string: {
+							default: "kubernetes.job_name"
+							syntax:  "literal"
+						}
+					}
+				}
+			}
+		}
+		pod_annotation_fields_allowlist: {
+			common:      false
+			description: """
+				The names of the `pod_annotation_fields` options to annotate the events with, for instance
+				`["pod_name", "pod_namespace", "deployment_name"]`. All the fields are annotated if not set.
+				"""
+			required:    false
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["pod_name", "deployment_name"]
+					syntax: "literal"
 				}
 			}
 		}
+		owner_cache_capacity: {
+			common:      false
+			description: """
+				The maximum number of ReplicaSets whose owning Deployment is kept to annotate the events with the
+				`deployment_name` field. The least recently used ReplicaSets are evicted first. Setting it to `0`
+				disables the watch of the ReplicaSets.
+				"""
+			required:    false
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
 		namespace_annotation_fields: {
 			common:      false
 			description: "Configuration for how the events are annotated with Namespace metadata."
@@ -212,6 +285,148 @@ components: sources: kubernetes_logs: {
 				syntax:  "literal"
 			}
 		}
+		kube_events: {
+			common:      false
+			description: "Watch the Kubernetes Events API and emit the events related to the Pods and the Node local to this Vector instance to the `<component_id>.kube_events` output, once per occurrence. Requires the `watch` permission on the `events` resource."
+			required:    false
+			type: bool: default: false
+		}
+		kube_event_fields: {
+			common:      false
+			description: "Configuration for how the Kubernetes events emitted to the `kube_events` output are annotated. Events about Pods are additionally annotated with the `pod_annotation_fields` Pod name, namespace and UID fields, for correlation with the Pod logs."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					event_name: {
+						common:      false
+						description: "Event field for the Kubernetes event name."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.name"
+							syntax:  "literal"
+						}
+					}
+					event_namespace: {
+						common:      false
+						description: "Event field for the Kubernetes event namespace."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.namespace"
+							syntax:  "literal"
+						}
+					}
+					event_uid: {
+						common:      false
+						description: "Event field for the Kubernetes event UID."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.uid"
+							syntax:  "literal"
+						}
+					}
+					event_type: {
+						common:      false
+						description: "Event field for the Kubernetes event type (`Normal` or `Warning`)."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.type"
+							syntax:  "literal"
+						}
+					}
+					event_reason: {
+						common:      false
+						description: "Event field for the Kubernetes event reason."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.reason"
+							syntax:  "literal"
+						}
+					}
+					event_count: {
+						common:      false
+						description: "Event field for the number of times the Kubernetes event has occurred."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.count"
+							syntax:  "literal"
+						}
+					}
+					event_reporting_component: {
+						common:      false
+						description: "Event field for the component that reported the Kubernetes event."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.reporting_component"
+							syntax:  "literal"
+						}
+					}
+					event_reporting_host: {
+						common:      false
+						description: "Event field for the host that reported the Kubernetes event."
+						required:    false
+						type: string: {
+							default: "kubernetes.event.reporting_host"
+							syntax:  "literal"
+						}
+					}
+					object_kind: {
+						common:      false
+						description: "Event field for the kind of the involved object."
+						required:    false
+						type: string: {
+							default: "kubernetes.involved_object.kind"
+							syntax:  "literal"
+						}
+					}
+					object_name: {
+						common:      false
+						description: "Event field for the name of the involved object."
+						required:    false
+						type: string: {
+							default: "kubernetes.involved_object.name"
+							syntax:  "literal"
+						}
+					}
+					object_namespace: {
+						common:      false
+						description: "Event field for the namespace of the involved object."
+						required:    false
+						type: string: {
+							default: "kubernetes.involved_object.namespace"
+							syntax:  "literal"
+						}
+					}
+					object_uid: {
+						common:      false
+						description: "Event field for the UID of the involved object."
+						required:    false
+						type: string: {
+							default: "kubernetes.involved_object.uid"
+							syntax:  "literal"
+						}
+					}
+					object_api_version: {
+						common:      false
+						description: "Event field for the API version of the involved object."
+						required:    false
+						type: string: {
+							default: "kubernetes.involved_object.api_version"
+							syntax:  "literal"
+						}
+					}
+					object_field_path: {
+						common:      false
+						description: "Event field for the field path of the involved object."
+						required:    false
+						type: string: {
+							default: "kubernetes.involved_object.field_path"
+							syntax:  "literal"
+						}
+					}
+				}
+			}
+		}
 		self_node_name: {
 			common:      false
 			description: "The name of the Kubernetes `Node` this Vector instance runs at. Configured to use an env var by default, to be evaluated to a value provided by Kubernetes at Pod deploy time."
@@ -406,6 +621,56 @@ components: sources: kubernetes_logs: {
 					syntax:  "literal"
 				}
 			}
+			"kubernetes.deployment_name": {
+				description: "The name of the Deployment owning the Pod through a ReplicaSet."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["web"]
+					default: null
+					syntax:  "literal"
+				}
+			}
+			"kubernetes.replicaset_name": {
+				description: "The name of the ReplicaSet owning the Pod."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["web-5d4f8c7b9"]
+					default: null
+					syntax:  "literal"
+				}
+			}
+			"kubernetes.statefulset_name": {
+				description: "The name of the StatefulSet owning the Pod."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["postgres"]
+					default: null
+					syntax:  "literal"
+				}
+			}
+			"kubernetes.daemonset_name": {
+				description: "The name of the DaemonSet owning the Pod."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["node-exporter"]
+					default: null
+					syntax:  "literal"
+				}
+			}
+			"kubernetes.job_name": {
+				description: "The name of the Job owning the Pod."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["db-migration"]
+					default: null
+					syntax:  "literal"
+				}
+			}
 			"kubernetes.pod_uid": {
 				description: "Pod uid."
 				required:    false
@@ -654,7 +919,8 @@ components: sources: kubernetes_logs: {
 				Vector requires access to the Kubernetes API.
 				Specifically, the [`kubernetes_logs` source](\(urls.vector_kubernetes_logs_source))
 				uses the `/api/v1/pods` endpoint to "watch" the pods from
-				all namespaces.
+				all namespaces, and the `/apis/apps/v1/replicasets` endpoint to
+				resolve the Deployments owning them.
 
 				Modern Kubernetes clusters run with RBAC (role-based access control)
 				scheme. RBAC-enabled clusters require some configuration to grant Vector
@@ -0,0 +1,232 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: {
+					name:     "NetFlow exporters"
+					thing:    "a \(name)"
+					url:      urls.netflow
+					versions: null
+
+					description: "Routers, switches and probes exporting [NetFlow](\(urls.netflow)) v5 or v9, [IPFIX](\(urls.ipfix)) or [sFlow](\(urls.sflow)) v5 flow records."
+				}
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The UDP address to listen for flow datagrams on. It _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:4739", "0.0.0.0:6343"]
+				syntax: "literal"
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event holding the address of the exporter. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			warnings: []
+			type: string: {
+				default: "host"
+				syntax:  "literal"
+			}
+		}
+		max_templates: {
+			common:      false
+			description: "The maximum number of NetFlow v9 and IPFIX templates to cache, across every exporter and observation domain. The least recently used templates are evicted first."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+	}
+
+	output: logs: flow: {
+		description: "An individual flow record, or sFlow flow sample."
+		fields: {
+			bytes: {
+				description: "The number of bytes of the flow, or the length of the sampled frame for sFlow."
+				required:    false
+				common:      true
+				type: uint: {
+					examples: [1500]
+					unit: "bytes"
+				}
+			}
+			dst_addr: {
+				description: "The destination IP address of the flow."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["10.0.0.2", "2001:db8::2"]
+					syntax: "literal"
+				}
+			}
+			dst_port: {
+				description: "The destination port of the flow."
+				required:    false
+				common:      true
+				type: uint: {
+					examples: [443]
+					unit: null
+				}
+			}
+			flow_type: {
+				description: "The protocol the record was exported with."
+				required:    true
+				type: string: {
+					enum: {
+						ipfix:      "An IPFIX data record."
+						netflow_v5: "A NetFlow v5 flow record."
+						netflow_v9: "A NetFlow v9 data record."
+						sflow:      "An sFlow v5 flow sample."
+					}
+					syntax: "literal"
+				}
+			}
+			host: fields._local_host & {
+				description: "The IP address of the exporter the datagram was received from."
+			}
+			packets: {
+				description: "The number of packets of the flow."
+				required:    false
+				common:      true
+				type: uint: {
+					examples: [12]
+					unit: null
+				}
+			}
+			protocol: {
+				description: "The IP protocol number of the flow, such as `6` for TCP and `17` for UDP."
+				required:    false
+				common:      true
+				type: uint: {
+					examples: [6, 17]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["netflow"]
+					syntax: "literal"
+				}
+			}
+			src_addr: {
+				description: "The source IP address of the flow."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["10.0.0.1", "2001:db8::1"]
+					syntax: "literal"
+				}
+			}
+			src_port: {
+				description: "The source port of the flow."
+				required:    false
+				common:      true
+				type: uint: {
+					examples: [51234]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The export time of the datagram the record was received in."
+			}
+			"*": {
+				description: "The other fields of the record, named after their information element."
+				required:    false
+				common:      false
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		field_names: {
+			title: "Field names"
+			body:  """
+				Fields are named after the information element they hold, such as `src_addr` for both
+				`sourceIPv4Address` and `sourceIPv6Address`, so records read the same whichever
+				protocol and IP version they were exported with. Information elements without a
+				known name are kept as `field_<id>`, or `enterprise_<number>_field_<id>` for
+				enterprise-specific elements, holding unsigned integers for fields of up to 8 bytes
+				and raw bytes otherwise.
+				"""
+		}
+		templates: {
+			title: "Templates"
+			body:  """
+				NetFlow v9 and IPFIX records are laid out by templates, which exporters send
+				periodically alongside the records. Templates are cached per exporter and
+				observation domain, up to `max_templates` of them. Records arriving before their
+				template, such as after Vector restarts, are dropped and counted by the
+				`netflow_template_missing_total` metric until the exporter sends the template
+				again. Options templates and their records are skipped.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_errors_total:         components.sources.internal_metrics.output.metrics.connection_errors_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		netflow_template_missing_total:  components.sources.internal_metrics.output.metrics.netflow_template_missing_total
+		parse_errors_total:              components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
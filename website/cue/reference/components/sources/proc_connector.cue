@@ -0,0 +1,175 @@
+package metadata
+
+components: sources: proc_connector: {
+	title:       "Proc Connector"
+	description: "The proc connector source reports process executions on the local Linux host."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service: {
+				name:     "Linux kernel"
+				thing:    "the \(name)"
+				url:      "https://www.kernel.org/doc/html/latest/driver-api/connector.html"
+				versions: ">= 2.6.15"
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            false
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: [
+			"""
+				Vector must run with the `CAP_NET_ADMIN` capability to subscribe to the proc connector.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		proc_root: {
+			common:      false
+			description: "The mount point of the `procfs` filesystem the process details are read from. Useful when running in a container with the host's `/proc` mounted elsewhere."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/proc"
+				examples: ["/host/proc"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: {
+		exec: {
+			description: "A process execution."
+			fields: {
+				event_type: {
+					description: "The kind of process event."
+					required:    true
+					type: string: {
+						examples: ["exec"]
+						syntax: "literal"
+					}
+				}
+				pid: {
+					description: "The ID of the process."
+					required:    true
+					type: uint: {
+						examples: [1234]
+						unit: null
+					}
+				}
+				ppid: {
+					description: "The ID of the parent process."
+					required:    false
+					common:      true
+					type: uint: {
+						default: null
+						examples: [1]
+						unit: null
+					}
+				}
+				uid: {
+					description: "The real user ID of the process."
+					required:    false
+					common:      true
+					type: uint: {
+						default: null
+						examples: [1000]
+						unit: null
+					}
+				}
+				comm: {
+					description: "The command name of the process."
+					required:    true
+					type: string: {
+						examples: ["curl"]
+						syntax: "literal"
+					}
+				}
+				filename: {
+					description: "The path of the executed file."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						examples: ["/usr/bin/curl"]
+						syntax: "literal"
+					}
+				}
+				argv: {
+					description: "The arguments of the process, including the command itself."
+					required:    true
+					type: array: items: type: string: {
+						examples: ["curl", "https://example.com"]
+						syntax: "literal"
+					}
+				}
+				message: {
+					description: "A summary of the process execution."
+					required:    true
+					type: string: {
+						examples: ["exec curl https://example.com"]
+						syntax: "literal"
+					}
+				}
+				source_type: {
+					description: "The name of the source type."
+					required:    true
+					type: string: {
+						examples: ["proc_connector"]
+						syntax: "literal"
+					}
+				}
+				timestamp: fields._current_timestamp
+			}
+		}
+	}
+
+	how_it_works: {
+		proc_connector: {
+			title: "Proc connector"
+			body:  """
+				Vector subscribes to the netlink proc connector, which is available on practically every Linux
+				kernel, to be notified of process executions. The details of each executed process are then read
+				from `procfs`, so short-lived processes may exit before they can be reported.
+
+				Failures to read from the connector are retried with an exponential backoff, of up to 30
+				seconds between attempts.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processing_errors_total:         components.sources.internal_metrics.output.metrics.processing_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -34,6 +34,10 @@ components: sources: socket: {
 				enabled:       true
 				relevant_when: "mode = `tcp` or mode = `udp`"
 			}
+			connection_limits: {
+				enabled:       true
+				relevant_when: "mode = `tcp`"
+			}
 			keepalive: enabled: true
 			tls: {
 				enabled:                true
@@ -174,5 +178,7 @@ components: sources: socket: {
 		connection_shutdown_total:        components.sources.internal_metrics.output.metrics.connection_shutdown_total
 		component_received_bytes_total:   components.sources.internal_metrics.output.metrics.component_received_bytes_total
 		component_received_events_total:  components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_rejected_total:        components.sources.internal_metrics.output.metrics.connection_rejected_total
+		connection_throttled_total:       components.sources.internal_metrics.output.metrics.connection_throttled_total
 	}
 }
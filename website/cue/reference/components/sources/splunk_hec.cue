@@ -62,6 +62,10 @@ components: sources: splunk_hec: {
 	}
 
 	configuration: {
+		idempotency: configuration._http_server_idempotency
+		http2:       configuration._http_server_http2
+		keepalive:   configuration._http_server_keepalive
+		quota:       configuration._http_server_quota
 		address: {
 			common:      true
 			description: "The address to accept connections on."
@@ -83,6 +87,13 @@ components: sources: splunk_hec: {
 				syntax: "literal"
 			}
 		}
+		validate_channel: {
+			common:      false
+			description: "If `true`, requests whose data channel, given by the `X-Splunk-Request-Channel` header or `channel` query parameter, isn't a GUID are rejected with the `Invalid data channel` error, just as Splunk does."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		valid_tokens: {
 			common:      true
 			description: "If supplied, incoming requests must supply one of these tokens in the `Authorization` header, just as a client would if it was communicating with the Splunk HEC endpoint directly. If _not_ supplied, the `Authorization` header will be ignored and requests will not be authenticated."
@@ -98,6 +109,8 @@ components: sources: splunk_hec: {
 		}
 	}
 
+	output: metrics: gauge: output._passthrough_gauge
+
 	output: logs: event: {
 		description: "A single event"
 		fields: {
@@ -111,6 +124,27 @@ components: sources: splunk_hec: {
 		}
 	}
 
+	how_it_works: {
+		metric_events: {
+			title: "Metric events"
+			body: """
+				Events sent to the `/services/collector/event` endpoint in the HEC metrics format are
+				converted into gauge metrics rather than logs. Each `metric_name:<name>` field of an
+				event, or its `metric_name` and `_value` fields, becomes one metric. The other fields
+				of the event become tags, with arrays becoming multi-value tags, along with the
+				`host`, `index`, `source`, `sourcetype` and channel of the event.
+				"""
+		}
+		raw_endpoint: {
+			title: "Raw endpoint"
+			body: """
+				Requests to the `/services/collector/raw` endpoint must have a data channel. The body of
+				each request becomes a single log event, with the `host`, `index`, `source`,
+				`sourcetype` and `time` query parameters applied to it.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
 		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
@@ -118,6 +152,12 @@ components: sources: splunk_hec: {
 		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
 		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
 		http_request_errors_total:            components.sources.internal_metrics.output.metrics.http_request_errors_total
+		idempotency_duplicates_total:         components.sources.internal_metrics.output.metrics.idempotency_duplicates_total
+		idempotency_keys_tracked:             components.sources.internal_metrics.output.metrics.idempotency_keys_tracked
+		idempotency_untracked_requests_total: components.sources.internal_metrics.output.metrics.idempotency_untracked_requests_total
+		quota_consumed_bytes_total:           components.sources.internal_metrics.output.metrics.quota_consumed_bytes_total
+		quota_consumed_events_total:          components.sources.internal_metrics.output.metrics.quota_consumed_events_total
+		quota_exceeded_total:                 components.sources.internal_metrics.output.metrics.quota_exceeded_total
 		requests_received_total:              components.sources.internal_metrics.output.metrics.requests_received_total
 	}
 }
@@ -34,6 +34,10 @@ components: sources: statsd: {
 				enabled:       true
 				relevant_when: "mode = `tcp` or mode = `udp`"
 			}
+			connection_limits: {
+				enabled:       true
+				relevant_when: "mode = `tcp`"
+			}
 			keepalive: enabled: true
 			tls: enabled:       false
 		}
@@ -136,5 +140,7 @@ components: sources: statsd: {
 		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:          components.sources.internal_metrics.output.metrics.processed_events_total
 		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_rejected_total:       components.sources.internal_metrics.output.metrics.connection_rejected_total
+		connection_throttled_total:      components.sources.internal_metrics.output.metrics.connection_throttled_total
 	}
 }
@@ -49,6 +49,7 @@ components: sources: stdin: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._acknowledgements
 		host_key: {
 			category:    "Context"
 			common:      false
@@ -73,6 +74,21 @@ components: sources: stdin: {
 				unit:    "bytes"
 			}
 		}
+		offset_key: {
+			common:      false
+			description: """
+				The key name added to each event representing the byte offset in the input just past the frame the
+				event was decoded from. Restarting the producer of the input from the offset of the last delivered
+				event resumes it without duplicates. Not added by default.
+				"""
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["offset"]
+				syntax: "literal"
+			}
+		}
 	}
 
 	output: logs: line: {
@@ -80,6 +96,14 @@ components: sources: stdin: {
 		fields: {
 			host:      fields._local_host
 			message:   fields._raw_line
+			offset: {
+				description: "The byte offset in the input just past the frame of the event, added if `offset_key` is set."
+				required:    false
+				type: uint: {
+					examples: [4096]
+					unit: "bytes"
+				}
+			}
 			timestamp: fields._current_timestamp
 		}
 	}
@@ -107,6 +131,25 @@ components: sources: stdin: {
 				Each line is read until a new line delimiter, the `0xA` byte, is found.
 				"""
 		}
+
+		backpressure: {
+			title: "Backpressure"
+			body: """
+				With `acknowledgements` enabled, the source stops reading STDIN once the events of 128 frames are
+				awaiting delivery by the sinks. The writer of the pipe is then blocked instead of having its output
+				buffered by Vector while the sinks are stalled.
+				"""
+		}
+
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				STDIN can't be rewound, so the source doesn't keep checkpoints itself. Instead, the `offset_key` option
+				tags each event with the position in the input where reading would resume after it. With
+				`acknowledgements` enabled, the producer of the input can be restarted from the offset of the last
+				event delivered by the sinks.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -29,6 +29,10 @@ components: sources: syslog: {
 				enabled:       true
 				relevant_when: "mode = `tcp` or mode = `udp`"
 			}
+			connection_limits: {
+				enabled:       true
+				relevant_when: "mode = `tcp`"
+			}
 			keepalive: enabled: true
 			tls: sources.socket.features.receive.tls
 		}
@@ -134,6 +138,15 @@ components: sources: syslog: {
 					syntax: "literal"
 				}
 			}
+			listen_port: {
+				description:   "The local port of the listener that received the message, telling apart messages sent to different listeners."
+				relevant_when: "mode = `tcp` or `udp`"
+				required:      false
+				type: uint: {
+					examples: [514]
+					unit: null
+				}
+			}
 			message: {
 				description: "The message extracted from the Syslog line."
 				required:    true
@@ -174,11 +187,42 @@ components: sources: syslog: {
 					syntax: "literal"
 				}
 			}
+			source_port: {
+				description:   "The port of the upstream peer the message was received from."
+				relevant_when: "mode = `tcp` or `udp`"
+				required:      false
+				type: uint: {
+					examples: [50514]
+					unit: null
+				}
+			}
 			timestamp: {
 				description: "The time extracted from the Syslog formatted line. If parsing fails, then the exact time the event was ingested into Vector is used."
 				required:    true
 				type: timestamp: {}
 			}
+			tls_server_name: {
+				description:   "The server name the peer requested through TLS Server Name Indication (SNI), if it sent one."
+				relevant_when: "mode = `tcp` and `tls.enabled` = `true`"
+				required:      false
+				type: string: {
+					examples: ["syslog.example.com"]
+					syntax: "literal"
+				}
+			}
+			transport: {
+				description: "The transport the message was received over."
+				required:    true
+				type: string: {
+					enum: {
+						tcp:  "Plain TCP."
+						tls:  "TCP with TLS."
+						udp:  "UDP."
+						unix: "Unix domain stream socket."
+					}
+					syntax: "literal"
+				}
+			}
 			version: {
 				description: "The version extracted from the Syslog line. If a version is not found, then the key will not be added."
 				required:    true
@@ -220,6 +264,9 @@ components: sources: syslog: {
 				timestamp:   _timestamp
 				host:        _values.local_host
 				source_ip:   _values.remote_host
+				source_port: 50514
+				listen_port: 514
+				transport:   "tcp"
 				hostname:    _hostname
 				appname:     _app_name
 				procid:      _procid
@@ -267,5 +314,7 @@ components: sources: syslog: {
 		component_received_bytes_total:  components.sources.internal_metrics.output.metrics.component_received_bytes_total
 		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
 		utf8_convert_errors_total:       components.sources.internal_metrics.output.metrics.utf8_convert_errors_total
+		connection_rejected_total:       components.sources.internal_metrics.output.metrics.connection_rejected_total
+		connection_throttled_total:      components.sources.internal_metrics.output.metrics.connection_throttled_total
 	}
 }
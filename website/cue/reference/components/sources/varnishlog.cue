@@ -0,0 +1,230 @@
+package metadata
+
+components: sources: varnishlog: {
+	title: "Varnishlog"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon", "sidecar"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.varnish
+
+				interface: binary: {
+					name: "varnishlog"
+					permissions: unix: group: "varnish"
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: [
+			"""
+				The `varnishlog` tool must be installed alongside Vector, and Vector must be able to
+				read the shared memory log of the Varnish instance, usually by being in the
+				`varnish` group.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		exclude_tags: {
+			common:      false
+			description: "The [VSL tags](\(urls.varnish_vsl_tags)) of the records to leave out of transactions, passed to `varnishlog -x`. Globs such as `VCL_*` are accepted."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["ReqHeader", "VCL_*"]
+					syntax: "literal"
+				}
+			}
+		}
+		include_tags: {
+			common:      true
+			description: "The [VSL tags](\(urls.varnish_vsl_tags)) of the records to keep in transactions, passed to `varnishlog -i`. Globs such as `Req*` are accepted. If empty, all records are kept."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["Req*", "Resp*", "Timestamp"]
+					syntax: "literal"
+				}
+			}
+		}
+		instance: {
+			common:      false
+			description: "The name of the Varnish instance to read the log of, passed to `varnishlog -n`. If not set, the default instance is read."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["edge"]
+				syntax: "literal"
+			}
+		}
+		query: {
+			common:      true
+			description: "A [VSL query](\(urls.varnish_vsl_query)) selecting the transaction groups to read, passed to `varnishlog -q`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["RespStatus >= 500", "ReqURL ~ \"^/api/\""]
+				syntax: "literal"
+			}
+		}
+		varnishlog_path: {
+			common:      false
+			description: "The full path of the `varnishlog` executable. If not set, Vector will search the path for `varnishlog`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "varnishlog"
+				examples: ["/usr/local/bin/varnishlog"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	output: logs: {
+		transaction: {
+			description: "A client request, along with the backend requests and ESI subrequests it started."
+			fields: {
+				backend_requests: {
+					description: "The backend requests of the transaction, each with the same fields as the transaction, and the `backend` it was sent to."
+					required:    false
+					common:      true
+					type: array: items: type: object: {}
+				}
+				client: {
+					description: "The `ip` and `port` of the client."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				errors: {
+					description: "The `Error` and `FetchError` records of the transaction."
+					required:    false
+					common:      false
+					type: array: items: type: string: syntax: "literal"
+				}
+				request: {
+					description: "The `method`, `url`, `protocol` and `headers` of the request, the headers being keyed by their lowercased name."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				response: {
+					description: "The `status`, `reason`, `protocol` and `headers` of the response, the headers being keyed by their lowercased name."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				source_type: {
+					description: "The name of the source type."
+					required:    true
+					type: string: {
+						examples: ["varnishlog"]
+						syntax: "literal"
+					}
+				}
+				subrequests: {
+					description: "The ESI subrequests of the transaction, each with the same fields as the transaction."
+					required:    false
+					common:      false
+					type: array: items: type: object: {}
+				}
+				timestamp: fields._current_timestamp & {
+					description: "The time the transaction started at, or the time it was read if it has no `Start` timestamp."
+				}
+				timings: {
+					description: "The seconds from the start of the transaction to each of its `Timestamp` records, keyed by their lowercased label, such as `resp`."
+					required:    false
+					common:      true
+					type: object: {}
+				}
+				type: {
+					description: "The type of the transaction."
+					required:    true
+					type: string: {
+						examples: ["request", "bereq"]
+						syntax: "literal"
+					}
+				}
+				vcl_calls: {
+					description: "The VCL subroutines called while handling the transaction."
+					required:    false
+					common:      false
+					type: array: items: type: string: {
+						examples: ["RECV", "HASH"]
+						syntax: "literal"
+					}
+				}
+				vcl_logs: {
+					description: "The messages logged by VCL with `std.log`."
+					required:    false
+					common:      false
+					type: array: items: type: string: syntax: "literal"
+				}
+				vxid: {
+					description: "The Varnish transaction ID."
+					required:    true
+					type: uint: {
+						examples: [32770]
+						unit: null
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		communication_strategy: {
+			title: "Communication Strategy"
+			body:  """
+				Vector reads the shared memory log of Varnish by spawning
+				[`varnishlog`](\(urls.varnishlog)) with `-g request`, which groups the records of each
+				client request with the backend requests and ESI subrequests it started, and turns
+				each group into a single event. `varnishlog` is restarted if it stops, and only
+				transactions completing while it runs are read, so there's no need to pipe
+				`varnishncsa` into the `stdin` source.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
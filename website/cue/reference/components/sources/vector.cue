@@ -32,7 +32,11 @@ components: sources: vector: {
 				}
 			}
 			receive_buffer_bytes: enabled: false
-			keepalive: enabled:            true
+			connection_limits: {
+				enabled:       true
+				relevant_when: "version = `1`"
+			}
+			keepalive: enabled: true
 			tls: {
 				enabled:                true
 				can_enable:             true
@@ -125,5 +129,7 @@ components: sources: vector: {
 		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
 		protobuf_decode_errors_total:    components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
 		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_rejected_total:       components.sources.internal_metrics.output.metrics.connection_rejected_total
+		connection_throttled_total:      components.sources.internal_metrics.output.metrics.connection_throttled_total
 	}
 }
@@ -0,0 +1,203 @@
+package metadata
+
+components: transforms: anonymize: {
+	title: "Anonymize"
+
+	description: """
+		Detects personally identifiable information, such as email addresses, IP addresses,
+		credit card numbers, and national identification numbers, in log fields and redacts,
+		hashes, encrypts, or pseudonymizes it.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: [
+			"""
+				Detection is pattern based. Values that don't look like the data they hold, such as a
+				credit card number split across fields, aren't detected.
+				""",
+		]
+		notices: []
+	}
+
+	_actions: {
+		redact:       "Replace the value with `redaction`."
+		hash:         "Replace the value with its hex encoded SHA-256 digest."
+		encrypt:      "Replace the value with its AES-256-GCM encryption under `key`, encoded as URL-safe base64. Equal values encrypt differently."
+		pseudonymize: "Replace the value with a pseudonym, its encryption under `key` with a nonce derived from a keyed HMAC of the value. Equal values map to equal pseudonyms."
+	}
+
+	configuration: {
+		action: {
+			common:      true
+			description: "What to replace detected values with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "redact"
+				enum:    _actions
+				syntax:  "literal"
+			}
+		}
+		audit_field: {
+			common: false
+			description: """
+				The field listing, for every anonymized field, the detectors that matched and how many
+				values they matched. Entries are appended if the field already holds an array. Set to
+				an empty string to disable.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: "anonymized"
+				examples: ["anonymized", "audit.pii"]
+				syntax: "literal"
+			}
+		}
+		detector_actions: {
+			common:      false
+			description: "Overrides `action` for individual detectors."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						email:       "pseudonymize"
+						credit_card: "redact"
+					},
+				]
+				options: {
+					"*": {
+						description: "The action for the detector."
+						required:    true
+						warnings: []
+						type: string: {
+							enum:   _actions
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		detectors: {
+			common:      true
+			description: "The detectors to run. Where the values of several detectors overlap, the detector listed first wins."
+			required:    false
+			warnings: []
+			type: array: {
+				default: ["email", "credit_card", "us_ssn", "uk_nino", "ipv4", "ipv6"]
+				items: type: string: {
+					enum: {
+						email:       "Email addresses."
+						credit_card: "Payment card numbers of 13 to 19 digits, optionally separated by spaces or dashes, that pass the Luhn check."
+						us_ssn:      "US Social Security numbers, in the `123-45-6789` format."
+						uk_nino:     "UK National Insurance numbers."
+						ipv4:        "IPv4 addresses."
+						ipv6:        "IPv6 addresses."
+					}
+					syntax: "literal"
+				}
+			}
+		}
+		fields: {
+			common:      true
+			description: "The fields to scan, including any fields nested under them. All string fields are scanned if unset."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["message", "user"]
+					syntax: "literal"
+				}
+			}
+		}
+		key: {
+			common:      true
+			description: "The secret the `encrypt` and `pseudonymize` actions derive their keys from. Required by these actions."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${ANONYMIZE_KEY}"]
+				syntax: "literal"
+			}
+		}
+		redaction: {
+			common:      false
+			description: "The text replacing values with the `redact` action."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "[REDACTED]"
+				syntax:  "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Hash emails and redact the rest"
+			configuration: {
+				action: "redact"
+				detector_actions: email: "hash"
+			}
+			input: log: {
+				message: "Login by jane@example.com from 10.1.2.3"
+			}
+			output: log: {
+				message: "Login by 8c87b489ce35cf2e2f39f80e282cb2e804932a56a213983eeeb428407d43b52d from [REDACTED]"
+				anonymized: [
+					{field: "message", detector: "email", count: 1},
+					{field: "message", detector: "ipv4", count: 1},
+				]
+			}
+		},
+	]
+
+	how_it_works: {
+		reversibility: {
+			title: "Reversibility"
+			body: """
+				Tokens produced by the `encrypt` and `pseudonymize` actions are the URL-safe base64
+				encoding, without padding, of a 12 byte nonce, the AES-256-GCM ciphertext, and the 16 byte
+				authentication tag. The encryption key is the HMAC-SHA256 of the text
+				`vector anonymize encryption` keyed with `key`, so the original values can be recovered
+				by anyone holding `key`. Values anonymized with `redact` or `hash` can't be recovered,
+				although hashes of values with few possible inputs, such as national identification
+				numbers, can be brute forced.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+		values_anonymized_total: components.sources.internal_metrics.output.metrics.values_anonymized_total
+	}
+}
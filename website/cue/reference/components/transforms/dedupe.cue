@@ -160,6 +160,7 @@ components: transforms: dedupe: {
 	}
 
 	telemetry: metrics: {
-		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		component_allocated_bytes: components.sources.internal_metrics.output.metrics.component_allocated_bytes
+		events_discarded_total:    components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
 }
@@ -0,0 +1,226 @@
+package metadata
+
+components: transforms: derive_severity: {
+	title: "Derive Severity"
+
+	description: """
+		Derives a canonical severity from the many ways logging conventions report
+		severities, such as syslog numbers, RFC 5424 keywords, Windows event log levels,
+		or Python and Java level names.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		fallback: {
+			common: false
+			description: """
+				The severity to set when the severity of an event isn't recognized, or when none
+				of the `fields` exist. Events are left without a severity if unset.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				enum:    _severities
+				syntax:  "literal"
+			}
+		}
+		fields: {
+			common: true
+			description: """
+				The fields the severity is derived from. The first of these fields that exists
+				in an event is used.
+				"""
+			required: false
+			warnings: []
+			type: array: {
+				default: ["severity", "level"]
+				items: type: string: {
+					examples: ["level", "levelname", "EventLevel"]
+					syntax: "literal"
+				}
+			}
+		}
+		mapping: {
+			common: false
+			description: """
+				Custom mapping of values to canonical severities, taking precedence over the known
+				severity names and the numeric `scale`. Values are matched regardless of their case.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"E": "error"
+						"W": "warning"
+						"8": "debug"
+					},
+				]
+				options: {
+					"*": {
+						description: "The canonical severity of the value."
+						required:    true
+						warnings: []
+						type: string: {
+							enum:   _severities
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		number_field: {
+			common:      false
+			description: "The field to set the syslog number of the canonical severity in, from `0` (emergency) to `7` (debug)."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["severity_number"]
+				syntax: "literal"
+			}
+		}
+		original_field: {
+			common:      false
+			description: "The field to keep the original severity in."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "severity_original"
+				syntax:  "literal"
+			}
+		}
+		scale: {
+			common:      true
+			description: "How numeric severities, including numbers in strings, are interpreted."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "syslog"
+				enum: {
+					syslog:        "Syslog severities, from `0` (emergency) to `7` (debug)."
+					windows:       "Windows event log levels, from `1` (critical) to `5` (verbose)."
+					python:        "Python logging levels, from `10` (debug) to `50` (critical)."
+					opentelemetry: "OpenTelemetry severity numbers, from `1` (trace) to `24` (fatal)."
+				}
+				syntax: "literal"
+			}
+		}
+		target_field: {
+			common:      true
+			description: "The field to set the canonical severity in."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "severity"
+				syntax:  "literal"
+			}
+		}
+	}
+
+	_severities: {
+		emergency: "The system is unusable."
+		alert:     "Action must be taken immediately."
+		critical:  "Critical conditions."
+		error:     "Error conditions."
+		warning:   "Warning conditions."
+		notice:    "Normal but significant conditions."
+		info:      "Informational messages."
+		debug:     "Debug-level messages."
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Java level names"
+			configuration: {
+				fields:       ["level"]
+				number_field: "severity_number"
+			}
+			input: log: {
+				level:   "SEVERE"
+				message: "Connection refused"
+			}
+			output: log: {
+				level:             "SEVERE"
+				message:           "Connection refused"
+				severity:          "error"
+				severity_number:   3
+				severity_original: "SEVERE"
+			}
+		},
+		{
+			title: "Python levels with a fallback"
+			configuration: {
+				fields:   ["levelno"]
+				scale:    "python"
+				fallback: "info"
+			}
+			input: log: {
+				levelno: 30
+				message: "Disk almost full"
+			}
+			output: log: {
+				levelno:           30
+				message:           "Disk almost full"
+				severity:          "warning"
+				severity_original: 30
+			}
+		},
+	]
+
+	how_it_works: {
+		derivation: {
+			title: "Derivation"
+			body: """
+				Canonical severities are the eight [syslog severities](\(urls.syslog_levels)). The severity of
+				an event is derived from the first of the `fields` it contains:
+
+				1. Values of the custom `mapping` are matched first, regardless of their case.
+				2. Then the known severity names, regardless of their case, along with their aliases, such
+				   as `fatal` (`critical`), `severe` (`error`), `warn` (`warning`), or `trace`, `verbose`, and
+				   `finest` (`debug`).
+				3. Finally, integers and numeric strings are interpreted according to the `scale`.
+
+				The original value is kept in the `original_field`, so that overwriting the field the severity
+				is derived from doesn't lose it. Events whose severity isn't recognized are still forwarded,
+				with the `fallback` severity if one is set.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,208 @@
+package metadata
+
+components: transforms: enrich_dns: {
+	title: "Enrich DNS"
+
+	description: """
+		Enriches events with the host names of IP addresses, or the IP addresses of
+		host names, resolved with the DNS resolver of the host.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "DNS"
+				url:      urls.dns
+				versions: null
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		fields: {
+			description: "The fields to resolve, mapped to the fields to insert their resolved value into."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: [{src_ip: "src_host", dst_ip: "dst_host"}]
+				options: {
+					"*": {
+						description: "The field to insert the resolved value of the field into."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["src_host", "destination.host"]
+							syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+		mode: {
+			common:      true
+			description: "The direction of the lookups."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "reverse"
+				enum: {
+					reverse: "Resolve IP addresses to host names, with their PTR records."
+					forward: "Resolve host names to the array of their IP addresses."
+				}
+				syntax: "literal"
+			}
+		}
+		on_failure: {
+			common:      true
+			description: "What to do with events whose fields couldn't be resolved, because the lookup failed or timed out, or the field isn't an IP address in `reverse` mode."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "skip"
+				enum: {
+					skip:       "Leave the target field unset."
+					use_source: "Set the target field to the value of the field that couldn't be resolved."
+				}
+				syntax: "literal"
+			}
+		}
+		timeout_ms: {
+			common:      false
+			description: "How long to wait for a lookup before giving up on it."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		max_concurrent_lookups: {
+			common:      false
+			description: "The maximum number of lookups running at once, which is also the number of events that can be waiting for lookups."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 32
+				unit:    null
+			}
+		}
+		cache: {
+			common:      false
+			description: "Options of the cache of lookup results."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_entries: {
+						common:      true
+						description: "The maximum number of cached lookup results. The least recently used results are evicted first."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 10000
+							unit:    null
+						}
+					}
+					ttl_secs: {
+						common:      true
+						description: "How long the results of successful lookups are cached."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+					negative_ttl_secs: {
+						common:      true
+						description: "How long failed lookups are cached, so that names without records aren't looked up for every event."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 60
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Resolve firewall log addresses"
+			configuration: {
+				fields: {
+					src_ip: "src_host"
+					dst_ip: "dst_host"
+				}
+			}
+			input: log: {
+				src_ip: "127.0.0.1"
+				dst_ip: "192.0.2.10"
+			}
+			output: log: {
+				src_ip:   "127.0.0.1"
+				src_host: "localhost"
+				dst_ip:   "192.0.2.10"
+			}
+		},
+	]
+
+	how_it_works: {
+		lookups: {
+			title: "Lookups"
+			body:  """
+				Lookups are made with the resolver of the host, so they follow its
+				`/etc/hosts` file and `/etc/resolv.conf` configuration. Up to
+				`max_concurrent_lookups` lookups run at once, each for up to `timeout_ms`,
+				so a slow DNS server delays events by at most `timeout_ms` rather than
+				stalling the pipeline. Events keep their order.
+				"""
+		}
+		caching: {
+			title: "Caching"
+			body:  """
+				The results of lookups are kept in a cache of up to `cache.max_entries`
+				entries, for `cache.ttl_secs` when they succeed and `cache.negative_ttl_secs`
+				when they fail or time out, regardless of the TTL of the DNS records.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		dns_cache_hits_total:    components.sources.internal_metrics.output.metrics.dns_cache_hits_total
+		dns_cache_misses_total:  components.sources.internal_metrics.output.metrics.dns_cache_misses_total
+		dns_lookup_errors_total: components.sources.internal_metrics.output.metrics.dns_lookup_errors_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
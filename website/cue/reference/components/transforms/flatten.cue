@@ -0,0 +1,151 @@
+package metadata
+
+components: transforms: flatten: {
+	title: "Flatten"
+
+	description: """
+		Flattens nested objects into keys joined by a separator, or splits such keys back into
+		nested objects, so that every event follows the same convention.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		array_separator: {
+			common:        false
+			description:   "The separator arrays are joined with by the `join` array handling."
+			relevant_when: "arrays = \"join\""
+			required:      false
+			warnings: []
+			type: string: {
+				default: ","
+				examples: [",", "|"]
+				syntax: "literal"
+			}
+		}
+		arrays: {
+			common:      true
+			description: "How arrays are handled."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "keep"
+				enum: {
+					keep:  "Leave arrays as they are."
+					index: "Treat arrays as objects keyed by index, so `tags: [\"a\"]` flattens to `tags.0: \"a\"`. When unflattening, objects keyed by every index from `0` up are turned back into arrays."
+					join:  "Join arrays of strings, numbers, and booleans into a string with `array_separator` when flattening. Arrays holding objects or arrays are left as they are. Joined arrays are left as strings when unflattening."
+				}
+				syntax: "literal"
+			}
+		}
+		max_depth: {
+			common:      false
+			description: "The number of levels of nesting collapsed into a single key when flattening, or split out of one when unflattening. Deeper objects stay nested, and the rest of the key is kept whole. Unlimited if unset."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1, 3]
+				unit: null
+			}
+		}
+		mode: {
+			common:      true
+			description: "Whether to flatten or unflatten events."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "flatten"
+				enum: {
+					flatten:   "Collapse nested objects into keys joined by `separator`."
+					unflatten: "Split keys on `separator` into nested objects. Keys conflicting with a value already at one of their parents are left as they are."
+				}
+				syntax: "literal"
+			}
+		}
+		separator: {
+			common:      true
+			description: "The separator between the keys of each level of nesting."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "."
+				examples: [".", "_"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Flatten"
+			configuration: {
+				arrays: "index"
+			}
+			input: log: {
+				http: {
+					request: method: "GET"
+					status: 200
+				}
+				tags: ["a", "b"]
+			}
+			output: log: {
+				"http.request.method": "GET"
+				"http.status":         200
+				"tags.0":              "a"
+				"tags.1":              "b"
+			}
+		},
+		{
+			title: "Unflatten"
+			configuration: {
+				mode:      "unflatten"
+				separator: "_"
+			}
+			input: log: {
+				http_request_method: "GET"
+				http_status:         200
+			}
+			output: log: {
+				http: {
+					request: method: "GET"
+					status: 200
+				}
+			}
+		},
+	]
+
+	telemetry: metrics: {
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
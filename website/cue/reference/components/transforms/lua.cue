@@ -115,6 +115,29 @@ components: transforms: lua: {
 				}
 			}
 		}
+		max_state_bytes: {
+			common:      false
+			description: "The maximum number of bytes the values stored in `state` can take. Values that would make the state outgrow this limit are not stored. Only used by the version 3 of the API."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10485760
+				unit:    "bytes"
+			}
+		}
+		outputs: {
+			common:      false
+			description: "Names of the outputs events can be emitted to, besides the default one, by passing them as the second argument of `emit`. Each of them can be consumed as `<transform_id>.<output>`. Only used by the version 3 of the API."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["errors", "slow"]
+					syntax: "literal"
+				}
+			}
+		}
 		search_dirs: {
 			common:      false
 			description: "A list of directories to search when loading a Lua file via the `require` function. If not specified, the modules are looked up in the directories of Vector's configs."
@@ -213,6 +236,7 @@ components: transforms: lua: {
 				enum: {
 					"1": "Lua transform API version 1"
 					"2": "Lua transform API version 2"
+					"3": "Lua transform API version 3"
 				}
 				syntax: "literal"
 			}
@@ -474,6 +498,40 @@ components: transforms: lua: {
 				[the manual](\(urls.lua_manual)) would suffice.
 				"""
 		}
+		named_outputs: {
+			title: "Named Outputs"
+			body:  """
+				With the version 3 of the API, `emit` takes the name of an output
+				as an optional second argument, which must be declared in
+				`outputs`. Events emitted without one go to the default output,
+				consumed as `<transform_id>`, while the others are consumed as
+				`<transform_id>.<output>`. Emitting to an undeclared output is a
+				runtime error.
+
+				Events are also handed to hooks with a `type` field, which is
+				either `log` or `metric`.
+				"""
+		}
+		state: {
+			title: "State"
+			body:  """
+				The version 3 of the API exposes a `state` global to keep values
+				across hooks: `state:get(key)`, `state:set(key, value)`,
+				`state:delete(key)` and `state:bytes()`. Unlike that of plain Lua
+				globals, its size is bounded by `max_state_bytes`; `state:set`
+				returns `false` when the value would not fit.
+				"""
+		}
+		coroutine_timers: {
+			title: "Coroutine Timers"
+			body:  """
+				With the version 3 of the API, timer handlers run as
+				[coroutines](\(urls.lua_coroutines)). A handler calling
+				`coroutine.yield()` is resumed where it left off on the next tick
+				of its timer, which allows spreading long-running work over
+				several ticks.
+				"""
+		}
 		search_dirs: {
 			title: "Search Directories"
 			body:  """
@@ -486,7 +544,9 @@ components: transforms: lua: {
 	}
 
 	telemetry: metrics: {
-		lua_memory_used_bytes:   components.sources.internal_metrics.output.metrics.lua_memory_used_bytes
-		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+		lua_memory_used_bytes:         components.sources.internal_metrics.output.metrics.lua_memory_used_bytes
+		lua_state_bytes:               components.sources.internal_metrics.output.metrics.lua_state_bytes
+		lua_state_limit_reached_total: components.sources.internal_metrics.output.metrics.lua_state_limit_reached_total
+		processing_errors_total:       components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
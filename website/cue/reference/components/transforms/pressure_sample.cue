@@ -0,0 +1,141 @@
+package metadata
+
+components: transforms: pressure_sample: {
+	title: "Pressure Sample"
+
+	description: """
+		Samples low priority events while the buffer of a sink is filling up, protecting the
+		delivery of higher priority events during incidents.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		check_interval_ms: {
+			common:      false
+			description: "How often to check how full the buffer of `sink` is."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		high_watermark: {
+			common:      true
+			description: "The fraction of the buffer of `sink` in use past which sampling starts, between `0` and `1`."
+			required:    false
+			warnings: []
+			type: float: default: 0.8
+		}
+		low_watermark: {
+			common:      true
+			description: "The fraction of the buffer of `sink` in use at or under which sampling stops, lower than `high_watermark`."
+			required:    false
+			warnings: []
+			type: float: default: 0.5
+		}
+		min_priority: {
+			common:      false
+			description: "The lowest priority of events that are never sampled. Events of lower priorities are sampled while the buffer is under pressure."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
+		priority_field: {
+			common:      false
+			description: "The log field or metric tag holding the priority of events, as an integer from zero, the same way as the `priority` option of [sink buffers](\(urls.vector_sinks)). Events without a priority have the lowest priority."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "priority"
+				examples: ["priority", "routing.priority"]
+				syntax: "literal"
+			}
+		}
+		rate: {
+			description: """
+				The rate at which low priority events are forwarded while sampling, expressed as 1/N. For example,
+				`rate = 10` means 1 out of every 10 low priority events will be forwarded and the rest will be dropped.
+				"""
+			required: true
+			warnings: []
+			type: uint: {
+				examples: [10]
+				unit: null
+			}
+		}
+		sink: {
+			description: "The ID of the sink whose buffer is watched. It doesn't have to be downstream of this transform."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["elasticsearch_out"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		watermarks: {
+			title: "Watermarks"
+			body:  """
+				Every `check_interval_ms`, the transform checks how much of the buffer of `sink` is in
+				use, by events for memory buffers and by bytes for disk buffers. Sampling starts once
+				it reaches `high_watermark`, and stops once the buffer has drained down to
+				`low_watermark`, so that sampling doesn't flap while the buffer hovers around a single
+				threshold. Events pass through untouched while `sink` isn't running.
+
+				While sampling, events with a priority lower than `min_priority` are forwarded at
+				`rate`, with a `sample_rate` field, or tag for metrics, set to `rate`. Events of
+				higher priorities are always forwarded.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:              components.sources.internal_metrics.output.metrics.events_discarded_total
+		pressure_sampling_active:            components.sources.internal_metrics.output.metrics.pressure_sampling_active
+		pressure_sampling_activations_total: components.sources.internal_metrics.output.metrics.pressure_sampling_activations_total
+	}
+}
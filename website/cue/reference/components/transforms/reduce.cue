@@ -137,6 +137,38 @@ components: transforms: reduce: {
 				}
 			}
 		}
+		persistence: {
+			common:      false
+			description: "Persists the in-progress reduce states across restarts. When enabled, the states are periodically snapshotted to disk, and on shutdown they are snapshotted instead of being flushed, so that they are restored and merged with the events received after a restart."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					data_dir: {
+						common:      false
+						description: "The directory used to persist the reduce states. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the Vector project has write permissions to this dir."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/var/lib/vector"]
+							syntax: "literal"
+						}
+					}
+					snapshot_interval_secs: {
+						common:      false
+						description: "How often the reduce states are snapshotted to disk."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 60
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
 		starts_when: {
 			common: false
 			description: """
@@ -272,6 +304,7 @@ components: transforms: reduce: {
 	]
 
 	telemetry: metrics: {
+		component_allocated_bytes:  components.sources.internal_metrics.output.metrics.component_allocated_bytes
 		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
 	}
 }
@@ -101,6 +101,18 @@ components: transforms: "remap": {
 				"""
 			type: bool: default: true
 		}
+		cache_capacity: {
+			common:   false
+			required: false
+			description: """
+				The maximum number of values the VRL program can keep with the `cache_set` function. Once full,
+				the least recently used value is evicted.
+				"""
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
 	}
 
 	input: {
@@ -192,6 +204,17 @@ components: transforms: "remap": {
 				```
 				"""#
 		}
+		emitting_metrics: {
+			title: "Emitting metrics"
+			body: #"""
+				Metrics can be derived from events with the
+				[`emit_metric`](\#(urls.vrl_functions)/#emit_metric) function, which emits a counter,
+				gauge, or histogram along with the events of the program. Emitted metrics are also
+				sent if the program aborts, so pairing `emit_metric` with `abort` and
+				`drop_on_abort` replaces the event with its metrics. They are discarded if the program
+				fails.
+				"""#
+		}
 	}
 
 	telemetry: metrics: {
@@ -0,0 +1,202 @@
+package metadata
+
+components: transforms: sessionize: {
+	title: "Sessionize"
+
+	description: """
+		Groups events into sessions of activity, such as the visits of users to a
+		website, ending a session once no event was received for it in a while.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		group_by: {
+			common:      true
+			description: "The fields identifying whose session an event belongs to. Events missing all of them are passed through as they are."
+			required:    true
+			warnings: []
+			type: array: items: type: string: {
+				examples: ["user_id", "client_ip"]
+				syntax: "literal"
+			}
+		}
+		inactivity_gap_secs: {
+			common:      true
+			description: "How long a session lasts without events before it ends. A session also ends once the timestamps of two of its consecutive events are further apart than this."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1800
+				unit:    "seconds"
+			}
+		}
+		max_sessions: {
+			common:      false
+			description: "The maximum number of active sessions. Once reached, the least recently active session is ended early to make room for new ones."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100000
+				unit:    null
+			}
+		}
+		mode: {
+			common:      true
+			description: "What the transform outputs."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "enrich"
+				enum: {
+					enrich:  "Pass events through, with the ID of their session in `session_id_field`."
+					summary: "Consume events, emitting a summary event for each session once it ends."
+				}
+				syntax: "literal"
+			}
+		}
+		page_field: {
+			common:      true
+			description: "The field holding the page an event was recorded on, such as its URL, for the entry and exit pages of session summaries."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["url", "request.path"]
+				syntax: "literal"
+			}
+		}
+		persistence: {
+			common:      false
+			description: "Persists the active sessions across restarts. When enabled, the sessions are periodically snapshotted to disk, and on shutdown they are snapshotted instead of being ended, so that they carry on after a restart."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					data_dir: {
+						common:      false
+						description: "The directory used to persist the sessions. By default, the [global `data_dir` option](\(urls.vector_configuration)/global-options#data_dir) is used. Please make sure the Vector project has write permissions to this dir."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/var/lib/vector"]
+							syntax: "literal"
+						}
+					}
+					snapshot_interval_secs: {
+						common:      false
+						description: "How often the sessions are snapshotted to disk."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 60
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+		session_id_field: {
+			common:      false
+			description: "The field the ID of the session is written to, in events and session summaries."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "session_id"
+				syntax:  "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Summarize clickstream sessions"
+			configuration: {
+				group_by:            ["user_id"]
+				inactivity_gap_secs: 1800
+				mode:                "summary"
+				page_field:          "url"
+			}
+			input: [
+				{log: {user_id: "alice", url: "/", timestamp: "2021-10-01T12:00:00Z"}},
+				{log: {user_id: "alice", url: "/products", timestamp: "2021-10-01T12:01:30Z"}},
+				{log: {user_id: "alice", url: "/cart", timestamp: "2021-10-01T12:04:00Z"}},
+			]
+			output: log: {
+				user_id:       "alice"
+				session_id:    "d2f5c3a4-2c8e-4b8e-9d6a-0f6e6f0c9a1b"
+				started_at:    "2021-10-01T12:00:00Z"
+				ended_at:      "2021-10-01T12:04:00Z"
+				duration_secs: 240.0
+				events:        3
+				entry_page:    "/"
+				exit_page:     "/cart"
+				timestamp:     "2021-10-01T12:04:00Z"
+			}
+		},
+	]
+
+	how_it_works: {
+		sessions: {
+			title: "Sessions"
+			body:  """
+				Events are grouped by the values of their `group_by` fields. A session starts
+				with the first event of a group, and ends once no event was received for it for
+				`inactivity_gap_secs`, or once the next event happened more than
+				`inactivity_gap_secs` after the previous one according to their timestamps.
+
+				Sessions are summarized with the times of their first and last events, their
+				duration, the number of their events, and with `page_field`, the pages of their
+				first and last events. Events without a timestamp are timed when they are
+				received.
+				"""
+		}
+		memory: {
+			title: "Memory usage"
+			body:  """
+				Only the summary of active sessions is held in memory, not their events, and
+				`max_sessions` bounds how many of them are. Once Vector stops, active sessions
+				are ended right away, unless `persistence` is enabled.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_allocated_bytes: components.sources.internal_metrics.output.metrics.component_allocated_bytes
+		component_errors_total:    components.sources.internal_metrics.output.metrics.component_errors_total
+		sessions_ended_total:      components.sources.internal_metrics.output.metrics.sessions_ended_total
+		sessions_started_total:    components.sources.internal_metrics.output.metrics.sessions_started_total
+	}
+}
@@ -0,0 +1,187 @@
+package metadata
+
+components: transforms: tail_sampling: {
+	title: "Tail Sampling"
+
+	description: """
+		Samples traces once their spans are in, keeping every trace matching an
+		error, latency or condition policy, and a fraction of the others.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		trace_id_field: {
+			common:      true
+			description: "The field holding the ID of the trace of spans. Events without it aren't sampled."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "trace_id"
+				examples: ["trace_id", "span.trace_id"]
+				syntax: "literal"
+			}
+		}
+		decision_wait_ms: {
+			common:      true
+			description: "How long to wait for the spans of a trace after its first span, before deciding whether to keep it."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10000
+				unit:    "milliseconds"
+			}
+		}
+		max_traces: {
+			common:      false
+			description: "The maximum number of traces waiting for a decision. Once reached, the oldest trace is decided early to make room for new ones. This is also the number of decisions remembered for the spans arriving after them."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 50000
+				unit:    null
+			}
+		}
+		rate: {
+			common:      true
+			description: "The rate at which traces matching no policy are kept, expressed as 1/N. Traces are sampled by the hash of their ID, so that instances of Vector receiving spans of the same traces make the same decisions."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		policies: {
+			common:      true
+			description: "The policies of the traces to keep in full. A trace is kept if any of its spans matches any policy."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						name: {
+							description: "The name of the policy, reported in the `policy` tag of the sampling metrics."
+							required:    true
+							warnings: []
+							type: string: {
+								examples: ["errors", "slow_checkouts"]
+								syntax: "literal"
+							}
+						}
+						type: {
+							description: "The type of the policy."
+							required:    true
+							warnings: []
+							type: string: {
+								enum: {
+									error:     "Keep traces with a span whose `field` is `true` or a non-zero integer."
+									latency:   "Keep traces with a span lasting at least `threshold_ms`."
+									condition: "Keep traces with a span matching `condition`."
+								}
+								syntax: "literal"
+							}
+						}
+						field: {
+							common:        true
+							description:   "The field flagging spans as errors."
+							relevant_when: "type = \"error\""
+							required:      false
+							warnings: []
+							type: string: {
+								default: "error"
+								syntax:  "literal"
+							}
+						}
+						threshold_ms: {
+							description:   "The minimum duration of the spans of the traces to keep."
+							relevant_when: "type = \"latency\""
+							required:      true
+							warnings: []
+							type: uint: {
+								examples: [1000]
+								unit: "milliseconds"
+							}
+						}
+						duration_field: {
+							common:        true
+							description:   "The field holding the duration of spans, in nanoseconds."
+							relevant_when: "type = \"latency\""
+							required:      false
+							warnings: []
+							type: string: {
+								default: "duration"
+								syntax:  "literal"
+							}
+						}
+						condition: {
+							description:   "The condition matching the spans of the traces to keep."
+							relevant_when: "type = \"condition\""
+							required:      true
+							warnings: []
+							type: string: {
+								examples: [#".service == "checkout" && .http.status_code >= 500"#]
+								syntax: "remap_boolean_expression"
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		decisions: {
+			title: "Sampling decisions"
+			body:  """
+				Spans are held for `decision_wait_ms` after the first span of their trace, then
+				all the spans of the trace are either kept or dropped together. Kept spans have a
+				`sample_rate` field set to `1` for traces matching a policy, and to `rate` for
+				sampled traces. Spans arriving after the decision about their trace share its fate.
+
+				Spans are held in memory, so `decision_wait_ms` and `max_traces` bound the memory
+				used by the transform. Once Vector stops, the traces still waiting for a decision
+				are decided right away.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:   components.sources.internal_metrics.output.metrics.events_discarded_total
+		sampled_late_spans_total: components.sources.internal_metrics.output.metrics.sampled_late_spans_total
+		sampled_spans_total:      components.sources.internal_metrics.output.metrics.sampled_spans_total
+		sampled_traces_total:     components.sources.internal_metrics.output.metrics.sampled_traces_total
+	}
+}
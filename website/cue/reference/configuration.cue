@@ -91,6 +91,122 @@ configuration: {
 			}
 		}
 
+		event_tracing: {
+			common: false
+			description: """
+				Configures event tracing. A sampled fraction of the events leaving each source
+				are followed through the topology, and every component they pass through
+				reports how long they waited in its input buffer and how long it took to
+				process them. Sinks also report the total latency since the event left its
+				source. Latencies are published as the `event_trace_*` internal metrics and
+				logged at the `debug` level along with the trace ID.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Enables event tracing."
+						required:    false
+						warnings: []
+						type: bool: {
+							default: false
+						}
+					}
+
+					sample_rate: {
+						common: true
+						description: """
+							The fraction of source events to trace, greater than `0` and at
+							most `1`.
+							"""
+						required: false
+						warnings: []
+						type: float: {
+							default: 0.01
+							examples: [0.001, 1.0]
+						}
+					}
+				}
+			}
+		}
+
+		limits: {
+			common: false
+			description: """
+				Caps the size of the events leaving every source, right after they are
+				decoded, protecting the rest of the topology from the occasional outsized
+				event. Each source can override these with its own `limits` option.
+				Truncated fields are counted by the `event_fields_truncated_total` internal
+				metric, and dropped events by the `events_discarded_total` one.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_field_bytes: {
+						common: true
+						description: """
+							The most bytes a string value of a log event can hold. Values over it
+							are handled according to `action`. Unlimited by default.
+							"""
+						required: false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [8192]
+							unit: "bytes"
+						}
+					}
+
+					max_event_bytes: {
+						common: true
+						description: """
+							The most bytes an event can hold, as estimated from its fields and
+							metadata, after `max_field_bytes` is applied. Events over it are
+							dropped. Unlimited by default.
+							"""
+						required: false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [1048576]
+							unit: "bytes"
+						}
+					}
+
+					action: {
+						common:      false
+						description: "What to do with log events holding values over `max_field_bytes`."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "truncate"
+							enum: {
+								truncate: "Truncate the values, at a character boundary, and list their fields in `annotation_field`."
+								drop:     "Drop the events."
+							}
+							syntax: "literal"
+						}
+					}
+
+					annotation_field: {
+						common:      false
+						description: "The field of truncated events listing the paths of their truncated fields."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "truncated_fields"
+							syntax:  "literal"
+						}
+					}
+				}
+			}
+		}
+
 		healthchecks: {
 			common: false
 			description: """
@@ -130,6 +246,172 @@ configuration: {
 			}
 		}
 
+		rate_limiters: {
+			common: false
+			description: """
+				Rate limiters shared between sinks, by name. Sinks referencing a rate
+				limiter through their `rate_limiter` option delay their requests, retries
+				included, as needed to keep the combined request rate of all of them
+				within its limit. This is useful when several sinks send to the same
+				vendor under a single, account-wide rate limit.
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [{datadog: {num: 100, duration_secs: 1}}]
+				options: {
+					"*": {
+						common:      false
+						description: "A rate limiter."
+						required:    true
+						warnings: []
+						type: object: options: {
+							duration_secs: {
+								common:      false
+								description: "The time window over which `num` requests are allowed."
+								required:    false
+								warnings: []
+								type: uint: {
+									default: 1
+									unit:    "seconds"
+								}
+							}
+							num: {
+								common:      true
+								description: "The maximum number of requests allowed over `duration_secs`."
+								required:    true
+								warnings: []
+								type: uint: {
+									examples: [100, 1000]
+									unit: null
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+
+		templates: {
+			common: false
+			description: """
+				Parameterized groups of components, instantiated any number of times by
+				`pipelines`. Templates hold `sources`, `transforms`, and `sinks` like the
+				ones of the config, whose values may reference the parameters of the
+				template as `{{ params.<name> }}`. See [Pipeline templates](#pipeline-templates).
+				"""
+			required: false
+			warnings: []
+			type: object: {
+				examples: [{app_logs: {params: {service: {}, index: {default: "logs"}}}}]
+				options: {
+					"*": {
+						common:      false
+						description: "A template."
+						required:    true
+						warnings: []
+						type: object: options: {
+							params: {
+								common:      true
+								description: "The parameters of the template, by name."
+								required:    false
+								warnings: []
+								type: object: options: "*": {
+									common:      false
+									description: "A parameter."
+									required:    true
+									warnings: []
+									type: object: options: {
+										default: {
+											common:      false
+											description: "The value of the parameter for the pipelines that don't set it. Pipelines have to set the parameters without a default."
+											required:    false
+											warnings: []
+											type: "*": {}
+										}
+										type: {
+											common:      false
+											description: "The type of the values of the parameter."
+											required:    false
+											warnings: []
+											type: string: {
+												default: "string"
+												enum: {
+													array:   "An array, such as a list of inputs."
+													bool:    "A boolean."
+													float:   "A number."
+													integer: "An integer."
+													string:  "A string."
+												}
+												syntax: "literal"
+											}
+										}
+									}
+								}
+							}
+							sinks: {
+								common:      true
+								description: "The sinks of the template, as in the `sinks` of the config."
+								required:    false
+								warnings: []
+								type: object: {}
+							}
+							sources: {
+								common:      true
+								description: "The sources of the template, as in the `sources` of the config."
+								required:    false
+								warnings: []
+								type: object: {}
+							}
+							transforms: {
+								common:      true
+								description: "The transforms of the template, as in the `transforms` of the config."
+								required:    false
+								warnings: []
+								type: object: {}
+							}
+						}
+					}
+				}
+			}
+		}
+
+		pipelines: {
+			common:      false
+			description: "Instances of `templates`, by name. The components of a pipeline are named after it, as `<pipeline>.<component>`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{checkout: {template: "app_logs", params: {service: "checkout"}}}]
+				options: {
+					"*": {
+						common:      false
+						description: "A pipeline."
+						required:    true
+						warnings: []
+						type: object: options: {
+							params: {
+								common:      true
+								description: "The values of the parameters of the template, by name."
+								required:    false
+								warnings: []
+								type: object: {}
+							}
+							template: {
+								description: "The name of the template to instantiate."
+								required:    true
+								warnings: []
+								type: string: {
+									examples: ["app_logs"]
+									syntax: "literal"
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+
 		timezone: {
 			common:      false
 			description: """
@@ -273,6 +555,57 @@ configuration: {
 				```
 				"""
 		}
+		pipeline_templates: {
+			title: "Pipeline templates"
+			body: """
+				Groups of components repeated across a config, with only a few values changing, can
+				be declared once as a template and instantiated as pipelines:
+
+				```toml
+				[templates.app_logs.params.service]
+				[templates.app_logs.params.inputs]
+				type = "array"
+				[templates.app_logs.params.index]
+				default = "app-logs"
+
+				[templates.app_logs.transforms.parse]
+				type = "remap"
+				inputs = "{{ params.inputs }}"
+				source = '''
+				  . = parse_json!(.message)
+				  .service = "{{ params.service }}"
+				  '''
+
+				[templates.app_logs.sinks.elasticsearch]
+				type = "elasticsearch"
+				inputs = ["parse"]
+				endpoint = "http://elasticsearch:9200"
+				index = "{{ params.index }}-%Y.%m.%d"
+
+				[pipelines.checkout]
+				template = "app_logs"
+				params.service = "checkout"
+				params.inputs = ["checkout_logs"]
+
+				[pipelines.billing]
+				template = "app_logs"
+				params.service = "billing"
+				params.inputs = ["billing_logs"]
+				params.index = "billing-logs"
+				```
+
+				Each pipeline adds the components of its template, named after it, such as
+				`checkout.parse` and `checkout.elasticsearch` above. Inputs naming other components
+				of the template point at the ones of the same pipeline, while the other inputs are
+				left untouched.
+
+				The `{{ params.<name> }}` references are substituted in the string values of the
+				components. A value holding nothing but a reference takes the value of the parameter
+				as is, so that array, number, and boolean parameters keep their types. Other
+				`{{ ... }}` templates, referencing event fields, are left for the components to
+				render.
+				"""
+		}
 		wildcards: {
 			title: "Wildcards in component names"
 			body: """
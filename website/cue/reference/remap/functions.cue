@@ -26,7 +26,7 @@ remap: {
 		examples?: [remap.#Example, ...remap.#Example]
 	}
 
-	#FunctionCategory: "Array" | "Codec" | "Coerce" | "Convert" | "Debug" | "Enumerate" | "Path" | "Hash" | "IP" | "Number" | "Object" | "Parse" | "Random" | "String" | "System" | "Timestamp" | "Type"
+	#FunctionCategory: "Array" | "Codec" | "Coerce" | "Convert" | "Debug" | "Enumerate" | "Geo" | "Path" | "Hash" | "IP" | "Number" | "Object" | "Parse" | "Random" | "String" | "System" | "Timestamp" | "Type"
 
 	// A helper array for generating docs. At some point, we should generate this from the
 	// #FunctionCategory enum if CUE adds support for that.
@@ -37,6 +37,7 @@ remap: {
 		"Convert",
 		"Debug",
 		"Enumerate",
+		"Geo",
 		"Path",
 		"Hash",
 		"IP",
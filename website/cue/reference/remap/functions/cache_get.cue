@@ -0,0 +1,36 @@
+package metadata
+
+remap: functions: cache_get: {
+	category: "System"
+	description: """
+		Returns the value cached under `key` by the [`cache_set`](#cache_set) function.
+
+		The cache is kept per transform across events, so combined with the error-coalescing
+		operator it avoids repeating expensive lookups for frequently seen keys:
+		`cache_get(string!(.ip)) ?? cache_set(string!(.ip), get_enrichment_table_record!("geoip", {"ip": .ip}), ttl_secs: 300)`.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key the value was cached under."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"No value is cached under `key`, or it expired",
+	]
+	return: types: ["any"]
+
+	examples: [
+		{
+			title: "Get a cached value"
+			source: #"""
+				cache_set("user", "vic")
+				cache_get!("user")
+				"""#
+			return: "vic"
+		},
+	]
+}
@@ -0,0 +1,49 @@
+package metadata
+
+remap: functions: cache_set: {
+	category: "System"
+	description: """
+		Caches `value` under `key` and returns it. The value can be read back with the
+		[`cache_get`](#cache_get) function while processing later events.
+
+		The cache holds at most `cache_capacity` values, evicting the least recently used one once full.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key to cache the value under."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value to cache."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name:        "ttl_secs"
+			description: "The number of seconds the value is cached for. Cached until evicted if not set."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["any"]
+		rules: [
+			"Returns `value`.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Cache a value"
+			source: #"""
+				cache_set("user", "vic", ttl_secs: 60)
+				"""#
+			return: "vic"
+		},
+	]
+}
@@ -0,0 +1,91 @@
+package metadata
+
+remap: functions: community_id: {
+	category: "Hash"
+	description: """
+		Calculates the [Community ID](\(urls.community_id)) of a network flow, a hash of its endpoints and protocol
+		that is the same whatever the direction the flow is seen in. Tools such as Zeek and Suricata record the
+		Community ID of the flows they observe, which allows joining their events with events of other tools.
+		"""
+	notices: [
+		"""
+			For ICMP and ICMPv6, `source_port` is the type of the message and `destination_port` its code.
+			Ports are ignored for protocols other than TCP, UDP, SCTP, ICMP and ICMPv6.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "source_ip"
+			description: "The IP address (v4 or v6) of the source of the flow."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "destination_ip"
+			description: "The IP address of the destination of the flow, of the same version as `source_ip`."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "protocol"
+			description: """
+				The IP protocol number of the flow, or its name: `tcp`, `udp`, `sctp`, `icmp` or `icmp6`. As in
+				Zeek and Suricata logs, `icmp` names ICMPv6 for IPv6 flows.
+				"""
+			required: true
+			type: ["integer", "string"]
+		},
+		{
+			name:        "source_port"
+			description: "The port of the source of the flow. Must be set along with `destination_port`."
+			required:    false
+			type: ["integer"]
+		},
+		{
+			name:        "destination_port"
+			description: "The port of the destination of the flow. Must be set along with `source_port`."
+			required:    false
+			type: ["integer"]
+		},
+		{
+			name:        "seed"
+			description: "The seed of the hash, between 0 and 65535. Tools need to use the same seed for their IDs to match."
+			required:    false
+			default:     0
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`source_ip` or `destination_ip` isn't a valid IP address.",
+		"`source_ip` and `destination_ip` aren't of the same IP version.",
+		"`protocol` isn't a known protocol name, or isn't between 0 and 255.",
+		"Only one of `source_port` and `destination_port` is set, or either isn't between 0 and 65535.",
+		"`seed` isn't between 0 and 65535.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate the Community ID of a TCP flow"
+			source: #"""
+				community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 3344)
+				"""#
+			return: "1:wCb3OG7yAFWelaUydu0D+125CLM="
+		},
+		{
+			title: "Calculate the Community ID of a flow with a seed"
+			source: #"""
+				community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: "tcp", source_port: 1122, destination_port: 3344, seed: 123)
+				"""#
+			return: "1:C6Pb16GyzQL53fHtCtR5PliXXkY="
+		},
+		{
+			title: "Calculate the Community ID of an ICMP echo request"
+			source: #"""
+				community_id!(source_ip: "192.168.0.89", destination_ip: "192.168.0.1", protocol: "icmp", source_port: 8, destination_port: 0)
+				"""#
+			return: "1:X0snYXpgwiv9TZtqg64sgzUn6Dk="
+		},
+	]
+}
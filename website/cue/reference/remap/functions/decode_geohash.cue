@@ -0,0 +1,35 @@
+package metadata
+
+remap: functions: decode_geohash: {
+	category: "Geo"
+	description: """
+		Decodes a [geohash](\(urls.geohash)) into the latitude and longitude of the center of its cell.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The geohash to decode, in either case."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is empty.",
+		"`value` contains characters outside of the geohash alphabet.",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Decode a geohash"
+			source: #"""
+				decode_geohash!("u09tvw")
+				"""#
+			return: {
+				latitude:  48.85894775390625
+				longitude: 2.3565673828125
+			}
+		},
+	]
+}
@@ -0,0 +1,62 @@
+package metadata
+
+remap: functions: decode_protobuf: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a binary [Protocol Buffers](\(urls.protobuf)) message) into an object, using the message
+		type `message_type` described in the descriptor set `desc_file`.
+		"""
+	notices: [
+		"""
+			The descriptor set is loaded when the program is compiled, so `desc_file` and `message_type` must be string
+			literals. Descriptor sets are generated with `protoc --include_imports --descriptor_set_out=<desc_file>`.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The Protocol Buffers message to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "desc_file"
+			description: "The path of the `FileDescriptorSet` describing the message type."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "message_type"
+			description: "The fully-qualified name of the message type, including its package."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded message of type `message_type`.",
+		"`value` holds an unsigned 64-bit integer that doesn't fit in an integer.",
+	]
+	return: {
+		types: ["object"]
+		rules: [
+			"Fields absent from the message aren't included in the returned object.",
+			"Enum values are returned by name, or as an integer if the number isn't a known variant.",
+			"Map fields are returned as objects, and repeated fields as arrays.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Decode a Protocol Buffers message"
+			source: #"""
+				decode_protobuf!(decode_base64!("Cgdzb21lb25lEAE="), "/etc/vector/person.desc", "example.Person")
+				"""#
+			return: {
+				name: "someone"
+				id:   1
+			}
+			skip_test: true
+		},
+	]
+}
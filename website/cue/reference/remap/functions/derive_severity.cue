@@ -0,0 +1,77 @@
+package metadata
+
+remap: functions: derive_severity: {
+	category:    "Convert"
+	description: """
+		Derives a canonical severity from the `value`, a severity as reported by any of the common logging
+		conventions, either as a name or as a number. Canonical severities are the eight
+		[Syslog severities](\(urls.syslog_levels)): `emergency`, `alert`, `critical`, `error`, `warning`,
+		`notice`, `info`, and `debug`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The severity to derive the canonical severity from."
+			required:    true
+			type: ["string", "integer"]
+		},
+		{
+			name: "mapping"
+			description: """
+				Custom mapping of values to canonical severities, taking precedence over the built-in names
+				and numeric scales. Values are matched regardless of their case.
+				"""
+			required: false
+			type: ["object"]
+		},
+		{
+			name:        "scale"
+			description: "How numeric severities, including numbers in strings, are interpreted."
+			required:    false
+			enum: {
+				syslog:        "Syslog severities, from `0` (emergency) to `7` (debug)."
+				windows:       "Windows event log levels, from `1` (critical) to `5` (verbose)."
+				python:        "Python logging levels, from `10` (debug) to `50` (critical)."
+				opentelemetry: "OpenTelemetry severity numbers, from `1` (trace) to `24` (fatal)."
+			}
+			default: "syslog"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a known severity name, nor a number of the `scale`",
+		"`mapping` maps a value to something else than a canonical severity",
+	]
+	return: {
+		types: ["string"]
+		rules: [
+			"Names are recognized regardless of their case, including their aliases, such as `fatal` (`critical`), `severe` (`error`), `warn` (`warning`), and `trace` or `finest` (`debug`).",
+			"Numbers not on the `scale` are not recognized.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Derive the severity of a name"
+			source: """
+				derive_severity!("WARN")
+				"""
+			return: "warning"
+		},
+		{
+			title: "Derive the severity of a Python logging level"
+			source: """
+				derive_severity!(40, scale: "python")
+				"""
+			return: "error"
+		},
+		{
+			title: "Derive a severity with a custom mapping"
+			source: """
+				derive_severity!("E", mapping: { "E": "error", "W": "warning" })
+				"""
+			return: "error"
+		},
+	]
+}
@@ -0,0 +1,72 @@
+package metadata
+
+remap: functions: emit_metric: {
+	category: "System"
+	description: """
+		Emits a metric, derived from the event, along with the event. Emitted metrics are sent
+		by the `remap` transform after the events of the program, timestamped with the current
+		time, and without the metadata of the event.
+
+		Metrics are also sent if the program aborts, so that a program can emit metrics instead
+		of its event by calling `abort` with `drop_on_abort` enabled. They are discarded if the
+		program fails.
+		"""
+
+	arguments: [
+		{
+			name:        "name"
+			description: "The name of the metric."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value of the metric, added to the counter, set as the gauge, or sampled by the histogram."
+			required:    true
+			type: ["integer", "float"]
+		},
+		{
+			name:        "type"
+			description: "The type of the metric."
+			required:    false
+			type: ["string"]
+			enum: {
+				counter:   "An incremental counter."
+				gauge:     "An absolute gauge."
+				histogram: "An incremental distribution, sampling the value once."
+			}
+			default: "counter"
+		},
+		{
+			name:        "tags"
+			description: "The tags of the metric. Values that aren't strings are converted to strings, and null values are left out."
+			required:    false
+			type: ["object"]
+		},
+		{
+			name:        "namespace"
+			description: "The namespace of the metric."
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["null"]
+
+	examples: [
+		{
+			title: "Count requests by status"
+			source: #"""
+				emit_metric("requests_total", 1, tags: {"status": "200"})
+				"""#
+			return: null
+		},
+		{
+			title: "Sample response times"
+			source: #"""
+				emit_metric("response_time_seconds", 0.25, type: "histogram", namespace: "app")
+				"""#
+			return: null
+		},
+	]
+}
@@ -0,0 +1,55 @@
+package metadata
+
+remap: functions: encode_geohash: {
+	category: "Geo"
+	description: """
+		Encodes a point given as a latitude and longitude as a [geohash](\(urls.geohash)), a string whose
+		length sets the size of the cell containing the point. Points sharing a prefix are in the same cell,
+		making geohashes useful to bucket locations coarsely.
+		"""
+
+	arguments: [
+		{
+			name:        "latitude"
+			description: "The latitude of the point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "longitude"
+			description: "The longitude of the point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "precision"
+			description: "The number of characters of the geohash, from `1`, for cells about 5,000 kilometers wide, to `12`, for cells a few centimeters wide."
+			required:    false
+			default:     12
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`latitude` isn't between -90 and 90.",
+		"`longitude` isn't between -180 and 180.",
+		"`precision` isn't between 1 and 12.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode a geohash"
+			source: #"""
+				encode_geohash!(57.64911, 10.40744)
+				"""#
+			return: "u4pruydqqvj8"
+		},
+		{
+			title: "Encode a geohash with precision"
+			source: #"""
+				encode_geohash!(48.8566, 2.3522, precision: 6)
+				"""#
+			return: "u09tvw"
+		},
+	]
+}
@@ -0,0 +1,59 @@
+package metadata
+
+remap: functions: encode_protobuf: {
+	category:    "Codec"
+	description: """
+		Encodes the `value` object into a binary [Protocol Buffers](\(urls.protobuf)) message of type `message_type`,
+		as described in the descriptor set `desc_file`.
+		"""
+	notices: [
+		"""
+			The descriptor set is loaded when the program is compiled, so `desc_file` and `message_type` must be string
+			literals. Descriptor sets are generated with `protoc --include_imports --descriptor_set_out=<desc_file>`.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to encode."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "desc_file"
+			description: "The path of the `FileDescriptorSet` describing the message type."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "message_type"
+			description: "The fully-qualified name of the message type, including its package."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` contains a field that isn't defined in `message_type`.",
+		"A field of `value` doesn't have the type its definition requires.",
+		"An enum field of `value` isn't a known variant of the enum.",
+	]
+	return: {
+		types: ["string"]
+		rules: [
+			"Fields set to `null` are omitted from the message.",
+			"Enum fields accept either the variant name or its number.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Encode a Protocol Buffers message"
+			source: #"""
+				encode_base64(encode_protobuf!({"name": "someone", "id": 1}, "/etc/vector/person.desc", "example.Person"))
+				"""#
+			return: "Cgdzb21lb25lEAE="
+			skip_test: true
+		},
+	]
+}
@@ -13,6 +13,13 @@ remap: functions: flatten: {
 			required:    true
 			type: ["array", "object"]
 		},
+		{
+			name:        "separator"
+			description: "The separator to join the keys of nested objects with."
+			required:    false
+			default:     "."
+			type: ["string"]
+		},
 	]
 	internal_failure_reasons: []
 	return: {
@@ -49,5 +56,18 @@ remap: functions: flatten: {
 				"parent2.child3": 3
 			}
 		},
+		{
+			title: "Flatten object with separator"
+			source: #"""
+				flatten({
+					"parent1": {
+						"child1": 1
+					}
+				}, "_")
+				"""#
+			return: {
+				"parent1_child1": 1
+			}
+		},
 	]
 }
@@ -0,0 +1,79 @@
+package metadata
+
+remap: functions: haversine: {
+	category: "Geo"
+	description: """
+		Calculates the great-circle distance between two points given as latitudes and longitudes, using the
+		[haversine formula](\(urls.haversine)), along with the initial bearing from the first point to the second.
+		"""
+
+	arguments: [
+		{
+			name:        "latitude1"
+			description: "The latitude of the first point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "longitude1"
+			description: "The longitude of the first point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "latitude2"
+			description: "The latitude of the second point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "longitude2"
+			description: "The longitude of the second point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "measurement_unit"
+			description: "The unit of the distance."
+			required:    false
+			enum: {
+				kilometers: "Kilometers"
+				miles:      "Miles"
+			}
+			default: "kilometers"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["object"]
+		rules: [
+			"The `distance` is rounded to 7 decimal places, and the `bearing` to 3.",
+			"The `bearing` is in degrees clockwise from north, from `0` up to `360`.",
+			"The Earth is treated as a sphere of its mean radius, which is accurate to within about 0.5%.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Distance in kilometers"
+			source: #"""
+				haversine(0.0, 0.0, 10.0, 10.0)
+				"""#
+			return: {
+				distance: 1568.5227233
+				bearing:  44.561
+			}
+		},
+		{
+			title: "Distance in miles"
+			source: #"""
+				haversine(0.0, 0.0, 10.0, 10.0, measurement_unit: "miles")
+				"""#
+			return: {
+				distance: 974.6348263
+				bearing:  44.561
+			}
+		},
+	]
+}
@@ -0,0 +1,64 @@
+package metadata
+
+remap: functions: jsonpath: {
+	category: "Path"
+	description: """
+		Selects the values within `value` that match a [JSONPath](\(urls.jsonpath)) query, for
+		selections that path expressions can't make, such as all of the elements of an array with a
+		field set to a given value.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The value to query, usually an object or array."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name: "path"
+			description: """
+				The JSONPath query, starting with `$`. Queries can contain member names (`.name` or
+				`['name']`), wildcards (`*`), array indices and slices (`[0]`, `[-1]`, `[1:5:2]`),
+				recursive descent (`..`), unions (`[0, 2]`) and filters (`[?(@.type == 'error')]`).
+				Filters can compare paths relative to the current element (`@`) or to the root (`$`)
+				with strings, numbers, booleans and `null`, combine comparisons with `&&`, `||` and
+				`!`, and test whether a path exists.
+				"""
+			required: true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["array"]
+		rules: [
+			"Returns the matching values in document order, or an empty array if nothing matches.",
+			"Comparisons in filters are false when a path doesn't select exactly one value.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Select with a filter"
+			source: #"""
+				jsonpath({"events": [{"type": "error", "id": 1}, {"type": "info", "id": 2}]}, "$.events[?(@.type == 'error')].id")
+				"""#
+			return: [1]
+		},
+		{
+			title: "Select with wildcards"
+			source: #"""
+				jsonpath({"a": {"id": 1}, "b": {"id": 2}}, "$.*.id")
+				"""#
+			return: [1, 2]
+		},
+		{
+			title: "Select recursively"
+			source: #"""
+				jsonpath({"id": 1, "children": [{"id": 2}, {"id": 3}]}, "$..id")
+				"""#
+			return: [1, 2, 3]
+		},
+	]
+}
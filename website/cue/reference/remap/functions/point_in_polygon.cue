@@ -0,0 +1,65 @@
+package metadata
+
+remap: functions: point_in_polygon: {
+	category: "Geo"
+	description: """
+		Determines whether a point given as a latitude and longitude is inside a [GeoJSON](\(urls.geojson))
+		`Polygon` or `MultiPolygon`, or inside any of the polygons of a `Feature` or `FeatureCollection`.
+		Points in the holes of a polygon are outside of it.
+		"""
+
+	arguments: [
+		{
+			name:        "latitude"
+			description: "The latitude of the point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name:        "longitude"
+			description: "The longitude of the point, in degrees."
+			required:    true
+			type: ["float", "integer"]
+		},
+		{
+			name: "polygon"
+			description: """
+				The GeoJSON object, either as an object or as a string to parse, such as a field of a record
+				looked up with [`get_enrichment_table_record`](\(urls.vrl_functions)/#get_enrichment_table_record).
+				"""
+			required: true
+			type: ["object", "string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`polygon` is a string that isn't valid JSON.",
+		"`polygon` isn't a GeoJSON `Polygon`, `MultiPolygon`, `Feature` or `FeatureCollection`.",
+		"The coordinates of `polygon` aren't arrays of positions.",
+	]
+	return: {
+		types: ["boolean"]
+		rules: [
+			"Coordinates are treated as planar, as in GeoJSON, rather than following great circles.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Point inside a polygon"
+			source: #"""
+				point_in_polygon!(5, 5, {
+					"type": "Polygon",
+					"coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]
+				})
+				"""#
+			return: true
+		},
+		{
+			title: "Point outside a polygon given as a string"
+			source: #"""
+				point_in_polygon!(15, 5, s'{"type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]}')
+				"""#
+			return: false
+		},
+	]
+}
@@ -0,0 +1,68 @@
+package metadata
+
+remap: functions: unflatten: {
+	category: "Enumerate"
+	description: #"""
+		Unflattens the `value` by splitting its keys on the `separator` into nested objects.
+		"""#
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to unflatten."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "separator"
+			description: "The separator to split the keys on."
+			required:    false
+			default:     "."
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`separator` is an empty string.",
+	]
+	return: {
+		types: ["object"]
+		rules: [
+			"Keys conflicting with a value already at one of their parents are left as they are.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Unflatten object"
+			source: #"""
+				unflatten({
+					"parent1.child1": 1,
+					"parent1.child2": 2,
+					"parent2.child3": 3
+				})
+				"""#
+			return: {
+				parent1: {
+					child1: 1
+					child2: 2
+				}
+				parent2: {
+					child3: 3
+				}
+			}
+		},
+		{
+			title: "Unflatten object with separator"
+			source: #"""
+				unflatten({
+					"parent1_child1": 1
+				}, "_")
+				"""#
+			return: {
+				parent1: {
+					child1: 1
+				}
+			}
+		},
+	]
+}
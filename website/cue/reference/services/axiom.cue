@@ -0,0 +1,10 @@
+package metadata
+
+services: axiom: {
+	name:     "Axiom"
+	thing:    "an \(name) dataset"
+	url:      urls.axiom
+	versions: null
+
+	description: "[Axiom](\(urls.axiom)) stores, queries and analyzes event data, such as logs and traces, without sampling or indexing it upfront."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: azure_event_hubs: {
+	name:     "Azure Event Hubs"
+	thing:    "an \(name) event hub"
+	url:      urls.azure_event_hubs
+	versions: null
+
+	description: "[Azure Event Hubs](\(urls.azure_event_hubs)) is a fully managed, real-time data ingestion service on Microsoft Azure. Events are appended to the partitions of an event hub, from which consumer groups read them independently, each at their own pace."
+}
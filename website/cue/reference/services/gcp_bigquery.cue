@@ -0,0 +1,10 @@
+package metadata
+
+services: gcp_bigquery: {
+	name:     "GCP BigQuery"
+	thing:    "a \(name) table"
+	url:      urls.big_query_storage_write_api
+	versions: null
+
+	description: "[GCP BigQuery](\(urls.big_query_storage_write_api)) is a serverless, highly scalable data warehouse on the Google Cloud Platform. Vector writes rows to its tables through the Storage Write API."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: git_webhooks: {
+	name:     "GitHub and GitLab webhooks"
+	thing:    "a \(name) sender"
+	url:      urls.github_webhooks
+	versions: null
+
+	description: "[GitHub](\(urls.github_webhooks)) and [GitLab](\(urls.gitlab_webhooks)) webhooks deliver an HTTP POST request for each event happening in a repository or project, such as pushes, pull requests or pipeline runs."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: imap: {
+	name:     "IMAP"
+	thing:    "an \(name) mailbox"
+	url:      urls.imap
+	versions: null
+
+	description: "The [Internet Message Access Protocol (IMAP)](\(urls.imap)) is the standard protocol email clients use to read messages from a mailbox kept on a mail server."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: industrial_equipment: {
+	name:     "Industrial Equipment"
+	thing:    "PLCs, sensors and other \(name)"
+	url:      urls.opc_ua
+	versions: null
+
+	description: "Industrial equipment, such as programmable logic controllers (PLCs), exposes its data through protocols like [Modbus](\(urls.modbus)) and [OPC UA](\(urls.opc_ua))."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: opentelemetry: {
+	name:     "OpenTelemetry"
+	thing:    "an \(name) collector"
+	url:      urls.opentelemetry
+	versions: null
+
+	description: "[OpenTelemetry](\(urls.opentelemetry)) is a collection of tools, APIs, and SDKs to instrument, generate, collect, and export telemetry data. Its collectors, and a growing number of vendors, receive data through the [OpenTelemetry protocol (OTLP)](\(urls.otlp))."
+}
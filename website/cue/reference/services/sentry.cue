@@ -0,0 +1,10 @@
+package metadata
+
+services: sentry: {
+	name:     "Sentry"
+	thing:    "a \(name) project"
+	url:      urls.sentry
+	versions: null
+
+	description: "[Sentry](\(urls.sentry)) is an application monitoring platform that groups errors into issues and alerts on them."
+}
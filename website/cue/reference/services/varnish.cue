@@ -0,0 +1,10 @@
+package metadata
+
+services: varnish: {
+	name:     "Varnish"
+	thing:    "a \(name) cache"
+	url:      urls.varnish
+	versions: ">= 6.0"
+
+	description: "[Varnish Cache](\(urls.varnish)) is a caching HTTP reverse proxy, which logs the transactions it handles to a shared memory log that the [`varnishlog`](\(urls.varnishlog)) tool reads."
+}
@@ -7,12 +7,14 @@ urls: {
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
+	apache_avro:                                              "https://avro.apache.org/"
 	apache_common:                                            "\(apache)/docs/current/logs.html#common"
 	apache_combined:                                          "\(apache)/docs/current/logs.html#combined"
 	apache_error:                                             "\(apache)/docs/current/logs.html#errorlog"
 	apache_extended_status:                                   "\(apache)/docs/current/mod/core.html#extendedstatus"
 	apache_install:                                           "\(apache)/docs/current/install.html"
 	apache_mod_status:                                        "http://httpd.apache.org/docs/current/mod/mod_status.html"
+	apicurio_registry:                                        "https://www.apicur.io/registry/"
 	apt:                                                      "\(wikipedia)/wiki/APT_(software)"
 	arm:                                                      "\(wikipedia)/wiki/ARM_architecture"
 	aws_access_keys:                                          "\(aws_docs)/IAM/latest/UserGuide/id_credentials_access-keys.html"
@@ -21,6 +23,7 @@ urls: {
 	aws_athena_console:                                       "https://console.aws.amazon.com/athena/home"
 	aws_canonical_user_id:                                    "\(aws_docs)/general/latest/gr/acct-identifiers.html#FindingCanonicalId"
 	aws_cloudwatch:                                           "https://aws.amazon.com/cloudwatch/"
+	aws_cloudwatch_high_resolution_metrics:                   "\(aws_docs)/AmazonCloudWatch/latest/monitoring/publishingMetrics.html#high-resolution-metrics"
 	aws_cloudwatch_logs:                                      "\(aws_docs)/AmazonCloudWatch/latest/logs/WhatIsCloudWatchLogs.html"
 	aws_cloudwatch_logs_api:                                  "\(aws_docs)/AmazonCloudWatchLogs/latest/APIReference/Welcome.html"
 	aws_cloudwatch_logs_firehose:                             "\(aws_docs)/firehose/latest/dev/writing-with-cloudwatch-logs.html"
@@ -76,20 +79,32 @@ urls: {
 	aws_s3_sse:                                               "\(aws_docs)/AmazonS3/latest/dev/UsingServerSideEncryption.html"
 	aws_s3_storage_classes:                                   "https://aws.amazon.com/s3/storage-classes/"
 	aws_s3_tags:                                              "\(aws_docs)/AmazonS3/latest/user-guide/add-object-tags.html"
+	aws_sns:                                                  "https://aws.amazon.com/sns/"
+	aws_sns_api:                                              "\(aws_docs)/sns/latest/api/welcome.html"
+	aws_sns_message_attributes:                               "\(aws_docs)/sns/latest/dg/sns-message-attributes.html"
+	aws_sns_message_deduplication_id:                         "\(aws_docs)/sns/latest/dg/fifo-message-dedup.html"
 	aws_sqs:                                                  "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                              "\(aws_docs)/AWSSimpleQueueService/latest/APIReference/Welcome.html"
 	aws_sqs_create:                                           "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-configure-create-queue.html"
 	aws_sqs_message_deduplication_id:                         "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/using-messagededuplicationid-property.html"
 	aws_vpc_flow_logs:                                        "\(aws_docs)/vpc/latest/userguide/flow-logs.html"
+	axiom:                                                    "https://axiom.co"
+	axiom_ingest:                                             "https://axiom.co/docs/restapi/ingest"
+	axiom_tokens:                                             "https://axiom.co/docs/restapi/token"
 	azure_blob:                                               "https://azure.microsoft.com/en-us/services/storage/blobs/"
+	azure_blob_access_tiers:                                  "https://docs.microsoft.com/en-us/azure/storage/blobs/access-tiers-overview"
 	azure_blob_endpoints:                                     "https://docs.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api"
+	azure_event_hubs:                                         "https://azure.microsoft.com/en-us/services/event-hubs/"
+	azure_managed_identities:                                 "https://docs.microsoft.com/en-us/azure/active-directory/managed-identities-azure-resources/overview"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
+	azure_workload_identity:                                  "https://azure.github.io/azure-workload-identity/docs/"
 	base64:                                                   "\(wikipedia)/wiki/Base64"
 	base64_padding:                                           "\(wikipedia)/wiki/Base64#Output_padding"
 	base64_standard:                                          "https://tools.ietf.org/html/rfc4648#section-4"
 	base64_url_safe:                                          "\(wikipedia)/wiki/Base64#URL_applications"
 	basic_auth:                                               "\(wikipedia)/wiki/Basic_access_authentication"
+	big_query_storage_write_api:                              "https://cloud.google.com/bigquery/docs/write-api"
 	big_query_streaming:                                      "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	bind_dnstap:                                              "https://kb.isc.org/docs/aa-01342"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
@@ -102,6 +117,8 @@ urls: {
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	community_id:                                             "https://github.com/corelight/community-id-spec"
+	confluent_schema_registry_api:                            "https://docs.confluent.io/platform/current/schema-registry/develop/api.html"
 	console:                                                  "\(wikipedia)/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
@@ -128,6 +145,7 @@ urls: {
 	debian:                                                   "https://www.debian.org/"
 	debian_system_groups:                                     "https://wiki.debian.org/SystemGroups"
 	default_configuration:                                    "\(vector_repo)/blob/master/config/vector.toml"
+	dns:                                                      "\(wikipedia)/wiki/Domain_Name_System"
 	dnstap:                                                   "http://dnstap.info/"
 	docker:                                                   "https://www.docker.com/"
 	docker_alpine:                                            "\(docker_hub)/_/alpine"
@@ -157,6 +175,7 @@ urls: {
 	dot_format:                                               "https://graphviz.org/doc/info/lang.html"
 	dpkg:                                                     "https://wiki.debian.org/dpkg"
 	dry_code:                                                 "\(wikipedia)/wiki/Don%27t_repeat_yourself"
+	duckdb:                                                   "https://duckdb.org/"
 	cidr:                                                     "\(wikipedia)/wiki/Classless_Inter-Domain_Routing"
 	elastic_beats:                                            "https://www.elastic.co/beats/"
 	elasticsearch:                                            "https://www.elastic.co/products/elasticsearch"
@@ -165,6 +184,8 @@ urls: {
 	elasticsearch_id_field:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-id-field.html"
 	elasticsearch_id_performance:                             "https://www.elastic.co/guide/en/elasticsearch/reference/master/tune-for-indexing-speed.html#_use_auto_generated_ids"
 	elasticsearch_ignore_malformed:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ignore-malformed.html"
+	elasticsearch_ingest_pipeline:                            "https://www.elastic.co/guide/en/elasticsearch/reference/current/ingest.html"
+	elasticsearch_routing:                                    "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-routing-field.html"
 	encoding_charset_labels:                                  "https://encoding.spec.whatwg.org/#concept-encoding-get"
 	encoding_standard:                                        "https://encoding.spec.whatwg.org/"
 	endler_dev:                                               "https://endler.dev/"
@@ -204,20 +225,27 @@ urls: {
 	gcs_predefined_acl:                                       "\(gcp)/storage/docs/access-control/lists#predefined-acl"
 	gcs_storage_classes:                                      "\(gcp)/storage/docs/storage-classes"
 	gcs_custom_metadata:                                      "\(gcp)/storage/docs/metadata#custom-metadata"
+	geohash:                                                  "\(wikipedia)/wiki/Geohash"
+	geojson:                                                  "https://datatracker.ietf.org/doc/html/rfc7946"
 	git:                                                      "https://git-scm.com/"
 	github:                                                   "https://github.com"
 	github_protected_branches:                                "https://help.github.com/en/github/administering-a-repository/about-protected-branches"
 	github_sign_commits:                                      "https://help.github.com/en/github/authenticating-to-github/signing-commits"
+	github_webhook_signatures:                                "https://docs.github.com/en/developers/webhooks-and-events/webhooks/securing-your-webhooks"
+	github_webhooks:                                          "https://docs.github.com/en/developers/webhooks-and-events/webhooks/about-webhooks"
+	gitlab_webhooks:                                          "https://docs.gitlab.com/ee/user/project/integrations/webhooks.html"
 	globbing:                                                 "\(wikipedia)/wiki/Glob_(programming)"
 	glog:                                                     "\(github)/google/glog"
 	graphql:                                                  "https://graphql.org"
 	graphql_playground:                                       "\(github)/graphql/graphql-playground"
 	graphviz:                                                 "https://graphviz.org/"
+	graylog_gelf:                                             "https://docs.graylog.org/docs/gelf"
 	grok:                                                     "https://grokdebug.herokuapp.com/"
 	grok_debugger:                                            "https://grokdebug.herokuapp.com/"
 	grok_patterns:                                            "\(github)/daschl/grok/tree/master/patterns"
 	gzip:                                                     "https://www.gzip.org/"
 	haproxy:                                                  "https://www.haproxy.org/"
+	haversine:                                                "\(wikipedia)/wiki/Haversine_formula"
 	helm:                                                     "https://helm.sh/"
 	heroku:                                                   "https://www.heroku.com"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
@@ -226,6 +254,7 @@ urls: {
 	homebrew_services:                                        "\(github)/Homebrew/homebrew-services"
 	honeycomb:                                                "https://honeycomb.io"
 	honeycomb_batch:                                          "https://docs.honeycomb.io/api/events/#batched-events"
+	honeycomb_markers:                                        "https://docs.honeycomb.io/api/markers/"
 	honeycomb_signup:                                         "https://ui.honeycomb.io/signup"
 	host:                                                     "\(wikipedia)/wiki/Host_(network)"
 	http:                                                     "https://www.w3.org/Protocols/"
@@ -239,6 +268,9 @@ urls: {
 	iana_time_zones:                                          "\(wikipedia)/wiki/List_of_tz_database_time_zones"
 	ieee_754:                                                 "\(wikipedia)/wiki/IEEE_754"
 	ietf_rfc_6750:                                            "https://tools.ietf.org/html/rfc6750"
+	imap:                                                     "https://datatracker.ietf.org/doc/html/rfc3501"
+	imap_move:                                                "https://datatracker.ietf.org/doc/html/rfc6851"
+	imap_search:                                              "https://datatracker.ietf.org/doc/html/rfc3501#section-6.4.4"
 	initd:                                                    "https://bash.cyberciti.biz/guide//etc/init.d"
 	influxdb:                                                 "https://www.influxdata.com/products/influxdb-overview/"
 	influxdb_http_api_v1:                                     "https://docs.influxdata.com/influxdb/latest/tools/api/#write-http-endpoint"
@@ -248,6 +280,7 @@ urls: {
 	inode:                                                    "\(wikipedia)/wiki/Inode"
 	ip_aton:                                                  "https://linux.die.net/man/3/inet_aton"
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
+	ipfix:                                                    "https://datatracker.ietf.org/doc/html/rfc7011"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
@@ -256,6 +289,7 @@ urls: {
 	json:                                                     "\(wikipedia)/wiki/JSON"
 	json_types:                                               "\(wikipedia)/wiki/JSON#Data_types_and_syntax"
 	jsonnet:                                                  "https://jsonnet.org/"
+	jsonpath:                                                 "https://goessner.net/articles/JsonPath/"
 	kafka:                                                    "https://kafka.apache.org/"
 	kafka_partitioning_docs:                                  "https://cwiki.apache.org/confluence/display/KAFKA/A+Guide+To+The+Kafka+Protocol#AGuideToTheKafkaProtocol-Partitioningandbootstrapping"
 	kafka_protocol:                                           "https://kafka.apache.org/protocol"
@@ -292,6 +326,7 @@ urls: {
 	logplex_protocol:                                         "\(github)/heroku/logplex/blob/master/doc/README.http_drains.md"
 	lua:                                                      "https://www.lua.org"
 	lua_boolean:                                              "\(lua)/pil/2.2.html"
+	lua_coroutines:                                           "\(lua)/manual/5.4/manual.html#2.6"
 	lua_csv_repo:                                             "\(github)/geoffleyland/lua-csv"
 	lua_csv_view:                                             "\(github)/geoffleyland/lua-csv/blob/09557e4608b02d136b9ae39a8fa0f36328fa1cec/lua/csv.lua"
 	lua_csv_raw:                                              "https://raw.githubusercontent.com/geoffleyland/lua-csv/d20cd42d61dc52e7f6bcb13b596ac7a7d4282fbf/lua/csv.lua"
@@ -324,12 +359,14 @@ urls: {
 	memory_safety_bugs:                                       "https://thenewstack.io/microsoft-rust-is-the-industrys-best-chance-at-safe-systems-programming/"
 	metric_event_source:                                      "\(vector_repo)/blob/master/src/event/metric.rs"
 	mlua:                                                     "\(github)/khvzak/mlua"
+	modbus:                                                   "https://modbus.org/specs.php"
 	mongodb:                                                  "https://www.mongodb.com"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	nats:                                                     "https://nats.io/"
 	nats_rs:                                                  "\(github)/nats-io/nats.rs"
+	netflow:                                                  "\(wikipedia)/wiki/NetFlow"
 	new_bug_report:                                           "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_relic:                                                "https://newrelic.com/"
@@ -346,6 +383,9 @@ urls: {
 	nix:                                                      "https://nixos.org/nix/"
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "\(github)/NixOS/nixpkgs/issues/9682"
+	opc_ua:                                                   "https://opcfoundation.org/about/opc-technologies/opc-ua/"
+	opentelemetry:                                            "https://opentelemetry.io/"
+	otlp:                                                     "https://opentelemetry.io/docs/specs/otlp/"
 	openssl:                                                  "https://www.openssl.org/"
 	order_of_ops:                                             "\(wikipedia)/wiki/Order_of_operations"
 	papertrail:                                               "https://www.papertrail.com/"
@@ -368,10 +408,12 @@ urls: {
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_counter:                                       "https://prometheus.io/docs/concepts/metric_types/#counter"
+	prometheus_exemplars:                                     "https://prometheus.io/docs/prometheus/latest/feature_flags/#exemplars-storage"
 	prometheus_gauge:                                         "https://prometheus.io/docs/concepts/metric_types/#gauge"
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
 	prometheus_histograms_guide:                              "https://prometheus.io/docs/practices/histograms/"
+	prometheus_native_histograms:                             "https://prometheus.io/docs/specs/native_histograms/"
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "\(github)/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
@@ -379,6 +421,7 @@ urls: {
 	prometheus_remote_write:                                  "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	prometheus_remote_write_protocol:                         "https://docs.google.com/document/d/1LPhVRSFkGNSuU1fBd81ulhsCPR4hkSZyyBj1SZ8fWOM/edit#heading=h.n0d0vphea3fe"
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
+	protobuf_varint:                                          "https://developers.google.com/protocol-buffers/docs/encoding#varints"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
 	raspbian:                                                 "https://www.raspbian.org/"
@@ -422,13 +465,19 @@ urls: {
 	sematext_monitoring:                                      "https://sematext.com/docs/monitoring/"
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
+	sentry:                                                   "https://sentry.io"
+	sentry_dsn:                                               "https://docs.sentry.io/product/sentry-basics/dsn-explainer/"
+	sentry_store:                                             "https://develop.sentry.dev/sdk/store/"
+	sflow:                                                    "https://sflow.org/sflow_version_5.txt"
 	sha1:                                                     "\(wikipedia)/wiki/SHA-1"
 	sha2:                                                     "\(wikipedia)/wiki/SHA-2"
 	sha3:                                                     "\(wikipedia)/wiki/SHA-3"
 	signal:                                                   "\(wikipedia)/wiki/Signal_(IPC)"
+	slack_incoming_webhooks:                                  "https://api.slack.com/messaging/webhooks"
 	snake_case:                                               "\(wikipedia)/wiki/Snake_case"
 	snappy:                                                   "https://google.github.io/snappy/"
 	socket:                                                   "\(wikipedia)/wiki/Network_socket"
+	spiffe_workload_api:                                      "https://github.com/spiffe/spiffe/blob/main/standards/SPIFFE_Workload_API.md"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
 	splunk_hec_event_endpoint:                                "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fevent"
@@ -437,6 +486,8 @@ urls: {
 	splunk_hec_raw_endpoint:                                  "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                                         "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
 	specs_instrumentation:                                    "\(vector_repo)/blob/master/docs/specs/instrumentation.md)"
+	sqlite:                                                   "https://www.sqlite.org/"
+	sqlite_wal:                                               "https://www.sqlite.org/wal.html"
 	standard_streams:                                         "\(wikipedia)/wiki/Standard_streams"
 	statsd:                                                   "\(github)/statsd/statsd"
 	statsd_multi:                                             "\(github)/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"
@@ -459,6 +510,7 @@ urls: {
 	systemd_resolved:                                         "https://wiki.archlinux.org/index.php/Systemd-resolved"
 	tcp:                                                      "\(wikipedia)/wiki/Transmission_Control_Protocol"
 	team:                                                     "/community#team"
+	teams_incoming_webhooks:                                  "https://docs.microsoft.com/en-us/microsoftteams/platform/webhooks-and-connectors/how-to/add-incoming-webhook"
 	timber:                                                   "https://timber.io"
 	toml:                                                     "\(github)/toml-lang/toml"
 	toml_array:                                               "\(github)/toml-lang/toml#array"
@@ -479,6 +531,10 @@ urls: {
 	url:                                                      "\(wikipedia)/wiki/URL"
 	us_social_security_number:                                "https://www.ssa.gov/history/ssn/geocard.html"
 	user_agent:                                               "https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent"
+	varnish:                                                  "https://varnish-cache.org/"
+	varnish_vsl_query:                                        "https://varnish-cache.org/docs/trunk/reference/vsl-query.html"
+	varnish_vsl_tags:                                         "https://varnish-cache.org/docs/trunk/reference/vsl.html"
+	varnishlog:                                               "https://varnish-cache.org/docs/trunk/reference/varnishlog.html"
 	vector_agent_role:                                        "/docs/setup/deployment/roles/#agent"
 	vector_aggregator_role:                                   "/docs/setup/deployment/roles/#aggregator"
 	vector_aws_s3_sink:                                       "/docs/reference/configuration/sinks/aws_s3/"
@@ -600,6 +656,7 @@ urls: {
 	vrl_safety:                                               "\(vrl_reference)#safety"
 	vrl_type_safety:                                          "\(vrl_reference)#type-safety"
 	vote_feature:                                             "\(vector_repo)/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
+	w3c_extended_log_format:                                  "https://www.w3.org/TR/WD-logfile.html"
 	wasm:                                                     "https://webassembly.org/"
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
 	wikipedia:                                                "https://en.wikipedia.org"